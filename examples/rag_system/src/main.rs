@@ -182,6 +182,8 @@ async fn add_document(
             id: lumosai_vector_core::DocumentId::from(doc_id),
             content: chunk.clone(),
             embedding: Some(embedding),
+            sparse_embedding: None,
+            vectors: HashMap::new(),
             metadata,
         };
         
@@ -225,6 +227,7 @@ async fn query_rag_system(
         include_metadata: true,
         include_vectors: false,
         options: HashMap::new(),
+        scoring: None,
     };
     
     let search_results = storage.search(search_request).await?;
@@ -67,6 +67,8 @@ async fn test_agent_creation_configuration() -> std::result::Result<(), Box<dyn
         metadata: None,
         max_tool_calls: Some(10),
         tool_timeout: Some(30),
+        reflection: None,
+        few_shot: None,
     };
 
     let agent = BasicAgent::new(agent_config, Arc::new(llm));
@@ -114,6 +116,8 @@ async fn test_agent_creation_configuration() -> std::result::Result<(), Box<dyn
             metadata: None,
             max_tool_calls: Some(10),
             tool_timeout: Some(30),
+            reflection: None,
+            few_shot: None,
         };
 
         let llm_clone = QwenProvider::new_with_api_type(
@@ -162,6 +166,8 @@ async fn test_agent_execution() -> std::result::Result<(), Box<dyn std::error::E
         metadata: None,
         max_tool_calls: Some(10),
         tool_timeout: Some(30),
+        reflection: None,
+        few_shot: None,
     };
 
     let agent = BasicAgent::new(agent_config, Arc::new(llm));
@@ -327,6 +333,8 @@ async fn test_agent_tool_usage() -> std::result::Result<(), Box<dyn std::error::
         metadata: None,
         max_tool_calls: Some(10),
         tool_timeout: Some(30),
+        reflection: None,
+        few_shot: None,
     };
 
     let agent = BasicAgent::new(agent_config, Arc::new(llm));
@@ -424,6 +432,8 @@ async fn test_agent_memory_management() -> std::result::Result<(), Box<dyn std::
         metadata: None,
         max_tool_calls: Some(10),
         tool_timeout: Some(30),
+        reflection: None,
+        few_shot: None,
     };
 
     let agent = BasicAgent::new(agent_config, Arc::new(llm));
@@ -161,6 +161,8 @@ async fn test_rag_streaming_integration() -> std::result::Result<(), Box<dyn std
         metadata: None,
         max_tool_calls: None,
         tool_timeout: None,
+        reflection: None,
+        few_shot: None,
     };
     
     let agent = BasicAgent::new(agent_config, Arc::new(llm));
@@ -285,6 +287,8 @@ async fn test_multi_agent_collaboration() -> std::result::Result<(), Box<dyn std
         metadata: None,
         max_tool_calls: None,
         tool_timeout: None,
+        reflection: None,
+        few_shot: None,
     };
     
     // 项目经理Agent
@@ -301,6 +305,8 @@ async fn test_multi_agent_collaboration() -> std::result::Result<(), Box<dyn std
         metadata: None,
         max_tool_calls: None,
         tool_timeout: None,
+        reflection: None,
+        few_shot: None,
     };
     
     let tech_analyst = BasicAgent::new(tech_analyst_config, Arc::new(llm));
@@ -402,6 +408,8 @@ async fn test_complex_workflow() -> std::result::Result<(), Box<dyn std::error::
         metadata: None,
         max_tool_calls: None,
         tool_timeout: None,
+        reflection: None,
+        few_shot: None,
     };
 
     let workflow_agent = BasicAgent::new(workflow_agent_config, Arc::new(llm));
@@ -486,6 +494,8 @@ async fn test_performance_stress() -> std::result::Result<(), Box<dyn std::error
         metadata: None,
         max_tool_calls: None,
         tool_timeout: None,
+        reflection: None,
+        few_shot: None,
     };
 
     let stress_agent = Arc::new(BasicAgent::new(stress_agent_config, Arc::new(llm)));
@@ -582,6 +592,8 @@ async fn test_error_recovery() -> std::result::Result<(), Box<dyn std::error::Er
         metadata: None,
         max_tool_calls: None,
         tool_timeout: None,
+        reflection: None,
+        few_shot: None,
     };
 
     let robust_agent = BasicAgent::new(robust_agent_config, Arc::new(llm));
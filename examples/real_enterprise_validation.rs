@@ -67,6 +67,8 @@ async fn test_monitoring_and_logging() -> std::result::Result<(), Box<dyn std::e
         metadata: None,
         max_tool_calls: None,
         tool_timeout: None,
+        reflection: None,
+        few_shot: None,
     };
     
     let monitoring_agent = BasicAgent::new(monitoring_agent_config, Arc::new(llm));
@@ -130,6 +132,8 @@ async fn test_security_features() -> std::result::Result<(), Box<dyn std::error:
         metadata: None,
         max_tool_calls: None,
         tool_timeout: None,
+        reflection: None,
+        few_shot: None,
     };
     
     let security_agent = BasicAgent::new(security_agent_config, Arc::new(llm));
@@ -217,6 +221,8 @@ async fn test_multi_tenant_support() -> std::result::Result<(), Box<dyn std::err
             metadata: None,
             max_tool_calls: None,
             tool_timeout: None,
+            reflection: None,
+            few_shot: None,
         };
         
         let tenant_llm = QwenProvider::new_with_api_type(
@@ -305,6 +311,8 @@ async fn test_configuration_management() -> std::result::Result<(), Box<dyn std:
             metadata: None,
             max_tool_calls: None,
             tool_timeout: None,
+            reflection: None,
+            few_shot: None,
         };
         
         let config_agent = BasicAgent::new(config_agent_config, Arc::new(llm));
@@ -366,6 +374,8 @@ async fn test_enterprise_integration() -> std::result::Result<(), Box<dyn std::e
         metadata: None,
         max_tool_calls: None,
         tool_timeout: None,
+        reflection: None,
+        few_shot: None,
     };
     
     let integration_agent = BasicAgent::new(integration_agent_config, Arc::new(llm));
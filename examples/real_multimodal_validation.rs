@@ -68,6 +68,8 @@ async fn test_image_processing() -> std::result::Result<(), Box<dyn std::error::
         metadata: None,
         max_tool_calls: None,
         tool_timeout: None,
+        reflection: None,
+        few_shot: None,
     };
     
     let image_agent = BasicAgent::new(image_agent_config, Arc::new(llm));
@@ -160,6 +162,8 @@ async fn test_audio_processing() -> std::result::Result<(), Box<dyn std::error::
         metadata: None,
         max_tool_calls: None,
         tool_timeout: None,
+        reflection: None,
+        few_shot: None,
     };
     
     let audio_agent = BasicAgent::new(audio_agent_config, Arc::new(llm));
@@ -249,6 +253,8 @@ async fn test_multimodal_understanding() -> std::result::Result<(), Box<dyn std:
         metadata: None,
         max_tool_calls: None,
         tool_timeout: None,
+        reflection: None,
+        few_shot: None,
     };
     
     let multimodal_agent = BasicAgent::new(multimodal_agent_config, Arc::new(llm));
@@ -335,6 +341,8 @@ async fn test_multimodal_generation() -> std::result::Result<(), Box<dyn std::er
         metadata: None,
         max_tool_calls: None,
         tool_timeout: None,
+        reflection: None,
+        few_shot: None,
     };
 
     let generation_agent = BasicAgent::new(generation_agent_config, Arc::new(llm));
@@ -423,6 +431,8 @@ async fn test_cross_modal_conversion() -> std::result::Result<(), Box<dyn std::e
         metadata: None,
         max_tool_calls: None,
         tool_timeout: None,
+        reflection: None,
+        few_shot: None,
     };
 
     let conversion_agent = BasicAgent::new(conversion_agent_config, Arc::new(llm));
@@ -71,6 +71,8 @@ async fn test_basic_performance() -> std::result::Result<(), Box<dyn std::error:
         metadata: None,
         max_tool_calls: None,
         tool_timeout: None,
+        reflection: None,
+        few_shot: None,
     };
     
     let perf_agent = BasicAgent::new(perf_agent_config, Arc::new(llm));
@@ -173,6 +175,8 @@ async fn test_concurrent_performance() -> std::result::Result<(), Box<dyn std::e
         metadata: None,
         max_tool_calls: None,
         tool_timeout: None,
+        reflection: None,
+        few_shot: None,
     };
     
     let concurrent_agent = Arc::new(BasicAgent::new(concurrent_agent_config, Arc::new(llm)));
@@ -293,6 +297,8 @@ async fn test_memory_optimization() -> std::result::Result<(), Box<dyn std::erro
         metadata: None,
         max_tool_calls: None,
         tool_timeout: None,
+        reflection: None,
+        few_shot: None,
     };
     
     // 测试多个Agent实例的内存使用
@@ -375,6 +381,8 @@ async fn test_streaming_performance() -> std::result::Result<(), Box<dyn std::er
         metadata: None,
         max_tool_calls: None,
         tool_timeout: None,
+        reflection: None,
+        few_shot: None,
     };
 
     let streaming_agent = BasicAgent::new(streaming_agent_config, Arc::new(llm));
@@ -496,6 +504,8 @@ async fn test_long_running_stability() -> std::result::Result<(), Box<dyn std::e
         metadata: None,
         max_tool_calls: None,
         tool_timeout: None,
+        reflection: None,
+        few_shot: None,
     };
 
     let stability_agent = BasicAgent::new(stability_agent_config, Arc::new(llm));
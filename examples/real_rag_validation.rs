@@ -472,6 +472,8 @@ async fn test_end_to_end_rag() -> std::result::Result<(), Box<dyn std::error::Er
         metadata: None,
         max_tool_calls: None,
         tool_timeout: None,
+        reflection: None,
+        few_shot: None,
     };
 
     let agent = BasicAgent::new(agent_config, Arc::new(llm));
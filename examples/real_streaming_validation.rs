@@ -69,6 +69,8 @@ async fn test_basic_streaming() -> std::result::Result<(), Box<dyn std::error::E
         metadata: None,
         max_tool_calls: None,
         tool_timeout: None,
+        reflection: None,
+        few_shot: None,
     };
     
     let agent = BasicAgent::new(agent_config, Arc::new(llm));
@@ -183,6 +185,8 @@ async fn test_long_text_streaming() -> std::result::Result<(), Box<dyn std::erro
         metadata: None,
         max_tool_calls: None,
         tool_timeout: None,
+        reflection: None,
+        few_shot: None,
     };
     
     let agent = BasicAgent::new(agent_config, Arc::new(llm));
@@ -314,6 +318,8 @@ async fn test_multi_turn_streaming() -> std::result::Result<(), Box<dyn std::err
         metadata: None,
         max_tool_calls: None,
         tool_timeout: None,
+        reflection: None,
+        few_shot: None,
     };
     
     let agent = BasicAgent::new(agent_config, Arc::new(llm));
@@ -435,6 +441,8 @@ async fn test_streaming_performance() -> std::result::Result<(), Box<dyn std::er
         metadata: None,
         max_tool_calls: None,
         tool_timeout: None,
+        reflection: None,
+        few_shot: None,
     };
 
     let agent = BasicAgent::new(agent_config, Arc::new(llm));
@@ -536,6 +544,8 @@ async fn test_streaming_error_handling() -> std::result::Result<(), Box<dyn std:
         metadata: None,
         max_tool_calls: None,
         tool_timeout: None,
+        reflection: None,
+        few_shot: None,
     };
 
     let agent = BasicAgent::new(agent_config, Arc::new(llm));
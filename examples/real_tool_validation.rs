@@ -162,6 +162,8 @@ async fn test_tool_execution() -> std::result::Result<(), Box<dyn std::error::Er
         metadata: None,
         max_tool_calls: Some(10),
         tool_timeout: Some(30),
+        reflection: None,
+        few_shot: None,
     };
 
     let agent = BasicAgent::new(agent_config, Arc::new(llm));
@@ -275,6 +277,8 @@ async fn test_complex_tool_calls() -> std::result::Result<(), Box<dyn std::error
         metadata: None,
         max_tool_calls: Some(10),
         tool_timeout: Some(30),
+        reflection: None,
+        few_shot: None,
     };
 
     let agent = BasicAgent::new(agent_config, Arc::new(llm));
@@ -361,6 +365,8 @@ async fn test_tool_error_handling() -> std::result::Result<(), Box<dyn std::erro
         metadata: None,
         max_tool_calls: Some(10),
         tool_timeout: Some(30),
+        reflection: None,
+        few_shot: None,
     };
 
     let agent = BasicAgent::new(agent_config, Arc::new(llm));
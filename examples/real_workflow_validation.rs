@@ -153,6 +153,8 @@ async fn test_basic_workflow() -> std::result::Result<(), Box<dyn std::error::Er
         metadata: None,
         max_tool_calls: None,
         tool_timeout: None,
+        reflection: None,
+        few_shot: None,
     };
     
     let workflow_agent = Arc::new(BasicAgent::new(workflow_config, Arc::new(llm)));
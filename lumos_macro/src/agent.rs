@@ -1,6 +1,9 @@
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{parse_macro_input, Expr, Ident, LitStr, Token, parse::{Parse, ParseStream}};
+use syn::{parse_macro_input, Expr, Ident, LitInt, LitStr, Token, braced, bracketed};
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
 
 /// 简化的Agent定义结构 - 使用syn直接解析
 struct SimpleAgentDef {
@@ -8,6 +11,143 @@ struct SimpleAgentDef {
     instructions: LitStr,
     provider: Expr,
     tools: Vec<Ident>,
+    guardrails: Option<GuardrailsDef>,
+    memory: Option<MemoryDef>,
+    rag: Option<RagDef>,
+}
+
+/// 护栏定义：`guardrails: { blocked_topics: [..], rules: [..], max_tool_calls: N }`
+#[derive(Default)]
+struct GuardrailsDef {
+    blocked_topics: Vec<LitStr>,
+    rules: Vec<LitStr>,
+    max_tool_calls: Option<LitInt>,
+}
+
+/// 内存类型
+enum MemoryKind {
+    /// 基于向量存储的语义内存
+    Vector,
+    /// 基本内存
+    Basic,
+}
+
+/// 内存定义：`memory: { type: vector, store: my_store, namespace: "ns" }`
+struct MemoryDef {
+    kind: MemoryKind,
+    store: Option<Expr>,
+    namespace: Option<LitStr>,
+}
+
+/// RAG绑定定义：`rag: { collection: "docs", top_k: 5, store: my_store }`
+struct RagDef {
+    collection: LitStr,
+    top_k: Option<LitInt>,
+    store: Option<Expr>,
+}
+
+/// 解析花括号内的`key: value`列表，对每个键调用`parse_value`
+fn parse_fields(
+    input: ParseStream,
+    mut parse_value: impl FnMut(&Ident, ParseStream) -> syn::Result<()>,
+) -> syn::Result<()> {
+    let content;
+    braced!(content in input);
+
+    while !content.is_empty() {
+        // `type`是关键字，需要按任意标识符解析
+        let key = Ident::parse_any(&content)?;
+        let _: Token![:] = content.parse()?;
+        parse_value(&key, &content)?;
+
+        if content.peek(Token![,]) {
+            let _: Token![,] = content.parse()?;
+        }
+    }
+    Ok(())
+}
+
+/// 解析字符串数组 `["a", "b", ...]`
+fn parse_str_list(input: ParseStream) -> syn::Result<Vec<LitStr>> {
+    let content;
+    bracketed!(content in input);
+    let items = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+    Ok(items.into_iter().collect())
+}
+
+fn unknown_field(key: &Ident, section: &str) -> syn::Error {
+    syn::Error::new(key.span(), format!("Unknown field '{}' in {} definition", key, section))
+}
+
+impl Parse for GuardrailsDef {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut guardrails = GuardrailsDef::default();
+        parse_fields(input, |key, value| {
+            match key.to_string().as_str() {
+                "blocked_topics" => guardrails.blocked_topics = parse_str_list(value)?,
+                "rules" => guardrails.rules = parse_str_list(value)?,
+                "max_tool_calls" => guardrails.max_tool_calls = Some(value.parse()?),
+                _ => return Err(unknown_field(key, "guardrails")),
+            }
+            Ok(())
+        })?;
+        Ok(guardrails)
+    }
+}
+
+impl Parse for MemoryDef {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let span = input.span();
+        let mut kind = None;
+        let mut store = None;
+        let mut namespace = None;
+        parse_fields(input, |key, value| {
+            match key.to_string().as_str() {
+                "type" => {
+                    let type_name: Ident = value.parse()?;
+                    kind = Some(match type_name.to_string().as_str() {
+                        "vector" => MemoryKind::Vector,
+                        "basic" => MemoryKind::Basic,
+                        other => return Err(syn::Error::new(
+                            type_name.span(),
+                            format!("Unknown memory type '{}', expected 'vector' or 'basic'", other),
+                        )),
+                    });
+                },
+                "store" => store = Some(value.parse()?),
+                "namespace" => namespace = Some(value.parse()?),
+                _ => return Err(unknown_field(key, "memory")),
+            }
+            Ok(())
+        })?;
+
+        let kind = kind.ok_or_else(|| syn::Error::new(span, "Missing 'type' field in memory definition"))?;
+        if matches!(kind, MemoryKind::Vector) && store.is_none() {
+            return Err(syn::Error::new(span, "Vector memory requires a 'store' field"));
+        }
+        Ok(MemoryDef { kind, store, namespace })
+    }
+}
+
+impl Parse for RagDef {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let span = input.span();
+        let mut collection = None;
+        let mut top_k = None;
+        let mut store = None;
+        parse_fields(input, |key, value| {
+            match key.to_string().as_str() {
+                "collection" => collection = Some(value.parse()?),
+                "top_k" => top_k = Some(value.parse()?),
+                "store" => store = Some(value.parse()?),
+                _ => return Err(unknown_field(key, "rag")),
+            }
+            Ok(())
+        })?;
+
+        let collection = collection.ok_or_else(|| syn::Error::new(span, "Missing 'collection' field in rag definition"))?;
+        Ok(RagDef { collection, top_k, store })
+    }
 }
 
 impl Parse for SimpleAgentDef {
@@ -16,6 +156,9 @@ impl Parse for SimpleAgentDef {
         let mut instructions = None;
         let mut provider = None;
         let mut tools = Vec::new();
+        let mut guardrails = None;
+        let mut memory = None;
+        let mut rag = None;
 
         // 直接解析字段，不需要额外的花括号
         while !input.is_empty() {
@@ -46,6 +189,15 @@ impl Parse for SimpleAgentDef {
                         }
                     }
                 },
+                "guardrails" => {
+                    guardrails = Some(input.parse::<GuardrailsDef>()?);
+                },
+                "memory" => {
+                    memory = Some(input.parse::<MemoryDef>()?);
+                },
+                "rag" => {
+                    rag = Some(input.parse::<RagDef>()?);
+                },
                 _ => {
                     return Err(syn::Error::new(key.span(), format!("Unknown field '{}' in agent definition", key)));
                 }
@@ -62,11 +214,25 @@ impl Parse for SimpleAgentDef {
         let instructions = instructions.ok_or_else(|| syn::Error::new(input.span(), "Missing 'instructions' field"))?;
         let provider = provider.ok_or_else(|| syn::Error::new(input.span(), "Missing 'provider' field"))?;
 
+        // RAG未指定存储时使用向量内存的存储
+        if let Some(rag) = &rag {
+            let memory_store = memory.as_ref().and_then(|memory: &MemoryDef| memory.store.as_ref());
+            if rag.store.is_none() && memory_store.is_none() {
+                return Err(syn::Error::new(
+                    rag.collection.span(),
+                    "RAG binding requires a 'store' field or a vector memory store",
+                ));
+            }
+        }
+
         Ok(SimpleAgentDef {
             name,
             instructions,
             provider,
             tools,
+            guardrails,
+            memory,
+            rag,
         })
     }
 }
@@ -74,14 +240,19 @@ impl Parse for SimpleAgentDef {
 /// 简化的agent!宏实现 - 回到syn解析
 ///
 /// 语法：
-/// ```
+/// ```text
 /// agent! {
 ///     name: "agent_name",
 ///     instructions: "instructions",
 ///     provider: provider_expr,
-///     tools: [tool1, tool2, ...]
+///     tools: [tool1, tool2, ...],
+///     guardrails: { blocked_topics: ["topic"], rules: ["rule"], max_tool_calls: 5 },
+///     memory: { type: vector, store: my_store },
+///     rag: { collection: "docs", top_k: 5 }
 /// }
 /// ```
+///
+/// `guardrails`、`memory`和`rag`可选；`rag`未指定`store`时使用向量内存的存储。
 pub fn agent(input: TokenStream) -> TokenStream {
     let agent_def = parse_macro_input!(input as SimpleAgentDef);
 
@@ -92,13 +263,73 @@ pub fn agent(input: TokenStream) -> TokenStream {
     // 生成工具注册代码
     let tool_registrations: Vec<_> = agent_def.tools.iter().map(|tool_name| {
         quote! {
-            agent.add_tool(#tool_name()).expect(&format!("Failed to add tool '{}' to agent", stringify!(#tool_name)));
+            let builder = builder.tool(#tool_name());
         }
     }).collect();
 
+    let guardrails = agent_def.guardrails.as_ref().map(|guardrails| {
+        let blocked_topics = &guardrails.blocked_topics;
+        let rules = &guardrails.rules;
+        let max_tool_calls = match &guardrails.max_tool_calls {
+            Some(max) => quote! { Some(#max) },
+            None => quote! { None },
+        };
+        quote! {
+            let builder = builder.guardrails(lumosai_core::config::GuardrailsConfig {
+                blocked_topics: Some(vec![#(#blocked_topics.to_string()),*]),
+                rules: Some(vec![#(#rules.to_string()),*]),
+                max_tool_calls: #max_tool_calls,
+            });
+        }
+    });
+
+    let memory_store = agent_def.memory.as_ref().and_then(|memory| memory.store.as_ref());
+    let memory = agent_def.memory.as_ref().map(|memory| {
+        let namespace = match &memory.namespace {
+            Some(namespace) => quote! { Some(#namespace.to_string()) },
+            None => quote! { None },
+        };
+        let memory_config = quote! {
+            lumosai_core::memory::MemoryConfig {
+                namespace: #namespace,
+                ..Default::default()
+            }
+        };
+        match memory.kind {
+            MemoryKind::Vector => {
+                let store = memory_store.expect("vector memory store is validated during parsing");
+                quote! {
+                    let builder = builder.memory(Arc::new(
+                        lumosai_core::memory::semantic::SemanticMemory::with_storage(
+                            &#memory_config,
+                            llm_provider.clone(),
+                            (#store).clone(),
+                        ).expect("Failed to create vector memory")
+                    ));
+                }
+            },
+            MemoryKind::Basic => quote! {
+                let builder = builder.memory_config(#memory_config);
+            },
+        }
+    });
+
+    let rag = agent_def.rag.as_ref().map(|rag| {
+        let collection = &rag.collection;
+        let store = rag.store.as_ref()
+            .or(memory_store)
+            .expect("rag store is validated during parsing");
+        let top_k = rag.top_k.as_ref().map(|top_k| quote! { .with_top_k(#top_k) });
+        quote! {
+            let builder = builder.rag(
+                lumosai_core::agent::RagBinding::new((#store).clone(), #collection) #top_k
+            );
+        }
+    });
+
     let expanded = quote! {
         {
-            use lumosai_core::agent::create_basic_agent;
+            use lumosai_core::agent::AgentBuilder;
             use lumosai_core::llm::LlmProvider;
             use std::sync::Arc;
 
@@ -106,16 +337,20 @@ pub fn agent(input: TokenStream) -> TokenStream {
             let llm_provider: Arc<dyn LlmProvider> = Arc::new(#provider_expr);
 
             // 创建代理
-            let mut agent = create_basic_agent(
-                #agent_name.to_string(),
-                #instructions.to_string(),
-                llm_provider
-            );
+            let builder = AgentBuilder::new()
+                .name(#agent_name)
+                .instructions(#instructions)
+                .model(llm_provider.clone());
 
             // 添加工具
             #(#tool_registrations)*
 
-            agent
+            // 护栏、内存与RAG绑定
+            #guardrails
+            #memory
+            #rag
+
+            builder.build().expect(&format!("Failed to build agent '{}'", #agent_name))
         }
     };
 
@@ -19,41 +19,45 @@ mod lumos;
 
 /// Macro for defining a tool in a simplified way
 /// 
+/// Turns a function into a constructor returning `Box<dyn Tool>`. The JSON Schema of
+/// each parameter is derived from its type, which must implement `Deserialize` and
+/// `schemars::JsonSchema`: `Option<T>` parameters are not required, and enums with
+/// unit variants are rendered as an `enum` of their names. Handlers may be `async`.
+/// If the handler returns a `Result`, an `Err` is reported to the LLM as an
+/// `{"error": "..."}` tool output instead of failing the run; invalid arguments are
+/// reported the same way.
+/// 
 /// # Example
-/// ```
+/// ```ignore
 /// use lumos_macro::tool;
 /// 
+/// #[derive(serde::Deserialize, schemars::JsonSchema)]
+/// #[serde(rename_all = "lowercase")]
+/// enum Unit {
+///     Celsius,
+///     Fahrenheit,
+/// }
+/// 
 /// #[tool(
-///     name = "calculator",
-///     description = "Performs basic math operations"
+///     name = "weather",
+///     description = "Gets the current temperature of a city"
 /// )]
-/// fn calculator(
-///     #[parameter(
-///         name = "operation",
-///         description = "The operation to perform: add, subtract, multiply, divide",
-///         r#type = "string", 
-///         required = true
-///     )]
-///     operation: String,
-///     
-///     #[parameter(
-///         name = "a",
-///         description = "First number",
-///         r#type = "number",
-///         required = true
-///     )]
-///     a: f64,
+/// async fn weather(
+///     #[parameter(description = "City name")]
+///     city: String,
 ///     
-///     #[parameter(
-///         name = "b",
-///         description = "Second number",
-///         r#type = "number",
-///         required = true
-///     )]
-///     b: f64,
-/// ) -> Result<serde_json::Value, lumosai_core::Error> {
-///     // Function implementation
+///     #[parameter(description = "Temperature unit, celsius by default")]
+///     unit: Option<Unit>,
+/// ) -> Result<serde_json::Value, WeatherError> {
+///     let celsius = fetch_temperature(&city).await?;
+///     let temperature = match unit.unwrap_or(Unit::Celsius) {
+///         Unit::Celsius => celsius,
+///         Unit::Fahrenheit => celsius * 9.0 / 5.0 + 32.0,
+///     };
+///     Ok(serde_json::json!({ "city": city, "temperature": temperature }))
 /// }
+/// 
+/// let tool = weather();
 /// ```
 #[proc_macro_attribute]
 pub fn tool(attr: TokenStream, item: TokenStream) -> TokenStream {
@@ -285,9 +289,12 @@ impl Parse for ToolExecuteArgs {
 
 /// 创建一个工作流定义，参考Mastra的工作流API设计
 /// 
+/// 步骤按顺序执行，`parallel`块中的步骤并行执行；`on_error`指定失败后重试、
+/// 执行备用步骤或中止，`input`把前面步骤的输出映射为步骤输入。
+/// 
 /// # 示例
 /// 
-/// ```rust
+/// ```rust,ignore
 /// workflow! {
 ///     name: "content_creation",
 ///     description: "创建高质量的内容",
@@ -296,12 +303,33 @@ impl Parse for ToolExecuteArgs {
 ///             name: "research",
 ///             agent: researcher,
 ///             instructions: "进行深入的主题研究",
+///             on_error: retry(3),
+///         },
+///         parallel {
+///             {
+///                 name: "writing",
+///                 agent: writer,
+///                 instructions: "将研究结果整理成文章",
+///                 input: { notes: "research.text" },
+///             },
+///             {
+///                 name: "outline",
+///                 agent: planner,
+///                 instructions: "列出文章大纲",
+///                 on_error: fallback_step("simple_outline"),
+///             }
+///         },
+///         {
+///             name: "review",
+///             agent: reviewer,
+///             instructions: "检查文章质量和准确性",
+///             input: { draft: "writing.text", outline: "outline" },
+///             on_error: abort,
 ///         },
 ///         {
-///             name: "writing",
-///             agent: writer,
-///             instructions: "将研究结果整理成文章",
-///             when: { completed("research") },
+///             name: "simple_outline",
+///             agent: planner,
+///             instructions: "列出简单的三段式大纲",
 ///         }
 ///     }
 /// }
@@ -313,6 +341,8 @@ pub fn workflow(input: TokenStream) -> TokenStream {
 
 /// 创建一个RAG管道，参考Mastra的RAG原语API设计
 /// 
+/// 分块大小、嵌入维度与向量存储是否匹配以及过滤条件的JSON语法在编译期校验。
+/// 
 /// # 示例
 /// 
 /// ```rust
@@ -415,26 +445,22 @@ pub fn mcp_client(input: TokenStream) -> TokenStream {
 /// 
 /// # 示例
 /// 
-/// ```rust
+/// ```rust,ignore
 /// agent! {
 ///     name: "research_assistant",
 ///     instructions: "你是一个专业的研究助手，擅长收集和整理信息。",
+///     provider: openai_adapter,
+///     tools: [search_tool, calculator_tool],
 ///     
-///     llm: {
-///         provider: openai_adapter,
-///         model: "gpt-4"
+///     guardrails: {
+///         blocked_topics: ["医疗诊断"],
+///         rules: ["引用信息时注明来源"],
+///         max_tool_calls: 5
 ///     },
 ///     
-///     memory: {
-///         store_type: "buffer",
-///         capacity: 10
-///     },
+///     memory: { type: vector, store: vector_store },
 ///     
-///     tools: {
-///         search_tool,
-///         calculator_tool: { precision: 2 },
-///         web_browser: { javascript: true, screenshots: true }
-///     }
+///     rag: { collection: "papers", top_k: 5 }
 /// }
 /// ```
 #[proc_macro]
@@ -539,6 +565,10 @@ pub fn tool_nom(input: TokenStream) -> TokenStream {
 
 /// 配置整个Lumos应用，参考Mastra的应用级API
 ///
+/// 展开为注册了所有组件的`LumosApp`。组件以`名称`（使用同名变量）或`名称: 表达式`声明，
+/// 工具给出构造函数（如`#[tool]`生成的函数）。`app.router()`为每个代理和工作流生成HTTP路由，
+/// 并在`/mcp`提供已注册的工具；指定`server`后`app.start().await`会启动HTTP服务。
+///
 /// # 示例
 ///
 /// ```rust
@@ -547,24 +577,28 @@ pub fn tool_nom(input: TokenStream) -> TokenStream {
 ///     description: "一个能够提供股票信息的AI助手",
 ///
 ///     agents: {
-///         stockAgent
+///         stock_agent
 ///     },
 ///
 ///     tools: {
-///         stockPriceTool,
-///         stockInfoTool
+///         stock_price_tool,
+///         stock_info_tool
 ///     },
 ///
 ///     rags: {
-///         stockKnowledgeBase
+///         stock_knowledge_base
 ///     },
 ///
 ///     workflows: {
-///         stockAnalysisWorkflow
+///         stock_analysis: stock_analysis_workflow
 ///     },
 ///
-///     mcp_endpoints: vec!["https://api.example.com/mcp"]
+///     mcp_endpoints: vec!["https://api.example.com/mcp"],
+///
+///     server: { host: "0.0.0.0", port: 8080 }
 /// };
+///
+/// app.start().await?;
 /// ```
 #[proc_macro]
 pub fn lumos(input: TokenStream) -> TokenStream {
@@ -1,7 +1,9 @@
 use proc_macro::TokenStream;
-use quote::{quote, format_ident};
+use std::collections::HashSet;
+
+use quote::quote;
 use syn::{
-    parse_macro_input, braced, Expr, Ident, LitStr, Token,
+    parse_macro_input, braced, token, Expr, Ident, LitInt, LitStr, Token,
     parse::{Parse, ParseStream},
     punctuated::Punctuated,
 };
@@ -43,6 +45,41 @@ impl Parse for ComponentsConfig {
     }
 }
 
+// HTTP服务的监听地址：`server: { host: "0.0.0.0", port: 8080 }`
+struct ServerDef {
+    host: Option<LitStr>,
+    port: Option<LitInt>,
+}
+
+impl Parse for ServerDef {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let content;
+        let _ = braced!(content in input);
+        
+        let mut host = None;
+        let mut port = None;
+        
+        while !content.is_empty() {
+            let key: Ident = content.parse()?;
+            let _: Token![:] = content.parse()?;
+            
+            match key.to_string().as_str() {
+                "host" => host = Some(content.parse()?),
+                "port" => {
+                    let value: LitInt = content.parse()?;
+                    value.base10_parse::<u16>()
+                        .map_err(|_| syn::Error::new(value.span(), "port must be between 0 and 65535"))?;
+                    port = Some(value);
+                },
+                _ => return Err(syn::Error::new(key.span(), "Unknown field in server options, expected `host` or `port`")),
+            }
+            let _: Option<Token![,]> = content.parse()?;
+        }
+        
+        Ok(ServerDef { host, port })
+    }
+}
+
 // 应用定义
 struct LumosAppDef {
     name: LitStr,
@@ -52,12 +89,19 @@ struct LumosAppDef {
     rags: Option<ComponentsConfig>,
     workflows: Option<ComponentsConfig>,
     mcp_endpoints: Option<Expr>,
+    server: Option<ServerDef>,
 }
 
 impl Parse for LumosAppDef {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        let content;
-        let _ = braced!(content in input);
+        // 字段外层的大括号可以省略
+        let braced_content;
+        let content = if input.peek(token::Brace) {
+            let _ = braced!(braced_content in input);
+            &braced_content
+        } else {
+            input
+        };
         
         let mut name = None;
         let mut description = None;
@@ -66,6 +110,7 @@ impl Parse for LumosAppDef {
         let mut rags = None;
         let mut workflows = None;
         let mut mcp_endpoints = None;
+        let mut server = None;
         
         while !content.is_empty() {
             let key: Ident = content.parse()?;
@@ -100,6 +145,10 @@ impl Parse for LumosAppDef {
                     mcp_endpoints = Some(content.parse()?);
                     let _: Option<Token![,]> = content.parse()?;
                 },
+                "server" => {
+                    server = Some(content.parse()?);
+                    let _: Option<Token![,]> = content.parse()?;
+                },
                 _ => return Err(syn::Error::new(key.span(), "Unknown field in Lumos app definition")),
             }
         }
@@ -114,136 +163,112 @@ impl Parse for LumosAppDef {
             rags,
             workflows,
             mcp_endpoints,
+            server,
         })
     }
 }
 
+/// 组件的注册名和表达式；只写名称时使用同名变量
+fn components(config: &Option<ComponentsConfig>) -> syn::Result<Vec<(LitStr, proc_macro2::TokenStream)>> {
+    let mut seen = HashSet::new();
+    let mut result = Vec::new();
+    
+    for item in config.iter().flat_map(|config| config.items.iter()) {
+        let name = item.name.to_string();
+        if !seen.insert(name.clone()) {
+            return Err(syn::Error::new(item.name.span(), format!("Component '{}' is declared twice", name)));
+        }
+        
+        let ident = &item.name;
+        let expr = match &item.expr {
+            Some(expr) => quote! { #expr },
+            None => quote! { #ident },
+        };
+        result.push((LitStr::new(&name, item.name.span()), expr));
+    }
+    
+    Ok(result)
+}
+
 /// 实现lumos!宏
+///
+/// 展开为注册了所有组件的`LumosApp`：代理和工作流按名称提供HTTP路由，工具通过MCP端点提供；
+/// 指定`server`时，`app.start().await`会在该地址上启动HTTP服务。
 pub fn lumos(input: TokenStream) -> TokenStream {
     let app_def = parse_macro_input!(input as LumosAppDef);
-    
-    let app_name = &app_def.name;
-    let app_name_str = app_name.value();
-    let _app_var_name = format_ident!("{}", app_name_str.to_lowercase().replace("-", "_")); // 标记为未使用
+    match generate_app(&app_def) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
 
-    // 描述
-    let _description = match &app_def.description {
+fn generate_app(app_def: &LumosAppDef) -> syn::Result<proc_macro2::TokenStream> {
+    let app_name = &app_def.name;
+    
+    let description = match &app_def.description {
         Some(desc) => quote! { .with_description(#desc) },
         None => quote! {},
     };
-
-    // 处理代理
-    let _agent_registrations = if let Some(agents) = &app_def.agents {
-        let agent_statements = agents.items.iter().map(|item| {
-            let agent_name = &item.name;
-            
-            let agent_expr = match &item.expr {
-                Some(expr) => quote! { #expr },
-                None => quote! { #agent_name },
-            };
-            
-            quote! {
-                app.add_agent(#agent_name.to_string(), #agent_expr);
-            }
-        }).collect::<Vec<_>>();
-        
-        quote! {
-            #(#agent_statements)*
-        }
-    } else {
-        quote! {}
-    };
     
-    // 处理工具
-    let _tool_registrations = if let Some(tools) = &app_def.tools {
-        let tool_statements = tools.items.iter().map(|item| {
-            let tool_name = &item.name;
-            
-            let tool_expr = match &item.expr {
-                Some(expr) => quote! { #expr() },
-                None => quote! { #tool_name() },
+    let server = match &app_def.server {
+        Some(server) => {
+            let host = match &server.host {
+                Some(host) => quote! { #host.to_string() },
+                None => quote! { defaults.host },
             };
-            
-            quote! {
-                app.add_tool(#tool_name.to_string(), #tool_expr);
-            }
-        }).collect::<Vec<_>>();
-        
-        quote! {
-            #(#tool_statements)*
-        }
-    } else {
-        quote! {}
-    };
-    
-    // 处理RAG
-    let _rag_registrations = if let Some(rags) = &app_def.rags {
-        let rag_statements = rags.items.iter().map(|item| {
-            let rag_name = &item.name;
-            
-            let rag_expr = match &item.expr {
-                Some(expr) => quote! { #expr },
-                None => quote! { #rag_name },
+            let port = match &server.port {
+                Some(port) => quote! { #port },
+                None => quote! { defaults.port },
             };
-            
             quote! {
-                app.add_rag(#rag_name.to_string(), #rag_expr);
+                .with_server({
+                    let defaults = lumosai_core::app::ServerConfig::default();
+                    lumosai_core::app::ServerConfig::new(#host, #port)
+                })
             }
-        }).collect::<Vec<_>>();
-        
-        quote! {
-            #(#rag_statements)*
-        }
-    } else {
-        quote! {}
+        },
+        None => quote! {},
     };
     
-    // 处理工作流
-    let _workflow_registrations = if let Some(workflows) = &app_def.workflows {
-        let workflow_statements = workflows.items.iter().map(|item| {
-            let workflow_name = &item.name;
-            
-            let workflow_expr = match &item.expr {
-                Some(expr) => quote! { #expr },
-                None => quote! { #workflow_name },
-            };
-            
-            quote! {
-                app.add_workflow(#workflow_name.to_string(), #workflow_expr);
-            }
-        }).collect::<Vec<_>>();
-        
-        quote! {
-            #(#workflow_statements)*
-        }
-    } else {
-        quote! {}
-    };
+    // 代理、RAG和工作流按值注册
+    let agent_registrations = components(&app_def.agents)?.into_iter().map(|(name, expr)| {
+        quote! { app.add_agent(#name.to_string(), #expr); }
+    });
+    let rag_registrations = components(&app_def.rags)?.into_iter().map(|(name, expr)| {
+        quote! { app.add_rag(#name.to_string(), #expr); }
+    });
+    let workflow_registrations = components(&app_def.workflows)?.into_iter().map(|(name, expr)| {
+        quote! { app.add_workflow(#name.to_string(), #expr); }
+    });
     
-    // 处理MCP端点
-    let _mcp_config = if let Some(endpoints) = &app_def.mcp_endpoints {
+    // 工具的表达式是构造函数，例如`#[tool]`生成的函数
+    let tool_registrations = components(&app_def.tools)?.into_iter().map(|(name, constructor)| {
         quote! {
-            app.set_mcp_endpoints(#endpoints);
+            let tool: Box<dyn lumosai_core::tool::Tool> = #constructor();
+            app.add_shared_tool(#name.to_string(), ::std::sync::Arc::from(tool));
         }
-    } else {
-        quote! {}
-    };
+    });
     
-    // 简化的应用实现，返回第一个代理
-    let expanded = if let Some(agents) = &app_def.agents {
-        if let Some(first_agent) = agents.items.first() {
-            let agent_name = &first_agent.name;
-            let agent_expr = match &first_agent.expr {
-                Some(expr) => quote! { #expr },
-                None => quote! { #agent_name() },
-            };
-            quote! { #agent_expr }
-        } else {
-            quote! { compile_error!("Lumos app must have at least one agent") }
-        }
-    } else {
-        quote! { compile_error!("Lumos app must have at least one agent") }
+    let mcp_config = match &app_def.mcp_endpoints {
+        Some(endpoints) => quote! {
+            app.set_mcp_endpoints(
+                (#endpoints).into_iter().map(|endpoint| endpoint.to_string()).collect()
+            );
+        },
+        None => quote! {},
     };
     
-    TokenStream::from(expanded)
-} 
\ No newline at end of file
+    Ok(quote! {
+        {
+            let mut app = lumosai_core::app::LumosApp::new(#app_name)
+                #description
+                #server;
+            #(#agent_registrations)*
+            #({ #tool_registrations })*
+            #(#rag_registrations)*
+            #(#workflow_registrations)*
+            #mcp_config
+            app
+        }
+    })
+}
@@ -1,6 +1,6 @@
 use proc_macro::TokenStream;
 use quote::{quote, format_ident};
-use syn::{parse_macro_input, LitStr, Expr, Token, braced, parse::{Parse, ParseStream}};
+use syn::{parse_macro_input, LitStr, Expr, Lit, Token, braced, parse::{Parse, ParseStream}};
 
 // RAG管道的Chunk选项
 struct ChunkOptions {
@@ -301,6 +301,212 @@ impl Parse for RagPipelineDef {
     }
 }
 
+// 支持的分块策略
+const CHUNK_STRATEGIES: &[&str] = &["recursive", "character", "token", "markdown", "html", "json", "latex"];
+
+// 支持的向量存储及其最大向量维度（None表示不限）
+const VECTOR_STORES: &[(&str, Option<usize>)] = &[
+    ("memory", None),
+    ("pgvector", Some(16000)),
+    ("postgres", Some(16000)),
+    ("qdrant", Some(65536)),
+    ("weaviate", Some(65535)),
+    ("milvus", Some(32768)),
+    ("lancedb", None),
+];
+
+// 已知嵌入模型的原生维度，以及是否支持降维
+const EMBEDDING_MODELS: &[(&str, usize, bool)] = &[
+    ("text-embedding-3-small", 1536, true),
+    ("text-embedding-3-large", 3072, true),
+    ("text-embedding-ada-002", 1536, false),
+];
+
+// 整数字面量的值；非字面量表达式在运行时再检查
+fn literal_usize(expr: &Expr) -> syn::Result<Option<usize>> {
+    match expr {
+        Expr::Lit(syn::ExprLit { lit: Lit::Int(int), .. }) => int.base10_parse().map(Some),
+        Expr::Group(group) => literal_usize(&group.expr),
+        Expr::Paren(paren) => literal_usize(&paren.expr),
+        _ => Ok(None),
+    }
+}
+
+// 收集校验错误，一次报告全部问题
+#[derive(Default)]
+struct Errors(Option<syn::Error>);
+
+impl Errors {
+    fn push(&mut self, error: syn::Error) {
+        match &mut self.0 {
+            Some(errors) => errors.combine(error),
+            None => self.0 = Some(error),
+        }
+    }
+
+    fn check(&mut self, result: syn::Result<()>) {
+        if let Err(error) = result {
+            self.push(error);
+        }
+    }
+
+    fn finish(self) -> syn::Result<()> {
+        self.0.map_or(Ok(()), Err)
+    }
+}
+
+impl RagPipelineDef {
+    // 编译期校验管道配置
+    fn validate(&self) -> syn::Result<()> {
+        let mut errors = Errors::default();
+        
+        if syn::parse_str::<syn::Ident>(&self.name.value()).is_err() {
+            errors.push(syn::Error::new(
+                self.name.span(),
+                format!("Pipeline name '{}' must be a valid Rust identifier", self.name.value()),
+            ));
+        }
+        
+        errors.check(self.pipeline.chunk.validate());
+        errors.check(self.pipeline.store.validate());
+        errors.check(self.validate_dimensions());
+        if let Some(query) = &self.query_pipeline {
+            errors.check(query.validate());
+        }
+        
+        errors.finish()
+    }
+    
+    // 嵌入维度须与模型和向量存储匹配
+    fn validate_dimensions(&self) -> syn::Result<()> {
+        let embed = &self.pipeline.embed;
+        let model = embed.model.value();
+        let known_model = EMBEDDING_MODELS.iter().find(|(name, _, _)| *name == model);
+        
+        let explicit = match &embed.dimensions {
+            Some(expr) => match literal_usize(expr)? {
+                Some(0) => return Err(syn::Error::new_spanned(expr, "Embedding dimensions must be greater than 0")),
+                Some(dimensions) => Some((dimensions, expr)),
+                None => return Ok(()),
+            },
+            None => None,
+        };
+        
+        if let (Some((dimensions, expr)), Some((_, native, reducible))) = (explicit, known_model) {
+            if dimensions > *native || (!reducible && dimensions != *native) {
+                let expected = if *reducible { format!("at most {}", native) } else { format!("exactly {}", native) };
+                return Err(syn::Error::new_spanned(
+                    expr,
+                    format!("Model '{}' produces {}-dimensional embeddings; dimensions must be {}", model, native, expected),
+                ));
+            }
+        }
+        
+        let db = self.pipeline.store.db.value();
+        let max = VECTOR_STORES.iter().find(|(name, _)| *name == db).and_then(|(_, max)| *max);
+        let dimensions = explicit.map(|(dimensions, _)| dimensions).or(known_model.map(|(_, native, _)| *native));
+        if let (Some(dimensions), Some(max)) = (dimensions, max) {
+            if dimensions > max {
+                let message = format!(
+                    "{} dimensions exceed the maximum of {} supported by the '{}' store",
+                    dimensions, max, db,
+                );
+                return Err(match explicit {
+                    Some((_, expr)) => syn::Error::new_spanned(expr, message),
+                    None => syn::Error::new(embed.model.span(), message),
+                });
+            }
+        }
+        
+        Ok(())
+    }
+}
+
+impl ChunkOptions {
+    fn validate(&self) -> syn::Result<()> {
+        let mut errors = Errors::default();
+        
+        let chunk_size = literal_usize(&self.chunk_size)?;
+        if chunk_size == Some(0) {
+            errors.push(syn::Error::new_spanned(&self.chunk_size, "chunk_size must be greater than 0"));
+        }
+        
+        if let Some(overlap_expr) = &self.chunk_overlap {
+            if let (Some(overlap), Some(size)) = (literal_usize(overlap_expr)?, chunk_size) {
+                if size > 0 && overlap >= size {
+                    errors.push(syn::Error::new_spanned(
+                        overlap_expr,
+                        format!("chunk_overlap ({}) must be smaller than chunk_size ({})", overlap, size),
+                    ));
+                }
+            }
+        }
+        
+        if let Some(separator) = &self.separator {
+            if separator.value().is_empty() {
+                errors.push(syn::Error::new(separator.span(), "separator must not be empty"));
+            }
+        }
+        
+        if let Some(strategy) = &self.strategy {
+            if !CHUNK_STRATEGIES.contains(&strategy.value().as_str()) {
+                errors.push(syn::Error::new(
+                    strategy.span(),
+                    format!("Unknown chunk strategy '{}', expected one of: {}", strategy.value(), CHUNK_STRATEGIES.join(", ")),
+                ));
+            }
+        }
+        
+        errors.finish()
+    }
+}
+
+impl StoreOptions {
+    fn validate(&self) -> syn::Result<()> {
+        let mut errors = Errors::default();
+        
+        let db = self.db.value();
+        if !VECTOR_STORES.iter().any(|(name, _)| *name == db) {
+            let supported: Vec<&str> = VECTOR_STORES.iter().map(|(name, _)| *name).collect();
+            errors.push(syn::Error::new(
+                self.db.span(),
+                format!("Unknown vector store '{}', expected one of: {}", db, supported.join(", ")),
+            ));
+        }
+        
+        if self.collection.value().trim().is_empty() {
+            errors.push(syn::Error::new(self.collection.span(), "collection must not be empty"));
+        }
+        
+        errors.finish()
+    }
+}
+
+impl QueryOptions {
+    fn validate(&self) -> syn::Result<()> {
+        let mut errors = Errors::default();
+        
+        if let Some(top_k) = &self.top_k {
+            if literal_usize(top_k)? == Some(0) {
+                errors.push(syn::Error::new_spanned(top_k, "top_k must be greater than 0"));
+            }
+        }
+        
+        if let Some(filter) = &self.filter {
+            match serde_json::from_str::<serde_json::Value>(&filter.value()) {
+                Ok(serde_json::Value::Object(_)) => {},
+                Ok(_) => errors.push(syn::Error::new(filter.span(), "filter must be a JSON object")),
+                Err(e) => errors.push(syn::Error::new(
+                    filter.span(),
+                    format!("Invalid filter JSON: {}", e),
+                )),
+            }
+        }
+        
+        errors.finish()
+    }
+}
+
 /// 创建一个RAG管道，参考Mastra的RAG原语API设计
 /// 
 /// # 示例
@@ -341,6 +547,9 @@ impl Parse for RagPipelineDef {
 /// ```
 pub fn rag_pipeline_impl(input: TokenStream) -> TokenStream {
     let rag_pipeline_def = parse_macro_input!(input as RagPipelineDef);
+    if let Err(err) = rag_pipeline_def.validate() {
+        return err.to_compile_error().into();
+    }
     
     let name = &rag_pipeline_def.name;
     let source = &rag_pipeline_def.source;
@@ -450,4 +659,89 @@ pub fn rag_pipeline_impl(input: TokenStream) -> TokenStream {
     };
     
     TokenStream::from(expanded)
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validate(input: &str) -> syn::Result<()> {
+        syn::parse_str::<RagPipelineDef>(input)?.validate()
+    }
+
+    fn pipeline(chunk: &str, embed: &str, store: &str, query: &str) -> String {
+        format!(
+            r#"{{
+                name: "knowledge_base",
+                source: DocumentSource::from_directory("./docs"),
+                pipeline: {{
+                    chunk: {{ {} }},
+                    embed: {{ {} }},
+                    store: {{ {} }}
+                }},
+                query_pipeline: {{ {} }}
+            }}"#,
+            chunk, embed, store, query,
+        )
+    }
+
+    #[test]
+    fn test_valid_pipeline() {
+        let input = pipeline(
+            r#"chunk_size: 1000, chunk_overlap: 200, strategy: "recursive""#,
+            r#"model: "text-embedding-3-small", dimensions: 1536"#,
+            r#"db: "pgvector", collection: "embeddings""#,
+            r###"top_k: 5, filter: r#"{ "type": { "$in": ["article", "faq"] } }"#"###,
+        );
+        assert!(validate(&input).is_ok());
+
+        // 非字面量表达式留到运行时检查
+        let input = pipeline("chunk_size: CHUNK_SIZE, chunk_overlap: 200", r#"model: "custom", dimensions: DIMS"#, r#"db: "memory", collection: "docs""#, "");
+        assert!(validate(&input).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_chunk_options() {
+        let input = pipeline(r#"chunk_size: 100, chunk_overlap: 100, strategy: "sentence""#, r#"model: "m""#, r#"db: "memory", collection: "docs""#, "");
+        let messages: Vec<String> = validate(&input).unwrap_err().into_iter().map(|e| e.to_string()).collect();
+        assert_eq!(messages, vec![
+            "chunk_overlap (100) must be smaller than chunk_size (100)".to_string(),
+            format!("Unknown chunk strategy 'sentence', expected one of: {}", CHUNK_STRATEGIES.join(", ")),
+        ]);
+
+        let input = pipeline("chunk_size: 0", r#"model: "m""#, r#"db: "memory", collection: "docs""#, "");
+        assert_eq!(validate(&input).unwrap_err().to_string(), "chunk_size must be greater than 0");
+    }
+
+    #[test]
+    fn test_dimensions_checked_against_model_and_store() {
+        let store = r#"db: "qdrant", collection: "docs""#;
+        let input = pipeline("chunk_size: 500", r#"model: "text-embedding-3-small", dimensions: 3072"#, store, "");
+        assert_eq!(
+            validate(&input).unwrap_err().to_string(),
+            "Model 'text-embedding-3-small' produces 1536-dimensional embeddings; dimensions must be at most 1536",
+        );
+
+        let input = pipeline("chunk_size: 500", r#"model: "text-embedding-ada-002", dimensions: 512"#, store, "");
+        assert!(validate(&input).unwrap_err().to_string().ends_with("dimensions must be exactly 1536"));
+
+        let input = pipeline("chunk_size: 500", r#"model: "custom", dimensions: 20000"#, r#"db: "pgvector", collection: "docs""#, "");
+        assert_eq!(
+            validate(&input).unwrap_err().to_string(),
+            "20000 dimensions exceed the maximum of 16000 supported by the 'pgvector' store",
+        );
+    }
+
+    #[test]
+    fn test_invalid_store_and_query_options() {
+        let input = pipeline("chunk_size: 500", r#"model: "m""#, r#"db: "mongo", collection: "docs""#, "");
+        assert!(validate(&input).unwrap_err().to_string().starts_with("Unknown vector store 'mongo'"));
+
+        let input = pipeline("chunk_size: 500", r#"model: "m""#, r#"db: "memory", collection: "docs""#, r#"top_k: 0, filter: "{ \"type\": }""#);
+        let messages: Vec<String> = validate(&input).unwrap_err().into_iter().map(|e| e.to_string()).collect();
+        assert_eq!(messages[0], "top_k must be greater than 0");
+        assert_eq!(messages[1], "Invalid filter JSON: expected value at line 1 column 11");
+
+        let input = pipeline("chunk_size: 500", r#"model: "m""#, r#"db: "memory", collection: "docs""#, r#"filter: "[1, 2]""#);
+        assert_eq!(validate(&input).unwrap_err().to_string(), "filter must be a JSON object");
+    }
+}
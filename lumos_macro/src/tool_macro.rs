@@ -4,6 +4,7 @@ use syn::{
     parse::{Parse, ParseStream},
     parse_macro_input, Expr, Ident, ItemFn, LitStr, Token
 };
+use syn::ext::IdentExt;
 use syn::spanned::Spanned;
 use proc_macro2::Span;
 use std::str::FromStr;
@@ -16,33 +17,35 @@ pub struct ToolAttributes {
     pub description: LitStr,
 }
 
+/// Parse the `=` (or legacy `:`) separating an attribute key from its value
+fn parse_separator(input: ParseStream) -> syn::Result<()> {
+    if input.peek(Token![=]) {
+        input.parse::<Token![=]>()?;
+    } else {
+        input.parse::<Token![:]>()?;
+    }
+    Ok(())
+}
+
 impl Parse for ToolAttributes {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        let content;
-        let _ = syn::parenthesized!(content in input);
-        
         let mut name = None;
         let mut description = None;
         
-        while !content.is_empty() {
-            let key: Ident = content.parse()?;
-            let _: Token![:] = content.parse()?;
+        while !input.is_empty() {
+            let key: Ident = input.call(Ident::parse_any)?;
+            parse_separator(input)?;
             
             match key.to_string().as_str() {
-                "name" => {
-                    name = Some(content.parse()?);
-                    let _: Option<Token![,]> = content.parse()?;
-                },
-                "description" => {
-                    description = Some(content.parse()?);
-                    let _: Option<Token![,]> = content.parse()?;
-                },
+                "name" => name = Some(input.parse()?),
+                "description" => description = Some(input.parse()?),
                 _ => return Err(syn::Error::new(key.span(), "Unknown attribute in tool definition")),
             }
+            let _: Option<Token![,]> = input.parse()?;
         }
         
-        let name = name.ok_or_else(|| syn::Error::new(content.span(), "Missing 'name' attribute in tool definition"))?;
-        let description = description.ok_or_else(|| syn::Error::new(content.span(), "Missing 'description' attribute in tool definition"))?;
+        let name = name.ok_or_else(|| syn::Error::new(input.span(), "Missing 'name' attribute in tool definition"))?;
+        let description = description.ok_or_else(|| syn::Error::new(input.span(), "Missing 'description' attribute in tool definition"))?;
         
         Ok(ToolAttributes {
             name,
@@ -52,64 +55,43 @@ impl Parse for ToolAttributes {
 }
 
 // 参数属性解析
+//
+// All keys are optional: the name defaults to the argument name, and the JSON Schema
+// type and whether the parameter is required are inferred from the argument type.
+#[derive(Default)]
 pub struct ParameterAttributes {
-    pub name: LitStr,
-    pub description: LitStr,
-    pub type_: LitStr,
-    pub required: bool,
+    pub name: Option<LitStr>,
+    pub description: Option<LitStr>,
+    pub type_: Option<LitStr>,
+    pub required: Option<bool>,
 }
 
 impl Parse for ParameterAttributes {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        let content;
-        let _ = syn::parenthesized!(content in input);
-        
-        let mut name = None;
-        let mut description = None;
-        let mut type_ = None;
-        let mut required = None;
+        let mut attributes = ParameterAttributes::default();
         
-        while !content.is_empty() {
-            let key: Ident = content.parse()?;
-            let _: Token![:] = content.parse()?;
+        while !input.is_empty() {
+            let key: Ident = input.call(Ident::parse_any)?;
+            parse_separator(input)?;
             
             match key.to_string().as_str() {
-                "name" => {
-                    name = Some(content.parse()?);
-                    let _: Option<Token![,]> = content.parse()?;
-                },
-                "description" => {
-                    description = Some(content.parse()?);
-                    let _: Option<Token![,]> = content.parse()?;
-                },
-                "r#type" | "type" => {
-                    type_ = Some(content.parse()?);
-                    let _: Option<Token![,]> = content.parse()?;
-                },
+                "name" => attributes.name = Some(input.parse()?),
+                "description" => attributes.description = Some(input.parse()?),
+                "r#type" | "type" => attributes.type_ = Some(input.parse()?),
                 "required" => {
-                    let expr: Expr = content.parse()?;
+                    let expr: Expr = input.parse()?;
                     if let Expr::Lit(syn::ExprLit { lit: syn::Lit::Bool(b), .. }) = expr {
-                        required = Some(b.value);
+                        attributes.required = Some(b.value);
                     } else {
                         return Err(syn::Error::new(expr.span(), "Expected boolean literal for 'required' attribute"));
                     }
-                    let _: Option<Token![,]> = content.parse()?;
                 },
                 _ => return Err(syn::Error::new(key.span(), "Unknown attribute in parameter definition")),
             }
+            let _: Option<Token![,]> = input.parse()?;
         }
         
-        let name = name.ok_or_else(|| syn::Error::new(content.span(), "Missing 'name' attribute in parameter definition"))?;
-        let description = description.ok_or_else(|| syn::Error::new(content.span(), "Missing 'description' attribute in parameter definition"))?;
-        let type_ = type_.ok_or_else(|| syn::Error::new(content.span(), "Missing 'type' attribute in parameter definition"))?;
-        let required = required.unwrap_or(false);
-        
-        Ok(ParameterAttributes {
-            name,
-            description,
-            type_,
-            required,
-        })
+        Ok(attributes)
     }
 }
 
@@ -158,72 +140,157 @@ impl Parse for ToolExecuteArgs {
     }
 }
 
+/// Inner type of an `Option<T>` argument
+fn option_inner(ty: &syn::Type) -> Option<&syn::Type> {
+    let syn::Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => match args.args.first()? {
+            syn::GenericArgument::Type(inner) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Whether the handler returns a `Result`, whose errors are reported to the LLM
+fn returns_result(output: &syn::ReturnType) -> bool {
+    match output {
+        syn::ReturnType::Type(_, ty) => matches!(
+            &**ty,
+            syn::Type::Path(path) if path.path.segments.last().is_some_and(|s| s.ident == "Result")
+        ),
+        syn::ReturnType::Default => false,
+    }
+}
+
 pub fn tool_macro(attr: TokenStream, item: TokenStream) -> TokenStream {
-    let input = parse_macro_input!(item as ItemFn);
+    let mut input = parse_macro_input!(item as ItemFn);
     let attrs = parse_macro_input!(attr as ToolAttributes);
     
-    let fn_name = &input.sig.ident;
-    let fn_params = &input.sig.inputs;
-    let fn_body = &input.block;
-    let _fn_output = &input.sig.output; // 标记为未使用
-    
+    let fn_name = input.sig.ident.clone();
+    let vis = input.vis.clone();
     let tool_name = attrs.name.value();
     let tool_description = attrs.description.value();
     
-    // Extract parameter metadata from attributes
+    // Build the schema and argument extraction for each parameter, stripping the
+    // #[parameter] attributes from the handler
     let mut parameters = Vec::new();
-    for param in fn_params.iter() {
-        if let syn::FnArg::Typed(pat_type) = param {
-            if let syn::Pat::Ident(pat_ident) = &*pat_type.pat {
-                let _param_name = &pat_ident.ident; // 标记为未使用
-                
-                // Find parameter attributes
-                for attr in &pat_ident.attrs {
-                    if attr.path().is_ident("parameter") {
-                        let param_attr = syn::parse2::<ParameterAttributes>(attr.meta.require_list().unwrap().tokens.clone()).unwrap();
-                        let name = param_attr.name.value();
-                        let description = param_attr.description.value();
-                        let type_ = param_attr.type_.value();
-                        let required = param_attr.required;
-                        
-                        let param_schema = quote! {
-                            ParameterSchema {
-                                name: #name.to_string(),
-                                description: #description.to_string(),
-                                r#type: #type_.to_string(),
-                                required: #required,
-                                properties: None,
-                                default: None,
-                            }
-                        };
-                        parameters.push(param_schema);
-                    }
-                }
+    let mut arguments = Vec::new();
+    let mut argument_names = Vec::new();
+    for param in input.sig.inputs.iter_mut() {
+        let syn::FnArg::Typed(pat_type) = param else {
+            return syn::Error::new(param.span(), "Tool functions cannot take self")
+                .to_compile_error()
+                .into();
+        };
+        let syn::Pat::Ident(pat_ident) = &*pat_type.pat else {
+            return syn::Error::new(pat_type.pat.span(), "Tool parameters must be plain identifiers")
+                .to_compile_error()
+                .into();
+        };
+        let ident = pat_ident.ident.clone();
+        
+        let mut param_attr = ParameterAttributes::default();
+        let mut kept = Vec::new();
+        for attr in pat_type.attrs.drain(..) {
+            if attr.path().is_ident("parameter") {
+                param_attr = match attr.parse_args::<ParameterAttributes>() {
+                    Ok(param_attr) => param_attr,
+                    Err(e) => return e.to_compile_error().into(),
+                };
+            } else {
+                kept.push(attr);
             }
         }
+        pat_type.attrs = kept;
+        
+        let ty = &*pat_type.ty;
+        let inner = option_inner(ty);
+        let name = param_attr.name.map(|name| name.value()).unwrap_or_else(|| ident.to_string());
+        let description = param_attr.description.map(|d| d.value()).unwrap_or_default();
+        let required = param_attr.required.unwrap_or(inner.is_none());
+        let schema_ty = inner.unwrap_or(ty);
+        let schema = match param_attr.type_ {
+            Some(type_) => quote! { serde_json::json!({ "type": #type_ }) },
+            None => quote! { lumosai_core::tool::macro_support::json_schema_for::<#schema_ty>() },
+        };
+        
+        parameters.push(quote! {
+            lumosai_core::tool::macro_support::MacroParameter {
+                name: #name,
+                description: #description,
+                required: #required,
+                schema: #schema,
+            }
+        });
+        arguments.push(quote! {
+            let #ident: #ty = match lumosai_core::tool::macro_support::argument(&params, #name) {
+                Ok(value) => value,
+                Err(e) => return Ok(lumosai_core::tool::macro_support::error_output(e)),
+            };
+        });
+        argument_names.push(ident);
     }
     
-    // Generate the ToolSchema
-    let schema_def = quote! {
-        ToolSchema {
-            parameters: vec![
-                #(#parameters),*
-            ]
+    let is_async = input.sig.asyncness.is_some();
+    let call = if is_async {
+        quote! { handler(#(#argument_names),*).await }
+    } else {
+        quote! { handler(#(#argument_names),*) }
+    };
+    let output = if returns_result(&input.sig.output) {
+        quote! { lumosai_core::tool::macro_support::tool_result(#call) }
+    } else {
+        quote! { lumosai_core::tool::macro_support::tool_output(#call) }
+    };
+    
+    // The handler keeps its body and signature, nested inside the tool constructor,
+    // which takes over its attributes and doc comments
+    let fn_attrs = std::mem::take(&mut input.attrs);
+    input.sig.ident = Ident::new("handler", Span::call_site());
+    input.vis = syn::Visibility::Inherited;
+    
+    let function_tool = if is_async {
+        quote! {
+            lumosai_core::tool::FunctionTool::new_async(
+                #tool_name,
+                #tool_description,
+                schema,
+                |params: serde_json::Value| Box::pin(async move {
+                    #(#arguments)*
+                    #output
+                }),
+            )
+        }
+    } else {
+        quote! {
+            lumosai_core::tool::FunctionTool::new(
+                #tool_name,
+                #tool_description,
+                schema,
+                |params: serde_json::Value| {
+                    #(#arguments)*
+                    #output
+                },
+            )
         }
     };
     
-    // Generate the FunctionTool implementation
     let expanded = quote! {
-        pub fn #fn_name() -> Box<dyn Tool> {
-            Box::new(FunctionTool::new(
-                #tool_name.to_string(),
-                #tool_description.to_string(),
-                #schema_def,
-                |params| {
-                    // The actual implementation function
-                    #fn_body
-                },
-            ))
+        #(#fn_attrs)*
+        #vis fn #fn_name() -> Box<dyn lumosai_core::tool::Tool> {
+            #input
+            
+            let schema = lumosai_core::tool::macro_support::tool_schema(vec![
+                #(#parameters),*
+            ]);
+            Box::new(#function_tool)
         }
     };
     
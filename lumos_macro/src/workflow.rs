@@ -1,32 +1,97 @@
+use std::collections::HashSet;
+
 use proc_macro::TokenStream;
-use quote::{quote, format_ident};
-use syn::{parse_macro_input, LitStr, Expr, Token, braced, parse::{Parse, ParseStream}};
+use quote::quote;
+use syn::{parse_macro_input, Ident, LitInt, LitStr, Expr, Token, braced, parenthesized, parse::{Parse, ParseStream}};
 use syn::punctuated::Punctuated;
 
+/// 未指定次数的`retry`的最大执行次数
+const DEFAULT_RETRY_ATTEMPTS: usize = 3;
+
+// 步骤失败后的处理方式
+enum OnError {
+    Retry(usize),
+    FallbackStep(LitStr),
+    Abort,
+}
+
+impl Parse for OnError {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let kind: Ident = input.parse()?;
+        match kind.to_string().as_str() {
+            "retry" => {
+                let attempts = if input.peek(syn::token::Paren) {
+                    let content;
+                    parenthesized!(content in input);
+                    content.parse::<LitInt>()?.base10_parse()?
+                } else {
+                    DEFAULT_RETRY_ATTEMPTS
+                };
+                Ok(OnError::Retry(attempts))
+            },
+            "fallback_step" => {
+                let content;
+                parenthesized!(content in input);
+                Ok(OnError::FallbackStep(content.parse()?))
+            },
+            "abort" => Ok(OnError::Abort),
+            _ => Err(syn::Error::new(
+                kind.span(),
+                "Expected 'retry', 'retry(n)', 'fallback_step(\"step\")' or 'abort' for 'on_error'",
+            )),
+        }
+    }
+}
+
+// 输入映射：`key: "step.path"`
+struct InputMapping {
+    key: Ident,
+    source: LitStr,
+}
+
+impl Parse for InputMapping {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let key = input.parse()?;
+        let _: Token![:] = input.parse()?;
+        let source = input.parse()?;
+        Ok(InputMapping { key, source })
+    }
+}
+
+impl InputMapping {
+    /// 来源步骤的名称，`trigger`表示工作流输入
+    fn source_step(&self) -> String {
+        let source = self.source.value();
+        source.split('.').next().unwrap_or_default().to_string()
+    }
+}
+
 // 工作流步骤定义
 struct WorkflowStep {
     name: LitStr,
     agent: Expr,
     instructions: Option<LitStr>,
-    tools: Option<Expr>,
     when: Option<Expr>,
+    on_error: Option<OnError>,
+    input: Vec<InputMapping>,
 }
 
 impl Parse for WorkflowStep {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let content;
         let _ = braced!(content in input);
-        
+
         let mut name = None;
         let mut agent = None;
         let mut instructions = None;
-        let mut tools = None;
         let mut when = None;
-        
+        let mut on_error = None;
+        let mut mappings = Vec::new();
+
         while !content.is_empty() {
             let key: syn::Ident = content.parse()?;
             let _: Token![:] = content.parse()?;
-            
+
             match key.to_string().as_str() {
                 "name" => {
                     name = Some(content.parse()?);
@@ -40,91 +105,184 @@ impl Parse for WorkflowStep {
                     instructions = Some(content.parse()?);
                     let _: Option<Token![,]> = content.parse()?;
                 },
-                "tools" => {
-                    tools = Some(content.parse()?);
-                    let _: Option<Token![,]> = content.parse()?;
-                },
                 "when" => {
                     when = Some(content.parse()?);
                     let _: Option<Token![,]> = content.parse()?;
                 },
+                "on_error" => {
+                    on_error = Some(content.parse()?);
+                    let _: Option<Token![,]> = content.parse()?;
+                },
+                "input" => {
+                    let input_content;
+                    braced!(input_content in content);
+                    mappings = Punctuated::<InputMapping, Token![,]>::parse_terminated(&input_content)?
+                        .into_iter()
+                        .collect();
+                    let _: Option<Token![,]> = content.parse()?;
+                },
                 _ => return Err(syn::Error::new(key.span(), "Unknown field in workflow step")),
             }
         }
-        
+
         let name = name.ok_or_else(|| syn::Error::new(content.span(), "Missing 'name' field in workflow step"))?;
         let agent = agent.ok_or_else(|| syn::Error::new(content.span(), "Missing 'agent' field in workflow step"))?;
-        
+
         Ok(WorkflowStep {
             name,
             agent,
             instructions,
-            tools,
             when,
+            on_error,
+            input: mappings,
         })
     }
 }
 
+// 步骤列表中的一项：单个步骤或`parallel { ... }`步骤组
+enum StepEntry {
+    Single(Box<WorkflowStep>),
+    Parallel(Vec<WorkflowStep>),
+}
+
+impl Parse for StepEntry {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(Ident) {
+            let keyword: Ident = input.parse()?;
+            if keyword != "parallel" {
+                return Err(syn::Error::new(keyword.span(), "Expected a step or a 'parallel' block"));
+            }
+            let content;
+            braced!(content in input);
+            let steps: Vec<WorkflowStep> = Punctuated::<WorkflowStep, Token![,]>::parse_terminated(&content)?
+                .into_iter()
+                .collect();
+            if steps.is_empty() {
+                return Err(syn::Error::new(keyword.span(), "A 'parallel' block needs at least one step"));
+            }
+            Ok(StepEntry::Parallel(steps))
+        } else {
+            Ok(StepEntry::Single(Box::new(input.parse()?)))
+        }
+    }
+}
+
+impl StepEntry {
+    fn steps(&self) -> &[WorkflowStep] {
+        match self {
+            StepEntry::Single(step) => std::slice::from_ref(&**step),
+            StepEntry::Parallel(steps) => steps,
+        }
+    }
+}
+
 // 工作流定义
 struct WorkflowDef {
     name: LitStr,
     description: Option<LitStr>,
-    steps: Punctuated<WorkflowStep, Token![,]>,
+    steps: Punctuated<StepEntry, Token![,]>,
 }
 
 impl Parse for WorkflowDef {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        let content;
-        let _ = braced!(content in input);
-        
         let mut name = None;
         let mut description = None;
         let mut steps_content = None;
-        
-        while !content.is_empty() {
-            let key: syn::Ident = content.parse()?;
-            let _: Token![:] = content.parse()?;
-            
+
+        // 直接解析字段，不需要额外的花括号
+        while !input.is_empty() {
+            let key: syn::Ident = input.parse()?;
+            let _: Token![:] = input.parse()?;
+
             match key.to_string().as_str() {
                 "name" => {
-                    name = Some(content.parse()?);
-                    let _: Option<Token![,]> = content.parse()?;
+                    name = Some(input.parse()?);
+                    let _: Option<Token![,]> = input.parse()?;
                 },
                 "description" => {
-                    description = Some(content.parse()?);
-                    let _: Option<Token![,]> = content.parse()?;
+                    description = Some(input.parse()?);
+                    let _: Option<Token![,]> = input.parse()?;
                 },
                 "steps" => {
                     let steps_braced;
-                    braced!(steps_braced in content);
+                    braced!(steps_braced in input);
                     steps_content = Some(steps_braced);
-                    let _: Option<Token![,]> = content.parse()?;
+                    let _: Option<Token![,]> = input.parse()?;
                 },
                 _ => return Err(syn::Error::new(key.span(), "Unknown field in workflow definition")),
             }
         }
-        
-        let name = name.ok_or_else(|| syn::Error::new(content.span(), "Missing 'name' field in workflow definition"))?;
-        
-        let steps_content = steps_content.ok_or_else(|| 
-            syn::Error::new(content.span(), "Missing 'steps' field in workflow definition")
+
+        let name = name.ok_or_else(|| syn::Error::new(input.span(), "Missing 'name' field in workflow definition"))?;
+
+        let steps_content = steps_content.ok_or_else(||
+            syn::Error::new(input.span(), "Missing 'steps' field in workflow definition")
         )?;
-        
+
         let steps = Punctuated::parse_terminated(&steps_content)?;
-        
-        Ok(WorkflowDef {
+
+        let workflow = WorkflowDef {
             name,
             description,
             steps,
-        })
+        };
+        workflow.validate()?;
+        Ok(workflow)
+    }
+}
+
+impl WorkflowDef {
+    fn all_steps(&self) -> impl Iterator<Item = &WorkflowStep> {
+        self.steps.iter().flat_map(StepEntry::steps)
+    }
+
+    /// 作为备用步骤引用的步骤名称
+    fn fallback_steps(&self) -> HashSet<String> {
+        self.all_steps()
+            .filter_map(|step| match &step.on_error {
+                Some(OnError::FallbackStep(fallback)) => Some(fallback.value()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// 检查步骤名称唯一，且备用步骤和输入映射引用的步骤存在
+    fn validate(&self) -> syn::Result<()> {
+        let mut names = HashSet::new();
+        for step in self.all_steps() {
+            if !names.insert(step.name.value()) {
+                return Err(syn::Error::new(step.name.span(), format!("Duplicate step name '{}'", step.name.value())));
+            }
+        }
+
+        for step in self.all_steps() {
+            if let Some(OnError::FallbackStep(fallback)) = &step.on_error {
+                if !names.contains(&fallback.value()) {
+                    return Err(syn::Error::new(fallback.span(), format!("Unknown fallback step '{}'", fallback.value())));
+                }
+            }
+            for mapping in &step.input {
+                let source = mapping.source_step();
+                if source != "trigger" && !names.contains(&source) {
+                    return Err(syn::Error::new(
+                        mapping.source.span(),
+                        format!("Input '{}' refers to unknown step '{}'", mapping.key, source),
+                    ));
+                }
+            }
+        }
+        Ok(())
     }
 }
 
 /// 创建一个工作流定义，参考Mastra的工作流API设计
-/// 
+///
+/// 步骤按顺序执行；`parallel`块中的步骤在前一步完成后并行执行，全部完成后才执行下一步。
+/// 作为`fallback_step`引用的步骤不参与排序，只在对应步骤失败时执行。
+///
 /// # 示例
-/// 
-/// ```rust
+///
+/// ```text
 /// workflow! {
 ///     name: "content_creation",
 ///     description: "创建高质量的内容",
@@ -133,91 +291,130 @@ impl Parse for WorkflowDef {
 ///             name: "research",
 ///             agent: researcher,
 ///             instructions: "进行深入的主题研究",
+///             input: { topic: "trigger.topic" },
+///             on_error: retry(3),
 ///         },
-///         {
-///             name: "writing",
-///             agent: writer,
-///             instructions: "将研究结果整理成文章",
-///             when: { completed("research") },
+///         parallel {
+///             {
+///                 name: "writing",
+///                 agent: writer,
+///                 instructions: "将研究结果整理成文章",
+///                 input: { notes: "research.text" },
+///             },
+///             {
+///                 name: "outline",
+///                 agent: planner,
+///                 instructions: "列出文章大纲",
+///                 on_error: fallback_step("simple_outline"),
+///             }
 ///         },
 ///         {
 ///             name: "review",
 ///             agent: reviewer,
 ///             instructions: "检查文章质量和准确性",
-///             when: { completed("writing") },
+///             input: { draft: "writing.text", outline: "outline" },
+///             on_error: abort,
+///         },
+///         {
+///             name: "simple_outline",
+///             agent: planner,
+///             instructions: "列出简单的三段式大纲",
 ///         }
 ///     }
 /// }
 /// ```
 pub fn workflow_impl(input: TokenStream) -> TokenStream {
     let workflow_def = parse_macro_input!(input as WorkflowDef);
-    
+
     let workflow_name = &workflow_def.name;
-    let workflow_name_str = workflow_name.value();
-    let workflow_var_name = format_ident!("{}", workflow_name_str.to_lowercase());
-    
+
     let description = match &workflow_def.description {
-        Some(desc) => quote! { .with_description(#desc) },
+        Some(desc) => quote! { .description(#desc) },
         None => quote! {},
     };
-    
-    let mut step_defs = Vec::new();
+
     let mut step_registrations = Vec::new();
-    
-    for step in workflow_def.steps.iter() {
+
+    for step in workflow_def.all_steps() {
         let step_name = &step.name;
-        let step_name_str = step_name.value();
-        let step_var_name = format_ident!("step_{}", step_name_str.to_lowercase());
         let agent = &step.agent;
-        
+
         let instructions = match &step.instructions {
-            Some(instr) => quote! { .with_instructions(#instr) },
-            None => quote! {},
+            Some(instr) => quote! { #instr },
+            None => quote! { "" },
         };
-        
-        let tools = match &step.tools {
-            Some(tools_expr) => quote! { .with_tools(#tools_expr) },
-            None => quote! {},
-        };
-        
-        let step_def = quote! {
-            let #step_var_name = lumosai_core::workflow::WorkflowStep::new(#step_name)
-                .with_agent(#agent)
-                #instructions
-                #tools;
+
+        let condition = match &step.when {
+            Some(when) => quote! { Some(#when) },
+            None => quote! { None },
         };
-        
-        step_defs.push(step_def);
-        
-        let step_reg = if let Some(when) = &step.when {
+
+        let on_error = step.on_error.as_ref().map(|on_error| {
+            let policy = match on_error {
+                OnError::Retry(attempts) => quote! { lumosai_core::workflow::ErrorPolicy::Retry { attempts: #attempts } },
+                OnError::FallbackStep(fallback) => quote! { lumosai_core::workflow::ErrorPolicy::Fallback(#fallback.to_string()) },
+                OnError::Abort => quote! { lumosai_core::workflow::ErrorPolicy::Abort },
+            };
             quote! {
-                workflow_def.add_step_with_condition(#step_var_name, |ctx| {
-                    #when
-                });
+                let builder = builder.on_error(#step_name, #policy);
             }
-        } else {
+        });
+
+        let mappings = step.input.iter().map(|mapping| {
+            let key = mapping.key.to_string();
+            let source = &mapping.source;
             quote! {
-                workflow_def.add_step(#step_var_name);
+                let builder = builder.map_input(#step_name, #key, #source);
             }
-        };
-        
-        step_registrations.push(step_reg);
+        });
+
+        step_registrations.push(quote! {
+            let agent: std::sync::Arc<dyn lumosai_core::Agent> = (#agent).clone();
+            let builder = builder.add_step(
+                lumosai_core::workflow::BasicStep::from_agent(#step_name, agent, #instructions),
+                None,
+                #condition,
+            );
+            #on_error
+            #(#mappings)*
+        });
     }
-    
+
+    // 相邻阶段之间的依赖边，备用步骤不参与排序
+    let fallback_steps = workflow_def.fallback_steps();
+    let stages: Vec<Vec<&LitStr>> = workflow_def.steps.iter()
+        .map(|entry| {
+            entry.steps().iter()
+                .map(|step| &step.name)
+                .filter(|name| !fallback_steps.contains(&name.value()))
+                .collect::<Vec<_>>()
+        })
+        .filter(|stage| !stage.is_empty())
+        .collect();
+
+    let mut dependencies = Vec::new();
+    for window in stages.windows(2) {
+        for from in &window[0] {
+            for to in &window[1] {
+                dependencies.push(quote! {
+                    let builder = builder.add_dependency(#from, #to);
+                });
+            }
+        }
+    }
+
     let expanded = quote! {
         {
-            #(#step_defs)*
-            
-            let mut workflow_def = lumosai_core::workflow::WorkflowDefinition::new(None)
-                .with_name(#workflow_name)
+            let builder = lumosai_core::workflow::WorkflowImpl::new(#workflow_name.to_string(), #workflow_name.to_string())
                 #description;
-                
+
             #(#step_registrations)*
-            
-            let #workflow_var_name = workflow_def;
-            #workflow_var_name
+
+            #(#dependencies)*
+
+            builder.build()
         }
     };
-    
+
     TokenStream::from(expanded)
-} 
\ No newline at end of file
+}
@@ -0,0 +1,26 @@
+//! 构建脚本
+//!
+//! 启用 `c-bindings` 特性时，根据 `src/c_bindings` 生成C头文件 `include/lumosai.h`。
+
+fn main() {
+    #[cfg(feature = "c-bindings")]
+    generate_c_header();
+}
+
+#[cfg(feature = "c-bindings")]
+fn generate_c_header() {
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR is set by cargo");
+    println!("cargo:rerun-if-changed=src/c_bindings");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+
+    let config = cbindgen::Config::from_file(format!("{}/cbindgen.toml", crate_dir))
+        .expect("failed to read cbindgen.toml");
+
+    match cbindgen::generate_with_config(&crate_dir, config) {
+        Ok(bindings) => {
+            bindings.write_to_file(format!("{}/include/lumosai.h", crate_dir));
+        }
+        // 头文件生成失败不应阻断Rust构建，保留已提交的头文件
+        Err(e) => println!("cargo:warning=failed to generate C header: {}", e),
+    }
+}
@@ -0,0 +1,188 @@
+/* Lumos.ai C API - 由cbindgen自动生成，请勿手动修改 */
+
+#ifndef LUMOSAI_H
+#define LUMOSAI_H
+
+/* Generated with cbindgen:0.26.0 */
+
+#include <stdint.h>
+
+// C错误码
+typedef enum CErrorCode {
+  Success = 0,
+  InvalidParameter = 1,
+  RuntimeError = 2,
+  SerializationError = 3,
+  NetworkError = 4,
+  TimeoutError = 5,
+  UnknownError = 99,
+} CErrorCode;
+
+// 流轮询状态
+typedef enum CStreamStatus {
+  // 已取得一个文本片段
+  Chunk = 0,
+  // 暂无新片段，稍后再轮询
+  Pending = 1,
+  // 流已结束
+  Done = 2,
+  // 流出错，错误描述见 `lumos_last_error`
+  Failed = 3,
+} CStreamStatus;
+
+// C AgentBuilder句柄
+typedef void *CAgentBuilder;
+
+// C Tool句柄
+typedef void *CTool;
+
+// C Agent句柄
+typedef void *CAgent;
+
+// C Response句柄
+typedef void *CResponse;
+
+// C响应结构
+typedef struct CResponseData {
+  const char *content;
+  const char *response_type;
+  const char *error;
+  unsigned int execution_time_ms;
+  int success;
+} CResponseData;
+
+// 工具回调
+//
+// 接收注册时的 `user_data` 和JSON参数，返回JSON结果；返回NULL表示调用失败。
+// 回调可能在任意工作线程上被调用，实现需保证线程安全。
+typedef char *(*CToolCallback)(void *user_data,
+                               const char *arguments_json);
+
+// 宿主语言的释放函数
+typedef void (*CFreeCallback)(void *ptr);
+
+// C Stream句柄
+typedef void *CStream;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// 获取当前线程最近一次错误的描述
+//
+// 返回的指针在同一线程下一次出错前有效，无需释放；没有错误时返回NULL。
+const char *lumos_last_error(void);
+
+// 创建新的AgentBuilder
+CAgentBuilder lumos_agent_builder_new(void);
+
+// 设置Agent名称
+enum CErrorCode lumos_agent_builder_name(CAgentBuilder builder,
+                                         const char *name);
+
+// 设置Agent指令
+enum CErrorCode lumos_agent_builder_instructions(CAgentBuilder builder,
+                                                 const char *instructions);
+
+// 设置模型
+enum CErrorCode lumos_agent_builder_model(CAgentBuilder builder,
+                                          const char *model);
+
+// 添加工具
+enum CErrorCode lumos_agent_builder_tool(CAgentBuilder builder,
+                                         CTool tool);
+
+// 构建Agent
+enum CErrorCode lumos_agent_builder_build(CAgentBuilder builder,
+                                          CAgent *agent_out);
+
+// 生成响应
+enum CErrorCode lumos_agent_generate(CAgent agent,
+                                     const char *input,
+                                     CResponse *response_out);
+
+// 发送消息并获取回复文本
+//
+// `reply_out` 中的字符串需通过 `lumos_string_free` 释放。
+enum CErrorCode lumos_agent_send_message(CAgent agent,
+                                         const char *message,
+                                         char **reply_out);
+
+// 获取响应数据
+enum CErrorCode lumos_response_get_data(CResponse response,
+                                        struct CResponseData *data_out);
+
+// 释放Agent
+void lumos_agent_free(CAgent agent);
+
+// 释放AgentBuilder
+void lumos_agent_builder_free(CAgentBuilder builder);
+
+// 释放Response
+void lumos_response_free(CResponse response);
+
+// 释放C字符串
+void lumos_string_free(char *s);
+
+// 快速创建Agent
+enum CErrorCode lumos_quick_agent(const char *name,
+                                  const char *instructions,
+                                  CAgent *agent_out);
+
+// 工具相关函数
+// 创建Web搜索工具
+enum CErrorCode lumos_tool_web_search(CTool *tool_out);
+
+// 创建计算器工具
+enum CErrorCode lumos_tool_calculator(CTool *tool_out);
+
+// 释放Tool
+void lumos_tool_free(CTool tool);
+
+// 获取版本信息
+const char *lumos_version(void);
+
+// 使用宿主语言回调创建工具
+//
+// - `parameters_json` 为参数的JSON Schema，可为NULL；
+// - `free_result` 用于释放回调返回的字符串，可为NULL（字符串由宿主语言自行管理）；
+// - `free_user_data` 在工具及其所有副本被释放后调用，可为NULL；创建失败时 `user_data` 仍归调用方所有。
+//
+// 创建的工具通过 `lumos_agent_builder_tool` 注册到Agent，之后仍需调用 `lumos_tool_free` 释放句柄。
+enum CErrorCode lumos_tool_callback_new(const char *name,
+                                        const char *description,
+                                        const char *parameters_json,
+                                        CToolCallback callback,
+                                        CFreeCallback free_result,
+                                        void *user_data,
+                                        CFreeCallback free_user_data,
+                                        CTool *tool_out);
+
+// 开始流式生成
+//
+// 返回的流需通过 `lumos_stream_free` 释放；提前释放会停止生成。
+enum CErrorCode lumos_agent_stream(CAgent agent,
+                                   const char *message,
+                                   CStream *stream_out);
+
+// 非阻塞地轮询下一个片段
+//
+// 返回 `Chunk` 时 `chunk_out` 中的字符串需通过 `lumos_string_free` 释放。
+enum CStreamStatus lumos_stream_poll(CStream stream,
+                                     char **chunk_out);
+
+// 阻塞等待下一个片段
+//
+// 返回 `Chunk` 时 `chunk_out` 中的字符串需通过 `lumos_string_free` 释放。
+// 不得在宿主语言的异步运行时线程上调用。
+enum CStreamStatus lumos_stream_next(CStream stream,
+                                     char **chunk_out);
+
+// 释放Stream
+void lumos_stream_free(CStream stream);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* LUMOSAI_H */
@@ -76,6 +76,71 @@ export interface ToolMetadata {
   parameters: any;
 }
 
+export interface StreamChunk {
+  /** 文本块，流结束时为空 */
+  value?: string;
+  /** 流是否结束 */
+  done: boolean;
+}
+
+export interface FunctionToolOptions {
+  /** 工具名称 */
+  name: string;
+  /** 工具描述 */
+  description: string;
+  /** 参数的JSON Schema */
+  parameters?: any;
+}
+
+export interface VectorStoreRecord {
+  /** 记录ID，为空时自动生成 */
+  id?: string;
+  /** 向量 */
+  vector: number[];
+  /** 元数据 */
+  metadata?: Record<string, any>;
+}
+
+export interface VectorStoreMatch {
+  /** 记录ID */
+  id: string;
+  /** 相似度分数 */
+  score: number;
+  /** 文档内容 */
+  content?: string;
+  /** 元数据 */
+  metadata: Record<string, any>;
+}
+
+export interface VectorIndexInfo {
+  /** 索引名称 */
+  name: string;
+  /** 向量维度 */
+  dimension: number;
+  /** 向量数量 */
+  count: number;
+  /** 相似度度量 */
+  metric: string;
+}
+
+export interface RagPipelineOptions {
+  /** 索引名称，默认 documents */
+  index?: string;
+  /** 分块大小（字符），默认 1000 */
+  chunkSize?: number;
+  /** 分块重叠（字符），默认 200 */
+  chunkOverlap?: number;
+}
+
+export interface RetrievalResult {
+  /** 查询文本 */
+  query: string;
+  /** 拼接后的上下文 */
+  context: string;
+  /** 命中的文档片段 */
+  matches: VectorStoreMatch[];
+}
+
 /**
  * Lumos.ai Agent
  * 
@@ -109,6 +174,13 @@ export declare class Agent {
    */
   generateAsync(input: string): Promise<Response>;
 
+  /**
+   * 流式生成响应
+   * @param input 输入文本
+   * @returns 可用于 `for await` 的文本块流
+   */
+  stream(input: string): ResponseStream;
+
   /**
    * 获取Agent配置
    * @returns 配置信息
@@ -225,6 +297,112 @@ export declare class Tool {
   execute(parameters: Record<string, any>): ToolCallResult;
 }
 
+/**
+ * 流式响应
+ * 
+ * @example
+ * ```typescript
+ * for await (const chunk of agent.stream('讲个故事')) {
+ *   process.stdout.write(chunk);
+ * }
+ * ```
+ */
+export declare class ResponseStream implements AsyncIterableIterator<string> {
+  /** 等待下一个文本块 */
+  next(): Promise<IteratorResult<string>>;
+
+  /** 提前结束迭代，丢弃剩余内容 */
+  return(): Promise<IteratorResult<string>>;
+
+  /** 读取剩余全部内容 */
+  collect(): Promise<string>;
+
+  [Symbol.asyncIterator](): AsyncIterableIterator<string>;
+}
+
+/**
+ * 将JS函数注册为工具
+ * 
+ * 工具在JS主线程上执行，带有JS工具的Agent需使用 `generateAsync` 或 `stream` 调用。
+ * 
+ * @example
+ * ```typescript
+ * const weather = functionTool(
+ *   { name: 'get_weather', description: '查询城市天气', parameters: { type: 'object', properties: { city: { type: 'string' } } } },
+ *   async ({ city }) => ({ city, forecast: '晴' })
+ * );
+ * ```
+ */
+export declare function functionTool(
+  options: FunctionToolOptions,
+  callback: (params: Record<string, any>) => any
+): Tool;
+
+/**
+ * 向量存储（内存实现）
+ * 
+ * @example
+ * ```typescript
+ * const store = new VectorStore();
+ * await store.createIndex('docs', 3);
+ * await store.upsert('docs', [{ vector: [0.1, 0.2, 0.3], metadata: { lang: 'zh' } }]);
+ * const matches = await store.search('docs', [0.1, 0.2, 0.3], 1, { lang: 'zh' });
+ * ```
+ */
+export declare class VectorStore {
+  constructor();
+
+  /** 创建索引，metric 可选 cosine、euclidean、dot_product */
+  createIndex(name: string, dimension: number, metric?: string): Promise<void>;
+
+  /** 列出所有索引 */
+  listIndexes(): Promise<string[]>;
+
+  /** 获取索引信息 */
+  describeIndex(name: string): Promise<VectorIndexInfo>;
+
+  /** 删除索引 */
+  deleteIndex(name: string): Promise<void>;
+
+  /** 写入或更新向量，返回记录ID */
+  upsert(index: string, records: VectorStoreRecord[]): Promise<string[]>;
+
+  /** 相似度检索，filter 中的字段需全部相等 */
+  search(index: string, vector: number[], topK?: number, filter?: Record<string, any>): Promise<VectorStoreMatch[]>;
+
+  /** 按ID删除向量 */
+  delete(index: string, ids: string[]): Promise<void>;
+}
+
+/**
+ * RAG管道
+ * 
+ * 文本经分块、嵌入后写入向量存储，查询时召回相关片段并拼接为上下文
+ * 
+ * @example
+ * ```typescript
+ * const rag = new RagPipeline(async (texts) => embed(texts), undefined, { chunkSize: 500 });
+ * await rag.addText(readme, { source: 'README.md' });
+ * const { context } = await rag.query('如何安装？', 3);
+ * ```
+ */
+export declare class RagPipeline {
+  constructor(
+    embedder: (texts: string[]) => number[][] | Promise<number[][]>,
+    store?: VectorStore,
+    options?: RagPipelineOptions
+  );
+
+  /** 管道使用的向量存储 */
+  readonly store: VectorStore;
+
+  /** 分块、嵌入并写入文本，返回片段ID */
+  addText(text: string, metadata?: Record<string, any>): Promise<string[]>;
+
+  /** 检索与查询最相关的片段 */
+  query(query: string, topK?: number): Promise<RetrievalResult>;
+}
+
 /**
  * 工具模块
  * 
@@ -360,6 +538,10 @@ declare const _default: {
   Agent: typeof Agent;
   AgentBuilder: typeof AgentBuilder;
   Tool: typeof Tool;
+  ResponseStream: typeof ResponseStream;
+  VectorStore: typeof VectorStore;
+  RagPipeline: typeof RagPipeline;
+  functionTool: typeof functionTool;
   tools: typeof tools;
   quickAgent: typeof quickAgent;
   createAgentBuilder: typeof createAgentBuilder;
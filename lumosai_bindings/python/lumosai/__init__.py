@@ -33,7 +33,7 @@ Lumos.ai Python绑定
     >>> response = await agent.generate_async("分析这个文件的内容")
 """
 
-from typing import List, Optional, Dict, Any, Union
+from typing import List, Optional, Dict, Any, Union, Callable, Iterator, AsyncIterator
 import asyncio
 import warnings
 
@@ -44,9 +44,13 @@ try:
         AgentBuilder as _AgentBuilder,
         Tool as _Tool,
         Response as _Response,
+        ResponseStream as _ResponseStream,
+        VectorStore as _VectorStore,
+        RagPipeline as _RagPipeline,
         LumosError,
         quick_agent as _quick_agent,
         create_agent_builder as _create_agent_builder,
+        function_tool as _function_tool,
     )
 except ImportError as e:
     raise ImportError(
@@ -67,7 +71,11 @@ __all__ = [
     "AgentBuilder", 
     "Tool",
     "Response",
+    "ResponseStream",
+    "VectorStore",
+    "RagPipeline",
     "LumosError",
+    "tool",
     "tools",
     "__version__",
 ]
@@ -123,6 +131,24 @@ class Agent:
         response = await self._inner.generate_async(input_text)
         return Response(response)
     
+    def stream(self, input_text: str) -> "ResponseStream":
+        """
+        流式生成响应
+        
+        返回的流同时支持 `for` 和 `async for` 迭代。
+        
+        Args:
+            input_text: 输入文本
+            
+        Returns:
+            ResponseStream: 文本块流
+            
+        Examples:
+            >>> async for chunk in agent.stream("讲个故事"):
+            ...     print(chunk, end="")
+        """
+        return ResponseStream(self._inner.stream(input_text))
+    
     def get_config(self) -> Dict[str, Any]:
         """
         获取Agent配置
@@ -390,6 +416,179 @@ class Response:
         return f"Response(content='{content_preview}', type={self.response_type})"
 
 
+class ResponseStream:
+    """
+    流式响应
+    
+    逐块产出Agent生成的文本，同时支持同步和asyncio迭代。
+    """
+    
+    def __init__(self, inner_stream: _ResponseStream):
+        """初始化ResponseStream"""
+        self._inner = inner_stream
+    
+    def __iter__(self) -> Iterator[str]:
+        return iter(self._inner)
+    
+    def __aiter__(self) -> AsyncIterator[str]:
+        return self._inner.__aiter__()
+    
+    def collect(self) -> str:
+        """读取剩余全部内容"""
+        return self._inner.collect()
+    
+    def __repr__(self) -> str:
+        return "ResponseStream()"
+
+
+def tool(
+    func: Optional[Callable] = None,
+    *,
+    name: Optional[str] = None,
+    description: Optional[str] = None,
+    parameters: Optional[Dict[str, Any]] = None,
+) -> Union[Tool, Callable[[Callable], Tool]]:
+    """
+    将Python函数注册为工具
+    
+    未指定时，名称取自函数名，描述取自文档字符串，参数模式由类型注解推断。
+    
+    Examples:
+        >>> @tool
+        ... def get_weather(city: str, days: int = 1) -> dict:
+        ...     '''查询城市天气'''
+        ...     return {"city": city, "forecast": "晴"}
+        >>> 
+        >>> agent = Agent.quick("assistant", "你是一个天气助手").tool(get_weather).build()
+    """
+    def wrap(f: Callable) -> Tool:
+        return Tool(_function_tool(f, name=name, description=description, parameters=parameters))
+    
+    if func is None:
+        return wrap
+    return wrap(func)
+
+
+class VectorStore:
+    """
+    向量存储
+    
+    Examples:
+        >>> store = VectorStore()
+        >>> store.create_index("docs", 3)
+        >>> store.upsert("docs", [{"vector": [0.1, 0.2, 0.3], "metadata": {"lang": "zh"}}])
+        >>> store.search("docs", [0.1, 0.2, 0.3], top_k=1, filter={"lang": "zh"})
+    """
+    
+    def __init__(self, inner_store: Optional[_VectorStore] = None):
+        """初始化VectorStore，默认使用内存存储"""
+        self._inner = inner_store if inner_store is not None else _VectorStore()
+    
+    def create_index(self, name: str, dimension: int, metric: Optional[str] = None) -> None:
+        """创建索引，metric 可选 cosine、euclidean、dot_product"""
+        self._inner.create_index(name, dimension, metric)
+    
+    def list_indexes(self) -> List[str]:
+        """列出所有索引"""
+        return self._inner.list_indexes()
+    
+    def describe_index(self, name: str) -> Dict[str, Any]:
+        """获取索引信息"""
+        return self._inner.describe_index(name)
+    
+    def delete_index(self, name: str) -> None:
+        """删除索引"""
+        self._inner.delete_index(name)
+    
+    def upsert(self, index: str, records: List[Dict[str, Any]]) -> List[str]:
+        """写入或更新向量，返回记录ID"""
+        return self._inner.upsert(index, records)
+    
+    async def upsert_async(self, index: str, records: List[Dict[str, Any]]) -> List[str]:
+        """异步写入或更新向量"""
+        return await self._inner.upsert_async(index, records)
+    
+    def search(
+        self,
+        index: str,
+        vector: List[float],
+        top_k: int = 10,
+        filter: Optional[Dict[str, Any]] = None,
+    ) -> List[Dict[str, Any]]:
+        """相似度检索，filter 中的字段需全部相等"""
+        return self._inner.search(index, vector, top_k, filter)
+    
+    async def search_async(
+        self,
+        index: str,
+        vector: List[float],
+        top_k: int = 10,
+        filter: Optional[Dict[str, Any]] = None,
+    ) -> List[Dict[str, Any]]:
+        """异步相似度检索"""
+        return await self._inner.search_async(index, vector, top_k, filter)
+    
+    def delete(self, index: str, ids: List[str]) -> None:
+        """按ID删除向量"""
+        self._inner.delete(index, ids)
+    
+    def __repr__(self) -> str:
+        return repr(self._inner)
+
+
+class RagPipeline:
+    """
+    RAG管道
+    
+    文本经分块、嵌入后写入向量存储，查询时召回相关片段并拼接为上下文。
+    
+    Examples:
+        >>> def embed(texts: List[str]) -> List[List[float]]:
+        ...     return [model.encode(t).tolist() for t in texts]
+        >>> 
+        >>> rag = RagPipeline(embed, chunk_size=500)
+        >>> rag.add_text(open("README.md").read(), {"source": "README.md"})
+        >>> result = await rag.query_async("如何安装？", top_k=3)
+        >>> print(result["context"])
+    """
+    
+    def __init__(
+        self,
+        embedder: Callable[[List[str]], List[List[float]]],
+        store: Optional[VectorStore] = None,
+        index: str = "documents",
+        chunk_size: int = 1000,
+        chunk_overlap: int = 200,
+    ):
+        """初始化RagPipeline"""
+        inner_store = store._inner if store is not None else None
+        self._inner = _RagPipeline(embedder, inner_store, index, chunk_size, chunk_overlap)
+    
+    @property
+    def store(self) -> VectorStore:
+        """管道使用的向量存储"""
+        return VectorStore(self._inner.store)
+    
+    def add_text(self, text: str, metadata: Optional[Dict[str, Any]] = None) -> List[str]:
+        """分块、嵌入并写入文本，返回片段ID"""
+        return self._inner.add_text(text, metadata)
+    
+    async def add_text_async(self, text: str, metadata: Optional[Dict[str, Any]] = None) -> List[str]:
+        """异步写入文本"""
+        return await self._inner.add_text_async(text, metadata)
+    
+    def query(self, query: str, top_k: int = 5) -> Dict[str, Any]:
+        """检索与查询最相关的片段"""
+        return self._inner.query(query, top_k)
+    
+    async def query_async(self, query: str, top_k: int = 5) -> Dict[str, Any]:
+        """异步检索"""
+        return await self._inner.query_async(query, top_k)
+    
+    def __repr__(self) -> str:
+        return repr(self._inner)
+
+
 # 工具模块
 class _ToolsModule:
     """工具模块，提供各种预定义工具"""
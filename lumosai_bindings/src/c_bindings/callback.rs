@@ -0,0 +1,134 @@
+//! C回调工具
+//!
+//! 工具逻辑由宿主语言实现：Agent调用工具时，参数以JSON字符串传给回调，
+//! 回调返回JSON字符串作为结果。
+
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_void};
+use std::sync::Arc;
+use crate::c_bindings::{read_str, set_last_error, CErrorCode, CTool};
+use crate::core::{CrossLangTool, ToolHandler, ToolMetadata};
+
+/// 工具回调
+///
+/// 接收注册时的 `user_data` 和JSON参数，返回JSON结果；返回NULL表示调用失败。
+/// 回调可能在任意工作线程上被调用，实现需保证线程安全。
+pub type CToolCallback = Option<unsafe extern "C" fn(user_data: *mut c_void, arguments_json: *const c_char) -> *mut c_char>;
+
+/// 宿主语言的释放函数
+pub type CFreeCallback = Option<unsafe extern "C" fn(ptr: *mut c_void)>;
+
+/// 宿主语言注册的回调及其上下文
+struct CallbackTool {
+    name: String,
+    callback: unsafe extern "C" fn(*mut c_void, *const c_char) -> *mut c_char,
+    free_result: CFreeCallback,
+    free_user_data: CFreeCallback,
+    user_data: *mut c_void,
+}
+
+// 回调约定为线程安全，`user_data` 的所有权在注册时移交给工具
+unsafe impl Send for CallbackTool {}
+unsafe impl Sync for CallbackTool {}
+
+impl CallbackTool {
+    fn call(&self, arguments: serde_json::Value) -> lumosai_core::Result<serde_json::Value> {
+        let arguments = std::ffi::CString::new(arguments.to_string())
+            .map_err(|e| lumosai_core::Error::Tool(format!("{}: {}", self.name, e)))?;
+
+        unsafe {
+            let result = (self.callback)(self.user_data, arguments.as_ptr());
+            if result.is_null() {
+                return Err(lumosai_core::Error::Tool(format!("{}: callback returned no result", self.name)));
+            }
+
+            let text = CStr::from_ptr(result).to_string_lossy().into_owned();
+            if let Some(free_result) = self.free_result {
+                free_result(result as *mut c_void);
+            }
+
+            // 非JSON结果按普通字符串处理
+            Ok(serde_json::from_str(&text).unwrap_or(serde_json::Value::String(text)))
+        }
+    }
+}
+
+impl Drop for CallbackTool {
+    fn drop(&mut self) {
+        if let Some(free_user_data) = self.free_user_data {
+            unsafe { free_user_data(self.user_data) };
+        }
+    }
+}
+
+/// 使用宿主语言回调创建工具
+///
+/// - `parameters_json` 为参数的JSON Schema，可为NULL；
+/// - `free_result` 用于释放回调返回的字符串，可为NULL（字符串由宿主语言自行管理）；
+/// - `free_user_data` 在工具及其所有副本被释放后调用，可为NULL；创建失败时 `user_data` 仍归调用方所有。
+///
+/// 创建的工具通过 `lumos_agent_builder_tool` 注册到Agent，之后仍需调用 `lumos_tool_free` 释放句柄。
+#[no_mangle]
+pub extern "C" fn lumos_tool_callback_new(
+    name: *const c_char,
+    description: *const c_char,
+    parameters_json: *const c_char,
+    callback: CToolCallback,
+    free_result: CFreeCallback,
+    user_data: *mut c_void,
+    free_user_data: CFreeCallback,
+    tool_out: *mut CTool,
+) -> CErrorCode {
+    let Some(callback) = callback else {
+        set_last_error("callback must not be null");
+        return CErrorCode::InvalidParameter;
+    };
+    if tool_out.is_null() {
+        set_last_error("tool_out must not be null");
+        return CErrorCode::InvalidParameter;
+    }
+
+    unsafe {
+        let (name, description) = match (read_str(name, "name"), read_str(description, "description")) {
+            (Ok(name), Ok(description)) => (name.to_string(), description.to_string()),
+            (Err(code), _) | (_, Err(code)) => return code,
+        };
+
+        let parameters = if parameters_json.is_null() {
+            serde_json::json!({ "type": "object", "properties": {} })
+        } else {
+            let text = match read_str(parameters_json, "parameters_json") {
+                Ok(text) => text,
+                Err(code) => return code,
+            };
+            match serde_json::from_str(text) {
+                Ok(parameters) => parameters,
+                Err(e) => {
+                    set_last_error(format!("parameters_json is not valid JSON: {}", e));
+                    return CErrorCode::SerializationError;
+                }
+            }
+        };
+
+        let tool = Arc::new(CallbackTool {
+            name: name.clone(),
+            callback,
+            free_result,
+            free_user_data,
+            user_data,
+        });
+        let handler: ToolHandler = Arc::new(move |arguments| tool.call(arguments));
+
+        let metadata = ToolMetadata {
+            name,
+            description,
+            parameters,
+            tool_type: "callback".to_string(),
+            is_async: false,
+        };
+
+        let tool_box = Box::new(CrossLangTool::from_handler(metadata, handler));
+        *tool_out = Box::into_raw(tool_box) as CTool;
+        CErrorCode::Success
+    }
+}
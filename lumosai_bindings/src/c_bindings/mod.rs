@@ -1,7 +1,17 @@
 //! C绑定模块
 //! 
 //! 为Go、C++等语言提供C ABI兼容的绑定支持
+//!
+//! C头文件 `include/lumosai.h` 在启用 `c-bindings` 特性构建时由cbindgen生成。
+//!
+//! 约定：
+//! - 所有句柄由对应的 `lumos_*_free` 函数释放，`lumos_*` 返回的字符串由 `lumos_string_free` 释放；
+//! - 函数返回非 `Success` 的错误码时，可在同一线程调用 `lumos_last_error` 获取错误描述。
 
+pub mod callback;
+pub mod stream;
+
+use std::cell::RefCell;
 use std::ffi::{CStr, CString};
 use std::os::raw::{c_char, c_int, c_uint, c_void};
 use std::ptr;
@@ -24,6 +34,7 @@ pub type CResponse = *mut c_void;
 
 /// C错误码
 #[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CErrorCode {
     Success = 0,
     InvalidParameter = 1,
@@ -55,6 +66,61 @@ pub struct CResponseData {
     pub success: c_int,
 }
 
+thread_local! {
+    /// 当前线程最近一次错误的描述
+    static LAST_ERROR: RefCell<Option<CString>> = RefCell::new(None);
+}
+
+/// 记录当前线程的最近一次错误
+pub(crate) fn set_last_error(message: impl std::fmt::Display) {
+    let message = CString::new(message.to_string().replace('\0', ""))
+        .unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+/// 记录错误并转换为C错误码
+pub(crate) fn error_code(error: &BindingError) -> CErrorCode {
+    set_last_error(error);
+    match error {
+        BindingError::InvalidParameter { .. } => CErrorCode::InvalidParameter,
+        BindingError::Network { .. } => CErrorCode::NetworkError,
+        BindingError::Timeout { .. } => CErrorCode::TimeoutError,
+        BindingError::Serialization { .. } => CErrorCode::SerializationError,
+        _ => CErrorCode::RuntimeError,
+    }
+}
+
+/// 读取以NUL结尾的UTF-8字符串参数
+pub(crate) unsafe fn read_str<'a>(value: *const c_char, parameter: &str) -> Result<&'a str, CErrorCode> {
+    if value.is_null() {
+        set_last_error(format!("{} must not be null", parameter));
+        return Err(CErrorCode::InvalidParameter);
+    }
+
+    CStr::from_ptr(value).to_str().map_err(|_| {
+        set_last_error(format!("{} is not valid UTF-8", parameter));
+        CErrorCode::InvalidParameter
+    })
+}
+
+/// 将Rust字符串转换为由 `lumos_string_free` 释放的C字符串
+pub(crate) fn into_c_string(value: String) -> Result<*mut c_char, CErrorCode> {
+    CString::new(value).map(CString::into_raw).map_err(|_| {
+        set_last_error("string contains an interior NUL byte");
+        CErrorCode::SerializationError
+    })
+}
+
+/// 获取当前线程最近一次错误的描述
+///
+/// 返回的指针在同一线程下一次出错前有效，无需释放；没有错误时返回NULL。
+#[no_mangle]
+pub extern "C" fn lumos_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow().as_ref().map_or(ptr::null(), |message| message.as_ptr())
+    })
+}
+
 /// 创建新的AgentBuilder
 #[no_mangle]
 pub extern "C" fn lumos_agent_builder_new() -> CAgentBuilder {
@@ -166,7 +232,7 @@ pub extern "C" fn lumos_agent_builder_build(
                 *agent_out = Box::into_raw(agent_box) as CAgent;
                 CErrorCode::Success
             }
-            Err(_) => CErrorCode::RuntimeError,
+            Err(e) => error_code(&e),
         }
     }
 }
@@ -195,12 +261,47 @@ pub extern "C" fn lumos_agent_generate(
                 *response_out = Box::into_raw(response_box) as CResponse;
                 CErrorCode::Success
             }
-            Err(e) => match e {
-                BindingError::Network { .. } => CErrorCode::NetworkError,
-                BindingError::Timeout { .. } => CErrorCode::TimeoutError,
-                BindingError::Serialization { .. } => CErrorCode::SerializationError,
-                _ => CErrorCode::RuntimeError,
+            Err(e) => error_code(&e),
+        }
+    }
+}
+
+/// 发送消息并获取回复文本
+///
+/// `reply_out` 中的字符串需通过 `lumos_string_free` 释放。
+#[no_mangle]
+pub extern "C" fn lumos_agent_send_message(
+    agent: CAgent,
+    message: *const c_char,
+    reply_out: *mut *mut c_char,
+) -> CErrorCode {
+    if agent.is_null() || reply_out.is_null() {
+        set_last_error("agent and reply_out must not be null");
+        return CErrorCode::InvalidParameter;
+    }
+    
+    unsafe {
+        let agent_ref = &*(agent as *const CrossLangAgent);
+        let message_str = match read_str(message, "message") {
+            Ok(s) => s,
+            Err(code) => return code,
+        };
+        
+        let response = match agent_ref.generate(message_str) {
+            Ok(response) => response,
+            Err(e) => return error_code(&e),
+        };
+        if let Some(error) = response.error {
+            set_last_error(error);
+            return CErrorCode::RuntimeError;
+        }
+        
+        match into_c_string(response.content) {
+            Ok(reply) => {
+                *reply_out = reply;
+                CErrorCode::Success
             }
+            Err(code) => code,
         }
     }
 }
@@ -318,7 +419,7 @@ pub extern "C" fn lumos_quick_agent(
                 *agent_out = Box::into_raw(agent_box) as CAgent;
                 CErrorCode::Success
             }
-            Err(_) => CErrorCode::RuntimeError,
+            Err(e) => error_code(&e),
         }
     }
 }
@@ -0,0 +1,130 @@
+//! C流式响应接口
+//!
+//! 流由后台任务驱动，宿主语言可非阻塞轮询（适合事件循环）或阻塞等待下一个片段。
+
+use std::os::raw::{c_char, c_void};
+use tokio::sync::mpsc::{self, error::TryRecvError};
+use crate::c_bindings::{error_code, into_c_string, read_str, set_last_error, CAgent, CErrorCode};
+use crate::core::CrossLangAgent;
+use crate::error::Result;
+
+/// C Stream句柄
+pub type CStream = *mut c_void;
+
+/// 流轮询状态
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CStreamStatus {
+    /// 已取得一个文本片段
+    Chunk = 0,
+    /// 暂无新片段，稍后再轮询
+    Pending = 1,
+    /// 流已结束
+    Done = 2,
+    /// 流出错，错误描述见 `lumos_last_error`
+    Failed = 3,
+}
+
+struct StreamHandle {
+    /// 持有Agent以保证其运行时在流结束前存活
+    _agent: CrossLangAgent,
+    receiver: mpsc::Receiver<Result<String>>,
+}
+
+/// 开始流式生成
+///
+/// 返回的流需通过 `lumos_stream_free` 释放；提前释放会停止生成。
+#[no_mangle]
+pub extern "C" fn lumos_agent_stream(
+    agent: CAgent,
+    message: *const c_char,
+    stream_out: *mut CStream,
+) -> CErrorCode {
+    if agent.is_null() || stream_out.is_null() {
+        set_last_error("agent and stream_out must not be null");
+        return CErrorCode::InvalidParameter;
+    }
+
+    unsafe {
+        let agent_ref = &*(agent as *const CrossLangAgent);
+        let message_str = match read_str(message, "message") {
+            Ok(s) => s,
+            Err(code) => return code,
+        };
+
+        let handle = Box::new(StreamHandle {
+            _agent: agent_ref.clone(),
+            receiver: agent_ref.stream(message_str),
+        });
+        *stream_out = Box::into_raw(handle) as CStream;
+        CErrorCode::Success
+    }
+}
+
+/// 非阻塞地轮询下一个片段
+///
+/// 返回 `Chunk` 时 `chunk_out` 中的字符串需通过 `lumos_string_free` 释放。
+#[no_mangle]
+pub extern "C" fn lumos_stream_poll(stream: CStream, chunk_out: *mut *mut c_char) -> CStreamStatus {
+    let Some(handle) = (unsafe { handle_mut(stream, chunk_out) }) else {
+        return CStreamStatus::Failed;
+    };
+
+    match handle.receiver.try_recv() {
+        Ok(chunk) => unsafe { deliver(chunk, chunk_out) },
+        Err(TryRecvError::Empty) => CStreamStatus::Pending,
+        Err(TryRecvError::Disconnected) => CStreamStatus::Done,
+    }
+}
+
+/// 阻塞等待下一个片段
+///
+/// 返回 `Chunk` 时 `chunk_out` 中的字符串需通过 `lumos_string_free` 释放。
+/// 不得在宿主语言的异步运行时线程上调用。
+#[no_mangle]
+pub extern "C" fn lumos_stream_next(stream: CStream, chunk_out: *mut *mut c_char) -> CStreamStatus {
+    let Some(handle) = (unsafe { handle_mut(stream, chunk_out) }) else {
+        return CStreamStatus::Failed;
+    };
+
+    match handle.receiver.blocking_recv() {
+        Some(chunk) => unsafe { deliver(chunk, chunk_out) },
+        None => CStreamStatus::Done,
+    }
+}
+
+/// 释放Stream
+#[no_mangle]
+pub extern "C" fn lumos_stream_free(stream: CStream) {
+    if !stream.is_null() {
+        unsafe {
+            let _ = Box::from_raw(stream as *mut StreamHandle);
+        }
+    }
+}
+
+unsafe fn handle_mut<'a>(stream: CStream, chunk_out: *mut *mut c_char) -> Option<&'a mut StreamHandle> {
+    if stream.is_null() || chunk_out.is_null() {
+        set_last_error("stream and chunk_out must not be null");
+        return None;
+    }
+    Some(&mut *(stream as *mut StreamHandle))
+}
+
+unsafe fn deliver(chunk: Result<String>, chunk_out: *mut *mut c_char) -> CStreamStatus {
+    let chunk = match chunk {
+        Ok(chunk) => chunk,
+        Err(e) => {
+            error_code(&e);
+            return CStreamStatus::Failed;
+        }
+    };
+
+    match into_c_string(chunk) {
+        Ok(chunk) => {
+            *chunk_out = chunk;
+            CStreamStatus::Chunk
+        }
+        Err(_) => CStreamStatus::Failed,
+    }
+}
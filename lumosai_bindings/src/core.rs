@@ -4,14 +4,25 @@
 
 use std::collections::HashMap;
 use std::sync::Arc;
+use futures::StreamExt;
 use serde::{Deserialize, Serialize};
-use lumosai_core::{Agent, AgentBuilder, Tool, LlmProvider};
+use tokio::sync::mpsc;
+use lumosai_core::{Agent, AgentBuilder, AgentStreamOptions, Tool, LlmProvider};
+use lumosai_core::agent::user_message;
+use lumosai_core::tool::{FunctionTool, ToolSchema};
 use crate::error::{BindingError, Result};
 
+/// 流式响应通道的缓冲区大小
+const STREAM_BUFFER_SIZE: usize = 64;
+
+/// 由宿主语言实现的工具处理函数
+pub type ToolHandler = Arc<dyn Fn(serde_json::Value) -> lumosai_core::Result<serde_json::Value> + Send + Sync>;
+
 /// 跨语言Agent包装器
+#[derive(Clone)]
 pub struct CrossLangAgent {
     /// 内部Rust Agent实例
-    inner: Agent,
+    inner: Arc<Agent>,
     
     /// 运行时状态
     runtime: Arc<tokio::runtime::Runtime>,
@@ -27,12 +38,16 @@ pub struct CrossLangAgentBuilder {
 }
 
 /// 跨语言工具包装器
+#[derive(Clone)]
 pub struct CrossLangTool {
     /// 内部工具实例
     inner: Arc<dyn Tool>,
     
     /// 工具元数据
     metadata: ToolMetadata,
+    
+    /// 宿主语言注册的处理函数
+    handler: Option<ToolHandler>,
 }
 
 /// 工具元数据
@@ -178,7 +193,7 @@ impl CrossLangAgent {
         );
         
         Self {
-            inner: agent,
+            inner: Arc::new(agent),
             runtime,
         }
     }
@@ -240,6 +255,39 @@ impl CrossLangAgent {
         }
     }
     
+    /// 流式生成响应
+    ///
+    /// 在内部运行时上驱动Agent的流，并通过通道逐块转发，
+    /// 便于各语言绑定将其包装为各自的异步迭代器。
+    pub fn stream(&self, input: &str) -> mpsc::Receiver<Result<String>> {
+        let (tx, rx) = mpsc::channel(STREAM_BUFFER_SIZE);
+        let agent = self.inner.clone();
+        let input = input.to_string();
+        
+        self.runtime.spawn(async move {
+            let messages = vec![user_message(&input)];
+            let options = AgentStreamOptions::default();
+            
+            let mut stream = match agent.stream(&messages, &options).await {
+                Ok(stream) => stream,
+                Err(e) => {
+                    let _ = tx.send(Err(BindingError::core(e.to_string()))).await;
+                    return;
+                }
+            };
+            
+            while let Some(chunk) = stream.next().await {
+                let chunk = chunk.map_err(|e| BindingError::core(e.to_string()));
+                if tx.send(chunk).await.is_err() {
+                    // 接收端已关闭，停止生成
+                    break;
+                }
+            }
+        });
+        
+        rx
+    }
+    
     /// 获取Agent配置
     pub fn get_config(&self) -> CrossLangConfig {
         // 从内部Agent提取配置信息
@@ -331,6 +379,26 @@ impl CrossLangTool {
         Self {
             inner: tool,
             metadata,
+            handler: None,
+        }
+    }
+    
+    /// 使用宿主语言的函数创建工具
+    ///
+    /// 处理函数接收JSON参数并返回JSON结果，Agent调用工具时同样经由该函数执行。
+    pub fn from_handler(metadata: ToolMetadata, handler: ToolHandler) -> Self {
+        let function = handler.clone();
+        let tool = FunctionTool::new(
+            metadata.name.clone(),
+            metadata.description.clone(),
+            ToolSchema::with_json_schema(metadata.parameters.clone()),
+            move |params| function(params),
+        );
+        
+        Self {
+            inner: Arc::new(tool),
+            metadata,
+            handler: Some(handler),
         }
     }
     
@@ -343,6 +411,30 @@ impl CrossLangTool {
     pub fn execute(&self, parameters: serde_json::Value) -> Result<ToolCallResult> {
         let start_time = std::time::Instant::now();
         
+        if let Some(handler) = &self.handler {
+            let result = handler(parameters.clone());
+            let execution_time = start_time.elapsed().as_millis() as u64;
+            
+            return Ok(match result {
+                Ok(value) => ToolCallResult {
+                    tool_name: self.metadata.name.clone(),
+                    parameters,
+                    result: value,
+                    execution_time_ms: execution_time,
+                    success: true,
+                    error: None,
+                },
+                Err(e) => ToolCallResult {
+                    tool_name: self.metadata.name.clone(),
+                    parameters,
+                    result: serde_json::Value::Null,
+                    execution_time_ms: execution_time,
+                    success: false,
+                    error: Some(e.to_string()),
+                },
+            });
+        }
+        
         // 这里需要实际的工具执行逻辑
         // 暂时返回模拟结果
         let execution_time = start_time.elapsed().as_millis() as u64;
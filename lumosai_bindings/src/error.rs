@@ -344,11 +344,39 @@ impl BindingError {
     }
 }
 
-// 从lumosai_core错误转换
+// 从lumosai_core错误转换，按错误分类映射以保留可重试性
 impl From<lumosai_core::error::LumosError> for BindingError {
     fn from(err: lumosai_core::error::LumosError) -> Self {
-        Self::Core {
-            message: err.to_string(),
+        use lumosai_core::error::{ErrorKind, LumosError};
+
+        let message = err.to_string();
+        match (err.kind(), err) {
+            (_, LumosError::ToolError { tool, message, .. }) => Self::Tool { tool_name: tool, message },
+            (_, LumosError::ProviderError { provider, message, retryable: false, .. }) => Self::Model {
+                model_name: provider,
+                message,
+            },
+            (ErrorKind::RateLimited, _) => Self::ResourceExhausted {
+                resource: "rate_limit".to_string(),
+                message,
+            },
+            (ErrorKind::Timeout | ErrorKind::Unavailable, _) => Self::Network { message },
+            (ErrorKind::Tool, _) => Self::Tool {
+                tool_name: "unknown".to_string(),
+                message,
+            },
+            (ErrorKind::InvalidInput, _) => Self::InvalidParameter {
+                parameter: "input".to_string(),
+                message,
+            },
+            (ErrorKind::Unauthenticated | ErrorKind::PermissionDenied | ErrorKind::Guardrail, _) => Self::Permission { message },
+            (ErrorKind::Unsupported, _) => Self::UnsupportedOperation { operation: message },
+            (ErrorKind::Configuration, _) => Self::Configuration {
+                field: "config".to_string(),
+                message,
+            },
+            (_, err) if err.is_retryable() => Self::Runtime { message },
+            _ => Self::Core { message },
         }
     }
 }
@@ -4,6 +4,7 @@
 
 pub mod core;
 pub mod error;
+pub mod rag;
 pub mod types;
 
 #[cfg(feature = "python")]
@@ -21,6 +22,7 @@ pub mod c_bindings;
 // 重新导出核心类型
 pub use crate::core::*;
 pub use crate::error::*;
+pub use crate::rag::*;
 pub use crate::types::*;
 
 // Python绑定入口
@@ -35,6 +37,9 @@ fn lumosai(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<python::PyAgentBuilder>()?;
     m.add_class::<python::PyTool>()?;
     m.add_class::<python::PyResponse>()?;
+    m.add_class::<python::PyResponseStream>()?;
+    m.add_class::<python::rag::PyVectorStore>()?;
+    m.add_class::<python::rag::PyRagPipeline>()?;
     
     // 注册工具模块
     let tools_module = PyModule::new(_py, "tools")?;
@@ -47,6 +52,7 @@ fn lumosai(_py: Python, m: &PyModule) -> PyResult<()> {
     // 注册便利函数
     m.add_function(wrap_pyfunction!(python::quick_agent, m)?)?;
     m.add_function(wrap_pyfunction!(python::create_agent_builder, m)?)?;
+    m.add_function(wrap_pyfunction!(python::function_tool, m)?)?;
     
     Ok(())
 }
@@ -3,12 +3,18 @@
 //! 为JavaScript/TypeScript提供Lumos.ai的完整绑定支持
 
 use napi::bindgen_prelude::*;
+use napi::threadsafe_function::{ErrorStrategy, ThreadSafeCallContext, ThreadsafeFunction};
+use napi::{Env, JsFunction, JsObject, JsSymbol};
 use napi_derive::napi;
 use std::collections::HashMap;
-use crate::core::{CrossLangAgent, CrossLangAgentBuilder, CrossLangTool, CrossLangResponse};
+use std::sync::Arc;
+use tokio::sync::{mpsc, Mutex};
+use crate::core::{CrossLangAgent, CrossLangAgentBuilder, CrossLangTool, CrossLangResponse, ToolHandler, ToolMetadata};
 use crate::error::BindingError;
 use crate::types::*;
 
+pub mod rag;
+
 /// Node.js Agent包装器
 #[napi]
 pub struct Agent {
@@ -27,6 +33,21 @@ pub struct Tool {
     inner: CrossLangTool,
 }
 
+/// Node.js流式响应，实现异步迭代器协议
+#[napi]
+pub struct ResponseStream {
+    receiver: Arc<Mutex<mpsc::Receiver<crate::error::Result<String>>>>,
+}
+
+/// 流式响应的迭代结果，与 `IteratorResult<string>` 结构一致
+#[napi(object)]
+pub struct StreamChunk {
+    /// 文本块，流结束时为空
+    pub value: Option<String>,
+    /// 流是否结束
+    pub done: bool,
+}
+
 /// Node.js Response包装器
 #[napi(object)]
 pub struct Response {
@@ -142,6 +163,25 @@ impl Agent {
         })
     }
     
+    /// 流式生成响应
+    ///
+    /// 返回的对象可直接用于 `for await (const chunk of agent.stream(input))`。
+    #[napi(ts_return_type = "ResponseStream")]
+    pub fn stream(&self, env: Env, input: String) -> Result<JsObject> {
+        let stream = ResponseStream {
+            receiver: Arc::new(Mutex::new(self.inner.stream(&input))),
+        };
+        
+        let mut object = stream.into_instance(env)?.as_object(env);
+        let async_iterator: JsSymbol = env.get_global()?
+            .get_named_property::<JsObject>("Symbol")?
+            .get_named_property("asyncIterator")?;
+        let iterator = env.create_function_from_closure("asyncIterator", |ctx| ctx.this::<JsObject>())?;
+        object.set_property(async_iterator, iterator)?;
+        
+        Ok(object)
+    }
+    
     /// 获取配置
     #[napi]
     pub fn get_config(&self) -> Config {
@@ -269,6 +309,113 @@ impl Tool {
     }
 }
 
+#[napi]
+impl ResponseStream {
+    /// 等待下一个文本块
+    #[napi]
+    pub async fn next(&self) -> Result<StreamChunk> {
+        match self.receiver.lock().await.recv().await {
+            Some(chunk) => Ok(StreamChunk {
+                value: Some(chunk.map_err(to_napi_err)?),
+                done: false,
+            }),
+            None => Ok(StreamChunk { value: None, done: true }),
+        }
+    }
+    
+    /// 提前结束迭代，丢弃剩余内容
+    #[napi(js_name = "return")]
+    pub async fn close(&self) -> Result<StreamChunk> {
+        self.receiver.lock().await.close();
+        Ok(StreamChunk { value: None, done: true })
+    }
+    
+    /// 读取剩余全部内容
+    #[napi]
+    pub async fn collect(&self) -> Result<String> {
+        let mut receiver = self.receiver.lock().await;
+        let mut content = String::new();
+        while let Some(chunk) = receiver.recv().await {
+            content.push_str(&chunk.map_err(to_napi_err)?);
+        }
+        Ok(content)
+    }
+}
+
+/// 函数工具选项
+#[napi(object)]
+pub struct FunctionToolOptions {
+    /// 工具名称
+    pub name: String,
+    /// 工具描述
+    pub description: String,
+    /// 参数的JSON Schema
+    pub parameters: Option<serde_json::Value>,
+}
+
+/// 将JS函数注册为工具
+///
+/// 工具函数接收参数对象，可返回值或Promise。工具在JS主线程上执行，
+/// 因此带有JS工具的Agent需使用 `generateAsync` 或 `stream` 调用。
+#[napi(ts_args_type = "options: FunctionToolOptions, callback: (params: Record<string, any>) => any")]
+pub fn function_tool(env: Env, options: FunctionToolOptions, callback: JsFunction) -> Result<Tool> {
+    let callback = js_callback::<serde_json::Value>(&env, callback)?;
+    
+    let tool_name = options.name.clone();
+    let handler: ToolHandler = Arc::new(move |params| {
+        call_js(&callback, params)
+            .map_err(|e| lumosai_core::Error::Tool(format!("{}: {}", tool_name, e)))
+    });
+    
+    let metadata = ToolMetadata {
+        name: options.name,
+        description: options.description,
+        parameters: options.parameters
+            .unwrap_or_else(|| serde_json::json!({ "type": "object", "properties": {} })),
+        tool_type: "javascript".to_string(),
+        is_async: true,
+    };
+    
+    Ok(Tool {
+        inner: CrossLangTool::from_handler(metadata, handler),
+    })
+}
+
+/// 将绑定错误转换为JS错误
+pub(crate) fn to_napi_err(error: BindingError) -> Error {
+    Error::new(Status::GenericFailure, error.to_string())
+}
+
+/// 创建可在工作线程调用的JS回调
+///
+/// 回调先被包装为async函数，因此无论原函数返回普通值还是Promise，调用结果都是Promise。
+/// 回调不会阻止Node.js进程退出。
+pub(crate) fn js_callback<T: ToNapiValue + 'static>(
+    env: &Env,
+    callback: JsFunction,
+) -> Result<ThreadsafeFunction<T, ErrorStrategy::Fatal>> {
+    let wrap: JsFunction = env.run_script("(fn) => async (arg) => fn(arg)")?;
+    let callback = JsFunction::try_from(wrap.call(None, &[callback])?)?;
+    
+    let mut callback: ThreadsafeFunction<T, ErrorStrategy::Fatal> = callback
+        .create_threadsafe_function(0, |ctx: ThreadSafeCallContext<T>| Ok(vec![ctx.value]))?;
+    callback.unref(env)?;
+    
+    Ok(callback)
+}
+
+/// 在当前线程阻塞等待JS回调的结果
+///
+/// 不能在JS主线程上调用，否则会因主线程无法执行回调而死锁。
+pub(crate) fn call_js<T: 'static>(
+    callback: &ThreadsafeFunction<T, ErrorStrategy::Fatal>,
+    value: T,
+) -> Result<serde_json::Value> {
+    futures::executor::block_on(async {
+        callback.call_async::<Promise<serde_json::Value>>(value).await?.await
+    })
+}
+
 /// 便利函数：快速创建Agent
 #[napi]
 pub fn quick_agent(name: String, instructions: String) -> AgentBuilder {
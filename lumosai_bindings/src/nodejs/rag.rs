@@ -0,0 +1,239 @@
+//! Node.js RAG与向量存储绑定
+//!
+//! 为JavaScript提供向量存储操作和RAG管道，所有操作均返回Promise
+
+use napi::bindgen_prelude::*;
+use napi::{Env, JsFunction};
+use napi_derive::napi;
+use std::collections::HashMap;
+use std::sync::Arc;
+use crate::error::BindingError;
+use crate::rag::{CrossLangRagPipeline, CrossLangVectorStore, EmbeddingFn, RagPipelineConfig, VectorMatch, VectorRecord};
+use crate::nodejs::{call_js, js_callback, to_napi_err};
+
+/// Node.js VectorStore包装器
+#[napi]
+pub struct VectorStore {
+    inner: CrossLangVectorStore,
+}
+
+/// Node.js RagPipeline包装器
+#[napi]
+pub struct RagPipeline {
+    inner: CrossLangRagPipeline,
+}
+
+/// 向量记录
+#[napi(object)]
+pub struct VectorStoreRecord {
+    /// 记录ID，为空时自动生成
+    pub id: Option<String>,
+    /// 向量
+    pub vector: Vec<f64>,
+    /// 元数据
+    pub metadata: Option<HashMap<String, serde_json::Value>>,
+}
+
+/// 向量检索命中
+#[napi(object)]
+pub struct VectorStoreMatch {
+    /// 记录ID
+    pub id: String,
+    /// 相似度分数
+    pub score: f64,
+    /// 文档内容
+    pub content: Option<String>,
+    /// 元数据
+    pub metadata: HashMap<String, serde_json::Value>,
+}
+
+/// 向量索引信息
+#[napi(object)]
+pub struct VectorIndexInfo {
+    /// 索引名称
+    pub name: String,
+    /// 向量维度
+    pub dimension: u32,
+    /// 向量数量
+    pub count: u32,
+    /// 相似度度量
+    pub metric: String,
+}
+
+/// RAG管道选项
+#[napi(object)]
+pub struct RagPipelineOptions {
+    /// 索引名称
+    pub index: Option<String>,
+    /// 分块大小（字符）
+    pub chunk_size: Option<u32>,
+    /// 分块重叠（字符）
+    pub chunk_overlap: Option<u32>,
+}
+
+/// RAG检索结果
+#[napi(object)]
+pub struct RetrievalResult {
+    /// 查询文本
+    pub query: String,
+    /// 拼接后的上下文
+    pub context: String,
+    /// 命中的文档片段
+    pub matches: Vec<VectorStoreMatch>,
+}
+
+#[napi]
+impl VectorStore {
+    /// 创建内存向量存储
+    #[napi(constructor)]
+    pub fn new() -> Self {
+        Self {
+            inner: CrossLangVectorStore::memory(),
+        }
+    }
+
+    /// 创建索引
+    #[napi]
+    pub async fn create_index(&self, name: String, dimension: u32, metric: Option<String>) -> Result<()> {
+        self.inner.create_index(&name, dimension as usize, metric.as_deref()).await
+            .map_err(to_napi_err)
+    }
+
+    /// 列出所有索引
+    #[napi]
+    pub async fn list_indexes(&self) -> Result<Vec<String>> {
+        self.inner.list_indexes().await.map_err(to_napi_err)
+    }
+
+    /// 获取索引信息
+    #[napi]
+    pub async fn describe_index(&self, name: String) -> Result<VectorIndexInfo> {
+        let info = self.inner.describe_index(&name).await.map_err(to_napi_err)?;
+
+        Ok(VectorIndexInfo {
+            name: info.name,
+            dimension: info.dimension as u32,
+            count: info.count as u32,
+            metric: info.metric,
+        })
+    }
+
+    /// 删除索引
+    #[napi]
+    pub async fn delete_index(&self, name: String) -> Result<()> {
+        self.inner.delete_index(&name).await.map_err(to_napi_err)
+    }
+
+    /// 写入或更新向量，返回记录ID
+    #[napi]
+    pub async fn upsert(&self, index: String, records: Vec<VectorStoreRecord>) -> Result<Vec<String>> {
+        let records = records.into_iter()
+            .map(|record| VectorRecord {
+                id: record.id,
+                vector: record.vector.into_iter().map(|v| v as f32).collect(),
+                metadata: record.metadata.unwrap_or_default(),
+            })
+            .collect();
+
+        self.inner.upsert(&index, records).await.map_err(to_napi_err)
+    }
+
+    /// 相似度检索，`filter` 中的字段需全部相等
+    #[napi]
+    pub async fn search(
+        &self,
+        index: String,
+        vector: Vec<f64>,
+        top_k: Option<u32>,
+        filter: Option<HashMap<String, serde_json::Value>>,
+    ) -> Result<Vec<VectorStoreMatch>> {
+        let vector = vector.into_iter().map(|v| v as f32).collect();
+        let matches = self.inner.search(&index, vector, top_k.unwrap_or(10) as usize, filter).await
+            .map_err(to_napi_err)?;
+
+        Ok(matches.into_iter().map(to_js_match).collect())
+    }
+
+    /// 按ID删除向量
+    #[napi]
+    pub async fn delete(&self, index: String, ids: Vec<String>) -> Result<()> {
+        self.inner.delete(&index, &ids).await.map_err(to_napi_err)
+    }
+}
+
+#[napi]
+impl RagPipeline {
+    /// 创建RAG管道
+    ///
+    /// `embedder` 接收字符串数组，返回向量数组或其Promise。
+    #[napi(constructor, ts_args_type = "embedder: (texts: string[]) => number[][] | Promise<number[][]>, store?: VectorStore, options?: RagPipelineOptions")]
+    pub fn new(env: Env, embedder: JsFunction, store: Option<&VectorStore>, options: Option<RagPipelineOptions>) -> Result<Self> {
+        let defaults = RagPipelineConfig::default();
+        let config = match options {
+            Some(options) => RagPipelineConfig {
+                index: options.index.unwrap_or(defaults.index),
+                chunk_size: options.chunk_size.map(|v| v as usize).unwrap_or(defaults.chunk_size),
+                chunk_overlap: options.chunk_overlap.map(|v| v as usize).unwrap_or(defaults.chunk_overlap),
+            },
+            None => defaults,
+        };
+
+        let store = store.map(|store| store.inner.clone()).unwrap_or_else(CrossLangVectorStore::memory);
+        let inner = CrossLangRagPipeline::new(config, store, js_embedder(&env, embedder)?)
+            .map_err(to_napi_err)?;
+
+        Ok(Self { inner })
+    }
+
+    /// 管道使用的向量存储
+    #[napi(getter)]
+    pub fn store(&self) -> VectorStore {
+        VectorStore {
+            inner: self.inner.store().clone(),
+        }
+    }
+
+    /// 分块、嵌入并写入文本，返回片段ID
+    #[napi]
+    pub async fn add_text(&self, text: String, metadata: Option<HashMap<String, serde_json::Value>>) -> Result<Vec<String>> {
+        self.inner.add_text(&text, metadata.unwrap_or_default()).await
+            .map_err(to_napi_err)
+    }
+
+    /// 检索与查询最相关的片段
+    #[napi]
+    pub async fn query(&self, query: String, top_k: Option<u32>) -> Result<RetrievalResult> {
+        let result = self.inner.query(&query, top_k.unwrap_or(5) as usize).await
+            .map_err(to_napi_err)?;
+
+        Ok(RetrievalResult {
+            query: result.query,
+            context: result.context,
+            matches: result.matches.into_iter().map(to_js_match).collect(),
+        })
+    }
+}
+
+/// 将JS嵌入函数包装为跨语言嵌入函数
+///
+/// 嵌入函数在JS主线程上执行，调用方需在工作线程上等待结果。
+fn js_embedder(env: &Env, embedder: JsFunction) -> Result<EmbeddingFn> {
+    let embedder = js_callback::<Vec<String>>(env, embedder)?;
+
+    Ok(Arc::new(move |texts: &[String]| {
+        let vectors = call_js(&embedder, texts.to_vec())
+            .map_err(|e| BindingError::runtime(format!("embedding function failed: {}", e)))?;
+
+        serde_json::from_value::<Vec<Vec<f32>>>(vectors)
+            .map_err(|e| BindingError::runtime(format!("embedding function returned invalid vectors: {}", e)))
+    }))
+}
+
+fn to_js_match(m: VectorMatch) -> VectorStoreMatch {
+    VectorStoreMatch {
+        id: m.id,
+        score: m.score as f64,
+        content: m.content,
+        metadata: m.metadata,
+    }
+}
@@ -3,13 +3,16 @@
 //! 为Python提供Lumos.ai的完整绑定支持
 
 use pyo3::prelude::*;
+use pyo3::exceptions::PyStopAsyncIteration;
 use pyo3::types::{PyDict, PyList};
 use std::collections::HashMap;
 use std::sync::Arc;
-use crate::core::{CrossLangAgent, CrossLangAgentBuilder, CrossLangTool, CrossLangResponse, ToolMetadata};
-use crate::error::BindingError;
+use tokio::sync::{mpsc, Mutex};
+use crate::core::{CrossLangAgent, CrossLangAgentBuilder, CrossLangTool, CrossLangResponse, ToolHandler, ToolMetadata};
+use crate::error::{BindingError, Result};
 use crate::types::*;
 
+pub mod rag;
 pub mod tools;
 
 /// Python Agent包装器
@@ -36,6 +39,12 @@ pub struct PyResponse {
     inner: CrossLangResponse,
 }
 
+/// Python流式响应，同时支持 `for` 和 `async for` 迭代
+#[pyclass(name = "ResponseStream")]
+pub struct PyResponseStream {
+    receiver: Arc<Mutex<mpsc::Receiver<Result<String>>>>,
+}
+
 /// Python错误类型
 #[pyclass(name = "LumosError", extends = pyo3::exceptions::PyException)]
 pub struct PyLumosError {
@@ -67,6 +76,14 @@ impl PyAgent {
         })
     }
     
+    /// 流式生成响应
+    #[pyo3(text_signature = "(self, input)")]
+    fn stream(&self, input: &str) -> PyResponseStream {
+        PyResponseStream {
+            receiver: Arc::new(Mutex::new(self.inner.stream(input))),
+        }
+    }
+    
     /// 获取配置
     #[pyo3(text_signature = "(self)")]
     fn get_config(&self) -> PyResult<PyObject> {
@@ -345,6 +362,153 @@ impl PyResponse {
     }
 }
 
+#[pymethods]
+impl PyResponseStream {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+    
+    /// 阻塞等待下一个文本块
+    fn __next__(&self, py: Python) -> PyResult<Option<String>> {
+        let receiver = self.receiver.clone();
+        let chunk = py.allow_threads(|| receiver.blocking_lock().blocking_recv());
+        chunk.transpose().map_err(to_py_err)
+    }
+    
+    fn __aiter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+    
+    /// 异步等待下一个文本块
+    fn __anext__(&self, py: Python) -> PyResult<Option<PyObject>> {
+        let receiver = self.receiver.clone();
+        
+        let future = pyo3_asyncio::tokio::future_into_py(py, async move {
+            match receiver.lock().await.recv().await {
+                Some(chunk) => chunk.map_err(to_py_err),
+                None => Err(PyStopAsyncIteration::new_err("stream finished")),
+            }
+        })?;
+        Ok(Some(future.into()))
+    }
+    
+    /// 读取剩余全部内容
+    fn collect(&self, py: Python) -> PyResult<String> {
+        let mut content = String::new();
+        while let Some(chunk) = self.__next__(py)? {
+            content.push_str(&chunk);
+        }
+        Ok(content)
+    }
+    
+    /// 调试表示
+    fn __repr__(&self) -> String {
+        "ResponseStream()".to_string()
+    }
+}
+
+/// 将Python函数注册为工具
+///
+/// 未指定时，名称取自函数名，描述取自文档字符串，参数模式由函数签名推断。
+/// 协程函数会在独立的事件循环中执行。
+#[pyfunction]
+#[pyo3(signature = (func, name = None, description = None, parameters = None))]
+pub fn function_tool(
+    py: Python,
+    func: PyObject,
+    name: Option<String>,
+    description: Option<String>,
+    parameters: Option<&PyDict>,
+) -> PyResult<PyTool> {
+    let callable = func.as_ref(py);
+    if !callable.is_callable() {
+        return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>("tool function must be callable"));
+    }
+    
+    let name = match name {
+        Some(name) => name,
+        None => callable.getattr("__name__")?.extract()?,
+    };
+    let description = match description {
+        Some(description) => description,
+        None => py.import("inspect")?
+            .call_method1("getdoc", (callable,))?
+            .extract::<Option<String>>()?
+            .unwrap_or_default(),
+    };
+    let parameters = match parameters {
+        Some(parameters) => python_to_json(parameters)?,
+        None => signature_schema(py, callable)?,
+    };
+    let is_async = py.import("inspect")?
+        .call_method1("iscoroutinefunction", (callable,))?
+        .is_true()?;
+    
+    let tool_name = name.clone();
+    let handler: ToolHandler = Arc::new(move |params| {
+        Python::with_gil(|py| -> PyResult<serde_json::Value> {
+            let kwargs = json_to_python(py, &params)?;
+            let kwargs: &PyDict = kwargs.as_ref(py).downcast()?;
+            let mut result = func.as_ref(py).call((), Some(kwargs))?;
+            if py.import("inspect")?.call_method1("iscoroutine", (result,))?.is_true()? {
+                result = py.import("asyncio")?.call_method1("run", (result,))?;
+            }
+            python_to_json(result)
+        })
+        .map_err(|e| lumosai_core::Error::Tool(format!("{}: {}", tool_name, e)))
+    });
+    
+    let metadata = ToolMetadata {
+        name,
+        description,
+        parameters,
+        tool_type: "python".to_string(),
+        is_async,
+    };
+    
+    Ok(PyTool {
+        inner: CrossLangTool::from_handler(metadata, handler),
+    })
+}
+
+/// 由Python函数签名推断JSON参数模式
+fn signature_schema(py: Python, func: &PyAny) -> PyResult<serde_json::Value> {
+    let inspect = py.import("inspect")?;
+    let empty = inspect.getattr("Parameter")?.getattr("empty")?;
+    let signature = inspect.call_method1("signature", (func,))?;
+    
+    let mut properties = serde_json::Map::new();
+    let mut required = Vec::new();
+    for parameter in signature.getattr("parameters")?.call_method0("values")?.iter()? {
+        let parameter = parameter?;
+        let name: String = parameter.getattr("name")?.extract()?;
+        let annotation = parameter.getattr("annotation")?;
+        let json_type = if annotation.is(empty) {
+            "string"
+        } else {
+            match annotation.getattr("__name__").and_then(|n| n.extract::<String>()).as_deref() {
+                Ok("int") => "integer",
+                Ok("float") => "number",
+                Ok("bool") => "boolean",
+                Ok("list") => "array",
+                Ok("dict") => "object",
+                _ => "string",
+            }
+        };
+        
+        properties.insert(name.clone(), serde_json::json!({ "type": json_type }));
+        if parameter.getattr("default")?.is(empty) {
+            required.push(serde_json::Value::String(name));
+        }
+    }
+    
+    Ok(serde_json::json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+    }))
+}
+
 /// 便利函数：快速创建Agent
 #[pyfunction]
 #[pyo3(text_signature = "(name, instructions)")]
@@ -363,12 +527,27 @@ pub fn create_agent_builder() -> PyAgentBuilder {
     }
 }
 
+/// 将绑定错误转换为Python异常
+pub(crate) fn to_py_err(error: BindingError) -> PyErr {
+    PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(error.to_string())
+}
+
+/// 将JSON值转换为Python对象
+pub(crate) fn json_to_python(py: Python, value: &serde_json::Value) -> PyResult<PyObject> {
+    let json_str = serde_json::to_string(value)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+    Ok(py.import("json")?.call_method1("loads", (json_str,))?.into())
+}
+
 /// 将Python对象转换为JSON值
-fn python_to_json(obj: &PyAny) -> PyResult<serde_json::Value> {
+pub(crate) fn python_to_json(obj: &PyAny) -> PyResult<serde_json::Value> {
     if obj.is_none() {
         Ok(serde_json::Value::Null)
     } else if let Ok(s) = obj.extract::<String>() {
         Ok(serde_json::Value::String(s))
+    } else if obj.is_instance_of::<pyo3::types::PyBool>() {
+        // bool需先于int判断，否则True会被提取为1
+        Ok(serde_json::Value::Bool(obj.is_true()?))
     } else if let Ok(i) = obj.extract::<i64>() {
         Ok(serde_json::Value::Number(serde_json::Number::from(i)))
     } else if let Ok(f) = obj.extract::<f64>() {
@@ -0,0 +1,250 @@
+//! Python RAG与向量存储绑定
+//!
+//! 为Python提供向量存储操作和RAG管道，同步和asyncio接口并存
+
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+use std::collections::HashMap;
+use std::sync::Arc;
+use crate::error::BindingError;
+use crate::rag::{CrossLangRagPipeline, CrossLangVectorStore, EmbeddingFn, RagPipelineConfig, VectorRecord, DEFAULT_RAG_INDEX};
+use crate::python::{json_to_python, python_to_json, to_py_err};
+
+/// Python VectorStore包装器
+#[pyclass(name = "VectorStore")]
+#[derive(Clone)]
+pub struct PyVectorStore {
+    inner: CrossLangVectorStore,
+}
+
+/// Python RagPipeline包装器
+#[pyclass(name = "RagPipeline")]
+pub struct PyRagPipeline {
+    inner: CrossLangRagPipeline,
+}
+
+#[pymethods]
+impl PyVectorStore {
+    /// 创建内存向量存储
+    #[new]
+    fn new() -> Self {
+        Self {
+            inner: CrossLangVectorStore::memory(),
+        }
+    }
+
+    /// 创建索引
+    #[pyo3(signature = (name, dimension, metric = None))]
+    fn create_index(&self, py: Python, name: &str, dimension: usize, metric: Option<&str>) -> PyResult<()> {
+        let store = &self.inner;
+        py.allow_threads(|| store.block_on(store.create_index(name, dimension, metric)))
+            .map_err(to_py_err)
+    }
+
+    /// 列出所有索引
+    fn list_indexes(&self, py: Python) -> PyResult<Vec<String>> {
+        let store = &self.inner;
+        py.allow_threads(|| store.block_on(store.list_indexes()))
+            .map_err(to_py_err)
+    }
+
+    /// 获取索引信息
+    fn describe_index(&self, py: Python, name: &str) -> PyResult<PyObject> {
+        let store = &self.inner;
+        let info = py.allow_threads(|| store.block_on(store.describe_index(name)))
+            .map_err(to_py_err)?;
+        json_to_python(py, &serde_json::to_value(info).map_err(|e| to_py_err(e.into()))?)
+    }
+
+    /// 删除索引
+    fn delete_index(&self, py: Python, name: &str) -> PyResult<()> {
+        let store = &self.inner;
+        py.allow_threads(|| store.block_on(store.delete_index(name)))
+            .map_err(to_py_err)
+    }
+
+    /// 写入或更新向量
+    ///
+    /// 每条记录为 `{"id": 可选, "vector": [...], "metadata": {...}}`
+    fn upsert(&self, py: Python, index: &str, records: &PyList) -> PyResult<Vec<String>> {
+        let records = extract_records(records)?;
+        let store = &self.inner;
+        py.allow_threads(|| store.block_on(store.upsert(index, records)))
+            .map_err(to_py_err)
+    }
+
+    /// 异步写入或更新向量
+    fn upsert_async<'p>(&self, py: Python<'p>, index: String, records: &PyList) -> PyResult<&'p PyAny> {
+        let records = extract_records(records)?;
+        let store = self.inner.clone();
+
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            store.upsert(&index, records).await.map_err(to_py_err)
+        })
+    }
+
+    /// 相似度检索
+    #[pyo3(signature = (index, vector, top_k = 10, filter = None))]
+    fn search(
+        &self,
+        py: Python,
+        index: &str,
+        vector: Vec<f32>,
+        top_k: usize,
+        filter: Option<HashMap<String, &PyAny>>,
+    ) -> PyResult<PyObject> {
+        let filter = extract_filter(filter)?;
+        let store = &self.inner;
+        let matches = py.allow_threads(|| store.block_on(store.search(index, vector, top_k, filter)))
+            .map_err(to_py_err)?;
+        json_to_python(py, &serde_json::to_value(matches).map_err(|e| to_py_err(e.into()))?)
+    }
+
+    /// 异步相似度检索
+    #[pyo3(signature = (index, vector, top_k = 10, filter = None))]
+    fn search_async<'p>(
+        &self,
+        py: Python<'p>,
+        index: String,
+        vector: Vec<f32>,
+        top_k: usize,
+        filter: Option<HashMap<String, &PyAny>>,
+    ) -> PyResult<&'p PyAny> {
+        let filter = extract_filter(filter)?;
+        let store = self.inner.clone();
+
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            let matches = store.search(&index, vector, top_k, filter).await.map_err(to_py_err)?;
+            let matches = serde_json::to_value(matches).map_err(|e| to_py_err(e.into()))?;
+            Python::with_gil(|py| json_to_python(py, &matches))
+        })
+    }
+
+    /// 按ID删除向量
+    fn delete(&self, py: Python, index: &str, ids: Vec<String>) -> PyResult<()> {
+        let store = &self.inner;
+        py.allow_threads(|| store.block_on(store.delete(index, &ids)))
+            .map_err(to_py_err)
+    }
+
+    /// 调试表示
+    fn __repr__(&self) -> String {
+        "VectorStore(backend='memory')".to_string()
+    }
+}
+
+#[pymethods]
+impl PyRagPipeline {
+    /// 创建RAG管道
+    ///
+    /// `embedder` 是接收字符串列表、返回向量列表的Python函数。
+    #[new]
+    #[pyo3(signature = (embedder, store = None, index = DEFAULT_RAG_INDEX.to_string(), chunk_size = 1000, chunk_overlap = 200))]
+    fn new(
+        embedder: PyObject,
+        store: Option<PyVectorStore>,
+        index: String,
+        chunk_size: usize,
+        chunk_overlap: usize,
+    ) -> PyResult<Self> {
+        let store = store.map(|store| store.inner).unwrap_or_else(CrossLangVectorStore::memory);
+        let config = RagPipelineConfig { index, chunk_size, chunk_overlap };
+        let inner = CrossLangRagPipeline::new(config, store, python_embedder(embedder))
+            .map_err(to_py_err)?;
+
+        Ok(Self { inner })
+    }
+
+    /// 管道使用的向量存储
+    #[getter]
+    fn store(&self) -> PyVectorStore {
+        PyVectorStore {
+            inner: self.inner.store().clone(),
+        }
+    }
+
+    /// 管道索引名称
+    #[getter]
+    fn index(&self) -> &str {
+        &self.inner.config().index
+    }
+
+    /// 分块、嵌入并写入文本，返回片段ID
+    #[pyo3(signature = (text, metadata = None))]
+    fn add_text(&self, py: Python, text: &str, metadata: Option<HashMap<String, &PyAny>>) -> PyResult<Vec<String>> {
+        let metadata = extract_metadata(metadata)?;
+        let pipeline = &self.inner;
+        py.allow_threads(|| pipeline.store().block_on(pipeline.add_text(text, metadata)))
+            .map_err(to_py_err)
+    }
+
+    /// 异步写入文本
+    #[pyo3(signature = (text, metadata = None))]
+    fn add_text_async<'p>(
+        &self,
+        py: Python<'p>,
+        text: String,
+        metadata: Option<HashMap<String, &PyAny>>,
+    ) -> PyResult<&'p PyAny> {
+        let metadata = extract_metadata(metadata)?;
+        let pipeline = self.inner.clone();
+
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            pipeline.add_text(&text, metadata).await.map_err(to_py_err)
+        })
+    }
+
+    /// 检索与查询最相关的片段
+    #[pyo3(signature = (query, top_k = 5))]
+    fn query(&self, py: Python, query: &str, top_k: usize) -> PyResult<PyObject> {
+        let pipeline = &self.inner;
+        let result = py.allow_threads(|| pipeline.store().block_on(pipeline.query(query, top_k)))
+            .map_err(to_py_err)?;
+        json_to_python(py, &serde_json::to_value(result).map_err(|e| to_py_err(e.into()))?)
+    }
+
+    /// 异步检索
+    #[pyo3(signature = (query, top_k = 5))]
+    fn query_async<'p>(&self, py: Python<'p>, query: String, top_k: usize) -> PyResult<&'p PyAny> {
+        let pipeline = self.inner.clone();
+
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            let result = pipeline.query(&query, top_k).await.map_err(to_py_err)?;
+            let result = serde_json::to_value(result).map_err(|e| to_py_err(e.into()))?;
+            Python::with_gil(|py| json_to_python(py, &result))
+        })
+    }
+
+    /// 调试表示
+    fn __repr__(&self) -> String {
+        let config = self.inner.config();
+        format!("RagPipeline(index='{}', chunk_size={}, chunk_overlap={})",
+                config.index, config.chunk_size, config.chunk_overlap)
+    }
+}
+
+/// 将Python嵌入函数包装为跨语言嵌入函数
+fn python_embedder(embedder: PyObject) -> EmbeddingFn {
+    Arc::new(move |texts: &[String]| {
+        Python::with_gil(|py| {
+            embedder.call1(py, (texts.to_vec(),))?.extract::<Vec<Vec<f32>>>(py)
+        })
+        .map_err(|e| BindingError::runtime(format!("embedding function failed: {}", e)))
+    })
+}
+
+fn extract_records(records: &PyList) -> PyResult<Vec<VectorRecord>> {
+    serde_json::from_value(python_to_json(records)?)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("invalid vector records: {}", e)))
+}
+
+fn extract_metadata(metadata: Option<HashMap<String, &PyAny>>) -> PyResult<HashMap<String, serde_json::Value>> {
+    metadata.unwrap_or_default()
+        .into_iter()
+        .map(|(key, value)| Ok((key, python_to_json(value)?)))
+        .collect()
+}
+
+fn extract_filter(filter: Option<HashMap<String, &PyAny>>) -> PyResult<Option<HashMap<String, serde_json::Value>>> {
+    filter.map(|filter| extract_metadata(Some(filter))).transpose()
+}
@@ -0,0 +1,364 @@
+//! 跨语言RAG与向量存储模块
+//!
+//! 为各语言绑定提供统一的向量存储操作和RAG管道
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use lumosai_core::vector::{FilterCondition, MemoryVectorStorage, SimilarityMetric, VectorStorage};
+use crate::error::{BindingError, Result};
+
+/// 默认的RAG索引名称
+pub const DEFAULT_RAG_INDEX: &str = "documents";
+
+/// 存放文档内容的元数据字段
+const CONTENT_FIELD: &str = "content";
+
+/// 由宿主语言实现的嵌入函数
+pub type EmbeddingFn = Arc<dyn Fn(&[String]) -> Result<Vec<Vec<f32>>> + Send + Sync>;
+
+/// 跨语言向量存储包装器
+#[derive(Clone)]
+pub struct CrossLangVectorStore {
+    /// 内部向量存储
+    inner: Arc<dyn VectorStorage>,
+
+    /// 运行时状态
+    runtime: Arc<tokio::runtime::Runtime>,
+}
+
+/// 待写入的向量记录
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VectorRecord {
+    /// 记录ID，为空时自动生成
+    pub id: Option<String>,
+
+    /// 向量
+    pub vector: Vec<f32>,
+
+    /// 元数据
+    #[serde(default)]
+    pub metadata: HashMap<String, serde_json::Value>,
+}
+
+/// 向量检索命中
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorMatch {
+    /// 记录ID
+    pub id: String,
+
+    /// 相似度分数
+    pub score: f32,
+
+    /// 文档内容
+    pub content: Option<String>,
+
+    /// 元数据
+    pub metadata: HashMap<String, serde_json::Value>,
+}
+
+/// 向量索引信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorIndexInfo {
+    /// 索引名称
+    pub name: String,
+
+    /// 向量维度
+    pub dimension: usize,
+
+    /// 向量数量
+    pub count: usize,
+
+    /// 相似度度量
+    pub metric: String,
+}
+
+/// RAG检索结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetrievalResult {
+    /// 查询文本
+    pub query: String,
+
+    /// 拼接后的上下文
+    pub context: String,
+
+    /// 命中的文档片段
+    pub matches: Vec<VectorMatch>,
+}
+
+/// RAG管道配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RagPipelineConfig {
+    /// 索引名称
+    pub index: String,
+
+    /// 分块大小（字符）
+    pub chunk_size: usize,
+
+    /// 分块重叠（字符）
+    pub chunk_overlap: usize,
+}
+
+/// 跨语言RAG管道
+///
+/// 文本经分块、嵌入后写入向量存储；查询时按相似度召回片段并拼接为上下文。
+#[derive(Clone)]
+pub struct CrossLangRagPipeline {
+    /// 管道配置
+    config: RagPipelineConfig,
+
+    /// 向量存储
+    store: CrossLangVectorStore,
+
+    /// 嵌入函数
+    embedder: EmbeddingFn,
+}
+
+impl CrossLangVectorStore {
+    /// 创建内存向量存储
+    pub fn memory() -> Self {
+        let runtime = Arc::new(
+            tokio::runtime::Runtime::new()
+                .expect("Failed to create tokio runtime")
+        );
+
+        Self {
+            inner: Arc::new(MemoryVectorStorage::new(1536, None)),
+            runtime,
+        }
+    }
+
+    /// 在内部运行时上同步执行异步操作
+    pub fn block_on<F: std::future::Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+
+    /// 创建索引
+    pub async fn create_index(&self, name: &str, dimension: usize, metric: Option<&str>) -> Result<()> {
+        let metric = metric.map(parse_metric).transpose()?;
+        self.inner.create_index(name, dimension, metric).await
+            .map_err(|e| BindingError::core(e.to_string()))
+    }
+
+    /// 列出所有索引
+    pub async fn list_indexes(&self) -> Result<Vec<String>> {
+        self.inner.list_indexes().await
+            .map_err(|e| BindingError::core(e.to_string()))
+    }
+
+    /// 获取索引信息
+    pub async fn describe_index(&self, name: &str) -> Result<VectorIndexInfo> {
+        let stats = self.inner.describe_index(name).await
+            .map_err(|e| BindingError::core(e.to_string()))?;
+
+        Ok(VectorIndexInfo {
+            name: name.to_string(),
+            dimension: stats.dimension,
+            count: stats.count,
+            metric: format!("{:?}", stats.metric).to_lowercase(),
+        })
+    }
+
+    /// 删除索引
+    pub async fn delete_index(&self, name: &str) -> Result<()> {
+        self.inner.delete_index(name).await
+            .map_err(|e| BindingError::core(e.to_string()))
+    }
+
+    /// 写入或更新向量，返回记录ID
+    pub async fn upsert(&self, index: &str, records: Vec<VectorRecord>) -> Result<Vec<String>> {
+        if records.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let ids = records.iter()
+            .map(|record| record.id.clone().unwrap_or_else(|| uuid::Uuid::new_v4().to_string()))
+            .collect();
+        let (vectors, metadata) = records.into_iter()
+            .map(|record| (record.vector, record.metadata))
+            .unzip();
+
+        self.inner.upsert(index, vectors, Some(ids), Some(metadata)).await
+            .map_err(|e| BindingError::core(e.to_string()))
+    }
+
+    /// 相似度检索
+    ///
+    /// `filter` 为字段到期望值的映射，所有字段需同时相等。
+    pub async fn search(
+        &self,
+        index: &str,
+        vector: Vec<f32>,
+        top_k: usize,
+        filter: Option<HashMap<String, serde_json::Value>>,
+    ) -> Result<Vec<VectorMatch>> {
+        let filter = filter
+            .filter(|fields| !fields.is_empty())
+            .map(|fields| FilterCondition::And(
+                fields.into_iter()
+                    .map(|(field, value)| FilterCondition::Eq(field, value))
+                    .collect()
+            ));
+
+        let results = self.inner.query(index, vector, top_k, filter, false).await
+            .map_err(|e| BindingError::core(e.to_string()))?;
+
+        Ok(results.into_iter()
+            .map(|result| {
+                let mut metadata = result.metadata.unwrap_or_default();
+                let content = metadata.remove(CONTENT_FIELD)
+                    .and_then(|value| value.as_str().map(str::to_string));
+
+                VectorMatch {
+                    id: result.id,
+                    score: result.score,
+                    content,
+                    metadata,
+                }
+            })
+            .collect())
+    }
+
+    /// 按ID删除向量
+    pub async fn delete(&self, index: &str, ids: &[String]) -> Result<()> {
+        for id in ids {
+            self.inner.delete_by_id(index, id).await
+                .map_err(|e| BindingError::core(e.to_string()))?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for RagPipelineConfig {
+    fn default() -> Self {
+        Self {
+            index: DEFAULT_RAG_INDEX.to_string(),
+            chunk_size: 1000,
+            chunk_overlap: 200,
+        }
+    }
+}
+
+impl CrossLangRagPipeline {
+    /// 创建RAG管道
+    pub fn new(config: RagPipelineConfig, store: CrossLangVectorStore, embedder: EmbeddingFn) -> Result<Self> {
+        if config.chunk_size == 0 || config.chunk_overlap >= config.chunk_size {
+            return Err(BindingError::InvalidParameter {
+                parameter: "chunk_overlap".to_string(),
+                message: "chunk_overlap must be smaller than chunk_size".to_string(),
+            });
+        }
+
+        Ok(Self { config, store, embedder })
+    }
+
+    /// 获取管道使用的向量存储
+    pub fn store(&self) -> &CrossLangVectorStore {
+        &self.store
+    }
+
+    /// 获取管道配置
+    pub fn config(&self) -> &RagPipelineConfig {
+        &self.config
+    }
+
+    /// 分块、嵌入并写入文本，返回片段ID
+    pub async fn add_text(&self, text: &str, metadata: HashMap<String, serde_json::Value>) -> Result<Vec<String>> {
+        let chunks = chunk_text(text, self.config.chunk_size, self.config.chunk_overlap);
+        if chunks.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let vectors = (self.embedder)(&chunks)?;
+        if vectors.len() != chunks.len() {
+            return Err(BindingError::runtime(format!(
+                "embedding function returned {} vectors for {} chunks", vectors.len(), chunks.len()
+            )));
+        }
+
+        self.ensure_index(vectors[0].len()).await?;
+
+        let records = chunks.into_iter()
+            .zip(vectors)
+            .map(|(chunk, vector)| {
+                let mut metadata = metadata.clone();
+                metadata.insert(CONTENT_FIELD.to_string(), serde_json::Value::String(chunk));
+                VectorRecord { id: None, vector, metadata }
+            })
+            .collect();
+
+        self.store.upsert(&self.config.index, records).await
+    }
+
+    /// 检索与查询最相关的片段
+    pub async fn query(&self, query: &str, top_k: usize) -> Result<RetrievalResult> {
+        let vector = (self.embedder)(&[query.to_string()])?
+            .into_iter()
+            .next()
+            .ok_or_else(|| BindingError::runtime("embedding function returned no vectors"))?;
+
+        let matches = match self.store.search(&self.config.index, vector, top_k, None).await {
+            Ok(matches) => matches,
+            // 尚未写入任何文档
+            Err(_) if !self.index_exists().await? => Vec::new(),
+            Err(e) => return Err(e),
+        };
+
+        let context = matches.iter()
+            .filter_map(|m| m.content.as_deref())
+            .collect::<Vec<_>>()
+            .join("\n\n");
+
+        Ok(RetrievalResult {
+            query: query.to_string(),
+            context,
+            matches,
+        })
+    }
+
+    async fn index_exists(&self) -> Result<bool> {
+        Ok(self.store.list_indexes().await?.contains(&self.config.index))
+    }
+
+    async fn ensure_index(&self, dimension: usize) -> Result<()> {
+        if !self.index_exists().await? {
+            self.store.create_index(&self.config.index, dimension, None).await?;
+        }
+        Ok(())
+    }
+}
+
+/// 解析相似度度量名称
+fn parse_metric(metric: &str) -> Result<SimilarityMetric> {
+    match metric.to_lowercase().as_str() {
+        "cosine" => Ok(SimilarityMetric::Cosine),
+        "euclidean" => Ok(SimilarityMetric::Euclidean),
+        "dot" | "dot_product" | "dotproduct" => Ok(SimilarityMetric::DotProduct),
+        other => Err(BindingError::InvalidParameter {
+            parameter: "metric".to_string(),
+            message: format!("unsupported similarity metric: {}", other),
+        }),
+    }
+}
+
+/// 按字符数分块，相邻块之间保留重叠
+fn chunk_text(text: &str, chunk_size: usize, chunk_overlap: usize) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let step = chunk_size - chunk_overlap;
+    let mut chunks = Vec::new();
+    let mut start = 0;
+
+    while start < chars.len() {
+        let end = (start + chunk_size).min(chars.len());
+        let chunk: String = chars[start..end].iter().collect();
+        if !chunk.trim().is_empty() {
+            chunks.push(chunk.trim().to_string());
+        }
+        if end == chars.len() {
+            break;
+        }
+        start += step;
+    }
+
+    chunks
+}
@@ -0,0 +1,185 @@
+//! 浏览器端Agent
+//!
+//! 完全在客户端运行：对话历史保存在页面内，LLM经由 `fetch` 远程调用，
+//! 可选地从浏览器端向量存储中检索知识并注入系统提示。
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use js_sys::{Array, Function, Promise};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use crate::wasm::llm::{ChatMessage, ChatMessageArray, WasmRemoteLlm};
+use crate::wasm::vector::{to_js, StoredVector, WasmVectorStore};
+
+/// 默认检索片段数量
+const DEFAULT_TOP_K: usize = 3;
+
+/// 知识库配置
+struct Knowledge {
+    store: WasmVectorStore,
+    embedder: Function,
+    top_k: usize,
+}
+
+/// 浏览器端Agent
+#[wasm_bindgen(js_name = BrowserAgent)]
+pub struct WasmBrowserAgent {
+    name: String,
+    instructions: String,
+    llm: WasmRemoteLlm,
+    knowledge: Option<Knowledge>,
+    history: Rc<RefCell<Vec<ChatMessage>>>,
+}
+
+#[wasm_bindgen(js_class = BrowserAgent)]
+impl WasmBrowserAgent {
+    /// 创建浏览器端Agent
+    #[wasm_bindgen(constructor)]
+    pub fn new(name: String, instructions: String, llm: &WasmRemoteLlm) -> WasmBrowserAgent {
+        Self {
+            name,
+            instructions,
+            llm: llm.clone(),
+            knowledge: None,
+            history: Rc::new(RefCell::new(Vec::new())),
+        }
+    }
+
+    /// Agent名称
+    #[wasm_bindgen(getter)]
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    /// 配置知识库
+    ///
+    /// `embedder` 接收字符串数组，返回向量数组或其Promise，通常调用远程嵌入接口或浏览器端模型。
+    #[wasm_bindgen(js_name = withKnowledge)]
+    pub fn with_knowledge(
+        mut self,
+        store: &WasmVectorStore,
+        #[wasm_bindgen(unchecked_param_type = "(texts: string[]) => number[][] | Promise<number[][]>")] embedder: Function,
+        top_k: Option<usize>,
+    ) -> WasmBrowserAgent {
+        self.knowledge = Some(Knowledge {
+            store: store.clone(),
+            embedder,
+            top_k: top_k.unwrap_or(DEFAULT_TOP_K),
+        });
+        self
+    }
+
+    /// 嵌入文本并写入知识库，返回记录ID
+    #[wasm_bindgen(js_name = addKnowledge)]
+    pub async fn add_knowledge(&self, texts: Vec<String>) -> Result<Vec<String>, JsValue> {
+        let knowledge = self.knowledge.as_ref()
+            .ok_or_else(|| JsValue::from_str("knowledge store is not configured, call withKnowledge first"))?;
+
+        let vectors = embed(&knowledge.embedder, &texts).await?;
+        let records = texts.into_iter()
+            .zip(vectors)
+            .map(|(content, vector)| StoredVector {
+                id: String::new(),
+                vector,
+                content: Some(content),
+                metadata: Default::default(),
+            })
+            .collect();
+
+        knowledge.store.upsert(records).await
+    }
+
+    /// 生成回复
+    pub async fn generate(&self, input: String) -> Result<String, JsValue> {
+        let messages = self.prepare(&input).await?;
+        let reply = self.llm.chat(&messages).await?;
+        self.record(input, &reply);
+        Ok(reply)
+    }
+
+    /// 流式生成回复，每收到一段文本调用一次 `onDelta`，最终返回完整回复
+    pub async fn stream(
+        &self,
+        input: String,
+        #[wasm_bindgen(unchecked_param_type = "(delta: string) => void")] on_delta: Function,
+    ) -> Result<String, JsValue> {
+        let messages = self.prepare(&input).await?;
+        let reply = self.llm.chat_stream(&messages, |delta| {
+            let _ = on_delta.call1(&JsValue::NULL, &JsValue::from_str(delta));
+        }).await?;
+        self.record(input, &reply);
+        Ok(reply)
+    }
+
+    /// 对话历史
+    #[wasm_bindgen(getter)]
+    pub fn history(&self) -> Result<ChatMessageArray, JsValue> {
+        Ok(to_js(&*self.history.borrow())?.unchecked_into())
+    }
+
+    /// 清空对话历史
+    #[wasm_bindgen(js_name = clearHistory)]
+    pub fn clear_history(&self) {
+        self.history.borrow_mut().clear();
+    }
+}
+
+impl WasmBrowserAgent {
+    /// 组装系统提示、检索上下文、历史和当前输入
+    async fn prepare(&self, input: &str) -> Result<Vec<ChatMessage>, JsValue> {
+        let mut system = self.instructions.clone();
+        if let Some(context) = self.retrieve(input).await? {
+            system.push_str("\n\n参考资料:\n");
+            system.push_str(&context);
+        }
+
+        let mut messages = vec![ChatMessage { role: "system".to_string(), content: system }];
+        messages.extend(self.history.borrow().iter().cloned());
+        messages.push(ChatMessage { role: "user".to_string(), content: input.to_string() });
+        Ok(messages)
+    }
+
+    async fn retrieve(&self, input: &str) -> Result<Option<String>, JsValue> {
+        let Some(knowledge) = &self.knowledge else {
+            return Ok(None);
+        };
+
+        let vector = embed(&knowledge.embedder, &[input.to_string()]).await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| JsValue::from_str("embedding function returned no vectors"))?;
+
+        let context = knowledge.store.search(&vector, knowledge.top_k, None)
+            .into_iter()
+            .filter_map(|m| m.content)
+            .collect::<Vec<_>>()
+            .join("\n\n");
+
+        Ok(if context.is_empty() { None } else { Some(context) })
+    }
+
+    fn record(&self, input: String, reply: &str) {
+        let mut history = self.history.borrow_mut();
+        history.push(ChatMessage { role: "user".to_string(), content: input });
+        history.push(ChatMessage { role: "assistant".to_string(), content: reply.to_string() });
+    }
+}
+
+/// 调用JS嵌入函数，兼容同步返回值和Promise
+async fn embed(embedder: &Function, texts: &[String]) -> Result<Vec<Vec<f32>>, JsValue> {
+    let input: Array = texts.iter().map(|text| JsValue::from_str(text)).collect();
+    let result = embedder.call1(&JsValue::NULL, &input)?;
+    let result = match result.dyn_into::<Promise>() {
+        Ok(promise) => JsFuture::from(promise).await?,
+        Err(value) => value,
+    };
+
+    let vectors: Vec<Vec<f32>> = serde_wasm_bindgen::from_value(result)?;
+    if vectors.len() != texts.len() {
+        return Err(JsValue::from_str(&format!(
+            "embedding function returned {} vectors for {} texts", vectors.len(), texts.len()
+        )));
+    }
+    Ok(vectors)
+}
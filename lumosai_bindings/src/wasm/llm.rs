@@ -0,0 +1,233 @@
+//! 浏览器端远程LLM客户端
+//!
+//! 通过 `fetch` 调用OpenAI兼容的Chat Completions接口，流式响应按SSE逐块解析。
+//! 同时适用于页面主线程和Web Worker。
+
+use js_sys::{Function, Promise, Reflect, Uint8Array};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Headers, ReadableStreamDefaultReader, Request, RequestInit, Response};
+
+#[wasm_bindgen(typescript_custom_section)]
+const TS_LLM_TYPES: &'static str = r#"
+export interface RemoteLlmOptions {
+  /** OpenAI兼容接口的基础URL，如 https://api.openai.com/v1 */
+  baseUrl: string;
+  /** 模型名称 */
+  model: string;
+  /** API密钥，建议仅在演示或经由代理转发时在浏览器中使用 */
+  apiKey?: string;
+  /** 采样温度 */
+  temperature?: number;
+  /** 最大生成token数 */
+  maxTokens?: number;
+}
+
+export interface ChatMessage {
+  role: 'system' | 'user' | 'assistant';
+  content: string;
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "RemoteLlmOptions")]
+    pub type RemoteLlmOptions;
+
+    #[wasm_bindgen(typescript_type = "ChatMessage[]")]
+    pub type ChatMessageArray;
+}
+
+/// 远程LLM配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteLlmConfig {
+    /// 基础URL
+    pub base_url: String,
+    /// 模型名称
+    pub model: String,
+    /// API密钥
+    pub api_key: Option<String>,
+    /// 采样温度
+    pub temperature: Option<f32>,
+    /// 最大生成token数
+    pub max_tokens: Option<u32>,
+}
+
+/// 对话消息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatMessage {
+    /// 角色
+    pub role: String,
+    /// 内容
+    pub content: String,
+}
+
+/// 浏览器端远程LLM
+#[wasm_bindgen(js_name = RemoteLlm)]
+#[derive(Clone)]
+pub struct WasmRemoteLlm {
+    config: RemoteLlmConfig,
+}
+
+#[wasm_bindgen(js_class = RemoteLlm)]
+impl WasmRemoteLlm {
+    /// 创建远程LLM
+    #[wasm_bindgen(constructor)]
+    pub fn new(options: RemoteLlmOptions) -> Result<WasmRemoteLlm, JsValue> {
+        let config: RemoteLlmConfig = serde_wasm_bindgen::from_value(options.into())?;
+        Ok(Self { config })
+    }
+
+    /// 模型名称
+    #[wasm_bindgen(getter)]
+    pub fn model(&self) -> String {
+        self.config.model.clone()
+    }
+
+    /// 发送对话并返回完整回复
+    #[wasm_bindgen(js_name = chat)]
+    pub async fn chat_js(&self, messages: ChatMessageArray) -> Result<String, JsValue> {
+        let messages: Vec<ChatMessage> = serde_wasm_bindgen::from_value(messages.into())?;
+        self.chat(&messages).await
+    }
+
+    /// 流式发送对话，每收到一段文本调用一次 `onDelta`，最终返回完整回复
+    #[wasm_bindgen(js_name = chatStream)]
+    pub async fn chat_stream_js(
+        &self,
+        messages: ChatMessageArray,
+        #[wasm_bindgen(unchecked_param_type = "(delta: string) => void")] on_delta: Function,
+    ) -> Result<String, JsValue> {
+        let messages: Vec<ChatMessage> = serde_wasm_bindgen::from_value(messages.into())?;
+        self.chat_stream(&messages, |delta| {
+            let _ = on_delta.call1(&JsValue::NULL, &JsValue::from_str(delta));
+        }).await
+    }
+}
+
+impl WasmRemoteLlm {
+    /// 发送对话并返回完整回复
+    pub async fn chat(&self, messages: &[ChatMessage]) -> Result<String, JsValue> {
+        let response = self.send(messages, false).await?;
+        let body = JsFuture::from(response.json()?).await?;
+
+        let content = Reflect::get(&body, &"choices".into())
+            .and_then(|choices| Reflect::get(&choices, &0.into()))
+            .and_then(|choice| Reflect::get(&choice, &"message".into()))
+            .and_then(|message| Reflect::get(&message, &"content".into()))?;
+
+        content.as_string()
+            .ok_or_else(|| JsValue::from_str("LLM response does not contain message content"))
+    }
+
+    /// 流式发送对话
+    pub async fn chat_stream<F: FnMut(&str)>(&self, messages: &[ChatMessage], mut on_delta: F) -> Result<String, JsValue> {
+        let response = self.send(messages, true).await?;
+        let body = response.body()
+            .ok_or_else(|| JsValue::from_str("LLM response has no body"))?;
+        let reader: ReadableStreamDefaultReader = body.get_reader().unchecked_into();
+
+        let mut parser = SseParser::default();
+        let mut content = String::new();
+        loop {
+            let chunk = JsFuture::from(reader.read()).await?;
+            if Reflect::get(&chunk, &"done".into())?.as_bool().unwrap_or(true) {
+                break;
+            }
+
+            let bytes = Uint8Array::new(&Reflect::get(&chunk, &"value".into())?).to_vec();
+            for delta in parser.push(&bytes) {
+                on_delta(&delta);
+                content.push_str(&delta);
+            }
+            if parser.finished {
+                let _ = reader.cancel();
+                break;
+            }
+        }
+
+        Ok(content)
+    }
+
+    async fn send(&self, messages: &[ChatMessage], stream: bool) -> Result<Response, JsValue> {
+        let mut body = serde_json::json!({
+            "model": self.config.model,
+            "messages": messages,
+            "stream": stream,
+        });
+        if let Some(temperature) = self.config.temperature {
+            body["temperature"] = serde_json::json!(temperature);
+        }
+        if let Some(max_tokens) = self.config.max_tokens {
+            body["max_tokens"] = serde_json::json!(max_tokens);
+        }
+
+        let headers = Headers::new()?;
+        headers.set("Content-Type", "application/json")?;
+        if let Some(api_key) = &self.config.api_key {
+            headers.set("Authorization", &format!("Bearer {}", api_key))?;
+        }
+
+        let init = RequestInit::new();
+        init.set_method("POST");
+        init.set_headers(&headers);
+        init.set_body(&JsValue::from_str(&body.to_string()));
+
+        let url = format!("{}/chat/completions", self.config.base_url.trim_end_matches('/'));
+        let request = Request::new_with_str_and_init(&url, &init)?;
+        let response: Response = JsFuture::from(fetch(&request)?).await?.dyn_into()?;
+
+        if !response.ok() {
+            let text = JsFuture::from(response.text()?).await?.as_string().unwrap_or_default();
+            return Err(JsValue::from_str(&format!("LLM request failed with status {}: {}", response.status(), text)));
+        }
+        Ok(response)
+    }
+}
+
+/// 调用全局 `fetch`，兼容页面和Worker环境
+pub(crate) fn fetch(request: &Request) -> Result<Promise, JsValue> {
+    let global = js_sys::global();
+    let fetch: Function = Reflect::get(&global, &"fetch".into())?.dyn_into()?;
+    fetch.call1(&global, request)?.dyn_into()
+}
+
+/// 增量SSE解析器，提取Chat Completions流中的文本增量
+#[derive(Default)]
+struct SseParser {
+    buffer: Vec<u8>,
+    finished: bool,
+}
+
+impl SseParser {
+    /// 追加字节并返回其中完整事件携带的文本增量
+    fn push(&mut self, bytes: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(bytes);
+
+        let mut deltas = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            let Some(data) = line.trim().strip_prefix("data:") else {
+                continue;
+            };
+
+            let data = data.trim();
+            if data == "[DONE]" {
+                self.finished = true;
+                break;
+            }
+
+            let delta = serde_json::from_str::<serde_json::Value>(data).ok()
+                .and_then(|event| event["choices"][0]["delta"]["content"].as_str().map(str::to_string));
+            if let Some(delta) = delta.filter(|d| !d.is_empty()) {
+                deltas.push(delta);
+            }
+        }
+
+        deltas
+    }
+}
@@ -1,6 +1,13 @@
 //! WebAssembly绑定模块
 //! 
 //! 为Web浏览器提供Lumos.ai的WebAssembly绑定支持
+//!
+//! `agent`、`llm` 和 `vector` 子模块不依赖原生运行时，可完全在浏览器中运行：
+//! LLM经由 `fetch` 远程调用，向量存储持久化到IndexedDB。
+
+pub mod agent;
+pub mod llm;
+pub mod vector;
 
 use wasm_bindgen::prelude::*;
 use wasm_bindgen_futures::JsFuture;
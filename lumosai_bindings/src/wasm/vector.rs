@@ -0,0 +1,306 @@
+//! 浏览器端向量存储
+//!
+//! 向量保存在内存中检索，并可选地持久化到IndexedDB，页面刷新后自动恢复。
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use js_sys::{Promise, Reflect};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{IdbDatabase, IdbFactory, IdbObjectStoreParameters, IdbOpenDbRequest, IdbRequest, IdbTransactionMode};
+
+/// IndexedDB中的对象仓库名称
+const OBJECT_STORE: &str = "vectors";
+
+/// IndexedDB数据库版本
+const DB_VERSION: u32 = 1;
+
+#[wasm_bindgen(typescript_custom_section)]
+const TS_VECTOR_TYPES: &'static str = r#"
+export interface VectorRecord {
+  /** 记录ID，为空时自动生成 */
+  id?: string;
+  /** 向量 */
+  vector: number[];
+  /** 文档内容 */
+  content?: string;
+  /** 元数据 */
+  metadata?: Record<string, any>;
+}
+
+export interface VectorMatch {
+  id: string;
+  score: number;
+  content?: string;
+  metadata: Record<string, any>;
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "VectorRecord[]")]
+    pub type VectorRecordArray;
+
+    #[wasm_bindgen(typescript_type = "VectorMatch[]")]
+    pub type VectorMatchArray;
+
+    #[wasm_bindgen(typescript_type = "Record<string, any>")]
+    pub type MetadataFilter;
+}
+
+/// 存储的向量记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredVector {
+    /// 记录ID
+    #[serde(default)]
+    pub id: String,
+    /// 向量
+    pub vector: Vec<f32>,
+    /// 文档内容
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// 元数据
+    #[serde(default)]
+    pub metadata: HashMap<String, serde_json::Value>,
+}
+
+/// 检索命中
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorMatch {
+    /// 记录ID
+    pub id: String,
+    /// 余弦相似度
+    pub score: f32,
+    /// 文档内容
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// 元数据
+    pub metadata: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Default)]
+struct StoreState {
+    records: Vec<StoredVector>,
+    db: Option<IdbDatabase>,
+}
+
+/// 浏览器端向量存储
+#[wasm_bindgen(js_name = VectorStore)]
+#[derive(Clone, Default)]
+pub struct WasmVectorStore {
+    state: Rc<RefCell<StoreState>>,
+}
+
+#[wasm_bindgen(js_class = VectorStore)]
+impl WasmVectorStore {
+    /// 创建仅驻留内存的向量存储
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> WasmVectorStore {
+        Self::default()
+    }
+
+    /// 打开IndexedDB持久化的向量存储，并载入已有记录
+    #[wasm_bindgen(js_name = open)]
+    pub async fn open(name: String) -> Result<WasmVectorStore, JsValue> {
+        let factory: IdbFactory = Reflect::get(&js_sys::global(), &"indexedDB".into())?
+            .dyn_into()
+            .map_err(|_| JsValue::from_str("IndexedDB is not available in this environment"))?;
+
+        let request = factory.open_with_u32(&name, DB_VERSION)?;
+        let upgrade_request = request.clone();
+        let on_upgrade = Closure::once_into_js(move |_: web_sys::Event| {
+            if let Ok(db) = upgrade_request.result().and_then(|db| db.dyn_into::<IdbDatabase>()) {
+                if !db.object_store_names().contains(OBJECT_STORE) {
+                    let params = IdbObjectStoreParameters::new();
+                    params.set_key_path(&JsValue::from_str("id"));
+                    let _ = db.create_object_store_with_optional_parameters(OBJECT_STORE, &params);
+                }
+            }
+        });
+        request.set_onupgradeneeded(Some(on_upgrade.unchecked_ref()));
+
+        let db: IdbDatabase = await_request(&request_of(&request)).await?.dyn_into()?;
+        let store = db.transaction_with_str(OBJECT_STORE)?.object_store(OBJECT_STORE)?;
+        let values = await_request(&store.get_all()?).await?;
+        let records: Vec<StoredVector> = serde_wasm_bindgen::from_value(values)?;
+
+        Ok(Self {
+            state: Rc::new(RefCell::new(StoreState { records, db: Some(db) })),
+        })
+    }
+
+    /// 记录数量
+    #[wasm_bindgen(getter)]
+    pub fn count(&self) -> usize {
+        self.state.borrow().records.len()
+    }
+
+    /// 是否持久化到IndexedDB
+    #[wasm_bindgen(getter)]
+    pub fn persistent(&self) -> bool {
+        self.state.borrow().db.is_some()
+    }
+
+    /// 写入或更新向量，返回记录ID
+    #[wasm_bindgen(js_name = upsert)]
+    pub async fn upsert_js(&self, records: VectorRecordArray) -> Result<Vec<String>, JsValue> {
+        let records: Vec<StoredVector> = serde_wasm_bindgen::from_value(records.into())?;
+        self.upsert(records).await
+    }
+
+    /// 余弦相似度检索，`filter` 中的字段需全部相等
+    #[wasm_bindgen(js_name = search)]
+    pub fn search_js(&self, vector: Vec<f32>, top_k: Option<usize>, filter: Option<MetadataFilter>) -> Result<VectorMatchArray, JsValue> {
+        let filter: Option<HashMap<String, serde_json::Value>> = match filter {
+            Some(filter) => Some(serde_wasm_bindgen::from_value(filter.into())?),
+            None => None,
+        };
+
+        let matches = self.search(&vector, top_k.unwrap_or(5), filter.as_ref());
+        Ok(to_js(&matches)?.unchecked_into())
+    }
+
+    /// 按ID删除向量
+    pub async fn delete(&self, ids: Vec<String>) -> Result<(), JsValue> {
+        self.state.borrow_mut().records.retain(|record| !ids.contains(&record.id));
+
+        let db = self.state.borrow().db.clone();
+        if let Some(db) = db {
+            let store = db.transaction_with_str_and_mode(OBJECT_STORE, IdbTransactionMode::Readwrite)?
+                .object_store(OBJECT_STORE)?;
+            // 在同一事务中发出全部请求，等待最后一个即可确认写入完成
+            let mut last = None;
+            for id in &ids {
+                last = Some(store.delete(&JsValue::from_str(id))?);
+            }
+            if let Some(request) = last {
+                await_request(&request).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 清空所有向量
+    pub async fn clear(&self) -> Result<(), JsValue> {
+        self.state.borrow_mut().records.clear();
+
+        let db = self.state.borrow().db.clone();
+        if let Some(db) = db {
+            let store = db.transaction_with_str_and_mode(OBJECT_STORE, IdbTransactionMode::Readwrite)?
+                .object_store(OBJECT_STORE)?;
+            await_request(&store.clear()?).await?;
+        }
+        Ok(())
+    }
+}
+
+impl WasmVectorStore {
+    /// 写入或更新向量，返回记录ID
+    pub async fn upsert(&self, records: Vec<StoredVector>) -> Result<Vec<String>, JsValue> {
+        let records: Vec<StoredVector> = records.into_iter()
+            .map(|mut record| {
+                if record.id.is_empty() {
+                    record.id = uuid::Uuid::new_v4().to_string();
+                }
+                record
+            })
+            .collect();
+        let ids = records.iter().map(|record| record.id.clone()).collect();
+
+        let db = self.state.borrow().db.clone();
+        if let Some(db) = db {
+            let store = db.transaction_with_str_and_mode(OBJECT_STORE, IdbTransactionMode::Readwrite)?
+                .object_store(OBJECT_STORE)?;
+            let mut last = None;
+            for record in &records {
+                last = Some(store.put(&to_js(record)?)?);
+            }
+            if let Some(request) = last {
+                await_request(&request).await?;
+            }
+        }
+
+        let mut state = self.state.borrow_mut();
+        for record in records {
+            match state.records.iter_mut().find(|existing| existing.id == record.id) {
+                Some(existing) => *existing = record,
+                None => state.records.push(record),
+            }
+        }
+
+        Ok(ids)
+    }
+
+    /// 余弦相似度检索
+    pub fn search(&self, vector: &[f32], top_k: usize, filter: Option<&HashMap<String, serde_json::Value>>) -> Vec<VectorMatch> {
+        let state = self.state.borrow();
+        let mut matches: Vec<VectorMatch> = state.records.iter()
+            .filter(|record| filter.map_or(true, |filter| {
+                filter.iter().all(|(key, value)| record.metadata.get(key) == Some(value))
+            }))
+            .map(|record| VectorMatch {
+                id: record.id.clone(),
+                score: cosine_similarity(vector, &record.vector),
+                content: record.content.clone(),
+                metadata: record.metadata.clone(),
+            })
+            .collect();
+
+        matches.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        matches.truncate(top_k);
+        matches
+    }
+}
+
+/// 计算余弦相似度，维度不一致或零向量时返回0
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+/// 序列化为普通JS对象（而非Map）
+pub(crate) fn to_js<T: Serialize + ?Sized>(value: &T) -> Result<JsValue, JsValue> {
+    value.serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .map_err(Into::into)
+}
+
+fn request_of(request: &IdbOpenDbRequest) -> IdbRequest {
+    request.clone().unchecked_into()
+}
+
+/// 等待IndexedDB请求完成
+async fn await_request(request: &IdbRequest) -> Result<JsValue, JsValue> {
+    let promise = Promise::new(&mut |resolve, reject| {
+        let success_request = request.clone();
+        let on_success = Closure::once_into_js(move |_: web_sys::Event| {
+            let result = success_request.result().unwrap_or(JsValue::UNDEFINED);
+            let _ = resolve.call1(&JsValue::UNDEFINED, &result);
+        });
+
+        let error_request = request.clone();
+        let on_error = Closure::once_into_js(move |_: web_sys::Event| {
+            let message = error_request.error().ok().flatten()
+                .map(|error| error.message())
+                .unwrap_or_else(|| "IndexedDB request failed".to_string());
+            let _ = reject.call1(&JsValue::UNDEFINED, &JsValue::from_str(&message));
+        });
+
+        request.set_onsuccess(Some(on_success.unchecked_ref()));
+        request.set_onerror(Some(on_error.unchecked_ref()));
+    });
+
+    JsFuture::from(promise).await
+}
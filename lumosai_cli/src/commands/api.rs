@@ -1,14 +1,28 @@
+pub mod codegen;
+
 use std::path::{Path, PathBuf};
 use std::fs;
 use colored::Colorize;
 use crate::error::{CliResult, CliError};
 use std::env;
+use codegen::SdkLanguage;
 
 /// 生成API端点
 pub async fn run(
     project_dir: Option<PathBuf>,
     output: Option<PathBuf>,
     agents: Option<Vec<String>>,
+) -> CliResult<()> {
+    run_with_sdk(project_dir, output, agents, &[], None).await
+}
+
+/// 生成API端点和OpenAPI规范，并为指定语言生成客户端SDK
+pub async fn run_with_sdk(
+    project_dir: Option<PathBuf>,
+    output: Option<PathBuf>,
+    agents: Option<Vec<String>>,
+    sdk_languages: &[SdkLanguage],
+    sdk_output: Option<PathBuf>,
 ) -> CliResult<()> {
     let project_dir = match project_dir {
         Some(dir) => dir,
@@ -83,9 +97,44 @@ pub async fn run(
         generate_agent_api(&output_dir, agent, &project_dir)?;
     }
 
+    // 生成OpenAPI规范，作为客户端SDK的来源
+    let project_name = project_dir
+        .canonicalize()
+        .ok()
+        .and_then(|dir| dir.file_name().and_then(|n| n.to_str()).map(str::to_string))
+        .unwrap_or_else(|| "lumosai".to_string());
+    let spec = codegen::agent_openapi_spec(&project_name, env!("CARGO_PKG_VERSION"), &agents_to_process);
+    let spec_path = output_dir.join("openapi.json");
+    fs::write(&spec_path, serde_json::to_string_pretty(&spec)?)
+        .map_err(|e| CliError::io_string(format!("无法写入文件: {}", spec_path.display()), e))?;
+    println!("{}", format!("生成OpenAPI规范: {}", spec_path.display()).bright_green());
+
     println!("{}", "API端点生成完成！".bright_green());
     println!("{}", format!("API文件位于: {}", output_dir.display()).bright_green());
 
+    if !sdk_languages.is_empty() {
+        let sdk_dir = sdk_output.unwrap_or_else(|| project_dir.join("sdk"));
+        generate_sdks(&spec, sdk_languages, &sdk_dir, &format!("{}-client", project_name))?;
+    }
+
+    Ok(())
+}
+
+/// 根据OpenAPI规范生成客户端SDK
+fn generate_sdks(spec: &serde_json::Value, languages: &[SdkLanguage], sdk_dir: &Path, package: &str) -> CliResult<()> {
+    for language in languages {
+        let language_dir = sdk_dir.join(language.dir_name());
+        for file in codegen::generate(*language, spec, package)? {
+            let path = language_dir.join(&file.path);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| CliError::io_string(format!("无法创建目录: {}", parent.display()), e))?;
+            }
+            fs::write(&path, file.content)
+                .map_err(|e| CliError::io_string(format!("无法写入文件: {}", path.display()), e))?;
+        }
+        println!("{}", format!("生成{} SDK: {}", language.dir_name(), language_dir.display()).bright_green());
+    }
     Ok(())
 }
 
@@ -209,6 +258,7 @@ fn generate_agent_api(output_dir: &Path, agent_name: &str, project_dir: &Path) -
     // 配置路由
     content.push_str("pub fn configure_routes(cfg: &mut web::ServiceConfig) {\n");
     content.push_str("    cfg.service(chat)\n");
+    content.push_str("        .service(chat_stream)\n");
     content.push_str("        .service(info);\n");
     content.push_str("}\n\n");
     
@@ -237,6 +287,22 @@ fn generate_agent_api(output_dir: &Path, agent_name: &str, project_dir: &Path) -
     content.push_str("    }\n");
     content.push_str("}\n\n");
     
+    // 添加流式聊天端点 (SSE)，事件格式与生成的客户端SDK一致
+    content.push_str("#[actix_web::post(\"/chat/stream\")]\nasync fn chat_stream(req: web::Json<ChatRequest>) -> impl Responder {\n");
+    content.push_str(&format!("    let agent = {}Agent::new();\n", agent_name.replace('-', "_")));
+    content.push_str("    \n");
+    content.push_str("    let body = match agent.process_message(&req.message, req.conversation_id.clone()).await {\n");
+    content.push_str("        Ok((response, conversation_id)) => format!(\n");
+    content.push_str("            \"data: {}\\n\\ndata: {}\\n\\n\",\n");
+    content.push_str("            serde_json::json!({ \"type\": \"delta\", \"content\": response }),\n");
+    content.push_str("            serde_json::json!({ \"type\": \"done\", \"conversation_id\": conversation_id }),\n");
+    content.push_str("        ),\n");
+    content.push_str("        Err(e) => format!(\"data: {}\\n\\n\", serde_json::json!({ \"type\": \"error\", \"error\": e.to_string() })),\n");
+    content.push_str("    };\n");
+    content.push_str("    \n");
+    content.push_str("    HttpResponse::Ok().content_type(\"text/event-stream\").body(body)\n");
+    content.push_str("}\n\n");
+    
     // 添加代理信息端点
     content.push_str("#[derive(Serialize)]\nstruct AgentInfo {\n    name: String,\n    description: String,\n}\n\n");
     content.push_str("#[actix_web::get(\"/\")]\nasync fn info() -> impl Responder {\n");
@@ -390,4 +456,34 @@ mod tests {
         assert!(agents.contains(&"agent2".to_string()));
         assert!(!agents.contains(&"invalid".to_string()));
     }
-} 
\ No newline at end of file
+    
+    #[tokio::test]
+    async fn test_run_with_sdk_generation() {
+        let temp_dir = tempdir().unwrap();
+        let temp_path = temp_dir.path();
+        
+        let agent_dir = temp_path.join("src").join("agents").join("weather");
+        fs::create_dir_all(&agent_dir).unwrap();
+        fs::write(agent_dir.join("agent.rs"), "// Test agent").unwrap();
+        
+        let result = run_with_sdk(
+            Some(temp_path.to_path_buf()),
+            None,
+            None,
+            &[SdkLanguage::TypeScript, SdkLanguage::Python],
+            None,
+        ).await;
+        assert!(result.is_ok());
+        
+        // 生成的端点包含流式路由，OpenAPI规范与之对应
+        let api_dir = temp_path.join("src").join("api");
+        assert!(fs::read_to_string(api_dir.join("weather.rs")).unwrap().contains("/chat/stream"));
+        let spec: serde_json::Value = serde_json::from_str(&fs::read_to_string(api_dir.join("openapi.json")).unwrap()).unwrap();
+        assert!(spec["paths"]["/weather/chat/stream"]["post"].is_object());
+        
+        let sdk_dir = temp_path.join("sdk");
+        let ts = fs::read_to_string(sdk_dir.join("typescript").join("src").join("index.ts")).unwrap();
+        assert!(ts.contains("weatherChatStream"));
+        assert!(sdk_dir.join("python").join("pyproject.toml").exists());
+    }
+}
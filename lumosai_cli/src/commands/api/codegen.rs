@@ -0,0 +1,787 @@
+//! 客户端SDK生成
+//!
+//! 根据 `lumos api` 生成的代理端点构建OpenAPI规范，
+//! 再由规范生成带类型的TypeScript和Python客户端，包括流式响应辅助方法。
+
+use std::path::PathBuf;
+use serde_json::{json, Map, Value};
+
+use crate::error::{CliResult, CliError};
+
+/// 默认的API服务地址，生成的路由挂载在 `/api` 下
+pub const DEFAULT_BASE_URL: &str = "http://localhost:4000/api";
+
+/// 生成头部注释
+const GENERATED_HEADER: &str = "由 lumos api --sdk 根据 openapi.json 生成，请勿手动修改";
+
+/// SDK语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SdkLanguage {
+    TypeScript,
+    Python,
+}
+
+impl SdkLanguage {
+    /// 解析逗号分隔的语言列表
+    pub fn parse_list(value: &str) -> CliResult<Vec<Self>> {
+        let mut languages = Vec::new();
+        for name in value.split(',').map(|s| s.trim().to_lowercase()).filter(|s| !s.is_empty()) {
+            let language = match name.as_str() {
+                "ts" | "typescript" => SdkLanguage::TypeScript,
+                "py" | "python" => SdkLanguage::Python,
+                other => {
+                    return Err(CliError::invalid_input_string(format!(
+                        "不支持的SDK语言: {} (可选: typescript, python)", other
+                    )));
+                }
+            };
+            if !languages.contains(&language) {
+                languages.push(language);
+            }
+        }
+        Ok(languages)
+    }
+
+    /// 输出子目录
+    pub fn dir_name(&self) -> &'static str {
+        match self {
+            SdkLanguage::TypeScript => "typescript",
+            SdkLanguage::Python => "python",
+        }
+    }
+}
+
+/// 生成的文件，路径相对于SDK输出目录
+#[derive(Debug, Clone)]
+pub struct GeneratedFile {
+    pub path: PathBuf,
+    pub content: String,
+}
+
+/// 从OpenAPI规范中提取的操作
+#[derive(Debug, Clone)]
+pub struct Operation {
+    pub id: String,
+    pub method: String,
+    pub path: String,
+    pub summary: Option<String>,
+    pub path_params: Vec<String>,
+    pub request: Option<Value>,
+    pub response: Option<Value>,
+    /// 响应为 `text/event-stream`
+    pub streaming: bool,
+}
+
+/// 构建代理端点的OpenAPI规范
+pub fn agent_openapi_spec(title: &str, version: &str, agents: &[String]) -> Value {
+    let mut paths = Map::new();
+    paths.insert("/".to_string(), json!({
+        "get": {
+            "operationId": "getApiInfo",
+            "summary": "获取API信息",
+            "responses": { "200": json_response("API信息", schema_ref("ApiInfo")) }
+        }
+    }));
+
+    for agent in agents {
+        let name = camel_case(agent);
+        paths.insert(format!("/{}/", agent), json!({
+            "get": {
+                "operationId": format!("{}Info", name),
+                "summary": format!("获取代理 {} 的信息", agent),
+                "responses": { "200": json_response("代理信息", schema_ref("AgentInfo")) }
+            }
+        }));
+        paths.insert(format!("/{}/chat", agent), json!({
+            "post": {
+                "operationId": format!("{}Chat", name),
+                "summary": format!("与代理 {} 对话", agent),
+                "requestBody": json_body(schema_ref("ChatRequest")),
+                "responses": { "200": json_response("代理回复", schema_ref("ChatResponse")) }
+            }
+        }));
+        paths.insert(format!("/{}/chat/stream", agent), json!({
+            "post": {
+                "operationId": format!("{}ChatStream", name),
+                "summary": format!("与代理 {} 对话，以SSE流式返回", agent),
+                "requestBody": json_body(schema_ref("ChatRequest")),
+                "responses": {
+                    "200": {
+                        "description": "SSE事件流",
+                        "content": { "text/event-stream": { "schema": schema_ref("StreamEvent") } }
+                    }
+                }
+            }
+        }));
+    }
+
+    json!({
+        "openapi": "3.0.0",
+        "info": {
+            "title": title,
+            "version": version,
+            "description": "JSON响应包装在 { success, data, error } 中，生成的客户端会自动解包 data"
+        },
+        "servers": [{ "url": DEFAULT_BASE_URL, "description": "本地开发服务器" }],
+        "paths": paths,
+        "components": {
+            "schemas": {
+                "ApiInfo": {
+                    "type": "object",
+                    "required": ["version", "agents"],
+                    "properties": {
+                        "version": { "type": "string" },
+                        "agents": { "type": "array", "items": { "type": "string" } }
+                    }
+                },
+                "AgentInfo": {
+                    "type": "object",
+                    "required": ["name", "description"],
+                    "properties": {
+                        "name": { "type": "string" },
+                        "description": { "type": "string" }
+                    }
+                },
+                "ChatRequest": {
+                    "type": "object",
+                    "required": ["message"],
+                    "properties": {
+                        "message": { "type": "string" },
+                        "conversation_id": { "type": "string" }
+                    }
+                },
+                "ChatResponse": {
+                    "type": "object",
+                    "required": ["response", "conversation_id"],
+                    "properties": {
+                        "response": { "type": "string" },
+                        "conversation_id": { "type": "string" }
+                    }
+                },
+                "StreamEvent": {
+                    "type": "object",
+                    "required": ["type"],
+                    "properties": {
+                        "type": { "type": "string", "enum": ["delta", "done", "error"] },
+                        "content": { "type": "string" },
+                        "conversation_id": { "type": "string" },
+                        "error": { "type": "string" }
+                    }
+                }
+            }
+        }
+    })
+}
+
+fn schema_ref(name: &str) -> Value {
+    json!({ "$ref": format!("#/components/schemas/{}", name) })
+}
+
+fn json_body(schema: Value) -> Value {
+    json!({ "required": true, "content": { "application/json": { "schema": schema } } })
+}
+
+fn json_response(description: &str, schema: Value) -> Value {
+    json!({ "description": description, "content": { "application/json": { "schema": schema } } })
+}
+
+/// 提取规范中的所有操作，按路径和方法排序
+pub fn operations(spec: &Value) -> CliResult<Vec<Operation>> {
+    let paths = spec.get("paths").and_then(Value::as_object)
+        .ok_or_else(|| CliError::invalid_input("OpenAPI规范缺少 paths"))?;
+
+    let mut operations = Vec::new();
+    for (path, item) in paths {
+        let Some(methods) = item.as_object() else { continue };
+        for (method, operation) in methods {
+            let Some(id) = operation.get("operationId").and_then(Value::as_str) else {
+                return Err(CliError::invalid_input_string(format!("操作 {} {} 缺少 operationId", method, path)));
+            };
+
+            let response = operation.pointer("/responses/200/content")
+                .or_else(|| operation.pointer("/responses/201/content"));
+            let streaming = response.and_then(|c| c.get("text/event-stream")).is_some();
+            let response = response.and_then(|c| {
+                c.get("application/json").or_else(|| c.get("text/event-stream"))
+            }).and_then(|m| m.get("schema")).cloned();
+
+            operations.push(Operation {
+                id: id.to_string(),
+                method: method.to_uppercase(),
+                path: path.clone(),
+                summary: operation.get("summary").and_then(Value::as_str).map(str::to_string),
+                path_params: path_params(path),
+                request: operation.pointer("/requestBody/content/application~1json/schema").cloned(),
+                response,
+                streaming,
+            });
+        }
+    }
+    operations.sort_by(|a, b| (&a.path, &a.method).cmp(&(&b.path, &b.method)));
+    Ok(operations)
+}
+
+fn path_params(path: &str) -> Vec<String> {
+    path.split('/')
+        .filter_map(|segment| segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')))
+        .map(str::to_string)
+        .collect()
+}
+
+fn schemas(spec: &Value) -> Vec<(String, Value)> {
+    let mut schemas: Vec<(String, Value)> = spec.pointer("/components/schemas")
+        .and_then(Value::as_object)
+        .map(|schemas| schemas.iter().map(|(name, schema)| (name.clone(), schema.clone())).collect())
+        .unwrap_or_default();
+    schemas.sort_by(|a, b| a.0.cmp(&b.0));
+    schemas
+}
+
+fn ref_name(schema: &Value) -> Option<&str> {
+    schema.get("$ref").and_then(Value::as_str).and_then(|r| r.rsplit('/').next())
+}
+
+/// 生成指定语言的SDK文件
+pub fn generate(language: SdkLanguage, spec: &Value, package: &str) -> CliResult<Vec<GeneratedFile>> {
+    match language {
+        SdkLanguage::TypeScript => generate_typescript(spec, package),
+        SdkLanguage::Python => generate_python(spec, package),
+    }
+}
+
+fn spec_version(spec: &Value) -> &str {
+    spec.pointer("/info/version").and_then(Value::as_str).unwrap_or("0.1.0")
+}
+
+fn default_base_url(spec: &Value) -> &str {
+    spec.pointer("/servers/0/url").and_then(Value::as_str).unwrap_or(DEFAULT_BASE_URL)
+}
+
+// ---------------------------------------------------------------------------
+// TypeScript
+// ---------------------------------------------------------------------------
+
+fn ts_type(schema: &Value) -> String {
+    if let Some(name) = ref_name(schema) {
+        return name.to_string();
+    }
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        return values.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(" | ");
+    }
+    match schema.get("type").and_then(Value::as_str) {
+        Some("string") => "string".to_string(),
+        Some("integer") | Some("number") => "number".to_string(),
+        Some("boolean") => "boolean".to_string(),
+        Some("array") => {
+            let item = schema.get("items").map(ts_type).unwrap_or_else(|| "unknown".to_string());
+            if item.contains(' ') { format!("Array<{}>", item) } else { format!("{}[]", item) }
+        }
+        Some("object") => match schema.get("properties").and_then(Value::as_object) {
+            Some(_) => format!("{{ {} }}", ts_fields(schema).join(" ")),
+            None => "Record<string, unknown>".to_string(),
+        },
+        _ => "unknown".to_string(),
+    }
+}
+
+fn ts_fields(schema: &Value) -> Vec<String> {
+    let required = required_fields(schema);
+    schema.get("properties").and_then(Value::as_object)
+        .map(|properties| properties.iter().map(|(name, property)| {
+            let optional = if required.contains(&name.as_str()) { "" } else { "?" };
+            format!("{}{}: {};", name, optional, ts_type(property))
+        }).collect())
+        .unwrap_or_default()
+}
+
+fn required_fields(schema: &Value) -> Vec<&str> {
+    schema.get("required").and_then(Value::as_array)
+        .map(|fields| fields.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default()
+}
+
+fn ts_path(path: &str) -> String {
+    let mut result = String::new();
+    for (i, segment) in path.split('/').enumerate() {
+        if i > 0 {
+            result.push('/');
+        }
+        match segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+            Some(param) => result.push_str(&format!("${{encodeURIComponent({})}}", camel_case(param))),
+            None => result.push_str(segment),
+        }
+    }
+    format!("`{}`", result)
+}
+
+/// 生成TypeScript客户端
+pub fn generate_typescript(spec: &Value, package: &str) -> CliResult<Vec<GeneratedFile>> {
+    let operations = operations(spec)?;
+    let mut out = String::new();
+
+    out.push_str(&format!("// {}\n\n", GENERATED_HEADER));
+
+    for (name, schema) in schemas(spec) {
+        if schema.get("properties").is_some() {
+            out.push_str(&format!("export interface {} {{\n", name));
+            for field in ts_fields(&schema) {
+                out.push_str(&format!("  {}\n", field));
+            }
+            out.push_str("}\n\n");
+        } else {
+            out.push_str(&format!("export type {} = {};\n\n", name, ts_type(&schema)));
+        }
+    }
+
+    out.push_str(&format!(r#"/** API返回错误时抛出 */
+export class LumosApiError extends Error {{
+  constructor(message: string, public readonly status: number) {{
+    super(message);
+    this.name = "LumosApiError";
+  }}
+}}
+
+export interface ClientOptions {{
+  /** API地址，默认 {base_url} */
+  baseUrl?: string;
+  /** 附加的请求头，例如认证信息 */
+  headers?: Record<string, string>;
+  /** 自定义fetch实现 */
+  fetch?: typeof fetch;
+}}
+
+export class LumosClient {{
+  private readonly baseUrl: string;
+  private readonly headers: Record<string, string>;
+  private readonly fetchImpl: typeof fetch;
+
+  constructor(options: ClientOptions = {{}}) {{
+    this.baseUrl = (options.baseUrl ?? "{base_url}").replace(/\/+$/, "");
+    this.headers = options.headers ?? {{}};
+    this.fetchImpl = options.fetch ?? globalThis.fetch.bind(globalThis);
+  }}
+"#, base_url = default_base_url(spec)));
+
+    for operation in &operations {
+        let mut params: Vec<String> = operation.path_params.iter()
+            .map(|p| format!("{}: string", camel_case(p)))
+            .collect();
+        if let Some(request) = &operation.request {
+            params.push(format!("body: {}", ts_type(request)));
+        }
+        let body = if operation.request.is_some() { "body" } else { "undefined" };
+        let response = operation.response.as_ref().map(ts_type).unwrap_or_else(|| "void".to_string());
+
+        out.push('\n');
+        if let Some(summary) = &operation.summary {
+            out.push_str(&format!("  /** {} */\n", summary));
+        }
+        if operation.streaming {
+            out.push_str(&format!(
+                "  {}({}): AsyncGenerator<{}> {{\n    return this.stream<{}>(\"{}\", {}, {});\n  }}\n",
+                operation.id, params.join(", "), response, response, operation.method, ts_path(&operation.path), body,
+            ));
+        } else {
+            out.push_str(&format!(
+                "  async {}({}): Promise<{}> {{\n    return this.request<{}>(\"{}\", {}, {});\n  }}\n",
+                operation.id, params.join(", "), response, response, operation.method, ts_path(&operation.path), body,
+            ));
+        }
+    }
+
+    out.push_str(r#"
+  private async request<T>(method: string, path: string, body?: unknown): Promise<T> {
+    const response = await this.fetchImpl(this.baseUrl + path, {
+      method,
+      headers: { ...this.headers, ...(body === undefined ? {} : { "Content-Type": "application/json" }) },
+      body: body === undefined ? undefined : JSON.stringify(body),
+    });
+    const text = await response.text();
+    let payload: any;
+    try {
+      payload = text ? JSON.parse(text) : undefined;
+    } catch {
+      throw new LumosApiError(text || response.statusText, response.status);
+    }
+    if (payload && typeof payload === "object" && "success" in payload) {
+      if (!response.ok || !payload.success) {
+        throw new LumosApiError(payload.error ?? response.statusText, response.status);
+      }
+      return payload.data as T;
+    }
+    if (!response.ok) {
+      throw new LumosApiError(text || response.statusText, response.status);
+    }
+    return payload as T;
+  }
+
+  /** 读取SSE事件流，每个 data 行解析为一个JSON事件 */
+  private async *stream<T>(method: string, path: string, body?: unknown): AsyncGenerator<T> {
+    const response = await this.fetchImpl(this.baseUrl + path, {
+      method,
+      headers: { ...this.headers, "Content-Type": "application/json", Accept: "text/event-stream" },
+      body: body === undefined ? undefined : JSON.stringify(body),
+    });
+    if (!response.ok || !response.body) {
+      throw new LumosApiError(await response.text(), response.status);
+    }
+
+    const reader = response.body.getReader();
+    const decoder = new TextDecoder();
+    let buffer = "";
+    while (true) {
+      const { done, value } = await reader.read();
+      if (done) break;
+      buffer += decoder.decode(value, { stream: true }).replace(/\r\n/g, "\n");
+
+      let index: number;
+      while ((index = buffer.indexOf("\n\n")) >= 0) {
+        const event = buffer.slice(0, index);
+        buffer = buffer.slice(index + 2);
+        const data = event
+          .split("\n")
+          .filter((line) => line.startsWith("data:"))
+          .map((line) => line.slice(5).trim())
+          .join("\n");
+        if (!data) continue;
+        if (data === "[DONE]") return;
+        yield JSON.parse(data) as T;
+      }
+    }
+  }
+}
+"#);
+
+    let package_json = serde_json::to_string_pretty(&json!({
+        "name": package,
+        "version": spec_version(spec),
+        "description": format!("{} TypeScript client", package),
+        "type": "module",
+        "main": "dist/index.js",
+        "types": "dist/index.d.ts",
+        "files": ["dist"],
+        "scripts": { "build": "tsc" },
+        "devDependencies": { "typescript": "^5.4.0" }
+    }))?;
+
+    let tsconfig = serde_json::to_string_pretty(&json!({
+        "compilerOptions": {
+            "target": "ES2020",
+            "module": "ES2020",
+            "moduleResolution": "node",
+            "lib": ["ES2020", "DOM"],
+            "declaration": true,
+            "outDir": "dist",
+            "strict": true
+        },
+        "include": ["src"]
+    }))?;
+
+    Ok(vec![
+        GeneratedFile { path: PathBuf::from("src/index.ts"), content: out },
+        GeneratedFile { path: PathBuf::from("package.json"), content: package_json + "\n" },
+        GeneratedFile { path: PathBuf::from("tsconfig.json"), content: tsconfig + "\n" },
+    ])
+}
+
+// ---------------------------------------------------------------------------
+// Python
+// ---------------------------------------------------------------------------
+
+fn py_type(schema: &Value) -> String {
+    if let Some(name) = ref_name(schema) {
+        return format!("\"{}\"", name);
+    }
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        return format!("Literal[{}]", values.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(", "));
+    }
+    match schema.get("type").and_then(Value::as_str) {
+        Some("string") => "str".to_string(),
+        Some("integer") => "int".to_string(),
+        Some("number") => "float".to_string(),
+        Some("boolean") => "bool".to_string(),
+        Some("array") => format!("List[{}]", schema.get("items").map(py_type).unwrap_or_else(|| "Any".to_string())),
+        Some("object") => "Dict[str, Any]".to_string(),
+        _ => "Any".to_string(),
+    }
+}
+
+fn py_path(path: &str) -> String {
+    if path.contains('{') {
+        let mut result = String::new();
+        for (i, segment) in path.split('/').enumerate() {
+            if i > 0 {
+                result.push('/');
+            }
+            match segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+                Some(param) => result.push_str(&format!("{{quote({}, safe='')}}", snake_case(param))),
+                None => result.push_str(segment),
+            }
+        }
+        format!("f\"{}\"", result)
+    } else {
+        format!("\"{}\"", path)
+    }
+}
+
+/// 生成Python客户端
+pub fn generate_python(spec: &Value, package: &str) -> CliResult<Vec<GeneratedFile>> {
+    let operations = operations(spec)?;
+    let module = snake_case(package);
+    let mut out = String::new();
+
+    out.push_str(&format!(r#""""{package} 客户端
+
+{header}
+"""
+
+from __future__ import annotations
+
+import json
+from typing import Any, Dict, Iterator, List, Literal, Optional, TypedDict
+from urllib.parse import quote
+
+import httpx
+
+DEFAULT_BASE_URL = "{base_url}"
+
+"#, package = package, header = GENERATED_HEADER, base_url = default_base_url(spec)));
+
+    for (name, schema) in schemas(spec) {
+        let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+            out.push_str(&format!("{} = {}\n\n\n", name, py_type(&schema)));
+            continue;
+        };
+        let required = required_fields(&schema);
+        out.push_str(&format!("class {}(TypedDict, total=False):\n", name));
+        if !required.is_empty() {
+            out.push_str(&format!("    \"\"\"必填字段: {}\"\"\"\n\n", required.join(", ")));
+        }
+        for (field, property) in properties {
+            out.push_str(&format!("    {}: {}\n", field, py_type(property)));
+        }
+        out.push_str("\n\n");
+    }
+
+    out.push_str(r#"class LumosApiError(Exception):
+    """API返回错误时抛出"""
+
+    def __init__(self, message: str, status: int) -> None:
+        super().__init__(message)
+        self.status = status
+
+
+class LumosClient:
+    def __init__(
+        self,
+        base_url: str = DEFAULT_BASE_URL,
+        headers: Optional[Dict[str, str]] = None,
+        timeout: float = 60.0,
+    ) -> None:
+        self._client = httpx.Client(base_url=base_url.rstrip("/"), headers=headers, timeout=timeout)
+
+    def close(self) -> None:
+        self._client.close()
+
+    def __enter__(self) -> "LumosClient":
+        return self
+
+    def __exit__(self, *exc: Any) -> None:
+        self.close()
+"#);
+
+    for operation in &operations {
+        let mut params = vec!["self".to_string()];
+        params.extend(operation.path_params.iter().map(|p| format!("{}: str", snake_case(p))));
+        if let Some(request) = &operation.request {
+            params.push(format!("body: {}", py_type(request)));
+        }
+        let body = if operation.request.is_some() { "body" } else { "None" };
+        let response = operation.response.as_ref().map(py_type).unwrap_or_else(|| "None".to_string());
+        let summary = operation.summary.as_ref()
+            .map(|s| format!("        \"\"\"{}\"\"\"\n", s))
+            .unwrap_or_default();
+
+        if operation.streaming {
+            out.push_str(&format!(
+                "\n    def {}({}) -> Iterator[{}]:\n{}        return self._stream(\"{}\", {}, {})\n",
+                snake_case(&operation.id), params.join(", "), response, summary,
+                operation.method, py_path(&operation.path), body,
+            ));
+        } else {
+            out.push_str(&format!(
+                "\n    def {}({}) -> {}:\n{}        return self._request(\"{}\", {}, {})\n",
+                snake_case(&operation.id), params.join(", "), response, summary,
+                operation.method, py_path(&operation.path), body,
+            ));
+        }
+    }
+
+    out.push_str(r#"
+    def _request(self, method: str, path: str, body: Any = None) -> Any:
+        response = self._client.request(method, path, json=body)
+        try:
+            payload = response.json()
+        except ValueError:
+            raise LumosApiError(response.text or response.reason_phrase, response.status_code)
+        if isinstance(payload, dict) and "success" in payload:
+            if response.is_error or not payload["success"]:
+                raise LumosApiError(payload.get("error") or response.reason_phrase, response.status_code)
+            return payload.get("data")
+        if response.is_error:
+            raise LumosApiError(response.text, response.status_code)
+        return payload
+
+    def _stream(self, method: str, path: str, body: Any = None) -> Iterator[Any]:
+        """读取SSE事件流，每个 data 行解析为一个JSON事件"""
+        headers = {"Accept": "text/event-stream"}
+        with self._client.stream(method, path, json=body, headers=headers) as response:
+            if response.is_error:
+                response.read()
+                raise LumosApiError(response.text, response.status_code)
+            data: List[str] = []
+            for line in response.iter_lines():
+                if line.startswith("data:"):
+                    data.append(line[5:].strip())
+                    continue
+                if line == "" and data:
+                    event = "\n".join(data)
+                    data = []
+                    if event == "[DONE]":
+                        return
+                    yield json.loads(event)
+            if data and data != ["[DONE]"]:
+                yield json.loads("\n".join(data))
+"#);
+
+    let pyproject = format!(r#"[project]
+name = "{package}"
+version = "{version}"
+description = "{package} Python client"
+requires-python = ">=3.8"
+dependencies = ["httpx>=0.24"]
+
+[build-system]
+requires = ["setuptools>=61"]
+build-backend = "setuptools.build_meta"
+"#, package = package, version = spec_version(spec));
+
+    Ok(vec![
+        GeneratedFile { path: PathBuf::from(&module).join("__init__.py"), content: out },
+        GeneratedFile { path: PathBuf::from("pyproject.toml"), content: pyproject },
+    ])
+}
+
+// ---------------------------------------------------------------------------
+// 命名
+// ---------------------------------------------------------------------------
+
+/// `weather-agent` / `weather_agent` -> `weatherAgent`
+pub fn camel_case(value: &str) -> String {
+    let mut result = String::new();
+    let mut upper = false;
+    for c in value.chars() {
+        if c == '-' || c == '_' || c == ' ' || c == '.' {
+            upper = !result.is_empty();
+        } else if upper {
+            result.extend(c.to_uppercase());
+            upper = false;
+        } else if result.is_empty() {
+            result.extend(c.to_lowercase());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// `weatherAgentChat` / `weather-agent` -> `weather_agent_chat`
+pub fn snake_case(value: &str) -> String {
+    let mut result = String::new();
+    for c in value.chars() {
+        if c.is_uppercase() {
+            if !result.is_empty() && !result.ends_with('_') {
+                result.push('_');
+            }
+            result.extend(c.to_lowercase());
+        } else if c == '-' || c == ' ' || c == '.' {
+            if !result.ends_with('_') {
+                result.push('_');
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn spec() -> Value {
+        agent_openapi_spec("demo", "1.2.0", &["weather-agent".to_string()])
+    }
+
+    #[test]
+    fn test_naming() {
+        assert_eq!(camel_case("weather-agent"), "weatherAgent");
+        assert_eq!(camel_case("Weather_agent"), "weatherAgent");
+        assert_eq!(snake_case("weatherAgentChatStream"), "weather_agent_chat_stream");
+        assert_eq!(snake_case("my-app"), "my_app");
+    }
+
+    #[test]
+    fn test_parse_languages() {
+        assert_eq!(
+            SdkLanguage::parse_list("ts, python,typescript").unwrap(),
+            vec![SdkLanguage::TypeScript, SdkLanguage::Python]
+        );
+        assert!(SdkLanguage::parse_list("go").is_err());
+    }
+
+    #[test]
+    fn test_operations_from_spec() {
+        let operations = operations(&spec()).unwrap();
+        let ids: Vec<&str> = operations.iter().map(|o| o.id.as_str()).collect();
+        assert_eq!(ids, vec!["getApiInfo", "weatherAgentInfo", "weatherAgentChat", "weatherAgentChatStream"]);
+
+        let stream = operations.iter().find(|o| o.id == "weatherAgentChatStream").unwrap();
+        assert!(stream.streaming);
+        assert_eq!(stream.method, "POST");
+        assert_eq!(ref_name(stream.response.as_ref().unwrap()), Some("StreamEvent"));
+    }
+
+    #[test]
+    fn test_path_params() {
+        assert_eq!(path_params("/agents/{agentId}/runs/{run_id}"), vec!["agentId", "run_id"]);
+        assert_eq!(ts_path("/agents/{agentId}"), "`/agents/${encodeURIComponent(agentId)}`");
+        assert_eq!(py_path("/agents/{agentId}"), "f\"/agents/{quote(agent_id, safe='')}\"");
+        assert_eq!(py_path("/info"), "\"/info\"");
+    }
+
+    #[test]
+    fn test_generate_typescript() {
+        let files = generate_typescript(&spec(), "demo-client").unwrap();
+        let index = &files.iter().find(|f| f.path == Path::new("src/index.ts")).unwrap().content;
+        assert!(index.contains("export interface ChatRequest {\n  conversation_id?: string;\n  message: string;\n}"));
+        assert!(index.contains("type: \"delta\" | \"done\" | \"error\";"));
+        assert!(index.contains("async weatherAgentChat(body: ChatRequest): Promise<ChatResponse>"));
+        assert!(index.contains("weatherAgentChatStream(body: ChatRequest): AsyncGenerator<StreamEvent>"));
+
+        let package: Value = serde_json::from_str(&files[1].content).unwrap();
+        assert_eq!(package["version"], "1.2.0");
+    }
+
+    #[test]
+    fn test_generate_python() {
+        let files = generate_python(&spec(), "demo-client").unwrap();
+        assert_eq!(files[0].path, PathBuf::from("demo_client/__init__.py"));
+        let module = &files[0].content;
+        assert!(module.contains("class ChatRequest(TypedDict, total=False):"));
+        assert!(module.contains("def weather_agent_chat(self, body: \"ChatRequest\") -> \"ChatResponse\":"));
+        assert!(module.contains("def weather_agent_chat_stream(self, body: \"ChatRequest\") -> Iterator[\"StreamEvent\"]:"));
+        assert!(module.contains("return self._stream(\"POST\", \"/weather-agent/chat/stream\", body)"));
+    }
+}
@@ -0,0 +1,230 @@
+use clap::Args;
+use std::path::{Path, PathBuf};
+use std::env;
+use std::fs;
+use std::sync::Arc;
+use std::time::Duration;
+use colored::Colorize;
+
+use lumosai_core::agent::create_basic_agent;
+use lumosai_core::app::LumosApp;
+use lumosai_core::performance::{run_load_test, LoadTestConfig, LoadTestReport, SimulatedProvider, TokenPricing};
+
+use crate::error::{CliResult, CliError};
+use crate::util::load_project_config;
+
+/// 对代理进行负载测试
+#[derive(Args, Debug)]
+pub struct BenchOptions {
+    /// 项目目录
+    #[arg(long)]
+    pub project_dir: Option<PathBuf>,
+
+    /// 要测试的代理，项目中只有一个代理时可省略
+    #[arg(long)]
+    pub agent: Option<String>,
+
+    /// 使用模拟提供商，不调用真实模型
+    #[arg(long)]
+    pub mock: bool,
+
+    /// 模拟提供商每次生成的延迟（毫秒）
+    #[arg(long, default_value = "50")]
+    pub mock_latency_ms: u64,
+
+    /// 同时进行的对话数
+    #[arg(long, short = 'c', default_value = "10")]
+    pub concurrency: usize,
+
+    /// 对话总数
+    #[arg(long, short = 'n', default_value = "100")]
+    pub conversations: usize,
+
+    /// 每个对话的轮数
+    #[arg(long, default_value = "1")]
+    pub turns: usize,
+
+    /// 用户消息，可重复指定，按对话和轮次轮流使用
+    #[arg(long = "prompt")]
+    pub prompts: Vec<String>,
+
+    /// 单次请求超时（秒）
+    #[arg(long)]
+    pub timeout: Option<u64>,
+
+    /// 每百万输入令牌的价格（美元），用于估算成本
+    #[arg(long)]
+    pub input_price: Option<f64>,
+
+    /// 每百万输出令牌的价格（美元），用于估算成本
+    #[arg(long)]
+    pub output_price: Option<f64>,
+
+    /// 将JSON报告写入文件
+    #[arg(long)]
+    pub output: Option<PathBuf>,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        Self {
+            project_dir: None,
+            agent: None,
+            mock: false,
+            mock_latency_ms: 50,
+            concurrency: 10,
+            conversations: 100,
+            turns: 1,
+            prompts: Vec::new(),
+            timeout: None,
+            input_price: None,
+            output_price: None,
+            output: None,
+        }
+    }
+}
+
+impl BenchOptions {
+    /// 转换为负载测试配置
+    pub fn load_test_config(&self) -> LoadTestConfig {
+        let defaults = LoadTestConfig::default();
+        let pricing = (self.input_price.is_some() || self.output_price.is_some()).then(|| TokenPricing {
+            input_per_million: self.input_price.unwrap_or(0.0),
+            output_per_million: self.output_price.unwrap_or(0.0),
+        });
+        LoadTestConfig {
+            concurrency: self.concurrency,
+            conversations: self.conversations,
+            turns: self.turns,
+            prompts: if self.prompts.is_empty() { defaults.prompts } else { self.prompts.clone() },
+            request_timeout: self.timeout.map(Duration::from_secs),
+            pricing,
+        }
+    }
+}
+
+/// 运行Bench命令
+pub async fn run(options: BenchOptions) -> CliResult<()> {
+    let config = options.load_test_config();
+    config.validate()?;
+
+    let agent = if options.mock {
+        println!("{}", format!("使用模拟提供商 (延迟 {} ms)", options.mock_latency_ms).bright_blue());
+        let llm = Arc::new(SimulatedProvider::new(Duration::from_millis(options.mock_latency_ms)));
+        Arc::new(create_basic_agent("bench", "You are a helpful assistant.", llm)) as Arc<dyn lumosai_core::Agent>
+    } else {
+        let project_dir = match &options.project_dir {
+            Some(dir) => dir.clone(),
+            None => env::current_dir().map_err(|e| CliError::io("获取当前目录失败", e))?,
+        };
+        let Some((config_path, project)) = load_project_config(&project_dir)? else {
+            return Err(CliError::invalid_input("未找到项目配置文件 (lumos.yaml)，可使用 --mock 测试模拟代理"));
+        };
+        println!("{}", format!("项目配置: {}", config_path.display()).bright_blue());
+
+        let mut agents = project.list_agents();
+        agents.sort();
+        let agent_name = resolve_agent(options.agent.as_deref(), &agents)?;
+        println!("{}", format!("测试代理: {}", agent_name).bright_blue());
+        let app = LumosApp::from_yaml_config(project).await?;
+        app.agent(&agent_name)?.clone()
+    };
+
+    println!(
+        "{}",
+        format!("{} 个对话 × {} 轮，并发 {}", config.conversations, config.turns, config.concurrency).bright_blue()
+    );
+    let report = run_load_test(agent, &config).await?;
+    print_report(&report);
+
+    if let Some(path) = &options.output {
+        write_report(&report, path)?;
+    }
+    Ok(())
+}
+
+/// 选择要测试的代理：命令行参数 > 唯一声明的代理
+fn resolve_agent(agent: Option<&str>, agents: &[String]) -> CliResult<String> {
+    match agent {
+        Some(agent) if agents.iter().any(|name| name == agent) => Ok(agent.to_string()),
+        Some(agent) => Err(CliError::invalid_input_string(format!("项目配置中未声明代理: {}", agent))),
+        None if agents.len() == 1 => Ok(agents[0].clone()),
+        None if agents.is_empty() => Err(CliError::invalid_input("项目中未声明代理")),
+        None => Err(CliError::invalid_input("项目中声明了多个代理，请使用 --agent 指定")),
+    }
+}
+
+/// 打印测试结果
+fn print_report(report: &LoadTestReport) {
+    let latency = &report.latency;
+    println!();
+    println!("{:<10} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10}", "延迟(ms)", "最小", "平均", "p50", "p95", "p99", "最大");
+    println!(
+        "{:<10} {:>10.1} {:>10.1} {:>10.1} {:>10.1} {:>10.1} {:>10.1}",
+        "", latency.min_ms, latency.mean_ms, latency.p50_ms, latency.p95_ms, latency.p99_ms, latency.max_ms,
+    );
+    println!();
+    println!("请求: {} (失败 {})，成功率 {:.1}%", report.requests, report.failures, report.success_rate() * 100.0);
+    println!("耗时: {} ms，吞吐量 {:.2} 请求/秒", report.duration_ms, report.throughput_rps);
+    println!(
+        "令牌: 输入 {}，输出 {}，合计 {}",
+        report.usage.prompt_tokens, report.usage.completion_tokens, report.usage.total_tokens,
+    );
+    if let Some(cost) = report.cost_usd {
+        println!("成本: ${:.4}", cost);
+    }
+
+    for (error, count) in &report.errors {
+        println!("{} {} × {}", "✗".bright_red(), count, error.dimmed());
+    }
+    if report.failures == 0 {
+        println!("{}", "所有请求均成功".bright_green());
+    }
+}
+
+/// 写入JSON报告
+fn write_report(report: &LoadTestReport, path: &Path) -> CliResult<()> {
+    let content = serde_json::to_string_pretty(report)
+        .map_err(|e| CliError::Other(format!("序列化报告失败: {}", e)))?;
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).map_err(|e| CliError::io("创建报告目录失败", e))?;
+    }
+    fs::write(path, content).map_err(|e| CliError::io(&format!("写入报告失败 {}", path.display()), e))?;
+    println!("{}", format!("报告已写入: {}", path.display()).bright_blue());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_test_config() {
+        let config = BenchOptions::default().load_test_config();
+        assert_eq!(config.prompts, vec!["Hello".to_string()]);
+        assert!(config.pricing.is_none());
+
+        let options = BenchOptions {
+            prompts: vec!["Hi".to_string()],
+            input_price: Some(2.5),
+            timeout: Some(30),
+            ..BenchOptions::default()
+        };
+        let config = options.load_test_config();
+        assert_eq!(config.prompts, vec!["Hi".to_string()]);
+        assert_eq!(config.pricing, Some(TokenPricing { input_per_million: 2.5, output_per_million: 0.0 }));
+        assert_eq!(config.request_timeout, Some(Duration::from_secs(30)));
+    }
+
+    #[test]
+    fn test_resolve_agent() {
+        let single = vec!["assistant".to_string()];
+        assert_eq!(resolve_agent(None, &single).unwrap(), "assistant");
+
+        let many = vec!["assistant".to_string(), "writer".to_string()];
+        assert!(resolve_agent(None, &many).is_err());
+        assert_eq!(resolve_agent(Some("writer"), &many).unwrap(), "writer");
+        assert!(resolve_agent(Some("missing"), &many).is_err());
+        assert!(resolve_agent(None, &[]).is_err());
+    }
+}
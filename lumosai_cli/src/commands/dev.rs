@@ -1,17 +1,21 @@
 use clap::Args;
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::env;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use colored::Colorize;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, RwLock};
 use tokio::task;
 use tokio::time::{sleep, Duration};
 use notify::{Watcher, RecursiveMode};
 
 use crate::error::{CliResult, CliError};
-use crate::util::is_lumos_project;
+use crate::util::{is_lumos_project, load_project_config};
 use crate::server::{ui_server, api_server};
+use crate::server::dev_reload::{DevEvent, DevEventHub, now_millis};
+use lumosai_core::app::{ConfigReload, LumosApp};
+use lumosai_core::config::ConfigLoader;
 
 /// 开发服务器配置选项
 #[derive(Args, Debug)]
@@ -97,6 +101,28 @@ pub async fn run(options: DevOptions) -> CliResult<()> {
         println!("{}", format!("自定义工具目录: {}", tools_dir.display()).bright_blue());
     }
 
+    // 热重载事件中心，通过 /ws/dev 推送给Playground/UI会话
+    let dev_events = DevEventHub::new();
+
+    // 加载并校验声明式项目配置
+    let project_config = load_project_config(&project_dir)?;
+    let project_app = match &project_config {
+        Some((config_path, config)) => {
+            println!("{}", format!("项目配置: {}", config_path.display()).bright_blue());
+            println!("{}", format!("已声明代理: {}", config.list_agents().join(", ")).bright_blue());
+
+            match LumosApp::from_yaml_config(config.clone()).await {
+                Ok(app) => Some(app),
+                Err(e) => {
+                    println!("{}", format!("警告: 无法根据项目配置创建代理: {}", e).bright_yellow());
+                    None
+                }
+            }
+        }
+        None => None,
+    };
+    let project_app = Arc::new(RwLock::new(project_app));
+
     // 创建通道用于服务器之间的通信
     let (tx, mut rx) = mpsc::channel::<String>(100);
     
@@ -114,12 +140,18 @@ pub async fn run(options: DevOptions) -> CliResult<()> {
         let project_dir = project_dir.clone();
         let api_tx = tx.clone();
         let running = running.clone();
+        let dev_events = dev_events.clone();
         
         task::spawn(async move {
             while running.load(Ordering::SeqCst) {
                 println!("{}", "正在启动API服务器...".bright_blue());
                 
-                match api_server::start_server(options.port, project_dir.clone(), None).await {
+                match api_server::start_server_with_events(
+                    options.port,
+                    project_dir.clone(),
+                    None,
+                    Some(dev_events.clone()),
+                ).await {
                     Ok(_) => {
                         api_tx.send("api_server_stopped".to_string()).await.ok();
                         break;
@@ -198,6 +230,19 @@ pub async fn run(options: DevOptions) -> CliResult<()> {
         None
     };
     
+    // 监视项目配置和提示词文件，变更后就地重建受影响的代理
+    let config_watcher_handle = project_config.map(|(config_path, _)| {
+        let app = project_app.clone();
+        let dev_events = dev_events.clone();
+        let running = running.clone();
+
+        task::spawn(async move {
+            if let Err(e) = start_config_watcher(config_path, app, dev_events, running).await {
+                println!("{}", format!("配置监视器错误: {}", e).bright_red());
+            }
+        })
+    });
+
     // 等待服务器事件或中断信号
     while running.load(Ordering::SeqCst) {
         match rx.recv().await {
@@ -239,6 +284,9 @@ pub async fn run(options: DevOptions) -> CliResult<()> {
     if let Some(handle) = watcher_handle {
         let _ = handle.await;
     }
+    if let Some(handle) = config_watcher_handle {
+        let _ = handle.await;
+    }
     
     println!("{}", "开发服务器已关闭".bright_green());
     Ok(())
@@ -320,6 +368,153 @@ async fn start_file_watcher(
     Ok(())
 }
 
+/// 需要监视的配置文件集合：项目配置文件及其引用的提示词文件
+fn reload_targets(config_path: &Path) -> HashSet<PathBuf> {
+    let mut targets = HashSet::new();
+    targets.insert(config_path.to_path_buf());
+
+    if let Ok(config) = ConfigLoader::load(config_path) {
+        let base_dir = config_path.parent().unwrap_or_else(|| Path::new("."));
+        targets.extend(config.prompt_files(base_dir));
+    }
+
+    targets
+}
+
+/// 启动项目配置监视器
+///
+/// 监视 `lumos.yaml` 及代理引用的提示词文件，变更后重新加载配置并
+/// 只重建受影响的代理；结果通过事件中心推送给已连接的会话。
+async fn start_config_watcher(
+    config_path: PathBuf,
+    app: Arc<RwLock<Option<LumosApp>>>,
+    events: DevEventHub,
+    running: Arc<AtomicBool>,
+) -> CliResult<()> {
+    let config_path = config_path.canonicalize()
+        .map_err(|e| CliError::io("解析配置文件路径失败", e))?;
+
+    let (event_tx, mut event_rx) = mpsc::channel::<Vec<PathBuf>>(100);
+    let mut watcher = notify::recommended_watcher(move |res: Result<notify::Event, notify::Error>| {
+        match res {
+            Ok(event) => {
+                if event.kind.is_modify() || event.kind.is_create() || event.kind.is_remove() {
+                    let _ = event_tx.blocking_send(event.paths);
+                }
+            },
+            Err(e) => println!("{}", format!("配置监视错误: {}", e).bright_red()),
+        }
+    }).map_err(|e| CliError::internal(format!("创建配置监视器失败: {}", e).as_str()))?;
+
+    // 监视文件所在目录，以便捕获编辑器通过重命名方式写入的变更
+    let mut watched_dirs = HashSet::new();
+    let mut targets = reload_targets(&config_path);
+    watch_target_dirs(&mut watcher, &targets, &mut watched_dirs);
+
+    println!("{}", format!("正在监视项目配置: {}", config_path.display()).bright_blue());
+
+    while running.load(Ordering::SeqCst) {
+        let paths = match tokio::time::timeout(Duration::from_secs(1), event_rx.recv()).await {
+            Ok(Some(paths)) => paths,
+            Ok(None) => break,
+            Err(_) => continue,
+        };
+
+        let mut changed: HashSet<PathBuf> = paths.into_iter().filter(|p| targets.contains(p)).collect();
+        if changed.is_empty() {
+            continue;
+        }
+
+        // 防抖动：合并短时间内的连续写入
+        sleep(Duration::from_millis(300)).await;
+        while let Ok(paths) = event_rx.try_recv() {
+            changed.extend(paths.into_iter().filter(|p| targets.contains(p)));
+        }
+
+        let mut changed: Vec<String> = changed.iter().map(|p| p.display().to_string()).collect();
+        changed.sort();
+        println!("{}", format!("检测到配置变更: {}", changed.join(", ")).bright_green());
+        events.publish(DevEvent::FilesChanged { paths: changed, timestamp: now_millis() });
+
+        match reload_project_config(&config_path, &app).await {
+            Ok(reload) => {
+                print_reload_summary(&reload);
+                events.publish(DevEvent::Reloaded {
+                    added: reload.added,
+                    updated: reload.updated,
+                    removed: reload.removed,
+                    timestamp: now_millis(),
+                });
+            }
+            Err(e) => {
+                println!("{}", format!("配置重新加载失败，继续使用之前的代理: {}", e).bright_red());
+                events.publish(DevEvent::ReloadFailed { error: e.to_string(), timestamp: now_millis() });
+            }
+        }
+
+        // 提示词文件引用可能已变化
+        targets = reload_targets(&config_path);
+        watch_target_dirs(&mut watcher, &targets, &mut watched_dirs);
+    }
+
+    println!("{}", "配置监视器已停止".bright_yellow());
+    Ok(())
+}
+
+/// 为尚未监视的目标文件目录添加监视
+fn watch_target_dirs(
+    watcher: &mut impl Watcher,
+    targets: &HashSet<PathBuf>,
+    watched_dirs: &mut HashSet<PathBuf>,
+) {
+    for dir in targets.iter().filter_map(|p| p.parent()) {
+        if watched_dirs.contains(dir) || !dir.is_dir() {
+            continue;
+        }
+        match watcher.watch(dir, RecursiveMode::NonRecursive) {
+            Ok(_) => {
+                watched_dirs.insert(dir.to_path_buf());
+            }
+            Err(e) => println!("{}", format!("监视目录失败 {}: {}", dir.display(), e).bright_red()),
+        }
+    }
+}
+
+/// 重新加载项目配置并就地重建代理
+async fn reload_project_config(
+    config_path: &Path,
+    app: &RwLock<Option<LumosApp>>,
+) -> CliResult<ConfigReload> {
+    let config = ConfigLoader::load_validated(config_path)?;
+
+    let mut app = app.write().await;
+    match app.as_mut() {
+        Some(app) => Ok(app.reload_config(config).await?),
+        None => {
+            let added = config.list_agents();
+            *app = Some(LumosApp::from_yaml_config(config).await?);
+            Ok(ConfigReload { added, ..ConfigReload::default() })
+        }
+    }
+}
+
+/// 打印重新加载结果
+fn print_reload_summary(reload: &ConfigReload) {
+    if reload.is_empty() {
+        println!("{}", "配置已重新加载，代理无变化".bright_blue());
+        return;
+    }
+    if !reload.added.is_empty() {
+        println!("{}", format!("新增代理: {}", reload.added.join(", ")).bright_green());
+    }
+    if !reload.updated.is_empty() {
+        println!("{}", format!("已重建代理: {}", reload.updated.join(", ")).bright_green());
+    }
+    if !reload.removed.is_empty() {
+        println!("{}", format!("已移除代理: {}", reload.removed.join(", ")).bright_yellow());
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -372,6 +567,32 @@ mod tests {
         assert!(result.is_err());
     }
     
+    #[test]
+    fn test_reload_targets_include_prompt_files() {
+        let dir = temp_dir().join("lumosai_dev_reload_targets");
+        if dir.exists() {
+            fs::remove_dir_all(&dir).unwrap();
+        }
+        fs::create_dir_all(dir.join("prompts")).unwrap();
+        fs::write(dir.join("prompts/assistant.md"), "You are helpful.").unwrap();
+        fs::write(
+            dir.join("lumos.yaml"),
+            r#"project:
+  name: reload-test
+agents:
+  assistant:
+    model: gpt-4
+    instructions_file: prompts/assistant.md
+"#,
+        ).unwrap();
+
+        let targets = reload_targets(&dir.join("lumos.yaml"));
+        assert!(targets.contains(&dir.join("lumos.yaml")));
+        assert!(targets.contains(&dir.join("prompts/assistant.md")));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
     #[test]
     fn test_create_minimal_project_structure() -> CliResult<()> {
         // 创建临时项目目录
@@ -0,0 +1,303 @@
+use clap::Args;
+use std::path::{Path, PathBuf};
+use std::env;
+use std::fs;
+use std::time::Instant;
+use colored::Colorize;
+use indicatif::{ProgressBar, ProgressStyle};
+
+use lumosai_core::agent::types::AgentGenerateOptions;
+use lumosai_core::app::LumosApp;
+use lumosai_core::llm::types::user_message;
+use lumosai_core::storage::object_store_from_url;
+use lumosai_evals::store::report_file_stem;
+use lumosai_evals::{CaseOutput, EvalSuite, ReportStore, SuiteReport};
+
+use crate::error::{CliResult, CliError};
+use crate::util::load_project_config;
+
+/// 阈值未通过时的退出码
+pub const EXIT_THRESHOLD_FAILED: i32 = 1;
+
+/// 评估无法运行时的退出码
+pub const EXIT_ERROR: i32 = 2;
+
+/// 报告目录中保存历史运行的子目录，评估看板从这里读取趋势
+pub const HISTORY_DIR: &str = "history";
+
+/// 运行评估套件
+#[derive(Args, Debug)]
+pub struct EvalOptions {
+    /// 评估套件文件 (YAML或JSON)
+    pub suite: PathBuf,
+
+    /// 项目目录
+    #[arg(long)]
+    pub project_dir: Option<PathBuf>,
+
+    /// 覆盖套件中配置的代理
+    #[arg(long)]
+    pub agent: Option<String>,
+
+    /// 报告输出目录
+    #[arg(long, default_value = "eval-reports")]
+    pub report_dir: PathBuf,
+
+    /// 报告格式，以逗号分隔 (json,html)
+    #[arg(long, default_value = "json,html")]
+    pub format: String,
+
+    /// 提示词或配置变体名称，用于在评估看板中对比不同运行
+    #[arg(long)]
+    pub variant: Option<String>,
+
+    /// 同时上传报告历史的对象存储，如 s3://bucket/evals、gs://bucket/evals 或 az://container/evals
+    #[arg(long)]
+    pub history_url: Option<String>,
+}
+
+impl Default for EvalOptions {
+    fn default() -> Self {
+        Self {
+            suite: PathBuf::from("evals/suite.yaml"),
+            project_dir: None,
+            agent: None,
+            report_dir: PathBuf::from("eval-reports"),
+            format: "json,html".to_string(),
+            variant: None,
+            history_url: None,
+        }
+    }
+}
+
+/// 运行Eval命令，返回套件是否通过所有阈值
+pub async fn run(options: EvalOptions) -> CliResult<bool> {
+    let project_dir = match &options.project_dir {
+        Some(dir) => dir.clone(),
+        None => env::current_dir().map_err(|e| CliError::io("获取当前目录失败", e))?,
+    };
+
+    let formats = parse_formats(&options.format)?;
+
+    let suite = EvalSuite::load(&options.suite)
+        .map_err(|e| CliError::Other(format!("无法加载评估套件 {}: {}", options.suite.display(), e)))?;
+
+    let Some((config_path, config)) = load_project_config(&project_dir)? else {
+        return Err(CliError::invalid_input("未找到项目配置文件 (lumos.yaml)"));
+    };
+
+    println!("{}", format!("评估套件: {} ({} 个用例)", suite.name, suite.tests.len()).bright_blue());
+    println!("{}", format!("项目配置: {}", config_path.display()).bright_blue());
+
+    let mut agents = config.list_agents();
+    agents.sort();
+    let agent_models: Vec<(String, String)> = agents.iter()
+        .filter_map(|name| config.get_agent(name).map(|agent| (name.clone(), agent.model.clone())))
+        .collect();
+    let app = LumosApp::from_yaml_config(config).await?;
+
+    let started_at = chrono::Utc::now();
+    let progress = ProgressBar::new(suite.tests.len() as u64);
+    progress.set_style(
+        ProgressStyle::default_bar()
+            .template("{spinner:.green} [{bar:40.cyan/blue}] {pos}/{len} {msg}")
+            .unwrap_or_else(|_| ProgressStyle::default_bar())
+            .progress_chars("#>-"),
+    );
+
+    let mut cases = Vec::with_capacity(suite.tests.len());
+    for case in &suite.tests {
+        let agent_name = resolve_agent(&options, &suite, case.agent.as_deref(), &agents)?;
+        progress.set_message(agent_name.clone());
+
+        let agent = app.agent(&agent_name)?;
+        let started = Instant::now();
+        let output = match agent.generate(&[user_message(&case.input)], &AgentGenerateOptions::default()).await {
+            Ok(result) => CaseOutput {
+                tools: result.steps
+                    .iter()
+                    .flat_map(|step| step.tool_calls.iter().map(|call| call.name.clone()))
+                    .collect(),
+                output: result.response,
+                latency_ms: started.elapsed().as_millis() as u64,
+                error: None,
+            },
+            Err(e) => CaseOutput {
+                latency_ms: started.elapsed().as_millis() as u64,
+                error: Some(e.to_string()),
+                ..CaseOutput::default()
+            },
+        };
+
+        let mut report = suite.score_case(case, output).await
+            .map_err(|e| CliError::Other(format!("评估用例 {} 失败: {}", case.id, e)))?;
+        report.agent = Some(agent_name);
+        cases.push(report);
+        progress.inc(1);
+    }
+    progress.finish_and_clear();
+
+    let mut models: Vec<String> = cases.iter()
+        .filter_map(|case| agent_models.iter().find(|(name, _)| Some(name) == case.agent.as_ref()))
+        .map(|(_, model)| model.clone())
+        .filter(|model| !model.is_empty())
+        .collect();
+    models.sort();
+    models.dedup();
+
+    let mut report = suite.report(cases, started_at);
+    report.model = (!models.is_empty()).then(|| models.join(","));
+    report.variant = options.variant.clone();
+    print_summary(&report);
+    write_reports(&report, &options.report_dir, &formats)?;
+
+    let history = ReportStore::new(options.report_dir.join(HISTORY_DIR));
+    let path = history.save(&report).map_err(|e| CliError::Other(format!("保存报告历史失败: {}", e)))?;
+    println!("{}", format!("报告历史: {}", path.display()).bright_blue());
+    if let Some(url) = &options.history_url {
+        let objects = object_store_from_url(url)?;
+        let key = history.push(&report, objects.as_ref()).await
+            .map_err(|e| CliError::Other(format!("上传报告历史失败: {}", e)))?;
+        println!("{}", format!("已上传报告历史: {}", key).bright_blue());
+    }
+
+    if report.passed {
+        println!("{}", "评估通过".bright_green());
+    } else {
+        for failure in &report.failures {
+            println!("{}", failure.bright_red());
+        }
+        println!("{}", "评估未通过".bright_red());
+    }
+
+    Ok(report.passed)
+}
+
+/// 选择运行用例的代理：命令行参数 > 用例 > 套件 > 唯一声明的代理
+fn resolve_agent(
+    options: &EvalOptions,
+    suite: &EvalSuite,
+    case_agent: Option<&str>,
+    agents: &[String],
+) -> CliResult<String> {
+    let agent = options.agent.as_deref()
+        .or(case_agent)
+        .or(suite.agent.as_deref())
+        .map(str::to_string);
+
+    match agent {
+        Some(agent) if agents.contains(&agent) => Ok(agent),
+        Some(agent) => Err(CliError::invalid_input_string(format!("项目配置中未声明代理: {}", agent))),
+        None if agents.len() == 1 => Ok(agents[0].clone()),
+        None => Err(CliError::invalid_input("项目中声明了多个代理，请在套件中设置 agent 或使用 --agent 指定")),
+    }
+}
+
+fn parse_formats(format: &str) -> CliResult<Vec<String>> {
+    let formats: Vec<String> = format
+        .split(',')
+        .map(|f| f.trim().to_lowercase())
+        .filter(|f| !f.is_empty())
+        .collect();
+
+    for format in &formats {
+        if format != "json" && format != "html" {
+            return Err(CliError::invalid_input_string(format!("不支持的报告格式: {} (可选: json, html)", format)));
+        }
+    }
+    Ok(formats)
+}
+
+/// 打印指标汇总表
+fn print_summary(report: &SuiteReport) {
+    println!();
+    println!("{:<20} {:>8} {:>8} {:>10} {:>6}", "指标", "平均", "最低", "通过率", "用例");
+    for metric in &report.metrics {
+        let line = format!(
+            "{:<20} {:>8.3} {:>8.3} {:>9.1}% {:>6}",
+            metric.metric, metric.mean, metric.min, metric.pass_rate * 100.0, metric.count,
+        );
+        if metric.pass_rate < 1.0 {
+            println!("{}", line.bright_yellow());
+        } else {
+            println!("{}", line);
+        }
+    }
+    println!();
+
+    for case in report.cases.iter().filter(|case| !case.passed) {
+        let reason = match &case.output.error {
+            Some(error) => error.clone(),
+            None => case.scores
+                .iter()
+                .filter(|score| !score.passed)
+                .map(|score| format!("{} {:.3} < {:.3}", score.metric, score.score, score.threshold))
+                .collect::<Vec<_>>()
+                .join(", "),
+        };
+        println!("{} {} {}", "✗".bright_red(), case.id, reason.dimmed());
+    }
+
+    let passed = report.cases.iter().filter(|case| case.passed).count();
+    println!(
+        "{}",
+        format!(
+            "用例: {}/{} 通过 ({:.1}%)，平均得分 {:.3}，耗时 {} ms",
+            passed, report.cases.len(), report.pass_rate * 100.0, report.mean_score, report.duration_ms,
+        ).bright_blue()
+    );
+}
+
+/// 写入JSON/HTML报告
+fn write_reports(report: &SuiteReport, report_dir: &Path, formats: &[String]) -> CliResult<()> {
+    if formats.is_empty() {
+        return Ok(());
+    }
+    fs::create_dir_all(report_dir).map_err(|e| CliError::io("创建报告目录失败", e))?;
+
+    let file_stem = report_file_stem(&report.suite);
+    for format in formats {
+        let path = report_dir.join(format!("{}.{}", file_stem, format));
+        let content = match format.as_str() {
+            "json" => report.to_json().map_err(|e| CliError::Other(format!("序列化报告失败: {}", e)))?,
+            _ => report.to_html(),
+        };
+        fs::write(&path, content).map_err(|e| CliError::io(&format!("写入报告失败 {}", path.display()), e))?;
+        println!("{}", format!("报告已写入: {}", path.display()).bright_blue());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_formats() {
+        assert_eq!(parse_formats("json, HTML").unwrap(), vec!["json".to_string(), "html".to_string()]);
+        assert!(parse_formats("pdf").is_err());
+    }
+
+    #[test]
+    fn test_resolve_agent() {
+        let suite: EvalSuite = serde_json::from_value(serde_json::json!({
+            "name": "smoke",
+            "tests": [{ "input": "hi" }]
+        })).unwrap();
+        let options = EvalOptions::default();
+
+        let single = vec!["assistant".to_string()];
+        assert_eq!(resolve_agent(&options, &suite, None, &single).unwrap(), "assistant");
+
+        let many = vec!["assistant".to_string(), "writer".to_string()];
+        assert!(resolve_agent(&options, &suite, None, &many).is_err());
+        assert_eq!(resolve_agent(&options, &suite, Some("writer"), &many).unwrap(), "writer");
+        assert!(resolve_agent(&options, &suite, Some("missing"), &many).is_err());
+    }
+
+    #[test]
+    fn test_report_file_stem() {
+        assert_eq!(report_file_stem("smoke tests/v1"), "smoke-tests-v1");
+        assert_eq!(report_file_stem(""), "report");
+    }
+}
@@ -0,0 +1,726 @@
+use clap::Args;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::env;
+use std::sync::Arc;
+use std::fs;
+use colored::Colorize;
+use indicatif::{ProgressBar, ProgressStyle};
+use serde::{Serialize, Deserialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use lumosai_core::config::RagConfig;
+use lumosai_core::config::{http_client_builder_for, http_client_for};
+use lumosai_rag::chunking::TextChunker;
+use lumosai_rag::embedding::{EmbeddingProvider, OpenAIEmbeddingProvider};
+use lumosai_rag::types::ChunkingConfig;
+use lumosai_vector::prelude::*;
+
+use crate::commands::create::VECTOR_STORES;
+use crate::error::{CliResult, CliError};
+use crate::util::load_project_config;
+
+/// 默认的导入状态文件（相对项目目录）
+pub const DEFAULT_STATE_FILE: &str = ".lumos/ingest-state.json";
+
+/// 默认的OpenAI嵌入模型
+const DEFAULT_OPENAI_EMBEDDING_MODEL: &str = "text-embedding-3-small";
+
+/// 批量导入文档到向量数据库
+#[derive(Args, Debug)]
+pub struct IngestOptions {
+    /// 要导入的目录、文件或URL，为空时使用RAG配置中的 documents
+    pub sources: Vec<String>,
+
+    /// 包含URL列表的文件（每行一个）
+    #[arg(long)]
+    pub urls: Option<PathBuf>,
+
+    /// 项目目录
+    #[arg(long)]
+    pub project_dir: Option<PathBuf>,
+
+    /// 使用的RAG管道名称 (lumos.yaml 中的 rag_pipelines，顶层 rag 为 default)
+    #[arg(long, default_value = "default")]
+    pub pipeline: String,
+
+    /// 覆盖向量存储 (memory, qdrant, postgres, milvus)
+    #[arg(long)]
+    pub vector_store: Option<String>,
+
+    /// 覆盖索引名称
+    #[arg(long)]
+    pub index: Option<String>,
+
+    /// 覆盖分块大小
+    #[arg(long)]
+    pub chunk_size: Option<usize>,
+
+    /// 覆盖分块重叠
+    #[arg(long)]
+    pub chunk_overlap: Option<usize>,
+
+    /// 目录中要导入的文件扩展名，以逗号分隔
+    #[arg(long, default_value = "md,txt,rst,html")]
+    pub extensions: String,
+
+    /// 每批嵌入和写入的片段数
+    #[arg(long, default_value = "32")]
+    pub batch_size: usize,
+
+    /// 导入状态文件，用于中断后继续导入
+    #[arg(long)]
+    pub state_file: Option<PathBuf>,
+
+    /// 忽略已有的导入状态，重新导入所有文档
+    #[arg(long)]
+    pub restart: bool,
+}
+
+impl Default for IngestOptions {
+    fn default() -> Self {
+        Self {
+            sources: Vec::new(),
+            urls: None,
+            project_dir: None,
+            pipeline: "default".to_string(),
+            vector_store: None,
+            index: None,
+            chunk_size: None,
+            chunk_overlap: None,
+            extensions: "md,txt,rst,html".to_string(),
+            batch_size: 32,
+            state_file: None,
+            restart: false,
+        }
+    }
+}
+
+/// 合并项目配置和命令行参数后的导入管道
+#[derive(Debug, Clone, PartialEq)]
+pub struct IngestPipeline {
+    pub vector_store: String,
+    pub embeddings: String,
+    pub index: String,
+    pub chunk_size: usize,
+    pub chunk_overlap: usize,
+}
+
+impl IngestPipeline {
+    /// 从RAG配置解析管道，命令行参数优先
+    pub fn resolve(rag: Option<&RagConfig>, options: &IngestOptions) -> CliResult<Self> {
+        let defaults = ChunkingConfig::default();
+        let pipeline = Self {
+            vector_store: options.vector_store.clone()
+                .or_else(|| rag.and_then(|r| r.vector_store.clone()))
+                .unwrap_or_else(|| "memory".to_string()),
+            embeddings: rag.and_then(|r| r.embeddings.clone()).unwrap_or_else(|| "openai".to_string()),
+            index: options.index.clone()
+                .or_else(|| rag.and_then(|r| r.index_name.clone()))
+                .or_else(|| env::var("VECTOR_INDEX").ok())
+                .unwrap_or_else(|| "documents".to_string()),
+            chunk_size: options.chunk_size
+                .or_else(|| rag.and_then(|r| r.chunk_size))
+                .unwrap_or(defaults.chunk_size),
+            chunk_overlap: options.chunk_overlap
+                .or_else(|| rag.and_then(|r| r.chunk_overlap))
+                .unwrap_or(defaults.chunk_overlap),
+        };
+
+        if !VECTOR_STORES.contains(&pipeline.vector_store.as_str()) {
+            return Err(CliError::invalid_input_string(format!(
+                "不支持的向量存储: {} (可选: {})", pipeline.vector_store, VECTOR_STORES.join(", ")
+            )));
+        }
+        if pipeline.chunk_size == 0 || pipeline.chunk_overlap >= pipeline.chunk_size {
+            return Err(CliError::invalid_input_string(format!(
+                "无效的分块配置: chunk_size={}, chunk_overlap={}", pipeline.chunk_size, pipeline.chunk_overlap
+            )));
+        }
+        Ok(pipeline)
+    }
+
+    /// 影响导入结果的配置摘要，变化时需要重新导入
+    fn fingerprint(&self) -> String {
+        format!(
+            "{}|{}|{}|{}|{}",
+            self.vector_store, self.index, self.embeddings, self.chunk_size, self.chunk_overlap
+        )
+    }
+}
+
+/// 待导入的文档来源
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    File(PathBuf),
+    Url(String),
+}
+
+impl Source {
+    /// 来源在状态文件中的键
+    pub fn key(&self) -> String {
+        match self {
+            Source::File(path) => path.display().to_string(),
+            Source::Url(url) => url.clone(),
+        }
+    }
+
+    /// 读取来源的文本内容
+    async fn load(&self, client: &reqwest::Client) -> CliResult<String> {
+        match self {
+            Source::File(path) => {
+                let content = fs::read_to_string(path)
+                    .map_err(|e| CliError::io(&format!("读取文件失败 {}", path.display()), e))?;
+                let is_html = matches!(path.extension().and_then(|e| e.to_str()), Some("html") | Some("htm"));
+                Ok(if is_html { html_to_text(&content) } else { content })
+            }
+            Source::Url(url) => {
+                let response = client.get(url).send().await
+                    .map_err(|e| CliError::Other(format!("请求失败 {}: {}", url, e)))?;
+                if !response.status().is_success() {
+                    return Err(CliError::Other(format!("请求失败 {}: HTTP {}", url, response.status())));
+                }
+                let is_html = response.headers()
+                    .get(reqwest::header::CONTENT_TYPE)
+                    .and_then(|value| value.to_str().ok())
+                    .is_some_and(|value| value.contains("html"));
+                let content = response.text().await
+                    .map_err(|e| CliError::Other(format!("读取响应失败 {}: {}", url, e)))?;
+                Ok(if is_html { html_to_text(&content) } else { content })
+            }
+        }
+    }
+}
+
+/// 已导入来源的记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceState {
+    /// 内容的SHA-256摘要
+    pub hash: String,
+    /// 写入的片段ID
+    pub chunks: Vec<String>,
+    /// 导入时间
+    pub ingested_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 导入状态，每个来源导入完成后立即写入磁盘
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IngestState {
+    /// 导入管道摘要
+    pub pipeline: String,
+    /// 已导入的来源
+    pub sources: BTreeMap<String, SourceState>,
+}
+
+impl IngestState {
+    /// 加载状态文件，管道配置变化时从头开始
+    pub fn load(path: &Path, pipeline: &IngestPipeline) -> CliResult<Self> {
+        let fresh = Self {
+            pipeline: pipeline.fingerprint(),
+            sources: BTreeMap::new(),
+        };
+        if !path.exists() {
+            return Ok(fresh);
+        }
+
+        let content = fs::read_to_string(path).map_err(|e| CliError::io("读取导入状态失败", e))?;
+        let state: Self = serde_json::from_str(&content)?;
+        if state.pipeline != fresh.pipeline {
+            println!("{}", "导入管道配置已变化，将重新导入所有文档".bright_yellow());
+            return Ok(fresh);
+        }
+        Ok(state)
+    }
+
+    /// 写入状态文件
+    pub fn save(&self, path: &Path) -> CliResult<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| CliError::io("创建状态目录失败", e))?;
+        }
+        // 先写临时文件再重命名，避免中断时留下损坏的状态
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(self)?).map_err(|e| CliError::io("写入导入状态失败", e))?;
+        fs::rename(&tmp, path).map_err(|e| CliError::io("写入导入状态失败", e))?;
+        Ok(())
+    }
+
+    /// 来源内容是否已导入
+    pub fn is_current(&self, key: &str, hash: &str) -> bool {
+        self.sources.get(key).is_some_and(|source| source.hash == hash)
+    }
+}
+
+/// 嵌入模型
+enum Embedder {
+    OpenAi(OpenAIEmbeddingProvider),
+    #[cfg(feature = "fastembed")]
+    FastEmbed(lumosai_vector::fastembed::FastEmbedProvider),
+}
+
+impl Embedder {
+    /// 根据RAG配置中的 `embeddings` 创建嵌入模型，格式为 `openai[:模型]` 或 `fastembed`
+    async fn from_spec(spec: &str) -> CliResult<Self> {
+        let (kind, model) = match spec.split_once(':') {
+            Some((kind, model)) => (kind, Some(model)),
+            None => (spec, None),
+        };
+
+        match kind {
+            "openai" => {
+                let api_key = env::var("OPENAI_API_KEY").or_else(|_| env::var("LLM_API_KEY"))
+                    .map_err(|_| CliError::invalid_input("使用OpenAI嵌入需要设置 OPENAI_API_KEY 或 LLM_API_KEY"))?;
+                let mut provider = OpenAIEmbeddingProvider::new(
+                    api_key,
+                    model.unwrap_or(DEFAULT_OPENAI_EMBEDDING_MODEL).to_string(),
+                );
+                if let Ok(base_url) = env::var("OPENAI_BASE_URL") {
+                    provider = provider.with_base_url(base_url);
+                }
+                Ok(Embedder::OpenAi(provider))
+            }
+            #[cfg(feature = "fastembed")]
+            "fastembed" => {
+                let provider = lumosai_vector::fastembed::FastEmbedProvider::with_model(
+                    lumosai_vector::fastembed::FastEmbedModel::BGESmallENV15,
+                ).await.map_err(vector_error)?;
+                Ok(Embedder::FastEmbed(provider))
+            }
+            #[cfg(not(feature = "fastembed"))]
+            "fastembed" => Err(CliError::invalid_input("当前CLI未启用 fastembed 支持，请使用 --features fastembed 重新安装")),
+            other => Err(CliError::invalid_input_string(format!("不支持的嵌入模型: {} (可选: openai, fastembed)", other))),
+        }
+    }
+
+    async fn embed_batch(&self, texts: &[String]) -> CliResult<Vec<Vec<f32>>> {
+        match self {
+            Embedder::OpenAi(provider) => provider.embed_batch(texts).await
+                .map_err(|e| CliError::Other(format!("生成嵌入失败: {}", e))),
+            #[cfg(feature = "fastembed")]
+            Embedder::FastEmbed(provider) => provider.embed_batch(texts).await.map_err(vector_error),
+        }
+    }
+}
+
+/// 导入统计
+#[derive(Debug, Default)]
+struct IngestStats {
+    ingested: usize,
+    skipped: usize,
+    chunks: usize,
+    failed: Vec<(String, String)>,
+}
+
+/// 运行Ingest命令
+pub async fn run(options: IngestOptions) -> CliResult<()> {
+    let project_dir = match &options.project_dir {
+        Some(dir) => dir.clone(),
+        None => env::current_dir().map_err(|e| CliError::io("获取当前目录失败", e))?,
+    };
+
+    let config = load_project_config(&project_dir)?.map(|(_, config)| config);
+    let rag = match &config {
+        Some(config) => {
+            let rag = config.get_rag_pipeline(&options.pipeline);
+            if rag.is_none() && options.pipeline != "default" {
+                return Err(CliError::invalid_input_string(format!("项目配置中未声明RAG管道: {}", options.pipeline)));
+            }
+            rag
+        }
+        None => None,
+    };
+    let pipeline = IngestPipeline::resolve(rag, &options)?;
+
+    // 命令行未指定来源时使用配置中的文档列表
+    let mut inputs = options.sources.clone();
+    if let Some(urls) = &options.urls {
+        inputs.extend(read_url_list(urls)?);
+    }
+    if inputs.is_empty() {
+        inputs = rag.and_then(|r| r.documents.clone()).unwrap_or_default()
+            .into_iter()
+            .map(|doc| if is_url(&doc) { doc } else { project_dir.join(doc).display().to_string() })
+            .collect();
+    }
+    if inputs.is_empty() {
+        return Err(CliError::invalid_input("没有要导入的文档，请指定目录、文件或 --urls"));
+    }
+
+    let extensions = parse_extensions(&options.extensions);
+    let sources = collect_sources(&inputs, &extensions)?;
+
+    println!("{}", format!("RAG管道: {}", options.pipeline).bright_blue());
+    println!("{}", format!("向量存储: {}  索引: {}", pipeline.vector_store, pipeline.index).bright_blue());
+    println!("{}", format!("嵌入模型: {}  分块: {}/{}", pipeline.embeddings, pipeline.chunk_size, pipeline.chunk_overlap).bright_blue());
+    println!("{}", format!("待处理来源: {}", sources.len()).bright_blue());
+
+    let state_file = options.state_file.clone().unwrap_or_else(|| project_dir.join(DEFAULT_STATE_FILE));
+    let state = if options.restart {
+        IngestState { pipeline: pipeline.fingerprint(), ..IngestState::default() }
+    } else {
+        IngestState::load(&state_file, &pipeline)?
+    };
+
+    let embedder = Embedder::from_spec(&pipeline.embeddings).await?;
+    let ingester = Ingester {
+        pipeline: &pipeline,
+        embedder,
+        batch_size: options.batch_size.max(1),
+        state,
+        state_file,
+    };
+
+    let stats = match pipeline.vector_store.as_str() {
+        "memory" => {
+            println!("{}", "内存存储不会保留数据，仅用于验证导入管道".bright_yellow());
+            let storage = lumosai_vector::memory::MemoryVectorStorage::new().await.map_err(vector_error)?;
+            ingester.run(&storage, &sources).await?
+        }
+        #[cfg(feature = "qdrant")]
+        "qdrant" => {
+            let url = env::var("QDRANT_URL").unwrap_or_else(|_| "http://localhost:6334".to_string());
+            let storage = lumosai_vector::qdrant::QdrantVectorStorage::new(&url).await.map_err(vector_error)?;
+            ingester.run(&storage, &sources).await?
+        }
+        #[cfg(feature = "postgres")]
+        "postgres" => {
+            let url = env::var("DATABASE_URL")
+                .map_err(|_| CliError::invalid_input("使用PostgreSQL需要设置 DATABASE_URL"))?;
+            let storage = lumosai_vector::postgres::PostgresVectorStorage::new(&url).await.map_err(vector_error)?;
+            ingester.run(&storage, &sources).await?
+        }
+        #[cfg(feature = "milvus")]
+        "milvus" => {
+            let url = env::var("MILVUS_URL").unwrap_or_else(|_| "http://localhost:19530".to_string());
+            let config = lumosai_vector::milvus::MilvusConfig::new(&url);
+            let client_builder = Arc::new(move || http_client_builder_for(&url));
+            let storage = lumosai_vector::milvus::MilvusStorage::with_client_builder(config, client_builder)
+                .await
+                .map_err(vector_error)?;
+            ingester.run(&storage, &sources).await?
+        }
+        other => {
+            return Err(CliError::invalid_input_string(format!(
+                "当前CLI未启用 {} 支持，请使用 --features {} 重新安装", other, other
+            )));
+        }
+    };
+
+    println!(
+        "{}",
+        format!(
+            "导入完成: {} 个来源，{} 个片段，跳过 {} 个未变化的来源",
+            stats.ingested, stats.chunks, stats.skipped,
+        ).bright_green()
+    );
+
+    if !stats.failed.is_empty() {
+        for (source, error) in &stats.failed {
+            println!("{} {} {}", "✗".bright_red(), source, error.dimmed());
+        }
+        return Err(CliError::Other(format!(
+            "{} 个来源导入失败，修复后重新运行将从中断处继续", stats.failed.len()
+        )));
+    }
+
+    Ok(())
+}
+
+/// 执行导入并维护状态文件
+struct Ingester<'a> {
+    pipeline: &'a IngestPipeline,
+    embedder: Embedder,
+    batch_size: usize,
+    state: IngestState,
+    state_file: PathBuf,
+}
+
+impl Ingester<'_> {
+    async fn run<S: VectorStorage>(mut self, storage: &S, sources: &[Source]) -> CliResult<IngestStats> {
+        let client = http_client_for("");
+        let chunker = TextChunker::new(ChunkingConfig {
+            chunk_size: self.pipeline.chunk_size,
+            chunk_overlap: self.pipeline.chunk_overlap,
+            ..ChunkingConfig::default()
+        });
+
+        let progress = ProgressBar::new(sources.len() as u64);
+        progress.set_style(
+            ProgressStyle::default_bar()
+                .template("{spinner:.green} [{bar:40.cyan/blue}] {pos}/{len} {msg}")
+                .unwrap_or_else(|_| ProgressStyle::default_bar())
+                .progress_chars("#>-"),
+        );
+
+        let mut stats = IngestStats::default();
+        let mut index_ready = storage.list_indexes().await.map_err(vector_error)?.contains(&self.pipeline.index);
+
+        for source in sources {
+            let key = source.key();
+            progress.set_message(key.clone());
+
+            let result = async {
+                let content = source.load(&client).await?;
+                let hash = format!("{:x}", Sha256::digest(content.as_bytes()));
+                if self.state.is_current(&key, &hash) {
+                    return Ok(None);
+                }
+
+                let chunks: Vec<String> = chunker.chunk_text(&content)
+                    .map_err(|e| CliError::Other(format!("分块失败: {}", e)))?
+                    .into_iter()
+                    .filter(|chunk| !chunk.trim().is_empty())
+                    .collect();
+
+                let mut ids = Vec::with_capacity(chunks.len());
+                for (batch_index, batch) in chunks.chunks(self.batch_size).enumerate() {
+                    let embeddings = self.embedder.embed_batch(batch).await?;
+
+                    if !index_ready {
+                        let dimension = embeddings.first().map(Vec::len).unwrap_or_default();
+                        storage.create_index(
+                            IndexConfig::new(&self.pipeline.index, dimension).with_metric(SimilarityMetric::Cosine)
+                        ).await.map_err(vector_error)?;
+                        index_ready = true;
+                    }
+
+                    let documents: Vec<Document> = batch.iter()
+                        .zip(embeddings)
+                        .enumerate()
+                        .map(|(i, (chunk, embedding))| {
+                            let chunk_index = batch_index * self.batch_size + i;
+                            let id = chunk_id(&key, chunk_index);
+                            ids.push(id.clone());
+                            Document::new(id, chunk.clone())
+                                .with_embedding(embedding)
+                                .with_metadata("source", key.clone())
+                                .with_metadata("chunk_index", chunk_index as i64)
+                        })
+                        .collect();
+                    storage.upsert_documents(&self.pipeline.index, documents).await.map_err(vector_error)?;
+                }
+
+                // 文档变短时删除多余的旧片段
+                let stale: Vec<String> = self.state.sources.get(&key)
+                    .map(|previous| previous.chunks.iter().filter(|id| !ids.contains(id)).cloned().collect())
+                    .unwrap_or_default();
+                if !stale.is_empty() {
+                    storage.delete_documents(&self.pipeline.index, stale).await.map_err(vector_error)?;
+                }
+
+                Ok::<_, CliError>(Some(SourceState { hash, chunks: ids, ingested_at: chrono::Utc::now() }))
+            }.await;
+
+            match result {
+                Ok(Some(source_state)) => {
+                    stats.ingested += 1;
+                    stats.chunks += source_state.chunks.len();
+                    self.state.sources.insert(key, source_state);
+                    self.state.save(&self.state_file)?;
+                }
+                Ok(None) => stats.skipped += 1,
+                Err(e) => {
+                    progress.println(format!("{} {}: {}", "导入失败".bright_red(), key, e));
+                    stats.failed.push((key, e.to_string()));
+                }
+            }
+            progress.inc(1);
+        }
+
+        progress.finish_and_clear();
+        Ok(stats)
+    }
+}
+
+/// 片段ID：由来源和序号生成的稳定UUID，重复导入时覆盖旧片段
+pub fn chunk_id(source: &str, index: usize) -> String {
+    Uuid::new_v5(&Uuid::NAMESPACE_URL, format!("{}#{}", source, index).as_bytes()).to_string()
+}
+
+fn vector_error(e: impl std::fmt::Display) -> CliError {
+    CliError::Other(format!("向量存储错误: {}", e))
+}
+
+fn is_url(input: &str) -> bool {
+    input.starts_with("http://") || input.starts_with("https://")
+}
+
+fn parse_extensions(extensions: &str) -> Vec<String> {
+    extensions
+        .split(',')
+        .map(|e| e.trim().trim_start_matches('.').to_lowercase())
+        .filter(|e| !e.is_empty())
+        .collect()
+}
+
+/// 读取URL列表文件，忽略空行和 `#` 注释
+fn read_url_list(path: &Path) -> CliResult<Vec<String>> {
+    let content = fs::read_to_string(path).map_err(|e| CliError::io("读取URL列表失败", e))?;
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect())
+}
+
+/// 展开输入为来源列表，目录按扩展名递归查找文件
+pub fn collect_sources(inputs: &[String], extensions: &[String]) -> CliResult<Vec<Source>> {
+    let mut sources = Vec::new();
+    for input in inputs {
+        if is_url(input) {
+            sources.push(Source::Url(input.clone()));
+            continue;
+        }
+
+        let path = PathBuf::from(input);
+        if path.is_dir() {
+            let mut files = Vec::new();
+            walk_dir(&path, extensions, &mut files)?;
+            files.sort();
+            sources.extend(files.into_iter().map(Source::File));
+        } else if path.is_file() {
+            sources.push(Source::File(path));
+        } else {
+            return Err(CliError::path_not_found(input.clone(), "导入来源不存在"));
+        }
+    }
+    sources.dedup();
+    Ok(sources)
+}
+
+fn walk_dir(dir: &Path, extensions: &[String], files: &mut Vec<PathBuf>) -> CliResult<()> {
+    for entry in fs::read_dir(dir).map_err(|e| CliError::io(&format!("读取目录失败 {}", dir.display()), e))? {
+        let path = entry.map_err(|e| CliError::io("读取目录失败", e))?.path();
+        let hidden = path.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with('.'));
+        if hidden {
+            continue;
+        }
+        if path.is_dir() {
+            walk_dir(&path, extensions, files)?;
+        } else if path.extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| extensions.contains(&e.to_lowercase()))
+        {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// 将HTML转换为纯文本：去掉脚本、样式和标签，保留段落换行
+pub fn html_to_text(html: &str) -> String {
+    let without_blocks = regex::Regex::new(r"(?is)<(script|style|noscript)[^>]*>.*?</(script|style|noscript)>")
+        .map(|re| re.replace_all(html, "").into_owned())
+        .unwrap_or_else(|_| html.to_string());
+    let with_breaks = regex::Regex::new(r"(?i)</?(p|div|br|li|h[1-6]|tr|section|article)[^>]*>")
+        .map(|re| re.replace_all(&without_blocks, "\n").into_owned())
+        .unwrap_or(without_blocks);
+    let text = regex::Regex::new(r"<[^>]*>")
+        .map(|re| re.replace_all(&with_breaks, "").into_owned())
+        .unwrap_or(with_breaks)
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+
+    text.lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn pipeline() -> IngestPipeline {
+        IngestPipeline::resolve(None, &IngestOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn test_resolve_pipeline() {
+        let rag = RagConfig {
+            vector_store: Some("qdrant".to_string()),
+            embeddings: Some("openai:text-embedding-3-large".to_string()),
+            chunk_size: Some(500),
+            chunk_overlap: Some(50),
+            documents: None,
+            index_name: Some("docs".to_string()),
+        };
+        let options = IngestOptions { chunk_size: Some(800), ..IngestOptions::default() };
+        let pipeline = IngestPipeline::resolve(Some(&rag), &options).unwrap();
+        assert_eq!(pipeline.vector_store, "qdrant");
+        assert_eq!(pipeline.index, "docs");
+        assert_eq!(pipeline.chunk_size, 800);
+        assert_eq!(pipeline.chunk_overlap, 50);
+
+        let options = IngestOptions { vector_store: Some("redis".to_string()), ..IngestOptions::default() };
+        assert!(IngestPipeline::resolve(Some(&rag), &options).is_err());
+
+        let options = IngestOptions { chunk_overlap: Some(500), ..IngestOptions::default() };
+        assert!(IngestPipeline::resolve(Some(&rag), &options).is_err());
+    }
+
+    #[test]
+    fn test_collect_sources() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("nested")).unwrap();
+        fs::create_dir_all(dir.path().join(".git")).unwrap();
+        fs::write(dir.path().join("a.md"), "a").unwrap();
+        fs::write(dir.path().join("nested").join("b.TXT"), "b").unwrap();
+        fs::write(dir.path().join("image.png"), "").unwrap();
+        fs::write(dir.path().join(".git").join("c.md"), "c").unwrap();
+
+        let inputs = vec![dir.path().display().to_string(), "https://example.com/doc".to_string()];
+        let sources = collect_sources(&inputs, &parse_extensions("md, .txt")).unwrap();
+        assert_eq!(sources, vec![
+            Source::File(dir.path().join("a.md")),
+            Source::File(dir.path().join("nested").join("b.TXT")),
+            Source::Url("https://example.com/doc".to_string()),
+        ]);
+
+        assert!(collect_sources(&["missing-dir".to_string()], &[]).is_err());
+    }
+
+    #[test]
+    fn test_state_resume() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("state").join("ingest.json");
+        let pipeline = pipeline();
+
+        let mut state = IngestState::load(&path, &pipeline).unwrap();
+        state.sources.insert("a.md".to_string(), SourceState {
+            hash: "abc".to_string(),
+            chunks: vec![chunk_id("a.md", 0)],
+            ingested_at: chrono::Utc::now(),
+        });
+        state.save(&path).unwrap();
+
+        let state = IngestState::load(&path, &pipeline).unwrap();
+        assert!(state.is_current("a.md", "abc"));
+        assert!(!state.is_current("a.md", "def"));
+
+        // 分块配置变化后需要重新导入
+        let changed = IngestPipeline { chunk_size: 2000, ..pipeline };
+        let state = IngestState::load(&path, &changed).unwrap();
+        assert!(state.sources.is_empty());
+    }
+
+    #[test]
+    fn test_chunk_id_is_stable_uuid() {
+        assert_eq!(chunk_id("a.md", 1), chunk_id("a.md", 1));
+        assert_ne!(chunk_id("a.md", 1), chunk_id("a.md", 2));
+        assert!(Uuid::parse_str(&chunk_id("a.md", 1)).is_ok());
+    }
+
+    #[test]
+    fn test_html_to_text() {
+        let html = "<html><head><style>p { color: red }</style></head><body><h1>Title</h1><p>Hello&nbsp;<b>world</b> &amp; more</p><script>alert(1)</script></body></html>";
+        assert_eq!(html_to_text(html), "Title\nHello world & more");
+    }
+}
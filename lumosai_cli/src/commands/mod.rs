@@ -8,4 +8,7 @@ pub mod playground;
 pub mod api;
 pub mod create;
 pub mod visualize;
-pub mod monitoring; 
\ No newline at end of file
+pub mod monitoring;
+pub mod eval;
+pub mod ingest;
+pub mod bench;
\ No newline at end of file
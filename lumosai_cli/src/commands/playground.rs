@@ -5,6 +5,8 @@ use colored::Colorize;
 
 use crate::error::{CliResult, CliError};
 use crate::server::ui_server;
+use crate::tui;
+use crate::util::load_project_config;
 
 /// 运行Lumosai交互式测试环境
 #[derive(Args, Debug)]
@@ -28,6 +30,14 @@ pub struct PlaygroundOptions {
     /// 自定义API URL
     #[arg(long)]
     api_url: Option<String>,
+
+    /// 使用Web界面代替终端界面
+    #[arg(long)]
+    web: bool,
+
+    /// 覆盖代理使用的模型
+    #[arg(long)]
+    model: Option<String>,
 }
 
 impl Default for PlaygroundOptions {
@@ -38,6 +48,8 @@ impl Default for PlaygroundOptions {
             agent: None,
             no_save_history: false,
             api_url: None,
+            web: false,
+            model: None,
         }
     }
 }
@@ -58,6 +70,15 @@ pub async fn run(options: PlaygroundOptions) -> CliResult<()> {
         ));
     }
 
+    // 默认使用终端界面，直接与项目配置中声明的代理对话
+    if !options.web {
+        let Some((config_path, config)) = load_project_config(&project_dir)? else {
+            return Err(CliError::invalid_input("未找到项目配置文件 (lumos.yaml)，使用 --web 启动Web版Playground"));
+        };
+        println!("{}", format!("项目配置: {}", config_path.display()).bright_blue());
+        return tui::run_playground(&project_dir, config, options.agent, options.model).await;
+    }
+
     println!("{}", "启动 Lumosai 交互式测试环境...".bright_blue());
     println!("{}", format!("项目目录: {}", project_dir.display()).bright_blue());
     println!("{}", format!("端口: {}", options.port).bright_blue());
@@ -102,6 +123,8 @@ mod tests {
         assert_eq!(options.agent, None);
         assert_eq!(options.no_save_history, false);
         assert_eq!(options.api_url, None);
+        assert!(!options.web);
+        assert_eq!(options.model, None);
     }
 
     #[test]
@@ -127,6 +150,8 @@ mod tests {
             agent: Some("test-agent".to_string()),
             no_save_history: true,
             api_url: Some("http://localhost:8000".to_string()),
+            web: true,
+            model: Some("gpt-4".to_string()),
         };
         
         assert_eq!(options.port, 5000);
@@ -4,6 +4,7 @@ use std::env;
 use std::fs;
 use colored::Colorize;
 use tokio::process::Command;
+use lumosai_network::TopologySnapshot;
 
 use crate::error::{CliResult, CliError};
 use crate::util::{is_lumos_project, ensure_dir_exists};
@@ -23,11 +24,15 @@ pub struct VisualizeOptions {
     #[arg(long)]
     workflow: Option<String>,
     
+    /// 运行中的API服务器地址，可视化其Agent网络拓扑（如 http://127.0.0.1:3001）
+    #[arg(long)]
+    network: Option<String>,
+    
     /// 输出文件路径
     #[arg(long)]
     output: Option<PathBuf>,
     
-    /// 可视化格式 (png, svg, pdf, html)
+    /// 可视化格式 (png, svg, pdf, html；可视化Agent网络时还支持json)
     #[arg(long, default_value = "svg")]
     format: String,
     
@@ -46,6 +51,7 @@ impl Default for VisualizeOptions {
             project_dir: None,
             agent: None,
             workflow: None,
+            network: None,
             output: None,
             format: "svg".to_string(),
             include_performance: false,
@@ -75,13 +81,14 @@ pub async fn run(options: VisualizeOptions) -> CliResult<()> {
         println!("{}", "警告: 当前目录不是标准的Lumosai项目目录结构".bright_yellow());
     }
 
-    // 检查代理和工作流参数
-    if options.agent.is_none() && options.workflow.is_none() {
-        return Err(CliError::Other("请指定要可视化的代理或工作流".to_string()));
+    // 检查代理、工作流和网络参数
+    if options.agent.is_none() && options.workflow.is_none() && options.network.is_none() {
+        return Err(CliError::Other("请指定要可视化的代理、工作流或Agent网络".to_string()));
     }
 
-    // 确定输出格式
-    let format = validate_format(&options.format)?;
+    // 确定输出格式，json只用于单独可视化Agent网络
+    let allow_json = options.agent.is_none() && options.workflow.is_none();
+    let format = validate_format(&options.format, allow_json)?;
     
     // 确定输出文件路径
     let output_path = determine_output_path(&options, &project_dir, &format)?;
@@ -117,6 +124,18 @@ pub async fn run(options: VisualizeOptions) -> CliResult<()> {
         ).await?;
     }
     
+    if let Some(url) = &options.network {
+        println!("{}", format!("Agent网络: {}", url).bright_blue());
+        
+        // 生成Agent网络可视化
+        generate_network_visualization(
+            url,
+            &output_path,
+            &format,
+            options.interactive,
+        ).await?;
+    }
+    
     println!("{}", format!("可视化图表已生成: {}", output_path.display()).bright_green());
     
     // 如果是交互式模式且输出格式是html，尝试在浏览器中打开
@@ -130,16 +149,17 @@ pub async fn run(options: VisualizeOptions) -> CliResult<()> {
 }
 
 /// 验证并返回有效的格式
-fn validate_format(format: &str) -> CliResult<String> {
+fn validate_format(format: &str, allow_json: bool) -> CliResult<String> {
     let valid_formats = ["png", "svg", "pdf", "html"];
     let format = format.to_lowercase();
     
-    if valid_formats.contains(&format.as_str()) {
+    if valid_formats.contains(&format.as_str()) || (allow_json && format == "json") {
         Ok(format)
     } else {
+        let supported = if allow_json { "png, svg, pdf, html, json" } else { "png, svg, pdf, html" };
         Err(CliError::other(format!(
-            "不支持的格式: {}。支持的格式: png, svg, pdf, html",
-            format
+            "不支持的格式: {}。支持的格式: {}",
+            format, supported
         )))
     }
 }
@@ -163,6 +183,8 @@ fn determine_output_path(
         format!("agent-{}.{}", agent, format)
     } else if let Some(workflow) = &options.workflow {
         format!("workflow-{}.{}", workflow, format)
+    } else if options.network.is_some() {
+        format!("network.{}", format)
     } else {
         format!("lumosai-visualization.{}", format)
     };
@@ -236,6 +258,58 @@ async fn generate_workflow_visualization(
     Ok(())
 }
 
+/// 生成Agent网络可视化
+async fn generate_network_visualization(
+    url: &str,
+    output_path: &Path,
+    format: &str,
+    interactive: bool,
+) -> CliResult<()> {
+    // 1. 从API服务器获取拓扑快照
+    let snapshot = fetch_network_topology(url).await?;
+    println!("{}", format!(
+        "节点: {} (健康 {}), 边: {}, 消息速率: {:.2}/s, 死信: {}",
+        snapshot.summary.node_count,
+        snapshot.summary.healthy_nodes,
+        snapshot.summary.edge_count,
+        snapshot.summary.messages_per_second,
+        snapshot.summary.dead_letters,
+    ).bright_blue());
+    
+    if let Some(parent) = output_path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        ensure_dir_exists(parent)?;
+    }
+    
+    // json格式直接保存快照
+    if format == "json" {
+        let json = serde_json::to_string_pretty(&snapshot)
+            .map_err(|e| CliError::other(format!("序列化网络拓扑失败: {}", e)))?;
+        fs::write(output_path, json).map_err(|e| CliError::io_error(e, output_path))?;
+        return Ok(());
+    }
+    
+    // 2. 生成DOT文件并转换为指定格式
+    let dot_file = output_path.with_extension("dot");
+    fs::write(&dot_file, snapshot.to_dot()).map_err(|e| CliError::io_error(e, &dot_file))?;
+    convert_dot_to_format(&dot_file, output_path, format, interactive).await?;
+    
+    // 3. 清理临时DOT文件
+    let _ = fs::remove_file(&dot_file);
+    
+    Ok(())
+}
+
+/// 获取API服务器上的Agent网络拓扑快照
+async fn fetch_network_topology(url: &str) -> CliResult<TopologySnapshot> {
+    let endpoint = format!("{}/api/network/topology", url.trim_end_matches('/'));
+    let snapshot = reqwest::get(&endpoint)
+        .await?
+        .error_for_status()?
+        .json::<TopologySnapshot>()
+        .await?;
+    Ok(snapshot)
+}
+
 /// 生成代理的DOT文件
 async fn generate_agent_dot_file(
     project_dir: &Path,
@@ -644,17 +718,22 @@ mod tests {
         assert_eq!(options.interactive, false);
         assert_eq!(options.agent, None);
         assert_eq!(options.workflow, None);
+        assert_eq!(options.network, None);
     }
     
     #[test]
     fn test_validate_format() {
-        assert!(validate_format("png").is_ok());
-        assert!(validate_format("svg").is_ok());
-        assert!(validate_format("pdf").is_ok());
-        assert!(validate_format("html").is_ok());
-        assert!(validate_format("PNG").is_ok()); // 大写也应该有效
+        assert!(validate_format("png", false).is_ok());
+        assert!(validate_format("svg", false).is_ok());
+        assert!(validate_format("pdf", false).is_ok());
+        assert!(validate_format("html", false).is_ok());
+        assert!(validate_format("PNG", false).is_ok()); // 大写也应该有效
+        
+        assert!(validate_format("unknown", false).is_err());
         
-        assert!(validate_format("unknown").is_err());
+        // json只用于Agent网络
+        assert!(validate_format("json", false).is_err());
+        assert!(validate_format("json", true).is_ok());
     }
     
     #[test]
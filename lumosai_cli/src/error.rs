@@ -209,4 +209,9 @@ impl From<reqwest::Error> for CliError {
     fn from(err: reqwest::Error) -> Self {
         CliError::Other(format!("HTTP请求错误: {}", err))
     }
-} 
\ No newline at end of file
+} 
+impl From<lumosai_core::Error> for CliError {
+    fn from(err: lumosai_core::Error) -> Self {
+        CliError::Other(format!("Lumosai错误: {}", err))
+    }
+}
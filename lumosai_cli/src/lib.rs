@@ -6,6 +6,7 @@ pub mod error;
 pub mod util;
 pub mod server;
 pub mod template;
+pub mod tui;
 
 /// Lumosai CLI工具主要版本号
 pub const LUMOSAI_CLI_VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -51,6 +52,15 @@ pub enum Commands {
 
     /// 启动监控服务器
     Monitoring(commands::monitoring::MonitoringOptions),
+
+    /// 运行评估套件
+    Eval(commands::eval::EvalOptions),
+
+    /// 批量导入文档到向量数据库
+    Ingest(commands::ingest::IngestOptions),
+
+    /// 对代理进行负载测试
+    Bench(commands::bench::BenchOptions),
 }
 
 #[derive(Args, Debug)]
@@ -78,6 +88,10 @@ pub struct CreateArgs {
     /// 添加示例代码
     #[arg(long)]
     pub example: bool,
+
+    /// RAG使用的向量数据库 (qdrant, postgres, milvus, memory)
+    #[arg(long)]
+    pub vector_store: Option<String>,
 }
 
 #[derive(Args, Debug)]
@@ -116,6 +130,14 @@ pub struct ApiArgs {
     /// 代理列表，以逗号分隔
     #[arg(long)]
     pub agents: Option<String>,
+
+    /// 生成客户端SDK的语言，以逗号分隔 (typescript,python)
+    #[arg(long)]
+    pub sdk: Option<String>,
+
+    /// SDK输出目录，默认为项目下的 sdk/
+    #[arg(long)]
+    pub sdk_output: Option<PathBuf>,
 }
 
 /// 获取完整的版本信息
@@ -29,6 +29,7 @@ async fn main() -> CliResult<()> {
                 args.llm_api_key,
                 args.name.or_else(|| args.project_dir.and_then(|p| p.file_name().and_then(|n| n.to_str().map(|s| s.to_string())))),
                 args.example,
+                args.vector_store,
             ).await
         },
         Commands::Dev(options) => {
@@ -52,10 +53,17 @@ async fn main() -> CliResult<()> {
                 s.split(',').map(|s| s.trim().to_string()).collect()
             });
             
-            commands::api::run(
+            let sdk_languages = match &args.sdk {
+                Some(sdk) => commands::api::codegen::SdkLanguage::parse_list(sdk)?,
+                None => Vec::new(),
+            };
+            
+            commands::api::run_with_sdk(
                 args.project_dir,
                 args.output,
                 agents,
+                &sdk_languages,
+                args.sdk_output,
             ).await
         },
         Commands::Visualize(options) => {
@@ -64,6 +72,23 @@ async fn main() -> CliResult<()> {
         Commands::Monitoring(options) => {
             commands::monitoring::run(options).await
         },
+        Commands::Eval(options) => {
+            // 作为CI门禁使用：阈值未通过与运行错误使用不同的退出码
+            match commands::eval::run(options).await {
+                Ok(true) => Ok(()),
+                Ok(false) => std::process::exit(commands::eval::EXIT_THRESHOLD_FAILED),
+                Err(e) => {
+                    eprintln!("{}", format!("评估失败: {}", e).bright_red());
+                    std::process::exit(commands::eval::EXIT_ERROR);
+                }
+            }
+        },
+        Commands::Ingest(options) => {
+            commands::ingest::run(options).await
+        },
+        Commands::Bench(options) => {
+            commands::bench::run(options).await
+        },
     }
 }
 
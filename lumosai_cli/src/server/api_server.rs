@@ -9,6 +9,7 @@ use colored::Colorize;
 
 use crate::error::{CliResult, CliError};
 use crate::util::get_available_port;
+use crate::server::dev_reload::{DevEventHub, dev_websocket_handler};
 
 // API服务器配置
 #[derive(Debug, Clone)]
@@ -155,6 +156,16 @@ pub fn start_server(
     port: u16,
     project_dir: PathBuf,
     api_module_path: Option<PathBuf>,
+) -> std::pin::Pin<Box<dyn std::future::Future<Output = CliResult<()>> + Send>> {
+    start_server_with_events(port, project_dir, api_module_path, None)
+}
+
+/// 启动API服务器，并在提供事件中心时通过 `/ws/dev` 推送热重载事件
+pub fn start_server_with_events(
+    port: u16,
+    project_dir: PathBuf,
+    api_module_path: Option<PathBuf>,
+    dev_events: Option<DevEventHub>,
 ) -> std::pin::Pin<Box<dyn std::future::Future<Output = CliResult<()>> + Send>> {
     Box::pin(async move {
     // 检查端口是否可用
@@ -162,7 +173,7 @@ pub fn start_server(
         let new_port = get_available_port(port).unwrap_or(port + 1);
         println!("{}", format!("端口 {} 已被占用，使用端口 {}", port, new_port).bright_yellow());
         
-        return start_server(new_port, project_dir, api_module_path).await;
+        return start_server_with_events(new_port, project_dir, api_module_path, dev_events).await;
     }
     
     // 创建配置
@@ -200,6 +211,12 @@ pub fn start_server(
             .app_data(config_data.clone())
             .service(web::resource("/api").route(web::get().to(api_info)))
             .service(web::resource("/api/info").route(web::get().to(api_info)))
+            .configure(|cfg| {
+                if let Some(hub) = &dev_events {
+                    cfg.app_data(web::Data::new(hub.clone()))
+                        .service(web::resource("/ws/dev").route(web::get().to(dev_websocket_handler)));
+                }
+            })
     })
     .bind(config.get_bind_address())
     .map_err(|e| CliError::io_string(format!("无法绑定到端口: {}", config.port), e))?
@@ -0,0 +1,190 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use actix::{Actor, ActorContext, AsyncContext, StreamHandler};
+use actix_web::{web, HttpRequest, Responder, Result as ActixResult};
+use actix_web_actors::ws;
+use serde::{Serialize, Deserialize};
+use tokio::sync::broadcast;
+
+/// 开发模式热重载事件，通过WebSocket推送给Playground/UI会话
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DevEvent {
+    /// 已连接
+    Connected { timestamp: u64 },
+    /// 检测到配置或提示词文件变更
+    FilesChanged { paths: Vec<String>, timestamp: u64 },
+    /// 代理已重建
+    Reloaded {
+        added: Vec<String>,
+        updated: Vec<String>,
+        removed: Vec<String>,
+        timestamp: u64,
+    },
+    /// 重载失败，仍在使用旧的代理
+    ReloadFailed { error: String, timestamp: u64 },
+}
+
+impl actix::Message for DevEvent {
+    type Result = ();
+}
+
+/// 当前时间戳（毫秒）
+pub fn now_millis() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+/// 热重载事件中心
+#[derive(Debug, Clone)]
+pub struct DevEventHub {
+    sender: broadcast::Sender<DevEvent>,
+}
+
+impl DevEventHub {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(64);
+        Self { sender }
+    }
+
+    /// 发布事件，没有订阅者时直接丢弃
+    pub fn publish(&self, event: DevEvent) {
+        let _ = self.sender.send(event);
+    }
+
+    /// 订阅事件
+    pub fn subscribe(&self) -> broadcast::Receiver<DevEvent> {
+        self.sender.subscribe()
+    }
+
+    /// 当前订阅者数量
+    pub fn subscriber_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+}
+
+impl Default for DevEventHub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 热重载WebSocket会话
+pub struct DevReloadSocket {
+    hub: DevEventHub,
+    last_heartbeat: std::time::Instant,
+}
+
+impl DevReloadSocket {
+    pub fn new(hub: DevEventHub) -> Self {
+        Self {
+            hub,
+            last_heartbeat: std::time::Instant::now(),
+        }
+    }
+}
+
+impl Actor for DevReloadSocket {
+    type Context = ws::WebsocketContext<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        // 心跳检测
+        ctx.run_interval(Duration::from_secs(30), |act, ctx| {
+            if act.last_heartbeat.elapsed() > Duration::from_secs(60) {
+                ctx.stop();
+                return;
+            }
+            ctx.ping(b"");
+        });
+
+        // 将事件中心的消息转发给当前会话
+        let addr = ctx.address();
+        let mut receiver = self.hub.subscribe();
+        actix::spawn(async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => {
+                        if !addr.connected() {
+                            break;
+                        }
+                        addr.do_send(event);
+                    }
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+
+        if let Ok(text) = serde_json::to_string(&DevEvent::Connected { timestamp: now_millis() }) {
+            ctx.text(text);
+        }
+    }
+}
+
+impl actix::Handler<DevEvent> for DevReloadSocket {
+    type Result = ();
+
+    fn handle(&mut self, event: DevEvent, ctx: &mut Self::Context) {
+        if let Ok(text) = serde_json::to_string(&event) {
+            ctx.text(text);
+        }
+    }
+}
+
+impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for DevReloadSocket {
+    fn handle(&mut self, msg: Result<ws::Message, ws::ProtocolError>, ctx: &mut Self::Context) {
+        match msg {
+            Ok(ws::Message::Ping(msg)) => {
+                self.last_heartbeat = std::time::Instant::now();
+                ctx.pong(&msg);
+            }
+            Ok(ws::Message::Pong(_)) | Ok(ws::Message::Text(_)) => {
+                self.last_heartbeat = std::time::Instant::now();
+            }
+            Ok(ws::Message::Close(reason)) => {
+                ctx.close(reason);
+                ctx.stop();
+            }
+            Ok(_) => {}
+            Err(_) => ctx.stop(),
+        }
+    }
+}
+
+/// 热重载WebSocket处理器 (`/ws/dev`)
+pub async fn dev_websocket_handler(
+    req: HttpRequest,
+    stream: web::Payload,
+    hub: web::Data<DevEventHub>,
+) -> ActixResult<impl Responder> {
+    ws::start(DevReloadSocket::new(hub.get_ref().clone()), &req, stream)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_serialization() {
+        let event = DevEvent::Reloaded {
+            added: vec![],
+            updated: vec!["assistant".to_string()],
+            removed: vec![],
+            timestamp: 1,
+        };
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], "reloaded");
+        assert_eq!(json["updated"][0], "assistant");
+    }
+
+    #[tokio::test]
+    async fn test_hub_broadcast() {
+        let hub = DevEventHub::new();
+        let mut receiver = hub.subscribe();
+        assert_eq!(hub.subscriber_count(), 1);
+
+        hub.publish(DevEvent::ReloadFailed { error: "bad yaml".to_string(), timestamp: 1 });
+        match receiver.recv().await.unwrap() {
+            DevEvent::ReloadFailed { error, .. } => assert_eq!(error, "bad yaml"),
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+}
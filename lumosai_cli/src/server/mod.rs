@@ -1,6 +1,7 @@
 pub mod ui_server;
 pub mod api_server;
 pub mod monitoring_server;
+pub mod dev_reload;
 
 use crate::error::CliResult;
 use colored::Colorize;
@@ -0,0 +1,327 @@
+//! 终端交互界面
+//!
+//! `lumos playground` 的TUI实现：与项目中声明的代理对话，
+//! 实时查看工具调用和检索片段，切换模型，并将对话保存为评估用例。
+
+pub mod state;
+mod view;
+
+use std::io::{self, Stdout};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use ratatui::backend::CrosstermBackend;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::crossterm::execute;
+use ratatui::crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
+use ratatui::Terminal;
+use tokio::sync::mpsc;
+
+use lumosai_core::agent::types::{AgentGenerateOptions, AgentGenerateResult};
+use lumosai_core::app::LumosApp;
+use lumosai_core::config::YamlConfig;
+use lumosai_core::llm::types::{assistant_message, system_message, user_message};
+use lumosai_evals::EvalTestCase;
+
+use crate::error::{CliError, CliResult};
+use state::{trace_from_result, trace_from_retrieval, PlaygroundCommand, PlaygroundState, TraceEntry, HELP_TEXT};
+
+/// 默认的评估用例保存路径（相对项目目录）
+pub const DEFAULT_TEST_CASES_PATH: &str = "evals/playground.jsonl";
+
+/// 每次检索返回的片段数量
+const RETRIEVAL_TOP_K: usize = 3;
+
+/// 后台生成任务的结果
+struct TurnOutcome {
+    index: usize,
+    result: Result<AgentGenerateResult, String>,
+    retrievals: Vec<TraceEntry>,
+    elapsed_ms: u64,
+}
+
+/// 终端状态守卫，退出时恢复终端
+struct TerminalGuard {
+    terminal: Terminal<CrosstermBackend<Stdout>>,
+}
+
+impl TerminalGuard {
+    fn new() -> CliResult<Self> {
+        enable_raw_mode().map_err(|e| CliError::io("无法进入终端原始模式", e))?;
+        let mut stdout = io::stdout();
+        execute!(stdout, EnterAlternateScreen).map_err(|e| CliError::io("无法切换终端屏幕", e))?;
+        let terminal = Terminal::new(CrosstermBackend::new(stdout))
+            .map_err(|e| CliError::io("无法初始化终端", e))?;
+        Ok(Self { terminal })
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+        let _ = execute!(self.terminal.backend_mut(), LeaveAlternateScreen);
+        let _ = self.terminal.show_cursor();
+    }
+}
+
+/// Playground会话
+struct PlaygroundSession {
+    project_dir: PathBuf,
+    config: YamlConfig,
+    app: LumosApp,
+    state: PlaygroundState,
+    outcomes_tx: mpsc::UnboundedSender<TurnOutcome>,
+}
+
+/// 运行终端Playground
+pub async fn run_playground(
+    project_dir: &Path,
+    mut config: YamlConfig,
+    agent: Option<String>,
+    model: Option<String>,
+) -> CliResult<()> {
+    let mut agents = config.list_agents();
+    agents.sort();
+
+    let current_agent = match agent {
+        Some(agent) if agents.contains(&agent) => agent,
+        Some(agent) => return Err(CliError::invalid_input_string(format!("项目配置中未声明代理: {}", agent))),
+        None => agents.first().cloned().ok_or_else(|| CliError::invalid_input("项目配置中没有声明任何代理"))?,
+    };
+
+    if let Some(model) = &model {
+        set_agent_model(&mut config, &current_agent, model);
+    }
+
+    let app = LumosApp::from_yaml_config(config.clone()).await?;
+    let current_model = agent_model(&config, &current_agent);
+    let state = PlaygroundState::new(agents, current_agent, declared_models(&config), current_model);
+
+    let (outcomes_tx, mut outcomes_rx) = mpsc::unbounded_channel();
+    let mut session = PlaygroundSession {
+        project_dir: project_dir.to_path_buf(),
+        config,
+        app,
+        state,
+        outcomes_tx,
+    };
+
+    let mut guard = TerminalGuard::new()?;
+
+    loop {
+        guard.terminal
+            .draw(|frame| view::draw(frame, &session.state))
+            .map_err(|e| CliError::io("绘制界面失败", e))?;
+
+        while let Ok(outcome) = outcomes_rx.try_recv() {
+            session.apply_outcome(outcome);
+        }
+
+        if !event::poll(Duration::from_millis(100)).map_err(|e| CliError::io("读取终端事件失败", e))? {
+            continue;
+        }
+
+        let Event::Key(key) = event::read().map_err(|e| CliError::io("读取终端事件失败", e))? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        match key.code {
+            KeyCode::Esc => break,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break,
+            KeyCode::Enter => {
+                let input = std::mem::take(&mut session.state.input);
+                if input.trim().is_empty() {
+                    continue;
+                }
+                if !session.handle_command(PlaygroundCommand::parse(&input)).await {
+                    break;
+                }
+            }
+            KeyCode::F(2) => {
+                session.handle_command(PlaygroundCommand::SwitchModel(None)).await;
+            }
+            KeyCode::Backspace => {
+                session.state.input.pop();
+            }
+            KeyCode::Char(c) => session.state.input.push(c),
+            KeyCode::Up => session.state.scroll = session.state.scroll.saturating_add(1),
+            KeyCode::Down => session.state.scroll = session.state.scroll.saturating_sub(1),
+            KeyCode::PageUp => session.state.scroll = session.state.scroll.saturating_add(10),
+            KeyCode::PageDown => session.state.scroll = session.state.scroll.saturating_sub(10),
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+impl PlaygroundSession {
+    /// 处理命令，返回 `false` 表示退出
+    async fn handle_command(&mut self, command: PlaygroundCommand) -> bool {
+        match command {
+            PlaygroundCommand::Send(input) => self.send(input),
+            PlaygroundCommand::SwitchAgent(agent) => {
+                if self.state.agents.contains(&agent) {
+                    self.state.current_model = agent_model(&self.config, &agent);
+                    self.state.status = format!("已切换到代理: {}", agent);
+                    self.state.current_agent = agent;
+                } else {
+                    self.state.status = format!("未知代理: {} (可用: {})", agent, self.state.agents.join(", "));
+                }
+            }
+            PlaygroundCommand::SwitchModel(model) => self.switch_model(model).await,
+            PlaygroundCommand::Save(path) => self.save(path),
+            PlaygroundCommand::Clear => {
+                self.state.turns.clear();
+                self.state.status = "对话已清空".to_string();
+            }
+            PlaygroundCommand::ToggleTrace => self.state.show_trace = !self.state.show_trace,
+            PlaygroundCommand::Help => self.state.status = HELP_TEXT.to_string(),
+            PlaygroundCommand::Quit => return false,
+            PlaygroundCommand::Unknown(input) => self.state.status = format!("未知命令: {} ({})", input, HELP_TEXT),
+        }
+        true
+    }
+
+    /// 在后台向当前代理发送消息
+    fn send(&mut self, input: String) {
+        if self.state.is_busy() {
+            self.state.status = "请等待当前回复完成".to_string();
+            return;
+        }
+
+        let agent = match self.app.agent(&self.state.current_agent) {
+            Ok(agent) => agent.clone(),
+            Err(e) => {
+                self.state.status = e.to_string();
+                return;
+            }
+        };
+
+        let rag = self.config.agents.as_ref()
+            .and_then(|agents| agents.get(&self.state.current_agent))
+            .and_then(|agent| agent.rag.clone())
+            .and_then(|name| self.app.rags().get(&name).cloned().map(|rag| (name, rag)));
+
+        let mut messages = Vec::new();
+        for (question, answer) in self.state.history() {
+            messages.push(user_message(&question));
+            messages.push(assistant_message(&answer));
+        }
+        messages.push(user_message(&input));
+
+        let index = self.state.start_turn(input.clone());
+        let outcomes_tx = self.outcomes_tx.clone();
+
+        tokio::spawn(async move {
+            let started = Instant::now();
+            let mut options = AgentGenerateOptions::default();
+            let mut retrievals = Vec::new();
+
+            if let Some((name, rag)) = rag {
+                if let Ok(result) = rag.query(&input, RETRIEVAL_TOP_K).await {
+                    retrievals = trace_from_retrieval(&name, &result);
+                    if !result.context.is_empty() {
+                        options.context = Some(vec![system_message(&result.context)]);
+                    }
+                }
+            }
+
+            let result = agent.generate(&messages, &options).await.map_err(|e| e.to_string());
+            let _ = outcomes_tx.send(TurnOutcome {
+                index,
+                result,
+                retrievals,
+                elapsed_ms: started.elapsed().as_millis() as u64,
+            });
+        });
+    }
+
+    /// 记录后台生成结果
+    fn apply_outcome(&mut self, outcome: TurnOutcome) {
+        let Some(turn) = self.state.turns.get_mut(outcome.index) else {
+            return;
+        };
+
+        turn.elapsed_ms = outcome.elapsed_ms;
+        turn.trace = outcome.retrievals;
+        match outcome.result {
+            Ok(result) => {
+                turn.trace.extend(trace_from_result(&result));
+                turn.response = Some(result.response);
+            }
+            Err(e) => turn.error = Some(e),
+        }
+    }
+
+    /// 切换当前代理的模型并就地重建代理
+    async fn switch_model(&mut self, model: Option<String>) {
+        let Some(model) = model.or_else(|| self.state.next_model()) else {
+            self.state.status = "项目配置中没有可切换的模型，使用 /model <名称> 指定".to_string();
+            return;
+        };
+
+        let mut config = self.config.clone();
+        set_agent_model(&mut config, &self.state.current_agent, &model);
+
+        match self.app.reload_config(config.clone()).await {
+            Ok(_) => {
+                self.config = config;
+                if !self.state.models.contains(&model) {
+                    self.state.models.push(model.clone());
+                }
+                self.state.status = format!("代理 {} 已切换到模型 {}", self.state.current_agent, model);
+                self.state.current_model = Some(model);
+            }
+            Err(e) => self.state.status = format!("切换模型失败: {}", e),
+        }
+    }
+
+    /// 将对话保存为评估测试用例
+    fn save(&mut self, path: Option<PathBuf>) {
+        let path = path.unwrap_or_else(|| PathBuf::from(DEFAULT_TEST_CASES_PATH));
+        let path = if path.is_absolute() { path } else { self.project_dir.join(path) };
+
+        let cases = self.state.to_test_cases();
+        if cases.is_empty() {
+            self.state.status = "没有可保存的对话".to_string();
+            return;
+        }
+
+        self.state.status = match EvalTestCase::append_jsonl(&path, &cases) {
+            Ok(()) => format!("已保存 {} 个评估用例到 {}", cases.len(), path.display()),
+            Err(e) => format!("保存评估用例失败: {}", e),
+        };
+    }
+}
+
+/// 代理当前配置的模型
+fn agent_model(config: &YamlConfig, agent: &str) -> Option<String> {
+    let agent = config.agents.as_ref()?.get(agent)?;
+    if !agent.model.is_empty() {
+        return Some(agent.model.clone());
+    }
+    let provider = config.get_provider(agent.provider.as_deref()?)?;
+    provider.model.clone()
+}
+
+fn set_agent_model(config: &mut YamlConfig, agent: &str, model: &str) {
+    if let Some(agent) = config.agents.as_mut().and_then(|agents| agents.get_mut(agent)) {
+        agent.model = model.to_string();
+    }
+}
+
+/// 项目配置中声明过的所有模型
+fn declared_models(config: &YamlConfig) -> Vec<String> {
+    let agent_models = config.agents.iter().flatten().map(|(_, agent)| agent.model.clone());
+    let provider_models = config.providers.iter().flatten().filter_map(|(_, provider)| provider.model.clone());
+
+    let mut models: Vec<String> = agent_models.chain(provider_models).filter(|m| !m.is_empty()).collect();
+    models.sort();
+    models.dedup();
+    models
+}
+
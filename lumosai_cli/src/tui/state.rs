@@ -0,0 +1,324 @@
+use std::path::PathBuf;
+use serde_json::Value;
+
+use lumosai_core::agent::types::{AgentGenerateResult, ToolResultStatus};
+use lumosai_core::rag::QueryResult;
+use lumosai_evals::EvalTestCase;
+
+/// 单次对话中记录的追踪条目
+#[derive(Debug, Clone, PartialEq)]
+pub enum TraceEntry {
+    /// 代理发起的工具调用
+    ToolCall { name: String, arguments: Value },
+    /// 工具返回的结果
+    ToolResult { name: String, result: Value, success: bool },
+    /// RAG检索到的片段
+    Retrieval { pipeline: String, content: String, score: Option<f32> },
+}
+
+/// 一轮对话
+#[derive(Debug, Clone)]
+pub struct Turn {
+    /// 用户输入
+    pub input: String,
+    /// 处理该轮对话的代理
+    pub agent: String,
+    /// 使用的模型
+    pub model: Option<String>,
+    /// 代理回复
+    pub response: Option<String>,
+    /// 错误信息
+    pub error: Option<String>,
+    /// 工具调用与检索追踪
+    pub trace: Vec<TraceEntry>,
+    /// 耗时（毫秒）
+    pub elapsed_ms: u64,
+}
+
+impl Turn {
+    /// 是否仍在等待代理回复
+    pub fn is_pending(&self) -> bool {
+        self.response.is_none() && self.error.is_none()
+    }
+}
+
+/// 用户在输入框中输入的命令
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlaygroundCommand {
+    /// 发送消息给当前代理
+    Send(String),
+    /// 切换代理
+    SwitchAgent(String),
+    /// 切换模型，为空时切换到下一个模型
+    SwitchModel(Option<String>),
+    /// 将对话保存为评估测试用例
+    Save(Option<PathBuf>),
+    /// 清空对话
+    Clear,
+    /// 显示/隐藏追踪面板
+    ToggleTrace,
+    /// 显示帮助
+    Help,
+    /// 退出
+    Quit,
+    /// 未知命令
+    Unknown(String),
+}
+
+impl PlaygroundCommand {
+    /// 解析输入框内容，以 `/` 开头的视为命令
+    pub fn parse(input: &str) -> Self {
+        let input = input.trim();
+        let Some(command) = input.strip_prefix('/') else {
+            return Self::Send(input.to_string());
+        };
+
+        let mut parts = command.splitn(2, char::is_whitespace);
+        let name = parts.next().unwrap_or_default();
+        let arg = parts.next().map(str::trim).filter(|arg| !arg.is_empty());
+
+        match (name, arg) {
+            ("agent", Some(agent)) => Self::SwitchAgent(agent.to_string()),
+            ("model", model) => Self::SwitchModel(model.map(str::to_string)),
+            ("save", path) => Self::Save(path.map(PathBuf::from)),
+            ("clear", None) => Self::Clear,
+            ("trace", None) => Self::ToggleTrace,
+            ("help", None) => Self::Help,
+            ("quit", None) | ("exit", None) => Self::Quit,
+            _ => Self::Unknown(input.to_string()),
+        }
+    }
+}
+
+/// 帮助文本
+pub const HELP_TEXT: &str = "/agent <名称> 切换代理 | /model [名称] 切换模型 | /save [路径] 保存为评估用例 | /trace 追踪面板 | /clear 清空 | /quit 退出";
+
+/// Playground界面状态
+#[derive(Debug, Clone)]
+pub struct PlaygroundState {
+    /// 可用代理
+    pub agents: Vec<String>,
+    /// 当前代理
+    pub current_agent: String,
+    /// 可切换的模型
+    pub models: Vec<String>,
+    /// 当前代理使用的模型
+    pub current_model: Option<String>,
+    /// 对话记录
+    pub turns: Vec<Turn>,
+    /// 输入框内容
+    pub input: String,
+    /// 状态栏消息
+    pub status: String,
+    /// 是否显示追踪面板
+    pub show_trace: bool,
+    /// 对话区滚动偏移（从底部计算）
+    pub scroll: u16,
+}
+
+impl PlaygroundState {
+    pub fn new(agents: Vec<String>, current_agent: String, models: Vec<String>, current_model: Option<String>) -> Self {
+        Self {
+            agents,
+            current_agent,
+            models,
+            current_model,
+            turns: Vec::new(),
+            input: String::new(),
+            status: HELP_TEXT.to_string(),
+            show_trace: true,
+            scroll: 0,
+        }
+    }
+
+    /// 是否有尚未完成的请求
+    pub fn is_busy(&self) -> bool {
+        self.turns.iter().any(Turn::is_pending)
+    }
+
+    /// 开始新的一轮对话，返回其索引
+    pub fn start_turn(&mut self, input: String) -> usize {
+        self.turns.push(Turn {
+            input,
+            agent: self.current_agent.clone(),
+            model: self.current_model.clone(),
+            response: None,
+            error: None,
+            trace: Vec::new(),
+            elapsed_ms: 0,
+        });
+        self.scroll = 0;
+        self.turns.len() - 1
+    }
+
+    /// 当前代理之前的对话历史（用户消息与回复成对）
+    pub fn history(&self) -> Vec<(String, String)> {
+        self.turns
+            .iter()
+            .filter(|turn| turn.agent == self.current_agent)
+            .filter_map(|turn| turn.response.as_ref().map(|response| (turn.input.clone(), response.clone())))
+            .collect()
+    }
+
+    /// 循环选择下一个模型
+    pub fn next_model(&self) -> Option<String> {
+        if self.models.is_empty() {
+            return None;
+        }
+        let next = match &self.current_model {
+            Some(current) => self.models
+                .iter()
+                .position(|m| m == current)
+                .map(|i| (i + 1) % self.models.len())
+                .unwrap_or(0),
+            None => 0,
+        };
+        Some(self.models[next].clone())
+    }
+
+    /// 将已完成的对话转换为评估测试用例，回复作为期望输出
+    pub fn to_test_cases(&self) -> Vec<EvalTestCase> {
+        self.turns
+            .iter()
+            .filter_map(|turn| {
+                let response = turn.response.as_ref()?;
+                let mut case = EvalTestCase::new(turn.input.clone());
+                case.expected_output = Some(response.clone());
+                case.agent = Some(turn.agent.clone());
+                case.model = turn.model.clone();
+                case.tags = vec!["playground".to_string()];
+                for entry in &turn.trace {
+                    match entry {
+                        TraceEntry::ToolCall { name, .. } if !case.expected_tools.contains(name) => {
+                            case.expected_tools.push(name.clone());
+                        }
+                        TraceEntry::Retrieval { content, .. } => case.context.push(content.clone()),
+                        _ => {}
+                    }
+                }
+                Some(case)
+            })
+            .collect()
+    }
+}
+
+/// 从生成结果中提取工具调用追踪
+pub fn trace_from_result(result: &AgentGenerateResult) -> Vec<TraceEntry> {
+    let mut trace = Vec::new();
+    for step in &result.steps {
+        for call in &step.tool_calls {
+            trace.push(TraceEntry::ToolCall {
+                name: call.name.clone(),
+                arguments: serde_json::to_value(&call.arguments).unwrap_or(Value::Null),
+            });
+        }
+        for tool_result in &step.tool_results {
+            trace.push(TraceEntry::ToolResult {
+                name: tool_result.name.clone(),
+                result: tool_result.result.clone(),
+                success: matches!(tool_result.status, ToolResultStatus::Success),
+            });
+        }
+    }
+    trace
+}
+
+/// 从检索结果中提取追踪条目
+pub fn trace_from_retrieval(pipeline: &str, result: &QueryResult) -> Vec<TraceEntry> {
+    result.documents
+        .iter()
+        .enumerate()
+        .map(|(i, document)| TraceEntry::Retrieval {
+            pipeline: pipeline.to_string(),
+            content: document.content.clone(),
+            score: result.scores.as_ref().and_then(|scores| scores.get(i).copied()),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use lumosai_core::agent::types::{AgentStep, StepType, TokenUsage, ToolCall, ToolResult};
+
+    fn state() -> PlaygroundState {
+        PlaygroundState::new(
+            vec!["assistant".to_string()],
+            "assistant".to_string(),
+            vec!["gpt-4".to_string(), "gpt-3.5-turbo".to_string()],
+            Some("gpt-4".to_string()),
+        )
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(PlaygroundCommand::parse("hello"), PlaygroundCommand::Send("hello".to_string()));
+        assert_eq!(PlaygroundCommand::parse("/agent writer"), PlaygroundCommand::SwitchAgent("writer".to_string()));
+        assert_eq!(PlaygroundCommand::parse("/model"), PlaygroundCommand::SwitchModel(None));
+        assert_eq!(PlaygroundCommand::parse("/model gpt-4"), PlaygroundCommand::SwitchModel(Some("gpt-4".to_string())));
+        assert_eq!(PlaygroundCommand::parse("/save evals/a.jsonl"), PlaygroundCommand::Save(Some(PathBuf::from("evals/a.jsonl"))));
+        assert_eq!(PlaygroundCommand::parse("/quit"), PlaygroundCommand::Quit);
+        assert_eq!(PlaygroundCommand::parse("/agent"), PlaygroundCommand::Unknown("/agent".to_string()));
+    }
+
+    #[test]
+    fn test_next_model_cycles() {
+        let mut state = state();
+        assert_eq!(state.next_model(), Some("gpt-3.5-turbo".to_string()));
+        state.current_model = Some("gpt-3.5-turbo".to_string());
+        assert_eq!(state.next_model(), Some("gpt-4".to_string()));
+    }
+
+    #[test]
+    fn test_transcript_to_test_cases() {
+        let mut state = state();
+        let index = state.start_turn("What's the weather?".to_string());
+        assert!(state.is_busy());
+
+        let result = AgentGenerateResult {
+            response: "Sunny".to_string(),
+            steps: vec![AgentStep {
+                id: "step-1".to_string(),
+                step_type: StepType::Tool,
+                input: vec![],
+                output: None,
+                tool_calls: vec![ToolCall {
+                    id: "call-1".to_string(),
+                    name: "weather".to_string(),
+                    arguments: HashMap::from([("city".to_string(), Value::from("Paris"))]),
+                }],
+                tool_results: vec![ToolResult {
+                    call_id: "call-1".to_string(),
+                    name: "weather".to_string(),
+                    result: Value::from("sunny"),
+                    status: ToolResultStatus::Success,
+                }],
+                metadata: HashMap::new(),
+            }],
+            usage: TokenUsage { prompt_tokens: 0, completion_tokens: 0, total_tokens: 0 },
+            metadata: HashMap::new(),
+            trace: None,
+        };
+
+        let turn = &mut state.turns[index];
+        turn.trace.push(TraceEntry::Retrieval {
+            pipeline: "docs".to_string(),
+            content: "Paris weather report".to_string(),
+            score: Some(0.9),
+        });
+        turn.trace.extend(trace_from_result(&result));
+        turn.response = Some(result.response.clone());
+
+        // 未完成的对话不会被导出
+        state.start_turn("pending".to_string());
+
+        let cases = state.to_test_cases();
+        assert_eq!(cases.len(), 1);
+        assert_eq!(cases[0].expected_output.as_deref(), Some("Sunny"));
+        assert_eq!(cases[0].expected_tools, vec!["weather".to_string()]);
+        assert_eq!(cases[0].context, vec!["Paris weather report".to_string()]);
+        assert_eq!(cases[0].model.as_deref(), Some("gpt-4"));
+        assert_eq!(state.history(), vec![("What's the weather?".to_string(), "Sunny".to_string())]);
+    }
+}
@@ -0,0 +1,155 @@
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
+use ratatui::Frame;
+
+use super::state::{PlaygroundState, TraceEntry};
+
+/// 追踪面板中单个值的最大显示长度
+const MAX_TRACE_VALUE_LEN: usize = 160;
+
+/// 绘制Playground界面
+pub fn draw(frame: &mut Frame, state: &PlaygroundState) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Min(3),
+            Constraint::Length(3),
+            Constraint::Length(1),
+        ])
+        .split(frame.area());
+
+    draw_header(frame, rows[0], state);
+
+    if state.show_trace {
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(rows[1]);
+        draw_chat(frame, columns[0], state);
+        draw_trace(frame, columns[1], state);
+    } else {
+        draw_chat(frame, rows[1], state);
+    }
+
+    let input = Paragraph::new(state.input.as_str())
+        .block(Block::default().borders(Borders::ALL).title(" 输入 (Enter 发送, F2 切换模型, Esc 退出) "));
+    frame.render_widget(input, rows[2]);
+    frame.set_cursor_position((
+        rows[2].x + 1 + Line::raw(state.input.as_str()).width() as u16,
+        rows[2].y + 1,
+    ));
+
+    let status = Paragraph::new(state.status.as_str()).style(Style::default().fg(Color::DarkGray));
+    frame.render_widget(status, rows[3]);
+}
+
+fn draw_header(frame: &mut Frame, area: Rect, state: &PlaygroundState) {
+    let busy = if state.is_busy() { " · 生成中..." } else { "" };
+    let header = Line::from(vec![
+        Span::styled(" Lumosai Playground ", Style::default().fg(Color::Black).bg(Color::Cyan)),
+        Span::raw(format!(
+            " 代理: {}  模型: {}{}",
+            state.current_agent,
+            state.current_model.as_deref().unwrap_or("默认"),
+            busy,
+        )),
+    ]);
+    frame.render_widget(Paragraph::new(header), area);
+}
+
+fn draw_chat(frame: &mut Frame, area: Rect, state: &PlaygroundState) {
+    let mut lines = Vec::new();
+    for turn in &state.turns {
+        lines.push(Line::from(vec![
+            Span::styled("你 ▸ ", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::raw(turn.input.clone()),
+        ]));
+
+        let speaker = match &turn.model {
+            Some(model) => format!("{} ({}) ▸ ", turn.agent, model),
+            None => format!("{} ▸ ", turn.agent),
+        };
+        let speaker = Span::styled(speaker, Style::default().fg(Color::Green).add_modifier(Modifier::BOLD));
+
+        match (&turn.response, &turn.error) {
+            (Some(response), _) => {
+                let mut response_lines = response.lines();
+                lines.push(Line::from(vec![speaker, Span::raw(response_lines.next().unwrap_or_default().to_string())]));
+                lines.extend(response_lines.map(|line| Line::raw(line.to_string())));
+                lines.push(Line::styled(
+                    format!("  {} 次追踪 · {} ms", turn.trace.len(), turn.elapsed_ms),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            (None, Some(error)) => {
+                lines.push(Line::from(vec![speaker, Span::styled(error.clone(), Style::default().fg(Color::Red))]));
+            }
+            (None, None) => {
+                lines.push(Line::from(vec![speaker, Span::styled("...", Style::default().fg(Color::DarkGray))]));
+            }
+        }
+        lines.push(Line::default());
+    }
+
+    render_scrolled(frame, area, lines, state.scroll, " 对话 ");
+}
+
+fn draw_trace(frame: &mut Frame, area: Rect, state: &PlaygroundState) {
+    let mut lines = Vec::new();
+    for (i, turn) in state.turns.iter().enumerate() {
+        if turn.trace.is_empty() {
+            continue;
+        }
+        lines.push(Line::styled(format!("#{} {}", i + 1, truncate(&turn.input, 40)), Style::default().add_modifier(Modifier::BOLD)));
+        for entry in &turn.trace {
+            lines.push(match entry {
+                TraceEntry::ToolCall { name, arguments } => Line::styled(
+                    format!("  ⚙ {}({})", name, truncate(&arguments.to_string(), MAX_TRACE_VALUE_LEN)),
+                    Style::default().fg(Color::Yellow),
+                ),
+                TraceEntry::ToolResult { name, result, success } => Line::styled(
+                    format!("  ↳ {}: {}", name, truncate(&result.to_string(), MAX_TRACE_VALUE_LEN)),
+                    Style::default().fg(if *success { Color::Green } else { Color::Red }),
+                ),
+                TraceEntry::Retrieval { pipeline, content, score } => Line::styled(
+                    format!(
+                        "  ▤ {}{}: {}",
+                        pipeline,
+                        score.map(|s| format!(" {:.3}", s)).unwrap_or_default(),
+                        truncate(content, MAX_TRACE_VALUE_LEN),
+                    ),
+                    Style::default().fg(Color::Magenta),
+                ),
+            });
+        }
+        lines.push(Line::default());
+    }
+
+    render_scrolled(frame, area, lines, 0, " 工具调用 / 检索片段 ");
+}
+
+/// 渲染自动滚动到底部的段落，`scroll` 为向上滚动的行数
+fn render_scrolled(frame: &mut Frame, area: Rect, lines: Vec<Line>, scroll: u16, title: &str) {
+    let width = area.width.saturating_sub(2).max(1) as usize;
+    let height = area.height.saturating_sub(2) as usize;
+    let total: usize = lines.iter().map(|line| line.width().max(1).div_ceil(width)).sum();
+    let offset = total.saturating_sub(height).saturating_sub(scroll as usize) as u16;
+
+    let paragraph = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title(title.to_string()))
+        .wrap(Wrap { trim: false })
+        .scroll((offset, 0));
+    frame.render_widget(paragraph, area);
+}
+
+fn truncate(value: &str, max_chars: usize) -> String {
+    let value = value.replace('\n', " ");
+    if value.chars().count() <= max_chars {
+        value
+    } else {
+        format!("{}…", value.chars().take(max_chars).collect::<String>())
+    }
+}
@@ -6,6 +6,7 @@ use std::net::TcpListener;
 use rand::Rng;
 use crate::error::{CliError, CliResult};
 use colored::Colorize;
+use lumosai_core::config::{ConfigLoader, YamlConfig};
 
 /// 检查Rust工具链是否正确安装
 pub fn check_rust_toolchain() -> CliResult<()> {
@@ -51,6 +52,11 @@ pub fn find_project_root() -> CliResult<PathBuf> {
 
 /// 检查目录是否为Lumosai项目
 pub fn is_lumos_project(path: &Path) -> bool {
+    // 存在声明式项目配置 (lumos.yaml 等) 即视为Lumosai项目
+    if ConfigLoader::find_in_dir(path).is_some() {
+        return true;
+    }
+    
     // 检查是否存在Cargo.toml
     let cargo_toml = path.join("Cargo.toml");
     if !cargo_toml.exists() {
@@ -208,6 +214,20 @@ pub fn copy_dir_all<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> CliResult
     Ok(())
 }
 
+/// 加载并校验项目的声明式配置 (lumos.yaml / lumos.toml 等)
+///
+/// 项目中没有配置文件时返回 `None`
+pub fn load_project_config(project_dir: &Path) -> CliResult<Option<(PathBuf, YamlConfig)>> {
+    let Some(config_path) = ConfigLoader::find_in_dir(project_dir) else {
+        return Ok(None);
+    };
+    
+    let config = ConfigLoader::load_validated(&config_path)
+        .map_err(|e| CliError::Other(format!("配置文件无效 {}: {}", config_path.display(), e)))?;
+    
+    Ok(Some((config_path, config)))
+}
+
 /// 读取项目配置
 pub fn read_project_config(project_dir: &Path) -> CliResult<toml::Value> {
     let config_file = project_dir.join("Cargo.toml");
@@ -0,0 +1,137 @@
+//! SQLite vector storage benchmarks on SIFT/GloVe-style datasets
+//!
+//! Run with: cd lumosai_core && cargo bench --bench sqlite_vector --features vector_sqlite
+//!
+//! Uses the datasets and report of `lumosai_vector::benchmark` and honours the same
+//! `LUMOS_BENCH_*` environment variables as the `vector_backends` bench in `lumosai_vector`,
+//! so its results appear next to the other backends in the comparison report.
+
+use std::time::{Duration, Instant};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use tokio::runtime::Runtime;
+use lumosai_core::vector::{SqliteVectorStorage, VectorStorage};
+use lumosai_vector::benchmark::{BenchmarkRecord, BenchmarkReport, Dataset, INSERT_BATCH_SIZE, RECALL_K};
+
+const QUERIES: usize = 100;
+const SEED: u64 = 42;
+
+fn scales() -> Vec<usize> {
+    std::env::var("LUMOS_BENCH_SCALES")
+        .ok()
+        .map(|scales| scales.split(',').filter_map(|scale| scale.trim().parse().ok()).collect())
+        .filter(|scales: &Vec<usize>| !scales.is_empty())
+        .unwrap_or_else(|| vec![1_000, 10_000])
+}
+
+fn datasets(size: usize) -> Vec<Dataset> {
+    let sift = match std::env::var("LUMOS_BENCH_SIFT_DIR") {
+        Ok(dir) => Dataset::load_texmex(dir, "sift", size, QUERIES),
+        Err(_) => Dataset::sift_like(size, QUERIES, SEED),
+    };
+    let glove = match std::env::var("LUMOS_BENCH_GLOVE") {
+        Ok(path) => Dataset::load_glove(path, size, QUERIES),
+        Err(_) => Dataset::glove_like(size, QUERIES, SEED),
+    };
+    vec![sift.expect("Failed to load SIFT dataset"), glove.expect("Failed to load GloVe dataset")]
+}
+
+fn id_batches(dataset: &Dataset) -> Vec<(Vec<Vec<f32>>, Vec<String>)> {
+    dataset
+        .base
+        .chunks(INSERT_BATCH_SIZE)
+        .enumerate()
+        .map(|(batch, vectors)| {
+            let ids = (0..vectors.len()).map(|offset| Dataset::document_id(batch * INSERT_BATCH_SIZE + offset)).collect();
+            (vectors.to_vec(), ids)
+        })
+        .collect()
+}
+
+async fn create_index(storage: &SqliteVectorStorage, index: &str, dataset: &Dataset) {
+    let _ = storage.delete_index(index).await;
+    storage.create_index(index, dataset.dimension, Some(dataset.metric.into())).await.unwrap();
+}
+
+async fn insert(storage: &SqliteVectorStorage, index: &str, batches: &[(Vec<Vec<f32>>, Vec<String>)]) {
+    for (vectors, ids) in batches {
+        storage.upsert(index, vectors.clone(), Some(ids.clone()), None).await.unwrap();
+    }
+}
+
+async fn search(storage: &SqliteVectorStorage, index: &str, query: &[f32]) -> Vec<String> {
+    let results = storage.query(index, query.to_vec(), RECALL_K, None, false).await.unwrap();
+    results.into_iter().map(|result| result.id).collect()
+}
+
+// Single timed pass over the dataset, for the comparison report
+async fn measure(storage: &SqliteVectorStorage, dataset: &Dataset) -> BenchmarkRecord {
+    let index = format!("bench_{}_{}", dataset.name, dataset.len());
+    create_index(storage, &index, dataset).await;
+
+    let batches = id_batches(dataset);
+    let started = Instant::now();
+    insert(storage, &index, &batches).await;
+    let insert_time = started.elapsed();
+
+    let mut searches: Vec<Duration> = Vec::with_capacity(dataset.queries.len());
+    let mut recall = 0.0;
+    for (query, vector) in dataset.queries.iter().enumerate() {
+        let started = Instant::now();
+        let ids = search(storage, &index, vector).await;
+        searches.push(started.elapsed());
+        recall += dataset.recall(query, &ids);
+    }
+    storage.delete_index(&index).await.unwrap();
+
+    let recall = recall / dataset.queries.len().max(1) as f64;
+    BenchmarkRecord::from_timings("sqlite", dataset, insert_time, &searches, recall)
+}
+
+fn sqlite_vector(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let dir = BenchmarkReport::default_dir();
+    let mut report = BenchmarkReport::load(&dir).unwrap();
+    let storage = SqliteVectorStorage::new_in_memory().unwrap();
+
+    for size in scales() {
+        for dataset in datasets(size) {
+            let index = format!("criterion_{}_{}", dataset.name, size);
+            rt.block_on(create_index(&storage, &index, &dataset));
+            let batches = id_batches(&dataset);
+
+            let mut group = c.benchmark_group(format!("{}/sqlite", dataset.name));
+            group.sample_size(10);
+
+            group.throughput(Throughput::Elements(size as u64));
+            group.bench_with_input(BenchmarkId::new("insert", size), &batches, |b, batches| {
+                b.to_async(&rt).iter(|| insert(&storage, &index, batches));
+            });
+
+            group.throughput(Throughput::Elements(1));
+            group.bench_with_input(BenchmarkId::new("search", size), &dataset.queries, |b, queries| {
+                let mut next = 0;
+                b.to_async(&rt).iter(|| {
+                    next += 1;
+                    search(&storage, &index, &queries[(next - 1) % queries.len()])
+                });
+            });
+            group.finish();
+
+            rt.block_on(storage.delete_index(&index)).unwrap();
+
+            let record = rt.block_on(measure(&storage, &dataset));
+            println!(
+                "sqlite {} x{}: {:.0} inserts/s, {:.1} QPS, recall@{} {:.3}",
+                dataset.name, size, record.insert_per_sec, record.search_qps, RECALL_K, record.recall
+            );
+            report.upsert(record);
+        }
+    }
+
+    report.save(&dir).unwrap();
+    println!("Benchmark report written to {}", dir.join("report.md").display());
+}
+
+criterion_group!(benches, sqlite_vector);
+criterion_main!(benches);
@@ -65,8 +65,13 @@ async fn test_zhipu_provider() -> Result<(), Box<dyn std::error::Error>> {
         temperature: Some(0.7),
         max_tokens: Some(100),
         stop: None,
+        logit_bias: None,
+        frequency_penalty: None,
+        presence_penalty: None,
+        seed: None,
         stream: false,
         extra: serde_json::Map::new(),
+        cancellation_token: None,
     };
     
     println!("✅ 测试配置:");
@@ -116,8 +121,13 @@ async fn test_baidu_provider() -> Result<(), Box<dyn std::error::Error>> {
         temperature: Some(0.8),
         max_tokens: Some(200),
         stop: None,
+        logit_bias: None,
+        frequency_penalty: None,
+        presence_penalty: None,
+        seed: None,
         stream: false,
         extra,
+        cancellation_token: None,
     };
     
     println!("✅ 测试配置:");
@@ -223,8 +233,13 @@ mod tests {
             temperature: Some(0.8),
             max_tokens: Some(200),
             stop: None,
+            logit_bias: None,
+            frequency_penalty: None,
+            presence_penalty: None,
+            seed: None,
             stream: false,
             extra,
+            cancellation_token: None,
         };
 
         assert_eq!(options.temperature, Some(0.8));
@@ -269,6 +269,12 @@ mod tests {
             assert!(result_obj.contains_key("agent_001"));
             assert!(result_obj.contains_key("agent_002"));
 
+            // 每个Agent的结果以其ID为名写入共享黑板
+            assert_eq!(session.blackboard.names().await, ["agent_001", "agent_002"]);
+            let artifact = session.blackboard.read("agent_001").await.unwrap();
+            assert_eq!(artifact.author.as_deref(), Some("agent_001"));
+            assert_eq!(artifact.artifact, Artifact::document(result_obj["agent_001"].as_str().unwrap()));
+
             // 检查Agent状态
             let states = session.get_results().await;
             assert_eq!(states.len(), 2);
@@ -0,0 +1,417 @@
+//! 多Agent共享黑板
+//!
+//! 协作任务中的Agent通过[`Blackboard`]读写具名的类型化产物（文档、表格、决策或任意JSON数据），
+//! 代替在步骤之间直接传递原始字符串。每次写入生成新版本并保留历史，订阅者通过
+//! [`Blackboard::subscribe`]收到变更通知；配置事件总线后变更同时以`blackboard_changed`
+//! 自定义事件发布。
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::{broadcast, RwLock};
+
+use super::events::{AgentEvent, EventBus};
+use crate::error::{Error, Result};
+
+/// 变更通知通道容量，落后的订阅者会丢失较早的通知
+const CHANGE_CHANNEL_CAPACITY: usize = 256;
+
+/// 渲染给Agent的产物内容的最大字符数
+const MAX_RENDERED_CHARS: usize = 2000;
+
+/// 黑板上的产物
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Artifact {
+    /// 文本文档，如调研报告或草稿
+    Document {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        title: Option<String>,
+        content: String,
+    },
+    /// 表格
+    Table {
+        columns: Vec<String>,
+        rows: Vec<Vec<Value>>,
+    },
+    /// 决策及其理由
+    Decision {
+        decision: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        rationale: Option<String>,
+    },
+    /// 任意结构化数据
+    Data { value: Value },
+}
+
+impl Artifact {
+    /// 创建文档
+    pub fn document(content: impl Into<String>) -> Self {
+        Artifact::Document { title: None, content: content.into() }
+    }
+
+    /// 创建表格，每行的列数须与表头一致
+    pub fn table(columns: Vec<String>, rows: Vec<Vec<Value>>) -> Result<Self> {
+        if let Some(row) = rows.iter().find(|row| row.len() != columns.len()) {
+            return Err(Error::InvalidInput(format!(
+                "Table row has {} cells but {} columns", row.len(), columns.len()
+            )));
+        }
+        Ok(Artifact::Table { columns, rows })
+    }
+
+    /// 创建决策
+    pub fn decision(decision: impl Into<String>, rationale: Option<String>) -> Self {
+        Artifact::Decision { decision: decision.into(), rationale }
+    }
+
+    /// 由可序列化的值创建数据产物
+    pub fn data<T: Serialize>(value: &T) -> Result<Self> {
+        Ok(Artifact::Data { value: serde_json::to_value(value)? })
+    }
+
+    /// 产物类型名称
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Artifact::Document { .. } => "document",
+            Artifact::Table { .. } => "table",
+            Artifact::Decision { .. } => "decision",
+            Artifact::Data { .. } => "data",
+        }
+    }
+
+    /// 产物的文本表示，用于提供给Agent
+    pub fn render(&self) -> String {
+        match self {
+            Artifact::Document { title: Some(title), content } => format!("{}\n{}", title, content),
+            Artifact::Document { title: None, content } => content.clone(),
+            Artifact::Table { columns, rows } => {
+                let mut text = format!("| {} |", columns.join(" | "));
+                for row in rows {
+                    let cells: Vec<String> = row.iter()
+                        .map(|cell| cell.as_str().map_or_else(|| cell.to_string(), str::to_string))
+                        .collect();
+                    text.push_str(&format!("\n| {} |", cells.join(" | ")));
+                }
+                text
+            }
+            Artifact::Decision { decision, rationale: Some(rationale) } => format!("{}（理由：{}）", decision, rationale),
+            Artifact::Decision { decision, rationale: None } => decision.clone(),
+            Artifact::Data { value } => value.to_string(),
+        }
+    }
+}
+
+/// 产物的一个版本
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArtifactVersion {
+    /// 产物名称
+    pub name: String,
+    /// 版本号，从1开始
+    pub version: u64,
+    /// 产物内容
+    pub artifact: Artifact,
+    /// 写入者，通常为Agent ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// 写入时间
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ArtifactVersion {
+    /// 将数据产物解析为`T`
+    pub fn parse<T: DeserializeOwned>(&self) -> Result<T> {
+        match &self.artifact {
+            Artifact::Data { value } => Ok(serde_json::from_value(value.clone())?),
+            other => Err(Error::InvalidInput(format!(
+                "Artifact '{}' is a {}, not data", self.name, other.kind()
+            ))),
+        }
+    }
+}
+
+/// 变更类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlackboardChangeKind {
+    /// 新建产物
+    Created,
+    /// 写入新版本
+    Updated,
+    /// 删除产物
+    Removed,
+}
+
+/// 变更通知
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlackboardChange {
+    /// 产物名称
+    pub name: String,
+    /// 变更后的版本，删除时为最后的版本
+    pub version: u64,
+    /// 变更类型
+    pub kind: BlackboardChangeKind,
+    /// 写入者
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+}
+
+/// 协作Agent共享的产物存储
+pub struct Blackboard {
+    artifacts: RwLock<HashMap<String, Vec<ArtifactVersion>>>,
+    changes: broadcast::Sender<BlackboardChange>,
+    event_bus: Option<Arc<EventBus>>,
+}
+
+impl Default for Blackboard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for Blackboard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Blackboard").finish_non_exhaustive()
+    }
+}
+
+impl Blackboard {
+    /// 创建空黑板
+    pub fn new() -> Self {
+        let (changes, _) = broadcast::channel(CHANGE_CHANNEL_CAPACITY);
+        Self {
+            artifacts: RwLock::new(HashMap::new()),
+            changes,
+            event_bus: None,
+        }
+    }
+
+    /// 同时将变更发布到事件总线
+    pub fn with_event_bus(mut self, event_bus: Arc<EventBus>) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+
+    /// 订阅变更通知
+    pub fn subscribe(&self) -> broadcast::Receiver<BlackboardChange> {
+        self.changes.subscribe()
+    }
+
+    /// 写入产物的新版本，返回版本号
+    pub async fn write(&self, name: &str, artifact: Artifact, author: Option<&str>) -> Result<u64> {
+        self.write_version(name, artifact, author, None).await
+    }
+
+    /// 仅当产物当前版本为`expected_version`时写入，不存在的产物版本为0
+    ///
+    /// 版本不符时返回[`Error::InvalidState`]，调用方应重新读取后再写入。
+    pub async fn write_if_version(
+        &self,
+        name: &str,
+        artifact: Artifact,
+        author: Option<&str>,
+        expected_version: u64,
+    ) -> Result<u64> {
+        self.write_version(name, artifact, author, Some(expected_version)).await
+    }
+
+    async fn write_version(
+        &self,
+        name: &str,
+        artifact: Artifact,
+        author: Option<&str>,
+        expected_version: Option<u64>,
+    ) -> Result<u64> {
+        if name.trim().is_empty() {
+            return Err(Error::InvalidInput("Artifact name must not be empty".to_string()));
+        }
+        let change = {
+            let mut artifacts = self.artifacts.write().await;
+            let current = artifacts.get(name)
+                .and_then(|versions| versions.last())
+                .map_or(0, |latest| latest.version);
+            if let Some(expected) = expected_version {
+                if expected != current {
+                    return Err(Error::InvalidState(format!(
+                        "Artifact '{}' is at version {}, expected {}", name, current, expected
+                    )));
+                }
+            }
+            let version = current + 1;
+            artifacts.entry(name.to_string()).or_default().push(ArtifactVersion {
+                name: name.to_string(),
+                version,
+                artifact,
+                author: author.map(str::to_string),
+                updated_at: Utc::now(),
+            });
+            BlackboardChange {
+                name: name.to_string(),
+                version,
+                kind: if version == 1 { BlackboardChangeKind::Created } else { BlackboardChangeKind::Updated },
+                author: author.map(str::to_string),
+            }
+        };
+        let version = change.version;
+        self.notify(change).await;
+        Ok(version)
+    }
+
+    /// 产物的最新版本
+    pub async fn read(&self, name: &str) -> Option<ArtifactVersion> {
+        let artifacts = self.artifacts.read().await;
+        artifacts.get(name).and_then(|versions| versions.last()).cloned()
+    }
+
+    /// 产物的指定版本
+    pub async fn read_version(&self, name: &str, version: u64) -> Option<ArtifactVersion> {
+        let artifacts = self.artifacts.read().await;
+        artifacts.get(name)
+            .and_then(|versions| versions.iter().find(|entry| entry.version == version))
+            .cloned()
+    }
+
+    /// 将数据产物的最新版本解析为`T`
+    pub async fn read_data<T: DeserializeOwned>(&self, name: &str) -> Result<T> {
+        let latest = self.read(name).await
+            .ok_or_else(|| Error::NotFound(format!("Artifact '{}' not found", name)))?;
+        latest.parse()
+    }
+
+    /// 产物的全部版本，从旧到新
+    pub async fn history(&self, name: &str) -> Vec<ArtifactVersion> {
+        let artifacts = self.artifacts.read().await;
+        artifacts.get(name).cloned().unwrap_or_default()
+    }
+
+    /// 所有产物名称，按名称排序
+    pub async fn names(&self) -> Vec<String> {
+        let artifacts = self.artifacts.read().await;
+        let mut names: Vec<String> = artifacts.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// 所有产物的最新版本，按名称排序
+    pub async fn snapshot(&self) -> Vec<ArtifactVersion> {
+        let artifacts = self.artifacts.read().await;
+        let mut latest: Vec<ArtifactVersion> = artifacts.values()
+            .filter_map(|versions| versions.last().cloned())
+            .collect();
+        latest.sort_by(|a, b| a.name.cmp(&b.name));
+        latest
+    }
+
+    /// 删除产物及其历史，返回是否存在
+    pub async fn remove(&self, name: &str, author: Option<&str>) -> bool {
+        let removed = {
+            let mut artifacts = self.artifacts.write().await;
+            artifacts.remove(name)
+        };
+        let Some(last) = removed.and_then(|versions| versions.last().map(|latest| latest.version)) else {
+            return false;
+        };
+        self.notify(BlackboardChange {
+            name: name.to_string(),
+            version: last,
+            kind: BlackboardChangeKind::Removed,
+            author: author.map(str::to_string),
+        }).await;
+        true
+    }
+
+    /// 以文本列出所有产物，作为Agent的上下文；黑板为空时返回`None`
+    pub async fn render(&self) -> Option<String> {
+        let snapshot = self.snapshot().await;
+        if snapshot.is_empty() {
+            return None;
+        }
+        let mut text = String::from("Shared artifacts from other agents:");
+        for entry in snapshot {
+            let mut content = entry.artifact.render();
+            if let Some((end, _)) = content.char_indices().nth(MAX_RENDERED_CHARS) {
+                content.truncate(end);
+                content.push('…');
+            }
+            text.push_str(&format!(
+                "\n\n## {} ({}, v{}{})\n{}",
+                entry.name,
+                entry.artifact.kind(),
+                entry.version,
+                entry.author.map(|author| format!(", by {}", author)).unwrap_or_default(),
+                content
+            ));
+        }
+        Some(text)
+    }
+
+    async fn notify(&self, change: BlackboardChange) {
+        if let Some(event_bus) = &self.event_bus {
+            let event = AgentEvent::Custom {
+                event_name: "blackboard_changed".to_string(),
+                data: serde_json::to_value(&change).unwrap_or_default(),
+                timestamp: Utc::now(),
+            };
+            if let Err(e) = event_bus.publish(event).await {
+                tracing::warn!("Failed to publish blackboard change: {}", e);
+            }
+        }
+        // 没有订阅者时发送失败，可以忽略
+        let _ = self.changes.send(change);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_versions_and_notifications() {
+        let blackboard = Blackboard::new();
+        let mut changes = blackboard.subscribe();
+
+        assert_eq!(blackboard.write("plan", Artifact::document("Draft"), Some("planner")).await.unwrap(), 1);
+        assert_eq!(blackboard.write("plan", Artifact::document("Final"), Some("editor")).await.unwrap(), 2);
+        assert_eq!(blackboard.read("plan").await.unwrap().artifact, Artifact::document("Final"));
+        assert_eq!(blackboard.read_version("plan", 1).await.unwrap().author.as_deref(), Some("planner"));
+        assert_eq!(blackboard.history("plan").await.len(), 2);
+
+        let stale = blackboard.write_if_version("plan", Artifact::document("Late"), None, 1).await;
+        assert!(matches!(stale, Err(Error::InvalidState(_))));
+        assert_eq!(blackboard.write_if_version("plan", Artifact::document("Next"), None, 2).await.unwrap(), 3);
+
+        assert_eq!(changes.recv().await.unwrap().kind, BlackboardChangeKind::Created);
+        assert_eq!(changes.recv().await.unwrap().kind, BlackboardChangeKind::Updated);
+        assert_eq!(changes.recv().await.unwrap().version, 3);
+
+        assert!(blackboard.remove("plan", None).await);
+        assert_eq!(changes.recv().await.unwrap().kind, BlackboardChangeKind::Removed);
+        assert!(blackboard.read("plan").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_typed_artifacts() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Budget {
+            total: u32,
+        }
+
+        let blackboard = Blackboard::new();
+        blackboard.write("budget", Artifact::data(&Budget { total: 1200 }).unwrap(), None).await.unwrap();
+        assert_eq!(blackboard.read_data::<Budget>("budget").await.unwrap(), Budget { total: 1200 });
+        assert!(matches!(blackboard.read_data::<Budget>("missing").await, Err(Error::NotFound(_))));
+
+        let table = Artifact::table(vec!["city".to_string(), "nights".to_string()], vec![vec![json!("Paris"), json!(3)]]).unwrap();
+        blackboard.write("itinerary", table, Some("planner")).await.unwrap();
+        assert!(Artifact::table(vec!["city".to_string()], vec![vec![]]).is_err());
+
+        let rendered = blackboard.render().await.unwrap();
+        assert!(rendered.contains("## itinerary (table, v1, by planner)\n| city | nights |\n| Paris | 3 |"));
+        assert!(rendered.contains("## budget (data, v1)\n{\"total\":1200}"));
+    }
+}
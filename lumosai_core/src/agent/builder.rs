@@ -10,10 +10,16 @@ use serde_json::Value;
 use crate::{Result, Error};
 use crate::llm::LlmProvider;
 use crate::tool::{Tool, ToolExecutionContext, ToolExecutionOptions};
-use crate::memory::{MemoryConfig, WorkingMemoryConfig};
-use super::{AgentConfig, BasicAgent, ModelResolver};
+use crate::memory::{Memory, MemoryConfig, WorkingMemoryConfig};
+use crate::config::GuardrailsConfig;
+use super::{AgentConfig, BasicAgent, ModelResolver, RagBinding};
 use super::trait_def::Agent;
 use super::types::{VoiceConfig, TelemetrySettings};
+use super::reflection::ReflectionConfig;
+use super::few_shot::FewShotConfig;
+use super::output_processor::OutputProcessor;
+use super::review::ReviewQueue;
+use super::tools_memory::{ToolsMemory, ToolsMemoryConfig};
 use crate::base::Base;
 use async_trait::async_trait;
 
@@ -120,7 +126,16 @@ pub struct AgentBuilder {
     metadata: Option<HashMap<String, String>>,
     max_tool_calls: Option<u32>,
     tool_timeout: Option<u64>,
+    reflection: Option<ReflectionConfig>,
+    few_shot: Option<FewShotConfig>,
+    output_processors: Vec<Arc<dyn OutputProcessor>>,
+    review_queue: Option<Arc<ReviewQueue>>,
+    output_schema: Option<Value>,
+    tools_memory: Option<Arc<ToolsMemory>>,
     tools: Vec<Box<dyn Tool>>,
+    guardrails: Option<GuardrailsConfig>,
+    memory: Option<Arc<dyn Memory>>,
+    rag: Option<RagBinding>,
     smart_defaults: bool,
     model_resolver: Option<ModelResolver>, // Model resolver for string names
 }
@@ -151,7 +166,16 @@ impl AgentBuilder {
             metadata: None,
             max_tool_calls: None,
             tool_timeout: None,
+            reflection: None,
+            few_shot: None,
+            output_processors: Vec::new(),
+            review_queue: None,
+            output_schema: None,
+            tools_memory: None,
             tools: Vec::new(),
+            guardrails: None,
+            memory: None,
+            rag: None,
             smart_defaults: false,
             model_resolver: None,
         }
@@ -285,6 +309,68 @@ impl AgentBuilder {
         self
     }
 
+    /// Enable the self-reflection loop that critiques and revises the final answer
+    pub fn reflection(mut self, reflection: ReflectionConfig) -> Self {
+        self.reflection = Some(reflection);
+        self
+    }
+
+    /// Inject the most similar few-shot examples into each request
+    pub fn few_shot(mut self, few_shot: FewShotConfig) -> Self {
+        self.few_shot = Some(few_shot);
+        self
+    }
+
+    /// Append a processor run on the final response before it is returned
+    pub fn output_processor(mut self, processor: impl OutputProcessor + 'static) -> Self {
+        self.output_processors.push(Arc::new(processor));
+        self
+    }
+
+    /// Flag answers the reflection judge scores below the queue's threshold for human review
+    pub fn review_queue(mut self, queue: Arc<ReviewQueue>) -> Self {
+        self.review_queue = Some(queue);
+        self
+    }
+
+    /// JSON schema structured output must match, included in the structured-output instructions
+    pub fn output_schema(mut self, schema: Value) -> Self {
+        self.output_schema = Some(schema);
+        self
+    }
+
+    /// Remember a compact summary of tool results per session and show it on later turns,
+    /// so the agent does not fetch the same data twice
+    pub fn tools_memory(mut self, config: ToolsMemoryConfig) -> Self {
+        self.tools_memory = Some(Arc::new(ToolsMemory::new(config)));
+        self
+    }
+
+    /// Share a tools memory with other agents
+    pub fn shared_tools_memory(mut self, memory: Arc<ToolsMemory>) -> Self {
+        self.tools_memory = Some(memory);
+        self
+    }
+
+    /// Apply guardrails: topic and rule guardrails are appended to the instructions,
+    /// and `max_tool_calls` is used unless set explicitly
+    pub fn guardrails(mut self, guardrails: GuardrailsConfig) -> Self {
+        self.guardrails = Some(guardrails);
+        self
+    }
+
+    /// Use the given memory instead of one created from the memory config
+    pub fn memory(mut self, memory: Arc<dyn Memory>) -> Self {
+        self.memory = Some(memory);
+        self
+    }
+
+    /// Bind a vector store collection; the agent gets a retrieval tool over it
+    pub fn rag(mut self, binding: RagBinding) -> Self {
+        self.rag = Some(binding);
+        self
+    }
+
     /// Add a tool to the agent
     pub fn tool(mut self, tool: Box<dyn Tool>) -> Self {
         self.tools.push(tool);
@@ -384,6 +470,13 @@ impl AgentBuilder {
 
         let model = self.model.ok_or_else(|| Error::Configuration("Agent model is required".to_string()))?;
 
+        let instructions = match self.guardrails.as_ref().and_then(|g| g.instructions()) {
+            Some(section) => format!("{}\n\n{}", instructions, section),
+            None => instructions,
+        };
+        let max_tool_calls = self.max_tool_calls
+            .or_else(|| self.guardrails.as_ref().and_then(|g| g.max_tool_calls));
+
         // Create config
         let config = AgentConfig {
             name,
@@ -396,17 +489,37 @@ impl AgentBuilder {
             enable_function_calling: self.enable_function_calling.or(Some(true)),
             context: self.context,
             metadata: self.metadata,
-            max_tool_calls: self.max_tool_calls.or(Some(10)),
+            max_tool_calls: max_tool_calls.or(Some(10)),
             tool_timeout: self.tool_timeout.or(Some(30)),
+            reflection: self.reflection,
+            few_shot: self.few_shot,
         };
 
         // Create agent
-        let mut agent = BasicAgent::new(config, model);
+        let mut agent = BasicAgent::new(config, model.clone());
+        if let Some(memory) = self.memory {
+            agent = agent.with_memory(memory);
+        }
+        for processor in self.output_processors {
+            agent = agent.with_output_processor(processor);
+        }
+        if let Some(queue) = self.review_queue {
+            agent = agent.with_review_queue(queue);
+        }
+        if let Some(schema) = self.output_schema {
+            agent = agent.with_output_schema(schema);
+        }
+        if let Some(memory) = self.tools_memory {
+            agent = agent.with_tools_memory(memory);
+        }
 
         // Add tools
         for tool in self.tools {
             agent.add_tool(tool)?;
         }
+        if let Some(binding) = self.rag {
+            agent.add_tool(binding.into_tool(model))?;
+        }
 
         Ok(agent)
     }
@@ -432,6 +545,13 @@ impl AgentBuilder {
             return Err(Error::Configuration("Either model or model_name is required".to_string()));
         };
 
+        let instructions = match self.guardrails.as_ref().and_then(|g| g.instructions()) {
+            Some(section) => format!("{}\n\n{}", instructions, section),
+            None => instructions,
+        };
+        let max_tool_calls = self.max_tool_calls
+            .or_else(|| self.guardrails.as_ref().and_then(|g| g.max_tool_calls));
+
         // Create config
         let config = AgentConfig {
             name,
@@ -444,17 +564,37 @@ impl AgentBuilder {
             enable_function_calling: self.enable_function_calling.or(Some(true)),
             context: self.context,
             metadata: self.metadata,
-            max_tool_calls: self.max_tool_calls.or(Some(10)),
+            max_tool_calls: max_tool_calls.or(Some(10)),
             tool_timeout: self.tool_timeout.or(Some(30)),
+            reflection: self.reflection,
+            few_shot: self.few_shot,
         };
 
         // Create agent
-        let mut agent = BasicAgent::new(config, model);
+        let mut agent = BasicAgent::new(config, model.clone());
+        if let Some(memory) = self.memory {
+            agent = agent.with_memory(memory);
+        }
+        for processor in self.output_processors {
+            agent = agent.with_output_processor(processor);
+        }
+        if let Some(queue) = self.review_queue {
+            agent = agent.with_review_queue(queue);
+        }
+        if let Some(schema) = self.output_schema {
+            agent = agent.with_output_schema(schema);
+        }
+        if let Some(memory) = self.tools_memory {
+            agent = agent.with_tools_memory(memory);
+        }
 
         // Add tools
         for tool in self.tools {
             agent.add_tool(tool)?;
         }
+        if let Some(binding) = self.rag {
+            agent.add_tool(binding.into_tool(model))?;
+        }
 
         Ok(agent)
     }
@@ -5,6 +5,8 @@ use crate::memory::MemoryConfig;
 use crate::memory::WorkingMemoryConfig;
 use crate::llm::{LlmOptions, Message};
 use crate::agent::types::{VoiceConfig, TelemetrySettings};
+use crate::agent::reflection::ReflectionConfig;
+use crate::agent::few_shot::FewShotConfig;
 
 /// Configuration for an agent
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -43,6 +45,12 @@ pub struct AgentConfig {
     /// Tool execution timeout in seconds
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tool_timeout: Option<u64>,
+    /// Self-reflection loop that critiques and revises the final answer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reflection: Option<ReflectionConfig>,
+    /// Few-shot examples injected into the prompt by similarity to the request
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub few_shot: Option<FewShotConfig>,
 }
 
 impl Default for AgentConfig {
@@ -60,6 +68,8 @@ impl Default for AgentConfig {
             metadata: None,
             max_tool_calls: Some(10),
             tool_timeout: Some(30),
+            reflection: None,
+            few_shot: None,
         }
     }
 }
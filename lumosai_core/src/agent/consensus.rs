@@ -0,0 +1,264 @@
+//! 辩论共识编排
+//!
+//! [`OrchestrationPattern::Consensus`](super::orchestration::OrchestrationPattern::Consensus)
+//! 的执行过程：各Agent先独立作答，随后进行若干轮互评，每轮看到其他Agent的最新答案并给出
+//! 修订后的答案；最后由投票或评判Agent选出最终答案。每轮的发言记录在[`ConsensusRound`]中，
+//! 便于检查Agent如何达成结论。
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use futures::future::join_all;
+use serde::{Deserialize, Serialize};
+
+use super::orchestration::VotingStrategy;
+use super::trait_def::Agent;
+use super::types::AgentGenerateOptions;
+use crate::error::{Error, Result};
+use crate::llm::{Message, Role};
+
+/// 选出最终答案的方式
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ConsensusSelection {
+    /// 参与的Agent按策略投票
+    Vote(VotingStrategy),
+    /// 由指定Agent评判，该Agent可以不参与辩论
+    Judge(String),
+}
+
+/// 一轮中的阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsensusStage {
+    /// 独立作答
+    Answer,
+    /// 互评并修订答案
+    Critique,
+    /// 投票或评判
+    Selection,
+}
+
+/// 一个Agent在一轮中的发言
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConsensusEntry {
+    /// 发言的Agent
+    pub agent_id: String,
+    /// 发言内容
+    pub content: String,
+    /// 选择的答案序号（从1开始），仅投票和评判阶段
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub choice: Option<usize>,
+}
+
+/// 一轮的发言记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConsensusRound {
+    /// 轮次，独立作答为第0轮
+    pub round: usize,
+    /// 阶段
+    pub stage: ConsensusStage,
+    /// 各Agent的发言
+    pub entries: Vec<ConsensusEntry>,
+}
+
+/// 共识结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConsensusOutcome {
+    /// 最终答案
+    pub answer: String,
+    /// 给出最终答案的Agent
+    pub selected: String,
+    /// 各答案得到的票数（加权投票时为权重之和），评判时为空
+    pub votes: HashMap<String, f32>,
+    /// 是否满足投票策略：多数决要求超过半数，一致同意要求全部相同；评判时为评判是否给出有效编号
+    pub reached: bool,
+    /// 各轮发言记录
+    pub rounds: Vec<ConsensusRound>,
+}
+
+/// 执行辩论共识，`participants`为参与辩论的Agent，按ID顺序编号
+pub(crate) async fn run_consensus(
+    participants: &[(String, Arc<dyn Agent>)],
+    judge: Option<(String, Arc<dyn Agent>)>,
+    question: &str,
+    rounds: usize,
+    selection: &ConsensusSelection,
+) -> Result<ConsensusOutcome> {
+    if participants.is_empty() {
+        return Err(Error::InvalidInput("Consensus needs at least one participant".to_string()));
+    }
+    let mut transcript = Vec::new();
+
+    // 第0轮：独立作答
+    let answers = join_all(participants.iter().map(|(_, agent)| ask(agent.as_ref(), question.to_string()))).await;
+    let mut answers = answers.into_iter().collect::<Result<Vec<String>>>()?;
+    transcript.push(round_of(0, ConsensusStage::Answer, participants, &answers, None));
+
+    // 互评：每个Agent看到其他Agent的最新答案后修订自己的答案
+    for round in 1..=rounds {
+        let prompts = participants.iter().enumerate().map(|(index, _)| critique_prompt(question, &answers, index));
+        let revised = join_all(participants.iter().zip(prompts).map(|((_, agent), prompt)| ask(agent.as_ref(), prompt))).await;
+        answers = revised.into_iter().collect::<Result<Vec<String>>>()?;
+        transcript.push(round_of(round, ConsensusStage::Critique, participants, &answers, None));
+    }
+
+    let selection_round = rounds + 1;
+    let ballot = ballot_prompt(question, &answers);
+    let (winner, votes, reached) = match selection {
+        ConsensusSelection::Judge(judge_id) => {
+            let (_, judge) = judge
+                .ok_or_else(|| Error::NotFound(format!("Judge agent '{}' not found", judge_id)))?;
+            let reply = ask(judge.as_ref(), ballot).await?;
+            let choice = parse_choice(&reply, answers.len());
+            transcript.push(ConsensusRound {
+                round: selection_round,
+                stage: ConsensusStage::Selection,
+                entries: vec![ConsensusEntry { agent_id: judge_id.clone(), content: reply, choice: choice.map(|index| index + 1) }],
+            });
+            (choice.unwrap_or(0), HashMap::new(), choice.is_some())
+        }
+        ConsensusSelection::Vote(strategy) => {
+            let replies = join_all(participants.iter().map(|(_, agent)| ask(agent.as_ref(), ballot.clone()))).await;
+            let replies = replies.into_iter().collect::<Result<Vec<String>>>()?;
+            let choices: Vec<Option<usize>> = replies.iter().map(|reply| parse_choice(reply, answers.len())).collect();
+            transcript.push(round_of(selection_round, ConsensusStage::Selection, participants, &replies, Some(&choices)));
+            tally(participants, &choices, strategy)
+        }
+    };
+
+    let votes = votes.into_iter()
+        .map(|(index, weight)| (participants[index].0.clone(), weight))
+        .collect();
+    Ok(ConsensusOutcome {
+        answer: answers[winner].clone(),
+        selected: participants[winner].0.clone(),
+        votes,
+        reached,
+        rounds: transcript,
+    })
+}
+
+/// 统计选票，返回胜出的答案序号、各答案得票和是否满足策略；平票时序号小的胜出
+fn tally(
+    participants: &[(String, Arc<dyn Agent>)],
+    choices: &[Option<usize>],
+    strategy: &VotingStrategy,
+) -> (usize, HashMap<usize, f32>, bool) {
+    let mut votes: HashMap<usize, f32> = HashMap::new();
+    let mut total = 0.0;
+    for ((agent_id, _), choice) in participants.iter().zip(choices) {
+        let weight = match strategy {
+            VotingStrategy::Weighted(weights) => weights.get(agent_id).copied().unwrap_or(1.0),
+            VotingStrategy::Majority | VotingStrategy::Unanimous => 1.0,
+        };
+        total += weight;
+        if let Some(choice) = choice {
+            *votes.entry(*choice).or_default() += weight;
+        }
+    }
+
+    let winner = votes.iter()
+        .max_by(|a, b| a.1.total_cmp(b.1).then(b.0.cmp(a.0)))
+        .map_or(0, |(index, _)| *index);
+    let winning = votes.get(&winner).copied().unwrap_or_default();
+    let reached = match strategy {
+        VotingStrategy::Unanimous => winning > 0.0 && winning >= total,
+        VotingStrategy::Majority | VotingStrategy::Weighted(_) => winning * 2.0 > total,
+    };
+    (winner, votes, reached)
+}
+
+async fn ask(agent: &dyn Agent, prompt: String) -> Result<String> {
+    let messages = [Message {
+        role: Role::User,
+        content: prompt,
+        metadata: None,
+        name: None,
+    }];
+    let result = agent.generate(&messages, &AgentGenerateOptions::default()).await?;
+    Ok(result.response)
+}
+
+fn round_of(
+    round: usize,
+    stage: ConsensusStage,
+    participants: &[(String, Arc<dyn Agent>)],
+    contents: &[String],
+    choices: Option<&[Option<usize>]>,
+) -> ConsensusRound {
+    let entries = participants.iter().zip(contents).enumerate()
+        .map(|(index, ((agent_id, _), content))| ConsensusEntry {
+            agent_id: agent_id.clone(),
+            content: content.clone(),
+            choice: choices.and_then(|choices| choices[index]).map(|choice| choice + 1),
+        })
+        .collect();
+    ConsensusRound { round, stage, entries }
+}
+
+fn critique_prompt(question: &str, answers: &[String], own: usize) -> String {
+    let mut prompt = format!("Question: {}\n\nYour previous answer:\n{}\n\nAnswers from other agents:", question, answers[own]);
+    for (index, answer) in answers.iter().enumerate().filter(|(index, _)| *index != own) {
+        prompt.push_str(&format!("\n\n[{}]\n{}", index + 1, answer));
+    }
+    prompt.push_str(
+        "\n\nCritique the other answers, point out mistakes in any of them including yours, \
+         then give your revised answer to the question.",
+    );
+    prompt
+}
+
+fn ballot_prompt(question: &str, answers: &[String]) -> String {
+    let mut prompt = format!("Question: {}\n\nCandidate answers:", question);
+    for (index, answer) in answers.iter().enumerate() {
+        prompt.push_str(&format!("\n\n[{}]\n{}", index + 1, answer));
+    }
+    prompt.push_str("\n\nWhich answer is the most accurate and complete? Reply with its number first, then a short reason.");
+    prompt
+}
+
+/// 回复中第一个有效的答案编号，转换为从0开始的序号
+fn parse_choice(reply: &str, candidates: usize) -> Option<usize> {
+    reply.split(|c: char| !c.is_ascii_digit())
+        .filter_map(|number| number.parse::<usize>().ok())
+        .find(|number| (1..=candidates).contains(number))
+        .map(|number| number - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_choice() {
+        assert_eq!(parse_choice("[2] is best because it cites sources", 3), Some(1));
+        assert_eq!(parse_choice("Answer 7 is wrong, I pick 3", 3), Some(2));
+        assert_eq!(parse_choice("None of them", 3), None);
+    }
+
+    #[test]
+    fn test_tally() {
+        let agent: Arc<dyn Agent> = Arc::new(crate::agent::create_basic_agent(
+            "a",
+            "Vote",
+            Arc::new(crate::llm::MockLlmProvider::new(Vec::new())),
+        ));
+        let participants: Vec<(String, Arc<dyn Agent>)> = ["a", "b", "c"].iter()
+            .map(|id| (id.to_string(), agent.clone()))
+            .collect();
+
+        let (winner, votes, reached) = tally(&participants, &[Some(1), Some(1), Some(0)], &VotingStrategy::Majority);
+        assert_eq!((winner, votes[&1], reached), (1, 2.0, true));
+
+        let (_, _, reached) = tally(&participants, &[Some(1), Some(1), Some(0)], &VotingStrategy::Unanimous);
+        assert!(!reached);
+
+        let weights = HashMap::from([("c".to_string(), 5.0)]);
+        let (winner, _, reached) = tally(&participants, &[Some(1), Some(1), Some(0)], &VotingStrategy::Weighted(weights));
+        assert_eq!((winner, reached), (0, true));
+
+        // 平票时序号小的胜出
+        let (winner, _, reached) = tally(&participants, &[Some(2), Some(0), None], &VotingStrategy::Majority);
+        assert_eq!((winner, reached), (0, false));
+    }
+}
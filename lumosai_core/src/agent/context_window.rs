@@ -0,0 +1,396 @@
+//! 上下文窗口管理
+//!
+//! 在组装提示词时保证消息总token数不超过模型的上下文窗口（扣除为输出预留的token）。
+//! 超出时由可插拔的截断策略决定保留哪些消息：
+//!
+//! - [`DropOldest`]：从最早的消息开始丢弃；
+//! - [`Summarize`]：用LLM把较早的消息压缩为一条摘要；
+//! - [`ImportanceRanked`]：按重要性评分丢弃最不重要的消息。
+//!
+//! 所有策略都会保留系统消息和最后一条消息，并且不会留下失去对应调用的工具结果消息。
+//! 运行时上下文的管理见 [`super::runtime_context::ContextManager`]。
+
+use std::sync::Arc;
+use async_trait::async_trait;
+
+use crate::error::{Error, Result};
+use crate::llm::{LlmOptions, LlmProvider, Message, Role};
+use crate::llm::tokenizer::{tokenizer_for_model, HeuristicTokenizer, Tokenizer};
+
+/// 消息元数据中表示重要性的字段，取值0.0到1.0
+pub const IMPORTANCE_METADATA_KEY: &str = "importance";
+
+/// 消息元数据中标记为必须保留的字段
+pub const PINNED_METADATA_KEY: &str = "pinned";
+
+/// 常见模型的上下文窗口大小，未知模型返回8192
+pub fn context_window_for_model(model: &str) -> usize {
+    let model = model.to_ascii_lowercase();
+    let table: &[(&str, usize)] = &[
+        ("gpt-4o", 128_000),
+        ("gpt-4.1", 1_047_576),
+        ("gpt-4-turbo", 128_000),
+        ("gpt-4-32k", 32_768),
+        ("gpt-4", 8_192),
+        ("gpt-3.5-turbo", 16_385),
+        ("o1", 200_000),
+        ("o3", 200_000),
+        ("claude", 200_000),
+        ("deepseek", 64_000),
+        ("qwen", 32_768),
+        ("glm-4", 128_000),
+        ("moonshot-v1-128k", 128_000),
+        ("moonshot-v1-32k", 32_768),
+        ("moonshot", 8_192),
+        ("llama-3.1", 128_000),
+        ("llama3", 8_192),
+        ("llama-3", 8_192),
+        ("mistral", 32_768),
+        ("gemini", 1_000_000),
+    ];
+    table.iter()
+        .find(|(prefix, _)| model.starts_with(prefix) || model.contains(&format!("/{}", prefix)))
+        .map(|(_, window)| *window)
+        .unwrap_or(8_192)
+}
+
+/// 截断策略
+#[async_trait]
+pub trait TruncationStrategy: Send + Sync {
+    /// 策略名称
+    fn name(&self) -> &str;
+
+    /// 调整消息使总token数不超过 `budget`
+    ///
+    /// 返回的消息可能仍超出预算（如系统消息本身过长），由调用方检查。
+    async fn truncate(&self, messages: Vec<Message>, budget: usize, tokenizer: &dyn Tokenizer) -> Result<Vec<Message>>;
+}
+
+/// 消息是否必须保留：系统消息、标记为pinned的消息和最后一条消息
+fn is_protected(messages: &[Message], index: usize) -> bool {
+    let message = &messages[index];
+    message.role == Role::System
+        || index + 1 == messages.len()
+        || message.metadata.as_ref()
+            .and_then(|metadata| metadata.get(PINNED_METADATA_KEY))
+            .and_then(|value| value.as_bool())
+            .unwrap_or(false)
+}
+
+/// 移除指定位置的消息及其后紧随的工具结果消息
+fn remove_with_tool_results(messages: &mut Vec<Message>, index: usize) {
+    messages.remove(index);
+    while index < messages.len() - 1 && matches!(messages[index].role, Role::Tool | Role::Function) {
+        messages.remove(index);
+    }
+}
+
+/// 从最早的消息开始丢弃
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DropOldest;
+
+#[async_trait]
+impl TruncationStrategy for DropOldest {
+    fn name(&self) -> &str {
+        "drop_oldest"
+    }
+
+    async fn truncate(&self, mut messages: Vec<Message>, budget: usize, tokenizer: &dyn Tokenizer) -> Result<Vec<Message>> {
+        while tokenizer.count_messages(&messages) > budget {
+            let Some(index) = (0..messages.len()).find(|&i| !is_protected(&messages, i)) else {
+                break;
+            };
+            remove_with_tool_results(&mut messages, index);
+        }
+        Ok(messages)
+    }
+}
+
+/// 用LLM将较早的消息压缩为一条摘要
+///
+/// 最近的 `keep_recent` 条消息保持原样；摘要后仍超出预算时再从最早的消息开始丢弃。
+pub struct Summarize {
+    llm: Arc<dyn LlmProvider>,
+    keep_recent: usize,
+    options: LlmOptions,
+}
+
+impl Summarize {
+    /// 创建摘要策略，默认保留最近6条消息
+    pub fn new(llm: Arc<dyn LlmProvider>) -> Self {
+        Self {
+            llm,
+            keep_recent: 6,
+            options: LlmOptions::default().with_temperature(0.0).with_max_tokens(512),
+        }
+    }
+
+    /// 设置保持原样的最近消息数
+    pub fn with_keep_recent(mut self, keep_recent: usize) -> Self {
+        self.keep_recent = keep_recent;
+        self
+    }
+
+    /// 设置摘要请求的模型参数
+    pub fn with_options(mut self, options: LlmOptions) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+#[async_trait]
+impl TruncationStrategy for Summarize {
+    fn name(&self) -> &str {
+        "summarize"
+    }
+
+    async fn truncate(&self, messages: Vec<Message>, budget: usize, tokenizer: &dyn Tokenizer) -> Result<Vec<Message>> {
+        if tokenizer.count_messages(&messages) <= budget {
+            return Ok(messages);
+        }
+
+        let split = messages.len().saturating_sub(self.keep_recent.max(1));
+        let (older, recent) = messages.split_at(split);
+        let mut kept = Vec::new();
+        let mut summarized = Vec::new();
+        for (index, message) in older.iter().enumerate() {
+            if is_protected(&messages, index) {
+                kept.push(message.clone());
+            } else {
+                summarized.push(message);
+            }
+        }
+        if summarized.is_empty() {
+            return DropOldest.truncate(messages, budget, tokenizer).await;
+        }
+
+        let transcript = summarized.iter()
+            .map(|message| format!("{}: {}", message.role.as_str(), message.content))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let prompt = format!(
+            "Summarize the following conversation so it can replace the original messages. \
+             Keep facts, decisions, open questions and user preferences; be concise.\n\n{}",
+            transcript
+        );
+        let summary = self.llm.generate(&prompt, &self.options).await?;
+
+        let mut result = kept;
+        result.push(Message::new(
+            Role::System,
+            format!("Summary of earlier conversation:\n{}", summary.trim()),
+            None,
+            None,
+        ));
+        // 摘要替换掉的消息中若有工具调用，其后的工具结果也一并移除
+        let recent_start = recent.iter()
+            .position(|message| !matches!(message.role, Role::Tool | Role::Function))
+            .unwrap_or(recent.len() - 1);
+        result.extend(recent[recent_start..].iter().cloned());
+
+        DropOldest.truncate(result, budget, tokenizer).await
+    }
+}
+
+/// 消息重要性评分
+pub type ImportanceScorer = Arc<dyn Fn(&Message, usize, usize) -> f32 + Send + Sync>;
+
+/// 按重要性评分丢弃消息
+///
+/// 默认评分综合消息位置（越新越重要）、角色和元数据中的 `importance` 字段，
+/// 可以通过 [`ImportanceRanked::with_scorer`] 替换。
+pub struct ImportanceRanked {
+    scorer: ImportanceScorer,
+}
+
+impl ImportanceRanked {
+    /// 使用默认评分创建
+    pub fn new() -> Self {
+        Self { scorer: Arc::new(default_importance) }
+    }
+
+    /// 使用自定义评分，参数为消息、位置和消息总数
+    pub fn with_scorer(scorer: impl Fn(&Message, usize, usize) -> f32 + Send + Sync + 'static) -> Self {
+        Self { scorer: Arc::new(scorer) }
+    }
+}
+
+impl Default for ImportanceRanked {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn default_importance(message: &Message, index: usize, total: usize) -> f32 {
+    let recency = (index + 1) as f32 / total.max(1) as f32;
+    let role = match message.role {
+        Role::User => 0.3,
+        Role::Assistant => 0.2,
+        Role::Tool | Role::Function => 0.1,
+        _ => 0.2,
+    };
+    let explicit = message.metadata.as_ref()
+        .and_then(|metadata| metadata.get(IMPORTANCE_METADATA_KEY))
+        .and_then(|value| value.as_f64())
+        .unwrap_or(0.0) as f32;
+    recency * 0.5 + role + explicit
+}
+
+#[async_trait]
+impl TruncationStrategy for ImportanceRanked {
+    fn name(&self) -> &str {
+        "importance_ranked"
+    }
+
+    async fn truncate(&self, mut messages: Vec<Message>, budget: usize, tokenizer: &dyn Tokenizer) -> Result<Vec<Message>> {
+        while tokenizer.count_messages(&messages) > budget {
+            let total = messages.len();
+            let least = (0..total)
+                .filter(|&i| !is_protected(&messages, i))
+                // 工具结果随对应的调用一起移除，不单独参与排序
+                .filter(|&i| !matches!(messages[i].role, Role::Tool | Role::Function))
+                .map(|i| (i, (self.scorer)(&messages[i], i, total)))
+                .min_by(|a, b| a.1.total_cmp(&b.1));
+            let Some((index, _)) = least else {
+                break;
+            };
+            remove_with_tool_results(&mut messages, index);
+        }
+        Ok(messages)
+    }
+}
+
+/// 上下文窗口管理器
+pub struct ContextWindowManager {
+    context_window: usize,
+    reserved_output: usize,
+    tokenizer: Arc<dyn Tokenizer>,
+    strategy: Arc<dyn TruncationStrategy>,
+}
+
+impl ContextWindowManager {
+    /// 按上下文窗口大小创建，默认丢弃最早的消息并为输出预留1024个token
+    pub fn new(context_window: usize) -> Self {
+        Self {
+            context_window,
+            reserved_output: 1024.min(context_window / 4),
+            tokenizer: Arc::new(HeuristicTokenizer),
+            strategy: Arc::new(DropOldest),
+        }
+    }
+
+    /// 按模型名称查找上下文窗口大小和分词器创建
+    pub fn for_model(model: &str) -> Self {
+        Self::new(context_window_for_model(model)).with_tokenizer(tokenizer_for_model(model))
+    }
+
+    /// 设置为输出预留的token数
+    pub fn with_reserved_output(mut self, reserved_output: usize) -> Self {
+        self.reserved_output = reserved_output;
+        self
+    }
+
+    /// 设置分词器
+    pub fn with_tokenizer(mut self, tokenizer: Arc<dyn Tokenizer>) -> Self {
+        self.tokenizer = tokenizer;
+        self
+    }
+
+    /// 设置截断策略
+    pub fn with_strategy(mut self, strategy: Arc<dyn TruncationStrategy>) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// 可用于输入消息的token数
+    pub fn budget(&self) -> usize {
+        self.context_window.saturating_sub(self.reserved_output)
+    }
+
+    /// 消息列表的token数
+    pub fn count(&self, messages: &[Message]) -> usize {
+        self.tokenizer.count_messages(messages)
+    }
+
+    /// 调整消息使其不超过预算
+    ///
+    /// 必须保留的消息本身已超出预算时返回 [`Error::InvalidInput`]。
+    pub async fn fit(&self, messages: Vec<Message>) -> Result<Vec<Message>> {
+        let budget = self.budget();
+        if self.count(&messages) <= budget {
+            return Ok(messages);
+        }
+        let fitted = self.strategy.truncate(messages, budget, self.tokenizer.as_ref()).await?;
+        let used = self.count(&fitted);
+        if used > budget {
+            return Err(Error::InvalidInput(format!(
+                "上下文超出模型限制：{} 个token，预算 {} 个token（策略 {}）",
+                used, budget, self.strategy.name()
+            )));
+        }
+        Ok(fitted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::MockLlmProvider;
+
+    fn conversation() -> Vec<Message> {
+        let mut messages = vec![Message::new(Role::System, "You are helpful.".to_string(), None, None)];
+        for i in 0..10 {
+            messages.push(Message::new(Role::User, format!("question {} {}", i, "x".repeat(200)), None, None));
+            messages.push(Message::new(Role::Assistant, format!("answer {} {}", i, "y".repeat(200)), None, None));
+        }
+        messages
+    }
+
+    #[tokio::test]
+    async fn test_drop_oldest_keeps_system_and_latest() {
+        let manager = ContextWindowManager::new(600).with_reserved_output(100);
+        let fitted = manager.fit(conversation()).await.unwrap();
+        assert!(manager.count(&fitted) <= 500);
+        assert_eq!(fitted[0].role, Role::System);
+        assert!(fitted.last().unwrap().content.starts_with("answer 9"));
+        assert!(fitted.len() < 21);
+
+        // 工具结果随对应的助手消息一起丢弃
+        let mut messages = conversation();
+        messages.insert(2, Message::new(Role::Tool, "tool output".to_string(), None, None));
+        let fitted = manager.fit(messages).await.unwrap();
+        assert!(fitted.iter().skip(1).take(1).all(|m| m.role != Role::Tool));
+    }
+
+    #[tokio::test]
+    async fn test_summarize_and_importance() {
+        let llm = Arc::new(MockLlmProvider::new(vec!["user asked ten questions".to_string()]));
+        let manager = ContextWindowManager::new(700)
+            .with_reserved_output(100)
+            .with_strategy(Arc::new(Summarize::new(llm).with_keep_recent(4)));
+        let fitted = manager.fit(conversation()).await.unwrap();
+        assert_eq!(fitted.len(), 6);
+        assert!(fitted[1].content.contains("user asked ten questions"));
+
+        let mut messages = conversation();
+        let mut metadata = std::collections::HashMap::new();
+        metadata.insert(IMPORTANCE_METADATA_KEY.to_string(), serde_json::json!(1.0));
+        messages[1].metadata = Some(metadata);
+        let manager = ContextWindowManager::new(600)
+            .with_reserved_output(100)
+            .with_strategy(Arc::new(ImportanceRanked::new()));
+        let fitted = manager.fit(messages).await.unwrap();
+        assert!(fitted[1].content.starts_with("question 0"));
+
+        let tiny = ContextWindowManager::new(20).with_reserved_output(0);
+        assert!(tiny.fit(conversation()).await.is_err());
+    }
+
+    #[test]
+    fn test_model_windows() {
+        assert_eq!(context_window_for_model("gpt-4o-mini"), 128_000);
+        assert_eq!(context_window_for_model("gpt-4"), 8_192);
+        assert_eq!(context_window_for_model("claude-3-5-sonnet-20241022"), 200_000);
+        assert_eq!(context_window_for_model("unknown"), 8_192);
+    }
+}
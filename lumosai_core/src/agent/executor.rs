@@ -8,11 +8,13 @@ use futures::stream::BoxStream;
 use futures::StreamExt;
 
 use regex::Regex;
+use serde::de::DeserializeOwned;
 use serde_json::{Value, Map};
 use uuid::Uuid;
 use tokio::sync::watch;
 
 use crate::base::{Base, BaseComponent, ComponentConfig};
+use crate::cancellation::{run_until_cancelled, CancellationToken};
 use crate::error::{Error, Result};
 use crate::logger::{Component, Logger};
 use crate::llm::{LlmProvider, LlmOptions, Message, Role, FunctionDefinition, ToolChoice as LlmToolChoice};
@@ -21,23 +23,36 @@ use crate::agent::trait_def::AgentStatus;
 use crate::telemetry::{TelemetrySink, MetricsCollector, TraceCollector, AgentMetrics, ExecutionContext, StepType as TraceStepType, TokenUsage as TelemetryTokenUsage, TraceStep};
 use crate::tool::{Tool, ToolExecutionOptions, ToolExecutionContext};
 use crate::llm::function_calling_utils;
+use crate::llm::tokenizer::{tokenizer_for_model, Tokenizer};
 use crate::agent::types::{
     AgentGenerateResult, 
     AgentGenerateOptions, 
     AgentStreamOptions,
     StepType,
     ToolCall,
+    ToolChoice,
     ToolResult,
     ToolResultStatus,
     TokenUsage,
     AgentStep,
 };
-use crate::agent::trait_def::Agent;
+use crate::agent::trait_def::{Agent, AgentStructuredOutput};
 use crate::voice::VoiceProvider;
 use crate::memory::{WorkingMemory, create_working_memory};
 use crate::agent::AgentConfig;
+use crate::agent::reflection::{Critique, ReflectionConfig};
+use crate::agent::trace::AgentTrace;
+use crate::agent::few_shot::{example_messages, FewShotStore};
+use crate::agent::language::{self, Language};
+use crate::agent::review::ReviewQueue;
+use crate::agent::tools_memory::ToolsMemory;
+use crate::agent::structured::{json_instructions, partial_json_stream, PartialJsonParser, PartialOutput};
+use crate::agent::output_processor::OutputProcessor;
 use crate::agent::types::{system_message, tool_message};
 
+/// Corrective retries allowed when the model ignores a required tool call
+const MAX_TOOL_CHOICE_RETRIES: usize = 2;
+
 /// Basic agent implementation
 #[allow(dead_code, clippy::borrowed_box)]
 pub struct BasicAgent {
@@ -73,6 +88,16 @@ pub struct BasicAgent {
     metrics_collector: Option<Arc<dyn MetricsCollector>>,
     /// Trace collector for execution tracing
     trace_collector: Option<Arc<dyn TraceCollector>>,
+    /// Self-reflection loop applied to the final answer
+    reflection: Option<ReflectionConfig>,
+    /// Few-shot examples injected by similarity to the request
+    few_shot: Option<FewShotStore>,
+    /// Queue receiving answers the reflection judge is not confident about
+    review_queue: Option<Arc<ReviewQueue>>,
+    /// Tool results remembered across turns of a session
+    tools_memory: Option<Arc<ToolsMemory>>,
+    /// Processors run on the final response, in order
+    output_processors: Vec<Arc<dyn OutputProcessor>>,
     /// Agent status
     status: AgentStatus,
 }
@@ -137,10 +162,150 @@ impl BasicAgent {
             telemetry: None,
             metrics_collector: None,
             trace_collector: None,
+            reflection: config.reflection,
+            few_shot: config.few_shot.map(FewShotStore::new),
+            review_queue: None,
+            tools_memory: None,
+            output_processors: Vec::new(),
             status: AgentStatus::Ready,
         }
     }
     
+    /// Replace the agent's memory, e.g. with a vector-backed semantic memory
+    pub fn with_memory(mut self, memory: Arc<dyn Memory>) -> Self {
+        self.memory = Some(memory);
+        self
+    }
+    
+    /// Critique and revise the final answer until the judge accepts it
+    pub fn with_reflection(mut self, reflection: ReflectionConfig) -> Self {
+        self.reflection = Some(reflection);
+        self
+    }
+    
+    /// Inject the most similar few-shot examples into each request
+    pub fn with_few_shot(mut self, few_shot: FewShotStore) -> Self {
+        self.few_shot = Some(few_shot);
+        self
+    }
+    
+    /// Append a processor run on the final response before it is returned
+    pub fn with_output_processor(mut self, processor: Arc<dyn OutputProcessor>) -> Self {
+        self.output_processors.push(processor);
+        self
+    }
+    
+    /// Flag answers whose final reflection score is below the queue's threshold for review
+    ///
+    /// Reviewed answers are added to the agent's few-shot examples on the next request.
+    pub fn with_review_queue(mut self, queue: Arc<ReviewQueue>) -> Self {
+        self.review_queue = Some(queue);
+        self
+    }
+
+    /// JSON schema that structured output must match
+    pub fn with_output_schema(mut self, schema: Value) -> Self {
+        self.output_schema = Some(schema);
+        self
+    }
+
+    /// Remember tool results per session and show them to the model on later turns
+    pub fn with_tools_memory(mut self, memory: Arc<ToolsMemory>) -> Self {
+        self.tools_memory = Some(memory);
+        self
+    }
+    
+    /// Few-shot examples of this agent, editable while it runs
+    pub fn few_shot(&self) -> Option<&FewShotStore> {
+        self.few_shot.as_ref()
+    }
+
+    /// Tool results this agent remembers per session
+    pub fn tools_memory(&self) -> Option<&Arc<ToolsMemory>> {
+        self.tools_memory.as_ref()
+    }
+
+    /// Session whose tool results are remembered: the thread, or the request's session
+    fn tools_memory_session(options: &AgentGenerateOptions) -> Option<String> {
+        options.thread_id.clone()
+            .or_else(|| crate::request_context::RequestContext::current().and_then(|context| context.session_id))
+    }
+    
+    /// Sources of nondeterminism a deterministic run cannot rule out
+    fn nondeterminism_warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if !self.llm.supports_seed() {
+            warnings.push(format!("Provider '{}' does not support sampling seeds, responses may vary between runs", self.llm.name()));
+        }
+        for warning in &warnings {
+            self.logger().warn(warning, None);
+        }
+        warnings
+    }
+
+    /// Critique and revise a draft answer, returning the final answer and one step per critique
+    async fn reflect(
+        &self,
+        reflection: &ReflectionConfig,
+        conversation: &[Message],
+        draft: String,
+        options: &LlmOptions,
+    ) -> Result<(String, Vec<AgentStep>)> {
+        let mut answer = draft;
+        let mut steps = Vec::new();
+
+        for iteration in 1..=reflection.max_iterations {
+            let critique_messages = reflection.critique_messages(conversation, &answer);
+            let reply = run_until_cancelled(
+                options.cancellation_token.as_ref(),
+                self.llm.generate_with_messages(&critique_messages, options),
+            ).await?;
+            let Some(critique) = Critique::parse(&reply) else {
+                self.logger().warn("Reflection judge returned no score, keeping the current answer", None);
+                break;
+            };
+
+            let accepted = critique.accepts(reflection);
+            let mut metadata = HashMap::new();
+            metadata.insert("iteration".to_string(), Value::from(iteration));
+            metadata.insert("score".to_string(), Value::from(critique.score));
+            metadata.insert("critique".to_string(), Value::from(critique.feedback.clone()));
+            metadata.insert("accepted".to_string(), Value::from(accepted));
+
+            let revised = if accepted {
+                None
+            } else {
+                let revision_messages = reflection.revision_messages(conversation, &answer, &critique);
+                Some(run_until_cancelled(
+                    options.cancellation_token.as_ref(),
+                    self.llm.generate_with_messages(&revision_messages, options),
+                ).await?)
+            };
+
+            steps.push(AgentStep {
+                id: Uuid::new_v4().to_string(),
+                step_type: StepType::Reflection,
+                input: critique_messages,
+                output: revised.clone().map(|content| Message {
+                    role: Role::Assistant,
+                    content,
+                    metadata: None,
+                    name: None,
+                }),
+                tool_calls: Vec::new(),
+                tool_results: Vec::new(),
+                metadata,
+            });
+
+            match revised {
+                Some(revised) => answer = revised,
+                None => break,
+            }
+        }
+
+        Ok((answer, steps))
+    }
+    
     /// Set metrics collector
     pub fn with_metrics_collector(mut self, collector: Arc<dyn MetricsCollector>) -> Self {
         self.metrics_collector = Some(collector);
@@ -186,7 +351,10 @@ impl BasicAgent {
         descriptions.push_str("结果: [tool execution result will appear here]\n\n");
         descriptions.push_str("Available tools:\n\n");
         
-        for tool in tools.values() {
+        // 按ID排序，保证每次运行的提示词一致
+        let mut sorted: Vec<&dyn Tool> = tools.values().map(|tool| tool.as_ref()).collect();
+        sorted.sort_by(|a, b| a.id().cmp(b.id()));
+        for tool in sorted {
             descriptions.push_str(&format!("工具ID: {}\n", tool.id()));
             descriptions.push_str(&format!("描述: {}\n", tool.description()));
             
@@ -231,11 +399,15 @@ impl BasicAgent {
         };
         
         // Generate appropriate system prompt based on mode
-        let system_content = crate::llm::function_calling_utils::generate_system_prompt(
+        let mut system_content = crate::llm::function_calling_utils::generate_system_prompt(
             instructions,
             use_function_calling,
             tool_descriptions.as_deref()
         );
+        if let Some(language) = &options.language {
+            system_content.push_str("\n\n");
+            system_content.push_str(&language::language_instruction(language));
+        }
         
         if use_function_calling {
             self.logger().debug("Using function calling mode - omitting tool format from system message", None);
@@ -341,7 +513,7 @@ async fn call_llm_with_monitoring(
     }
     
     // Make the LLM call
-    let response = llm.generate_with_messages(messages, options).await?;
+    let response = run_until_cancelled(options.cancellation_token.as_ref(), llm.generate_with_messages(messages, options)).await?;
     let execution_time = start_time.elapsed();
     
     // Update agent metrics if available
@@ -365,6 +537,15 @@ async fn call_llm_with_monitoring(
     Ok(response)
 }
 
+/// Add the tokens of one LLM call to the running usage total
+fn add_estimated_usage(usage: &mut TelemetryTokenUsage, tokenizer: &dyn Tokenizer, prompt: &[Message], completion: &str) {
+    let prompt_tokens = tokenizer.count_messages(prompt) as u32;
+    let completion_tokens = tokenizer.count_tokens(completion) as u32;
+    usage.prompt_tokens += prompt_tokens;
+    usage.completion_tokens += completion_tokens;
+    usage.total_tokens += prompt_tokens + completion_tokens;
+}
+
 impl Base for BasicAgent {
     fn name(&self) -> Option<&str> {
         self.base.name()
@@ -661,7 +842,7 @@ impl Agent for BasicAgent {
 
 
 
-    async fn execute_tool_call(&self, tool_call: &ToolCall) -> Result<Value> {
+    async fn execute_tool_call(&self, tool_call: &ToolCall, cancellation: Option<&CancellationToken>) -> Result<Value> {
         let start_time = std::time::Instant::now();
         
         // First get a clone of the tool to avoid holding the lock across await
@@ -688,13 +869,16 @@ impl Agent for BasicAgent {
             .map_err(|e| Error::Json(e))?;
         
         // Create execution context and options
-        let context = ToolExecutionContext::new()
+        let mut context = ToolExecutionContext::new()
             .with_tool_call_id(tool_call.id.clone());
+        if let Some(token) = cancellation {
+            context = context.with_cancellation_token(token.clone());
+        }
         
         let options = ToolExecutionOptions::default();
         
-        // Execute tool and record metrics
-        let result = tool_clone.execute(args_value.clone(), context, &options).await;
+        // Execute tool and record metrics; cancelling drops the tool future and its subprocesses
+        let result = run_until_cancelled(cancellation, tool_clone.execute(args_value.clone(), context, &options)).await;
         let execution_time = start_time.elapsed();
         
         // Record tool metrics regardless of success/failure
@@ -784,11 +968,44 @@ impl Agent for BasicAgent {
         messages: &[Message],
         options: &AgentGenerateOptions
     ) -> Result<AgentGenerateResult> {
+        let resolved = options.resolved();
+        let options = resolved.as_ref();
+        let nondeterminism_warnings = if options.deterministic {
+            self.nondeterminism_warnings()
+        } else {
+            Vec::new()
+        };
         let mut steps = Vec::new();
         let mut all_messages = self.format_messages(messages, options);
+        let query = messages.iter().rev()
+            .find(|message| message.role == Role::User)
+            .map(|message| message.content.as_str())
+            .unwrap_or_default();
+        if let Some(few_shot) = &self.few_shot {
+            if let Some(queue) = &self.review_queue {
+                queue.apply_to_few_shot(&self.name, few_shot);
+            }
+            // Examples go right after the system message, before any context and the conversation
+            let selected = if options.deterministic {
+                few_shot.select_stable(self.llm.as_ref(), query).await
+            } else {
+                few_shot.select(self.llm.as_ref(), query).await
+            };
+            let examples = example_messages(&selected);
+            let position = all_messages.iter().take_while(|message| message.role == Role::System).count();
+            all_messages.splice(position..position, examples);
+        }
+        let tools_memory_session = Self::tools_memory_session(options);
+        if let (Some(memory), Some(session)) = (&self.tools_memory, &tools_memory_session) {
+            if let Some(message) = memory.context_message(session) {
+                let position = all_messages.iter().take_while(|message| message.role == Role::System).count();
+                all_messages.insert(position, message);
+            }
+        }
         let run_id = options.run_id.clone().unwrap_or_else(|| Uuid::new_v4().to_string());
         let max_steps = options.max_steps.unwrap_or(5);
         let mut current_step = 0;
+        let cancellation = options.llm_options.cancellation_token.clone();
         
         // Initialize comprehensive monitoring
         let start_time = SystemTime::now()
@@ -822,6 +1039,7 @@ impl Agent for BasicAgent {
                     trace_metadata.insert("agent_name".to_string(), serde_json::Value::String(self.name.clone()));
                     trace_metadata.insert("max_steps".to_string(), serde_json::Value::Number(serde_json::Number::from(max_steps)));
                     trace_metadata.insert("message_count".to_string(), serde_json::Value::Number(serde_json::Number::from(messages.len())));
+                    crate::request_context::attach_current(&mut trace_metadata);
                     trace_metadata
                 }
             ).await {
@@ -869,7 +1087,9 @@ impl Agent for BasicAgent {
         steps.push(initial_step);
         
         let mut final_response = String::new();
-        let total_tokens = TelemetryTokenUsage::default();
+        let mut total_tokens = TelemetryTokenUsage::default();
+        // Providers do not report usage through this path, so count it with the model's tokenizer
+        let tokenizer = tokenizer_for_model(options.llm_options.model.as_deref().unwrap_or(self.llm.name()));
         let mut total_tool_calls = 0;
         let mut total_errors = 0;
         
@@ -880,6 +1100,24 @@ impl Agent for BasicAgent {
         
         self.logger().info(&format!("Using function calling mode: {}", use_function_calling), None);
         
+        // A forced tool call needs function calling and, for a specific tool, that tool
+        let tool_choice = options.tool_choice.clone().unwrap_or_default();
+        if tool_choice.requires_tool_call() {
+            if !use_function_calling {
+                return Err(Error::Configuration(format!(
+                    "Tool choice {:?} requires function calling, which agent '{}' cannot use", tool_choice, self.name
+                )));
+            }
+            if let ToolChoice::Tool { tool_name } = &tool_choice {
+                if !self.tools.lock().map(|tools| tools.contains_key(tool_name)).unwrap_or(false) {
+                    return Err(Error::Configuration(format!("Tool choice names unknown tool '{}'", tool_name)));
+                }
+            }
+        }
+        // The requirement applies until the model has called the tool; later steps are free to answer
+        let mut tool_choice_pending = tool_choice.requires_tool_call();
+        let mut tool_choice_retries = 0;
+        
         // Record function calling mode in trace
         if let (Some(trace_collector), Some(trace_id)) = (&self.trace_collector, &trace_id) {
             let mut mode_step = TraceStep::new(
@@ -895,6 +1133,10 @@ impl Agent for BasicAgent {
         }
 
         while current_step < max_steps {
+            if cancellation.as_ref().is_some_and(|token| token.is_cancelled()) {
+                self.logger().info(&format!("Generation cancelled before step {} (run_id: {})", current_step + 1, run_id), None);
+                return Err(crate::cancellation::cancelled_error());
+            }
             current_step += 1;
             let step_start_time = std::time::Instant::now();
             
@@ -919,30 +1161,33 @@ impl Agent for BasicAgent {
                 
                 if !function_definitions.is_empty() {
                     // Convert tool choice from agent options to LLM tool choice
-                    let llm_tool_choice = match &options.tool_choice {
-                        Some(crate::agent::types::ToolChoice::Auto) => LlmToolChoice::Auto,
-                        Some(crate::agent::types::ToolChoice::None) => LlmToolChoice::None,
-                        Some(crate::agent::types::ToolChoice::Required) => LlmToolChoice::Required,
-                        Some(crate::agent::types::ToolChoice::Tool { tool_name }) => {
-                            LlmToolChoice::Function { name: tool_name.clone() }
-                        },
-                        None => LlmToolChoice::Auto,
+                    let llm_tool_choice = if tool_choice.requires_tool_call() && !tool_choice_pending {
+                        LlmToolChoice::Auto
+                    } else {
+                        LlmToolChoice::from(&tool_choice)
                     };
                     
                     let llm_options = options.llm_options.clone();
                     let llm_start_time = std::time::Instant::now();
                     
-                    let response: crate::llm::provider::FunctionCallingResponse = self.llm.generate_with_functions(
-                        &all_messages, 
-                        &function_definitions,
-                        &llm_tool_choice,
-                        &llm_options
+                    let response: crate::llm::provider::FunctionCallingResponse = run_until_cancelled(
+                        cancellation.as_ref(),
+                        self.llm.generate_with_functions(
+                            &all_messages, 
+                            &function_definitions,
+                            &llm_tool_choice,
+                            &llm_options
+                        )
                     ).await?;
                     
                     let llm_duration = llm_start_time.elapsed();
                     
-                    // Note: FunctionCallingResponse doesn't include usage info
-                    // Token usage tracking would need to be handled separately
+                    add_estimated_usage(
+                        &mut total_tokens,
+                        tokenizer.as_ref(),
+                        &all_messages,
+                        response.content.as_deref().unwrap_or_default(),
+                    );
                     
                     // Record LLM call completion in trace
                     if let (Some(trace_collector), Some(trace_id)) = (&self.trace_collector, &trace_id) {
@@ -964,6 +1209,32 @@ impl Agent for BasicAgent {
                         let _ = trace_collector.add_trace_step(trace_id, llm_step).await;
                     }
                     
+                    // Providers that cannot force tool calls may answer directly; ask again
+                    if tool_choice_pending {
+                        if !tool_choice.is_satisfied_by(response.function_calls.iter().map(|call| call.name.as_str())) {
+                            if tool_choice_retries >= MAX_TOOL_CHOICE_RETRIES || current_step >= max_steps {
+                                return Err(Error::Agent(format!(
+                                    "Model did not honor tool choice {:?} after {} corrective retries", tool_choice, tool_choice_retries
+                                )));
+                            }
+                            tool_choice_retries += 1;
+                            self.logger().warn(&format!("Model ignored tool choice {:?}, retrying ({}/{})",
+                                tool_choice, tool_choice_retries, MAX_TOOL_CHOICE_RETRIES), None);
+                            
+                            if let Some(content) = response.content.filter(|content| !content.is_empty()) {
+                                all_messages.push(Message {
+                                    role: Role::Assistant,
+                                    content,
+                                    metadata: None,
+                                    name: None,
+                                });
+                            }
+                            all_messages.extend(tool_choice.corrective_message());
+                            continue;
+                        }
+                        tool_choice_pending = false;
+                    }
+                    
                     if !response.function_calls.is_empty() {
                         // Execute function calls with enhanced logging and metrics
                         let tool_calls = self.parse_function_calls(&response.function_calls);
@@ -984,7 +1255,8 @@ impl Agent for BasicAgent {
                             
                             let tool_start_time = std::time::Instant::now();
                             
-                            let result = match self.execute_tool_call(call).await {
+                            let result = match self.execute_tool_call(call, cancellation.as_ref()).await {
+                                Err(Error::Cancelled(reason)) => return Err(Error::Cancelled(reason)),
                                 Ok(result) => {
                                     let execution_time = tool_start_time.elapsed();
                                     self.logger().debug(&format!("Function call '{}' completed in {:?}", call.name, execution_time), None);
@@ -1170,9 +1442,12 @@ impl Agent for BasicAgent {
                 }
             } else {
                 // Use legacy regex-based tool calling
-                let response = self.llm.generate_with_messages(&all_messages, &options.llm_options).await?;
+                let response = run_until_cancelled(
+                    cancellation.as_ref(),
+                    self.llm.generate_with_messages(&all_messages, &options.llm_options)
+                ).await?;
                 
-                // Note: generate_with_messages returns String, no usage info available
+                add_estimated_usage(&mut total_tokens, tokenizer.as_ref(), &all_messages, &response);
                 
                 // Record legacy LLM call in trace
                 if let (Some(trace_collector), Some(trace_id)) = (&self.trace_collector, &trace_id) {
@@ -1202,7 +1477,8 @@ impl Agent for BasicAgent {
                     for call in &tool_calls {
                         let tool_start_time = std::time::Instant::now();
                         
-                        let result = match self.execute_tool_call(call).await {
+                        let result = match self.execute_tool_call(call, cancellation.as_ref()).await {
+                            Err(Error::Cancelled(reason)) => return Err(Error::Cancelled(reason)),
                             Ok(result) => {
                                 let execution_time = tool_start_time.elapsed();
                                 
@@ -1356,6 +1632,75 @@ impl Agent for BasicAgent {
             }
         }
         
+        // Remember this turn's successful tool calls for later turns of the session
+        if let (Some(memory), Some(session)) = (&self.tools_memory, &tools_memory_session) {
+            for step in &steps {
+                for result in step.tool_results.iter().filter(|result| matches!(result.status, ToolResultStatus::Success)) {
+                    let Some(call) = step.tool_calls.iter().find(|call| call.id == result.call_id) else {
+                        continue;
+                    };
+                    let arguments = serde_json::to_value(&call.arguments).unwrap_or_default();
+                    memory.record(self.llm.as_ref(), session, &call.name, arguments, &result.result).await;
+                }
+            }
+        }
+
+        // Critique and revise the answer when self-reflection is enabled
+        let mut result_metadata = HashMap::new();
+        if tool_choice_retries > 0 {
+            result_metadata.insert("tool_choice_retries".to_string(), Value::from(tool_choice_retries));
+        }
+        if !nondeterminism_warnings.is_empty() {
+            result_metadata.insert("nondeterminism_warnings".to_string(), Value::from(nondeterminism_warnings));
+        }
+        if let Some(reflection) = &self.reflection {
+            let conversation = self.format_messages(messages, options);
+            let (answer, reflection_steps) = self.reflect(reflection, &conversation, final_response, &options.llm_options).await?;
+            let scores: Vec<Value> = reflection_steps.iter()
+                .filter_map(|step| step.metadata.get("score").cloned())
+                .collect();
+            result_metadata.insert("reflection_iterations".to_string(), Value::from(reflection_steps.len()));
+            result_metadata.insert("reflection_scores".to_string(), Value::Array(scores));
+            // The last judge score is the confidence in the final answer
+            let final_critique = reflection_steps.last().map(|step| &step.metadata);
+            if let (Some(queue), Some(critique)) = (&self.review_queue, final_critique) {
+                let score = critique.get("score").and_then(Value::as_f64).unwrap_or_default();
+                let reason = critique.get("critique").and_then(Value::as_str).map(str::to_string);
+                if let Some(item) = queue.consider(&self.name, query, answer.clone(), score, "judge", reason) {
+                    result_metadata.insert("review_id".to_string(), Value::from(item.id));
+                }
+            }
+            steps.extend(reflection_steps);
+            final_response = answer;
+        }
+        
+        // Move intermediate reasoning into the trace so it stays out of the answer
+        let trace = if options.capture_trace {
+            let (trace, answer) = AgentTrace::capture(&steps, &final_response);
+            final_response = answer;
+            Some(trace)
+        } else {
+            None
+        };
+        
+        // Rewrite answers clearly written in another language than the one requested
+        if let Some(target) = options.language.as_deref().and_then(Language::parse) {
+            if !target.is_written_in(&final_response) {
+                self.logger().debug(&format!("Answer is not in {}, rewriting it", target.name), None);
+                let rewrite = language::rewrite_messages(&final_response, target);
+                final_response = run_until_cancelled(
+                    options.llm_options.cancellation_token.as_ref(),
+                    self.llm.generate_with_messages(&rewrite, &options.llm_options),
+                ).await?;
+                result_metadata.insert("language_rewritten".to_string(), Value::from(true));
+            }
+        }
+        
+        for processor in &self.output_processors {
+            final_response = processor.process(final_response).await?;
+            self.logger().debug(&format!("Applied output processor '{}'", processor.name()), None);
+        }
+        
         // Calculate total execution time
         let end_time = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -1439,7 +1784,8 @@ impl Agent for BasicAgent {
                 completion_tokens: total_tokens.completion_tokens as usize,
                 total_tokens: total_tokens.total_tokens as usize,
             },
-            metadata: HashMap::new(),
+            metadata: result_metadata,
+            trace,
         })
     }
     
@@ -1594,6 +1940,42 @@ impl Agent for BasicAgent {
     }
 }
 
+#[async_trait]
+impl AgentStructuredOutput for BasicAgent {
+    async fn generate_structured<T: DeserializeOwned + Send + 'static>(
+        &self,
+        messages: &[Message],
+        options: &AgentGenerateOptions
+    ) -> Result<T> {
+        let mut options = options.clone();
+        let instructions = options.instructions.as_deref().unwrap_or(&self.instructions);
+        options.instructions = Some(json_instructions(instructions, self.output_schema.as_ref()));
+
+        let result = self.generate(messages, &options).await?;
+        let mut parser = PartialJsonParser::new();
+        parser.push(&result.response);
+        parser.finish()
+    }
+
+    async fn stream_structured<'a, T: DeserializeOwned + Send + 'a>(
+        &'a self,
+        messages: &'a [Message],
+        options: &'a AgentStreamOptions
+    ) -> Result<BoxStream<'a, Result<PartialOutput<T>>>> {
+        let mut options = options.clone();
+        let instructions = options.instructions.as_deref().unwrap_or(&self.instructions);
+        options.instructions = Some(json_instructions(instructions, self.output_schema.as_ref()));
+
+        let chunks = async_stream::try_stream! {
+            let mut chunks = self.stream(messages, &options).await?;
+            while let Some(chunk) = chunks.next().await {
+                yield chunk?;
+            }
+        };
+        Ok(partial_json_stream(chunks.boxed()))
+    }
+}
+
 impl BasicAgent {
     /// Legacy mode fallback for LLMs that don't support streaming
     async fn stream_legacy_mode<'a>(&'a self,
@@ -1777,20 +2159,18 @@ impl BasicAgent {
 }
 
 impl BasicAgent {
-    /// Create smart chunks that respect word and sentence boundaries
+    /// Create smart chunks that respect word boundaries
+    ///
+    /// Whitespace is kept, so the chunks concatenate to the original text; structured
+    /// output streamed this way still parses as the same JSON.
     fn create_smart_chunks(&self, text: &str) -> Vec<String> {
         let mut chunks = Vec::new();
         let mut current_chunk = String::new();
         let target_chunk_size = 50; // Characters per chunk
 
-        for word in text.split_whitespace() {
-            if current_chunk.len() + word.len() + 1 > target_chunk_size && !current_chunk.is_empty() {
-                chunks.push(current_chunk.clone());
-                current_chunk.clear();
-            }
-
-            if !current_chunk.is_empty() {
-                current_chunk.push(' ');
+        for word in text.split_inclusive(char::is_whitespace) {
+            if current_chunk.len() + word.len() > target_chunk_size && !current_chunk.is_empty() {
+                chunks.push(std::mem::take(&mut current_chunk));
             }
             current_chunk.push_str(word);
         }
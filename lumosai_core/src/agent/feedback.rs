@@ -0,0 +1,229 @@
+//! 用户反馈
+//!
+//! 用户可以对会话中的某条助手消息点赞或点踩、评分（1到5分）或提交修正后的回答。
+//! 反馈以会话ID和消息在会话中的位置定位消息，由[`SessionStorage`](super::session::SessionStorage)
+//! 保存；同一用户对同一条消息再次提交时覆盖之前的反馈。收集的反馈可转换为评测用例和微调样本。
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+
+/// 评分下限
+pub const MIN_RATING: u8 = 1;
+
+/// 评分上限
+pub const MAX_RATING: u8 = 5;
+
+/// 点赞或点踩
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Thumbs {
+    /// 点赞
+    Up,
+    /// 点踩
+    Down,
+}
+
+/// 对一条助手消息的反馈
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Feedback {
+    /// 反馈ID
+    pub id: String,
+    /// 会话ID
+    pub session_id: String,
+    /// 消息在会话中的位置，从0开始
+    pub message_index: usize,
+    /// 提交反馈的用户
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+    /// 点赞或点踩
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thumbs: Option<Thumbs>,
+    /// 评分
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rating: Option<u8>,
+    /// 修正后的回答
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correction: Option<String>,
+    /// 备注
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    /// 提交时间
+    pub created_at: DateTime<Utc>,
+}
+
+impl Feedback {
+    /// 创建对会话中第`message_index`条消息的空反馈
+    pub fn new(session_id: impl Into<String>, message_index: usize) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            session_id: session_id.into(),
+            message_index,
+            user_id: None,
+            thumbs: None,
+            rating: None,
+            correction: None,
+            comment: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// 设置提交反馈的用户
+    pub fn with_user(mut self, user_id: impl Into<String>) -> Self {
+        self.user_id = Some(user_id.into());
+        self
+    }
+
+    /// 设置点赞或点踩
+    pub fn with_thumbs(mut self, thumbs: Thumbs) -> Self {
+        self.thumbs = Some(thumbs);
+        self
+    }
+
+    /// 设置评分
+    pub fn with_rating(mut self, rating: u8) -> Self {
+        self.rating = Some(rating);
+        self
+    }
+
+    /// 设置修正后的回答
+    pub fn with_correction(mut self, correction: impl Into<String>) -> Self {
+        self.correction = Some(correction.into());
+        self
+    }
+
+    /// 设置备注
+    pub fn with_comment(mut self, comment: impl Into<String>) -> Self {
+        self.comment = Some(comment.into());
+        self
+    }
+
+    /// 校验反馈：至少包含点赞、评分或修正之一，评分在1到5之间，修正不能为空
+    pub fn validate(&self) -> Result<()> {
+        if self.thumbs.is_none() && self.rating.is_none() && self.correction.is_none() {
+            return Err(Error::InvalidInput("Feedback needs thumbs, a rating or a correction".to_string()));
+        }
+        if let Some(rating) = self.rating {
+            if !(MIN_RATING..=MAX_RATING).contains(&rating) {
+                return Err(Error::InvalidInput(format!("Rating must be between {} and {}", MIN_RATING, MAX_RATING)));
+            }
+        }
+        if self.correction.as_deref().is_some_and(|correction| correction.trim().is_empty()) {
+            return Err(Error::InvalidInput("Correction must not be empty".to_string()));
+        }
+        Ok(())
+    }
+
+    /// 用户认可原回答：点赞或评分不低于4分，且没有提交修正
+    pub fn is_positive(&self) -> bool {
+        self.correction.is_none()
+            && self.thumbs != Some(Thumbs::Down)
+            && (self.thumbs == Some(Thumbs::Up) || self.rating.is_some_and(|rating| rating >= 4))
+    }
+
+    /// 用户否定原回答：点踩、评分不高于2分或提交了修正
+    pub fn is_negative(&self) -> bool {
+        self.correction.is_some()
+            || self.thumbs == Some(Thumbs::Down)
+            || self.rating.is_some_and(|rating| rating <= 2)
+    }
+
+    /// 是否与`other`是同一用户对同一条消息的反馈
+    pub(crate) fn same_target(&self, other: &Feedback) -> bool {
+        self.session_id == other.session_id && self.message_index == other.message_index && self.user_id == other.user_id
+    }
+}
+
+/// 反馈查询条件
+#[derive(Debug, Clone, Default)]
+pub struct FeedbackQuery {
+    /// 会话ID过滤
+    pub session_id: Option<String>,
+    /// 用户ID过滤
+    pub user_id: Option<String>,
+    /// 只返回此时间之后提交的反馈
+    pub since: Option<DateTime<Utc>>,
+    /// 结果限制
+    pub limit: Option<usize>,
+}
+
+impl FeedbackQuery {
+    /// 查询一个会话的反馈
+    pub fn for_session(session_id: impl Into<String>) -> Self {
+        Self {
+            session_id: Some(session_id.into()),
+            ..Self::default()
+        }
+    }
+
+    /// 反馈是否满足查询条件
+    pub fn matches(&self, feedback: &Feedback) -> bool {
+        self.session_id.as_ref().map_or(true, |id| &feedback.session_id == id)
+            && self.user_id.as_ref().map_or(true, |id| feedback.user_id.as_ref() == Some(id))
+            && self.since.map_or(true, |since| feedback.created_at >= since)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::session::{MemorySessionStorage, SessionManager};
+    use crate::llm::{Message, Role};
+    use std::sync::Arc;
+
+    fn message(role: Role, content: &str) -> Message {
+        Message { role, content: content.to_string(), metadata: None, name: None }
+    }
+
+    #[test]
+    fn test_feedback_validation_and_polarity() {
+        assert!(Feedback::new("s", 1).validate().is_err());
+        assert!(Feedback::new("s", 1).with_rating(6).validate().is_err());
+        assert!(Feedback::new("s", 1).with_correction("  ").validate().is_err());
+
+        let liked = Feedback::new("s", 1).with_thumbs(Thumbs::Up);
+        assert!(liked.validate().is_ok());
+        assert!(liked.is_positive() && !liked.is_negative());
+
+        let corrected = Feedback::new("s", 1).with_rating(5).with_correction("Better answer");
+        assert!(!corrected.is_positive() && corrected.is_negative());
+        assert!(Feedback::new("s", 1).with_rating(3).validate().is_ok());
+        assert!(!Feedback::new("s", 1).with_rating(3).is_positive());
+    }
+
+    #[tokio::test]
+    async fn test_feedback_attaches_to_assistant_messages() {
+        let manager = SessionManager::new(Arc::new(MemorySessionStorage::new()));
+        manager.create_session("s1".to_string(), "support".to_string(), Some("ann".to_string())).await.unwrap();
+        manager.add_message("s1", message(Role::User, "How do I reset my password?")).await.unwrap();
+        manager.add_message("s1", message(Role::Assistant, "Click 'Forgot password'.")).await.unwrap();
+
+        // Only existing assistant messages can receive feedback
+        assert!(matches!(
+            manager.add_feedback(Feedback::new("s1", 0).with_thumbs(Thumbs::Up)).await,
+            Err(Error::InvalidInput(_))
+        ));
+        assert!(matches!(
+            manager.add_feedback(Feedback::new("s1", 5).with_thumbs(Thumbs::Up)).await,
+            Err(Error::InvalidInput(_))
+        ));
+        assert!(matches!(
+            manager.add_feedback(Feedback::new("missing", 1).with_thumbs(Thumbs::Up)).await,
+            Err(Error::NotFound(_))
+        ));
+
+        manager.add_feedback(Feedback::new("s1", 1).with_user("ann").with_thumbs(Thumbs::Up)).await.unwrap();
+        manager.add_feedback(Feedback::new("s1", 1).with_user("bob").with_rating(2)).await.unwrap();
+        // A user's new feedback on the same message replaces their earlier one
+        manager.add_feedback(Feedback::new("s1", 1).with_user("ann").with_thumbs(Thumbs::Down).with_correction("Use the reset link")).await.unwrap();
+
+        let all = manager.list_feedback(&FeedbackQuery::for_session("s1")).await.unwrap();
+        assert_eq!(all.len(), 2);
+        let ann = all.iter().find(|feedback| feedback.user_id.as_deref() == Some("ann")).unwrap();
+        assert_eq!(ann.correction.as_deref(), Some("Use the reset link"));
+
+        let query = FeedbackQuery { user_id: Some("bob".to_string()), ..FeedbackQuery::default() };
+        assert_eq!(manager.list_feedback(&query).await.unwrap()[0].rating, Some(2));
+    }
+}
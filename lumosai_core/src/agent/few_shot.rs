@@ -0,0 +1,290 @@
+//! Few-shot examples attached to agents
+//!
+//! A [`FewShotStore`] holds labeled example exchanges. For each request the examples
+//! most similar to the user's message are selected by embedding similarity and shown
+//! to the model as prior user/assistant turns, right after the system message.
+
+use std::sync::{Arc, RwLock};
+
+use serde::{Deserialize, Serialize};
+
+use crate::llm::{LlmProvider, Message, Role};
+
+/// Default number of examples injected per request
+const DEFAULT_TOP_K: usize = 3;
+
+/// Similarity resolution of stable selection, so that tiny embedding drift cannot reorder examples
+const STABLE_SIMILARITY_RESOLUTION: f32 = 1e-3;
+
+/// A labeled example exchange
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FewShotExample {
+    /// The user's message
+    pub input: String,
+    /// The answer the agent should give
+    pub output: String,
+    /// Optional label describing what the example demonstrates
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+impl FewShotExample {
+    /// Create an unlabeled example
+    pub fn new(input: impl Into<String>, output: impl Into<String>) -> Self {
+        Self {
+            input: input.into(),
+            output: output.into(),
+            label: None,
+        }
+    }
+
+    /// Label the example
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+}
+
+/// Few-shot examples and how many to inject per request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FewShotConfig {
+    /// Stored examples
+    #[serde(default)]
+    pub examples: Vec<FewShotExample>,
+    /// Number of most similar examples injected per request
+    #[serde(default = "default_top_k")]
+    pub top_k: usize,
+}
+
+fn default_top_k() -> usize {
+    DEFAULT_TOP_K
+}
+
+impl Default for FewShotConfig {
+    fn default() -> Self {
+        Self {
+            examples: Vec::new(),
+            top_k: DEFAULT_TOP_K,
+        }
+    }
+}
+
+impl FewShotConfig {
+    /// Create a config with the given examples
+    pub fn new(examples: Vec<FewShotExample>) -> Self {
+        Self {
+            examples,
+            ..Self::default()
+        }
+    }
+
+    /// Set how many examples are injected per request
+    pub fn with_top_k(mut self, top_k: usize) -> Self {
+        self.top_k = top_k;
+        self
+    }
+}
+
+/// An example and its cached embedding
+#[derive(Debug, Clone)]
+struct StoredExample {
+    example: FewShotExample,
+    embedding: Option<Vec<f32>>,
+}
+
+/// Store of few-shot examples selecting the most similar ones per request
+///
+/// Example embeddings are computed on first use and cached until the example changes.
+/// When embeddings are unavailable the first `top_k` examples are used instead.
+pub struct FewShotStore {
+    examples: RwLock<Vec<StoredExample>>,
+    top_k: usize,
+    embedder: Option<Arc<dyn LlmProvider>>,
+}
+
+impl FewShotStore {
+    /// Create a store from a config
+    pub fn new(config: FewShotConfig) -> Self {
+        let examples = config.examples.into_iter()
+            .map(|example| StoredExample { example, embedding: None })
+            .collect();
+        Self {
+            examples: RwLock::new(examples),
+            top_k: config.top_k,
+            embedder: None,
+        }
+    }
+
+    /// Embed with a dedicated provider instead of the agent's model
+    pub fn with_embedder(mut self, embedder: Arc<dyn LlmProvider>) -> Self {
+        self.embedder = Some(embedder);
+        self
+    }
+
+    /// Number of examples injected per request
+    pub fn top_k(&self) -> usize {
+        self.top_k
+    }
+
+    /// All stored examples
+    pub fn examples(&self) -> Vec<FewShotExample> {
+        self.read().iter().map(|stored| stored.example.clone()).collect()
+    }
+
+    /// Add an example
+    pub fn add(&self, example: FewShotExample) {
+        self.write().push(StoredExample { example, embedding: None });
+    }
+
+    /// Replace the example at `index`, returning the previous one
+    pub fn replace(&self, index: usize, example: FewShotExample) -> Option<FewShotExample> {
+        let mut examples = self.write();
+        let stored = examples.get_mut(index)?;
+        stored.embedding = None;
+        Some(std::mem::replace(&mut stored.example, example))
+    }
+
+    /// Remove the example at `index`
+    pub fn remove(&self, index: usize) -> Option<FewShotExample> {
+        let mut examples = self.write();
+        (index < examples.len()).then(|| examples.remove(index).example)
+    }
+
+    /// The examples most similar to `query`, most similar first
+    ///
+    /// `llm` embeds the texts unless the store has its own embedder.
+    pub async fn select(&self, llm: &dyn LlmProvider, query: &str) -> Vec<FewShotExample> {
+        self.select_ranked(llm, query, None).await
+    }
+
+    /// Like [`select`](Self::select), but reproducible across runs
+    ///
+    /// Similarities are rounded before ranking and equal scores keep insertion order.
+    pub async fn select_stable(&self, llm: &dyn LlmProvider, query: &str) -> Vec<FewShotExample> {
+        self.select_ranked(llm, query, Some(STABLE_SIMILARITY_RESOLUTION)).await
+    }
+
+    async fn select_ranked(&self, llm: &dyn LlmProvider, query: &str, resolution: Option<f32>) -> Vec<FewShotExample> {
+        let examples: Vec<StoredExample> = self.read().clone();
+        if examples.len() <= self.top_k {
+            return examples.into_iter().map(|stored| stored.example).collect();
+        }
+
+        let embedder = self.embedder.as_deref().unwrap_or(llm);
+        match self.rank(embedder, query, &examples, resolution).await {
+            Ok(ranked) => ranked.into_iter().take(self.top_k).map(|index| examples[index].example.clone()).collect(),
+            Err(e) => {
+                tracing::warn!("Few-shot examples selected in order, embedding failed: {}", e);
+                examples.into_iter().take(self.top_k).map(|stored| stored.example).collect()
+            }
+        }
+    }
+
+    /// Example indices ordered by similarity to the query
+    async fn rank(
+        &self,
+        embedder: &dyn LlmProvider,
+        query: &str,
+        examples: &[StoredExample],
+        resolution: Option<f32>,
+    ) -> crate::Result<Vec<usize>> {
+        let query_embedding = embedder.get_embedding(query).await?;
+
+        let mut scored = Vec::with_capacity(examples.len());
+        for (index, stored) in examples.iter().enumerate() {
+            let embedding = match &stored.embedding {
+                Some(embedding) => embedding.clone(),
+                None => {
+                    let embedding = embedder.get_embedding(&stored.example.input).await?;
+                    self.cache_embedding(&stored.example, embedding.clone());
+                    embedding
+                }
+            };
+            let similarity = cosine_similarity(&query_embedding, &embedding);
+            scored.push((index, resolution.map_or(similarity, |step| (similarity / step).round() * step)));
+        }
+
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        Ok(scored.into_iter().map(|(index, _)| index).collect())
+    }
+
+    /// Cache an embedding unless the example was edited meanwhile
+    fn cache_embedding(&self, example: &FewShotExample, embedding: Vec<f32>) {
+        let mut examples = self.write();
+        if let Some(stored) = examples.iter_mut().find(|stored| stored.embedding.is_none() && &stored.example == example) {
+            stored.embedding = Some(embedding);
+        }
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, Vec<StoredExample>> {
+        self.examples.read().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, Vec<StoredExample>> {
+        self.examples.write().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl std::fmt::Debug for FewShotStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FewShotStore")
+            .field("examples", &self.read().len())
+            .field("top_k", &self.top_k)
+            .finish()
+    }
+}
+
+/// Render examples as prior user/assistant turns
+pub fn example_messages(examples: &[FewShotExample]) -> Vec<Message> {
+    examples.iter()
+        .flat_map(|example| {
+            [(Role::User, &example.input), (Role::Assistant, &example.output)].map(|(role, content)| Message {
+                role,
+                content: content.clone(),
+                metadata: None,
+                name: None,
+            })
+        })
+        .collect()
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_edits_invalidate_embeddings() {
+        let store = FewShotStore::new(FewShotConfig::new(vec![FewShotExample::new("hi", "hello")]));
+        store.add(FewShotExample::new("bye", "goodbye").with_label("farewell"));
+        store.write()[0].embedding = Some(vec![1.0]);
+
+        let previous = store.replace(0, FewShotExample::new("hey", "hello")).unwrap();
+        assert_eq!(previous.input, "hi");
+        assert!(store.read()[0].embedding.is_none());
+        assert_eq!(store.remove(1).unwrap().label.as_deref(), Some("farewell"));
+        assert!(store.remove(1).is_none());
+        assert_eq!(store.examples(), [FewShotExample::new("hey", "hello")]);
+    }
+
+    #[test]
+    fn test_example_messages_alternate_roles() {
+        let messages = example_messages(&[FewShotExample::new("2+2?", "4")]);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].role, Role::User);
+        assert_eq!(messages[1].content, "4");
+    }
+}
@@ -0,0 +1,259 @@
+//! Response language control
+//!
+//! [`AgentGenerateOptions::language`](super::types::AgentGenerateOptions::language) asks the
+//! agent to answer in a given language. The instruction is added to the system prompt and
+//! the final answer is checked with a lightweight detector: when it is clearly written in
+//! another language the agent rewrites it once in the requested language.
+//!
+//! Detection works on the writing system and, for Latin-script languages, on common
+//! function words. Languages the detector does not know are still requested through the
+//! prompt but not validated.
+
+use crate::llm::{Message, Role};
+
+/// Answers with fewer letters than this are not validated
+const MIN_LETTERS: usize = 20;
+
+/// Share of kana among CJK characters above which text counts as Japanese
+const KANA_SHARE: f64 = 0.1;
+
+/// Writing system of a language
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Script {
+    Latin,
+    Cyrillic,
+    Greek,
+    Arabic,
+    Hebrew,
+    Devanagari,
+    Thai,
+    Hangul,
+    /// Japanese: kana mixed with kanji
+    Kana,
+    /// Chinese characters without kana
+    Han,
+}
+
+/// A language the detector knows
+#[derive(Debug, PartialEq, Eq)]
+pub struct Language {
+    /// ISO 639-1 code
+    pub code: &'static str,
+    /// English name
+    pub name: &'static str,
+    /// Name in the language itself
+    pub native_name: &'static str,
+    /// Writing system
+    pub script: Script,
+    /// Frequent function words used to tell Latin-script languages apart
+    stopwords: &'static [&'static str],
+}
+
+/// Languages recognised by [`Language::parse`] and [`detect_language`]
+pub static LANGUAGES: &[Language] = &[
+    Language {
+        code: "en", name: "English", native_name: "English", script: Script::Latin,
+        stopwords: &["the", "and", "is", "are", "of", "to", "you", "that", "it", "with", "for", "this", "not", "can", "be"],
+    },
+    Language {
+        code: "fr", name: "French", native_name: "français", script: Script::Latin,
+        stopwords: &["le", "la", "les", "et", "est", "des", "une", "vous", "que", "pour", "dans", "pas", "avec", "sont", "du"],
+    },
+    Language {
+        code: "de", name: "German", native_name: "Deutsch", script: Script::Latin,
+        stopwords: &["der", "die", "das", "und", "ist", "nicht", "sie", "ein", "eine", "mit", "sind", "für", "auf", "den", "zu"],
+    },
+    Language {
+        code: "es", name: "Spanish", native_name: "español", script: Script::Latin,
+        stopwords: &["el", "los", "las", "es", "y", "que", "por", "para", "una", "con", "del", "está", "puede", "son", "usted"],
+    },
+    Language {
+        code: "it", name: "Italian", native_name: "italiano", script: Script::Latin,
+        stopwords: &["il", "gli", "è", "che", "di", "per", "una", "con", "sono", "non", "della", "questo", "puoi", "lo", "anche"],
+    },
+    Language {
+        code: "pt", name: "Portuguese", native_name: "português", script: Script::Latin,
+        stopwords: &["o", "os", "as", "é", "que", "não", "uma", "com", "para", "você", "do", "da", "são", "pode", "em"],
+    },
+    Language {
+        code: "nl", name: "Dutch", native_name: "Nederlands", script: Script::Latin,
+        stopwords: &["de", "het", "een", "en", "is", "niet", "van", "je", "u", "met", "voor", "zijn", "dat", "op", "kunt"],
+    },
+    Language { code: "ru", name: "Russian", native_name: "русский", script: Script::Cyrillic, stopwords: &[] },
+    Language { code: "el", name: "Greek", native_name: "ελληνικά", script: Script::Greek, stopwords: &[] },
+    Language { code: "ar", name: "Arabic", native_name: "العربية", script: Script::Arabic, stopwords: &[] },
+    Language { code: "he", name: "Hebrew", native_name: "עברית", script: Script::Hebrew, stopwords: &[] },
+    Language { code: "hi", name: "Hindi", native_name: "हिन्दी", script: Script::Devanagari, stopwords: &[] },
+    Language { code: "th", name: "Thai", native_name: "ไทย", script: Script::Thai, stopwords: &[] },
+    Language { code: "ko", name: "Korean", native_name: "한국어", script: Script::Hangul, stopwords: &[] },
+    Language { code: "ja", name: "Japanese", native_name: "日本語", script: Script::Kana, stopwords: &[] },
+    Language { code: "zh", name: "Chinese", native_name: "中文", script: Script::Han, stopwords: &[] },
+];
+
+impl Language {
+    /// Look up a language by code (`fr`, `pt-BR`), English name or native name
+    pub fn parse(value: &str) -> Option<&'static Language> {
+        let value = value.trim().to_lowercase();
+        let code = value.split(['-', '_']).next().unwrap_or_default();
+        LANGUAGES.iter().find(|language| {
+            language.code == code
+                || language.name.to_lowercase() == value
+                || language.native_name.to_lowercase() == value
+        })
+    }
+
+    /// Whether `text` could be written in this language
+    ///
+    /// Only returns `false` when the text is clearly in another language; short
+    /// texts and Latin-script texts without a clear match are accepted.
+    pub fn is_written_in(&self, text: &str) -> bool {
+        let Some(script) = dominant_script(text) else {
+            return true;
+        };
+        if script != self.script {
+            return false;
+        }
+        match detect_latin(text) {
+            Some(detected) if script == Script::Latin => detected == self,
+            _ => true,
+        }
+    }
+}
+
+/// Detect the language of `text`, if it is long enough and clear enough
+pub fn detect_language(text: &str) -> Option<&'static Language> {
+    match dominant_script(text)? {
+        Script::Latin => detect_latin(text),
+        // Scripts used by a single language in the table
+        script => LANGUAGES.iter().find(|language| language.script == script),
+    }
+}
+
+/// System prompt instruction requesting answers in `language`
+pub(crate) fn language_instruction(language: &str) -> String {
+    let name = Language::parse(language).map_or(language, |language| language.name);
+    format!("Always respond in {}, whatever language the user writes in.", name)
+}
+
+/// Messages asking the model to rewrite an answer in `language`
+pub(crate) fn rewrite_messages(answer: &str, language: &Language) -> Vec<Message> {
+    vec![
+        Message {
+            role: Role::System,
+            content: format!(
+                "Rewrite the text you are given in {}. Keep its meaning, formatting, code and names unchanged. \
+                 Reply with the rewritten text only.",
+                language.name
+            ),
+            metadata: None,
+            name: None,
+        },
+        Message {
+            role: Role::User,
+            content: answer.to_string(),
+            metadata: None,
+            name: None,
+        },
+    ]
+}
+
+fn char_script(c: char) -> Option<Script> {
+    let script = match c as u32 {
+        0x0041..=0x005A | 0x0061..=0x007A | 0x00C0..=0x024F => Script::Latin,
+        0x0370..=0x03FF => Script::Greek,
+        0x0400..=0x04FF => Script::Cyrillic,
+        0x0590..=0x05FF => Script::Hebrew,
+        0x0600..=0x06FF => Script::Arabic,
+        0x0900..=0x097F => Script::Devanagari,
+        0x0E00..=0x0E7F => Script::Thai,
+        0x1100..=0x11FF | 0xAC00..=0xD7AF => Script::Hangul,
+        0x3040..=0x30FF => Script::Kana,
+        0x4E00..=0x9FFF | 0x3400..=0x4DBF => Script::Han,
+        _ => return None,
+    };
+    Some(script)
+}
+
+/// The writing system most letters of `text` use, if it has enough letters
+fn dominant_script(text: &str) -> Option<Script> {
+    let mut counts: Vec<(Script, usize)> = Vec::new();
+    for script in text.chars().filter_map(char_script) {
+        match counts.iter_mut().find(|(counted, _)| *counted == script) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((script, 1)),
+        }
+    }
+    if counts.iter().map(|(_, count)| count).sum::<usize>() < MIN_LETTERS {
+        return None;
+    }
+
+    // Japanese mixes kana with kanji; count both as Japanese when kana are frequent
+    let count_of = |script: Script| counts.iter().find(|(counted, _)| *counted == script).map_or(0, |(_, count)| *count);
+    let (kana, han) = (count_of(Script::Kana), count_of(Script::Han));
+    if kana + han > 0 {
+        let cjk = if kana as f64 / (kana + han) as f64 >= KANA_SHARE { Script::Kana } else { Script::Han };
+        counts.retain(|(script, _)| !matches!(script, Script::Kana | Script::Han));
+        counts.push((cjk, kana + han));
+    }
+    counts.into_iter().max_by_key(|(_, count)| *count).map(|(script, _)| script)
+}
+
+/// The Latin-script language whose function words occur most often, if clearly ahead
+fn detect_latin(text: &str) -> Option<&'static Language> {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+
+    let mut scores: Vec<(&'static Language, usize)> = LANGUAGES.iter()
+        .filter(|language| language.script == Script::Latin)
+        .map(|language| {
+            let hits = words.iter().filter(|word| language.stopwords.contains(&word.as_str())).count();
+            (language, hits)
+        })
+        .collect();
+    scores.sort_by(|a, b| b.1.cmp(&a.1));
+
+    match scores.as_slice() {
+        [(best, hits), (_, runner_up), ..] if *hits >= 2 && *hits > runner_up + runner_up / 2 => Some(*best),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_language() {
+        assert_eq!(Language::parse("fr").unwrap().name, "French");
+        assert_eq!(Language::parse("pt-BR").unwrap().code, "pt");
+        assert_eq!(Language::parse("German").unwrap().code, "de");
+        assert_eq!(Language::parse("日本語").unwrap().code, "ja");
+        assert!(Language::parse("Klingon").is_none());
+        assert_eq!(language_instruction("es"), "Always respond in Spanish, whatever language the user writes in.");
+        assert_eq!(language_instruction("Swahili"), "Always respond in Swahili, whatever language the user writes in.");
+    }
+
+    #[test]
+    fn test_detect_language() {
+        let english = "You can reset the password from the settings page, and it is not hard to do.";
+        let french = "Vous pouvez réinitialiser le mot de passe dans la page des paramètres, et ce n'est pas difficile.";
+        let german = "Sie können das Passwort auf der Seite mit den Einstellungen zurücksetzen, und das ist nicht schwer.";
+        assert_eq!(detect_language(english).unwrap().code, "en");
+        assert_eq!(detect_language(french).unwrap().code, "fr");
+        assert_eq!(detect_language(german).unwrap().code, "de");
+        assert_eq!(detect_language("您可以在设置页面中重置密码，这并不难。请先登录您的账户然后打开设置。").unwrap().code, "zh");
+        assert_eq!(detect_language("設定ページからパスワードをリセットできます。まずアカウントにログインしてください。").unwrap().code, "ja");
+        assert_eq!(detect_language("Вы можете сбросить пароль на странице настроек."), Language::parse("ru"));
+        assert!(detect_language("OK").is_none());
+
+        let french_language = Language::parse("fr").unwrap();
+        assert!(french_language.is_written_in(french));
+        assert!(!french_language.is_written_in(english));
+        assert!(!french_language.is_written_in("您可以在设置页面中重置密码，这并不难。请先登录您的账户然后打开设置。"));
+        // Too short to tell
+        assert!(french_language.is_written_in("OK, done."));
+    }
+}
@@ -1,5 +1,7 @@
 //! Agent message utilities
 
+use serde::de::DeserializeOwned;
+
 use crate::llm::{Message, Role};
 
 /// Create a system message
@@ -65,4 +67,11 @@ mod tests {
         assert_eq!(tool.content, "Tool result");
         assert_eq!(tool.name, Some("calculator".to_string()));
     }
-} 
\ No newline at end of file
+} 
+
+/// Parse the JSON object in a model reply, tolerating surrounding text and code fences
+pub fn parse_json_reply<T: DeserializeOwned>(reply: &str) -> Option<T> {
+    let start = reply.find('{')?;
+    let end = reply.rfind('}')?;
+    serde_json::from_str(reply.get(start..=end)?).ok()
+}
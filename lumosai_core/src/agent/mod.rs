@@ -16,13 +16,33 @@ pub mod mastra_compat;
 pub mod convenience;
 pub mod simplified_api;
 pub mod session;
+pub mod feedback;
+pub mod state;
+pub mod session_export;
 pub mod orchestration;
+pub mod blackboard;
+pub mod consensus;
 pub mod events;
+pub mod webhooks;
 pub mod model_resolver;
 pub mod performance;
 pub mod api_consistency;
 pub mod feature_completion;
 pub mod chain;
+pub mod versioning;
+pub mod scheduler;
+pub mod context_window;
+pub mod retrieval;
+pub mod reflection;
+pub mod review;
+pub mod few_shot;
+pub mod language;
+pub mod structured;
+pub mod tools_memory;
+pub mod output_processor;
+pub mod sampling;
+pub mod planner;
+pub mod trace;
 
 #[cfg(feature = "demos")]
 pub mod websocket_demo;
@@ -42,7 +62,18 @@ mod advanced_features_test;
 mod simple_test;
 
 pub use config::{AgentConfig, AgentGenerateOptions};
+pub use reflection::{Critique, ReflectionConfig};
+pub use review::{ReviewDecision, ReviewItem, ReviewQueue, ReviewStatus, DEFAULT_REVIEW_THRESHOLD};
+pub use few_shot::{FewShotConfig, FewShotExample, FewShotStore};
+pub use language::{detect_language, Language, Script};
+pub use structured::{parse_partial_json, partial_json_stream, PartialJsonParser, PartialOutput};
+pub use tools_memory::{ToolRecord, ToolsMemory, ToolsMemoryConfig};
+pub use output_processor::{LinkRewriter, MarkdownCleanup, OutputProcessor, Redactor, StripReasoning};
+pub use sampling::{BestOfN, Selection};
+pub use planner::{Plan, PlanState, PlanStep, PlannerAgent, PlannerAgentBuilder, StepProgress, StepStatus};
+pub use trace::{AgentTrace, TraceEntry};
 pub use trait_def::Agent as AgentTrait;
+pub use trait_def::AgentStructuredOutput;
 pub use executor::BasicAgent;
 pub use message_utils::{system_message, user_message, assistant_message, tool_message};
 pub use runtime_context::{RuntimeContext, ContextManager, ToolCallRecord, create_context_manager};
@@ -114,6 +145,12 @@ pub use session::{
     SessionData, SessionMetadata, SessionState, SessionQuery,
     ToolCallHistory, ToolCallStatus,
 };
+pub use feedback::{Feedback, FeedbackQuery, Thumbs};
+pub use state::{AgentState, StateChange};
+pub use session_export::{
+    SessionExport, SessionImportOptions, ExportedSession, ExportedMessage,
+    ExportedCitation, ExportedToolCall, SESSION_EXPORT_FORMAT, SESSION_EXPORT_VERSION,
+};
 
 // Re-export orchestration
 pub use orchestration::{
@@ -121,12 +158,43 @@ pub use orchestration::{
     CollaborationTask, OrchestrationPattern, AgentRole,
     AgentExecutionState, VotingStrategy, RetryConfig,
 };
+pub use blackboard::{
+    Artifact, ArtifactVersion, Blackboard, BlackboardChange, BlackboardChangeKind,
+};
+pub use consensus::{
+    ConsensusEntry, ConsensusOutcome, ConsensusRound, ConsensusSelection, ConsensusStage,
+};
 
 // Re-export events
 pub use events::{
     EventBus, EventHandler, EventFilter,
     LogEventHandler, MetricsEventHandler,
 };
+pub use webhooks::{
+    WebhookDispatcher, WebhookEndpoint, WebhookRetryConfig, WebhookTransport,
+    HttpWebhookTransport, WebhookDelivery, DeliveryStatus,
+};
+
+// Re-export versioning
+pub use versioning::{
+    AgentVersion, AgentVersionRouter, ToolSnapshot,
+    TrafficSplit, VersionStats,
+};
+
+// Re-export context window management
+pub use context_window::{
+    ContextWindowManager, TruncationStrategy,
+    DropOldest, Summarize, ImportanceRanked, context_window_for_model,
+};
+
+// Re-export RAG binding
+pub use retrieval::{RagBinding, RETRIEVAL_TOOL_ID};
+
+// Re-export scheduler
+pub use scheduler::{
+    PriorityClass, RequestScheduler, SchedulerConfig, SchedulerPermit,
+    TenantQuota, TenantSchedulerStats,
+};
 
 /// Create a basic agent with default configuration
 pub fn create_basic_agent(
@@ -147,6 +215,8 @@ pub fn create_basic_agent(
         metadata: None,
         max_tool_calls: None,
         tool_timeout: None,
+        reflection: None,
+        few_shot: None,
     };
 
     BasicAgent::new(_config, llm)
@@ -466,6 +536,8 @@ mod tests {
             metadata: None,
             max_tool_calls: None,
             tool_timeout: None,
+            reflection: None,
+            few_shot: None,
         };
         
         let mock_llm = Arc::new(MockLlmProvider::new(vec![
@@ -492,4 +564,21 @@ mod tests {
         
         assert_eq!(result.response, "The tool returned: Echo: Hello from tool!");
     }
+
+    #[tokio::test]
+    async fn test_map_over_dataset() {
+        let mock_llm = Arc::new(MockLlmProvider::new(vec!["label: positive".to_string()]));
+        let agent = create_basic_agent(
+            "Labeler".to_string(),
+            "Label the sentiment of the review.".to_string(),
+            mock_llm,
+        );
+
+        let dataset = vec!["Love it".to_string(), "Works great".to_string(), "Five stars".to_string()];
+        let responses = agent.map_over(dataset, &LlmOptions::default()).await.unwrap();
+
+        assert_eq!(responses.len(), 3);
+        assert_eq!(responses[2].custom_id, "2");
+        assert!(responses.iter().all(|r| r.content.as_deref() == Some("label: positive")));
+    }
 }
\ No newline at end of file
@@ -6,6 +6,7 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 use crate::llm::{LlmProvider, OpenAiProvider, AnthropicProvider, QwenProvider};
+use crate::config::SecretResolver;
 use crate::{Error, Result};
 
 /// Model resolver for automatic LLM provider creation
@@ -222,6 +223,21 @@ impl ModelResolver {
         self.api_keys.insert(provider.to_string(), api_key.to_string());
     }
     
+    /// Add an API key resolved through the secrets subsystem
+    ///
+    /// `reference` may be a secret reference such as `vault://openai#api_key`
+    /// or a literal key.
+    pub async fn add_api_key_from_secret(
+        &mut self,
+        provider: &str,
+        reference: &str,
+        secrets: &SecretResolver,
+    ) -> Result<()> {
+        let api_key = secrets.resolve(reference).await?;
+        self.api_keys.insert(provider.to_string(), api_key);
+        Ok(())
+    }
+    
     /// List supported models
     pub fn list_models(&self) -> Vec<String> {
         self.model_mappings.keys().cloned().collect()
@@ -1,6 +1,9 @@
 //! Agent编排系统
 //! 
 //! 提供多Agent协作、编排和调度功能，支持复杂的Agent交互模式。
+//!
+//! 协作会话中的Agent通过共享[`Blackboard`]交换产物：每个Agent执行时收到黑板上已有的产物，
+//! 其回复以该Agent的ID为名写入黑板。
 
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -13,6 +16,8 @@ use uuid::Uuid;
 use crate::agent::trait_def::Agent;
 use crate::llm::Message;
 use crate::error::{Result, Error};
+use super::blackboard::{Artifact, Blackboard};
+use super::consensus::{run_consensus, ConsensusSelection, ConsensusStage};
 use super::events::{AgentEvent, EventBus};
 
 /// Agent编排模式
@@ -43,6 +48,11 @@ pub enum OrchestrationPattern {
         threshold: f32, // 0.0-1.0
         strategy: VotingStrategy,
     },
+    /// 辩论共识：各Agent独立作答并互评`rounds`轮，再由投票或评判选出最终答案
+    Consensus {
+        rounds: usize,
+        selection: ConsensusSelection,
+    },
 }
 
 /// 投票策略
@@ -140,6 +150,8 @@ pub struct CollaborationSession {
     pub context: Arc<RwLock<HashMap<String, serde_json::Value>>>,
     /// 事件总线
     pub event_bus: Arc<EventBus>,
+    /// Agent共享的产物黑板
+    pub blackboard: Arc<Blackboard>,
     /// 创建时间
     pub created_at: DateTime<Utc>,
     /// 更新时间
@@ -169,12 +181,19 @@ impl CollaborationSession {
             agent_states: Arc::new(RwLock::new(agent_states)),
             message_history: Arc::new(RwLock::new(Vec::new())),
             context: Arc::new(RwLock::new(HashMap::new())),
+            blackboard: Arc::new(Blackboard::new().with_event_bus(event_bus.clone())),
             event_bus,
             created_at: now,
             updated_at: Arc::new(Mutex::new(now)),
         }
     }
     
+    /// 使用已有的黑板，例如预先写入了参考资料或与其他会话共享
+    pub fn with_blackboard(mut self, blackboard: Arc<Blackboard>) -> Self {
+        self.blackboard = blackboard;
+        self
+    }
+    
     /// 添加消息到历史
     pub async fn add_message(&self, message: Message) {
         let mut history = self.message_history.write().await;
@@ -300,9 +319,11 @@ impl BasicOrchestrator {
                 // 更新状态为运行中
                 session.update_agent_state(agent_id, AgentExecutionState::Running).await;
                 
-                // 执行Agent
-                match self.execute_single_agent(agent.clone(), &session.task.input).await {
+                // 执行Agent，前面的Agent写入的产物随输入提供
+                let shared = session.blackboard.render().await;
+                match execute_single_agent(agent.clone(), session.task.input.clone(), shared).await {
                     Ok(result) => {
+                        publish_result(&session.blackboard, agent_id, &result).await?;
                         results.insert(agent_id.clone(), result.clone());
                         session.update_agent_state(agent_id, AgentExecutionState::Completed(result)).await;
                     }
@@ -321,6 +342,8 @@ impl BasicOrchestrator {
     /// 执行并行模式
     async fn execute_parallel(&self, session: &mut CollaborationSession) -> Result<serde_json::Value> {
         let mut handles = Vec::new();
+        // 所有Agent看到启动前的黑板内容
+        let shared = session.blackboard.render().await;
 
         // 启动所有Agent
         for agent_id in &session.task.participants {
@@ -329,32 +352,13 @@ impl BasicOrchestrator {
 
                 let agent_clone = agent.clone();
                 let input_clone = session.task.input.clone();
+                let shared_clone = shared.clone();
                 let agent_id_clone = agent_id.clone();
 
-                // 创建一个函数来执行单个Agent
-                async fn execute_single_agent_async(
-                    agent: Arc<dyn Agent>,
-                    input: serde_json::Value
-                ) -> std::result::Result<serde_json::Value, crate::error::Error> {
-                    // 简化实现：将JSON输入转换为消息
-                    let input_text = input.to_string();
-                    let messages = vec![crate::llm::Message {
-                        role: crate::llm::Role::User,
-                        content: input_text,
-                        metadata: None,
-                        name: None,
-                    }];
-
-                    let options = crate::agent::types::AgentGenerateOptions::default();
-                    let result = agent.generate(&messages, &options).await?;
-
-                    Ok(serde_json::Value::String(result.response))
-                }
-
-                let handle = tokio::spawn(async move {
-                    let result = execute_single_agent_async(agent_clone, input_clone).await;
+                let handle = tokio::spawn(crate::request_context::propagate(async move {
+                    let result = execute_single_agent(agent_clone, input_clone, shared_clone).await;
                     (agent_id_clone, result)
-                });
+                }));
 
                 handles.push(handle);
             }
@@ -367,6 +371,7 @@ impl BasicOrchestrator {
                 Ok((agent_id, result)) => {
                     match result {
                         Ok(value) => {
+                            publish_result(&session.blackboard, &agent_id, &value).await?;
                             results.insert(agent_id.clone(), value.clone());
                             session.update_agent_state(&agent_id, AgentExecutionState::Completed(value)).await;
                         }
@@ -385,24 +390,88 @@ impl BasicOrchestrator {
         Ok(serde_json::Value::Object(results))
     }
     
-    /// 执行单个Agent
-    async fn execute_single_agent(&self, agent: Arc<dyn Agent>, input: &serde_json::Value) -> Result<serde_json::Value> {
-        // 简化实现：将JSON输入转换为消息
-        let input_text = input.to_string();
-        let messages = vec![crate::llm::Message {
-            role: crate::llm::Role::User,
-            content: input_text,
-            metadata: None,
-            name: None,
-        }];
+    /// 执行辩论共识模式，结果包含最终答案、票数和各轮发言记录
+    async fn execute_consensus(
+        &self,
+        session: &mut CollaborationSession,
+        rounds: usize,
+        selection: &ConsensusSelection,
+    ) -> Result<serde_json::Value> {
+        let participants: Vec<(String, Arc<dyn Agent>)> = session.task.participants.iter()
+            .filter_map(|agent_id| session.agents.get(agent_id).map(|agent| (agent_id.clone(), agent.clone())))
+            .collect();
+        let judge = match selection {
+            ConsensusSelection::Judge(judge_id) => session.agents.get(judge_id).map(|agent| (judge_id.clone(), agent.clone())),
+            ConsensusSelection::Vote(_) => None,
+        };
+        let question = match &session.task.input {
+            serde_json::Value::String(text) => text.clone(),
+            other => other.to_string(),
+        };
         
-        let options = crate::agent::types::AgentGenerateOptions::default();
-        let result = agent.generate(&messages, &options).await?;
+        for (agent_id, _) in &participants {
+            session.update_agent_state(agent_id, AgentExecutionState::Running).await;
+        }
+        let outcome = match run_consensus(&participants, judge, &question, rounds, selection).await {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                for (agent_id, _) in &participants {
+                    session.update_agent_state(agent_id, AgentExecutionState::Failed(e.to_string())).await;
+                }
+                return Err(Error::Agent(format!("Consensus failed: {}", e)));
+            }
+        };
+        
+        // 各Agent的最终答案为最后一轮互评后的答案
+        let final_answers = outcome.rounds.iter()
+            .rev()
+            .find(|round| round.stage != ConsensusStage::Selection)
+            .map(|round| round.entries.clone())
+            .unwrap_or_default();
+        for entry in final_answers {
+            session.update_agent_state(&entry.agent_id, AgentExecutionState::Completed(entry.content.into())).await;
+        }
         
-        Ok(serde_json::Value::String(result.response))
+        let rationale = format!("Selected the answer of {} after {} critique rounds", outcome.selected, rounds);
+        session.blackboard.write("consensus", Artifact::decision(outcome.answer.clone(), Some(rationale)), None).await?;
+        session.blackboard.write("consensus_transcript", Artifact::data(&outcome.rounds)?, None).await?;
+        Ok(serde_json::to_value(outcome)?)
     }
 }
 
+/// 执行单个Agent，`shared`为黑板内容，作为上下文消息提供
+async fn execute_single_agent(
+    agent: Arc<dyn Agent>,
+    input: serde_json::Value,
+    shared: Option<String>,
+) -> Result<serde_json::Value> {
+    // 简化实现：将JSON输入转换为消息
+    let input_text = input.to_string();
+    let messages = vec![crate::llm::Message {
+        role: crate::llm::Role::User,
+        content: input_text,
+        metadata: None,
+        name: None,
+    }];
+    
+    let options = crate::agent::types::AgentGenerateOptions {
+        context: shared.map(|shared| vec![crate::agent::types::system_message(shared)]),
+        ..Default::default()
+    };
+    let result = agent.generate(&messages, &options).await?;
+    
+    Ok(serde_json::Value::String(result.response))
+}
+
+/// 将Agent的回复以其ID为名写入黑板
+async fn publish_result(blackboard: &Blackboard, agent_id: &str, result: &serde_json::Value) -> Result<u64> {
+    let artifact = match result {
+        serde_json::Value::String(text) => Artifact::document(text.clone()),
+        other => Artifact::Data { value: other.clone() },
+    };
+    blackboard.write(agent_id, artifact, Some(agent_id)).await
+}
+
 #[async_trait]
 impl AgentOrchestrator for BasicOrchestrator {
     async fn execute_collaboration(&self, session: &mut CollaborationSession) -> Result<serde_json::Value> {
@@ -413,6 +482,10 @@ impl AgentOrchestrator for BasicOrchestrator {
             OrchestrationPattern::Parallel => {
                 self.execute_parallel(session).await
             }
+            OrchestrationPattern::Consensus { rounds, selection } => {
+                let (rounds, selection) = (*rounds, selection.clone());
+                self.execute_consensus(session, rounds, &selection).await
+            }
             _ => {
                 Err(Error::Agent("Unsupported orchestration pattern".to_string()))
             }
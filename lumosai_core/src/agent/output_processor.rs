@@ -0,0 +1,180 @@
+//! Post-processing of final agent responses
+//!
+//! An agent runs its [`OutputProcessor`]s in order on the final response, after
+//! self-reflection and trace capture and before the response is returned. A processor
+//! may rewrite the text or reject it with an error such as [`Error::guardrail`].
+
+use async_trait::async_trait;
+use regex::Regex;
+
+use super::trace::split_reasoning;
+use crate::{Error, Result};
+
+/// A step that transforms or rejects a final response
+#[async_trait]
+pub trait OutputProcessor: Send + Sync {
+    /// Name used in logs and errors
+    fn name(&self) -> &str;
+
+    /// Transform the response, or fail to reject it
+    async fn process(&self, output: String) -> Result<String>;
+}
+
+/// Remove chain-of-thought: `<think>` blocks and the reasoning before a ReAct `Final Answer:`
+#[derive(Debug, Clone, Default)]
+pub struct StripReasoning;
+
+#[async_trait]
+impl OutputProcessor for StripReasoning {
+    fn name(&self) -> &str {
+        "strip_reasoning"
+    }
+
+    async fn process(&self, output: String) -> Result<String> {
+        Ok(split_reasoning(&output).1)
+    }
+}
+
+/// Tidy markdown: unwrap a reply fenced as a whole, drop trailing whitespace,
+/// collapse runs of blank lines and close an unterminated code fence
+#[derive(Debug, Clone, Default)]
+pub struct MarkdownCleanup;
+
+#[async_trait]
+impl OutputProcessor for MarkdownCleanup {
+    fn name(&self) -> &str {
+        "markdown_cleanup"
+    }
+
+    async fn process(&self, output: String) -> Result<String> {
+        Ok(clean_markdown(&output))
+    }
+}
+
+fn clean_markdown(output: &str) -> String {
+    let trimmed = output.trim();
+    let unwrapped = ["```markdown\n", "```md\n"].iter()
+        .find_map(|fence| trimmed.strip_prefix(fence))
+        .and_then(|inner| inner.strip_suffix("```"))
+        .filter(|inner| !inner.contains("```"))
+        .unwrap_or(trimmed);
+
+    let mut lines: Vec<&str> = Vec::new();
+    for line in unwrapped.lines().map(str::trim_end) {
+        if line.is_empty() && lines.last().is_some_and(|last| last.is_empty()) {
+            continue;
+        }
+        lines.push(line);
+    }
+
+    let mut cleaned = lines.join("\n").trim().to_string();
+    if cleaned.lines().filter(|line| line.trim_start().starts_with("```")).count() % 2 == 1 {
+        cleaned.push_str("\n```");
+    }
+    cleaned
+}
+
+/// Rewrite link prefixes, e.g. internal hosts to their public equivalents
+#[derive(Debug, Clone, Default)]
+pub struct LinkRewriter {
+    rules: Vec<(String, String)>,
+}
+
+impl LinkRewriter {
+    /// Create a rewriter without rules
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace URLs starting with `from` by `to` followed by the rest of the URL
+    pub fn rewrite(mut self, from: impl Into<String>, to: impl Into<String>) -> Self {
+        self.rules.push((from.into(), to.into()));
+        self
+    }
+}
+
+#[async_trait]
+impl OutputProcessor for LinkRewriter {
+    fn name(&self) -> &str {
+        "link_rewriter"
+    }
+
+    async fn process(&self, output: String) -> Result<String> {
+        Ok(self.rules.iter().fold(output, |output, (from, to)| output.replace(from.as_str(), to)))
+    }
+}
+
+/// Replace matches of patterns such as emails or card numbers
+#[derive(Debug, Clone, Default)]
+pub struct Redactor {
+    patterns: Vec<(Regex, String)>,
+}
+
+impl Redactor {
+    /// Create a redactor without patterns
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Redact email addresses and payment card numbers
+    pub fn with_defaults() -> Self {
+        Self::new()
+            .pattern(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}", "[REDACTED_EMAIL]")
+            .and_then(|redactor| redactor.pattern(r"\b(?:\d[ -]?){13,16}\b", "[REDACTED_CARD]"))
+            .expect("default redaction patterns are valid")
+    }
+
+    /// Replace every match of `pattern` with `replacement`
+    pub fn pattern(mut self, pattern: &str, replacement: impl Into<String>) -> Result<Self> {
+        let regex = Regex::new(pattern)
+            .map_err(|e| Error::Configuration(format!("Invalid redaction pattern '{}': {}", pattern, e)))?;
+        self.patterns.push((regex, replacement.into()));
+        Ok(self)
+    }
+
+    /// Apply every pattern to `text`
+    pub fn redact(&self, text: &str) -> String {
+        self.patterns.iter().fold(text.to_string(), |text, (regex, replacement)| {
+            regex.replace_all(&text, regex::NoExpand(replacement)).into_owned()
+        })
+    }
+}
+
+#[async_trait]
+impl OutputProcessor for Redactor {
+    fn name(&self) -> &str {
+        "redactor"
+    }
+
+    async fn process(&self, output: String) -> Result<String> {
+        Ok(self.redact(&output))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_markdown_cleanup() {
+        let output = "```markdown\n# Title   \n\n\n\nBody\n```".to_string();
+        assert_eq!(MarkdownCleanup.process(output).await.unwrap(), "# Title\n\nBody");
+
+        let output = "Run:\n```bash\ncargo test".to_string();
+        assert_eq!(MarkdownCleanup.process(output).await.unwrap(), "Run:\n```bash\ncargo test\n```");
+    }
+
+    #[tokio::test]
+    async fn test_link_rewriter_and_redactor() {
+        let rewriter = LinkRewriter::new().rewrite("http://wiki.internal/", "https://docs.example.com/");
+        let output = rewriter.process("See http://wiki.internal/setup".to_string()).await.unwrap();
+        assert_eq!(output, "See https://docs.example.com/setup");
+
+        let output = Redactor::with_defaults()
+            .process("Mail ana@example.com, card 4111 1111 1111 1111".to_string())
+            .await
+            .unwrap();
+        assert_eq!(output, "Mail [REDACTED_EMAIL], card [REDACTED_CARD]");
+        assert!(Redactor::new().pattern("(", "x").is_err());
+    }
+}
@@ -0,0 +1,465 @@
+//! 先规划后执行的代理
+//!
+//! [`PlannerAgent`]先让规划模型把目标分解为结构化的计划（步骤及所需工具），再由执行代理逐步完成。
+//! 进度记录在类型化的[`PlanState`]中，它实现了[`AgentState`]，可以保存到会话并在之后继续执行。
+//! 步骤失败时，规划模型根据已完成的步骤和失败原因重新规划剩余步骤；超过重新规划次数后返回错误，
+//! 此时状态中保留了已完成的步骤。
+
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::error::{Error, Result};
+use crate::llm::{LlmProvider, Message};
+use super::message_utils::{parse_json_reply, system_message, user_message};
+use super::state::{AgentState, StateChange};
+use super::trait_def::Agent;
+use super::types::AgentGenerateOptions;
+
+/// 计划中的一个步骤
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlanStep {
+    /// 步骤要完成的工作
+    pub description: String,
+    /// 完成步骤需要的工具
+    #[serde(default)]
+    pub tools: Vec<String>,
+}
+
+/// 规划模型生成的计划
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Plan {
+    /// 按执行顺序排列的步骤
+    pub steps: Vec<PlanStep>,
+}
+
+/// 步骤的执行状态
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    /// 等待执行
+    #[default]
+    Pending,
+    /// 正在执行
+    Running,
+    /// 已完成
+    Completed,
+    /// 执行失败，已被重新规划的步骤替代
+    Failed,
+}
+
+/// 一个步骤的执行进度
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StepProgress {
+    /// 计划中的步骤
+    pub step: PlanStep,
+    /// 执行状态
+    pub status: StepStatus,
+    /// 执行代理的输出
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    /// 失败原因
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl StepProgress {
+    fn pending(step: PlanStep) -> Self {
+        Self {
+            step,
+            status: StepStatus::Pending,
+            output: None,
+            error: None,
+        }
+    }
+}
+
+/// 计划的执行进度
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlanState {
+    /// 要完成的目标
+    pub goal: String,
+    /// 所有步骤，包括已失败的步骤
+    pub steps: Vec<StepProgress>,
+    /// 已重新规划的次数
+    pub replans: u32,
+    /// 所有步骤完成后汇总的最终回答
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub answer: Option<String>,
+}
+
+impl PlanState {
+    /// 为目标和计划创建初始状态
+    pub fn new(goal: impl Into<String>, plan: Plan) -> Self {
+        Self {
+            goal: goal.into(),
+            steps: plan.steps.into_iter().map(StepProgress::pending).collect(),
+            replans: 0,
+            answer: None,
+        }
+    }
+
+    /// 下一个待执行步骤的下标；中断时处于执行中的步骤会被重新执行
+    pub fn next_step(&self) -> Option<usize> {
+        self.steps.iter().position(|progress| matches!(progress.status, StepStatus::Pending | StepStatus::Running))
+    }
+
+    /// 是否所有步骤都已结束
+    pub fn is_finished(&self) -> bool {
+        self.next_step().is_none()
+    }
+
+    /// 已完成的步骤
+    pub fn completed_steps(&self) -> impl Iterator<Item = &StepProgress> {
+        self.steps.iter().filter(|progress| progress.status == StepStatus::Completed)
+    }
+
+    /// 用新计划替换尚未执行的步骤，保留已完成和已失败的步骤
+    fn replace_remaining(&mut self, plan: Plan) {
+        self.steps.retain(|progress| matches!(progress.status, StepStatus::Completed | StepStatus::Failed));
+        self.steps.extend(plan.steps.into_iter().map(StepProgress::pending));
+    }
+
+    /// 已完成步骤及其输出的文字描述
+    fn completed_summary(&self) -> String {
+        let summary: Vec<String> = self.completed_steps()
+            .enumerate()
+            .map(|(i, progress)| format!(
+                "{}. {}\n   Result: {}",
+                i + 1,
+                progress.step.description,
+                progress.output.as_deref().unwrap_or_default()
+            ))
+            .collect();
+        if summary.is_empty() {
+            "(none)".to_string()
+        } else {
+            summary.join("\n")
+        }
+    }
+}
+
+impl AgentState for PlanState {
+    const STATE_KEY: &'static str = "plan_state";
+
+    fn to_state_value(&self) -> Result<Value> {
+        Ok(serde_json::to_value(self)?)
+    }
+
+    fn from_state_value(value: &Value) -> Result<Self> {
+        Ok(serde_json::from_value(value.clone())?)
+    }
+
+    fn state_schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "goal": { "type": "string" },
+                "steps": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "step": {
+                                "type": "object",
+                                "properties": {
+                                    "description": { "type": "string" },
+                                    "tools": { "type": "array", "items": { "type": "string" } }
+                                },
+                                "required": ["description"]
+                            },
+                            "status": { "type": "string", "enum": ["pending", "running", "completed", "failed"] },
+                            "output": { "type": "string" },
+                            "error": { "type": "string" }
+                        },
+                        "required": ["step", "status"]
+                    }
+                },
+                "replans": { "type": "integer", "minimum": 0 },
+                "answer": { "type": "string" }
+            }
+        })
+    }
+
+    fn diff(&self, previous: &Self) -> Result<Vec<StateChange>> {
+        let (new, old) = (self.to_state_value()?, previous.to_state_value()?);
+        Ok(["goal", "steps", "replans", "answer"].into_iter()
+            .filter_map(|field| {
+                let (old, new) = (old.get(field).cloned().unwrap_or(Value::Null), new.get(field).cloned().unwrap_or(Value::Null));
+                (old != new).then(|| StateChange { field: field.to_string(), old, new })
+            })
+            .collect())
+    }
+}
+
+/// 先规划后执行的代理
+///
+/// 规划模型负责分解目标、重新规划和汇总最终回答，执行代理逐个完成步骤，可以使用它自己的工具。
+pub struct PlannerAgent {
+    name: String,
+    planner: Arc<dyn LlmProvider>,
+    worker: Arc<dyn Agent>,
+    max_steps: usize,
+    max_replans: u32,
+}
+
+impl PlannerAgent {
+    /// 创建构建器
+    pub fn builder() -> PlannerAgentBuilder {
+        PlannerAgentBuilder::default()
+    }
+
+    /// 代理名称
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// 规划并执行目标，返回包含最终回答的状态
+    pub async fn execute(&self, goal: &str, options: &AgentGenerateOptions) -> Result<PlanState> {
+        let plan = self.plan(goal, options).await?;
+        let mut state = PlanState::new(goal, plan);
+        self.resume(&mut state, options).await?;
+        Ok(state)
+    }
+
+    /// 把目标分解为计划
+    pub async fn plan(&self, goal: &str, options: &AgentGenerateOptions) -> Result<Plan> {
+        let messages = vec![
+            system_message(self.planning_instructions()),
+            user_message(format!("Goal: {}", goal)),
+        ];
+        self.request_plan(&messages, options).await
+    }
+
+    /// 从状态中的下一个步骤继续执行，直到所有步骤完成并汇总出最终回答
+    ///
+    /// 状态在每个步骤后更新；返回错误时状态中保留了已完成的步骤，可以保存后再次调用继续执行。
+    pub async fn resume(&self, state: &mut PlanState, options: &AgentGenerateOptions) -> Result<String> {
+        while let Some(index) = state.next_step() {
+            state.steps[index].status = StepStatus::Running;
+            let prompt = self.step_prompt(state, index);
+
+            match self.worker.generate(&[user_message(prompt)], options).await {
+                Ok(result) => {
+                    let progress = &mut state.steps[index];
+                    progress.status = StepStatus::Completed;
+                    progress.output = Some(result.response);
+                },
+                // 取消不是步骤失败，不重新规划
+                Err(Error::Cancelled(reason)) => {
+                    state.steps[index].status = StepStatus::Pending;
+                    return Err(Error::Cancelled(reason));
+                },
+                Err(e) => {
+                    let progress = &mut state.steps[index];
+                    progress.status = StepStatus::Failed;
+                    progress.error = Some(e.to_string());
+
+                    if state.replans >= self.max_replans {
+                        return Err(Error::Agent(format!(
+                            "Plan step '{}' failed after {} replans: {}",
+                            state.steps[index].step.description, state.replans, e
+                        )));
+                    }
+                    let plan = self.replan(state, index, options).await?;
+                    state.replace_remaining(plan);
+                    state.replans += 1;
+                },
+            }
+        }
+
+        let answer = self.summarize(state, options).await?;
+        state.answer = Some(answer.clone());
+        Ok(answer)
+    }
+
+    /// 根据已完成的步骤和失败原因规划剩余步骤
+    async fn replan(&self, state: &PlanState, failed: usize, options: &AgentGenerateOptions) -> Result<Plan> {
+        let failed = &state.steps[failed];
+        let messages = vec![
+            system_message(self.planning_instructions()),
+            user_message(format!(
+                "Goal: {}\n\nCompleted steps:\n{}\n\nFailed step: {}\nError: {}\n\n\
+                 Plan the remaining steps to reach the goal without repeating completed steps.",
+                state.goal,
+                state.completed_summary(),
+                failed.step.description,
+                failed.error.as_deref().unwrap_or_default()
+            )),
+        ];
+        self.request_plan(&messages, options).await
+    }
+
+    /// 汇总步骤结果为最终回答
+    async fn summarize(&self, state: &PlanState, options: &AgentGenerateOptions) -> Result<String> {
+        let messages = vec![
+            system_message("Combine the results of the completed steps into the final answer for the user. Reply with the answer only."),
+            user_message(format!("Goal: {}\n\nCompleted steps:\n{}", state.goal, state.completed_summary())),
+        ];
+        self.planner.generate_with_messages(&messages, &options.llm_options).await
+    }
+
+    /// 请求规划模型生成计划并校验
+    async fn request_plan(&self, messages: &[Message], options: &AgentGenerateOptions) -> Result<Plan> {
+        let reply = self.planner.generate_with_messages(messages, &options.llm_options).await?;
+        let mut plan: Plan = parse_json_reply(&reply)
+            .ok_or_else(|| Error::Parsing(format!("Planner returned an invalid plan: {}", reply)))?;
+        if plan.steps.is_empty() {
+            return Err(Error::Parsing("Planner returned a plan without steps".to_string()));
+        }
+        if plan.steps.len() > self.max_steps {
+            return Err(Error::Constraint(format!(
+                "Plan has {} steps, more than the limit of {}",
+                plan.steps.len(), self.max_steps
+            )));
+        }
+
+        // 执行代理没有的工具无法使用，只保留可用的工具
+        let available = self.worker.get_tools();
+        for step in &mut plan.steps {
+            step.tools.retain(|tool| {
+                let known = available.contains_key(tool);
+                if !known {
+                    tracing::warn!("Planner referenced unknown tool '{}'", tool);
+                }
+                known
+            });
+        }
+        Ok(plan)
+    }
+
+    /// 规划模型的系统提示，包括执行代理可用的工具
+    fn planning_instructions(&self) -> String {
+        let mut tools: Vec<String> = self.worker.get_tools().iter()
+            .map(|(name, tool)| format!("- {}: {}", name, tool.description()))
+            .collect();
+        tools.sort();
+        let tools = if tools.is_empty() { "(none)".to_string() } else { tools.join("\n") };
+
+        format!(
+            "You are a planner. Break the user's goal into at most {} concrete steps for a worker agent, \
+             in the order they must be done.\n\nTools available to the worker:\n{}\n\n\
+             Respond only with JSON of the form {{\"steps\": [{{\"description\": \"<what to do>\", \"tools\": [\"<tool name>\"]}}]}}.",
+            self.max_steps, tools
+        )
+    }
+
+    /// 执行代理处理一个步骤时的输入
+    fn step_prompt(&self, state: &PlanState, index: usize) -> String {
+        let step = &state.steps[index].step;
+        let tools = if step.tools.is_empty() { "none".to_string() } else { step.tools.join(", ") };
+        format!(
+            "Overall goal: {}\n\nCompleted steps:\n{}\n\nCurrent step: {}\nSuggested tools: {}\n\n\
+             Complete only the current step and report its result.",
+            state.goal,
+            state.completed_summary(),
+            step.description,
+            tools
+        )
+    }
+}
+
+/// [`PlannerAgent`]的构建器
+pub struct PlannerAgentBuilder {
+    name: String,
+    planner: Option<Arc<dyn LlmProvider>>,
+    worker: Option<Arc<dyn Agent>>,
+    max_steps: usize,
+    max_replans: u32,
+}
+
+impl Default for PlannerAgentBuilder {
+    fn default() -> Self {
+        Self {
+            name: "planner".to_string(),
+            planner: None,
+            worker: None,
+            max_steps: 10,
+            max_replans: 2,
+        }
+    }
+}
+
+impl PlannerAgentBuilder {
+    /// 设置代理名称
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// 设置规划模型，默认使用执行代理的模型
+    pub fn planner(mut self, planner: Arc<dyn LlmProvider>) -> Self {
+        self.planner = Some(planner);
+        self
+    }
+
+    /// 设置执行步骤的代理
+    pub fn worker(mut self, worker: Arc<dyn Agent>) -> Self {
+        self.worker = Some(worker);
+        self
+    }
+
+    /// 设置计划的最大步骤数
+    pub fn max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    /// 设置步骤失败后最多重新规划的次数
+    pub fn max_replans(mut self, max_replans: u32) -> Self {
+        self.max_replans = max_replans;
+        self
+    }
+
+    /// 构建代理，必须设置执行代理
+    pub fn build(self) -> Result<PlannerAgent> {
+        let worker = self.worker
+            .ok_or_else(|| Error::Configuration("PlannerAgent requires a worker agent".to_string()))?;
+        let planner = self.planner.unwrap_or_else(|| worker.get_llm());
+        Ok(PlannerAgent {
+            name: self.name,
+            planner,
+            worker,
+            max_steps: self.max_steps,
+            max_replans: self.max_replans,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(description: &str) -> PlanStep {
+        PlanStep { description: description.to_string(), tools: Vec::new() }
+    }
+
+    #[test]
+    fn test_replanning_keeps_finished_steps() {
+        let mut state = PlanState::new("Book a trip", Plan { steps: vec![step("search"), step("book"), step("pay")] });
+        state.steps[0].status = StepStatus::Completed;
+        state.steps[1].status = StepStatus::Failed;
+        assert_eq!(state.next_step(), Some(2));
+
+        state.replace_remaining(Plan { steps: vec![step("book elsewhere")] });
+        let descriptions: Vec<&str> = state.steps.iter().map(|p| p.step.description.as_str()).collect();
+        assert_eq!(descriptions, ["search", "book", "book elsewhere"]);
+        assert_eq!(state.next_step(), Some(2));
+    }
+
+    #[test]
+    fn test_plan_state_round_trips_as_agent_state() {
+        let previous = PlanState::new("Book a trip", Plan { steps: vec![step("search")] });
+        let mut state = previous.clone();
+        state.steps[0].status = StepStatus::Completed;
+        state.steps[0].output = Some("3 flights".to_string());
+
+        let restored = PlanState::from_state_value(&state.to_state_value().unwrap()).unwrap();
+        assert_eq!(restored, state);
+        let changes = state.diff(&previous).unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].field, "steps");
+    }
+}
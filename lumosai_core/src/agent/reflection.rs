@@ -0,0 +1,162 @@
+//! Self-reflection loop for agents
+//!
+//! When an agent has a [`ReflectionConfig`], its final answer is treated as a draft:
+//! the model critiques the draft as a judge, scoring it between 0.0 and 1.0, and
+//! revises it using the critique. The loop stops once the score reaches the
+//! configured threshold or the iteration limit is hit.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::llm::{Message, Role};
+use super::message_utils::parse_json_reply;
+
+/// Default critique criteria used when none are configured
+const DEFAULT_CRITERIA: &str = "correctness, completeness, and whether the answer directly addresses the request";
+
+/// Configuration for generate → critique → revise loops
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReflectionConfig {
+    /// Maximum number of critique/revise iterations after the first draft
+    pub max_iterations: u32,
+    /// Judge score (0.0 to 1.0) at which the answer is accepted
+    pub score_threshold: f64,
+    /// Criteria the judge should apply, replacing the default criteria
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub criteria: Option<String>,
+}
+
+impl Default for ReflectionConfig {
+    fn default() -> Self {
+        Self {
+            max_iterations: 2,
+            score_threshold: 0.8,
+            criteria: None,
+        }
+    }
+}
+
+impl ReflectionConfig {
+    /// Create a reflection config with the default limits
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum number of critique/revise iterations
+    pub fn with_max_iterations(mut self, max_iterations: u32) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Set the judge score at which the answer is accepted
+    pub fn with_score_threshold(mut self, score_threshold: f64) -> Self {
+        self.score_threshold = score_threshold;
+        self
+    }
+
+    /// Set the criteria the judge applies
+    pub fn with_criteria(mut self, criteria: impl Into<String>) -> Self {
+        self.criteria = Some(criteria.into());
+        self
+    }
+
+    /// Messages asking the judge to score a draft answer to the conversation
+    pub(crate) fn critique_messages(&self, conversation: &[Message], draft: &str) -> Vec<Message> {
+        let criteria = self.criteria.as_deref().unwrap_or(DEFAULT_CRITERIA);
+        let request = conversation.iter()
+            .filter(|message| message.role != Role::System)
+            .map(|message| format!("{}: {}", message.role, message.content))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        vec![
+            Message {
+                role: Role::System,
+                content: format!(
+                    "You are a strict reviewer. Judge the assistant's answer on {}. \
+                     Respond only with JSON of the form {{\"score\": <number between 0 and 1>, \"critique\": \"<specific problems to fix>\"}}.",
+                    criteria
+                ),
+                metadata: None,
+                name: None,
+            },
+            Message {
+                role: Role::User,
+                content: format!("Conversation:\n{}\n\nAnswer to review:\n{}", request, draft),
+                metadata: None,
+                name: None,
+            },
+        ]
+    }
+
+    /// Messages asking the agent to revise its draft using the critique
+    pub(crate) fn revision_messages(&self, conversation: &[Message], draft: &str, critique: &Critique) -> Vec<Message> {
+        let mut messages = conversation.to_vec();
+        messages.push(Message {
+            role: Role::Assistant,
+            content: draft.to_string(),
+            metadata: None,
+            name: None,
+        });
+        messages.push(Message {
+            role: Role::User,
+            content: format!(
+                "A reviewer scored your answer {:.2} with this critique:\n{}\n\n\
+                 Rewrite the answer to address the critique. Reply with the improved answer only.",
+                critique.score, critique.feedback
+            ),
+            metadata: None,
+            name: None,
+        });
+        messages
+    }
+}
+
+/// A judge's verdict on a draft answer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Critique {
+    /// Score between 0.0 and 1.0
+    pub score: f64,
+    /// What should be improved
+    pub feedback: String,
+}
+
+impl Critique {
+    /// Parse the judge's JSON reply, tolerating surrounding text and code fences
+    pub fn parse(reply: &str) -> Option<Self> {
+        let value: Value = parse_json_reply(reply)?;
+        let score = value.get("score")?.as_f64()?;
+        let feedback = value.get("critique")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        Some(Self {
+            score: score.clamp(0.0, 1.0),
+            feedback,
+        })
+    }
+
+    /// Whether the draft is good enough to stop revising
+    pub fn accepts(&self, config: &ReflectionConfig) -> bool {
+        self.score >= config.score_threshold
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_critique() {
+        let critique = Critique::parse("```json\n{\"score\": 0.4, \"critique\": \"Missing the price\"}\n```").unwrap();
+        assert_eq!(critique, Critique { score: 0.4, feedback: "Missing the price".to_string() });
+        assert!(!critique.accepts(&ReflectionConfig::default()));
+
+        let critique = Critique::parse("{\"score\": 3}").unwrap();
+        assert_eq!(critique.score, 1.0);
+        assert!(critique.accepts(&ReflectionConfig::default()));
+
+        assert!(Critique::parse("Looks good to me").is_none());
+        assert!(Critique::parse("{\"critique\": \"no score\"}").is_none());
+    }
+}
@@ -0,0 +1,88 @@
+//! 代理的RAG绑定
+//!
+//! 把向量存储中的一个集合绑定到代理：构建代理时注册一个检索工具，
+//! 用代理的LLM为查询生成嵌入，并返回集合中最相关的`top_k`条内容。
+
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use crate::error::{Error, Result};
+use crate::llm::LlmProvider;
+use crate::tool::{FunctionTool, ParameterSchema, Tool, ToolSchema};
+use crate::vector::VectorStorage;
+
+/// 检索工具的ID
+pub const RETRIEVAL_TOOL_ID: &str = "knowledge_search";
+
+/// 代理检索使用的集合与结果数
+#[derive(Clone)]
+pub struct RagBinding {
+    /// 向量存储
+    pub store: Arc<dyn VectorStorage>,
+    /// 检索的集合（索引）名称
+    pub collection: String,
+    /// 每次检索返回的结果数
+    pub top_k: usize,
+}
+
+impl RagBinding {
+    /// 绑定存储中的集合，默认返回5条结果
+    pub fn new(store: Arc<dyn VectorStorage>, collection: impl Into<String>) -> Self {
+        Self {
+            store,
+            collection: collection.into(),
+            top_k: 5,
+        }
+    }
+
+    /// 设置每次检索返回的结果数
+    pub fn with_top_k(mut self, top_k: usize) -> Self {
+        self.top_k = top_k;
+        self
+    }
+
+    /// 创建检索工具，查询由`llm`生成嵌入
+    pub fn into_tool(self, llm: Arc<dyn LlmProvider>) -> Box<dyn Tool> {
+        let schema = ToolSchema::new(vec![ParameterSchema {
+            name: "query".to_string(),
+            description: "What to look up in the knowledge base".to_string(),
+            r#type: "string".to_string(),
+            required: true,
+            properties: None,
+            default: None,
+        }]);
+        let description = format!("Searches the '{}' knowledge base for passages relevant to a query", self.collection);
+
+        Box::new(FunctionTool::new_async(RETRIEVAL_TOOL_ID, description, schema, move |params: Value| {
+            let binding = self.clone();
+            let llm = llm.clone();
+            Box::pin(async move {
+                let query = params.get("query").and_then(Value::as_str)
+                    .ok_or_else(|| Error::InvalidParams("Required parameter 'query' is missing".to_string()))?;
+                let embedding = llm.get_embedding(query).await?;
+                let results = binding.store
+                    .query(&binding.collection, embedding, binding.top_k, None, false)
+                    .await?;
+
+                let passages: Vec<Value> = results.into_iter()
+                    .map(|result| json!({
+                        "id": result.id,
+                        "score": result.score,
+                        "metadata": result.metadata.unwrap_or_default(),
+                    }))
+                    .collect();
+                Ok(json!({ "collection": binding.collection, "results": passages }))
+            })
+        }))
+    }
+}
+
+impl std::fmt::Debug for RagBinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RagBinding")
+            .field("collection", &self.collection)
+            .field("top_k", &self.top_k)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,287 @@
+//! Review queue for low-confidence answers
+//!
+//! Answers whose judge or guardrail confidence falls below a threshold are flagged
+//! into a [`ReviewQueue`]. Reviewers approve, correct or dismiss them; approved and
+//! corrected answers become few-shot examples for the agent and can be exported as
+//! eval cases, so the agent learns from the cases it was least sure about.
+//!
+//! An agent with both a review queue and self-reflection flags its answer when the
+//! final judge score is below the threshold. Other sources, such as guardrails,
+//! submit their confidence through [`ReviewQueue::consider`].
+
+use std::sync::RwLock;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::agent::few_shot::{FewShotExample, FewShotStore};
+use crate::error::{Error, Result};
+
+/// Default confidence below which answers are flagged
+pub const DEFAULT_REVIEW_THRESHOLD: f64 = 0.6;
+
+/// Where an item is in the review process
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewStatus {
+    /// Waiting for a reviewer
+    Pending,
+    /// The answer was right as given
+    Approved,
+    /// The reviewer supplied the right answer
+    Corrected,
+    /// Not useful for learning, e.g. an unclear question
+    Dismissed,
+}
+
+/// A reviewer's decision on a flagged answer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum ReviewDecision {
+    /// Accept the answer
+    Approve,
+    /// Replace the answer with a correction
+    Correct {
+        /// The right answer
+        correction: String,
+    },
+    /// Drop the item
+    Dismiss,
+}
+
+/// A flagged answer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReviewItem {
+    /// Item ID
+    pub id: String,
+    /// Agent that gave the answer
+    pub agent: String,
+    /// The user's message
+    pub input: String,
+    /// The flagged answer
+    pub answer: String,
+    /// Confidence between 0 and 1
+    pub confidence: f64,
+    /// What scored the answer, e.g. `judge` or `guardrail`
+    pub source: String,
+    /// Why the answer scored low, such as the judge's critique
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Review status
+    pub status: ReviewStatus,
+    /// The reviewer's correction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correction: Option<String>,
+    /// Who reviewed the item
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reviewer: Option<String>,
+    /// When the answer was flagged
+    pub created_at: DateTime<Utc>,
+    /// When the item was reviewed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reviewed_at: Option<DateTime<Utc>>,
+    /// Whether the item was added to the agent's few-shot examples
+    #[serde(default)]
+    pub applied: bool,
+}
+
+impl ReviewItem {
+    /// The answer the agent should have given: the correction or the approved answer
+    pub fn expected_output(&self) -> Option<&str> {
+        match self.status {
+            ReviewStatus::Corrected => self.correction.as_deref(),
+            ReviewStatus::Approved => Some(&self.answer),
+            ReviewStatus::Pending | ReviewStatus::Dismissed => None,
+        }
+    }
+
+    /// Few-shot example teaching the expected output, once reviewed
+    pub fn to_few_shot_example(&self) -> Option<FewShotExample> {
+        let output = self.expected_output()?;
+        Some(FewShotExample::new(self.input.clone(), output).with_label(format!("review:{}", self.id)))
+    }
+}
+
+/// Queue of answers awaiting human review
+#[derive(Debug)]
+pub struct ReviewQueue {
+    items: RwLock<Vec<ReviewItem>>,
+    threshold: f64,
+}
+
+impl Default for ReviewQueue {
+    fn default() -> Self {
+        Self::new(DEFAULT_REVIEW_THRESHOLD)
+    }
+}
+
+impl ReviewQueue {
+    /// Create a queue flagging answers with confidence below `threshold`
+    pub fn new(threshold: f64) -> Self {
+        Self {
+            items: RwLock::new(Vec::new()),
+            threshold,
+        }
+    }
+
+    /// Confidence below which answers are flagged
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+
+    /// Flag the answer if its confidence is below the threshold, returning the new item
+    pub fn consider(
+        &self,
+        agent: impl Into<String>,
+        input: impl Into<String>,
+        answer: impl Into<String>,
+        confidence: f64,
+        source: impl Into<String>,
+        reason: Option<String>,
+    ) -> Option<ReviewItem> {
+        if confidence >= self.threshold {
+            return None;
+        }
+
+        let item = ReviewItem {
+            id: uuid::Uuid::new_v4().to_string(),
+            agent: agent.into(),
+            input: input.into(),
+            answer: answer.into(),
+            confidence: confidence.clamp(0.0, 1.0),
+            source: source.into(),
+            reason,
+            status: ReviewStatus::Pending,
+            correction: None,
+            reviewer: None,
+            created_at: Utc::now(),
+            reviewed_at: None,
+            applied: false,
+        };
+        self.write().push(item.clone());
+        Some(item)
+    }
+
+    /// Items with the given status, or all items, oldest first
+    pub fn list(&self, status: Option<ReviewStatus>) -> Vec<ReviewItem> {
+        self.read().iter()
+            .filter(|item| status.map_or(true, |status| item.status == status))
+            .cloned()
+            .collect()
+    }
+
+    /// Number of items waiting for review
+    pub fn pending_count(&self) -> usize {
+        self.read().iter().filter(|item| item.status == ReviewStatus::Pending).count()
+    }
+
+    /// The item with `id`
+    pub fn get(&self, id: &str) -> Option<ReviewItem> {
+        self.read().iter().find(|item| item.id == id).cloned()
+    }
+
+    /// Record a reviewer's decision, replacing any earlier one
+    pub fn review(&self, id: &str, decision: ReviewDecision, reviewer: Option<String>) -> Result<ReviewItem> {
+        let (status, correction) = match decision {
+            ReviewDecision::Approve => (ReviewStatus::Approved, None),
+            ReviewDecision::Correct { correction } if correction.trim().is_empty() => {
+                return Err(Error::InvalidInput("Correction must not be empty".to_string()));
+            }
+            ReviewDecision::Correct { correction } => (ReviewStatus::Corrected, Some(correction)),
+            ReviewDecision::Dismiss => (ReviewStatus::Dismissed, None),
+        };
+
+        let mut items = self.write();
+        let item = items.iter_mut()
+            .find(|item| item.id == id)
+            .ok_or_else(|| Error::NotFound(format!("Review item '{}' not found", id)))?;
+        item.status = status;
+        item.correction = correction;
+        item.reviewer = reviewer;
+        item.reviewed_at = Some(Utc::now());
+        item.applied = false;
+        Ok(item.clone())
+    }
+
+    /// Add the agent's reviewed answers not yet applied to its few-shot examples
+    ///
+    /// A re-reviewed item replaces the example added for its earlier decision.
+    /// Returns the number of examples added.
+    pub fn apply_to_few_shot(&self, agent: &str, store: &FewShotStore) -> usize {
+        let mut applied = 0;
+        for item in self.write().iter_mut().filter(|item| item.agent == agent && !item.applied) {
+            let label = format!("review:{}", item.id);
+            if let Some(index) = store.examples().iter().position(|example| example.label.as_deref() == Some(label.as_str())) {
+                store.remove(index);
+            }
+            if let Some(example) = item.to_few_shot_example() {
+                store.add(example);
+                applied += 1;
+            }
+            item.applied = item.status != ReviewStatus::Pending;
+        }
+        applied
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, Vec<ReviewItem>> {
+        self.items.read().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, Vec<ReviewItem>> {
+        self.items.write().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::few_shot::FewShotConfig;
+
+    #[test]
+    fn test_low_confidence_answers_are_flagged_and_reviewed() {
+        let queue = ReviewQueue::new(0.5);
+        assert!(queue.consider("support", "Refund?", "Yes.", 0.9, "judge", None).is_none());
+        let item = queue.consider("support", "Refund after 60 days?", "Yes.", 0.3, "judge", Some("Ignores the policy".to_string())).unwrap();
+        let other = queue.consider("sales", "Discount?", "No.", 0.1, "guardrail", None).unwrap();
+        assert_eq!(queue.pending_count(), 2);
+
+        assert!(matches!(
+            queue.review(&item.id, ReviewDecision::Correct { correction: " ".to_string() }, None),
+            Err(Error::InvalidInput(_))
+        ));
+        assert!(matches!(queue.review("missing", ReviewDecision::Approve, None), Err(Error::NotFound(_))));
+
+        let decision = ReviewDecision::Correct { correction: "No, refunds are limited to 30 days.".to_string() };
+        let reviewed = queue.review(&item.id, decision, Some("ann".to_string())).unwrap();
+        assert_eq!(reviewed.expected_output(), Some("No, refunds are limited to 30 days."));
+        queue.review(&other.id, ReviewDecision::Dismiss, None).unwrap();
+        assert_eq!(queue.list(Some(ReviewStatus::Pending)).len(), 0);
+        assert_eq!(queue.list(Some(ReviewStatus::Corrected))[0].reviewer.as_deref(), Some("ann"));
+
+        let decision: ReviewDecision = serde_json::from_str(r#"{"action": "correct", "correction": "Ask billing."}"#).unwrap();
+        assert_eq!(decision, ReviewDecision::Correct { correction: "Ask billing.".to_string() });
+    }
+
+    #[test]
+    fn test_reviewed_answers_become_few_shot_examples() {
+        let queue = ReviewQueue::default();
+        let store = FewShotStore::new(FewShotConfig::new(vec![FewShotExample::new("Hi", "Hello!")]));
+        let item = queue.consider("support", "Refund after 60 days?", "Yes.", 0.2, "judge", None).unwrap();
+        queue.consider("sales", "Discount?", "No.", 0.1, "judge", None).unwrap();
+
+        // Pending items are not applied
+        assert_eq!(queue.apply_to_few_shot("support", &store), 0);
+
+        queue.review(&item.id, ReviewDecision::Approve, None).unwrap();
+        assert_eq!(queue.apply_to_few_shot("support", &store), 1);
+        assert_eq!(queue.apply_to_few_shot("support", &store), 0);
+        assert_eq!(store.examples()[1].output, "Yes.");
+
+        // A later correction replaces the approved example
+        queue.review(&item.id, ReviewDecision::Correct { correction: "Only within 30 days.".to_string() }, None).unwrap();
+        assert_eq!(queue.apply_to_few_shot("support", &store), 1);
+        let examples = store.examples();
+        assert_eq!(examples.len(), 2);
+        assert_eq!(examples[1].output, "Only within 30 days.");
+    }
+}
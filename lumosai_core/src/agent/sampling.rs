@@ -0,0 +1,137 @@
+//! Multi-response sampling and best-of-n selection
+//!
+//! [`Agent::generate_n`](super::trait_def::Agent::generate_n) runs the same request several
+//! times concurrently, varying temperature and seed so the candidates differ.
+//! [`Agent::generate_best_of_n`](super::trait_def::Agent::generate_best_of_n) then asks a
+//! judge model to pick the best candidate.
+
+use serde::Deserialize;
+
+use crate::llm::{LlmOptions, LlmProvider, Message, Role};
+use crate::{Error, Result};
+use super::message_utils::parse_json_reply;
+use super::types::{AgentGenerateOptions, AgentGenerateResult};
+
+/// Temperature used for the first candidate when none is configured
+const DEFAULT_TEMPERATURE: f32 = 0.7;
+
+/// Temperature added for each further candidate
+const TEMPERATURE_STEP: f32 = 0.15;
+
+/// Highest temperature a candidate is sampled at
+const MAX_TEMPERATURE: f32 = 1.5;
+
+/// Default judging criteria
+const DEFAULT_CRITERIA: &str = "correctness, helpfulness, and how directly the answer addresses the request";
+
+/// Options for candidate `index`: a rising temperature and a distinct seed
+///
+/// The first candidate keeps the configured temperature. Seeds continue from
+/// the configured seed, or from 0.
+pub fn candidate_options(options: &AgentGenerateOptions, index: usize) -> AgentGenerateOptions {
+    let mut candidate = options.clone();
+    let base = options.llm_options.temperature.unwrap_or(DEFAULT_TEMPERATURE);
+    let temperature = (base + TEMPERATURE_STEP * index as f32).min(MAX_TEMPERATURE.max(base));
+    let base_seed = options.llm_options.seed.unwrap_or(0);
+
+    candidate.llm_options.temperature = Some(temperature);
+    candidate.llm_options.seed = Some(base_seed + index as u64);
+    candidate.run_id = options.run_id.as_ref().map(|run_id| format!("{}-{}", run_id, index));
+    candidate
+}
+
+/// Candidates sampled for one request and the judge's choice
+#[derive(Debug, Clone)]
+pub struct BestOfN {
+    /// All successful candidates, in sampling order
+    pub candidates: Vec<AgentGenerateResult>,
+    /// Index of the chosen candidate
+    pub selected: usize,
+    /// The judge's reason for the choice
+    pub reason: Option<String>,
+}
+
+impl BestOfN {
+    /// The chosen candidate
+    pub fn best(&self) -> &AgentGenerateResult {
+        &self.candidates[self.selected]
+    }
+}
+
+/// A judge's verdict over candidates
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Selection {
+    /// Zero-based index of the best candidate
+    pub best: usize,
+    /// Why it was chosen
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// Ask `judge` which candidate answers the conversation best
+pub async fn select_best(
+    judge: &dyn LlmProvider,
+    conversation: &[Message],
+    candidates: &[&str],
+    criteria: Option<&str>,
+) -> Result<Selection> {
+    let request = conversation.iter()
+        .filter(|message| message.role != Role::System)
+        .map(|message| format!("{}: {}", message.role, message.content))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let listing = candidates.iter()
+        .enumerate()
+        .map(|(index, candidate)| format!("Candidate {}:\n{}", index, candidate))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    let messages = vec![
+        Message {
+            role: Role::System,
+            content: format!(
+                "You are a strict judge comparing candidate answers on {}. \
+                 Respond only with JSON of the form {{\"best\": <candidate number>, \"reason\": \"<short justification>\"}}.",
+                criteria.unwrap_or(DEFAULT_CRITERIA)
+            ),
+            metadata: None,
+            name: None,
+        },
+        Message {
+            role: Role::User,
+            content: format!("Conversation:\n{}\n\n{}", request, listing),
+            metadata: None,
+            name: None,
+        },
+    ];
+
+    let reply = judge.generate_with_messages(&messages, &LlmOptions::default().with_temperature(0.0)).await?;
+    parse_json_reply::<Selection>(&reply)
+        .filter(|selection| selection.best < candidates.len())
+        .ok_or_else(|| Error::Parsing(format!("Judge reply does not select one of {} candidates: {}", candidates.len(), reply)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_candidate_options_vary_temperature_and_seed() {
+        let mut options = AgentGenerateOptions::default();
+        options.llm_options.temperature = Some(0.2);
+        options.llm_options.seed = Some(42);
+        options.run_id = Some("run".to_string());
+
+        let first = candidate_options(&options, 0);
+        assert_eq!(first.llm_options.temperature, Some(0.2));
+        assert_eq!(first.llm_options.seed, Some(42));
+
+        let third = candidate_options(&options, 2);
+        assert!((third.llm_options.temperature.unwrap() - 0.5).abs() < 1e-6);
+        assert_eq!(third.llm_options.seed, Some(44));
+        assert_eq!(third.run_id.as_deref(), Some("run-2"));
+
+        let hot = candidate_options(&options, 100);
+        assert_eq!(hot.llm_options.temperature, Some(MAX_TEMPERATURE));
+    }
+}
@@ -0,0 +1,401 @@
+//! 多租户请求调度
+//!
+//! 在服务层为Agent请求分配执行槽位：
+//!
+//! - 每个租户有独立的并发配额和排队上限，超过排队上限的请求直接被拒绝；
+//! - 请求分为交互式和批处理两个优先级，交互式请求总是先于批处理请求调度，
+//!   并且批处理请求最多只能占用 `max_concurrent - reserved_interactive` 个槽位，
+//!   保证某个租户的批量任务不会让聊天流量饿死；
+//! - 同一优先级内按租户权重做加权公平调度：优先调度 `运行中请求数 / 权重` 最小的租户，
+//!   相同时轮流调度。
+
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use tokio::sync::oneshot;
+
+use crate::error::{Error, Result};
+
+/// 请求优先级
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PriorityClass {
+    /// 交互式请求，如聊天
+    Interactive,
+    /// 批处理请求，如批量评测和数据导入
+    Batch,
+}
+
+impl PriorityClass {
+    const ALL: [PriorityClass; 2] = [PriorityClass::Interactive, PriorityClass::Batch];
+
+    fn index(self) -> usize {
+        match self {
+            PriorityClass::Interactive => 0,
+            PriorityClass::Batch => 1,
+        }
+    }
+
+    /// 从字符串解析，无法识别时返回 `None`
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "interactive" => Some(PriorityClass::Interactive),
+            "batch" => Some(PriorityClass::Batch),
+            _ => None,
+        }
+    }
+}
+
+/// 租户配额
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TenantQuota {
+    /// 同时运行的最大请求数
+    pub max_concurrent: usize,
+    /// 最大排队请求数
+    pub max_queued: usize,
+    /// 公平调度权重
+    pub weight: u32,
+}
+
+impl Default for TenantQuota {
+    fn default() -> Self {
+        Self {
+            max_concurrent: 8,
+            max_queued: 100,
+            weight: 1,
+        }
+    }
+}
+
+/// 调度器配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchedulerConfig {
+    /// 全局最大并发请求数
+    pub max_concurrent: usize,
+    /// 为交互式请求保留的槽位数
+    pub reserved_interactive: usize,
+    /// 未单独配置的租户使用的配额
+    pub default_quota: TenantQuota,
+    /// 最长排队时间
+    pub queue_timeout: Duration,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent: 64,
+            reserved_interactive: 16,
+            default_quota: TenantQuota::default(),
+            queue_timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+/// 租户调度统计
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TenantSchedulerStats {
+    /// 运行中的请求数
+    pub running: usize,
+    /// 排队中的交互式请求数
+    pub queued_interactive: usize,
+    /// 排队中的批处理请求数
+    pub queued_batch: usize,
+    /// 累计被拒绝的请求数
+    pub rejected: u64,
+}
+
+struct Waiter {
+    id: u64,
+    sender: oneshot::Sender<SchedulerPermit>,
+}
+
+#[derive(Default)]
+struct TenantState {
+    quota: Option<TenantQuota>,
+    running: usize,
+    queues: [VecDeque<Waiter>; 2],
+    last_served: u64,
+    rejected: u64,
+}
+
+#[derive(Default)]
+struct SchedulerState {
+    tenants: HashMap<String, TenantState>,
+    running: [usize; 2],
+    next_id: u64,
+    /// 调度序号，用于同等条件下的轮流调度
+    tick: u64,
+}
+
+struct SchedulerInner {
+    config: SchedulerConfig,
+    state: Mutex<SchedulerState>,
+}
+
+/// 多租户请求调度器
+#[derive(Clone)]
+pub struct RequestScheduler {
+    inner: Arc<SchedulerInner>,
+}
+
+/// 执行许可，释放时让出槽位
+pub struct SchedulerPermit {
+    inner: Arc<SchedulerInner>,
+    tenant: String,
+    priority: PriorityClass,
+    /// 未送达等待方的许可不归还槽位
+    armed: bool,
+}
+
+impl SchedulerPermit {
+    /// 所属租户
+    pub fn tenant(&self) -> &str {
+        &self.tenant
+    }
+
+    /// 请求优先级
+    pub fn priority(&self) -> PriorityClass {
+        self.priority
+    }
+}
+
+impl Drop for SchedulerPermit {
+    fn drop(&mut self) {
+        if self.armed {
+            let mut state = self.inner.state.lock().unwrap();
+            self.inner.release(&mut state, &self.tenant, self.priority);
+        }
+    }
+}
+
+impl SchedulerInner {
+    fn quota<'a>(&'a self, tenant: &'a TenantState) -> &'a TenantQuota {
+        tenant.quota.as_ref().unwrap_or(&self.config.default_quota)
+    }
+
+    fn release(self: &Arc<Self>, state: &mut SchedulerState, tenant: &str, priority: PriorityClass) {
+        state.running[priority.index()] -= 1;
+        if let Some(tenant) = state.tenants.get_mut(tenant) {
+            tenant.running -= 1;
+        }
+        self.dispatch(state);
+    }
+
+    fn capacity(&self, state: &SchedulerState, priority: PriorityClass) -> bool {
+        let total = state.running[0] + state.running[1];
+        match priority {
+            PriorityClass::Interactive => total < self.config.max_concurrent,
+            PriorityClass::Batch => {
+                let batch_limit = self.config.max_concurrent.saturating_sub(self.config.reserved_interactive).max(1);
+                total < self.config.max_concurrent && state.running[1] < batch_limit
+            }
+        }
+    }
+
+    /// 在有空闲槽位时唤醒排队的请求
+    fn dispatch(self: &Arc<Self>, state: &mut SchedulerState) {
+        for priority in PriorityClass::ALL {
+            while self.capacity(state, priority) {
+                let next = state.tenants.iter()
+                    .filter(|(_, tenant)| {
+                        !tenant.queues[priority.index()].is_empty()
+                            && tenant.running < self.quota(tenant).max_concurrent
+                    })
+                    .min_by(|(_, a), (_, b)| {
+                        let share_a = a.running as f64 / self.quota(a).weight.max(1) as f64;
+                        let share_b = b.running as f64 / self.quota(b).weight.max(1) as f64;
+                        share_a.total_cmp(&share_b).then(a.last_served.cmp(&b.last_served))
+                    })
+                    .map(|(name, _)| name.clone());
+                let Some(name) = next else {
+                    break;
+                };
+
+                state.tick += 1;
+                let tick = state.tick;
+                let tenant = state.tenants.get_mut(&name).expect("tenant exists");
+                let waiter = tenant.queues[priority.index()].pop_front().expect("queue not empty");
+                let permit = SchedulerPermit {
+                    inner: self.clone(),
+                    tenant: name,
+                    priority,
+                    armed: true,
+                };
+                // 等待方已放弃（超时或请求被取消）时不占用槽位
+                if let Err(mut permit) = waiter.sender.send(permit) {
+                    permit.armed = false;
+                    continue;
+                }
+                tenant.running += 1;
+                tenant.last_served = tick;
+                state.running[priority.index()] += 1;
+            }
+        }
+    }
+}
+
+impl RequestScheduler {
+    /// 创建调度器
+    pub fn new(config: SchedulerConfig) -> Self {
+        Self {
+            inner: Arc::new(SchedulerInner {
+                config,
+                state: Mutex::new(SchedulerState::default()),
+            }),
+        }
+    }
+
+    /// 调度器配置
+    pub fn config(&self) -> &SchedulerConfig {
+        &self.inner.config
+    }
+
+    /// 设置租户配额
+    pub fn set_tenant_quota(&self, tenant: impl Into<String>, quota: TenantQuota) {
+        let mut state = self.inner.state.lock().unwrap();
+        state.tenants.entry(tenant.into()).or_default().quota = Some(quota);
+        // 配额提高后可能有排队请求可以立即运行
+        self.inner.dispatch(&mut state);
+    }
+
+    /// 申请执行许可，必要时排队等待
+    ///
+    /// 排队已满时返回 [`Error::Unavailable`]，排队超时返回 [`Error::Timeout`]。
+    pub async fn acquire(&self, tenant: &str, priority: PriorityClass) -> Result<SchedulerPermit> {
+        let (id, receiver) = {
+            let mut state = self.inner.state.lock().unwrap();
+            state.next_id += 1;
+            let id = state.next_id;
+            let entry = state.tenants.entry(tenant.to_string()).or_default();
+            let queued: usize = entry.queues.iter().map(VecDeque::len).sum();
+            if queued >= self.inner.quota(entry).max_queued {
+                entry.rejected += 1;
+                return Err(Error::Unavailable(format!("租户 {} 的排队请求已达上限", tenant)));
+            }
+
+            let (sender, receiver) = oneshot::channel();
+            entry.queues[priority.index()].push_back(Waiter { id, sender });
+            self.inner.dispatch(&mut state);
+            (id, receiver)
+        };
+
+        // 接收端在这条语句结束时释放，超时瞬间送达的许可随之归还
+        let received = tokio::time::timeout(self.inner.config.queue_timeout, receiver).await;
+        match received {
+            Ok(Ok(permit)) => Ok(permit),
+            Ok(Err(_)) => Err(Error::Internal("调度器已关闭".to_string())),
+            Err(_) => {
+                let mut state = self.inner.state.lock().unwrap();
+                let tenant_state = state.tenants.get_mut(tenant).expect("tenant exists");
+                let queue = &mut tenant_state.queues[priority.index()];
+                if let Some(position) = queue.iter().position(|waiter| waiter.id == id) {
+                    queue.remove(position);
+                    tenant_state.rejected += 1;
+                }
+                Err(Error::Timeout(format!("租户 {} 的请求排队超时", tenant)))
+            }
+        }
+    }
+
+    /// 取得许可后执行任务
+    pub async fn run<F, T>(&self, tenant: &str, priority: PriorityClass, task: F) -> Result<T>
+    where
+        F: Future<Output = T>,
+    {
+        let _permit = self.acquire(tenant, priority).await?;
+        Ok(task.await)
+    }
+
+    /// 单个租户的调度统计
+    pub fn tenant_stats(&self, tenant: &str) -> TenantSchedulerStats {
+        let state = self.inner.state.lock().unwrap();
+        state.tenants.get(tenant)
+            .map(|tenant| TenantSchedulerStats {
+                running: tenant.running,
+                queued_interactive: tenant.queues[0].len(),
+                queued_batch: tenant.queues[1].len(),
+                rejected: tenant.rejected,
+            })
+            .unwrap_or_default()
+    }
+
+    /// 全部租户的调度统计
+    pub fn stats(&self) -> HashMap<String, TenantSchedulerStats> {
+        let names: Vec<String> = self.inner.state.lock().unwrap().tenants.keys().cloned().collect();
+        names.into_iter().map(|name| {
+            let stats = self.tenant_stats(&name);
+            (name, stats)
+        }).collect()
+    }
+}
+
+impl Default for RequestScheduler {
+    fn default() -> Self {
+        Self::new(SchedulerConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scheduler(max_concurrent: usize, reserved_interactive: usize) -> RequestScheduler {
+        RequestScheduler::new(SchedulerConfig {
+            max_concurrent,
+            reserved_interactive,
+            default_quota: TenantQuota { max_concurrent: 10, max_queued: 10, weight: 1 },
+            queue_timeout: Duration::from_millis(200),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_batch_cannot_starve_interactive() {
+        let scheduler = scheduler(3, 1);
+        let _b1 = scheduler.acquire("bulk", PriorityClass::Batch).await.unwrap();
+        let _b2 = scheduler.acquire("bulk", PriorityClass::Batch).await.unwrap();
+
+        // 批处理最多占用两个槽位，第三个排队
+        let queued = tokio::spawn({
+            let scheduler = scheduler.clone();
+            async move { scheduler.acquire("bulk", PriorityClass::Batch).await.map(|_| ()) }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(scheduler.tenant_stats("bulk").queued_batch, 1);
+
+        // 保留的槽位留给交互式请求
+        let chat = scheduler.acquire("acme", PriorityClass::Interactive).await.unwrap();
+        assert_eq!(chat.tenant(), "acme");
+        assert!(queued.await.unwrap().is_err());
+        assert_eq!(scheduler.tenant_stats("bulk").rejected, 1);
+    }
+
+    #[tokio::test]
+    async fn test_fair_share_and_quotas() {
+        let scheduler = scheduler(1, 0);
+        scheduler.set_tenant_quota("small", TenantQuota { max_concurrent: 1, max_queued: 1, weight: 1 });
+        let first = scheduler.acquire("big", PriorityClass::Interactive).await.unwrap();
+
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let mut handles = Vec::new();
+        for tenant in ["big", "big", "small"] {
+            let scheduler = scheduler.clone();
+            let order = order.clone();
+            handles.push(tokio::spawn(async move {
+                let _permit = scheduler.acquire(tenant, PriorityClass::Interactive).await.unwrap();
+                order.lock().unwrap().push(tenant);
+            }));
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert!(scheduler.acquire("small", PriorityClass::Interactive).await.is_err());
+
+        drop(first);
+        for handle in handles {
+            handle.await.unwrap();
+        }
+        // big刚被调度过，后到的small不会被big的积压挡住
+        assert_eq!(*order.lock().unwrap(), vec!["small", "big", "big"]);
+        assert_eq!(scheduler.tenant_stats("big"), TenantSchedulerStats::default());
+    }
+}
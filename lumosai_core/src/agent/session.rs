@@ -9,8 +9,13 @@ use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use tokio::sync::RwLock;
 
-use crate::llm::Message;
+use crate::llm::{Message, Role};
 use crate::error::{Result, Error};
+use super::feedback::{Feedback, FeedbackQuery};
+use super::message_utils::system_message;
+use super::state::{AgentState, StateChange};
+use super::trait_def::Agent;
+use super::types::{AgentGenerateOptions, AgentGenerateResult};
 
 /// 会话状态枚举
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -120,6 +125,18 @@ pub trait SessionStorage: Send + Sync {
     
     /// 清理过期会话
     async fn cleanup_expired_sessions(&self, before: DateTime<Utc>) -> Result<usize>;
+    
+    /// 保存消息反馈，同一用户对同一条消息的反馈覆盖之前的反馈
+    async fn save_feedback(&self, feedback: &Feedback) -> Result<()> {
+        let _ = feedback;
+        Err(Error::Unsupported("Session storage does not support feedback".to_string()))
+    }
+    
+    /// 查询消息反馈，按提交时间排序
+    async fn list_feedback(&self, query: &FeedbackQuery) -> Result<Vec<Feedback>> {
+        let _ = query;
+        Err(Error::Unsupported("Session storage does not support feedback".to_string()))
+    }
 }
 
 /// 会话查询条件
@@ -145,6 +162,7 @@ pub struct SessionQuery {
 /// 内存会话存储实现（用于测试和开发）
 pub struct MemorySessionStorage {
     sessions: Arc<RwLock<HashMap<String, SessionData>>>,
+    feedback: Arc<RwLock<Vec<Feedback>>>,
 }
 
 impl MemorySessionStorage {
@@ -152,6 +170,7 @@ impl MemorySessionStorage {
     pub fn new() -> Self {
         Self {
             sessions: Arc::new(RwLock::new(HashMap::new())),
+            feedback: Arc::new(RwLock::new(Vec::new())),
         }
     }
 }
@@ -178,6 +197,7 @@ impl SessionStorage for MemorySessionStorage {
     async fn delete_session(&self, session_id: &str) -> Result<()> {
         let mut sessions = self.sessions.write().await;
         sessions.remove(session_id);
+        self.feedback.write().await.retain(|feedback| feedback.session_id != session_id);
         Ok(())
     }
     
@@ -296,6 +316,26 @@ impl SessionStorage for MemorySessionStorage {
         
         Ok(initial_count - sessions.len())
     }
+    
+    async fn save_feedback(&self, feedback: &Feedback) -> Result<()> {
+        let mut entries = self.feedback.write().await;
+        entries.retain(|existing| !existing.same_target(feedback));
+        entries.push(feedback.clone());
+        Ok(())
+    }
+    
+    async fn list_feedback(&self, query: &FeedbackQuery) -> Result<Vec<Feedback>> {
+        let entries = self.feedback.read().await;
+        let mut results: Vec<Feedback> = entries.iter()
+            .filter(|feedback| query.matches(feedback))
+            .cloned()
+            .collect();
+        results.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        if let Some(limit) = query.limit {
+            results.truncate(limit);
+        }
+        Ok(results)
+    }
 }
 
 /// 会话管理器
@@ -392,6 +432,67 @@ impl SessionManager {
         Ok(())
     }
     
+    /// 为会话中的一条助手消息添加反馈
+    pub async fn add_feedback(&self, feedback: Feedback) -> Result<Feedback> {
+        feedback.validate()?;
+        let session = self.get_session(&feedback.session_id).await?
+            .ok_or_else(|| Error::NotFound(format!("Session '{}' not found", feedback.session_id)))?;
+        match session.messages.get(feedback.message_index) {
+            Some(message) if message.role == Role::Assistant => {},
+            Some(_) => return Err(Error::InvalidInput(format!(
+                "Message {} of session '{}' is not an assistant message", feedback.message_index, feedback.session_id
+            ))),
+            None => return Err(Error::InvalidInput(format!(
+                "Session '{}' has no message {}", feedback.session_id, feedback.message_index
+            ))),
+        }
+        self.storage.save_feedback(&feedback).await?;
+        Ok(feedback)
+    }
+    
+    /// 查询消息反馈
+    pub async fn list_feedback(&self, query: &FeedbackQuery) -> Result<Vec<Feedback>> {
+        self.storage.list_feedback(query).await
+    }
+    
+    /// 带类型化状态执行一次生成
+    ///
+    /// 生成前从会话加载状态并作为系统消息提供给代理，生成后由`update`更新状态；
+    /// 状态有变化时保存回会话。返回生成结果和变化的字段。
+    pub async fn generate_with_state<S, A, F>(
+        &self,
+        session_id: &str,
+        agent: &A,
+        messages: &[Message],
+        options: &AgentGenerateOptions,
+        update: F,
+    ) -> Result<(AgentGenerateResult, Vec<StateChange>)>
+    where
+        S: AgentState,
+        A: Agent + ?Sized,
+        F: FnOnce(&mut S, &AgentGenerateResult),
+    {
+        let mut session = self.get_session(session_id).await?
+            .ok_or_else(|| Error::NotFound(format!("Session '{}' not found", session_id)))?;
+        let previous = S::load(&session)?;
+        
+        let mut prompt = Vec::with_capacity(messages.len() + 1);
+        prompt.push(system_message(previous.state_prompt()?));
+        prompt.extend_from_slice(messages);
+        let result = agent.generate(&prompt, options).await?;
+        
+        let mut state = previous.clone();
+        update(&mut state, &result);
+        let changes = state.diff(&previous)?;
+        if !changes.is_empty() {
+            state.save(&mut session)?;
+            session.metadata.updated_at = Utc::now();
+            self.update_session(&session).await?;
+        }
+        
+        Ok((result, changes))
+    }
+    
     /// 清理过期会话
     pub async fn cleanup_expired(&self) -> Result<usize> {
         let cutoff = Utc::now() - self.default_expiry;
@@ -0,0 +1,399 @@
+//! 会话导出与导入
+//!
+//! 将会话导出为可移植的JSON文档，用于在不同部署之间迁移会话、附加到工单或在评测中回放。
+//!
+//! 文档格式（`format = "lumos.session"`，`version = 1`）：
+//!
+//! ```json
+//! {
+//!   "format": "lumos.session",
+//!   "version": 1,
+//!   "exported_at": "2024-01-01T00:00:00Z",
+//!   "session": {
+//!     "id": "session-1",
+//!     "agent_name": "support",
+//!     "user_id": "user-1",
+//!     "title": "Refund request",
+//!     "state": "Active",
+//!     "created_at": "...",
+//!     "updated_at": "...",
+//!     "tags": [],
+//!     "properties": {}
+//!   },
+//!   "messages": [
+//!     {
+//!       "role": "assistant",
+//!       "content": "...",
+//!       "name": null,
+//!       "citations": [{ "source": "kb/refunds.md", "title": null, "url": null, "snippet": "...", "score": 0.82 }],
+//!       "metadata": {}
+//!     }
+//!   ],
+//!   "tool_calls": [
+//!     { "id": "call-1", "tool": "lookup_order", "arguments": {}, "result": {}, "status": "Success", "error": null, "timestamp": "..." }
+//!   ],
+//!   "context": {}
+//! }
+//! ```
+//!
+//! 消息的 `citations` 来自消息元数据中的 `citations` 字段，导入时会写回该字段。
+//! 时间均为RFC 3339格式，未知字段在导入时被忽略，以便旧版本读取新版本导出的文档。
+
+use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::llm::{Message, Role};
+use super::session::{
+    SessionData, SessionManager, SessionMetadata, SessionState, ToolCallHistory, ToolCallStatus,
+};
+
+/// 导出文档的格式标识
+pub const SESSION_EXPORT_FORMAT: &str = "lumos.session";
+
+/// 当前导出格式版本
+pub const SESSION_EXPORT_VERSION: u32 = 1;
+
+/// 消息元数据中存放引用的字段
+pub const CITATIONS_METADATA_KEY: &str = "citations";
+
+/// 会话导出文档
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionExport {
+    /// 格式标识
+    pub format: String,
+    /// 格式版本
+    pub version: u32,
+    /// 导出时间
+    pub exported_at: DateTime<Utc>,
+    /// 会话信息
+    pub session: ExportedSession,
+    /// 消息历史
+    pub messages: Vec<ExportedMessage>,
+    /// 工具调用历史
+    #[serde(default)]
+    pub tool_calls: Vec<ExportedToolCall>,
+    /// 会话上下文变量
+    #[serde(default)]
+    pub context: HashMap<String, serde_json::Value>,
+}
+
+/// 导出的会话信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedSession {
+    /// 会话ID
+    pub id: String,
+    /// Agent名称
+    pub agent_name: String,
+    /// 用户ID
+    #[serde(default)]
+    pub user_id: Option<String>,
+    /// 会话标题
+    #[serde(default)]
+    pub title: Option<String>,
+    /// 会话状态
+    pub state: SessionState,
+    /// 创建时间
+    pub created_at: DateTime<Utc>,
+    /// 最后更新时间
+    pub updated_at: DateTime<Utc>,
+    /// 标签
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// 额外属性
+    #[serde(default)]
+    pub properties: HashMap<String, serde_json::Value>,
+}
+
+/// 导出的消息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedMessage {
+    /// 角色：system、user、assistant、tool、function或自定义角色
+    pub role: String,
+    /// 内容
+    pub content: String,
+    /// 名称
+    #[serde(default)]
+    pub name: Option<String>,
+    /// 引用
+    #[serde(default)]
+    pub citations: Vec<ExportedCitation>,
+    /// 其他元数据
+    #[serde(default)]
+    pub metadata: HashMap<String, serde_json::Value>,
+}
+
+/// 导出的引用
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportedCitation {
+    /// 来源标识，如文档ID或路径
+    pub source: String,
+    /// 标题
+    #[serde(default)]
+    pub title: Option<String>,
+    /// 链接
+    #[serde(default)]
+    pub url: Option<String>,
+    /// 引用片段
+    #[serde(default)]
+    pub snippet: Option<String>,
+    /// 相关度
+    #[serde(default)]
+    pub score: Option<f32>,
+}
+
+/// 导出的工具调用
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedToolCall {
+    /// 调用ID
+    pub id: String,
+    /// 工具名称
+    pub tool: String,
+    /// 调用参数
+    pub arguments: serde_json::Value,
+    /// 调用结果
+    #[serde(default)]
+    pub result: Option<serde_json::Value>,
+    /// 执行状态
+    pub status: ToolCallStatus,
+    /// 错误信息
+    #[serde(default)]
+    pub error: Option<String>,
+    /// 调用时间
+    pub timestamp: DateTime<Utc>,
+}
+
+/// 导入选项
+#[derive(Debug, Clone, Default)]
+pub struct SessionImportOptions {
+    /// 使用新的会话ID，避免与目标部署中的会话冲突
+    pub session_id: Option<String>,
+    /// 覆盖同ID的已有会话
+    pub overwrite: bool,
+}
+
+impl SessionExport {
+    /// 序列化为格式化的JSON
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// 从JSON解析并校验格式
+    pub fn from_json(json: &str) -> Result<Self> {
+        let export: SessionExport = serde_json::from_str(json)?;
+        export.validate()?;
+        Ok(export)
+    }
+
+    /// 校验格式标识和版本
+    pub fn validate(&self) -> Result<()> {
+        if self.format != SESSION_EXPORT_FORMAT {
+            return Err(Error::Parsing(format!("不支持的会话导出格式: {}", self.format)));
+        }
+        if self.version == 0 || self.version > SESSION_EXPORT_VERSION {
+            return Err(Error::Unsupported(format!(
+                "不支持的会话导出版本: {} (支持 1..={})", self.version, SESSION_EXPORT_VERSION
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl ExportedMessage {
+    fn from_message(message: &Message) -> Self {
+        let mut metadata = message.metadata.clone().unwrap_or_default();
+        let citations = match metadata.remove(CITATIONS_METADATA_KEY) {
+            Some(value) => match serde_json::from_value::<Vec<ExportedCitation>>(value.clone()) {
+                Ok(citations) => citations,
+                Err(_) => {
+                    // 无法识别的引用格式原样保留在元数据中
+                    metadata.insert(CITATIONS_METADATA_KEY.to_string(), value);
+                    Vec::new()
+                }
+            },
+            None => Vec::new(),
+        };
+        Self {
+            role: message.role.as_str().to_string(),
+            content: message.content.clone(),
+            name: message.name.clone(),
+            citations,
+            metadata,
+        }
+    }
+
+    fn into_message(self) -> Result<Message> {
+        let mut metadata = self.metadata;
+        if !self.citations.is_empty() {
+            metadata.insert(CITATIONS_METADATA_KEY.to_string(), serde_json::to_value(&self.citations)?);
+        }
+        Ok(Message {
+            role: Role::new(self.role),
+            content: self.content,
+            metadata: if metadata.is_empty() { None } else { Some(metadata) },
+            name: self.name,
+        })
+    }
+}
+
+impl SessionData {
+    /// 导出为可移植的会话文档
+    pub fn export(&self) -> SessionExport {
+        let metadata = &self.metadata;
+        SessionExport {
+            format: SESSION_EXPORT_FORMAT.to_string(),
+            version: SESSION_EXPORT_VERSION,
+            exported_at: Utc::now(),
+            session: ExportedSession {
+                id: metadata.session_id.clone(),
+                agent_name: metadata.agent_name.clone(),
+                user_id: metadata.user_id.clone(),
+                title: metadata.title.clone(),
+                state: metadata.state.clone(),
+                created_at: metadata.created_at,
+                updated_at: metadata.updated_at,
+                tags: metadata.tags.clone(),
+                properties: metadata.properties.clone(),
+            },
+            messages: self.messages.iter().map(ExportedMessage::from_message).collect(),
+            tool_calls: self.tool_calls.iter()
+                .map(|call| ExportedToolCall {
+                    id: call.call_id.clone(),
+                    tool: call.tool_name.clone(),
+                    arguments: call.parameters.clone(),
+                    result: call.result.clone(),
+                    status: call.status.clone(),
+                    error: call.error.clone(),
+                    timestamp: call.timestamp,
+                })
+                .collect(),
+            context: self.context.clone(),
+        }
+    }
+
+    /// 从会话文档恢复会话数据
+    pub fn import(export: SessionExport) -> Result<Self> {
+        export.validate()?;
+        let session = export.session;
+        let messages = export.messages.into_iter()
+            .map(ExportedMessage::into_message)
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            metadata: SessionMetadata {
+                session_id: session.id,
+                user_id: session.user_id,
+                agent_name: session.agent_name,
+                title: session.title,
+                created_at: session.created_at,
+                updated_at: session.updated_at,
+                state: session.state,
+                message_count: messages.len(),
+                tags: session.tags,
+                properties: session.properties,
+            },
+            messages,
+            context: export.context,
+            tool_calls: export.tool_calls.into_iter()
+                .map(|call| ToolCallHistory {
+                    call_id: call.id,
+                    tool_name: call.tool,
+                    parameters: call.arguments,
+                    result: call.result,
+                    timestamp: call.timestamp,
+                    status: call.status,
+                    error: call.error,
+                })
+                .collect(),
+        })
+    }
+}
+
+impl SessionManager {
+    /// 导出会话
+    pub async fn export_session(&self, session_id: &str) -> Result<SessionExport> {
+        self.get_session(session_id).await?
+            .map(|session| session.export())
+            .ok_or_else(|| Error::NotFound(format!("会话不存在: {}", session_id)))
+    }
+
+    /// 导入会话并保存到存储后端
+    pub async fn import_session(&self, export: SessionExport, options: SessionImportOptions) -> Result<SessionData> {
+        let mut session = SessionData::import(export)?;
+        if let Some(session_id) = options.session_id {
+            session.metadata.session_id = session_id;
+        }
+        if !options.overwrite && self.get_session(&session.metadata.session_id).await?.is_some() {
+            return Err(Error::AlreadyExists(format!("会话已存在: {}", session.metadata.session_id)));
+        }
+        self.update_session(&session).await?;
+        Ok(session)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::agent::session::MemorySessionStorage;
+
+    #[tokio::test]
+    async fn test_session_export_import_round_trip() {
+        let source = SessionManager::new(Arc::new(MemorySessionStorage::new()));
+        source.create_session("s1".to_string(), "support".to_string(), Some("u1".to_string())).await.unwrap();
+        source.add_message("s1", Message::new(Role::User, "Where is my refund?".to_string(), None, None)).await.unwrap();
+
+        let mut metadata = HashMap::new();
+        metadata.insert(CITATIONS_METADATA_KEY.to_string(), serde_json::json!([
+            { "source": "kb/refunds.md", "snippet": "Refunds take 5 days", "score": 0.9 }
+        ]));
+        metadata.insert("model".to_string(), serde_json::json!("gpt-4o"));
+        source.add_message("s1", Message::new(Role::Assistant, "Within 5 days.".to_string(), Some(metadata), None)).await.unwrap();
+        source.add_tool_call("s1", ToolCallHistory {
+            call_id: "call-1".to_string(),
+            tool_name: "lookup_order".to_string(),
+            parameters: serde_json::json!({ "order": 42 }),
+            result: Some(serde_json::json!({ "status": "refunded" })),
+            timestamp: Utc::now(),
+            status: ToolCallStatus::Success,
+            error: None,
+        }).await.unwrap();
+
+        let json = source.export_session("s1").await.unwrap().to_json().unwrap();
+        let document: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(document["format"], SESSION_EXPORT_FORMAT);
+        assert_eq!(document["messages"][1]["role"], "assistant");
+        assert_eq!(document["messages"][1]["citations"][0]["source"], "kb/refunds.md");
+        assert_eq!(document["tool_calls"][0]["tool"], "lookup_order");
+
+        let target = SessionManager::new(Arc::new(MemorySessionStorage::new()));
+        let export = SessionExport::from_json(&json).unwrap();
+        target.import_session(export.clone(), SessionImportOptions::default()).await.unwrap();
+        assert!(target.import_session(export.clone(), SessionImportOptions::default()).await.is_err());
+
+        let imported = target.get_session("s1").await.unwrap().unwrap();
+        assert_eq!(imported.metadata.message_count, 2);
+        assert_eq!(imported.messages[0].role, Role::User);
+        let metadata = imported.messages[1].metadata.as_ref().unwrap();
+        assert_eq!(metadata["model"], "gpt-4o");
+        assert_eq!(metadata[CITATIONS_METADATA_KEY][0]["snippet"], "Refunds take 5 days");
+        assert_eq!(imported.tool_calls[0].status, ToolCallStatus::Success);
+
+        let copy = target.import_session(export, SessionImportOptions {
+            session_id: Some("s1-copy".to_string()),
+            overwrite: false,
+        }).await.unwrap();
+        assert_eq!(copy.metadata.session_id, "s1-copy");
+    }
+
+    #[test]
+    fn test_rejects_unknown_format() {
+        let json = r#"{"format":"other","version":1,"exported_at":"2024-01-01T00:00:00Z",
+            "session":{"id":"s","agent_name":"a","state":"Active","created_at":"2024-01-01T00:00:00Z","updated_at":"2024-01-01T00:00:00Z"},
+            "messages":[]}"#;
+        assert!(SessionExport::from_json(json).is_err());
+        assert!(SessionExport::from_json(&json.replace("\"other\"", "\"lumos.session\"")).is_ok());
+        assert!(SessionExport::from_json(&json.replace("\"other\"", "\"lumos.session\"").replace("\"version\":1", "\"version\":9")).is_err());
+    }
+}
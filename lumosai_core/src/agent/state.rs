@@ -0,0 +1,64 @@
+//! 代理会话的类型化状态
+//!
+//! 实现[`AgentState`]的结构体（通常通过`lumosai_derive`的`#[derive(AgentState)]`）保存在
+//! 会话上下文中，由[`SessionManager::generate_with_state`](super::SessionManager::generate_with_state)
+//! 在每次生成前加载、生成后保存。
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::Result;
+use super::session::SessionData;
+
+/// 状态字段的一次变更
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateChange {
+    /// 字段名
+    pub field: String,
+    /// 变更前的值
+    pub old: Value,
+    /// 变更后的值
+    pub new: Value,
+}
+
+/// 跨轮次保存在会话中的类型化状态
+pub trait AgentState: Default + Clone + Send + Sync + 'static {
+    /// 状态在会话上下文中的键
+    const STATE_KEY: &'static str;
+
+    /// 序列化为JSON对象
+    fn to_state_value(&self) -> Result<Value>;
+
+    /// 从JSON对象反序列化，缺失的字段取默认值
+    fn from_state_value(value: &Value) -> Result<Self>;
+
+    /// 状态的JSON Schema
+    fn state_schema() -> Value;
+
+    /// 与之前的状态相比发生变化的字段
+    fn diff(&self, previous: &Self) -> Result<Vec<StateChange>>;
+
+    /// 从会话加载状态，会话中没有时返回默认值
+    fn load(session: &SessionData) -> Result<Self> {
+        match session.context.get(Self::STATE_KEY) {
+            Some(value) => Self::from_state_value(value),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// 把状态保存到会话上下文
+    fn save(&self, session: &mut SessionData) -> Result<()> {
+        session.context.insert(Self::STATE_KEY.to_string(), self.to_state_value()?);
+        Ok(())
+    }
+
+    /// 以系统消息提供给代理的当前状态
+    fn state_prompt(&self) -> Result<String> {
+        Ok(format!(
+            "Current session state `{}` (schema: {}):\n{}",
+            Self::STATE_KEY,
+            Self::state_schema(),
+            self.to_state_value()?
+        ))
+    }
+}
@@ -16,7 +16,8 @@ use uuid::Uuid;
 
 use crate::agent::trait_def::Agent;
 use crate::agent::types::{AgentGenerateOptions, AgentStep, ToolCall, ToolResult};
-use crate::llm::Message;
+use crate::cancellation::{cancellable_stream, run_until_cancelled};
+use crate::llm::{LlmOptions, LlmProvider, Message};
 use crate::telemetry::TraceCollector;
 
 /// Events emitted during streaming agent execution
@@ -277,7 +278,7 @@ impl<T: Agent> StreamingAgent<T> {
         
         Ok(Box::pin(async_stream::stream! {
             // Stream initial LLM generation directly here instead of calling self.stream_llm_generation
-            match llm.generate_stream(&prompt, &llm_options).await {
+            match start_llm_stream(llm.as_ref(), &prompt, &llm_options).await {
                 Ok(mut llm_stream) => {
                     let mut accumulated_response = String::new();
                     let mut text_buffer = String::new();
@@ -406,7 +407,7 @@ impl<T: Agent> StreamingAgent<T> {
         
         Ok(Box::pin(async_stream::stream! {
             // Stream LLM generation directly here instead of calling self.stream_llm_generation
-            match llm.generate_stream(&prompt, &llm_options).await {
+            match start_llm_stream(llm.as_ref(), &prompt, &llm_options).await {
                 Ok(mut llm_stream) => {
                     let mut accumulated_response = String::new();
                     let mut text_buffer = String::new();
@@ -466,6 +467,21 @@ impl<T: Agent> StreamingAgent<T> {
     }
 }
 
+/// Start provider streaming that stops pulling from the provider once the options'
+/// cancellation token is cancelled, which drops the upstream request
+async fn start_llm_stream<'a>(
+    llm: &'a dyn LlmProvider,
+    prompt: &'a str,
+    options: &'a LlmOptions,
+) -> crate::Result<futures::stream::BoxStream<'a, crate::Result<String>>> {
+    let token = options.cancellation_token.as_ref();
+    let stream = run_until_cancelled(token, llm.generate_stream(prompt, options)).await?;
+    Ok(match token {
+        Some(token) => cancellable_stream(stream, token.clone()),
+        None => stream,
+    })
+}
+
 /// Helper trait to add streaming capabilities to existing agents
 pub trait IntoStreaming<T: Agent> {
     fn into_streaming(self) -> StreamingAgent<T>;
@@ -0,0 +1,353 @@
+//! Structured output and incremental JSON parsing
+//!
+//! Agents asked for structured output answer with a JSON value. While the answer
+//! streams in, [`PartialJsonParser`] turns the text received so far into the value
+//! it describes: open objects and arrays are closed, strings being written are cut
+//! at the last complete character, and keys whose value has not started are left
+//! out. [`partial_json_stream`] applies it to a stream of text chunks so UIs can
+//! render tables and cards while the model is still writing them.
+//!
+//! Numbers and literals at the very end of the text are omitted until they are
+//! complete, so a field never shows `1` on its way to `1024`.
+
+use futures::stream::{self, BoxStream, StreamExt};
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+use crate::error::{Error, Result};
+
+/// Parser for a JSON value that arrives in chunks
+#[derive(Debug, Clone, Default)]
+pub struct PartialJsonParser {
+    buffer: String,
+}
+
+impl PartialJsonParser {
+    /// Create an empty parser
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a chunk and return the value parsed so far
+    pub fn push(&mut self, chunk: &str) -> Option<Value> {
+        self.buffer.push_str(chunk);
+        self.value()
+    }
+
+    /// The value parsed so far, `None` before the first `{` or `[`
+    pub fn value(&self) -> Option<Value> {
+        parse_partial_json(&self.buffer)
+    }
+
+    /// The value parsed so far as `T`, once it deserializes
+    ///
+    /// Give `T` optional or `#[serde(default)]` fields to see it populate field by field.
+    pub fn partial<T: DeserializeOwned>(&self) -> Option<T> {
+        self.value().and_then(|value| serde_json::from_value(value).ok())
+    }
+
+    /// Text received so far
+    pub fn buffer(&self) -> &str {
+        &self.buffer
+    }
+
+    /// Parse the complete value, tolerating text and code fences around it
+    pub fn finish<T: DeserializeOwned>(&self) -> Result<T> {
+        let start = self.buffer.find(['{', '['])
+            .ok_or_else(|| Error::Parsing("Structured output contains no JSON value".to_string()))?;
+        let end = self.buffer.rfind(['}', ']']).filter(|end| *end >= start)
+            .ok_or_else(|| Error::Parsing("Structured output is incomplete".to_string()))?;
+        serde_json::from_str(&self.buffer[start..=end])
+            .map_err(|e| Error::Parsing(format!("Invalid structured output: {}", e)))
+    }
+}
+
+/// Parse the JSON value at the start of possibly incomplete `text`
+///
+/// Text before the first `{` or `[` is skipped, e.g. an opening code fence.
+pub fn parse_partial_json(text: &str) -> Option<Value> {
+    let start = text.find(['{', '['])?;
+    let mut parser = Partial { text: &text[start..], pos: 0 };
+    parser.value().map(|(value, _)| value)
+}
+
+/// A structured result as it streams in
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartialOutput<T> {
+    /// Text received since the previous item
+    pub delta: String,
+    /// JSON parsed so far
+    pub value: Value,
+    /// `value` as `T`, once it deserializes
+    pub partial: Option<T>,
+    /// Whether the stream has ended and `value` is the complete output
+    pub complete: bool,
+}
+
+/// Turn a stream of text chunks into partially parsed values
+///
+/// Yields an item for each chunk once a JSON value has started, then a final item
+/// with `complete` set. The stream fails if the finished text is not valid JSON or
+/// does not deserialize into `T`.
+pub fn partial_json_stream<'a, T>(chunks: BoxStream<'a, Result<String>>) -> BoxStream<'a, Result<PartialOutput<T>>>
+where
+    T: DeserializeOwned + Send + 'a,
+{
+    struct State<'a> {
+        chunks: BoxStream<'a, Result<String>>,
+        parser: PartialJsonParser,
+        pending: String,
+        done: bool,
+    }
+
+    let state = State { chunks, parser: PartialJsonParser::new(), pending: String::new(), done: false };
+    stream::unfold(state, |mut state| async move {
+        if state.done {
+            return None;
+        }
+        loop {
+            match state.chunks.next().await {
+                Some(Ok(chunk)) => {
+                    state.pending.push_str(&chunk);
+                    // Hold text back until a value has started
+                    if let Some(value) = state.parser.push(&chunk) {
+                        let partial = serde_json::from_value(value.clone()).ok();
+                        let delta = std::mem::take(&mut state.pending);
+                        return Some((Ok(PartialOutput { delta, value, partial, complete: false }), state));
+                    }
+                }
+                Some(Err(e)) => {
+                    state.done = true;
+                    return Some((Err(e), state));
+                }
+                None => {
+                    state.done = true;
+                    let output = state.parser.finish::<Value>().and_then(|value| {
+                        let partial = serde_json::from_value(value.clone())
+                            .map_err(|e| Error::Parsing(format!("Structured output does not match the expected type: {}", e)))?;
+                        let delta = std::mem::take(&mut state.pending);
+                        Ok(PartialOutput { delta, value, partial: Some(partial), complete: true })
+                    });
+                    return Some((output, state));
+                }
+            }
+        }
+    })
+    .boxed()
+}
+
+/// Instructions asking for a JSON answer, optionally matching `schema`
+pub(crate) fn json_instructions(instructions: &str, schema: Option<&Value>) -> String {
+    match schema {
+        Some(schema) => format!(
+            "{}\n\nRespond only with a JSON value matching this JSON schema, without code fences or commentary:\n{}",
+            instructions, schema
+        ),
+        None => format!("{}\n\nRespond only with a JSON value, without code fences or commentary.", instructions),
+    }
+}
+
+/// Recursive descent over a prefix of a JSON document
+struct Partial<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl Partial<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\n' | b'\r' | b'\t')) {
+            self.pos += 1;
+        }
+    }
+
+    /// The next value and whether it is complete; `None` when nothing usable has arrived
+    fn value(&mut self) -> Option<(Value, bool)> {
+        self.skip_whitespace();
+        match self.peek()? {
+            b'{' => Some(self.object()),
+            b'[' => Some(self.array()),
+            b'"' => self.string().map(|(text, complete)| (Value::String(text), complete)),
+            b't' => self.literal("true", Value::Bool(true)),
+            b'f' => self.literal("false", Value::Bool(false)),
+            b'n' => self.literal("null", Value::Null),
+            b'-' | b'0'..=b'9' => self.number(),
+            _ => None,
+        }
+    }
+
+    fn object(&mut self) -> (Value, bool) {
+        self.pos += 1;
+        let mut map = Map::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b'}') => {
+                    self.pos += 1;
+                    return (Value::Object(map), true);
+                }
+                Some(b',') => {
+                    self.pos += 1;
+                    continue;
+                }
+                Some(b'"') => {}
+                _ => return (Value::Object(map), false),
+            }
+
+            // Keys are only added once complete and followed by the start of a value
+            let Some((key, true)) = self.string() else {
+                return (Value::Object(map), false);
+            };
+            self.skip_whitespace();
+            if self.peek() != Some(b':') {
+                return (Value::Object(map), false);
+            }
+            self.pos += 1;
+            match self.value() {
+                Some((value, true)) => {
+                    map.insert(key, value);
+                }
+                Some((value, false)) => {
+                    map.insert(key, value);
+                    return (Value::Object(map), false);
+                }
+                None => return (Value::Object(map), false),
+            }
+        }
+    }
+
+    fn array(&mut self) -> (Value, bool) {
+        self.pos += 1;
+        let mut items = Vec::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b']') => {
+                    self.pos += 1;
+                    return (Value::Array(items), true);
+                }
+                Some(b',') => {
+                    self.pos += 1;
+                    continue;
+                }
+                None => return (Value::Array(items), false),
+                _ => {}
+            }
+            match self.value() {
+                Some((value, true)) => items.push(value),
+                Some((value, false)) => {
+                    items.push(value);
+                    return (Value::Array(items), false);
+                }
+                None => return (Value::Array(items), false),
+            }
+        }
+    }
+
+    /// A string starting at the opening quote, cut at the last complete character if unterminated
+    fn string(&mut self) -> Option<(String, bool)> {
+        let start = self.pos;
+        let bytes = self.text.as_bytes();
+        let mut index = start + 1;
+        while index < bytes.len() {
+            match bytes[index] {
+                b'\\' => index += 2,
+                b'"' => {
+                    self.pos = index + 1;
+                    let text = serde_json::from_str(&self.text[start..=index]).ok()?;
+                    return Some((text, true));
+                }
+                _ => index += 1,
+            }
+        }
+
+        // Drop an escape sequence that has not fully arrived
+        self.pos = bytes.len();
+        let mut body = &self.text[start + 1..];
+        if let Some(escape) = body.rfind('\\') {
+            let sequence = &body[escape..];
+            let backslashes = body[..escape].chars().rev().take_while(|c| *c == '\\').count();
+            let incomplete = sequence.len() == 1 || (sequence.starts_with("\\u") && sequence.len() < 6);
+            if backslashes % 2 == 0 && incomplete {
+                body = &body[..escape];
+            }
+        }
+        let text = serde_json::from_str(&format!("\"{}\"", body)).ok()?;
+        Some((text, false))
+    }
+
+    fn literal(&mut self, word: &str, value: Value) -> Option<(Value, bool)> {
+        if self.text[self.pos..].starts_with(word) {
+            self.pos += word.len();
+            Some((value, true))
+        } else {
+            None
+        }
+    }
+
+    fn number(&mut self) -> Option<(Value, bool)> {
+        let start = self.pos;
+        while matches!(self.peek(), Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9')) {
+            self.pos += 1;
+        }
+        // A number at the end of the text may still be growing
+        if self.pos == self.text.len() {
+            return None;
+        }
+        serde_json::from_str(&self.text[start..self.pos]).ok().map(|value| (value, true))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_partial_json() {
+        assert_eq!(parse_partial_json("Sure: ```json\n{\"title\": \"Q3 rep"), Some(json!({ "title": "Q3 rep" })));
+        assert_eq!(parse_partial_json(r#"{"title": "Q3", "rows": [{"name": "EMEA", "revenue": 12"#), Some(json!({ "title": "Q3", "rows": [{ "name": "EMEA" }] })));
+        assert_eq!(parse_partial_json(r#"{"title": "Q3", "rows": [{"name": "EMEA", "revenue": 120},"#), Some(json!({ "title": "Q3", "rows": [{ "name": "EMEA", "revenue": 120 }] })));
+        assert_eq!(parse_partial_json(r#"{"done": tr"#), Some(json!({})));
+        assert_eq!(parse_partial_json(r#"{"done": true, "note": "line\"#), Some(json!({ "done": true, "note": "line" })));
+        assert_eq!(parse_partial_json(r#"{"note": "caf\u00"#), Some(json!({ "note": "caf" })));
+        assert_eq!(parse_partial_json(r#"{"ti"#), Some(json!({})));
+        assert_eq!(parse_partial_json("[1, 2, 3"), Some(json!([1, 2])));
+        assert_eq!(parse_partial_json("no json yet"), None);
+    }
+
+    #[derive(Debug, Default, Deserialize, PartialEq)]
+    #[serde(default)]
+    struct Card {
+        title: String,
+        tags: Vec<String>,
+    }
+
+    #[tokio::test]
+    async fn test_partial_json_stream_yields_typed_fields() {
+        let chunks = vec![r#"{"title": "Wea"#, r#"ther", "tags": ["sun"#, r#"ny", "warm"]}"#];
+        let chunks = stream::iter(chunks.into_iter().map(|chunk| Ok(chunk.to_string()))).boxed();
+        let outputs: Vec<PartialOutput<Card>> = partial_json_stream(chunks)
+            .map(|output| output.unwrap())
+            .collect()
+            .await;
+
+        assert_eq!(outputs.len(), 4);
+        assert_eq!(outputs[0].partial, Some(Card { title: "Wea".to_string(), tags: vec![] }));
+        assert_eq!(outputs[1].partial.as_ref().unwrap().tags, vec!["sun"]);
+        assert!(!outputs[2].complete);
+        assert!(outputs[3].complete);
+        assert_eq!(outputs[3].partial, Some(Card { title: "Weather".to_string(), tags: vec!["sunny".to_string(), "warm".to_string()] }));
+    }
+
+    #[tokio::test]
+    async fn test_partial_json_stream_fails_on_invalid_output() {
+        let chunks = stream::iter(vec![Ok(r#"{"title": "#.to_string())]).boxed();
+        let outputs: Vec<Result<PartialOutput<Value>>> = partial_json_stream(chunks).collect().await;
+        assert!(matches!(outputs.last(), Some(Err(Error::Parsing(_)))));
+    }
+}
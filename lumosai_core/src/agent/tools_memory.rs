@@ -0,0 +1,252 @@
+//! Tool results remembered across turns of a session
+//!
+//! A [`ToolsMemory`] keeps a compact record of the tool calls an agent made in each
+//! session. On later turns of the same session the records are shown to the model in
+//! a system message, so it reuses data it already fetched instead of calling the same
+//! APIs again. Sessions are identified by the request's `thread_id`, or the session
+//! of the current [`RequestContext`](crate::request_context::RequestContext).
+//!
+//! Results are kept short: long results are truncated, or summarized by the agent's
+//! model when [`ToolsMemoryConfig::summarize`] is set. Repeating a call with the same
+//! arguments replaces the older record.
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::llm::{LlmOptions, LlmProvider, Message, Role};
+
+/// Default number of tool results remembered per session
+const DEFAULT_MAX_ENTRIES: usize = 20;
+
+/// Default length of a remembered result, in characters
+const DEFAULT_MAX_RESULT_CHARS: usize = 500;
+
+/// How tool results are remembered
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolsMemoryConfig {
+    /// Most recent tool results kept per session
+    #[serde(default = "default_max_entries")]
+    pub max_entries: usize,
+    /// Results longer than this are truncated or summarized
+    #[serde(default = "default_max_result_chars")]
+    pub max_result_chars: usize,
+    /// Summarize long results with the agent's model instead of truncating them
+    #[serde(default)]
+    pub summarize: bool,
+}
+
+fn default_max_entries() -> usize {
+    DEFAULT_MAX_ENTRIES
+}
+
+fn default_max_result_chars() -> usize {
+    DEFAULT_MAX_RESULT_CHARS
+}
+
+impl Default for ToolsMemoryConfig {
+    fn default() -> Self {
+        Self {
+            max_entries: DEFAULT_MAX_ENTRIES,
+            max_result_chars: DEFAULT_MAX_RESULT_CHARS,
+            summarize: false,
+        }
+    }
+}
+
+impl ToolsMemoryConfig {
+    /// Set how many results are kept per session
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+
+    /// Set the length above which results are shortened
+    pub fn with_max_result_chars(mut self, max_result_chars: usize) -> Self {
+        self.max_result_chars = max_result_chars;
+        self
+    }
+
+    /// Summarize long results with the agent's model
+    pub fn with_summarize(mut self, summarize: bool) -> Self {
+        self.summarize = summarize;
+        self
+    }
+}
+
+/// A remembered tool call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolRecord {
+    /// Tool name
+    pub tool: String,
+    /// Arguments the tool was called with
+    pub arguments: Value,
+    /// Compact form of the result
+    pub result: String,
+    /// When the tool was called
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// Compact record of the tool results of each session
+#[derive(Debug, Default)]
+pub struct ToolsMemory {
+    config: ToolsMemoryConfig,
+    sessions: RwLock<HashMap<String, Vec<ToolRecord>>>,
+}
+
+impl ToolsMemory {
+    /// Create an empty memory
+    pub fn new(config: ToolsMemoryConfig) -> Self {
+        Self {
+            config,
+            sessions: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// The memory's configuration
+    pub fn config(&self) -> &ToolsMemoryConfig {
+        &self.config
+    }
+
+    /// Remember a successful tool call in `session`
+    ///
+    /// `llm` summarizes long results when summarizing is enabled; if it fails the
+    /// result is truncated instead.
+    pub async fn record(&self, llm: &dyn LlmProvider, session: &str, tool: &str, arguments: Value, result: &Value) {
+        let text = match result {
+            Value::String(text) => text.clone(),
+            other => other.to_string(),
+        };
+        let result = if text.chars().count() <= self.config.max_result_chars {
+            text
+        } else if self.config.summarize {
+            match self.summarize(llm, tool, &text).await {
+                Some(summary) => summary,
+                None => truncate(&text, self.config.max_result_chars),
+            }
+        } else {
+            truncate(&text, self.config.max_result_chars)
+        };
+
+        let record = ToolRecord {
+            tool: tool.to_string(),
+            arguments,
+            result,
+            recorded_at: Utc::now(),
+        };
+        let mut sessions = self.sessions.write().unwrap_or_else(|e| e.into_inner());
+        let records = sessions.entry(session.to_string()).or_default();
+        records.retain(|existing| existing.tool != record.tool || existing.arguments != record.arguments);
+        records.push(record);
+        let excess = records.len().saturating_sub(self.config.max_entries);
+        records.drain(..excess);
+    }
+
+    /// Tool calls remembered for `session`, oldest first
+    pub fn records(&self, session: &str) -> Vec<ToolRecord> {
+        let sessions = self.sessions.read().unwrap_or_else(|e| e.into_inner());
+        sessions.get(session).cloned().unwrap_or_default()
+    }
+
+    /// Forget the tool calls of `session`
+    pub fn clear(&self, session: &str) {
+        let mut sessions = self.sessions.write().unwrap_or_else(|e| e.into_inner());
+        sessions.remove(session);
+    }
+
+    /// System message listing the tool results already fetched in `session`
+    pub(crate) fn context_message(&self, session: &str) -> Option<Message> {
+        let records = self.records(session);
+        if records.is_empty() {
+            return None;
+        }
+
+        let mut content = String::from(
+            "Tool results already fetched earlier in this conversation. Reuse them instead of \
+             calling the same tool with the same arguments again, unless the data may have changed:",
+        );
+        for record in &records {
+            content.push_str(&format!("\n- {}({}): {}", record.tool, record.arguments, record.result));
+        }
+        Some(Message {
+            role: Role::System,
+            content,
+            metadata: None,
+            name: None,
+        })
+    }
+
+    async fn summarize(&self, llm: &dyn LlmProvider, tool: &str, text: &str) -> Option<String> {
+        let messages = vec![
+            Message {
+                role: Role::System,
+                content: format!(
+                    "Summarize the output of the `{}` tool in at most {} characters. Keep names, numbers, \
+                     IDs and dates exactly as given. Reply with the summary only.",
+                    tool, self.config.max_result_chars
+                ),
+                metadata: None,
+                name: None,
+            },
+            Message {
+                role: Role::User,
+                content: text.to_string(),
+                metadata: None,
+                name: None,
+            },
+        ];
+        let summary = llm.generate_with_messages(&messages, &LlmOptions::default()).await.ok()?;
+        let summary = summary.trim();
+        (!summary.is_empty()).then(|| truncate(summary, self.config.max_result_chars))
+    }
+}
+
+/// Cut `text` to at most `max_chars` characters, marking the cut
+fn truncate(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::MockLlmProvider;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_records_are_compact_and_deduplicated() {
+        let llm = MockLlmProvider::new(Vec::new());
+        let memory = ToolsMemory::new(ToolsMemoryConfig::default().with_max_entries(2).with_max_result_chars(10));
+
+        memory.record(&llm, "s1", "weather", json!({ "city": "Paris" }), &json!("sunny and warm all week")).await;
+        memory.record(&llm, "s1", "weather", json!({ "city": "Paris" }), &json!("rain")).await;
+        memory.record(&llm, "s1", "weather", json!({ "city": "Rome" }), &json!({ "sky": "clear" })).await;
+        memory.record(&llm, "s1", "time", json!({}), &json!("12:00")).await;
+
+        let records = memory.records("s1");
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].result, "{\"sky\":\"cl…");
+        assert_eq!(records[1].tool, "time");
+        assert!(memory.records("s2").is_empty());
+
+        let message = memory.context_message("s1").unwrap();
+        assert!(message.content.contains("- time({}): 12:00"));
+        memory.clear("s1");
+        assert!(memory.context_message("s1").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_long_results_are_summarized() {
+        let llm = MockLlmProvider::new(vec!["Paris: sunny, 24°C".to_string()]);
+        let memory = ToolsMemory::new(ToolsMemoryConfig::default().with_max_result_chars(20).with_summarize(true));
+
+        let forecast = "Monday sunny 24°C, Tuesday sunny 25°C, Wednesday cloudy 21°C";
+        memory.record(&llm, "s1", "weather", json!({ "city": "Paris" }), &json!(forecast)).await;
+        assert_eq!(memory.records("s1")[0].result, "Paris: sunny, 24°C");
+    }
+}
@@ -0,0 +1,159 @@
+//! Structured ReAct traces of an agent run
+//!
+//! With [`AgentGenerateOptions::capture_trace`](super::types::AgentGenerateOptions::capture_trace)
+//! set, the agent returns an [`AgentTrace`] alongside its response: the reasoning the model
+//! produced, each tool it chose with the arguments, what the tool returned, and any
+//! self-reflection critiques. Reasoning embedded in the final reply (`<think>` blocks or a
+//! ReAct `Final Answer:` preamble) is moved into the trace so it never reaches the user.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::types::{AgentStep, StepType, ToolResultStatus};
+
+/// Marker separating ReAct reasoning from the answer
+const FINAL_ANSWER_MARKER: &str = "Final Answer:";
+
+/// Tags wrapping model reasoning in the reply
+const REASONING_TAGS: [(&str, &str); 2] = [("<think>", "</think>"), ("<thinking>", "</thinking>")];
+
+/// One entry of an agent trace; `step` is the 1-based reasoning step it belongs to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TraceEntry {
+    /// Reasoning produced by the model
+    Thought { step: usize, content: String },
+    /// A tool the model chose to call
+    Action { step: usize, tool: String, arguments: Value },
+    /// What the tool returned
+    Observation { step: usize, tool: String, output: Value, success: bool },
+    /// A self-reflection verdict on the draft answer
+    Critique { step: usize, score: f64, feedback: String },
+}
+
+/// Intermediate reasoning and tool selection of one agent run
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AgentTrace {
+    /// Entries in the order they happened
+    pub entries: Vec<TraceEntry>,
+}
+
+impl AgentTrace {
+    /// Build the trace from the steps of a run and split the reasoning out of the final reply
+    ///
+    /// Returns the trace and the answer to show the user.
+    pub fn capture(steps: &[AgentStep], final_reply: &str) -> (Self, String) {
+        let mut entries = Vec::new();
+        let mut step_number = 0;
+
+        for step in steps {
+            match step.step_type {
+                StepType::Tool => {
+                    step_number += 1;
+                    let thought = step.output.as_ref().map(|message| message.content.trim()).unwrap_or_default();
+                    if !thought.is_empty() {
+                        entries.push(TraceEntry::Thought { step: step_number, content: thought.to_string() });
+                    }
+                    for call in &step.tool_calls {
+                        entries.push(TraceEntry::Action {
+                            step: step_number,
+                            tool: call.name.clone(),
+                            arguments: Value::Object(call.arguments.clone().into_iter().collect()),
+                        });
+                    }
+                    for result in &step.tool_results {
+                        entries.push(TraceEntry::Observation {
+                            step: step_number,
+                            tool: result.name.clone(),
+                            output: result.result.clone(),
+                            success: matches!(result.status, ToolResultStatus::Success),
+                        });
+                    }
+                },
+                StepType::Reflection => {
+                    step_number += 1;
+                    let score = step.metadata.get("score").and_then(Value::as_f64).unwrap_or_default();
+                    let feedback = step.metadata.get("critique").and_then(Value::as_str).unwrap_or_default();
+                    entries.push(TraceEntry::Critique { step: step_number, score, feedback: feedback.to_string() });
+                },
+                StepType::Initial | StepType::Final => {},
+            }
+        }
+
+        let (reasoning, answer) = split_reasoning(final_reply);
+        if let Some(content) = reasoning {
+            entries.push(TraceEntry::Thought { step: step_number + 1, content });
+        }
+        (Self { entries }, answer)
+    }
+
+    /// Names of the tools called, in order
+    pub fn tools_called(&self) -> Vec<&str> {
+        self.entries.iter()
+            .filter_map(|entry| match entry {
+                TraceEntry::Action { tool, .. } => Some(tool.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// All reasoning text, in order
+    pub fn thoughts(&self) -> Vec<&str> {
+        self.entries.iter()
+            .filter_map(|entry| match entry {
+                TraceEntry::Thought { content, .. } => Some(content.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Split model reasoning out of a reply, returning the reasoning (if any) and the answer
+pub fn split_reasoning(reply: &str) -> (Option<String>, String) {
+    let mut reasoning = Vec::new();
+    let mut answer = reply.to_string();
+
+    for (open, close) in REASONING_TAGS {
+        while let Some(start) = answer.find(open) {
+            let Some(end) = answer[start..].find(close).map(|end| start + end) else {
+                break;
+            };
+            reasoning.push(answer[start + open.len()..end].trim().to_string());
+            answer.replace_range(start..end + close.len(), "");
+        }
+    }
+
+    if let Some(index) = answer.find(FINAL_ANSWER_MARKER) {
+        reasoning.push(answer[..index].trim().to_string());
+        answer = answer[index + FINAL_ANSWER_MARKER.len()..].to_string();
+    }
+
+    reasoning.retain(|text| !text.is_empty());
+    let reasoning = (!reasoning.is_empty()).then(|| reasoning.join("\n"));
+    (reasoning, answer.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_reasoning() {
+        let (reasoning, answer) = split_reasoning("<think>The user wants a number.</think>\n42");
+        assert_eq!(reasoning.as_deref(), Some("The user wants a number."));
+        assert_eq!(answer, "42");
+
+        let (reasoning, answer) = split_reasoning("Thought: I know this.\nFinal Answer: Paris");
+        assert_eq!(reasoning.as_deref(), Some("Thought: I know this."));
+        assert_eq!(answer, "Paris");
+
+        let (reasoning, answer) = split_reasoning("Just the answer");
+        assert!(reasoning.is_none());
+        assert_eq!(answer, "Just the answer");
+
+        // An unterminated block is left alone rather than swallowing the answer
+        let (reasoning, answer) = split_reasoning("<think>cut off");
+        assert!(reasoning.is_none());
+        assert_eq!(answer, "<think>cut off");
+    }
+}
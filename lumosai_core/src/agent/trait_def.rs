@@ -9,7 +9,8 @@ use serde::de::DeserializeOwned;
 
 use crate::base::Base;
 use crate::error::{Error, Result};
-use crate::llm::{LlmProvider, Message};
+use crate::llm::{LlmOptions, LlmProvider, Message, Role};
+use crate::llm::batch::{BatchRequest, BatchResponse};
 use crate::memory::Memory;
 use crate::memory::working::WorkingMemory;
 use crate::tool::Tool;
@@ -24,6 +25,8 @@ use crate::agent::types::{
 use crate::voice::{VoiceProvider, VoiceOptions, ListenOptions};
 use crate::workflow::Workflow;
 use crate::agent::config::AgentConfig;
+use crate::agent::sampling::{candidate_options, select_best, BestOfN};
+use crate::agent::structured::PartialOutput;
 use tokio::io::AsyncRead;
 use serde::{Serialize, Deserialize};
 
@@ -59,6 +62,13 @@ pub trait AgentStructuredOutput: Send + Sync {
         messages: &[Message], 
         options: &AgentGenerateOptions
     ) -> Result<T>;
+
+    /// Stream structured output, yielding the partially parsed value as chunks arrive
+    async fn stream_structured<'a, T: DeserializeOwned + Send + 'a>(
+        &'a self,
+        messages: &'a [Message],
+        options: &'a AgentStreamOptions
+    ) -> Result<BoxStream<'a, Result<PartialOutput<T>>>>;
 }
 
 /// Trait for agents that support voice input (speech-to-text)
@@ -186,6 +196,84 @@ pub trait Agent: Base + Send + Sync {
         Ok(result.response)
     }
 
+    /// Apply the agent's instructions to every input of an offline dataset
+    ///
+    /// Each input becomes one batch request with the instructions as system prompt, so
+    /// providers with a native batch API process the whole dataset at batch pricing.
+    /// Responses keep dataset order and use the input index as `custom_id`; tools and
+    /// memory are not involved.
+    async fn map_over(&self, dataset: Vec<String>, options: &LlmOptions) -> Result<Vec<BatchResponse>> {
+        let instructions = self.get_instructions().to_string();
+        let requests = dataset
+            .into_iter()
+            .enumerate()
+            .map(|(index, input)| {
+                let mut messages = Vec::with_capacity(2);
+                if !instructions.is_empty() {
+                    messages.push(Message::new(Role::System, instructions.clone(), None, None));
+                }
+                messages.push(Message::new(Role::User, input, None, None));
+                BatchRequest::new(index.to_string(), messages).with_options(options.clone())
+            })
+            .collect();
+
+        self.get_llm().generate_batch(requests).await
+    }
+
+    /// Sample `n` candidate responses to the same request concurrently
+    ///
+    /// Candidates differ in temperature and seed (see [`candidate_options`]). Failed
+    /// candidates are dropped; an error is returned only when every candidate fails
+    /// or the request was cancelled.
+    async fn generate_n(&self,
+        messages: &[Message],
+        n: usize,
+        options: &AgentGenerateOptions
+    ) -> Result<Vec<AgentGenerateResult>> {
+        if n == 0 {
+            return Err(Error::InvalidInput("generate_n needs at least one candidate".to_string()));
+        }
+
+        let attempts = futures::future::join_all((0..n).map(|index| {
+            let options = candidate_options(options, index);
+            async move { self.generate(messages, &options).await }
+        })).await;
+
+        let mut candidates = Vec::with_capacity(n);
+        let mut last_error = None;
+        for attempt in attempts {
+            match attempt {
+                Ok(result) => candidates.push(result),
+                Err(Error::Cancelled(reason)) => return Err(Error::Cancelled(reason)),
+                Err(e) => {
+                    tracing::warn!("Candidate of agent '{}' failed: {}", self.get_name(), e);
+                    last_error = Some(e);
+                }
+            }
+        }
+        match last_error {
+            Some(e) if candidates.is_empty() => Err(e),
+            _ => Ok(candidates),
+        }
+    }
+
+    /// Sample `n` candidates and let the agent's model judge which is best
+    async fn generate_best_of_n(&self,
+        messages: &[Message],
+        n: usize,
+        options: &AgentGenerateOptions,
+        criteria: Option<&str>
+    ) -> Result<BestOfN> {
+        let candidates = self.generate_n(messages, n, options).await?;
+        if candidates.len() == 1 {
+            return Ok(BestOfN { candidates, selected: 0, reason: None });
+        }
+
+        let answers: Vec<&str> = candidates.iter().map(|candidate| candidate.response.as_str()).collect();
+        let selection = select_best(self.get_llm().as_ref(), messages, &answers, criteria).await?;
+        Ok(BestOfN { candidates, selected: selection.best, reason: selection.reason })
+    }
+
     /// Generate with multi-step reasoning
     async fn generate_with_steps(&self,
         messages: &[Message],
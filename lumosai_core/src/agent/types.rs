@@ -1,13 +1,15 @@
 //! Agent types and configurations
 
+use std::borrow::Cow;
 use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use uuid::Uuid;
 
-use crate::llm::{LlmOptions, Message, Role};
+use crate::llm::{LlmOptions, Message, Role, ToolChoice as LlmToolChoice};
 use crate::memory::MemoryConfig;
 use crate::tool::Tool;
+use super::trace::AgentTrace;
 
 /// Voice configuration for an agent
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -70,6 +72,44 @@ impl Default for ToolChoice {
     }
 }
 
+impl ToolChoice {
+    /// Whether the model must call a tool before answering
+    pub fn requires_tool_call(&self) -> bool {
+        matches!(self, Self::Required | Self::Tool { .. })
+    }
+
+    /// Whether the tools the model called satisfy this choice
+    pub fn is_satisfied_by<'a>(&self, called: impl IntoIterator<Item = &'a str>) -> bool {
+        let mut called = called.into_iter();
+        match self {
+            Self::Auto | Self::None => true,
+            Self::Required => called.next().is_some(),
+            Self::Tool { tool_name } => called.any(|name| name == tool_name),
+        }
+    }
+
+    /// Corrective instruction sent when the model ignored a required tool call
+    pub(crate) fn corrective_message(&self) -> Option<Message> {
+        let content = match self {
+            Self::Auto | Self::None => return None,
+            Self::Required => "You must call one of the available tools before answering. Call a tool now.".to_string(),
+            Self::Tool { tool_name } => format!("You must call the `{}` tool before answering. Call it now.", tool_name),
+        };
+        Some(user_message(content))
+    }
+}
+
+impl From<&ToolChoice> for LlmToolChoice {
+    fn from(choice: &ToolChoice) -> Self {
+        match choice {
+            ToolChoice::Auto => LlmToolChoice::Auto,
+            ToolChoice::None => LlmToolChoice::None,
+            ToolChoice::Required => LlmToolChoice::Required,
+            ToolChoice::Tool { tool_name } => LlmToolChoice::Function { name: tool_name.clone() },
+        }
+    }
+}
+
 /// Tool data structure for agent tools
 #[derive(Debug, Clone)]
 pub struct ToolData {
@@ -122,6 +162,25 @@ pub struct AgentGenerateOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub context_window: Option<usize>,
     
+    /// Return an [`AgentTrace`](super::trace::AgentTrace) of intermediate reasoning with the result
+    #[serde(default)]
+    pub capture_trace: bool,
+    
+    /// Replay mode for reproducible runs, e.g. snapshot tests
+    ///
+    /// Pins the sampling seed and temperature and keeps example selection stable.
+    /// Sources of nondeterminism that remain are listed in the result metadata
+    /// under `nondeterminism_warnings`.
+    #[serde(default)]
+    pub deterministic: bool,
+    
+    /// Language the answer must be written in, as a code (`fr`) or name (`French`)
+    ///
+    /// Requested in the system prompt; an answer detected in another language is
+    /// rewritten once, which is recorded in the result metadata as `language_rewritten`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    
     /// LLM options
     #[serde(flatten)]
     pub llm_options: LlmOptions,
@@ -140,11 +199,30 @@ impl Default for AgentGenerateOptions {
             max_steps: Some(5),
             tool_choice: Some(ToolChoice::Auto),
             context_window: Some(10),
+            capture_trace: false,
+            deterministic: false,
+            language: None,
             llm_options: LlmOptions::default(),
         }
     }
 }
 
+impl AgentGenerateOptions {
+    /// Seed used in deterministic mode when none is configured
+    pub const DETERMINISTIC_SEED: u64 = 0;
+
+    /// The options actually used for a run: unchanged, or with sampling pinned in deterministic mode
+    pub fn resolved(&self) -> Cow<'_, Self> {
+        if !self.deterministic {
+            return Cow::Borrowed(self);
+        }
+        let mut options = self.clone();
+        options.llm_options.temperature = Some(0.0);
+        options.llm_options.seed.get_or_insert(Self::DETERMINISTIC_SEED);
+        Cow::Owned(options)
+    }
+}
+
 /// Options for streaming responses with an agent
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentStreamOptions {
@@ -230,6 +308,8 @@ pub enum StepType {
     Tool,
     /// Final response step
     Final,
+    /// Self-reflection critique and revision step
+    Reflection,
 }
 
 /// Tool call structure (Alias for backward compatibility)
@@ -280,6 +360,9 @@ pub struct AgentGenerateResult {
     pub usage: TokenUsage,
     /// Agent metadata
     pub metadata: HashMap<String, Value>,
+    /// Intermediate reasoning, present when trace capture was requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace: Option<AgentTrace>,
 }
 
 /// Token usage information
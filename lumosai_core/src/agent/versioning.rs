@@ -0,0 +1,423 @@
+//! Agent versioning and traffic splitting
+//!
+//! An [`AgentVersion`] is an immutable snapshot of an agent's configuration,
+//! instructions and tool schemas, identified by a content fingerprint. The
+//! [`AgentVersionRouter`] serves several versions of the same agent side by side
+//! and splits traffic between them by percentage, which enables canary and
+//! blue/green rollouts with instant rollback to the previous split.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::agent::config::AgentConfig;
+use crate::agent::trait_def::Agent;
+use crate::agent::types::{AgentGenerateOptions, AgentGenerateResult};
+use crate::error::{Error, Result};
+use crate::llm::Message;
+use crate::tool::{Tool, ToolSchema};
+
+/// Snapshot of a tool as seen by the model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolSnapshot {
+    /// Tool identifier
+    pub name: String,
+    /// Tool description
+    pub description: String,
+    /// Parameter schema
+    pub schema: ToolSchema,
+}
+
+impl ToolSnapshot {
+    /// Capture a tool's public definition
+    pub fn capture(tool: &dyn Tool) -> Self {
+        Self {
+            name: tool.id().to_string(),
+            description: tool.description().to_string(),
+            schema: tool.schema(),
+        }
+    }
+}
+
+/// Immutable snapshot of an agent's config, prompt and tools
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentVersion {
+    version: String,
+    config: AgentConfig,
+    tools: Vec<ToolSnapshot>,
+    fingerprint: String,
+    created_at: DateTime<Utc>,
+    notes: Option<String>,
+}
+
+impl AgentVersion {
+    /// Create a version from a config and tool snapshots
+    pub fn new(version: impl Into<String>, config: AgentConfig, mut tools: Vec<ToolSnapshot>) -> Self {
+        tools.sort_by(|a, b| a.name.cmp(&b.name));
+        let fingerprint = fingerprint(&config, &tools);
+        Self {
+            version: version.into(),
+            config,
+            tools,
+            fingerprint,
+            created_at: Utc::now(),
+            notes: None,
+        }
+    }
+
+    /// Snapshot a running agent, taking its current instructions and tools
+    pub fn capture(version: impl Into<String>, agent: &dyn Agent, config: &AgentConfig) -> Self {
+        let mut config = config.clone();
+        config.name = agent.get_name().to_string();
+        config.instructions = agent.get_instructions().to_string();
+        let tools = agent.get_tools().values().map(|tool| ToolSnapshot::capture(tool.as_ref())).collect();
+        Self::new(version, config, tools)
+    }
+
+    /// Attach release notes
+    pub fn with_notes(mut self, notes: impl Into<String>) -> Self {
+        self.notes = Some(notes.into());
+        self
+    }
+
+    /// Version label
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Name of the versioned agent
+    pub fn agent_name(&self) -> &str {
+        &self.config.name
+    }
+
+    /// Agent configuration at snapshot time
+    pub fn config(&self) -> &AgentConfig {
+        &self.config
+    }
+
+    /// System prompt at snapshot time
+    pub fn instructions(&self) -> &str {
+        &self.config.instructions
+    }
+
+    /// Tools available at snapshot time, sorted by name
+    pub fn tools(&self) -> &[ToolSnapshot] {
+        &self.tools
+    }
+
+    /// SHA-256 of the config and tool schemas; equal fingerprints mean identical behaviour inputs
+    pub fn fingerprint(&self) -> &str {
+        &self.fingerprint
+    }
+
+    /// When the snapshot was taken
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    /// Release notes
+    pub fn notes(&self) -> Option<&str> {
+        self.notes.as_deref()
+    }
+}
+
+fn fingerprint(config: &AgentConfig, tools: &[ToolSnapshot]) -> String {
+    // Going through Value sorts map keys, so HashMap fields hash deterministically
+    let canonical = serde_json::json!({ "config": config, "tools": tools });
+    let digest = ring::digest::digest(&ring::digest::SHA256, canonical.to_string().as_bytes());
+    digest.as_ref().iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Percentage of traffic sent to each version
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrafficSplit {
+    /// `(version, percent)` pairs summing to 100
+    pub weights: Vec<(String, u32)>,
+}
+
+impl TrafficSplit {
+    /// Send all traffic to one version
+    pub fn single(version: impl Into<String>) -> Self {
+        Self { weights: vec![(version.into(), 100)] }
+    }
+
+    /// Percentage assigned to a version
+    pub fn percent_for(&self, version: &str) -> u32 {
+        self.weights.iter().filter(|(v, _)| v == version).map(|(_, p)| *p).sum()
+    }
+
+    /// Version receiving the largest share
+    pub fn primary(&self) -> Option<&str> {
+        self.weights.iter().max_by_key(|(_, p)| *p).map(|(v, _)| v.as_str())
+    }
+
+    fn pick(&self, bucket: u32) -> Option<&str> {
+        let mut cumulative = 0;
+        for (version, percent) in &self.weights {
+            cumulative += percent;
+            if bucket < cumulative {
+                return Some(version);
+            }
+        }
+        None
+    }
+}
+
+/// Per-version request counters
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VersionStats {
+    /// Requests routed to the version
+    pub requests: u64,
+    /// Requests that returned an error
+    pub errors: u64,
+}
+
+struct RouterState {
+    versions: HashMap<String, (AgentVersion, Arc<dyn Agent>)>,
+    split: TrafficSplit,
+    history: Vec<TrafficSplit>,
+    stats: HashMap<String, VersionStats>,
+}
+
+/// Routes requests between versions of one agent by traffic percentage
+pub struct AgentVersionRouter {
+    name: String,
+    state: RwLock<RouterState>,
+}
+
+impl AgentVersionRouter {
+    /// Create a router serving all traffic from an initial version
+    pub fn new(version: AgentVersion, agent: Arc<dyn Agent>) -> Self {
+        let name = version.agent_name().to_string();
+        let label = version.version().to_string();
+        let mut versions = HashMap::new();
+        versions.insert(label.clone(), (version, agent));
+        Self {
+            name,
+            state: RwLock::new(RouterState {
+                versions,
+                split: TrafficSplit::single(label),
+                history: Vec::new(),
+                stats: HashMap::new(),
+            }),
+        }
+    }
+
+    /// Name of the routed agent
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn read(&self) -> Result<std::sync::RwLockReadGuard<'_, RouterState>> {
+        self.state.read().map_err(|e| Error::Lock(e.to_string()))
+    }
+
+    fn write(&self) -> Result<std::sync::RwLockWriteGuard<'_, RouterState>> {
+        self.state.write().map_err(|e| Error::Lock(e.to_string()))
+    }
+
+    /// Register a new version without sending it any traffic
+    pub fn register(&self, version: AgentVersion, agent: Arc<dyn Agent>) -> Result<()> {
+        let mut state = self.write()?;
+        if state.versions.contains_key(version.version()) {
+            return Err(Error::AlreadyExists(format!(
+                "Version {} of agent {} is already registered", version.version(), self.name
+            )));
+        }
+        state.versions.insert(version.version().to_string(), (version, agent));
+        Ok(())
+    }
+
+    /// Snapshot of a registered version
+    pub fn version(&self, version: &str) -> Option<AgentVersion> {
+        self.read().ok()?.versions.get(version).map(|(v, _)| v.clone())
+    }
+
+    /// All registered versions, oldest first
+    pub fn versions(&self) -> Vec<AgentVersion> {
+        let mut versions: Vec<AgentVersion> = match self.read() {
+            Ok(state) => state.versions.values().map(|(v, _)| v.clone()).collect(),
+            Err(_) => Vec::new(),
+        };
+        versions.sort_by_key(|v| v.created_at());
+        versions
+    }
+
+    /// Current traffic split
+    pub fn split(&self) -> TrafficSplit {
+        self.read().map(|state| state.split.clone()).unwrap_or_else(|_| TrafficSplit { weights: Vec::new() })
+    }
+
+    /// Replace the traffic split; the previous split is kept for rollback
+    pub fn set_split(&self, split: TrafficSplit) -> Result<()> {
+        let mut state = self.write()?;
+        let total: u32 = split.weights.iter().map(|(_, p)| p).sum();
+        if total != 100 {
+            return Err(Error::InvalidInput(format!("Traffic split must sum to 100, got {}", total)));
+        }
+        if let Some((unknown, _)) = split.weights.iter().find(|(v, _)| !state.versions.contains_key(v)) {
+            return Err(Error::NotFound(format!("Version {} of agent {} is not registered", unknown, self.name)));
+        }
+        let previous = std::mem::replace(&mut state.split, split);
+        state.history.push(previous);
+        Ok(())
+    }
+
+    /// Send `percent` of traffic to a canary, leaving the rest on the current primary version
+    pub fn canary(&self, version: &str, percent: u32) -> Result<()> {
+        if percent > 100 {
+            return Err(Error::InvalidInput(format!("Canary percentage {} exceeds 100", percent)));
+        }
+        let primary = self.split().primary().map(str::to_string).ok_or_else(|| {
+            Error::InvalidState(format!("Agent {} has no primary version", self.name))
+        })?;
+        if primary == version {
+            return self.promote(version);
+        }
+        let mut weights = vec![(primary, 100 - percent), (version.to_string(), percent)];
+        weights.retain(|(_, p)| *p > 0);
+        self.set_split(TrafficSplit { weights })
+    }
+
+    /// Send all traffic to one version (blue/green switch)
+    pub fn promote(&self, version: &str) -> Result<()> {
+        self.set_split(TrafficSplit::single(version))
+    }
+
+    /// Restore the previous traffic split
+    pub fn rollback(&self) -> Result<TrafficSplit> {
+        let mut state = self.write()?;
+        let previous = state.history.pop().ok_or_else(|| {
+            Error::InvalidState(format!("Agent {} has no previous traffic split", self.name))
+        })?;
+        state.split = previous.clone();
+        Ok(previous)
+    }
+
+    /// Choose a version for a request
+    ///
+    /// Requests with the same routing key (user or session id) always land on
+    /// the same version for a given split; requests without one are spread randomly.
+    pub fn route(&self, routing_key: Option<&str>) -> Result<(AgentVersion, Arc<dyn Agent>)> {
+        let bucket = match routing_key {
+            Some(key) => stable_bucket(&self.name, key),
+            None => rand::random::<u32>() % 100,
+        };
+        let state = self.read()?;
+        let label = state.split.pick(bucket).ok_or_else(|| {
+            Error::InvalidState(format!("Agent {} has an empty traffic split", self.name))
+        })?;
+        let (version, agent) = state.versions.get(label).ok_or_else(|| {
+            Error::NotFound(format!("Version {} of agent {} is not registered", label, self.name))
+        })?;
+        Ok((version.clone(), agent.clone()))
+    }
+
+    /// Route a request and generate a response, recording per-version outcomes
+    ///
+    /// The chosen version is reported in the result metadata under `agent_version`.
+    pub async fn generate(
+        &self,
+        routing_key: Option<&str>,
+        messages: &[Message],
+        options: &AgentGenerateOptions,
+    ) -> Result<AgentGenerateResult> {
+        let (version, agent) = self.route(routing_key)?;
+        let result = agent.generate(messages, options).await;
+
+        if let Ok(mut state) = self.state.write() {
+            let stats = state.stats.entry(version.version().to_string()).or_default();
+            stats.requests += 1;
+            if result.is_err() {
+                stats.errors += 1;
+            }
+        }
+
+        let mut result = result?;
+        result.metadata.insert("agent_version".to_string(), serde_json::Value::String(version.version().to_string()));
+        Ok(result)
+    }
+
+    /// Request counters per version
+    pub fn stats(&self) -> HashMap<String, VersionStats> {
+        self.read().map(|state| state.stats.clone()).unwrap_or_default()
+    }
+}
+
+/// FNV-1a hash of the routing key into 0..100, stable across processes
+fn stable_bucket(agent: &str, key: &str) -> u32 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in agent.bytes().chain(std::iter::once(0)).chain(key.bytes()) {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    (hash % 100) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::executor::BasicAgent;
+    use crate::llm::MockLlmProvider;
+
+    fn agent(instructions: &str, reply: &str) -> (AgentVersion, Arc<dyn Agent>) {
+        let config = AgentConfig {
+            name: "support".to_string(),
+            instructions: instructions.to_string(),
+            ..AgentConfig::default()
+        };
+        let llm = Arc::new(MockLlmProvider::new(vec![reply.to_string(); 200]));
+        let agent = BasicAgent::new(config.clone(), llm);
+        let version = AgentVersion::capture(reply, &agent, &config);
+        (version, Arc::new(agent))
+    }
+
+    #[test]
+    fn test_version_fingerprint() {
+        let (v1, _) = agent("Be concise.", "v1");
+        let (same, _) = agent("Be concise.", "v1-copy");
+        let (v2, _) = agent("Be thorough.", "v2");
+        assert_eq!(v1.fingerprint(), same.fingerprint());
+        assert_ne!(v1.fingerprint(), v2.fingerprint());
+        assert_eq!(v2.instructions(), "Be thorough.");
+    }
+
+    #[tokio::test]
+    async fn test_canary_rollout_and_rollback() {
+        let (v1, a1) = agent("Be concise.", "v1");
+        let (v2, a2) = agent("Be thorough.", "v2");
+        let router = AgentVersionRouter::new(v1, a1);
+        router.register(v2.clone(), a2.clone()).unwrap();
+        assert!(router.register(v2, a2).is_err());
+        assert!(router.set_split(TrafficSplit { weights: vec![("v1".to_string(), 50)] }).is_err());
+
+        router.canary("v2", 20).unwrap();
+        assert_eq!(router.split().percent_for("v2"), 20);
+
+        let keys: Vec<String> = (0..500).map(|i| format!("user-{}", i)).collect();
+        let on_canary = keys.iter()
+            .filter(|k| router.route(Some(k)).unwrap().0.version() == "v2")
+            .count();
+        assert!((50..150).contains(&on_canary), "canary got {} of 500", on_canary);
+
+        // Routing is sticky per key
+        let first = router.route(Some("user-7")).unwrap().0.version().to_string();
+        assert_eq!(router.route(Some("user-7")).unwrap().0.version(), first);
+
+        let message = crate::agent::user_message("hi");
+        let result = router.generate(Some("user-7"), &[message], &AgentGenerateOptions::default()).await.unwrap();
+        assert_eq!(result.metadata["agent_version"], first.as_str());
+        assert_eq!(router.stats()[&first].requests, 1);
+
+        router.promote("v2").unwrap();
+        assert_eq!(router.route(Some("user-7")).unwrap().0.version(), "v2");
+
+        // Rollback restores the canary split, then the original one
+        router.rollback().unwrap();
+        assert_eq!(router.split().percent_for("v2"), 20);
+        router.rollback().unwrap();
+        assert_eq!(router.split(), TrafficSplit::single("v1"));
+        assert!(router.rollback().is_err());
+    }
+}
@@ -0,0 +1,559 @@
+//! Webhook事件推送
+//!
+//! 将事件总线上的Agent生命周期和会话事件推送到外部HTTP端点，外部系统无需轮询即可响应。
+//! 每次推送使用HMAC-SHA256签名，失败时按指数退避重试。
+//!
+//! 请求体格式：
+//!
+//! ```json
+//! { "id": "<delivery id>", "type": "tool.failed", "created_at": "...", "data": { ... } }
+//! ```
+//!
+//! 签名头 `X-Lumos-Signature: t=<unix秒>,v1=<hex>`，其中 `v1` 为
+//! `HMAC-SHA256(secret, "<t>.<请求体>")`，接收方可用 [`verify_signature`] 校验。
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use ring::hmac;
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::error::{Error, Result};
+use super::events::{AgentEvent, EventHandler};
+
+/// 会话完成
+pub const WEBHOOK_CONVERSATION_COMPLETED: &str = "conversation.completed";
+
+/// 工具调用失败
+pub const WEBHOOK_TOOL_FAILED: &str = "tool.failed";
+
+/// 护栏触发
+pub const WEBHOOK_GUARDRAIL_TRIGGERED: &str = "guardrail.triggered";
+
+/// 签名请求头
+pub const SIGNATURE_HEADER: &str = "X-Lumos-Signature";
+
+/// 事件类型请求头
+pub const EVENT_HEADER: &str = "X-Lumos-Event";
+
+/// 推送ID请求头
+pub const DELIVERY_HEADER: &str = "X-Lumos-Delivery";
+
+/// 保留的推送记录数
+const MAX_DELIVERY_LOG: usize = 1000;
+
+impl AgentEvent {
+    /// Webhook事件类型，自定义事件使用其事件名
+    pub fn webhook_type(&self) -> String {
+        match self {
+            AgentEvent::AgentStarted { .. } => "agent.started".to_string(),
+            AgentEvent::AgentStopped { .. } => "agent.stopped".to_string(),
+            AgentEvent::StateChanged { .. } => "agent.state_changed".to_string(),
+            AgentEvent::MessageSent { .. } => "message.sent".to_string(),
+            AgentEvent::MessageReceived { .. } => "message.received".to_string(),
+            AgentEvent::ToolCalled { .. } => "tool.called".to_string(),
+            AgentEvent::ToolResult { result: Ok(_), .. } => "tool.completed".to_string(),
+            AgentEvent::ToolResult { result: Err(_), .. } => WEBHOOK_TOOL_FAILED.to_string(),
+            AgentEvent::Error { .. } => "agent.error".to_string(),
+            AgentEvent::CollaborationStarted { .. } => "collaboration.started".to_string(),
+            AgentEvent::CollaborationCompleted { .. } => "collaboration.completed".to_string(),
+            AgentEvent::Custom { event_name, .. } => event_name.clone(),
+        }
+    }
+}
+
+/// Webhook端点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookEndpoint {
+    /// 端点ID
+    pub id: String,
+    /// 推送地址
+    pub url: String,
+    /// 签名密钥
+    pub secret: String,
+    /// 订阅的事件类型，支持 `*` 和 `tool.*` 形式的通配
+    pub events: Vec<String>,
+    /// 是否启用
+    pub enabled: bool,
+    /// 附加请求头
+    pub headers: HashMap<String, String>,
+}
+
+impl WebhookEndpoint {
+    /// 创建订阅全部事件的端点
+    pub fn new(url: impl Into<String>, secret: impl Into<String>) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            url: url.into(),
+            secret: secret.into(),
+            events: vec!["*".to_string()],
+            enabled: true,
+            headers: HashMap::new(),
+        }
+    }
+
+    /// 只订阅指定的事件类型
+    pub fn with_events<I, S>(mut self, events: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.events = events.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// 添加请求头
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.insert(name.into(), value.into());
+        self
+    }
+
+    /// 是否订阅了某个事件类型
+    pub fn subscribes_to(&self, event_type: &str) -> bool {
+        self.enabled && self.events.iter().any(|pattern| {
+            pattern == "*"
+                || pattern == event_type
+                || pattern.strip_suffix(".*").is_some_and(|prefix| {
+                    event_type.strip_prefix(prefix).is_some_and(|rest| rest.starts_with('.'))
+                })
+        })
+    }
+}
+
+/// 重试配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookRetryConfig {
+    /// 最多尝试次数（含首次）
+    pub max_attempts: u32,
+    /// 首次重试前的等待时间
+    pub initial_backoff: Duration,
+    /// 最长等待时间
+    pub max_backoff: Duration,
+    /// 单次请求超时
+    pub timeout: Duration,
+}
+
+impl Default for WebhookRetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+impl WebhookRetryConfig {
+    /// 第 `attempt` 次失败后的等待时间（从1开始）
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff.saturating_mul(factor).min(self.max_backoff)
+    }
+}
+
+/// Webhook发送通道
+#[async_trait]
+pub trait WebhookTransport: Send + Sync {
+    /// 发送请求并返回HTTP状态码
+    async fn send(&self, url: &str, headers: &[(String, String)], body: &str, timeout: Duration) -> Result<u16>;
+}
+
+/// 基于reqwest的HTTP发送通道
+#[derive(Debug, Clone, Default)]
+pub struct HttpWebhookTransport {
+    client: reqwest::Client,
+}
+
+impl HttpWebhookTransport {
+    /// 创建新的HTTP发送通道
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl WebhookTransport for HttpWebhookTransport {
+    async fn send(&self, url: &str, headers: &[(String, String)], body: &str, timeout: Duration) -> Result<u16> {
+        let mut request = self.client.post(url)
+            .timeout(timeout)
+            .header("Content-Type", "application/json")
+            .body(body.to_string());
+        for (name, value) in headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+        let response = request.send().await?;
+        Ok(response.status().as_u16())
+    }
+}
+
+/// 推送状态
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DeliveryStatus {
+    /// 已送达
+    Delivered,
+    /// 重试耗尽或不可重试的失败
+    Failed,
+}
+
+/// 推送记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    /// 推送ID
+    pub id: String,
+    /// 端点ID
+    pub endpoint_id: String,
+    /// 事件类型
+    pub event_type: String,
+    /// 尝试次数
+    pub attempts: u32,
+    /// 推送状态
+    pub status: DeliveryStatus,
+    /// 最后一次响应的状态码
+    pub response_status: Option<u16>,
+    /// 最后一次错误
+    pub last_error: Option<String>,
+    /// 完成时间
+    pub completed_at: DateTime<Utc>,
+}
+
+/// Webhook分发器
+///
+/// 作为 [`EventHandler`] 注册到事件总线后，匹配的事件在后台任务中推送，不阻塞事件发布。
+pub struct WebhookDispatcher {
+    name: String,
+    endpoints: RwLock<Vec<WebhookEndpoint>>,
+    transport: Arc<dyn WebhookTransport>,
+    retry: WebhookRetryConfig,
+    deliveries: Arc<RwLock<VecDeque<WebhookDelivery>>>,
+}
+
+impl WebhookDispatcher {
+    /// 使用HTTP发送通道创建分发器
+    pub fn new() -> Self {
+        Self::with_transport(Arc::new(HttpWebhookTransport::new()))
+    }
+
+    /// 使用指定发送通道创建分发器
+    pub fn with_transport(transport: Arc<dyn WebhookTransport>) -> Self {
+        Self {
+            name: "webhook_dispatcher".to_string(),
+            endpoints: RwLock::new(Vec::new()),
+            transport,
+            retry: WebhookRetryConfig::default(),
+            deliveries: Arc::new(RwLock::new(VecDeque::new())),
+        }
+    }
+
+    /// 设置重试配置
+    pub fn with_retry(mut self, retry: WebhookRetryConfig) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// 添加端点
+    pub async fn add_endpoint(&self, endpoint: WebhookEndpoint) -> Result<()> {
+        let url = url::Url::parse(&endpoint.url)
+            .map_err(|e| Error::InvalidInput(format!("Invalid webhook URL {}: {}", endpoint.url, e)))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(Error::InvalidInput(format!("Unsupported webhook URL scheme: {}", url.scheme())));
+        }
+        let mut endpoints = self.endpoints.write().await;
+        endpoints.retain(|e| e.id != endpoint.id);
+        endpoints.push(endpoint);
+        Ok(())
+    }
+
+    /// 删除端点
+    pub async fn remove_endpoint(&self, endpoint_id: &str) -> bool {
+        let mut endpoints = self.endpoints.write().await;
+        let before = endpoints.len();
+        endpoints.retain(|e| e.id != endpoint_id);
+        endpoints.len() != before
+    }
+
+    /// 最近的推送记录
+    pub async fn deliveries(&self) -> Vec<WebhookDelivery> {
+        self.deliveries.read().await.iter().cloned().collect()
+    }
+
+    /// 推送事件到所有匹配的端点并等待完成
+    pub async fn dispatch(&self, event: &AgentEvent) -> Result<Vec<WebhookDelivery>> {
+        let jobs = self.prepare(event).await?;
+        let mut deliveries = Vec::with_capacity(jobs.len());
+        for job in jobs {
+            deliveries.push(job.run().await);
+        }
+        Ok(deliveries)
+    }
+
+    async fn prepare(&self, event: &AgentEvent) -> Result<Vec<DeliveryJob>> {
+        let event_type = event.webhook_type();
+        let endpoints: Vec<WebhookEndpoint> = self.endpoints.read().await.iter()
+            .filter(|e| e.subscribes_to(&event_type))
+            .cloned()
+            .collect();
+
+        let mut jobs = Vec::with_capacity(endpoints.len());
+        for endpoint in endpoints {
+            let delivery_id = Uuid::new_v4().to_string();
+            let body = serde_json::to_string(&serde_json::json!({
+                "id": delivery_id,
+                "type": event_type,
+                "created_at": event.timestamp(),
+                "data": event,
+            }))?;
+            jobs.push(DeliveryJob {
+                delivery_id,
+                event_type: event_type.clone(),
+                endpoint,
+                body,
+                transport: self.transport.clone(),
+                retry: self.retry.clone(),
+                log: self.deliveries.clone(),
+            });
+        }
+        Ok(jobs)
+    }
+}
+
+impl Default for WebhookDispatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl EventHandler for WebhookDispatcher {
+    async fn handle_event(&self, event: &AgentEvent) -> Result<()> {
+        for job in self.prepare(event).await? {
+            tokio::spawn(job.run());
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn interested_events(&self) -> Vec<String> {
+        vec!["*".to_string()]
+    }
+}
+
+struct DeliveryJob {
+    delivery_id: String,
+    event_type: String,
+    endpoint: WebhookEndpoint,
+    body: String,
+    transport: Arc<dyn WebhookTransport>,
+    retry: WebhookRetryConfig,
+    log: Arc<RwLock<VecDeque<WebhookDelivery>>>,
+}
+
+impl DeliveryJob {
+    async fn run(self) -> WebhookDelivery {
+        let mut attempts = 0;
+        let mut response_status = None;
+        let mut last_error = None;
+        let mut status = DeliveryStatus::Failed;
+
+        while attempts < self.retry.max_attempts.max(1) {
+            attempts += 1;
+            // 每次尝试重新签名，使时间戳反映实际发送时间
+            let timestamp = Utc::now().timestamp();
+            let mut headers: Vec<(String, String)> = self.endpoint.headers.iter()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect();
+            headers.push((SIGNATURE_HEADER.to_string(), sign_payload(&self.endpoint.secret, timestamp, &self.body)));
+            headers.push((EVENT_HEADER.to_string(), self.event_type.clone()));
+            headers.push((DELIVERY_HEADER.to_string(), self.delivery_id.clone()));
+
+            let retryable = match self.transport.send(&self.endpoint.url, &headers, &self.body, self.retry.timeout).await {
+                Ok(code) if (200..300).contains(&code) => {
+                    response_status = Some(code);
+                    last_error = None;
+                    status = DeliveryStatus::Delivered;
+                    break;
+                }
+                Ok(code) => {
+                    response_status = Some(code);
+                    last_error = Some(format!("HTTP {}", code));
+                    code >= 500 || code == 408 || code == 429
+                }
+                Err(e) => {
+                    last_error = Some(e.to_string());
+                    true
+                }
+            };
+            if !retryable || attempts >= self.retry.max_attempts {
+                break;
+            }
+            tokio::time::sleep(self.retry.backoff(attempts)).await;
+        }
+
+        if status == DeliveryStatus::Failed {
+            tracing::warn!(
+                "Webhook delivery {} of {} to {} failed after {} attempts: {}",
+                self.delivery_id, self.event_type, self.endpoint.url, attempts,
+                last_error.as_deref().unwrap_or("unknown error"),
+            );
+        }
+
+        let delivery = WebhookDelivery {
+            id: self.delivery_id,
+            endpoint_id: self.endpoint.id,
+            event_type: self.event_type,
+            attempts,
+            status,
+            response_status,
+            last_error,
+            completed_at: Utc::now(),
+        };
+        let mut log = self.log.write().await;
+        log.push_back(delivery.clone());
+        while log.len() > MAX_DELIVERY_LOG {
+            log.pop_front();
+        }
+        delivery
+    }
+}
+
+/// 生成签名头的值
+pub fn sign_payload(secret: &str, timestamp: i64, body: &str) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    let tag = hmac::sign(&key, format!("{}.{}", timestamp, body).as_bytes());
+    format!("t={},v1={}", timestamp, to_hex(tag.as_ref()))
+}
+
+/// 校验签名头，`tolerance` 为允许的时间偏差
+pub fn verify_signature(secret: &str, header: &str, body: &str, tolerance: Duration) -> bool {
+    let mut timestamp = None;
+    let mut signatures = Vec::new();
+    for part in header.split(',') {
+        match part.trim().split_once('=') {
+            Some(("t", value)) => timestamp = value.parse::<i64>().ok(),
+            Some(("v1", value)) => signatures.push(value),
+            _ => {}
+        }
+    }
+    let Some(timestamp) = timestamp else {
+        return false;
+    };
+    if (Utc::now().timestamp() - timestamp).unsigned_abs() > tolerance.as_secs() {
+        return false;
+    }
+
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    let message = format!("{}.{}", timestamp, body);
+    signatures.into_iter().any(|signature| {
+        from_hex(signature).is_some_and(|tag| hmac::verify(&key, message.as_bytes(), &tag).is_ok())
+    })
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// 前 `failures` 次返回503，之后返回200
+    struct FlakyTransport {
+        failures: u32,
+        requests: Mutex<Vec<(Vec<(String, String)>, String)>>,
+    }
+
+    #[async_trait]
+    impl WebhookTransport for FlakyTransport {
+        async fn send(&self, _url: &str, headers: &[(String, String)], body: &str, _timeout: Duration) -> Result<u16> {
+            let mut requests = self.requests.lock().unwrap();
+            requests.push((headers.to_vec(), body.to_string()));
+            Ok(if requests.len() as u32 <= self.failures { 503 } else { 200 })
+        }
+    }
+
+    fn tool_failed() -> AgentEvent {
+        AgentEvent::ToolResult {
+            agent_id: "agent-1".to_string(),
+            tool_name: "search".to_string(),
+            result: Err("timeout".to_string()),
+            duration_ms: 30_000,
+            timestamp: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_event_subscription_patterns() {
+        let endpoint = WebhookEndpoint::new("https://example.com/hook", "s")
+            .with_events(["tool.*", WEBHOOK_CONVERSATION_COMPLETED]);
+        assert!(endpoint.subscribes_to(WEBHOOK_TOOL_FAILED));
+        assert!(endpoint.subscribes_to(WEBHOOK_CONVERSATION_COMPLETED));
+        assert!(!endpoint.subscribes_to(WEBHOOK_GUARDRAIL_TRIGGERED));
+        assert!(!endpoint.subscribes_to("toolbox.opened"));
+        assert_eq!(tool_failed().webhook_type(), WEBHOOK_TOOL_FAILED);
+    }
+
+    #[tokio::test]
+    async fn test_signed_delivery_with_retries() {
+        let transport = Arc::new(FlakyTransport { failures: 2, requests: Mutex::new(Vec::new()) });
+        let dispatcher = WebhookDispatcher::with_transport(transport.clone()).with_retry(WebhookRetryConfig {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(5),
+            timeout: Duration::from_secs(1),
+        });
+        dispatcher.add_endpoint(WebhookEndpoint::new("https://example.com/hook", "whsec").with_events(["tool.failed"])).await.unwrap();
+        dispatcher.add_endpoint(WebhookEndpoint::new("https://example.com/other", "whsec").with_events(["agent.*"])).await.unwrap();
+        assert!(dispatcher.add_endpoint(WebhookEndpoint::new("ftp://example.com", "s")).await.is_err());
+
+        let deliveries = dispatcher.dispatch(&tool_failed()).await.unwrap();
+        assert_eq!(deliveries.len(), 1);
+        assert_eq!(deliveries[0].status, DeliveryStatus::Delivered);
+        assert_eq!(deliveries[0].attempts, 3);
+
+        let requests = transport.requests.lock().unwrap();
+        let (headers, body) = requests.last().unwrap();
+        let signature = &headers.iter().find(|(k, _)| k == SIGNATURE_HEADER).unwrap().1;
+        assert!(verify_signature("whsec", signature, body, Duration::from_secs(300)));
+        assert!(!verify_signature("other", signature, body, Duration::from_secs(300)));
+        assert!(!verify_signature("whsec", signature, "tampered", Duration::from_secs(300)));
+
+        let payload: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert_eq!(payload["type"], WEBHOOK_TOOL_FAILED);
+        assert_eq!(payload["data"]["tool_name"], "search");
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_attempts() {
+        let transport = Arc::new(FlakyTransport { failures: 10, requests: Mutex::new(Vec::new()) });
+        let dispatcher = WebhookDispatcher::with_transport(transport).with_retry(WebhookRetryConfig {
+            max_attempts: 2,
+            initial_backoff: Duration::from_millis(1),
+            ..WebhookRetryConfig::default()
+        });
+        dispatcher.add_endpoint(WebhookEndpoint::new("https://example.com/hook", "s")).await.unwrap();
+
+        let deliveries = dispatcher.dispatch(&tool_failed()).await.unwrap();
+        assert_eq!(deliveries[0].status, DeliveryStatus::Failed);
+        assert_eq!(deliveries[0].attempts, 2);
+        assert_eq!(deliveries[0].response_status, Some(503));
+        assert_eq!(dispatcher.deliveries().await.len(), 1);
+    }
+}
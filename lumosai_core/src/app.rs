@@ -1,15 +1,22 @@
 use crate::Result;
-use crate::agent::{trait_def::Agent, AgentBuilder, ModelResolver};
+use crate::agent::{trait_def::Agent, AgentBuilder, ModelResolver, ReviewQueue, SessionManager, SessionStorage};
+use crate::attachment::AttachmentService;
+use crate::channels::{ChannelBridge, ChannelsConfig};
 use crate::tool::Tool;
-use crate::config::{ConfigLoader, YamlConfig, WorkflowConfig};
+use crate::config::{ConfigLoader, YamlConfig, WorkflowConfig, ProviderConfig, SecretResolver, HttpClientConfig};
+use crate::llm::{LlmProvider, OpenAiProvider, AnthropicProvider, QwenProvider};
 use std::collections::HashMap;
 use std::sync::Arc;
+use serde::{Deserialize, Serialize};
 use std::path::Path;
 use crate::rag::RagPipeline;
 use crate::workflow::Workflow;
 use crate::workflow::EnhancedWorkflow;
 
 pub mod enhanced;
+pub mod shutdown;
+#[cfg(feature = "server")]
+mod server;
 
 pub use enhanced::{
     EnhancedApp,
@@ -19,6 +26,55 @@ pub use enhanced::{
     ChunkingConfig,
     AppStats,
 };
+pub use shutdown::{shutdown_signal, InFlightGuard, ShutdownCoordinator, ShutdownReport};
+
+/// 配置热重载的结果
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigReload {
+    /// 新增的代理
+    pub added: Vec<String>,
+    /// 配置变更后重建的代理
+    pub updated: Vec<String>,
+    /// 从配置中移除的代理
+    pub removed: Vec<String>,
+}
+
+impl ConfigReload {
+    /// 是否没有任何代理发生变化
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// 应用HTTP服务的监听地址
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServerConfig {
+    /// 监听的主机
+    pub host: String,
+    /// 监听的端口
+    pub port: u16,
+}
+
+impl ServerConfig {
+    /// 创建监听地址
+    pub fn new(host: impl Into<String>, port: u16) -> Self {
+        Self {
+            host: host.into(),
+            port,
+        }
+    }
+
+    /// `host:port`形式的地址
+    pub fn address(&self) -> String {
+        format!("{}:{}", self.host, self.port)
+    }
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self::new("127.0.0.1", 3000)
+    }
+}
 
 /// Lumosai应用主类，用于整合代理、工具、RAG和MCP等组件
 pub struct LumosApp {
@@ -29,8 +85,14 @@ pub struct LumosApp {
     rags: HashMap<String, Arc<dyn RagPipeline>>,
     workflows: HashMap<String, Arc<dyn Workflow>>,
     mcp_endpoints: Vec<String>,
+    server: Option<ServerConfig>,
+    shutdown: ShutdownCoordinator,
     config: Option<YamlConfig>,
     model_resolver: ModelResolver,
+    sessions: Option<Arc<SessionManager>>,
+    reviews: Option<Arc<ReviewQueue>>,
+    attachments: Option<Arc<AttachmentService>>,
+    channels: Option<ChannelsConfig>,
 }
 
 impl LumosApp {
@@ -44,19 +106,37 @@ impl LumosApp {
             rags: HashMap::new(),
             workflows: HashMap::new(),
             mcp_endpoints: Vec::new(),
+            server: None,
+            shutdown: ShutdownCoordinator::new(),
             config: None,
             model_resolver: ModelResolver::new(),
+            sessions: None,
+            reviews: None,
+            attachments: None,
+            channels: None,
         }
     }
 
     /// 从配置文件创建应用实例
+    ///
+    /// 支持 `${VAR}` 环境变量插值与 `LUMOS__*` 环境变量覆盖，加载后会进行校验
     pub async fn from_config<P: AsRef<Path>>(config_path: P) -> Result<Self> {
-        let config = ConfigLoader::load(config_path)?;
+        let config = ConfigLoader::load_validated(config_path)?;
         Self::from_yaml_config(config).await
     }
 
     /// 从 YAML 配置创建应用实例
     pub async fn from_yaml_config(config: YamlConfig) -> Result<Self> {
+        Self::from_yaml_config_with_secrets(config, &SecretResolver::from_env()).await
+    }
+
+    /// 从 YAML 配置创建应用实例，使用指定的密钥解析器解析提供商 API 密钥
+    pub async fn from_yaml_config_with_secrets(config: YamlConfig, secrets: &SecretResolver) -> Result<Self> {
+        config.validate()?;
+        if let Some(http) = &config.http {
+            http.clone().install()?;
+        }
+
         let mut app = Self {
             name: config.project.as_ref()
                 .map(|p| p.name.clone())
@@ -68,18 +148,29 @@ impl LumosApp {
             rags: HashMap::new(),
             workflows: HashMap::new(),
             mcp_endpoints: Vec::new(),
+            server: None,
+            shutdown: ShutdownCoordinator::new(),
             config: Some(config.clone()),
             model_resolver: ModelResolver::new(),
+            sessions: None,
+            reviews: None,
+            attachments: None,
+            channels: None,
         };
 
         // 创建配置中定义的 Agents
         if let Some(agents_config) = &config.agents {
             for (name, agent_config) in agents_config {
-                let agent = app.create_agent_from_config(name, agent_config).await?;
+                let agent = app.create_agent_from_config(name, agent_config, secrets).await?;
                 app.agents.insert(name.clone(), Arc::new(agent));
             }
         }
 
+        // 渠道凭证中的密钥引用在创建时解析
+        if let Some(channels) = &config.channels {
+            app.channels = Some(channels.resolve_secrets(secrets).await?);
+        }
+
         // 创建配置中定义的 Workflows
         if let Some(workflows_config) = &config.workflows {
             for (name, workflow_config) in workflows_config {
@@ -91,6 +182,93 @@ impl LumosApp {
         Ok(app)
     }
 
+    /// 使用新配置热重载代理
+    ///
+    /// 只重建配置发生变化的代理，未变化的代理保持原实例；任一代理构建失败时
+    /// 应用保持重载前的状态。
+    pub async fn reload_config(&mut self, config: YamlConfig) -> Result<ConfigReload> {
+        self.reload_config_with_secrets(config, &SecretResolver::from_env()).await
+    }
+
+    /// 使用新配置热重载代理，使用指定的密钥解析器
+    pub async fn reload_config_with_secrets(
+        &mut self,
+        config: YamlConfig,
+        secrets: &SecretResolver,
+    ) -> Result<ConfigReload> {
+        config.validate()?;
+        let http_changed = self.config.as_ref().map(|c| &c.http) != Some(&config.http);
+        if http_changed {
+            Self::install_http_config(&config)?;
+        }
+
+        let previous = self.config.replace(config.clone());
+        let mut changes = ConfigReload::default();
+        let mut rebuilt = Vec::new();
+
+        for name in config.list_agents() {
+            let old = previous.as_ref().and_then(|c| Self::agent_fingerprint(c, &name));
+            let new = Self::agent_fingerprint(&config, &name);
+            if old.is_some() && old == new {
+                continue;
+            }
+
+            let agent_config = config.get_agent(&name).expect("listed agent exists");
+            match self.create_agent_from_config(&name, agent_config, secrets).await {
+                Ok(agent) => {
+                    if old.is_some() {
+                        changes.updated.push(name.clone());
+                    } else {
+                        changes.added.push(name.clone());
+                    }
+                    rebuilt.push((name, Arc::new(agent) as Arc<dyn Agent>));
+                },
+                Err(e) => {
+                    if http_changed {
+                        if let Some(previous) = &previous {
+                            Self::install_http_config(previous)?;
+                        }
+                    }
+                    self.config = previous;
+                    return Err(e);
+                }
+            }
+        }
+
+        if let Some(previous) = &previous {
+            for name in previous.list_agents() {
+                if config.get_agent(&name).is_none() {
+                    self.agents.remove(&name);
+                    changes.removed.push(name);
+                }
+            }
+        }
+
+        self.agents.extend(rebuilt);
+        changes.added.sort();
+        changes.updated.sort();
+        changes.removed.sort();
+        Ok(changes)
+    }
+
+    /// 代理的配置指纹，包含其引用的提供商配置和出站 HTTP 配置
+    fn agent_fingerprint(config: &YamlConfig, name: &str) -> Option<serde_json::Value> {
+        let agent = config.get_agent(name)?;
+        let provider = agent.provider.as_ref().and_then(|p| config.get_provider(p));
+        serde_json::to_value((agent, provider, &config.http)).ok()
+    }
+
+    /// 安装配置中的出站 HTTP 配置；未配置时恢复为环境变量配置
+    fn install_http_config(config: &YamlConfig) -> Result<()> {
+        match &config.http {
+            Some(http) => http.clone().install(),
+            None => {
+                HttpClientConfig::uninstall();
+                Ok(())
+            },
+        }
+    }
+
     /// 自动检测并加载配置文件
     pub async fn auto_load() -> Result<Self> {
         let config = ConfigLoader::auto_detect()?;
@@ -102,11 +280,36 @@ impl LumosApp {
         &self,
         name: &str,
         config: &crate::config::AgentConfig,
+        secrets: &SecretResolver,
     ) -> Result<impl Agent> {
         let mut builder = AgentBuilder::new()
             .name(name)
-            .instructions(&config.instructions)
-            .model_name(&config.model);
+            .instructions(&config.instructions);
+
+        if let Some(guardrails) = &config.guardrails {
+            builder = builder.guardrails(guardrails.clone());
+        }
+
+        if let Some(few_shot) = &config.few_shot {
+            builder = builder.few_shot(few_shot.clone());
+        }
+
+        if let Some(tools_memory) = &config.tools_memory {
+            builder = builder.tools_memory(tools_memory.clone());
+        }
+
+        // 使用命名的提供商配置，否则按模型名称自动解析
+        let provider_config = config.provider.as_ref().and_then(|provider| {
+            self.config.as_ref().and_then(|c| c.get_provider(provider))
+        });
+        let model = match provider_config {
+            Some(provider) => Some(self.create_provider_from_config(provider, &config.model, secrets).await?),
+            None => None,
+        };
+        builder = match &model {
+            Some(model) => builder.model(model.clone()),
+            None => builder.model_name(&config.model),
+        };
 
         // 设置可选参数
         if let Some(temperature) = config.temperature {
@@ -124,6 +327,11 @@ impl LumosApp {
         // 添加工具
         if let Some(tools) = &config.tools {
             for tool_name in tools {
+                if tool_name == "translate" && !self.tools.contains_key(tool_name) {
+                    let tool = self.translate_tool(model.as_ref(), &config.model).await?;
+                    builder = builder.add_tool(Arc::new(tool));
+                    continue;
+                }
                 if let Some(tool) = self.resolve_tool(tool_name)? {
                     builder = builder.add_tool(tool);
                 }
@@ -133,6 +341,81 @@ impl LumosApp {
         builder.build_async().await
     }
 
+    /// 根据提供商配置创建 LLM 提供商，API 密钥可以是密钥引用
+    async fn create_provider_from_config(
+        &self,
+        provider: &ProviderConfig,
+        model: &str,
+        secrets: &SecretResolver,
+    ) -> Result<Arc<dyn LlmProvider>> {
+        let model = if model.is_empty() {
+            provider.model.clone().unwrap_or_default()
+        } else {
+            model.to_string()
+        };
+
+        let api_key = match &provider.api_key {
+            Some(reference) => Some(secrets.resolve(reference).await?),
+            None => None,
+        };
+
+        match (provider.provider_type.as_str(), api_key) {
+            ("openai", Some(api_key)) => {
+                let mut llm = OpenAiProvider::new(api_key, model);
+                if let Some(base_url) = &provider.base_url {
+                    llm = llm.with_base_url(base_url.clone());
+                }
+                Ok(Arc::new(llm))
+            },
+            ("anthropic", Some(api_key)) => {
+                let mut llm = AnthropicProvider::new(api_key, model);
+                if let Some(base_url) = &provider.base_url {
+                    llm = llm.with_base_url(base_url.clone());
+                }
+                Ok(Arc::new(llm))
+            },
+            (provider_type @ ("deepseek" | "qwen"), Some(api_key)) => {
+                let default_url = if provider_type == "deepseek" {
+                    "https://api.deepseek.com/v1"
+                } else {
+                    "https://dashscope.aliyuncs.com/compatible-mode/v1"
+                };
+                let base_url = provider.base_url.clone().unwrap_or_else(|| default_url.to_string());
+                Ok(Arc::new(QwenProvider::new(api_key, model, base_url)))
+            },
+            // 未配置密钥时回退到环境变量中的密钥
+            (provider_type, _) => {
+                self.model_resolver.resolve(&format!("{}/{}", provider_type, model)).await
+            }
+        }
+    }
+
+    /// 内置翻译工具
+    ///
+    /// 设置了 `LUMOSAI_TRANSLATE_URL` 时使用兼容LibreTranslate的翻译服务
+    /// （密钥取自 `LUMOSAI_TRANSLATE_API_KEY`），否则使用Agent自身的模型翻译
+    async fn translate_tool(
+        &self,
+        model: Option<&Arc<dyn LlmProvider>>,
+        model_name: &str,
+    ) -> Result<crate::tool::builtin::TranslateTool> {
+        use crate::tool::builtin::{HttpTranslator, TranslateTool};
+
+        if let Ok(endpoint) = std::env::var("LUMOSAI_TRANSLATE_URL") {
+            let mut translator = HttpTranslator::new(endpoint);
+            if let Ok(api_key) = std::env::var("LUMOSAI_TRANSLATE_API_KEY") {
+                translator = translator.with_api_key(api_key);
+            }
+            return Ok(TranslateTool::new(Arc::new(translator)));
+        }
+
+        let llm = match model {
+            Some(model) => model.clone(),
+            None => self.model_resolver.resolve(model_name).await?,
+        };
+        Ok(TranslateTool::with_llm(llm))
+    }
+
     /// 解析工具名称到工具实例
     fn resolve_tool(&self, tool_name: &str) -> Result<Option<Arc<dyn crate::tool::Tool>>> {
         // 首先检查已注册的工具
@@ -207,6 +490,11 @@ impl LumosApp {
         self.tools.insert(name, Arc::new(tool));
     }
     
+    /// 添加共享的工具实例到应用，例如`#[tool]`生成的`Box<dyn Tool>`
+    pub fn add_shared_tool(&mut self, name: String, tool: Arc<dyn Tool>) {
+        self.tools.insert(name, tool);
+    }
+    
     /// 添加RAG到应用
     pub fn add_rag(&mut self, name: String, rag: impl RagPipeline + 'static) {
         self.rags.insert(name, Arc::new(rag));
@@ -222,6 +510,84 @@ impl LumosApp {
         self.mcp_endpoints = endpoints;
     }
     
+    /// 设置HTTP服务的监听地址，`start`时自动启动服务
+    pub fn with_server(mut self, server: ServerConfig) -> Self {
+        self.server = Some(server);
+        self
+    }
+    
+    /// 设置关闭协调器，例如调整等待进行中请求的截止时间
+    pub fn with_shutdown_coordinator(mut self, coordinator: ShutdownCoordinator) -> Self {
+        self.shutdown = coordinator;
+        self
+    }
+    
+    /// 设置会话存储，HTTP服务据此提供会话消息的反馈接口
+    pub fn with_session_storage(mut self, storage: Arc<dyn SessionStorage>) -> Self {
+        self.sessions = Some(Arc::new(SessionManager::new(storage)));
+        self
+    }
+    
+    /// 设置审核队列，HTTP服务据此提供低置信度回复的审核接口
+    ///
+    /// 代理需通过[`AgentBuilder::review_queue`]共享同一队列，审核结果才会作为少样本示例回流
+    pub fn with_review_queue(mut self, queue: Arc<ReviewQueue>) -> Self {
+        self.reviews = Some(queue);
+        self
+    }
+    
+    /// 设置附件服务，HTTP服务据此提供文件上传接口，代理调用请求可以引用已上传的附件
+    pub fn with_attachments(mut self, attachments: AttachmentService) -> Self {
+        self.attachments = Some(Arc::new(attachments));
+        self
+    }
+    
+    /// 设置聊天渠道，`start`时连接各渠道
+    pub fn with_channels(mut self, channels: ChannelsConfig) -> Self {
+        self.channels = Some(channels);
+        self
+    }
+    
+    /// 连接Slack、Telegram和Discord，返回各渠道的后台任务
+    ///
+    /// 渠道共享应用的会话存储和附件服务。Teams通过HTTP服务的`/api/messages`接收消息，不在此启动。
+    pub fn spawn_channels(&self) -> Result<Vec<tokio::task::JoinHandle<Result<()>>>> {
+        let Some(channels) = &self.channels else {
+            return Ok(Vec::new());
+        };
+        let mut tasks = Vec::new();
+        if let Some(slack) = &channels.slack {
+            let bridge = self.channel_bridge(channels, &slack.agent)?;
+            tasks.push(tokio::spawn(Arc::new(slack.adapter()).run(bridge)));
+        }
+        if let Some(telegram) = &channels.telegram {
+            let bridge = self.channel_bridge(channels, &telegram.agent)?;
+            tasks.push(tokio::spawn(Arc::new(telegram.adapter()).run(bridge)));
+        }
+        if let Some(discord) = &channels.discord {
+            let bridge = self.channel_bridge(channels, &discord.agent)?;
+            tasks.push(tokio::spawn(Arc::new(discord.adapter()).run(bridge)));
+        }
+        Ok(tasks)
+    }
+    
+    /// 为渠道创建连接到指定代理的桥接
+    fn channel_bridge(&self, channels: &ChannelsConfig, agent: &str) -> Result<Arc<ChannelBridge>> {
+        let mut bridge = ChannelBridge::new(self.agent(agent)?.clone());
+        if let Some(sessions) = &self.sessions {
+            bridge = bridge.with_sessions(sessions.clone());
+        }
+        if let Some(attachments) = &self.attachments {
+            bridge = bridge.with_attachments(attachments.clone());
+        }
+        Ok(Arc::new(channels.configure(bridge)))
+    }
+    
+    /// 优雅关闭：拒绝新请求，等待进行中的请求完成后刷新注册的存储
+    pub async fn shutdown(&self) -> ShutdownReport {
+        self.shutdown.shutdown().await
+    }
+    
     /// 启动应用
     pub async fn start(&self) -> Result<()> {
         println!("Starting Lumosai application: {}", self.name);
@@ -239,7 +605,21 @@ impl LumosApp {
             println!("MCP endpoints: {}", self.mcp_endpoints.join(", "));
         }
         
-        // 实际应用中，这里会执行更多的启动逻辑
+        let channels = self.spawn_channels()?;
+        
+        // 配置了监听地址时提供HTTP服务，直到服务结束
+        #[cfg(feature = "server")]
+        if let Some(server) = &self.server {
+            let result = self.serve(server).await;
+            channels.iter().for_each(|task| task.abort());
+            return result;
+        }
+        
+        // 没有HTTP服务时运行渠道，直到某个渠道出错
+        let mut channels: futures::stream::FuturesUnordered<_> = channels.into_iter().collect();
+        while let Some(result) = futures::StreamExt::next(&mut channels).await {
+            result.map_err(|e| crate::Error::Internal(format!("Channel task failed: {}", e)))??;
+        }
         
         Ok(())
     }
@@ -261,8 +641,9 @@ impl LumosApp {
                 metadata: None,
             };
             
-            // 调用代理
-            let result = agent.generate(&[user_message], &crate::agent::types::AgentGenerateOptions::default()).await?;
+            // 调用代理，关闭时等待其完成
+            let options = crate::agent::types::AgentGenerateOptions::default();
+            let result = self.shutdown.track(agent.generate(&[user_message], &options)).await??;
             
             Ok(result.response)
         } else {
@@ -306,10 +687,30 @@ impl LumosApp {
         &self.workflows
     }
     
+    /// 获取加载应用时使用的配置
+    pub fn config(&self) -> Option<&YamlConfig> {
+        self.config.as_ref()
+    }
+    
     /// 获取MCP端点列表
     pub fn mcp_endpoints(&self) -> &[String] {
         &self.mcp_endpoints
     }
+    
+    /// 获取HTTP服务的监听地址
+    pub fn server(&self) -> Option<&ServerConfig> {
+        self.server.as_ref()
+    }
+    
+    /// 获取聊天渠道配置
+    pub fn channels(&self) -> Option<&ChannelsConfig> {
+        self.channels.as_ref()
+    }
+    
+    /// 获取关闭协调器，用于跟踪请求或注册刷新钩子
+    pub fn shutdown_coordinator(&self) -> &ShutdownCoordinator {
+        &self.shutdown
+    }
 }
 
 impl Default for LumosApp {
@@ -0,0 +1,759 @@
+//! LumosApp的HTTP服务
+//!
+//! 为应用中的每个代理和工作流生成HTTP路由，并通过MCP端点（JSON-RPC 2.0）提供已注册的工具：
+//!
+//! - `GET /health`：健康检查
+//! - `GET /api`：应用信息和已注册的组件
+//! - `POST /api/agents/{name}/generate`：调用代理，请求体为`{"message": "..."}`或`{"messages": [{"role", "content"}]}`，
+//!   可加`language`（如`"fr"`）指定回复语言；`attachments`为`message`引用的附件ID，
+//!   `messages`中的消息也可以各自带`attachments`，附件内容随消息提供给代理
+//! - `POST /api/agents/{name}/stream`：以SSE流式调用代理，请求体同上，每个事件为`{"delta": "..."}`；
+//!   请求体加`structured: true`或`schema`（JSON Schema）时代理以JSON回复，每个事件另带
+//!   `partial`（已收到部分解析出的值）和`complete`，界面可以逐步渲染各字段
+//! - `POST /api/agents/{name}/variants`：并发生成多个候选回复，请求体另加`n`（默认3）和`select`（由模型评判最佳候选）
+//! - `POST /api/workflows/{name}/run`：以请求体为输入执行工作流
+//! - `POST /api/sessions/{id}/messages/{index}/feedback`：对会话中的一条助手消息提交反馈，
+//!   请求体为`{"thumbs": "up"|"down", "rating": 1-5, "correction": "...", "comment": "..."}`，
+//!   用户取自`x-user-id`请求头；需要通过[`LumosApp::with_session_storage`]配置会话存储
+//! - `GET /api/sessions/{id}/feedback`：列出会话收到的反馈
+//! - `GET /api/reviews`：列出审核队列中的低置信度回复，可用`?status=pending`等过滤；
+//!   需要通过[`LumosApp::with_review_queue`]配置审核队列
+//! - `POST /api/reviews/{id}`：提交审核结论，请求体为`{"action": "approve"|"dismiss"}`或
+//!   `{"action": "correct", "correction": "..."}`，审核人取自`x-user-id`请求头
+//! - `POST /api/attachments`：以`multipart/form-data`上传文件，每个带文件名的字段为一个附件，
+//!   `ingest`字段为`true`时把文本内容写入检索管道；单次请求的总大小不超过单个附件的大小上限。
+//!   需要通过[`LumosApp::with_attachments`]配置附件服务
+//! - `GET /api/attachments/{id}`：附件元数据；`GET /api/attachments/{id}/content`：下载文件；
+//!   `DELETE /api/attachments/{id}`：删除附件
+//! - `POST /mcp`：MCP端点，支持`initialize`、`tools/list`和`tools/call`
+//! - `POST /api/messages`：配置了Teams渠道时的Bot Framework消息端点，见[`crate::channels`]
+//!
+//! 所有请求都由应用的[`ShutdownCoordinator`]跟踪：关闭开始后新请求返回503，
+//! 服务在进行中的请求完成并刷新存储后退出。
+//!
+//! 客户端断开连接时请求的[`CancellationToken`]被取消，进行中的LLM请求和工具子进程随之中止。
+//! 流式输出通过有界通道发送，客户端读取较慢时代理生成随之暂停。
+//!
+//! 每个请求在[`RequestContext`]中执行：追踪ID取自`x-request-id`或W3C `traceparent`请求头，
+//! 没有时生成新的ID；`x-tenant-id`、`x-user-id`和`x-session-id`分别设置租户、用户和会话。
+//! 追踪ID通过响应头`x-request-id`返回，便于关联客户端日志。
+//!
+//! 错误按[`ErrorKind`](crate::error::ErrorKind)映射为HTTP状态码，响应体为
+//! `{"error": {"code", "message", "retryable", "status"}}`，其中`message`可以直接展示给终端用户。
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use axum::extract::{DefaultBodyLimit, Multipart, Path, Query, Request, State};
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use futures::StreamExt;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio_stream::wrappers::ReceiverStream;
+
+use crate::attachment::AttachmentService;
+use crate::agent::feedback::{Feedback, FeedbackQuery, Thumbs};
+use crate::agent::review::{ReviewDecision, ReviewQueue, ReviewStatus};
+use crate::agent::session::SessionManager;
+use crate::agent::structured::{json_instructions, partial_json_stream};
+use crate::agent::trait_def::Agent;
+use crate::agent::types::{AgentGenerateOptions, AgentStreamOptions, RuntimeContext};
+use crate::cancellation::CancellationToken;
+use crate::llm::{FunctionDefinition, Message, Role};
+use crate::request_context::{self, RequestContext};
+use crate::tool::{Tool, ToolExecutionContext, ToolExecutionOptions};
+use crate::workflow::Workflow;
+use crate::{Error, Result};
+use super::{LumosApp, ServerConfig, ShutdownCoordinator};
+
+/// 支持的MCP协议版本
+const MCP_PROTOCOL_VERSION: &str = "2024-11-05";
+
+/// 流式输出缓冲的块数
+const STREAM_BUFFER: usize = 16;
+
+/// 携带追踪ID的请求头和响应头
+const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// 调用方提供的追踪ID的最大长度
+const MAX_TRACE_ID_LEN: usize = 128;
+
+/// 未指定`n`时生成的候选数
+const DEFAULT_VARIANTS: usize = 3;
+
+/// 单次请求最多生成的候选数
+const MAX_VARIANTS: usize = 8;
+
+/// 上传请求体中除文件内容外预留的字节数
+const MULTIPART_OVERHEAD: usize = 64 * 1024;
+
+/// 路由共享的应用组件
+struct AppState {
+    name: String,
+    info: Value,
+    agents: HashMap<String, Arc<dyn Agent>>,
+    tools: HashMap<String, Arc<dyn Tool>>,
+    workflows: HashMap<String, Arc<dyn Workflow>>,
+    sessions: Option<Arc<SessionManager>>,
+    reviews: Option<Arc<ReviewQueue>>,
+    attachments: Option<Arc<AttachmentService>>,
+}
+
+impl LumosApp {
+    /// 构建应用的HTTP路由
+    pub fn router(&self) -> Router {
+        let state = Arc::new(AppState {
+            name: self.name.clone(),
+            info: self.info(),
+            agents: self.agents.clone(),
+            tools: self.tools.clone(),
+            workflows: self.workflows.clone(),
+            sessions: self.sessions.clone(),
+            reviews: self.reviews.clone(),
+            attachments: self.attachments.clone(),
+        });
+        let upload_limit = self.attachments.as_ref()
+            .map_or(0, |attachments| attachments.max_size())
+            .saturating_add(MULTIPART_OVERHEAD);
+
+        let mut router = Router::new()
+            .route("/health", get(health))
+            .route("/api", get(app_info))
+            .route("/api/agents/{name}/generate", post(generate))
+            .route("/api/agents/{name}/stream", post(stream))
+            .route("/api/agents/{name}/variants", post(variants))
+            .route("/api/workflows/{name}/run", post(run_workflow))
+            .route("/api/sessions/{id}/messages/{index}/feedback", post(submit_feedback))
+            .route("/api/sessions/{id}/feedback", get(list_feedback))
+            .route("/api/reviews", get(list_reviews))
+            .route("/api/reviews/{id}", post(submit_review))
+            .route("/api/attachments", post(upload_attachments).layer(DefaultBodyLimit::max(upload_limit)))
+            .route("/api/attachments/{id}", get(get_attachment).delete(delete_attachment))
+            .route("/api/attachments/{id}/content", get(download_attachment))
+            .route("/mcp", post(mcp))
+            .with_state(state);
+
+        // 配置了Teams渠道时挂载Bot Framework消息端点
+        if let Some(channels) = &self.channels {
+            if let Some(teams) = &channels.teams {
+                match self.channel_bridge(channels, &teams.agent) {
+                    Ok(bridge) => router = router.merge(Arc::new(teams.adapter()).router(bridge)),
+                    Err(e) => tracing::warn!("Teams channel is not available: {}", e),
+                }
+            }
+        }
+
+        router
+            .layer(middleware::from_fn_with_state(self.shutdown.clone(), track_request))
+            .layer(middleware::from_fn(with_request_context))
+    }
+
+    /// 在指定地址上提供HTTP服务，收到Ctrl+C或SIGTERM后优雅关闭
+    pub async fn serve(&self, server: &ServerConfig) -> Result<()> {
+        let listener = tokio::net::TcpListener::bind(server.address()).await?;
+        tracing::info!("Serving Lumosai application '{}' on {}", self.name, server.address());
+
+        // 信号到达后先排空进行中的请求并刷新存储，再停止监听
+        let shutdown = self.shutdown.clone();
+        axum::serve(listener, self.router())
+            .with_graceful_shutdown(async move {
+                let signal = shutdown.clone();
+                tokio::select! {
+                    report = signal.shutdown_on_signal() => {
+                        tracing::info!("Lumosai application shut down: {:?}", report);
+                    },
+                    _ = shutdown.wait_for_shutdown() => {},
+                }
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// 应用信息和已注册组件的名称
+    fn info(&self) -> Value {
+        json!({
+            "name": self.name,
+            "description": self.description,
+            "agents": sorted_names(&self.agents),
+            "tools": sorted_names(&self.tools),
+            "rags": sorted_names(&self.rags),
+            "workflows": sorted_names(&self.workflows),
+            "mcp_endpoints": self.mcp_endpoints,
+        })
+    }
+}
+
+/// 按名称排序的组件名
+fn sorted_names<V>(components: &HashMap<String, V>) -> Vec<&String> {
+    let mut names: Vec<&String> = components.keys().collect();
+    names.sort();
+    names
+}
+
+/// 跟踪进行中的请求，关闭开始后拒绝新请求
+async fn track_request(State(shutdown): State<ShutdownCoordinator>, request: Request, next: Next) -> Response {
+    match shutdown.begin() {
+        Ok(_guard) => next.run(request).await,
+        Err(e) => error_response(&e),
+    }
+}
+
+/// 在请求上下文中处理请求，并在响应头中返回追踪ID
+async fn with_request_context(request: Request, next: Next) -> Response {
+    let context = request_context_from_headers(request.headers());
+    let trace_id = context.trace_id.clone();
+    let mut response = context.scope(next.run(request)).await;
+    if let Ok(value) = HeaderValue::from_str(&trace_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+/// 从请求头构建请求上下文
+fn request_context_from_headers(headers: &HeaderMap) -> RequestContext {
+    let header = |name: &str| {
+        headers.get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|value| !value.is_empty() && value.len() <= MAX_TRACE_ID_LEN)
+    };
+    // traceparent格式为`version-trace_id-parent_id-flags`
+    let trace_id = header(REQUEST_ID_HEADER)
+        .or_else(|| header("traceparent").and_then(|value| value.split('-').nth(1)));
+
+    let mut context = match trace_id {
+        Some(trace_id) => RequestContext::with_trace_id(trace_id),
+        None => RequestContext::new(),
+    };
+    context.tenant_id = header("x-tenant-id").map(str::to_string);
+    context.user_id = header("x-user-id").map(str::to_string);
+    context.session_id = header("x-session-id").map(str::to_string);
+    context
+}
+
+/// 错误响应：状态码和可展示的消息由错误分类决定，完整错误只写入日志
+fn error_response(error: &Error) -> Response {
+    let response = error.to_response();
+    if response.status >= 500 {
+        tracing::warn!("Request failed: {}", error);
+    }
+    let status = StatusCode::from_u16(response.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    (status, Json(json!({ "error": response }))).into_response()
+}
+
+async fn health() -> Json<Value> {
+    Json(json!({ "status": "ok" }))
+}
+
+async fn app_info(State(state): State<Arc<AppState>>) -> Json<Value> {
+    Json(state.info.clone())
+}
+
+/// 请求中的一条消息
+#[derive(Debug, Deserialize)]
+struct RequestMessage {
+    role: String,
+    content: String,
+    /// 消息引用的附件ID
+    #[serde(default)]
+    attachments: Vec<String>,
+}
+
+/// 代理调用请求：`messages`为之前的对话，`message`为追加的用户消息
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct GenerateRequest {
+    message: Option<String>,
+    messages: Vec<RequestMessage>,
+    /// 回复语言，如`fr`或`French`
+    language: Option<String>,
+    /// 流式输出时以JSON回复并逐步解析
+    structured: bool,
+    /// 结构化输出须符合的JSON Schema，设置时隐含`structured`
+    schema: Option<Value>,
+    /// `message`引用的附件ID
+    attachments: Vec<String>,
+}
+
+impl GenerateRequest {
+    /// 各消息引用的附件：消息在转换后列表中的位置和附件ID
+    fn attachment_references(&self) -> Vec<(usize, Vec<String>)> {
+        let mut references: Vec<(usize, Vec<String>)> = self.messages.iter()
+            .enumerate()
+            .filter(|(_, message)| !message.attachments.is_empty())
+            .map(|(index, message)| (index, message.attachments.clone()))
+            .collect();
+        if self.message.is_some() && !self.attachments.is_empty() {
+            references.push((self.messages.len(), self.attachments.clone()));
+        }
+        references
+    }
+
+    /// 转换为代理的输入消息
+    fn into_messages(self) -> Result<Vec<Message>> {
+        let mut messages: Vec<Message> = self.messages.into_iter()
+            .map(|message| Message {
+                role: Role::new(message.role),
+                content: message.content,
+                metadata: None,
+                name: None,
+            })
+            .collect();
+        if let Some(content) = self.message {
+            messages.push(Message {
+                role: Role::User,
+                content,
+                metadata: None,
+                name: None,
+            });
+        }
+        if messages.is_empty() {
+            return Err(Error::InvalidInput("Request must contain `message` or `messages`".to_string()));
+        }
+        Ok(messages)
+    }
+}
+
+/// 多候选请求：在代理调用请求之外指定候选数和是否评判最佳候选
+#[derive(Debug, Deserialize)]
+struct VariantsRequest {
+    #[serde(flatten)]
+    request: GenerateRequest,
+    n: Option<usize>,
+    #[serde(default)]
+    select: bool,
+}
+
+/// 查找代理并解析请求中的消息，引用的附件随消息提供
+async fn agent_input(state: &AppState, name: &str, request: GenerateRequest) -> Result<(Arc<dyn Agent>, Vec<Message>)> {
+    let agent = state.agents.get(name).cloned()
+        .ok_or_else(|| Error::NotFound(format!("Agent '{}' not found", name)))?;
+    let references = request.attachment_references();
+    let mut messages = request.into_messages()?;
+    attach_files(state, &mut messages, references).await?;
+    Ok((agent, messages))
+}
+
+/// 把附件记录到消息的元数据中，并在消息内容后附上附件内容
+async fn attach_files(state: &AppState, messages: &mut [Message], references: Vec<(usize, Vec<String>)>) -> Result<()> {
+    if references.is_empty() {
+        return Ok(());
+    }
+    let service = attachment_service(state)?;
+    for (index, ids) in references {
+        let message = &mut messages[index];
+        let attachments = service.resolve(&ids).await?;
+        let context = service.context(&attachments, &message.content).await?;
+        message.content = format!("{}\n\n{}", message.content, context);
+        *message = message.clone().with_attachments(attachments);
+    }
+    Ok(())
+}
+
+async fn generate(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    Json(request): Json<GenerateRequest>,
+) -> Response {
+    let language = request.language.clone();
+    let (agent, messages) = match agent_input(&state, &name, request).await {
+        Ok(input) => input,
+        Err(e) => return error_response(&e),
+    };
+
+    // 客户端断开时处理函数被丢弃，守卫随之取消令牌
+    let token = CancellationToken::new();
+    let _guard = token.clone().drop_guard();
+    let mut options = AgentGenerateOptions::default();
+    options.language = language;
+    options.llm_options.cancellation_token = Some(token);
+
+    match agent.generate(&messages, &options).await {
+        Ok(result) => Json(result).into_response(),
+        Err(e) => error_response(&e),
+    }
+}
+
+/// 生成多个候选回复，用于“重新生成”等界面；`select`为真时返回评判选出的候选
+async fn variants(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    Json(request): Json<VariantsRequest>,
+) -> Response {
+    let n = request.n.unwrap_or(DEFAULT_VARIANTS);
+    if !(1..=MAX_VARIANTS).contains(&n) {
+        return error_response(&Error::InvalidInput(format!("`n` must be between 1 and {}", MAX_VARIANTS)));
+    }
+    let language = request.request.language.clone();
+    let (agent, messages) = match agent_input(&state, &name, request.request).await {
+        Ok(input) => input,
+        Err(e) => return error_response(&e),
+    };
+
+    let token = CancellationToken::new();
+    let _guard = token.clone().drop_guard();
+    let mut options = AgentGenerateOptions::default();
+    options.language = language;
+    options.llm_options.cancellation_token = Some(token);
+
+    if request.select {
+        match agent.generate_best_of_n(&messages, n, &options, None).await {
+            Ok(best) => Json(json!({
+                "variants": best.candidates,
+                "selected": best.selected,
+                "reason": best.reason,
+            })).into_response(),
+            Err(e) => error_response(&e),
+        }
+    } else {
+        match agent.generate_n(&messages, n, &options).await {
+            Ok(candidates) => Json(json!({ "variants": candidates })).into_response(),
+            Err(e) => error_response(&e),
+        }
+    }
+}
+
+/// 以SSE发送代理的输出块，出错时发送`error`事件
+async fn stream(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    Json(request): Json<GenerateRequest>,
+) -> Response {
+    let structured = request.structured || request.schema.is_some();
+    let schema = request.schema.clone();
+    let (agent, messages) = match agent_input(&state, &name, request).await {
+        Ok(input) => input,
+        Err(e) => return error_response(&e),
+    };
+
+    let token = CancellationToken::new();
+    let mut options = AgentStreamOptions::default();
+    options.llm_options.cancellation_token = Some(token.clone());
+    if structured {
+        options.instructions = Some(json_instructions(agent.get_instructions(), schema.as_ref()));
+    }
+
+    // 有界通道：客户端读取较慢时发送方等待，生成随之暂停
+    let (sender, receiver) = tokio::sync::mpsc::channel(STREAM_BUFFER);
+    tokio::spawn(request_context::propagate(async move {
+        let chunks = agent.stream(&messages, &options).await;
+        let mut chunks = match chunks {
+            Ok(chunks) => chunks,
+            Err(e) => {
+                let _ = sender.send(Err(e)).await;
+                return;
+            },
+        };
+        while let Some(chunk) = chunks.next().await {
+            // 接收方已丢弃说明客户端断开
+            if sender.send(chunk).await.is_err() {
+                break;
+            }
+        }
+    }));
+
+    let chunks = ReceiverStream::new(receiver).boxed();
+    let payloads = if structured {
+        partial_json_stream::<Value>(chunks)
+            .map(|output| output.map(|output| json!({
+                "delta": output.delta,
+                "partial": output.value,
+                "complete": output.complete,
+            })))
+            .boxed()
+    } else {
+        chunks.map(|chunk| chunk.map(|text| json!({ "delta": text }))).boxed()
+    };
+
+    // 响应体随连接关闭被丢弃，守卫随之取消令牌
+    let guard = token.drop_guard();
+    let events = payloads.map(move |payload: Result<Value>| {
+        let _ = &guard;
+        // 以JSON编码输出块，块中的换行不会破坏SSE格式
+        let event = match payload {
+            Ok(payload) => Event::default().json_data(payload),
+            Err(e) => Event::default().event("error").json_data(e.to_response()),
+        };
+        Ok::<_, Infallible>(event.unwrap_or_else(|_| Event::default().event("error").data("Failed to encode event")))
+    });
+    Sse::new(events).keep_alive(KeepAlive::default()).into_response()
+}
+
+async fn run_workflow(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    Json(input): Json<Value>,
+) -> Response {
+    let Some(workflow) = state.workflows.get(&name) else {
+        return error_response(&Error::NotFound(format!("Workflow '{}' not found", name)));
+    };
+
+    match workflow.execute(input, &RuntimeContext::default()).await {
+        Ok(output) => Json(json!({ "output": output })).into_response(),
+        Err(e) => error_response(&e),
+    }
+}
+
+/// 反馈请求，至少包含`thumbs`、`rating`或`correction`之一
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct FeedbackRequest {
+    thumbs: Option<Thumbs>,
+    rating: Option<u8>,
+    correction: Option<String>,
+    comment: Option<String>,
+}
+
+/// 未配置会话存储时会话接口不可用
+fn session_manager(state: &AppState) -> Result<&Arc<SessionManager>> {
+    state.sessions.as_ref()
+        .ok_or_else(|| Error::NotFound("Session storage is not configured".to_string()))
+}
+
+async fn submit_feedback(
+    State(state): State<Arc<AppState>>,
+    Path((session_id, message_index)): Path<(String, usize)>,
+    Json(request): Json<FeedbackRequest>,
+) -> Response {
+    let sessions = match session_manager(&state) {
+        Ok(sessions) => sessions,
+        Err(e) => return error_response(&e),
+    };
+
+    let mut feedback = Feedback::new(session_id, message_index);
+    feedback.user_id = RequestContext::current().and_then(|context| context.user_id);
+    feedback.thumbs = request.thumbs;
+    feedback.rating = request.rating;
+    feedback.correction = request.correction;
+    feedback.comment = request.comment;
+
+    match sessions.add_feedback(feedback).await {
+        Ok(feedback) => (StatusCode::CREATED, Json(feedback)).into_response(),
+        Err(e) => error_response(&e),
+    }
+}
+
+async fn list_feedback(
+    State(state): State<Arc<AppState>>,
+    Path(session_id): Path<String>,
+) -> Response {
+    let sessions = match session_manager(&state) {
+        Ok(sessions) => sessions,
+        Err(e) => return error_response(&e),
+    };
+
+    match sessions.list_feedback(&FeedbackQuery::for_session(session_id)).await {
+        Ok(feedback) => Json(json!({ "feedback": feedback })).into_response(),
+        Err(e) => error_response(&e),
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ReviewListQuery {
+    status: Option<ReviewStatus>,
+}
+
+/// 未配置审核队列时审核接口不可用
+fn review_queue(state: &AppState) -> Result<&Arc<ReviewQueue>> {
+    state.reviews.as_ref()
+        .ok_or_else(|| Error::NotFound("Review queue is not configured".to_string()))
+}
+
+async fn list_reviews(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ReviewListQuery>,
+) -> Response {
+    match review_queue(&state) {
+        Ok(queue) => Json(json!({
+            "reviews": queue.list(query.status),
+            "pending": queue.pending_count(),
+        })).into_response(),
+        Err(e) => error_response(&e),
+    }
+}
+
+async fn submit_review(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(decision): Json<ReviewDecision>,
+) -> Response {
+    let reviewer = RequestContext::current().and_then(|context| context.user_id);
+    match review_queue(&state).and_then(|queue| queue.review(&id, decision, reviewer)) {
+        Ok(item) => Json(item).into_response(),
+        Err(e) => error_response(&e),
+    }
+}
+
+/// 未配置附件服务时附件接口不可用
+fn attachment_service(state: &AppState) -> Result<&Arc<AttachmentService>> {
+    state.attachments.as_ref()
+        .ok_or_else(|| Error::NotFound("Attachment storage is not configured".to_string()))
+}
+
+/// 上传的一个文件
+struct UploadedFile {
+    filename: String,
+    content_type: Option<String>,
+    data: Vec<u8>,
+}
+
+/// 读取上传请求中的文件和`ingest`字段
+async fn read_upload(mut multipart: Multipart) -> Result<(Vec<UploadedFile>, bool)> {
+    let invalid = |e: axum::extract::multipart::MultipartError| Error::InvalidInput(format!("Invalid upload: {}", e.body_text()));
+    let mut files = Vec::new();
+    let mut ingest = false;
+    while let Some(field) = multipart.next_field().await.map_err(invalid)? {
+        if field.name() == Some("ingest") {
+            let value = field.text().await.map_err(invalid)?;
+            ingest = matches!(value.trim(), "true" | "1" | "yes" | "on");
+            continue;
+        }
+        // 没有文件名的其他字段忽略
+        let Some(filename) = field.file_name().map(str::to_string) else {
+            continue;
+        };
+        let content_type = field.content_type().map(str::to_string);
+        let data = field.bytes().await.map_err(invalid)?.to_vec();
+        files.push(UploadedFile { filename, content_type, data });
+    }
+    if files.is_empty() {
+        return Err(Error::InvalidInput("Upload must contain at least one file".to_string()));
+    }
+    Ok((files, ingest))
+}
+
+async fn upload_attachments(State(state): State<Arc<AppState>>, multipart: Multipart) -> Response {
+    let service = match attachment_service(&state) {
+        Ok(service) => service,
+        Err(e) => return error_response(&e),
+    };
+    let (files, ingest) = match read_upload(multipart).await {
+        Ok(upload) => upload,
+        Err(e) => return error_response(&e),
+    };
+
+    let mut attachments = Vec::with_capacity(files.len());
+    for file in files {
+        match service.upload(&file.filename, file.content_type.as_deref(), file.data, ingest).await {
+            Ok(attachment) => attachments.push(attachment),
+            Err(e) => return error_response(&e),
+        }
+    }
+    (StatusCode::CREATED, Json(json!({ "attachments": attachments }))).into_response()
+}
+
+async fn get_attachment(State(state): State<Arc<AppState>>, Path(id): Path<String>) -> Response {
+    let service = match attachment_service(&state) {
+        Ok(service) => service,
+        Err(e) => return error_response(&e),
+    };
+    match service.get(&id).await {
+        Ok(attachment) => Json(attachment).into_response(),
+        Err(e) => error_response(&e),
+    }
+}
+
+async fn download_attachment(State(state): State<Arc<AppState>>, Path(id): Path<String>) -> Response {
+    let service = match attachment_service(&state) {
+        Ok(service) => service,
+        Err(e) => return error_response(&e),
+    };
+    match service.content(&id).await {
+        Ok((attachment, data)) => {
+            // 存储键的最后一段是清理过的文件名
+            let filename = attachment.key.rsplit('/').next().unwrap_or("file");
+            let disposition = format!("attachment; filename=\"{}\"", filename);
+            ([(header::CONTENT_TYPE, attachment.content_type), (header::CONTENT_DISPOSITION, disposition)], data).into_response()
+        },
+        Err(e) => error_response(&e),
+    }
+}
+
+async fn delete_attachment(State(state): State<Arc<AppState>>, Path(id): Path<String>) -> Response {
+    let service = match attachment_service(&state) {
+        Ok(service) => service,
+        Err(e) => return error_response(&e),
+    };
+    match service.delete(&id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => error_response(&e),
+    }
+}
+
+/// JSON-RPC请求；通知没有`id`
+#[derive(Debug, Deserialize)]
+struct JsonRpcRequest {
+    #[serde(default)]
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+/// JSON-RPC错误码和消息
+type JsonRpcError = (i64, String);
+
+async fn mcp(State(state): State<Arc<AppState>>, Json(request): Json<JsonRpcRequest>) -> Response {
+    // 通知（如`notifications/initialized`）不需要响应
+    let Some(id) = request.id else {
+        return StatusCode::ACCEPTED.into_response();
+    };
+
+    let result = match request.method.as_str() {
+        "initialize" => Ok(json!({
+            "protocolVersion": MCP_PROTOCOL_VERSION,
+            "capabilities": { "tools": {} },
+            "serverInfo": { "name": state.name, "version": env!("CARGO_PKG_VERSION") },
+        })),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(list_tools(&state)),
+        "tools/call" => call_tool(&state, &request.params).await,
+        method => Err((-32601, format!("Method not found: {}", method))),
+    };
+
+    let response = match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } }),
+    };
+    Json(response).into_response()
+}
+
+fn list_tools(state: &AppState) -> Value {
+    let tools: Vec<Value> = sorted_names(&state.tools).into_iter()
+        .map(|name| {
+            let definition = FunctionDefinition::from_tool(state.tools[name].as_ref());
+            json!({
+                "name": name,
+                "description": definition.description,
+                "inputSchema": definition.parameters,
+            })
+        })
+        .collect();
+    json!({ "tools": tools })
+}
+
+/// 执行工具；工具本身的错误以`isError`结果返回，而不是JSON-RPC错误
+async fn call_tool(state: &AppState, params: &Value) -> std::result::Result<Value, JsonRpcError> {
+    let name = params.get("name").and_then(Value::as_str)
+        .ok_or_else(|| (-32602, "Missing tool name".to_string()))?;
+    let tool = state.tools.get(name)
+        .ok_or_else(|| (-32602, format!("Unknown tool: {}", name)))?;
+    let arguments = params.get("arguments").cloned().unwrap_or_else(|| json!({}));
+
+    let (text, is_error) = match tool.execute(arguments, ToolExecutionContext::new(), &ToolExecutionOptions::default()).await {
+        Ok(Value::String(text)) => (text, false),
+        Ok(output) => (output.to_string(), false),
+        Err(e) => (e.to_string(), true),
+    };
+    Ok(json!({
+        "content": [{ "type": "text", "text": text }],
+        "isError": is_error,
+    }))
+}
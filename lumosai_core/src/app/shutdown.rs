@@ -0,0 +1,296 @@
+//! 代理运行时的优雅关闭
+//!
+//! [`ShutdownCoordinator`]在关闭开始后拒绝新的请求，在截止时间内等待进行中的生成和工具调用完成，
+//! 然后依次执行注册的刷新钩子（遥测导出器、记忆存储等）。服务模式和CLI模式通过
+//! [`shutdown_signal`]响应Ctrl+C和SIGTERM。
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+
+use crate::error::{Error, Result};
+use crate::telemetry::otel::OtelExporter;
+
+/// 刷新钩子返回的Future
+type ShutdownFuture = Pin<Box<dyn Future<Output = Result<()>> + Send>>;
+
+/// 关闭时执行的刷新钩子，参数为允许的超时时间
+type ShutdownHook = Box<dyn Fn(Duration) -> ShutdownFuture + Send + Sync>;
+
+/// 运行时状态：是否接受新请求以及进行中的请求数
+#[derive(Debug, Clone, Copy)]
+struct RuntimeState {
+    accepting: bool,
+    in_flight: usize,
+}
+
+/// 一次关闭的结果
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShutdownReport {
+    /// 进行中的请求是否都在截止时间前完成
+    pub drained: bool,
+    /// 截止时间到达时仍未完成的请求数
+    pub abandoned: usize,
+    /// 成功刷新的钩子
+    pub flushed: Vec<String>,
+    /// 刷新失败或超时的钩子及原因
+    pub failed: Vec<(String, String)>,
+}
+
+/// 协调运行时关闭：拒绝新请求、等待进行中的请求、刷新存储
+///
+/// 克隆的实例共享同一状态。
+#[derive(Clone)]
+pub struct ShutdownCoordinator {
+    state: Arc<watch::Sender<RuntimeState>>,
+    hooks: Arc<Mutex<Vec<(String, ShutdownHook)>>>,
+    drain_timeout: Duration,
+    flush_timeout: Duration,
+}
+
+/// 进行中的请求，释放时计数减一
+#[must_use = "the request counts as in flight only while the guard is held"]
+pub struct InFlightGuard {
+    state: Arc<watch::Sender<RuntimeState>>,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.state.send_modify(|state| state.in_flight -= 1);
+    }
+}
+
+impl ShutdownCoordinator {
+    /// 创建协调器，默认最多等待30秒，每个刷新钩子最多5秒
+    pub fn new() -> Self {
+        let (state, _) = watch::channel(RuntimeState {
+            accepting: true,
+            in_flight: 0,
+        });
+        Self {
+            state: Arc::new(state),
+            hooks: Arc::new(Mutex::new(Vec::new())),
+            drain_timeout: Duration::from_secs(30),
+            flush_timeout: Duration::from_secs(5),
+        }
+    }
+
+    /// 设置等待进行中请求的截止时间
+    pub fn with_drain_timeout(mut self, timeout: Duration) -> Self {
+        self.drain_timeout = timeout;
+        self
+    }
+
+    /// 设置每个刷新钩子的超时时间
+    pub fn with_flush_timeout(mut self, timeout: Duration) -> Self {
+        self.flush_timeout = timeout;
+        self
+    }
+
+    /// 是否仍接受新请求
+    pub fn is_accepting(&self) -> bool {
+        self.state.borrow().accepting
+    }
+
+    /// 进行中的请求数
+    pub fn in_flight(&self) -> usize {
+        self.state.borrow().in_flight
+    }
+
+    /// 开始一个请求；关闭开始后返回`Unavailable`错误
+    pub fn begin(&self) -> Result<InFlightGuard> {
+        let accepted = self.state.send_if_modified(|state| {
+            if state.accepting {
+                state.in_flight += 1;
+            }
+            state.accepting
+        });
+
+        if accepted {
+            Ok(InFlightGuard { state: self.state.clone() })
+        } else {
+            Err(Error::Unavailable("Runtime is shutting down".to_string()))
+        }
+    }
+
+    /// 把一次生成或工具调用作为进行中的请求执行
+    pub async fn track<F: Future>(&self, future: F) -> Result<F::Output> {
+        let _guard = self.begin()?;
+        Ok(future.await)
+    }
+
+    /// 注册关闭时执行的刷新钩子，按注册顺序执行
+    pub fn on_shutdown<F, Fut>(&self, name: impl Into<String>, hook: F)
+    where
+        F: Fn(Duration) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        let hook: ShutdownHook = Box::new(move |timeout| Box::pin(hook(timeout)));
+        self.hooks.lock().unwrap_or_else(|e| e.into_inner()).push((name.into(), hook));
+    }
+
+    /// 关闭时刷新并关闭遥测导出器
+    pub fn flush_telemetry(&self, name: impl Into<String>, exporter: Arc<dyn OtelExporter>) {
+        self.on_shutdown(name, move |timeout| {
+            let exporter = exporter.clone();
+            async move {
+                exporter.shutdown(timeout).await
+                    .map_err(|e| Error::Other(format!("Failed to flush telemetry: {}", e)))
+            }
+        });
+    }
+
+    /// 等待关闭开始
+    pub async fn wait_for_shutdown(&self) {
+        let mut state = self.state.subscribe();
+        // 发送端由协调器持有，不会提前关闭
+        let _ = state.wait_for(|state| !state.accepting).await;
+    }
+
+    /// 关闭运行时
+    ///
+    /// 立即停止接受新请求，在截止时间内等待进行中的请求完成，然后执行刷新钩子。
+    /// 钩子只执行一次，重复调用只等待进行中的请求。
+    pub async fn shutdown(&self) -> ShutdownReport {
+        self.state.send_modify(|state| state.accepting = false);
+
+        let mut state = self.state.subscribe();
+        let drained = tokio::time::timeout(self.drain_timeout, state.wait_for(|state| state.in_flight == 0))
+            .await
+            .is_ok();
+        let abandoned = self.in_flight();
+        if !drained {
+            tracing::warn!("Shutdown deadline reached with {} requests still in flight", abandoned);
+        }
+
+        let hooks = std::mem::take(&mut *self.hooks.lock().unwrap_or_else(|e| e.into_inner()));
+        let mut report = ShutdownReport {
+            drained,
+            abandoned,
+            ..Default::default()
+        };
+        for (name, hook) in hooks {
+            match tokio::time::timeout(self.flush_timeout, hook(self.flush_timeout)).await {
+                Ok(Ok(())) => report.flushed.push(name),
+                Ok(Err(e)) => report.failed.push((name, e.to_string())),
+                Err(_) => report.failed.push((name, "flush timed out".to_string())),
+            }
+        }
+
+        report
+    }
+
+    /// 收到Ctrl+C或SIGTERM后关闭运行时
+    pub async fn shutdown_on_signal(&self) -> ShutdownReport {
+        shutdown_signal().await;
+        tracing::info!("Shutdown signal received, draining in-flight requests");
+        self.shutdown().await
+    }
+}
+
+impl Default for ShutdownCoordinator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for ShutdownCoordinator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = *self.state.borrow();
+        f.debug_struct("ShutdownCoordinator")
+            .field("accepting", &state.accepting)
+            .field("in_flight", &state.in_flight)
+            .field("drain_timeout", &self.drain_timeout)
+            .field("flush_timeout", &self.flush_timeout)
+            .finish_non_exhaustive()
+    }
+}
+
+/// 等待Ctrl+C，Unix平台上也等待SIGTERM
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!("Failed to listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            },
+            Err(e) => {
+                tracing::error!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            },
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_shutdown_waits_for_in_flight_requests_and_flushes() {
+        let coordinator = ShutdownCoordinator::new().with_drain_timeout(Duration::from_secs(1));
+        let flushed = Arc::new(Mutex::new(false));
+        let flag = flushed.clone();
+        coordinator.on_shutdown("memory", move |_| {
+            let flag = flag.clone();
+            async move {
+                *flag.lock().unwrap() = true;
+                Ok(())
+            }
+        });
+        coordinator.on_shutdown("telemetry", |_| async { Err(Error::Other("exporter offline".to_string())) });
+
+        let guard = coordinator.begin().unwrap();
+        let request = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            drop(guard);
+        });
+
+        let report = coordinator.shutdown().await;
+        request.await.unwrap();
+        assert!(report.drained);
+        assert_eq!(report.abandoned, 0);
+        assert_eq!(report.flushed, vec!["memory".to_string()]);
+        assert_eq!(report.failed, vec![("telemetry".to_string(), "exporter offline".to_string())]);
+        assert!(*flushed.lock().unwrap());
+
+        // 关闭开始后拒绝新请求
+        assert!(!coordinator.is_accepting());
+        assert!(matches!(coordinator.begin(), Err(Error::Unavailable(_))));
+        assert!(coordinator.track(async {}).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_deadline_abandons_stuck_requests() {
+        let coordinator = ShutdownCoordinator::new().with_drain_timeout(Duration::from_millis(20));
+        let _stuck = coordinator.begin().unwrap();
+
+        let waiter = coordinator.clone();
+        let started = tokio::spawn(async move { waiter.wait_for_shutdown().await });
+
+        let report = coordinator.shutdown().await;
+        started.await.unwrap();
+        assert!(!report.drained);
+        assert_eq!(report.abandoned, 1);
+        assert!(report.flushed.is_empty());
+    }
+}
@@ -0,0 +1,377 @@
+//! 消息附件
+//!
+//! 上传的文件保存到[`ObjectStore`]（本地目录、S3、GCS或Azure Blob），以[`Attachment`]的形式
+//! 记录在消息的`attachments`元数据中。
+//!
+//! [`AttachmentService`]负责上传、下载和删除附件，并为引用附件的消息生成上下文：
+//! 文本类附件的内容直接提供给代理，内容过长时截断；上传时选择写入检索管道的附件，
+//! 过长部分改为按用户问题检索相关片段。
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Mutex, RwLock};
+
+use crate::error::{Error, Result};
+use crate::rag::{DocumentSource, RagPipeline};
+use crate::storage::ObjectStore;
+
+/// 消息元数据中保存附件的键
+pub const ATTACHMENTS_METADATA_KEY: &str = "attachments";
+
+/// 默认的单个附件大小上限（20 MiB）
+pub const DEFAULT_MAX_ATTACHMENT_SIZE: usize = 20 * 1024 * 1024;
+
+/// 默认直接提供给代理的附件内容字符数上限
+pub const DEFAULT_MAX_INLINE_CHARS: usize = 8000;
+
+/// 每次检索返回的片段数
+const RETRIEVAL_TOP_K: usize = 4;
+
+/// 存储中附件元数据的文件名
+const METADATA_FILE: &str = "attachment.json";
+
+/// 按扩展名视为文本的文件
+const TEXT_EXTENSIONS: &[&str] = &[
+    "txt", "md", "markdown", "rst", "csv", "tsv", "json", "jsonl", "yaml", "yml", "toml", "ini",
+    "xml", "html", "htm", "log", "rs", "py", "js", "ts", "java", "go", "c", "h", "cpp", "sql", "sh",
+];
+
+/// 已上传的附件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Attachment {
+    /// 附件ID
+    pub id: String,
+    /// 原始文件名
+    pub filename: String,
+    /// MIME类型
+    pub content_type: String,
+    /// 文件大小（字节）
+    pub size: usize,
+    /// 在对象存储中的键
+    pub key: String,
+    /// 能否提取出文本内容
+    pub has_text: bool,
+    /// 是否已写入检索管道
+    pub ingested: bool,
+    /// 上传时间
+    pub uploaded_at: DateTime<Utc>,
+}
+
+/// 清理文件名中的路径和特殊字符，用作存储键
+fn sanitize_filename(filename: &str) -> String {
+    let name = filename.rsplit(['/', '\\']).next().unwrap_or_default();
+    let cleaned: String = name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') { c } else { '_' })
+        .collect();
+    let cleaned = cleaned.trim_start_matches('.');
+    if cleaned.is_empty() { "file".to_string() } else { cleaned.to_string() }
+}
+
+fn extension(filename: &str) -> Option<String> {
+    Path::new(filename).extension().and_then(|ext| ext.to_str()).map(str::to_ascii_lowercase)
+}
+
+/// 确定MIME类型：请求未提供或为`application/octet-stream`时按扩展名推断
+fn resolve_content_type(filename: &str, content_type: Option<&str>) -> String {
+    if let Some(content_type) = content_type.map(str::trim).filter(|value| !value.is_empty() && *value != "application/octet-stream") {
+        return content_type.to_string();
+    }
+    let content_type = match extension(filename).as_deref() {
+        Some("txt" | "log") => "text/plain",
+        Some("md" | "markdown") => "text/markdown",
+        Some("csv") => "text/csv",
+        Some("html" | "htm") => "text/html",
+        Some("json") => "application/json",
+        Some("xml") => "application/xml",
+        Some("yaml" | "yml") => "application/yaml",
+        Some("pdf") => "application/pdf",
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        _ => "application/octet-stream",
+    };
+    content_type.to_string()
+}
+
+/// 提取文本内容，非文本或非UTF-8的文件返回`None`
+fn extract_text(filename: &str, content_type: &str, data: &[u8]) -> Option<String> {
+    let mime = content_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+    let textual = mime.starts_with("text/")
+        || matches!(mime.as_str(), "application/json" | "application/xml" | "application/yaml" | "application/x-yaml" | "application/javascript")
+        || extension(filename).is_some_and(|ext| TEXT_EXTENSIONS.contains(&ext.as_str()));
+    if !textual {
+        return None;
+    }
+    let text = std::str::from_utf8(data).ok()?;
+    Some(text.trim_start_matches('\u{feff}').to_string())
+}
+
+/// 附件服务：上传、读取附件，并为引用附件的消息生成上下文
+pub struct AttachmentService {
+    store: Arc<dyn ObjectStore>,
+    /// 已知附件的元数据
+    attachments: RwLock<HashMap<String, Attachment>>,
+    /// 已提取的文本内容
+    texts: RwLock<HashMap<String, String>>,
+    /// 写入附件内容的检索管道
+    pipeline: Option<Mutex<Box<dyn RagPipeline>>>,
+    max_size: usize,
+    max_inline_chars: usize,
+}
+
+impl AttachmentService {
+    /// 创建使用指定对象存储的附件服务，多个用途共用存储时可用[`crate::storage::PrefixedObjectStore`]隔离
+    pub fn new(store: Arc<dyn ObjectStore>) -> Self {
+        Self {
+            store,
+            attachments: RwLock::new(HashMap::new()),
+            texts: RwLock::new(HashMap::new()),
+            pipeline: None,
+            max_size: DEFAULT_MAX_ATTACHMENT_SIZE,
+            max_inline_chars: DEFAULT_MAX_INLINE_CHARS,
+        }
+    }
+
+    /// 设置单个附件的大小上限
+    pub fn with_max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// 设置直接提供给代理的附件内容字符数上限
+    pub fn with_max_inline_chars(mut self, max_inline_chars: usize) -> Self {
+        self.max_inline_chars = max_inline_chars;
+        self
+    }
+
+    /// 设置检索管道，上传时选择写入的附件内容会被分块索引
+    pub fn with_ingest_pipeline(mut self, pipeline: impl RagPipeline + 'static) -> Self {
+        self.pipeline = Some(Mutex::new(Box::new(pipeline)));
+        self
+    }
+
+    /// 单个附件的大小上限
+    pub fn max_size(&self) -> usize {
+        self.max_size
+    }
+
+    /// 是否配置了检索管道，即上传时能否写入附件内容
+    pub fn can_ingest(&self) -> bool {
+        self.pipeline.is_some()
+    }
+
+    /// 上传附件；`ingest`为真时将文本内容写入检索管道
+    pub async fn upload(&self, filename: &str, content_type: Option<&str>, data: Vec<u8>, ingest: bool) -> Result<Attachment> {
+        if data.len() > self.max_size {
+            return Err(Error::InvalidInput(format!(
+                "Attachment '{}' is {} bytes, larger than the limit of {} bytes", filename, data.len(), self.max_size,
+            )));
+        }
+        if ingest && self.pipeline.is_none() {
+            return Err(Error::InvalidInput("No retrieval pipeline is configured for attachments".to_string()));
+        }
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let content_type = resolve_content_type(filename, content_type);
+        let text = extract_text(filename, &content_type, &data);
+        let mut attachment = Attachment {
+            key: format!("{}/{}", id, sanitize_filename(filename)),
+            id,
+            filename: filename.to_string(),
+            content_type,
+            size: data.len(),
+            has_text: text.is_some(),
+            ingested: false,
+            uploaded_at: Utc::now(),
+        };
+
+        self.store.put(&attachment.key, &attachment.content_type, data).await?;
+        if let (true, Some(pipeline), Some(text)) = (ingest, &self.pipeline, &text) {
+            pipeline.lock().await.process_documents(DocumentSource::Text(text.clone())).await?;
+            attachment.ingested = true;
+        }
+        self.store.put(&metadata_key(&attachment.id), "application/json", serde_json::to_vec(&attachment)?).await?;
+
+        if let Some(text) = text {
+            self.texts.write().await.insert(attachment.id.clone(), text);
+        }
+        self.attachments.write().await.insert(attachment.id.clone(), attachment.clone());
+        Ok(attachment)
+    }
+
+    /// 获取附件元数据，服务重启后从存储中读取
+    pub async fn get(&self, id: &str) -> Result<Attachment> {
+        if let Some(attachment) = self.attachments.read().await.get(id) {
+            return Ok(attachment.clone());
+        }
+
+        let metadata = self.store.get(&metadata_key(id)).await
+            .map_err(|e| match e {
+                Error::NotFound(_) | Error::InvalidInput(_) => Error::NotFound(format!("Attachment '{}' not found", id)),
+                e => e,
+            })?;
+        let attachment: Attachment = serde_json::from_slice(&metadata)?;
+        self.attachments.write().await.insert(id.to_string(), attachment.clone());
+        Ok(attachment)
+    }
+
+    /// 按ID获取多个附件
+    pub async fn resolve(&self, ids: &[String]) -> Result<Vec<Attachment>> {
+        let mut attachments = Vec::with_capacity(ids.len());
+        for id in ids {
+            attachments.push(self.get(id).await?);
+        }
+        Ok(attachments)
+    }
+
+    /// 读取附件的文件内容
+    pub async fn content(&self, id: &str) -> Result<(Attachment, Vec<u8>)> {
+        let attachment = self.get(id).await?;
+        let data = self.store.get(&attachment.key).await?;
+        Ok((attachment, data))
+    }
+
+    /// 删除附件；已写入检索管道的内容不会被移除
+    pub async fn delete(&self, id: &str) -> Result<()> {
+        let attachment = self.get(id).await?;
+        self.store.delete(&attachment.key).await?;
+        self.store.delete(&metadata_key(id)).await?;
+        self.attachments.write().await.remove(id);
+        self.texts.write().await.remove(id);
+        Ok(())
+    }
+
+    /// 附件的文本内容
+    async fn text(&self, attachment: &Attachment) -> Result<Option<String>> {
+        if !attachment.has_text {
+            return Ok(None);
+        }
+        if let Some(text) = self.texts.read().await.get(&attachment.id) {
+            return Ok(Some(text.clone()));
+        }
+
+        let data = self.store.get(&attachment.key).await?;
+        let text = extract_text(&attachment.filename, &attachment.content_type, &data);
+        if let Some(text) = &text {
+            self.texts.write().await.insert(attachment.id.clone(), text.clone());
+        }
+        Ok(text)
+    }
+
+    /// 为引用附件的消息生成上下文，`query`为用户的问题
+    ///
+    /// 附件内容按顺序直接提供，总长度超过上限后，已写入检索管道的附件改为检索与问题相关的片段，
+    /// 其余附件截断。
+    pub async fn context(&self, attachments: &[Attachment], query: &str) -> Result<String> {
+        let mut context = String::from("The user attached the following files:\n");
+        let mut remaining = self.max_inline_chars;
+        let mut retrieve = false;
+
+        for (index, attachment) in attachments.iter().enumerate() {
+            context.push_str(&format!(
+                "\n[Attachment {}] {} ({}, {} bytes)\n",
+                index + 1, attachment.filename, attachment.content_type, attachment.size,
+            ));
+            let Some(text) = self.text(attachment).await? else {
+                context.push_str("(binary file, its content is not available as text)\n");
+                continue;
+            };
+
+            let length = text.chars().count();
+            if length <= remaining {
+                remaining -= length;
+                context.push_str(&text);
+                context.push('\n');
+            } else if attachment.ingested {
+                retrieve = true;
+                context.push_str("(too long to include in full; relevant excerpts are listed below)\n");
+            } else {
+                context.extend(text.chars().take(remaining));
+                context.push_str(&format!("\n(truncated, showing {} of {} characters)\n", remaining, length));
+                remaining = 0;
+            }
+        }
+
+        if let (true, Some(pipeline)) = (retrieve, &self.pipeline) {
+            let result = pipeline.lock().await.query(query, RETRIEVAL_TOP_K).await?;
+            context.push_str("\nRelevant excerpts from the attached files:\n");
+            for document in result.documents {
+                context.push_str(&format!("- {}\n", document.content.trim()));
+            }
+        }
+        Ok(context)
+    }
+}
+
+fn metadata_key(id: &str) -> String {
+    format!("{}/{}", id, METADATA_FILE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::LocalObjectStore;
+
+    fn service(root: &Path) -> AttachmentService {
+        AttachmentService::new(Arc::new(LocalObjectStore::new(root))).with_max_inline_chars(40)
+    }
+
+    #[tokio::test]
+    async fn test_upload_and_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let attachments = service(dir.path());
+
+        let notes = attachments.upload("../notes.md", None, b"Meeting moved to Friday".to_vec(), false).await.unwrap();
+        assert_eq!(notes.content_type, "text/markdown");
+        assert!(notes.has_text);
+        assert!(notes.key.ends_with("/notes.md"));
+        assert!(dir.path().join(&notes.key).exists());
+
+        // 新的服务实例从存储中读取元数据
+        let reloaded = service(dir.path());
+        assert_eq!(reloaded.get(&notes.id).await.unwrap(), notes);
+        let (_, data) = reloaded.content(&notes.id).await.unwrap();
+        assert_eq!(data, b"Meeting moved to Friday");
+
+        reloaded.delete(&notes.id).await.unwrap();
+        assert!(matches!(reloaded.get(&notes.id).await, Err(Error::NotFound(_))));
+        assert!(matches!(attachments.get("../../etc").await, Err(Error::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_upload_limits() {
+        let dir = tempfile::tempdir().unwrap();
+        let attachments = service(dir.path()).with_max_size(4);
+
+        assert!(matches!(attachments.upload("big.txt", None, vec![b'a'; 5], false).await, Err(Error::InvalidInput(_))));
+        // 没有检索管道时不能写入
+        assert!(matches!(attachments.upload("a.txt", None, b"a".to_vec(), true).await, Err(Error::InvalidInput(_))));
+    }
+
+    #[tokio::test]
+    async fn test_context() {
+        let dir = tempfile::tempdir().unwrap();
+        let attachments = service(dir.path());
+
+        let short = attachments.upload("short.txt", Some("text/plain"), b"The code is 42".to_vec(), false).await.unwrap();
+        let image = attachments.upload("photo.png", None, vec![0x89, b'P', b'N', b'G'], false).await.unwrap();
+        let long = attachments.upload("long.txt", None, "x".repeat(100).into_bytes(), false).await.unwrap();
+        assert!(!image.has_text);
+
+        let context = attachments.context(&[short, image, long], "What is the code?").await.unwrap();
+        assert!(context.contains("[Attachment 1] short.txt (text/plain, 14 bytes)\nThe code is 42\n"));
+        assert!(context.contains("[Attachment 2] photo.png (image/png, 4 bytes)\n(binary file"));
+        assert!(context.contains(&format!("{}\n(truncated, showing 26 of 100 characters)", "x".repeat(26))));
+    }
+
+    #[test]
+    fn test_sanitize_filename() {
+        assert_eq!(sanitize_filename("C:\\docs\\report 2024.pdf"), "report_2024.pdf");
+        assert_eq!(sanitize_filename("../../.env"), "env");
+        assert_eq!(sanitize_filename(""), "file");
+    }
+}
@@ -0,0 +1,645 @@
+//! 计量和按量开票
+//!
+//! 按租户和API密钥记录计量事件（Token、工具调用、存储字节数），按时间窗口聚合，
+//! 根据计量套餐计算费用，并导出与Stripe发票明细项兼容的数据
+
+use super::*;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 计量指标
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum MeterKind {
+    /// 输入Token
+    InputTokens,
+    /// 输出Token
+    OutputTokens,
+    /// 工具调用次数
+    ToolCalls,
+    /// 存储字节数
+    StorageBytes,
+    /// 自定义指标
+    Custom(String),
+}
+
+impl MeterKind {
+    /// 指标名称，同时用作使用量记录的资源类型
+    pub fn as_str(&self) -> &str {
+        match self {
+            MeterKind::InputTokens => "input_tokens",
+            MeterKind::OutputTokens => "output_tokens",
+            MeterKind::ToolCalls => "tool_calls",
+            MeterKind::StorageBytes => "storage_bytes",
+            MeterKind::Custom(name) => name,
+        }
+    }
+
+    /// 计量单位
+    pub fn unit(&self) -> &str {
+        match self {
+            MeterKind::InputTokens | MeterKind::OutputTokens => "tokens",
+            MeterKind::ToolCalls => "calls",
+            MeterKind::StorageBytes => "bytes",
+            MeterKind::Custom(_) => "units",
+        }
+    }
+}
+
+/// 计量事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeteringEvent {
+    /// 事件ID
+    pub id: String,
+    /// 租户ID
+    pub tenant_id: Uuid,
+    /// API密钥ID
+    pub api_key_id: Option<String>,
+    /// 计量指标
+    pub meter: MeterKind,
+    /// 数量
+    pub quantity: u64,
+    /// 发生时间
+    pub timestamp: SystemTime,
+    /// 幂等键，重复上报的事件只计一次
+    pub idempotency_key: Option<String>,
+    /// 附加属性，如模型名、工具名
+    pub properties: HashMap<String, String>,
+}
+
+impl MeteringEvent {
+    /// 创建新的计量事件
+    pub fn new(tenant_id: Uuid, meter: MeterKind, quantity: u64) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            tenant_id,
+            api_key_id: None,
+            meter,
+            quantity,
+            timestamp: SystemTime::now(),
+            idempotency_key: None,
+            properties: HashMap::new(),
+        }
+    }
+
+    /// 设置API密钥
+    pub fn with_api_key(mut self, api_key_id: impl Into<String>) -> Self {
+        self.api_key_id = Some(api_key_id.into());
+        self
+    }
+
+    /// 设置发生时间
+    pub fn at(mut self, timestamp: SystemTime) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    /// 设置幂等键
+    pub fn with_idempotency_key(mut self, key: impl Into<String>) -> Self {
+        self.idempotency_key = Some(key.into());
+        self
+    }
+
+    /// 添加属性
+    pub fn with_property(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.properties.insert(key.into(), value.into());
+        self
+    }
+
+    /// 转换为使用量记录，便于接入 [`UsageTracker`]
+    pub fn to_usage_record(&self) -> UsageRecord {
+        let mut record = UsageRecord::new(
+            self.tenant_id,
+            self.meter.as_str().to_string(),
+            self.quantity,
+            self.meter.unit().to_string(),
+        );
+        record.timestamp = self.timestamp;
+        record.metadata = self.properties.clone();
+        if let Some(api_key_id) = &self.api_key_id {
+            record.metadata.insert("api_key_id".to_string(), api_key_id.clone());
+        }
+        record
+    }
+}
+
+/// 聚合窗口
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AggregationWindow {
+    /// 按小时
+    Hourly,
+    /// 按天 (UTC)
+    Daily,
+    /// 整个统计周期
+    Period,
+    /// 自定义窗口长度
+    Custom { seconds: u64 },
+}
+
+impl AggregationWindow {
+    /// 计算某个时间点所在窗口的起止时间
+    pub fn bounds(&self, timestamp: SystemTime, period: (SystemTime, SystemTime)) -> (SystemTime, SystemTime) {
+        let seconds = match self {
+            AggregationWindow::Hourly => 60 * 60,
+            AggregationWindow::Daily => 24 * 60 * 60,
+            AggregationWindow::Custom { seconds } => (*seconds).max(1),
+            AggregationWindow::Period => return period,
+        };
+        let since_epoch = timestamp.duration_since(UNIX_EPOCH).unwrap_or(Duration::ZERO).as_secs();
+        let start = UNIX_EPOCH + Duration::from_secs(since_epoch - since_epoch % seconds);
+        (start, start + Duration::from_secs(seconds))
+    }
+}
+
+/// 聚合方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AggregationMethod {
+    /// 求和，适用于Token和调用次数
+    Sum,
+    /// 取最大值，适用于存储量等瞬时值
+    Max,
+    /// 取窗口内最后一次上报的值
+    Last,
+}
+
+/// 计量聚合结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeterAggregate {
+    /// 租户ID
+    pub tenant_id: Uuid,
+    /// API密钥ID，未按密钥分组时为空
+    pub api_key_id: Option<String>,
+    /// 计量指标
+    pub meter: MeterKind,
+    /// 窗口开始时间
+    pub window_start: SystemTime,
+    /// 窗口结束时间
+    pub window_end: SystemTime,
+    /// 聚合后的数量
+    pub quantity: u64,
+    /// 事件数量
+    pub event_count: u64,
+}
+
+/// 聚合查询
+#[derive(Debug, Clone)]
+pub struct AggregationQuery {
+    /// 租户ID
+    pub tenant_id: Uuid,
+    /// 统计周期
+    pub period: (SystemTime, SystemTime),
+    /// 聚合窗口
+    pub window: AggregationWindow,
+    /// 只统计某个API密钥
+    pub api_key_id: Option<String>,
+    /// 是否按API密钥分组
+    pub group_by_api_key: bool,
+}
+
+impl AggregationQuery {
+    /// 创建按整个周期聚合的查询
+    pub fn new(tenant_id: Uuid, period: (SystemTime, SystemTime)) -> Self {
+        Self {
+            tenant_id,
+            period,
+            window: AggregationWindow::Period,
+            api_key_id: None,
+            group_by_api_key: false,
+        }
+    }
+
+    /// 设置聚合窗口
+    pub fn with_window(mut self, window: AggregationWindow) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// 只统计某个API密钥
+    pub fn for_api_key(mut self, api_key_id: impl Into<String>) -> Self {
+        self.api_key_id = Some(api_key_id.into());
+        self
+    }
+
+    /// 按API密钥分组
+    pub fn group_by_api_key(mut self) -> Self {
+        self.group_by_api_key = true;
+        self
+    }
+}
+
+/// 计量价格
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeterPrice {
+    /// 计量指标
+    pub meter: MeterKind,
+    /// 聚合方式
+    pub aggregation: AggregationMethod,
+    /// 套餐内包含的数量
+    pub included: u64,
+    /// 每个计价单位的价格
+    pub unit_amount: f64,
+    /// 计价单位包含的数量，例如每1000个Token计价一次
+    pub package_size: u64,
+    /// 对应的Stripe价格ID
+    pub stripe_price_id: Option<String>,
+}
+
+impl MeterPrice {
+    /// 创建按总量求和计价的价格
+    pub fn per_unit(meter: MeterKind, unit_amount: f64) -> Self {
+        Self {
+            meter,
+            aggregation: AggregationMethod::Sum,
+            included: 0,
+            unit_amount,
+            package_size: 1,
+            stripe_price_id: None,
+        }
+    }
+
+    /// 设置聚合方式
+    pub fn with_aggregation(mut self, aggregation: AggregationMethod) -> Self {
+        self.aggregation = aggregation;
+        self
+    }
+
+    /// 设置套餐内包含的数量
+    pub fn with_included(mut self, included: u64) -> Self {
+        self.included = included;
+        self
+    }
+
+    /// 设置计价单位包含的数量
+    pub fn with_package_size(mut self, package_size: u64) -> Self {
+        self.package_size = package_size.max(1);
+        self
+    }
+
+    /// 设置Stripe价格ID
+    pub fn with_stripe_price(mut self, price_id: impl Into<String>) -> Self {
+        self.stripe_price_id = Some(price_id.into());
+        self
+    }
+
+    /// 计算超出套餐部分的计价单位数量，不足一个单位的按一个计
+    pub fn billable_units(&self, quantity: u64) -> u64 {
+        quantity.saturating_sub(self.included).div_ceil(self.package_size.max(1))
+    }
+}
+
+/// 计量套餐
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeteredPlan {
+    /// 套餐ID
+    pub id: String,
+    /// 套餐名称
+    pub name: String,
+    /// 货币
+    pub currency: String,
+    /// 每个计费周期的基础费用
+    pub base_fee: f64,
+    /// 基础费用对应的Stripe价格ID
+    pub stripe_price_id: Option<String>,
+    /// 各计量指标的价格
+    pub prices: Vec<MeterPrice>,
+}
+
+impl MeteredPlan {
+    /// 创建新的计量套餐
+    pub fn new(id: impl Into<String>, name: impl Into<String>, currency: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            currency: currency.into(),
+            base_fee: 0.0,
+            stripe_price_id: None,
+            prices: Vec::new(),
+        }
+    }
+
+    /// 设置基础费用
+    pub fn with_base_fee(mut self, base_fee: f64) -> Self {
+        self.base_fee = base_fee;
+        self
+    }
+
+    /// 添加计量价格
+    pub fn with_price(mut self, price: MeterPrice) -> Self {
+        self.prices.retain(|p| p.meter != price.meter);
+        self.prices.push(price);
+        self
+    }
+
+    /// 查找计量指标的价格
+    pub fn price_for(&self, meter: &MeterKind) -> Option<&MeterPrice> {
+        self.prices.iter().find(|p| &p.meter == meter)
+    }
+}
+
+/// 计量服务
+#[derive(Debug, Default)]
+pub struct MeteringService {
+    /// 计量事件
+    events: Vec<MeteringEvent>,
+    /// 已处理的幂等键
+    seen_keys: HashSet<(Uuid, String)>,
+    /// 计量套餐
+    plans: HashMap<String, MeteredPlan>,
+    /// 租户使用的套餐
+    tenant_plans: HashMap<Uuid, String>,
+}
+
+impl MeteringService {
+    /// 创建新的计量服务
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 注册计量套餐
+    pub fn add_plan(&mut self, plan: MeteredPlan) {
+        self.plans.insert(plan.id.clone(), plan);
+    }
+
+    /// 获取计量套餐
+    pub fn get_plan(&self, plan_id: &str) -> Option<&MeteredPlan> {
+        self.plans.get(plan_id)
+    }
+
+    /// 为租户指定套餐
+    pub fn assign_plan(&mut self, tenant_id: Uuid, plan_id: &str) -> BillingResult<()> {
+        if !self.plans.contains_key(plan_id) {
+            return Err(BillingError::InvalidConfiguration(format!("Unknown metered plan: {}", plan_id)));
+        }
+        self.tenant_plans.insert(tenant_id, plan_id.to_string());
+        Ok(())
+    }
+
+    /// 租户当前的套餐
+    pub fn tenant_plan(&self, tenant_id: &Uuid) -> Option<&MeteredPlan> {
+        self.tenant_plans.get(tenant_id).and_then(|plan_id| self.plans.get(plan_id))
+    }
+
+    /// 记录计量事件，重复的幂等键返回 `false`
+    pub fn record(&mut self, event: MeteringEvent) -> bool {
+        if let Some(key) = &event.idempotency_key {
+            if !self.seen_keys.insert((event.tenant_id, key.clone())) {
+                return false;
+            }
+        }
+        self.events.push(event);
+        true
+    }
+
+    /// 记录计量事件并同步到使用量跟踪器，使限额检查对计量事件生效
+    pub fn record_and_track(&mut self, event: MeteringEvent, tracker: &mut UsageTracker) -> BillingResult<bool> {
+        let record = event.to_usage_record();
+        if !self.record(event) {
+            return Ok(false);
+        }
+        tracker.record_usage(record)?;
+        Ok(true)
+    }
+
+    /// 按查询条件聚合计量事件，未配置套餐的指标按求和聚合
+    pub fn aggregate(&self, query: &AggregationQuery) -> Vec<MeterAggregate> {
+        let plan = self.tenant_plan(&query.tenant_id);
+        let mut events: Vec<&MeteringEvent> = self.events.iter()
+            .filter(|e| e.tenant_id == query.tenant_id)
+            .filter(|e| e.timestamp >= query.period.0 && e.timestamp < query.period.1)
+            .filter(|e| query.api_key_id.is_none() || e.api_key_id == query.api_key_id)
+            .collect();
+        events.sort_by_key(|e| e.timestamp);
+
+        type GroupKey = (MeterKind, Option<String>, SystemTime);
+        let mut groups: BTreeMap<GroupKey, MeterAggregate> = BTreeMap::new();
+        for event in events {
+            let (window_start, window_end) = query.window.bounds(event.timestamp, query.period);
+            let api_key_id = if query.group_by_api_key { event.api_key_id.clone() } else { query.api_key_id.clone() };
+            let method = plan
+                .and_then(|p| p.price_for(&event.meter))
+                .map(|p| p.aggregation)
+                .unwrap_or(AggregationMethod::Sum);
+
+            let aggregate = groups.entry((event.meter.clone(), api_key_id.clone(), window_start))
+                .or_insert_with(|| MeterAggregate {
+                    tenant_id: query.tenant_id,
+                    api_key_id,
+                    meter: event.meter.clone(),
+                    window_start,
+                    window_end,
+                    quantity: 0,
+                    event_count: 0,
+                });
+            aggregate.quantity = match method {
+                AggregationMethod::Sum => aggregate.quantity + event.quantity,
+                AggregationMethod::Max => aggregate.quantity.max(event.quantity),
+                AggregationMethod::Last => event.quantity,
+            };
+            aggregate.event_count += 1;
+        }
+        groups.into_values().collect()
+    }
+
+    /// 根据租户套餐计算周期内的计费项目
+    pub fn rate(&self, tenant_id: &Uuid, period: (SystemTime, SystemTime)) -> BillingResult<Vec<BillingItem>> {
+        let plan = self.tenant_plan(tenant_id).ok_or_else(|| {
+            BillingError::InvalidConfiguration(format!("No metered plan assigned to tenant {}", tenant_id))
+        })?;
+
+        let mut items = Vec::new();
+        if plan.base_fee > 0.0 {
+            let mut metadata = HashMap::new();
+            metadata.insert("plan_id".to_string(), plan.id.clone());
+            if let Some(price_id) = &plan.stripe_price_id {
+                metadata.insert("stripe_price_id".to_string(), price_id.clone());
+            }
+            items.push(BillingItem {
+                id: Uuid::new_v4().to_string(),
+                name: plan.name.clone(),
+                description: format!("{} base fee", plan.name),
+                resource_type: "base_fee".to_string(),
+                quantity: 1,
+                unit_price: plan.base_fee,
+                total_amount: plan.base_fee,
+                billing_period: period,
+                currency: plan.currency.clone(),
+                metadata,
+            });
+        }
+
+        for aggregate in self.aggregate(&AggregationQuery::new(*tenant_id, period)) {
+            let Some(price) = plan.price_for(&aggregate.meter) else {
+                continue;
+            };
+            let units = price.billable_units(aggregate.quantity);
+            if units == 0 {
+                continue;
+            }
+
+            let mut metadata = HashMap::new();
+            metadata.insert("plan_id".to_string(), plan.id.clone());
+            metadata.insert("meter".to_string(), aggregate.meter.as_str().to_string());
+            metadata.insert("metered_quantity".to_string(), aggregate.quantity.to_string());
+            metadata.insert("included_quantity".to_string(), price.included.to_string());
+            metadata.insert("package_size".to_string(), price.package_size.to_string());
+            if let Some(price_id) = &price.stripe_price_id {
+                metadata.insert("stripe_price_id".to_string(), price_id.clone());
+            }
+
+            let description = if price.package_size > 1 {
+                format!(
+                    "{} {} over {} included, billed per {}",
+                    aggregate.quantity.saturating_sub(price.included),
+                    aggregate.meter.unit(),
+                    price.included,
+                    price.package_size,
+                )
+            } else {
+                format!("{} {} over {} included", units, aggregate.meter.unit(), price.included)
+            };
+            items.push(BillingItem {
+                id: Uuid::new_v4().to_string(),
+                name: aggregate.meter.as_str().to_string(),
+                description,
+                resource_type: aggregate.meter.as_str().to_string(),
+                quantity: units,
+                unit_price: price.unit_amount,
+                total_amount: units as f64 * price.unit_amount,
+                billing_period: period,
+                currency: plan.currency.clone(),
+                metadata,
+            });
+        }
+        Ok(items)
+    }
+
+    /// 根据租户套餐生成周期内的发票
+    pub fn generate_invoice(&self, tenant_id: &Uuid, period: (SystemTime, SystemTime), due_days: u32) -> BillingResult<Invoice> {
+        let items = self.rate(tenant_id, period)?;
+        let mut invoice = Invoice::new(*tenant_id, items, period, due_days);
+        if let Some(plan) = self.tenant_plan(tenant_id) {
+            invoice.currency = plan.currency.clone();
+            invoice.metadata.insert("plan_id".to_string(), plan.id.clone());
+        }
+        Ok(invoice)
+    }
+
+    /// 清理某个时间之前的计量事件
+    pub fn prune_before(&mut self, cutoff: SystemTime) {
+        self.events.retain(|e| e.timestamp >= cutoff);
+    }
+}
+
+/// Stripe发票明细的计费周期
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StripePeriod {
+    /// 开始时间 (Unix秒)
+    pub start: i64,
+    /// 结束时间 (Unix秒)
+    pub end: i64,
+}
+
+/// 与Stripe `POST /v1/invoiceitems` 参数兼容的发票明细项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StripeInvoiceLineItem {
+    /// Stripe客户ID
+    pub customer: String,
+    /// 小写的ISO货币代码
+    pub currency: String,
+    /// 描述
+    pub description: String,
+    /// 数量
+    pub quantity: u64,
+    /// 以最小货币单位表示的单价，允许小数
+    pub unit_amount_decimal: String,
+    /// Stripe价格ID，设置后Stripe使用价格中的单价
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price: Option<String>,
+    /// 计费周期
+    pub period: StripePeriod,
+    /// 元数据
+    pub metadata: HashMap<String, String>,
+}
+
+impl StripeInvoiceLineItem {
+    /// 从计费项目转换
+    pub fn from_billing_item(item: &BillingItem, customer: &str) -> Self {
+        let mut metadata = item.metadata.clone();
+        let price = metadata.remove("stripe_price_id");
+        metadata.insert("lumos_item_id".to_string(), item.id.clone());
+        metadata.insert("resource_type".to_string(), item.resource_type.clone());
+
+        Self {
+            customer: customer.to_string(),
+            currency: item.currency.to_lowercase(),
+            description: item.description.clone(),
+            quantity: item.quantity,
+            unit_amount_decimal: format_minor_units(item.unit_price * minor_unit_factor(&item.currency)),
+            price,
+            period: StripePeriod {
+                start: unix_seconds(item.billing_period.0),
+                end: unix_seconds(item.billing_period.1),
+            },
+            metadata,
+        }
+    }
+
+    /// 以最小货币单位表示的总金额
+    pub fn amount(&self) -> i64 {
+        let unit: f64 = self.unit_amount_decimal.parse().unwrap_or(0.0);
+        (unit * self.quantity as f64).round() as i64
+    }
+
+    /// 编码为Stripe API使用的表单参数
+    pub fn to_form_params(&self) -> Vec<(String, String)> {
+        let mut params = vec![
+            ("customer".to_string(), self.customer.clone()),
+            ("currency".to_string(), self.currency.clone()),
+            ("description".to_string(), self.description.clone()),
+            ("quantity".to_string(), self.quantity.to_string()),
+            ("period[start]".to_string(), self.period.start.to_string()),
+            ("period[end]".to_string(), self.period.end.to_string()),
+        ];
+        match &self.price {
+            Some(price) => params.push(("price".to_string(), price.clone())),
+            None => params.push(("unit_amount_decimal".to_string(), self.unit_amount_decimal.clone())),
+        }
+        let mut metadata: Vec<_> = self.metadata.iter().collect();
+        metadata.sort();
+        for (key, value) in metadata {
+            params.push((format!("metadata[{}]", key), value.clone()));
+        }
+        params
+    }
+}
+
+impl Invoice {
+    /// 导出为Stripe发票明细项，税费和折扣由Stripe侧计算
+    pub fn to_stripe_line_items(&self, customer: &str) -> Vec<StripeInvoiceLineItem> {
+        self.items.iter()
+            .map(|item| {
+                let mut line = StripeInvoiceLineItem::from_billing_item(item, customer);
+                line.metadata.insert("lumos_invoice_number".to_string(), self.invoice_number.clone());
+                line
+            })
+            .collect()
+    }
+}
+
+/// 货币最小单位的换算系数，零小数位货币为1
+fn minor_unit_factor(currency: &str) -> f64 {
+    const ZERO_DECIMAL: &[&str] = &["BIF", "CLP", "DJF", "GNF", "JPY", "KMF", "KRW", "MGA", "PYG", "RWF", "UGX", "VND", "VUV", "XAF", "XOF", "XPF"];
+    if ZERO_DECIMAL.contains(&currency.to_uppercase().as_str()) { 1.0 } else { 100.0 }
+}
+
+/// 格式化为最多12位小数且去掉末尾零的字符串，符合Stripe的decimal格式
+fn format_minor_units(amount: f64) -> String {
+    let formatted = format!("{:.12}", amount);
+    let trimmed = formatted.trim_end_matches('0').trim_end_matches('.');
+    if trimmed.is_empty() || trimmed == "-0" { "0".to_string() } else { trimmed.to_string() }
+}
+
+fn unix_seconds(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH).unwrap_or(Duration::ZERO).as_secs() as i64
+}
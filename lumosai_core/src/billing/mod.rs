@@ -16,12 +16,14 @@ pub mod usage_tracking;
 pub mod billing_engine;
 pub mod payment_processor;
 pub mod resource_manager;
+pub mod metering;
 
 pub use subscription::*;
 pub use usage_tracking::*;
 pub use billing_engine::*;
 pub use payment_processor::*;
 pub use resource_manager::*;
+pub use metering::*;
 
 #[cfg(test)]
 mod tests;
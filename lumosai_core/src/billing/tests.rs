@@ -254,6 +254,31 @@ mod usage_tracking_tests {
     }
 }
 
+#[cfg(test)]
+mod prompt_cache_tests {
+    use super::*;
+    use crate::llm::prompt_cache::{CachePricing, CacheUsage};
+
+    #[test]
+    fn test_prompt_cache_savings() {
+        let mut tracker = UsageTracker::new();
+        let tenant_id = Uuid::new_v4();
+        let since = SystemTime::now() - Duration::from_secs(60);
+
+        // 首次请求写入缓存，之后的请求命中缓存
+        let write = CacheUsage { input_tokens: 20, cache_creation_input_tokens: 1000, cache_read_input_tokens: 0 };
+        let hit = CacheUsage { input_tokens: 20, cache_creation_input_tokens: 0, cache_read_input_tokens: 1000 };
+        tracker.record_prompt_cache(tenant_id, "claude-3-5-sonnet", &write, &CachePricing::anthropic()).unwrap();
+        tracker.record_prompt_cache(tenant_id, "claude-3-5-sonnet", &hit, &CachePricing::anthropic()).unwrap();
+        tracker.record_prompt_cache(tenant_id, "claude-3-5-sonnet", &CacheUsage::default(), &CachePricing::anthropic()).unwrap();
+
+        assert_eq!(tracker.records_since(since).count(), 2);
+        let savings = tracker.prompt_cache_savings(&tenant_id, since);
+        assert!((savings - 650.0).abs() < 1e-6);
+        assert_eq!(tracker.prompt_cache_savings(&Uuid::new_v4(), since), 0.0);
+    }
+}
+
 #[cfg(test)]
 mod billing_engine_tests {
     use super::*;
@@ -446,3 +471,102 @@ mod payment_processor_tests {
         assert!(processor.validate_payment_method(&bank_transfer).await.unwrap());
     }
 }
+
+#[cfg(test)]
+mod metering_tests {
+    use super::*;
+
+    fn start_of_day() -> SystemTime {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        UNIX_EPOCH + Duration::from_secs(now - now % (24 * 60 * 60))
+    }
+
+    fn test_plan() -> MeteredPlan {
+        MeteredPlan::new("pro", "Pro", "USD")
+            .with_base_fee(49.0)
+            .with_price(
+                MeterPrice::per_unit(MeterKind::InputTokens, 0.002)
+                    .with_included(10_000)
+                    .with_package_size(1_000)
+                    .with_stripe_price("price_input_tokens"),
+            )
+            .with_price(MeterPrice::per_unit(MeterKind::ToolCalls, 0.01))
+            .with_price(
+                MeterPrice::per_unit(MeterKind::StorageBytes, 0.5)
+                    .with_aggregation(AggregationMethod::Max)
+                    .with_package_size(1_000_000_000),
+            )
+    }
+
+    #[test]
+    fn test_metering_aggregation_windows() {
+        let mut service = MeteringService::new();
+        service.add_plan(test_plan());
+        let tenant_id = Uuid::new_v4();
+        service.assign_plan(tenant_id, "pro").unwrap();
+
+        let day = start_of_day();
+        let hour = Duration::from_secs(60 * 60);
+        service.record(MeteringEvent::new(tenant_id, MeterKind::InputTokens, 8_000).with_api_key("key-a").at(day));
+        service.record(MeteringEvent::new(tenant_id, MeterKind::InputTokens, 7_000).with_api_key("key-b").at(day + hour));
+        service.record(MeteringEvent::new(tenant_id, MeterKind::StorageBytes, 3_000_000_000).at(day));
+        service.record(MeteringEvent::new(tenant_id, MeterKind::StorageBytes, 2_000_000_000).at(day + hour));
+        // 重复上报的事件只计一次
+        assert!(service.record(MeteringEvent::new(tenant_id, MeterKind::ToolCalls, 5).at(day).with_idempotency_key("run-1")));
+        assert!(!service.record(MeteringEvent::new(tenant_id, MeterKind::ToolCalls, 5).at(day).with_idempotency_key("run-1")));
+
+        let period = (day, day + Duration::from_secs(24 * 60 * 60));
+        let totals = service.aggregate(&AggregationQuery::new(tenant_id, period));
+        let total = |meter: MeterKind| totals.iter().find(|a| a.meter == meter).unwrap().quantity;
+        assert_eq!(total(MeterKind::InputTokens), 15_000);
+        assert_eq!(total(MeterKind::ToolCalls), 5);
+        assert_eq!(total(MeterKind::StorageBytes), 3_000_000_000);
+
+        let hourly = service.aggregate(
+            &AggregationQuery::new(tenant_id, period)
+                .with_window(AggregationWindow::Hourly)
+                .group_by_api_key(),
+        );
+        let tokens: Vec<_> = hourly.iter().filter(|a| a.meter == MeterKind::InputTokens).collect();
+        assert_eq!(tokens.len(), 2);
+        assert_eq!(tokens[0].api_key_id.as_deref(), Some("key-a"));
+        assert_eq!(tokens[0].window_end, day + hour);
+
+        let key_b = service.aggregate(&AggregationQuery::new(tenant_id, period).for_api_key("key-b"));
+        assert_eq!(key_b.len(), 1);
+        assert_eq!(key_b[0].quantity, 7_000);
+    }
+
+    #[test]
+    fn test_metered_invoice_stripe_export() {
+        let mut service = MeteringService::new();
+        service.add_plan(test_plan());
+        let tenant_id = Uuid::new_v4();
+        service.assign_plan(tenant_id, "pro").unwrap();
+        assert!(service.assign_plan(tenant_id, "missing").is_err());
+
+        let day = start_of_day();
+        service.record(MeteringEvent::new(tenant_id, MeterKind::InputTokens, 12_500).at(day));
+        service.record(MeteringEvent::new(tenant_id, MeterKind::ToolCalls, 30).at(day));
+
+        let period = (day, day + Duration::from_secs(24 * 60 * 60));
+        let invoice = service.generate_invoice(&tenant_id, period, 30).unwrap();
+        // 基础费用 + 3个千Token包 + 30次工具调用
+        assert_eq!(invoice.items.len(), 3);
+        assert!((invoice.subtotal - (49.0 + 3.0 * 0.002 + 30.0 * 0.01)).abs() < 1e-9);
+
+        let lines = invoice.to_stripe_line_items("cus_123");
+        let tokens = lines.iter().find(|l| l.metadata["resource_type"] == "input_tokens").unwrap();
+        assert_eq!(tokens.currency, "usd");
+        assert_eq!(tokens.quantity, 3);
+        assert_eq!(tokens.unit_amount_decimal, "0.2");
+        assert_eq!(tokens.price.as_deref(), Some("price_input_tokens"));
+        assert_eq!(tokens.period.start, day.duration_since(UNIX_EPOCH).unwrap().as_secs() as i64);
+
+        let base = lines.iter().find(|l| l.metadata["resource_type"] == "base_fee").unwrap();
+        assert_eq!(base.amount(), 4900);
+        let params = base.to_form_params();
+        assert!(params.contains(&("unit_amount_decimal".to_string(), "4900".to_string())));
+        assert!(params.iter().any(|(k, _)| k == "metadata[plan_id]"));
+    }
+}
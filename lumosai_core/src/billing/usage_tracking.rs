@@ -7,6 +7,12 @@ use std::collections::{HashMap, VecDeque};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
+use crate::llm::prompt_cache::{CachePricing, CacheUsage};
+
+/// 提示缓存读取的资源类型
+pub const CACHE_READ_TOKENS: &str = "cache_read_tokens";
+/// 提示缓存写入的资源类型
+pub const CACHE_WRITE_TOKENS: &str = "cache_write_tokens";
 
 /// 使用量记录
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -455,6 +461,53 @@ impl UsageTracker {
         }
     }
     
+    /// 获取指定时间之后的使用量记录，按记录时间排序
+    pub fn records_since(&self, since: SystemTime) -> impl Iterator<Item = &UsageRecord> {
+        self.usage_records.iter().filter(move |record| record.timestamp >= since)
+    }
+
+    /// 记录一次LLM调用的提示缓存使用量
+    ///
+    /// 缓存读写分别记为独立的资源类型，节省的Token数（按常规输入价格折算）
+    /// 写入 `saved_tokens` 元数据，可通过 [`Self::prompt_cache_savings`] 汇总
+    pub fn record_prompt_cache(
+        &mut self,
+        tenant_id: Uuid,
+        model: &str,
+        usage: &CacheUsage,
+        pricing: &CachePricing,
+    ) -> BillingResult<()> {
+        let saved = usage.saved_tokens(pricing);
+        let entries = [
+            (CACHE_READ_TOKENS, usage.cache_read_input_tokens),
+            (CACHE_WRITE_TOKENS, usage.cache_creation_input_tokens),
+        ];
+        let mut saved_recorded = false;
+        for (resource_type, quantity) in entries {
+            if quantity == 0 {
+                continue;
+            }
+            let mut record = UsageRecord::new(tenant_id, resource_type.to_string(), quantity, "tokens".to_string())
+                .with_metadata("model".to_string(), model.to_string());
+            // 节省量只记在一条记录上，避免汇总时重复计算
+            if !saved_recorded {
+                record = record.with_metadata("saved_tokens".to_string(), saved.to_string());
+                saved_recorded = true;
+            }
+            self.record_usage(record)?;
+        }
+        Ok(())
+    }
+
+    /// 汇总租户自指定时间以来提示缓存节省的Token数
+    pub fn prompt_cache_savings(&self, tenant_id: &Uuid, since: SystemTime) -> f64 {
+        self.records_since(since)
+            .filter(|record| &record.tenant_id == tenant_id)
+            .filter_map(|record| record.metadata.get("saved_tokens"))
+            .filter_map(|saved| saved.parse::<f64>().ok())
+            .sum()
+    }
+
     /// 清理过期记录
     pub fn cleanup_old_records(&mut self, retention_period: Duration) {
         let cutoff_time = SystemTime::now() - retention_period;
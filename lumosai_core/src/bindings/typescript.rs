@@ -102,6 +102,8 @@ impl TypeScriptBindings {
             max_tool_calls: None,
             tool_timeout: None,
             memory_config: None,
+            reflection: None,
+            few_shot: None,
         };
 
         // For now, just return the agent name as ID
@@ -205,16 +207,17 @@ impl TypeScriptBindings {
         serde_json::json!({
             "type": "LumosError",
             "message": error.to_string(),
+            "retryable": error.is_retryable(),
             "code": match error {
                 Error::Agent(_) => "AGENT_ERROR",
-                Error::Tool(_) => "TOOL_ERROR",
+                Error::Tool(_) | Error::ToolError { .. } => "TOOL_ERROR",
                 Error::Memory(_) => "MEMORY_ERROR",
                 Error::Network(_) => "NETWORK_ERROR",
                 Error::Io(_) => "IO_ERROR",
                 Error::Json(_) => "SERIALIZATION_ERROR",
                 Error::Internal(_) => "INTERNAL_ERROR",
                 Error::Other(_) => "UNKNOWN_ERROR",
-                Error::Llm(_) => "LLM_ERROR",
+                Error::Llm(_) | Error::ProviderError { .. } => "LLM_ERROR",
                 Error::Storage(_) => "STORAGE_ERROR",
                 Error::Workflow(_) => "WORKFLOW_ERROR",
                 Error::Http(_) => "HTTP_ERROR",
@@ -7,6 +7,10 @@ use async_trait::async_trait;
 use serde::{Serialize, Deserialize};
 use crate::error::{Error, Result};
 
+pub mod model;
+
+pub use model::{CachedModel, ModelArtifact, ModelCache, ModelSource};
+
 /// 缓存条目
 #[derive(Debug, Clone)]
 pub struct CacheEntry<T> {
@@ -0,0 +1,557 @@
+//! 模型文件缓存
+//!
+//! [`ModelCache`]下载并缓存本地推理使用的模型文件，如FastEmbed的ONNX模型、Whisper权重和
+//! Candle加载的GGUF文件。下载时计算SHA-256并与[`ModelArtifact`]声明的值比对，不一致的文件
+//! 不会进入缓存。缓存总大小超过上限时按最近使用时间淘汰。离线模式下不访问网络，
+//! 缓存中没有的模型立即返回错误。
+//!
+//! 缓存目录结构为`<dir>/files/<名称>`，`<dir>/index.json`记录每个文件的哈希、大小和使用时间。
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use ring::digest;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::Mutex;
+
+use crate::config::http_client_for;
+use crate::error::{Error, Result};
+use crate::storage::object::{check_key, write_file};
+use crate::storage::ObjectStore;
+
+/// 缓存目录的环境变量
+pub const MODEL_CACHE_DIR_ENV: &str = "LUMOS_MODEL_CACHE_DIR";
+
+/// 缓存大小上限（字节）的环境变量
+pub const MODEL_CACHE_MAX_SIZE_ENV: &str = "LUMOS_MODEL_CACHE_MAX_SIZE";
+
+/// 开启离线模式的环境变量，取值为`1`或`true`
+pub const OFFLINE_ENV: &str = "LUMOS_OFFLINE";
+
+/// 缓存索引文件名
+const INDEX_FILE: &str = "index.json";
+
+/// 模型文件所在的子目录
+const FILES_DIR: &str = "files";
+
+/// 模型文件来源
+#[derive(Clone)]
+pub enum ModelSource {
+    /// HTTP(S)地址
+    Url(String),
+    /// 对象存储中的对象
+    Object {
+        /// 对象存储
+        store: Arc<dyn ObjectStore>,
+        /// 对象键
+        key: String,
+    },
+}
+
+impl fmt::Debug for ModelSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModelSource::Url(url) => f.debug_tuple("Url").field(url).finish(),
+            ModelSource::Object { key, .. } => f.debug_struct("Object").field("key", key).finish_non_exhaustive(),
+        }
+    }
+}
+
+/// 需要缓存的模型文件
+#[derive(Debug, Clone)]
+pub struct ModelArtifact {
+    /// 缓存中的相对路径，如`fastembed/bge-small-en-v1.5/model.onnx`
+    pub name: String,
+    /// 下载来源
+    pub source: ModelSource,
+    /// 期望的SHA-256（十六进制）；未设置时不校验，只记录下载文件的哈希
+    pub sha256: Option<String>,
+}
+
+impl ModelArtifact {
+    /// 从HTTP(S)地址下载的模型文件
+    pub fn from_url(name: impl Into<String>, url: impl Into<String>) -> Self {
+        Self { name: name.into(), source: ModelSource::Url(url.into()), sha256: None }
+    }
+
+    /// 从对象存储读取的模型文件
+    pub fn from_object(name: impl Into<String>, store: Arc<dyn ObjectStore>, key: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            source: ModelSource::Object { store, key: key.into() },
+            sha256: None,
+        }
+    }
+
+    /// Hugging Face Hub仓库中的文件，缓存为`huggingface/<仓库>/<版本>/<文件>`
+    ///
+    /// 例如`huggingface("ggerganov/whisper.cpp", "main", "ggml-base.en.bin")`。
+    pub fn huggingface(repo: &str, revision: &str, file: &str) -> Self {
+        Self::from_url(
+            format!("huggingface/{}/{}/{}", repo, revision, file),
+            format!("https://huggingface.co/{}/resolve/{}/{}", repo, revision, file),
+        )
+    }
+
+    /// 设置期望的SHA-256
+    pub fn with_sha256(mut self, sha256: impl Into<String>) -> Self {
+        self.sha256 = Some(sha256.into().trim().to_ascii_lowercase());
+        self
+    }
+}
+
+/// 缓存中的模型文件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedModel {
+    /// 缓存中的相对路径
+    pub name: String,
+    /// 文件的SHA-256
+    pub sha256: String,
+    /// 文件大小（字节）
+    pub size: u64,
+    /// 下载时间
+    pub downloaded_at: DateTime<Utc>,
+    /// 最近一次使用时间
+    pub last_used: DateTime<Utc>,
+}
+
+/// 模型文件缓存
+pub struct ModelCache {
+    dir: PathBuf,
+    max_size: Option<u64>,
+    offline: bool,
+    /// 下载Hugging Face Hub文件时使用的令牌
+    hf_token: Option<String>,
+    client: reqwest::Client,
+    index: Mutex<HashMap<String, CachedModel>>,
+}
+
+impl ModelCache {
+    /// 使用`dir`作为缓存目录，读取其中已有的索引
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        let dir = dir.into();
+        let index = load_index(&dir.join(INDEX_FILE));
+        Self {
+            dir,
+            max_size: None,
+            offline: false,
+            hf_token: None,
+            client: http_client_for(""),
+            index: Mutex::new(index),
+        }
+    }
+
+    /// 按环境变量创建缓存
+    ///
+    /// 目录取`LUMOS_MODEL_CACHE_DIR`，默认为`$XDG_CACHE_HOME/lumosai/models`或
+    /// `~/.cache/lumosai/models`；`LUMOS_MODEL_CACHE_MAX_SIZE`设置大小上限；
+    /// `LUMOS_OFFLINE`或`HF_HUB_OFFLINE`开启离线模式；`HF_TOKEN`用于下载需要授权的模型。
+    pub fn from_env() -> Self {
+        let var = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+        let dir = var(MODEL_CACHE_DIR_ENV).map(PathBuf::from).unwrap_or_else(|| {
+            var("XDG_CACHE_HOME")
+                .map(PathBuf::from)
+                .or_else(|| var("HOME").map(|home| Path::new(&home).join(".cache")))
+                .unwrap_or_else(std::env::temp_dir)
+                .join("lumosai")
+                .join("models")
+        });
+        let enabled = |name: &str| var(name).is_some_and(|value| matches!(value.to_ascii_lowercase().as_str(), "1" | "true" | "yes"));
+
+        let mut cache = Self::new(dir).offline(enabled(OFFLINE_ENV) || enabled("HF_HUB_OFFLINE"));
+        cache.max_size = var(MODEL_CACHE_MAX_SIZE_ENV).and_then(|value| value.parse().ok());
+        cache.hf_token = var("HF_TOKEN");
+        cache
+    }
+
+    /// 设置缓存总大小上限（字节）
+    pub fn with_max_size(mut self, max_size: u64) -> Self {
+        self.max_size = Some(max_size);
+        self
+    }
+
+    /// 开启或关闭离线模式
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// 设置下载Hugging Face Hub文件时使用的令牌
+    pub fn with_hf_token(mut self, token: impl Into<String>) -> Self {
+        self.hf_token = Some(token.into());
+        self
+    }
+
+    /// 缓存目录
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// 是否处于离线模式
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    /// 模型文件的本地路径，缓存中没有时下载
+    ///
+    /// 缓存的文件与声明的SHA-256不一致时重新下载。缓存目录中已有但没有记录的文件
+    /// （如手动复制到离线环境的文件）校验通过后直接使用。
+    pub async fn get(&self, artifact: &ModelArtifact) -> Result<PathBuf> {
+        check_key(&artifact.name)?;
+        let path = self.path(&artifact.name);
+        let expected = artifact.sha256.as_deref();
+
+        {
+            let mut index = self.index.lock().await;
+            let recorded = index.get(&artifact.name).map(|entry| entry.sha256.clone());
+            let on_disk = tokio::fs::try_exists(&path).await?;
+            let sha256 = match recorded {
+                Some(sha256) if on_disk => Some(sha256),
+                // 没有记录的文件先计算哈希
+                None if on_disk => Some(hash_file(&path).await?),
+                _ => None,
+            };
+            if let Some(sha256) = sha256.filter(|sha256| expected.is_none_or(|expected| expected == sha256)) {
+                let now = Utc::now();
+                let size = tokio::fs::metadata(&path).await?.len();
+                let entry = index.entry(artifact.name.clone()).or_insert_with(|| CachedModel {
+                    name: artifact.name.clone(),
+                    sha256,
+                    size,
+                    downloaded_at: now,
+                    last_used: now,
+                });
+                entry.last_used = now;
+                self.evict(&mut index, &artifact.name).await?;
+                self.save_index(&index).await?;
+                return Ok(path);
+            }
+        }
+
+        if self.offline {
+            return Err(Error::NotFound(format!(
+                "Model file '{}' is not in the cache at {} and offline mode is enabled; \
+                 download it while online or unset {}",
+                artifact.name, self.dir.display(), OFFLINE_ENV,
+            )));
+        }
+
+        let (sha256, size) = self.download(artifact, &path).await?;
+        let mut index = self.index.lock().await;
+        let now = Utc::now();
+        index.insert(artifact.name.clone(), CachedModel {
+            name: artifact.name.clone(),
+            sha256,
+            size,
+            downloaded_at: now,
+            last_used: now,
+        });
+        self.evict(&mut index, &artifact.name).await?;
+        self.save_index(&index).await?;
+        Ok(path)
+    }
+
+    /// 模型文件是否已缓存
+    pub async fn is_cached(&self, artifact: &ModelArtifact) -> bool {
+        let index = self.index.lock().await;
+        index.get(&artifact.name).is_some_and(|entry| {
+            artifact.sha256.as_deref().is_none_or(|expected| expected == entry.sha256)
+                && self.path(&entry.name).exists()
+        })
+    }
+
+    /// 缓存中的所有模型文件，按名称排序
+    pub async fn entries(&self) -> Vec<CachedModel> {
+        let mut entries: Vec<CachedModel> = self.index.lock().await.values().cloned().collect();
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        entries
+    }
+
+    /// 缓存文件的总大小（字节）
+    pub async fn total_size(&self) -> u64 {
+        self.index.lock().await.values().map(|entry| entry.size).sum()
+    }
+
+    /// 重新计算文件的哈希并与记录比对，损坏的文件会被删除
+    pub async fn verify(&self, name: &str) -> Result<bool> {
+        let mut index = self.index.lock().await;
+        let entry = index.get(name)
+            .ok_or_else(|| Error::NotFound(format!("Model file '{}' is not in the cache", name)))?;
+        let path = self.path(name);
+        let intact = match hash_file(&path).await {
+            Ok(sha256) => sha256 == entry.sha256,
+            Err(Error::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => false,
+            Err(e) => return Err(e),
+        };
+        if !intact {
+            tracing::warn!("Removing corrupt model file {} from the cache", path.display());
+            remove_file(&path).await?;
+            index.remove(name);
+            self.save_index(&index).await?;
+        }
+        Ok(intact)
+    }
+
+    /// 从缓存中删除模型文件
+    pub async fn remove(&self, name: &str) -> Result<()> {
+        check_key(name)?;
+        let mut index = self.index.lock().await;
+        remove_file(&self.path(name)).await?;
+        if index.remove(name).is_some() {
+            self.save_index(&index).await?;
+        }
+        Ok(())
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(FILES_DIR).join(name)
+    }
+
+    /// 下载到临时文件并校验哈希，通过后移动到`path`，返回哈希和大小
+    async fn download(&self, artifact: &ModelArtifact, path: &Path) -> Result<(String, u64)> {
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let mut partial = path.as_os_str().to_owned();
+        partial.push(format!(".{}.partial", uuid::Uuid::new_v4().simple()));
+        let partial = PathBuf::from(partial);
+
+        let result = self.download_to(artifact, &partial).await;
+        let (sha256, size) = match result {
+            Ok(downloaded) => downloaded,
+            Err(e) => {
+                let _ = tokio::fs::remove_file(&partial).await;
+                return Err(e);
+            },
+        };
+        if let Some(expected) = artifact.sha256.as_deref().filter(|expected| *expected != sha256) {
+            let _ = tokio::fs::remove_file(&partial).await;
+            return Err(Error::ValidationError(format!(
+                "Checksum mismatch for model file '{}': expected sha256 {}, downloaded {}",
+                artifact.name, expected, sha256,
+            )));
+        }
+        tokio::fs::rename(&partial, path).await?;
+        tracing::info!("Cached model file {} ({} bytes)", artifact.name, size);
+        Ok((sha256, size))
+    }
+
+    async fn download_to(&self, artifact: &ModelArtifact, partial: &Path) -> Result<(String, u64)> {
+        let mut file = tokio::fs::File::create(partial).await?;
+        let mut hasher = digest::Context::new(&digest::SHA256);
+        let mut size = 0u64;
+
+        match &artifact.source {
+            ModelSource::Url(url) => {
+                let mut request = self.client.get(url);
+                if let Some(token) = self.hf_token.as_ref().filter(|_| url.starts_with("https://huggingface.co/")) {
+                    request = request.bearer_auth(token);
+                }
+                let mut response = request.send().await.map_err(|e| {
+                    Error::Unavailable(format!("Failed to download model file '{}' from {}: {}", artifact.name, url, e))
+                })?;
+                let status = response.status();
+                if status == reqwest::StatusCode::NOT_FOUND {
+                    return Err(Error::NotFound(format!("Model file '{}' not found at {}", artifact.name, url)));
+                }
+                if !status.is_success() {
+                    return Err(Error::Storage(format!(
+                        "Downloading model file '{}' from {} failed with {}", artifact.name, url, status,
+                    )));
+                }
+                while let Some(chunk) = response.chunk().await? {
+                    hasher.update(&chunk);
+                    file.write_all(&chunk).await?;
+                    size += chunk.len() as u64;
+                }
+            },
+            ModelSource::Object { store, key } => {
+                let data = store.get(key).await?;
+                hasher.update(&data);
+                file.write_all(&data).await?;
+                size = data.len() as u64;
+            },
+        }
+
+        file.flush().await?;
+        file.sync_all().await?;
+        Ok((hex(hasher.finish().as_ref()), size))
+    }
+
+    /// 超过大小上限时按最近使用时间淘汰文件，`keep`不会被淘汰
+    async fn evict(&self, index: &mut HashMap<String, CachedModel>, keep: &str) -> Result<()> {
+        let Some(max_size) = self.max_size else {
+            return Ok(());
+        };
+        let mut total: u64 = index.values().map(|entry| entry.size).sum();
+        let mut candidates: Vec<(DateTime<Utc>, String)> = index.values()
+            .filter(|entry| entry.name != keep)
+            .map(|entry| (entry.last_used, entry.name.clone()))
+            .collect();
+        candidates.sort();
+
+        for (_, name) in candidates {
+            if total <= max_size {
+                break;
+            }
+            remove_file(&self.path(&name)).await?;
+            if let Some(entry) = index.remove(&name) {
+                tracing::info!("Evicted model file {} ({} bytes) from the cache", name, entry.size);
+                total -= entry.size;
+            }
+        }
+        if total > max_size {
+            tracing::warn!("Model cache holds {} bytes, more than its limit of {} bytes", total, max_size);
+        }
+        Ok(())
+    }
+
+    async fn save_index(&self, index: &HashMap<String, CachedModel>) -> Result<()> {
+        let mut entries: Vec<&CachedModel> = index.values().collect();
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        write_file(&self.dir.join(INDEX_FILE), &serde_json::to_vec_pretty(&entries)?).await
+    }
+}
+
+/// 读取缓存索引，不存在或无法解析时返回空索引
+fn load_index(path: &Path) -> HashMap<String, CachedModel> {
+    let Ok(data) = std::fs::read(path) else {
+        return HashMap::new();
+    };
+    match serde_json::from_slice::<Vec<CachedModel>>(&data) {
+        Ok(entries) => entries.into_iter().map(|entry| (entry.name.clone(), entry)).collect(),
+        Err(e) => {
+            tracing::warn!("Ignoring unreadable model cache index {}: {}", path.display(), e);
+            HashMap::new()
+        },
+    }
+}
+
+async fn hash_file(path: &Path) -> Result<String> {
+    let mut file = tokio::fs::File::open(path).await?;
+    let mut hasher = digest::Context::new(&digest::SHA256);
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hex(hasher.finish().as_ref()))
+}
+
+async fn remove_file(path: &Path) -> Result<()> {
+    match tokio::fs::remove_file(path).await {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::LocalObjectStore;
+
+    const HELLO_SHA256: &str = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+
+    async fn remote(files: &[(&str, &[u8])]) -> (tempfile::TempDir, Arc<dyn ObjectStore>) {
+        let dir = tempfile::tempdir().unwrap();
+        let store: Arc<dyn ObjectStore> = Arc::new(LocalObjectStore::new(dir.path()));
+        for (key, data) in files {
+            store.put(key, "application/octet-stream", data.to_vec()).await.unwrap();
+        }
+        (dir, store)
+    }
+
+    #[tokio::test]
+    async fn test_download_and_verify() {
+        let (_remote, store) = remote(&[("hello.bin", b"hello")]).await;
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ModelCache::new(dir.path());
+
+        let artifact = ModelArtifact::from_object("whisper/hello.bin", store.clone(), "hello.bin").with_sha256(HELLO_SHA256.to_uppercase());
+        let path = cache.get(&artifact).await.unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"hello");
+        assert!(cache.is_cached(&artifact).await);
+        assert_eq!(cache.entries().await[0].sha256, HELLO_SHA256);
+
+        // 哈希不一致的文件不会进入缓存
+        let wrong = ModelArtifact::from_object("whisper/wrong.bin", store.clone(), "hello.bin").with_sha256("00");
+        assert!(matches!(cache.get(&wrong).await, Err(Error::ValidationError(_))));
+        assert!(!dir.path().join("files/whisper/wrong.bin").exists());
+        assert!(std::fs::read_dir(dir.path().join("files/whisper")).unwrap().count() == 1);
+
+        // 损坏的文件在校验时删除
+        std::fs::write(&path, b"tampered").unwrap();
+        assert!(!cache.verify("whisper/hello.bin").await.unwrap());
+        assert!(!cache.is_cached(&artifact).await);
+    }
+
+    #[tokio::test]
+    async fn test_offline_mode() {
+        let (_remote, store) = remote(&[("model.gguf", b"hello")]).await;
+        let dir = tempfile::tempdir().unwrap();
+        let artifact = ModelArtifact::from_object("candle/model.gguf", store, "model.gguf");
+
+        let offline = ModelCache::new(dir.path()).offline(true);
+        let error = offline.get(&artifact).await.unwrap_err();
+        assert!(matches!(error, Error::NotFound(_)));
+        assert!(error.to_string().contains("offline mode"));
+
+        // 在线时下载，之后离线的新实例从索引中读取
+        ModelCache::new(dir.path()).get(&artifact).await.unwrap();
+        let offline = ModelCache::new(dir.path()).offline(true);
+        assert!(offline.get(&artifact).await.is_ok());
+
+        // 手动放入缓存目录的文件校验后使用
+        let copied = dir.path().join("files/fastembed/model.onnx");
+        std::fs::create_dir_all(copied.parent().unwrap()).unwrap();
+        std::fs::write(&copied, b"hello").unwrap();
+        let onnx = ModelArtifact::from_url("fastembed/model.onnx", "http://127.0.0.1:9/model.onnx").with_sha256(HELLO_SHA256);
+        assert_eq!(offline.get(&onnx).await.unwrap(), copied);
+        assert_eq!(offline.entries().await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_size_eviction() {
+        let (_remote, store) = remote(&[("a", &[0; 4]), ("b", &[1; 4]), ("c", &[2; 4])]).await;
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ModelCache::new(dir.path()).with_max_size(8);
+        let artifact = |key: &str| ModelArtifact::from_object(format!("models/{}", key), store.clone(), key);
+
+        cache.get(&artifact("a")).await.unwrap();
+        cache.get(&artifact("b")).await.unwrap();
+        // 使用a之后，b成为最久未使用的文件
+        cache.get(&artifact("a")).await.unwrap();
+        cache.get(&artifact("c")).await.unwrap();
+
+        let names: Vec<String> = cache.entries().await.into_iter().map(|entry| entry.name).collect();
+        assert_eq!(names, vec!["models/a", "models/c"]);
+        assert_eq!(cache.total_size().await, 8);
+        assert!(!dir.path().join("files/models/b").exists());
+
+        cache.remove("models/a").await.unwrap();
+        assert_eq!(cache.total_size().await, 4);
+        assert!(matches!(cache.get(&ModelArtifact::from_object("../escape", store, "a")).await, Err(Error::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_huggingface_artifact() {
+        let artifact = ModelArtifact::huggingface("Qdrant/bge-small-en-v1.5-onnx-Q", "main", "model_optimized.onnx");
+        assert_eq!(artifact.name, "huggingface/Qdrant/bge-small-en-v1.5-onnx-Q/main/model_optimized.onnx");
+        assert!(matches!(
+            artifact.source,
+            ModelSource::Url(ref url) if url == "https://huggingface.co/Qdrant/bge-small-en-v1.5-onnx-Q/resolve/main/model_optimized.onnx"
+        ));
+    }
+}
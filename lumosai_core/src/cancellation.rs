@@ -0,0 +1,94 @@
+//! 生成、工具调用和流式输出的取消
+//!
+//! [`CancellationToken`]随[`LlmOptions`](crate::llm::LlmOptions)和
+//! [`ToolExecutionContext`](crate::tool::ToolExecutionContext)传递。令牌取消后，进行中的提供商请求
+//! 和工具调用的Future被丢弃：上游HTTP请求随之中止，以`kill_on_drop`启动的工具子进程被终止。
+//! 服务端在客户端断开时通过[`DropGuard`]取消令牌，避免为已放弃的请求继续付费。
+
+use std::future::Future;
+
+use futures::stream::{self, BoxStream, StreamExt};
+
+use crate::error::{Error, Result};
+
+pub use tokio_util::sync::{CancellationToken, DropGuard};
+
+/// 取消时返回的错误
+pub fn cancelled_error() -> Error {
+    Error::Cancelled("Operation cancelled".to_string())
+}
+
+/// 执行Future，令牌取消时丢弃它并返回`Cancelled`错误；没有令牌时直接执行
+pub async fn run_until_cancelled<T>(
+    token: Option<&CancellationToken>,
+    future: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    let Some(token) = token else {
+        return future.await;
+    };
+    if token.is_cancelled() {
+        return Err(cancelled_error());
+    }
+
+    tokio::select! {
+        biased;
+        _ = token.cancelled() => Err(cancelled_error()),
+        result = future => result,
+    }
+}
+
+/// 令牌取消时以`Cancelled`错误结束流，并立即丢弃上游流
+///
+/// 包装后的流仍按消费者的速度逐项拉取，不会在内部缓冲上游输出。
+pub fn cancellable_stream<'a, T: Send + 'a>(
+    upstream: BoxStream<'a, Result<T>>,
+    token: CancellationToken,
+) -> BoxStream<'a, Result<T>> {
+    stream::unfold(Some((upstream, token)), |state| async move {
+        let (mut upstream, token) = state?;
+        tokio::select! {
+            biased;
+            _ = token.cancelled() => Some((Err(cancelled_error()), None)),
+            item = upstream.next() => item.map(|item| (item, Some((upstream, token)))),
+        }
+    })
+    .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_run_until_cancelled_drops_pending_future() {
+        let token = CancellationToken::new();
+        let canceller = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            canceller.cancel();
+        });
+
+        let result: Result<()> = run_until_cancelled(Some(&token), async {
+            tokio::time::sleep(Duration::from_secs(30)).await;
+            Ok(())
+        }).await;
+        assert!(matches!(result, Err(Error::Cancelled(_))));
+
+        let result = run_until_cancelled(None, async { Ok(1) }).await;
+        assert_eq!(result.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_cancellable_stream_stops_pulling_upstream() {
+        let token = CancellationToken::new();
+        let upstream = stream::iter(0..).map(Ok).boxed();
+        let mut stream = cancellable_stream(upstream, token.clone());
+
+        assert_eq!(stream.next().await.unwrap().unwrap(), 0);
+        assert_eq!(stream.next().await.unwrap().unwrap(), 1);
+        token.cancel();
+        assert!(matches!(stream.next().await, Some(Err(Error::Cancelled(_)))));
+        assert!(stream.next().await.is_none());
+    }
+}
@@ -0,0 +1,264 @@
+//! 渠道配置
+//!
+//! `lumos.yaml`中的`channels`部分声明各渠道使用的代理和凭证，凭证可以是`${VAR}`插值，
+//! 也可以是`vault://`等密钥引用：
+//!
+//! ```yaml
+//! channels:
+//!   edit_interval_ms: 1500
+//!   slack:
+//!     agent: assistant
+//!     bot_token: ${SLACK_BOT_TOKEN}
+//!     app_token: ${SLACK_APP_TOKEN}
+//!   teams:
+//!     agent: assistant
+//!     app_id: ${TEAMS_APP_ID}
+//!     app_password: vault://bots/teams#password
+//!   telegram:
+//!     agent: assistant
+//!     bot_token: ${TELEGRAM_BOT_TOKEN}
+//!   discord:
+//!     agent: support
+//!     bot_token: ${DISCORD_BOT_TOKEN}
+//! ```
+//!
+//! [`LumosApp::start`](crate::app::LumosApp::start)启动时连接Slack、Telegram和Discord，
+//! Teams的消息端点挂载在应用HTTP服务的`/api/messages`上。
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{AgentConfig, SecretResolver};
+use crate::error::{Error, Result};
+use super::{ChannelBridge, DiscordAdapter, SlackAdapter, TeamsAdapter, TelegramAdapter};
+
+/// `lumos.yaml`中的`channels`部分
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChannelsConfig {
+    /// 流式回复的最小编辑间隔（毫秒）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub edit_interval_ms: Option<u64>,
+    /// 提供给代理的历史消息数
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_history: Option<usize>,
+    /// Slack机器人
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slack: Option<SlackChannelConfig>,
+    /// Microsoft Teams机器人
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub teams: Option<TeamsChannelConfig>,
+    /// Telegram机器人
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub telegram: Option<TelegramChannelConfig>,
+    /// Discord机器人
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discord: Option<DiscordChannelConfig>,
+}
+
+/// Slack机器人配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SlackChannelConfig {
+    /// 处理消息的代理
+    pub agent: String,
+    /// 机器人令牌（`xoxb-`）
+    pub bot_token: String,
+    /// 应用令牌（`xapp-`）
+    pub app_token: String,
+}
+
+/// Microsoft Teams机器人配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TeamsChannelConfig {
+    /// 处理消息的代理
+    pub agent: String,
+    /// Azure Bot的应用ID
+    pub app_id: String,
+    /// Azure Bot的应用密码
+    pub app_password: String,
+    /// 单租户机器人所在的租户ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<String>,
+}
+
+/// Telegram机器人配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TelegramChannelConfig {
+    /// 处理消息的代理
+    pub agent: String,
+    /// BotFather签发的令牌
+    pub bot_token: String,
+    /// 自建Bot API服务的地址
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_url: Option<String>,
+}
+
+/// Discord机器人配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiscordChannelConfig {
+    /// 处理消息的代理
+    pub agent: String,
+    /// 机器人令牌
+    pub bot_token: String,
+}
+
+impl ChannelsConfig {
+    /// 各渠道的名称和使用的代理
+    pub fn agents(&self) -> Vec<(&'static str, &str)> {
+        let mut agents = Vec::new();
+        if let Some(slack) = &self.slack {
+            agents.push(("slack", slack.agent.as_str()));
+        }
+        if let Some(teams) = &self.teams {
+            agents.push(("teams", teams.agent.as_str()));
+        }
+        if let Some(telegram) = &self.telegram {
+            agents.push(("telegram", telegram.agent.as_str()));
+        }
+        if let Some(discord) = &self.discord {
+            agents.push(("discord", discord.agent.as_str()));
+        }
+        agents
+    }
+
+    /// 是否没有配置任何渠道
+    pub fn is_empty(&self) -> bool {
+        self.agents().is_empty()
+    }
+
+    /// 校验配置，`agents`为配置中声明的代理
+    pub fn validate(&self, agents: Option<&HashMap<String, AgentConfig>>) -> Result<()> {
+        if self.edit_interval_ms == Some(0) {
+            return Err(Error::Configuration("channels edit_interval_ms must be positive".to_string()));
+        }
+        for (channel, agent) in self.agents() {
+            if agent.is_empty() {
+                return Err(Error::Configuration(format!("Channel '{}' must name an agent", channel)));
+            }
+            if agents.is_some_and(|agents| !agents.contains_key(agent)) {
+                return Err(Error::Configuration(format!("Channel '{}' references unknown agent '{}'", channel, agent)));
+            }
+        }
+
+        let credentials = [
+            self.slack.as_ref().map(|c| ("slack", "bot_token", &c.bot_token)),
+            self.slack.as_ref().map(|c| ("slack", "app_token", &c.app_token)),
+            self.teams.as_ref().map(|c| ("teams", "app_id", &c.app_id)),
+            self.teams.as_ref().map(|c| ("teams", "app_password", &c.app_password)),
+            self.telegram.as_ref().map(|c| ("telegram", "bot_token", &c.bot_token)),
+            self.discord.as_ref().map(|c| ("discord", "bot_token", &c.bot_token)),
+        ];
+        for (channel, field, value) in credentials.into_iter().flatten() {
+            if value.trim().is_empty() {
+                return Err(Error::Configuration(format!("Channel '{}' {} cannot be empty", channel, field)));
+            }
+        }
+        Ok(())
+    }
+
+    /// 解析凭证中的密钥引用，返回解析后的配置
+    pub async fn resolve_secrets(&self, secrets: &SecretResolver) -> Result<Self> {
+        let mut resolved = self.clone();
+        if let Some(slack) = &mut resolved.slack {
+            slack.bot_token = secrets.resolve(&slack.bot_token).await?;
+            slack.app_token = secrets.resolve(&slack.app_token).await?;
+        }
+        if let Some(teams) = &mut resolved.teams {
+            teams.app_id = secrets.resolve(&teams.app_id).await?;
+            teams.app_password = secrets.resolve(&teams.app_password).await?;
+        }
+        if let Some(telegram) = &mut resolved.telegram {
+            telegram.bot_token = secrets.resolve(&telegram.bot_token).await?;
+        }
+        if let Some(discord) = &mut resolved.discord {
+            discord.bot_token = secrets.resolve(&discord.bot_token).await?;
+        }
+        Ok(resolved)
+    }
+
+    /// 按配置设置桥接的编辑间隔和历史消息数
+    pub fn configure(&self, mut bridge: ChannelBridge) -> ChannelBridge {
+        if let Some(edit_interval_ms) = self.edit_interval_ms {
+            bridge = bridge.with_edit_interval(Duration::from_millis(edit_interval_ms));
+        }
+        if let Some(max_history) = self.max_history {
+            bridge = bridge.with_max_history(max_history);
+        }
+        bridge
+    }
+}
+
+impl SlackChannelConfig {
+    /// 按配置创建适配器
+    pub fn adapter(&self) -> SlackAdapter {
+        SlackAdapter::new(&self.bot_token, &self.app_token)
+    }
+}
+
+impl TeamsChannelConfig {
+    /// 按配置创建适配器
+    pub fn adapter(&self) -> TeamsAdapter {
+        let adapter = TeamsAdapter::new(&self.app_id, &self.app_password);
+        match &self.tenant {
+            Some(tenant) => adapter.with_tenant(tenant),
+            None => adapter,
+        }
+    }
+}
+
+impl TelegramChannelConfig {
+    /// 按配置创建适配器
+    pub fn adapter(&self) -> TelegramAdapter {
+        let adapter = TelegramAdapter::new(&self.bot_token);
+        match &self.api_url {
+            Some(api_url) => adapter.with_api_url(api_url),
+            None => adapter,
+        }
+    }
+}
+
+impl DiscordChannelConfig {
+    /// 按配置创建适配器
+    pub fn adapter(&self) -> DiscordAdapter {
+        DiscordAdapter::new(&self.bot_token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::YamlConfig;
+
+    const CONFIG: &str = r#"
+agents:
+  assistant:
+    model: gpt-4o
+    instructions: You are a helpful assistant
+channels:
+  edit_interval_ms: 1500
+  telegram:
+    agent: assistant
+    bot_token: "123:abc"
+  discord:
+    agent: assistant
+    bot_token: discord-token
+"#;
+
+    #[test]
+    fn test_channels_from_yaml() {
+        let config = YamlConfig::from_str(CONFIG).unwrap();
+        config.validate().unwrap();
+        let channels = config.channels.unwrap();
+        assert_eq!(channels.agents(), vec![("telegram", "assistant"), ("discord", "assistant")]);
+        assert_eq!(channels.edit_interval_ms, Some(1500));
+        assert!(channels.slack.is_none());
+
+        let unknown = CONFIG.replace("agent: assistant\n    bot_token: discord-token", "agent: support\n    bot_token: discord-token");
+        let error = YamlConfig::from_str(&unknown).unwrap().validate().unwrap_err();
+        assert!(error.to_string().contains("unknown agent 'support'"));
+
+        let empty = CONFIG.replace("discord-token", "\"\"");
+        assert!(YamlConfig::from_str(&empty).unwrap().validate().is_err());
+    }
+}
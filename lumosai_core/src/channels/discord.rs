@@ -0,0 +1,373 @@
+//! Discord适配器
+//!
+//! 通过[Gateway](https://discord.com/developers/docs/events/gateway)的WebSocket连接接收消息，
+//! 不需要公网地址。机器人需要在开发者后台开启Message Content特权意图，才能读取消息内容。
+//!
+//! 私信全部交给代理处理，服务器频道中只处理@机器人的消息。Discord的子区本身就是频道，
+//! 因此每个频道或子区对应一个会话。连接断开时优先恢复（Resume）原会话，避免丢失事件。
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::{Sink, SinkExt, StreamExt};
+use serde_json::{json, Value};
+use tokio::sync::Mutex;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+use crate::config::http_client_for;
+use crate::error::{Error, Result};
+use super::{truncate_message, ChannelAdapter, ChannelBridge, ChannelFile, ChannelMessage};
+
+/// Discord REST API地址
+pub const DISCORD_API_URL: &str = "https://discord.com/api/v10";
+
+/// 订阅的事件：服务器消息、私信和消息内容
+pub const DISCORD_INTENTS: u64 = (1 << 9) | (1 << 12) | (1 << 15);
+
+/// 消息文本长度上限
+const MAX_MESSAGE_CHARS: usize = 2000;
+
+/// 重连等待时间上限
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// 可恢复的Gateway会话
+#[derive(Debug, Clone, Default)]
+struct GatewaySession {
+    session_id: String,
+    resume_url: String,
+    sequence: Option<u64>,
+}
+
+/// Discord适配器
+pub struct DiscordAdapter {
+    bot_token: String,
+    api_url: String,
+    client: reqwest::Client,
+    bot_user_id: Mutex<Option<String>>,
+}
+
+impl DiscordAdapter {
+    /// 使用机器人令牌创建适配器
+    pub fn new(bot_token: impl Into<String>) -> Self {
+        Self {
+            bot_token: bot_token.into(),
+            api_url: DISCORD_API_URL.to_string(),
+            client: http_client_for(DISCORD_API_URL),
+            bot_user_id: Mutex::new(None),
+        }
+    }
+
+    /// 设置REST API地址，用于测试或代理
+    pub fn with_api_url(mut self, api_url: impl Into<String>) -> Self {
+        self.api_url = api_url.into().trim_end_matches('/').to_string();
+        self.client = http_client_for(&self.api_url);
+        self
+    }
+
+    /// 连接Gateway并持续处理消息
+    ///
+    /// 连接断开时自动恢复或重新连接，令牌无效或意图未开启时返回错误。
+    pub async fn run(self: Arc<Self>, bridge: Arc<ChannelBridge>) -> Result<()> {
+        let mut session = None;
+        let mut backoff = Duration::from_secs(1);
+        loop {
+            match self.clone().run_connection(&bridge, &mut session).await {
+                Ok(()) => backoff = Duration::from_secs(1),
+                Err(e @ (Error::Authentication(_) | Error::Configuration(_))) => return Err(e),
+                Err(e) => tracing::warn!("Discord gateway connection failed: {}", e),
+            }
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(MAX_BACKOFF);
+        }
+    }
+
+    /// 处理一个连接直到断开
+    async fn run_connection(
+        self: Arc<Self>,
+        bridge: &Arc<ChannelBridge>,
+        session: &mut Option<GatewaySession>,
+    ) -> Result<()> {
+        let url = match session {
+            Some(session) => session.resume_url.clone(),
+            None => self.request(self.client.get(format!("{}/gateway/bot", self.api_url))).await?["url"]
+                .as_str()
+                .ok_or_else(|| Error::Network("Discord did not return a gateway URL".to_string()))?
+                .to_string(),
+        };
+        let (mut socket, _) = tokio_tungstenite::connect_async(format!("{}/?v=10&encoding=json", url)).await
+            .map_err(|e| Error::Network(format!("Failed to connect to the Discord gateway: {}", e)))?;
+
+        let hello = match socket.next().await {
+            Some(Ok(WsMessage::Text(text))) => serde_json::from_str::<Value>(&text)?,
+            _ => return Err(Error::Network("Discord gateway did not send Hello".to_string())),
+        };
+        let interval = hello["d"]["heartbeat_interval"].as_u64().unwrap_or(41_250);
+        let mut heartbeat = tokio::time::interval(Duration::from_millis(interval));
+        heartbeat.tick().await;
+
+        let greeting = match session {
+            Some(session) => json!({ "op": 6, "d": {
+                "token": self.bot_token, "session_id": session.session_id, "seq": session.sequence,
+            }}),
+            None => json!({ "op": 2, "d": {
+                "token": self.bot_token,
+                "intents": DISCORD_INTENTS,
+                "properties": { "os": std::env::consts::OS, "browser": "lumosai", "device": "lumosai" },
+            }}),
+        };
+        send(&mut socket, greeting).await?;
+
+        let mut acknowledged = true;
+        loop {
+            let frame = tokio::select! {
+                _ = heartbeat.tick() => {
+                    // 上一次心跳没有确认，说明连接已失效
+                    if !acknowledged {
+                        return Ok(());
+                    }
+                    acknowledged = false;
+                    send(&mut socket, json!({ "op": 1, "d": session.as_ref().and_then(|s| s.sequence) })).await?;
+                    continue;
+                },
+                frame = socket.next() => frame,
+            };
+            let text = match frame {
+                Some(Ok(WsMessage::Text(text))) => text,
+                Some(Ok(WsMessage::Close(frame))) => {
+                    let code = frame.map(|frame| u16::from(frame.code)).unwrap_or_default();
+                    if !resumable(code) {
+                        *session = None;
+                    }
+                    return close_error(code).map_or(Ok(()), Err);
+                },
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => return Err(Error::Network(format!("Discord gateway error: {}", e))),
+                None => return Ok(()),
+            };
+            let Ok(payload) = serde_json::from_str::<Value>(&text) else {
+                continue;
+            };
+
+            match payload["op"].as_u64() {
+                Some(0) => {
+                    if let (Some(session), Some(sequence)) = (session.as_mut(), payload["s"].as_u64()) {
+                        session.sequence = Some(sequence);
+                    }
+                    self.dispatch(bridge, session, &payload).await;
+                },
+                Some(1) => send(&mut socket, json!({ "op": 1, "d": session.as_ref().and_then(|s| s.sequence) })).await?,
+                // 服务端要求重连
+                Some(7) => return Ok(()),
+                // 会话失效，`d`表示能否恢复
+                Some(9) => {
+                    if payload["d"].as_bool() != Some(true) {
+                        *session = None;
+                    }
+                    return Ok(());
+                },
+                Some(11) => acknowledged = true,
+                _ => {},
+            }
+        }
+    }
+
+    /// 处理分发事件
+    async fn dispatch(self: &Arc<Self>, bridge: &Arc<ChannelBridge>, session: &mut Option<GatewaySession>, payload: &Value) {
+        let data = &payload["d"];
+        match payload["t"].as_str() {
+            Some("READY") => {
+                *session = Some(GatewaySession {
+                    session_id: data["session_id"].as_str().unwrap_or_default().to_string(),
+                    resume_url: data["resume_gateway_url"].as_str().unwrap_or_default().to_string(),
+                    sequence: payload["s"].as_u64(),
+                });
+                *self.bot_user_id.lock().await = data["user"]["id"].as_str().map(str::to_string);
+            },
+            Some("MESSAGE_CREATE") => {
+                let bot_user_id = self.bot_user_id.lock().await.clone().unwrap_or_default();
+                let Some(message) = parse_message(data, &bot_user_id) else {
+                    return;
+                };
+                let adapter = self.clone();
+                let bridge = bridge.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = bridge.handle(adapter.as_ref(), &message).await {
+                        tracing::warn!("Failed to handle Discord message: {}", e);
+                    }
+                });
+            },
+            _ => {},
+        }
+    }
+
+    async fn request(&self, request: reqwest::RequestBuilder) -> Result<Value> {
+        let response = request.header(reqwest::header::AUTHORIZATION, format!("Bot {}", self.bot_token)).send().await?;
+        let status = response.status();
+        if status == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(Error::Authentication("Discord rejected the bot token".to_string()));
+        }
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(Error::Network(format!("Discord API request failed with {}: {}", status, body)));
+        }
+        Ok(response.json().await?)
+    }
+}
+
+#[async_trait]
+impl ChannelAdapter for DiscordAdapter {
+    fn name(&self) -> &str {
+        "discord"
+    }
+
+    async fn post_reply(&self, message: &ChannelMessage, text: &str) -> Result<String> {
+        let body = json!({
+            "content": truncate_message(text, MAX_MESSAGE_CHARS),
+            "message_reference": { "message_id": message.message_id, "fail_if_not_exists": false },
+            // 代理的回复不触发任何提及
+            "allowed_mentions": { "parse": [] },
+        });
+        let url = format!("{}/channels/{}/messages", self.api_url, message.conversation_id);
+        let sent = self.request(self.client.post(url).json(&body)).await?;
+        sent["id"].as_str()
+            .map(str::to_string)
+            .ok_or_else(|| Error::Network("Discord did not return the ID of the reply".to_string()))
+    }
+
+    async fn update_reply(&self, message: &ChannelMessage, reply_id: &str, text: &str) -> Result<()> {
+        let body = json!({ "content": truncate_message(text, MAX_MESSAGE_CHARS) });
+        let url = format!("{}/channels/{}/messages/{}", self.api_url, message.conversation_id, reply_id);
+        self.request(self.client.patch(url).json(&body)).await?;
+        Ok(())
+    }
+
+    async fn download(&self, file: &ChannelFile) -> Result<Vec<u8>> {
+        // 附件地址是带签名的CDN地址，不需要机器人令牌
+        let response = self.client.get(&file.url).send().await?;
+        if !response.status().is_success() {
+            return Err(Error::Network(format!("Downloading Discord file '{}' failed with {}", file.name, response.status())));
+        }
+        Ok(response.bytes().await?.to_vec())
+    }
+}
+
+/// 将`MESSAGE_CREATE`事件转换为渠道消息
+///
+/// 忽略机器人发送的消息，服务器频道中只处理@机器人的消息。
+pub fn parse_message(data: &Value, bot_user_id: &str) -> Option<ChannelMessage> {
+    if data["author"]["bot"].as_bool() == Some(true) {
+        return None;
+    }
+    let in_guild = data["guild_id"].is_string();
+    let mentioned = data["mentions"].as_array().into_iter().flatten()
+        .any(|user| user["id"].as_str() == Some(bot_user_id));
+    if in_guild && !mentioned {
+        return None;
+    }
+
+    let content = data["content"].as_str().unwrap_or_default();
+    let text = content
+        .replace(&format!("<@{}>", bot_user_id), "")
+        .replace(&format!("<@!{}>", bot_user_id), "")
+        .trim()
+        .to_string();
+    let files: Vec<_> = data["attachments"].as_array().into_iter().flatten()
+        .filter_map(|attachment| Some(ChannelFile {
+            name: attachment["filename"].as_str().unwrap_or("file").to_string(),
+            mime_type: attachment["content_type"].as_str().map(str::to_string),
+            url: attachment["url"].as_str()?.to_string(),
+            size: attachment["size"].as_u64().map(|size| size as usize),
+            authenticated: false,
+        }))
+        .collect();
+    if text.is_empty() && files.is_empty() {
+        return None;
+    }
+
+    let channel_id = data["channel_id"].as_str()?;
+    Some(ChannelMessage {
+        channel: "discord".to_string(),
+        conversation_id: channel_id.to_string(),
+        thread_id: channel_id.to_string(),
+        message_id: data["id"].as_str()?.to_string(),
+        user_id: data["author"]["id"].as_str().map(str::to_string),
+        text,
+        files,
+        service_url: None,
+    })
+}
+
+/// 不可恢复的关闭码对应的错误
+///
+/// 令牌无效时返回认证错误，意图无效或未开启时返回配置错误，其余关闭码可以重连。
+fn close_error(code: u16) -> Option<Error> {
+    match code {
+        4004 => Some(Error::Authentication("Discord rejected the bot token".to_string())),
+        4010..=4012 => Some(Error::Configuration(format!("Discord closed the gateway connection with code {}", code))),
+        4013 | 4014 => Some(Error::Configuration(
+            "Discord rejected the gateway intents; enable the Message Content intent for the bot".to_string()
+        )),
+        _ => None,
+    }
+}
+
+/// 以该关闭码断开后能否恢复原会话
+fn resumable(code: u16) -> bool {
+    !matches!(code, 4007 | 4009) && close_error(code).is_none()
+}
+
+async fn send<S>(socket: &mut S, payload: Value) -> Result<()>
+where
+    S: Sink<WsMessage> + Unpin,
+    S::Error: std::fmt::Display,
+{
+    socket.send(WsMessage::Text(payload.to_string())).await
+        .map_err(|e| Error::Network(format!("Failed to send to the Discord gateway: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_message() {
+        let guild = |mentions: Value| json!({
+            "id": "1100",
+            "channel_id": "900",
+            "guild_id": "800",
+            "author": { "id": "55", "bot": false },
+            "content": "<@42> what changed in this log?",
+            "mentions": mentions,
+            "attachments": [{
+                "filename": "build.log",
+                "content_type": "text/plain; charset=utf-8",
+                "url": "https://cdn.discordapp.com/attachments/900/1/build.log?ex=1",
+                "size": 2048,
+            }],
+        });
+        let message = parse_message(&guild(json!([{ "id": "42" }])), "42").unwrap();
+        assert_eq!(message.text, "what changed in this log?");
+        assert_eq!(message.conversation_id, "900");
+        assert_eq!(message.thread_id, "900");
+        assert_eq!(message.user_id.as_deref(), Some("55"));
+        assert_eq!(message.files[0].name, "build.log");
+        assert!(!message.files[0].authenticated);
+        assert!(parse_message(&guild(json!([])), "42").is_none());
+
+        let direct = json!({ "id": "1", "channel_id": "700", "author": { "id": "55" }, "content": "hi" });
+        assert_eq!(parse_message(&direct, "42").unwrap().text, "hi");
+        let from_bot = json!({ "id": "2", "channel_id": "700", "author": { "id": "42", "bot": true }, "content": "hi" });
+        assert!(parse_message(&from_bot, "42").is_none());
+    }
+
+    #[test]
+    fn test_close_codes() {
+        assert!(matches!(close_error(4004), Some(Error::Authentication(_))));
+        assert!(matches!(close_error(4014), Some(Error::Configuration(_))));
+        assert!(close_error(1001).is_none());
+        assert!(resumable(1001));
+        assert!(!resumable(4009));
+        assert!(!resumable(4004));
+    }
+}
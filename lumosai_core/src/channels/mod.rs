@@ -0,0 +1,427 @@
+//! 聊天渠道
+//!
+//! 将代理部署为聊天机器人，无需自行编写对接代码：
+//!
+//! - [`SlackAdapter`]：通过Socket Mode接收消息，不需要公网地址
+//! - [`TeamsAdapter`]：通过Bot Framework接收消息，`server`特性下提供`POST /api/messages`路由
+//! - [`TelegramAdapter`]：通过Bot API长轮询接收消息
+//! - [`DiscordAdapter`]：通过Gateway连接接收消息
+//!
+//! 渠道适配器只负责收发消息，会话、附件和流式回复由[`ChannelBridge`]统一处理：
+//! 每个渠道线程对应一个会话，消息中的文件上传到[`AttachmentService`]后作为上下文提供给代理，
+//! 回复先发送占位消息，再随代理的流式输出按间隔编辑为最新内容。
+//!
+//! 渠道也可以在`lumos.yaml`的`channels`部分声明，见[`ChannelsConfig`]。
+//!
+//! ```rust,no_run
+//! use std::sync::Arc;
+//! use lumosai_core::channels::{ChannelBridge, SlackAdapter};
+//! # async fn run(agent: Arc<dyn lumosai_core::agent::trait_def::Agent>) -> lumosai_core::Result<()> {
+//! let bridge = Arc::new(ChannelBridge::new(agent));
+//! let slack = Arc::new(SlackAdapter::new("xoxb-...", "xapp-..."));
+//! slack.run(bridge).await
+//! # }
+//! ```
+
+pub mod config;
+pub mod slack;
+pub mod teams;
+pub mod telegram;
+pub mod discord;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use crate::agent::message_utils::{assistant_message, user_message};
+use crate::agent::session::{MemorySessionStorage, SessionManager, SessionStorage};
+use crate::agent::trait_def::Agent;
+use crate::agent::types::AgentStreamOptions;
+use crate::attachment::AttachmentService;
+use crate::error::Result;
+use crate::llm::Message;
+
+pub use config::{
+    ChannelsConfig, DiscordChannelConfig, SlackChannelConfig, TeamsChannelConfig, TelegramChannelConfig,
+};
+pub use slack::{markdown_to_mrkdwn, SlackAdapter};
+pub use teams::TeamsAdapter;
+pub use telegram::{TelegramAdapter, TelegramBot};
+pub use discord::DiscordAdapter;
+
+/// 默认的回复编辑间隔
+pub const DEFAULT_EDIT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 默认提供给代理的历史消息数
+pub const DEFAULT_MAX_HISTORY: usize = 50;
+
+/// 默认的占位回复
+pub const DEFAULT_PLACEHOLDER: &str = "_Thinking…_";
+
+/// 渠道消息中的文件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChannelFile {
+    /// 文件名
+    pub name: String,
+    /// MIME类型
+    pub mime_type: Option<String>,
+    /// 下载地址，Telegram中为`file_id`
+    pub url: String,
+    /// 文件大小（字节），渠道未提供时为空
+    pub size: Option<usize>,
+    /// 下载时是否需要机器人凭证
+    pub authenticated: bool,
+}
+
+/// 从渠道收到的用户消息
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChannelMessage {
+    /// 渠道名称，如`slack`、`teams`
+    pub channel: String,
+    /// 频道或会话ID
+    pub conversation_id: String,
+    /// 线程ID，同一线程中的消息属于同一会话
+    pub thread_id: String,
+    /// 消息ID，回复以此消息为目标
+    pub message_id: String,
+    /// 发送者ID
+    pub user_id: Option<String>,
+    /// 去掉@机器人后的消息文本
+    pub text: String,
+    /// 消息中的文件
+    pub files: Vec<ChannelFile>,
+    /// 渠道服务地址（Teams的`serviceUrl`）
+    pub service_url: Option<String>,
+}
+
+/// 渠道适配器，负责在渠道中发送、编辑回复和下载文件
+#[async_trait]
+pub trait ChannelAdapter: Send + Sync {
+    /// 渠道名称
+    fn name(&self) -> &str;
+
+    /// 在`message`所在线程中发送回复，返回回复的消息ID
+    async fn post_reply(&self, message: &ChannelMessage, text: &str) -> Result<String>;
+
+    /// 将已发送的回复编辑为`text`
+    async fn update_reply(&self, message: &ChannelMessage, reply_id: &str, text: &str) -> Result<()>;
+
+    /// 下载消息中的文件
+    async fn download(&self, file: &ChannelFile) -> Result<Vec<u8>>;
+}
+
+/// 连接渠道和代理
+///
+/// 同一会话中的消息按顺序处理，不同会话并发处理。
+pub struct ChannelBridge {
+    agent: Arc<dyn Agent>,
+    sessions: Arc<SessionManager>,
+    attachments: Option<Arc<AttachmentService>>,
+    edit_interval: Duration,
+    max_history: usize,
+    placeholder: String,
+    locks: Mutex<HashMap<String, Arc<Mutex<()>>>>,
+}
+
+impl ChannelBridge {
+    /// 创建桥接，会话保存在内存中
+    pub fn new(agent: Arc<dyn Agent>) -> Self {
+        Self {
+            agent,
+            sessions: Arc::new(SessionManager::new(Arc::new(MemorySessionStorage::new()))),
+            attachments: None,
+            edit_interval: DEFAULT_EDIT_INTERVAL,
+            max_history: DEFAULT_MAX_HISTORY,
+            placeholder: DEFAULT_PLACEHOLDER.to_string(),
+            locks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 将会话保存到指定存储
+    pub fn with_session_storage(self, storage: Arc<dyn SessionStorage>) -> Self {
+        self.with_sessions(Arc::new(SessionManager::new(storage)))
+    }
+
+    /// 使用已有的会话管理器，例如与HTTP服务共享
+    pub fn with_sessions(mut self, sessions: Arc<SessionManager>) -> Self {
+        self.sessions = sessions;
+        self
+    }
+
+    /// 设置附件服务，未设置时消息中的文件不提供给代理
+    pub fn with_attachments(mut self, attachments: Arc<AttachmentService>) -> Self {
+        self.attachments = Some(attachments);
+        self
+    }
+
+    /// 设置流式回复的最小编辑间隔，避免触发渠道的频率限制
+    pub fn with_edit_interval(mut self, edit_interval: Duration) -> Self {
+        self.edit_interval = edit_interval;
+        self
+    }
+
+    /// 设置提供给代理的历史消息数
+    pub fn with_max_history(mut self, max_history: usize) -> Self {
+        self.max_history = max_history;
+        self
+    }
+
+    /// 设置代理开始输出前显示的占位回复
+    pub fn with_placeholder(mut self, placeholder: impl Into<String>) -> Self {
+        self.placeholder = placeholder.into();
+        self
+    }
+
+    /// 会话管理器
+    pub fn sessions(&self) -> &Arc<SessionManager> {
+        &self.sessions
+    }
+
+    /// 消息所属的会话ID，格式为`渠道:会话:线程`
+    pub fn session_id(message: &ChannelMessage) -> String {
+        format!("{}:{}:{}", message.channel, message.conversation_id, message.thread_id)
+    }
+
+    /// 处理一条渠道消息，返回代理的完整回复
+    ///
+    /// 出错时将回复编辑为错误提示后返回错误。
+    pub async fn handle(&self, adapter: &dyn ChannelAdapter, message: &ChannelMessage) -> Result<String> {
+        let session_id = Self::session_id(message);
+        let lock = self.session_lock(&session_id).await;
+        let _guard = lock.lock().await;
+
+        let mut messages = match self.sessions.get_session(&session_id).await? {
+            Some(session) => session.messages,
+            None => {
+                self.sessions.create_session(
+                    session_id.clone(),
+                    self.agent.get_name().to_string(),
+                    message.user_id.clone(),
+                ).await?;
+                Vec::new()
+            },
+        };
+        let skip = messages.len().saturating_sub(self.max_history);
+        messages.drain(..skip);
+
+        let reply_id = adapter.post_reply(message, &self.placeholder).await?;
+        let result: Result<(Message, String)> = async {
+            let user = self.user_message(adapter, message).await?;
+            messages.push(user.clone());
+            let reply = self.stream_reply(adapter, message, &reply_id, &session_id, &messages).await?;
+            Ok((user, reply))
+        }.await;
+
+        match result {
+            Ok((user, reply)) => {
+                self.sessions.add_message(&session_id, user).await?;
+                self.sessions.add_message(&session_id, assistant_message(reply.clone())).await?;
+                Ok(reply)
+            },
+            Err(error) => {
+                let notice = format!("Sorry, something went wrong: {}", error.user_message());
+                if let Err(e) = adapter.update_reply(message, &reply_id, &notice).await {
+                    tracing::warn!("Failed to report error in {} reply: {}", adapter.name(), e);
+                }
+                Err(error)
+            },
+        }
+    }
+
+    /// 构造用户消息，文件内容作为上下文附在文本之后
+    async fn user_message(&self, adapter: &dyn ChannelAdapter, message: &ChannelMessage) -> Result<Message> {
+        let text = if message.text.is_empty() && !message.files.is_empty() {
+            "Please look at the attached files."
+        } else {
+            message.text.as_str()
+        };
+        if message.files.is_empty() {
+            return Ok(user_message(text));
+        }
+        let Some(service) = &self.attachments else {
+            let names = message.files.iter().map(|file| file.name.as_str()).collect::<Vec<_>>().join(", ");
+            return Ok(user_message(format!("{}\n\n(The user attached files that are not available: {})", text, names)));
+        };
+
+        let mut attachments = Vec::new();
+        for file in &message.files {
+            if file.size.is_some_and(|size| size > service.max_size()) {
+                return Err(crate::Error::InvalidInput(format!(
+                    "Attachment '{}' is larger than the limit of {} bytes", file.name, service.max_size()
+                )));
+            }
+            let data = adapter.download(file).await?;
+            attachments.push(service.upload(&file.name, file.mime_type.as_deref(), data, service.can_ingest()).await?);
+        }
+        let context = service.context(&attachments, text).await?;
+        Ok(user_message(format!("{}\n\n{}", text, context)).with_attachments(attachments))
+    }
+
+    /// 流式生成回复，按间隔编辑已发送的占位回复
+    async fn stream_reply(
+        &self,
+        adapter: &dyn ChannelAdapter,
+        message: &ChannelMessage,
+        reply_id: &str,
+        session_id: &str,
+        messages: &[Message],
+    ) -> Result<String> {
+        let options = AgentStreamOptions {
+            thread_id: Some(session_id.to_string()),
+            resource_id: message.user_id.clone(),
+            ..Default::default()
+        };
+        let mut stream = self.agent.stream(messages, &options).await?;
+
+        let mut reply = String::new();
+        let mut shown = 0;
+        let mut last_edit = Instant::now();
+        while let Some(chunk) = stream.next().await {
+            reply.push_str(&chunk?);
+            if last_edit.elapsed() >= self.edit_interval && reply.len() != shown && !reply.trim().is_empty() {
+                // 中间编辑失败（如触发频率限制）不影响最终回复
+                if let Err(e) = adapter.update_reply(message, reply_id, &reply).await {
+                    tracing::warn!("Failed to update {} reply: {}", adapter.name(), e);
+                }
+                shown = reply.len();
+                last_edit = Instant::now();
+            }
+        }
+
+        if reply.trim().is_empty() {
+            reply = "(no response)".to_string();
+        }
+        adapter.update_reply(message, reply_id, &reply).await?;
+        Ok(reply)
+    }
+
+    async fn session_lock(&self, session_id: &str) -> Arc<Mutex<()>> {
+        let mut locks = self.locks.lock().await;
+        // 移除没有消息在处理的会话
+        locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+        locks.entry(session_id.to_string()).or_default().clone()
+    }
+}
+
+/// 截断超过渠道长度限制的消息
+pub(crate) fn truncate_message(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(max_chars.saturating_sub(2)).collect();
+    truncated.push_str(" …");
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex as StdMutex;
+    use crate::agent::create_basic_agent;
+    use crate::llm::MockLlmProvider;
+    use crate::storage::LocalObjectStore;
+
+    #[derive(Default)]
+    struct RecordingAdapter {
+        replies: StdMutex<Vec<String>>,
+        edits: StdMutex<Vec<(String, String)>>,
+    }
+
+    #[async_trait]
+    impl ChannelAdapter for RecordingAdapter {
+        fn name(&self) -> &str {
+            "test"
+        }
+
+        async fn post_reply(&self, _message: &ChannelMessage, text: &str) -> Result<String> {
+            let mut replies = self.replies.lock().unwrap();
+            replies.push(text.to_string());
+            Ok(format!("reply-{}", replies.len()))
+        }
+
+        async fn update_reply(&self, _message: &ChannelMessage, reply_id: &str, text: &str) -> Result<()> {
+            self.edits.lock().unwrap().push((reply_id.to_string(), text.to_string()));
+            Ok(())
+        }
+
+        async fn download(&self, file: &ChannelFile) -> Result<Vec<u8>> {
+            Ok(format!("contents of {}", file.name).into_bytes())
+        }
+    }
+
+    fn channel_message(thread_id: &str, text: &str) -> ChannelMessage {
+        ChannelMessage {
+            channel: "test".to_string(),
+            conversation_id: "C1".to_string(),
+            thread_id: thread_id.to_string(),
+            message_id: format!("{}-{}", thread_id, text.len()),
+            user_id: Some("U1".to_string()),
+            text: text.to_string(),
+            files: Vec::new(),
+            service_url: None,
+        }
+    }
+
+    fn bridge(responses: Vec<&str>) -> ChannelBridge {
+        let llm = Arc::new(MockLlmProvider::new(responses.into_iter().map(str::to_string).collect()));
+        ChannelBridge::new(Arc::new(create_basic_agent("bot".to_string(), "Be helpful".to_string(), llm)))
+            .with_edit_interval(Duration::ZERO)
+    }
+
+    #[tokio::test]
+    async fn test_threads_map_to_sessions() {
+        let bridge = bridge(vec!["First answer", "Second answer", "Other thread"]);
+        let adapter = RecordingAdapter::default();
+
+        assert_eq!(bridge.handle(&adapter, &channel_message("t1", "hello")).await.unwrap(), "First answer");
+        assert_eq!(bridge.handle(&adapter, &channel_message("t1", "again")).await.unwrap(), "Second answer");
+        bridge.handle(&adapter, &channel_message("t2", "new thread")).await.unwrap();
+
+        let session = bridge.sessions().get_session("test:C1:t1").await.unwrap().unwrap();
+        let contents: Vec<_> = session.messages.iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(contents, vec!["hello", "First answer", "again", "Second answer"]);
+        let other = bridge.sessions().get_session("test:C1:t2").await.unwrap().unwrap();
+        assert_eq!(other.messages.len(), 2);
+
+        // 每条消息先发占位回复，最后一次编辑为完整回复
+        assert_eq!(adapter.replies.lock().unwrap().as_slice(), [DEFAULT_PLACEHOLDER; 3]);
+        let edits = adapter.edits.lock().unwrap();
+        let last_of_first = edits.iter().rev().find(|(id, _)| id == "reply-1").unwrap();
+        assert_eq!(last_of_first.1, "First answer");
+    }
+
+    #[tokio::test]
+    async fn test_files_become_attachments() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = Arc::new(AttachmentService::new(Arc::new(LocalObjectStore::new(dir.path()))));
+        let bridge = bridge(vec!["Summary"]).with_attachments(service);
+        let adapter = RecordingAdapter::default();
+
+        let mut message = channel_message("t1", "");
+        message.files.push(ChannelFile {
+            name: "notes.txt".to_string(),
+            mime_type: Some("text/plain".to_string()),
+            url: "https://files.example.com/notes.txt".to_string(),
+            size: Some(20),
+            authenticated: true,
+        });
+        bridge.handle(&adapter, &message).await.unwrap();
+
+        let session = bridge.sessions().get_session("test:C1:t1").await.unwrap().unwrap();
+        let user = &session.messages[0];
+        assert!(user.content.starts_with("Please look at the attached files."));
+        assert!(user.content.contains("contents of notes.txt"));
+        assert_eq!(user.attachments()[0].filename, "notes.txt");
+    }
+
+    #[test]
+    fn test_truncate_message() {
+        assert_eq!(truncate_message("short", 10), "short");
+        assert_eq!(truncate_message("a longer message", 8), "a long …");
+    }
+}
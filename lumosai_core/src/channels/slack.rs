@@ -0,0 +1,355 @@
+//! Slack适配器
+//!
+//! 通过[Socket Mode](https://api.slack.com/apis/socket-mode)接收事件：机器人主动建立WebSocket连接，
+//! 不需要公网可访问的回调地址。需要两个令牌：
+//!
+//! - 机器人令牌（`xoxb-`）：发送、编辑消息和下载文件，需要`chat:write`、`files:read`、
+//!   `app_mentions:read`和`im:history`权限
+//! - 应用令牌（`xapp-`）：建立Socket Mode连接，需要`connections:write`权限
+//!
+//! 频道中@机器人的消息和发给机器人的私信会交给代理处理，回复发在消息所在的线程中。
+
+use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::{SinkExt, StreamExt};
+use regex::Regex;
+use serde_json::{json, Value};
+use tokio::sync::Mutex;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+use crate::config::http_client_for;
+use crate::error::{Error, Result};
+use super::{truncate_message, ChannelAdapter, ChannelBridge, ChannelFile, ChannelMessage};
+
+/// Slack Web API地址
+pub const SLACK_API_URL: &str = "https://slack.com/api";
+
+/// 消息文本长度上限
+const MAX_MESSAGE_CHARS: usize = 39_000;
+
+/// 用于去重的最近事件数
+const RECENT_EVENTS: usize = 1000;
+
+/// 重连等待时间上限
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// 表示令牌无效的错误码
+const AUTH_ERRORS: &[&str] = &["invalid_auth", "not_authed", "account_inactive", "token_revoked", "token_expired"];
+
+/// Slack适配器
+pub struct SlackAdapter {
+    bot_token: String,
+    app_token: String,
+    api_url: String,
+    client: reqwest::Client,
+    recent_events: Mutex<(HashSet<String>, VecDeque<String>)>,
+}
+
+impl SlackAdapter {
+    /// 使用机器人令牌和应用令牌创建适配器
+    pub fn new(bot_token: impl Into<String>, app_token: impl Into<String>) -> Self {
+        Self {
+            bot_token: bot_token.into(),
+            app_token: app_token.into(),
+            api_url: SLACK_API_URL.to_string(),
+            client: http_client_for(SLACK_API_URL),
+            recent_events: Mutex::new((HashSet::new(), VecDeque::new())),
+        }
+    }
+
+    /// 设置Web API地址，用于测试或代理
+    pub fn with_api_url(mut self, api_url: impl Into<String>) -> Self {
+        self.api_url = api_url.into().trim_end_matches('/').to_string();
+        self.client = http_client_for(&self.api_url);
+        self
+    }
+
+    /// 连接Socket Mode并持续处理事件
+    ///
+    /// 连接断开或Slack要求刷新连接时自动重连，令牌无效时返回错误。
+    pub async fn run(self: Arc<Self>, bridge: Arc<ChannelBridge>) -> Result<()> {
+        let mut backoff = Duration::from_secs(1);
+        loop {
+            match self.clone().run_connection(bridge.clone()).await {
+                Ok(true) => backoff = Duration::from_secs(1),
+                Ok(false) => {},
+                Err(Error::Authentication(message)) => return Err(Error::Authentication(message)),
+                Err(e) => tracing::warn!("Slack Socket Mode connection failed: {}", e),
+            }
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(MAX_BACKOFF);
+        }
+    }
+
+    /// 处理一个连接直到断开，返回是否收到过`hello`
+    async fn run_connection(self: Arc<Self>, bridge: Arc<ChannelBridge>) -> Result<bool> {
+        let response = self.call_with(&self.app_token, "apps.connections.open", json!({})).await?;
+        let url = response["url"].as_str()
+            .ok_or_else(|| Error::Network("Slack did not return a Socket Mode URL".to_string()))?;
+        let (mut socket, _) = tokio_tungstenite::connect_async(url).await
+            .map_err(|e| Error::Network(format!("Failed to connect to Slack Socket Mode: {}", e)))?;
+
+        let mut connected = false;
+        while let Some(frame) = socket.next().await {
+            let frame = frame.map_err(|e| Error::Network(format!("Slack Socket Mode error: {}", e)))?;
+            let text = match frame {
+                WsMessage::Text(text) => text,
+                WsMessage::Close(_) => break,
+                _ => continue,
+            };
+            let Ok(envelope) = serde_json::from_str::<Value>(&text) else {
+                continue;
+            };
+
+            // 先确认再处理，避免Slack因超时重发
+            if let Some(envelope_id) = envelope["envelope_id"].as_str() {
+                socket.send(WsMessage::Text(json!({ "envelope_id": envelope_id }).to_string())).await
+                    .map_err(|e| Error::Network(format!("Failed to acknowledge Slack event: {}", e)))?;
+            }
+
+            match envelope["type"].as_str() {
+                Some("hello") => connected = true,
+                Some("disconnect") => break,
+                Some("events_api") => {
+                    let payload = &envelope["payload"];
+                    if let Some(event_id) = payload["event_id"].as_str() {
+                        if !self.first_delivery(event_id).await {
+                            continue;
+                        }
+                    }
+                    let Some(message) = parse_event(&payload["event"]) else {
+                        continue;
+                    };
+                    let adapter = self.clone();
+                    let bridge = bridge.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = bridge.handle(adapter.as_ref(), &message).await {
+                            tracing::warn!("Failed to handle Slack message: {}", e);
+                        }
+                    });
+                },
+                _ => {},
+            }
+        }
+        Ok(connected)
+    }
+
+    /// 记录事件ID，返回是否首次收到
+    async fn first_delivery(&self, event_id: &str) -> bool {
+        let mut recent = self.recent_events.lock().await;
+        let (seen, order) = &mut *recent;
+        if !seen.insert(event_id.to_string()) {
+            return false;
+        }
+        order.push_back(event_id.to_string());
+        if order.len() > RECENT_EVENTS {
+            if let Some(oldest) = order.pop_front() {
+                seen.remove(&oldest);
+            }
+        }
+        true
+    }
+
+    async fn call(&self, method: &str, body: Value) -> Result<Value> {
+        self.call_with(&self.bot_token, method, body).await
+    }
+
+    async fn call_with(&self, token: &str, method: &str, body: Value) -> Result<Value> {
+        let response = self.client
+            .post(format!("{}/{}", self.api_url, method))
+            .bearer_auth(token)
+            .json(&body)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(Error::Network(format!("Slack {} failed with {}", method, response.status())));
+        }
+        let response: Value = response.json().await?;
+        if response["ok"].as_bool() == Some(true) {
+            return Ok(response);
+        }
+        let error = response["error"].as_str().unwrap_or("unknown_error");
+        if AUTH_ERRORS.contains(&error) {
+            Err(Error::Authentication(format!("Slack {} failed: {}", method, error)))
+        } else {
+            Err(Error::Network(format!("Slack {} failed: {}", method, error)))
+        }
+    }
+}
+
+#[async_trait]
+impl ChannelAdapter for SlackAdapter {
+    fn name(&self) -> &str {
+        "slack"
+    }
+
+    async fn post_reply(&self, message: &ChannelMessage, text: &str) -> Result<String> {
+        let response = self.call("chat.postMessage", json!({
+            "channel": message.conversation_id,
+            "thread_ts": message.thread_id,
+            "text": truncate_message(&markdown_to_mrkdwn(text), MAX_MESSAGE_CHARS),
+        })).await?;
+        response["ts"].as_str()
+            .map(str::to_string)
+            .ok_or_else(|| Error::Network("Slack chat.postMessage returned no message timestamp".to_string()))
+    }
+
+    async fn update_reply(&self, message: &ChannelMessage, reply_id: &str, text: &str) -> Result<()> {
+        self.call("chat.update", json!({
+            "channel": message.conversation_id,
+            "ts": reply_id,
+            "text": truncate_message(&markdown_to_mrkdwn(text), MAX_MESSAGE_CHARS),
+        })).await?;
+        Ok(())
+    }
+
+    async fn download(&self, file: &ChannelFile) -> Result<Vec<u8>> {
+        let mut request = self.client.get(&file.url);
+        if file.authenticated {
+            request = request.bearer_auth(&self.bot_token);
+        }
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(Error::Network(format!("Downloading Slack file '{}' failed with {}", file.name, response.status())));
+        }
+        // 缺少files:read权限时Slack返回登录页面而不是文件
+        let is_html = response.headers().get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with("text/html"));
+        if is_html && !file.mime_type.as_deref().is_some_and(|mime| mime.starts_with("text/html")) {
+            return Err(Error::Authentication(format!(
+                "Slack did not return file '{}'; the bot token needs the files:read scope", file.name
+            )));
+        }
+        Ok(response.bytes().await?.to_vec())
+    }
+}
+
+/// 将Events API事件转换为渠道消息
+///
+/// 只处理@机器人的消息和私信，忽略机器人自己和其他机器人发送的消息。
+pub fn parse_event(event: &Value) -> Option<ChannelMessage> {
+    if event["bot_id"].is_string() {
+        return None;
+    }
+    match (event["type"].as_str()?, event["subtype"].as_str()) {
+        ("app_mention", _) => {},
+        ("message", None | Some("file_share")) if event["channel_type"] == "im" => {},
+        _ => return None,
+    }
+
+    let mention = Regex::new(r"<@[A-Z0-9]+(\|[^>]*)?>").expect("pattern is valid");
+    let text = mention.replace_all(event["text"].as_str().unwrap_or_default(), "").trim().to_string();
+    let files: Vec<_> = event["files"].as_array().into_iter().flatten()
+        .filter_map(|file| {
+            let url = file["url_private_download"].as_str().or_else(|| file["url_private"].as_str())?;
+            Some(ChannelFile {
+                name: file["name"].as_str().unwrap_or("file").to_string(),
+                mime_type: file["mimetype"].as_str().map(str::to_string),
+                url: url.to_string(),
+                size: file["size"].as_u64().map(|size| size as usize),
+                authenticated: true,
+            })
+        })
+        .collect();
+    if text.is_empty() && files.is_empty() {
+        return None;
+    }
+
+    let ts = event["ts"].as_str()?;
+    Some(ChannelMessage {
+        channel: "slack".to_string(),
+        conversation_id: event["channel"].as_str()?.to_string(),
+        thread_id: event["thread_ts"].as_str().unwrap_or(ts).to_string(),
+        message_id: ts.to_string(),
+        user_id: event["user"].as_str().map(str::to_string),
+        text,
+        files,
+        service_url: None,
+    })
+}
+
+/// 将Markdown转换为Slack的mrkdwn格式，代码块保持不变
+pub fn markdown_to_mrkdwn(markdown: &str) -> String {
+    let heading = Regex::new(r"^#{1,6}\s+(.+)$").expect("pattern is valid");
+    let bold = Regex::new(r"\*\*(.+?)\*\*").expect("pattern is valid");
+    let strike = Regex::new(r"~~(.+?)~~").expect("pattern is valid");
+    let link = Regex::new(r"\[([^\]]+)\]\((https?://[^)\s]+)\)").expect("pattern is valid");
+    let mut in_code = false;
+    markdown.lines()
+        .map(|line| {
+            if line.trim_start().starts_with("```") {
+                in_code = !in_code;
+                return line.to_string();
+            }
+            if in_code {
+                return line.to_string();
+            }
+            let line = heading.replace(line, "**$1**");
+            let line = bold.replace_all(&line, "*$1*");
+            let line = strike.replace_all(&line, "~$1~");
+            link.replace_all(&line, "<$2|$1>").into_owned()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_event() {
+        let mention = json!({
+            "type": "app_mention",
+            "channel": "C123",
+            "user": "U1",
+            "text": "<@U0BOT> summarize this",
+            "ts": "1700000000.000200",
+            "thread_ts": "1700000000.000100",
+            "files": [{
+                "name": "report.csv",
+                "mimetype": "text/csv",
+                "url_private_download": "https://files.slack.com/report.csv",
+                "size": 120,
+            }],
+        });
+        let message = parse_event(&mention).unwrap();
+        assert_eq!(message.text, "summarize this");
+        assert_eq!(message.conversation_id, "C123");
+        assert_eq!(message.thread_id, "1700000000.000100");
+        assert_eq!(message.message_id, "1700000000.000200");
+        assert_eq!(message.files[0].url, "https://files.slack.com/report.csv");
+        assert_eq!(message.files[0].size, Some(120));
+
+        // 频道中的普通消息、机器人消息和编辑事件被忽略
+        let direct = json!({ "type": "message", "channel_type": "im", "channel": "D1", "text": "hi", "ts": "1.0" });
+        assert_eq!(parse_event(&direct).unwrap().thread_id, "1.0");
+        let channel = json!({ "type": "message", "channel_type": "channel", "channel": "C1", "text": "hi", "ts": "1.0" });
+        assert!(parse_event(&channel).is_none());
+        let bot = json!({ "type": "message", "channel_type": "im", "bot_id": "B1", "channel": "D1", "text": "hi", "ts": "1.0" });
+        assert!(parse_event(&bot).is_none());
+        let edited = json!({ "type": "message", "subtype": "message_changed", "channel_type": "im", "channel": "D1", "ts": "1.0" });
+        assert!(parse_event(&edited).is_none());
+    }
+
+    #[test]
+    fn test_markdown_to_mrkdwn() {
+        let markdown = "## Result\nThis is **important** and ~~old~~, see [docs](https://example.com).\n```\n**kept**\n```";
+        assert_eq!(
+            markdown_to_mrkdwn(markdown),
+            "*Result*\nThis is *important* and ~old~, see <https://example.com|docs>.\n```\n**kept**\n```"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_events_are_skipped() {
+        let adapter = SlackAdapter::new("xoxb", "xapp");
+        assert!(adapter.first_delivery("Ev1").await);
+        assert!(!adapter.first_delivery("Ev1").await);
+        assert!(adapter.first_delivery("Ev2").await);
+    }
+}
@@ -0,0 +1,449 @@
+//! Microsoft Teams适配器
+//!
+//! 通过Bot Framework收发消息：Teams将消息以Activity的形式推送到机器人的消息端点，
+//! 机器人使用应用ID和密码换取的令牌调用`serviceUrl`上的Connector API回复和编辑消息。
+//!
+//! 推送请求带有Bot Framework签发的JWT，[`TeamsAdapter::verify_request`]按Bot Framework的
+//! OpenID配置获取公钥校验签名，并检查签发者、受众、有效期和`serviceUrl`。启用`server`特性后，
+//! [`TeamsAdapter::router`]提供完成校验的`POST /api/messages`路由。
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use base64::Engine;
+use regex::Regex;
+use ring::signature::{RsaPublicKeyComponents, RSA_PKCS1_2048_8192_SHA256};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::sync::Mutex;
+
+use crate::config::http_client_for;
+use crate::error::{Error, Result};
+use super::{truncate_message, ChannelAdapter, ChannelFile, ChannelMessage};
+
+/// Bot Framework的OpenID配置地址
+pub const BOT_FRAMEWORK_OPENID_URL: &str = "https://login.botframework.com/v1/.well-known/openidconfiguration";
+
+/// Bot Framework令牌的签发者
+pub const BOT_FRAMEWORK_ISSUER: &str = "https://api.botframework.com";
+
+/// 调用Connector API的令牌作用域
+const CONNECTOR_SCOPE: &str = "https://api.botframework.com/.default";
+
+/// 多租户机器人使用的租户
+const DEFAULT_TENANT: &str = "botframework.com";
+
+/// 允许的时钟偏差（秒）
+const CLOCK_SKEW_SECS: i64 = 300;
+
+/// 公钥缓存时间
+const KEYS_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// 消息文本长度上限
+const MAX_MESSAGE_CHARS: usize = 28_000;
+
+/// Teams中的文件附件类型
+const FILE_DOWNLOAD_INFO: &str = "application/vnd.microsoft.teams.file.download.info";
+
+/// JWKS中的一个公钥
+#[derive(Debug, Clone, Deserialize)]
+struct SigningKey {
+    kid: String,
+    n: String,
+    e: String,
+    #[serde(default)]
+    endorsements: Vec<String>,
+}
+
+/// Microsoft Teams适配器
+pub struct TeamsAdapter {
+    app_id: String,
+    app_password: String,
+    tenant: String,
+    client: reqwest::Client,
+    token: Mutex<Option<(String, Instant)>>,
+    keys: Mutex<Option<(HashMap<String, SigningKey>, Instant)>>,
+}
+
+impl TeamsAdapter {
+    /// 使用Azure Bot的应用ID和密码创建适配器
+    pub fn new(app_id: impl Into<String>, app_password: impl Into<String>) -> Self {
+        Self {
+            app_id: app_id.into(),
+            app_password: app_password.into(),
+            tenant: DEFAULT_TENANT.to_string(),
+            client: http_client_for(BOT_FRAMEWORK_ISSUER),
+            token: Mutex::new(None),
+            keys: Mutex::new(None),
+        }
+    }
+
+    /// 设置租户，单租户机器人需要设置为所在租户的ID
+    pub fn with_tenant(mut self, tenant: impl Into<String>) -> Self {
+        self.tenant = tenant.into();
+        self
+    }
+
+    /// 应用ID
+    pub fn app_id(&self) -> &str {
+        &self.app_id
+    }
+
+    /// 校验推送请求的`Authorization`头，`activity`为请求体
+    pub async fn verify_request(&self, authorization: Option<&str>, activity: &Value) -> Result<()> {
+        let token = authorization
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or_else(|| Error::Authentication("Missing bearer token".to_string()))?;
+        let parts: Vec<&str> = token.split('.').collect();
+        let [encoded_header, payload, signature] = parts[..] else {
+            return Err(Error::Authentication("Malformed bearer token".to_string()));
+        };
+
+        let header: Value = decode_segment(encoded_header)?;
+        if header["alg"] != "RS256" {
+            return Err(Error::Authentication(format!("Unsupported token algorithm {}", header["alg"])));
+        }
+        let kid = header["kid"].as_str()
+            .ok_or_else(|| Error::Authentication("Token has no key ID".to_string()))?;
+        let key = self.signing_key(kid).await?;
+        if let Some(channel_id) = activity["channelId"].as_str() {
+            if !key.endorsements.is_empty() && !key.endorsements.iter().any(|e| e == channel_id) {
+                return Err(Error::Authentication(format!("Signing key is not endorsed for channel '{}'", channel_id)));
+            }
+        }
+
+        let engine = base64::engine::general_purpose::URL_SAFE_NO_PAD;
+        let decode = |value: &str| engine.decode(value)
+            .map_err(|e| Error::Authentication(format!("Malformed signing key or token: {}", e)));
+        let public_key = RsaPublicKeyComponents { n: decode(&key.n)?, e: decode(&key.e)? };
+        public_key
+            .verify(&RSA_PKCS1_2048_8192_SHA256, format!("{}.{}", encoded_header, payload).as_bytes(), &decode(signature)?)
+            .map_err(|_| Error::Authentication("Invalid token signature".to_string()))?;
+
+        let claims: Value = decode_segment(payload)?;
+        check_claims(&claims, &self.app_id, activity["serviceUrl"].as_str(), chrono::Utc::now().timestamp())
+    }
+
+    /// 按ID获取签名公钥，未知ID时刷新缓存以应对密钥轮换
+    async fn signing_key(&self, kid: &str) -> Result<SigningKey> {
+        let mut keys = self.keys.lock().await;
+        if let Some((cached, fetched_at)) = &*keys {
+            if fetched_at.elapsed() < KEYS_TTL {
+                if let Some(key) = cached.get(kid) {
+                    return Ok(key.clone());
+                }
+            }
+        }
+
+        let config: Value = self.client.get(BOT_FRAMEWORK_OPENID_URL).send().await?.error_for_status()?.json().await?;
+        let jwks_uri = config["jwks_uri"].as_str()
+            .ok_or_else(|| Error::Network("Bot Framework OpenID configuration has no jwks_uri".to_string()))?;
+        let jwks: Value = self.client.get(jwks_uri).send().await?.error_for_status()?.json().await?;
+        let fetched: HashMap<String, SigningKey> = jwks["keys"].as_array().into_iter().flatten()
+            .filter_map(|key| serde_json::from_value::<SigningKey>(key.clone()).ok())
+            .map(|key| (key.kid.clone(), key))
+            .collect();
+        let key = fetched.get(kid).cloned();
+        *keys = Some((fetched, Instant::now()));
+        key.ok_or_else(|| Error::Authentication(format!("Unknown signing key '{}'", kid)))
+    }
+
+    /// 调用Connector API的访问令牌，过期前自动刷新
+    async fn access_token(&self) -> Result<String> {
+        let mut token = self.token.lock().await;
+        if let Some((value, expires_at)) = &*token {
+            if Instant::now() < *expires_at {
+                return Ok(value.clone());
+            }
+        }
+
+        let url = format!("https://login.microsoftonline.com/{}/oauth2/v2.0/token", self.tenant);
+        let response = self.client
+            .post(&url)
+            .form(&[
+                ("grant_type", "client_credentials"),
+                ("client_id", self.app_id.as_str()),
+                ("client_secret", self.app_password.as_str()),
+                ("scope", CONNECTOR_SCOPE),
+            ])
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(Error::Authentication(format!("Bot Framework token request failed with {}: {}", status, body)));
+        }
+        let response: Value = response.json().await?;
+        let value = response["access_token"].as_str()
+            .ok_or_else(|| Error::Authentication("Bot Framework returned no access token".to_string()))?
+            .to_string();
+        // 提前一分钟刷新
+        let lifetime = response["expires_in"].as_u64().unwrap_or(3600).saturating_sub(60);
+        *token = Some((value.clone(), Instant::now() + Duration::from_secs(lifetime)));
+        Ok(value)
+    }
+
+    /// Connector API中的活动地址
+    fn activity_url(message: &ChannelMessage, activity_id: &str) -> Result<reqwest::Url> {
+        let service_url = message.service_url.as_deref()
+            .ok_or_else(|| Error::InvalidInput("Teams message has no service URL".to_string()))?;
+        let mut url = reqwest::Url::parse(service_url)
+            .map_err(|e| Error::InvalidInput(format!("Invalid Teams service URL '{}': {}", service_url, e)))?;
+        url.path_segments_mut()
+            .map_err(|_| Error::InvalidInput(format!("Invalid Teams service URL '{}'", service_url)))?
+            .pop_if_empty()
+            .extend(["v3", "conversations", message.conversation_id.as_str(), "activities", activity_id]);
+        Ok(url)
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder, action: &str) -> Result<Value> {
+        let response = request.bearer_auth(self.access_token().await?).send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(Error::Network(format!("Teams {} failed with {}: {}", action, status, body)));
+        }
+        Ok(response.json().await.unwrap_or(Value::Null))
+    }
+
+    /// 以Teams应用消息端点的形式处理推送
+    #[cfg(feature = "server")]
+    pub fn router(self: std::sync::Arc<Self>, bridge: std::sync::Arc<super::ChannelBridge>) -> axum::Router {
+        use axum::routing::post;
+
+        axum::Router::new()
+            .route("/api/messages", post(receive_activity))
+            .with_state((self, bridge))
+    }
+}
+
+#[cfg(feature = "server")]
+async fn receive_activity(
+    axum::extract::State((adapter, bridge)): axum::extract::State<(std::sync::Arc<TeamsAdapter>, std::sync::Arc<super::ChannelBridge>)>,
+    headers: axum::http::HeaderMap,
+    axum::Json(activity): axum::Json<Value>,
+) -> axum::http::StatusCode {
+    let authorization = headers.get(axum::http::header::AUTHORIZATION).and_then(|value| value.to_str().ok());
+    if let Err(e) = adapter.verify_request(authorization, &activity).await {
+        tracing::warn!("Rejected Teams activity: {}", e);
+        return axum::http::StatusCode::UNAUTHORIZED;
+    }
+    // Bot Framework要求尽快响应，回复通过Connector API异步发送
+    if let Some(message) = parse_activity(&activity) {
+        tokio::spawn(async move {
+            if let Err(e) = bridge.handle(adapter.as_ref(), &message).await {
+                tracing::warn!("Failed to handle Teams message: {}", e);
+            }
+        });
+    }
+    axum::http::StatusCode::OK
+}
+
+#[async_trait]
+impl ChannelAdapter for TeamsAdapter {
+    fn name(&self) -> &str {
+        "teams"
+    }
+
+    async fn post_reply(&self, message: &ChannelMessage, text: &str) -> Result<String> {
+        let body = json!({
+            "type": "message",
+            "text": truncate_message(text, MAX_MESSAGE_CHARS),
+            "textFormat": "markdown",
+            "replyToId": message.message_id,
+        });
+        let url = Self::activity_url(message, &message.message_id)?;
+        let response = self.send(self.client.post(url).json(&body), "reply").await?;
+        response["id"].as_str()
+            .map(str::to_string)
+            .ok_or_else(|| Error::Network("Teams did not return the ID of the reply".to_string()))
+    }
+
+    async fn update_reply(&self, message: &ChannelMessage, reply_id: &str, text: &str) -> Result<()> {
+        let body = json!({
+            "type": "message",
+            "id": reply_id,
+            "text": truncate_message(text, MAX_MESSAGE_CHARS),
+            "textFormat": "markdown",
+        });
+        let url = Self::activity_url(message, reply_id)?;
+        self.send(self.client.put(url).json(&body), "update").await?;
+        Ok(())
+    }
+
+    async fn download(&self, file: &ChannelFile) -> Result<Vec<u8>> {
+        let mut request = self.client.get(&file.url);
+        if file.authenticated {
+            request = request.bearer_auth(self.access_token().await?);
+        }
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(Error::Network(format!("Downloading Teams file '{}' failed with {}", file.name, response.status())));
+        }
+        Ok(response.bytes().await?.to_vec())
+    }
+}
+
+/// 将Activity转换为渠道消息，非消息类型的Activity返回`None`
+pub fn parse_activity(activity: &Value) -> Option<ChannelMessage> {
+    if activity["type"] != "message" {
+        return None;
+    }
+    let mention = Regex::new(r"(?s)<at>.*?</at>").expect("pattern is valid");
+    let text = mention.replace_all(activity["text"].as_str().unwrap_or_default(), "")
+        .replace("&nbsp;", " ")
+        .trim()
+        .to_string();
+
+    let files: Vec<_> = activity["attachments"].as_array().into_iter().flatten()
+        .filter_map(|attachment| {
+            let content_type = attachment["contentType"].as_str()?;
+            if content_type == FILE_DOWNLOAD_INFO {
+                // 下载地址已带授权，不需要机器人令牌
+                let content = &attachment["content"];
+                Some(ChannelFile {
+                    name: attachment["name"].as_str().unwrap_or("file").to_string(),
+                    mime_type: None,
+                    url: content["downloadUrl"].as_str()?.to_string(),
+                    size: None,
+                    authenticated: false,
+                })
+            } else if content_type.starts_with("image/") {
+                let extension = content_type.trim_start_matches("image/");
+                Some(ChannelFile {
+                    name: attachment["name"].as_str().map_or_else(|| format!("image.{}", extension), str::to_string),
+                    mime_type: Some(content_type.to_string()),
+                    url: attachment["contentUrl"].as_str()?.to_string(),
+                    size: None,
+                    authenticated: true,
+                })
+            } else {
+                None
+            }
+        })
+        .collect();
+    if text.is_empty() && files.is_empty() {
+        return None;
+    }
+
+    let conversation_id = activity["conversation"]["id"].as_str()?;
+    // 频道中的回复串使用`;messageid=`区分，个人聊天整个会话为一个线程
+    let thread_id = conversation_id.split_once(";messageid=").map_or(conversation_id, |(_, id)| id);
+    let from = &activity["from"];
+    Some(ChannelMessage {
+        channel: "teams".to_string(),
+        conversation_id: conversation_id.to_string(),
+        thread_id: thread_id.to_string(),
+        message_id: activity["id"].as_str()?.to_string(),
+        user_id: from["aadObjectId"].as_str().or_else(|| from["id"].as_str()).map(str::to_string),
+        text,
+        files,
+        service_url: activity["serviceUrl"].as_str().map(str::to_string),
+    })
+}
+
+/// 检查令牌声明：签发者、受众、有效期，以及签发时的`serviceUrl`与Activity一致
+pub fn check_claims(claims: &Value, app_id: &str, service_url: Option<&str>, now: i64) -> Result<()> {
+    if claims["iss"] != BOT_FRAMEWORK_ISSUER {
+        return Err(Error::Authentication(format!("Unexpected token issuer {}", claims["iss"])));
+    }
+    if claims["aud"] != app_id {
+        return Err(Error::Authentication(format!("Token is not issued for app '{}'", app_id)));
+    }
+    let expires_at = claims["exp"].as_i64()
+        .ok_or_else(|| Error::Authentication("Token has no expiry".to_string()))?;
+    if expires_at + CLOCK_SKEW_SECS < now {
+        return Err(Error::Authentication("Token has expired".to_string()));
+    }
+    if claims["nbf"].as_i64().is_some_and(|not_before| not_before - CLOCK_SKEW_SECS > now) {
+        return Err(Error::Authentication("Token is not valid yet".to_string()));
+    }
+    let claimed = claims["serviceurl"].as_str().or_else(|| claims["serviceUrl"].as_str());
+    if let (Some(claimed), Some(service_url)) = (claimed, service_url) {
+        if claimed.trim_end_matches('/') != service_url.trim_end_matches('/') {
+            return Err(Error::Authentication("Token was issued for a different service URL".to_string()));
+        }
+    }
+    Ok(())
+}
+
+fn decode_segment(segment: &str) -> Result<Value> {
+    let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD.decode(segment.trim_end_matches('='))
+        .map_err(|e| Error::Authentication(format!("Malformed bearer token: {}", e)))?;
+    serde_json::from_slice(&bytes).map_err(|e| Error::Authentication(format!("Malformed bearer token: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_activity() {
+        let activity = json!({
+            "type": "message",
+            "id": "1700000000123",
+            "channelId": "msteams",
+            "serviceUrl": "https://smba.trafficmanager.net/emea/",
+            "text": "<at>Lumos</at> what does this say?",
+            "from": { "id": "29:abc", "aadObjectId": "user-1" },
+            "conversation": { "id": "19:channel@thread.tacv2;messageid=1699999999000" },
+            "attachments": [
+                { "contentType": "text/html", "content": "<p>what does this say?</p>" },
+                {
+                    "contentType": FILE_DOWNLOAD_INFO,
+                    "name": "plan.docx",
+                    "content": { "downloadUrl": "https://contoso.sharepoint.com/plan.docx", "fileType": "docx" },
+                },
+                { "contentType": "image/png", "contentUrl": "https://smba.trafficmanager.net/img/1" },
+            ],
+        });
+        let message = parse_activity(&activity).unwrap();
+        assert_eq!(message.text, "what does this say?");
+        assert_eq!(message.thread_id, "1699999999000");
+        assert_eq!(message.user_id.as_deref(), Some("user-1"));
+        assert_eq!(message.files.len(), 2);
+        assert!(!message.files[0].authenticated);
+        assert_eq!(message.files[1].name, "image.png");
+        assert!(message.files[1].authenticated);
+
+        let url = TeamsAdapter::activity_url(&message, "reply 1").unwrap();
+        assert_eq!(
+            url.as_str(),
+            "https://smba.trafficmanager.net/emea/v3/conversations/19:channel@thread.tacv2;messageid=1699999999000/activities/reply%201"
+        );
+
+        assert!(parse_activity(&json!({ "type": "conversationUpdate" })).is_none());
+    }
+
+    #[test]
+    fn test_check_claims() {
+        let now = 1_700_000_000;
+        let claims = json!({
+            "iss": BOT_FRAMEWORK_ISSUER,
+            "aud": "app-id",
+            "exp": now + 3600,
+            "nbf": now - 10,
+            "serviceurl": "https://smba.trafficmanager.net/emea/",
+        });
+        let service_url = Some("https://smba.trafficmanager.net/emea");
+        assert!(check_claims(&claims, "app-id", service_url, now).is_ok());
+        assert!(check_claims(&claims, "other-app", service_url, now).is_err());
+        assert!(check_claims(&claims, "app-id", Some("https://evil.example.com"), now).is_err());
+        assert!(check_claims(&claims, "app-id", service_url, now + 7200).is_err());
+
+        let mut forged = claims.clone();
+        forged["iss"] = json!("https://sts.windows.net/tenant/");
+        assert!(matches!(check_claims(&forged, "app-id", service_url, now), Err(Error::Authentication(_))));
+    }
+
+    #[tokio::test]
+    async fn test_verify_request_rejects_malformed_tokens() {
+        let adapter = TeamsAdapter::new("app-id", "secret");
+        let activity = json!({ "type": "message" });
+        assert!(adapter.verify_request(None, &activity).await.is_err());
+        assert!(adapter.verify_request(Some("Bearer not-a-jwt"), &activity).await.is_err());
+        let unsigned = format!("Bearer {}.e30.", base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(r#"{"alg":"none"}"#));
+        assert!(matches!(adapter.verify_request(Some(&unsigned), &activity).await, Err(Error::Authentication(_))));
+    }
+}
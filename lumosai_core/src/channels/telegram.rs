@@ -0,0 +1,321 @@
+//! Telegram适配器
+//!
+//! 通过[Bot API](https://core.telegram.org/bots/api)的`getUpdates`长轮询接收消息，不需要公网地址；
+//! 机器人设置了Webhook时长轮询不可用，需要先删除Webhook。
+//!
+//! 私聊中的消息全部交给代理处理，群组中只处理@机器人或回复机器人的消息。话题群组中
+//! 每个话题对应一个会话，其余聊天整个聊天对应一个会话。Telegram的文件以`file_id`标识，
+//! [`ChannelFile::url`]中保存的是`file_id`，下载时再换取实际地址。
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use crate::config::http_client_for;
+use crate::error::{Error, Result};
+use super::{truncate_message, ChannelAdapter, ChannelBridge, ChannelFile, ChannelMessage};
+
+/// Telegram Bot API地址
+pub const TELEGRAM_API_URL: &str = "https://api.telegram.org";
+
+/// 消息文本长度上限
+const MAX_MESSAGE_CHARS: usize = 4096;
+
+/// 长轮询等待时间（秒）
+const POLL_TIMEOUT_SECS: u64 = 30;
+
+/// 重试等待时间上限
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// 机器人自身的信息，用于识别群组中@机器人和回复机器人的消息
+#[derive(Debug, Clone, PartialEq)]
+pub struct TelegramBot {
+    /// 机器人的用户ID
+    pub id: i64,
+    /// 机器人的用户名，不含`@`
+    pub username: String,
+}
+
+/// Telegram适配器
+pub struct TelegramAdapter {
+    bot_token: String,
+    api_url: String,
+    client: reqwest::Client,
+}
+
+impl TelegramAdapter {
+    /// 使用BotFather签发的令牌创建适配器
+    pub fn new(bot_token: impl Into<String>) -> Self {
+        Self {
+            bot_token: bot_token.into(),
+            api_url: TELEGRAM_API_URL.to_string(),
+            client: http_client_for(TELEGRAM_API_URL),
+        }
+    }
+
+    /// 设置Bot API地址，例如自建的Bot API服务
+    pub fn with_api_url(mut self, api_url: impl Into<String>) -> Self {
+        self.api_url = api_url.into().trim_end_matches('/').to_string();
+        self.client = http_client_for(&self.api_url);
+        self
+    }
+
+    /// 长轮询接收消息并持续处理
+    ///
+    /// 网络错误时等待后重试，令牌无效或轮询被Webhook占用时返回错误。
+    pub async fn run(self: Arc<Self>, bridge: Arc<ChannelBridge>) -> Result<()> {
+        let me = self.call("getMe", json!({})).await?;
+        let bot = TelegramBot {
+            id: me["id"].as_i64().unwrap_or_default(),
+            username: me["username"].as_str().unwrap_or_default().to_string(),
+        };
+
+        let mut offset = 0;
+        let mut backoff = Duration::from_secs(1);
+        loop {
+            let updates = match self.poll(offset).await {
+                Ok(updates) => updates,
+                Err(e @ (Error::Authentication(_) | Error::Configuration(_))) => return Err(e),
+                Err(e) => {
+                    tracing::warn!("Telegram getUpdates failed: {}", e);
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(MAX_BACKOFF);
+                    continue;
+                },
+            };
+            backoff = Duration::from_secs(1);
+
+            for update in updates {
+                if let Some(update_id) = update["update_id"].as_i64() {
+                    offset = offset.max(update_id + 1);
+                }
+                let Some(message) = parse_update(&update, &bot) else {
+                    continue;
+                };
+                let adapter = self.clone();
+                let bridge = bridge.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = bridge.handle(adapter.as_ref(), &message).await {
+                        tracing::warn!("Failed to handle Telegram message: {}", e);
+                    }
+                });
+            }
+        }
+    }
+
+    async fn poll(&self, offset: i64) -> Result<Vec<Value>> {
+        let body = json!({ "offset": offset, "timeout": POLL_TIMEOUT_SECS, "allowed_updates": ["message"] });
+        let request = self.client
+            .post(self.method_url("getUpdates"))
+            .timeout(Duration::from_secs(POLL_TIMEOUT_SECS + 10))
+            .json(&body);
+        let result = Self::result("getUpdates", request.send().await?.json().await?)?;
+        Ok(result.as_array().cloned().unwrap_or_default())
+    }
+
+    async fn call(&self, method: &str, body: Value) -> Result<Value> {
+        let response = self.client.post(self.method_url(method)).json(&body).send().await?;
+        Self::result(method, response.json().await?)
+    }
+
+    fn method_url(&self, method: &str) -> String {
+        format!("{}/bot{}/{}", self.api_url, self.bot_token, method)
+    }
+
+    /// 取出Bot API响应中的`result`
+    fn result(method: &str, response: Value) -> Result<Value> {
+        if response["ok"].as_bool() == Some(true) {
+            return Ok(response["result"].clone());
+        }
+        let description = response["description"].as_str().unwrap_or("unknown error");
+        let message = format!("Telegram {} failed: {}", method, description);
+        match response["error_code"].as_i64() {
+            Some(401) | Some(404) => Err(Error::Authentication(message)),
+            // 另一个实例在轮询，或者设置了Webhook
+            Some(409) => Err(Error::Configuration(message)),
+            _ => Err(Error::Network(message)),
+        }
+    }
+}
+
+#[async_trait]
+impl ChannelAdapter for TelegramAdapter {
+    fn name(&self) -> &str {
+        "telegram"
+    }
+
+    async fn post_reply(&self, message: &ChannelMessage, text: &str) -> Result<String> {
+        let mut body = json!({
+            "chat_id": message.conversation_id,
+            "text": truncate_message(text, MAX_MESSAGE_CHARS),
+            "reply_parameters": { "message_id": message.message_id.parse::<i64>().unwrap_or_default(), "allow_sending_without_reply": true },
+        });
+        if message.thread_id != message.conversation_id {
+            body["message_thread_id"] = json!(message.thread_id.parse::<i64>().unwrap_or_default());
+        }
+        let sent = self.call("sendMessage", body).await?;
+        sent["message_id"].as_i64()
+            .map(|id| id.to_string())
+            .ok_or_else(|| Error::Network("Telegram sendMessage returned no message ID".to_string()))
+    }
+
+    async fn update_reply(&self, message: &ChannelMessage, reply_id: &str, text: &str) -> Result<()> {
+        let body = json!({
+            "chat_id": message.conversation_id,
+            "message_id": reply_id.parse::<i64>().unwrap_or_default(),
+            "text": truncate_message(text, MAX_MESSAGE_CHARS),
+        });
+        match self.call("editMessageText", body).await {
+            // 内容没有变化时Telegram返回错误，视为成功
+            Err(Error::Network(e)) if e.contains("message is not modified") => Ok(()),
+            result => result.map(|_| ()),
+        }
+    }
+
+    async fn download(&self, file: &ChannelFile) -> Result<Vec<u8>> {
+        let info = self.call("getFile", json!({ "file_id": file.url })).await?;
+        let path = info["file_path"].as_str().ok_or_else(|| Error::Network(format!(
+            "Telegram file '{}' cannot be downloaded; bots can only download files up to 20 MB", file.name
+        )))?;
+        let url = format!("{}/file/bot{}/{}", self.api_url, self.bot_token, path);
+        let response = self.client.get(url).send().await?;
+        if !response.status().is_success() {
+            return Err(Error::Network(format!("Downloading Telegram file '{}' failed with {}", file.name, response.status())));
+        }
+        Ok(response.bytes().await?.to_vec())
+    }
+}
+
+/// 将更新转换为渠道消息
+///
+/// 忽略其他机器人的消息，群组中只处理@机器人或回复机器人的消息。
+pub fn parse_update(update: &Value, bot: &TelegramBot) -> Option<ChannelMessage> {
+    let message = &update["message"];
+    if message["from"]["is_bot"].as_bool() == Some(true) {
+        return None;
+    }
+    let text = message["text"].as_str().or_else(|| message["caption"].as_str()).unwrap_or_default();
+    let mention = format!("@{}", bot.username);
+    if message["chat"]["type"] != "private" {
+        let mentioned = !bot.username.is_empty() && text.contains(&mention);
+        let replied = message["reply_to_message"]["from"]["id"].as_i64() == Some(bot.id);
+        if !mentioned && !replied {
+            return None;
+        }
+    }
+    let text = if bot.username.is_empty() { text.trim().to_string() } else { text.replace(&mention, "").trim().to_string() };
+
+    let mut files = Vec::new();
+    let document = &message["document"];
+    if let Some(file_id) = document["file_id"].as_str() {
+        files.push(ChannelFile {
+            name: document["file_name"].as_str().unwrap_or("document").to_string(),
+            mime_type: document["mime_type"].as_str().map(str::to_string),
+            url: file_id.to_string(),
+            size: document["file_size"].as_u64().map(|size| size as usize),
+            authenticated: true,
+        });
+    }
+    // 同一张图片有多种尺寸，最后一个最大
+    if let Some(photo) = message["photo"].as_array().and_then(|sizes| sizes.last()) {
+        files.push(ChannelFile {
+            name: "photo.jpg".to_string(),
+            mime_type: Some("image/jpeg".to_string()),
+            url: photo["file_id"].as_str()?.to_string(),
+            size: photo["file_size"].as_u64().map(|size| size as usize),
+            authenticated: true,
+        });
+    }
+    if text.is_empty() && files.is_empty() {
+        return None;
+    }
+
+    let chat_id = message["chat"]["id"].as_i64()?.to_string();
+    let thread_id = message["message_thread_id"].as_i64().map_or_else(|| chat_id.clone(), |id| id.to_string());
+    Some(ChannelMessage {
+        channel: "telegram".to_string(),
+        conversation_id: chat_id,
+        thread_id,
+        message_id: message["message_id"].as_i64()?.to_string(),
+        user_id: message["from"]["id"].as_i64().map(|id| id.to_string()),
+        text,
+        files,
+        service_url: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bot() -> TelegramBot {
+        TelegramBot { id: 42, username: "lumos_bot".to_string() }
+    }
+
+    #[test]
+    fn test_parse_update() {
+        let private = json!({
+            "update_id": 1,
+            "message": {
+                "message_id": 7,
+                "from": { "id": 1001, "is_bot": false },
+                "chat": { "id": 1001, "type": "private" },
+                "caption": "what is in this file?",
+                "document": { "file_id": "BQAD", "file_name": "notes.txt", "mime_type": "text/plain", "file_size": 12 },
+            },
+        });
+        let message = parse_update(&private, &bot()).unwrap();
+        assert_eq!(message.text, "what is in this file?");
+        assert_eq!(message.conversation_id, "1001");
+        assert_eq!(message.thread_id, "1001");
+        assert_eq!(message.message_id, "7");
+        assert_eq!(message.files[0].url, "BQAD");
+
+        let group = |text: &str, reply_to: Option<i64>| json!({
+            "update_id": 2,
+            "message": {
+                "message_id": 8,
+                "message_thread_id": 3,
+                "from": { "id": 1001, "is_bot": false },
+                "chat": { "id": -100200, "type": "supergroup" },
+                "text": text,
+                "reply_to_message": reply_to.map(|id| json!({ "from": { "id": id } })),
+            },
+        });
+        let mentioned = parse_update(&group("@lumos_bot summarize", None), &bot()).unwrap();
+        assert_eq!(mentioned.text, "summarize");
+        assert_eq!(mentioned.thread_id, "3");
+        assert!(parse_update(&group("thanks", Some(42)), &bot()).is_some());
+        assert!(parse_update(&group("unrelated chatter", None), &bot()).is_none());
+        assert!(parse_update(&group("reply to someone else", Some(7)), &bot()).is_none());
+    }
+
+    #[test]
+    fn test_photo_uses_largest_size() {
+        let update = json!({
+            "message": {
+                "message_id": 9,
+                "from": { "id": 1001, "is_bot": false },
+                "chat": { "id": 1001, "type": "private" },
+                "photo": [{ "file_id": "small", "file_size": 100 }, { "file_id": "large", "file_size": 900 }],
+            },
+        });
+        let message = parse_update(&update, &bot()).unwrap();
+        assert_eq!(message.text, "");
+        assert_eq!(message.files[0].url, "large");
+        assert_eq!(message.files[0].mime_type.as_deref(), Some("image/jpeg"));
+    }
+
+    #[test]
+    fn test_api_errors() {
+        let error = |code: i64, description: &str| {
+            TelegramAdapter::result("sendMessage", json!({ "ok": false, "error_code": code, "description": description }))
+        };
+        assert!(matches!(error(401, "Unauthorized"), Err(Error::Authentication(_))));
+        assert!(matches!(error(409, "Conflict: can't use getUpdates method while webhook is active"), Err(Error::Configuration(_))));
+        assert!(matches!(error(429, "Too Many Requests: retry after 3"), Err(Error::Network(_))));
+        assert_eq!(TelegramAdapter::result("getMe", json!({ "ok": true, "result": { "id": 42 } })).unwrap()["id"], 42);
+    }
+}
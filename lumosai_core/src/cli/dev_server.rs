@@ -192,16 +192,20 @@ impl DevServer {
     async fn run_server_loop(&self) -> Result<()> {
         let mut interval = interval(Duration::from_secs(1));
         
+        let shutdown = crate::app::shutdown_signal();
+        tokio::pin!(shutdown);
+        
         while *self.is_running.read().await {
-            interval.tick().await;
-            
-            // Perform periodic tasks
-            self.perform_health_checks().await?;
-            
-            // Check for shutdown signal
-            if tokio::signal::ctrl_c().await.is_ok() {
-                CliUtils::info("Shutdown signal received");
-                break;
+            tokio::select! {
+                _ = interval.tick() => {
+                    // Perform periodic tasks
+                    self.perform_health_checks().await?;
+                }
+                // Stop on Ctrl+C or SIGTERM
+                _ = &mut shutdown => {
+                    CliUtils::info("Shutdown signal received");
+                    break;
+                }
             }
         }
         
@@ -0,0 +1,195 @@
+//! Environment variable interpolation and overrides for configuration files
+//!
+//! Configuration files may reference environment variables using shell-like
+//! syntax, which is expanded before the file is parsed:
+//!
+//! - `${VAR}` - value of `VAR`, error if unset
+//! - `${VAR:-default}` - value of `VAR`, or `default` if unset or empty
+//! - `${VAR:?message}` - value of `VAR`, or fail with `message`
+//! - `$$` - a literal `$`
+//!
+//! After parsing, variables prefixed with `LUMOS__` override individual
+//! fields, using `__` as the path separator:
+//! `LUMOS__AGENTS__ASSISTANT__MODEL=gpt-4o` sets `agents.assistant.model`.
+
+use crate::{Error, Result};
+
+/// Prefix for environment variables that override configuration fields
+pub const ENV_OVERRIDE_PREFIX: &str = "LUMOS__";
+
+/// Expand `${VAR}` style references in `content` using the process environment
+pub fn interpolate_env(content: &str) -> Result<String> {
+    interpolate_with(content, |name| std::env::var(name).ok())
+}
+
+/// Expand `${VAR}` style references using a custom variable lookup
+pub fn interpolate_with<F>(content: &str, lookup: F) -> Result<String>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut output = String::with_capacity(content.len());
+    let mut rest = content;
+
+    while let Some(pos) = rest.find('$') {
+        output.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+
+        if let Some(stripped) = after.strip_prefix('$') {
+            output.push('$');
+            rest = stripped;
+            continue;
+        }
+
+        let Some(body) = after.strip_prefix('{') else {
+            output.push('$');
+            rest = after;
+            continue;
+        };
+
+        let end = body.find('}').ok_or_else(|| {
+            Error::Configuration(format!(
+                "Unterminated variable reference: ${{{}",
+                body.lines().next().unwrap_or_default()
+            ))
+        })?;
+        let expr = &body[..end];
+        output.push_str(&expand_expression(expr, &lookup)?);
+        rest = &body[end + 1..];
+    }
+
+    output.push_str(rest);
+    Ok(output)
+}
+
+fn expand_expression<F>(expr: &str, lookup: &F) -> Result<String>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let (name, modifier) = match expr.find(":-").or_else(|| expr.find(":?")) {
+        Some(idx) => (&expr[..idx], Some((&expr[idx..idx + 2], &expr[idx + 2..]))),
+        None => (expr, None),
+    };
+
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(Error::Configuration(format!(
+            "Invalid environment variable name in '${{{}}}'",
+            expr
+        )));
+    }
+
+    let value = lookup(name).filter(|v| !v.is_empty());
+    match (value, modifier) {
+        (Some(value), _) => Ok(value),
+        (None, Some((":-", default))) => Ok(default.to_string()),
+        (None, Some((_, message))) if !message.is_empty() => Err(Error::Configuration(format!(
+            "Environment variable '{}' is required: {}",
+            name, message
+        ))),
+        (None, _) => Err(Error::Configuration(format!(
+            "Environment variable '{}' referenced in configuration is not set",
+            name
+        ))),
+    }
+}
+
+/// Apply `LUMOS__...` environment overrides to a parsed configuration tree
+pub fn apply_env_overrides(value: &mut serde_yaml::Value) {
+    let overrides: Vec<(String, String)> = std::env::vars()
+        .filter(|(name, _)| name.starts_with(ENV_OVERRIDE_PREFIX))
+        .collect();
+    apply_overrides(value, overrides);
+}
+
+/// Apply `(VARIABLE, value)` overrides to a configuration tree
+pub fn apply_overrides<I>(value: &mut serde_yaml::Value, overrides: I)
+where
+    I: IntoIterator<Item = (String, String)>,
+{
+    for (name, raw) in overrides {
+        let Some(path) = name.strip_prefix(ENV_OVERRIDE_PREFIX) else {
+            continue;
+        };
+        let segments: Vec<String> = path
+            .split("__")
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_lowercase())
+            .collect();
+        if segments.is_empty() {
+            continue;
+        }
+
+        // Parse scalars so numbers and booleans keep their types
+        let parsed = serde_yaml::from_str::<serde_yaml::Value>(&raw)
+            .ok()
+            .filter(|v| !v.is_mapping() && !v.is_sequence())
+            .unwrap_or(serde_yaml::Value::String(raw));
+        set_path(value, &segments, parsed);
+    }
+}
+
+fn set_path(root: &mut serde_yaml::Value, segments: &[String], new_value: serde_yaml::Value) {
+    let mut current = root;
+    for segment in segments {
+        if !current.is_mapping() {
+            *current = serde_yaml::Value::Mapping(serde_yaml::Mapping::new());
+        }
+        let map = current.as_mapping_mut().expect("value was just made a mapping");
+        let key = serde_yaml::Value::String(segment.clone());
+        current = map.entry(key).or_insert(serde_yaml::Value::Null);
+    }
+    *current = new_value;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn lookup(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn test_interpolation() {
+        let vars = lookup(&[("MODEL", "gpt-4"), ("EMPTY", "")]);
+
+        assert_eq!(interpolate_with("model: ${MODEL}", &vars).unwrap(), "model: gpt-4");
+        assert_eq!(interpolate_with("${MISSING:-fallback}", &vars).unwrap(), "fallback");
+        assert_eq!(interpolate_with("${EMPTY:-fallback}", &vars).unwrap(), "fallback");
+        assert_eq!(interpolate_with("cost: $$5 and $HOME", &vars).unwrap(), "cost: $5 and $HOME");
+
+        assert!(interpolate_with("${MISSING}", &vars).is_err());
+        assert!(interpolate_with("${UNCLOSED", &vars).is_err());
+        assert!(interpolate_with("${BAD-NAME}", &vars).is_err());
+
+        let err = interpolate_with("${KEY:?set KEY to your API key}", &vars).unwrap_err();
+        assert!(err.to_string().contains("set KEY to your API key"));
+    }
+
+    #[test]
+    fn test_overrides() {
+        let mut value: serde_yaml::Value = serde_yaml::from_str(
+            "agents:\n  assistant:\n    model: gpt-4\n    temperature: 0.7\n",
+        )
+        .unwrap();
+
+        apply_overrides(
+            &mut value,
+            vec![
+                ("LUMOS__AGENTS__ASSISTANT__MODEL".to_string(), "gpt-4o".to_string()),
+                ("LUMOS__AGENTS__ASSISTANT__TEMPERATURE".to_string(), "0.2".to_string()),
+                ("LUMOS__PROJECT__NAME".to_string(), "overridden".to_string()),
+                ("OTHER__VAR".to_string(), "ignored".to_string()),
+            ],
+        );
+
+        assert_eq!(value["agents"]["assistant"]["model"].as_str(), Some("gpt-4o"));
+        assert_eq!(value["agents"]["assistant"]["temperature"].as_f64(), Some(0.2));
+        assert_eq!(value["project"]["name"].as_str(), Some("overridden"));
+        assert!(value.get("other").is_none());
+    }
+}
@@ -4,57 +4,186 @@
 //! supporting both TOML and YAML formats.
 
 pub mod yaml_config;
+pub mod secrets;
+pub mod interpolation;
+pub mod http;
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use crate::{Result, Error};
 
 pub use yaml_config::*;
+pub use interpolation::{interpolate_env, apply_env_overrides};
+pub use http::{HttpClientConfig, http_client_for, http_client_builder_for};
+pub use secrets::{
+    SecretsProvider,
+    SecretReference,
+    SecretResolver,
+    EnvSecretsProvider,
+    VaultSecretsProvider,
+    KubernetesSecretsProvider,
+    AwsSecretsManagerProvider,
+};
+
+/// Configuration file names searched for, in priority order
+pub const CONFIG_FILE_CANDIDATES: &[&str] = &[
+    "lumos.yaml",
+    "lumos.yml",
+    "lumos.toml",
+    "lumosai.yaml",
+    "lumosai.yml",
+    "lumosai.toml",
+    ".lumosai.yaml",
+    ".lumosai.yml",
+    ".lumosai.toml",
+];
 
 /// Unified configuration loader that supports both TOML and YAML
 pub struct ConfigLoader;
 
 impl ConfigLoader {
     /// Load configuration from file, auto-detecting format
+    ///
+    /// `${VAR}` references are expanded before parsing and `LUMOS__*`
+    /// environment overrides are applied afterwards.
     pub fn load<P: AsRef<Path>>(path: P) -> Result<YamlConfig> {
         let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| Error::Configuration(format!("Failed to read config file {}: {}", path.display(), e)))?;
+        Self::load_content(path, &content)
+    }
+    
+    /// Parse configuration content as if it were read from `path`
+    fn load_content(path: &Path, content: &str) -> Result<YamlConfig> {
+        let content = interpolate_env(content)?;
+        let mut value = Self::parse_value(path, &content)?;
+        apply_env_overrides(&mut value);
+        
+        let mut config: YamlConfig = serde_yaml::from_value(value)
+            .map_err(|e| Error::Configuration(format!("Invalid configuration in {}: {}", path.display(), e)))?;
+        
+        // Prompt files are resolved relative to the configuration file
+        let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+        config.load_prompt_files(base_dir)?;
+        
+        Ok(config)
+    }
+    
+    /// Parse configuration content into a value tree, using the extension of `path` to pick the format
+    fn parse_value(path: &Path, content: &str) -> Result<serde_yaml::Value> {
         let extension = path.extension()
             .and_then(|ext| ext.to_str())
             .unwrap_or("");
         
-        match extension.to_lowercase().as_str() {
-            "yaml" | "yml" => YamlConfig::from_file(path),
-            "toml" => Self::load_toml_as_yaml(path),
-            _ => {
-                // Try to detect by content
-                let content = std::fs::read_to_string(path)
-                    .map_err(|e| Error::Configuration(format!("Failed to read config file: {}", e)))?;
-                
+        match ConfigFormat::from_extension(extension) {
+            Some(ConfigFormat::Yaml) => serde_yaml::from_str(content)
+                .map_err(|e| Error::Configuration(format!("Failed to parse YAML config: {}", e))),
+            Some(ConfigFormat::Toml) => Self::parse_toml_value(content),
+            None => {
                 // Try YAML first, then TOML
-                if let Ok(config) = YamlConfig::from_str(&content) {
-                    Ok(config)
-                } else {
-                    Self::parse_toml_content(&content)
+                match serde_yaml::from_str::<serde_yaml::Value>(content) {
+                    Ok(value) if value.is_mapping() => Ok(value),
+                    _ => Self::parse_toml_value(content),
                 }
             }
         }
     }
     
-    /// Load TOML file and convert to YAML config structure
-    fn load_toml_as_yaml<P: AsRef<Path>>(path: P) -> Result<YamlConfig> {
-        let content = std::fs::read_to_string(path)
-            .map_err(|e| Error::Configuration(format!("Failed to read TOML config file: {}", e)))?;
+    /// Add or replace an agent in the configuration file
+    ///
+    /// Only the `agents.<name>` entry is rewritten; `${VAR}` references elsewhere are kept
+    /// as written. Comments are not preserved. The resulting file must validate, otherwise
+    /// it is left untouched.
+    pub fn save_agent<P: AsRef<Path>>(path: P, name: &str, agent: &AgentConfig) -> Result<()> {
+        let agent = serde_yaml::to_value(agent)
+            .map_err(|e| Error::Configuration(format!("Failed to serialize agent '{}': {}", name, e)))?;
+        Self::update_agents(path.as_ref(), |agents| {
+            agents.insert(serde_yaml::Value::String(name.to_string()), Self::strip_nulls(agent));
+        })
+    }
+    
+    /// Remove an agent from the configuration file, returning whether it existed
+    pub fn remove_agent<P: AsRef<Path>>(path: P, name: &str) -> Result<bool> {
+        let mut removed = false;
+        Self::update_agents(path.as_ref(), |agents| {
+            removed = agents.remove(name).is_some();
+        })?;
+        Ok(removed)
+    }
+    
+    /// Apply `update` to the raw `agents` mapping and write the file back in its own format
+    fn update_agents(path: &Path, update: impl FnOnce(&mut serde_yaml::Mapping)) -> Result<()> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(Error::Configuration(format!("Failed to read config file {}: {}", path.display(), e))),
+        };
+        
+        let mut value = if content.trim().is_empty() {
+            serde_yaml::Value::Mapping(serde_yaml::Mapping::new())
+        } else {
+            Self::parse_value(path, &content)?
+        };
+        let root = value.as_mapping_mut()
+            .ok_or_else(|| Error::Configuration(format!("{} is not a configuration mapping", path.display())))?;
         
-        Self::parse_toml_content(&content)
+        let agents = root.entry(serde_yaml::Value::String("agents".to_string()))
+            .or_insert_with(|| serde_yaml::Value::Mapping(serde_yaml::Mapping::new()));
+        if agents.is_null() {
+            *agents = serde_yaml::Value::Mapping(serde_yaml::Mapping::new());
+        }
+        let agents = agents.as_mapping_mut()
+            .ok_or_else(|| Error::Configuration("`agents` must be a mapping".to_string()))?;
+        update(agents);
+        
+        let format = path.extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(ConfigFormat::from_extension)
+            .unwrap_or(ConfigFormat::Yaml);
+        let content = match format {
+            ConfigFormat::Yaml => serde_yaml::to_string(&value)
+                .map_err(|e| Error::Configuration(format!("Failed to serialize YAML config: {}", e)))?,
+            ConfigFormat::Toml => toml::to_string_pretty(&Self::yaml_to_toml_value(value)?)
+                .map_err(|e| Error::Configuration(format!("Failed to serialize TOML: {}", e)))?,
+        };
+        
+        Self::load_content(path, &content)?.validate()?;
+        std::fs::write(path, content)
+            .map_err(|e| Error::Configuration(format!("Failed to write config file {}: {}", path.display(), e)))
     }
     
-    /// Parse TOML content and convert to YAML config
-    pub fn parse_toml_content(content: &str) -> Result<YamlConfig> {
-        // Parse TOML to generic value
+    /// Drop unset optional fields so saved entries stay minimal
+    fn strip_nulls(value: serde_yaml::Value) -> serde_yaml::Value {
+        match value {
+            serde_yaml::Value::Mapping(map) => serde_yaml::Value::Mapping(
+                map.into_iter()
+                    .filter(|(_, value)| !value.is_null())
+                    .map(|(key, value)| (key, Self::strip_nulls(value)))
+                    .collect(),
+            ),
+            serde_yaml::Value::Sequence(seq) => {
+                serde_yaml::Value::Sequence(seq.into_iter().map(Self::strip_nulls).collect())
+            }
+            other => other,
+        }
+    }
+    
+    /// Load configuration from file and validate it
+    pub fn load_validated<P: AsRef<Path>>(path: P) -> Result<YamlConfig> {
+        let config = Self::load(path)?;
+        config.validate()?;
+        Ok(config)
+    }
+    
+    /// Parse TOML content into a YAML value tree
+    fn parse_toml_value(content: &str) -> Result<serde_yaml::Value> {
         let toml_value: toml::Value = toml::from_str(content)
             .map_err(|e| Error::Configuration(format!("Failed to parse TOML: {}", e)))?;
-        
-        // Convert TOML value to YAML value
-        let yaml_value = Self::toml_to_yaml_value(toml_value)?;
+        Self::toml_to_yaml_value(toml_value)
+    }
+    
+    /// Parse TOML content and convert to YAML config
+    pub fn parse_toml_content(content: &str) -> Result<YamlConfig> {
+        let yaml_value = Self::parse_toml_value(content)?;
         
         // Deserialize from YAML value
         serde_yaml::from_value(yaml_value)
@@ -89,24 +218,21 @@ impl ConfigLoader {
     
     /// Auto-detect configuration file in current directory
     pub fn auto_detect() -> Result<YamlConfig> {
-        let candidates = [
-            "lumosai.yaml",
-            "lumosai.yml", 
-            "lumosai.toml",
-            ".lumosai.yaml",
-            ".lumosai.yml",
-            ".lumosai.toml",
-        ];
-        
-        for candidate in &candidates {
-            if Path::new(candidate).exists() {
-                return Self::load(candidate);
-            }
+        match Self::find_in_dir(".") {
+            Some(path) => Self::load(path),
+            None => Err(Error::Configuration(format!(
+                "No configuration file found. Looking for: {}",
+                CONFIG_FILE_CANDIDATES.join(", ")
+            ))),
         }
-        
-        Err(Error::Configuration(
-            "No configuration file found. Looking for: lumosai.yaml, lumosai.yml, lumosai.toml".to_string()
-        ))
+    }
+    
+    /// Find the project configuration file in `dir`, if any
+    pub fn find_in_dir<P: AsRef<Path>>(dir: P) -> Option<PathBuf> {
+        CONFIG_FILE_CANDIDATES
+            .iter()
+            .map(|candidate| dir.as_ref().join(candidate))
+            .find(|path| path.is_file())
     }
     
     /// Create a default configuration file
@@ -161,6 +287,10 @@ impl ConfigLoader {
             serde_yaml::Value::Mapping(map) => {
                 let mut toml_table = toml::map::Map::new();
                 for (key, value) in map {
+                    // TOML has no null; omit unset optional fields
+                    if value.is_null() {
+                        continue;
+                    }
                     if let serde_yaml::Value::String(key_str) = key {
                         let toml_value = Self::yaml_to_toml_value(value)?;
                         toml_table.insert(key_str, toml_value);
@@ -250,6 +380,133 @@ instructions = "You are helpful"
         assert_eq!(config.project.as_ref().unwrap().name, "test-app");
     }
     
+    #[test]
+    fn test_env_interpolation_and_validation() {
+        let yaml_content = r#"
+project:
+  name: ${LUMOS_CONFIG_TEST_NAME:-fallback-app}
+
+providers:
+  primary:
+    type: openai
+    api_key: ${LUMOS_CONFIG_TEST_KEY:-env://OPENAI_API_KEY}
+
+agents:
+  assistant:
+    model: gpt-4
+    instructions: You are helpful
+    provider: primary
+"#;
+        
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("lumos.yaml");
+        fs::write(&file_path, yaml_content).unwrap();
+        
+        let config = ConfigLoader::load_validated(&file_path).unwrap();
+        assert_eq!(config.project.as_ref().unwrap().name, "fallback-app");
+        assert_eq!(
+            config.get_provider("primary").unwrap().api_key.as_deref(),
+            Some("env://OPENAI_API_KEY")
+        );
+        assert_eq!(ConfigLoader::find_in_dir(dir.path()), Some(file_path.clone()));
+        
+        fs::write(&file_path, yaml_content.replace("provider: primary", "provider: missing")).unwrap();
+        assert!(ConfigLoader::load(&file_path).is_ok());
+        assert!(ConfigLoader::load_validated(&file_path).is_err());
+    }
+    
+    #[test]
+    fn test_prompt_files() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("prompts")).unwrap();
+        fs::write(dir.path().join("prompts/assistant.md"), "You answer from the prompt file").unwrap();
+        
+        let file_path = dir.path().join("lumos.yaml");
+        fs::write(&file_path, r#"
+agents:
+  assistant:
+    model: gpt-4
+    instructions_file: prompts/assistant.md
+"#).unwrap();
+        
+        let config = ConfigLoader::load_validated(&file_path).unwrap();
+        assert_eq!(config.get_agent("assistant").unwrap().instructions, "You answer from the prompt file");
+        assert_eq!(config.prompt_files(dir.path()), vec![dir.path().join("prompts/assistant.md")]);
+        
+        fs::remove_file(dir.path().join("prompts/assistant.md")).unwrap();
+        assert!(ConfigLoader::load(&file_path).is_err());
+    }
+    
+    #[test]
+    fn test_save_and_remove_agent() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("lumos.yaml");
+        fs::write(&file_path, r#"
+project:
+  name: test-app
+providers:
+  primary:
+    type: openai
+    api_key: ${OPENAI_API_KEY:-sk-test}
+"#).unwrap();
+        
+        let agent = AgentConfig {
+            model: "gpt-4".to_string(),
+            instructions: "You triage tickets".to_string(),
+            instructions_file: None,
+            tools: Some(vec!["calculator".to_string()]),
+            temperature: Some(0.2),
+            max_tokens: None,
+            timeout: None,
+            memory: None,
+            voice: None,
+            provider: Some("primary".to_string()),
+            rag: None,
+            guardrails: Some(GuardrailsConfig {
+                blocked_topics: Some(vec!["pricing".to_string()]),
+                rules: None,
+                max_tool_calls: Some(2),
+            }),
+            few_shot: None,
+            tools_memory: None,
+        };
+        ConfigLoader::save_agent(&file_path, "triage", &agent).unwrap();
+        
+        let content = fs::read_to_string(&file_path).unwrap();
+        assert!(content.contains("${OPENAI_API_KEY:-sk-test}"));
+        assert!(!content.contains("null"));
+        
+        let config = ConfigLoader::load(&file_path).unwrap();
+        let saved = config.get_agent("triage").unwrap();
+        assert_eq!(saved.instructions, "You triage tickets");
+        assert_eq!(saved.guardrails.as_ref().unwrap().max_tool_calls, Some(2));
+        
+        // Invalid agents are rejected without touching the file
+        let mut invalid = agent.clone();
+        invalid.provider = Some("missing".to_string());
+        assert!(ConfigLoader::save_agent(&file_path, "broken", &invalid).is_err());
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), content);
+        
+        assert!(ConfigLoader::remove_agent(&file_path, "triage").unwrap());
+        assert!(!ConfigLoader::remove_agent(&file_path, "triage").unwrap());
+        assert!(ConfigLoader::load(&file_path).unwrap().get_agent("triage").is_none());
+    }
+    
+    #[test]
+    fn test_save_agent_toml() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("lumos.toml");
+        fs::write(&file_path, "[project]\nname = \"test-app\"\n").unwrap();
+        
+        let mut agent = YamlConfig::default().get_agent("assistant").unwrap().clone();
+        agent.voice = None;
+        ConfigLoader::save_agent(&file_path, "assistant", &agent).unwrap();
+        
+        let config = ConfigLoader::load_validated(&file_path).unwrap();
+        assert_eq!(config.project.as_ref().unwrap().name, "test-app");
+        assert_eq!(config.get_agent("assistant").unwrap().model, "gpt-4");
+    }
+    
     #[test]
     fn test_auto_detect() {
         let dir = tempdir().unwrap();
@@ -0,0 +1,851 @@
+//! Secrets management for LumosAI
+//!
+//! API keys and credentials should never live in plain configuration files.
+//! Instead, configuration values may hold *secret references* which are
+//! resolved at runtime through a [`SecretsProvider`]:
+//!
+//! | Reference                              | Backend                     |
+//! |----------------------------------------|-----------------------------|
+//! | `env://OPENAI_API_KEY`                 | Process environment         |
+//! | `vault://openai#api_key`               | HashiCorp Vault (KV v2)     |
+//! | `k8s://llm-credentials#openai`         | Kubernetes Secret           |
+//! | `k8s://prod/llm-credentials#openai`    | Kubernetes Secret (with ns) |
+//! | `aws-sm://prod/openai#api_key`         | AWS Secrets Manager         |
+//!
+//! Values without a registered scheme are treated as literals so existing
+//! configurations keep working.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use base64::Engine;
+use ring::{digest, hmac};
+use tokio::sync::RwLock;
+
+use crate::{Error, Result};
+use super::http::{http_client_builder_for, http_client_for};
+
+/// Parsed secret reference of the form `scheme://path#key`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecretReference {
+    /// Backend scheme (e.g. `vault`, `k8s`, `aws-sm`, `env`)
+    pub scheme: String,
+    /// Backend specific secret path
+    pub path: String,
+    /// Optional field inside a structured secret
+    pub key: Option<String>,
+}
+
+impl SecretReference {
+    /// Parse a secret reference, returning `None` for plain values
+    pub fn parse(value: &str) -> Option<Self> {
+        let (scheme, rest) = value.split_once("://")?;
+        if scheme.is_empty()
+            || !scheme.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return None;
+        }
+
+        let (path, key) = match rest.split_once('#') {
+            Some((path, key)) if !key.is_empty() => (path, Some(key.to_string())),
+            Some((path, _)) => (path, None),
+            None => (rest, None),
+        };
+
+        if path.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            scheme: scheme.to_lowercase(),
+            path: path.to_string(),
+            key,
+        })
+    }
+}
+
+impl std::fmt::Display for SecretReference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}://{}", self.scheme, self.path)?;
+        if let Some(key) = &self.key {
+            write!(f, "#{}", key)?;
+        }
+        Ok(())
+    }
+}
+
+/// Backend capable of resolving secret references
+#[async_trait]
+pub trait SecretsProvider: Send + Sync {
+    /// Reference scheme handled by this provider
+    fn scheme(&self) -> &str;
+
+    /// Fetch the secret value at `path`, optionally selecting `key`
+    async fn get_secret(&self, path: &str, key: Option<&str>) -> Result<String>;
+}
+
+/// Select a field from a structured (JSON object) secret payload
+fn select_field(
+    reference: &str,
+    fields: &serde_json::Map<String, serde_json::Value>,
+    key: Option<&str>,
+) -> Result<String> {
+    let value = match key {
+        Some(key) => fields.get(key).ok_or_else(|| {
+            Error::NotFound(format!("Secret '{}' has no field '{}'", reference, key))
+        })?,
+        None if fields.len() == 1 => fields.values().next().unwrap(),
+        None => {
+            return Err(Error::Configuration(format!(
+                "Secret '{}' has {} fields; specify one with '#<key>'",
+                reference,
+                fields.len()
+            )))
+        }
+    };
+
+    Ok(match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    })
+}
+
+/// Resolves secrets from process environment variables
+#[derive(Debug, Default, Clone)]
+pub struct EnvSecretsProvider;
+
+#[async_trait]
+impl SecretsProvider for EnvSecretsProvider {
+    fn scheme(&self) -> &str {
+        "env"
+    }
+
+    async fn get_secret(&self, path: &str, _key: Option<&str>) -> Result<String> {
+        std::env::var(path)
+            .map_err(|_| Error::NotFound(format!("Environment variable '{}' is not set", path)))
+    }
+}
+
+/// HashiCorp Vault KV v2 secrets provider
+pub struct VaultSecretsProvider {
+    address: String,
+    token: String,
+    mount: String,
+    namespace: Option<String>,
+    client: reqwest::Client,
+}
+
+impl VaultSecretsProvider {
+    /// Create a provider for the Vault server at `address`
+    pub fn new(address: impl Into<String>, token: impl Into<String>) -> Self {
+        let address = address.into().trim_end_matches('/').to_string();
+        Self {
+            client: http_client_for(&address),
+            address,
+            token: token.into(),
+            mount: "secret".to_string(),
+            namespace: None,
+        }
+    }
+
+    /// Create a provider from `VAULT_ADDR`, `VAULT_TOKEN` and optional `VAULT_NAMESPACE`
+    pub fn from_env() -> Option<Self> {
+        let address = std::env::var("VAULT_ADDR").ok()?;
+        let token = std::env::var("VAULT_TOKEN").ok()?;
+        let mut provider = Self::new(address, token);
+        provider.namespace = std::env::var("VAULT_NAMESPACE").ok();
+        Some(provider)
+    }
+
+    /// Set the KV v2 mount point (defaults to `secret`)
+    pub fn with_mount(mut self, mount: impl Into<String>) -> Self {
+        self.mount = mount.into().trim_matches('/').to_string();
+        self
+    }
+
+    /// Set the Vault Enterprise namespace
+    pub fn with_namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = Some(namespace.into());
+        self
+    }
+
+    fn secret_url(&self, path: &str) -> String {
+        format!(
+            "{}/v1/{}/data/{}",
+            self.address,
+            self.mount,
+            path.trim_start_matches('/')
+        )
+    }
+}
+
+#[async_trait]
+impl SecretsProvider for VaultSecretsProvider {
+    fn scheme(&self) -> &str {
+        "vault"
+    }
+
+    async fn get_secret(&self, path: &str, key: Option<&str>) -> Result<String> {
+        let mut request = self
+            .client
+            .get(self.secret_url(path))
+            .header("X-Vault-Token", &self.token);
+        if let Some(namespace) = &self.namespace {
+            request = request.header("X-Vault-Namespace", namespace);
+        }
+
+        let response = request.send().await?;
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Err(Error::NotFound(format!("Vault secret '{}' not found", path)));
+        }
+        if status == reqwest::StatusCode::FORBIDDEN {
+            return Err(Error::Authentication(format!(
+                "Vault denied access to secret '{}'",
+                path
+            )));
+        }
+        if !status.is_success() {
+            return Err(Error::ApiError {
+                message: format!("Vault request for '{}' failed", path),
+                status_code: Some(status.as_u16()),
+            });
+        }
+
+        let body: serde_json::Value = response.json().await?;
+        let fields = body
+            .pointer("/data/data")
+            .and_then(|v| v.as_object())
+            .ok_or_else(|| {
+                Error::Parsing(format!("Unexpected Vault response for secret '{}'", path))
+            })?;
+
+        select_field(path, fields, key)
+    }
+}
+
+const K8S_SERVICE_ACCOUNT_DIR: &str = "/var/run/secrets/kubernetes.io/serviceaccount";
+
+/// Kubernetes Secret provider
+///
+/// Reads secrets from volume mounts when a mount directory is configured,
+/// falling back to the Kubernetes API using the pod's service account.
+pub struct KubernetesSecretsProvider {
+    api_server: String,
+    token: Option<String>,
+    namespace: String,
+    mount_dir: Option<PathBuf>,
+    client: reqwest::Client,
+}
+
+impl KubernetesSecretsProvider {
+    /// Create a provider talking to the given API server
+    pub fn new(api_server: impl Into<String>, namespace: impl Into<String>) -> Self {
+        let api_server = api_server.into().trim_end_matches('/').to_string();
+        Self {
+            client: http_client_for(&api_server),
+            api_server,
+            token: None,
+            namespace: namespace.into(),
+            mount_dir: None,
+        }
+    }
+
+    /// Create a provider using the in-cluster service account, if running in a pod
+    pub fn in_cluster() -> Option<Self> {
+        let host = std::env::var("KUBERNETES_SERVICE_HOST").ok()?;
+        let port = std::env::var("KUBERNETES_SERVICE_PORT").unwrap_or_else(|_| "443".to_string());
+        let sa_dir = Path::new(K8S_SERVICE_ACCOUNT_DIR);
+
+        let token = std::fs::read_to_string(sa_dir.join("token")).ok()?;
+        let namespace = std::fs::read_to_string(sa_dir.join("namespace"))
+            .map(|ns| ns.trim().to_string())
+            .unwrap_or_else(|_| "default".to_string());
+
+        let api_server = format!("https://{}:{}", host, port);
+        let mut builder = http_client_builder_for(&api_server);
+        if let Ok(ca) = std::fs::read(sa_dir.join("ca.crt")) {
+            if let Ok(cert) = reqwest::Certificate::from_pem(&ca) {
+                builder = builder.add_root_certificate(cert);
+            }
+        }
+
+        Some(Self {
+            api_server,
+            token: Some(token.trim().to_string()),
+            namespace,
+            mount_dir: None,
+            client: builder.build().ok()?,
+        })
+    }
+
+    /// Set the bearer token used for API requests
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Read secrets from `<dir>/<secret-name>/<key>` volume mounts before calling the API
+    pub fn with_mount_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.mount_dir = Some(dir.into());
+        self
+    }
+
+    /// Split `[namespace/]name` into its components
+    fn split_path<'a>(&'a self, path: &'a str) -> (&'a str, &'a str) {
+        match path.split_once('/') {
+            Some((namespace, name)) => (namespace, name),
+            None => (self.namespace.as_str(), path),
+        }
+    }
+
+    fn read_mounted(&self, name: &str, key: Option<&str>) -> Option<String> {
+        let dir = self.mount_dir.as_ref()?.join(name);
+        let file = match key {
+            Some(key) => dir.join(key),
+            None => dir,
+        };
+        std::fs::read_to_string(file).ok()
+    }
+}
+
+#[async_trait]
+impl SecretsProvider for KubernetesSecretsProvider {
+    fn scheme(&self) -> &str {
+        "k8s"
+    }
+
+    async fn get_secret(&self, path: &str, key: Option<&str>) -> Result<String> {
+        let (namespace, name) = self.split_path(path);
+
+        if let Some(value) = self.read_mounted(name, key) {
+            return Ok(value);
+        }
+
+        let url = format!(
+            "{}/api/v1/namespaces/{}/secrets/{}",
+            self.api_server, namespace, name
+        );
+        let mut request = self.client.get(url);
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request.send().await?;
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Err(Error::NotFound(format!(
+                "Kubernetes secret '{}/{}' not found",
+                namespace, name
+            )));
+        }
+        if status == reqwest::StatusCode::FORBIDDEN || status == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(Error::Authentication(format!(
+                "Service account cannot read secret '{}/{}'",
+                namespace, name
+            )));
+        }
+        if !status.is_success() {
+            return Err(Error::ApiError {
+                message: format!("Kubernetes request for secret '{}/{}' failed", namespace, name),
+                status_code: Some(status.as_u16()),
+            });
+        }
+
+        let body: serde_json::Value = response.json().await?;
+        let encoded = body.get("data").and_then(|v| v.as_object()).ok_or_else(|| {
+            Error::Parsing(format!("Kubernetes secret '{}/{}' has no data", namespace, name))
+        })?;
+
+        // Secret data values are base64 encoded
+        let mut decoded = serde_json::Map::new();
+        for (field, value) in encoded {
+            let raw = value.as_str().unwrap_or_default();
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(raw)
+                .map_err(|e| Error::Parsing(format!("Invalid base64 in secret field '{}': {}", field, e)))?;
+            let text = String::from_utf8(bytes)
+                .map_err(|e| Error::Parsing(format!("Secret field '{}' is not UTF-8: {}", field, e)))?;
+            decoded.insert(field.clone(), serde_json::Value::String(text));
+        }
+
+        select_field(path, &decoded, key)
+    }
+}
+
+/// AWS Secrets Manager provider using SigV4 signed requests
+pub struct AwsSecretsManagerProvider {
+    region: String,
+    access_key_id: String,
+    secret_access_key: String,
+    session_token: Option<String>,
+    endpoint: Option<String>,
+    client: reqwest::Client,
+}
+
+impl AwsSecretsManagerProvider {
+    /// Create a provider with static credentials
+    pub fn new(
+        region: impl Into<String>,
+        access_key_id: impl Into<String>,
+        secret_access_key: impl Into<String>,
+    ) -> Self {
+        let region = region.into();
+        Self {
+            client: http_client_for(&format!("https://secretsmanager.{}.amazonaws.com", region)),
+            region,
+            access_key_id: access_key_id.into(),
+            secret_access_key: secret_access_key.into(),
+            session_token: None,
+            endpoint: None,
+        }
+    }
+
+    /// Create a provider from the standard `AWS_*` environment variables
+    pub fn from_env() -> Option<Self> {
+        let region = std::env::var("AWS_REGION")
+            .or_else(|_| std::env::var("AWS_DEFAULT_REGION"))
+            .ok()?;
+        let access_key_id = std::env::var("AWS_ACCESS_KEY_ID").ok()?;
+        let secret_access_key = std::env::var("AWS_SECRET_ACCESS_KEY").ok()?;
+
+        let mut provider = Self::new(region, access_key_id, secret_access_key);
+        provider.session_token = std::env::var("AWS_SESSION_TOKEN").ok();
+        Some(provider)
+    }
+
+    /// Set a temporary session token
+    pub fn with_session_token(mut self, token: impl Into<String>) -> Self {
+        self.session_token = Some(token.into());
+        self
+    }
+
+    /// Override the service endpoint (e.g. for LocalStack)
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        let endpoint = endpoint.into().trim_end_matches('/').to_string();
+        self.client = http_client_for(&endpoint);
+        self.endpoint = Some(endpoint);
+        self
+    }
+
+    fn host(&self) -> String {
+        match &self.endpoint {
+            Some(endpoint) => endpoint
+                .trim_start_matches("https://")
+                .trim_start_matches("http://")
+                .to_string(),
+            None => format!("secretsmanager.{}.amazonaws.com", self.region),
+        }
+    }
+
+    fn url(&self) -> String {
+        match &self.endpoint {
+            Some(endpoint) => format!("{}/", endpoint),
+            None => format!("https://{}/", self.host()),
+        }
+    }
+
+    /// Build the SigV4 `Authorization` header for a request body
+    fn authorization(&self, host: &str, amz_date: &str, target: &str, payload: &str) -> String {
+        let date_stamp = &amz_date[..8];
+
+        let mut headers = vec![
+            ("content-type", "application/x-amz-json-1.1".to_string()),
+            ("host", host.to_string()),
+            ("x-amz-date", amz_date.to_string()),
+        ];
+        if let Some(token) = &self.session_token {
+            headers.push(("x-amz-security-token", token.clone()));
+        }
+        headers.push(("x-amz-target", target.to_string()));
+
+        let canonical_headers: String = headers
+            .iter()
+            .map(|(name, value)| format!("{}:{}\n", name, value.trim()))
+            .collect();
+        let signed_headers = headers
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>()
+            .join(";");
+
+        let canonical_request = format!(
+            "POST\n/\n\n{}\n{}\n{}",
+            canonical_headers,
+            signed_headers,
+            hex_sha256(payload.as_bytes())
+        );
+
+        let scope = format!("{}/{}/secretsmanager/aws4_request", date_stamp, self.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            amz_date,
+            scope,
+            hex_sha256(canonical_request.as_bytes())
+        );
+
+        let signing_key =
+            sigv4_signing_key(&self.secret_access_key, date_stamp, &self.region, "secretsmanager");
+        let signature = hex_encode(
+            hmac::sign(&hmac::Key::new(hmac::HMAC_SHA256, &signing_key), string_to_sign.as_bytes())
+                .as_ref(),
+        );
+
+        format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+            self.access_key_id, scope, signed_headers, signature
+        )
+    }
+}
+
+#[async_trait]
+impl SecretsProvider for AwsSecretsManagerProvider {
+    fn scheme(&self) -> &str {
+        "aws-sm"
+    }
+
+    async fn get_secret(&self, path: &str, key: Option<&str>) -> Result<String> {
+        const TARGET: &str = "secretsmanager.GetSecretValue";
+
+        let host = self.host();
+        let amz_date = chrono::Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+        let payload = serde_json::json!({ "SecretId": path }).to_string();
+        let authorization = self.authorization(&host, &amz_date, TARGET, &payload);
+
+        let mut request = self
+            .client
+            .post(self.url())
+            .header("Content-Type", "application/x-amz-json-1.1")
+            .header("X-Amz-Date", &amz_date)
+            .header("X-Amz-Target", TARGET)
+            .header("Authorization", authorization)
+            .body(payload);
+        if let Some(token) = &self.session_token {
+            request = request.header("X-Amz-Security-Token", token);
+        }
+
+        let response = request.send().await?;
+        let status = response.status();
+        let body: serde_json::Value = response.json().await.unwrap_or_default();
+
+        if !status.is_success() {
+            let error_type = body
+                .get("__type")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default();
+            return Err(match error_type {
+                t if t.ends_with("ResourceNotFoundException") => {
+                    Error::NotFound(format!("AWS secret '{}' not found", path))
+                }
+                t if t.ends_with("AccessDeniedException") => {
+                    Error::Authentication(format!("Access denied to AWS secret '{}'", path))
+                }
+                _ => Error::ApiError {
+                    message: format!("AWS Secrets Manager request for '{}' failed: {}", path, error_type),
+                    status_code: Some(status.as_u16()),
+                },
+            });
+        }
+
+        let secret_string = body
+            .get("SecretString")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| {
+                Error::Unsupported(format!("AWS secret '{}' has no SecretString value", path))
+            })?;
+
+        match key {
+            Some(key) => {
+                let fields: serde_json::Map<String, serde_json::Value> =
+                    serde_json::from_str(secret_string).map_err(|_| {
+                        Error::Parsing(format!("AWS secret '{}' is not a JSON object", path))
+                    })?;
+                select_field(path, &fields, Some(key))
+            }
+            None => Ok(secret_string.to_string()),
+        }
+    }
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn hex_sha256(data: &[u8]) -> String {
+    hex_encode(digest::digest(&digest::SHA256, data).as_ref())
+}
+
+/// Derive the SigV4 signing key for a date, region and service
+fn sigv4_signing_key(secret: &str, date_stamp: &str, region: &str, service: &str) -> Vec<u8> {
+    let sign = |key: &[u8], data: &str| -> Vec<u8> {
+        hmac::sign(&hmac::Key::new(hmac::HMAC_SHA256, key), data.as_bytes())
+            .as_ref()
+            .to_vec()
+    };
+
+    let k_date = sign(format!("AWS4{}", secret).as_bytes(), date_stamp);
+    let k_region = sign(&k_date, region);
+    let k_service = sign(&k_region, service);
+    sign(&k_service, "aws4_request")
+}
+
+/// Resolves secret references through registered providers with caching
+pub struct SecretResolver {
+    providers: HashMap<String, Arc<dyn SecretsProvider>>,
+    cache: RwLock<HashMap<String, (String, Instant)>>,
+    cache_ttl: Duration,
+}
+
+impl SecretResolver {
+    /// Create a resolver with only the environment provider registered
+    pub fn new() -> Self {
+        let mut resolver = Self {
+            providers: HashMap::new(),
+            cache: RwLock::new(HashMap::new()),
+            cache_ttl: Duration::from_secs(300),
+        };
+        resolver.register(EnvSecretsProvider);
+        resolver
+    }
+
+    /// Create a resolver with every backend that is configured in the environment
+    pub fn from_env() -> Self {
+        let mut resolver = Self::new();
+        if let Some(vault) = VaultSecretsProvider::from_env() {
+            resolver.register(vault);
+        }
+        if let Some(k8s) = KubernetesSecretsProvider::in_cluster() {
+            resolver.register(k8s);
+        }
+        if let Some(aws) = AwsSecretsManagerProvider::from_env() {
+            resolver.register(aws);
+        }
+        resolver
+    }
+
+    /// Register a provider, replacing any provider with the same scheme
+    pub fn register(&mut self, provider: impl SecretsProvider + 'static) {
+        self.providers
+            .insert(provider.scheme().to_string(), Arc::new(provider));
+    }
+
+    /// Builder-style variant of [`SecretResolver::register`]
+    pub fn with_provider(mut self, provider: impl SecretsProvider + 'static) -> Self {
+        self.register(provider);
+        self
+    }
+
+    /// Set how long resolved secrets are cached (zero disables caching)
+    pub fn with_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = ttl;
+        self
+    }
+
+    /// Whether `value` is a reference handled by a registered provider
+    pub fn is_reference(&self, value: &str) -> bool {
+        SecretReference::parse(value)
+            .map(|r| self.providers.contains_key(&r.scheme))
+            .unwrap_or(false)
+    }
+
+    /// Resolve a configuration value, returning literals unchanged
+    pub async fn resolve(&self, value: &str) -> Result<String> {
+        let reference = match SecretReference::parse(value) {
+            Some(reference) if self.providers.contains_key(&reference.scheme) => reference,
+            _ => return Ok(value.to_string()),
+        };
+
+        if !self.cache_ttl.is_zero() {
+            let cache = self.cache.read().await;
+            if let Some((secret, fetched_at)) = cache.get(value) {
+                if fetched_at.elapsed() < self.cache_ttl {
+                    return Ok(secret.clone());
+                }
+            }
+        }
+
+        let provider = &self.providers[&reference.scheme];
+        let secret = provider
+            .get_secret(&reference.path, reference.key.as_deref())
+            .await?;
+        let secret = secret.trim_end_matches(['\r', '\n']).to_string();
+
+        if !self.cache_ttl.is_zero() {
+            self.cache
+                .write()
+                .await
+                .insert(value.to_string(), (secret.clone(), Instant::now()));
+        }
+
+        Ok(secret)
+    }
+
+    /// Resolve every value of a map (e.g. deployment environment variables)
+    pub async fn resolve_map(&self, values: &HashMap<String, String>) -> Result<HashMap<String, String>> {
+        let mut resolved = HashMap::with_capacity(values.len());
+        for (name, value) in values {
+            let secret = self.resolve(value).await.map_err(|e| {
+                Error::Configuration(format!("Failed to resolve secret for '{}': {}", name, e))
+            })?;
+            resolved.insert(name.clone(), secret);
+        }
+        Ok(resolved)
+    }
+
+    /// Drop all cached secrets, forcing the next lookup to hit the backend
+    pub async fn clear_cache(&self) {
+        self.cache.write().await.clear();
+    }
+}
+
+impl Default for SecretResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct CountingProvider {
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl SecretsProvider for CountingProvider {
+        fn scheme(&self) -> &str {
+            "test"
+        }
+
+        async fn get_secret(&self, path: &str, key: Option<&str>) -> Result<String> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(format!("{}:{}\n", path, key.unwrap_or("-")))
+        }
+    }
+
+    #[test]
+    fn test_parse_reference() {
+        let reference = SecretReference::parse("vault://openai#api_key").unwrap();
+        assert_eq!(reference.scheme, "vault");
+        assert_eq!(reference.path, "openai");
+        assert_eq!(reference.key.as_deref(), Some("api_key"));
+        assert_eq!(reference.to_string(), "vault://openai#api_key");
+
+        let reference = SecretReference::parse("k8s://prod/llm-credentials").unwrap();
+        assert_eq!(reference.path, "prod/llm-credentials");
+        assert!(reference.key.is_none());
+
+        assert!(SecretReference::parse("sk-plain-api-key").is_none());
+        assert!(SecretReference::parse("vault://").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_resolver_passes_literals_through() {
+        let resolver = SecretResolver::new();
+        assert_eq!(resolver.resolve("sk-literal").await.unwrap(), "sk-literal");
+        // Unknown schemes are not treated as references
+        assert_eq!(
+            resolver.resolve("https://example.com").await.unwrap(),
+            "https://example.com"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resolver_caches_secrets() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let resolver = SecretResolver::new().with_provider(CountingProvider { calls: calls.clone() });
+
+        assert!(resolver.is_reference("test://db#password"));
+        assert_eq!(resolver.resolve("test://db#password").await.unwrap(), "db:password");
+        assert_eq!(resolver.resolve("test://db#password").await.unwrap(), "db:password");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        resolver.clear_cache().await;
+        resolver.resolve("test://db#password").await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_map_from_env() {
+        std::env::set_var("LUMOS_SECRETS_TEST_KEY", "from-env");
+        let resolver = SecretResolver::new();
+
+        let mut values = HashMap::new();
+        values.insert("OPENAI_API_KEY".to_string(), "env://LUMOS_SECRETS_TEST_KEY".to_string());
+        values.insert("LOG_LEVEL".to_string(), "info".to_string());
+
+        let resolved = resolver.resolve_map(&values).await.unwrap();
+        assert_eq!(resolved["OPENAI_API_KEY"], "from-env");
+        assert_eq!(resolved["LOG_LEVEL"], "info");
+
+        values.insert("MISSING".to_string(), "env://LUMOS_SECRETS_TEST_MISSING".to_string());
+        assert!(resolver.resolve_map(&values).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_kubernetes_mounted_secret() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("llm-credentials")).unwrap();
+        std::fs::write(dir.path().join("llm-credentials/openai"), "sk-mounted\n").unwrap();
+
+        let provider = KubernetesSecretsProvider::new("https://127.0.0.1:6443", "default")
+            .with_mount_dir(dir.path());
+        let resolver = SecretResolver::new().with_provider(provider);
+
+        assert_eq!(
+            resolver.resolve("k8s://llm-credentials#openai").await.unwrap(),
+            "sk-mounted"
+        );
+    }
+
+    #[test]
+    fn test_select_field() {
+        let mut fields = serde_json::Map::new();
+        fields.insert("api_key".to_string(), serde_json::json!("sk-123"));
+        assert_eq!(select_field("s", &fields, None).unwrap(), "sk-123");
+        assert_eq!(select_field("s", &fields, Some("api_key")).unwrap(), "sk-123");
+        assert!(select_field("s", &fields, Some("other")).is_err());
+
+        fields.insert("port".to_string(), serde_json::json!(5432));
+        assert!(select_field("s", &fields, None).is_err());
+        assert_eq!(select_field("s", &fields, Some("port")).unwrap(), "5432");
+    }
+
+    #[test]
+    fn test_sigv4_signing_key() {
+        // Example from the AWS Signature Version 4 documentation
+        let key = sigv4_signing_key(
+            "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            "20120215",
+            "us-east-1",
+            "iam",
+        );
+        assert_eq!(
+            hex_encode(&key),
+            "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d"
+        );
+    }
+
+    #[test]
+    fn test_aws_authorization_header() {
+        let provider = AwsSecretsManagerProvider::new("us-east-1", "AKIDEXAMPLE", "secret")
+            .with_session_token("session");
+        let header = provider.authorization(
+            "secretsmanager.us-east-1.amazonaws.com",
+            "20240101T000000Z",
+            "secretsmanager.GetSecretValue",
+            "{}",
+        );
+        assert!(header.starts_with(
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20240101/us-east-1/secretsmanager/aws4_request"
+        ));
+        assert!(header.contains(
+            "SignedHeaders=content-type;host;x-amz-date;x-amz-security-token;x-amz-target"
+        ));
+    }
+}
@@ -4,9 +4,10 @@
 //! extending the existing TOML configuration support.
 
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use serde::{Deserialize, Serialize};
 use crate::{Result, Error};
+use crate::agent::{FewShotConfig, ToolsMemoryConfig};
 
 /// YAML configuration structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,6 +18,14 @@ pub struct YamlConfig {
     pub rag: Option<RagConfig>,
     pub deployment: Option<DeploymentConfig>,
     pub tools: Option<HashMap<String, ToolConfig>>,
+    /// Named LLM providers that agents can reference
+    pub providers: Option<HashMap<String, ProviderConfig>>,
+    /// Named RAG pipelines that agents can reference
+    pub rag_pipelines: Option<HashMap<String, RagConfig>>,
+    /// Proxy, TLS and timeout settings for outbound HTTP
+    pub http: Option<super::HttpClientConfig>,
+    /// Chat channels (Slack, Teams, Telegram, Discord) that agents are deployed to
+    pub channels: Option<crate::channels::ChannelsConfig>,
 }
 
 /// Project configuration
@@ -31,14 +40,72 @@ pub struct ProjectConfig {
 /// Agent configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentConfig {
+    /// Model name; may be omitted when the referenced provider defines a default model
+    #[serde(default)]
     pub model: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub instructions: String,
+    /// Prompt file whose contents are used as instructions, relative to the config file
+    pub instructions_file: Option<String>,
     pub tools: Option<Vec<String>>,
     pub temperature: Option<f32>,
     pub max_tokens: Option<u32>,
     pub timeout: Option<u64>,
     pub memory: Option<MemoryConfig>,
     pub voice: Option<VoiceConfig>,
+    /// Name of an entry in `providers` used to serve `model`
+    pub provider: Option<String>,
+    /// Name of an entry in `rag_pipelines` used for retrieval
+    pub rag: Option<String>,
+    /// Behavioural limits applied on top of the instructions
+    pub guardrails: Option<GuardrailsConfig>,
+    /// Example exchanges shown to the model, selected by similarity to each request
+    pub few_shot: Option<FewShotConfig>,
+    /// Compact summary of earlier tool results shown on later turns of a session
+    pub tools_memory: Option<ToolsMemoryConfig>,
+}
+
+/// Agent guardrails configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GuardrailsConfig {
+    /// Topics the agent must decline to discuss
+    pub blocked_topics: Option<Vec<String>>,
+    /// Additional rules the agent must always follow
+    pub rules: Option<Vec<String>>,
+    /// Maximum number of tool calls per response
+    pub max_tool_calls: Option<u32>,
+}
+
+impl GuardrailsConfig {
+    /// Render the topic and rule guardrails as an instructions section
+    pub fn instructions(&self) -> Option<String> {
+        let mut lines = Vec::new();
+        for topic in self.blocked_topics.iter().flatten() {
+            lines.push(format!("- Politely decline any request about: {}", topic));
+        }
+        for rule in self.rules.iter().flatten() {
+            lines.push(format!("- {}", rule));
+        }
+
+        if lines.is_empty() {
+            None
+        } else {
+            Some(format!("Guardrails (always apply):\n{}", lines.join("\n")))
+        }
+    }
+}
+
+/// LLM provider configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderConfig {
+    /// Provider type (openai, anthropic, deepseek, qwen, ...)
+    #[serde(rename = "type")]
+    pub provider_type: String,
+    /// API key or secret reference (e.g. `vault://openai#api_key`)
+    pub api_key: Option<String>,
+    pub base_url: Option<String>,
+    /// Default model when an agent does not specify one
+    pub model: Option<String>,
 }
 
 /// Memory configuration
@@ -129,6 +196,12 @@ pub struct DockerConfig {
     pub optimize: Option<bool>,
 }
 
+/// Provider types understood by the model resolver
+pub const SUPPORTED_PROVIDER_TYPES: &[&str] = &["openai", "anthropic", "deepseek", "qwen", "ollama"];
+
+/// Built-in tools that can be referenced without a `tools` entry
+pub const BUILTIN_TOOL_NAMES: &[&str] = &["web_search", "calculator", "file_manager", "code_executor", "translate"];
+
 /// Tool configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolConfig {
@@ -179,15 +252,99 @@ impl YamlConfig {
                 if name.is_empty() {
                     return Err(Error::Configuration("Agent name cannot be empty".to_string()));
                 }
-                if agent.model.is_empty() {
+                let provider_model = agent.provider.as_ref()
+                    .and_then(|provider| self.get_provider(provider))
+                    .and_then(|provider| provider.model.as_ref());
+                if agent.model.is_empty() && provider_model.is_none() {
                     return Err(Error::Configuration(format!("Agent '{}' must have a model", name)));
                 }
-                if agent.instructions.is_empty() {
+                if agent.instructions.is_empty() && agent.instructions_file.is_none() {
                     return Err(Error::Configuration(format!("Agent '{}' must have instructions", name)));
                 }
+                if let Some(temperature) = agent.temperature {
+                    if !(0.0..=2.0).contains(&temperature) {
+                        return Err(Error::Configuration(format!(
+                            "Agent '{}' temperature must be between 0.0 and 2.0", name
+                        )));
+                    }
+                }
+                if let Some(provider) = &agent.provider {
+                    if self.get_provider(provider).is_none() {
+                        return Err(Error::Configuration(format!(
+                            "Agent '{}' references unknown provider '{}'", name, provider
+                        )));
+                    }
+                }
+                if let Some(rag) = &agent.rag {
+                    if self.get_rag_pipeline(rag).is_none() {
+                        return Err(Error::Configuration(format!(
+                            "Agent '{}' references unknown RAG pipeline '{}'", name, rag
+                        )));
+                    }
+                }
+                if let Some(few_shot) = &agent.few_shot {
+                    if few_shot.top_k == 0 {
+                        return Err(Error::Configuration(format!("Agent '{}' few_shot top_k must be at least 1", name)));
+                    }
+                    if few_shot.examples.iter().any(|example| example.input.trim().is_empty() || example.output.trim().is_empty()) {
+                        return Err(Error::Configuration(format!(
+                            "Agent '{}' few_shot examples need both an input and an output", name
+                        )));
+                    }
+                }
+                if agent.tools_memory.as_ref().is_some_and(|tools_memory| tools_memory.max_entries == 0) {
+                    return Err(Error::Configuration(format!("Agent '{}' tools_memory max_entries must be at least 1", name)));
+                }
+                for tool in agent.tools.iter().flatten() {
+                    let declared = self.tools.as_ref().map_or(false, |tools| tools.contains_key(tool));
+                    if !declared && !BUILTIN_TOOL_NAMES.contains(&tool.as_str()) {
+                        return Err(Error::Configuration(format!(
+                            "Agent '{}' references unknown tool '{}'", name, tool
+                        )));
+                    }
+                }
+            }
+        }
+
+        // Validate providers
+        if let Some(providers) = &self.providers {
+            for (name, provider) in providers {
+                if !SUPPORTED_PROVIDER_TYPES.contains(&provider.provider_type.as_str()) {
+                    return Err(Error::Configuration(format!(
+                        "Provider '{}' has unsupported type '{}' (expected one of: {})",
+                        name, provider.provider_type, SUPPORTED_PROVIDER_TYPES.join(", ")
+                    )));
+                }
+                if let Some(base_url) = &provider.base_url {
+                    url::Url::parse(base_url).map_err(|e| Error::Configuration(format!(
+                        "Provider '{}' has invalid base_url '{}': {}", name, base_url, e
+                    )))?;
+                }
+            }
+        }
+
+        // Validate RAG pipelines
+        let pipelines = self.rag.iter().map(|rag| ("rag", rag))
+            .chain(self.rag_pipelines.iter().flatten().map(|(name, rag)| (name.as_str(), rag)));
+        for (name, rag) in pipelines {
+            if rag.chunk_size == Some(0) {
+                return Err(Error::Configuration(format!("RAG pipeline '{}' chunk_size must be positive", name)));
+            }
+            if let (Some(size), Some(overlap)) = (rag.chunk_size, rag.chunk_overlap) {
+                if overlap >= size {
+                    return Err(Error::Configuration(format!(
+                        "RAG pipeline '{}' chunk_overlap ({}) must be smaller than chunk_size ({})",
+                        name, overlap, size
+                    )));
+                }
             }
         }
         
+        // Validate channels
+        if let Some(channels) = &self.channels {
+            channels.validate(self.agents.as_ref())?;
+        }
+        
         // Validate workflows
         if let Some(workflows) = &self.workflows {
             for (name, workflow) in workflows {
@@ -214,6 +371,11 @@ impl YamlConfig {
                                 "Workflow '{}' step {} agent name cannot be empty", name, i
                             )));
                         }
+                        if self.agents.is_some() && self.get_agent(agent_name).is_none() {
+                            return Err(Error::Configuration(format!(
+                                "Workflow '{}' step {} references unknown agent '{}'", name, i, agent_name
+                            )));
+                        }
                     }
 
                     // Validate tool reference if specified
@@ -240,11 +402,49 @@ impl YamlConfig {
         Ok(())
     }
     
+    /// Prompt files referenced by agents, resolved against `base_dir`
+    pub fn prompt_files(&self, base_dir: &Path) -> Vec<PathBuf> {
+        self.agents
+            .iter()
+            .flatten()
+            .filter_map(|(_, agent)| agent.instructions_file.as_ref())
+            .map(|file| base_dir.join(file))
+            .collect()
+    }
+    
+    /// Load `instructions_file` contents into `instructions` for every agent
+    pub fn load_prompt_files(&mut self, base_dir: &Path) -> Result<()> {
+        for (name, agent) in self.agents.iter_mut().flatten() {
+            if let Some(file) = &agent.instructions_file {
+                let path = base_dir.join(file);
+                agent.instructions = std::fs::read_to_string(&path).map_err(|e| Error::Configuration(format!(
+                    "Failed to read prompt file {} for agent '{}': {}", path.display(), name, e
+                )))?;
+            }
+        }
+        Ok(())
+    }
+    
     /// Get agent configuration by name
     pub fn get_agent(&self, name: &str) -> Option<&AgentConfig> {
         self.agents.as_ref()?.get(name)
     }
     
+    /// Get provider configuration by name
+    pub fn get_provider(&self, name: &str) -> Option<&ProviderConfig> {
+        self.providers.as_ref()?.get(name)
+    }
+    
+    /// Get RAG pipeline configuration by name
+    ///
+    /// The top-level `rag` section is available under the name `default`.
+    pub fn get_rag_pipeline(&self, name: &str) -> Option<&RagConfig> {
+        self.rag_pipelines
+            .as_ref()
+            .and_then(|pipelines| pipelines.get(name))
+            .or_else(|| if name == "default" { self.rag.as_ref() } else { None })
+    }
+    
     /// Get workflow configuration by name
     pub fn get_workflow(&self, name: &str) -> Option<&WorkflowConfig> {
         self.workflows.as_ref()?.get(name)
@@ -281,6 +481,7 @@ impl Default for YamlConfig {
                 agents.insert("assistant".to_string(), AgentConfig {
                     model: "gpt-4".to_string(),
                     instructions: "You are a helpful assistant".to_string(),
+                    instructions_file: None,
                     tools: Some(vec!["web_search".to_string(), "calculator".to_string()]),
                     temperature: Some(0.7),
                     max_tokens: Some(2000),
@@ -291,6 +492,11 @@ impl Default for YamlConfig {
                         persistence: Some("memory".to_string()),
                     }),
                     voice: None,
+                    provider: None,
+                    rag: None,
+                    guardrails: None,
+                    few_shot: None,
+                    tools_memory: None,
                 });
                 agents
             }),
@@ -315,6 +521,10 @@ impl Default for YamlConfig {
                 }),
             }),
             tools: None,
+            providers: None,
+            rag_pipelines: None,
+            http: None,
+            channels: None,
         }
     }
 }
@@ -374,6 +584,117 @@ workflows:
         assert!(config.validate().is_err());
     }
     
+    #[test]
+    fn test_provider_and_rag_references() {
+        let yaml_content = r#"
+providers:
+  primary:
+    type: openai
+    api_key: vault://openai#api_key
+
+rag_pipelines:
+  docs:
+    vector_store: memory
+    chunk_size: 500
+    chunk_overlap: 50
+
+agents:
+  assistant:
+    model: gpt-4
+    instructions: You are helpful
+    provider: primary
+    rag: docs
+    tools: [calculator]
+"#;
+        
+        let mut config = YamlConfig::from_str(yaml_content).unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.get_provider("primary").unwrap().provider_type, "openai");
+        assert_eq!(config.get_rag_pipeline("docs").unwrap().chunk_size, Some(500));
+        
+        let agent = config.agents.as_mut().unwrap().get_mut("assistant").unwrap();
+        agent.provider = Some("missing".to_string());
+        assert!(config.validate().unwrap_err().to_string().contains("unknown provider"));
+        
+        let agent = config.agents.as_mut().unwrap().get_mut("assistant").unwrap();
+        agent.provider = None;
+        agent.tools = Some(vec!["not_a_tool".to_string()]);
+        assert!(config.validate().unwrap_err().to_string().contains("unknown tool"));
+        
+        let agent = config.agents.as_mut().unwrap().get_mut("assistant").unwrap();
+        agent.tools = None;
+        config.rag_pipelines.as_mut().unwrap().get_mut("docs").unwrap().chunk_overlap = Some(600);
+        assert!(config.validate().unwrap_err().to_string().contains("chunk_overlap"));
+    }
+    
+    #[test]
+    fn test_guardrails_instructions() {
+        let yaml_content = r#"
+agents:
+  support:
+    model: gpt-4
+    instructions: You answer support questions
+    guardrails:
+      blocked_topics: [legal advice]
+      rules: [Never share internal ticket numbers]
+      max_tool_calls: 3
+"#;
+        
+        let config = YamlConfig::from_str(yaml_content).unwrap();
+        let guardrails = config.get_agent("support").unwrap().guardrails.as_ref().unwrap();
+        assert_eq!(guardrails.max_tool_calls, Some(3));
+        
+        let section = guardrails.instructions().unwrap();
+        assert!(section.contains("legal advice"));
+        assert!(section.contains("Never share internal ticket numbers"));
+        assert!(GuardrailsConfig::default().instructions().is_none());
+    }
+    
+    #[test]
+    fn test_few_shot_config() {
+        let yaml_content = r#"
+agents:
+  support:
+    model: gpt-4
+    instructions: You answer support questions
+    few_shot:
+      examples:
+        - input: How do I reset my password?
+          output: Open Settings, choose Security and click Reset password.
+          label: account
+"#;
+        
+        let mut config = YamlConfig::from_str(yaml_content).unwrap();
+        let few_shot = config.get_agent("support").unwrap().few_shot.clone().unwrap();
+        assert_eq!(few_shot.top_k, 3);
+        assert_eq!(few_shot.examples[0].label.as_deref(), Some("account"));
+        assert!(config.validate().is_ok());
+        
+        config.agents.as_mut().unwrap().get_mut("support").unwrap().few_shot.as_mut().unwrap().examples[0].output.clear();
+        assert!(config.validate().unwrap_err().to_string().contains("few_shot"));
+    }
+
+    #[test]
+    fn test_tools_memory_config() {
+        let yaml_content = r#"
+agents:
+  travel:
+    model: gpt-4
+    instructions: You plan trips
+    tools_memory:
+      summarize: true
+"#;
+
+        let mut config = YamlConfig::from_str(yaml_content).unwrap();
+        let tools_memory = config.get_agent("travel").unwrap().tools_memory.clone().unwrap();
+        assert!(tools_memory.summarize);
+        assert_eq!(tools_memory.max_entries, 20);
+        assert!(config.validate().is_ok());
+
+        config.agents.as_mut().unwrap().get_mut("travel").unwrap().tools_memory.as_mut().unwrap().max_entries = 0;
+        assert!(config.validate().unwrap_err().to_string().contains("tools_memory"));
+    }
+    
     #[test]
     fn test_yaml_config_serialization() {
         let config = YamlConfig::default();
@@ -0,0 +1,349 @@
+//! 微调数据集导出
+//!
+//! 将会话存储中的对话按代理、创建时间和用户反馈筛选后，转换为微调用的JSONL数据集：
+//!
+//! - [`FineTuningFormat::OpenAi`]：`{"messages": [{"role", "content"}]}`
+//! - [`FineTuningFormat::Anthropic`]：`{"system": "...", "messages": [...]}`，系统提示单独存放，
+//!   对话从用户消息开始且用户与助手交替出现
+//! - [`FineTuningFormat::ShareGpt`]：`{"conversations": [{"from": "system"|"human"|"gpt", "value"}]}`
+//!
+//! 助手回复收到修正时以修正后的回答替换原回答；收到负面反馈且没有修正的回复及其之后的对话被丢弃。
+//! 所有消息在导出前经过[`Redactor`]脱敏，默认替换邮箱地址和银行卡号。
+
+use std::io::Write;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::agent::feedback::{Feedback, FeedbackQuery};
+use crate::agent::output_processor::Redactor;
+use crate::agent::session::{SessionData, SessionQuery, SessionStorage};
+use crate::error::{Error, Result};
+use crate::llm::Role;
+
+/// 数据集格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FineTuningFormat {
+    /// OpenAI聊天微调格式
+    OpenAi,
+    /// Anthropic聊天微调格式
+    Anthropic,
+    /// ShareGPT对话格式
+    ShareGpt,
+}
+
+/// 按用户反馈筛选对话
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedbackFilter {
+    /// 导出所有对话
+    #[default]
+    All,
+    /// 只导出收到过反馈的对话
+    Rated,
+    /// 只导出包含被认可或被修正回复的对话，截止到最后一条这样的回复
+    Approved,
+}
+
+/// 导出结果
+#[derive(Debug, Clone, Default)]
+pub struct FineTuningExport {
+    /// 数据集记录，每条对应JSONL中的一行
+    pub records: Vec<Value>,
+    /// 满足代理和时间条件的会话数
+    pub sessions_scanned: usize,
+    /// 因反馈筛选或没有完整问答而跳过的会话数
+    pub skipped: usize,
+}
+
+impl FineTuningExport {
+    /// 将记录写入JSONL文件，每行一条记录
+    pub fn write_jsonl<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        if let Some(parent) = path.as_ref().parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        for record in &self.records {
+            writeln!(file, "{}", serde_json::to_string(record)?)?;
+        }
+        file.flush()?;
+        Ok(())
+    }
+}
+
+/// 微调数据集导出器
+#[derive(Debug, Clone)]
+pub struct FineTuningExporter {
+    format: FineTuningFormat,
+    query: SessionQuery,
+    feedback: FeedbackFilter,
+    redactor: Redactor,
+}
+
+impl FineTuningExporter {
+    /// 创建导出所有对话的导出器，使用默认脱敏规则
+    pub fn new(format: FineTuningFormat) -> Self {
+        Self {
+            format,
+            query: SessionQuery {
+                user_id: None,
+                agent_name: None,
+                state: None,
+                tags: Vec::new(),
+                created_after: None,
+                created_before: None,
+                limit: None,
+                offset: None,
+            },
+            feedback: FeedbackFilter::default(),
+            redactor: Redactor::with_defaults(),
+        }
+    }
+
+    /// 只导出指定代理的对话
+    pub fn for_agent(mut self, agent_name: impl Into<String>) -> Self {
+        self.query.agent_name = Some(agent_name.into());
+        self
+    }
+
+    /// 只导出在此时间范围内创建的对话
+    pub fn between(mut self, after: Option<DateTime<Utc>>, before: Option<DateTime<Utc>>) -> Self {
+        self.query.created_after = after;
+        self.query.created_before = before;
+        self
+    }
+
+    /// 设置反馈筛选条件
+    pub fn with_feedback_filter(mut self, filter: FeedbackFilter) -> Self {
+        self.feedback = filter;
+        self
+    }
+
+    /// 替换脱敏规则
+    pub fn with_redactor(mut self, redactor: Redactor) -> Self {
+        self.redactor = redactor;
+        self
+    }
+
+    /// 从会话存储导出数据集，按会话创建时间排序
+    ///
+    /// 不支持反馈的存储只能在[`FeedbackFilter::All`]下导出。
+    pub async fn export(&self, storage: &dyn SessionStorage) -> Result<FineTuningExport> {
+        let mut sessions = storage.search_sessions(&self.query).await?;
+        sessions.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+
+        let mut export = FineTuningExport::default();
+        for metadata in sessions {
+            let Some(session) = storage.load_session(&metadata.session_id).await? else {
+                continue;
+            };
+            export.sessions_scanned += 1;
+
+            let feedback = match storage.list_feedback(&FeedbackQuery::for_session(&metadata.session_id)).await {
+                Ok(feedback) => feedback,
+                Err(Error::Unsupported(_)) if self.feedback == FeedbackFilter::All => Vec::new(),
+                Err(e) => return Err(e),
+            };
+            match self.convert(&session, &feedback) {
+                Some(record) => export.records.push(record),
+                None => export.skipped += 1,
+            }
+        }
+        Ok(export)
+    }
+
+    /// 将一个会话及其反馈转换为数据集记录，没有可用的问答时返回`None`
+    pub fn convert(&self, session: &SessionData, feedback: &[Feedback]) -> Option<Value> {
+        let feedback: Vec<&Feedback> = feedback.iter()
+            .filter(|entry| entry.session_id == session.metadata.session_id)
+            .collect();
+        if self.feedback == FeedbackFilter::Rated && feedback.is_empty() {
+            return None;
+        }
+
+        let mut turns: Vec<(Role, String)> = Vec::new();
+        let mut approved_len = None;
+        for (index, message) in session.messages.iter().enumerate() {
+            match message.role {
+                Role::System | Role::User => turns.push((message.role.clone(), self.redactor.redact(&message.content))),
+                Role::Assistant => {
+                    let entries: Vec<&&Feedback> = feedback.iter().filter(|entry| entry.message_index == index).collect();
+                    // 反馈按提交时间排序，采用最新的修正
+                    if let Some(correction) = entries.iter().rev().find_map(|entry| entry.correction.as_deref()) {
+                        turns.push((Role::Assistant, self.redactor.redact(correction)));
+                        approved_len = Some(turns.len());
+                    } else if entries.iter().any(|entry| entry.is_negative()) {
+                        break;
+                    } else if !message.content.trim().is_empty() {
+                        turns.push((Role::Assistant, self.redactor.redact(&message.content)));
+                        if entries.iter().any(|entry| entry.is_positive()) {
+                            approved_len = Some(turns.len());
+                        }
+                    }
+                }
+                // 工具调用结果不属于微调对话
+                _ => {}
+            }
+        }
+
+        if self.feedback == FeedbackFilter::Approved {
+            turns.truncate(approved_len?);
+        }
+        while turns.last().is_some_and(|(role, _)| *role != Role::Assistant) {
+            turns.pop();
+        }
+        if !turns.iter().any(|(role, _)| *role == Role::User) {
+            return None;
+        }
+
+        Some(match self.format {
+            FineTuningFormat::OpenAi => openai_record(&turns),
+            FineTuningFormat::Anthropic => anthropic_record(&turns)?,
+            FineTuningFormat::ShareGpt => sharegpt_record(&turns),
+        })
+    }
+}
+
+fn openai_record(turns: &[(Role, String)]) -> Value {
+    let messages: Vec<Value> = turns.iter()
+        .map(|(role, content)| json!({ "role": role.to_string(), "content": content }))
+        .collect();
+    json!({ "messages": messages })
+}
+
+/// 系统提示合并到`system`字段，连续的同角色消息合并，丢弃首条用户消息之前的助手消息
+fn anthropic_record(turns: &[(Role, String)]) -> Option<Value> {
+    let system: Vec<&str> = turns.iter()
+        .filter(|(role, _)| *role == Role::System)
+        .map(|(_, content)| content.as_str())
+        .collect();
+
+    let mut messages: Vec<(&Role, String)> = Vec::new();
+    for (role, content) in turns.iter().filter(|(role, _)| *role != Role::System) {
+        match messages.last_mut() {
+            Some((last, merged)) if *last == role => {
+                merged.push_str("\n\n");
+                merged.push_str(content);
+            }
+            None if *role != Role::User => {}
+            _ => messages.push((role, content.clone())),
+        }
+    }
+    if messages.is_empty() {
+        return None;
+    }
+
+    let messages: Vec<Value> = messages.into_iter()
+        .map(|(role, content)| json!({ "role": role.to_string(), "content": content }))
+        .collect();
+    let mut record = json!({ "messages": messages });
+    if !system.is_empty() {
+        record["system"] = Value::String(system.join("\n\n"));
+    }
+    Some(record)
+}
+
+fn sharegpt_record(turns: &[(Role, String)]) -> Value {
+    let conversations: Vec<Value> = turns.iter()
+        .map(|(role, content)| {
+            let from = match role {
+                Role::System => "system",
+                Role::User => "human",
+                _ => "gpt",
+            };
+            json!({ "from": from, "value": content })
+        })
+        .collect();
+    json!({ "conversations": conversations })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::feedback::Thumbs;
+    use crate::agent::session::{MemorySessionStorage, SessionManager};
+    use crate::llm::Message;
+    use std::sync::Arc;
+
+    fn message(role: Role, content: &str) -> Message {
+        Message { role, content: content.to_string(), metadata: None, name: None }
+    }
+
+    async fn storage() -> Arc<MemorySessionStorage> {
+        let storage = Arc::new(MemorySessionStorage::new());
+        let manager = SessionManager::new(storage.clone());
+        let conversations = [
+            ("s1", "support", vec![
+                (Role::System, "Be brief."),
+                (Role::User, "My email is ann@example.com, can you help?"),
+                (Role::Assistant, "Sure."),
+                (Role::User, "Where is my order?"),
+                (Role::Assistant, "It is lost."),
+                (Role::User, "Really?"),
+                (Role::Assistant, "Yes."),
+            ]),
+            ("s2", "support", vec![(Role::User, "Hi"), (Role::Assistant, "Hello!")]),
+            ("s3", "sales", vec![(Role::User, "Price?"), (Role::Assistant, "$10.")]),
+        ];
+        for (id, agent, messages) in conversations {
+            manager.create_session(id.to_string(), agent.to_string(), None).await.unwrap();
+            for (role, content) in messages {
+                manager.add_message(id, message(role, content)).await.unwrap();
+            }
+        }
+        manager.add_feedback(Feedback::new("s1", 2).with_thumbs(Thumbs::Up)).await.unwrap();
+        manager.add_feedback(Feedback::new("s1", 4).with_thumbs(Thumbs::Down).with_correction("It ships tomorrow.")).await.unwrap();
+        manager.add_feedback(Feedback::new("s1", 6).with_rating(1)).await.unwrap();
+        storage
+    }
+
+    #[tokio::test]
+    async fn test_export_applies_feedback_and_scrubs_pii() {
+        let storage = storage().await;
+        let export = FineTuningExporter::new(FineTuningFormat::OpenAi)
+            .for_agent("support")
+            .with_feedback_filter(FeedbackFilter::Rated)
+            .export(storage.as_ref())
+            .await
+            .unwrap();
+        assert_eq!((export.sessions_scanned, export.skipped, export.records.len()), (2, 1, 1));
+
+        // 被修正的回复被替换，负面反馈的回复及之后的对话被丢弃
+        let messages = export.records[0]["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 5);
+        assert_eq!(messages[1]["content"], "My email is [REDACTED_EMAIL], can you help?");
+        assert_eq!(messages[4], json!({ "role": "assistant", "content": "It ships tomorrow." }));
+
+        let all = FineTuningExporter::new(FineTuningFormat::OpenAi).export(storage.as_ref()).await.unwrap();
+        assert_eq!(all.records.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_anthropic_and_sharegpt_formats() {
+        let storage = storage().await;
+        let session = storage.load_session("s1").await.unwrap().unwrap();
+        let feedback = storage.list_feedback(&FeedbackQuery::for_session("s1")).await.unwrap();
+
+        let approved = FineTuningExporter::new(FineTuningFormat::Anthropic)
+            .with_feedback_filter(FeedbackFilter::Approved);
+        let record = approved.convert(&session, &feedback).unwrap();
+        assert_eq!(record["system"], "Be brief.");
+        let roles: Vec<&str> = record["messages"].as_array().unwrap().iter()
+            .map(|message| message["role"].as_str().unwrap())
+            .collect();
+        assert_eq!(roles, vec!["user", "assistant", "user", "assistant"]);
+
+        let sharegpt = FineTuningExporter::new(FineTuningFormat::ShareGpt).with_redactor(Redactor::new());
+        let record = sharegpt.convert(&session, &feedback).unwrap();
+        let conversations = record["conversations"].as_array().unwrap();
+        assert_eq!(conversations[0], json!({ "from": "system", "value": "Be brief." }));
+        assert_eq!(conversations[1]["value"], "My email is ann@example.com, can you help?");
+        assert_eq!(conversations[4], json!({ "from": "gpt", "value": "It ships tomorrow." }));
+
+        // 没有被认可的回复时不导出
+        assert!(approved.convert(&storage.load_session("s2").await.unwrap().unwrap(), &[]).is_none());
+    }
+}
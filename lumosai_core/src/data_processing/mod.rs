@@ -4,6 +4,10 @@ use async_trait::async_trait;
 use serde::{Serialize, Deserialize};
 use crate::error::{Error, Result};
 
+pub mod fine_tuning;
+
+pub use fine_tuning::{FeedbackFilter, FineTuningExport, FineTuningExporter, FineTuningFormat};
+
 /// 数据类型
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum DataType {
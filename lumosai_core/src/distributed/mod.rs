@@ -6,6 +6,7 @@ use serde::{Serialize, Deserialize};
 use tokio::sync::{RwLock, Mutex};
 use crate::error::{Error, Result};
 use crate::agent::trait_def::Agent;
+use crate::config::SecretResolver;
 
 /// 分布式Agent管理器
 pub struct DistributedAgentManager {
@@ -438,6 +439,19 @@ pub struct AgentDeploymentConfig {
     pub preferred_region: Option<String>,
     pub resource_requirements: ResourceRequirements,
     pub environment_variables: HashMap<String, String>,
+    /// Environment variables sourced from secret references (e.g. `vault://openai#api_key`),
+    /// resolved on the target node rather than shipped in plain text
+    #[serde(default)]
+    pub secrets: HashMap<String, String>,
+}
+
+impl AgentDeploymentConfig {
+    /// Build the runtime environment, resolving secret references through `resolver`
+    pub async fn resolve_environment(&self, resolver: &SecretResolver) -> Result<HashMap<String, String>> {
+        let mut environment = self.environment_variables.clone();
+        environment.extend(resolver.resolve_map(&self.secrets).await?);
+        Ok(environment)
+    }
 }
 
 /// 资源需求
@@ -1,9 +1,12 @@
 //! Error types for the Lumosai framework
 
 pub mod friendly;
+pub mod taxonomy;
 
 use thiserror::Error;
 
+pub use taxonomy::{ErrorKind, ErrorResponse};
+
 /// Result type for Lumosai operations
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -192,6 +195,10 @@ pub enum Error {
     #[error("Timeout: {0}")]
     Timeout(String),
 
+    /// Cancelled operations, e.g. after the client disconnected
+    #[error("Cancelled: {0}")]
+    Cancelled(String),
+
     /// Documentation errors
     #[error("Documentation error: {0}")]
     Documentation(String),
@@ -203,6 +210,76 @@ pub enum Error {
     /// Distributed system errors
     #[error("Distributed system error: {0}")]
     Distributed(String),
+
+    /// Model provider errors (structured)
+    #[error("Provider error ({provider}): {message}")]
+    ProviderError {
+        provider: String,
+        message: String,
+        status_code: Option<u16>,
+        retryable: bool,
+    },
+
+    /// Tool execution errors (structured)
+    #[error("Tool '{tool}' failed: {message}")]
+    ToolError {
+        tool: String,
+        message: String,
+        retryable: bool,
+    },
+
+    /// Guardrail violations; the message is shown to end users
+    #[error("Guardrail '{guardrail}' violated: {message}")]
+    GuardrailViolation {
+        guardrail: String,
+        message: String,
+    },
+
+    /// Retrieval errors (structured)
+    #[error("Retrieval error ({source_name}): {message}")]
+    RetrievalError {
+        source_name: String,
+        message: String,
+        retryable: bool,
+    },
+}
+
+impl Error {
+    /// Create a provider error; rate limits, request timeouts and server errors are retryable
+    pub fn provider(provider: impl Into<String>, status_code: Option<u16>, message: impl Into<String>) -> Self {
+        Error::ProviderError {
+            provider: provider.into(),
+            message: message.into(),
+            status_code,
+            retryable: status_code.is_some_and(taxonomy::is_retryable_status),
+        }
+    }
+
+    /// Create a tool execution error
+    pub fn tool_failed(tool: impl Into<String>, message: impl Into<String>, retryable: bool) -> Self {
+        Error::ToolError {
+            tool: tool.into(),
+            message: message.into(),
+            retryable,
+        }
+    }
+
+    /// Create a guardrail violation
+    pub fn guardrail(guardrail: impl Into<String>, message: impl Into<String>) -> Self {
+        Error::GuardrailViolation {
+            guardrail: guardrail.into(),
+            message: message.into(),
+        }
+    }
+
+    /// Create a retrieval error for a vector store, index or knowledge base
+    pub fn retrieval(source_name: impl Into<String>, message: impl Into<String>, retryable: bool) -> Self {
+        Error::RetrievalError {
+            source_name: source_name.into(),
+            message: message.into(),
+            retryable,
+        }
+    }
 }
 
 impl From<&str> for Error {
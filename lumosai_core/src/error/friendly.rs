@@ -91,7 +91,7 @@ impl FriendlyError {
             Error::Configuration(_) => ErrorCategory::Configuration,
             Error::Authentication(_) => ErrorCategory::Authentication,
             Error::Network(_) => ErrorCategory::Network,
-            Error::Tool(_) => ErrorCategory::Tool,
+            Error::Tool(_) | Error::ToolError { .. } => ErrorCategory::Tool,
             Error::Agent(_) => ErrorCategory::Agent,
             Error::Memory(_) => ErrorCategory::Memory,
             Error::Validation(_) => ErrorCategory::Validation,
@@ -0,0 +1,321 @@
+//! Structured error taxonomy
+//!
+//! Every [`Error`] belongs to an [`ErrorKind`] that decides whether the failed operation can be
+//! retried, which HTTP status the server returns and which message is safe to show end users.
+//! Server layers and language bindings use these instead of matching on individual variants.
+
+use serde::{Deserialize, Serialize};
+
+use super::Error;
+
+/// Category of an error
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    /// The model provider failed or rejected the request
+    Provider,
+    /// A tool call failed
+    Tool,
+    /// A guardrail blocked the input or output
+    Guardrail,
+    /// Searching a vector store or knowledge base failed
+    Retrieval,
+    /// The request is malformed or fails validation
+    InvalidInput,
+    /// The requested resource does not exist
+    NotFound,
+    /// The resource already exists or is in the wrong state
+    Conflict,
+    /// The caller is not authenticated
+    Unauthenticated,
+    /// The caller may not perform the operation
+    PermissionDenied,
+    /// A rate limit was hit
+    RateLimited,
+    /// The operation timed out
+    Timeout,
+    /// The caller cancelled the operation or disconnected
+    Cancelled,
+    /// A dependency is unreachable or the runtime is shutting down
+    Unavailable,
+    /// The operation is not supported
+    Unsupported,
+    /// The framework is misconfigured
+    Configuration,
+    /// Any other internal failure
+    Internal,
+}
+
+impl ErrorKind {
+    /// Stable machine-readable code
+    pub fn code(&self) -> &'static str {
+        match self {
+            ErrorKind::Provider => "provider_error",
+            ErrorKind::Tool => "tool_error",
+            ErrorKind::Guardrail => "guardrail_violation",
+            ErrorKind::Retrieval => "retrieval_error",
+            ErrorKind::InvalidInput => "invalid_input",
+            ErrorKind::NotFound => "not_found",
+            ErrorKind::Conflict => "conflict",
+            ErrorKind::Unauthenticated => "unauthenticated",
+            ErrorKind::PermissionDenied => "permission_denied",
+            ErrorKind::RateLimited => "rate_limited",
+            ErrorKind::Timeout => "timeout",
+            ErrorKind::Cancelled => "cancelled",
+            ErrorKind::Unavailable => "unavailable",
+            ErrorKind::Unsupported => "unsupported",
+            ErrorKind::Configuration => "configuration_error",
+            ErrorKind::Internal => "internal_error",
+        }
+    }
+
+    /// HTTP status code for errors of this kind
+    pub fn http_status(&self) -> u16 {
+        match self {
+            ErrorKind::InvalidInput => 400,
+            ErrorKind::Unauthenticated => 401,
+            ErrorKind::PermissionDenied => 403,
+            ErrorKind::NotFound => 404,
+            ErrorKind::Conflict => 409,
+            ErrorKind::Guardrail => 422,
+            ErrorKind::RateLimited => 429,
+            // 499 Client Closed Request
+            ErrorKind::Cancelled => 499,
+            ErrorKind::Unsupported => 501,
+            ErrorKind::Provider => 502,
+            ErrorKind::Unavailable => 503,
+            ErrorKind::Timeout => 504,
+            ErrorKind::Tool | ErrorKind::Retrieval | ErrorKind::Configuration | ErrorKind::Internal => 500,
+        }
+    }
+
+    /// Whether errors of this kind are retryable when the error itself carries no flag
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, ErrorKind::RateLimited | ErrorKind::Timeout | ErrorKind::Unavailable)
+    }
+
+    /// Generic message that reveals nothing about the failure
+    fn default_message(&self) -> &'static str {
+        match self {
+            ErrorKind::Provider => "The language model provider could not complete the request.",
+            ErrorKind::Tool => "A tool needed to answer the request failed.",
+            ErrorKind::Guardrail => "The request was blocked by a content policy.",
+            ErrorKind::Retrieval => "The knowledge base could not be searched.",
+            ErrorKind::InvalidInput => "The request is invalid.",
+            ErrorKind::NotFound => "The requested resource was not found.",
+            ErrorKind::Conflict => "The request conflicts with the current state of the resource.",
+            ErrorKind::Unauthenticated => "Authentication is required.",
+            ErrorKind::PermissionDenied => "You do not have permission to perform this action.",
+            ErrorKind::RateLimited => "Too many requests. Please wait a moment and try again.",
+            ErrorKind::Timeout => "The request timed out. Please try again.",
+            ErrorKind::Cancelled => "The request was cancelled.",
+            ErrorKind::Unavailable => "The service is temporarily unavailable. Please try again later.",
+            ErrorKind::Unsupported => "This operation is not supported.",
+            ErrorKind::Configuration | ErrorKind::Internal => "An internal error occurred.",
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+/// Error body returned to API clients
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorResponse {
+    /// Machine-readable code, see [`ErrorKind::code`]
+    pub code: String,
+    /// Message that is safe to show end users
+    pub message: String,
+    /// Whether the request may succeed if retried
+    pub retryable: bool,
+    /// HTTP status code
+    pub status: u16,
+}
+
+/// Whether an upstream HTTP status indicates a transient failure
+pub(crate) fn is_retryable_status(status: u16) -> bool {
+    status == 408 || status == 429 || status >= 500
+}
+
+/// Kind of an upstream HTTP status
+fn status_kind(status: u16) -> Option<ErrorKind> {
+    match status {
+        408 | 504 => Some(ErrorKind::Timeout),
+        429 => Some(ErrorKind::RateLimited),
+        503 => Some(ErrorKind::Unavailable),
+        _ => None,
+    }
+}
+
+impl Error {
+    /// Category of the error
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::ProviderError { status_code, .. } => {
+                status_code.and_then(status_kind).unwrap_or(ErrorKind::Provider)
+            },
+            Error::ApiError { status_code, .. } => {
+                status_code.and_then(status_kind).unwrap_or(ErrorKind::Provider)
+            },
+            Error::Http(e) => {
+                if e.is_timeout() {
+                    ErrorKind::Timeout
+                } else if e.is_connect() {
+                    ErrorKind::Unavailable
+                } else {
+                    e.status().and_then(|status| status_kind(status.as_u16())).unwrap_or(ErrorKind::Provider)
+                }
+            },
+            Error::Llm(_) | Error::LlmProvider(_) => ErrorKind::Provider,
+            Error::Tool(_) | Error::ToolError { .. } => ErrorKind::Tool,
+            Error::GuardrailViolation { .. } => ErrorKind::Guardrail,
+            Error::Rag(_) | Error::VectorStore(_) | Error::RetrievalError { .. } => ErrorKind::Retrieval,
+            Error::InvalidInput(_)
+            | Error::InvalidParams(_)
+            | Error::Validation(_)
+            | Error::ValidationError(_)
+            | Error::SchemaError(_)
+            | Error::Constraint(_)
+            | Error::InvalidOperation(_) => ErrorKind::InvalidInput,
+            Error::NotFound(_) => ErrorKind::NotFound,
+            Error::AlreadyExists(_) | Error::InvalidState(_) => ErrorKind::Conflict,
+            Error::Authentication(_) => ErrorKind::Unauthenticated,
+            Error::AccessDenied(_) | Error::SecurityError(_) => ErrorKind::PermissionDenied,
+            Error::Timeout(_) => ErrorKind::Timeout,
+            Error::Cancelled(_) => ErrorKind::Cancelled,
+            Error::Unavailable(_) | Error::Network(_) | Error::NetworkError { .. } => ErrorKind::Unavailable,
+            Error::Unsupported(_) => ErrorKind::Unsupported,
+            Error::Configuration(_) | Error::Config(_) | Error::ConfigError { .. } => ErrorKind::Configuration,
+            Error::Agent(_)
+            | Error::Memory(_)
+            | Error::Storage(_)
+            | Error::SystemTime(_)
+            | Error::Workflow(_)
+            | Error::Json(_)
+            | Error::Io(_)
+            | Error::Other(_)
+            | Error::Lock(_)
+            | Error::Internal(_)
+            | Error::Parsing(_)
+            | Error::ParseError { .. }
+            | Error::Serialization(_)
+            | Error::Event(_)
+            | Error::Documentation(_)
+            | Error::Plugin(_)
+            | Error::Distributed(_) => ErrorKind::Internal,
+        }
+    }
+
+    /// Whether the failed operation may succeed if retried unchanged
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::ProviderError { retryable, .. }
+            | Error::ToolError { retryable, .. }
+            | Error::RetrievalError { retryable, .. } => *retryable,
+            Error::ApiError { status_code, .. } => status_code.is_some_and(is_retryable_status),
+            Error::Http(e) => {
+                e.is_timeout() || e.is_connect() || e.status().is_some_and(|status| is_retryable_status(status.as_u16()))
+            },
+            _ => self.kind().is_retryable(),
+        }
+    }
+
+    /// HTTP status code the server returns for this error
+    pub fn http_status(&self) -> u16 {
+        self.kind().http_status()
+    }
+
+    /// Message that is safe to show end users
+    ///
+    /// Guardrail violations and client errors keep their own message; provider, tool and
+    /// internal failures are replaced by a generic message so that API keys, prompts and
+    /// stack details never leak. Use `to_string()` for logs.
+    pub fn user_message(&self) -> String {
+        let detail = match self {
+            Error::GuardrailViolation { message, .. } => Some(message),
+            Error::InvalidInput(message)
+            | Error::InvalidParams(message)
+            | Error::Validation(message)
+            | Error::ValidationError(message)
+            | Error::SchemaError(message)
+            | Error::Constraint(message)
+            | Error::InvalidOperation(message)
+            | Error::NotFound(message)
+            | Error::AlreadyExists(message)
+            | Error::InvalidState(message)
+            | Error::Unsupported(message) => Some(message),
+            _ => None,
+        };
+        match detail {
+            Some(message) if !message.is_empty() => message.clone(),
+            _ => self.kind().default_message().to_string(),
+        }
+    }
+
+    /// Error body for API clients
+    pub fn to_response(&self) -> ErrorResponse {
+        let kind = self.kind();
+        ErrorResponse {
+            code: kind.code().to_string(),
+            message: self.user_message(),
+            retryable: self.is_retryable(),
+            status: kind.http_status(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_provider_errors_are_classified_by_status() {
+        let overloaded = Error::provider("openai", Some(503), "OpenAI API returned error status 503: overloaded");
+        assert_eq!(overloaded.kind(), ErrorKind::Unavailable);
+        assert!(overloaded.is_retryable());
+        assert_eq!(overloaded.http_status(), 503);
+
+        let limited = Error::provider("anthropic", Some(429), "rate limited");
+        assert_eq!(limited.kind(), ErrorKind::RateLimited);
+        assert!(limited.is_retryable());
+        assert_eq!(limited.http_status(), 429);
+
+        let rejected = Error::provider("openai", Some(401), "Incorrect API key provided: sk-123");
+        assert_eq!(rejected.kind(), ErrorKind::Provider);
+        assert!(!rejected.is_retryable());
+        assert_eq!(rejected.http_status(), 502);
+        // Provider details such as API keys never reach end users
+        assert!(!rejected.user_message().contains("sk-123"));
+    }
+
+    #[test]
+    fn test_user_messages_and_responses() {
+        let blocked = Error::guardrail("pii", "Responses may not contain credit card numbers");
+        assert_eq!(blocked.to_response(), ErrorResponse {
+            code: "guardrail_violation".to_string(),
+            message: "Responses may not contain credit card numbers".to_string(),
+            retryable: false,
+            status: 422,
+        });
+
+        let invalid = Error::InvalidInput("`message` must not be empty".to_string());
+        assert_eq!(invalid.http_status(), 400);
+        assert_eq!(invalid.user_message(), "`message` must not be empty");
+
+        let tool = Error::tool_failed("database", "connection refused at 10.0.0.5:5432", true);
+        assert_eq!(tool.kind(), ErrorKind::Tool);
+        assert!(tool.is_retryable());
+        assert_eq!(tool.user_message(), "A tool needed to answer the request failed.");
+
+        let internal = Error::Internal("lock poisoned".to_string());
+        assert_eq!(internal.to_response().code, "internal_error");
+        assert_eq!(internal.http_status(), 500);
+        assert!(!internal.is_retryable());
+
+        assert!(Error::Timeout("generation took longer than 30s".to_string()).is_retryable());
+        assert_eq!(Error::retrieval("docs", "index missing", false).kind(), ErrorKind::Retrieval);
+    }
+}
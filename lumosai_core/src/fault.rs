@@ -0,0 +1,549 @@
+//! 故障注入
+//!
+//! [`FaultInjector`]包装LLM提供商、工具或向量存储，按配置的概率注入随机延迟、可重试错误和
+//! 畸形响应，用于系统地验证重试、降级和回退逻辑。固定[`FaultConfig::seed`]后，同样的调用序列
+//! 得到同样的故障序列，失败的测试可以稳定复现。
+//!
+//! 本模块只在测试构建或启用`fault-injection`特性时编译，不会进入生产构建：
+//!
+//! ```rust,ignore
+//! let config = FaultConfig::new().with_error_rate(0.2).with_latency(0.5, Duration::from_millis(50), Duration::from_millis(500)).with_seed(7);
+//! let provider: Arc<dyn LlmProvider> = Arc::new(FaultInjector::new(provider, config));
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::stream::{BoxStream, StreamExt};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde_json::Value;
+
+use crate::base::Base;
+use crate::llm::function_calling::{FunctionDefinition, ToolChoice};
+use crate::llm::provider::FunctionCallingResponse;
+use crate::llm::{CacheUsage, LlmOptions, LlmProvider, Message};
+use crate::tool::{Tool, ToolExecutionContext, ToolExecutionOptions, ToolSchema};
+use crate::vector::{FilterCondition, IndexStats, QueryResult, SimilarityMetric, VectorError, VectorStorage};
+use crate::{Error, Result};
+
+/// 注入错误的说明，便于在日志中区分注入的故障和真实故障
+const INJECTED: &str = "injected fault";
+
+/// 故障注入配置
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FaultConfig {
+    /// 调用前注入延迟的概率
+    pub latency_rate: f64,
+    /// 注入延迟的最小值
+    pub min_latency: Duration,
+    /// 注入延迟的最大值
+    pub max_latency: Duration,
+    /// 调用失败的概率，注入的错误均可重试
+    pub error_rate: f64,
+    /// 返回畸形响应的概率
+    pub malformed_rate: f64,
+    /// 随机种子，为空时每次运行的故障序列不同
+    pub seed: Option<u64>,
+}
+
+impl FaultConfig {
+    /// 不注入任何故障的配置
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 以`rate`的概率注入`min`到`max`之间的随机延迟
+    pub fn with_latency(mut self, rate: f64, min: Duration, max: Duration) -> Self {
+        self.latency_rate = rate.clamp(0.0, 1.0);
+        self.min_latency = min.min(max);
+        self.max_latency = max.max(min);
+        self
+    }
+
+    /// 以`rate`的概率返回可重试错误
+    pub fn with_error_rate(mut self, rate: f64) -> Self {
+        self.error_rate = rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// 以`rate`的概率返回畸形响应
+    pub fn with_malformed_rate(mut self, rate: f64) -> Self {
+        self.malformed_rate = rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// 固定随机种子
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+}
+
+/// 已注入故障的统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FaultStats {
+    /// 经过注入器的调用数
+    pub calls: u64,
+    /// 注入延迟的调用数
+    pub delayed: u64,
+    /// 注入错误的调用数
+    pub errors: u64,
+    /// 返回畸形响应的调用数
+    pub malformed: u64,
+}
+
+/// 单次调用的注入结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Pass,
+    Error,
+    Malformed,
+}
+
+struct FaultState {
+    rng: StdRng,
+    stats: FaultStats,
+}
+
+/// 为被包装的组件注入故障
+///
+/// 包装`Arc<dyn LlmProvider>`、`Arc<dyn Tool>`或`Arc<dyn VectorStorage>`后，注入器本身实现对应的
+/// trait，可以直接替换原组件。克隆的注入器共享随机数序列和统计。
+pub struct FaultInjector<T: ?Sized> {
+    inner: Arc<T>,
+    config: FaultConfig,
+    state: Arc<Mutex<FaultState>>,
+}
+
+impl<T: ?Sized> FaultInjector<T> {
+    /// 按`config`为`inner`注入故障
+    pub fn new(inner: Arc<T>, config: FaultConfig) -> Self {
+        let rng = match config.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        Self {
+            inner,
+            config,
+            state: Arc::new(Mutex::new(FaultState { rng, stats: FaultStats::default() })),
+        }
+    }
+
+    /// 被包装的组件
+    pub fn inner(&self) -> &Arc<T> {
+        &self.inner
+    }
+
+    /// 注入配置
+    pub fn config(&self) -> &FaultConfig {
+        &self.config
+    }
+
+    /// 已注入故障的统计
+    pub fn stats(&self) -> FaultStats {
+        self.state.lock().map(|state| state.stats).unwrap_or_default()
+    }
+
+    /// 为一次调用抽取故障并等待注入的延迟；`malformable`为假的调用不会返回畸形响应
+    async fn inject(&self, malformable: bool) -> Outcome {
+        let (delay, outcome) = {
+            let mut state = self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            let FaultState { rng, stats } = &mut *state;
+            stats.calls += 1;
+
+            let delay = (rng.gen::<f64>() < self.config.latency_rate)
+                .then(|| rng.gen_range(self.config.min_latency..=self.config.max_latency));
+            if delay.is_some() {
+                stats.delayed += 1;
+            }
+
+            let roll = rng.gen::<f64>();
+            let outcome = if roll < self.config.error_rate {
+                stats.errors += 1;
+                Outcome::Error
+            } else if malformable && roll < self.config.error_rate + self.config.malformed_rate {
+                stats.malformed += 1;
+                Outcome::Malformed
+            } else {
+                Outcome::Pass
+            };
+            (delay, outcome)
+        };
+
+        if let Some(delay) = delay {
+            tokio::time::sleep(delay).await;
+        }
+        outcome
+    }
+}
+
+impl<T: ?Sized> Clone for FaultInjector<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            config: self.config.clone(),
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl<T: ?Sized> fmt::Debug for FaultInjector<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FaultInjector")
+            .field("config", &self.config)
+            .field("stats", &self.stats())
+            .finish()
+    }
+}
+
+/// 截去文本的后半部分，模拟被截断的响应
+fn truncate(text: &str) -> String {
+    let keep = text.chars().count() / 2;
+    text.chars().take(keep).collect()
+}
+
+#[async_trait]
+impl<P: LlmProvider + ?Sized> LlmProvider for FaultInjector<P> {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn generate(&self, prompt: &str, options: &LlmOptions) -> Result<String> {
+        match self.inject(true).await {
+            Outcome::Error => Err(Error::provider(self.inner.name(), Some(503), INJECTED)),
+            Outcome::Malformed => self.inner.generate(prompt, options).await.map(|text| truncate(&text)),
+            Outcome::Pass => self.inner.generate(prompt, options).await,
+        }
+    }
+
+    async fn generate_with_messages(&self, messages: &[Message], options: &LlmOptions) -> Result<String> {
+        match self.inject(true).await {
+            Outcome::Error => Err(Error::provider(self.inner.name(), Some(503), INJECTED)),
+            Outcome::Malformed => self.inner.generate_with_messages(messages, options).await.map(|text| truncate(&text)),
+            Outcome::Pass => self.inner.generate_with_messages(messages, options).await,
+        }
+    }
+
+    async fn generate_stream<'a>(
+        &'a self,
+        prompt: &'a str,
+        options: &'a LlmOptions,
+    ) -> Result<BoxStream<'a, Result<String>>> {
+        match self.inject(true).await {
+            Outcome::Error => Err(Error::provider(self.inner.name(), Some(503), INJECTED)),
+            // 流在第一个块之后中断
+            Outcome::Malformed => Ok(self.inner.generate_stream(prompt, options).await?.take(1).boxed()),
+            Outcome::Pass => self.inner.generate_stream(prompt, options).await,
+        }
+    }
+
+    async fn get_embedding(&self, text: &str) -> Result<Vec<f32>> {
+        match self.inject(true).await {
+            Outcome::Error => Err(Error::provider(self.inner.name(), Some(503), INJECTED)),
+            // 维度减半的向量
+            Outcome::Malformed => {
+                let mut embedding = self.inner.get_embedding(text).await?;
+                embedding.truncate(embedding.len() / 2);
+                Ok(embedding)
+            }
+            Outcome::Pass => self.inner.get_embedding(text).await,
+        }
+    }
+
+    fn supports_function_calling(&self) -> bool {
+        self.inner.supports_function_calling()
+    }
+
+    fn supports_seed(&self) -> bool {
+        self.inner.supports_seed()
+    }
+
+    fn prompt_cache_usage(&self) -> Option<CacheUsage> {
+        self.inner.prompt_cache_usage()
+    }
+
+    async fn generate_with_functions(
+        &self,
+        messages: &[Message],
+        functions: &[FunctionDefinition],
+        tool_choice: &ToolChoice,
+        options: &LlmOptions,
+    ) -> Result<FunctionCallingResponse> {
+        match self.inject(true).await {
+            Outcome::Error => Err(Error::provider(self.inner.name(), Some(503), INJECTED)),
+            // 函数调用参数不再是合法的JSON
+            Outcome::Malformed => {
+                let mut response = self.inner.generate_with_functions(messages, functions, tool_choice, options).await?;
+                for call in response.function_calls.iter_mut() {
+                    call.arguments = truncate(&call.arguments);
+                }
+                response.content = response.content.map(|content| truncate(&content));
+                Ok(response)
+            }
+            Outcome::Pass => self.inner.generate_with_functions(messages, functions, tool_choice, options).await,
+        }
+    }
+}
+
+impl<T: Tool + ?Sized> Base for FaultInjector<T> {
+    fn name(&self) -> Option<&str> {
+        self.inner.name()
+    }
+
+    fn component(&self) -> crate::logger::Component {
+        self.inner.component()
+    }
+
+    fn logger(&self) -> Arc<dyn crate::logger::Logger> {
+        self.inner.logger()
+    }
+
+    fn set_logger(&mut self, _logger: Arc<dyn crate::logger::Logger>) {
+        // 被包装的工具在Arc中，无法修改
+    }
+
+    fn telemetry(&self) -> Option<Arc<dyn crate::telemetry::TelemetrySink>> {
+        self.inner.telemetry()
+    }
+
+    fn set_telemetry(&mut self, _telemetry: Arc<dyn crate::telemetry::TelemetrySink>) {
+        // 被包装的工具在Arc中，无法修改
+    }
+}
+
+#[async_trait]
+impl<T: Tool + ?Sized + 'static> Tool for FaultInjector<T> {
+    fn id(&self) -> &str {
+        self.inner.id()
+    }
+
+    fn description(&self) -> &str {
+        self.inner.description()
+    }
+
+    fn schema(&self) -> ToolSchema {
+        self.inner.schema()
+    }
+
+    fn output_schema(&self) -> Option<Value> {
+        self.inner.output_schema()
+    }
+
+    fn category(&self) -> Option<String> {
+        self.inner.category()
+    }
+
+    fn examples(&self) -> Option<Vec<String>> {
+        self.inner.examples()
+    }
+
+    async fn execute(
+        &self,
+        params: Value,
+        context: ToolExecutionContext,
+        options: &ToolExecutionOptions,
+    ) -> Result<Value> {
+        match self.inject(true).await {
+            Outcome::Error => Err(Error::tool_failed(self.inner.id(), INJECTED, true)),
+            // 输出变成被截断的JSON文本，不再符合输出模式
+            Outcome::Malformed => {
+                let output = self.inner.execute(params, context, options).await?;
+                Ok(Value::String(truncate(&output.to_string())))
+            }
+            Outcome::Pass => self.inner.execute(params, context, options).await,
+        }
+    }
+
+    fn clone_box(&self) -> Box<dyn Tool> {
+        Box::new(self.clone())
+    }
+}
+
+#[async_trait]
+impl<V: VectorStorage + ?Sized> VectorStorage for FaultInjector<V> {
+    async fn create_index(
+        &self,
+        index_name: &str,
+        dimension: usize,
+        metric: Option<SimilarityMetric>,
+    ) -> std::result::Result<(), VectorError> {
+        if self.inject(false).await == Outcome::Error {
+            return Err(VectorError::ConnectionFailed(INJECTED.to_string()));
+        }
+        self.inner.create_index(index_name, dimension, metric).await
+    }
+
+    async fn list_indexes(&self) -> std::result::Result<Vec<String>, VectorError> {
+        if self.inject(false).await == Outcome::Error {
+            return Err(VectorError::ConnectionFailed(INJECTED.to_string()));
+        }
+        self.inner.list_indexes().await
+    }
+
+    async fn describe_index(&self, index_name: &str) -> std::result::Result<IndexStats, VectorError> {
+        if self.inject(false).await == Outcome::Error {
+            return Err(VectorError::ConnectionFailed(INJECTED.to_string()));
+        }
+        self.inner.describe_index(index_name).await
+    }
+
+    async fn delete_index(&self, index_name: &str) -> std::result::Result<(), VectorError> {
+        if self.inject(false).await == Outcome::Error {
+            return Err(VectorError::ConnectionFailed(INJECTED.to_string()));
+        }
+        self.inner.delete_index(index_name).await
+    }
+
+    async fn upsert(
+        &self,
+        index_name: &str,
+        vectors: Vec<Vec<f32>>,
+        ids: Option<Vec<String>>,
+        metadata: Option<Vec<HashMap<String, Value>>>,
+    ) -> std::result::Result<Vec<String>, VectorError> {
+        if self.inject(false).await == Outcome::Error {
+            return Err(VectorError::ConnectionFailed(INJECTED.to_string()));
+        }
+        self.inner.upsert(index_name, vectors, ids, metadata).await
+    }
+
+    async fn query(
+        &self,
+        index_name: &str,
+        query_vector: Vec<f32>,
+        top_k: usize,
+        filter: Option<FilterCondition>,
+        include_vectors: bool,
+    ) -> std::result::Result<Vec<QueryResult>, VectorError> {
+        match self.inject(true).await {
+            Outcome::Error => Err(VectorError::ConnectionFailed(INJECTED.to_string())),
+            // 分数为NaN且丢失元数据的结果
+            Outcome::Malformed => {
+                let mut results = self.inner.query(index_name, query_vector, top_k, filter, include_vectors).await?;
+                for result in results.iter_mut() {
+                    result.score = f32::NAN;
+                    result.metadata = None;
+                }
+                Ok(results)
+            }
+            Outcome::Pass => self.inner.query(index_name, query_vector, top_k, filter, include_vectors).await,
+        }
+    }
+
+    async fn update_by_id(
+        &self,
+        index_name: &str,
+        id: &str,
+        vector: Option<Vec<f32>>,
+        metadata: Option<HashMap<String, Value>>,
+    ) -> std::result::Result<(), VectorError> {
+        if self.inject(false).await == Outcome::Error {
+            return Err(VectorError::ConnectionFailed(INJECTED.to_string()));
+        }
+        self.inner.update_by_id(index_name, id, vector, metadata).await
+    }
+
+    async fn delete_by_id(&self, index_name: &str, id: &str) -> std::result::Result<(), VectorError> {
+        if self.inject(false).await == Outcome::Error {
+            return Err(VectorError::ConnectionFailed(INJECTED.to_string()));
+        }
+        self.inner.delete_by_id(index_name, id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+    use crate::llm::MockLlmProvider;
+    use crate::tool::GenericTool;
+    use crate::vector::MemoryVectorStorage;
+
+    fn mock_provider() -> Arc<dyn LlmProvider> {
+        Arc::new(MockLlmProvider::new(Vec::new()))
+    }
+
+    #[tokio::test]
+    async fn test_seeded_faults_drive_retries() {
+        let config = FaultConfig::new().with_error_rate(0.5).with_seed(11);
+        let first = FaultInjector::new(mock_provider(), config.clone());
+        let second = FaultInjector::new(mock_provider(), config);
+
+        let mut failures = Vec::new();
+        for _ in 0..20 {
+            let a = first.generate("hi", &LlmOptions::default()).await;
+            let b = second.generate("hi", &LlmOptions::default()).await;
+            assert_eq!(a.is_err(), b.is_err());
+            if let Err(error) = a {
+                assert!(error.is_retryable());
+                failures.push(error);
+            }
+        }
+        assert!(!failures.is_empty() && failures.len() < 20);
+        assert_eq!(first.stats(), FaultStats { calls: 20, delayed: 0, errors: failures.len() as u64, malformed: 0 });
+
+        // A retry loop gets through a flaky provider
+        let mut attempts = 0;
+        let response = loop {
+            attempts += 1;
+            match first.generate("hi", &LlmOptions::default()).await {
+                Ok(response) => break response,
+                Err(error) if error.is_retryable() && attempts < 20 => continue,
+                Err(error) => panic!("retries exhausted: {}", error),
+            }
+        };
+        assert_eq!(response, "Default mock response");
+
+        let reliable = FaultInjector::new(mock_provider(), FaultConfig::new());
+        for _ in 0..10 {
+            assert!(reliable.generate("hi", &LlmOptions::default()).await.is_ok());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_malformed_tool_output_and_latency() {
+        let tool: Arc<dyn Tool> = Arc::new(GenericTool::new(
+            "lookup",
+            "Looks up a record",
+            ToolSchema::new(Vec::new()),
+            |_params, _context| Ok(serde_json::json!({ "status": "found", "id": 42 })),
+        ));
+        let config = FaultConfig::new()
+            .with_malformed_rate(1.0)
+            .with_latency(1.0, Duration::from_millis(20), Duration::from_millis(30));
+        let faulty = FaultInjector::new(tool, config);
+
+        let started = Instant::now();
+        let output = faulty.execute(Value::Null, ToolExecutionContext::new(), &ToolExecutionOptions::new()).await.unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(20));
+        let text = output.as_str().unwrap();
+        assert!(serde_json::from_str::<Value>(text).is_err());
+        assert_eq!(faulty.stats(), FaultStats { calls: 1, delayed: 1, errors: 0, malformed: 1 });
+        assert_eq!(faulty.clone_box().id(), "lookup");
+    }
+
+    #[tokio::test]
+    async fn test_vector_store_faults() {
+        let storage: Arc<dyn VectorStorage> = Arc::new(MemoryVectorStorage::new(2, None));
+        storage.create_index("docs", 2, None).await.unwrap();
+        storage.upsert("docs", vec![vec![1.0, 0.0]], Some(vec!["a".to_string()]), None).await.unwrap();
+
+        let failing = FaultInjector::new(storage.clone(), FaultConfig::new().with_error_rate(1.0));
+        assert!(matches!(
+            failing.upsert("docs", vec![vec![0.0, 1.0]], None, None).await,
+            Err(VectorError::ConnectionFailed(_))
+        ));
+
+        let malformed = FaultInjector::new(storage, FaultConfig::new().with_malformed_rate(1.0));
+        let results = malformed.query("docs", vec![1.0, 0.0], 1, None, false).await.unwrap();
+        assert!(results[0].score.is_nan());
+        // Only query responses can be malformed
+        assert_eq!(malformed.list_indexes().await.unwrap(), vec!["docs"]);
+        assert_eq!(malformed.stats().malformed, 1);
+    }
+}
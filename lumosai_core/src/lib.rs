@@ -3,7 +3,9 @@
 //! 提供了Agent、工作流、工具、LLM接口等核心功能
 
 pub mod agent;
+pub mod attachment;
 pub mod base;
+pub mod cancellation;
 pub mod config;
 pub mod error;
 pub mod llm;
@@ -22,9 +24,14 @@ pub mod types;
 pub mod vector;
 pub mod workflow;
 pub mod cache;
+pub mod channels;
 pub mod data_processing;
 pub mod app;
 pub mod rag;
+pub mod request_context;
+pub mod performance;
+#[cfg(any(test, feature = "fault-injection"))]
+pub mod fault;
 pub mod voice;
 pub mod debug;
 pub mod logging;
@@ -38,13 +45,14 @@ pub mod unified_api;
 pub mod prelude;
 
 /// Re-export common types and traits
-pub use error::{Error, Result};
+pub use error::{Error, ErrorKind, ErrorResponse, Result};
 pub use llm::{LlmProvider, LlmOptions, Message, Role};
 pub use llm::{OpenAiProvider, AnthropicProvider, QwenProvider, MockLlmProvider};
 pub use agent::{AgentTrait as Agent, AgentConfig, BasicAgent, create_basic_agent, AgentGenerateOptions, AgentStreamOptions, AgentFactory};
 pub use base::{Base, ComponentConfig, BaseComponent};
 pub use logger::{Logger, LogLevel, Component as LogComponent, create_logger, create_noop_logger};
 pub use lumosai::{Lumosai, LumosaiConfig};
+pub use request_context::RequestContext;
 pub use memory::{Memory, WorkingMemory, WorkingMemoryContent};
 pub use storage::{Storage, create_memory_storage};
 pub use tool::{Tool};
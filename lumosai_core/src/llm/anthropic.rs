@@ -3,8 +3,9 @@ use serde::{Deserialize, Serialize};
 use reqwest::header::{HeaderMap, HeaderValue};
 
 use crate::error::{Error, Result};
+use crate::config::http_client_for;
 use crate::llm::provider::LlmProvider;
-use crate::llm::types::{LlmOptions, Message, Role};
+use crate::llm::types::{LlmOptions, Message, Role, SamplingParam};
 use futures::stream::BoxStream;
 
 /// Anthropic API响应结构
@@ -68,12 +69,13 @@ impl AnthropicProvider {
             api_key, 
             model,
             base_url: "https://api.anthropic.com/v1".to_string(),
-            client: reqwest::Client::new(),
+            client: http_client_for("https://api.anthropic.com/v1"),
         }
     }
     
     /// 设置自定义的API基础URL
     pub fn with_base_url(mut self, base_url: String) -> Self {
+        self.client = http_client_for(&base_url);
         self.base_url = base_url;
         self
     }
@@ -155,6 +157,7 @@ impl LlmProvider for AnthropicProvider {
         if let Some(stop) = &options.stop {
             body["stop_sequences"] = serde_json::json!(stop);
         }
+        options.warn_unsupported("anthropic", &[SamplingParam::Stop]);
         
         // 发送请求
         let res = self.client
@@ -170,7 +173,7 @@ impl LlmProvider for AnthropicProvider {
             .map_err(|e| Error::Llm(format!("Failed to read Anthropic response: {}", e)))?;
             
         if !status.is_success() {
-            return Err(Error::Llm(format!(
+            return Err(Error::provider("anthropic", Some(status.as_u16()), format!(
                 "Anthropic API returned error status {}: {}",
                 status, text
             )));
@@ -217,6 +220,7 @@ impl LlmProvider for AnthropicProvider {
         if let Some(stop) = &options.stop {
             body["stop_sequences"] = serde_json::json!(stop);
         }
+        options.warn_unsupported("anthropic", &[SamplingParam::Stop]);
         
         // 发送请求
         let res = self.client
@@ -232,7 +236,7 @@ impl LlmProvider for AnthropicProvider {
             .map_err(|e| Error::Llm(format!("Failed to read Anthropic response: {}", e)))?;
             
         if !status.is_success() {
-            return Err(Error::Llm(format!(
+            return Err(Error::provider("anthropic", Some(status.as_u16()), format!(
                 "Anthropic API returned error status {}: {}",
                 status, text
             )));
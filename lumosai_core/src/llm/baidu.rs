@@ -6,10 +6,14 @@ use serde::Deserialize;
 use serde_json::Value;
 
 use crate::{Error, Result};
+use crate::config::http_client_for;
 use super::provider::{LlmProvider, FunctionCallingResponse};
-use super::types::{LlmOptions, Message};
+use super::types::{LlmOptions, Message, SamplingParam};
 use super::function_calling::{FunctionDefinition, FunctionCall, ToolChoice};
 
+/// Sampling controls the Baidu ERNIE API accepts
+const SAMPLING_PARAMS: &[SamplingParam] = &[SamplingParam::Stop];
+
 /// 百度ERNIE API response structures
 #[derive(Debug, Deserialize)]
 #[allow(dead_code)]
@@ -100,7 +104,7 @@ impl BaiduProvider {
         Self {
             api_key,
             secret_key,
-            client: reqwest::Client::new(),
+            client: http_client_for("https://aip.baidubce.com"),
             model: model.unwrap_or_else(|| "ernie-bot".to_string()),
             base_url: "https://aip.baidubce.com".to_string(),
             access_token: None,
@@ -122,7 +126,7 @@ impl BaiduProvider {
         Self {
             api_key,
             secret_key,
-            client: reqwest::Client::new(),
+            client: http_client_for(&base_url),
             model: model.unwrap_or_else(|| "ernie-bot".to_string()),
             base_url,
             access_token: None,
@@ -234,6 +238,7 @@ impl LlmProvider for BaiduProvider {
         if let Some(top_p) = options.extra.get("top_p") {
             body["top_p"] = top_p.clone();
         }
+        options.apply_sampling_params(&mut body, "baidu", SAMPLING_PARAMS);
 
         // Send request
         let res = self.client
@@ -296,6 +301,7 @@ impl LlmProvider for BaiduProvider {
         if let Some(top_p) = options.extra.get("top_p") {
             body["top_p"] = top_p.clone();
         }
+        options.apply_sampling_params(&mut body, "baidu", SAMPLING_PARAMS);
 
         // Send request
         let res = self.client
@@ -366,6 +372,7 @@ impl LlmProvider for BaiduProvider {
         if let Some(top_p) = options.extra.get("top_p") {
             body["top_p"] = top_p.clone();
         }
+        options.apply_sampling_params(&mut body, "baidu", SAMPLING_PARAMS);
 
         // Send request
         let response = self.client
@@ -481,6 +488,7 @@ impl LlmProvider for BaiduProvider {
         if let Some(top_p) = options.extra.get("top_p") {
             body["top_p"] = top_p.clone();
         }
+        options.apply_sampling_params(&mut body, "baidu", SAMPLING_PARAMS);
 
         // Send request
         let res = self.client
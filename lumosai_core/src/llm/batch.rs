@@ -0,0 +1,125 @@
+//! Batch generation for offline workloads
+//!
+//! Large labeling and enrichment jobs submit many independent requests at once through
+//! [`LlmProvider::generate_batch`]. Providers with a native batch API (OpenAI) hand the
+//! whole job to the provider at discounted pricing; the rest fall back to running the
+//! requests with bounded concurrency.
+
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+
+use super::provider::LlmProvider;
+use super::types::{LlmOptions, Message, Role};
+
+/// Number of in-flight requests when a provider has no native batch API
+pub const DEFAULT_BATCH_CONCURRENCY: usize = 8;
+
+/// One request in a batch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchRequest {
+    /// Caller-chosen identifier, unique within the batch
+    pub custom_id: String,
+    /// Conversation to complete
+    pub messages: Vec<Message>,
+    /// Generation options
+    pub options: LlmOptions,
+}
+
+impl BatchRequest {
+    /// Create a request from a conversation
+    pub fn new(custom_id: impl Into<String>, messages: Vec<Message>) -> Self {
+        Self {
+            custom_id: custom_id.into(),
+            messages,
+            options: LlmOptions::default(),
+        }
+    }
+
+    /// Create a request from a single user prompt
+    pub fn from_prompt(custom_id: impl Into<String>, prompt: impl Into<String>) -> Self {
+        Self::new(custom_id, vec![Message::new(Role::User, prompt.into(), None, None)])
+    }
+
+    /// Set the generation options
+    pub fn with_options(mut self, options: LlmOptions) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+/// Outcome of one batch request; a failed item does not fail the batch
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchResponse {
+    /// Identifier of the originating request
+    pub custom_id: String,
+    /// Generated text on success
+    pub content: Option<String>,
+    /// Error message on failure
+    pub error: Option<String>,
+}
+
+impl BatchResponse {
+    /// Successful response
+    pub fn success(custom_id: impl Into<String>, content: impl Into<String>) -> Self {
+        Self {
+            custom_id: custom_id.into(),
+            content: Some(content.into()),
+            error: None,
+        }
+    }
+
+    /// Failed response
+    pub fn failure(custom_id: impl Into<String>, error: impl Into<String>) -> Self {
+        Self {
+            custom_id: custom_id.into(),
+            content: None,
+            error: Some(error.into()),
+        }
+    }
+
+    /// Whether the request produced content
+    pub fn is_success(&self) -> bool {
+        self.content.is_some()
+    }
+}
+
+/// Run batch requests through `generate_with_messages` with at most `concurrency` in flight
+///
+/// Responses are returned in request order.
+pub async fn generate_concurrently<P: LlmProvider + ?Sized>(
+    provider: &P,
+    requests: Vec<BatchRequest>,
+    concurrency: usize,
+) -> Vec<BatchResponse> {
+    stream::iter(requests)
+        .map(|request| async move {
+            match provider.generate_with_messages(&request.messages, &request.options).await {
+                Ok(content) => BatchResponse::success(request.custom_id, content),
+                Err(e) => BatchResponse::failure(request.custom_id, e.to_string()),
+            }
+        })
+        .buffered(concurrency.max(1))
+        .collect()
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::MockLlmProvider;
+
+    #[tokio::test]
+    async fn test_default_batch_keeps_request_order() {
+        let provider = MockLlmProvider::new(vec!["positive".to_string(), "negative".to_string(), "neutral".to_string()]);
+        let requests = vec![
+            BatchRequest::from_prompt("review-1", "Great product"),
+            BatchRequest::from_prompt("review-2", "Broke after a day"),
+            BatchRequest::from_prompt("review-3", "It is fine"),
+        ];
+
+        let responses = provider.generate_batch(requests).await.unwrap();
+        let ids: Vec<_> = responses.iter().map(|r| r.custom_id.as_str()).collect();
+        assert_eq!(ids, vec!["review-1", "review-2", "review-3"]);
+        assert!(responses.iter().all(BatchResponse::is_success));
+    }
+}
@@ -10,14 +10,17 @@
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 use super::{
-    LlmProvider, LlmOptions, Message, Role,
+    LlmProvider, LlmOptions, Message, Role, SamplingParam,
     function_calling::{FunctionDefinition, ToolChoice},
+    prompt_cache::{apply_anthropic_cache_control, is_cache_breakpoint, CacheUsage, PromptCacheConfig, PromptCacheStats},
     provider::FunctionCallingResponse
 };
 use futures::stream::BoxStream;
 use crate::error::{LumosError, Result};
+use crate::config::http_client_for;
 
 /// Claude API配置
 #[derive(Debug, Clone)]
@@ -42,6 +45,7 @@ impl Default for ClaudeConfig {
 pub struct ClaudeProvider {
     config: ClaudeConfig,
     client: Client,
+    cache_stats: Arc<PromptCacheStats>,
 }
 
 impl ClaudeProvider {
@@ -53,7 +57,8 @@ impl ClaudeProvider {
                 model,
                 base_url: "https://api.anthropic.com".to_string(),
             },
-            client: Client::new(),
+            client: http_client_for("https://api.anthropic.com"),
+            cache_stats: Arc::new(PromptCacheStats::default()),
         }
     }
 
@@ -91,8 +96,30 @@ impl ClaudeProvider {
 
     /// 转换消息格式
     fn convert_messages(&self, messages: &[Message]) -> Vec<ClaudeMessage> {
+        let (system_content, mut claude_messages, _) = self.split_messages(messages);
+
+        // 如果有系统消息，添加到第一个用户消息前
+        if !system_content.is_empty() && !claude_messages.is_empty() {
+            if let Some(first_msg) = claude_messages.first_mut() {
+                if first_msg.role == "user" {
+                    first_msg.content = format!("{}\n\n{}", system_content, first_msg.content);
+                }
+            } else {
+                claude_messages.insert(0, ClaudeMessage {
+                    role: "user".to_string(),
+                    content: system_content,
+                });
+            }
+        }
+
+        claude_messages
+    }
+
+    /// 拆分系统提示和对话消息，同时返回缓存断点在对话消息中的位置
+    fn split_messages(&self, messages: &[Message]) -> (String, Vec<ClaudeMessage>, Vec<usize>) {
         let mut claude_messages = Vec::new();
         let mut system_content = String::new();
+        let mut breakpoints = Vec::new();
 
         for message in messages {
             match message.role {
@@ -101,6 +128,7 @@ impl ClaudeProvider {
                         system_content.push('\n');
                     }
                     system_content.push_str(&message.content);
+                    continue;
                 }
                 Role::User => {
                     claude_messages.push(ClaudeMessage {
@@ -122,27 +150,51 @@ impl ClaudeProvider {
                     });
                 }
             }
+            if is_cache_breakpoint(message) {
+                breakpoints.push(claude_messages.len() - 1);
+            }
         }
 
-        // 如果有系统消息，添加到第一个用户消息前
-        if !system_content.is_empty() && !claude_messages.is_empty() {
-            if let Some(first_msg) = claude_messages.first_mut() {
-                if first_msg.role == "user" {
-                    first_msg.content = format!("{}\n\n{}", system_content, first_msg.content);
-                }
-            } else {
-                claude_messages.insert(0, ClaudeMessage {
-                    role: "user".to_string(),
-                    content: system_content,
-                });
+        (system_content, claude_messages, breakpoints)
+    }
+
+    /// 构建请求正文
+    ///
+    /// 启用提示缓存时，系统提示作为独立的 `system` 字段发送，以便缓存稳定的前缀
+    fn build_request(&self, messages: &[Message], options: &LlmOptions, stream: bool) -> Result<serde_json::Value> {
+        let claude_options = self.convert_options(options);
+        let cache_config = PromptCacheConfig::from_options(options);
+
+        let (system, claude_messages, breakpoints) = match &cache_config {
+            Some(_) => {
+                let (system, claude_messages, breakpoints) = self.split_messages(messages);
+                (Some(system).filter(|s| !s.is_empty()), claude_messages, breakpoints)
             }
-        }
+            None => (None, self.convert_messages(messages), Vec::new()),
+        };
 
-        claude_messages
+        let request = ClaudeRequest {
+            model: self.config.model.clone(),
+            system,
+            messages: claude_messages,
+            max_tokens: claude_options.max_tokens,
+            temperature: claude_options.temperature,
+            top_p: claude_options.top_p,
+            top_k: claude_options.top_k,
+            stop_sequences: claude_options.stop_sequences,
+            stream,
+        };
+
+        let mut body = serde_json::to_value(&request)?;
+        if let Some(cache_config) = &cache_config {
+            apply_anthropic_cache_control(&mut body, cache_config, &breakpoints);
+        }
+        Ok(body)
     }
 
     /// 转换选项
     fn convert_options(&self, options: &LlmOptions) -> ClaudeOptions {
+        options.warn_unsupported("claude", &[SamplingParam::Stop]);
         ClaudeOptions {
             max_tokens: options.max_tokens.unwrap_or(4096),
             temperature: options.temperature,
@@ -163,6 +215,10 @@ impl LlmProvider for ClaudeProvider {
         true // Claude 3支持函数调用
     }
 
+    fn prompt_cache_usage(&self) -> Option<CacheUsage> {
+        Some(self.cache_stats.snapshot())
+    }
+
     async fn generate(&self, prompt: &str, options: &LlmOptions) -> Result<String> {
         let messages = vec![Message {
             role: Role::User,
@@ -175,19 +231,7 @@ impl LlmProvider for ClaudeProvider {
     }
 
     async fn generate_with_messages(&self, messages: &[Message], options: &LlmOptions) -> Result<String> {
-        let claude_messages = self.convert_messages(messages);
-        let claude_options = self.convert_options(options);
-
-        let request = ClaudeRequest {
-            model: self.config.model.clone(),
-            messages: claude_messages,
-            max_tokens: claude_options.max_tokens,
-            temperature: claude_options.temperature,
-            top_p: claude_options.top_p,
-            top_k: claude_options.top_k,
-            stop_sequences: claude_options.stop_sequences,
-            stream: false,
-        };
+        let request = self.build_request(messages, options, false)?;
 
         let response = self
             .client
@@ -220,6 +264,12 @@ impl LlmProvider for ClaudeProvider {
             }
         })?;
 
+        self.cache_stats.record(CacheUsage {
+            input_tokens: claude_response.usage.input_tokens as u64,
+            cache_creation_input_tokens: claude_response.usage.cache_creation_input_tokens as u64,
+            cache_read_input_tokens: claude_response.usage.cache_read_input_tokens as u64,
+        });
+
         if let Some(content) = claude_response.content.first() {
             Ok(content.text.clone())
         } else {
@@ -268,19 +318,7 @@ impl ClaudeProvider {
         messages: &[Message],
         options: &LlmOptions,
     ) -> Result<BoxStream<'_, Result<String>>> {
-        let claude_messages = self.convert_messages(messages);
-        let claude_options = self.convert_options(options);
-
-        let request = ClaudeRequest {
-            model: self.config.model.clone(),
-            messages: claude_messages,
-            max_tokens: claude_options.max_tokens,
-            temperature: claude_options.temperature,
-            top_p: claude_options.top_p,
-            top_k: claude_options.top_k,
-            stop_sequences: claude_options.stop_sequences,
-            stream: true,
-        };
+        let request = self.build_request(messages, options, true)?;
 
         let response = self
             .client
@@ -387,6 +425,8 @@ impl ClaudeProvider {
 #[derive(Debug, Serialize)]
 struct ClaudeRequest {
     model: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system: Option<String>,
     messages: Vec<ClaudeMessage>,
     max_tokens: u32,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -438,6 +478,10 @@ struct ClaudeContent {
 struct ClaudeUsage {
     input_tokens: u32,
     output_tokens: u32,
+    #[serde(default)]
+    cache_creation_input_tokens: u32,
+    #[serde(default)]
+    cache_read_input_tokens: u32,
 }
 
 #[cfg(test)]
@@ -506,4 +550,25 @@ mod tests {
         assert!(claude_messages[0].content.contains("You are a helpful assistant."));
         assert!(claude_messages[0].content.contains("Hello!"));
     }
+
+    #[test]
+    fn test_claude_prompt_cache_request() {
+        use crate::llm::prompt_cache::mark_cache_breakpoint;
+
+        let provider = ClaudeProvider::sonnet("test-key".to_string());
+        let messages = vec![
+            Message::new(Role::System, "You are a helpful assistant.".to_string(), None, None),
+            mark_cache_breakpoint(Message::new(Role::User, "Context: product manual".to_string(), None, None)),
+            Message::new(Role::User, "How do I reset it?".to_string(), None, None),
+        ];
+
+        let plain = provider.build_request(&messages, &LlmOptions::default(), false).unwrap();
+        assert!(plain.get("system").is_none());
+
+        let options = LlmOptions::default().with_prompt_cache(PromptCacheConfig::new());
+        let cached = provider.build_request(&messages, &options, false).unwrap();
+        assert_eq!(cached["system"][0]["cache_control"]["type"], "ephemeral");
+        assert_eq!(cached["messages"][0]["content"][0]["cache_control"]["type"], "ephemeral");
+        assert_eq!(cached["messages"][1]["content"], "How do I reset it?");
+    }
 }
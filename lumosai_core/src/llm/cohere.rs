@@ -8,8 +8,9 @@ use reqwest::Client;
 use serde_json::json;
 
 use crate::error::{Error, Result};
+use crate::config::http_client_builder_for;
 use super::{
-    LlmProvider, LlmOptions, Message, Role,
+    LlmProvider, LlmOptions, Message, Role, SamplingParam,
     function_calling::{FunctionDefinition, ToolChoice},
     provider::FunctionCallingResponse
 };
@@ -50,7 +51,7 @@ impl CohereProvider {
             ..Default::default()
         };
         
-        let client = Client::builder()
+        let client = http_client_builder_for(&config.base_url)
             .default_headers({
                 let mut headers = reqwest::header::HeaderMap::new();
                 headers.insert(
@@ -120,15 +121,19 @@ impl LlmProvider for CohereProvider {
     }
 
     async fn generate(&self, prompt: &str, options: &LlmOptions) -> Result<String> {
-        let request_body = json!({
+        let mut request_body = json!({
             "model": self.config.model,
             "prompt": prompt,
             "max_tokens": options.max_tokens.unwrap_or(1000),
             "temperature": options.temperature.unwrap_or(0.7),
             "k": 0,
-            "stop_sequences": options.stop.as_ref().unwrap_or(&vec![]),
             "return_likelihoods": "NONE"
         });
+        // The generate endpoint uses the OpenAI names except for stop sequences
+        options.apply_sampling_params(&mut request_body, "cohere", &SamplingParam::ALL);
+        if let Some(stop) = request_body.as_object_mut().and_then(|body| body.remove("stop")) {
+            request_body["stop_sequences"] = stop;
+        }
 
         let response = self.client
             .post(&format!("{}/v1/generate", self.config.base_url))
@@ -211,6 +216,10 @@ impl LlmProvider for CohereProvider {
         false // Cohere doesn't support OpenAI-style function calling yet
     }
 
+    fn supports_seed(&self) -> bool {
+        true
+    }
+
     async fn generate_with_functions(
         &self,
         messages: &[Message],
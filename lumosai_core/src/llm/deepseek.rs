@@ -5,10 +5,14 @@ use serde::Deserialize;
 use serde_json::Value;
 
 use crate::{Error, Result};
+use crate::config::http_client_for;
 use super::provider::{LlmProvider, FunctionCallingResponse};
-use super::types::{LlmOptions, Message, Role};
+use super::types::{LlmOptions, Message, Role, SamplingParam};
 use super::function_calling::{FunctionDefinition, FunctionCall, ToolChoice};
 
+/// Sampling controls the DeepSeek API accepts
+const SAMPLING_PARAMS: &[SamplingParam] = &[SamplingParam::Stop, SamplingParam::FrequencyPenalty, SamplingParam::PresencePenalty];
+
 /// DeepSeek API response structures (compatible with OpenAI format)
 #[derive(Debug, Deserialize)]
 #[allow(dead_code)]
@@ -90,7 +94,7 @@ impl DeepSeekProvider {
     pub fn new(api_key: String, model: Option<String>) -> Self {
         Self {
             api_key,
-            client: reqwest::Client::new(),
+            client: http_client_for("https://api.deepseek.com"),
             model: model.unwrap_or_else(|| "deepseek-chat".to_string()),
             base_url: "https://api.deepseek.com".to_string(),
         }
@@ -100,7 +104,7 @@ impl DeepSeekProvider {
     pub fn with_base_url(api_key: String, base_url: String, model: Option<String>) -> Self {
         Self {
             api_key,
-            client: reqwest::Client::new(),
+            client: http_client_for(&base_url),
             model: model.unwrap_or_else(|| "deepseek-chat".to_string()),
             base_url,
         }
@@ -154,6 +158,7 @@ impl LlmProvider for DeepSeekProvider {
         if let Some(top_p) = options.extra.get("top_p") {
             body["top_p"] = top_p.clone();
         }
+        options.apply_sampling_params(&mut body, "deepseek", SAMPLING_PARAMS);
 
         // Send request
         let res = self.client
@@ -169,7 +174,7 @@ impl LlmProvider for DeepSeekProvider {
             .map_err(|e| Error::Llm(format!("Failed to read DeepSeek response: {}", e)))?;
 
         if !status.is_success() {
-            return Err(Error::Llm(format!(
+            return Err(Error::provider("deepseek", Some(status.as_u16()), format!(
                 "DeepSeek API returned error status {}: {}",
                 status, text
             )));
@@ -222,6 +227,7 @@ impl LlmProvider for DeepSeekProvider {
         if let Some(top_p) = options.extra.get("top_p") {
             body["top_p"] = top_p.clone();
         }
+        options.apply_sampling_params(&mut body, "deepseek", SAMPLING_PARAMS);
 
         // Send request
         let res = self.client
@@ -237,7 +243,7 @@ impl LlmProvider for DeepSeekProvider {
             .map_err(|e| Error::Llm(format!("Failed to read DeepSeek response: {}", e)))?;
 
         if !status.is_success() {
-            return Err(Error::Llm(format!(
+            return Err(Error::provider("deepseek", Some(status.as_u16()), format!(
                 "DeepSeek API returned error status {}: {}",
                 status, text
             )));
@@ -379,6 +385,7 @@ impl LlmProvider for DeepSeekProvider {
         if let Some(top_p) = options.extra.get("top_p") {
             body["top_p"] = top_p.clone();
         }
+        options.apply_sampling_params(&mut body, "deepseek", SAMPLING_PARAMS);
 
         // Send request
         let res = self.client
@@ -394,7 +401,7 @@ impl LlmProvider for DeepSeekProvider {
             .map_err(|e| Error::Llm(format!("Failed to read DeepSeek response: {}", e)))?;
 
         if !status.is_success() {
-            return Err(Error::Llm(format!(
+            return Err(Error::provider("deepseek", Some(status.as_u16()), format!(
                 "DeepSeek API returned error status {}: {}",
                 status, response_text
             )));
@@ -36,6 +36,15 @@ impl FunctionDefinition {
     pub fn from_tool(tool: &dyn Tool) -> Self {
         let schema = tool.schema();
         
+        // A full JSON Schema, e.g. one generated by the `tool` macro, is passed through as is
+        if let Some(json_schema) = schema.json_schema {
+            return Self {
+                name: tool.id().to_string(),
+                description: Some(tool.description().to_string()),
+                parameters: json_schema,
+            };
+        }
+        
         // Convert tool schema to OpenAI function parameters format
         let mut properties = Map::new();
         let mut required = Vec::new();
@@ -19,9 +19,12 @@ pub fn struct_to_json_schema<T>() -> Value where T: schemars::JsonSchema {
 
 /// Convert a collection of tools to function definitions
 pub fn tools_to_function_definitions(tools: &HashMap<String, Box<dyn Tool>>) -> Vec<FunctionDefinition> {
-    tools.values()
+    // Sort by name, map iteration order would change the prompt between runs
+    let mut definitions: Vec<FunctionDefinition> = tools.values()
         .map(|tool| FunctionDefinition::from_tool(tool.as_ref()))
-        .collect()
+        .collect();
+    definitions.sort_by(|a, b| a.name.cmp(&b.name));
+    definitions
 }
 
 /// Convert tool definitions to OpenAI tool format with enhanced features
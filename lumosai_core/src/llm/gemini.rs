@@ -9,7 +9,8 @@ use serde::{Deserialize, Serialize};
 use serde_json::json;
 
 use crate::error::{Error, Result};
-use super::{
+use crate::config::http_client_builder_for;
+    LlmProvider, LlmOptions, Message, Role, SamplingParam,
     LlmProvider, LlmOptions, Message, Role,
     function_calling::{FunctionDefinition, ToolChoice},
     provider::FunctionCallingResponse
@@ -62,6 +63,36 @@ pub struct GenerationConfig {
     pub top_p: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub top_k: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_sequences: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub presence_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequency_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+}
+
+impl GenerationConfig {
+    /// Map LLM options to Gemini generation parameters
+    pub fn from_options(options: &LlmOptions) -> Self {
+        options.warn_unsupported("gemini", &[
+            SamplingParam::Stop,
+            SamplingParam::FrequencyPenalty,
+            SamplingParam::PresencePenalty,
+            SamplingParam::Seed,
+        ]);
+        Self {
+            temperature: options.temperature,
+            max_output_tokens: options.max_tokens,
+            top_p: options.extra.get("top_p").and_then(|v| v.as_f64()).map(|f| f as f32),
+            top_k: None,
+            stop_sequences: options.stop.clone(),
+            presence_penalty: options.presence_penalty,
+            frequency_penalty: options.frequency_penalty,
+            seed: options.seed,
+        }
+    }
 }
 
 /// Gemini request structure
@@ -103,7 +134,7 @@ impl GeminiProvider {
             ..Default::default()
         };
         
-        let client = Client::builder()
+        let client = http_client_builder_for(&config.base_url)
             .default_headers({
                 let mut headers = reqwest::header::HeaderMap::new();
                 headers.insert(
@@ -180,12 +211,7 @@ impl LlmProvider for GeminiProvider {
     async fn generate(&self, prompt: &str, options: &LlmOptions) -> Result<String> {
         let contents = self.convert_prompt(prompt);
         
-        let generation_config = GenerationConfig {
-            temperature: options.temperature,
-            max_output_tokens: options.max_tokens,
-            top_p: options.extra.get("top_p").and_then(|v| v.as_f64()).map(|f| f as f32),
-            top_k: None,
-        };
+        let generation_config = GenerationConfig::from_options(options);
 
         let request = GeminiRequest {
             contents,
@@ -227,12 +253,7 @@ impl LlmProvider for GeminiProvider {
     async fn generate_with_messages(&self, messages: &[Message], options: &LlmOptions) -> Result<String> {
         let contents = self.convert_messages(messages)?;
         
-        let generation_config = GenerationConfig {
-            temperature: options.temperature,
-            max_output_tokens: options.max_tokens,
-            top_p: options.extra.get("top_p").and_then(|v| v.as_f64()).map(|f| f as f32),
-            top_k: None,
-        };
+        let generation_config = GenerationConfig::from_options(options);
 
         let request = GeminiRequest {
             contents,
@@ -331,6 +352,10 @@ impl LlmProvider for GeminiProvider {
         true // Gemini supports function calling
     }
 
+    fn supports_seed(&self) -> bool {
+        true
+    }
+
     async fn generate_with_functions(
         &self,
         messages: &[Message],
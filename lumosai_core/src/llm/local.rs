@@ -0,0 +1,303 @@
+//! Local inference provider
+//!
+//! Runs token-level models in-process (for example Candle-backed weights) behind the
+//! [`LlmProvider`] interface. A model only has to expose its vocabulary codec and
+//! next-token logits; registering a smaller draft model that shares the vocabulary
+//! enables speculative decoding, see [`super::speculative`].
+
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::sync::{Arc, Mutex};
+
+use crate::error::{Error, Result};
+use super::provider::LlmProvider;
+use super::speculative::{SpeculativeConfig, SpeculativeDecoder, SpeculativeMetrics};
+use super::types::{LlmOptions, Message, SamplingParam};
+
+/// Default number of generated tokens when the request does not set `max_tokens`
+const DEFAULT_MAX_TOKENS: usize = 256;
+
+/// A language model evaluated token by token
+pub trait TokenModel: Send + Sync {
+    /// Model name
+    fn name(&self) -> &str;
+
+    /// Encode text into token ids
+    fn encode(&self, text: &str) -> Result<Vec<u32>>;
+
+    /// Decode token ids into text
+    fn decode(&self, tokens: &[u32]) -> Result<String>;
+
+    /// End-of-sequence token, if the model has one
+    fn eos_token(&self) -> Option<u32>;
+
+    /// Next-token logits for the last `positions` prefixes of `tokens`
+    ///
+    /// Entry `i` predicts the token following `tokens[..tokens.len() - positions + 1 + i]`,
+    /// so `positions = 1` is plain next-token prediction. Models should score all positions
+    /// in a single forward pass; speculative decoding relies on that to verify draft tokens.
+    fn logits(&self, tokens: &[u32], positions: usize) -> Result<Vec<Vec<f32>>>;
+}
+
+/// Temperature sampling over logits; a temperature of zero selects greedily
+#[derive(Debug, Clone, Copy)]
+pub struct Sampler {
+    temperature: f32,
+}
+
+impl Sampler {
+    /// Create a sampler with the given temperature
+    pub fn new(temperature: f32) -> Self {
+        Self { temperature: temperature.max(0.0) }
+    }
+
+    /// Whether the sampler always picks the most likely token
+    pub fn is_greedy(&self) -> bool {
+        self.temperature == 0.0
+    }
+
+    /// Convert logits into a probability distribution
+    pub fn probabilities(&self, logits: &[f32]) -> Vec<f32> {
+        if self.is_greedy() {
+            let mut probs = vec![0.0; logits.len()];
+            if let Some(best) = argmax(logits) {
+                probs[best as usize] = 1.0;
+            }
+            return probs;
+        }
+        let max = logits.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        let exps: Vec<f32> = logits.iter().map(|l| ((l - max) / self.temperature).exp()).collect();
+        let sum: f32 = exps.iter().sum();
+        exps.into_iter().map(|e| e / sum).collect()
+    }
+
+    /// Draw a token from a probability distribution
+    pub fn sample(&self, probs: &[f32], rng: &mut impl Rng) -> u32 {
+        if self.is_greedy() {
+            return argmax(probs).unwrap_or(0);
+        }
+        let total: f32 = probs.iter().sum();
+        let mut target = rng.gen::<f32>() * total;
+        for (token, p) in probs.iter().enumerate() {
+            if target < *p {
+                return token as u32;
+            }
+            target -= p;
+        }
+        // Rounding can leave a sliver of mass unassigned; fall back to the last non-zero entry
+        probs.iter().rposition(|p| *p > 0.0).unwrap_or(0) as u32
+    }
+}
+
+/// Index of the largest value
+pub(crate) fn argmax(values: &[f32]) -> Option<u32> {
+    values
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(b.1))
+        .map(|(index, _)| index as u32)
+}
+
+/// Plain autoregressive decoding, one target forward pass per token
+pub fn decode_autoregressive(
+    model: &dyn TokenModel,
+    prompt: &[u32],
+    max_tokens: usize,
+    sampler: &Sampler,
+    rng: &mut impl Rng,
+) -> Result<Vec<u32>> {
+    let mut tokens = prompt.to_vec();
+    let mut output = Vec::with_capacity(max_tokens);
+    while output.len() < max_tokens {
+        let logits = model.logits(&tokens, 1)?.pop()
+            .ok_or_else(|| Error::Internal(format!("Model '{}' returned no logits", model.name())))?;
+        let token = sampler.sample(&sampler.probabilities(&logits), rng);
+        output.push(token);
+        tokens.push(token);
+        if Some(token) == model.eos_token() {
+            break;
+        }
+    }
+    Ok(output)
+}
+
+/// LLM provider running a local [`TokenModel`], optionally with a draft model
+pub struct LocalProvider {
+    model: Arc<dyn TokenModel>,
+    draft: Option<Arc<dyn TokenModel>>,
+    metrics: Arc<Mutex<SpeculativeMetrics>>,
+}
+
+impl LocalProvider {
+    /// Create a provider for a local model
+    pub fn new(model: Arc<dyn TokenModel>) -> Self {
+        Self {
+            model,
+            draft: None,
+            metrics: Arc::new(Mutex::new(SpeculativeMetrics::default())),
+        }
+    }
+
+    /// Register a small draft model sharing the target vocabulary
+    pub fn with_draft_model(mut self, draft: Arc<dyn TokenModel>) -> Self {
+        self.draft = Some(draft);
+        self
+    }
+
+    /// Speculative decoding counters accumulated across requests
+    pub fn speculative_metrics(&self) -> SpeculativeMetrics {
+        self.metrics.lock().map(|metrics| *metrics).unwrap_or_default()
+    }
+
+    /// Render chat messages as a plain-text prompt
+    fn format_prompt(messages: &[Message]) -> String {
+        let mut prompt = String::new();
+        for message in messages {
+            prompt.push_str(&format!("{}: {}\n", message.role.as_str(), message.content));
+        }
+        prompt.push_str("assistant: ");
+        prompt
+    }
+
+    /// Generate a completion for an already formatted prompt
+    async fn complete(&self, prompt: String, options: &LlmOptions) -> Result<String> {
+        options.warn_unsupported("local", &[SamplingParam::Stop, SamplingParam::Seed]);
+        let model = self.model.clone();
+        let speculative = SpeculativeConfig::from_options(options).filter(|config| config.enabled);
+        let draft = self.draft.clone().zip(speculative);
+        let metrics = self.metrics.clone();
+        let sampler = Sampler::new(options.temperature.unwrap_or(0.0));
+        let max_tokens = options.max_tokens.map(|n| n as usize).unwrap_or(DEFAULT_MAX_TOKENS);
+        let seed = options.seed;
+
+        // Forward passes are CPU/GPU bound, keep them off the async executor
+        let text = tokio::task::spawn_blocking(move || -> Result<String> {
+            let mut rng = match seed {
+                Some(seed) => StdRng::seed_from_u64(seed),
+                None => StdRng::from_entropy(),
+            };
+            let prompt_tokens = model.encode(&prompt)?;
+            let mut output = match draft {
+                Some((draft, config)) => {
+                    let decoder = SpeculativeDecoder::new(model.as_ref(), draft.as_ref(), config);
+                    let (output, run) = decoder.generate(&prompt_tokens, max_tokens, &sampler, &mut rng)?;
+                    if let Ok(mut metrics) = metrics.lock() {
+                        *metrics += run;
+                    }
+                    output
+                }
+                None => decode_autoregressive(model.as_ref(), &prompt_tokens, max_tokens, &sampler, &mut rng)?,
+            };
+            if model.eos_token().is_some() && output.last().copied() == model.eos_token() {
+                output.pop();
+            }
+            model.decode(&output)
+        })
+        .await
+        .map_err(|e| Error::Internal(format!("Local inference task failed: {}", e)))??;
+
+        Ok(truncate_at_stop(text, options.stop.as_deref()))
+    }
+}
+
+/// Cut the text at the first stop sequence
+fn truncate_at_stop(mut text: String, stop: Option<&[String]>) -> String {
+    if let Some(end) = stop.unwrap_or_default().iter().filter_map(|s| text.find(s.as_str())).min() {
+        text.truncate(end);
+    }
+    text
+}
+
+#[async_trait]
+impl LlmProvider for LocalProvider {
+    fn name(&self) -> &str {
+        "local"
+    }
+
+    fn supports_seed(&self) -> bool {
+        true
+    }
+
+    async fn generate(&self, prompt: &str, options: &LlmOptions) -> Result<String> {
+        self.complete(prompt.to_string(), options).await
+    }
+
+    async fn generate_with_messages(&self, messages: &[Message], options: &LlmOptions) -> Result<String> {
+        self.complete(Self::format_prompt(messages), options).await
+    }
+
+    async fn generate_stream<'a>(
+        &'a self,
+        prompt: &'a str,
+        options: &'a LlmOptions,
+    ) -> Result<BoxStream<'a, Result<String>>> {
+        let response = self.generate(prompt, options).await?;
+        Ok(Box::pin(stream::once(async move { Ok(response) })))
+    }
+
+    async fn get_embedding(&self, _text: &str) -> Result<Vec<f32>> {
+        Err(Error::Unsupported(format!("Local model '{}' does not provide embeddings", self.model.name())))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Toy model over the letters `a..h` that continues the alphabet, wrapping around
+    pub(crate) struct AlphabetModel {
+        /// How far ahead the model jumps; a draft with a different step always disagrees
+        pub step: u32,
+    }
+
+    impl TokenModel for AlphabetModel {
+        fn name(&self) -> &str {
+            "alphabet"
+        }
+
+        fn encode(&self, text: &str) -> Result<Vec<u32>> {
+            Ok(text.chars().filter(|c| ('a'..='h').contains(c)).map(|c| c as u32 - 'a' as u32).collect())
+        }
+
+        fn decode(&self, tokens: &[u32]) -> Result<String> {
+            Ok(tokens.iter().map(|t| char::from(b'a' + *t as u8)).collect())
+        }
+
+        fn eos_token(&self) -> Option<u32> {
+            None
+        }
+
+        fn logits(&self, tokens: &[u32], positions: usize) -> Result<Vec<Vec<f32>>> {
+            Ok((0..positions)
+                .map(|i| {
+                    let last = tokens[tokens.len() - positions + i];
+                    let mut logits = vec![0.0; 8];
+                    logits[((last + self.step) % 8) as usize] = 8.0;
+                    logits
+                })
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_local_provider_with_draft() {
+        let provider = LocalProvider::new(Arc::new(AlphabetModel { step: 1 }))
+            .with_draft_model(Arc::new(AlphabetModel { step: 1 }));
+        let options = LlmOptions::new()
+            .with_temperature(0.0)
+            .with_max_tokens(6)
+            .with_stop(vec!["g".to_string()])
+            .with_speculative_decoding(SpeculativeConfig::new().with_draft_tokens(3));
+
+        assert_eq!(provider.generate("abc", &options).await.unwrap(), "def");
+        let metrics = provider.speculative_metrics();
+        assert_eq!(metrics.generated_tokens, 6);
+        assert_eq!(metrics.target_calls, 2);
+
+        // Without the option the draft model is not used
+        provider.generate("abc", &LlmOptions::new().with_temperature(0.0).with_max_tokens(2)).await.unwrap();
+        assert_eq!(provider.speculative_metrics().generated_tokens, 6);
+    }
+}
@@ -132,6 +132,10 @@ impl LlmProvider for MockLlmProvider {
     fn supports_function_calling(&self) -> bool {
         true
     }
+
+    fn supports_seed(&self) -> bool {
+        true
+    }
 }
 
 #[cfg(test)]
@@ -16,6 +16,13 @@ pub mod claude;
 pub mod zhipu;
 pub mod baidu;
 pub mod providers;
+pub mod tokenizer;
+pub mod prompt_cache;
+pub mod local;
+pub mod speculative;
+pub mod batch;
+pub mod wire_log;
+pub mod repair;
 #[cfg(test)]
 mod tests;
 
@@ -24,8 +31,15 @@ mod new_providers_test;
 mod third_party_integration_test;
 
 
-pub use types::{Message, LlmOptions, Role};
+pub use types::{Message, LlmOptions, Role, SamplingParam};
 pub use provider::LlmProvider;
+pub use tokenizer::{Tokenizer, HeuristicTokenizer, tokenizer_for_model, register_tokenizer};
+pub use prompt_cache::{PromptCacheConfig, CacheUsage, CachePricing, mark_cache_breakpoint};
+pub use local::{LocalProvider, TokenModel};
+pub use speculative::{SpeculativeConfig, SpeculativeMetrics};
+pub use batch::{BatchRequest, BatchResponse};
+pub use wire_log::{WireLogConfig, WireLoggingProvider};
+pub use repair::{RepairConfig, RepairStats, RepairingProvider};
 pub use mock::MockLlmProvider;
 pub use openai::OpenAiProvider;
 pub use anthropic::AnthropicProvider;
@@ -8,8 +8,9 @@ use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
 use crate::error::{Error, Result};
+use crate::config::http_client_builder_for;
 use super::{
-    LlmProvider, LlmOptions, Message, Role,
+    LlmProvider, LlmOptions, Message, Role, SamplingParam,
     function_calling::{FunctionDefinition, ToolChoice},
     provider::FunctionCallingResponse
 };
@@ -72,6 +73,14 @@ pub struct OllamaOptions {
     pub top_k: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub num_predict: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequency_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub presence_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
 }
 
 /// Ollama response
@@ -107,7 +116,7 @@ impl OllamaProvider {
     pub fn new(base_url: String, model: String) -> Self {
         let config = OllamaConfig { base_url, model };
         
-        let client = Client::builder()
+        let client = http_client_builder_for(&config.base_url)
             .default_headers({
                 let mut headers = reqwest::header::HeaderMap::new();
                 headers.insert(
@@ -154,11 +163,21 @@ impl OllamaProvider {
 
     /// Convert LlmOptions to OllamaOptions
     fn convert_options(&self, options: &LlmOptions) -> OllamaOptions {
+        options.warn_unsupported("ollama", &[
+            SamplingParam::Stop,
+            SamplingParam::FrequencyPenalty,
+            SamplingParam::PresencePenalty,
+            SamplingParam::Seed,
+        ]);
         OllamaOptions {
             temperature: options.temperature,
             top_p: options.extra.get("top_p").and_then(|v| v.as_f64()).map(|f| f as f32),
             top_k: None,
             num_predict: options.max_tokens,
+            stop: options.stop.clone(),
+            frequency_penalty: options.frequency_penalty,
+            presence_penalty: options.presence_penalty,
+            seed: options.seed,
         }
     }
 }
@@ -312,6 +331,10 @@ impl LlmProvider for OllamaProvider {
         false // Ollama doesn't support OpenAI-style function calling
     }
 
+    fn supports_seed(&self) -> bool {
+        true
+    }
+
     async fn generate_with_functions(
         &self,
         messages: &[Message],
@@ -3,11 +3,20 @@ use futures::stream::{self, BoxStream};
 use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
 
 use crate::{Error, Result};
+use crate::config::http_client_for;
 use super::provider::{LlmProvider, FunctionCallingResponse};
-use super::types::{LlmOptions, Message};
+use super::types::{LlmOptions, Message, SamplingParam};
 use super::function_calling::{FunctionDefinition, FunctionCall, ToolChoice};
+use super::prompt_cache::{CacheUsage, PromptCacheConfig, PromptCacheStats};
+use super::batch::{generate_concurrently, BatchRequest, BatchResponse, DEFAULT_BATCH_CONCURRENCY};
+
+/// Batch API任务的默认轮询间隔
+const DEFAULT_BATCH_POLL_INTERVAL: Duration = Duration::from_secs(30);
 
 /// OpenAI API响应结构
 #[derive(Debug, Deserialize)]
@@ -111,6 +120,12 @@ pub struct OpenAiProvider {
     base_url: String,
     /// HTTP客户端
     client: reqwest::Client,
+    /// 提示缓存命中统计
+    cache_stats: Arc<PromptCacheStats>,
+    /// 是否通过Batch API处理批量请求
+    batch_api: bool,
+    /// Batch API任务的轮询间隔
+    batch_poll_interval: Duration,
 }
 
 impl OpenAiProvider {
@@ -120,15 +135,33 @@ impl OpenAiProvider {
             api_key, 
             model, 
             base_url: "https://api.openai.com/v1".to_string(),
-            client: reqwest::Client::new(),
+            client: http_client_for("https://api.openai.com/v1"),
+            cache_stats: Arc::new(PromptCacheStats::default()),
+            batch_api: true,
+            batch_poll_interval: DEFAULT_BATCH_POLL_INTERVAL,
         }
     }
     
     /// 设置自定义的API基础URL
     pub fn with_base_url(mut self, base_url: String) -> Self {
+        self.client = http_client_for(&base_url);
         self.base_url = base_url;
         self
     }
+
+    /// 设置是否使用Batch API处理批量请求
+    ///
+    /// 不支持 `/batches` 的OpenAI兼容服务应关闭，批量请求将改为有限并发的普通请求
+    pub fn with_batch_api(mut self, enabled: bool) -> Self {
+        self.batch_api = enabled;
+        self
+    }
+
+    /// 设置Batch API任务的轮询间隔
+    pub fn with_batch_poll_interval(mut self, interval: Duration) -> Self {
+        self.batch_poll_interval = interval;
+        self
+    }
     
     /// 创建授权请求头
     fn create_headers(&self) -> HeaderMap {
@@ -142,6 +175,189 @@ impl OpenAiProvider {
         headers
     }
     
+    /// 应用提示缓存选项
+    ///
+    /// OpenAI自动缓存较长的提示前缀，这里只需附加路由键，让共享前缀的请求命中同一缓存
+    fn apply_prompt_cache(&self, body: &mut Value, options: &LlmOptions) {
+        if let Some(key) = PromptCacheConfig::from_options(options).and_then(|config| config.cache_key) {
+            body["prompt_cache_key"] = Value::String(key);
+        }
+    }
+
+    /// 记录响应中的缓存命中情况
+    fn record_cache_usage(&self, response: &Value) {
+        if let Some(usage) = response.get("usage") {
+            self.cache_stats.record(CacheUsage::from_openai(usage));
+        }
+    }
+
+    /// 构建Chat Completions请求正文
+    fn chat_completion_body(&self, messages: &[Message], options: &LlmOptions) -> Value {
+        // 转换消息格式
+        let api_messages: Vec<serde_json::Value> = messages
+            .iter()
+            .map(|msg| {
+                serde_json::json!({
+                    "role": msg.role.as_str(),
+                    "content": msg.content.clone(),
+                    "name": msg.name.clone(),
+                })
+            })
+            .collect();
+        
+        // 构建请求正文
+        let mut body = serde_json::json!({
+            "model": options.model.clone().unwrap_or_else(|| self.model.clone()),
+            "messages": api_messages,
+        });
+        
+        // 添加选项参数
+        if let Some(temperature) = options.temperature {
+            body["temperature"] = serde_json::json!(temperature);
+        }
+        
+        if let Some(max_tokens) = options.max_tokens {
+            body["max_tokens"] = serde_json::json!(max_tokens);
+        }
+        
+        options.apply_sampling_params(&mut body, "openai", &SamplingParam::ALL);
+        self.apply_prompt_cache(&mut body, options);
+        body
+    }
+
+    /// 生成Batch API的JSONL输入文件
+    fn batch_input_file(&self, requests: &[BatchRequest]) -> Result<String> {
+        let mut seen = HashSet::new();
+        let mut lines = Vec::with_capacity(requests.len());
+        for request in requests {
+            if !seen.insert(request.custom_id.as_str()) {
+                return Err(Error::InvalidInput(format!("Duplicate batch custom_id: {}", request.custom_id)));
+            }
+            let line = serde_json::json!({
+                "custom_id": request.custom_id,
+                "method": "POST",
+                "url": "/v1/chat/completions",
+                "body": self.chat_completion_body(&request.messages, &request.options),
+            });
+            lines.push(serde_json::to_string(&line)?);
+        }
+        Ok(lines.join("\n"))
+    }
+
+    /// 发送请求并解析JSON响应
+    async fn send_json(&self, request: reqwest::RequestBuilder, action: &str) -> Result<Value> {
+        let res = request
+            .send()
+            .await
+            .map_err(|e| Error::Llm(format!("OpenAI {} request failed: {}", action, e)))?;
+        let status = res.status();
+        let text = res.text().await
+            .map_err(|e| Error::Llm(format!("Failed to read OpenAI {} response: {}", action, e)))?;
+        if !status.is_success() {
+            return Err(Error::provider("openai", Some(status.as_u16()), format!("OpenAI {} returned error status {}: {}", action, status, text)));
+        }
+        serde_json::from_str(&text)
+            .map_err(|e| Error::Llm(format!("Failed to parse OpenAI {} response: {}", action, e)))
+    }
+
+    /// 上传Batch API输入文件，返回文件ID
+    async fn upload_batch_file(&self, content: String) -> Result<String> {
+        let boundary = format!("lumos-{}", uuid::Uuid::new_v4().simple());
+        let body = format!(
+            "--{b}\r\nContent-Disposition: form-data; name=\"purpose\"\r\n\r\nbatch\r\n\
+             --{b}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"batch.jsonl\"\r\n\
+             Content-Type: application/jsonl\r\n\r\n{content}\r\n--{b}--\r\n",
+            b = boundary,
+            content = content,
+        );
+        let request = self.client
+            .post(format!("{}/files", self.base_url))
+            .header(AUTHORIZATION, format!("Bearer {}", self.api_key))
+            .header(CONTENT_TYPE, format!("multipart/form-data; boundary={}", boundary))
+            .body(body);
+        let file = self.send_json(request, "file upload").await?;
+        file["id"].as_str()
+            .map(str::to_string)
+            .ok_or_else(|| Error::Llm("OpenAI file upload response has no id".to_string()))
+    }
+
+    /// 下载文件内容
+    async fn download_file(&self, file_id: &str) -> Result<String> {
+        let res = self.client
+            .get(format!("{}/files/{}/content", self.base_url, file_id))
+            .headers(self.create_headers())
+            .send()
+            .await
+            .map_err(|e| Error::Llm(format!("OpenAI file download failed: {}", e)))?;
+        let status = res.status();
+        let text = res.text().await
+            .map_err(|e| Error::Llm(format!("Failed to read OpenAI file {}: {}", file_id, e)))?;
+        if !status.is_success() {
+            return Err(Error::provider("openai", Some(status.as_u16()), format!("OpenAI file download returned error status {}: {}", status, text)));
+        }
+        Ok(text)
+    }
+
+    /// 通过Batch API执行批量请求：上传输入、创建任务、轮询直至结束并收集结果
+    async fn run_batch_job(&self, requests: Vec<BatchRequest>) -> Result<Vec<BatchResponse>> {
+        let input_file_id = self.upload_batch_file(self.batch_input_file(&requests)?).await?;
+        let request = self.client
+            .post(format!("{}/batches", self.base_url))
+            .headers(self.create_headers())
+            .json(&serde_json::json!({
+                "input_file_id": input_file_id,
+                "endpoint": "/v1/chat/completions",
+                "completion_window": "24h",
+            }));
+        let mut batch = self.send_json(request, "batch creation").await?;
+        let batch_id = batch["id"].as_str()
+            .map(str::to_string)
+            .ok_or_else(|| Error::Llm("OpenAI batch response has no id".to_string()))?;
+
+        loop {
+            match batch["status"].as_str().unwrap_or_default() {
+                "completed" | "expired" | "cancelled" => break,
+                "failed" => {
+                    return Err(Error::Llm(format!("OpenAI batch {} failed: {}", batch_id, batch["errors"])));
+                }
+                _ => {
+                    tokio::time::sleep(self.batch_poll_interval).await;
+                    let request = self.client
+                        .get(format!("{}/batches/{}", self.base_url, batch_id))
+                        .headers(self.create_headers());
+                    batch = self.send_json(request, "batch status").await?;
+                }
+            }
+        }
+
+        // 过期或取消的任务仍可能有部分结果
+        let mut results = HashMap::new();
+        for field in ["output_file_id", "error_file_id"] {
+            if let Some(file_id) = batch[field].as_str() {
+                let content = self.download_file(file_id).await?;
+                for (custom_id, result) in parse_batch_output(&content) {
+                    if let Some(usage) = result.as_ref().ok().and_then(|body| body.get("usage")) {
+                        self.cache_stats.record(CacheUsage::from_openai(usage));
+                    }
+                    results.insert(custom_id, result);
+                }
+            }
+        }
+
+        let status = batch["status"].as_str().unwrap_or_default().to_string();
+        Ok(requests
+            .into_iter()
+            .map(|request| match results.remove(&request.custom_id) {
+                Some(Ok(body)) => match body["choices"][0]["message"]["content"].as_str() {
+                    Some(content) => BatchResponse::success(request.custom_id, content),
+                    None => BatchResponse::failure(request.custom_id, "Invalid response format from OpenAI"),
+                },
+                Some(Err(error)) => BatchResponse::failure(request.custom_id, error),
+                None => BatchResponse::failure(request.custom_id, format!("Missing from batch output (batch {})", status)),
+            })
+            .collect())
+    }
+
     /// 从Lumosai消息转换为OpenAI消息格式
     fn convert_messages(&self, messages: &[Message]) -> Vec<OpenAIRequestMessage> {
         messages
@@ -186,9 +402,8 @@ impl LlmProvider for OpenAiProvider {
             body["max_tokens"] = serde_json::json!(max_tokens);
         }
         
-        if let Some(stop) = &options.stop {
-            body["stop"] = serde_json::json!(stop);
-        }
+        options.apply_sampling_params(&mut body, "openai", &SamplingParam::ALL);
+        self.apply_prompt_cache(&mut body, options);
         
         // 发送请求
         let res = self.client
@@ -204,7 +419,7 @@ impl LlmProvider for OpenAiProvider {
             .map_err(|e| Error::Llm(format!("Failed to read OpenAI response: {}", e)))?;
             
         if !status.is_success() {
-            return Err(Error::Llm(format!(
+            return Err(Error::provider("openai", Some(status.as_u16()), format!(
                 "OpenAI API returned error status {}: {}",
                 status, text
             )));
@@ -213,6 +428,7 @@ impl LlmProvider for OpenAiProvider {
         // 解析响应
         let response: serde_json::Value = serde_json::from_str(&text)
             .map_err(|e| Error::Llm(format!("Failed to parse OpenAI response: {}", e)))?;
+        self.record_cache_usage(&response);
             
         // 提取生成的文本
         let content = response["choices"][0]["message"]["content"]
@@ -225,37 +441,7 @@ impl LlmProvider for OpenAiProvider {
     async fn generate_with_messages(&self, messages: &[Message], options: &LlmOptions) -> Result<String> {
         // 准备请求数据
         let url = format!("{}/chat/completions", self.base_url);
-        
-        // 转换消息格式
-        let api_messages: Vec<serde_json::Value> = messages
-            .iter()
-            .map(|msg| {
-                serde_json::json!({
-                    "role": msg.role.as_str(),
-                    "content": msg.content.clone(),
-                    "name": msg.name.clone(),
-                })
-            })
-            .collect();
-        
-        // 构建请求正文
-        let mut body = serde_json::json!({
-            "model": options.model.clone().unwrap_or_else(|| self.model.clone()),
-            "messages": api_messages,
-        });
-        
-        // 添加选项参数
-        if let Some(temperature) = options.temperature {
-            body["temperature"] = serde_json::json!(temperature);
-        }
-        
-        if let Some(max_tokens) = options.max_tokens {
-            body["max_tokens"] = serde_json::json!(max_tokens);
-        }
-        
-        if let Some(stop) = &options.stop {
-            body["stop"] = serde_json::json!(stop);
-        }
+        let body = self.chat_completion_body(messages, options);
         
         // 发送请求
         let res = self.client
@@ -271,7 +457,7 @@ impl LlmProvider for OpenAiProvider {
             .map_err(|e| Error::Llm(format!("Failed to read OpenAI response: {}", e)))?;
             
         if !status.is_success() {
-            return Err(Error::Llm(format!(
+            return Err(Error::provider("openai", Some(status.as_u16()), format!(
                 "OpenAI API returned error status {}: {}",
                 status, text
             )));
@@ -280,6 +466,7 @@ impl LlmProvider for OpenAiProvider {
         // 解析响应
         let response: serde_json::Value = serde_json::from_str(&text)
             .map_err(|e| Error::Llm(format!("Failed to parse OpenAI response: {}", e)))?;
+        self.record_cache_usage(&response);
             
         // 提取生成的文本
         let content = response["choices"][0]["message"]["content"]
@@ -326,7 +513,7 @@ impl LlmProvider for OpenAiProvider {
             .map_err(|e| Error::Llm(format!("Failed to read OpenAI embedding response: {}", e)))?;
             
         if !status.is_success() {
-            return Err(Error::Llm(format!(
+            return Err(Error::provider("openai", Some(status.as_u16()), format!(
                 "OpenAI API returned error status {}: {}",
                 status, text
             )));
@@ -353,6 +540,21 @@ impl LlmProvider for OpenAiProvider {
         true
     }
 
+    fn supports_seed(&self) -> bool {
+        true
+    }
+
+    fn prompt_cache_usage(&self) -> Option<CacheUsage> {
+        Some(self.cache_stats.snapshot())
+    }
+
+    async fn generate_batch(&self, requests: Vec<BatchRequest>) -> Result<Vec<BatchResponse>> {
+        if !self.batch_api || requests.is_empty() {
+            return Ok(generate_concurrently(self, requests, DEFAULT_BATCH_CONCURRENCY).await);
+        }
+        self.run_batch_job(requests).await
+    }
+
     async fn generate_with_functions(
         &self,
         messages: &[Message],
@@ -415,6 +617,8 @@ impl LlmProvider for OpenAiProvider {
         if let Some(max_tokens) = options.max_tokens {
             body["max_tokens"] = serde_json::json!(max_tokens);
         }
+        options.apply_sampling_params(&mut body, "openai", &SamplingParam::ALL);
+        self.apply_prompt_cache(&mut body, options);
 
         // 发送请求
         let res = self.client
@@ -430,14 +634,17 @@ impl LlmProvider for OpenAiProvider {
             .map_err(|e| Error::Llm(format!("Failed to read OpenAI response: {}", e)))?;
 
         if !status.is_success() {
-            return Err(Error::Llm(format!(
+            return Err(Error::provider("openai", Some(status.as_u16()), format!(
                 "OpenAI API returned error status {}: {}",
                 status, response_text
             )));
         }
 
         // 解析响应
-        let response: OpenAIResponse = serde_json::from_str(&response_text)
+        let response: Value = serde_json::from_str(&response_text)
+            .map_err(|e| Error::Llm(format!("Failed to parse OpenAI response: {}", e)))?;
+        self.record_cache_usage(&response);
+        let response: OpenAIResponse = serde_json::from_value(response)
             .map_err(|e| Error::Llm(format!("Failed to parse OpenAI response: {}", e)))?;
 
         if response.choices.is_empty() {
@@ -464,4 +671,64 @@ impl LlmProvider for OpenAiProvider {
             finish_reason: choice.finish_reason.clone().unwrap_or_else(|| "stop".to_string()),
         })
     }
-}
\ No newline at end of file
+}
+
+/// 解析Batch API输出或错误文件，返回 `custom_id` 与响应正文或错误信息
+fn parse_batch_output(content: &str) -> Vec<(String, std::result::Result<Value, String>)> {
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .filter_map(|line| {
+            let custom_id = line["custom_id"].as_str()?.to_string();
+            let response = &line["response"];
+            let status = response["status_code"].as_u64().unwrap_or(0);
+            let result = if !line["error"].is_null() {
+                Err(line["error"]["message"].as_str().map(str::to_string).unwrap_or_else(|| line["error"].to_string()))
+            } else if (200..300).contains(&status) {
+                Ok(response["body"].clone())
+            } else {
+                Err(format!("OpenAI returned error status {}: {}", status, response["body"]))
+            };
+            Some((custom_id, result))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::types::{system_message, user_message};
+
+    #[test]
+    fn test_batch_input_file() {
+        let provider = OpenAiProvider::new("test-key".to_string(), "gpt-4o-mini".to_string());
+        let requests = vec![
+            BatchRequest::new("a", vec![system_message("Label sentiment"), user_message("Great!")]),
+            BatchRequest::from_prompt("b", "Terrible").with_options(LlmOptions::new().with_max_tokens(5)),
+        ];
+
+        let content = provider.batch_input_file(&requests).unwrap();
+        let lines: Vec<Value> = content.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["url"], "/v1/chat/completions");
+        assert_eq!(lines[0]["body"]["model"], "gpt-4o-mini");
+        assert_eq!(lines[0]["body"]["messages"][0]["role"], "system");
+        assert_eq!(lines[1]["body"]["max_tokens"], 5);
+
+        let duplicate = vec![BatchRequest::from_prompt("a", "x"), BatchRequest::from_prompt("a", "y")];
+        assert!(provider.batch_input_file(&duplicate).is_err());
+    }
+
+    #[test]
+    fn test_parse_batch_output() {
+        let content = r#"{"id":"r1","custom_id":"a","response":{"status_code":200,"body":{"choices":[{"message":{"content":"positive"}}]}},"error":null}
+{"id":"r2","custom_id":"b","response":{"status_code":400,"body":{"error":{"message":"bad"}}},"error":null}
+{"id":"r3","custom_id":"c","response":null,"error":{"code":"batch_expired","message":"This request could not be executed before the completion window expired."}}
+"#;
+        let results: HashMap<_, _> = parse_batch_output(content).into_iter().collect();
+        assert_eq!(results["a"].as_ref().unwrap()["choices"][0]["message"]["content"], "positive");
+        assert!(results["b"].as_ref().unwrap_err().contains("400"));
+        assert!(results["c"].as_ref().unwrap_err().contains("completion window"));
+    }
+}
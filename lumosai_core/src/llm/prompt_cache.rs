@@ -0,0 +1,346 @@
+//! Provider-native prompt caching
+//!
+//! Anthropic caches the prompt prefix up to each `cache_control` breakpoint, while OpenAI
+//! caches long prefixes automatically and only benefits from a stable prefix plus an
+//! optional routing key. [`PromptCacheConfig`] selects which stable parts of a request
+//! (system prompt, tool schemas, marked messages such as RAG boilerplate) are cacheable,
+//! and [`CacheUsage`] carries the cache counters reported back by the provider.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::ops::AddAssign;
+use std::sync::Mutex;
+
+use super::types::{LlmOptions, Message};
+
+/// Key in [`LlmOptions::extra`] holding the prompt cache configuration
+pub const PROMPT_CACHE_OPTION_KEY: &str = "prompt_cache";
+
+/// Message metadata key marking the end of a stable, cacheable prefix
+pub const CACHE_BREAKPOINT_METADATA_KEY: &str = "cache_breakpoint";
+
+/// Anthropic accepts at most four cache breakpoints per request
+const MAX_ANTHROPIC_BREAKPOINTS: usize = 4;
+
+/// Which parts of a request are marked as cacheable
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PromptCacheConfig {
+    /// Cache the system prompt
+    pub system: bool,
+    /// Cache the tool schemas
+    pub tools: bool,
+    /// Cache up to messages marked with [`mark_cache_breakpoint`]
+    pub marked_messages: bool,
+    /// Routing key that keeps requests sharing a prefix on the same cache (OpenAI)
+    pub cache_key: Option<String>,
+}
+
+impl Default for PromptCacheConfig {
+    fn default() -> Self {
+        Self {
+            system: true,
+            tools: true,
+            marked_messages: true,
+            cache_key: None,
+        }
+    }
+}
+
+impl PromptCacheConfig {
+    /// Cache the system prompt, tool schemas and marked messages
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the cache routing key
+    pub fn with_cache_key(mut self, key: impl Into<String>) -> Self {
+        self.cache_key = Some(key.into());
+        self
+    }
+
+    /// Set whether the system prompt is cached
+    pub fn with_system(mut self, enabled: bool) -> Self {
+        self.system = enabled;
+        self
+    }
+
+    /// Set whether the tool schemas are cached
+    pub fn with_tools(mut self, enabled: bool) -> Self {
+        self.tools = enabled;
+        self
+    }
+
+    /// Read the configuration from the request options, if prompt caching is enabled
+    pub fn from_options(options: &LlmOptions) -> Option<Self> {
+        options
+            .extra
+            .get(PROMPT_CACHE_OPTION_KEY)
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+    }
+}
+
+impl LlmOptions {
+    /// Enable provider-native prompt caching for this request
+    pub fn with_prompt_cache(mut self, config: PromptCacheConfig) -> Self {
+        let value = serde_json::to_value(config).unwrap_or(Value::Null);
+        self.extra.insert(PROMPT_CACHE_OPTION_KEY.to_string(), value);
+        self
+    }
+}
+
+/// Mark a message as the end of a stable prefix, e.g. retrieved context boilerplate
+pub fn mark_cache_breakpoint(message: Message) -> Message {
+    message.with_metadata(CACHE_BREAKPOINT_METADATA_KEY, Value::Bool(true))
+}
+
+/// Whether a message ends a cacheable prefix
+pub fn is_cache_breakpoint(message: &Message) -> bool {
+    message
+        .metadata
+        .as_ref()
+        .and_then(|metadata| metadata.get(CACHE_BREAKPOINT_METADATA_KEY))
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+}
+
+fn ephemeral() -> Value {
+    serde_json::json!({ "type": "ephemeral" })
+}
+
+/// Turn a plain string content into a single text block carrying a cache marker
+fn mark_content(content: &mut Value) {
+    match content {
+        Value::String(text) => {
+            *content = serde_json::json!([{
+                "type": "text",
+                "text": std::mem::take(text),
+                "cache_control": ephemeral(),
+            }]);
+        }
+        Value::Array(blocks) => {
+            if let Some(Value::Object(last)) = blocks.last_mut() {
+                last.insert("cache_control".to_string(), ephemeral());
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Add `cache_control` breakpoints to an Anthropic Messages API request body
+///
+/// Anthropic builds the prompt as tools, then system, then messages. Tool and system
+/// breakpoints are always kept; the remaining slots go to the latest message breakpoints,
+/// since a later breakpoint covers a longer prefix.
+pub fn apply_anthropic_cache_control(body: &mut Value, config: &PromptCacheConfig, message_breakpoints: &[usize]) {
+    let mut used = 0;
+
+    if config.tools {
+        if let Some(Value::Object(last)) = body.get_mut("tools").and_then(Value::as_array_mut).and_then(|tools| tools.last_mut()) {
+            last.insert("cache_control".to_string(), ephemeral());
+            used += 1;
+        }
+    }
+
+    if config.system {
+        if let Some(system) = body.get_mut("system").filter(|system| !system.is_null()) {
+            mark_content(system);
+            used += 1;
+        }
+    }
+
+    if config.marked_messages {
+        let available = MAX_ANTHROPIC_BREAKPOINTS.saturating_sub(used);
+        let skip = message_breakpoints.len().saturating_sub(available);
+        if let Some(messages) = body.get_mut("messages").and_then(Value::as_array_mut) {
+            for &index in &message_breakpoints[skip..] {
+                if let Some(content) = messages.get_mut(index).and_then(|message| message.get_mut("content")) {
+                    mark_content(content);
+                }
+            }
+        }
+    }
+}
+
+/// Prompt token counters split by cache outcome
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheUsage {
+    /// Prompt tokens billed at the regular input rate
+    pub input_tokens: u64,
+    /// Prompt tokens written to the cache
+    pub cache_creation_input_tokens: u64,
+    /// Prompt tokens served from the cache
+    pub cache_read_input_tokens: u64,
+}
+
+impl CacheUsage {
+    /// Parse an Anthropic `usage` object, where `input_tokens` excludes cached tokens
+    pub fn from_anthropic(usage: &Value) -> Self {
+        let field = |name: &str| usage.get(name).and_then(Value::as_u64).unwrap_or(0);
+        Self {
+            input_tokens: field("input_tokens"),
+            cache_creation_input_tokens: field("cache_creation_input_tokens"),
+            cache_read_input_tokens: field("cache_read_input_tokens"),
+        }
+    }
+
+    /// Parse an OpenAI `usage` object, where `prompt_tokens` includes cached tokens
+    pub fn from_openai(usage: &Value) -> Self {
+        let prompt_tokens = usage.get("prompt_tokens").and_then(Value::as_u64).unwrap_or(0);
+        let cached = usage
+            .pointer("/prompt_tokens_details/cached_tokens")
+            .and_then(Value::as_u64)
+            .unwrap_or(0);
+        Self {
+            input_tokens: prompt_tokens.saturating_sub(cached),
+            cache_creation_input_tokens: 0,
+            cache_read_input_tokens: cached,
+        }
+    }
+
+    /// Total prompt tokens regardless of cache outcome
+    pub fn prompt_tokens(&self) -> u64 {
+        self.input_tokens + self.cache_creation_input_tokens + self.cache_read_input_tokens
+    }
+
+    /// Fraction of prompt tokens served from the cache
+    pub fn hit_ratio(&self) -> f64 {
+        match self.prompt_tokens() {
+            0 => 0.0,
+            total => self.cache_read_input_tokens as f64 / total as f64,
+        }
+    }
+
+    /// Input tokens saved compared to an uncached request, in regular-rate token equivalents
+    ///
+    /// Cache writes cost more than regular input on some providers, so this can be
+    /// negative for a request that only populated the cache.
+    pub fn saved_tokens(&self, pricing: &CachePricing) -> f64 {
+        self.cache_read_input_tokens as f64 * (1.0 - pricing.read_multiplier)
+            - self.cache_creation_input_tokens as f64 * (pricing.write_multiplier - 1.0)
+    }
+}
+
+impl AddAssign for CacheUsage {
+    fn add_assign(&mut self, other: Self) {
+        self.input_tokens += other.input_tokens;
+        self.cache_creation_input_tokens += other.cache_creation_input_tokens;
+        self.cache_read_input_tokens += other.cache_read_input_tokens;
+    }
+}
+
+/// Price of cached prompt tokens relative to the regular input rate
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CachePricing {
+    /// Multiplier applied to tokens read from the cache
+    pub read_multiplier: f64,
+    /// Multiplier applied to tokens written to the cache
+    pub write_multiplier: f64,
+}
+
+impl CachePricing {
+    /// Anthropic: reads cost 10% and 5-minute writes 125% of the input rate
+    pub fn anthropic() -> Self {
+        Self { read_multiplier: 0.1, write_multiplier: 1.25 }
+    }
+
+    /// OpenAI: cached reads are discounted by half and writes are free
+    pub fn openai() -> Self {
+        Self { read_multiplier: 0.5, write_multiplier: 1.0 }
+    }
+
+    /// Pricing for a provider name as reported by [`LlmProvider::name`](super::LlmProvider::name)
+    pub fn for_provider(provider: &str) -> Option<Self> {
+        match provider {
+            "anthropic" | "claude" => Some(Self::anthropic()),
+            "openai" => Some(Self::openai()),
+            _ => None,
+        }
+    }
+}
+
+/// Running cache counters kept by a provider
+#[derive(Debug, Default)]
+pub struct PromptCacheStats {
+    totals: Mutex<CacheUsage>,
+}
+
+impl PromptCacheStats {
+    /// Add the counters of one response
+    pub fn record(&self, usage: CacheUsage) {
+        if let Ok(mut totals) = self.totals.lock() {
+            *totals += usage;
+        }
+    }
+
+    /// Counters accumulated so far
+    pub fn snapshot(&self) -> CacheUsage {
+        self.totals.lock().map(|totals| *totals).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::types::{system_message, user_message};
+
+    #[test]
+    fn test_anthropic_breakpoints() {
+        let mut body = serde_json::json!({
+            "system": "You are a support agent.",
+            "tools": [{ "name": "lookup" }, { "name": "refund" }],
+            "messages": [
+                { "role": "user", "content": "doc 1" },
+                { "role": "user", "content": "doc 2" },
+                { "role": "user", "content": "doc 3" },
+                { "role": "user", "content": "question" },
+            ],
+        });
+        apply_anthropic_cache_control(&mut body, &PromptCacheConfig::new(), &[0, 1, 2]);
+
+        assert_eq!(body["tools"][1]["cache_control"]["type"], "ephemeral");
+        assert!(body["tools"][0].get("cache_control").is_none());
+        assert_eq!(body["system"][0]["text"], "You are a support agent.");
+        assert_eq!(body["system"][0]["cache_control"]["type"], "ephemeral");
+        // Only two slots remain, so the earliest message breakpoint is dropped
+        assert_eq!(body["messages"][0]["content"], "doc 1");
+        assert_eq!(body["messages"][1]["content"][0]["cache_control"]["type"], "ephemeral");
+        assert_eq!(body["messages"][2]["content"][0]["text"], "doc 3");
+        assert_eq!(body["messages"][3]["content"], "question");
+    }
+
+    #[test]
+    fn test_options_and_markers() {
+        let options = LlmOptions::new().with_prompt_cache(PromptCacheConfig::new().with_cache_key("support-v1"));
+        let config = PromptCacheConfig::from_options(&options).unwrap();
+        assert_eq!(config.cache_key.as_deref(), Some("support-v1"));
+        assert!(PromptCacheConfig::from_options(&LlmOptions::new()).is_none());
+
+        assert!(is_cache_breakpoint(&mark_cache_breakpoint(system_message("rules"))));
+        assert!(!is_cache_breakpoint(&user_message("hi")));
+    }
+
+    #[test]
+    fn test_usage_and_savings() {
+        let anthropic = CacheUsage::from_anthropic(&serde_json::json!({
+            "input_tokens": 50,
+            "cache_creation_input_tokens": 0,
+            "cache_read_input_tokens": 2000,
+            "output_tokens": 30,
+        }));
+        assert_eq!(anthropic.prompt_tokens(), 2050);
+        assert!((anthropic.saved_tokens(&CachePricing::anthropic()) - 1800.0).abs() < 1e-6);
+
+        let openai = CacheUsage::from_openai(&serde_json::json!({
+            "prompt_tokens": 2048,
+            "completion_tokens": 10,
+            "prompt_tokens_details": { "cached_tokens": 1024 },
+        }));
+        assert_eq!(openai.input_tokens, 1024);
+        assert!((openai.hit_ratio() - 0.5).abs() < 1e-6);
+
+        let stats = PromptCacheStats::default();
+        stats.record(anthropic);
+        stats.record(openai);
+        assert_eq!(stats.snapshot().cache_read_input_tokens, 3024);
+    }
+}
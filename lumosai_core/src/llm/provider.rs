@@ -4,6 +4,8 @@ use futures::stream::BoxStream;
 use crate::Result;
 use super::types::{LlmOptions, Message};
 use super::function_calling::{FunctionDefinition, FunctionCall, ToolChoice};
+use super::prompt_cache::CacheUsage;
+use super::batch::{generate_concurrently, BatchRequest, BatchResponse, DEFAULT_BATCH_CONCURRENCY};
 
 /// Trait representing an LLM provider
 #[async_trait]
@@ -32,6 +34,25 @@ pub trait LlmProvider: Send + Sync {
         false
     }
     
+    /// Check if the provider honours [`LlmOptions::seed`] for reproducible sampling
+    fn supports_seed(&self) -> bool {
+        false
+    }
+    
+    /// Generate completions for many independent requests
+    ///
+    /// Responses come back in request order and a failed item does not fail the batch.
+    /// The default runs the requests with bounded concurrency; providers with a native
+    /// batch API override it.
+    async fn generate_batch(&self, requests: Vec<BatchRequest>) -> Result<Vec<BatchResponse>> {
+        Ok(generate_concurrently(self, requests, DEFAULT_BATCH_CONCURRENCY).await)
+    }
+    
+    /// Prompt cache counters accumulated by providers with native prompt caching
+    fn prompt_cache_usage(&self) -> Option<CacheUsage> {
+        None
+    }
+    
     /// Generate text with function calling support
     async fn generate_with_functions(
         &self,
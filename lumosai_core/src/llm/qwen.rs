@@ -26,8 +26,12 @@ use serde_json::{self, json};
 
 use crate::Result;
 use crate::Error;
+use crate::config::http_client_for;
 use super::provider::LlmProvider;
-use super::types::{LlmOptions, Message, Role};
+use super::types::{LlmOptions, Message, Role, SamplingParam};
+
+/// Sampling controls the DashScope APIs accept
+const SAMPLING_PARAMS: &[SamplingParam] = &[SamplingParam::Stop, SamplingParam::PresencePenalty, SamplingParam::Seed];
 
 impl From<OpenAIError> for Error {
     fn from(err: OpenAIError) -> Self {
@@ -229,6 +233,8 @@ pub struct QwenProvider {
     api_type: QwenApiType,
     /// API key
     api_key: String,
+    /// HTTP client for requests sent directly to DashScope
+    http: reqwest::Client,
 }
 
 impl QwenProvider {
@@ -240,12 +246,14 @@ impl QwenProvider {
         api_type: QwenApiType
     ) -> Self {
         let api_key_str = api_key.into();
+        let base_url = base_url.into();
+        let http = http_client_for(&base_url);
         let config = OpenAIConfig::new()
             .with_api_key(api_key_str.clone())
-            .with_api_base(base_url.into());
+            .with_api_base(base_url);
 
         Self {
-            client: Client::with_config(config),
+            client: Client::with_config(config).with_http_client(http.clone()),
             model: model.into(),
             embedding_model: match api_type {
                 QwenApiType::DashScope => "text-embedding-v1".to_string(),
@@ -253,6 +261,7 @@ impl QwenProvider {
             },
             api_type,
             api_key: api_key_str,
+            http,
         }
     }
 
@@ -342,6 +351,10 @@ impl LlmProvider for QwenProvider {
         "qwen"
     }
 
+    fn supports_seed(&self) -> bool {
+        true
+    }
+
     async fn generate(&self, prompt: &str, options: &LlmOptions) -> Result<String> {
         let messages = vec![Message {
             role: Role::User,
@@ -368,16 +381,16 @@ impl LlmProvider for QwenProvider {
                     })
                 }).collect();
 
-                let request = json!({
+                let mut request = json!({
                     "model": self.model,
                     "messages": messages_json,
                     "max_tokens": options.max_tokens.unwrap_or(1024),
                     "temperature": options.temperature.unwrap_or(0.7),
                     "enable_thinking": false
                 });
+                options.apply_sampling_params(&mut request, "qwen", SAMPLING_PARAMS);
 
-                let client = reqwest::Client::new();
-                let response = client
+                let response = self.http
                     .post("https://dashscope.aliyuncs.com/compatible-mode/v1/chat/completions")
                     .header("Authorization", format!("Bearer {}", self.api_key))
                     .header("Content-Type", "application/json")
@@ -417,7 +430,7 @@ impl LlmProvider for QwenProvider {
                 }).collect();
 
                 // Build DashScope request
-                let request = json!({
+                let mut request = json!({
                     "model": self.model,
                     "input": {
                         "messages": messages_json
@@ -429,10 +442,10 @@ impl LlmProvider for QwenProvider {
                         "result_format": "message"
                     }
                 });
+                options.apply_sampling_params(&mut request["parameters"], "qwen", SAMPLING_PARAMS);
 
                 // Send request using reqwest directly
-                let client = reqwest::Client::new();
-                let response = client
+                let response = self.http
                     .post("https://dashscope.aliyuncs.com/api/v1/services/aigc/text-generation/generation")
                     .header("Authorization", format!("Bearer {}", self.api_key))
                     .header("Content-Type", "application/json")
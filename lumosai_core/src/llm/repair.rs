@@ -0,0 +1,457 @@
+//! Validation and repair of malformed provider output
+//!
+//! [`RepairingProvider`] wraps an [`LlmProvider`] and checks every completion before
+//! returning it. Empty responses, JSON cut off mid-document, JSON that does not match an
+//! expected schema, and tool calls naming unknown functions or carrying arguments that do
+//! not parse or validate are sent back to the model: the conversation is extended with the
+//! faulty output and a message describing the problem, and the request is retried up to
+//! [`RepairConfig::max_retries`] times.
+//!
+//! Streams are passed through unchecked, since their chunks have already been delivered.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+use super::function_calling::{FunctionDefinition, ToolChoice};
+use super::function_calling_utils::validate_against_schema;
+use super::prompt_cache::CacheUsage;
+use super::provider::{FunctionCallingResponse, LlmProvider};
+use super::types::{assistant_message, user_message, LlmOptions, Message};
+
+/// Default number of corrective follow-up requests
+pub const DEFAULT_MAX_REPAIR_RETRIES: u32 = 2;
+
+/// Longest faulty output echoed back to the model, in bytes
+const MAX_ECHOED_OUTPUT: usize = 4096;
+
+/// Which responses count as malformed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RepairConfig {
+    /// Corrective follow-up requests before giving up
+    pub max_retries: u32,
+    /// Treat an empty response as malformed
+    pub reject_empty: bool,
+    /// Require every text response to be JSON
+    ///
+    /// Without it, only responses that start like a JSON document but fail to parse
+    /// (typically because the output was truncated) are repaired.
+    pub expect_json: bool,
+    /// JSON schema that JSON responses must match
+    pub json_schema: Option<Value>,
+}
+
+impl Default for RepairConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: DEFAULT_MAX_REPAIR_RETRIES,
+            reject_empty: true,
+            expect_json: false,
+            json_schema: None,
+        }
+    }
+}
+
+impl RepairConfig {
+    /// Retry at most `max_retries` times
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Require JSON responses, optionally matching `schema`
+    pub fn expect_json(mut self, schema: Option<Value>) -> Self {
+        self.expect_json = true;
+        self.json_schema = schema;
+        self
+    }
+
+    /// Accept empty responses
+    pub fn allow_empty(mut self) -> Self {
+        self.reject_empty = false;
+        self
+    }
+
+    /// Problem with a text response, if any
+    pub fn check_text(&self, text: &str) -> Option<String> {
+        let body = strip_code_fence(text.trim());
+        if body.is_empty() {
+            return self.reject_empty.then(|| "The response was empty.".to_string());
+        }
+
+        let looks_like_json = body.starts_with('{') || body.starts_with('[');
+        if !self.expect_json && !looks_like_json {
+            return None;
+        }
+        let value: Value = match serde_json::from_str(body) {
+            Ok(value) => value,
+            Err(e) if e.is_eof() => {
+                return Some(format!("The JSON in the response is truncated ({}). Return the complete document.", e));
+            },
+            Err(e) => return Some(format!("The response is not valid JSON: {}.", e)),
+        };
+        match &self.json_schema {
+            Some(schema) => validate_against_schema(&value, schema).err().map(|e| format!("{}.", e)),
+            None => None,
+        }
+    }
+
+    /// Problem with a function calling response, if any
+    pub fn check_function_calls(
+        &self,
+        response: &FunctionCallingResponse,
+        functions: &[FunctionDefinition],
+    ) -> Option<String> {
+        if response.function_calls.is_empty() {
+            return self.check_text(response.content.as_deref().unwrap_or_default());
+        }
+
+        let problems: Vec<String> = response.function_calls.iter()
+            .filter_map(|call| {
+                let Some(definition) = functions.iter().find(|function| function.name == call.name) else {
+                    let known: Vec<&str> = functions.iter().map(|function| function.name.as_str()).collect();
+                    return Some(format!("Function '{}' does not exist; available functions: {}.", call.name, known.join(", ")));
+                };
+                match serde_json::from_str::<Value>(if call.arguments.trim().is_empty() { "{}" } else { &call.arguments }) {
+                    Ok(arguments) => validate_against_schema(&arguments, &definition.parameters)
+                        .err()
+                        .map(|e| format!("Arguments of '{}' do not match its parameters: {}.", call.name, e)),
+                    Err(e) => Some(format!("Arguments of '{}' are not valid JSON: {}.", call.name, e)),
+                }
+            })
+            .collect();
+        (!problems.is_empty()).then(|| problems.join("\n"))
+    }
+}
+
+/// Counters of a [`RepairingProvider`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RepairStats {
+    /// Malformed responses detected
+    pub detected: u64,
+    /// Requests that produced a valid response after at least one follow-up
+    pub repaired: u64,
+    /// Requests that were still malformed after the last follow-up
+    pub failed: u64,
+}
+
+#[derive(Default)]
+struct Counters {
+    detected: AtomicU64,
+    repaired: AtomicU64,
+    failed: AtomicU64,
+}
+
+/// Provider wrapper repairing malformed responses of the inner provider
+pub struct RepairingProvider {
+    inner: Arc<dyn LlmProvider>,
+    config: RepairConfig,
+    counters: Counters,
+}
+
+impl RepairingProvider {
+    /// Repair responses of `inner` with the default configuration
+    pub fn new(inner: Arc<dyn LlmProvider>) -> Self {
+        Self {
+            inner,
+            config: RepairConfig::default(),
+            counters: Counters::default(),
+        }
+    }
+
+    /// Use `config` to decide which responses are malformed
+    pub fn with_config(mut self, config: RepairConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// The wrapped provider
+    pub fn inner(&self) -> &Arc<dyn LlmProvider> {
+        &self.inner
+    }
+
+    /// Repair counters since creation
+    pub fn stats(&self) -> RepairStats {
+        RepairStats {
+            detected: self.counters.detected.load(Ordering::Relaxed),
+            repaired: self.counters.repaired.load(Ordering::Relaxed),
+            failed: self.counters.failed.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Call `request` until `check` accepts the response or the retries run out
+    ///
+    /// After a rejected response, the conversation is extended with that response
+    /// (as rendered by `render`) and a corrective message before the next call.
+    async fn repair<T, F, Fut>(
+        &self,
+        messages: Vec<Message>,
+        request: F,
+        check: impl Fn(&T) -> Option<String>,
+        render: impl Fn(&T) -> String,
+    ) -> Result<T>
+    where
+        F: Fn(Vec<Message>) -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+    {
+        let mut messages = messages;
+        let mut attempt = 0;
+        loop {
+            let response = request(messages.clone()).await?;
+            let Some(problem) = check(&response) else {
+                if attempt > 0 {
+                    self.counters.repaired.fetch_add(1, Ordering::Relaxed);
+                }
+                return Ok(response);
+            };
+
+            self.counters.detected.fetch_add(1, Ordering::Relaxed);
+            if attempt >= self.config.max_retries {
+                self.counters.failed.fetch_add(1, Ordering::Relaxed);
+                return Err(Error::Llm(format!(
+                    "{} returned a malformed response after {} repair attempts: {}",
+                    self.inner.name(), attempt, problem
+                )));
+            }
+            attempt += 1;
+            tracing::debug!("Repairing malformed response from {} (attempt {}): {}", self.inner.name(), attempt, problem);
+
+            messages.push(assistant_message(&truncate(&render(&response))));
+            messages.push(user_message(&format!(
+                "Your previous response could not be used:\n{}\nReply again with a corrected, complete response.",
+                problem
+            )));
+        }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for RepairingProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn generate(&self, prompt: &str, options: &LlmOptions) -> Result<String> {
+        // Follow-ups continue as a conversation so the faulty response can be shown to the model
+        self.repair(
+            vec![user_message(prompt)],
+            |messages| async move {
+                if messages.len() == 1 {
+                    self.inner.generate(prompt, options).await
+                } else {
+                    self.inner.generate_with_messages(&messages, options).await
+                }
+            },
+            |text: &String| self.config.check_text(text),
+            |text: &String| text.clone(),
+        ).await
+    }
+
+    async fn generate_with_messages(&self, messages: &[Message], options: &LlmOptions) -> Result<String> {
+        self.repair(
+            messages.to_vec(),
+            |messages| async move { self.inner.generate_with_messages(&messages, options).await },
+            |text: &String| self.config.check_text(text),
+            |text: &String| text.clone(),
+        ).await
+    }
+
+    async fn generate_stream<'a>(
+        &'a self,
+        prompt: &'a str,
+        options: &'a LlmOptions,
+    ) -> Result<BoxStream<'a, Result<String>>> {
+        self.inner.generate_stream(prompt, options).await
+    }
+
+    async fn get_embedding(&self, text: &str) -> Result<Vec<f32>> {
+        self.inner.get_embedding(text).await
+    }
+
+    fn supports_function_calling(&self) -> bool {
+        self.inner.supports_function_calling()
+    }
+
+    fn supports_seed(&self) -> bool {
+        self.inner.supports_seed()
+    }
+
+    fn prompt_cache_usage(&self) -> Option<CacheUsage> {
+        self.inner.prompt_cache_usage()
+    }
+
+    async fn generate_with_functions(
+        &self,
+        messages: &[Message],
+        functions: &[FunctionDefinition],
+        tool_choice: &ToolChoice,
+        options: &LlmOptions,
+    ) -> Result<FunctionCallingResponse> {
+        self.repair(
+            messages.to_vec(),
+            |messages| async move {
+                self.inner.generate_with_functions(&messages, functions, tool_choice, options).await
+            },
+            |response: &FunctionCallingResponse| self.config.check_function_calls(response, functions),
+            render_function_response,
+        ).await
+    }
+}
+
+/// Text standing in for a function calling response in the follow-up conversation
+fn render_function_response(response: &FunctionCallingResponse) -> String {
+    let calls = response.function_calls.iter()
+        .map(|call| format!("Called function '{}' with arguments: {}", call.name, call.arguments));
+    response.content.iter().cloned().chain(calls).collect::<Vec<_>>().join("\n")
+}
+
+/// Body of a response wrapped in a Markdown code fence
+fn strip_code_fence(text: &str) -> &str {
+    let Some(rest) = text.strip_prefix("```") else {
+        return text;
+    };
+    let rest = rest.split_once('\n').map_or("", |(_, body)| body);
+    rest.trim_end().strip_suffix("```").unwrap_or(rest).trim()
+}
+
+/// Output echoed back to the model, capped at [`MAX_ECHOED_OUTPUT`]
+fn truncate(text: &str) -> String {
+    if text.len() <= MAX_ECHOED_OUTPUT {
+        return text.to_string();
+    }
+    let mut end = MAX_ECHOED_OUTPUT;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}...", &text[..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::function_calling::FunctionCall;
+    use crate::llm::MockLlmProvider;
+    use serde_json::json;
+    use std::sync::Mutex;
+
+    /// Provider returning scripted function calling responses and recording requests
+    struct ScriptedProvider {
+        responses: Mutex<Vec<FunctionCallingResponse>>,
+        requests: Mutex<Vec<Vec<Message>>>,
+    }
+
+    #[async_trait]
+    impl LlmProvider for ScriptedProvider {
+        fn name(&self) -> &str {
+            "scripted"
+        }
+
+        async fn generate(&self, _prompt: &str, _options: &LlmOptions) -> Result<String> {
+            unimplemented!()
+        }
+
+        async fn generate_with_messages(&self, _messages: &[Message], _options: &LlmOptions) -> Result<String> {
+            unimplemented!()
+        }
+
+        async fn generate_stream<'a>(
+            &'a self,
+            _prompt: &'a str,
+            _options: &'a LlmOptions,
+        ) -> Result<BoxStream<'a, Result<String>>> {
+            unimplemented!()
+        }
+
+        async fn get_embedding(&self, _text: &str) -> Result<Vec<f32>> {
+            unimplemented!()
+        }
+
+        async fn generate_with_functions(
+            &self,
+            messages: &[Message],
+            _functions: &[FunctionDefinition],
+            _tool_choice: &ToolChoice,
+            _options: &LlmOptions,
+        ) -> Result<FunctionCallingResponse> {
+            self.requests.lock().unwrap().push(messages.to_vec());
+            Ok(self.responses.lock().unwrap().remove(0))
+        }
+    }
+
+    fn call(name: &str, arguments: &str) -> FunctionCallingResponse {
+        FunctionCallingResponse {
+            content: None,
+            function_calls: vec![FunctionCall { id: None, name: name.to_string(), arguments: arguments.to_string() }],
+            finish_reason: "tool_calls".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_check_text() {
+        let config = RepairConfig::default();
+        assert!(config.check_text("  ").is_some());
+        assert!(config.check_text("plain answer").is_none());
+        assert!(config.check_text("{\"a\": 1}").is_none());
+        assert!(config.check_text("{\"a\": [1, 2").unwrap().contains("truncated"));
+        assert!(config.check_text("```json\n{\"a\": 1}\n```").is_none());
+        assert!(config.clone().allow_empty().check_text("").is_none());
+
+        let strict = config.expect_json(Some(json!({"type": "object", "required": ["name"]})));
+        assert!(strict.check_text("plain answer").unwrap().contains("not valid JSON"));
+        assert!(strict.check_text("{\"age\": 3}").is_some());
+        assert!(strict.check_text("{\"name\": \"a\"}").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_repairs_text() {
+        let inner = Arc::new(MockLlmProvider::new(vec![
+            "{\"items\": [1, 2".to_string(),
+            "{\"items\": [1, 2, 3]}".to_string(),
+        ]));
+        let provider = RepairingProvider::new(inner);
+        let response = provider.generate("list items as JSON", &LlmOptions::default()).await.unwrap();
+        assert_eq!(response, "{\"items\": [1, 2, 3]}");
+        assert_eq!(provider.stats(), RepairStats { detected: 1, repaired: 1, failed: 0 });
+
+        let inner = Arc::new(MockLlmProvider::new(vec!["".to_string(), "".to_string()]));
+        let provider = RepairingProvider::new(inner).with_config(RepairConfig::default().with_max_retries(1));
+        let error = provider.generate_with_messages(&[user_message("hi")], &LlmOptions::default()).await.unwrap_err();
+        assert!(error.to_string().contains("empty"));
+        assert_eq!(provider.stats(), RepairStats { detected: 2, repaired: 0, failed: 1 });
+    }
+
+    #[tokio::test]
+    async fn test_repairs_function_calls() {
+        let functions = vec![FunctionDefinition::new(
+            "get_weather".to_string(),
+            None,
+            json!({"type": "object", "properties": {"city": {"type": "string"}}, "required": ["city"]}),
+        )];
+        let inner = Arc::new(ScriptedProvider {
+            responses: Mutex::new(vec![
+                call("get_wether", "{\"city\": \"Paris\"}"),
+                call("get_weather", "{\"town\": \"Paris\"}"),
+                call("get_weather", "{\"city\": \"Paris\"}"),
+            ]),
+            requests: Mutex::new(Vec::new()),
+        });
+        let provider = RepairingProvider::new(inner.clone());
+
+        let response = provider
+            .generate_with_functions(&[user_message("weather?")], &functions, &ToolChoice::Auto, &LlmOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(response.function_calls[0].arguments, "{\"city\": \"Paris\"}");
+
+        let requests = inner.requests.lock().unwrap();
+        assert_eq!(requests.iter().map(Vec::len).collect::<Vec<_>>(), vec![1, 3, 5]);
+        assert!(requests[1][2].content.contains("'get_wether' does not exist"));
+        assert!(requests[2][3].content.contains("get_weather"));
+        assert!(requests[2][4].content.contains("do not match its parameters"));
+    }
+}
@@ -0,0 +1,282 @@
+//! Speculative decoding with a draft model
+//!
+//! A small draft model proposes several tokens autoregressively, then the target model
+//! scores all of them in one forward pass. Each draft token is accepted with probability
+//! `min(1, q(x) / p(x))`; on rejection a replacement is drawn from the residual
+//! distribution `max(0, q - p)`, which keeps the output distribution identical to
+//! sampling from the target alone. When the draft agrees with the target most of the
+//! time, each expensive target pass yields several tokens.
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::ops::AddAssign;
+
+use crate::error::{Error, Result};
+use super::local::{argmax, Sampler, TokenModel};
+use super::types::LlmOptions;
+
+/// Key in [`LlmOptions::extra`] holding the speculative decoding configuration
+pub const SPECULATIVE_OPTION_KEY: &str = "speculative";
+
+/// Speculative decoding settings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpeculativeConfig {
+    /// Whether the draft model is used
+    pub enabled: bool,
+    /// Number of tokens the draft model proposes per target pass
+    pub draft_tokens: usize,
+}
+
+impl Default for SpeculativeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            draft_tokens: 4,
+        }
+    }
+}
+
+impl SpeculativeConfig {
+    /// Enable speculative decoding with four draft tokens per step
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the number of draft tokens per step
+    pub fn with_draft_tokens(mut self, draft_tokens: usize) -> Self {
+        self.draft_tokens = draft_tokens.max(1);
+        self
+    }
+
+    /// Read the configuration from the request options
+    pub fn from_options(options: &LlmOptions) -> Option<Self> {
+        options
+            .extra
+            .get(SPECULATIVE_OPTION_KEY)
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+    }
+}
+
+impl LlmOptions {
+    /// Decode with the provider's draft model when one is configured
+    pub fn with_speculative_decoding(mut self, config: SpeculativeConfig) -> Self {
+        let value = serde_json::to_value(config).unwrap_or(serde_json::Value::Null);
+        self.extra.insert(SPECULATIVE_OPTION_KEY.to_string(), value);
+        self
+    }
+}
+
+/// Counters describing how well the draft model tracks the target
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpeculativeMetrics {
+    /// Tokens proposed by the draft model
+    pub drafted_tokens: u64,
+    /// Draft tokens accepted by the target model
+    pub accepted_tokens: u64,
+    /// Tokens emitted, including corrections and bonus tokens from the target
+    pub generated_tokens: u64,
+    /// Target model forward passes
+    pub target_calls: u64,
+    /// Draft model forward passes
+    pub draft_calls: u64,
+}
+
+impl SpeculativeMetrics {
+    /// Fraction of draft tokens accepted by the target
+    pub fn acceptance_rate(&self) -> f64 {
+        match self.drafted_tokens {
+            0 => 0.0,
+            drafted => self.accepted_tokens as f64 / drafted as f64,
+        }
+    }
+
+    /// Tokens emitted per target forward pass; plain decoding yields exactly one
+    pub fn tokens_per_target_call(&self) -> f64 {
+        match self.target_calls {
+            0 => 0.0,
+            calls => self.generated_tokens as f64 / calls as f64,
+        }
+    }
+}
+
+impl AddAssign for SpeculativeMetrics {
+    fn add_assign(&mut self, other: Self) {
+        self.drafted_tokens += other.drafted_tokens;
+        self.accepted_tokens += other.accepted_tokens;
+        self.generated_tokens += other.generated_tokens;
+        self.target_calls += other.target_calls;
+        self.draft_calls += other.draft_calls;
+    }
+}
+
+/// Speculative decoder pairing a target model with a draft model
+pub struct SpeculativeDecoder<'a> {
+    target: &'a dyn TokenModel,
+    draft: &'a dyn TokenModel,
+    config: SpeculativeConfig,
+}
+
+impl<'a> SpeculativeDecoder<'a> {
+    /// Create a decoder; both models must share the same vocabulary
+    pub fn new(target: &'a dyn TokenModel, draft: &'a dyn TokenModel, config: SpeculativeConfig) -> Self {
+        Self { target, draft, config }
+    }
+
+    /// Generate up to `max_tokens` tokens following `prompt`
+    pub fn generate(
+        &self,
+        prompt: &[u32],
+        max_tokens: usize,
+        sampler: &Sampler,
+        rng: &mut impl Rng,
+    ) -> Result<(Vec<u32>, SpeculativeMetrics)> {
+        let eos = self.target.eos_token();
+        let mut metrics = SpeculativeMetrics::default();
+        let mut tokens = prompt.to_vec();
+        let mut output = Vec::with_capacity(max_tokens);
+
+        'outer: while output.len() < max_tokens {
+            let lookahead = self.config.draft_tokens.max(1).min(max_tokens - output.len());
+
+            // Propose tokens with the draft model
+            let mut drafted = Vec::with_capacity(lookahead);
+            let mut draft_probs = Vec::with_capacity(lookahead);
+            let mut context = tokens.clone();
+            while drafted.len() < lookahead {
+                let logits = self.draft.logits(&context, 1)?.pop()
+                    .ok_or_else(|| Error::Internal(format!("Draft model '{}' returned no logits", self.draft.name())))?;
+                metrics.draft_calls += 1;
+                let probs = sampler.probabilities(&logits);
+                let token = sampler.sample(&probs, rng);
+                drafted.push(token);
+                draft_probs.push(probs);
+                context.push(token);
+                if Some(token) == eos {
+                    break;
+                }
+            }
+            metrics.drafted_tokens += drafted.len() as u64;
+
+            // Score every draft position plus one bonus position in a single target pass
+            let target_logits = self.target.logits(&context, drafted.len() + 1)?;
+            metrics.target_calls += 1;
+            if target_logits.len() != drafted.len() + 1 {
+                return Err(Error::Internal(format!(
+                    "Target model '{}' returned {} logit rows, expected {}",
+                    self.target.name(), target_logits.len(), drafted.len() + 1
+                )));
+            }
+
+            for (i, &token) in drafted.iter().enumerate() {
+                let target_probs = sampler.probabilities(&target_logits[i]);
+                let draft_probs = &draft_probs[i];
+                if target_probs.len() != draft_probs.len() {
+                    return Err(Error::Configuration(format!(
+                        "Draft model '{}' and target model '{}' have different vocabularies",
+                        self.draft.name(), self.target.name()
+                    )));
+                }
+
+                let accepted = if sampler.is_greedy() {
+                    argmax(&target_probs) == Some(token)
+                } else {
+                    let ratio = target_probs[token as usize] / draft_probs[token as usize];
+                    rng.gen::<f32>() < ratio.min(1.0)
+                };
+
+                let emitted = if accepted {
+                    metrics.accepted_tokens += 1;
+                    token
+                } else if sampler.is_greedy() {
+                    argmax(&target_probs).unwrap_or(token)
+                } else {
+                    let residual: Vec<f32> = target_probs.iter().zip(draft_probs)
+                        .map(|(q, p)| (q - p).max(0.0))
+                        .collect();
+                    // The residual is empty only when both distributions are identical
+                    if residual.iter().sum::<f32>() > 0.0 {
+                        sampler.sample(&residual, rng)
+                    } else {
+                        sampler.sample(&target_probs, rng)
+                    }
+                };
+
+                output.push(emitted);
+                tokens.push(emitted);
+                if Some(emitted) == eos || output.len() >= max_tokens {
+                    break 'outer;
+                }
+                if !accepted {
+                    continue 'outer;
+                }
+            }
+
+            // Every draft token was accepted, so the bonus position comes for free
+            let bonus = sampler.sample(&sampler.probabilities(&target_logits[drafted.len()]), rng);
+            output.push(bonus);
+            tokens.push(bonus);
+            if Some(bonus) == eos {
+                break;
+            }
+        }
+
+        metrics.generated_tokens = output.len() as u64;
+        Ok((output, metrics))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::local::decode_autoregressive;
+    use crate::llm::local::tests::AlphabetModel;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_matching_draft_is_fully_accepted() {
+        let target = AlphabetModel { step: 1 };
+        let draft = AlphabetModel { step: 1 };
+        let decoder = SpeculativeDecoder::new(&target, &draft, SpeculativeConfig::new());
+        let mut rng = StdRng::seed_from_u64(7);
+
+        let (output, metrics) = decoder.generate(&[0], 10, &Sampler::new(0.0), &mut rng).unwrap();
+        assert_eq!(output, vec![1, 2, 3, 4, 5, 6, 7, 0, 1, 2]);
+        assert_eq!(metrics.acceptance_rate(), 1.0);
+        assert_eq!(metrics.target_calls, 2);
+        assert!(metrics.tokens_per_target_call() > 4.0);
+    }
+
+    #[test]
+    fn test_rejected_draft_matches_target_output() {
+        let target = AlphabetModel { step: 1 };
+        let draft = AlphabetModel { step: 2 };
+        let decoder = SpeculativeDecoder::new(&target, &draft, SpeculativeConfig::new().with_draft_tokens(3));
+        let mut rng = StdRng::seed_from_u64(7);
+
+        let (output, metrics) = decoder.generate(&[0], 5, &Sampler::new(0.0), &mut rng).unwrap();
+        let expected = decode_autoregressive(&target, &[0], 5, &Sampler::new(0.0), &mut rng).unwrap();
+        assert_eq!(output, expected);
+        assert_eq!(metrics.acceptance_rate(), 0.0);
+        assert_eq!(metrics.target_calls, 5);
+    }
+
+    #[test]
+    fn test_sampled_decoding_and_options() {
+        let target = AlphabetModel { step: 1 };
+        let draft = AlphabetModel { step: 1 };
+        let decoder = SpeculativeDecoder::new(&target, &draft, SpeculativeConfig::new());
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let (output, metrics) = decoder.generate(&[3], 16, &Sampler::new(1.0), &mut rng).unwrap();
+        assert_eq!(output.len(), 16);
+        assert!(output.iter().all(|token| *token < 8));
+        assert_eq!(metrics.generated_tokens, 16);
+        // Identical distributions are always accepted
+        assert_eq!(metrics.acceptance_rate(), 1.0);
+
+        let options = LlmOptions::new().with_speculative_decoding(SpeculativeConfig::new().with_draft_tokens(6));
+        assert_eq!(SpeculativeConfig::from_options(&options).unwrap().draft_tokens, 6);
+        assert!(SpeculativeConfig::from_options(&LlmOptions::new()).is_none());
+    }
+}
@@ -16,6 +16,48 @@ mod tests {
         assert!(options.extra.is_empty());
     }
     
+    // 测试采样参数在各提供商间的映射
+    #[test]
+    fn test_sampling_params_mapping() {
+        use crate::llm::gemini::GenerationConfig;
+        use crate::llm::SamplingParam;
+
+        let options = LlmOptions::default()
+            .with_stop(vec!["END".to_string()])
+            .ban_tokens([50256])
+            .with_frequency_penalty(0.5)
+            .with_presence_penalty(0.2)
+            .with_seed(7);
+        assert_eq!(options.sampling_params(), SamplingParam::ALL);
+        assert_eq!(options.logit_bias.as_ref().unwrap()[&50256], -100.0);
+
+        // OpenAI兼容接口：不支持的参数被忽略
+        let mut body = serde_json::json!({});
+        options.apply_sampling_params(&mut body, "test", &[SamplingParam::Stop, SamplingParam::Seed]);
+        assert_eq!(body, serde_json::json!({ "stop": ["END"], "seed": 7 }));
+        assert_eq!(
+            options.warn_unsupported("test", &[SamplingParam::Stop, SamplingParam::Seed]),
+            [SamplingParam::LogitBias, SamplingParam::FrequencyPenalty, SamplingParam::PresencePenalty]
+        );
+
+        let mut body = serde_json::json!({});
+        options.apply_sampling_params(&mut body, "openai", &SamplingParam::ALL);
+        assert_eq!(body["logit_bias"], serde_json::json!({ "50256": -100.0 }));
+        assert_eq!(body["frequency_penalty"], 0.5);
+
+        // Gemini使用驼峰命名的原生参数
+        let config = serde_json::to_value(GenerationConfig::from_options(&options)).unwrap();
+        assert_eq!(config["stopSequences"], serde_json::json!(["END"]));
+        assert_eq!(config["presencePenalty"].as_f64().unwrap() as f32, 0.2);
+        assert_eq!(config["seed"], 7);
+
+        // 序列化往返保留采样参数
+        let restored: LlmOptions = serde_json::from_value(serde_json::to_value(&options).unwrap()).unwrap();
+        assert_eq!(restored.logit_bias, options.logit_bias);
+        assert_eq!(restored.seed, Some(7));
+        assert!(restored.extra.is_empty());
+    }
+    
     // 测试消息结构
     #[test]
     fn test_message_creation() {
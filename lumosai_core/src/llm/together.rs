@@ -6,8 +6,10 @@ use async_trait::async_trait;
 use futures::stream::BoxStream;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 use crate::error::{Error, Result};
+use crate::config::http_client_builder_for;
 use super::{
     LlmProvider, LlmOptions, Message, Role,
     function_calling::{FunctionDefinition, ToolChoice},
@@ -57,6 +59,14 @@ pub struct TogetherChatRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub stop: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub logit_bias: Option<HashMap<u32, f32>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequency_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub presence_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub stream: Option<bool>,
 }
 
@@ -75,6 +85,14 @@ pub struct TogetherCompletionRequest {
     pub top_k: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub stop: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logit_bias: Option<HashMap<u32, f32>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequency_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub presence_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
 }
 
 /// Together AI response
@@ -124,7 +142,7 @@ impl TogetherProvider {
             ..Default::default()
         };
         
-        let client = Client::builder()
+        let client = http_client_builder_for(&config.base_url)
             .default_headers({
                 let mut headers = reqwest::header::HeaderMap::new();
                 headers.insert(
@@ -191,6 +209,10 @@ impl LlmProvider for TogetherProvider {
             top_p: options.extra.get("top_p").and_then(|v| v.as_f64()).map(|f| f as f32),
             top_k: None,
             stop: options.stop.clone(),
+            logit_bias: options.logit_bias.clone(),
+            frequency_penalty: options.frequency_penalty,
+            presence_penalty: options.presence_penalty,
+            seed: options.seed,
         };
 
         let response = self.client
@@ -230,6 +252,10 @@ impl LlmProvider for TogetherProvider {
             top_p: options.extra.get("top_p").and_then(|v| v.as_f64()).map(|f| f as f32),
             top_k: None,
             stop: options.stop.clone(),
+            logit_bias: options.logit_bias.clone(),
+            frequency_penalty: options.frequency_penalty,
+            presence_penalty: options.presence_penalty,
+            seed: options.seed,
             stream: Some(false),
         };
 
@@ -303,6 +329,10 @@ impl LlmProvider for TogetherProvider {
         false // Together AI doesn't support OpenAI-style function calling yet
     }
 
+    fn supports_seed(&self) -> bool {
+        true
+    }
+
     async fn generate_with_functions(
         &self,
         messages: &[Message],
@@ -0,0 +1,327 @@
+//! Token counting per model family.
+//!
+//! [`Tokenizer`] is the single abstraction used for context-window management,
+//! usage accounting and token-based chunking. Backends:
+//!
+//! - [`TiktokenTokenizer`] (feature `tiktoken`): exact BPE counts for OpenAI models
+//!   (`o200k_base` for GPT-4o/o-series, `cl100k_base` for GPT-4/3.5 and embeddings).
+//! - [`HfTokenizer`] (feature `hf-tokenizers`): Hugging Face `tokenizer.json` files for
+//!   open models such as Llama, Qwen or Mistral.
+//! - [`HeuristicTokenizer`]: a dependency-free estimate used when no exact tokenizer
+//!   is available for a model.
+//!
+//! [`tokenizer_for_model`] picks the best tokenizer for a model name; tokenizers for
+//! open models are registered at runtime with [`register_tokenizer`].
+
+use std::sync::{Arc, OnceLock, RwLock};
+
+use crate::llm::Message;
+
+/// Counts and splits text in model tokens.
+pub trait Tokenizer: Send + Sync {
+    /// Name of the tokenizer or encoding, e.g. `cl100k_base`.
+    fn name(&self) -> &str;
+
+    /// Number of tokens in `text`.
+    fn count_tokens(&self, text: &str) -> usize;
+
+    /// Number of tokens a chat message occupies, including role and formatting overhead.
+    fn count_message(&self, message: &Message) -> usize {
+        // Chat formats add roughly 4 tokens per message for the role and separators
+        4 + self.count_tokens(&message.content)
+            + message.name.as_deref().map(|name| self.count_tokens(name)).unwrap_or(0)
+    }
+
+    /// Number of tokens a list of chat messages occupies.
+    fn count_messages(&self, messages: &[Message]) -> usize {
+        // Every reply is primed with 3 tokens
+        3 + messages.iter().map(|message| self.count_message(message)).sum::<usize>()
+    }
+
+    /// Splits `text` into chunks of at most `chunk_size` tokens, with `overlap` tokens
+    /// repeated between consecutive chunks.
+    ///
+    /// The default implementation packs whitespace-delimited words greedily; exact
+    /// tokenizers override it to split on token boundaries.
+    fn split(&self, text: &str, chunk_size: usize, overlap: usize) -> Vec<String> {
+        let chunk_size = chunk_size.max(1);
+        let overlap = overlap.min(chunk_size - 1);
+        let words: Vec<(&str, usize)> = text
+            .split_inclusive(char::is_whitespace)
+            .map(|word| (word, self.count_tokens(word).max(1)))
+            .collect();
+
+        let mut chunks = Vec::new();
+        let mut start = 0;
+        while start < words.len() {
+            let mut end = start;
+            let mut tokens = 0;
+            while end < words.len() && (end == start || tokens + words[end].1 <= chunk_size) {
+                tokens += words[end].1;
+                end += 1;
+            }
+            let chunk: String = words[start..end].iter().map(|(word, _)| *word).collect();
+            chunks.push(chunk.trim().to_string());
+            if end == words.len() {
+                break;
+            }
+
+            // Step back over words that fit in the overlap, but always make progress
+            let mut next = end;
+            let mut carried = 0;
+            while next > start + 1 && carried + words[next - 1].1 <= overlap {
+                carried += words[next - 1].1;
+                next -= 1;
+            }
+            start = next;
+        }
+        chunks.retain(|chunk| !chunk.is_empty());
+        chunks
+    }
+}
+
+/// Estimates tokens from characters: one token per CJK character and one per four
+/// other characters.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HeuristicTokenizer;
+
+impl Tokenizer for HeuristicTokenizer {
+    fn name(&self) -> &str {
+        "heuristic"
+    }
+
+    fn count_tokens(&self, text: &str) -> usize {
+        let (cjk, other) = text.chars().fold((0usize, 0usize), |(cjk, other), c| {
+            if is_cjk(c) { (cjk + 1, other) } else { (cjk, other + 1) }
+        });
+        cjk + other.div_ceil(4)
+    }
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x4E00..=0x9FFF | 0x3400..=0x4DBF | 0x3040..=0x30FF | 0xAC00..=0xD7AF | 0xF900..=0xFAFF)
+}
+
+/// Splits a token sequence into overlapping windows and decodes each one.
+#[cfg(any(feature = "tiktoken", feature = "hf-tokenizers"))]
+fn split_tokens<F>(tokens: &[u32], chunk_size: usize, overlap: usize, decode: F) -> Vec<String>
+where
+    F: Fn(&[u32]) -> Option<String>,
+{
+    let chunk_size = chunk_size.max(1);
+    let step = chunk_size - overlap.min(chunk_size - 1);
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < tokens.len() {
+        let end = (start + chunk_size).min(tokens.len());
+        if let Some(chunk) = decode(&tokens[start..end]) {
+            chunks.push(chunk);
+        }
+        if end == tokens.len() {
+            break;
+        }
+        start += step;
+    }
+    chunks
+}
+
+/// Exact tokenizer for OpenAI models backed by `tiktoken-rs`.
+#[cfg(feature = "tiktoken")]
+pub struct TiktokenTokenizer {
+    name: &'static str,
+    bpe: &'static tiktoken_rs::CoreBPE,
+}
+
+#[cfg(feature = "tiktoken")]
+impl TiktokenTokenizer {
+    /// `o200k_base`, used by GPT-4o and the o-series.
+    pub fn o200k_base() -> Self {
+        Self { name: "o200k_base", bpe: tiktoken_rs::o200k_base_singleton() }
+    }
+
+    /// `cl100k_base`, used by GPT-4, GPT-3.5 and the `text-embedding-3` models.
+    pub fn cl100k_base() -> Self {
+        Self { name: "cl100k_base", bpe: tiktoken_rs::cl100k_base_singleton() }
+    }
+
+    /// Tokenizer for an OpenAI model or encoding name, or `None` if neither is known.
+    pub fn for_model(model: &str) -> Option<Self> {
+        use tiktoken_rs::tokenizer::{get_tokenizer, Tokenizer as Encoding};
+        let model = model.rsplit('/').next().unwrap_or(model);
+        let encoding = match model {
+            "o200k_base" => Encoding::O200kBase,
+            "cl100k_base" => Encoding::Cl100kBase,
+            "p50k_base" => Encoding::P50kBase,
+            "r50k_base" | "gpt2" => Encoding::R50kBase,
+            _ => get_tokenizer(model)?,
+        };
+        match encoding {
+            Encoding::O200kBase => Some(Self::o200k_base()),
+            Encoding::Cl100kBase => Some(Self::cl100k_base()),
+            Encoding::P50kBase | Encoding::P50kEdit => {
+                Some(Self { name: "p50k_base", bpe: tiktoken_rs::p50k_base_singleton() })
+            }
+            Encoding::R50kBase | Encoding::Gpt2 => {
+                Some(Self { name: "r50k_base", bpe: tiktoken_rs::r50k_base_singleton() })
+            }
+        }
+    }
+}
+
+#[cfg(feature = "tiktoken")]
+impl Tokenizer for TiktokenTokenizer {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn count_tokens(&self, text: &str) -> usize {
+        self.bpe.encode_with_special_tokens(text).len()
+    }
+
+    fn split(&self, text: &str, chunk_size: usize, overlap: usize) -> Vec<String> {
+        let tokens = self.bpe.encode_with_special_tokens(text);
+        split_tokens(&tokens, chunk_size, overlap, |window| {
+            // Windows may cut a multi-byte character in half, so decode lossily
+            let bytes: Vec<u8> = self.bpe._decode_native_and_split(window.to_vec()).flatten().collect();
+            Some(String::from_utf8_lossy(&bytes).into_owned())
+        })
+    }
+}
+
+/// Tokenizer loaded from a Hugging Face `tokenizer.json`.
+#[cfg(feature = "hf-tokenizers")]
+pub struct HfTokenizer {
+    name: String,
+    inner: tokenizers::Tokenizer,
+}
+
+#[cfg(feature = "hf-tokenizers")]
+impl HfTokenizer {
+    /// Loads a tokenizer from a `tokenizer.json` file.
+    pub fn from_file(name: impl Into<String>, path: impl AsRef<std::path::Path>) -> crate::Result<Self> {
+        let inner = tokenizers::Tokenizer::from_file(path)
+            .map_err(|e| crate::Error::Configuration(format!("Failed to load tokenizer: {}", e)))?;
+        Ok(Self { name: name.into(), inner })
+    }
+
+    /// Loads a tokenizer from the contents of a `tokenizer.json` file.
+    pub fn from_bytes(name: impl Into<String>, bytes: impl AsRef<[u8]>) -> crate::Result<Self> {
+        let inner = tokenizers::Tokenizer::from_bytes(bytes)
+            .map_err(|e| crate::Error::Configuration(format!("Failed to load tokenizer: {}", e)))?;
+        Ok(Self { name: name.into(), inner })
+    }
+
+    fn encode(&self, text: &str) -> Vec<u32> {
+        self.inner
+            .encode(text, false)
+            .map(|encoding| encoding.get_ids().to_vec())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(feature = "hf-tokenizers")]
+impl Tokenizer for HfTokenizer {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn count_tokens(&self, text: &str) -> usize {
+        self.encode(text).len()
+    }
+
+    fn split(&self, text: &str, chunk_size: usize, overlap: usize) -> Vec<String> {
+        let tokens = self.encode(text);
+        split_tokens(&tokens, chunk_size, overlap, |window| self.inner.decode(window, true).ok())
+    }
+}
+
+type Registry = RwLock<Vec<(String, Arc<dyn Tokenizer>)>>;
+
+fn registry() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(Vec::new()))
+}
+
+/// Registers a tokenizer for all models whose name starts with `model_prefix`.
+///
+/// Registered tokenizers take precedence over the built-in ones; the longest
+/// matching prefix wins.
+pub fn register_tokenizer(model_prefix: impl Into<String>, tokenizer: Arc<dyn Tokenizer>) {
+    let model_prefix = model_prefix.into().to_ascii_lowercase();
+    let mut registry = registry().write().unwrap();
+    registry.retain(|(prefix, _)| *prefix != model_prefix);
+    registry.push((model_prefix, tokenizer));
+}
+
+/// Returns the most accurate tokenizer available for a model.
+///
+/// Encoding names such as `cl100k_base` are accepted as well.
+pub fn tokenizer_for_model(model: &str) -> Arc<dyn Tokenizer> {
+    let lower = model.to_ascii_lowercase();
+    let registered = registry().read().unwrap().iter()
+        .filter(|(prefix, _)| lower.starts_with(prefix.as_str()))
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map(|(_, tokenizer)| tokenizer.clone());
+    if let Some(tokenizer) = registered {
+        return tokenizer;
+    }
+
+    #[cfg(feature = "tiktoken")]
+    if let Some(tokenizer) = TiktokenTokenizer::for_model(&lower) {
+        return Arc::new(tokenizer);
+    }
+
+    Arc::new(HeuristicTokenizer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heuristic_counts_and_split() {
+        assert_eq!(HeuristicTokenizer.count_tokens("abcdefgh"), 2);
+        assert_eq!(HeuristicTokenizer.count_tokens("你好世界"), 4);
+
+        let text = (0..20).map(|i| format!("word{}", i)).collect::<Vec<_>>().join(" ");
+        let chunks = HeuristicTokenizer.split(&text, 6, 2);
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|chunk| HeuristicTokenizer.count_tokens(chunk) <= 6));
+        assert!(chunks[0].starts_with("word0"));
+        assert!(chunks.last().unwrap().ends_with("word19"));
+        // Consecutive chunks share the overlapping word
+        let last_of_first = chunks[0].split_whitespace().last().unwrap();
+        assert!(chunks[1].starts_with(last_of_first));
+    }
+
+    #[test]
+    fn test_registered_tokenizer_takes_precedence() {
+        struct Fixed;
+        impl Tokenizer for Fixed {
+            fn name(&self) -> &str {
+                "fixed"
+            }
+            fn count_tokens(&self, _text: &str) -> usize {
+                7
+            }
+        }
+
+        register_tokenizer("test-llama", Arc::new(Fixed));
+        assert_eq!(tokenizer_for_model("Test-Llama-3-8B").name(), "fixed");
+        assert_eq!(tokenizer_for_model("test-llama-3").count_tokens("anything"), 7);
+        assert_eq!(tokenizer_for_model("some-unknown-model").name(), "heuristic");
+    }
+
+    #[cfg(feature = "tiktoken")]
+    #[test]
+    fn test_tiktoken_for_openai_models() {
+        let tokenizer = tokenizer_for_model("gpt-4o-mini");
+        assert_eq!(tokenizer.name(), "o200k_base");
+        assert_eq!(tokenizer.count_tokens("hello world"), 2);
+        assert_eq!(tokenizer_for_model("gpt-4").name(), "cl100k_base");
+
+        let chunks = tokenizer.split(&"hello world ".repeat(50), 10, 2);
+        assert!(chunks.iter().all(|chunk| tokenizer.count_tokens(chunk) <= 10));
+    }
+}
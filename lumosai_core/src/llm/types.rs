@@ -2,6 +2,9 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt;
 
+use crate::attachment::{Attachment, ATTACHMENTS_METADATA_KEY};
+use crate::cancellation::CancellationToken;
+
 /// Role enum representing the role of a message sender
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Role {
@@ -125,6 +128,20 @@ impl Message {
         metadata.insert(key.to_string(), value);
         self
     }
+    
+    /// Attach uploaded files to the message, stored under the `attachments` metadata key
+    pub fn with_attachments(self, attachments: Vec<Attachment>) -> Self {
+        let value = serde_json::to_value(attachments).unwrap_or_default();
+        self.with_metadata(ATTACHMENTS_METADATA_KEY, value)
+    }
+    
+    /// Files attached to the message
+    pub fn attachments(&self) -> Vec<Attachment> {
+        self.metadata.as_ref()
+            .and_then(|metadata| metadata.get(ATTACHMENTS_METADATA_KEY))
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+            .unwrap_or_default()
+    }
 }
 
 /// Options for LLM text generation
@@ -140,9 +157,24 @@ pub struct LlmOptions {
     pub stop: Option<Vec<String>>,
     /// Model name
     pub model: Option<String>,
+    /// Bias added to the logits of token ids (-100 bans a token, 100 forces it)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logit_bias: Option<HashMap<u32, f32>>,
+    /// Penalty on tokens proportional to how often they already appeared
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frequency_penalty: Option<f32>,
+    /// Penalty on tokens that already appeared at all
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub presence_penalty: Option<f32>,
+    /// Sampling seed for reproducible generations
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
     /// Additional model-specific parameters
     #[serde(flatten)]
     pub extra: serde_json::Map<String, serde_json::Value>,
+    /// Token that aborts the request and any stream it produced when cancelled
+    #[serde(skip)]
+    pub cancellation_token: Option<CancellationToken>,
 }
 
 impl Default for LlmOptions {
@@ -153,7 +185,12 @@ impl Default for LlmOptions {
             stream: false,
             stop: None,
             model: None,
+            logit_bias: None,
+            frequency_penalty: None,
+            presence_penalty: None,
+            seed: None,
             extra: serde_json::Map::new(),
+            cancellation_token: None,
         }
     }
 }
@@ -194,11 +231,128 @@ impl LlmOptions {
         self
     }
     
+    /// Set logit bias per token id
+    pub fn with_logit_bias(mut self, logit_bias: HashMap<u32, f32>) -> Self {
+        self.logit_bias = Some(logit_bias);
+        self
+    }
+    
+    /// Ban token ids by biasing them to -100
+    pub fn ban_tokens(mut self, tokens: impl IntoIterator<Item = u32>) -> Self {
+        self.logit_bias.get_or_insert_with(HashMap::new).extend(tokens.into_iter().map(|token| (token, -100.0)));
+        self
+    }
+    
+    /// Set frequency penalty
+    pub fn with_frequency_penalty(mut self, penalty: f32) -> Self {
+        self.frequency_penalty = Some(penalty);
+        self
+    }
+    
+    /// Set presence penalty
+    pub fn with_presence_penalty(mut self, penalty: f32) -> Self {
+        self.presence_penalty = Some(penalty);
+        self
+    }
+    
+    /// Set sampling seed
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+    
+    /// Set the token that cancels the request
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation_token = Some(token);
+        self
+    }
+    
     /// Add extra options
     pub fn with_extra(mut self, key: impl Into<String>, value: impl Into<serde_json::Value>) -> Self {
         self.extra.insert(key.into(), value.into());
         self
     }
+    
+    /// Sampling controls set on these options
+    pub fn sampling_params(&self) -> Vec<SamplingParam> {
+        SamplingParam::ALL.into_iter()
+            .filter(|param| match param {
+                SamplingParam::Stop => self.stop.as_ref().is_some_and(|stop| !stop.is_empty()),
+                SamplingParam::LogitBias => self.logit_bias.as_ref().is_some_and(|bias| !bias.is_empty()),
+                SamplingParam::FrequencyPenalty => self.frequency_penalty.is_some(),
+                SamplingParam::PresencePenalty => self.presence_penalty.is_some(),
+                SamplingParam::Seed => self.seed.is_some(),
+            })
+            .collect()
+    }
+    
+    /// Warn about each set sampling control that `provider` does not support
+    ///
+    /// Returns the ignored controls.
+    pub fn warn_unsupported(&self, provider: &str, supported: &[SamplingParam]) -> Vec<SamplingParam> {
+        let ignored: Vec<SamplingParam> = self.sampling_params().into_iter()
+            .filter(|param| !supported.contains(param))
+            .collect();
+        for param in &ignored {
+            tracing::warn!(provider, option = param.as_str(), "LLM option is not supported by the provider and is ignored");
+        }
+        ignored
+    }
+    
+    /// Write the supported sampling controls into an OpenAI-compatible request body
+    /// and warn about the rest
+    pub fn apply_sampling_params(&self, body: &mut serde_json::Value, provider: &str, supported: &[SamplingParam]) {
+        self.warn_unsupported(provider, supported);
+        for param in self.sampling_params() {
+            if !supported.contains(&param) {
+                continue;
+            }
+            body[param.as_str()] = match param {
+                SamplingParam::Stop => serde_json::json!(self.stop),
+                SamplingParam::LogitBias => serde_json::json!(self.logit_bias),
+                SamplingParam::FrequencyPenalty => serde_json::json!(self.frequency_penalty),
+                SamplingParam::PresencePenalty => serde_json::json!(self.presence_penalty),
+                SamplingParam::Seed => serde_json::json!(self.seed),
+            };
+        }
+    }
+}
+
+/// Sampling controls of [`LlmOptions`] that not every provider supports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SamplingParam {
+    /// Stop sequences
+    Stop,
+    /// Logit bias and banned tokens
+    LogitBias,
+    /// Frequency penalty
+    FrequencyPenalty,
+    /// Presence penalty
+    PresencePenalty,
+    /// Sampling seed
+    Seed,
+}
+
+impl SamplingParam {
+    /// Every sampling control
+    pub const ALL: [SamplingParam; 5] = [
+        SamplingParam::Stop,
+        SamplingParam::LogitBias,
+        SamplingParam::FrequencyPenalty,
+        SamplingParam::PresencePenalty,
+        SamplingParam::Seed,
+    ];
+    
+    /// The OpenAI request field name of the control
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SamplingParam::Stop => "stop",
+            SamplingParam::LogitBias => "logit_bias",
+            SamplingParam::FrequencyPenalty => "frequency_penalty",
+            SamplingParam::PresencePenalty => "presence_penalty",
+            SamplingParam::Seed => "seed",
+        }
+    }
 }
 
 /// Create system message
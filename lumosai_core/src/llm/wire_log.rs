@@ -0,0 +1,436 @@
+//! Request/response logging for LLM providers
+//!
+//! [`WireLoggingProvider`] wraps any [`LlmProvider`] and records each call (the messages
+//! and options sent, the response or error received, and the latency) as an entry of the
+//! [`logging`](crate::logging) module, so production issues can be debugged from what the
+//! provider actually saw. Before anything is stored, API keys, bearer tokens and fields
+//! such as `api_key` are redacted, the configured [`Redactor`] removes user PII, and
+//! bodies are capped at [`WireLogConfig::max_body_bytes`]. Only a sampled fraction of
+//! successful calls is logged; failed calls are always logged unless disabled.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream, StreamExt};
+use rand::Rng;
+use regex::Regex;
+use serde_json::{json, Value};
+
+use crate::agent::Redactor;
+use crate::logging::{LogEntry, LogLevel, LogSink, Logger};
+use crate::Result;
+use super::batch::{BatchRequest, BatchResponse};
+use super::function_calling::{FunctionDefinition, ToolChoice};
+use super::prompt_cache::CacheUsage;
+use super::provider::{FunctionCallingResponse, LlmProvider};
+use super::types::{LlmOptions, Message};
+
+/// Module name of the log entries
+pub const WIRE_LOG_MODULE: &str = "llm.wire";
+
+/// Default cap on each logged body
+pub const DEFAULT_MAX_BODY_BYTES: usize = 16 * 1024;
+
+/// Replacement for redacted secrets
+const REDACTED: &str = "[REDACTED]";
+
+/// Field names whose values are always redacted
+const SECRET_FIELDS: &[&str] = &[
+    "api_key", "apikey", "api-key", "authorization", "password", "secret", "token", "access_token",
+    "refresh_token", "client_secret", "x-api-key",
+];
+
+/// Patterns of API keys and tokens found in free text
+const SECRET_PATTERNS: &[&str] = &[
+    r"\bsk-[A-Za-z0-9_\-]{16,}",
+    r"(?i)\bbearer\s+[A-Za-z0-9._~+/=\-]{8,}",
+    r"\bAKIA[0-9A-Z]{16}\b",
+    r"\bAIza[0-9A-Za-z_\-]{35}\b",
+    r"\b(?:ghp|gho|ghs|xox[abprs])[-_][A-Za-z0-9\-]{16,}",
+];
+
+/// What gets logged and how it is redacted
+#[derive(Debug, Clone)]
+pub struct WireLogConfig {
+    /// Fraction of successful calls to log, from 0.0 to 1.0
+    pub sample_rate: f64,
+    /// Always log failed calls, regardless of `sample_rate`
+    pub log_errors: bool,
+    /// Maximum size of each logged request or response body in bytes
+    pub max_body_bytes: usize,
+    /// Redact API keys, bearer tokens and secret fields
+    pub redact_secrets: bool,
+    /// Redaction applied to message content and responses, e.g. for user PII
+    pub redactor: Redactor,
+}
+
+impl Default for WireLogConfig {
+    fn default() -> Self {
+        Self {
+            sample_rate: 1.0,
+            log_errors: true,
+            max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+            redact_secrets: true,
+            redactor: Redactor::with_defaults(),
+        }
+    }
+}
+
+impl WireLogConfig {
+    /// Log the given fraction of successful calls
+    pub fn with_sample_rate(mut self, sample_rate: f64) -> Self {
+        self.sample_rate = sample_rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Cap each logged body at `max_body_bytes`
+    pub fn with_max_body_bytes(mut self, max_body_bytes: usize) -> Self {
+        self.max_body_bytes = max_body_bytes;
+        self
+    }
+
+    /// Replace the PII redactor
+    pub fn with_redactor(mut self, redactor: Redactor) -> Self {
+        self.redactor = redactor;
+        self
+    }
+
+    /// Also replace every match of `pattern` with `replacement`
+    pub fn with_redaction(mut self, pattern: &str, replacement: impl Into<String>) -> Result<Self> {
+        self.redactor = self.redactor.pattern(pattern, replacement)?;
+        Ok(self)
+    }
+
+    /// Log failed calls only when sampled
+    pub fn sample_errors(mut self) -> Self {
+        self.log_errors = false;
+        self
+    }
+}
+
+/// Provider wrapper logging requests and responses of the inner provider
+pub struct WireLoggingProvider {
+    inner: Arc<dyn LlmProvider>,
+    config: WireLogConfig,
+    logger: Logger,
+    secrets: Vec<Regex>,
+}
+
+impl WireLoggingProvider {
+    /// Log calls to `inner` through a logger for [`WIRE_LOG_MODULE`]
+    pub fn new(inner: Arc<dyn LlmProvider>) -> Self {
+        let secrets = SECRET_PATTERNS.iter()
+            .map(|pattern| Regex::new(pattern).expect("secret patterns are valid"))
+            .collect();
+        Self {
+            inner,
+            config: WireLogConfig::default(),
+            logger: Logger::new(WIRE_LOG_MODULE),
+            secrets,
+        }
+    }
+
+    /// Use `config` for sampling, redaction and size caps
+    pub fn with_config(mut self, config: WireLogConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Log through `logger`
+    pub fn with_logger(mut self, logger: Logger) -> Self {
+        self.logger = logger;
+        self
+    }
+
+    /// Store entries in `sink`
+    pub fn with_sink(self, sink: Arc<dyn LogSink>) -> Self {
+        let logger = Logger::new(WIRE_LOG_MODULE).with_sink(sink);
+        self.with_logger(logger)
+    }
+
+    /// The wrapped provider
+    pub fn inner(&self) -> &Arc<dyn LlmProvider> {
+        &self.inner
+    }
+
+    fn sampled(&self) -> bool {
+        self.config.sample_rate >= 1.0
+            || (self.config.sample_rate > 0.0 && rand::thread_rng().gen_bool(self.config.sample_rate))
+    }
+
+    /// Redact secrets and PII in text
+    fn redact_text(&self, text: &str) -> String {
+        let text = if self.config.redact_secrets {
+            self.secrets.iter().fold(text.to_string(), |text, regex| regex.replace_all(&text, REDACTED).into_owned())
+        } else {
+            text.to_string()
+        };
+        self.config.redactor.redact(&text)
+    }
+
+    /// Redact every string in a JSON value, and secret fields entirely
+    fn redact_value(&self, value: Value) -> Value {
+        match value {
+            Value::String(text) => Value::String(self.redact_text(&text)),
+            Value::Array(items) => Value::Array(items.into_iter().map(|item| self.redact_value(item)).collect()),
+            Value::Object(fields) => Value::Object(fields.into_iter()
+                .map(|(name, value)| {
+                    let secret = self.config.redact_secrets
+                        && SECRET_FIELDS.contains(&name.to_ascii_lowercase().as_str())
+                        && !value.is_null();
+                    let value = if secret { Value::String(REDACTED.to_string()) } else { self.redact_value(value) };
+                    (name, value)
+                })
+                .collect()),
+            other => other,
+        }
+    }
+
+    /// Redacted body, replaced by a truncated string when larger than the cap
+    fn body(&self, value: Value) -> Value {
+        let value = self.redact_value(value);
+        let text = match &value {
+            Value::String(text) => text.clone(),
+            other => other.to_string(),
+        };
+        if text.len() <= self.config.max_body_bytes {
+            return value;
+        }
+        let mut end = self.config.max_body_bytes;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        Value::String(format!("{}...[truncated {} bytes]", &text[..end], text.len() - end))
+    }
+
+    /// Record one call if it was sampled or failed
+    fn record(&self, call: Call, sampled: bool, outcome: std::result::Result<Value, String>) {
+        if !(sampled || outcome.is_err() && self.config.log_errors) {
+            return;
+        }
+
+        let mut fields = HashMap::new();
+        fields.insert("provider".to_string(), json!(self.inner.name()));
+        fields.insert("operation".to_string(), json!(call.operation));
+        fields.insert("duration_ms".to_string(), json!(call.started.elapsed().as_millis() as u64));
+        fields.insert("request".to_string(), self.body(call.request));
+        let (level, message) = match outcome {
+            Ok(response) => {
+                fields.insert("response".to_string(), self.body(response));
+                (LogLevel::Info, format!("{} {} succeeded", self.inner.name(), call.operation))
+            },
+            Err(error) => {
+                fields.insert("error".to_string(), json!(self.redact_text(&error)));
+                (LogLevel::Error, format!("{} {} failed", self.inner.name(), call.operation))
+            },
+        };
+
+        let entry = LogEntry::new(level, message, WIRE_LOG_MODULE.to_string())
+            .with_fields(fields)
+            .with_tags(["llm", "wire"]);
+        self.logger.log_entry(entry);
+    }
+
+    fn outcome<T>(result: &Result<T>, response: impl FnOnce(&T) -> Value) -> std::result::Result<Value, String> {
+        result.as_ref().map(response).map_err(|e| e.to_string())
+    }
+}
+
+/// A call being logged
+struct Call {
+    operation: &'static str,
+    request: Value,
+    started: Instant,
+}
+
+impl Call {
+    fn new(operation: &'static str, request: Value) -> Self {
+        Self { operation, request, started: Instant::now() }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for WireLoggingProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn generate(&self, prompt: &str, options: &LlmOptions) -> Result<String> {
+        let sampled = self.sampled();
+        let call = Call::new("generate", json!({ "prompt": prompt, "options": options }));
+        let result = self.inner.generate(prompt, options).await;
+        self.record(call, sampled, Self::outcome(&result, |text| json!(text)));
+        result
+    }
+
+    async fn generate_with_messages(&self, messages: &[Message], options: &LlmOptions) -> Result<String> {
+        let sampled = self.sampled();
+        let call = Call::new("generate_with_messages", json!({ "messages": messages, "options": options }));
+        let result = self.inner.generate_with_messages(messages, options).await;
+        self.record(call, sampled, Self::outcome(&result, |text| json!(text)));
+        result
+    }
+
+    async fn generate_stream<'a>(
+        &'a self,
+        prompt: &'a str,
+        options: &'a LlmOptions,
+    ) -> Result<BoxStream<'a, Result<String>>> {
+        let sampled = self.sampled();
+        let call = Call::new("generate_stream", json!({ "prompt": prompt, "options": options }));
+        let inner = match self.inner.generate_stream(prompt, options).await {
+            Ok(inner) => inner,
+            Err(e) => {
+                self.record(call, sampled, Err(e.to_string()));
+                return Err(e);
+            },
+        };
+
+        // Collect the chunks and log once the stream ends
+        let state = Arc::new(std::sync::Mutex::new((String::new(), None::<String>)));
+        let collected = state.clone();
+        let chunks = inner.map(move |chunk| {
+            let mut state = collected.lock().unwrap_or_else(|e| e.into_inner());
+            match &chunk {
+                Ok(text) => state.0.push_str(text),
+                Err(e) => state.1 = Some(e.to_string()),
+            }
+            chunk
+        });
+        let finish = stream::once(async move {
+            let (text, error) = std::mem::take(&mut *state.lock().unwrap_or_else(|e| e.into_inner()));
+            self.record(call, sampled, error.map_or(Ok(json!(text)), Err));
+        })
+        .filter_map(|()| async { None });
+        Ok(chunks.chain(finish).boxed())
+    }
+
+    async fn get_embedding(&self, text: &str) -> Result<Vec<f32>> {
+        let sampled = self.sampled();
+        let call = Call::new("get_embedding", json!({ "text": text }));
+        let result = self.inner.get_embedding(text).await;
+        self.record(call, sampled, Self::outcome(&result, |embedding| json!({ "dimensions": embedding.len() })));
+        result
+    }
+
+    fn supports_function_calling(&self) -> bool {
+        self.inner.supports_function_calling()
+    }
+
+    fn supports_seed(&self) -> bool {
+        self.inner.supports_seed()
+    }
+
+    async fn generate_batch(&self, requests: Vec<BatchRequest>) -> Result<Vec<BatchResponse>> {
+        let sampled = self.sampled();
+        let call = Call::new("generate_batch", json!({ "requests": requests }));
+        let result = self.inner.generate_batch(requests).await;
+        self.record(call, sampled, Self::outcome(&result, |responses| json!(responses)));
+        result
+    }
+
+    fn prompt_cache_usage(&self) -> Option<CacheUsage> {
+        self.inner.prompt_cache_usage()
+    }
+
+    async fn generate_with_functions(
+        &self,
+        messages: &[Message],
+        functions: &[FunctionDefinition],
+        tool_choice: &ToolChoice,
+        options: &LlmOptions,
+    ) -> Result<FunctionCallingResponse> {
+        let sampled = self.sampled();
+        let call = Call::new("generate_with_functions", json!({
+            "messages": messages,
+            "functions": functions.iter().map(|function| &function.name).collect::<Vec<_>>(),
+            "tool_choice": tool_choice,
+            "options": options,
+        }));
+        let result = self.inner.generate_with_functions(messages, functions, tool_choice, options).await;
+        self.record(call, sampled, Self::outcome(&result, |response| json!({
+            "content": response.content,
+            "function_calls": response.function_calls,
+            "finish_reason": response.finish_reason,
+        })));
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use crate::llm::MockLlmProvider;
+    use crate::logging::MemoryLogSink;
+
+    fn logged(sink: &MemoryLogSink) -> Vec<LogEntry> {
+        sink.entries().into_iter().filter(|entry| entry.module == WIRE_LOG_MODULE).collect()
+    }
+
+    #[tokio::test]
+    async fn test_logs_redacted_calls() {
+        let sink = Arc::new(MemoryLogSink::new(10));
+        let inner = Arc::new(MockLlmProvider::new(vec!["Mail me at jane@example.com".to_string()]));
+        let provider = WireLoggingProvider::new(inner).with_sink(sink.clone());
+
+        let mut options = LlmOptions::default();
+        options.extra.insert("api_key".to_string(), json!("secret-value"));
+        let messages = vec![Message::new(crate::llm::Role::User, "my key is sk-abcdefghijklmnopqrstuv".to_string(), None, None)];
+        provider.generate_with_messages(&messages, &options).await.unwrap();
+
+        let entries = logged(&sink);
+        assert_eq!(entries.len(), 1);
+        let fields = &entries[0].fields;
+        assert_eq!(fields["provider"], "mock");
+        assert_eq!(fields["operation"], "generate_with_messages");
+        assert_eq!(fields["response"], "Mail me at [REDACTED_EMAIL]");
+        assert_eq!(fields["request"]["options"]["extra"]["api_key"], REDACTED);
+        let request = fields["request"].to_string();
+        assert!(request.contains("my key is [REDACTED]"));
+        assert!(!request.contains("sk-abc"));
+    }
+
+    #[tokio::test]
+    async fn test_sampling_and_errors() {
+        let sink = Arc::new(MemoryLogSink::new(10));
+        let inner = Arc::new(MockLlmProvider::new_with_embeddings(Vec::new()));
+        let provider = WireLoggingProvider::new(inner)
+            .with_config(WireLogConfig::default().with_sample_rate(0.0))
+            .with_sink(sink.clone());
+
+        provider.generate("hello", &LlmOptions::default()).await.unwrap();
+        assert!(logged(&sink).is_empty());
+
+        // The mock has no embeddings, so this call fails and is logged anyway
+        assert!(matches!(provider.get_embedding("hello").await, Err(Error::Unavailable(_))));
+        let entries = logged(&sink);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].level, LogLevel::Error);
+        assert!(entries[0].fields.contains_key("error"));
+    }
+
+    #[tokio::test]
+    async fn test_body_cap_and_stream() {
+        let sink = Arc::new(MemoryLogSink::new(10));
+        let inner = Arc::new(MockLlmProvider::new(vec!["streamed response".to_string()]));
+        let provider = WireLoggingProvider::new(inner)
+            .with_config(WireLogConfig::default().with_max_body_bytes(8))
+            .with_sink(sink.clone());
+
+        let chunks: Vec<String> = provider.generate_stream("ünïcödé prompt", &LlmOptions::default()).await.unwrap()
+            .map(|chunk| chunk.unwrap())
+            .collect()
+            .await;
+        assert_eq!(chunks.concat(), "streamed response");
+
+        let entries = logged(&sink);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].fields["operation"], "generate_stream");
+        let response = entries[0].fields["response"].as_str().unwrap();
+        assert!(response.starts_with("streamed...[truncated"));
+        assert!(entries[0].fields["request"].as_str().unwrap().contains("[truncated"));
+    }
+}
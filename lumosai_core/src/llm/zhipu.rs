@@ -6,10 +6,14 @@ use serde::Deserialize;
 use serde_json::Value;
 
 use crate::{Error, Result};
+use crate::config::http_client_for;
 use super::provider::{LlmProvider, FunctionCallingResponse};
-use super::types::{LlmOptions, Message, Role};
+use super::types::{LlmOptions, Message, Role, SamplingParam};
 use super::function_calling::{FunctionDefinition, FunctionCall, ToolChoice};
 
+/// Sampling controls the Zhipu API accepts
+const SAMPLING_PARAMS: &[SamplingParam] = &[SamplingParam::Stop];
+
 /// 智谱AI API response structures
 #[derive(Debug, Deserialize)]
 #[allow(dead_code)]
@@ -125,7 +129,7 @@ impl ZhipuProvider {
     pub fn new(api_key: String, model: Option<String>) -> Self {
         Self {
             api_key,
-            client: reqwest::Client::new(),
+            client: http_client_for("https://open.bigmodel.cn/api/paas/v4"),
             model: model.unwrap_or_else(|| "glm-4".to_string()),
             base_url: "https://open.bigmodel.cn/api/paas/v4".to_string(),
         }
@@ -145,7 +149,7 @@ impl ZhipuProvider {
     pub fn with_base_url(api_key: String, base_url: String, model: Option<String>) -> Self {
         Self {
             api_key,
-            client: reqwest::Client::new(),
+            client: http_client_for(&base_url),
             model: model.unwrap_or_else(|| "glm-4".to_string()),
             base_url,
         }
@@ -237,6 +241,7 @@ impl LlmProvider for ZhipuProvider {
         if let Some(top_p) = options.extra.get("top_p") {
             body["top_p"] = top_p.clone();
         }
+        options.apply_sampling_params(&mut body, "zhipu", SAMPLING_PARAMS);
 
         // Send request
         let res = self.client
@@ -296,6 +301,7 @@ impl LlmProvider for ZhipuProvider {
         if let Some(top_p) = options.extra.get("top_p") {
             body["top_p"] = top_p.clone();
         }
+        options.apply_sampling_params(&mut body, "zhipu", SAMPLING_PARAMS);
 
         // Send request
         let res = self.client
@@ -363,6 +369,7 @@ impl LlmProvider for ZhipuProvider {
         if let Some(top_p) = options.extra.get("top_p") {
             body["top_p"] = top_p.clone();
         }
+        options.apply_sampling_params(&mut body, "zhipu", SAMPLING_PARAMS);
 
         // Send request
         let response = self.client
@@ -486,6 +493,7 @@ impl LlmProvider for ZhipuProvider {
         if let Some(top_p) = options.extra.get("top_p") {
             body["top_p"] = top_p.clone();
         }
+        options.apply_sampling_params(&mut body, "zhipu", SAMPLING_PARAMS);
 
         // Send request
         let res = self.client
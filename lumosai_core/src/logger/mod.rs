@@ -3,6 +3,7 @@
 use std::sync::Arc;
 use serde::{Serialize, Deserialize};
 use crate::types::Metadata;
+use crate::request_context::RequestContext;
 
 /// Log level enumeration
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
@@ -121,41 +122,43 @@ impl ConsoleLogger {
     }
 }
 
+impl ConsoleLogger {
+    /// Print a log line; metadata is enriched with the current request context
+    fn log(&self, level: LogLevel, label: &str, message: &str, metadata: Option<Metadata>) {
+        if self.level > level {
+            return;
+        }
+        println!("[{}][{}][{}] {}", label, self.component, self.name, message);
+
+        let metadata = match RequestContext::current() {
+            Some(context) => {
+                let mut metadata = metadata.unwrap_or_default();
+                context.attach(&mut metadata);
+                Some(metadata)
+            },
+            None => metadata,
+        };
+        if let Some(meta) = metadata {
+            println!("  Metadata: {:?}", meta);
+        }
+    }
+}
+
 impl Logger for ConsoleLogger {
     fn debug(&self, message: &str, metadata: Option<Metadata>) {
-        if self.level <= LogLevel::Debug {
-            println!("[DEBUG][{}][{}] {}", self.component, self.name, message);
-            if let Some(meta) = metadata {
-                println!("  Metadata: {:?}", meta);
-            }
-        }
+        self.log(LogLevel::Debug, "DEBUG", message, metadata);
     }
     
     fn info(&self, message: &str, metadata: Option<Metadata>) {
-        if self.level <= LogLevel::Info {
-            println!("[INFO][{}][{}] {}", self.component, self.name, message);
-            if let Some(meta) = metadata {
-                println!("  Metadata: {:?}", meta);
-            }
-        }
+        self.log(LogLevel::Info, "INFO", message, metadata);
     }
     
     fn warn(&self, message: &str, metadata: Option<Metadata>) {
-        if self.level <= LogLevel::Warn {
-            println!("[WARN][{}][{}] {}", self.component, self.name, message);
-            if let Some(meta) = metadata {
-                println!("  Metadata: {:?}", meta);
-            }
-        }
+        self.log(LogLevel::Warn, "WARN", message, metadata);
     }
     
     fn error(&self, message: &str, metadata: Option<Metadata>) {
-        if self.level <= LogLevel::Error {
-            println!("[ERROR][{}][{}] {}", self.component, self.name, message);
-            if let Some(meta) = metadata {
-                println!("  Metadata: {:?}", meta);
-            }
-        }
+        self.log(LogLevel::Error, "ERROR", message, metadata);
     }
     
     fn get_logs_by_run_id(&self, _run_id: &str) -> Vec<LogEntry> {
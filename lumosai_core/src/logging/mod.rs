@@ -3,10 +3,15 @@
 //! This module provides structured logging, metrics, and observability tools
 
 use serde_json::{Value, json};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH};
 use uuid::Uuid;
 
+use crate::request_context::RequestContext;
+
 /// Log levels with semantic meaning
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum LogLevel {
@@ -69,7 +74,7 @@ impl LogEntry {
             module,
             fields: HashMap::new(),
             tags: Vec::new(),
-            correlation_id: None,
+            correlation_id: RequestContext::current().map(|context| context.trace_id),
         }
     }
 
@@ -198,11 +203,105 @@ pub enum LogFormat {
     Structured,
 }
 
+/// Destination for log entries, replacing console output
+pub trait LogSink: Send + Sync {
+    /// Store one entry
+    fn write(&self, entry: &LogEntry);
+}
+
+/// Sink keeping the most recent entries in memory
+pub struct MemoryLogSink {
+    capacity: usize,
+    entries: Mutex<VecDeque<LogEntry>>,
+}
+
+impl MemoryLogSink {
+    /// Keep at most `capacity` entries, dropping the oldest first
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Stored entries, oldest first
+    pub fn entries(&self) -> Vec<LogEntry> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).iter().cloned().collect()
+    }
+
+    /// Remove all stored entries
+    pub fn clear(&self) {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+}
+
+impl LogSink for MemoryLogSink {
+    fn write(&self, entry: &LogEntry) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if self.capacity == 0 {
+            return;
+        }
+        while entries.len() >= self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(entry.clone());
+    }
+}
+
+/// Sink appending entries as JSON lines to a file
+pub struct JsonFileLogSink {
+    path: PathBuf,
+    file: Mutex<Option<std::fs::File>>,
+}
+
+impl JsonFileLogSink {
+    /// Append to `path`, creating the file and its directory on first write
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            file: Mutex::new(None),
+        }
+    }
+
+    /// Path of the log file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn open(&self) -> std::io::Result<std::fs::File> {
+        if let Some(parent) = self.path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::OpenOptions::new().create(true).append(true).open(&self.path)
+    }
+}
+
+impl LogSink for JsonFileLogSink {
+    fn write(&self, entry: &LogEntry) {
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        if file.is_none() {
+            match self.open() {
+                Ok(opened) => *file = Some(opened),
+                Err(e) => {
+                    eprintln!("Failed to open log file {}: {}", self.path.display(), e);
+                    return;
+                },
+            }
+        }
+        if let Some(file) = file.as_mut() {
+            if let Err(e) = writeln!(file, "{}", entry.format_json()) {
+                eprintln!("Failed to write log file {}: {}", self.path.display(), e);
+            }
+        }
+    }
+}
+
 /// Enhanced logger with Mastra-style features
 pub struct Logger {
     config: LoggerConfig,
     correlation_id: Option<String>,
     module: String,
+    sink: Option<Arc<dyn LogSink>>,
 }
 
 impl Logger {
@@ -212,6 +311,7 @@ impl Logger {
             config: LoggerConfig::default(),
             correlation_id: None,
             module: module.to_string(),
+            sink: None,
         }
     }
 
@@ -221,6 +321,7 @@ impl Logger {
             config,
             correlation_id: None,
             module: module.to_string(),
+            sink: None,
         }
     }
 
@@ -230,6 +331,12 @@ impl Logger {
         self
     }
 
+    /// Send entries to `sink` instead of the console
+    pub fn with_sink(mut self, sink: Arc<dyn LogSink>) -> Self {
+        self.sink = Some(sink);
+        self
+    }
+
     /// Get the module name
     pub fn get_module(&self) -> &str {
         &self.module
@@ -280,24 +387,32 @@ impl Logger {
         self.log(level, message, fields);
     }
 
-    /// Internal log method
-    fn log(&self, level: LogLevel, message: &str, fields: HashMap<String, Value>) {
-        if level < self.config.min_level {
+    /// Log a prepared entry, applying the level filter and correlation ID
+    pub fn log_entry(&self, mut entry: LogEntry) {
+        if entry.level < self.config.min_level {
             return;
         }
-
-        let mut entry = LogEntry::new(level, message.to_string(), self.module.clone())
-            .with_fields(fields);
-
         if let Some(correlation_id) = &self.correlation_id {
             entry = entry.with_correlation_id(correlation_id.clone());
         }
-
         self.output_entry(&entry);
     }
 
+    /// Internal log method
+    fn log(&self, level: LogLevel, message: &str, fields: HashMap<String, Value>) {
+        if level < self.config.min_level {
+            return;
+        }
+
+        self.log_entry(LogEntry::new(level, message.to_string(), self.module.clone()).with_fields(fields));
+    }
+
     /// Output log entry based on configuration
     fn output_entry(&self, entry: &LogEntry) {
+        if let Some(sink) = &self.sink {
+            sink.write(entry);
+            return;
+        }
         match self.config.format {
             LogFormat::Console => {
                 println!("{}", entry.format_console());
@@ -465,6 +580,30 @@ mod tests {
         assert_eq!(logger.config.min_level, LogLevel::Info);
     }
 
+    #[test]
+    fn test_sinks() {
+        let memory = Arc::new(MemoryLogSink::new(2));
+        let logger = Logger::new("sink_module").with_sink(memory.clone()).with_correlation_id("corr-1");
+        logger.debug("filtered");
+        logger.info("first");
+        logger.warn("second");
+        logger.log_entry(LogEntry::new(LogLevel::Error, "third".to_string(), "other".to_string()));
+
+        let entries = memory.entries();
+        assert_eq!(entries.iter().map(|e| e.message.as_str()).collect::<Vec<_>>(), vec!["second", "third"]);
+        assert_eq!(entries[1].correlation_id.as_deref(), Some("corr-1"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = Arc::new(JsonFileLogSink::new(dir.path().join("logs/wire.jsonl")));
+        let logger = Logger::new("file_module").with_sink(file.clone());
+        logger.info("one");
+        logger.info("two");
+        let content = std::fs::read_to_string(file.path()).unwrap();
+        let lines: Vec<Value> = content.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1]["message"], "two");
+    }
+
     #[test]
     fn test_log_levels() {
         assert!(LogLevel::Error > LogLevel::Warn);
@@ -0,0 +1,183 @@
+//! Encryption-at-rest wrapper for memory thread storage
+//!
+//! [`EncryptedThreadStorage`] envelope-encrypts thread titles, thread metadata
+//! values and message contents for one tenant before they reach the wrapped
+//! [`MemoryThreadStorage`]. Because the backend only sees ciphertext, content
+//! searches and keyword filters are evaluated here after decryption.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use async_trait::async_trait;
+
+use crate::llm::Message;
+use crate::security::EnvelopeEncryptor;
+use crate::Result;
+use super::thread::{
+    GetMessagesParams, MemoryThread, MemoryThreadStorage, MessageFilter,
+    MessageOperationResult, ThreadStats,
+};
+
+/// Memory thread storage that encrypts one tenant's conversations
+pub struct EncryptedThreadStorage<S: MemoryThreadStorage> {
+    inner: S,
+    encryptor: Arc<EnvelopeEncryptor>,
+    tenant_id: String,
+}
+
+impl<S: MemoryThreadStorage> EncryptedThreadStorage<S> {
+    /// Wrap a thread storage for the given tenant
+    pub fn new(inner: S, encryptor: Arc<EnvelopeEncryptor>, tenant_id: impl Into<String>) -> Self {
+        Self {
+            inner,
+            encryptor,
+            tenant_id: tenant_id.into(),
+        }
+    }
+
+    /// The wrapped storage
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    async fn seal_thread(&self, thread: &MemoryThread) -> Result<MemoryThread> {
+        let mut sealed = thread.clone();
+        sealed.title = self.encryptor.encrypt_str(&self.tenant_id, &thread.title).await?;
+        sealed.metadata = HashMap::with_capacity(thread.metadata.len());
+        for (key, value) in &thread.metadata {
+            sealed.metadata.insert(key.clone(), self.encryptor.encrypt_json(&self.tenant_id, value).await?);
+        }
+        Ok(sealed)
+    }
+
+    async fn open_thread(&self, mut thread: MemoryThread) -> Result<MemoryThread> {
+        thread.title = self.encryptor.decrypt_str(&self.tenant_id, &thread.title).await?;
+        for value in thread.metadata.values_mut() {
+            *value = self.encryptor.decrypt_json(&self.tenant_id, value).await?;
+        }
+        Ok(thread)
+    }
+
+    async fn open_threads(&self, threads: Vec<MemoryThread>) -> Result<Vec<MemoryThread>> {
+        let mut opened = Vec::with_capacity(threads.len());
+        for thread in threads {
+            opened.push(self.open_thread(thread).await?);
+        }
+        Ok(opened)
+    }
+
+    async fn open_messages(&self, messages: Vec<Message>, keywords: Option<&[String]>) -> Result<Vec<Message>> {
+        let mut opened = Vec::with_capacity(messages.len());
+        for mut message in messages {
+            message.content = self.encryptor.decrypt_str(&self.tenant_id, &message.content).await?;
+            if keywords.is_none_or(|keywords| contains_any(&message.content, keywords)) {
+                opened.push(message);
+            }
+        }
+        Ok(opened)
+    }
+}
+
+/// Split content keywords off a filter so they can be applied after decryption
+fn split_keywords(filter: Option<&MessageFilter>) -> (Option<MessageFilter>, Option<Vec<String>>) {
+    match filter {
+        Some(filter) => {
+            let mut backend_filter = filter.clone();
+            let keywords = backend_filter.keywords.take();
+            (Some(backend_filter), keywords)
+        }
+        None => (None, None),
+    }
+}
+
+fn contains_any(content: &str, keywords: &[String]) -> bool {
+    let content = content.to_lowercase();
+    keywords.iter().any(|keyword| content.contains(&keyword.to_lowercase()))
+}
+
+#[async_trait]
+impl<S: MemoryThreadStorage> MemoryThreadStorage for EncryptedThreadStorage<S> {
+    async fn create_thread(&self, thread: &MemoryThread) -> Result<MemoryThread> {
+        let created = self.inner.create_thread(&self.seal_thread(thread).await?).await?;
+        self.open_thread(created).await
+    }
+
+    async fn get_thread(&self, thread_id: &str) -> Result<Option<MemoryThread>> {
+        match self.inner.get_thread(thread_id).await? {
+            Some(thread) => Ok(Some(self.open_thread(thread).await?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn update_thread(&self, thread: &MemoryThread) -> Result<MemoryThread> {
+        let updated = self.inner.update_thread(&self.seal_thread(thread).await?).await?;
+        self.open_thread(updated).await
+    }
+
+    async fn delete_thread(&self, thread_id: &str) -> Result<()> {
+        self.inner.delete_thread(thread_id).await
+    }
+
+    async fn list_threads_by_resource(&self, resource_id: &str) -> Result<Vec<MemoryThread>> {
+        let threads = self.inner.list_threads_by_resource(resource_id).await?;
+        self.open_threads(threads).await
+    }
+
+    async fn list_threads_by_agent(&self, agent_id: &str) -> Result<Vec<MemoryThread>> {
+        let threads = self.inner.list_threads_by_agent(agent_id).await?;
+        self.open_threads(threads).await
+    }
+
+    async fn add_message(&self, thread_id: &str, message: &Message) -> Result<()> {
+        let mut sealed = message.clone();
+        sealed.content = self.encryptor.encrypt_str(&self.tenant_id, &message.content).await?;
+        self.inner.add_message(thread_id, &sealed).await
+    }
+
+    async fn get_messages(&self, thread_id: &str, params: &GetMessagesParams) -> Result<Vec<Message>> {
+        let (filter, keywords) = split_keywords(params.filter.as_ref());
+        let mut backend_params = params.clone();
+        backend_params.filter = filter;
+        if keywords.is_some() {
+            // The limit has to apply to matching messages, not to ciphertext rows
+            backend_params.limit = None;
+        }
+
+        let mut messages = self.open_messages(
+            self.inner.get_messages(thread_id, &backend_params).await?,
+            keywords.as_deref(),
+        ).await?;
+        if keywords.is_some() {
+            if let Some(limit) = params.limit {
+                messages.truncate(limit);
+            }
+        }
+        Ok(messages)
+    }
+
+    async fn delete_messages(&self, thread_id: &str, message_ids: &[String]) -> Result<MessageOperationResult> {
+        self.inner.delete_messages(thread_id, message_ids).await
+    }
+
+    async fn search_messages(&self, query: &str, filter: Option<&MessageFilter>) -> Result<Vec<Message>> {
+        let (filter, keywords) = split_keywords(filter);
+        let candidates = self.inner.search_messages("", filter.as_ref()).await?;
+        let query = [query.to_string()];
+        let messages = self.open_messages(candidates, Some(&query)).await?;
+        Ok(match keywords {
+            Some(keywords) => messages.into_iter().filter(|m| contains_any(&m.content, &keywords)).collect(),
+            None => messages,
+        })
+    }
+
+    async fn get_thread_stats(&self, thread_id: &str) -> Result<ThreadStats> {
+        self.inner.get_thread_stats(thread_id).await
+    }
+}
+
+impl<S: MemoryThreadStorage> std::fmt::Debug for EncryptedThreadStorage<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EncryptedThreadStorage")
+            .field("tenant_id", &self.tenant_id)
+            .finish_non_exhaustive()
+    }
+}
@@ -107,6 +107,7 @@ pub mod thread;
 pub mod session;
 pub mod processor;
 pub mod enhanced;
+pub mod encrypted;
 
 // #[cfg(test)]
 // mod processor_tests;
@@ -126,6 +127,7 @@ pub use semantic_memory::{
     create_semantic_memory,
 };
 pub use basic::BasicMemory;
+pub use encrypted::EncryptedThreadStorage;
 pub use thread::{
     MemoryThread,
     MemoryThreadStorage,
@@ -23,7 +23,7 @@ pub struct SemanticMemory {
     /// LLM提供者，用于生成嵌入向量
     llm: Arc<dyn LlmProvider>,
     /// 向量存储
-    vector_storage: Arc<crate::vector::MemoryVectorStorage>,
+    vector_storage: Arc<dyn VectorStorage>,
     /// 消息存储（ID -> 消息）
     messages: Mutex<HashMap<String, Message>>,
     /// 命名空间
@@ -33,10 +33,16 @@ pub struct SemanticMemory {
 impl SemanticMemory {
     /// 创建新的语义搜索内存
     pub fn new(config: &MemoryConfig, llm: Arc<dyn LlmProvider>) -> Result<Self> {
-        // 直接创建MemoryVectorStorage实例而非通过函数获取Box<dyn VectorStorage>
-        let vector_storage = crate::vector::MemoryVectorStorage::new(1536, None);
-        let vector_storage = Arc::new(vector_storage);
-        
+        let vector_storage = Arc::new(crate::vector::MemoryVectorStorage::new(1536, None));
+        Self::with_storage(config, llm, vector_storage)
+    }
+    
+    /// 使用给定的向量存储创建语义搜索内存
+    pub fn with_storage(
+        config: &MemoryConfig,
+        llm: Arc<dyn LlmProvider>,
+        vector_storage: Arc<dyn VectorStorage>,
+    ) -> Result<Self> {
         let namespace = config.namespace.clone().unwrap_or_else(|| "default".to_string());
         
         let component_config = ComponentConfig {
@@ -0,0 +1,332 @@
+//! 代理负载测试
+//!
+//! [`run_load_test`]以固定的并发度对代理发起多轮对话，统计每次请求的延迟分位数、
+//! 吞吐量、令牌用量和成本，用于验证性能指标。`lumos bench`命令和性能测试都基于此模块，
+//! 不依赖真实模型时可使用带模拟延迟的[`SimulatedProvider`]。
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream, StreamExt};
+use serde::{Deserialize, Serialize};
+
+use crate::agent::trait_def::Agent;
+use crate::agent::types::{AgentGenerateOptions, TokenUsage};
+use crate::llm::{LlmOptions, LlmProvider, Message, Role};
+use crate::{Error, Result};
+
+/// 负载测试配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadTestConfig {
+    /// 同时进行的对话数
+    pub concurrency: usize,
+    /// 对话总数
+    pub conversations: usize,
+    /// 每个对话的轮数
+    pub turns: usize,
+    /// 用户消息，按对话和轮次轮流使用
+    pub prompts: Vec<String>,
+    /// 单次请求超时，超时计为失败
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_timeout: Option<Duration>,
+    /// 令牌价格，为空时不计算成本
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pricing: Option<TokenPricing>,
+}
+
+impl Default for LoadTestConfig {
+    fn default() -> Self {
+        Self {
+            concurrency: 10,
+            conversations: 100,
+            turns: 1,
+            prompts: vec!["Hello".to_string()],
+            request_timeout: None,
+            pricing: None,
+        }
+    }
+}
+
+impl LoadTestConfig {
+    /// 校验配置
+    pub fn validate(&self) -> Result<()> {
+        if self.concurrency == 0 || self.conversations == 0 || self.turns == 0 {
+            return Err(Error::Configuration("Load test concurrency, conversations and turns must be at least 1".to_string()));
+        }
+        if self.prompts.is_empty() {
+            return Err(Error::Configuration("Load test needs at least one prompt".to_string()));
+        }
+        Ok(())
+    }
+
+    /// 第`conversation`个对话第`turn`轮的用户消息
+    fn prompt(&self, conversation: usize, turn: usize) -> &str {
+        &self.prompts[(conversation + turn) % self.prompts.len()]
+    }
+}
+
+/// 每百万令牌的价格（美元）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TokenPricing {
+    /// 输入令牌价格
+    pub input_per_million: f64,
+    /// 输出令牌价格
+    pub output_per_million: f64,
+}
+
+impl TokenPricing {
+    /// 用量对应的成本（美元）
+    pub fn cost(&self, usage: &TokenUsage) -> f64 {
+        (usage.prompt_tokens as f64 * self.input_per_million
+            + usage.completion_tokens as f64 * self.output_per_million) / 1_000_000.0
+    }
+}
+
+/// 延迟统计，单位毫秒
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LatencyStats {
+    /// 最小值
+    pub min_ms: f64,
+    /// 平均值
+    pub mean_ms: f64,
+    /// 中位数
+    pub p50_ms: f64,
+    /// 90分位
+    pub p90_ms: f64,
+    /// 95分位
+    pub p95_ms: f64,
+    /// 99分位
+    pub p99_ms: f64,
+    /// 最大值
+    pub max_ms: f64,
+}
+
+impl LatencyStats {
+    /// 由请求延迟计算统计值，分位数取最近秩
+    pub fn from_latencies(latencies: &[Duration]) -> Self {
+        if latencies.is_empty() {
+            return Self::default();
+        }
+        let mut millis: Vec<f64> = latencies.iter().map(|latency| latency.as_secs_f64() * 1000.0).collect();
+        millis.sort_by(f64::total_cmp);
+        let percentile = |p: f64| millis[((p / 100.0 * millis.len() as f64).ceil() as usize).clamp(1, millis.len()) - 1];
+        Self {
+            min_ms: millis[0],
+            mean_ms: millis.iter().sum::<f64>() / millis.len() as f64,
+            p50_ms: percentile(50.0),
+            p90_ms: percentile(90.0),
+            p95_ms: percentile(95.0),
+            p99_ms: percentile(99.0),
+            max_ms: millis[millis.len() - 1],
+        }
+    }
+}
+
+/// 负载测试报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadTestReport {
+    /// 使用的配置
+    pub config: LoadTestConfig,
+    /// 请求总数
+    pub requests: usize,
+    /// 失败的请求数
+    pub failures: usize,
+    /// 错误信息及其出现次数
+    pub errors: BTreeMap<String, usize>,
+    /// 总耗时（毫秒）
+    pub duration_ms: u64,
+    /// 吞吐量（每秒成功请求数）
+    pub throughput_rps: f64,
+    /// 成功请求的延迟
+    pub latency: LatencyStats,
+    /// 成功请求的令牌用量
+    pub usage: TokenUsage,
+    /// 令牌成本（美元），未配置价格时为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_usd: Option<f64>,
+}
+
+impl LoadTestReport {
+    /// 成功请求的比例
+    pub fn success_rate(&self) -> f64 {
+        if self.requests == 0 {
+            return 0.0;
+        }
+        (self.requests - self.failures) as f64 / self.requests as f64
+    }
+}
+
+/// 一次请求的结果
+struct RequestOutcome {
+    latency: Duration,
+    result: std::result::Result<TokenUsage, String>,
+}
+
+/// 对代理运行负载测试
+///
+/// 失败的请求计入报告而不中断测试，失败后该对话的剩余轮次不再执行。
+pub async fn run_load_test(agent: Arc<dyn Agent>, config: &LoadTestConfig) -> Result<LoadTestReport> {
+    config.validate()?;
+
+    let started = Instant::now();
+    let outcomes: Vec<RequestOutcome> = stream::iter(0..config.conversations)
+        .map(|conversation| run_conversation(agent.clone(), config, conversation))
+        .buffer_unordered(config.concurrency)
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .flatten()
+        .collect();
+    let elapsed = started.elapsed();
+
+    let mut latencies = Vec::with_capacity(outcomes.len());
+    let mut errors = BTreeMap::new();
+    let mut usage = TokenUsage { prompt_tokens: 0, completion_tokens: 0, total_tokens: 0 };
+    for outcome in &outcomes {
+        match &outcome.result {
+            Ok(request_usage) => {
+                latencies.push(outcome.latency);
+                usage.prompt_tokens += request_usage.prompt_tokens;
+                usage.completion_tokens += request_usage.completion_tokens;
+                usage.total_tokens += request_usage.total_tokens;
+            }
+            Err(error) => *errors.entry(error.clone()).or_insert(0) += 1,
+        }
+    }
+
+    Ok(LoadTestReport {
+        config: config.clone(),
+        requests: outcomes.len(),
+        failures: outcomes.len() - latencies.len(),
+        errors,
+        duration_ms: elapsed.as_millis() as u64,
+        throughput_rps: latencies.len() as f64 / elapsed.as_secs_f64().max(f64::EPSILON),
+        latency: LatencyStats::from_latencies(&latencies),
+        cost_usd: config.pricing.map(|pricing| pricing.cost(&usage)),
+        usage,
+    })
+}
+
+/// 执行一个多轮对话，返回每轮请求的结果
+async fn run_conversation(agent: Arc<dyn Agent>, config: &LoadTestConfig, conversation: usize) -> Vec<RequestOutcome> {
+    let mut messages = Vec::with_capacity(config.turns * 2);
+    let mut outcomes = Vec::with_capacity(config.turns);
+    let options = AgentGenerateOptions {
+        thread_id: Some(format!("bench-{}", conversation)),
+        ..Default::default()
+    };
+
+    for turn in 0..config.turns {
+        messages.push(message(Role::User, config.prompt(conversation, turn)));
+        let started = Instant::now();
+        let result = match config.request_timeout {
+            Some(timeout) => tokio::time::timeout(timeout, agent.generate(&messages, &options)).await
+                .unwrap_or_else(|_| Err(Error::Timeout(format!("Request timed out after {} ms", timeout.as_millis())))),
+            None => agent.generate(&messages, &options).await,
+        };
+        let latency = started.elapsed();
+
+        match result {
+            Ok(result) => {
+                messages.push(message(Role::Assistant, &result.response));
+                outcomes.push(RequestOutcome { latency, result: Ok(result.usage) });
+            }
+            Err(e) => {
+                outcomes.push(RequestOutcome { latency, result: Err(e.to_string()) });
+                break;
+            }
+        }
+    }
+    outcomes
+}
+
+fn message(role: Role, content: &str) -> Message {
+    Message {
+        role,
+        content: content.to_string(),
+        metadata: None,
+        name: None,
+    }
+}
+
+/// 以固定延迟回复的模拟提供商，用于不调用真实模型的负载测试
+#[derive(Debug, Clone)]
+pub struct SimulatedProvider {
+    latency: Duration,
+    response: String,
+}
+
+impl SimulatedProvider {
+    /// 创建模拟提供商，每次生成耗时`latency`
+    pub fn new(latency: Duration) -> Self {
+        Self {
+            latency,
+            response: "This is a simulated response.".to_string(),
+        }
+    }
+
+    /// 设置回复内容
+    pub fn with_response(mut self, response: impl Into<String>) -> Self {
+        self.response = response.into();
+        self
+    }
+}
+
+#[async_trait]
+impl LlmProvider for SimulatedProvider {
+    fn name(&self) -> &str {
+        "simulated"
+    }
+
+    async fn generate(&self, _prompt: &str, _options: &LlmOptions) -> Result<String> {
+        tokio::time::sleep(self.latency).await;
+        Ok(self.response.clone())
+    }
+
+    async fn generate_with_messages(&self, _messages: &[Message], _options: &LlmOptions) -> Result<String> {
+        tokio::time::sleep(self.latency).await;
+        Ok(self.response.clone())
+    }
+
+    async fn generate_stream<'a>(&'a self, _prompt: &'a str, _options: &'a LlmOptions) -> Result<BoxStream<'a, Result<String>>> {
+        tokio::time::sleep(self.latency).await;
+        Ok(stream::once(async move { Ok(self.response.clone()) }).boxed())
+    }
+
+    async fn get_embedding(&self, text: &str) -> Result<Vec<f32>> {
+        Ok(vec![text.len() as f32, 1.0])
+    }
+
+    fn supports_seed(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_percentiles() {
+        let latencies: Vec<Duration> = (1..=100).map(Duration::from_millis).collect();
+        let stats = LatencyStats::from_latencies(&latencies);
+        assert_eq!(stats.min_ms, 1.0);
+        assert_eq!(stats.p50_ms, 50.0);
+        assert_eq!(stats.p95_ms, 95.0);
+        assert_eq!(stats.p99_ms, 99.0);
+        assert_eq!(stats.max_ms, 100.0);
+        assert!((stats.mean_ms - 50.5).abs() < 1e-9);
+
+        assert_eq!(LatencyStats::from_latencies(&[Duration::from_millis(7)]).p99_ms, 7.0);
+        assert_eq!(LatencyStats::from_latencies(&[]), LatencyStats::default());
+    }
+
+    #[test]
+    fn test_token_cost() {
+        let pricing = TokenPricing { input_per_million: 2.5, output_per_million: 10.0 };
+        let usage = TokenUsage { prompt_tokens: 1_000_000, completion_tokens: 500_000, total_tokens: 1_500_000 };
+        assert!((pricing.cost(&usage) - 7.5).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,183 @@
+//! 请求上下文
+//!
+//! [`RequestContext`]记录一次请求的追踪ID、租户、用户和会话。它保存在任务局部存储中，
+//! 代理、工具、RAG和向量存储在同一任务中执行时无需修改函数签名即可读取。
+//! 在[`RequestContext::scope`]中执行的代码会自动带上这些字段：
+//!
+//! - `tracing`事件归属于携带这些字段的`request`span
+//! - [`ConsoleLogger`](crate::logger::ConsoleLogger)输出的元数据和[`LogEntry`](crate::logging::LogEntry)的关联ID
+//! - 代理执行追踪的元数据
+//! - [`AuditLogger`](crate::security::audit::AuditLogger)记录的审计事件
+//!
+//! 任务局部存储不会跨越`tokio::spawn`，派生任务需要用[`propagate`]包装。
+
+use std::collections::HashMap;
+use std::future::Future;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::Instrument;
+
+tokio::task_local! {
+    static CURRENT: RequestContext;
+}
+
+/// 一次请求的关联标识
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RequestContext {
+    /// 追踪ID，贯穿一次请求的所有日志、span和审计记录
+    pub trace_id: String,
+    /// 租户ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tenant_id: Option<String>,
+    /// 用户ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+    /// 会话ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+}
+
+impl RequestContext {
+    /// 创建上下文，生成新的追踪ID（32位十六进制，与W3C Trace Context兼容）
+    pub fn new() -> Self {
+        Self::with_trace_id(uuid::Uuid::new_v4().simple().to_string())
+    }
+
+    /// 使用调用方提供的追踪ID创建上下文
+    pub fn with_trace_id(trace_id: impl Into<String>) -> Self {
+        Self {
+            trace_id: trace_id.into(),
+            tenant_id: None,
+            user_id: None,
+            session_id: None,
+        }
+    }
+
+    /// 设置租户ID
+    pub fn with_tenant(mut self, tenant_id: impl Into<String>) -> Self {
+        self.tenant_id = Some(tenant_id.into());
+        self
+    }
+
+    /// 设置用户ID
+    pub fn with_user(mut self, user_id: impl Into<String>) -> Self {
+        self.user_id = Some(user_id.into());
+        self
+    }
+
+    /// 设置会话ID
+    pub fn with_session(mut self, session_id: impl Into<String>) -> Self {
+        self.session_id = Some(session_id.into());
+        self
+    }
+
+    /// 当前任务的请求上下文；不在[`scope`](Self::scope)中时返回`None`
+    pub fn current() -> Option<RequestContext> {
+        CURRENT.try_with(Clone::clone).ok()
+    }
+
+    /// 在此上下文中执行Future，其中的`tracing`事件归属于携带上下文字段的`request`span
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        let span = tracing::info_span!(
+            "request",
+            trace_id = %self.trace_id,
+            tenant_id = self.tenant_id.as_deref(),
+            user_id = self.user_id.as_deref(),
+            session_id = self.session_id.as_deref(),
+        );
+        CURRENT.scope(self, future.instrument(span)).await
+    }
+
+    /// 上下文字段，键为`trace_id`、`tenant_id`、`user_id`和`session_id`，未设置的字段省略
+    pub fn fields(&self) -> HashMap<String, Value> {
+        let mut fields = HashMap::new();
+        fields.insert("trace_id".to_string(), Value::String(self.trace_id.clone()));
+        let optional = [
+            ("tenant_id", &self.tenant_id),
+            ("user_id", &self.user_id),
+            ("session_id", &self.session_id),
+        ];
+        for (key, value) in optional {
+            if let Some(value) = value {
+                fields.insert(key.to_string(), Value::String(value.clone()));
+            }
+        }
+        fields
+    }
+
+    /// 把上下文字段写入元数据，不覆盖调用方已设置的同名字段
+    pub fn attach(&self, metadata: &mut HashMap<String, Value>) {
+        for (key, value) in self.fields() {
+            metadata.entry(key).or_insert(value);
+        }
+    }
+}
+
+impl Default for RequestContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 把当前请求上下文写入元数据；不在请求中时不做修改
+pub fn attach_current(metadata: &mut HashMap<String, Value>) {
+    if let Some(context) = RequestContext::current() {
+        context.attach(metadata);
+    }
+}
+
+/// 让Future继承创建时的请求上下文，用于`tokio::spawn`派生的任务
+///
+/// 上下文在调用时捕获，而不是在Future首次执行时。
+pub fn propagate<F: Future>(future: F) -> impl Future<Output = F::Output> {
+    let context = RequestContext::current();
+    async move {
+        match context {
+            Some(context) => context.scope(future).await,
+            None => future.await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_context_is_visible_inside_scope_only() {
+        assert!(RequestContext::current().is_none());
+
+        let context = RequestContext::with_trace_id("trace-1").with_tenant("acme").with_user("ann");
+        let seen = context.clone().scope(async { RequestContext::current() }).await;
+        assert_eq!(seen, Some(context));
+        assert!(RequestContext::current().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_propagate_carries_context_into_spawned_tasks() {
+        let context = RequestContext::with_trace_id("trace-2").with_session("s-1");
+        let (plain, propagated) = context.scope(async {
+            let plain = tokio::spawn(async { RequestContext::current() });
+            let propagated = tokio::spawn(propagate(async { RequestContext::current() }));
+            (plain.await.unwrap(), propagated.await.unwrap())
+        }).await;
+
+        assert!(plain.is_none());
+        let propagated = propagated.unwrap();
+        assert_eq!(propagated.trace_id, "trace-2");
+        assert_eq!(propagated.session_id.as_deref(), Some("s-1"));
+    }
+
+    #[test]
+    fn test_attach_keeps_existing_fields() {
+        let context = RequestContext::with_trace_id("trace-3").with_tenant("acme");
+        let mut metadata = HashMap::new();
+        metadata.insert("tenant_id".to_string(), Value::String("override".to_string()));
+        context.attach(&mut metadata);
+
+        assert_eq!(metadata["trace_id"], "trace-3");
+        assert_eq!(metadata["tenant_id"], "override");
+        assert!(!metadata.contains_key("user_id"));
+    }
+}
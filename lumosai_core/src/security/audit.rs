@@ -10,6 +10,7 @@ use uuid::Uuid;
 
 use crate::error::{LumosError, Result};
 use super::SecurityEvent;
+use crate::request_context::RequestContext;
 
 /// 审计配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -209,6 +210,17 @@ impl AuditLogger {
         
         // 处理事件（掩码敏感字段等）
         self.event_processor.process_event(&mut event)?;
+
+        // 关联当前请求：补全用户和会话，追踪ID和租户写入详情（任何审计级别都保留）
+        if let Some(context) = RequestContext::current() {
+            if event.user_id.is_none() {
+                event.user_id = context.user_id.clone();
+            }
+            if event.session_id.is_none() {
+                event.session_id = context.session_id.clone();
+            }
+            context.attach(&mut event.details);
+        }
         
         // 存储事件
         self.storage.store_event(&event).await?;
@@ -475,4 +487,40 @@ mod tests {
         let result = logger.log_event(security_event).await;
         assert!(result.is_ok());
     }
+
+    struct Recorder(std::sync::Arc<std::sync::Mutex<Vec<AuditEvent>>>);
+
+    #[async_trait]
+    impl AuditSubscriber for Recorder {
+        async fn on_audit_event(&mut self, event: &AuditEvent) -> Result<()> {
+            self.0.lock().unwrap().push(event.clone());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_audit_events_carry_request_context() {
+        let config = AuditConfig::default();
+        let mut logger = AuditLogger::new(&config).await.unwrap();
+        let events = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        logger.add_subscriber(Box::new(Recorder(events.clone()))).await.unwrap();
+
+        let context = RequestContext::with_trace_id("trace-audit").with_tenant("acme").with_session("s-1");
+        context.scope(async {
+            let security_event = SecurityEvent::DataAccess {
+                user_id: "ann".to_string(),
+                resource_type: "document".to_string(),
+                resource_id: "doc-1".to_string(),
+                action: "read".to_string(),
+                timestamp: Utc::now(),
+            };
+            logger.log_event(security_event).await.unwrap();
+        }).await;
+
+        let events = events.lock().unwrap();
+        assert_eq!(events[0].user_id.as_deref(), Some("ann"));
+        assert_eq!(events[0].session_id.as_deref(), Some("s-1"));
+        assert_eq!(events[0].details["trace_id"], "trace-audit");
+        assert_eq!(events[0].details["tenant_id"], "acme");
+    }
 }
@@ -0,0 +1,450 @@
+//! 信封加密模块
+//!
+//! 为存储层的敏感字段提供静态加密：每个数据密钥（DEK）用AES-256-GCM加密字段内容，
+//! 数据密钥本身由可插拔的密钥提供者（KMS）包装后随密文一起保存。
+//! 租户ID作为附加认证数据参与加密，密文无法在租户之间挪用。
+//!
+//! 加密后的字段是带 [`ENVELOPE_PREFIX`] 前缀的字符串，未加密的旧数据按原样读取，
+//! 因此可以在已有数据上直接启用。
+
+use async_trait::async_trait;
+use base64::{Engine as _, engine::general_purpose};
+use ring::{aead, rand::{SecureRandom, SystemRandom}};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::error::{LumosError, Result};
+
+/// 加密字段的前缀
+pub const ENVELOPE_PREFIX: &str = "lumos:enc:v1:";
+
+/// 一个数据密钥默认最多加密的字段数，超过后重新生成
+pub const DEFAULT_MAX_DATA_KEY_USES: u64 = 10_000;
+
+/// 解密时缓存的已解包数据密钥数量上限
+const UNWRAPPED_KEY_CACHE_SIZE: usize = 1024;
+
+const NONCE_LEN: usize = 12;
+const DATA_KEY_LEN: usize = 32;
+
+/// 被密钥提供者包装后的数据密钥
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WrappedKey {
+    /// 密钥提供者名称
+    pub provider: String,
+    /// 包装所用的主密钥ID
+    pub key_id: String,
+    /// Base64编码的包装结果
+    pub ciphertext: String,
+}
+
+/// 密钥提供者（KMS），负责包装和解包数据密钥
+#[async_trait]
+pub trait KeyProvider: Send + Sync {
+    /// 提供者名称，写入密文以便解密时找回
+    fn name(&self) -> &str;
+
+    /// 包装数据密钥
+    async fn wrap_key(&self, data_key: &[u8]) -> Result<WrappedKey>;
+
+    /// 解包数据密钥
+    async fn unwrap_key(&self, wrapped: &WrappedKey) -> Result<Vec<u8>>;
+}
+
+/// 本地主密钥提供者
+///
+/// 用本地保存的256位主密钥包装数据密钥，适合开发环境或自行管理密钥的部署。
+/// 轮换主密钥时把旧密钥通过 [`LocalKeyProvider::with_previous_key`] 保留用于解包。
+pub struct LocalKeyProvider {
+    name: String,
+    current_key_id: String,
+    keys: HashMap<String, aead::LessSafeKey>,
+    rng: SystemRandom,
+}
+
+impl LocalKeyProvider {
+    /// 使用256位主密钥创建
+    pub fn new(key_id: impl Into<String>, master_key: &[u8]) -> Result<Self> {
+        let key_id = key_id.into();
+        let mut keys = HashMap::new();
+        keys.insert(key_id.clone(), aes_key(master_key)?);
+        Ok(Self {
+            name: "local".to_string(),
+            current_key_id: key_id,
+            keys,
+            rng: SystemRandom::new(),
+        })
+    }
+
+    /// 使用Base64编码的主密钥创建
+    pub fn from_base64(key_id: impl Into<String>, master_key: &str) -> Result<Self> {
+        let bytes = general_purpose::STANDARD.decode(master_key.trim())
+            .map_err(|e| LumosError::SecurityError(format!("Invalid base64 master key: {}", e)))?;
+        Self::new(key_id, &bytes)
+    }
+
+    /// 从口令派生主密钥，盐为密钥ID
+    pub fn from_passphrase(key_id: impl Into<String>, passphrase: &str) -> Result<Self> {
+        let key_id = key_id.into();
+        let mut key = [0u8; DATA_KEY_LEN];
+        ring::pbkdf2::derive(
+            ring::pbkdf2::PBKDF2_HMAC_SHA256,
+            std::num::NonZeroU32::new(100_000).unwrap(),
+            key_id.as_bytes(),
+            passphrase.as_bytes(),
+            &mut key,
+        );
+        Self::new(key_id, &key)
+    }
+
+    /// 设置提供者名称，默认为 `local`
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// 保留轮换前的主密钥，仅用于解包
+    pub fn with_previous_key(mut self, key_id: impl Into<String>, master_key: &[u8]) -> Result<Self> {
+        self.keys.insert(key_id.into(), aes_key(master_key)?);
+        Ok(self)
+    }
+}
+
+#[async_trait]
+impl KeyProvider for LocalKeyProvider {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn wrap_key(&self, data_key: &[u8]) -> Result<WrappedKey> {
+        let key = &self.keys[&self.current_key_id];
+        let sealed = seal(key, &self.rng, self.current_key_id.as_bytes(), data_key)?;
+        Ok(WrappedKey {
+            provider: self.name.clone(),
+            key_id: self.current_key_id.clone(),
+            ciphertext: general_purpose::STANDARD.encode(sealed),
+        })
+    }
+
+    async fn unwrap_key(&self, wrapped: &WrappedKey) -> Result<Vec<u8>> {
+        let key = self.keys.get(&wrapped.key_id).ok_or_else(|| {
+            LumosError::SecurityError(format!("Unknown master key: {}", wrapped.key_id))
+        })?;
+        let sealed = general_purpose::STANDARD.decode(&wrapped.ciphertext)
+            .map_err(|_| LumosError::SecurityError("Failed to decode wrapped key".to_string()))?;
+        open(key, wrapped.key_id.as_bytes(), sealed)
+    }
+}
+
+/// 单个租户的加密配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TenantEncryption {
+    /// 是否加密该租户的数据
+    pub enabled: bool,
+    /// 使用的密钥提供者，为空时使用默认提供者
+    #[serde(default)]
+    pub provider: Option<String>,
+}
+
+/// 信封加密配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvelopeConfig {
+    /// 未单独配置的租户是否加密
+    pub enabled_by_default: bool,
+    /// 默认密钥提供者
+    #[serde(default)]
+    pub default_provider: Option<String>,
+    /// 按租户的配置
+    #[serde(default)]
+    pub tenants: HashMap<String, TenantEncryption>,
+    /// 一个数据密钥最多加密的字段数
+    pub max_data_key_uses: u64,
+}
+
+impl Default for EnvelopeConfig {
+    fn default() -> Self {
+        Self {
+            enabled_by_default: true,
+            default_provider: None,
+            tenants: HashMap::new(),
+            max_data_key_uses: DEFAULT_MAX_DATA_KEY_USES,
+        }
+    }
+}
+
+/// 信封中的密文
+#[derive(Debug, Serialize, Deserialize)]
+struct Envelope {
+    #[serde(rename = "k")]
+    wrapped_key: WrappedKey,
+    #[serde(rename = "n")]
+    nonce: String,
+    #[serde(rename = "c")]
+    ciphertext: String,
+}
+
+/// 当前使用中的数据密钥
+struct ActiveDataKey {
+    key: Arc<aead::LessSafeKey>,
+    wrapped: WrappedKey,
+    uses: u64,
+}
+
+/// 信封加密器
+///
+/// 同一租户复用数据密钥直到达到使用上限，避免每个字段都调用一次KMS；
+/// 解密时按包装结果缓存已解包的数据密钥。
+pub struct EnvelopeEncryptor {
+    config: EnvelopeConfig,
+    providers: HashMap<String, Arc<dyn KeyProvider>>,
+    active_keys: Mutex<HashMap<String, ActiveDataKey>>,
+    unwrapped_keys: Mutex<HashMap<String, Arc<aead::LessSafeKey>>>,
+    rng: SystemRandom,
+}
+
+impl EnvelopeEncryptor {
+    /// 创建加密器
+    pub fn new(config: EnvelopeConfig) -> Self {
+        Self {
+            config,
+            providers: HashMap::new(),
+            active_keys: Mutex::new(HashMap::new()),
+            unwrapped_keys: Mutex::new(HashMap::new()),
+            rng: SystemRandom::new(),
+        }
+    }
+
+    /// 注册密钥提供者，配置中未指定默认提供者时第一个注册的即为默认
+    pub fn with_provider(mut self, provider: Arc<dyn KeyProvider>) -> Self {
+        let name = provider.name().to_string();
+        if self.config.default_provider.is_none() {
+            self.config.default_provider = Some(name.clone());
+        }
+        self.providers.insert(name, provider);
+        self
+    }
+
+    /// 加密配置
+    pub fn config(&self) -> &EnvelopeConfig {
+        &self.config
+    }
+
+    /// 租户的数据是否加密
+    pub fn is_enabled_for(&self, tenant_id: &str) -> bool {
+        self.config.tenants.get(tenant_id)
+            .map_or(self.config.enabled_by_default, |tenant| tenant.enabled)
+    }
+
+    /// 字段是否为信封密文
+    pub fn is_encrypted(value: &str) -> bool {
+        value.starts_with(ENVELOPE_PREFIX)
+    }
+
+    /// 加密字符串字段，租户未启用加密时原样返回
+    pub async fn encrypt_str(&self, tenant_id: &str, plaintext: &str) -> Result<String> {
+        if !self.is_enabled_for(tenant_id) || Self::is_encrypted(plaintext) {
+            return Ok(plaintext.to_string());
+        }
+
+        let (key, wrapped_key) = self.data_key(tenant_id).await?;
+        let sealed = seal(&key, &self.rng, tenant_id.as_bytes(), plaintext.as_bytes())?;
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        let envelope = Envelope {
+            wrapped_key,
+            nonce: general_purpose::STANDARD.encode(nonce),
+            ciphertext: general_purpose::STANDARD.encode(ciphertext),
+        };
+        let json = serde_json::to_vec(&envelope)
+            .map_err(|e| LumosError::SecurityError(format!("Failed to serialize envelope: {}", e)))?;
+        Ok(format!("{}{}", ENVELOPE_PREFIX, general_purpose::URL_SAFE_NO_PAD.encode(json)))
+    }
+
+    /// 解密字符串字段，未加密的值原样返回
+    pub async fn decrypt_str(&self, tenant_id: &str, value: &str) -> Result<String> {
+        let Some(encoded) = value.strip_prefix(ENVELOPE_PREFIX) else {
+            return Ok(value.to_string());
+        };
+
+        let json = general_purpose::URL_SAFE_NO_PAD.decode(encoded)
+            .map_err(|_| LumosError::SecurityError("Failed to decode envelope".to_string()))?;
+        let envelope: Envelope = serde_json::from_slice(&json)
+            .map_err(|e| LumosError::SecurityError(format!("Malformed envelope: {}", e)))?;
+
+        let key = self.unwrap_data_key(&envelope.wrapped_key).await?;
+        let mut sealed = general_purpose::STANDARD.decode(&envelope.nonce)
+            .map_err(|_| LumosError::SecurityError("Failed to decode nonce".to_string()))?;
+        sealed.extend(general_purpose::STANDARD.decode(&envelope.ciphertext)
+            .map_err(|_| LumosError::SecurityError("Failed to decode ciphertext".to_string()))?);
+
+        let plaintext = open(&key, tenant_id.as_bytes(), sealed)?;
+        String::from_utf8(plaintext)
+            .map_err(|_| LumosError::SecurityError("Decrypted field is not valid UTF-8".to_string()))
+    }
+
+    /// 加密JSON值，结果为字符串值
+    pub async fn encrypt_json(&self, tenant_id: &str, value: &serde_json::Value) -> Result<serde_json::Value> {
+        if !self.is_enabled_for(tenant_id) {
+            return Ok(value.clone());
+        }
+        let json = serde_json::to_string(value)?;
+        Ok(serde_json::Value::String(self.encrypt_str(tenant_id, &json).await?))
+    }
+
+    /// 解密 [`EnvelopeEncryptor::encrypt_json`] 的结果，其他值原样返回
+    pub async fn decrypt_json(&self, tenant_id: &str, value: &serde_json::Value) -> Result<serde_json::Value> {
+        match value.as_str() {
+            Some(text) if Self::is_encrypted(text) => {
+                let json = self.decrypt_str(tenant_id, text).await?;
+                Ok(serde_json::from_str(&json)?)
+            }
+            _ => Ok(value.clone()),
+        }
+    }
+
+    fn provider_for(&self, tenant_id: &str) -> Result<&Arc<dyn KeyProvider>> {
+        let name = self.config.tenants.get(tenant_id)
+            .and_then(|tenant| tenant.provider.as_ref())
+            .or(self.config.default_provider.as_ref())
+            .ok_or_else(|| LumosError::SecurityError("No key provider configured".to_string()))?;
+        self.providers.get(name)
+            .ok_or_else(|| LumosError::SecurityError(format!("Key provider not registered: {}", name)))
+    }
+
+    async fn data_key(&self, tenant_id: &str) -> Result<(Arc<aead::LessSafeKey>, WrappedKey)> {
+        {
+            let mut active = self.active_keys.lock().unwrap();
+            if let Some(data_key) = active.get_mut(tenant_id) {
+                if data_key.uses < self.config.max_data_key_uses {
+                    data_key.uses += 1;
+                    return Ok((data_key.key.clone(), data_key.wrapped.clone()));
+                }
+            }
+        }
+
+        let mut raw = [0u8; DATA_KEY_LEN];
+        self.rng.fill(&mut raw)
+            .map_err(|_| LumosError::SecurityError("Failed to generate data key".to_string()))?;
+        let wrapped = self.provider_for(tenant_id)?.wrap_key(&raw).await?;
+        let key = Arc::new(aes_key(&raw)?);
+
+        self.active_keys.lock().unwrap().insert(tenant_id.to_string(), ActiveDataKey {
+            key: key.clone(),
+            wrapped: wrapped.clone(),
+            uses: 1,
+        });
+        Ok((key, wrapped))
+    }
+
+    async fn unwrap_data_key(&self, wrapped: &WrappedKey) -> Result<Arc<aead::LessSafeKey>> {
+        if let Some(key) = self.unwrapped_keys.lock().unwrap().get(&wrapped.ciphertext) {
+            return Ok(key.clone());
+        }
+
+        let provider = self.providers.get(&wrapped.provider).ok_or_else(|| {
+            LumosError::SecurityError(format!("Key provider not registered: {}", wrapped.provider))
+        })?;
+        let key = Arc::new(aes_key(&provider.unwrap_key(wrapped).await?)?);
+
+        let mut cache = self.unwrapped_keys.lock().unwrap();
+        if cache.len() >= UNWRAPPED_KEY_CACHE_SIZE {
+            cache.clear();
+        }
+        cache.insert(wrapped.ciphertext.clone(), key.clone());
+        Ok(key)
+    }
+}
+
+fn aes_key(bytes: &[u8]) -> Result<aead::LessSafeKey> {
+    let unbound = aead::UnboundKey::new(&aead::AES_256_GCM, bytes)
+        .map_err(|_| LumosError::SecurityError("AES-256-GCM keys must be 32 bytes".to_string()))?;
+    Ok(aead::LessSafeKey::new(unbound))
+}
+
+/// 加密，返回 `nonce || ciphertext || tag`
+fn seal(key: &aead::LessSafeKey, rng: &SystemRandom, aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+    let mut nonce = [0u8; NONCE_LEN];
+    rng.fill(&mut nonce)
+        .map_err(|_| LumosError::SecurityError("Failed to generate nonce".to_string()))?;
+
+    let mut in_out = plaintext.to_vec();
+    key.seal_in_place_append_tag(aead::Nonce::assume_unique_for_key(nonce), aead::Aad::from(aad), &mut in_out)
+        .map_err(|_| LumosError::SecurityError("Encryption failed".to_string()))?;
+
+    let mut sealed = nonce.to_vec();
+    sealed.extend(in_out);
+    Ok(sealed)
+}
+
+/// 解密 [`seal`] 的结果
+fn open(key: &aead::LessSafeKey, aad: &[u8], mut sealed: Vec<u8>) -> Result<Vec<u8>> {
+    if sealed.len() < NONCE_LEN {
+        return Err(LumosError::SecurityError("Ciphertext too short".to_string()));
+    }
+    let mut in_out = sealed.split_off(NONCE_LEN);
+    let nonce = aead::Nonce::try_assume_unique_for_key(&sealed)
+        .map_err(|_| LumosError::SecurityError("Invalid nonce".to_string()))?;
+    let plaintext = key.open_in_place(nonce, aead::Aad::from(aad), &mut in_out)
+        .map_err(|_| LumosError::SecurityError("Decryption failed".to_string()))?;
+    Ok(plaintext.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MASTER_KEY: [u8; 32] = [1u8; 32];
+
+    fn encryptor(config: EnvelopeConfig) -> EnvelopeEncryptor {
+        let provider = LocalKeyProvider::new("master-1", &MASTER_KEY).unwrap();
+        EnvelopeEncryptor::new(config).with_provider(Arc::new(provider))
+    }
+
+    #[tokio::test]
+    async fn test_envelope_round_trip() {
+        let encryptor = encryptor(EnvelopeConfig::default());
+
+        let sealed = encryptor.encrypt_str("acme", "my card is 4111-1111").await.unwrap();
+        assert!(EnvelopeEncryptor::is_encrypted(&sealed));
+        assert!(!sealed.contains("4111"));
+        assert_eq!(encryptor.decrypt_str("acme", &sealed).await.unwrap(), "my card is 4111-1111");
+
+        // 租户ID参与认证，密文不能在租户之间挪用
+        assert!(encryptor.decrypt_str("globex", &sealed).await.is_err());
+
+        // 未加密的旧数据原样读取
+        assert_eq!(encryptor.decrypt_str("acme", "legacy").await.unwrap(), "legacy");
+
+        let value = serde_json::json!({ "email": "a@example.com" });
+        let sealed = encryptor.encrypt_json("acme", &value).await.unwrap();
+        assert!(sealed.is_string());
+        assert_eq!(encryptor.decrypt_json("acme", &sealed).await.unwrap(), value);
+    }
+
+    #[tokio::test]
+    async fn test_per_tenant_config_and_key_rotation() {
+        let mut config = EnvelopeConfig { max_data_key_uses: 2, ..EnvelopeConfig::default() };
+        config.tenants.insert("sandbox".to_string(), TenantEncryption { enabled: false, provider: None });
+        let encryptor = encryptor(config);
+
+        assert_eq!(encryptor.encrypt_str("sandbox", "plain").await.unwrap(), "plain");
+
+        let envelope = |sealed: &str| -> Envelope {
+            let json = general_purpose::URL_SAFE_NO_PAD.decode(&sealed[ENVELOPE_PREFIX.len()..]).unwrap();
+            serde_json::from_slice(&json).unwrap()
+        };
+        let first = encryptor.encrypt_str("acme", "a").await.unwrap();
+        let second = encryptor.encrypt_str("acme", "b").await.unwrap();
+        let third = encryptor.encrypt_str("acme", "c").await.unwrap();
+        assert_eq!(envelope(&first).wrapped_key, envelope(&second).wrapped_key);
+        assert_ne!(envelope(&second).wrapped_key, envelope(&third).wrapped_key);
+        assert_eq!(encryptor.decrypt_str("acme", &first).await.unwrap(), "a");
+
+        // 轮换主密钥后仍能解开旧密文
+        let rotated = LocalKeyProvider::new("master-2", &[2u8; 32]).unwrap()
+            .with_previous_key("master-1", &MASTER_KEY)
+            .unwrap();
+        let rotated = EnvelopeEncryptor::new(EnvelopeConfig::default()).with_provider(Arc::new(rotated));
+        assert_eq!(rotated.decrypt_str("acme", &third).await.unwrap(), "c");
+    }
+}
@@ -37,6 +37,7 @@
 #![allow(unexpected_cfgs, unused_assignments)]
 
 pub mod encryption;
+pub mod envelope;
 pub mod zero_trust;
 pub mod threat_detection;
 pub mod audit;
@@ -52,6 +53,7 @@ use serde::{Deserialize, Serialize};
 use crate::error::{LumosError, Result};
 
 pub use encryption::*;
+pub use envelope::*;
 pub use zero_trust::*;
 pub use threat_detection::*;
 pub use audit::*;
@@ -0,0 +1,230 @@
+//! Encryption-at-rest wrapper for storage providers
+//!
+//! [`EncryptedStorage`] wraps another [`Storage`] and envelope-encrypts the
+//! sensitive fields of one tenant's data before it reaches the provider:
+//! thread titles and metadata, and message contents. Identifiers, timestamps
+//! and roles stay in plaintext so lookups and ordering keep working in the
+//! underlying database. Generic table records, eval rows, traces and workflow
+//! snapshots are passed through unchanged.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::error::Result;
+use crate::security::EnvelopeEncryptor;
+use crate::storage::types::*;
+use crate::workflow::WorkflowState;
+
+/// Storage provider that encrypts one tenant's conversation data
+pub struct EncryptedStorage {
+    inner: Arc<dyn Storage>,
+    encryptor: Arc<EnvelopeEncryptor>,
+    tenant_id: String,
+}
+
+impl EncryptedStorage {
+    /// Wrap a storage provider for the given tenant
+    pub fn new(inner: Arc<dyn Storage>, encryptor: Arc<EnvelopeEncryptor>, tenant_id: impl Into<String>) -> Self {
+        Self {
+            inner,
+            encryptor,
+            tenant_id: tenant_id.into(),
+        }
+    }
+
+    /// The tenant whose data this storage encrypts
+    pub fn tenant_id(&self) -> &str {
+        &self.tenant_id
+    }
+
+    async fn seal_metadata(&self, metadata: &Value) -> Result<Value> {
+        self.encryptor.encrypt_json(&self.tenant_id, metadata).await
+    }
+
+    async fn seal_thread(&self, mut thread: Thread) -> Result<Thread> {
+        thread.title = self.encryptor.encrypt_str(&self.tenant_id, &thread.title).await?;
+        if let Some(metadata) = &thread.metadata {
+            thread.metadata = Some(self.seal_metadata(metadata).await?);
+        }
+        Ok(thread)
+    }
+
+    async fn open_thread(&self, mut thread: Thread) -> Result<Thread> {
+        thread.title = self.encryptor.decrypt_str(&self.tenant_id, &thread.title).await?;
+        if let Some(metadata) = &thread.metadata {
+            thread.metadata = Some(self.encryptor.decrypt_json(&self.tenant_id, metadata).await?);
+        }
+        Ok(thread)
+    }
+
+    async fn open_threads(&self, threads: Vec<Thread>) -> Result<Vec<Thread>> {
+        let mut opened = Vec::with_capacity(threads.len());
+        for thread in threads {
+            opened.push(self.open_thread(thread).await?);
+        }
+        Ok(opened)
+    }
+
+    async fn open_messages(&self, messages: Vec<Message>) -> Result<Vec<Message>> {
+        let mut opened = Vec::with_capacity(messages.len());
+        for mut message in messages {
+            message.content = self.encryptor.decrypt_str(&self.tenant_id, &message.content).await?;
+            opened.push(message);
+        }
+        Ok(opened)
+    }
+}
+
+#[async_trait]
+impl Storage for EncryptedStorage {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn init(&self) -> Result<()> {
+        self.inner.init().await
+    }
+
+    async fn create_table(&self, table_name: &str, schema: HashMap<String, ColumnDefinition>) -> Result<()> {
+        self.inner.create_table(table_name, schema).await
+    }
+
+    async fn clear_table(&self, table_name: &str) -> Result<()> {
+        self.inner.clear_table(table_name).await
+    }
+
+    async fn insert(&self, table_name: &str, record: Value) -> Result<()> {
+        self.inner.insert(table_name, record).await
+    }
+
+    async fn batch_insert(&self, table_name: &str, records: Vec<Value>) -> Result<()> {
+        self.inner.batch_insert(table_name, records).await
+    }
+
+    async fn load(&self, table_name: &str, keys: HashMap<String, String>) -> Result<Option<Value>> {
+        self.inner.load(table_name, keys).await
+    }
+
+    async fn get_thread_by_id(&self, thread_id: &str) -> Result<Option<Thread>> {
+        match self.inner.get_thread_by_id(thread_id).await? {
+            Some(thread) => Ok(Some(self.open_thread(thread).await?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn get_threads_by_resource_id(&self, resource_id: &str) -> Result<Vec<Thread>> {
+        let threads = self.inner.get_threads_by_resource_id(resource_id).await?;
+        self.open_threads(threads).await
+    }
+
+    async fn save_thread(&self, thread: Thread) -> Result<Thread> {
+        let sealed = self.seal_thread(thread).await?;
+        let saved = self.inner.save_thread(sealed).await?;
+        self.open_thread(saved).await
+    }
+
+    async fn update_thread(&self, id: &str, title: &str, metadata: Value) -> Result<Thread> {
+        let title = self.encryptor.encrypt_str(&self.tenant_id, title).await?;
+        let metadata = self.seal_metadata(&metadata).await?;
+        let updated = self.inner.update_thread(id, &title, metadata).await?;
+        self.open_thread(updated).await
+    }
+
+    async fn delete_thread(&self, thread_id: &str) -> Result<()> {
+        self.inner.delete_thread(thread_id).await
+    }
+
+    async fn get_messages(&self, args: GetMessagesArgs) -> Result<Vec<Message>> {
+        let messages = self.inner.get_messages(args).await?;
+        self.open_messages(messages).await
+    }
+
+    async fn save_messages(&self, messages: Vec<Message>) -> Result<Vec<Message>> {
+        let mut sealed = Vec::with_capacity(messages.len());
+        for mut message in messages {
+            message.content = self.encryptor.encrypt_str(&self.tenant_id, &message.content).await?;
+            sealed.push(message);
+        }
+        let saved = self.inner.save_messages(sealed).await?;
+        self.open_messages(saved).await
+    }
+
+    async fn get_evals_by_agent_name(&self, agent_name: &str, eval_type: Option<&str>) -> Result<Vec<EvalRow>> {
+        self.inner.get_evals_by_agent_name(agent_name, eval_type).await
+    }
+
+    async fn get_traces(&self,
+        name: Option<&str>,
+        scope: Option<&str>,
+        page: usize,
+        per_page: usize,
+        attributes: Option<HashMap<String, String>>
+    ) -> Result<Vec<Value>> {
+        self.inner.get_traces(name, scope, page, per_page, attributes).await
+    }
+
+    async fn persist_workflow_snapshot(&self,
+        workflow_name: &str,
+        run_id: &str,
+        snapshot: &WorkflowState
+    ) -> Result<()> {
+        self.inner.persist_workflow_snapshot(workflow_name, run_id, snapshot).await
+    }
+
+    async fn load_workflow_snapshot(&self,
+        workflow_name: &str,
+        run_id: &str
+    ) -> Result<Option<WorkflowState>> {
+        self.inner.load_workflow_snapshot(workflow_name, run_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::{EnvelopeConfig, LocalKeyProvider};
+    use crate::storage::providers::memory::MemoryStorage;
+    use chrono::Utc;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_encrypted_storage_round_trip() {
+        let raw: Arc<dyn Storage> = Arc::new(MemoryStorage::new("raw".to_string()));
+        let encryptor = EnvelopeEncryptor::new(EnvelopeConfig::default())
+            .with_provider(Arc::new(LocalKeyProvider::new("master", &[3u8; 32]).unwrap()));
+        let storage = EncryptedStorage::new(raw.clone(), Arc::new(encryptor), "acme");
+
+        storage.save_thread(Thread {
+            id: "t1".to_string(),
+            resource_id: "user-1".to_string(),
+            title: "Payroll questions".to_string(),
+            metadata: Some(json!({ "ssn": "123-45-6789" })),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }).await.unwrap();
+        storage.save_messages(vec![Message {
+            id: "m1".to_string(),
+            thread_id: "t1".to_string(),
+            content: "My salary is 90k".to_string(),
+            role: "user".to_string(),
+            message_type: "text".to_string(),
+            created_at: Utc::now(),
+        }]).await.unwrap();
+
+        // The underlying provider only ever sees ciphertext
+        let stored = raw.get_thread_by_id("t1").await.unwrap().unwrap();
+        assert!(EnvelopeEncryptor::is_encrypted(&stored.title));
+        assert!(!stored.metadata.unwrap().to_string().contains("6789"));
+        let args = GetMessagesArgs { thread_id: "t1".to_string(), resource_id: None, select_by: None, thread_config: None };
+        let stored = raw.get_messages(args.clone()).await.unwrap();
+        assert!(!stored[0].content.contains("90k"));
+
+        let thread = storage.get_thread_by_id("t1").await.unwrap().unwrap();
+        assert_eq!(thread.title, "Payroll questions");
+        assert_eq!(thread.metadata, Some(json!({ "ssn": "123-45-6789" })));
+        let messages = storage.get_messages(args).await.unwrap();
+        assert_eq!(messages[0].content, "My salary is 90k");
+    }
+}
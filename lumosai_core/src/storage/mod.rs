@@ -2,14 +2,22 @@
 //! 
 //! This module provides interfaces and implementations for storing
 //! various types of data including workflows, threads, messages, and evaluation results.
+//! Files and other binary objects go to an [`ObjectStore`], see [`object`].
 
 mod constants;
 mod types;
 mod providers;
+mod encrypted;
+pub mod object;
 
 pub use constants::*;
 pub use types::*;
 pub use providers::*;
+pub use encrypted::EncryptedStorage;
+pub use object::{
+    object_store_from_url, AzureBlobObjectStore, GcsObjectStore, LocalObjectStore, ObjectFileCache,
+    ObjectStore, PrefixedObjectStore, S3ObjectStore,
+};
 
 use crate::error::Result;
 use std::sync::Arc;
@@ -0,0 +1,294 @@
+//! Azure Blob Storage object storage
+//!
+//! Requests are authorized with the storage account key (Shared Key) or with a
+//! shared access signature. Objects are stored as block blobs.
+
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chrono::Utc;
+use reqwest::Method;
+use ring::hmac;
+
+use super::{check_key, check_response, uri_encode, xml_values, ObjectStore};
+use crate::config::http_client_for;
+use crate::error::{Error, Result};
+
+/// Blob service REST API version
+const API_VERSION: &str = "2021-08-06";
+
+/// How requests are authorized
+#[derive(Debug, Clone)]
+enum Credentials {
+    /// Decoded storage account key
+    SharedKey(Vec<u8>),
+    /// Shared access signature query string
+    Sas(String),
+}
+
+/// Object store in an Azure Blob Storage container
+#[derive(Debug, Clone)]
+pub struct AzureBlobObjectStore {
+    client: reqwest::Client,
+    account: String,
+    container: String,
+    /// Blob service endpoint, `https://<account>.blob.core.windows.net` by default
+    endpoint: String,
+    credentials: Credentials,
+}
+
+impl AzureBlobObjectStore {
+    /// Create a store authorized with the base64 storage account key
+    pub fn new(account: impl Into<String>, container: impl Into<String>, account_key: &str) -> Result<Self> {
+        let key = BASE64.decode(account_key.trim())
+            .map_err(|e| Error::Configuration(format!("Invalid Azure storage account key: {}", e)))?;
+        Ok(Self::with_credentials(account.into(), container.into(), Credentials::SharedKey(key)))
+    }
+
+    /// Create a store authorized with a shared access signature
+    pub fn with_sas_token(account: impl Into<String>, container: impl Into<String>, sas_token: &str) -> Self {
+        let sas = sas_token.trim().trim_start_matches('?').to_string();
+        Self::with_credentials(account.into(), container.into(), Credentials::Sas(sas))
+    }
+
+    /// Create a store from `AZURE_STORAGE_ACCOUNT` and either `AZURE_STORAGE_KEY` or
+    /// `AZURE_STORAGE_SAS_TOKEN`; `AZURE_STORAGE_ENDPOINT` overrides the endpoint
+    pub fn from_env(container: impl Into<String>) -> Result<Self> {
+        let var = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+        let account = var("AZURE_STORAGE_ACCOUNT")
+            .ok_or_else(|| Error::Configuration("AZURE_STORAGE_ACCOUNT is not set".to_string()))?;
+        let store = match (var("AZURE_STORAGE_KEY"), var("AZURE_STORAGE_SAS_TOKEN")) {
+            (Some(key), _) => Self::new(account, container, &key)?,
+            (None, Some(sas)) => Self::with_sas_token(account, container, &sas),
+            (None, None) => {
+                return Err(Error::Configuration(
+                    "Neither AZURE_STORAGE_KEY nor AZURE_STORAGE_SAS_TOKEN is set".to_string(),
+                ))
+            },
+        };
+        Ok(match var("AZURE_STORAGE_ENDPOINT") {
+            Some(endpoint) => store.with_endpoint(endpoint),
+            None => store,
+        })
+    }
+
+    /// Use a custom endpoint, such as `http://127.0.0.1:10000/devstoreaccount1` for Azurite
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into().trim_end_matches('/').to_string();
+        self.client = http_client_for(&self.endpoint);
+        self
+    }
+
+    fn with_credentials(account: String, container: String, credentials: Credentials) -> Self {
+        let endpoint = format!("https://{}.blob.core.windows.net", account);
+        Self {
+            client: http_client_for(&endpoint),
+            endpoint,
+            account,
+            container,
+            credentials,
+        }
+    }
+
+    /// Path of a blob, or of the container when `key` is empty
+    fn path(&self, key: &str) -> String {
+        let base = self.endpoint
+            .split_once("://")
+            .and_then(|(_, rest)| rest.find('/').map(|index| &rest[index..]))
+            .unwrap_or("");
+        let mut path = format!("{}/{}", base, uri_encode(&self.container, false));
+        if !key.is_empty() {
+            path.push('/');
+            path.push_str(&uri_encode(key, true));
+        }
+        path
+    }
+
+    /// Send an authorized request
+    async fn send(
+        &self,
+        method: Method,
+        key: &str,
+        query: &[(&str, &str)],
+        headers: &[(&str, &str)],
+        body: Vec<u8>,
+    ) -> Result<reqwest::Response> {
+        let path = self.path(key);
+        let origin = match self.endpoint.split_once("://") {
+            Some((scheme, rest)) => format!("{}://{}", scheme, rest.split('/').next().unwrap_or(rest)),
+            None => self.endpoint.clone(),
+        };
+
+        let mut headers: Vec<(String, String)> = headers.iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        headers.push(("x-ms-date".to_string(), Utc::now().format("%a, %d %b %Y %H:%M:%S GMT").to_string()));
+        headers.push(("x-ms-version".to_string(), API_VERSION.to_string()));
+
+        let mut query_string = query.iter()
+            .map(|(name, value)| format!("{}={}", uri_encode(name, false), uri_encode(value, false)))
+            .collect::<Vec<_>>()
+            .join("&");
+        match &self.credentials {
+            Credentials::SharedKey(key) => {
+                let string_to_sign = string_to_sign(method.as_str(), body.len(), &headers, &self.account, &path, query);
+                let signature = hmac::sign(&hmac::Key::new(hmac::HMAC_SHA256, key), string_to_sign.as_bytes());
+                headers.push((
+                    "authorization".to_string(),
+                    format!("SharedKey {}:{}", self.account, BASE64.encode(signature.as_ref())),
+                ));
+            },
+            Credentials::Sas(sas) => {
+                if !query_string.is_empty() {
+                    query_string.push('&');
+                }
+                query_string.push_str(sas);
+            },
+        }
+
+        let mut url = format!("{}{}", origin, path);
+        if !query_string.is_empty() {
+            url.push('?');
+            url.push_str(&query_string);
+        }
+        let mut request = self.client.request(method, url);
+        for (name, value) in &headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+        Ok(request.body(body).send().await?)
+    }
+}
+
+#[async_trait]
+impl ObjectStore for AzureBlobObjectStore {
+    async fn put(&self, key: &str, content_type: &str, data: Vec<u8>) -> Result<()> {
+        check_key(key)?;
+        let headers = [("content-type", content_type), ("x-ms-blob-type", "BlockBlob")];
+        let response = self.send(Method::PUT, key, &[], &headers, data).await?;
+        check_response("Azure Blob", key, response).await?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>> {
+        check_key(key)?;
+        let response = self.send(Method::GET, key, &[], &[], Vec::new()).await?;
+        let response = check_response("Azure Blob", key, response).await?;
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        check_key(key)?;
+        let response = self.send(Method::DELETE, key, &[], &[], Vec::new()).await?;
+        match check_response("Azure Blob", key, response).await {
+            Ok(_) | Err(Error::NotFound(_)) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        let mut keys = Vec::new();
+        let mut marker = String::new();
+        loop {
+            let mut query = vec![("comp", "list"), ("prefix", prefix), ("restype", "container")];
+            if !marker.is_empty() {
+                query.push(("marker", &marker));
+            }
+            let response = self.send(Method::GET, "", &query, &[], Vec::new()).await?;
+            let body = check_response("Azure Blob", prefix, response).await?.text().await?;
+            keys.extend(xml_values(&body, "Name"));
+
+            marker = xml_values(&body, "NextMarker").into_iter().next().unwrap_or_default();
+            if marker.is_empty() {
+                break;
+            }
+        }
+        keys.sort();
+        Ok(keys)
+    }
+}
+
+/// String signed with the account key for Shared Key authorization
+///
+/// `headers` holds the request headers with lowercase names; standard headers are read
+/// from it by name and every `x-ms-` header is canonicalized.
+fn string_to_sign(
+    method: &str,
+    content_length: usize,
+    headers: &[(String, String)],
+    account: &str,
+    path: &str,
+    query: &[(&str, &str)],
+) -> String {
+    let header = |name: &str| headers.iter().find(|(header, _)| header == name).map_or("", |(_, value)| value.as_str());
+    let content_length = if content_length == 0 { String::new() } else { content_length.to_string() };
+
+    let mut ms_headers: Vec<(&str, &str)> = headers.iter()
+        .filter(|(name, _)| name.starts_with("x-ms-"))
+        .map(|(name, value)| (name.as_str(), value.trim()))
+        .collect();
+    ms_headers.sort();
+    let canonical_headers: String = ms_headers.iter().map(|(name, value)| format!("{}:{}\n", name, value)).collect();
+
+    let mut canonical_resource = format!("/{}{}", account, path);
+    let mut query: Vec<(String, &str)> = query.iter().map(|(name, value)| (name.to_ascii_lowercase(), *value)).collect();
+    query.sort();
+    for (name, value) in query {
+        canonical_resource.push_str(&format!("\n{}:{}", name, value));
+    }
+
+    [
+        method,
+        header("content-encoding"),
+        header("content-language"),
+        &content_length,
+        header("content-md5"),
+        header("content-type"),
+        header("date"),
+        header("if-modified-since"),
+        header("if-match"),
+        header("if-none-match"),
+        header("if-unmodified-since"),
+        header("range"),
+    ]
+    .iter()
+    .map(|value| format!("{}\n", value))
+    .collect::<String>()
+        + &canonical_headers
+        + &canonical_resource
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paths() {
+        let store = AzureBlobObjectStore::with_sas_token("acct", "snapshots", "?sv=2021&sig=abc");
+        assert_eq!(store.path("index/a b.bin"), "/snapshots/index/a%20b.bin");
+        assert_eq!(store.path(""), "/snapshots");
+
+        let azurite = store.with_endpoint("http://127.0.0.1:10000/devstoreaccount1/");
+        assert_eq!(azurite.path("a.bin"), "/devstoreaccount1/snapshots/a.bin");
+        assert!(matches!(AzureBlobObjectStore::new("acct", "c", "not base64!"), Err(Error::Configuration(_))));
+    }
+
+    #[test]
+    fn test_string_to_sign() {
+        let headers = vec![
+            ("content-type".to_string(), "application/json".to_string()),
+            ("x-ms-version".to_string(), API_VERSION.to_string()),
+            ("x-ms-blob-type".to_string(), "BlockBlob".to_string()),
+            ("x-ms-date".to_string(), "Fri, 16 Oct 2026 10:00:00 GMT".to_string()),
+        ];
+        assert_eq!(
+            string_to_sign("PUT", 11, &headers, "acct", "/reports/a.json", &[]),
+            "PUT\n\n\n11\n\napplication/json\n\n\n\n\n\n\n\
+             x-ms-blob-type:BlockBlob\nx-ms-date:Fri, 16 Oct 2026 10:00:00 GMT\nx-ms-version:2021-08-06\n\
+             /acct/reports/a.json"
+        );
+
+        let list = string_to_sign("GET", 0, &headers[1..2], "acct", "/reports", &[("restype", "container"), ("comp", "list"), ("prefix", "a/")]);
+        assert!(list.starts_with("GET\n\n\n\n"));
+        assert!(list.ends_with("/acct/reports\ncomp:list\nprefix:a/\nrestype:container"));
+    }
+}
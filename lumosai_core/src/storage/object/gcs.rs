@@ -0,0 +1,239 @@
+//! Google Cloud Storage object storage
+//!
+//! Uses the JSON API with OAuth 2.0 bearer tokens. Without an explicit token, tokens
+//! are requested from the metadata server of the Compute Engine, GKE or Cloud Run
+//! instance and refreshed shortly before they expire.
+
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use reqwest::Method;
+use serde::Deserialize;
+use tokio::sync::Mutex;
+
+use super::{check_key, check_response, uri_encode, ObjectStore};
+use crate::config::http_client_for;
+use crate::error::{Error, Result};
+
+/// Default JSON API endpoint
+const DEFAULT_ENDPOINT: &str = "https://storage.googleapis.com";
+
+/// Metadata server endpoint returning tokens of the instance service account
+const METADATA_TOKEN_URL: &str =
+    "http://metadata.google.internal/computeMetadata/v1/instance/service-accounts/default/token";
+
+/// Tokens are refreshed this long before they expire
+const TOKEN_REFRESH_MARGIN: Duration = Duration::from_secs(60);
+
+/// How requests are authorized
+#[derive(Debug, Clone)]
+enum Credentials {
+    /// Fixed access token
+    Token(String),
+    /// Tokens from the instance metadata server
+    MetadataServer,
+    /// No authorization, for public buckets and local emulators
+    Anonymous,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+/// Object store in a Google Cloud Storage bucket
+#[derive(Debug)]
+pub struct GcsObjectStore {
+    client: reqwest::Client,
+    bucket: String,
+    endpoint: String,
+    credentials: Credentials,
+    /// Metadata server token and when it must be refreshed
+    cached_token: Mutex<Option<(String, Instant)>>,
+}
+
+impl GcsObjectStore {
+    /// Create a store for `bucket` authorized with `access_token`
+    pub fn new(bucket: impl Into<String>, access_token: impl Into<String>) -> Self {
+        Self::with_credentials(bucket.into(), Credentials::Token(access_token.into()))
+    }
+
+    /// Create a store for `bucket` authorized by the instance metadata server
+    pub fn from_metadata_server(bucket: impl Into<String>) -> Self {
+        Self::with_credentials(bucket.into(), Credentials::MetadataServer)
+    }
+
+    /// Create a store for `bucket` that sends no credentials
+    pub fn anonymous(bucket: impl Into<String>) -> Self {
+        Self::with_credentials(bucket.into(), Credentials::Anonymous)
+    }
+
+    /// Create a store authorized with `GOOGLE_OAUTH_ACCESS_TOKEN` when set, and by
+    /// the metadata server otherwise; `STORAGE_EMULATOR_HOST` selects an emulator
+    pub fn from_env(bucket: impl Into<String>) -> Self {
+        let var = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+        let mut store = match var("GOOGLE_OAUTH_ACCESS_TOKEN") {
+            Some(token) => Self::new(bucket, token),
+            None if var("STORAGE_EMULATOR_HOST").is_some() => Self::anonymous(bucket),
+            None => Self::from_metadata_server(bucket),
+        };
+        if let Some(host) = var("STORAGE_EMULATOR_HOST") {
+            let endpoint = if host.contains("://") { host } else { format!("http://{}", host) };
+            store = store.with_endpoint(endpoint);
+        }
+        store
+    }
+
+    /// Use a custom endpoint such as a local emulator
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into().trim_end_matches('/').to_string();
+        self.client = http_client_for(&self.endpoint);
+        self
+    }
+
+    fn with_credentials(bucket: String, credentials: Credentials) -> Self {
+        Self {
+            client: http_client_for(DEFAULT_ENDPOINT),
+            bucket,
+            endpoint: DEFAULT_ENDPOINT.to_string(),
+            credentials,
+            cached_token: Mutex::new(None),
+        }
+    }
+
+    /// URL of an object in the JSON API
+    fn object_url(&self, key: &str) -> String {
+        format!("{}/storage/v1/b/{}/o/{}", self.endpoint, uri_encode(&self.bucket, false), uri_encode(key, false))
+    }
+
+    /// Bearer token for the next request
+    async fn token(&self) -> Result<Option<String>> {
+        match &self.credentials {
+            Credentials::Token(token) => Ok(Some(token.clone())),
+            Credentials::Anonymous => Ok(None),
+            Credentials::MetadataServer => {
+                let mut cached = self.cached_token.lock().await;
+                if let Some((token, refresh_at)) = cached.as_ref() {
+                    if Instant::now() < *refresh_at {
+                        return Ok(Some(token.clone()));
+                    }
+                }
+
+                let response = self.client.get(METADATA_TOKEN_URL)
+                    .header("Metadata-Flavor", "Google")
+                    .send()
+                    .await
+                    .map_err(|e| Error::Configuration(format!("Failed to reach the GCE metadata server: {}", e)))?;
+                let response: TokenResponse = check_response("GCS token", "default", response).await?.json().await?;
+                let lifetime = Duration::from_secs(response.expires_in).saturating_sub(TOKEN_REFRESH_MARGIN);
+                *cached = Some((response.access_token.clone(), Instant::now() + lifetime));
+                Ok(Some(response.access_token))
+            },
+        }
+    }
+
+    async fn request(&self, method: Method, url: String) -> Result<reqwest::RequestBuilder> {
+        let request = self.client.request(method, url);
+        Ok(match self.token().await? {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        })
+    }
+}
+
+#[async_trait]
+impl ObjectStore for GcsObjectStore {
+    async fn put(&self, key: &str, content_type: &str, data: Vec<u8>) -> Result<()> {
+        check_key(key)?;
+        let url = format!(
+            "{}/upload/storage/v1/b/{}/o?uploadType=media&name={}",
+            self.endpoint, uri_encode(&self.bucket, false), uri_encode(key, false),
+        );
+        let response = self.request(Method::POST, url).await?
+            .header("content-type", content_type)
+            .body(data)
+            .send()
+            .await?;
+        check_response("GCS", key, response).await?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>> {
+        check_key(key)?;
+        let response = self.request(Method::GET, format!("{}?alt=media", self.object_url(key))).await?
+            .send()
+            .await?;
+        let response = check_response("GCS", key, response).await?;
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        check_key(key)?;
+        let response = self.request(Method::DELETE, self.object_url(key)).await?.send().await?;
+        match check_response("GCS", key, response).await {
+            Ok(_) | Err(Error::NotFound(_)) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct ListResponse {
+            #[serde(default)]
+            items: Vec<Item>,
+            next_page_token: Option<String>,
+        }
+
+        #[derive(Deserialize)]
+        struct Item {
+            name: String,
+        }
+
+        let mut keys = Vec::new();
+        let mut page_token: Option<String> = None;
+        loop {
+            let mut url = format!(
+                "{}/storage/v1/b/{}/o?prefix={}&fields=items(name),nextPageToken",
+                self.endpoint, uri_encode(&self.bucket, false), uri_encode(prefix, false),
+            );
+            if let Some(token) = &page_token {
+                url.push_str(&format!("&pageToken={}", uri_encode(token, false)));
+            }
+            let response = self.request(Method::GET, url).await?.send().await?;
+            let page: ListResponse = check_response("GCS", prefix, response).await?.json().await?;
+            keys.extend(page.items.into_iter().map(|item| item.name));
+
+            page_token = page.next_page_token;
+            if page_token.is_none() {
+                break;
+            }
+        }
+        keys.sort();
+        Ok(keys)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_object_url() {
+        let store = GcsObjectStore::new("models", "token");
+        assert_eq!(
+            store.object_url("embeddings/bge small.onnx"),
+            "https://storage.googleapis.com/storage/v1/b/models/o/embeddings%2Fbge%20small.onnx"
+        );
+
+        let emulator = GcsObjectStore::anonymous("models").with_endpoint("http://localhost:4443/");
+        assert_eq!(emulator.object_url("a.bin"), "http://localhost:4443/storage/v1/b/models/o/a.bin");
+    }
+
+    #[tokio::test]
+    async fn test_tokens() {
+        assert_eq!(GcsObjectStore::new("b", "abc").token().await.unwrap().as_deref(), Some("abc"));
+        assert_eq!(GcsObjectStore::anonymous("b").token().await.unwrap(), None);
+    }
+}
@@ -0,0 +1,452 @@
+//! Object storage for files and blobs
+//!
+//! [`ObjectStore`] is a flat key/value store for binary objects, backed by a local
+//! directory ([`LocalObjectStore`]), Amazon S3 or any S3-compatible service
+//! ([`S3ObjectStore`]), Google Cloud Storage ([`GcsObjectStore`]) or Azure Blob
+//! Storage ([`AzureBlobObjectStore`]). Keys are `/`-separated relative paths.
+//!
+//! It backs message attachments, evaluation report history, cached model files
+//! ([`ObjectFileCache`]) and vector index snapshots, so all of them can live in the
+//! same bucket under different prefixes ([`PrefixedObjectStore`]).
+
+mod azure;
+mod gcs;
+mod s3;
+
+pub use azure::AzureBlobObjectStore;
+pub use gcs::GcsObjectStore;
+pub use s3::S3ObjectStore;
+
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::error::{Error, Result};
+
+/// Binary object storage
+#[async_trait]
+pub trait ObjectStore: Send + Sync {
+    /// Store `data` under `key`, replacing any existing object
+    async fn put(&self, key: &str, content_type: &str, data: Vec<u8>) -> Result<()>;
+
+    /// Read an object, returning [`Error::NotFound`] if it does not exist
+    async fn get(&self, key: &str) -> Result<Vec<u8>>;
+
+    /// Delete an object; deleting a missing object succeeds
+    async fn delete(&self, key: &str) -> Result<()>;
+
+    /// Keys of all objects starting with `prefix`, sorted
+    async fn list(&self, prefix: &str) -> Result<Vec<String>>;
+
+    /// Whether an object exists
+    async fn exists(&self, key: &str) -> Result<bool> {
+        match self.get(key).await {
+            Ok(_) => Ok(true),
+            Err(Error::NotFound(_)) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Create an object store from a URL
+///
+/// Supported forms are `s3://bucket/prefix`, `gs://bucket/prefix`,
+/// `az://container/prefix`, `file:///path` and plain filesystem paths. Cloud
+/// credentials are read from the environment, see the `from_env` constructor of
+/// each store; `AWS_ENDPOINT_URL` points S3 URLs at a compatible service such as MinIO.
+pub fn object_store_from_url(url: &str) -> Result<Arc<dyn ObjectStore>> {
+    let Some((scheme, location)) = url.split_once("://") else {
+        return Ok(Arc::new(LocalObjectStore::new(url)));
+    };
+    if scheme == "file" {
+        return Ok(Arc::new(LocalObjectStore::new(location)));
+    }
+
+    let (bucket, prefix) = location.split_once('/').unwrap_or((location, ""));
+    if bucket.is_empty() {
+        return Err(Error::Configuration(format!("Object store URL '{}' has no bucket", url)));
+    }
+    let store: Arc<dyn ObjectStore> = match scheme {
+        "s3" => {
+            let store = S3ObjectStore::from_env(bucket)?;
+            match std::env::var("AWS_ENDPOINT_URL").ok().filter(|endpoint| !endpoint.is_empty()) {
+                Some(endpoint) => Arc::new(store.with_endpoint(endpoint)),
+                None => Arc::new(store),
+            }
+        },
+        "gs" => Arc::new(GcsObjectStore::from_env(bucket)),
+        "az" => Arc::new(AzureBlobObjectStore::from_env(bucket)?),
+        _ => return Err(Error::Configuration(format!("Unsupported object store URL scheme '{}'", scheme))),
+    };
+    if prefix.trim_matches('/').is_empty() {
+        Ok(store)
+    } else {
+        Ok(Arc::new(PrefixedObjectStore::new(store, prefix)))
+    }
+}
+
+/// Object store that places every key under a prefix of another store
+#[derive(Clone)]
+pub struct PrefixedObjectStore {
+    inner: Arc<dyn ObjectStore>,
+    prefix: String,
+}
+
+impl PrefixedObjectStore {
+    /// Use the keys under `prefix` in `inner`; a trailing `/` is added when missing
+    pub fn new(inner: Arc<dyn ObjectStore>, prefix: impl Into<String>) -> Self {
+        let mut prefix = prefix.into().trim_matches('/').to_string();
+        if !prefix.is_empty() {
+            prefix.push('/');
+        }
+        Self { inner, prefix }
+    }
+
+    /// The prefix, ending in `/` unless empty
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    fn key(&self, key: &str) -> String {
+        format!("{}{}", self.prefix, key)
+    }
+}
+
+#[async_trait]
+impl ObjectStore for PrefixedObjectStore {
+    async fn put(&self, key: &str, content_type: &str, data: Vec<u8>) -> Result<()> {
+        check_key(key)?;
+        self.inner.put(&self.key(key), content_type, data).await
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>> {
+        check_key(key)?;
+        self.inner.get(&self.key(key)).await
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        check_key(key)?;
+        self.inner.delete(&self.key(key)).await
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        let keys = self.inner.list(&self.key(prefix)).await?;
+        Ok(keys.into_iter().filter_map(|key| key.strip_prefix(&self.prefix).map(str::to_string)).collect())
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool> {
+        check_key(key)?;
+        self.inner.exists(&self.key(key)).await
+    }
+}
+
+/// Object store in a local directory
+#[derive(Debug, Clone)]
+pub struct LocalObjectStore {
+    root: PathBuf,
+}
+
+impl LocalObjectStore {
+    /// Store objects under `root`, which is created on the first write
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Root directory
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn path(&self, key: &str) -> Result<PathBuf> {
+        check_key(key)?;
+        Ok(self.root.join(key))
+    }
+}
+
+#[async_trait]
+impl ObjectStore for LocalObjectStore {
+    async fn put(&self, key: &str, _content_type: &str, data: Vec<u8>) -> Result<()> {
+        write_file(&self.path(key)?, &data).await
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>> {
+        match tokio::fs::read(self.path(key)?).await {
+            Ok(data) => Ok(data),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Err(not_found(key)),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        match tokio::fs::remove_file(self.path(key)?).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        let mut keys = Vec::new();
+        let mut pending = vec![(self.root.clone(), String::new())];
+        while let Some((dir, dir_key)) = pending.pop() {
+            let mut entries = match tokio::fs::read_dir(&dir).await {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e.into()),
+            };
+            while let Some(entry) = entries.next_entry().await? {
+                let Ok(name) = entry.file_name().into_string() else {
+                    continue;
+                };
+                let key = format!("{}{}", dir_key, name);
+                if entry.file_type().await?.is_dir() {
+                    // Only descend into directories that can contain matching keys
+                    let dir_prefix = format!("{}/", key);
+                    if dir_prefix.starts_with(prefix) || prefix.starts_with(&dir_prefix) {
+                        pending.push((entry.path(), dir_prefix));
+                    }
+                } else if key.starts_with(prefix) && !name.ends_with(PARTIAL_SUFFIX) {
+                    keys.push(key);
+                }
+            }
+        }
+        keys.sort();
+        Ok(keys)
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool> {
+        Ok(tokio::fs::try_exists(self.path(key)?).await?)
+    }
+}
+
+/// Local cache of objects, for files that must exist on disk such as model weights
+///
+/// The first [`fetch`](Self::fetch) of a key downloads the object into the cache
+/// directory; later calls return the cached file without contacting the store.
+#[derive(Clone)]
+pub struct ObjectFileCache {
+    store: Arc<dyn ObjectStore>,
+    dir: PathBuf,
+}
+
+impl ObjectFileCache {
+    /// Cache objects of `store` in `dir`
+    pub fn new(store: Arc<dyn ObjectStore>, dir: impl Into<PathBuf>) -> Self {
+        Self { store, dir: dir.into() }
+    }
+
+    /// Cache directory
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Local path of an object, downloading it if it is not cached yet
+    pub async fn fetch(&self, key: &str) -> Result<PathBuf> {
+        let path = self.path(key)?;
+        if !tokio::fs::try_exists(&path).await? {
+            download_file(self.store.as_ref(), key, &path).await?;
+        }
+        Ok(path)
+    }
+
+    /// Remove a cached file so the next fetch downloads it again
+    pub async fn evict(&self, key: &str) -> Result<()> {
+        match tokio::fs::remove_file(self.path(key)?).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn path(&self, key: &str) -> Result<PathBuf> {
+        check_key(key)?;
+        Ok(self.dir.join(key))
+    }
+}
+
+/// Upload the file at `path` as `key`
+pub async fn upload_file(store: &dyn ObjectStore, key: &str, path: &Path, content_type: &str) -> Result<()> {
+    let data = tokio::fs::read(path).await?;
+    store.put(key, content_type, data).await
+}
+
+/// Download `key` to `path`, replacing the file only once the download has completed
+pub async fn download_file(store: &dyn ObjectStore, key: &str, path: &Path) -> Result<()> {
+    let data = store.get(key).await?;
+    write_file(path, &data).await
+}
+
+/// Suffix of files being written, renamed into place when complete
+const PARTIAL_SUFFIX: &str = ".partial";
+
+/// Write a file through a temporary file so readers never see partial content
+pub(crate) async fn write_file(path: &Path, data: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(format!(".{}{}", uuid::Uuid::new_v4().simple(), PARTIAL_SUFFIX));
+    let partial = PathBuf::from(partial);
+    tokio::fs::write(&partial, data).await?;
+    if let Err(e) = tokio::fs::rename(&partial, path).await {
+        let _ = tokio::fs::remove_file(&partial).await;
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+/// Reject keys that are empty, absolute or contain `.` or `..` segments
+pub(crate) fn check_key(key: &str) -> Result<()> {
+    let valid = !key.is_empty()
+        && !key.starts_with('/')
+        && !key.contains('\\')
+        && Path::new(key).components().all(|component| matches!(component, Component::Normal(_)))
+        && key.split('/').all(|segment| !segment.is_empty() && segment != "." && segment != "..");
+    if valid {
+        Ok(())
+    } else {
+        Err(Error::InvalidInput(format!("Invalid object key: {}", key)))
+    }
+}
+
+fn not_found(key: &str) -> Error {
+    Error::NotFound(format!("Object '{}' not found", key))
+}
+
+/// Convert a failed response into an error, mapping 404 to [`Error::NotFound`]
+async fn check_response(service: &str, key: &str, response: reqwest::Response) -> Result<reqwest::Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    if status == reqwest::StatusCode::NOT_FOUND {
+        return Err(not_found(key));
+    }
+    let body = response.text().await.unwrap_or_default();
+    Err(Error::Storage(format!("{} request for '{}' failed with {}: {}", service, key, status, body)))
+}
+
+/// Percent-encode everything except unreserved characters, and `/` when `keep_slash` is set
+fn uri_encode(value: &str, keep_slash: bool) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => encoded.push(byte as char),
+            b'/' if keep_slash => encoded.push('/'),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Text of every `<tag>` element in an XML document
+fn xml_values(xml: &str, tag: &str) -> Vec<String> {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+    let mut values = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find(&open) {
+        rest = &rest[start + open.len()..];
+        let Some(end) = rest.find(&close) else {
+            break;
+        };
+        values.push(
+            rest[..end]
+                .replace("&lt;", "<")
+                .replace("&gt;", ">")
+                .replace("&quot;", "\"")
+                .replace("&apos;", "'")
+                .replace("&amp;", "&"),
+        );
+        rest = &rest[end + close.len()..];
+    }
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_local_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = LocalObjectStore::new(dir.path());
+
+        store.put("reports/a.json", "application/json", b"{}".to_vec()).await.unwrap();
+        store.put("reports/nested/b.json", "application/json", b"[]".to_vec()).await.unwrap();
+        store.put("models/tokenizer.json", "application/json", b"tok".to_vec()).await.unwrap();
+        assert_eq!(store.get("reports/a.json").await.unwrap(), b"{}");
+        assert!(store.exists("models/tokenizer.json").await.unwrap());
+
+        assert_eq!(store.list("reports/").await.unwrap(), vec!["reports/a.json", "reports/nested/b.json"]);
+        assert_eq!(store.list("rep").await.unwrap().len(), 2);
+        assert_eq!(store.list("").await.unwrap().len(), 3);
+
+        store.delete("reports/a.json").await.unwrap();
+        store.delete("reports/a.json").await.unwrap();
+        assert!(matches!(store.get("reports/a.json").await, Err(Error::NotFound(_))));
+        assert!(!store.exists("reports/a.json").await.unwrap());
+
+        for key in ["", "/etc/passwd", "../secret", "a/../b", "a//b", "a\\b"] {
+            assert!(matches!(store.get(key).await, Err(Error::InvalidInput(_))), "{}", key);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prefixed_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let root: Arc<dyn ObjectStore> = Arc::new(LocalObjectStore::new(dir.path()));
+        let attachments = PrefixedObjectStore::new(root.clone(), "/attachments/");
+        assert_eq!(attachments.prefix(), "attachments/");
+
+        attachments.put("id/a.txt", "text/plain", b"a".to_vec()).await.unwrap();
+        root.put("other/b.txt", "text/plain", b"b".to_vec()).await.unwrap();
+        assert_eq!(root.get("attachments/id/a.txt").await.unwrap(), b"a");
+        assert_eq!(attachments.list("").await.unwrap(), vec!["id/a.txt"]);
+        assert!(matches!(attachments.get("../other/b.txt").await, Err(Error::InvalidInput(_))));
+    }
+
+    #[tokio::test]
+    async fn test_file_cache() {
+        let remote = tempfile::tempdir().unwrap();
+        let cache_dir = tempfile::tempdir().unwrap();
+        let store: Arc<dyn ObjectStore> = Arc::new(LocalObjectStore::new(remote.path()));
+        store.put("models/small/weights.bin", "application/octet-stream", vec![1, 2, 3]).await.unwrap();
+
+        let cache = ObjectFileCache::new(store.clone(), cache_dir.path());
+        let path = cache.fetch("models/small/weights.bin").await.unwrap();
+        assert_eq!(path, cache_dir.path().join("models/small/weights.bin"));
+        assert_eq!(std::fs::read(&path).unwrap(), vec![1, 2, 3]);
+
+        // Cached files are served without the store
+        store.delete("models/small/weights.bin").await.unwrap();
+        assert_eq!(cache.fetch("models/small/weights.bin").await.unwrap(), path);
+        cache.evict("models/small/weights.bin").await.unwrap();
+        assert!(matches!(cache.fetch("models/small/weights.bin").await, Err(Error::NotFound(_))));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_store_from_url() {
+        assert!(object_store_from_url("/tmp/objects").is_ok());
+        assert!(object_store_from_url("file:///tmp/objects").is_ok());
+        assert!(object_store_from_url("gs://bucket/prefix").is_ok());
+        assert!(matches!(object_store_from_url("ftp://host/path"), Err(Error::Configuration(_))));
+        assert!(matches!(object_store_from_url("s3:///prefix"), Err(Error::Configuration(_))));
+    }
+
+    #[test]
+    fn test_helpers() {
+        assert_eq!(uri_encode("a b/c+d", true), "a%20b/c%2Bd");
+        assert_eq!(uri_encode("a b/c", false), "a%20b%2Fc");
+        assert_eq!(
+            xml_values("<r><Key>a&amp;b</Key><Key>c</Key><Other>x</Other></r>", "Key"),
+            vec!["a&b", "c"]
+        );
+    }
+}
@@ -392,6 +392,7 @@ impl TraceCollector for InMemoryMetricsCollector {
                     StepType::LlmCall => "llm_call",
                     StepType::ToolCall => "tool_call",
                     StepType::MemoryOperation => "memory_operation",
+                    StepType::Retrieval => "retrieval",
                     StepType::DataProcessing => "data_processing",
                     StepType::Validation => "validation",
                     StepType::Transformation => "transformation",
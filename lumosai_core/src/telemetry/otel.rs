@@ -4,6 +4,7 @@
 
 use crate::telemetry::metrics::*;
 use crate::telemetry::trace::*;
+use crate::config::http_client_for;
 use async_trait::async_trait;
 use std::collections::HashMap;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
@@ -229,8 +230,8 @@ impl HttpOtlpExporter {
         headers.insert("Content-Type".to_string(), "application/x-protobuf".to_string());
         
         Self {
+            client: http_client_for(&endpoint),
             endpoint,
-            client: reqwest::Client::new(),
             headers,
             timeout: Duration::from_secs(10),
         }
@@ -237,6 +237,30 @@ async fn test_trace_builder() {
     assert!(trace.steps[1].success);
 }
 
+#[test]
+fn test_trace_token_usage() {
+    let mut trace = crate::telemetry::ExecutionTrace::new("test_agent".to_string());
+    
+    let mut llm = TraceStep::new("LLM call".to_string(), StepType::LlmCall);
+    llm.set_token_usage(&TokenUsage { prompt_tokens: 120, completion_tokens: 30, total_tokens: 150 });
+    
+    let mut nested = TraceStep::new("Summarize".to_string(), StepType::LlmCall);
+    nested.set_token_usage(&TokenUsage { prompt_tokens: 40, completion_tokens: 10, total_tokens: 50 });
+    let mut retrieval = TraceStep::new("Search docs".to_string(), StepType::Retrieval);
+    retrieval.add_child(nested);
+    
+    trace.steps.push(llm);
+    trace.steps.push(retrieval);
+    
+    assert_eq!(trace.steps[0].token_usage().unwrap().total_tokens, 150);
+    assert!(trace.steps[1].token_usage().is_none());
+    
+    let total = trace.token_usage();
+    assert_eq!(total.prompt_tokens, 160);
+    assert_eq!(total.completion_tokens, 40);
+    assert_eq!(total.total_tokens, 200);
+}
+
 #[tokio::test]
 async fn test_filesystem_metrics_collector() {
     use tempfile::TempDir;
@@ -3,6 +3,11 @@ use std::collections::HashMap;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use uuid::Uuid;
 use async_trait::async_trait;
+use super::metrics::TokenUsage;
+
+/// 步骤元数据中记录Token用量的键
+const PROMPT_TOKENS_KEY: &str = "prompt_tokens";
+const COMPLETION_TOKENS_KEY: &str = "completion_tokens";
 
 /// 执行追踪数据结构
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -70,6 +75,8 @@ pub enum StepType {
     ToolCall,
     /// 内存操作
     MemoryOperation,
+    /// 检索
+    Retrieval,
     /// 数据处理
     DataProcessing,
     /// 验证
@@ -349,6 +356,15 @@ impl ExecutionTrace {
             .cloned()
             .collect()
     }
+    
+    /// 汇总所有步骤（含子步骤）的Token用量
+    pub fn token_usage(&self) -> TokenUsage {
+        let mut total = TokenUsage::default();
+        for step in &self.steps {
+            step.accumulate_token_usage(&mut total);
+        }
+        total
+    }
 }
 
 impl TraceStep {
@@ -386,6 +402,37 @@ impl TraceStep {
         self.duration_ms = duration.as_millis() as u64;
         self.end_time = self.start_time + self.duration_ms;
     }
+    
+    /// 记录本步骤的Token用量
+    pub fn set_token_usage(&mut self, usage: &TokenUsage) {
+        self.metadata.insert(PROMPT_TOKENS_KEY.to_string(), serde_json::Value::from(usage.prompt_tokens));
+        self.metadata.insert(COMPLETION_TOKENS_KEY.to_string(), serde_json::Value::from(usage.completion_tokens));
+    }
+    
+    /// 本步骤自身记录的Token用量，不含子步骤
+    pub fn token_usage(&self) -> Option<TokenUsage> {
+        let read = |key: &str| self.metadata.get(key).and_then(|v| v.as_u64()).map(|v| v as u32);
+        let (prompt_tokens, completion_tokens) = match (read(PROMPT_TOKENS_KEY), read(COMPLETION_TOKENS_KEY)) {
+            (None, None) => return None,
+            (prompt, completion) => (prompt.unwrap_or(0), completion.unwrap_or(0)),
+        };
+        Some(TokenUsage {
+            prompt_tokens,
+            completion_tokens,
+            total_tokens: prompt_tokens + completion_tokens,
+        })
+    }
+    
+    fn accumulate_token_usage(&self, total: &mut TokenUsage) {
+        if let Some(usage) = self.token_usage() {
+            total.prompt_tokens += usage.prompt_tokens;
+            total.completion_tokens += usage.completion_tokens;
+            total.total_tokens += usage.total_tokens;
+        }
+        for child in &self.children {
+            child.accumulate_token_usage(total);
+        }
+    }
 }
 
 impl Default for TraceQuery {
@@ -0,0 +1,578 @@
+//! Date and time tool
+//!
+//! The `datetime` tool gives agents the current time in any timezone, converts between
+//! timezones, does calendar-aware date arithmetic and parses dates written as timestamps,
+//! common formats or natural language such as "next friday at 3pm" or "in 2 weeks".
+
+use std::fmt::Write;
+
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, LocalResult, Months, NaiveDate, NaiveDateTime, NaiveTime,
+    TimeZone, Timelike, Utc, Weekday,
+};
+use chrono_tz::Tz;
+use regex::Regex;
+use serde_json::{json, Value};
+
+use crate::tool::{FunctionTool, ParameterSchema, ToolSchema};
+use crate::{Error, Result};
+
+/// Default output format of the `formatted` field
+pub const DEFAULT_DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Operations of the `datetime` tool
+const OPERATIONS: &[&str] = &["now", "parse", "format", "add", "subtract", "convert", "diff"];
+
+/// Naive datetime formats accepted by [`parse_datetime`]
+const DATETIME_FORMATS: &[&str] = &[
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M",
+    "%Y/%m/%d %H:%M:%S",
+    "%Y/%m/%d %H:%M",
+];
+
+/// Date formats accepted by [`parse_datetime`]
+const DATE_FORMATS: &[&str] = &[
+    "%Y-%m-%d", "%Y/%m/%d", "%m/%d/%Y", "%d.%m.%Y", "%B %d, %Y", "%B %d %Y", "%b %d, %Y", "%b %d %Y",
+    "%d %B %Y", "%d %b %Y",
+];
+
+/// Abbreviations that are not IANA zone names
+const ZONE_ALIASES: &[(&str, &str)] = &[
+    ("PST", "America/Los_Angeles"),
+    ("PDT", "America/Los_Angeles"),
+    ("MDT", "America/Denver"),
+    ("CST", "America/Chicago"),
+    ("CDT", "America/Chicago"),
+    ("EDT", "America/New_York"),
+    ("BST", "Europe/London"),
+    ("CEST", "Europe/Paris"),
+    ("IST", "Asia/Kolkata"),
+    ("JST", "Asia/Tokyo"),
+    ("KST", "Asia/Seoul"),
+    ("AEST", "Australia/Sydney"),
+];
+
+/// Timezone of a `datetime` operation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Zone {
+    /// IANA timezone, with daylight saving time
+    Named(Tz),
+    /// Fixed UTC offset
+    Fixed(FixedOffset),
+    /// Timezone of the host
+    Local,
+}
+
+impl Zone {
+    /// Name of the timezone, or its offset for fixed offsets
+    pub fn name(&self) -> String {
+        match self {
+            Zone::Named(tz) => tz.name().to_string(),
+            Zone::Fixed(offset) => offset.to_string(),
+            Zone::Local => "Local".to_string(),
+        }
+    }
+
+    /// The instant `utc` in this timezone
+    pub fn from_utc(&self, utc: DateTime<Utc>) -> DateTime<FixedOffset> {
+        match self {
+            Zone::Named(tz) => utc.with_timezone(tz).fixed_offset(),
+            Zone::Fixed(offset) => utc.with_timezone(offset),
+            Zone::Local => utc.with_timezone(&chrono::Local).fixed_offset(),
+        }
+    }
+
+    /// Wall-clock time `naive` in this timezone
+    ///
+    /// Ambiguous times at the end of daylight saving time resolve to the earlier instant;
+    /// times skipped at its start move forward by the length of the gap.
+    pub fn resolve(&self, naive: NaiveDateTime) -> Result<DateTime<FixedOffset>> {
+        let resolve = |naive: NaiveDateTime| match self {
+            Zone::Named(tz) => tz.from_local_datetime(&naive).map(|dt| dt.fixed_offset()),
+            Zone::Fixed(offset) => offset.from_local_datetime(&naive),
+            Zone::Local => chrono::Local.from_local_datetime(&naive).map(|dt| dt.fixed_offset()),
+        };
+        match resolve(naive) {
+            LocalResult::Single(dt) | LocalResult::Ambiguous(dt, _) => Ok(dt),
+            LocalResult::None => resolve(naive + Duration::hours(1))
+                .earliest()
+                .ok_or_else(|| Error::InvalidInput(format!("{} does not exist in {}", naive, self.name()))),
+        }
+    }
+}
+
+/// Parse a timezone: an IANA name like `Asia/Shanghai`, `UTC`, `Local`, an offset like
+/// `+08:00` or `UTC-5`, or a common abbreviation like `PST`
+pub fn parse_timezone(name: &str) -> Result<Zone> {
+    let name = name.trim();
+    let upper = name.to_ascii_uppercase();
+    if name.eq_ignore_ascii_case("local") {
+        return Ok(Zone::Local);
+    }
+    if matches!(upper.as_str(), "UTC" | "GMT" | "Z" | "") {
+        return Ok(Zone::Named(Tz::UTC));
+    }
+    if let Some(offset) = parse_offset(upper.trim_start_matches("UTC").trim_start_matches("GMT")) {
+        return Ok(Zone::Fixed(offset));
+    }
+    if let Some((_, iana)) = ZONE_ALIASES.iter().find(|(alias, _)| *alias == upper) {
+        return Ok(Zone::Named(iana.parse().expect("zone aliases are valid")));
+    }
+    name.parse::<Tz>()
+        .or_else(|_| {
+            // Accept any capitalization of IANA names, e.g. "asia/shanghai"
+            chrono_tz::TZ_VARIANTS.iter().copied()
+                .find(|tz| tz.name().eq_ignore_ascii_case(name))
+                .ok_or(())
+        })
+        .map(Zone::Named)
+        .map_err(|_| Error::InvalidInput(format!("Unknown timezone '{}'", name)))
+}
+
+/// Offset such as `+08:00`, `-0530` or `+8`
+fn parse_offset(text: &str) -> Option<FixedOffset> {
+    let sign = match text.chars().next()? {
+        '+' => 1,
+        '-' => -1,
+        _ => return None,
+    };
+    let digits = text[1..].replace(':', "");
+    if digits.is_empty() || digits.len() > 4 || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let (hours, minutes) = match digits.len() {
+        1 | 2 => (digits.parse::<i32>().ok()?, 0),
+        len => (digits[..len - 2].parse::<i32>().ok()?, digits[len - 2..].parse::<i32>().ok()?),
+    };
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+/// Parse a date or time, interpreting times without an offset in `zone`
+///
+/// Accepts RFC 3339 and RFC 2822, Unix timestamps (optionally prefixed with `@`),
+/// common date and datetime formats, and natural language relative to `now`: `now`,
+/// `today`, `tomorrow`, `yesterday`, `in 3 days`, `2 hours ago`, `next friday`,
+/// `last month`, optionally followed by a time like `at 5pm`, `17:30` or `noon`.
+pub fn parse_datetime(input: &str, zone: Zone, now: DateTime<Utc>) -> Result<DateTime<FixedOffset>> {
+    let input = input.trim();
+    let unparsable = || Error::InvalidInput(format!("Cannot parse date '{}'", input));
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(input).or_else(|_| DateTime::parse_from_rfc2822(input)) {
+        return Ok(zone.from_utc(dt.with_timezone(&Utc)));
+    }
+    let timestamp = input.strip_prefix('@').unwrap_or(input);
+    if (9..=11).contains(&timestamp.len()) && timestamp.chars().all(|c| c.is_ascii_digit()) {
+        let utc = timestamp.parse().ok().and_then(|seconds| DateTime::from_timestamp(seconds, 0)).ok_or_else(unparsable)?;
+        return Ok(zone.from_utc(utc));
+    }
+    if let Some(naive) = parse_naive_datetime(input) {
+        return zone.resolve(naive);
+    }
+    if let Some(date) = parse_naive_date(input) {
+        return zone.resolve(date.and_time(NaiveTime::MIN));
+    }
+    parse_natural(&input.to_lowercase(), zone, zone.from_utc(now)).ok_or_else(unparsable)?
+}
+
+fn parse_naive_datetime(text: &str) -> Option<NaiveDateTime> {
+    DATETIME_FORMATS.iter().find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+}
+
+fn parse_naive_date(text: &str) -> Option<NaiveDate> {
+    DATE_FORMATS.iter().find_map(|format| NaiveDate::parse_from_str(text, format).ok())
+}
+
+/// Day named by the first part of a natural language date
+enum Day {
+    /// A specific instant, such as "now" or "in 3 hours"
+    Instant(DateTime<FixedOffset>),
+    /// A calendar day, such as "tomorrow"
+    Date(NaiveDate),
+}
+
+/// Parse natural language; `None` if the text is not understood
+fn parse_natural(text: &str, zone: Zone, now: DateTime<FixedOffset>) -> Option<Result<DateTime<FixedOffset>>> {
+    let time_re = Regex::new(
+        r"^(?P<day>.*?)\s*(?:\bat\s+)?\b(?:(?P<word>noon|midnight)|(?P<h12>\d{1,2})(?::(?P<m12>\d{2}))?\s*(?P<ampm>am|pm)|(?P<h24>\d{1,2}):(?P<m24>\d{2})|at\s+(?P<hour>\d{1,2}))$",
+    ).expect("time pattern is valid");
+
+    let (day_text, time) = match time_re.captures(text) {
+        Some(captures) => {
+            let number = |name: &str| captures.name(name).and_then(|m| m.as_str().parse::<u32>().ok());
+            let time = if let Some(word) = captures.name("word") {
+                NaiveTime::from_hms_opt(if word.as_str() == "noon" { 12 } else { 0 }, 0, 0)
+            } else if let Some(hour) = number("h12") {
+                if !(1..=12).contains(&hour) {
+                    return None;
+                }
+                let hour = hour % 12 + if &captures["ampm"] == "pm" { 12 } else { 0 };
+                NaiveTime::from_hms_opt(hour, number("m12").unwrap_or(0), 0)
+            } else if let Some(hour) = number("h24") {
+                NaiveTime::from_hms_opt(hour, number("m24")?, 0)
+            } else {
+                NaiveTime::from_hms_opt(number("hour")?, 0, 0)
+            };
+            (captures["day"].trim().to_string(), Some(time?))
+        },
+        None => (text.to_string(), None),
+    };
+
+    let day = match day_text.as_str() {
+        "" if time.is_some() => Day::Date(now.date_naive()),
+        "now" | "right now" => Day::Instant(now),
+        "today" | "tonight" => Day::Date(now.date_naive()),
+        "tomorrow" => Day::Date(now.date_naive().succ_opt()?),
+        "yesterday" => Day::Date(now.date_naive().pred_opt()?),
+        "day after tomorrow" | "the day after tomorrow" => Day::Date(now.date_naive() + Duration::days(2)),
+        "day before yesterday" | "the day before yesterday" => Day::Date(now.date_naive() - Duration::days(2)),
+        other => match parse_naive_date(other) {
+            Some(date) => Day::Date(date),
+            None => parse_relative_day(other, zone, now)?,
+        },
+    };
+
+    Some(match (day, time) {
+        (Day::Instant(instant), None) => Ok(instant),
+        (Day::Instant(instant), Some(time)) => zone.resolve(instant.date_naive().and_time(time)),
+        (Day::Date(date), time) => zone.resolve(date.and_time(time.unwrap_or(NaiveTime::MIN))),
+    })
+}
+
+/// "in 3 days", "2 weeks ago", "next friday", "last month" and similar
+fn parse_relative_day(text: &str, zone: Zone, now: DateTime<FixedOffset>) -> Option<Day> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let count = |word: &str| match word {
+        "a" | "an" | "one" => Some(1),
+        "two" => Some(2),
+        "three" => Some(3),
+        other => other.parse::<i64>().ok(),
+    };
+
+    let (amount, unit) = match words.as_slice() {
+        ["in", n, unit] | [n, unit, "from", "now"] | [n, unit, "later"] => (count(n)?, *unit),
+        [n, unit, "ago"] => (-count(n)?, *unit),
+        ["next", unit] | ["this", unit] | [unit] => {
+            let next = words[0] == "next";
+            if let Some(weekday) = parse_weekday(unit) {
+                let today = now.date_naive();
+                let ahead = (7 + weekday.num_days_from_monday() as i64 - today.weekday().num_days_from_monday() as i64) % 7;
+                let ahead = if next && ahead == 0 { 7 } else { ahead };
+                return Some(Day::Date(today + Duration::days(ahead)));
+            }
+            if !next {
+                return None;
+            }
+            (1, *unit)
+        },
+        ["last", unit] | ["previous", unit] => {
+            if let Some(weekday) = parse_weekday(unit) {
+                let today = now.date_naive();
+                let behind = (7 + today.weekday().num_days_from_monday() as i64 - weekday.num_days_from_monday() as i64) % 7;
+                return Some(Day::Date(today - Duration::days(if behind == 0 { 7 } else { behind })));
+            }
+            (-1, *unit)
+        },
+        _ => return None,
+    };
+
+    shift(now, zone, amount, unit).ok().map(Day::Instant)
+}
+
+fn parse_weekday(text: &str) -> Option<Weekday> {
+    match text.trim_end_matches('s') {
+        "monday" | "mon" => Some(Weekday::Mon),
+        "tuesday" | "tue" | "tues" => Some(Weekday::Tue),
+        "wednesday" | "wed" => Some(Weekday::Wed),
+        "thursday" | "thu" | "thur" | "thurs" => Some(Weekday::Thu),
+        "friday" | "fri" => Some(Weekday::Fri),
+        "saturday" | "sat" => Some(Weekday::Sat),
+        "sunday" | "sun" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+/// Move `dt` by `amount` units
+///
+/// Seconds, minutes and hours are exact durations. Days and weeks keep the wall-clock
+/// time across daylight saving changes, and months and years clamp to the last day of
+/// shorter months (Jan 31 + 1 month = Feb 28 or 29).
+pub fn shift(dt: DateTime<FixedOffset>, zone: Zone, amount: i64, unit: &str) -> Result<DateTime<FixedOffset>> {
+    let overflow = || Error::InvalidInput(format!("Date out of range after adding {} {}", amount, unit));
+    let local = dt.naive_local();
+    let unit = unit.trim().to_ascii_lowercase();
+    let unit = unit.strip_suffix('s').unwrap_or(&unit);
+
+    let exact = |duration: Option<Duration>| -> Result<DateTime<FixedOffset>> {
+        let utc = dt.with_timezone(&Utc).checked_add_signed(duration.ok_or_else(overflow)?).ok_or_else(overflow)?;
+        Ok(zone.from_utc(utc))
+    };
+    let wall_clock = |local: Option<NaiveDateTime>| zone.resolve(local.ok_or_else(overflow)?);
+    let months = |months: i64| {
+        if months >= 0 {
+            local.checked_add_months(Months::new(u32::try_from(months).ok()?))
+        } else {
+            local.checked_sub_months(Months::new(u32::try_from(-months).ok()?))
+        }
+    };
+
+    match unit {
+        "second" | "sec" | "s" => exact(Duration::try_seconds(amount)),
+        "minute" | "min" | "m" => exact(Duration::try_minutes(amount)),
+        "hour" | "hr" | "h" => exact(Duration::try_hours(amount)),
+        "day" | "d" => wall_clock(Duration::try_days(amount).and_then(|d| local.checked_add_signed(d))),
+        "week" | "w" => wall_clock(Duration::try_weeks(amount).and_then(|d| local.checked_add_signed(d))),
+        "month" => wall_clock(months(amount)),
+        "year" | "y" => wall_clock(amount.checked_mul(12).and_then(months)),
+        other => Err(Error::InvalidInput(format!(
+            "Unknown unit '{}'; use seconds, minutes, hours, days, weeks, months or years", other
+        ))),
+    }
+}
+
+/// Difference from `start` to `end` as JSON
+fn describe_duration(start: DateTime<FixedOffset>, end: DateTime<FixedOffset>) -> Value {
+    let seconds = (end - start).num_seconds();
+    let abs = seconds.unsigned_abs();
+    let (days, hours, minutes, rest) = (abs / 86_400, abs % 86_400 / 3600, abs % 3600 / 60, abs % 60);
+    let mut parts: Vec<String> = [(days, "day"), (hours, "hour"), (minutes, "minute"), (rest, "second")]
+        .iter()
+        .filter(|(value, _)| *value > 0)
+        .map(|(value, unit)| format!("{} {}{}", value, unit, if *value == 1 { "" } else { "s" }))
+        .collect();
+    if parts.is_empty() {
+        parts.push("0 seconds".to_string());
+    }
+    let human = parts.join(" ");
+    json!({
+        "seconds": seconds,
+        "minutes": seconds as f64 / 60.0,
+        "hours": seconds as f64 / 3600.0,
+        "days": seconds as f64 / 86_400.0,
+        "human": if seconds < 0 { format!("-{}", human) } else { human },
+    })
+}
+
+/// Fields describing `dt`
+fn describe(dt: DateTime<FixedOffset>, zone: Zone, format: &str) -> Result<Value> {
+    let mut formatted = String::new();
+    write!(formatted, "{}", dt.format(format))
+        .map_err(|_| Error::InvalidInput(format!("Invalid format string '{}'", format)))?;
+    Ok(json!({
+        "iso": dt.to_rfc3339(),
+        "timestamp": dt.timestamp(),
+        "formatted": formatted,
+        "date": dt.format("%Y-%m-%d").to_string(),
+        "time": dt.format("%H:%M:%S").to_string(),
+        "weekday": dt.format("%A").to_string(),
+        "timezone": zone.name(),
+        "utc_offset": dt.offset().to_string(),
+        "hour": dt.hour(),
+    }))
+}
+
+/// Run one operation of the `datetime` tool at the instant `now`
+fn run(params: &Value, now: DateTime<Utc>) -> Result<Value> {
+    let text = |name: &str| params.get(name).and_then(|v| v.as_str()).filter(|v| !v.trim().is_empty());
+    let operation = text("operation").ok_or_else(|| Error::InvalidInput("Operation is required".to_string()))?;
+    let format = text("format").unwrap_or(DEFAULT_DATETIME_FORMAT);
+    let zone = parse_timezone(text("timezone").unwrap_or("UTC"))?;
+    let at = |name: &str| match text(name) {
+        Some(input) => parse_datetime(input, zone, now),
+        None => Ok(zone.from_utc(now)),
+    };
+
+    let result = match operation {
+        "now" => describe(zone.from_utc(now), zone, format)?,
+        "parse" => {
+            let input = text("input").ok_or_else(|| Error::InvalidInput("Input is required for parse".to_string()))?;
+            describe(parse_datetime(input, zone, now)?, zone, format)?
+        },
+        "format" => describe(at("input")?, zone, format)?,
+        "add" | "subtract" => {
+            let amount = params.get("amount").and_then(|v| v.as_i64().or_else(|| v.as_f64().map(|f| f.round() as i64)))
+                .ok_or_else(|| Error::InvalidInput("Amount is required for add and subtract".to_string()))?;
+            let amount = if operation == "subtract" { -amount } else { amount };
+            describe(shift(at("input")?, zone, amount, text("unit").unwrap_or("days"))?, zone, format)?
+        },
+        "convert" => {
+            let target = parse_timezone(text("to_timezone").ok_or_else(|| {
+                Error::InvalidInput("to_timezone is required for convert".to_string())
+            })?)?;
+            let dt = target.from_utc(at("input")?.with_timezone(&Utc));
+            describe(dt, target, format)?
+        },
+        "diff" => describe_duration(at("input")?, at("end")?),
+        other => {
+            return Ok(json!({
+                "success": false,
+                "error": format!("Unknown operation: {}", other),
+                "supported_operations": OPERATIONS,
+            }))
+        },
+    };
+    Ok(json!({ "success": true, "operation": operation, "result": result }))
+}
+
+fn string_parameter(name: &str, description: &str, required: bool, default: Option<&str>) -> ParameterSchema {
+    ParameterSchema {
+        name: name.to_string(),
+        description: description.to_string(),
+        r#type: "string".to_string(),
+        required,
+        properties: None,
+        default: default.map(|default| json!(default)),
+    }
+}
+
+/// Create the `datetime` tool
+pub fn create_datetime_tool() -> FunctionTool {
+    let schema = ToolSchema::new(vec![
+        string_parameter(
+            "operation",
+            "Operation to perform: now, parse, format, add, subtract, convert (to another timezone) or diff",
+            true,
+            None,
+        ),
+        string_parameter(
+            "input",
+            "Date to operate on, defaulting to now. Accepts ISO 8601, Unix timestamps, dates like \
+             2024-05-01 or May 1, 2024, and phrases like 'tomorrow at 9am', 'next friday' or 'in 3 days'",
+            false,
+            None,
+        ),
+        string_parameter("format", "strftime format of the 'formatted' field", false, Some(DEFAULT_DATETIME_FORMAT)),
+        string_parameter(
+            "timezone",
+            "Timezone for the result and for inputs without an offset: an IANA name such as \
+             America/New_York, UTC, Local, or an offset such as +08:00",
+            false,
+            Some("UTC"),
+        ),
+        ParameterSchema {
+            name: "amount".to_string(),
+            description: "Amount to add or subtract".to_string(),
+            r#type: "number".to_string(),
+            required: false,
+            properties: None,
+            default: None,
+        },
+        string_parameter(
+            "unit",
+            "Unit to add or subtract: seconds, minutes, hours, days, weeks, months or years",
+            false,
+            Some("days"),
+        ),
+        string_parameter("to_timezone", "Target timezone for convert", false, None),
+        string_parameter("end", "End date for diff, defaulting to now; 'input' is the start", false, None),
+    ]);
+
+    FunctionTool::new(
+        "datetime",
+        "Current date and time in any timezone, timezone conversion, date arithmetic and date parsing",
+        schema,
+        |params| match run(&params, Utc::now()) {
+            Ok(result) => Ok(result),
+            Err(Error::InvalidInput(message)) => Ok(json!({ "success": false, "error": message })),
+            Err(e) => Err(e),
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tool::{Tool, ToolExecutionContext, ToolExecutionOptions};
+
+    /// Wednesday 2024-03-06 15:30:00 UTC
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, 6, 15, 30, 0).unwrap()
+    }
+
+    fn parse(input: &str, zone: &str) -> String {
+        parse_datetime(input, parse_timezone(zone).unwrap(), now()).unwrap().to_rfc3339()
+    }
+
+    #[test]
+    fn test_timezones() {
+        assert_eq!(parse_timezone("Asia/Shanghai").unwrap(), Zone::Named(Tz::Asia__Shanghai));
+        assert_eq!(parse_timezone("asia/shanghai").unwrap(), Zone::Named(Tz::Asia__Shanghai));
+        assert_eq!(parse_timezone("PST").unwrap(), Zone::Named(Tz::America__Los_Angeles));
+        assert_eq!(parse_timezone("utc+8").unwrap(), Zone::Fixed(FixedOffset::east_opt(8 * 3600).unwrap()));
+        assert_eq!(parse_timezone("-05:30").unwrap(), Zone::Fixed(FixedOffset::west_opt(5 * 3600 + 1800).unwrap()));
+        assert!(parse_timezone("Mars/Olympus").is_err());
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse("2024-05-01T10:00:00Z", "Asia/Tokyo"), "2024-05-01T19:00:00+09:00");
+        assert_eq!(parse("2024-05-01 10:00", "America/New_York"), "2024-05-01T10:00:00-04:00");
+        assert_eq!(parse("May 1, 2024", "UTC"), "2024-05-01T00:00:00+00:00");
+        assert_eq!(parse("@1700000000", "UTC"), "2023-11-14T22:13:20+00:00");
+        assert_eq!(parse("now", "Asia/Shanghai"), "2024-03-06T23:30:00+08:00");
+        assert_eq!(parse("tomorrow at 9am", "UTC"), "2024-03-07T09:00:00+00:00");
+        assert_eq!(parse("yesterday noon", "UTC"), "2024-03-05T12:00:00+00:00");
+        assert_eq!(parse("in 3 hours", "UTC"), "2024-03-06T18:30:00+00:00");
+        assert_eq!(parse("2 weeks ago", "UTC"), "2024-02-21T15:30:00+00:00");
+        assert_eq!(parse("next friday at 17:45", "UTC"), "2024-03-08T17:45:00+00:00");
+        assert_eq!(parse("next wednesday", "UTC"), "2024-03-13T00:00:00+00:00");
+        assert_eq!(parse("wednesday", "UTC"), "2024-03-06T00:00:00+00:00");
+        assert_eq!(parse("last monday", "UTC"), "2024-03-04T00:00:00+00:00");
+        assert_eq!(parse("next month", "UTC"), "2024-04-06T15:30:00+00:00");
+        assert_eq!(parse("2024-05-01 at 3pm", "UTC"), "2024-05-01T15:00:00+00:00");
+        assert!(parse_datetime("sometime soon", Zone::Named(Tz::UTC), now()).is_err());
+    }
+
+    #[test]
+    fn test_shift() {
+        let zone = parse_timezone("America/New_York").unwrap();
+        let jan31 = parse_datetime("2024-01-31 12:00", zone, now()).unwrap();
+        assert_eq!(shift(jan31, zone, 1, "months").unwrap().to_rfc3339(), "2024-02-29T12:00:00-05:00");
+        assert_eq!(shift(jan31, zone, -1, "year").unwrap().to_rfc3339(), "2023-01-31T12:00:00-05:00");
+
+        // Daylight saving time starts on 2024-03-10 in New York
+        let before = parse_datetime("2024-03-09 12:00", zone, now()).unwrap();
+        assert_eq!(shift(before, zone, 1, "day").unwrap().to_rfc3339(), "2024-03-10T12:00:00-04:00");
+        assert_eq!(shift(before, zone, 24, "hours").unwrap().to_rfc3339(), "2024-03-10T13:00:00-04:00");
+        assert!(shift(before, zone, 1, "fortnight").is_err());
+    }
+
+    #[test]
+    fn test_operations() {
+        let result = run(&json!({"operation": "convert", "input": "2024-03-06 09:00", "timezone": "Europe/London", "to_timezone": "Asia/Kolkata"}), now()).unwrap();
+        assert_eq!(result["result"]["iso"], "2024-03-06T14:30:00+05:30");
+        assert_eq!(result["result"]["timezone"], "Asia/Kolkata");
+
+        let result = run(&json!({"operation": "add", "amount": 10, "unit": "days", "format": "%d/%m/%Y"}), now()).unwrap();
+        assert_eq!(result["result"]["formatted"], "16/03/2024");
+        assert_eq!(result["result"]["weekday"], "Saturday");
+
+        let result = run(&json!({"operation": "diff", "input": "2024-03-01", "end": "2024-03-02 01:30"}), now()).unwrap();
+        assert_eq!(result["result"]["seconds"], 91800);
+        assert_eq!(result["result"]["human"], "1 day 1 hour 30 minutes");
+
+        assert!(run(&json!({"operation": "now", "format": "%Q"}), now()).is_err());
+        let result = run(&json!({"operation": "teleport"}), now()).unwrap();
+        assert_eq!(result["success"], false);
+        assert_eq!(result["supported_operations"].as_array().unwrap().len(), OPERATIONS.len());
+    }
+
+    #[tokio::test]
+    async fn test_datetime_tool() {
+        let tool = create_datetime_tool();
+        let context = ToolExecutionContext::new();
+        let options = ToolExecutionOptions::new();
+
+        let response = tool.execute(json!({"operation": "now", "timezone": "Asia/Tokyo"}), context.clone(), &options).await.unwrap();
+        assert_eq!(response["success"], true);
+        assert_eq!(response["result"]["utc_offset"], "+09:00");
+
+        let response = tool.execute(json!({"operation": "parse", "input": "whenever"}), context, &options).await.unwrap();
+        assert_eq!(response["success"], false);
+        assert!(response["error"].as_str().unwrap().contains("whenever"));
+    }
+}
@@ -0,0 +1,594 @@
+//! HTTP request tool with a domain policy
+//!
+//! The `http_request` tool sends GET, POST and other requests on behalf of an agent.
+//! Every request, including each redirect, is checked against an [`HttpRequestPolicy`]:
+//! domain allowlist and denylist, permitted methods, and whether private network
+//! addresses may be reached. Responses are capped in size and time, and HTML bodies are
+//! converted to plain text so they fit in a model's context.
+
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use regex::Regex;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::base::Base;
+use crate::cancellation::run_until_cancelled;
+use crate::config::http_client_builder_for;
+use crate::tool::{ParameterSchema, Tool, ToolExecutionContext, ToolExecutionOptions, ToolSchema};
+use crate::{Error, Result};
+
+/// Which requests the `http_request` tool may send
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpRequestPolicy {
+    /// Domains that may be requested, including their subdomains; empty allows any domain
+    pub allowed_domains: Vec<String>,
+    /// Domains that may never be requested, including their subdomains; checked first
+    pub denied_domains: Vec<String>,
+    /// Permitted HTTP methods
+    pub allowed_methods: Vec<String>,
+    /// Whether loopback, private and link-local addresses may be requested
+    pub allow_private_networks: bool,
+    /// Bytes of response body kept; the rest is discarded and `truncated` is set
+    pub max_response_bytes: usize,
+    /// Time limit for the whole request, in seconds
+    pub timeout_secs: u64,
+    /// Redirects followed before giving up
+    pub max_redirects: usize,
+    /// Whether HTML bodies are converted to plain text
+    pub html_to_text: bool,
+}
+
+impl Default for HttpRequestPolicy {
+    fn default() -> Self {
+        Self {
+            allowed_domains: Vec::new(),
+            denied_domains: Vec::new(),
+            allowed_methods: ["GET", "HEAD", "POST", "PUT", "PATCH", "DELETE"].iter().map(|m| m.to_string()).collect(),
+            allow_private_networks: false,
+            max_response_bytes: 1024 * 1024,
+            timeout_secs: 30,
+            max_redirects: 5,
+            html_to_text: true,
+        }
+    }
+}
+
+impl HttpRequestPolicy {
+    /// Allow `domain` and its subdomains; once any domain is allowed, all others are refused
+    pub fn allow_domain(mut self, domain: impl Into<String>) -> Self {
+        self.allowed_domains.push(domain.into());
+        self
+    }
+
+    /// Refuse `domain` and its subdomains
+    pub fn deny_domain(mut self, domain: impl Into<String>) -> Self {
+        self.denied_domains.push(domain.into());
+        self
+    }
+
+    /// Restrict requests to `methods`, e.g. `["GET"]` for a read-only tool
+    pub fn with_methods<I, S>(mut self, methods: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.allowed_methods = methods.into_iter().map(|m| m.into().to_ascii_uppercase()).collect();
+        self
+    }
+
+    /// Allow or refuse loopback, private and link-local addresses
+    pub fn with_private_networks(mut self, allow: bool) -> Self {
+        self.allow_private_networks = allow;
+        self
+    }
+
+    /// Set the response body cap
+    pub fn with_max_response_bytes(mut self, bytes: usize) -> Self {
+        self.max_response_bytes = bytes;
+        self
+    }
+
+    /// Set the request time limit
+    pub fn with_timeout_secs(mut self, secs: u64) -> Self {
+        self.timeout_secs = secs;
+        self
+    }
+
+    /// Set how many redirects are followed
+    pub fn with_max_redirects(mut self, redirects: usize) -> Self {
+        self.max_redirects = redirects;
+        self
+    }
+
+    /// Enable or disable HTML to text conversion
+    pub fn with_html_to_text(mut self, enabled: bool) -> Self {
+        self.html_to_text = enabled;
+        self
+    }
+
+    /// Check that `method` is permitted
+    pub fn check_method(&self, method: &str) -> Result<()> {
+        if self.allowed_methods.iter().any(|allowed| allowed.eq_ignore_ascii_case(method)) {
+            Ok(())
+        } else {
+            Err(Error::AccessDenied(format!(
+                "HTTP method {} is not allowed; allowed methods: {}", method, self.allowed_methods.join(", ")
+            )))
+        }
+    }
+
+    /// Check the scheme and host of `url`
+    ///
+    /// Hosts given as IP addresses are checked against the private network rule here;
+    /// host names are checked once resolved, see [`HttpRequestPolicy::check_addresses`].
+    pub fn check_url(&self, url: &Url) -> Result<()> {
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(Error::AccessDenied(format!("Only http and https URLs are allowed, got {}", url.scheme())));
+        }
+        let host = url.host_str()
+            .ok_or_else(|| Error::InvalidInput(format!("URL {} has no host", url)))?
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .trim_end_matches('.')
+            .to_ascii_lowercase();
+
+        if let Some(domain) = self.denied_domains.iter().find(|domain| domain_matches(&host, domain)) {
+            return Err(Error::AccessDenied(format!("Requests to {} are denied by policy ({})", host, domain)));
+        }
+        if !self.allowed_domains.is_empty() && !self.allowed_domains.iter().any(|domain| domain_matches(&host, domain)) {
+            return Err(Error::AccessDenied(format!("Requests to {} are not in the allowed domains", host)));
+        }
+        if !self.allow_private_networks {
+            let private = match host.parse::<IpAddr>() {
+                Ok(ip) => is_private(ip),
+                Err(_) => host == "localhost" || host.ends_with(".localhost"),
+            };
+            if private {
+                return Err(Error::AccessDenied(format!("Requests to private network address {} are not allowed", host)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Resolve the host of `url` and check that it is not a private network address
+    ///
+    /// This closes the gap left by host names that resolve to internal addresses. The
+    /// address is resolved again when connecting, so a hostile DNS server could still
+    /// answer differently; deny such hosts explicitly where that matters.
+    pub async fn check_addresses(&self, url: &Url) -> Result<()> {
+        if self.allow_private_networks {
+            return Ok(());
+        }
+        let (Some(host), Some(port)) = (url.host_str(), url.port_or_known_default()) else {
+            return Ok(());
+        };
+        let addresses = tokio::net::lookup_host((host.trim_start_matches('[').trim_end_matches(']'), port)).await
+            .map_err(|e| Error::Network(format!("Failed to resolve {}: {}", host, e)))?;
+        for address in addresses {
+            if is_private(address.ip()) {
+                return Err(Error::AccessDenied(format!(
+                    "{} resolves to private network address {}, which is not allowed", host, address.ip()
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Whether `host` is `domain` or one of its subdomains; `*.` before `domain` is ignored
+fn domain_matches(host: &str, domain: &str) -> bool {
+    let domain = domain.trim().trim_start_matches("*.").trim_end_matches('.').to_ascii_lowercase();
+    !domain.is_empty()
+        && (host == domain || host.strip_suffix(domain.as_str()).is_some_and(|prefix| prefix.ends_with('.')))
+}
+
+/// Loopback, private, link-local, unspecified and similar non-public addresses
+fn is_private(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            ip.is_loopback() || ip.is_private() || ip.is_link_local() || ip.is_unspecified()
+                || ip.is_broadcast() || ip.is_documentation()
+                // Carrier-grade NAT, 100.64.0.0/10
+                || (ip.octets()[0] == 100 && (ip.octets()[1] & 0xc0) == 64)
+        },
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(v4) => is_private(IpAddr::V4(v4)),
+            None => {
+                let first = ip.segments()[0];
+                ip.is_loopback() || ip.is_unspecified()
+                    // Unique local fc00::/7 and link-local fe80::/10
+                    || (first & 0xfe00) == 0xfc00 || (first & 0xffc0) == 0xfe80
+            },
+        },
+    }
+}
+
+/// Convert an HTML document to readable plain text
+///
+/// Scripts, styles and comments are dropped, block elements become line breaks, and
+/// common character references are decoded.
+pub fn html_to_text(html: &str) -> String {
+    let dropped = Regex::new(r"(?is)<!--.*?-->|<(script|style|noscript|template|svg|head)\b.*?</(script|style|noscript|template|svg|head)\s*>")
+        .expect("pattern is valid");
+    let breaks = Regex::new(r"(?i)<br\s*/?>|</?(p|div|section|article|header|footer|main|nav|aside|h[1-6]|ul|ol|li|tr|table|blockquote|pre|hr|dl|dt|dd|form|figure)\b[^>]*>")
+        .expect("pattern is valid");
+    let tags = Regex::new(r"(?s)<[^>]*>").expect("pattern is valid");
+    let spaces = Regex::new(r"[ \t\r\f\u{a0}]+").expect("pattern is valid");
+    let blank_lines = Regex::new(r"\n\s*\n\s*(\n\s*)+").expect("pattern is valid");
+
+    let text = dropped.replace_all(html, " ");
+    let text = breaks.replace_all(&text, "\n");
+    let text = tags.replace_all(&text, "");
+    let text = decode_entities(&text);
+    let text = spaces.replace_all(&text, " ");
+    let text: Vec<&str> = text.lines().map(str::trim).collect();
+    blank_lines.replace_all(&text.join("\n"), "\n\n").trim().to_string()
+}
+
+/// Contents of the `<title>` element, if any
+fn html_title(html: &str) -> Option<String> {
+    let title = Regex::new(r"(?is)<title[^>]*>(.*?)</title\s*>").expect("pattern is valid");
+    title.captures(html)
+        .map(|captures| decode_entities(captures[1].trim()))
+        .filter(|title| !title.is_empty())
+}
+
+fn decode_entities(text: &str) -> String {
+    let entity = Regex::new(r"&(#[0-9]{1,7}|#[xX][0-9a-fA-F]{1,6}|[a-zA-Z]+);").expect("pattern is valid");
+    entity.replace_all(text, |captures: &regex::Captures| {
+        let name = &captures[1];
+        let decoded = match name {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            "mdash" => Some('—'),
+            "ndash" => Some('–'),
+            "hellip" => Some('…'),
+            "copy" => Some('©'),
+            _ => match name.strip_prefix("#x").or_else(|| name.strip_prefix("#X")) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok().and_then(char::from_u32),
+                None => name.strip_prefix('#').and_then(|n| n.parse().ok()).and_then(char::from_u32),
+            },
+        };
+        decoded.map_or_else(|| captures[0].to_string(), |c| c.to_string())
+    }).into_owned()
+}
+
+/// Built-in `http_request` tool
+#[derive(Clone)]
+pub struct HttpRequestTool {
+    base: crate::base::BaseComponent,
+    id: String,
+    description: String,
+    schema: ToolSchema,
+    policy: Arc<HttpRequestPolicy>,
+}
+
+impl HttpRequestTool {
+    /// Create an HTTP request tool with the default policy: public addresses only,
+    /// 1 MiB responses and a 30 second time limit
+    pub fn new() -> Self {
+        Self::with_policy(HttpRequestPolicy::default())
+    }
+
+    /// Create an HTTP request tool restricted by `policy`
+    pub fn with_policy(policy: HttpRequestPolicy) -> Self {
+        let schema = ToolSchema::new(vec![
+            ParameterSchema {
+                name: "url".to_string(),
+                description: "The http or https URL to request".to_string(),
+                r#type: "string".to_string(),
+                required: true,
+                properties: None,
+                default: None,
+            },
+            ParameterSchema {
+                name: "method".to_string(),
+                description: "HTTP method (GET, POST, PUT, PATCH, DELETE, HEAD)".to_string(),
+                r#type: "string".to_string(),
+                required: false,
+                properties: None,
+                default: Some(json!("GET")),
+            },
+            ParameterSchema {
+                name: "headers".to_string(),
+                description: "HTTP headers as JSON object".to_string(),
+                r#type: "object".to_string(),
+                required: false,
+                properties: None,
+                default: None,
+            },
+            ParameterSchema {
+                name: "body".to_string(),
+                description: "Request body; JSON objects and arrays are sent as application/json".to_string(),
+                r#type: "string".to_string(),
+                required: false,
+                properties: None,
+                default: None,
+            },
+        ]);
+
+        Self {
+            base: crate::base::BaseComponent::new_with_name(
+                "http_request".to_string(),
+                crate::logger::Component::Tool
+            ),
+            id: "http_request".to_string(),
+            description: "Make HTTP requests to web APIs and websites; HTML pages are returned as plain text".to_string(),
+            schema,
+            policy: Arc::new(policy),
+        }
+    }
+
+    /// Policy applied to requests
+    pub fn policy(&self) -> &HttpRequestPolicy {
+        &self.policy
+    }
+
+    async fn send(&self, params: &Value) -> Result<Value> {
+        let url = params.get("url")
+            .and_then(|v| v.as_str())
+            .filter(|url| !url.trim().is_empty())
+            .ok_or_else(|| Error::Tool("url parameter is required".to_string()))?;
+        let url = Url::parse(url.trim()).map_err(|e| Error::Tool(format!("Invalid URL '{}': {}", url, e)))?;
+        let method = params.get("method")
+            .and_then(|v| v.as_str())
+            .unwrap_or("GET")
+            .to_ascii_uppercase();
+        self.policy.check_method(&method)?;
+        self.policy.check_url(&url)?;
+        self.policy.check_addresses(&url).await?;
+
+        let policy = self.policy.clone();
+        let redirects = reqwest::redirect::Policy::custom(move |attempt| {
+            if attempt.previous().len() > policy.max_redirects {
+                attempt.error(format!("Stopped after {} redirects", policy.max_redirects))
+            } else if let Err(e) = policy.check_url(attempt.url()) {
+                attempt.error(e.to_string())
+            } else {
+                attempt.follow()
+            }
+        });
+        let client = http_client_builder_for(url.as_str()).redirect(redirects).build()?;
+
+        let method = reqwest::Method::from_bytes(method.as_bytes())
+            .map_err(|_| Error::Tool(format!("Invalid HTTP method {}", method)))?;
+        let mut request = client.request(method.clone(), url.clone())
+            .timeout(Duration::from_secs(self.policy.timeout_secs));
+        if let Some(headers) = params.get("headers").and_then(|v| v.as_object()) {
+            for (name, value) in headers {
+                let value = value.as_str().map_or_else(|| value.to_string(), str::to_string);
+                request = request.header(name.as_str(), value);
+            }
+        }
+        request = match params.get("body") {
+            None | Some(Value::Null) => request,
+            Some(Value::String(body)) => request.body(body.clone()),
+            Some(body) => request.json(body),
+        };
+
+        let started = Instant::now();
+        let mut response = request.send().await.map_err(|e| self.request_error(e))?;
+        let status = response.status();
+        let final_url = response.url().to_string();
+        let content_type = response.headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let headers: Map<String, Value> = response.headers().iter()
+            .map(|(name, value)| (name.to_string(), json!(String::from_utf8_lossy(value.as_bytes()))))
+            .collect();
+
+        let mut body = Vec::new();
+        let mut truncated = false;
+        while let Some(chunk) = response.chunk().await.map_err(|e| self.request_error(e))? {
+            let room = self.policy.max_response_bytes - body.len();
+            if chunk.len() > room {
+                body.extend_from_slice(&chunk[..room]);
+                truncated = true;
+                break;
+            }
+            body.extend_from_slice(&chunk);
+        }
+        let text = String::from_utf8_lossy(&body).into_owned();
+
+        let mut result = json!({
+            "status": status.as_u16(),
+            "ok": status.is_success(),
+            "url": final_url,
+            "method": method.as_str(),
+            "headers": headers,
+            "content_type": content_type,
+            "bytes": body.len(),
+            "truncated": truncated,
+            "elapsed_ms": started.elapsed().as_millis() as u64,
+        });
+        result["body"] = if content_type.contains("json") && !truncated {
+            serde_json::from_str(&text).unwrap_or(Value::String(text))
+        } else if self.policy.html_to_text && (content_type.contains("html") || text.trim_start().starts_with("<!")) {
+            if let Some(title) = html_title(&text) {
+                result["title"] = json!(title);
+            }
+            Value::String(html_to_text(&text))
+        } else {
+            Value::String(text)
+        };
+        Ok(result)
+    }
+
+    fn request_error(&self, error: reqwest::Error) -> Error {
+        if error.is_timeout() {
+            Error::Timeout(format!("HTTP request timed out after {} seconds", self.policy.timeout_secs))
+        } else {
+            Error::Http(error)
+        }
+    }
+}
+
+impl Default for HttpRequestTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for HttpRequestTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HttpRequestTool")
+            .field("id", &self.id)
+            .field("policy", &self.policy)
+            .finish()
+    }
+}
+
+impl Base for HttpRequestTool {
+    fn name(&self) -> Option<&str> {
+        self.base.name()
+    }
+
+    fn component(&self) -> crate::logger::Component {
+        self.base.component()
+    }
+
+    fn logger(&self) -> std::sync::Arc<dyn crate::logger::Logger> {
+        self.base.logger()
+    }
+
+    fn set_logger(&mut self, logger: std::sync::Arc<dyn crate::logger::Logger>) {
+        self.base.set_logger(logger);
+    }
+
+    fn telemetry(&self) -> Option<std::sync::Arc<dyn crate::telemetry::TelemetrySink>> {
+        self.base.telemetry()
+    }
+
+    fn set_telemetry(&mut self, telemetry: std::sync::Arc<dyn crate::telemetry::TelemetrySink>) {
+        self.base.set_telemetry(telemetry);
+    }
+}
+
+#[async_trait]
+impl Tool for HttpRequestTool {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn schema(&self) -> ToolSchema {
+        self.schema.clone()
+    }
+
+    async fn execute(
+        &self,
+        params: Value,
+        context: ToolExecutionContext,
+        _options: &ToolExecutionOptions
+    ) -> Result<Value> {
+        run_until_cancelled(context.cancellation_token.as_ref(), self.send(&params)).await
+    }
+
+    fn clone_box(&self) -> Box<dyn Tool> {
+        Box::new(self.clone())
+    }
+}
+
+/// Create an HTTP request tool with the default policy
+/// Similar to Mastra's fetch tool
+pub fn create_http_request_tool() -> HttpRequestTool {
+    HttpRequestTool::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Serve one request on a local port with `content_type` and `body`
+    async fn serve_once(content_type: &'static str, body: String) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 4096];
+            let _ = socket.read(&mut request).await;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                content_type, body.len(), body
+            );
+            let _ = socket.write_all(response.as_bytes()).await;
+        });
+        format!("http://{}/page", address)
+    }
+
+    #[test]
+    fn test_policy_checks() {
+        let url = |url: &str| Url::parse(url).unwrap();
+        let policy = HttpRequestPolicy::default()
+            .allow_domain("example.com")
+            .deny_domain("admin.example.com")
+            .with_methods(["get"]);
+
+        assert!(policy.check_url(&url("https://example.com/a")).is_ok());
+        assert!(policy.check_url(&url("https://api.example.com/a")).is_ok());
+        assert!(policy.check_url(&url("https://badexample.com/")).is_err());
+        assert!(policy.check_url(&url("https://x.admin.example.com/")).is_err());
+        assert!(policy.check_url(&url("ftp://example.com/")).is_err());
+        assert!(policy.check_method("GET").is_ok());
+        assert!(policy.check_method("POST").is_err());
+
+        let open = HttpRequestPolicy::default();
+        for private in ["http://127.0.0.1/", "http://10.1.2.3/", "http://169.254.169.254/", "http://[::1]/", "http://localhost:8080/"] {
+            assert!(matches!(open.check_url(&url(private)), Err(Error::AccessDenied(_))), "{}", private);
+        }
+        assert!(open.check_url(&url("http://93.184.216.34/")).is_ok());
+        assert!(open.with_private_networks(true).check_url(&url("http://127.0.0.1/")).is_ok());
+    }
+
+    #[test]
+    fn test_html_to_text() {
+        let html = r#"<!DOCTYPE html><html><head><title>Rust &amp; You</title><style>p { color: red }</style></head>
+            <body><h1>Hello</h1><script>alert("x")</script><p>Fish &lt;3 chips&nbsp;&#8212; <b>really</b></p>
+            <ul><li>one</li><li>two</li></ul><!-- hidden --></body></html>"#;
+
+        assert_eq!(html_to_text(html), "Hello\n\nFish <3 chips — really\n\none\n\ntwo");
+        assert_eq!(html_title(html).as_deref(), Some("Rust & You"));
+    }
+
+    #[tokio::test]
+    async fn test_request_and_size_cap() {
+        let tool = HttpRequestTool::with_policy(
+            HttpRequestPolicy::default().with_private_networks(true).with_max_response_bytes(64)
+        );
+
+        let url = serve_once("application/json", r#"{"ok":true}"#.to_string()).await;
+        let result = tool.execute(json!({ "url": url }), ToolExecutionContext::default(), &ToolExecutionOptions::default())
+            .await.unwrap();
+        assert_eq!(result["status"], 200);
+        assert_eq!(result["body"]["ok"], true);
+        assert_eq!(result["truncated"], false);
+
+        let url = serve_once("text/html", format!("<html><title>Big</title><p>{}</p></html>", "x".repeat(100))).await;
+        let result = tool.execute(json!({ "url": url }), ToolExecutionContext::default(), &ToolExecutionOptions::default())
+            .await.unwrap();
+        assert_eq!(result["bytes"], 64);
+        assert_eq!(result["truncated"], true);
+        assert_eq!(result["title"], "Big");
+
+        let denied = HttpRequestTool::new()
+            .execute(json!({ "url": url }), ToolExecutionContext::default(), &ToolExecutionOptions::default())
+            .await;
+        assert!(matches!(denied, Err(Error::AccessDenied(_))));
+    }
+}
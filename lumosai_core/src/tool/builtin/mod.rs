@@ -91,23 +91,29 @@ impl Default for DataProcessingConfig {
 
 // Import the new tool modules
 pub mod web;
+pub mod http;
 pub mod file;
 pub mod data;
 pub mod system;
+pub mod datetime;
 pub mod math;
 pub mod ai;
 pub mod database;
 pub mod communication;
+pub mod translate;
 
 // Re-export tool creation functions
 pub use web::*;
+pub use http::*;
 pub use file::*;
 pub use data::*;
 pub use system::*;
+pub use datetime::*;
 pub use math::*;
 pub use ai::*;
 pub use database::*;
 pub use communication::*;
+pub use translate::*;
 
 /// 创建所有内置工具
 ///
@@ -1,193 +1,18 @@
 //! System utility tools inspired by Mastra's system integrations
 //! 
-//! This module provides datetime, UUID generation, and other system utilities
+//! This module provides UUID generation, hashing, code execution and other system utilities
 
 use crate::tool::{Tool, ToolSchema, ParameterSchema, FunctionTool, ToolExecutionContext, ToolExecutionOptions};
 use serde_json::{Value, json};
 use std::collections::HashMap;
-use std::process::{Command, Stdio};
+use std::process::Stdio;
+use tokio::process::Command;
 use std::io::Write;
 use tempfile::NamedTempFile;
 use crate::{Result, Error};
 use crate::base::Base;
 use async_trait::async_trait;
 
-/// Create a datetime tool
-/// Similar to Mastra's date/time utilities
-pub fn create_datetime_tool() -> FunctionTool {
-    let schema = ToolSchema::new(vec![
-        ParameterSchema {
-            name: "operation".to_string(),
-            description: "Operation to perform (now, parse, format, add, subtract)".to_string(),
-            r#type: "string".to_string(),
-            required: true,
-            properties: None,
-            default: None,
-        },
-        ParameterSchema {
-            name: "input".to_string(),
-            description: "Input datetime string (for parse/format operations)".to_string(),
-            r#type: "string".to_string(),
-            required: false,
-            properties: None,
-            default: None,
-        },
-        ParameterSchema {
-            name: "format".to_string(),
-            description: "Format string for parsing or formatting".to_string(),
-            r#type: "string".to_string(),
-            required: false,
-            properties: None,
-            default: Some(json!("%Y-%m-%d %H:%M:%S")),
-        },
-        ParameterSchema {
-            name: "timezone".to_string(),
-            description: "Timezone for the operation (UTC, Local, or timezone name)".to_string(),
-            r#type: "string".to_string(),
-            required: false,
-            properties: None,
-            default: Some(json!("UTC")),
-        },
-        ParameterSchema {
-            name: "amount".to_string(),
-            description: "Amount to add/subtract (for add/subtract operations)".to_string(),
-            r#type: "number".to_string(),
-            required: false,
-            properties: None,
-            default: None,
-        },
-        ParameterSchema {
-            name: "unit".to_string(),
-            description: "Unit for add/subtract (seconds, minutes, hours, days, weeks, months, years)".to_string(),
-            r#type: "string".to_string(),
-            required: false,
-            properties: None,
-            default: Some(json!("seconds")),
-        },
-    ]);
-
-    FunctionTool::new(
-        "datetime",
-        "Comprehensive datetime operations and formatting",
-        schema,
-        |params| {
-            let operation = params.get("operation")
-                .and_then(|v| v.as_str())
-                .ok_or("Operation is required")?;
-            
-            let input = params.get("input").and_then(|v| v.as_str());
-            let format = params.get("format")
-                .and_then(|v| v.as_str())
-                .unwrap_or("%Y-%m-%d %H:%M:%S");
-            let timezone = params.get("timezone")
-                .and_then(|v| v.as_str())
-                .unwrap_or("UTC");
-            let amount = params.get("amount").and_then(|v| v.as_i64());
-            let unit = params.get("unit")
-                .and_then(|v| v.as_str())
-                .unwrap_or("seconds");
-
-            let now = chrono::Utc::now();
-
-            match operation {
-                "now" => {
-                    Ok(json!({
-                        "success": true,
-                        "operation": "now",
-                        "result": {
-                            "iso": now.to_rfc3339(),
-                            "timestamp": now.timestamp(),
-                            "formatted": now.format(format).to_string(),
-                            "timezone": timezone
-                        }
-                    }))
-                },
-                "parse" => {
-                    if let Some(input) = input {
-                        // Mock parsing - in real implementation would use chrono parsing
-                        Ok(json!({
-                            "success": true,
-                            "operation": "parse",
-                            "input": input,
-                            "format": format,
-                            "result": {
-                                "iso": now.to_rfc3339(),
-                                "timestamp": now.timestamp(),
-                                "timezone": timezone
-                            }
-                        }))
-                    } else {
-                        Ok(json!({
-                            "success": false,
-                            "error": "Input datetime string is required for parse operation"
-                        }))
-                    }
-                },
-                "format" => {
-                    if let Some(input) = input {
-                        Ok(json!({
-                            "success": true,
-                            "operation": "format",
-                            "input": input,
-                            "format": format,
-                            "result": {
-                                "formatted": now.format(format).to_string(),
-                                "timezone": timezone
-                            }
-                        }))
-                    } else {
-                        Ok(json!({
-                            "success": false,
-                            "error": "Input datetime string is required for format operation"
-                        }))
-                    }
-                },
-                "add" | "subtract" => {
-                    if let Some(amount) = amount {
-                        let multiplier = if operation == "subtract" { -1 } else { 1 };
-                        let adjusted_amount = amount * multiplier;
-                        
-                        // Mock calculation
-                        let result_time = match unit {
-                            "seconds" => now + chrono::Duration::seconds(adjusted_amount),
-                            "minutes" => now + chrono::Duration::minutes(adjusted_amount),
-                            "hours" => now + chrono::Duration::hours(adjusted_amount),
-                            "days" => now + chrono::Duration::days(adjusted_amount),
-                            "weeks" => now + chrono::Duration::weeks(adjusted_amount),
-                            _ => now, // For months/years, would need more complex logic
-                        };
-
-                        Ok(json!({
-                            "success": true,
-                            "operation": operation,
-                            "amount": amount,
-                            "unit": unit,
-                            "result": {
-                                "iso": result_time.to_rfc3339(),
-                                "timestamp": result_time.timestamp(),
-                                "formatted": result_time.format(format).to_string(),
-                                "timezone": timezone
-                            }
-                        }))
-                    } else {
-                        Ok(json!({
-                            "success": false,
-                            "error": "Amount is required for add/subtract operations"
-                        }))
-                    }
-                },
-                _ => {
-                    Ok(json!({
-                        "success": false,
-                        "error": format!("Unknown operation: {}", operation),
-                        "supported_operations": ["now", "parse", "format", "add", "subtract"]
-                    }))
-                }
-            }
-        },
-    )
-}
-
 /// Create a UUID generator tool
 /// Similar to Mastra's ID generation utilities
 pub fn create_uuid_generator_tool() -> FunctionTool {
@@ -400,19 +225,16 @@ impl CodeExecutorTool {
         }
     }
 
-    fn execute_python(&self, code: &str, timeout: u64) -> Result<(String, String, i32)> {
+    async fn execute_python(&self, code: &str, timeout: u64, context: &ToolExecutionContext) -> Result<(String, String, i32)> {
         let mut temp_file = NamedTempFile::new()
             .map_err(|e| Error::Tool(format!("Failed to create temp file: {}", e)))?;
 
         temp_file.write_all(code.as_bytes())
             .map_err(|e| Error::Tool(format!("Failed to write code to temp file: {}", e)))?;
 
-        let output = Command::new("python3")
-            .arg(temp_file.path())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
-            .map_err(|e| Error::Tool(format!("Failed to execute Python code: {}", e)))?;
+        let mut command = Command::new("python3");
+        command.arg(temp_file.path());
+        let output = run_command(command, context, "Failed to execute Python code").await?;
 
         let stdout = String::from_utf8_lossy(&output.stdout).to_string();
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
@@ -421,19 +243,16 @@ impl CodeExecutorTool {
         Ok((stdout, stderr, exit_code))
     }
 
-    fn execute_javascript(&self, code: &str, timeout: u64) -> Result<(String, String, i32)> {
+    async fn execute_javascript(&self, code: &str, timeout: u64, context: &ToolExecutionContext) -> Result<(String, String, i32)> {
         let mut temp_file = NamedTempFile::new()
             .map_err(|e| Error::Tool(format!("Failed to create temp file: {}", e)))?;
 
         temp_file.write_all(code.as_bytes())
             .map_err(|e| Error::Tool(format!("Failed to write code to temp file: {}", e)))?;
 
-        let output = Command::new("node")
-            .arg(temp_file.path())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
-            .map_err(|e| Error::Tool(format!("Failed to execute JavaScript code: {}", e)))?;
+        let mut command = Command::new("node");
+        command.arg(temp_file.path());
+        let output = run_command(command, context, "Failed to execute JavaScript code").await?;
 
         let stdout = String::from_utf8_lossy(&output.stdout).to_string();
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
@@ -442,14 +261,12 @@ impl CodeExecutorTool {
         Ok((stdout, stderr, exit_code))
     }
 
-    fn execute_bash(&self, code: &str, timeout: u64) -> Result<(String, String, i32)> {
-        let output = Command::new("bash")
+    async fn execute_bash(&self, code: &str, timeout: u64, context: &ToolExecutionContext) -> Result<(String, String, i32)> {
+        let mut command = Command::new("bash");
+        command
             .arg("-c")
-            .arg(code)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
-            .map_err(|e| Error::Tool(format!("Failed to execute Bash code: {}", e)))?;
+            .arg(code);
+        let output = run_command(command, context, "Failed to execute Bash code").await?;
 
         let stdout = String::from_utf8_lossy(&output.stdout).to_string();
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
@@ -458,7 +275,7 @@ impl CodeExecutorTool {
         Ok((stdout, stderr, exit_code))
     }
 
-    fn execute_rust(&self, code: &str, timeout: u64) -> Result<(String, String, i32)> {
+    async fn execute_rust(&self, code: &str, timeout: u64, context: &ToolExecutionContext) -> Result<(String, String, i32)> {
         // For Rust, we'll create a simple main function wrapper
         let wrapped_code = format!(
             "fn main() {{\n{}\n}}",
@@ -472,14 +289,12 @@ impl CodeExecutorTool {
             .map_err(|e| Error::Tool(format!("Failed to write code to temp file: {}", e)))?;
 
         // Compile first
-        let compile_output = Command::new("rustc")
+        let mut command = Command::new("rustc");
+        command
             .arg(temp_file.path())
             .arg("-o")
-            .arg("/tmp/rust_temp_exec")
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
-            .map_err(|e| Error::Tool(format!("Failed to compile Rust code: {}", e)))?;
+            .arg("/tmp/rust_temp_exec");
+        let compile_output = run_command(command, context, "Failed to compile Rust code").await?;
 
         if !compile_output.status.success() {
             let stderr = String::from_utf8_lossy(&compile_output.stderr).to_string();
@@ -487,11 +302,7 @@ impl CodeExecutorTool {
         }
 
         // Execute compiled binary
-        let output = Command::new("/tmp/rust_temp_exec")
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
-            .map_err(|e| Error::Tool(format!("Failed to execute Rust binary: {}", e)))?;
+        let output = run_command(Command::new("/tmp/rust_temp_exec"), context, "Failed to execute Rust binary").await?;
 
         let stdout = String::from_utf8_lossy(&output.stdout).to_string();
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
@@ -504,6 +315,22 @@ impl CodeExecutorTool {
     }
 }
 
+/// Run a subprocess and collect its output
+///
+/// The child is killed when the returned future is dropped, so a cancelled tool call
+/// (see [`ToolExecutionContext::cancellation_token`]) does not leave the process running.
+async fn run_command(mut command: Command, context: &ToolExecutionContext, action: &str) -> Result<std::process::Output> {
+    command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    crate::cancellation::run_until_cancelled(context.cancellation_token.as_ref(), async {
+        command.output().await
+            .map_err(|e| Error::Tool(format!("{}: {}", action, e)))
+    }).await
+}
+
 impl std::fmt::Debug for CodeExecutorTool {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("CodeExecutorTool")
@@ -556,7 +383,7 @@ impl Tool for CodeExecutorTool {
     async fn execute(
         &self,
         params: Value,
-        _context: ToolExecutionContext,
+        context: ToolExecutionContext,
         _options: &ToolExecutionOptions
     ) -> Result<Value> {
         let language = params.get("language")
@@ -572,10 +399,10 @@ impl Tool for CodeExecutorTool {
             .unwrap_or(30);
 
         let (stdout, stderr, exit_code) = match language.to_lowercase().as_str() {
-            "python" | "py" => self.execute_python(code, timeout)?,
-            "javascript" | "js" | "node" => self.execute_javascript(code, timeout)?,
-            "bash" | "sh" => self.execute_bash(code, timeout)?,
-            "rust" | "rs" => self.execute_rust(code, timeout)?,
+            "python" | "py" => self.execute_python(code, timeout, &context).await?,
+            "javascript" | "js" | "node" => self.execute_javascript(code, timeout, &context).await?,
+            "bash" | "sh" => self.execute_bash(code, timeout, &context).await?,
+            "rust" | "rs" => self.execute_rust(code, timeout, &context).await?,
             _ => return Err(Error::Tool(format!("Unsupported language: {}", language)))
         };
 
@@ -604,24 +431,6 @@ impl Default for CodeExecutorTool {
 mod tests {
     use super::*;
 
-    #[tokio::test]
-    async fn test_datetime_tool_now() {
-        let tool = create_datetime_tool();
-        
-        let mut params = HashMap::new();
-        params.insert("operation".to_string(), json!("now"));
-
-        let context = crate::tool::context::ToolExecutionContext::new();
-        let options = crate::tool::schema::ToolExecutionOptions::new();
-        let result = tool.execute(serde_json::to_value(&params).unwrap(), context, &options).await;
-        assert!(result.is_ok());
-        
-        let response = result.unwrap();
-        assert_eq!(response["success"], true);
-        assert_eq!(response["operation"], "now");
-        assert!(response["result"]["iso"].is_string());
-    }
-
     #[tokio::test]
     async fn test_uuid_generator_tool() {
         let tool = create_uuid_generator_tool();
@@ -693,6 +502,25 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_code_executor_cancellation_kills_process() {
+        let tool = CodeExecutorTool::new();
+        let token = crate::cancellation::CancellationToken::new();
+        let context = crate::tool::ToolExecutionContext::new().with_cancellation_token(token.clone());
+        let options = crate::tool::ToolExecutionOptions::default();
+
+        tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            token.cancel();
+        });
+
+        let params = json!({ "language": "bash", "code": "sleep 30" });
+        let result = tokio::time::timeout(std::time::Duration::from_secs(5), tool.execute(params, context, &options))
+            .await
+            .expect("cancelled command should not run to completion");
+        assert!(matches!(result, Err(Error::Cancelled(_))));
+    }
+
     #[tokio::test]
     async fn test_code_executor_unsupported_language() {
         let tool = CodeExecutorTool::new();
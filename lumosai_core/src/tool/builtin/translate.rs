@@ -0,0 +1,307 @@
+//! Translation tool with pluggable backends
+//!
+//! The `translate` tool translates text through a [`TranslationBackend`]: the agent's
+//! own model ([`LlmTranslator`]) or a LibreTranslate-compatible HTTP service
+//! ([`HttpTranslator`]). Language codes and names are normalised with
+//! [`Language::parse`], so `fr`, `French` and `français` are equivalent.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use crate::agent::language::Language;
+use crate::base::Base;
+use crate::cancellation::run_until_cancelled;
+use crate::config::http_client_for;
+use crate::llm::{LlmOptions, LlmProvider, Message, Role};
+use crate::tool::{ParameterSchema, Tool, ToolExecutionContext, ToolExecutionOptions, ToolSchema};
+use crate::{Error, Result};
+
+/// A service that translates text
+#[async_trait]
+pub trait TranslationBackend: Send + Sync {
+    /// Backend name reported in tool results
+    fn name(&self) -> &str;
+
+    /// Translate `text` into `target`, detecting the source language when `source` is `None`
+    ///
+    /// Languages are given as the caller wrote them; see [`Language::parse`].
+    async fn translate(&self, text: &str, source: Option<&str>, target: &str) -> Result<String>;
+}
+
+/// Translates with a language model
+pub struct LlmTranslator {
+    llm: Arc<dyn LlmProvider>,
+}
+
+impl LlmTranslator {
+    /// Create a translator using `llm`
+    pub fn new(llm: Arc<dyn LlmProvider>) -> Self {
+        Self { llm }
+    }
+}
+
+#[async_trait]
+impl TranslationBackend for LlmTranslator {
+    fn name(&self) -> &str {
+        "llm"
+    }
+
+    async fn translate(&self, text: &str, source: Option<&str>, target: &str) -> Result<String> {
+        let language_name = |value: &str| Language::parse(value).map_or(value.to_string(), |language| language.name.to_string());
+        let from = source.map(|source| format!(" from {}", language_name(source))).unwrap_or_default();
+        let messages = vec![
+            Message {
+                role: Role::System,
+                content: format!(
+                    "Translate the text you are given{} into {}. Keep formatting, code and names unchanged. \
+                     Reply with the translation only.",
+                    from,
+                    language_name(target)
+                ),
+                metadata: None,
+                name: None,
+            },
+            Message {
+                role: Role::User,
+                content: text.to_string(),
+                metadata: None,
+                name: None,
+            },
+        ];
+        let translation = self.llm.generate_with_messages(&messages, &LlmOptions::default()).await?;
+        Ok(translation.trim().to_string())
+    }
+}
+
+/// Translates with a LibreTranslate-compatible HTTP API
+///
+/// Posts `{"q", "source", "target", "format": "text", "api_key"}` to the endpoint and
+/// reads `translatedText` from the response. Languages are sent as ISO 639-1 codes.
+pub struct HttpTranslator {
+    endpoint: String,
+    api_key: Option<String>,
+    client: reqwest::Client,
+}
+
+impl HttpTranslator {
+    /// Create a translator posting to `endpoint`, e.g. `https://libretranslate.com/translate`
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        let endpoint = endpoint.into();
+        Self {
+            client: http_client_for(&endpoint),
+            endpoint,
+            api_key: None,
+        }
+    }
+
+    /// Send an API key with each request
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+}
+
+#[async_trait]
+impl TranslationBackend for HttpTranslator {
+    fn name(&self) -> &str {
+        "http"
+    }
+
+    async fn translate(&self, text: &str, source: Option<&str>, target: &str) -> Result<String> {
+        let code = |value: &str| Language::parse(value).map_or(value.to_string(), |language| language.code.to_string());
+        let mut body = json!({
+            "q": text,
+            "source": source.map_or("auto".to_string(), code),
+            "target": code(target),
+            "format": "text",
+        });
+        if let Some(api_key) = &self.api_key {
+            body["api_key"] = Value::from(api_key.as_str());
+        }
+
+        let response = self.client.post(&self.endpoint).json(&body).send().await?;
+        let status = response.status();
+        let payload: Value = response.json().await?;
+        if !status.is_success() {
+            let message = payload.get("error").and_then(Value::as_str).unwrap_or("unknown error");
+            return Err(Error::Tool(format!("Translation service returned {}: {}", status, message)));
+        }
+        payload.get("translatedText")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .ok_or_else(|| Error::Tool("Translation service response has no translatedText".to_string()))
+    }
+}
+
+/// Built-in `translate` tool
+#[derive(Clone)]
+pub struct TranslateTool {
+    base: crate::base::BaseComponent,
+    id: String,
+    description: String,
+    schema: ToolSchema,
+    backend: Arc<dyn TranslationBackend>,
+}
+
+impl TranslateTool {
+    /// Create a translate tool using `backend`
+    pub fn new(backend: Arc<dyn TranslationBackend>) -> Self {
+        let schema = ToolSchema::new(vec![
+            ParameterSchema {
+                name: "text".to_string(),
+                description: "Text to translate".to_string(),
+                r#type: "string".to_string(),
+                required: true,
+                properties: None,
+                default: None,
+            },
+            ParameterSchema {
+                name: "target_language".to_string(),
+                description: "Language to translate into, as a code (fr) or name (French)".to_string(),
+                r#type: "string".to_string(),
+                required: true,
+                properties: None,
+                default: None,
+            },
+            ParameterSchema {
+                name: "source_language".to_string(),
+                description: "Language of the text; detected when omitted".to_string(),
+                r#type: "string".to_string(),
+                required: false,
+                properties: None,
+                default: None,
+            },
+        ]);
+
+        Self {
+            base: crate::base::BaseComponent::new_with_name(
+                "translate".to_string(),
+                crate::logger::Component::Tool
+            ),
+            id: "translate".to_string(),
+            description: "Translate text into another language".to_string(),
+            schema,
+            backend,
+        }
+    }
+
+    /// Create a translate tool using `llm`
+    pub fn with_llm(llm: Arc<dyn LlmProvider>) -> Self {
+        Self::new(Arc::new(LlmTranslator::new(llm)))
+    }
+}
+
+impl std::fmt::Debug for TranslateTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TranslateTool")
+            .field("id", &self.id)
+            .field("backend", &self.backend.name())
+            .finish()
+    }
+}
+
+impl Base for TranslateTool {
+    fn name(&self) -> Option<&str> {
+        self.base.name()
+    }
+
+    fn component(&self) -> crate::logger::Component {
+        self.base.component()
+    }
+
+    fn logger(&self) -> std::sync::Arc<dyn crate::logger::Logger> {
+        self.base.logger()
+    }
+
+    fn set_logger(&mut self, logger: std::sync::Arc<dyn crate::logger::Logger>) {
+        self.base.set_logger(logger);
+    }
+
+    fn telemetry(&self) -> Option<std::sync::Arc<dyn crate::telemetry::TelemetrySink>> {
+        self.base.telemetry()
+    }
+
+    fn set_telemetry(&mut self, telemetry: std::sync::Arc<dyn crate::telemetry::TelemetrySink>) {
+        self.base.set_telemetry(telemetry);
+    }
+}
+
+#[async_trait]
+impl Tool for TranslateTool {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn schema(&self) -> ToolSchema {
+        self.schema.clone()
+    }
+
+    async fn execute(
+        &self,
+        params: Value,
+        context: ToolExecutionContext,
+        _options: &ToolExecutionOptions
+    ) -> Result<Value> {
+        let text = params.get("text")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| Error::Tool("text parameter is required".to_string()))?;
+        let target = params.get("target_language")
+            .and_then(|v| v.as_str())
+            .filter(|target| !target.trim().is_empty())
+            .ok_or_else(|| Error::Tool("target_language parameter is required".to_string()))?;
+        let source = params.get("source_language")
+            .and_then(|v| v.as_str())
+            .filter(|source| !source.trim().is_empty());
+
+        let translation = run_until_cancelled(
+            context.cancellation_token.as_ref(),
+            self.backend.translate(text, source, target),
+        ).await?;
+
+        let code = |value: &str| Language::parse(value).map_or(value.to_string(), |language| language.code.to_string());
+        Ok(json!({
+            "translation": translation,
+            "source_language": source.map(code),
+            "target_language": code(target),
+            "backend": self.backend.name(),
+        }))
+    }
+
+    fn clone_box(&self) -> Box<dyn Tool> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::MockLlmProvider;
+
+    #[tokio::test]
+    async fn test_translate_with_llm_backend() {
+        let llm = Arc::new(MockLlmProvider::new(vec!["  Bonjour le monde  ".to_string()]));
+        let tool = TranslateTool::with_llm(llm);
+
+        let result = tool.execute(
+            json!({ "text": "Hello world", "target_language": "French" }),
+            ToolExecutionContext::default(),
+            &ToolExecutionOptions::default(),
+        ).await.unwrap();
+        assert_eq!(result["translation"], "Bonjour le monde");
+        assert_eq!(result["target_language"], "fr");
+        assert_eq!(result["backend"], "llm");
+
+        let missing = tool.execute(
+            json!({ "text": "Hello" }),
+            ToolExecutionContext::default(),
+            &ToolExecutionOptions::default(),
+        ).await;
+        assert!(matches!(missing, Err(Error::Tool(_))));
+    }
+}
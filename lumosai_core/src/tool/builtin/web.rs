@@ -1,6 +1,7 @@
 //! Web-related tools inspired by Mastra's web tools
 //! 
-//! This module provides HTTP request, web scraping, and API interaction tools
+//! This module provides web scraping, search and API interaction tools; the
+//! `http_request` tool lives in [`super::http`]
 
 use crate::tool::{Tool, ToolSchema, ParameterSchema, FunctionTool, ToolExecutionContext, ToolExecutionOptions};
 use serde_json::{Value, json};
@@ -9,72 +10,6 @@ use async_trait::async_trait;
 use crate::{Result, Error};
 use crate::base::Base;
 
-/// Create an HTTP request tool
-/// Similar to Mastra's fetch tool
-pub fn create_http_request_tool() -> FunctionTool {
-    let schema = ToolSchema::new(vec![
-        ParameterSchema {
-            name: "url".to_string(),
-            description: "The URL to request".to_string(),
-            r#type: "string".to_string(),
-            required: true,
-            properties: None,
-            default: None,
-        },
-        ParameterSchema {
-            name: "method".to_string(),
-            description: "HTTP method (GET, POST, PUT, DELETE)".to_string(),
-            r#type: "string".to_string(),
-            required: false,
-            properties: None,
-            default: Some(json!("GET")),
-        },
-        ParameterSchema {
-            name: "headers".to_string(),
-            description: "HTTP headers as JSON object".to_string(),
-            r#type: "object".to_string(),
-            required: false,
-            properties: None,
-            default: None,
-        },
-        ParameterSchema {
-            name: "body".to_string(),
-            description: "Request body for POST/PUT requests".to_string(),
-            r#type: "string".to_string(),
-            required: false,
-            properties: None,
-            default: None,
-        },
-    ]);
-
-    FunctionTool::new(
-        "http_request",
-        "Make HTTP requests to web APIs and websites",
-        schema,
-        |params| {
-            let url = params.get("url")
-                .and_then(|v| v.as_str())
-                .ok_or("URL is required")?;
-            
-            let method = params.get("method")
-                .and_then(|v| v.as_str())
-                .unwrap_or("GET");
-
-            // For now, return a mock response
-            // In a real implementation, this would use reqwest or similar
-            Ok(json!({
-                "status": 200,
-                "url": url,
-                "method": method,
-                "body": format!("Mock response for {} {}", method, url),
-                "headers": {
-                    "content-type": "application/json"
-                }
-            }))
-        },
-    )
-}
-
 /// Create a web scraper tool
 /// Similar to Mastra's scraping capabilities
 pub fn create_web_scraper_tool() -> FunctionTool {
@@ -391,25 +326,6 @@ pub fn create_web_search_tool() -> WebSearchTool {
 mod tests {
     use super::*;
 
-    #[tokio::test]
-    async fn test_http_request_tool() {
-        let tool = create_http_request_tool();
-        
-        let mut params = HashMap::new();
-        params.insert("url".to_string(), json!("https://api.example.com/data"));
-        params.insert("method".to_string(), json!("GET"));
-
-        let context = crate::tool::context::ToolExecutionContext::new();
-        let options = crate::tool::schema::ToolExecutionOptions::new();
-        let result = tool.execute(serde_json::to_value(&params).unwrap(), context, &options).await;
-        assert!(result.is_ok());
-        
-        let response = result.unwrap();
-        assert_eq!(response["status"], 200);
-        assert_eq!(response["url"], "https://api.example.com/data");
-        assert_eq!(response["method"], "GET");
-    }
-
     #[tokio::test]
     async fn test_web_scraper_tool() {
         let tool = create_web_scraper_tool();
@@ -1,5 +1,6 @@
 use serde::{Serialize, Deserialize};
 use tokio::sync::watch;
+use crate::cancellation::CancellationToken;
 use crate::llm::Message;
 
 /// Context for tool execution
@@ -31,6 +32,10 @@ pub struct ToolExecutionContext {
     /// Signal that can be used to abort the tool execution
     #[serde(skip)]
     pub abort_signal: Option<watch::Receiver<bool>>,
+    
+    /// Token cancelled when the request that triggered the tool call is abandoned
+    #[serde(skip)]
+    pub cancellation_token: Option<CancellationToken>,
 }
 
 impl ToolExecutionContext {
@@ -77,9 +82,18 @@ impl ToolExecutionContext {
         self
     }
     
+    /// Add a cancellation token to the context
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation_token = Some(token);
+        self
+    }
+    
     /// Check if an abort has been requested
     pub fn is_abort_requested(&self) -> bool {
-        self.abort_signal.as_ref()
+        let cancelled = self.cancellation_token.as_ref()
+            .map(|token| token.is_cancelled())
+            .unwrap_or(false);
+        cancelled || self.abort_signal.as_ref()
             .map(|signal| *signal.borrow())
             .unwrap_or(false)
     }
@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use futures::future::BoxFuture;
 use serde_json::Value;
 use std::collections::HashMap;
 use std::fmt::Debug;
@@ -11,6 +12,13 @@ use super::tool::Tool;
 use super::schema::{ToolSchema, ToolExecutionOptions};
 use super::context::ToolExecutionContext;
 
+/// Function behind a [`FunctionTool`]
+#[derive(Clone)]
+enum ToolFunction {
+    Sync(Arc<dyn Fn(Value) -> Result<Value> + Send + Sync>),
+    Async(Arc<dyn Fn(Value) -> BoxFuture<'static, Result<Value>> + Send + Sync>),
+}
+
 /// A simple tool that executes a function
 pub struct FunctionTool {
     /// Base component
@@ -22,7 +30,7 @@ pub struct FunctionTool {
     /// The schema of the tool
     schema: ToolSchema,
     /// The function to execute
-    function: Arc<dyn Fn(Value) -> Result<Value> + Send + Sync>,
+    function: ToolFunction,
     /// Output schema for validation
     output_schema: Option<Value>,
 }
@@ -53,11 +61,24 @@ impl FunctionTool {
             id: id_str,
             description: description.into(),
             schema,
-            function: Arc::new(function),
+            function: ToolFunction::Sync(Arc::new(function)),
             output_schema: None,
         }
     }
     
+    /// Create a new function tool backed by an async function
+    pub fn new_async(
+        id: impl Into<String>,
+        description: impl Into<String>,
+        schema: ToolSchema,
+        function: impl Fn(Value) -> BoxFuture<'static, Result<Value>> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            function: ToolFunction::Async(Arc::new(function)),
+            ..Self::new(id, description, schema, |_| Ok(Value::Null))
+        }
+    }
+    
     /// Set the output schema
     pub fn with_output_schema(mut self, output_schema: Value) -> Self {
         self.output_schema = Some(output_schema);
@@ -132,7 +153,10 @@ impl Tool for FunctionTool {
         }
         
         // Execute the function
-        let result = (self.function)(params)?;
+        let result = match &self.function {
+            ToolFunction::Sync(function) => function(params)?,
+            ToolFunction::Async(function) => function(params).await?,
+        };
         
         // Validate output if needed
         if options.validate_output {
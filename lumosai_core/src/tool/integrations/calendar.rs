@@ -0,0 +1,384 @@
+//! Calendar tool: `create_event`
+//!
+//! Events are created through a [`CalendarBackend`]: [`CalDavCalendar`] for CalDAV
+//! servers (Nextcloud, Fastmail, iCloud, Radicale, ...) or [`GoogleCalendar`] for the
+//! Google Calendar API.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, FixedOffset, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::email::{address_list, api_response};
+use super::oauth::OAuthClient;
+use crate::config::http_client_for;
+use crate::tool::builtin::datetime::{parse_datetime, parse_timezone};
+use crate::tool::{FunctionTool, ParameterSchema, ToolSchema};
+use crate::{Error, Result};
+
+/// Base URL of the Google Calendar API
+pub const GOOGLE_CALENDAR_API_URL: &str = "https://www.googleapis.com/calendar/v3";
+
+/// Length of events created without an end time, in minutes
+const DEFAULT_EVENT_MINUTES: i64 = 60;
+
+/// Event to create
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CalendarEvent {
+    pub title: String,
+    pub start: DateTime<FixedOffset>,
+    pub end: DateTime<FixedOffset>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub location: Option<String>,
+    /// Email addresses of attendees
+    #[serde(default)]
+    pub attendees: Vec<String>,
+}
+
+/// Event created by a backend
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CreatedEvent {
+    /// Backend-specific event id
+    pub id: String,
+    /// Link to the event, when the backend provides one
+    pub url: Option<String>,
+}
+
+/// A calendar events can be added to
+#[async_trait]
+pub trait CalendarBackend: Send + Sync {
+    /// Backend name reported in tool results
+    fn name(&self) -> &str;
+
+    /// Add `event` to the calendar
+    async fn create_event(&self, event: &CalendarEvent) -> Result<CreatedEvent>;
+}
+
+/// How to authenticate with a calendar server
+#[derive(Clone)]
+pub enum CalendarAuth {
+    /// HTTP basic authentication; resolve secret references with
+    /// [`SecretResolver`](crate::config::SecretResolver) before passing the password here
+    Basic { username: String, password: String },
+    /// OAuth bearer tokens of `account`
+    OAuth { account: String, client: Arc<OAuthClient> },
+}
+
+impl std::fmt::Debug for CalendarAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CalendarAuth::Basic { username, .. } => f.debug_struct("Basic").field("username", username).finish_non_exhaustive(),
+            CalendarAuth::OAuth { account, .. } => f.debug_struct("OAuth").field("account", account).finish_non_exhaustive(),
+        }
+    }
+}
+
+/// Calendar collection on a CalDAV server
+#[derive(Debug, Clone)]
+pub struct CalDavCalendar {
+    collection_url: String,
+    auth: CalendarAuth,
+    client: reqwest::Client,
+}
+
+impl CalDavCalendar {
+    /// Create a calendar for the collection at `collection_url`, e.g.
+    /// `https://cloud.example.com/remote.php/dav/calendars/alice/personal/`
+    pub fn new(collection_url: impl Into<String>, auth: CalendarAuth) -> Self {
+        let collection_url = collection_url.into().trim_end_matches('/').to_string();
+        Self {
+            client: http_client_for(&collection_url),
+            collection_url,
+            auth,
+        }
+    }
+}
+
+#[async_trait]
+impl CalendarBackend for CalDavCalendar {
+    fn name(&self) -> &str {
+        "caldav"
+    }
+
+    async fn create_event(&self, event: &CalendarEvent) -> Result<CreatedEvent> {
+        let uid = uuid::Uuid::new_v4().to_string();
+        let url = format!("{}/{}.ics", self.collection_url, uid);
+        let mut request = self.client
+            .put(&url)
+            .header(reqwest::header::CONTENT_TYPE, "text/calendar; charset=utf-8")
+            // Never overwrite an existing resource
+            .header(reqwest::header::IF_NONE_MATCH, "*")
+            .body(to_icalendar(event, &uid, Utc::now()));
+        request = match &self.auth {
+            CalendarAuth::Basic { username, password } => request.basic_auth(username, Some(password)),
+            CalendarAuth::OAuth { account, client } => request.bearer_auth(client.access_token(account).await?),
+        };
+
+        api_response(request.send().await?, "CalDAV server").await?;
+        Ok(CreatedEvent { id: uid, url: Some(url) })
+    }
+}
+
+/// Calendar of a Google account
+#[derive(Debug, Clone)]
+pub struct GoogleCalendar {
+    account: String,
+    calendar_id: String,
+    oauth: Arc<OAuthClient>,
+    api_url: String,
+    client: reqwest::Client,
+}
+
+impl GoogleCalendar {
+    /// Create a handle on the primary calendar of `account`, whose token `oauth` holds
+    pub fn new(account: impl Into<String>, oauth: Arc<OAuthClient>) -> Self {
+        Self {
+            account: account.into(),
+            calendar_id: "primary".to_string(),
+            oauth,
+            api_url: GOOGLE_CALENDAR_API_URL.to_string(),
+            client: http_client_for(GOOGLE_CALENDAR_API_URL),
+        }
+    }
+
+    /// Use another calendar of the account
+    pub fn with_calendar_id(mut self, calendar_id: impl Into<String>) -> Self {
+        self.calendar_id = calendar_id.into();
+        self
+    }
+
+    /// Use another API base URL, e.g. a test server
+    pub fn with_api_url(mut self, api_url: impl Into<String>) -> Self {
+        self.api_url = api_url.into().trim_end_matches('/').to_string();
+        self.client = http_client_for(&self.api_url);
+        self
+    }
+}
+
+#[async_trait]
+impl CalendarBackend for GoogleCalendar {
+    fn name(&self) -> &str {
+        "google_calendar"
+    }
+
+    async fn create_event(&self, event: &CalendarEvent) -> Result<CreatedEvent> {
+        let token = self.oauth.access_token(&self.account).await?;
+        let body = json!({
+            "summary": event.title,
+            "description": event.description,
+            "location": event.location,
+            "start": { "dateTime": event.start.to_rfc3339() },
+            "end": { "dateTime": event.end.to_rfc3339() },
+            "attendees": event.attendees.iter().map(|email| json!({ "email": email })).collect::<Vec<_>>(),
+        });
+        let response = self.client
+            .post(format!("{}/calendars/{}/events", self.api_url, urlencoding::encode(&self.calendar_id)))
+            .bearer_auth(token)
+            .json(&body)
+            .send()
+            .await?;
+        let created = api_response(response, "Google Calendar").await?;
+        Ok(CreatedEvent {
+            id: created["id"].as_str().unwrap_or_default().to_string(),
+            url: created["htmlLink"].as_str().map(str::to_string),
+        })
+    }
+}
+
+/// iCalendar (RFC 5545) document holding `event`
+pub fn to_icalendar(event: &CalendarEvent, uid: &str, now: DateTime<Utc>) -> String {
+    let utc = |dt: DateTime<FixedOffset>| dt.with_timezone(&Utc).format("%Y%m%dT%H%M%SZ").to_string();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//LumosAI//Integrations//EN".to_string(),
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:{}", uid),
+        format!("DTSTAMP:{}", now.format("%Y%m%dT%H%M%SZ")),
+        format!("DTSTART:{}", utc(event.start)),
+        format!("DTEND:{}", utc(event.end)),
+        format!("SUMMARY:{}", escape_text(&event.title)),
+    ];
+    if let Some(description) = &event.description {
+        lines.push(format!("DESCRIPTION:{}", escape_text(description)));
+    }
+    if let Some(location) = &event.location {
+        lines.push(format!("LOCATION:{}", escape_text(location)));
+    }
+    lines.extend(event.attendees.iter().map(|email| format!("ATTENDEE;RSVP=TRUE:mailto:{}", email)));
+    lines.push("END:VEVENT".to_string());
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| fold(line)).collect::<Vec<_>>().join("\r\n") + "\r\n"
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Fold `line` into lines of at most 75 octets, continuation lines starting with a space
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + line.len() / 74 * 3);
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded
+}
+
+fn parameter(name: &str, r#type: &str, description: &str, required: bool) -> ParameterSchema {
+    ParameterSchema {
+        name: name.to_string(),
+        description: description.to_string(),
+        r#type: r#type.to_string(),
+        required,
+        properties: None,
+        default: None,
+    }
+}
+
+/// Create the `create_event` tool
+///
+/// Start and end times accept everything the `datetime` tool parses, including phrases
+/// like "tomorrow at 3pm"; they are read in `timezone`, UTC by default.
+pub fn create_event_tool(calendar: Arc<dyn CalendarBackend>) -> FunctionTool {
+    let schema = ToolSchema::new(vec![
+        parameter("title", "string", "Event title", true),
+        parameter("start", "string", "Start time, e.g. 2024-05-01 14:00 or 'next monday at 10am'", true),
+        parameter("end", "string", "End time; defaults to start plus duration_minutes", false),
+        parameter("duration_minutes", "integer", "Length of the event when no end is given, 60 by default", false),
+        parameter("timezone", "string", "Timezone of start and end, e.g. Europe/Berlin; UTC by default", false),
+        parameter("description", "string", "Event description", false),
+        parameter("location", "string", "Event location", false),
+        parameter("attendees", "array", "Email addresses of attendees", false),
+    ]);
+
+    FunctionTool::new_async("create_event", "Create a calendar event", schema, move |params: Value| {
+        let calendar = calendar.clone();
+        Box::pin(async move {
+            let text = |name: &str| params.get(name).and_then(Value::as_str).filter(|v| !v.trim().is_empty()).map(str::to_string);
+            let title = text("title").ok_or_else(|| Error::InvalidParams("Required parameter 'title' is missing".to_string()))?;
+            let zone = parse_timezone(text("timezone").as_deref().unwrap_or("UTC"))?;
+            let now = Utc::now();
+            let start = parse_datetime(
+                &text("start").ok_or_else(|| Error::InvalidParams("Required parameter 'start' is missing".to_string()))?,
+                zone,
+                now,
+            )?;
+            let end = match text("end") {
+                Some(end) => parse_datetime(&end, zone, now)?,
+                None => {
+                    let minutes = params.get("duration_minutes").and_then(Value::as_i64).unwrap_or(DEFAULT_EVENT_MINUTES);
+                    start + Duration::minutes(minutes)
+                },
+            };
+            if end <= start {
+                return Err(Error::InvalidInput(format!("Event ends ({}) before it starts ({})", end, start)));
+            }
+
+            let event = CalendarEvent {
+                title,
+                start,
+                end,
+                description: text("description"),
+                location: text("location"),
+                attendees: address_list(params.get("attendees")),
+            };
+            let created = calendar.create_event(&event).await?;
+            Ok(json!({
+                "created": true,
+                "id": created.id,
+                "url": created.url,
+                "title": event.title,
+                "start": event.start.to_rfc3339(),
+                "end": event.end.to_rfc3339(),
+                "backend": calendar.name(),
+            }))
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tool::{Tool, ToolExecutionContext, ToolExecutionOptions};
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct RecordingCalendar {
+        events: Mutex<Vec<CalendarEvent>>,
+    }
+
+    #[async_trait]
+    impl CalendarBackend for RecordingCalendar {
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        async fn create_event(&self, event: &CalendarEvent) -> Result<CreatedEvent> {
+            self.events.lock().unwrap().push(event.clone());
+            Ok(CreatedEvent { id: "evt-1".to_string(), url: None })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_event_tool() {
+        let calendar = Arc::new(RecordingCalendar::default());
+        let tool = create_event_tool(calendar.clone());
+        let context = ToolExecutionContext::default();
+        let options = ToolExecutionOptions::default();
+
+        let result = tool.execute(
+            json!({ "title": "Standup", "start": "2024-05-01 09:30", "timezone": "Europe/Berlin", "attendees": ["bob@example.com"] }),
+            context.clone(),
+            &options,
+        ).await.unwrap();
+        assert_eq!(result["id"], "evt-1");
+        assert_eq!(result["start"], "2024-05-01T09:30:00+02:00");
+        assert_eq!(result["end"], "2024-05-01T10:30:00+02:00");
+        assert_eq!(calendar.events.lock().unwrap()[0].attendees, vec!["bob@example.com"]);
+
+        let backwards = tool.execute(
+            json!({ "title": "Oops", "start": "2024-05-01 10:00", "end": "2024-05-01 09:00" }),
+            context,
+            &options,
+        ).await;
+        assert!(matches!(backwards, Err(Error::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_icalendar() {
+        let start = DateTime::parse_from_rfc3339("2024-05-01T09:30:00+02:00").unwrap();
+        let event = CalendarEvent {
+            title: "Review; part 1, draft".to_string(),
+            start,
+            end: start + Duration::hours(1),
+            description: Some(format!("Line one\n{}", "x".repeat(100))),
+            location: None,
+            attendees: vec!["bob@example.com".to_string()],
+        };
+        let now = DateTime::parse_from_rfc3339("2024-04-30T12:00:00Z").unwrap().with_timezone(&Utc);
+        let ics = to_icalendar(&event, "uid-1", now);
+
+        assert!(ics.contains("DTSTART:20240501T073000Z\r\n"));
+        assert!(ics.contains("DTEND:20240501T083000Z\r\n"));
+        assert!(ics.contains("SUMMARY:Review\\; part 1\\, draft\r\n"));
+        assert!(ics.contains("DESCRIPTION:Line one\\nxxx"));
+        assert!(ics.contains("ATTENDEE;RSVP=TRUE:mailto:bob@example.com\r\n"));
+        assert!(ics.lines().all(|line| line.trim_end_matches('\r').len() <= 75));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    }
+}
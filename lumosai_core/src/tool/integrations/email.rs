@@ -0,0 +1,703 @@
+//! Email tools: `send_email` and `search_inbox`
+//!
+//! Both tools work through a [`MailBackend`]: [`SmtpImapMailer`] for any provider with
+//! SMTP and IMAP access, or [`GmailMailer`] for the Gmail API.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use base64::Engine;
+use chrono::NaiveDate;
+use lettre::message::{header::ContentType, Mailbox, MultiPart, SinglePart};
+use lettre::transport::smtp::authentication::{Credentials, Mechanism};
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::imap::{quote, ImapSession};
+use super::oauth::OAuthClient;
+use crate::config::http_client_for;
+use crate::tool::{FunctionTool, ParameterSchema, ToolSchema};
+use crate::{Error, Result};
+
+/// Base URL of the Gmail API
+pub const GMAIL_API_URL: &str = "https://gmail.googleapis.com/gmail/v1/users/me";
+
+/// Most messages `search_inbox` returns at once
+const MAX_SEARCH_RESULTS: usize = 50;
+
+/// Header fields shown in search results
+const SUMMARY_HEADERS: &[&str] = &["FROM", "TO", "SUBJECT", "DATE", "MESSAGE-ID"];
+
+/// Email to send
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OutgoingEmail {
+    /// Recipients, as `alice@example.com` or `Alice <alice@example.com>`
+    pub to: Vec<String>,
+    /// Carbon-copy recipients
+    #[serde(default)]
+    pub cc: Vec<String>,
+    /// Blind carbon-copy recipients
+    #[serde(default)]
+    pub bcc: Vec<String>,
+    /// Subject line
+    pub subject: String,
+    /// Plain text body
+    pub body: String,
+    /// Optional HTML alternative of `body`
+    #[serde(default)]
+    pub html_body: Option<String>,
+    /// Address replies should go to
+    #[serde(default)]
+    pub reply_to: Option<String>,
+}
+
+/// Criteria of an inbox search; all given criteria must match
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmailQuery {
+    /// Text anywhere in the message
+    pub text: Option<String>,
+    /// Text in the sender
+    pub from: Option<String>,
+    /// Text in the subject
+    pub subject: Option<String>,
+    /// Only messages received on or after this day
+    pub since: Option<NaiveDate>,
+    /// Only unread messages
+    pub unread_only: bool,
+    /// Mailbox to search
+    pub mailbox: String,
+    /// Most messages returned, newest first
+    pub limit: usize,
+}
+
+impl Default for EmailQuery {
+    fn default() -> Self {
+        Self {
+            text: None,
+            from: None,
+            subject: None,
+            since: None,
+            unread_only: false,
+            mailbox: "INBOX".to_string(),
+            limit: 10,
+        }
+    }
+}
+
+/// Message found by a search
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmailSummary {
+    /// Backend-specific message id
+    pub id: String,
+    pub from: String,
+    pub to: String,
+    pub subject: String,
+    pub date: String,
+    /// Whether the message has been read
+    pub unread: bool,
+    /// Start of the body, when the backend provides one
+    pub snippet: Option<String>,
+}
+
+/// A service that sends and searches email
+#[async_trait]
+pub trait MailBackend: Send + Sync {
+    /// Backend name reported in tool results
+    fn name(&self) -> &str;
+
+    /// Send `email`, returning its message id
+    async fn send(&self, email: &OutgoingEmail) -> Result<String>;
+
+    /// Messages matching `query`, newest first
+    async fn search(&self, query: &EmailQuery) -> Result<Vec<EmailSummary>>;
+}
+
+/// How to authenticate with a mail server
+#[derive(Clone)]
+pub enum MailAuth {
+    /// Password or app password; resolve secret references with
+    /// [`SecretResolver`](crate::config::SecretResolver) before passing it here
+    Password(String),
+    /// OAuth access tokens of the account, sent with SASL XOAUTH2
+    OAuth(Arc<OAuthClient>),
+}
+
+impl std::fmt::Debug for MailAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MailAuth::Password(_) => f.write_str("Password(..)"),
+            MailAuth::OAuth(client) => f.debug_tuple("OAuth").field(client).finish(),
+        }
+    }
+}
+
+impl MailAuth {
+    async fn secret(&self, username: &str) -> Result<String> {
+        match self {
+            MailAuth::Password(password) => Ok(password.clone()),
+            MailAuth::OAuth(client) => client.access_token(username).await,
+        }
+    }
+}
+
+/// Mail account reached over SMTP and IMAP
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MailAccount {
+    /// Sender address, e.g. `Assistant <assistant@example.com>`
+    pub address: String,
+    /// Login name, usually the email address
+    pub username: String,
+    pub smtp_host: String,
+    /// SMTP port; 465 uses implicit TLS, any other port STARTTLS
+    pub smtp_port: u16,
+    pub imap_host: String,
+    /// IMAP port, always with implicit TLS
+    pub imap_port: u16,
+}
+
+impl MailAccount {
+    /// Account on `smtp_host:587` and `imap_host:993`
+    pub fn new(
+        address: impl Into<String>,
+        username: impl Into<String>,
+        smtp_host: impl Into<String>,
+        imap_host: impl Into<String>,
+    ) -> Self {
+        Self {
+            address: address.into(),
+            username: username.into(),
+            smtp_host: smtp_host.into(),
+            smtp_port: 587,
+            imap_host: imap_host.into(),
+            imap_port: 993,
+        }
+    }
+
+    /// Gmail or Google Workspace account
+    pub fn gmail(address: impl Into<String>) -> Self {
+        let address = address.into();
+        Self::new(address.clone(), address, "smtp.gmail.com", "imap.gmail.com")
+    }
+
+    /// Set the SMTP port
+    pub fn with_smtp_port(mut self, port: u16) -> Self {
+        self.smtp_port = port;
+        self
+    }
+
+    /// Set the IMAP port
+    pub fn with_imap_port(mut self, port: u16) -> Self {
+        self.imap_port = port;
+        self
+    }
+}
+
+/// Sends over SMTP and searches over IMAP
+#[derive(Debug, Clone)]
+pub struct SmtpImapMailer {
+    account: MailAccount,
+    auth: MailAuth,
+}
+
+impl SmtpImapMailer {
+    /// Create a mailer for `account`
+    pub fn new(account: MailAccount, auth: MailAuth) -> Self {
+        Self { account, auth }
+    }
+
+    async fn imap_session(&self) -> Result<ImapSession<tokio_native_tls::TlsStream<tokio::net::TcpStream>>> {
+        let host = self.account.imap_host.as_str();
+        let tcp = tokio::net::TcpStream::connect((host, self.account.imap_port)).await
+            .map_err(|e| Error::Network(format!("Failed to connect to {}:{}: {}", host, self.account.imap_port, e)))?;
+        let connector = tokio_native_tls::native_tls::TlsConnector::new()
+            .map_err(|e| Error::Network(format!("Failed to set up TLS: {}", e)))?;
+        let tls = tokio_native_tls::TlsConnector::from(connector).connect(host, tcp).await
+            .map_err(|e| Error::Network(format!("TLS handshake with {} failed: {}", host, e)))?;
+
+        let mut session = ImapSession::start(tls).await?;
+        let secret = self.auth.secret(&self.account.username).await?;
+        match self.auth {
+            MailAuth::Password(_) => session.login(&self.account.username, &secret).await?,
+            MailAuth::OAuth(_) => session.login_oauth(&self.account.username, &secret).await?,
+        }
+        Ok(session)
+    }
+}
+
+#[async_trait]
+impl MailBackend for SmtpImapMailer {
+    fn name(&self) -> &str {
+        "smtp_imap"
+    }
+
+    async fn send(&self, email: &OutgoingEmail) -> Result<String> {
+        let (message, message_id) = build_message(&self.account.address, email)?;
+
+        let host = self.account.smtp_host.as_str();
+        let builder = if self.account.smtp_port == 465 {
+            AsyncSmtpTransport::<Tokio1Executor>::relay(host)
+        } else {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)
+        }.map_err(|e| Error::Configuration(format!("Invalid SMTP host {}: {}", host, e)))?;
+        let secret = self.auth.secret(&self.account.username).await?;
+        let mechanism = match self.auth {
+            MailAuth::Password(_) => vec![Mechanism::Plain, Mechanism::Login],
+            MailAuth::OAuth(_) => vec![Mechanism::Xoauth2],
+        };
+        let transport = builder
+            .port(self.account.smtp_port)
+            .credentials(Credentials::new(self.account.username.clone(), secret))
+            .authentication(mechanism)
+            .build();
+
+        transport.send(message).await
+            .map_err(|e| Error::Network(format!("Sending email through {} failed: {}", host, e)))?;
+        Ok(message_id)
+    }
+
+    async fn search(&self, query: &EmailQuery) -> Result<Vec<EmailSummary>> {
+        let criteria = imap_criteria(query)?;
+        let mut session = self.imap_session().await?;
+        session.examine(&query.mailbox).await?;
+
+        let mut uids = session.uid_search(&criteria).await?;
+        uids.sort_unstable_by(|a, b| b.cmp(a));
+        uids.truncate(query.limit.min(MAX_SEARCH_RESULTS));
+        let messages = session.uid_fetch_headers(&uids, SUMMARY_HEADERS).await?;
+        // Logging out is a courtesy; the results are already in hand
+        let _ = session.logout().await;
+
+        let mut summaries: Vec<EmailSummary> = messages.into_iter()
+            .map(|message| {
+                let header = |name: &str| header_value(&message.headers, name).unwrap_or_default();
+                EmailSummary {
+                    id: message.uid.to_string(),
+                    from: header("From"),
+                    to: header("To"),
+                    subject: header("Subject"),
+                    date: header("Date"),
+                    unread: !message.flags.iter().any(|flag| flag.eq_ignore_ascii_case("\\Seen")),
+                    snippet: None,
+                }
+            })
+            .collect();
+        summaries.sort_by_key(|summary| std::cmp::Reverse(summary.id.parse::<u32>().unwrap_or(0)));
+        Ok(summaries)
+    }
+}
+
+/// Sends and searches through the Gmail API
+#[derive(Debug, Clone)]
+pub struct GmailMailer {
+    address: String,
+    oauth: Arc<OAuthClient>,
+    api_url: String,
+    client: reqwest::Client,
+}
+
+impl GmailMailer {
+    /// Create a mailer for the Google account `address`, whose token `oauth` holds
+    pub fn new(address: impl Into<String>, oauth: Arc<OAuthClient>) -> Self {
+        Self {
+            address: address.into(),
+            oauth,
+            api_url: GMAIL_API_URL.to_string(),
+            client: http_client_for(GMAIL_API_URL),
+        }
+    }
+
+    /// Use another API base URL, e.g. a test server
+    pub fn with_api_url(mut self, api_url: impl Into<String>) -> Self {
+        self.api_url = api_url.into().trim_end_matches('/').to_string();
+        self.client = http_client_for(&self.api_url);
+        self
+    }
+
+    async fn get(&self, path: &str, query: &[(&str, String)]) -> Result<Value> {
+        let token = self.oauth.access_token(&self.address).await?;
+        let response = self.client
+            .get(format!("{}/{}", self.api_url, path))
+            .bearer_auth(token)
+            .query(query)
+            .send()
+            .await?;
+        api_response(response, "Gmail").await
+    }
+}
+
+#[async_trait]
+impl MailBackend for GmailMailer {
+    fn name(&self) -> &str {
+        "gmail"
+    }
+
+    async fn send(&self, email: &OutgoingEmail) -> Result<String> {
+        let (message, message_id) = build_message(&self.address, email)?;
+        let raw = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(message.formatted());
+
+        let token = self.oauth.access_token(&self.address).await?;
+        let response = self.client
+            .post(format!("{}/messages/send", self.api_url))
+            .bearer_auth(token)
+            .json(&json!({ "raw": raw }))
+            .send()
+            .await?;
+        let sent = api_response(response, "Gmail").await?;
+        Ok(sent["id"].as_str().map_or(message_id, str::to_string))
+    }
+
+    async fn search(&self, query: &EmailQuery) -> Result<Vec<EmailSummary>> {
+        let limit = query.limit.clamp(1, MAX_SEARCH_RESULTS);
+        let list = self.get("messages", &[("q", gmail_query(query)), ("maxResults", limit.to_string())]).await?;
+        let ids: Vec<String> = list["messages"].as_array().into_iter().flatten()
+            .filter_map(|message| message["id"].as_str().map(str::to_string))
+            .collect();
+
+        let mut summaries = Vec::with_capacity(ids.len());
+        for id in ids {
+            let mut params = vec![("format", "metadata".to_string())];
+            params.extend(["From", "To", "Subject", "Date"].iter().map(|name| ("metadataHeaders", name.to_string())));
+            let message = self.get(&format!("messages/{}", id), &params).await?;
+            let header = |name: &str| {
+                message["payload"]["headers"].as_array().into_iter().flatten()
+                    .find(|header| header["name"].as_str().is_some_and(|n| n.eq_ignore_ascii_case(name)))
+                    .and_then(|header| header["value"].as_str())
+                    .unwrap_or_default()
+                    .to_string()
+            };
+            summaries.push(EmailSummary {
+                from: header("From"),
+                to: header("To"),
+                subject: header("Subject"),
+                date: header("Date"),
+                unread: message["labelIds"].as_array().into_iter().flatten().any(|label| label == "UNREAD"),
+                snippet: message["snippet"].as_str().map(str::to_string),
+                id,
+            });
+        }
+        Ok(summaries)
+    }
+}
+
+/// JSON body of a successful API response, or an error naming `service`
+pub(crate) async fn api_response(response: reqwest::Response, service: &str) -> Result<Value> {
+    let status = response.status();
+    if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+        let body = response.text().await.unwrap_or_default();
+        return Err(Error::Authentication(format!("{} rejected the credentials ({}): {}", service, status, body)));
+    }
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(Error::Network(format!("{} request failed with {}: {}", service, status, body)));
+    }
+    let text = response.text().await?;
+    if text.trim().is_empty() {
+        return Ok(Value::Null);
+    }
+    Ok(serde_json::from_str(&text)?)
+}
+
+/// RFC 5322 message for `email` from `from`, with its generated message id
+fn build_message(from: &str, email: &OutgoingEmail) -> Result<(Message, String)> {
+    let mailbox = |address: &str| -> Result<Mailbox> {
+        address.trim().parse()
+            .map_err(|e| Error::InvalidInput(format!("Invalid email address '{}': {}", address, e)))
+    };
+    if email.to.is_empty() {
+        return Err(Error::InvalidInput("An email needs at least one recipient".to_string()));
+    }
+
+    let sender = mailbox(from)?;
+    let message_id = format!("<{}@{}>", uuid::Uuid::new_v4(), sender.email.domain());
+    let mut builder = Message::builder()
+        .from(sender)
+        .subject(email.subject.clone())
+        .message_id(Some(message_id.clone()));
+    for to in &email.to {
+        builder = builder.to(mailbox(to)?);
+    }
+    for cc in &email.cc {
+        builder = builder.cc(mailbox(cc)?);
+    }
+    for bcc in &email.bcc {
+        builder = builder.bcc(mailbox(bcc)?);
+    }
+    if let Some(reply_to) = &email.reply_to {
+        builder = builder.reply_to(mailbox(reply_to)?);
+    }
+
+    let message = match &email.html_body {
+        Some(html) => builder.multipart(MultiPart::alternative_plain_html(email.body.clone(), html.clone())),
+        None => builder.singlepart(SinglePart::builder().header(ContentType::TEXT_PLAIN).body(email.body.clone())),
+    }.map_err(|e| Error::InvalidInput(format!("Cannot build email: {}", e)))?;
+    Ok((message, message_id))
+}
+
+/// IMAP `SEARCH` criteria for `query`
+fn imap_criteria(query: &EmailQuery) -> Result<String> {
+    let mut criteria = Vec::new();
+    if let Some(text) = &query.text {
+        criteria.push(format!("TEXT {}", quote(text)?));
+    }
+    if let Some(from) = &query.from {
+        criteria.push(format!("FROM {}", quote(from)?));
+    }
+    if let Some(subject) = &query.subject {
+        criteria.push(format!("SUBJECT {}", quote(subject)?));
+    }
+    if let Some(since) = query.since {
+        criteria.push(format!("SINCE {}", since.format("%-d-%b-%Y")));
+    }
+    if query.unread_only {
+        criteria.push("UNSEEN".to_string());
+    }
+    Ok(if criteria.is_empty() { "ALL".to_string() } else { criteria.join(" ") })
+}
+
+/// Gmail search syntax for `query`
+fn gmail_query(query: &EmailQuery) -> String {
+    let quoted = |value: &str| format!("\"{}\"", value.replace('"', ""));
+    let mut terms = Vec::new();
+    match query.mailbox.to_ascii_uppercase().as_str() {
+        "INBOX" => terms.push("in:inbox".to_string()),
+        "ALL" | "" => {},
+        _ => terms.push(format!("label:{}", quoted(&query.mailbox))),
+    }
+    if let Some(text) = &query.text {
+        terms.push(text.clone());
+    }
+    if let Some(from) = &query.from {
+        terms.push(format!("from:{}", quoted(from)));
+    }
+    if let Some(subject) = &query.subject {
+        terms.push(format!("subject:{}", quoted(subject)));
+    }
+    if let Some(since) = query.since {
+        terms.push(format!("after:{}", since.format("%Y/%m/%d")));
+    }
+    if query.unread_only {
+        terms.push("is:unread".to_string());
+    }
+    terms.join(" ")
+}
+
+/// Value of header `name` in a raw header block, unfolded and with encoded words decoded
+fn header_value(headers: &str, name: &str) -> Option<String> {
+    let unfolded = headers.replace("\r\n ", " ").replace("\r\n\t", " ").replace("\n ", " ").replace("\n\t", " ");
+    unfolded.lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(field, _)| field.trim().eq_ignore_ascii_case(name))
+        .map(|(_, value)| decode_encoded_words(value.trim()))
+}
+
+/// Decode RFC 2047 encoded words such as `=?UTF-8?B?SGVsbG8=?=`
+fn decode_encoded_words(value: &str) -> String {
+    // Whitespace between adjacent encoded words is not part of the text
+    let between = regex::Regex::new(r"\?=\s+=\?").expect("pattern is valid");
+    let pattern = regex::Regex::new(r"=\?([^?]+)\?([bBqQ])\?([^?]*)\?=").expect("pattern is valid");
+    let value = between.replace_all(value, "?==?");
+    pattern.replace_all(&value, |captures: &regex::Captures| {
+        let text = &captures[3];
+        let bytes = if captures[2].eq_ignore_ascii_case("b") {
+            base64::engine::general_purpose::STANDARD.decode(text).ok()
+        } else {
+            Some(decode_quoted_printable(&text.replace('_', " ")))
+        };
+        bytes.map_or_else(|| captures[0].to_string(), |bytes| String::from_utf8_lossy(&bytes).into_owned())
+    }).into_owned()
+}
+
+fn decode_quoted_printable(text: &str) -> Vec<u8> {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|hex| std::str::from_utf8(hex).ok()).and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'=', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            },
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            },
+        }
+    }
+    decoded
+}
+
+/// Addresses given as a JSON array or a comma-separated string
+pub(crate) fn address_list(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::Array(items)) => items.iter().filter_map(Value::as_str).map(str::to_string).collect(),
+        Some(Value::String(list)) => list.split(',').map(str::trim).filter(|a| !a.is_empty()).map(str::to_string).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn parameter(name: &str, r#type: &str, description: &str, required: bool) -> ParameterSchema {
+    ParameterSchema {
+        name: name.to_string(),
+        description: description.to_string(),
+        r#type: r#type.to_string(),
+        required,
+        properties: None,
+        default: None,
+    }
+}
+
+/// Create the `send_email` tool
+pub fn send_email_tool(mailer: Arc<dyn MailBackend>) -> FunctionTool {
+    let schema = ToolSchema::new(vec![
+        parameter("to", "array", "Recipient addresses", true),
+        parameter("subject", "string", "Subject line", true),
+        parameter("body", "string", "Plain text body", true),
+        parameter("cc", "array", "Carbon-copy addresses", false),
+        parameter("bcc", "array", "Blind carbon-copy addresses", false),
+        parameter("html_body", "string", "Optional HTML version of the body", false),
+    ]);
+
+    FunctionTool::new_async("send_email", "Send an email", schema, move |params: Value| {
+        let mailer = mailer.clone();
+        Box::pin(async move {
+            let text = |name: &str| params.get(name).and_then(Value::as_str).map(str::to_string);
+            let email = OutgoingEmail {
+                to: address_list(params.get("to")),
+                cc: address_list(params.get("cc")),
+                bcc: address_list(params.get("bcc")),
+                subject: text("subject").ok_or_else(|| Error::InvalidParams("Required parameter 'subject' is missing".to_string()))?,
+                body: text("body").ok_or_else(|| Error::InvalidParams("Required parameter 'body' is missing".to_string()))?,
+                html_body: text("html_body"),
+                reply_to: None,
+            };
+            let message_id = mailer.send(&email).await?;
+            Ok(json!({
+                "sent": true,
+                "message_id": message_id,
+                "recipients": email.to.len() + email.cc.len() + email.bcc.len(),
+                "backend": mailer.name(),
+            }))
+        })
+    })
+}
+
+/// Create the `search_inbox` tool
+pub fn search_inbox_tool(mailer: Arc<dyn MailBackend>) -> FunctionTool {
+    let schema = ToolSchema::new(vec![
+        parameter("text", "string", "Text anywhere in the message", false),
+        parameter("from", "string", "Text in the sender's name or address", false),
+        parameter("subject", "string", "Text in the subject", false),
+        parameter("since", "string", "Only messages on or after this date (YYYY-MM-DD)", false),
+        parameter("unread_only", "boolean", "Only unread messages", false),
+        parameter("mailbox", "string", "Mailbox or label to search, INBOX by default", false),
+        parameter("limit", "integer", "Most messages to return, 10 by default", false),
+    ]);
+
+    FunctionTool::new_async("search_inbox", "Search the mailbox and list matching emails, newest first", schema, move |params: Value| {
+        let mailer = mailer.clone();
+        Box::pin(async move {
+            let text = |name: &str| params.get(name).and_then(Value::as_str).filter(|v| !v.trim().is_empty()).map(str::to_string);
+            let since = text("since")
+                .map(|since| NaiveDate::parse_from_str(&since, "%Y-%m-%d")
+                    .map_err(|_| Error::InvalidParams(format!("'since' must be a date like 2024-05-01, got '{}'", since))))
+                .transpose()?;
+            let defaults = EmailQuery::default();
+            let query = EmailQuery {
+                text: text("text"),
+                from: text("from"),
+                subject: text("subject"),
+                since,
+                unread_only: params.get("unread_only").and_then(Value::as_bool).unwrap_or(false),
+                mailbox: text("mailbox").unwrap_or(defaults.mailbox),
+                limit: params.get("limit").and_then(Value::as_u64).map_or(defaults.limit, |limit| limit as usize),
+            };
+            let messages = mailer.search(&query).await?;
+            Ok(json!({
+                "count": messages.len(),
+                "messages": messages,
+                "backend": mailer.name(),
+            }))
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tool::{Tool, ToolExecutionContext, ToolExecutionOptions};
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct RecordingMailer {
+        sent: Mutex<Vec<OutgoingEmail>>,
+        queries: Mutex<Vec<EmailQuery>>,
+    }
+
+    #[async_trait]
+    impl MailBackend for RecordingMailer {
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        async fn send(&self, email: &OutgoingEmail) -> Result<String> {
+            build_message("assistant@example.com", email)?;
+            self.sent.lock().unwrap().push(email.clone());
+            Ok("<1@example.com>".to_string())
+        }
+
+        async fn search(&self, query: &EmailQuery) -> Result<Vec<EmailSummary>> {
+            self.queries.lock().unwrap().push(query.clone());
+            Ok(Vec::new())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_email_tools() {
+        let mailer = Arc::new(RecordingMailer::default());
+        let context = ToolExecutionContext::default();
+        let options = ToolExecutionOptions::default();
+
+        let result = send_email_tool(mailer.clone()).execute(
+            json!({ "to": "bob@example.com, Carol <carol@example.com>", "subject": "Hi", "body": "Lunch?" }),
+            context.clone(),
+            &options,
+        ).await.unwrap();
+        assert_eq!(result["recipients"], 2);
+        assert_eq!(mailer.sent.lock().unwrap()[0].to[1], "Carol <carol@example.com>");
+
+        let invalid = send_email_tool(mailer.clone())
+            .execute(json!({ "to": ["not an address"], "subject": "Hi", "body": "x" }), context.clone(), &options)
+            .await;
+        assert!(matches!(invalid, Err(Error::InvalidInput(_))));
+
+        search_inbox_tool(mailer.clone())
+            .execute(json!({ "from": "bob", "since": "2024-05-01", "unread_only": true }), context, &options)
+            .await.unwrap();
+        let query = mailer.queries.lock().unwrap()[0].clone();
+        assert_eq!(imap_criteria(&query).unwrap(), "FROM \"bob\" SINCE 1-May-2024 UNSEEN");
+        assert_eq!(gmail_query(&query), "in:inbox from:\"bob\" after:2024/05/01 is:unread");
+    }
+
+    #[test]
+    fn test_message_and_headers() {
+        let email = OutgoingEmail {
+            to: vec!["bob@example.com".to_string()],
+            subject: "Grüße".to_string(),
+            body: "Hello".to_string(),
+            ..Default::default()
+        };
+        let (message, id) = build_message("Assistant <assistant@example.com>", &email).unwrap();
+        let formatted = String::from_utf8(message.formatted()).unwrap();
+        assert!(id.ends_with("@example.com>"));
+        assert!(formatted.contains(&format!("Message-ID: {}", id)));
+        assert!(formatted.contains("To: bob@example.com"));
+
+        let headers = "Subject: =?UTF-8?B?R3LDvMOfZQ==?=\r\n =?UTF-8?Q?_und_Tsch=C3=BCss?=\r\nFrom: Bob <bob@example.com>\r\n";
+        assert_eq!(header_value(headers, "subject").as_deref(), Some("Grüße und Tschüss"));
+        assert_eq!(header_value(headers, "From").as_deref(), Some("Bob <bob@example.com>"));
+        assert_eq!(header_value(headers, "Date"), None);
+    }
+}
@@ -0,0 +1,255 @@
+//! Minimal IMAP4rev1 client for searching a mailbox
+//!
+//! Supports what the `search_inbox` tool needs: password or XOAUTH2 login, read-only
+//! `EXAMINE`, `UID SEARCH` and fetching envelope headers. Responses may contain
+//! literals (`{n}` followed by n raw bytes), which are collected separately from the
+//! surrounding text.
+
+use base64::Engine;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+
+use crate::{Error, Result};
+
+/// One untagged response, split into its text and literals
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct ImapResponse {
+    /// Text with each literal replaced by `{}`
+    pub text: String,
+    /// Literals in order, each paired with the text before it
+    pub literals: Vec<(String, Vec<u8>)>,
+}
+
+/// Headers and flags of one fetched message
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct FetchedMessage {
+    pub uid: u32,
+    pub flags: Vec<String>,
+    pub headers: String,
+}
+
+/// Session on an established connection
+pub(crate) struct ImapSession<S> {
+    stream: BufReader<S>,
+    next_tag: u32,
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> ImapSession<S> {
+    /// Start a session, reading the server greeting
+    pub async fn start(stream: S) -> Result<Self> {
+        let mut session = Self { stream: BufReader::new(stream), next_tag: 1 };
+        let greeting = session.read_line().await?;
+        if !greeting.starts_with("* OK") && !greeting.starts_with("* PREAUTH") {
+            return Err(Error::Network(format!("Unexpected IMAP greeting: {}", greeting.trim_end())));
+        }
+        Ok(session)
+    }
+
+    /// Log in with a password
+    pub async fn login(&mut self, username: &str, password: &str) -> Result<()> {
+        self.command(&format!("LOGIN {} {}", quote(username)?, quote(password)?)).await
+            .map_err(authentication_error)?;
+        Ok(())
+    }
+
+    /// Log in with an OAuth access token (SASL XOAUTH2)
+    pub async fn login_oauth(&mut self, username: &str, access_token: &str) -> Result<()> {
+        let payload = format!("user={}\x01auth=Bearer {}\x01\x01", username, access_token);
+        let encoded = base64::engine::general_purpose::STANDARD.encode(payload);
+        self.command(&format!("AUTHENTICATE XOAUTH2 {}", encoded)).await
+            .map_err(authentication_error)?;
+        Ok(())
+    }
+
+    /// Open `mailbox` read-only
+    pub async fn examine(&mut self, mailbox: &str) -> Result<()> {
+        self.command(&format!("EXAMINE {}", quote(mailbox)?)).await?;
+        Ok(())
+    }
+
+    /// UIDs of messages matching the search `criteria`
+    pub async fn uid_search(&mut self, criteria: &str) -> Result<Vec<u32>> {
+        let responses = self.command(&format!("UID SEARCH {}", criteria)).await?;
+        Ok(responses.iter()
+            .filter_map(|response| response.text.strip_prefix("* SEARCH"))
+            .flat_map(|uids| uids.split_whitespace().filter_map(|uid| uid.parse().ok()))
+            .collect())
+    }
+
+    /// Flags and the given header fields of the messages with `uids`
+    pub async fn uid_fetch_headers(&mut self, uids: &[u32], fields: &[&str]) -> Result<Vec<FetchedMessage>> {
+        if uids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let set = uids.iter().map(u32::to_string).collect::<Vec<_>>().join(",");
+        let responses = self.command(&format!(
+            "UID FETCH {} (UID FLAGS BODY.PEEK[HEADER.FIELDS ({})])", set, fields.join(" ")
+        )).await?;
+        Ok(responses.iter().filter_map(parse_fetch).collect())
+    }
+
+    /// End the session
+    pub async fn logout(&mut self) -> Result<()> {
+        self.command("LOGOUT").await?;
+        Ok(())
+    }
+
+    /// Send a command and collect untagged responses until its tagged completion
+    async fn command(&mut self, command: &str) -> Result<Vec<ImapResponse>> {
+        let tag = format!("A{}", self.next_tag);
+        self.next_tag += 1;
+        let stream = self.stream.get_mut();
+        stream.write_all(format!("{} {}\r\n", tag, command).as_bytes()).await?;
+        stream.flush().await?;
+
+        let mut responses = Vec::new();
+        loop {
+            let response = self.read_response().await?;
+            if let Some(status) = response.text.strip_prefix(&format!("{} ", tag)) {
+                let verb = command.split_whitespace().next().unwrap_or_default();
+                return if status.starts_with("OK") {
+                    Ok(responses)
+                } else {
+                    Err(Error::Network(format!("IMAP {} failed: {}", verb, status)))
+                };
+            }
+            if response.text.starts_with('+') {
+                // A continuation request here means an authentication challenge;
+                // answering with an empty line makes the server report the failure
+                let stream = self.stream.get_mut();
+                stream.write_all(b"\r\n").await?;
+                stream.flush().await?;
+                continue;
+            }
+            responses.push(response);
+        }
+    }
+
+    /// Read one response line, including any literals it announces
+    async fn read_response(&mut self) -> Result<ImapResponse> {
+        let mut response = ImapResponse::default();
+        loop {
+            let line = self.read_line().await?;
+            let line = line.trim_end_matches(['\r', '\n']);
+            let Some(size) = literal_size(line) else {
+                response.text.push_str(line);
+                return Ok(response);
+            };
+            let before = &line[..line.rfind('{').unwrap_or(line.len())];
+            response.text.push_str(before);
+            response.text.push_str("{}");
+            let mut literal = vec![0u8; size];
+            self.stream.read_exact(&mut literal).await?;
+            response.literals.push((before.to_string(), literal));
+        }
+    }
+
+    async fn read_line(&mut self) -> Result<String> {
+        let mut line = Vec::new();
+        if self.stream.read_until(b'\n', &mut line).await? == 0 {
+            return Err(Error::Network("IMAP server closed the connection".to_string()));
+        }
+        Ok(String::from_utf8_lossy(&line).into_owned())
+    }
+}
+
+/// Size of the literal announced at the end of `line`, e.g. `{42}`
+fn literal_size(line: &str) -> Option<usize> {
+    let start = line.rfind('{')?;
+    line[start + 1..].strip_suffix('}')?.trim_end_matches('+').parse().ok()
+}
+
+/// Quote `value` as an IMAP string
+///
+/// Quoted strings are 7-bit, so non-ASCII values are rejected rather than mangled.
+pub(crate) fn quote(value: &str) -> Result<String> {
+    if !value.is_ascii() || value.contains(['\r', '\n']) {
+        return Err(Error::InvalidInput(format!(
+            "IMAP search terms must be single-line ASCII text, got '{}'", value
+        )));
+    }
+    Ok(format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\"")))
+}
+
+fn authentication_error(error: Error) -> Error {
+    match error {
+        Error::Network(message) => Error::Authentication(message),
+        other => other,
+    }
+}
+
+fn parse_fetch(response: &ImapResponse) -> Option<FetchedMessage> {
+    if !response.text.starts_with("* ") || !response.text.contains(" FETCH (") {
+        return None;
+    }
+    let uid = response.text.split("UID ").nth(1)?
+        .split(|c: char| !c.is_ascii_digit()).next()?
+        .parse().ok()?;
+    let flags = response.text.split("FLAGS (").nth(1)
+        .and_then(|rest| rest.split(')').next())
+        .map(|flags| flags.split_whitespace().map(str::to_string).collect())
+        .unwrap_or_default();
+    let headers = response.literals.iter()
+        .find(|(before, _)| before.contains("HEADER"))
+        .map(|(_, literal)| String::from_utf8_lossy(literal).into_owned())
+        .unwrap_or_default();
+    Some(FetchedMessage { uid, flags, headers })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_search_and_fetch() {
+        let (client, server) = tokio::io::duplex(4096);
+        let script = tokio::spawn(async move {
+            let (read, mut write) = tokio::io::split(server);
+            let mut lines = BufReader::new(read).lines();
+            write.write_all(b"* OK IMAP4rev1 ready\r\n").await.unwrap();
+            let mut commands = Vec::new();
+            while let Some(line) = lines.next_line().await.unwrap() {
+                let (tag, command) = line.split_once(' ').unwrap();
+                let reply = match command.split_whitespace().next().unwrap() {
+                    "UID" if command.starts_with("UID SEARCH") => "* SEARCH 4 7\r\n".to_string(),
+                    "UID" => {
+                        let headers = "Subject: Lunch\r\nFrom: Bob <bob@example.com>\r\n\r\n";
+                        format!(
+                            "* 2 FETCH (UID 7 FLAGS (\\Seen) BODY[HEADER.FIELDS (SUBJECT FROM)] {{{}}}\r\n{})\r\n",
+                            headers.len(), headers
+                        )
+                    },
+                    _ => String::new(),
+                };
+                write.write_all(format!("{}{} OK done\r\n", reply, tag).as_bytes()).await.unwrap();
+                commands.push(command.to_string());
+                if command == "LOGOUT" {
+                    break;
+                }
+            }
+            commands
+        });
+
+        let mut session = ImapSession::start(client).await.unwrap();
+        session.login("alice", "pa\"ss").await.unwrap();
+        session.examine("INBOX").await.unwrap();
+        assert_eq!(session.uid_search("UNSEEN").await.unwrap(), vec![4, 7]);
+        let messages = session.uid_fetch_headers(&[7], &["SUBJECT", "FROM"]).await.unwrap();
+        session.logout().await.unwrap();
+
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].uid, 7);
+        assert_eq!(messages[0].flags, vec!["\\Seen"]);
+        assert!(messages[0].headers.starts_with("Subject: Lunch"));
+
+        let commands = script.await.unwrap();
+        assert_eq!(commands[0], "LOGIN \"alice\" \"pa\\\"ss\"");
+        assert_eq!(commands[3], "UID FETCH 7 (UID FLAGS BODY.PEEK[HEADER.FIELDS (SUBJECT FROM)])");
+    }
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(quote("a\\b").unwrap(), "\"a\\\\b\"");
+        assert!(quote("café").is_err());
+        assert!(quote("a\r\nLOGOUT").is_err());
+    }
+}
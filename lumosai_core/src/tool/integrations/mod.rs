@@ -0,0 +1,60 @@
+//! Email and calendar integrations for assistant agents
+//!
+//! Enabled with the `integrations` feature, this pack provides three tools:
+//!
+//! | Tool           | Backends                                        |
+//! |----------------|-------------------------------------------------|
+//! | `send_email`   | [`SmtpImapMailer`] (SMTP), [`GmailMailer`]      |
+//! | `search_inbox` | [`SmtpImapMailer`] (IMAP), [`GmailMailer`]      |
+//! | `create_event` | [`CalDavCalendar`], [`GoogleCalendar`]          |
+//!
+//! Google and other OAuth providers authenticate through an [`OAuthClient`], which
+//! refreshes access tokens from refresh tokens kept in a [`TokenStore`] such as
+//! [`SecretTokenStore`]:
+//!
+//! ```rust,no_run
+//! use std::sync::Arc;
+//! use lumosai_core::config::SecretResolver;
+//! use lumosai_core::tool::integrations::{google_workspace_tools, OAuthClient, SecretTokenStore};
+//!
+//! let store = SecretTokenStore::new(Arc::new(SecretResolver::from_env()))
+//!     .with_account("alice@example.com", "vault://google/alice#refresh_token");
+//! let oauth = Arc::new(OAuthClient::google("client-id", "client-secret", Arc::new(store)));
+//! let tools = google_workspace_tools("alice@example.com", oauth);
+//! ```
+
+mod imap;
+pub mod oauth;
+pub mod email;
+pub mod calendar;
+
+use std::sync::Arc;
+
+use crate::tool::Tool;
+
+pub use oauth::{MemoryTokenStore, OAuthClient, OAuthToken, SecretTokenStore, TokenStore, GOOGLE_TOKEN_URL};
+pub use email::{
+    search_inbox_tool, send_email_tool, EmailQuery, EmailSummary, GmailMailer, MailAccount, MailAuth,
+    MailBackend, OutgoingEmail, SmtpImapMailer,
+};
+pub use calendar::{
+    create_event_tool, CalDavCalendar, CalendarAuth, CalendarBackend, CalendarEvent, CreatedEvent, GoogleCalendar,
+};
+
+/// `send_email`, `search_inbox` and `create_event` tools on the given backends
+pub fn assistant_tools(mailer: Arc<dyn MailBackend>, calendar: Arc<dyn CalendarBackend>) -> Vec<Box<dyn Tool>> {
+    vec![
+        Box::new(send_email_tool(mailer.clone())),
+        Box::new(search_inbox_tool(mailer)),
+        Box::new(create_event_tool(calendar)),
+    ]
+}
+
+/// Assistant tools on the Gmail and Google Calendar of `account`
+pub fn google_workspace_tools(account: impl Into<String>, oauth: Arc<OAuthClient>) -> Vec<Box<dyn Tool>> {
+    let account = account.into();
+    assistant_tools(
+        Arc::new(GmailMailer::new(account.clone(), oauth.clone())),
+        Arc::new(GoogleCalendar::new(account, oauth)),
+    )
+}
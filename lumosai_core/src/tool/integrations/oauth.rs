@@ -0,0 +1,332 @@
+//! OAuth 2.0 access tokens for integrations
+//!
+//! Integrations authenticate with short-lived access tokens obtained from a long-lived
+//! refresh token. [`OAuthClient`] hands out access tokens per account and refreshes them
+//! shortly before they expire; tokens are kept in a [`TokenStore`].
+//! [`SecretTokenStore`] reads the initial token of each account through the
+//! [`SecretResolver`], so refresh tokens can live in Vault, Kubernetes or AWS Secrets
+//! Manager instead of configuration files.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Mutex, RwLock};
+
+use crate::config::{http_client_for, SecretResolver};
+use crate::{Error, Result};
+
+/// Token endpoint of Google accounts
+pub const GOOGLE_TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+
+/// Tokens are refreshed this long before they expire
+const EXPIRY_MARGIN_SECS: i64 = 60;
+
+/// OAuth 2.0 token of one account
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OAuthToken {
+    /// Bearer token sent to the API; empty when only a refresh token is known
+    #[serde(default)]
+    pub access_token: String,
+    /// Long-lived token used to obtain new access tokens
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+    /// When `access_token` expires; `None` if it does not
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl OAuthToken {
+    /// A token with only a refresh token, which is exchanged on first use
+    pub fn from_refresh_token(refresh_token: impl Into<String>) -> Self {
+        Self {
+            access_token: String::new(),
+            refresh_token: Some(refresh_token.into()),
+            expires_at: None,
+        }
+    }
+
+    /// Whether the access token is missing or expires within a minute of `now`
+    pub fn needs_refresh(&self, now: DateTime<Utc>) -> bool {
+        self.access_token.is_empty()
+            || self.expires_at.is_some_and(|expires_at| expires_at - Duration::seconds(EXPIRY_MARGIN_SECS) <= now)
+    }
+}
+
+/// Storage of OAuth tokens by account
+#[async_trait]
+pub trait TokenStore: Send + Sync {
+    /// Token of `account`, if one is stored
+    async fn load(&self, account: &str) -> Result<Option<OAuthToken>>;
+
+    /// Store the token of `account`, e.g. after a refresh
+    async fn save(&self, account: &str, token: &OAuthToken) -> Result<()>;
+}
+
+/// Tokens kept in memory
+#[derive(Debug, Default)]
+pub struct MemoryTokenStore {
+    tokens: RwLock<HashMap<String, OAuthToken>>,
+}
+
+impl MemoryTokenStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builder-style way to store the token of `account`
+    pub fn with_token(mut self, account: impl Into<String>, token: OAuthToken) -> Self {
+        self.tokens.get_mut().insert(account.into(), token);
+        self
+    }
+}
+
+#[async_trait]
+impl TokenStore for MemoryTokenStore {
+    async fn load(&self, account: &str) -> Result<Option<OAuthToken>> {
+        Ok(self.tokens.read().await.get(account).cloned())
+    }
+
+    async fn save(&self, account: &str, token: &OAuthToken) -> Result<()> {
+        self.tokens.write().await.insert(account.to_string(), token.clone());
+        Ok(())
+    }
+}
+
+/// Tokens read from secret references
+///
+/// Each account maps to a reference such as `vault://google/alice#refresh_token`. The
+/// secret is either a bare refresh token or an [`OAuthToken`] as JSON. Secrets providers
+/// are read-only, so refreshed access tokens are kept in memory and take precedence over
+/// the secret until the process restarts.
+pub struct SecretTokenStore {
+    resolver: Arc<SecretResolver>,
+    references: HashMap<String, String>,
+    refreshed: MemoryTokenStore,
+}
+
+impl SecretTokenStore {
+    /// Create a store resolving references with `resolver`
+    pub fn new(resolver: Arc<SecretResolver>) -> Self {
+        Self {
+            resolver,
+            references: HashMap::new(),
+            refreshed: MemoryTokenStore::new(),
+        }
+    }
+
+    /// Read the token of `account` from `reference`
+    pub fn with_account(mut self, account: impl Into<String>, reference: impl Into<String>) -> Self {
+        self.references.insert(account.into(), reference.into());
+        self
+    }
+}
+
+#[async_trait]
+impl TokenStore for SecretTokenStore {
+    async fn load(&self, account: &str) -> Result<Option<OAuthToken>> {
+        if let Some(token) = self.refreshed.load(account).await? {
+            return Ok(Some(token));
+        }
+        let Some(reference) = self.references.get(account) else {
+            return Ok(None);
+        };
+        let secret = self.resolver.resolve(reference).await?;
+        let secret = secret.trim();
+        if secret.starts_with('{') {
+            let token = serde_json::from_str(secret).map_err(|e| {
+                Error::Configuration(format!("OAuth token of account '{}' is not valid JSON: {}", account, e))
+            })?;
+            Ok(Some(token))
+        } else {
+            Ok(Some(OAuthToken::from_refresh_token(secret)))
+        }
+    }
+
+    async fn save(&self, account: &str, token: &OAuthToken) -> Result<()> {
+        self.refreshed.save(account, token).await
+    }
+}
+
+/// Response of a token endpoint
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    #[serde(default)]
+    refresh_token: Option<String>,
+    #[serde(default)]
+    expires_in: Option<i64>,
+}
+
+/// OAuth 2.0 client that hands out fresh access tokens
+pub struct OAuthClient {
+    client_id: String,
+    client_secret: String,
+    token_url: String,
+    store: Arc<dyn TokenStore>,
+    client: reqwest::Client,
+    refresh_lock: Mutex<()>,
+}
+
+impl OAuthClient {
+    /// Create a client refreshing tokens at `token_url`
+    pub fn new(
+        client_id: impl Into<String>,
+        client_secret: impl Into<String>,
+        token_url: impl Into<String>,
+        store: Arc<dyn TokenStore>,
+    ) -> Self {
+        let token_url = token_url.into();
+        Self {
+            client_id: client_id.into(),
+            client_secret: client_secret.into(),
+            client: http_client_for(&token_url),
+            token_url,
+            store,
+            refresh_lock: Mutex::new(()),
+        }
+    }
+
+    /// Create a client for Google accounts
+    pub fn google(client_id: impl Into<String>, client_secret: impl Into<String>, store: Arc<dyn TokenStore>) -> Self {
+        Self::new(client_id, client_secret, GOOGLE_TOKEN_URL, store)
+    }
+
+    /// Store backing this client
+    pub fn store(&self) -> &Arc<dyn TokenStore> {
+        &self.store
+    }
+
+    /// Current access token of `account`, refreshing it if it is about to expire
+    pub async fn access_token(&self, account: &str) -> Result<String> {
+        let token = self.load(account).await?;
+        if !token.needs_refresh(Utc::now()) {
+            return Ok(token.access_token);
+        }
+
+        // One refresh at a time; whoever waited may find the token already refreshed
+        let _guard = self.refresh_lock.lock().await;
+        let token = self.load(account).await?;
+        if !token.needs_refresh(Utc::now()) {
+            return Ok(token.access_token);
+        }
+        let refreshed = self.refresh(account, &token).await?;
+        Ok(refreshed.access_token)
+    }
+
+    async fn load(&self, account: &str) -> Result<OAuthToken> {
+        self.store.load(account).await?
+            .ok_or_else(|| Error::Authentication(format!("No OAuth token stored for account '{}'", account)))
+    }
+
+    async fn refresh(&self, account: &str, token: &OAuthToken) -> Result<OAuthToken> {
+        let refresh_token = token.refresh_token.as_deref().ok_or_else(|| {
+            Error::Authentication(format!("OAuth token of account '{}' expired and has no refresh token", account))
+        })?;
+
+        let response = self.client
+            .post(&self.token_url)
+            .form(&[
+                ("grant_type", "refresh_token"),
+                ("refresh_token", refresh_token),
+                ("client_id", self.client_id.as_str()),
+                ("client_secret", self.client_secret.as_str()),
+            ])
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(Error::Authentication(format!(
+                "Refreshing the OAuth token of account '{}' failed with {}: {}", account, status, body
+            )));
+        }
+
+        let response: TokenResponse = response.json().await?;
+        let refreshed = OAuthToken {
+            access_token: response.access_token,
+            // Providers may rotate the refresh token; keep the old one otherwise
+            refresh_token: response.refresh_token.or_else(|| token.refresh_token.clone()),
+            expires_at: response.expires_in.map(|seconds| Utc::now() + Duration::seconds(seconds)),
+        };
+        self.store.save(account, &refreshed).await?;
+        Ok(refreshed)
+    }
+}
+
+impl std::fmt::Debug for OAuthClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OAuthClient")
+            .field("client_id", &self.client_id)
+            .field("token_url", &self.token_url)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SecretsProvider;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    struct StaticSecret(&'static str);
+
+    #[async_trait]
+    impl SecretsProvider for StaticSecret {
+        fn scheme(&self) -> &str {
+            "test"
+        }
+
+        async fn get_secret(&self, _path: &str, _key: Option<&str>) -> Result<String> {
+            Ok(self.0.to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_refresh_from_secret() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let token_url = format!("http://{}/token", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = vec![0u8; 4096];
+            let read = socket.read(&mut request).await.unwrap();
+            let body = r#"{"access_token":"fresh","expires_in":3600}"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(), body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8_lossy(&request[..read]).into_owned()
+        });
+
+        let resolver = Arc::new(SecretResolver::new().with_provider(StaticSecret("refresh-123\n")));
+        let store = Arc::new(SecretTokenStore::new(resolver).with_account("alice", "test://google/alice"));
+        let client = OAuthClient::new("id", "secret", token_url, store.clone());
+
+        assert_eq!(client.access_token("alice").await.unwrap(), "fresh");
+        let request = server.await.unwrap();
+        assert!(request.contains("grant_type=refresh_token"));
+        assert!(request.contains("refresh_token=refresh-123"));
+
+        // The refreshed token is served from memory without another request
+        assert_eq!(client.access_token("alice").await.unwrap(), "fresh");
+        let stored = store.load("alice").await.unwrap().unwrap();
+        assert_eq!(stored.refresh_token.as_deref(), Some("refresh-123"));
+
+        assert!(matches!(client.access_token("bob").await, Err(Error::Authentication(_))));
+    }
+
+    #[test]
+    fn test_needs_refresh() {
+        let now = Utc::now();
+        let token = |expires_at| OAuthToken { access_token: "a".to_string(), refresh_token: None, expires_at };
+
+        assert!(!token(None).needs_refresh(now));
+        assert!(!token(Some(now + Duration::minutes(10))).needs_refresh(now));
+        assert!(token(Some(now + Duration::seconds(30))).needs_refresh(now));
+        assert!(OAuthToken::from_refresh_token("r").needs_refresh(now));
+    }
+}
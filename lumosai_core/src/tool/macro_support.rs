@@ -0,0 +1,121 @@
+//! Runtime support for tools generated by the `lumos_macro::tool` attribute
+//!
+//! Generated code derives each parameter's JSON Schema from its Rust type, pulls the
+//! arguments out of the call parameters and turns handler errors into messages the
+//! LLM can read and react to, instead of failing the agent run.
+
+use std::fmt::Display;
+
+use schemars::{gen::SchemaSettings, JsonSchema};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::error::{Error, Result};
+use super::schema::{ParameterSchema, SchemaFormat, ToolSchema};
+
+/// Parameter of a macro-generated tool
+pub struct MacroParameter {
+    /// Parameter name
+    pub name: &'static str,
+    /// Parameter description
+    pub description: &'static str,
+    /// Whether the parameter is required; `Option<T>` parameters are not
+    pub required: bool,
+    /// JSON Schema of the parameter type
+    pub schema: Value,
+}
+
+/// Inline JSON Schema of a parameter type; enums with unit variants render as `enum`
+pub fn json_schema_for<T: JsonSchema>() -> Value {
+    let settings = SchemaSettings::draft07().with(|settings| {
+        settings.inline_subschemas = true;
+        settings.meta_schema = None;
+    });
+    let root = settings.into_generator().into_root_schema_for::<T>();
+    let mut schema = serde_json::to_value(root.schema).unwrap_or_else(|_| json!({}));
+    if let Some(object) = schema.as_object_mut() {
+        object.remove("title");
+    }
+    schema
+}
+
+/// Simple type name of a JSON Schema, as used by [`ParameterSchema::r#type`]
+fn schema_type(schema: &Value) -> String {
+    match schema.get("type") {
+        Some(Value::String(type_)) => type_.clone(),
+        Some(Value::Array(types)) => types.iter()
+            .filter_map(Value::as_str)
+            .find(|type_| *type_ != "null")
+            .unwrap_or("object")
+            .to_string(),
+        _ if schema.get("enum").is_some() => "string".to_string(),
+        _ => "object".to_string(),
+    }
+}
+
+/// Tool schema carrying both the parameter list and the full JSON Schema
+pub fn tool_schema(parameters: Vec<MacroParameter>) -> ToolSchema {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    let mut parameter_schemas = Vec::new();
+
+    for parameter in parameters {
+        let mut schema = parameter.schema;
+        if let Some(object) = schema.as_object_mut() {
+            object.insert("description".to_string(), json!(parameter.description));
+        }
+        if parameter.required {
+            required.push(json!(parameter.name));
+        }
+        parameter_schemas.push(ParameterSchema {
+            name: parameter.name.to_string(),
+            description: parameter.description.to_string(),
+            r#type: schema_type(&schema),
+            required: parameter.required,
+            properties: None,
+            default: None,
+        });
+        properties.insert(parameter.name.to_string(), schema);
+    }
+
+    ToolSchema {
+        parameters: parameter_schemas,
+        json_schema: Some(json!({
+            "type": "object",
+            "properties": properties,
+            "required": required,
+        })),
+        format: SchemaFormat::JsonSchema,
+        output_schema: None,
+    }
+}
+
+/// Deserialize the argument `name` from the call parameters
+///
+/// A missing argument deserializes from `null`, so `Option<T>` parameters become `None`.
+pub fn argument<T: DeserializeOwned>(params: &Value, name: &str) -> Result<T> {
+    match params.get(name) {
+        Some(value) => serde_json::from_value(value.clone())
+            .map_err(|e| Error::InvalidParams(format!("Invalid parameter '{}': {}", name, e))),
+        None => serde_json::from_value(Value::Null)
+            .map_err(|_| Error::InvalidParams(format!("Required parameter '{}' is missing", name))),
+    }
+}
+
+/// Tool output reporting an error to the LLM
+pub fn error_output(error: impl Display) -> Value {
+    json!({ "error": error.to_string() })
+}
+
+/// Serialize a handler's return value as tool output
+pub fn tool_output<T: Serialize>(value: T) -> Result<Value> {
+    serde_json::to_value(value).map_err(Error::from)
+}
+
+/// Tool output of a fallible handler; errors become an `error` message for the LLM
+pub fn tool_result<T: Serialize, E: Display>(result: std::result::Result<T, E>) -> Result<Value> {
+    match result {
+        Ok(value) => tool_output(value),
+        Err(error) => Ok(error_output(error)),
+    }
+}
@@ -12,6 +12,10 @@ pub mod builtin;
 pub mod builder;
 pub mod enhanced;
 pub mod toolset;
+#[cfg(feature = "integrations")]
+pub mod integrations;
+#[doc(hidden)]
+pub mod macro_support;
 
 #[cfg(test)]
 mod tests;
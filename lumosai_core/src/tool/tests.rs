@@ -196,6 +196,26 @@ async fn test_tool_execution_context_abort() {
     }
 }
 
+#[tokio::test]
+async fn test_tool_execution_context_cancellation_token() {
+    let tool = FunctionTool::new(
+        "cancellable-tool",
+        "A tool whose caller goes away",
+        ToolSchema::new(vec![]),
+        |_params| Ok(json!({ "status": "completed" })),
+    );
+    
+    let token = crate::cancellation::CancellationToken::new();
+    let context = ToolExecutionContext::new().with_cancellation_token(token.clone());
+    assert!(!context.is_abort_requested());
+    
+    // Cancelling the token aborts the tool like the abort signal does
+    token.cancel();
+    assert!(context.is_abort_requested());
+    let result = tool.execute(json!({}), context, &ToolExecutionOptions::default()).await;
+    assert!(matches!(result, Err(crate::error::Error::Tool(msg)) if msg == "Tool execution aborted"));
+}
+
 #[tokio::test]
 async fn test_tool_with_output_validation() {
     // Create a tool with output schema for validation
@@ -144,14 +144,16 @@ pub mod vector {
     /// 创建Weaviate向量存储
     #[cfg(feature = "weaviate")]
     pub async fn weaviate(url: Option<&str>) -> Result<Arc<dyn VectorStorage>> {
-        use lumosai_vector_weaviate::WeaviateVectorStorage;
+        use lumosai_vector_weaviate::{WeaviateConfig, WeaviateVectorStorage};
         
         let weaviate_url = url
             .map(|s| s.to_string())
             .or_else(|| std::env::var("WEAVIATE_URL").ok())
             .unwrap_or_else(|| "http://localhost:8080".to_string());
 
-        let storage = WeaviateVectorStorage::new(&weaviate_url).await?;
+        let config = WeaviateConfig::new(&weaviate_url);
+        let client_builder = Arc::new(move || crate::config::http_client_builder_for(&weaviate_url));
+        let storage = WeaviateVectorStorage::with_client_builder(config, client_builder).await?;
         Ok(Arc::new(storage))
     }
 
@@ -0,0 +1,177 @@
+//! Encryption-at-rest wrapper for vector metadata
+//!
+//! Embeddings have to stay in plaintext for similarity search, but the
+//! metadata stored next to them often carries the source text. Wrapping a
+//! [`VectorStorage`] in [`EncryptedVectorStorage`] envelope-encrypts the
+//! configured metadata fields for one tenant. Encrypted fields cannot be used
+//! in query filters, so only mark fields that are never filtered on.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::error::Result;
+use crate::security::EnvelopeEncryptor;
+use super::{FilterCondition, IndexStats, QueryResult, SimilarityMetric, VectorError, VectorStorage};
+
+/// Metadata fields encrypted by default
+pub const DEFAULT_SENSITIVE_FIELDS: &[&str] = &["content", "text"];
+
+/// Vector storage that encrypts sensitive metadata fields of one tenant
+pub struct EncryptedVectorStorage {
+    inner: Arc<dyn VectorStorage>,
+    encryptor: Arc<EnvelopeEncryptor>,
+    tenant_id: String,
+    sensitive_fields: HashSet<String>,
+}
+
+impl EncryptedVectorStorage {
+    /// Wrap a vector storage, encrypting [`DEFAULT_SENSITIVE_FIELDS`]
+    pub fn new(inner: Arc<dyn VectorStorage>, encryptor: Arc<EnvelopeEncryptor>, tenant_id: impl Into<String>) -> Self {
+        Self {
+            inner,
+            encryptor,
+            tenant_id: tenant_id.into(),
+            sensitive_fields: DEFAULT_SENSITIVE_FIELDS.iter().map(|f| f.to_string()).collect(),
+        }
+    }
+
+    /// Replace the set of encrypted metadata fields
+    pub fn with_sensitive_fields<I, S>(mut self, fields: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.sensitive_fields = fields.into_iter().map(Into::into).collect();
+        self
+    }
+
+    async fn seal_metadata(&self, mut metadata: HashMap<String, Value>) -> Result<HashMap<String, Value>> {
+        for (key, value) in metadata.iter_mut() {
+            if self.sensitive_fields.contains(key) {
+                *value = self.encryptor.encrypt_json(&self.tenant_id, value).await?;
+            }
+        }
+        Ok(metadata)
+    }
+
+    async fn open_metadata(&self, mut metadata: HashMap<String, Value>) -> Result<HashMap<String, Value>> {
+        for (key, value) in metadata.iter_mut() {
+            if self.sensitive_fields.contains(key) {
+                *value = self.encryptor.decrypt_json(&self.tenant_id, value).await?;
+            }
+        }
+        Ok(metadata)
+    }
+}
+
+#[async_trait]
+impl VectorStorage for EncryptedVectorStorage {
+    async fn create_index(
+        &self,
+        index_name: &str,
+        dimension: usize,
+        metric: Option<SimilarityMetric>,
+    ) -> std::result::Result<(), VectorError> {
+        self.inner.create_index(index_name, dimension, metric).await
+    }
+
+    async fn list_indexes(&self) -> std::result::Result<Vec<String>, VectorError> {
+        self.inner.list_indexes().await
+    }
+
+    async fn describe_index(&self, index_name: &str) -> std::result::Result<IndexStats, VectorError> {
+        self.inner.describe_index(index_name).await
+    }
+
+    async fn delete_index(&self, index_name: &str) -> std::result::Result<(), VectorError> {
+        self.inner.delete_index(index_name).await
+    }
+
+    async fn upsert(
+        &self,
+        index_name: &str,
+        vectors: Vec<Vec<f32>>,
+        ids: Option<Vec<String>>,
+        metadata: Option<Vec<HashMap<String, Value>>>,
+    ) -> std::result::Result<Vec<String>, VectorError> {
+        let metadata = match metadata {
+            Some(entries) => {
+                let mut sealed = Vec::with_capacity(entries.len());
+                for entry in entries {
+                    sealed.push(self.seal_metadata(entry).await?);
+                }
+                Some(sealed)
+            }
+            None => None,
+        };
+        self.inner.upsert(index_name, vectors, ids, metadata).await
+    }
+
+    async fn query(
+        &self,
+        index_name: &str,
+        query_vector: Vec<f32>,
+        top_k: usize,
+        filter: Option<FilterCondition>,
+        include_vectors: bool,
+    ) -> std::result::Result<Vec<QueryResult>, VectorError> {
+        let mut results = self.inner.query(index_name, query_vector, top_k, filter, include_vectors).await?;
+        for result in results.iter_mut() {
+            if let Some(metadata) = result.metadata.take() {
+                result.metadata = Some(self.open_metadata(metadata).await?);
+            }
+        }
+        Ok(results)
+    }
+
+    async fn update_by_id(
+        &self,
+        index_name: &str,
+        id: &str,
+        vector: Option<Vec<f32>>,
+        metadata: Option<HashMap<String, Value>>,
+    ) -> std::result::Result<(), VectorError> {
+        let metadata = match metadata {
+            Some(metadata) => Some(self.seal_metadata(metadata).await?),
+            None => None,
+        };
+        self.inner.update_by_id(index_name, id, vector, metadata).await
+    }
+
+    async fn delete_by_id(&self, index_name: &str, id: &str) -> std::result::Result<(), VectorError> {
+        self.inner.delete_by_id(index_name, id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::{EnvelopeConfig, LocalKeyProvider};
+    use crate::vector::MemoryVectorStorage;
+
+    #[tokio::test]
+    async fn test_encrypted_vector_metadata() {
+        let raw: Arc<dyn VectorStorage> = Arc::new(MemoryVectorStorage::new(3, None));
+        let encryptor = EnvelopeEncryptor::new(EnvelopeConfig::default())
+            .with_provider(Arc::new(LocalKeyProvider::new("master", &[5u8; 32]).unwrap()));
+        let storage = EncryptedVectorStorage::new(raw.clone(), Arc::new(encryptor), "acme");
+
+        storage.create_index("docs", 3, None).await.unwrap();
+        let mut metadata = HashMap::new();
+        metadata.insert("content".to_string(), Value::String("quarterly revenue".to_string()));
+        metadata.insert("source".to_string(), Value::String("report.pdf".to_string()));
+        storage.upsert("docs", vec![vec![1.0, 0.0, 0.0]], Some(vec!["d1".to_string()]), Some(vec![metadata]))
+            .await
+            .unwrap();
+
+        let stored = raw.query("docs", vec![1.0, 0.0, 0.0], 1, None, false).await.unwrap();
+        let stored = stored[0].metadata.as_ref().unwrap();
+        assert!(EnvelopeEncryptor::is_encrypted(stored["content"].as_str().unwrap()));
+        assert_eq!(stored["source"], "report.pdf");
+
+        let results = storage.query("docs", vec![1.0, 0.0, 0.0], 1, None, false).await.unwrap();
+        assert_eq!(results[0].metadata.as_ref().unwrap()["content"], "quarterly revenue");
+    }
+}
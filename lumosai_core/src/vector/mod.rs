@@ -195,10 +195,13 @@ pub trait VectorStorage: Send + Sync {
 
 pub mod memory;
 pub mod types;
+pub mod encrypted;
+pub mod snapshot;
 // pub mod adapter; // Temporarily disabled due to dependency conflicts
 
 // Re-export memory implementation
 pub use memory::MemoryVectorStorage;
+pub use encrypted::EncryptedVectorStorage;
 // pub use adapter::VectorStorageAdapter;
 
 /// Create a new memory vector storage instance
@@ -316,7 +319,8 @@ pub fn create_vector_storage(config: Option<VectorStorageConfig>) -> Result<Box<
                 if let Some(key) = api_key {
                     config = config.with_api_key(key);
                 }
-                let storage = WeaviateVectorStorage::with_config(config).await
+                let client_builder = std::sync::Arc::new(move || crate::config::http_client_builder_for(&url));
+                let storage = WeaviateVectorStorage::with_client_builder(config, client_builder).await
                     .map_err(|e| Error::Storage(e.to_string()))?;
                 Ok(Box::new(storage))
             }
@@ -0,0 +1,74 @@
+//! Vector index snapshots in object storage
+//!
+//! Copies snapshots of the memory vector storage to and from an [`ObjectStore`], so an
+//! index built once can be shared between instances or restored after a redeploy.
+
+use std::path::{Path, PathBuf};
+
+use lumosai_vector::memory::{MemoryConfig, MemoryVectorStorage, PersistenceConfig};
+
+use crate::error::{Error, Result};
+use crate::storage::object::{download_file, upload_file};
+use crate::storage::ObjectStore;
+
+/// Content type of uploaded snapshots
+const SNAPSHOT_CONTENT_TYPE: &str = "application/octet-stream";
+
+/// Upload a snapshot of all indexes and aliases in `storage` as `key`
+pub async fn upload_snapshot(storage: &MemoryVectorStorage, store: &dyn ObjectStore, key: &str) -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("index.snapshot");
+    storage.snapshot_to(&path).await?;
+    upload_file(store, key, &path, SNAPSHOT_CONTENT_TYPE).await
+}
+
+/// Download the snapshot `key` to `path`, returning `false` if the store has none
+pub async fn download_snapshot(store: &dyn ObjectStore, key: &str, path: &Path) -> Result<bool> {
+    match download_file(store, key, path).await {
+        Ok(()) => Ok(true),
+        Err(Error::NotFound(_)) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// Restore a memory vector storage from the snapshot `key`
+///
+/// The snapshot is downloaded to `path`, which the storage then uses for its own
+/// snapshots. Without a snapshot in the store the storage starts empty.
+pub async fn restore_memory_storage(
+    store: &dyn ObjectStore,
+    key: &str,
+    path: impl Into<PathBuf>,
+) -> Result<MemoryVectorStorage> {
+    let path = path.into();
+    download_snapshot(store, key, &path).await?;
+    let config = MemoryConfig::new().with_persistence(PersistenceConfig::new(path));
+    Ok(MemoryVectorStorage::with_config(config).await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::LocalObjectStore;
+    use lumosai_vector::prelude::{Document, IndexConfig, VectorStorage as _};
+
+    #[tokio::test]
+    async fn test_snapshot_round_trip() {
+        let remote = tempfile::tempdir().unwrap();
+        let local = tempfile::tempdir().unwrap();
+        let store = LocalObjectStore::new(remote.path());
+
+        let storage = MemoryVectorStorage::new().await.unwrap();
+        storage.create_index(IndexConfig::new("docs", 2)).await.unwrap();
+        storage.upsert_documents("docs", vec![Document::new("a", "first").with_embedding(vec![1.0, 0.0])]).await.unwrap();
+        upload_snapshot(&storage, &store, "indexes/docs.snapshot").await.unwrap();
+
+        let restored = restore_memory_storage(&store, "indexes/docs.snapshot", local.path().join("docs.snapshot")).await.unwrap();
+        assert_eq!(restored.list_indexes().await.unwrap(), vec!["docs".to_string()]);
+        let documents = restored.get_documents("docs", vec!["a".to_string()], true).await.unwrap();
+        assert_eq!(documents[0].embedding, Some(vec![1.0, 0.0]));
+
+        let empty = restore_memory_storage(&store, "indexes/missing.snapshot", local.path().join("missing.snapshot")).await.unwrap();
+        assert!(empty.list_indexes().await.unwrap().is_empty());
+    }
+}
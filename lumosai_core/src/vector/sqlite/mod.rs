@@ -18,7 +18,7 @@ pub struct SqliteVectorStorage {
 impl SqliteVectorStorage {
     /// Create a new SQLite vector storage with connection to database file
     pub fn new<P: AsRef<Path>>(db_path: P) -> Result<Self> {
-        let conn = Connection::open(db_path)
+        let mut conn = Connection::open(db_path)
             .map_err(|e| Error::Storage(format!("Failed to open SQLite database: {}", e)))?;
         
         // Create metadata table if it doesn't exist
@@ -53,11 +53,44 @@ impl SqliteVectorStorage {
             [],
         ).map_err(|e| Error::Storage(format!("Failed to create vectors table: {}", e)))?;
         
+        // Write-ahead logging: a crash mid-batch leaves the committed state intact, and SQLite
+        // replays or discards the log when the database is next opened
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))
+            .map_err(|e| Error::Storage(format!("Failed to enable write-ahead logging: {}", e)))?;
+        conn.pragma_update(None, "synchronous", "NORMAL")
+            .map_err(|e| Error::Storage(format!("Failed to set synchronous mode: {}", e)))?;
+        
+        Self::recover(&mut conn)?;
+        
         Ok(Self {
             conn: Arc::new(Mutex::new(conn)),
         })
     }
     
+    /// Check the database and repair what an interrupted write may have left behind
+    ///
+    /// Removes vectors and metadata of indexes that no longer exist and recomputes each
+    /// index's vector count from the stored vectors.
+    fn recover(conn: &mut Connection) -> Result<()> {
+        let check: String = conn.query_row("PRAGMA quick_check", [], |row| row.get(0))
+            .map_err(|e| Error::Storage(format!("Failed to check database integrity: {}", e)))?;
+        if check != "ok" {
+            return Err(Error::Storage(format!("SQLite database is corrupt: {}", check)));
+        }
+        
+        let tx = conn.transaction()
+            .map_err(|e| Error::Storage(format!("Failed to begin transaction: {}", e)))?;
+        tx.execute_batch(
+            "DELETE FROM vectors WHERE index_name NOT IN (SELECT name FROM indexes);
+             DELETE FROM metadata WHERE index_name NOT IN (SELECT name FROM indexes);
+             UPDATE indexes SET count = (SELECT COUNT(*) FROM vectors WHERE vectors.index_name = indexes.name);",
+        ).map_err(|e| Error::Storage(format!("Failed to recover database: {}", e)))?;
+        tx.commit()
+            .map_err(|e| Error::Storage(format!("Failed to commit transaction: {}", e)))?;
+        
+        Ok(())
+    }
+    
     /// Create a new in-memory SQLite vector storage (for testing)
     pub fn new_in_memory() -> Result<Self> {
         let conn = Connection::open_in_memory()
@@ -652,6 +685,32 @@ mod tests {
         assert!(indexes.is_empty());
     }
     
+    #[tokio::test]
+    async fn test_sqlite_recovery_on_open() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vectors.db");
+        
+        let storage = SqliteVectorStorage::new(&path).unwrap();
+        storage.create_index("docs", 2, None).await.unwrap();
+        storage.upsert("docs", vec![vec![1.0, 0.0], vec![0.0, 1.0]], None, None).await.unwrap();
+        drop(storage);
+        
+        // Leftovers of an interrupted write
+        {
+            let conn = Connection::open(&path).unwrap();
+            conn.execute("UPDATE indexes SET count = 7 WHERE name = 'docs'", []).unwrap();
+            conn.execute("INSERT INTO vectors (id, index_name, vector_json) VALUES ('x', 'gone', '[1.0]')", []).unwrap();
+        }
+        
+        let storage = SqliteVectorStorage::new(&path).unwrap();
+        assert_eq!(storage.describe_index("docs").await.unwrap().count, 2);
+        let conn = storage.conn.lock().unwrap();
+        let orphans: i64 = conn.query_row("SELECT COUNT(*) FROM vectors WHERE index_name = 'gone'", [], |row| row.get(0)).unwrap();
+        assert_eq!(orphans, 0);
+        let journal_mode: String = conn.query_row("PRAGMA journal_mode", [], |row| row.get(0)).unwrap();
+        assert_eq!(journal_mode, "wal");
+    }
+    
     #[tokio::test]
     async fn test_sqlite_similarity_metrics() {
         let storage = SqliteVectorStorage::new_in_memory().unwrap();
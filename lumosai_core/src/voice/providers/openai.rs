@@ -7,6 +7,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::json;
 
 use crate::base::{Base, BaseComponent, ComponentConfig};
+use crate::config::http_client_for;
 use crate::error::{Error, Result};
 use crate::logger::{Component, Logger, LogLevel};
 use crate::telemetry::TelemetrySink;
@@ -62,10 +63,11 @@ impl OpenAIVoice {
             log_level: Some(LogLevel::Info),
         };
         
+        let client = http_client_for(config.api_base.as_deref().unwrap_or("https://api.openai.com/v1"));
         Ok(Self {
             base: BaseComponent::new(component_config),
             config,
-            client: reqwest::Client::new(),
+            client,
         })
     }
     
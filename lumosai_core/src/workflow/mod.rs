@@ -60,7 +60,7 @@ pub enum WorkflowStatus {
 
 // 重新导出公共项
 pub use step::{BasicStep, StepBuilder, StepConfig};
-pub use workflow::{Workflow as WorkflowImpl, WorkflowInstance, resume_workflow};
-pub use types::{Step, StepContext, StepStatus, RetryConfig, WorkflowRunResult, WorkflowState};
+pub use workflow::{Workflow as WorkflowImpl, WorkflowBuilder, WorkflowInstance, resume_workflow};
+pub use types::{Step, StepContext, StepStatus, StepCondition, ErrorPolicy, RetryConfig, WorkflowRunResult, WorkflowState};
 pub use enhanced::{EnhancedWorkflow, WorkflowStep, StepFlowEntry, StepExecutor, StepType};
 pub use execution_engine::{ExecutionEngine, DefaultExecutionEngine, ExecutionMetrics};
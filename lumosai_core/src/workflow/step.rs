@@ -1,7 +1,11 @@
 use async_trait::async_trait;
 use std::sync::Arc;
 use serde::{Serialize, Deserialize};
+use serde_json::json;
+use crate::agent::trait_def::Agent;
+use crate::agent::types::AgentGenerateOptions;
 use crate::error::Error;
+use crate::llm::types::user_message;
 use super::types::{Step, StepContext, RetryConfig};
 
 /// 步骤的配置
@@ -50,6 +54,33 @@ impl BasicStep {
         }
     }
     
+    /// 创建由代理执行的步骤
+    ///
+    /// 代理收到步骤指令、步骤输入和触发数据；回复能解析为JSON时作为输出，
+    /// 否则输出`{"text": 回复}`。
+    pub fn from_agent(id: impl Into<String>, agent: Arc<dyn Agent>, instructions: impl Into<String>) -> Self {
+        let instructions = instructions.into();
+        let description = instructions.clone();
+        Self::new(
+            id.into(),
+            description,
+            move |ctx: StepContext| {
+                let agent = agent.clone();
+                let prompt = json!({
+                    "instructions": instructions,
+                    "input": ctx.input_data,
+                    "trigger": ctx.trigger_data,
+                }).to_string();
+                async move {
+                    let result = agent.generate(&[user_message(&prompt)], &AgentGenerateOptions::default()).await?;
+                    Ok(serde_json::from_str(&result.response)
+                        .unwrap_or_else(|_| json!({ "text": result.response })))
+                }
+            },
+            None,
+        )
+    }
+    
     /// 创建一个简单的步骤
     pub fn create_simple<F>(id: String, description: String, f: F) -> Self
     where
@@ -182,6 +182,7 @@ mod tests {
                     total_tokens: 0,
                 },
                 metadata: HashMap::new(),
+                trace: None,
             })
         }));
         let agent2 = Arc::new(MockAgent::new(|messages| {
@@ -209,6 +210,7 @@ mod tests {
                     total_tokens: 0,
                 },
                 metadata: HashMap::new(),
+                trace: None,
             })
         }));
         
@@ -282,6 +284,7 @@ mod tests {
                     total_tokens: 0,
                 },
                 metadata: HashMap::new(),
+                trace: None,
             })
         }));
         let agent2 = Arc::new(MockAgent::new(|messages| {
@@ -309,6 +312,7 @@ mod tests {
                     total_tokens: 0,
                 },
                 metadata: HashMap::new(),
+                trace: None,
             })
         }));
         let agent3 = Arc::new(MockAgent::new(|messages| {
@@ -336,6 +340,7 @@ mod tests {
                     total_tokens: 0,
                 },
                 metadata: HashMap::new(),
+                trace: None,
             })
         }));
         
@@ -429,6 +434,7 @@ mod tests {
                     total_tokens: 0,
                 },
                 metadata: HashMap::new(),
+                trace: None,
             })
         });
 
@@ -519,4 +525,67 @@ mod tests {
         assert!(!output.to_string().contains("这个步骤应该不会执行"), 
                "条件步骤不应该被执行");
     }
-} 
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_graph_workflow_parallel_steps_and_error_policies() {
+        use crate::workflow::{BasicStep, ErrorPolicy, WorkflowImpl};
+        use crate::workflow::types::StepResult;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let flaky_calls = Arc::new(AtomicUsize::new(0));
+        let calls = flaky_calls.clone();
+
+        // fetch之后并行执行flaky和broken，两者都完成后执行combine
+        let workflow = WorkflowImpl::new("graph".to_string(), "图工作流".to_string())
+            .add_step(BasicStep::create_simple("fetch".to_string(), String::new(), |_| {
+                Ok(json!({ "topic": "rust" }))
+            }), None, None)
+            .add_step(BasicStep::create_simple("flaky".to_string(), String::new(), move |input: Value| {
+                if calls.fetch_add(1, Ordering::SeqCst) < 2 {
+                    Err(Error::Workflow("暂时失败".to_string()))
+                } else {
+                    Ok(json!({ "summary": format!("about {}", input["topic"].as_str().unwrap_or_default()) }))
+                }
+            }), None, None)
+            .add_step(BasicStep::create_simple("broken".to_string(), String::new(), |_| {
+                Err(Error::Workflow("总是失败".to_string()))
+            }), None, None)
+            .add_step(BasicStep::create_simple("backup".to_string(), String::new(), |_| {
+                Ok(json!({ "summary": "backup" }))
+            }), None, None)
+            .add_step(BasicStep::create_simple("combine".to_string(), String::new(), Ok), None, None)
+            .add_dependency("fetch", "flaky")
+            .add_dependency("fetch", "broken")
+            .add_dependency("flaky", "combine")
+            .add_dependency("broken", "combine")
+            .on_error("flaky", ErrorPolicy::Retry { attempts: 3 })
+            .on_error("broken", ErrorPolicy::Fallback("backup".to_string()))
+            .map_input("flaky", "topic", "fetch.topic")
+            .map_input("combine", "first", "flaky.summary")
+            .map_input("combine", "second", "broken.summary")
+            .map_input("combine", "query", "trigger.query")
+            .build();
+
+        let result = workflow.create_run(json!({ "query": "q" })).run().await.unwrap();
+        assert_eq!(flaky_calls.load(Ordering::SeqCst), 3);
+        match &result.results["combine"] {
+            StepResult::Success { output } => {
+                assert_eq!(output, &json!({ "first": "about rust", "second": "backup", "query": "q" }));
+            },
+            other => panic!("combine步骤应该成功: {:?}", other),
+        }
+
+        // abort策略的步骤失败时中止工作流
+        let workflow = WorkflowImpl::new("abort".to_string(), "中止工作流".to_string())
+            .add_step(BasicStep::create_simple("fail".to_string(), String::new(), |_| {
+                Err(Error::Workflow("失败".to_string()))
+            }), None, None)
+            .add_step(BasicStep::create_simple("after".to_string(), String::new(), Ok), None, None)
+            .add_dependency("fail", "after")
+            .on_error("fail", ErrorPolicy::Abort)
+            .build();
+
+        let error = workflow.create_run(json!({})).run().await.unwrap_err();
+        assert!(error.to_string().contains("步骤 fail 失败，工作流已中止"));
+    }
+}
@@ -194,6 +194,20 @@ pub struct RetryConfig {
     pub delay: Option<u64>,
 }
 
+/// 步骤失败后的处理方式
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ErrorPolicy {
+    /// 重试步骤，总共最多执行`attempts`次
+    Retry {
+        /// 最大执行次数
+        attempts: usize,
+    },
+    /// 执行备用步骤，其输出作为失败步骤的输出
+    Fallback(String),
+    /// 中止整个工作流
+    Abort,
+}
+
 /// 步骤的依赖检查结果
 #[derive(Debug, Clone)]
 pub enum DependencyCheckOutput {
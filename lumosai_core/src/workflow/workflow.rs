@@ -10,7 +10,7 @@ use crate::error::Error;
 use super::types::{
     Step, StepResult, StepContext, WorkflowRunResult, WorkflowState, 
     StepCondition, DependencyCheckOutput, StepExecutorOutput, RetryConfig,
-    ActivePathInfo, StepStatus, StepStatusInfo
+    ActivePathInfo, StepStatus, StepStatusInfo, ErrorPolicy
 };
 
 /// 工作流图节点
@@ -22,6 +22,10 @@ struct StepNode {
     when: Option<StepCondition>,
     /// 步骤配置数据
     data: serde_json::Value,
+    /// 失败后的处理方式
+    on_error: Option<ErrorPolicy>,
+    /// 输入映射：输入字段 -> 来源路径（`步骤ID.路径`或`trigger.路径`）
+    input_mapping: HashMap<String, String>,
 }
 
 /// 工作流图
@@ -84,35 +88,52 @@ impl WorkflowInstance {
     }
     
     /// 开始执行工作流
+    ///
+    /// 前驱步骤全部完成的步骤作为一批并行执行；`Abort`策略的步骤失败时中止工作流。
     pub async fn run(&self) -> Result<WorkflowRunResult, Error> {
-        // 使用层次遍历处理工作流步骤
+        // 每个步骤的前驱步骤
+        let mut predecessors: HashMap<&str, HashSet<&str>> = HashMap::new();
+        for (from_id, to_ids) in &self.graph.edges {
+            for to_id in to_ids {
+                predecessors.entry(to_id.as_str()).or_default().insert(from_id.as_str());
+            }
+        }
+        
         let mut pending_steps: Vec<String> = self.graph.initial.clone();
         let mut processed_steps: HashSet<String> = HashSet::new();
         
-        // 处理所有步骤，直到没有更多步骤或所有步骤都已处理
         while !pending_steps.is_empty() {
-            let step_id = pending_steps.remove(0);
-            
-            // 如果步骤已处理过，跳过
-            if processed_steps.contains(&step_id) {
-                continue;
-            }
-            
-            // 执行步骤
-            if let Err(e) = self.execute_step(&step_id).await {
-                eprintln!("执行步骤 {} 失败: {}", step_id, e);
+            let (ready, waiting): (Vec<String>, Vec<String>) = pending_steps.drain(..).partition(|step_id| {
+                predecessors.get(step_id.as_str())
+                    .is_none_or(|from_ids| from_ids.iter().all(|from_id| processed_steps.contains(*from_id)))
+            });
+            if ready.is_empty() {
+                return Err(Error::Workflow(format!("步骤的依赖无法满足: {}", waiting.join(", "))));
             }
+            pending_steps = waiting;
             
-            // 标记为已处理
-            processed_steps.insert(step_id.clone());
+            // 并行执行就绪的步骤
+            let results = futures::future::join_all(ready.iter().map(|step_id| self.execute_step(step_id))).await;
             
-            // 添加后续步骤到待处理列表
-            if let Some(next_steps) = self.graph.edges.get(&step_id) {
-                for next_step in next_steps {
-                    if !processed_steps.contains(next_step) {
-                        pending_steps.push(next_step.clone());
+            for (step_id, result) in ready.into_iter().zip(results) {
+                match result {
+                    Ok(StepResult::Failed { error }) if self.aborts_on_error(&step_id) => {
+                        return Err(Error::Workflow(format!("步骤 {} 失败，工作流已中止: {}", step_id, error)));
+                    },
+                    Ok(_) => {},
+                    Err(e) => eprintln!("执行步骤 {} 失败: {}", step_id, e),
+                }
+                
+                // 添加后续步骤到待处理列表
+                if let Some(next_steps) = self.graph.edges.get(&step_id) {
+                    for next_step in next_steps {
+                        if !processed_steps.contains(next_step) && !pending_steps.contains(next_step) {
+                            pending_steps.push(next_step.clone());
+                        }
                     }
                 }
+                
+                processed_steps.insert(step_id);
             }
         }
         
@@ -123,6 +144,30 @@ impl WorkflowInstance {
         Ok(self.get_result().await)
     }
     
+    /// 步骤失败时是否中止工作流
+    fn aborts_on_error(&self, step_id: &str) -> bool {
+        self.graph.nodes.get(step_id)
+            .is_some_and(|node| node.on_error == Some(ErrorPolicy::Abort))
+    }
+    
+    /// 步骤的最大执行次数
+    fn max_attempts(&self, node: &StepNode) -> usize {
+        if let Some(ErrorPolicy::Retry { attempts }) = &node.on_error {
+            return *attempts;
+        }
+        node.step.retry_config()
+            .and_then(|c| c.attempts)
+            .or_else(|| self.retry_config.as_ref().and_then(|c| c.attempts))
+            .unwrap_or(1)
+    }
+    
+    /// 重试前的延迟（毫秒）
+    fn retry_delay(&self, node: &StepNode) -> Option<u64> {
+        node.step.retry_config()
+            .and_then(|c| c.delay)
+            .or_else(|| self.retry_config.as_ref().and_then(|c| c.delay))
+    }
+    
     /// 等待工作流执行完成
     async fn wait_for_completion(&self) -> Result<(), Error> {
         // 简单实现：轮询检查是否所有步骤都完成了
@@ -164,8 +209,23 @@ impl WorkflowInstance {
         
         match check_result {
             DependencyCheckOutput::ConditionsMet => {
-                // 条件满足，执行步骤
-                let executor_output = self.run_step(step_id, &node).await?;
+                // 条件满足，执行步骤，失败时按重试次数重新执行
+                let mut executor_output = self.run_step(step_id, &node).await?;
+                while matches!(executor_output, StepExecutorOutput::StepFailed { .. })
+                    && self.attempts(step_id).await < self.max_attempts(&node)
+                {
+                    if let Some(delay) = self.retry_delay(&node) {
+                        sleep(Duration::from_millis(delay)).await;
+                    }
+                    executor_output = self.run_step(step_id, &node).await?;
+                }
+                
+                // 重试后仍失败时执行备用步骤
+                if let (StepExecutorOutput::StepFailed { error }, Some(ErrorPolicy::Fallback(fallback_id))) =
+                    (&executor_output, &node.on_error)
+                {
+                    executor_output = self.run_fallback(fallback_id, error).await?;
+                }
                 
                 match executor_output {
                     StepExecutorOutput::StepSuccess { output } => {
@@ -224,6 +284,50 @@ impl WorkflowInstance {
         }
     }
     
+    /// 步骤已执行的次数
+    async fn attempts(&self, step_id: &str) -> usize {
+        let state = self.state.lock().await;
+        state.attempts.get(step_id).copied().unwrap_or(0)
+    }
+    
+    /// 执行备用步骤，成功时其输出作为失败步骤的输出
+    async fn run_fallback(&self, fallback_id: &str, error: &str) -> Result<StepExecutorOutput, Error> {
+        let fallback = self.graph.nodes.get(fallback_id)
+            .ok_or_else(|| Error::Workflow(format!("未找到备用步骤: {}", fallback_id)))?;
+        
+        match self.run_step(fallback_id, fallback).await? {
+            StepExecutorOutput::StepFailed { error: fallback_error } => Ok(StepExecutorOutput::StepFailed {
+                error: format!("{}；备用步骤 {} 也失败: {}", error, fallback_id, fallback_error),
+            }),
+            output => Ok(output),
+        }
+    }
+    
+    /// 根据输入映射构建步骤输入，映射的字段覆盖配置数据中的同名字段
+    fn resolve_input(&self, node: &StepNode, steps: &HashMap<String, StepResult>) -> serde_json::Value {
+        if node.input_mapping.is_empty() {
+            return node.data.clone();
+        }
+        
+        let mut input = match &node.data {
+            serde_json::Value::Object(data) => data.clone(),
+            _ => serde_json::Map::new(),
+        };
+        for (key, source) in &node.input_mapping {
+            let (source_id, path) = source.split_once('.').unwrap_or((source.as_str(), ""));
+            let value = if source_id == "trigger" {
+                self.get_value_at_path(&self.trigger_data, path)
+            } else {
+                match steps.get(source_id) {
+                    Some(StepResult::Success { output }) => self.get_value_at_path(output, path),
+                    _ => serde_json::Value::Null,
+                }
+            };
+            input.insert(key.clone(), value);
+        }
+        serde_json::Value::Object(input)
+    }
+    
     /// 检查步骤依赖条件
     async fn check_dependencies(&self, node: &StepNode) -> Result<DependencyCheckOutput, Error> {
         if let Some(condition) = &node.when {
@@ -390,10 +494,7 @@ impl WorkflowInstance {
         };
         
         // 检查是否超过最大重试次数
-        let max_attempts = node.step.retry_config()
-            .and_then(|c| c.attempts)
-            .or_else(|| self.retry_config.as_ref().and_then(|c| c.attempts))
-            .unwrap_or(1);
+        let max_attempts = self.max_attempts(node);
         
         if attempts > max_attempts {
             return Ok(StepExecutorOutput::StepFailed { 
@@ -417,28 +518,30 @@ impl WorkflowInstance {
         
         // 构建步骤上下文
         let context = {
+            let steps: HashMap<String, StepResult> = state_guard.steps.iter().map(|(k, v)| {
+                let result = match v.status {
+                    StepStatus::Success => StepResult::Success { 
+                        output: v.payload.clone().unwrap_or(serde_json::Value::Null) 
+                    },
+                    StepStatus::Failed => StepResult::Failed { 
+                        error: v.error.clone().unwrap_or_else(|| "Unknown error".to_string()) 
+                    },
+                    StepStatus::Suspended => StepResult::Suspended { 
+                        suspend_payload: v.payload.clone(), 
+                        output: None 
+                    },
+                    StepStatus::Waiting => StepResult::Waiting,
+                    StepStatus::Skipped => StepResult::Skipped,
+                    _ => StepResult::Waiting,
+                };
+                (k.clone(), result)
+            }).collect();
+            
             StepContext {
                 run_id: self.run_id.clone(),
-                input_data: node.data.clone(),
+                input_data: self.resolve_input(node, &steps),
                 trigger_data: self.trigger_data.clone(),
-                steps: state_guard.steps.iter().map(|(k, v)| {
-                    let result = match v.status {
-                        StepStatus::Success => StepResult::Success { 
-                            output: v.payload.clone().unwrap_or(serde_json::Value::Null) 
-                        },
-                        StepStatus::Failed => StepResult::Failed { 
-                            error: v.error.clone().unwrap_or_else(|| "Unknown error".to_string()) 
-                        },
-                        StepStatus::Suspended => StepResult::Suspended { 
-                            suspend_payload: v.payload.clone(), 
-                            output: None 
-                        },
-                        StepStatus::Waiting => StepResult::Waiting,
-                        StepStatus::Skipped => StepResult::Skipped,
-                        _ => StepResult::Waiting,
-                    };
-                    (k.clone(), result)
-                }).collect(),
+                steps,
                 attempts: state_guard.attempts.clone(),
             }
         };
@@ -586,6 +689,8 @@ pub struct WorkflowBuilder {
     id: String,
     /// 工作流名称
     name: String,
+    /// 工作流描述
+    description: Option<String>,
     /// 步骤集
     steps: HashMap<String, Arc<dyn Step>>,
     /// 步骤条件
@@ -594,6 +699,10 @@ pub struct WorkflowBuilder {
     step_data: HashMap<String, serde_json::Value>,
     /// 步骤关系
     dependencies: HashMap<String, HashSet<String>>,
+    /// 步骤失败后的处理方式
+    error_policies: HashMap<String, ErrorPolicy>,
+    /// 步骤输入映射
+    input_mappings: HashMap<String, HashMap<String, String>>,
     /// 重试配置
     retry_config: Option<RetryConfig>,
 }
@@ -604,14 +713,23 @@ impl WorkflowBuilder {
         Self {
             id,
             name,
+            description: None,
             steps: HashMap::new(),
             conditions: HashMap::new(),
             step_data: HashMap::new(),
             dependencies: HashMap::new(),
+            error_policies: HashMap::new(),
+            input_mappings: HashMap::new(),
             retry_config: None,
         }
     }
     
+    /// 设置工作流描述
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+    
     /// 添加步骤
     pub fn add_step<S: Step + 'static>(
         mut self,
@@ -644,6 +762,25 @@ impl WorkflowBuilder {
         self
     }
     
+    /// 设置步骤失败后的处理方式
+    ///
+    /// `Fallback`指向的步骤只在失败时执行，不作为初始步骤。
+    pub fn on_error(mut self, step_id: &str, policy: ErrorPolicy) -> Self {
+        self.error_policies.insert(step_id.to_string(), policy);
+        self
+    }
+    
+    /// 把`source`处的值映射到步骤输入的`key`字段
+    ///
+    /// `source`为`步骤ID.路径`或`trigger.路径`；只有步骤ID时映射整个输出。
+    pub fn map_input(mut self, step_id: &str, key: &str, source: &str) -> Self {
+        self.input_mappings
+            .entry(step_id.to_string())
+            .or_default()
+            .insert(key.to_string(), source.to_string());
+        self
+    }
+    
     /// 设置重试配置
     pub fn retry_config(mut self, retry_config: RetryConfig) -> Self {
         self.retry_config = Some(retry_config);
@@ -664,6 +801,8 @@ impl WorkflowBuilder {
                 step: Arc::clone(step),
                 when: condition,
                 data,
+                on_error: self.error_policies.get(step_id).cloned(),
+                input_mapping: self.input_mappings.get(step_id).cloned().unwrap_or_default(),
             });
         }
         
@@ -683,8 +822,16 @@ impl WorkflowBuilder {
             }
         }
         
+        // 备用步骤只在其他步骤失败时执行
+        let fallback_steps: HashSet<&String> = self.error_policies.values()
+            .filter_map(|policy| match policy {
+                ErrorPolicy::Fallback(step_id) => Some(step_id),
+                _ => None,
+            })
+            .collect();
+        
         for step_id in self.steps.keys() {
-            if !has_incoming.contains(step_id) {
+            if !has_incoming.contains(step_id) && !fallback_steps.contains(step_id) {
                 graph.initial.push(step_id.clone());
             }
         }
@@ -693,6 +840,7 @@ impl WorkflowBuilder {
         Workflow {
             id: self.id,
             name: self.name,
+            description: self.description,
             definition: WorkflowDefinition {
                 graph: Arc::new(graph),
                 retry_config: self.retry_config,
@@ -707,6 +855,8 @@ pub struct Workflow {
     pub id: String,
     /// 工作流名称
     pub name: String,
+    /// 工作流描述
+    pub description: Option<String>,
     /// 工作流定义
     definition: WorkflowDefinition,
 }
@@ -736,6 +886,11 @@ impl Workflow {
     pub fn name(&self) -> &str {
         &self.name
     }
+    
+    /// 获取工作流描述
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
 }
 
 /// 恢复工作流
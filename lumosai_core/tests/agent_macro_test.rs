@@ -0,0 +1,53 @@
+//! Integration tests for guardrails, memory and RAG bindings in the `agent!` macro
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use lumos_macro::agent;
+use lumosai_core::agent::RETRIEVAL_TOOL_ID;
+use lumosai_core::tool::{ToolExecutionContext, ToolExecutionOptions};
+use lumosai_core::vector::{MemoryVectorStorage, VectorStorage};
+use lumosai_core::{Agent, MockLlmProvider};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_agent_macro_with_guardrails_memory_and_rag() {
+    let store = Arc::new(MemoryVectorStorage::new(3, None));
+    store.create_index("docs", 3, None).await.unwrap();
+    store.upsert(
+        "docs",
+        vec![vec![1.0, 0.0, 0.0], vec![0.0, 1.0, 0.0]],
+        Some(vec!["refunds".to_string(), "shipping".to_string()]),
+        Some(vec![
+            HashMap::from([("text".to_string(), json!("Refunds take 5 days"))]),
+            HashMap::from([("text".to_string(), json!("Shipping is free"))]),
+        ]),
+    ).await.unwrap();
+
+    let agent = agent! {
+        name: "support",
+        instructions: "You answer customer questions.",
+        provider: MockLlmProvider::new_with_embeddings(vec![vec![1.0, 0.0, 0.0]]),
+        guardrails: {
+            blocked_topics: ["legal advice"],
+            rules: ["Never share internal ticket ids"],
+            max_tool_calls: 3,
+        },
+        memory: { type: vector, store: store },
+        rag: { collection: "docs", top_k: 1 }
+    };
+
+    assert!(agent.get_instructions().starts_with("You answer customer questions."));
+    assert!(agent.get_instructions().contains("Politely decline any request about: legal advice"));
+    assert!(agent.get_instructions().contains("- Never share internal ticket ids"));
+    assert!(agent.get_memory().is_some());
+
+    let tool = agent.get_tool(RETRIEVAL_TOOL_ID).expect("retrieval tool is registered");
+    let result = tool.execute(json!({ "query": "how long do refunds take?" }), ToolExecutionContext::new(), &ToolExecutionOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(result["collection"], "docs");
+    assert_eq!(result["results"].as_array().unwrap().len(), 1);
+    assert_eq!(result["results"][0]["id"], "refunds");
+    assert_eq!(result["results"][0]["metadata"]["text"], "Refunds take 5 days");
+}
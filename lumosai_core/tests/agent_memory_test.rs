@@ -233,6 +233,9 @@ mod tests {
             tool_choice: Some(lumosai_core::agent::types::ToolChoice::Auto),
             llm_options: LlmOptions::default(),
             context_window: None,
+            capture_trace: false,
+            deterministic: false,
+            language: None,
         };
         
         // Call generate_with_memory
@@ -283,6 +286,9 @@ mod tests {
             tool_choice: Some(lumosai_core::agent::types::ToolChoice::Auto),
             llm_options: LlmOptions::default(),
             context_window: None,
+            capture_trace: false,
+            deterministic: false,
+            language: None,
         };
         
         // First message
@@ -350,6 +356,9 @@ mod tests {
             tool_choice: Some(lumosai_core::agent::types::ToolChoice::Auto),
             llm_options: LlmOptions::default(),
             context_window: None,
+            capture_trace: false,
+            deterministic: false,
+            language: None,
         };
         
         let result = agent.generate_with_memory(&messages, None, &options).await;
@@ -397,6 +406,9 @@ mod tests {
             tool_choice: Some(lumosai_core::agent::types::ToolChoice::Auto),
             llm_options: LlmOptions::default(),
             context_window: None,
+            capture_trace: false,
+            deterministic: false,
+            language: None,
         };
         
         let result = agent.generate_with_memory(&messages, Some("test_thread".to_string()), &options).await;
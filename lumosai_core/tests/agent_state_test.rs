@@ -0,0 +1,88 @@
+//! Integration tests for typed agent state persisted in sessions via `#[derive(AgentState)]`
+
+use std::sync::Arc;
+
+use lumosai_core::agent::{user_message, AgentState, MemorySessionStorage, SessionManager, StateChange};
+use lumosai_core::agent::types::AgentGenerateOptions;
+use lumosai_core::{create_basic_agent, MockLlmProvider};
+use lumosai_derive::AgentState;
+use serde_json::json;
+
+#[derive(Debug, Default, Clone, PartialEq, AgentState)]
+#[agent_state(key = "checkout")]
+struct CheckoutState {
+    /// Items in the cart
+    items: Vec<String>,
+    /// Whether the user confirmed the order
+    confirmed: bool,
+    #[agent_state(skip)]
+    scratch: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, AgentState)]
+struct TripPlan {
+    destination: Option<String>,
+}
+
+#[test]
+fn test_agent_state_serialization_and_schema() {
+    let state = CheckoutState {
+        items: vec!["book".to_string()],
+        confirmed: true,
+        scratch: Some("temporary".to_string()),
+    };
+    let value = state.to_state_value().unwrap();
+    assert_eq!(value, json!({ "items": ["book"], "confirmed": true }));
+
+    // Skipped and missing fields fall back to their defaults
+    let restored = CheckoutState::from_state_value(&json!({ "items": ["pen"] })).unwrap();
+    assert_eq!(restored, CheckoutState { items: vec!["pen".to_string()], ..Default::default() });
+    assert!(CheckoutState::from_state_value(&json!("not an object")).is_err());
+
+    let schema = CheckoutState::state_schema();
+    assert_eq!(schema["properties"]["items"], json!({ "type": "array", "description": "Items in the cart" }));
+    assert_eq!(schema["properties"]["confirmed"]["type"], "boolean");
+    assert!(schema["properties"].get("scratch").is_none());
+
+    assert_eq!(TripPlan::STATE_KEY, "trip_plan");
+}
+
+#[tokio::test]
+async fn test_generate_with_state_persists_changes_across_turns() {
+    let manager = SessionManager::new(Arc::new(MemorySessionStorage::new()));
+    manager.create_session("s1".to_string(), "shop".to_string(), None).await.unwrap();
+
+    let llm = Arc::new(MockLlmProvider::new(vec![
+        "Added the book to your cart".to_string(),
+        "Your order is confirmed".to_string(),
+    ]));
+    let agent = create_basic_agent("shop", "You help users check out", llm);
+    let options = AgentGenerateOptions::default();
+
+    let (result, changes) = manager.generate_with_state::<CheckoutState, _, _>(
+        "s1", &agent, &[user_message("Add a book")], &options,
+        |state, _| state.items.push("book".to_string()),
+    ).await.unwrap();
+    assert_eq!(result.response, "Added the book to your cart");
+    assert_eq!(changes, vec![StateChange { field: "items".to_string(), old: json!([]), new: json!(["book"]) }]);
+
+    let (_, changes) = manager.generate_with_state::<CheckoutState, _, _>(
+        "s1", &agent, &[user_message("Confirm")], &options,
+        |state, _| {
+            assert_eq!(state.items, vec!["book".to_string()]);
+            state.confirmed = true;
+        },
+    ).await.unwrap();
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].field, "confirmed");
+
+    let session = manager.get_session("s1").await.unwrap().unwrap();
+    let state = CheckoutState::load(&session).unwrap();
+    assert_eq!(state.items, vec!["book".to_string()]);
+    assert!(state.confirmed);
+
+    let missing = manager.generate_with_state::<CheckoutState, _, _>(
+        "missing", &agent, &[user_message("Hi")], &options, |_, _| {},
+    ).await;
+    assert!(missing.is_err());
+}
@@ -0,0 +1,68 @@
+//! Integration tests for capturing agent reasoning traces
+
+use std::sync::Arc;
+
+use lumosai_core::agent::types::AgentGenerateOptions;
+use lumosai_core::agent::{user_message, AgentBuilder, ReflectionConfig, TraceEntry};
+use lumosai_core::{Agent, MockLlmProvider};
+
+fn responses(responses: &[&str]) -> Arc<MockLlmProvider> {
+    Arc::new(MockLlmProvider::new(responses.iter().map(|r| r.to_string()).collect()))
+}
+
+#[tokio::test]
+async fn test_trace_keeps_reasoning_out_of_the_answer() {
+    let agent = AgentBuilder::new()
+        .name("calculator")
+        .instructions("Answer arithmetic questions")
+        .model(responses(&["<think>6 times 7 is 42.</think>The answer is 42."]))
+        .build()
+        .unwrap();
+    let options = AgentGenerateOptions {
+        capture_trace: true,
+        ..Default::default()
+    };
+
+    let result = agent.generate(&[user_message("What is 6 times 7?")], &options).await.unwrap();
+    assert_eq!(result.response, "The answer is 42.");
+    let trace = result.trace.unwrap();
+    assert_eq!(trace.thoughts(), ["6 times 7 is 42."]);
+    assert!(trace.tools_called().is_empty());
+}
+
+#[tokio::test]
+async fn test_trace_records_critiques_and_is_opt_in() {
+    let replies = [
+        "Thought: Paris is in France.\nFinal Answer: France",
+        r#"{"score": 0.9, "critique": "Correct."}"#,
+    ];
+    let agent = AgentBuilder::new()
+        .name("geographer")
+        .instructions("Answer geography questions")
+        .model(responses(&replies))
+        .reflection(ReflectionConfig::new())
+        .build()
+        .unwrap();
+    let options = AgentGenerateOptions {
+        capture_trace: true,
+        ..Default::default()
+    };
+
+    let result = agent.generate(&[user_message("Which country is Paris in?")], &options).await.unwrap();
+    assert_eq!(result.response, "France");
+    let entries = result.trace.unwrap().entries;
+    assert_eq!(entries, [
+        TraceEntry::Critique { step: 1, score: 0.9, feedback: "Correct.".to_string() },
+        TraceEntry::Thought { step: 2, content: "Thought: Paris is in France.".to_string() },
+    ]);
+
+    let agent = AgentBuilder::new()
+        .name("geographer")
+        .instructions("Answer geography questions")
+        .model(responses(&["<think>Easy.</think>France"]))
+        .build()
+        .unwrap();
+    let result = agent.generate(&[user_message("Which country is Paris in?")], &AgentGenerateOptions::default()).await.unwrap();
+    assert!(result.trace.is_none());
+    assert_eq!(result.response, "<think>Easy.</think>France");
+}
@@ -0,0 +1,84 @@
+//! Integration tests for the HTTP application generated by the `lumos!` macro
+
+use std::future::IntoFuture;
+use std::sync::Arc;
+
+use lumos_macro::{lumos, tool};
+use lumosai_core::{create_basic_agent, MockLlmProvider};
+use serde_json::{json, Value};
+
+#[tool(name = "greet", description = "Greets someone")]
+fn greet(#[parameter(name = "who", description = "Name to greet")] name: String) -> String {
+    format!("Hello, {}!", name)
+}
+
+#[tokio::test]
+async fn test_lumos_app_serves_agents_and_mcp_tools() {
+    let llm = Arc::new(MockLlmProvider::new(vec!["Apple trades at $190".to_string()]));
+    let stock_agent = create_basic_agent("stock_agent", "You answer stock questions", llm);
+
+    let app = lumos! {
+        name: "stock_assistant",
+        description: "Answers stock questions",
+        agents: { stock_agent },
+        tools: { greet },
+        mcp_endpoints: vec!["https://api.example.com/mcp"],
+        server: { host: "127.0.0.1", port: 8080 }
+    };
+    assert_eq!(app.server().unwrap().address(), "127.0.0.1:8080");
+    assert_eq!(app.mcp_endpoints(), ["https://api.example.com/mcp".to_string()]);
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(axum::serve(listener, app.router()).into_future());
+    let client = reqwest::Client::new();
+
+    let info: Value = client.get(format!("{}/api", base)).send().await.unwrap().json().await.unwrap();
+    assert_eq!(info["agents"], json!(["stock_agent"]));
+    assert_eq!(info["tools"], json!(["greet"]));
+
+    let response = client.post(format!("{}/api/agents/stock_agent/generate", base))
+        .json(&json!({ "message": "How much is Apple?" }))
+        .send().await.unwrap();
+    assert!(response.status().is_success());
+    let result: Value = response.json().await.unwrap();
+    assert_eq!(result["response"], "Apple trades at $190");
+
+    let missing = client.post(format!("{}/api/agents/unknown/generate", base))
+        .json(&json!({ "message": "Hi" }))
+        .send().await.unwrap();
+    assert_eq!(missing.status(), reqwest::StatusCode::NOT_FOUND);
+    let body: Value = missing.json().await.unwrap();
+    assert_eq!(body["error"], json!({ "code": "not_found", "message": "Agent 'unknown' not found", "retryable": false, "status": 404 }));
+
+    let empty = client.post(format!("{}/api/agents/stock_agent/generate", base))
+        .json(&json!({}))
+        .send().await.unwrap();
+    assert_eq!(empty.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    let tools: Value = client.post(format!("{}/mcp", base))
+        .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": "tools/list" }))
+        .send().await.unwrap().json().await.unwrap();
+    assert_eq!(tools["result"]["tools"][0]["name"], "greet");
+    assert_eq!(tools["result"]["tools"][0]["inputSchema"]["required"], json!(["who"]));
+
+    let call: Value = client.post(format!("{}/mcp", base))
+        .json(&json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/call", "params": { "name": "greet", "arguments": { "who": "Ann" } } }))
+        .send().await.unwrap().json().await.unwrap();
+    assert_eq!(call["id"], 2);
+    assert_eq!(call["result"], json!({ "content": [{ "type": "text", "text": "Hello, Ann!" }], "isError": false }));
+
+    let unknown: Value = client.post(format!("{}/mcp", base))
+        .json(&json!({ "jsonrpc": "2.0", "id": 3, "method": "resources/list" }))
+        .send().await.unwrap().json().await.unwrap();
+    assert_eq!(unknown["error"]["code"], -32601);
+
+    // New requests are rejected once shutdown starts
+    let report = app.shutdown().await;
+    assert!(report.drained);
+    let rejected = client.get(format!("{}/health", base)).send().await.unwrap();
+    assert_eq!(rejected.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+    let body: Value = rejected.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "unavailable");
+    assert_eq!(body["error"]["retryable"], true);
+}
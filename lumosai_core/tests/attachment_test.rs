@@ -0,0 +1,155 @@
+//! Integration tests for uploading files and referencing them from agent requests over HTTP
+
+use std::future::IntoFuture;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream, StreamExt};
+use lumosai_core::agent::AgentBuilder;
+use lumosai_core::attachment::AttachmentService;
+use lumosai_core::storage::LocalObjectStore;
+use lumosai_core::{LlmOptions, LlmProvider, LumosApp, Message, Result};
+use serde_json::{json, Value};
+
+const BOUNDARY: &str = "lumos-test-boundary";
+
+/// Records the messages of every request
+#[derive(Default)]
+struct RecordingProvider {
+    prompts: Mutex<Vec<Vec<Message>>>,
+}
+
+#[async_trait]
+impl LlmProvider for RecordingProvider {
+    fn name(&self) -> &str {
+        "recording"
+    }
+
+    async fn generate(&self, _prompt: &str, _options: &LlmOptions) -> Result<String> {
+        Ok("The meeting moved to Friday.".to_string())
+    }
+
+    async fn generate_with_messages(&self, messages: &[Message], _options: &LlmOptions) -> Result<String> {
+        self.prompts.lock().unwrap().push(messages.to_vec());
+        Ok("The meeting moved to Friday.".to_string())
+    }
+
+    async fn generate_stream<'a>(&'a self, _prompt: &'a str, _options: &'a LlmOptions) -> Result<BoxStream<'a, Result<String>>> {
+        Ok(stream::empty().boxed())
+    }
+
+    async fn get_embedding(&self, _text: &str) -> Result<Vec<f32>> {
+        Ok(vec![0.0])
+    }
+}
+
+/// Builds a multipart body with one field per `(name, filename, content)`
+fn multipart(fields: &[(&str, Option<&str>, &str)]) -> String {
+    let mut body = String::new();
+    for (name, filename, content) in fields {
+        body.push_str(&format!("--{}\r\n", BOUNDARY));
+        match filename {
+            Some(filename) => body.push_str(&format!(
+                "Content-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\nContent-Type: text/plain\r\n\r\n",
+                name, filename,
+            )),
+            None => body.push_str(&format!("Content-Disposition: form-data; name=\"{}\"\r\n\r\n", name)),
+        }
+        body.push_str(content);
+        body.push_str("\r\n");
+    }
+    body.push_str(&format!("--{}--\r\n", BOUNDARY));
+    body
+}
+
+async fn serve(app: LumosApp) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(axum::serve(listener, app.router()).into_future());
+    base
+}
+
+#[tokio::test]
+async fn test_upload_and_ask_about_attachment() {
+    let dir = tempfile::tempdir().unwrap();
+    let llm = Arc::new(RecordingProvider::default());
+    let agent = AgentBuilder::new()
+        .name("assistant")
+        .instructions("Answer questions about the user's files")
+        .model(llm.clone())
+        .build()
+        .unwrap();
+    let mut app = LumosApp::new("attachments")
+        .with_attachments(AttachmentService::new(Arc::new(LocalObjectStore::new(dir.path()))));
+    app.add_agent("assistant".to_string(), agent);
+    let base = serve(app).await;
+    let client = reqwest::Client::new();
+
+    let response = client.post(format!("{}/api/attachments", base))
+        .header("content-type", format!("multipart/form-data; boundary={}", BOUNDARY))
+        .body(multipart(&[("file", Some("notes.txt"), "The weekly meeting moved to Friday.")]))
+        .send().await.unwrap();
+    assert_eq!(response.status(), 201);
+    let uploaded: Value = response.json().await.unwrap();
+    let attachment = &uploaded["attachments"][0];
+    let id = attachment["id"].as_str().unwrap();
+    assert_eq!(attachment["filename"], "notes.txt");
+    assert_eq!(attachment["has_text"], true);
+
+    let content = client.get(format!("{}/api/attachments/{}/content", base, id))
+        .send().await.unwrap();
+    assert_eq!(content.headers()["content-type"], "text/plain");
+    assert_eq!(content.text().await.unwrap(), "The weekly meeting moved to Friday.");
+
+    let response = client.post(format!("{}/api/agents/assistant/generate", base))
+        .json(&json!({ "message": "When is the meeting?", "attachments": [id] }))
+        .send().await.unwrap();
+    assert_eq!(response.status(), 200);
+
+    // The agent sees the file content and the message keeps a reference to the attachment
+    let prompts = llm.prompts.lock().unwrap().clone();
+    let question = prompts[0].last().unwrap();
+    assert!(question.content.starts_with("When is the meeting?"));
+    assert!(question.content.contains("[Attachment 1] notes.txt"));
+    assert!(question.content.contains("The weekly meeting moved to Friday."));
+    assert_eq!(question.attachments()[0].id, id);
+
+    let response = client.post(format!("{}/api/agents/assistant/generate", base))
+        .json(&json!({ "message": "And this one?", "attachments": ["missing"] }))
+        .send().await.unwrap();
+    assert_eq!(response.status(), 404);
+
+    let response = client.delete(format!("{}/api/attachments/{}", base, id)).send().await.unwrap();
+    assert_eq!(response.status(), 204);
+    let response = client.get(format!("{}/api/attachments/{}", base, id)).send().await.unwrap();
+    assert_eq!(response.status(), 404);
+}
+
+#[tokio::test]
+async fn test_upload_validation() {
+    let dir = tempfile::tempdir().unwrap();
+    let service = AttachmentService::new(Arc::new(LocalObjectStore::new(dir.path())));
+    let base = serve(LumosApp::new("attachments").with_attachments(service)).await;
+    let client = reqwest::Client::new();
+
+    // A form without files, or asking to ingest without a retrieval pipeline, is rejected
+    let response = client.post(format!("{}/api/attachments", base))
+        .header("content-type", format!("multipart/form-data; boundary={}", BOUNDARY))
+        .body(multipart(&[("ingest", None, "true")]))
+        .send().await.unwrap();
+    assert_eq!(response.status(), 400);
+    let response = client.post(format!("{}/api/attachments", base))
+        .header("content-type", format!("multipart/form-data; boundary={}", BOUNDARY))
+        .body(multipart(&[("file", Some("a.txt"), "a"), ("ingest", None, "true")]))
+        .send().await.unwrap();
+    assert_eq!(response.status(), 400);
+}
+
+#[tokio::test]
+async fn test_attachments_require_configuration() {
+    let base = serve(LumosApp::new("no-attachments")).await;
+    let response = reqwest::Client::new()
+        .get(format!("{}/api/attachments/some-id", base))
+        .send().await.unwrap();
+    assert_eq!(response.status(), 404);
+}
@@ -0,0 +1,120 @@
+//! Integration tests for cancelling generations once the caller goes away
+
+use std::future::IntoFuture;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream, StreamExt};
+use lumosai_core::agent::trait_def::Agent;
+use lumosai_core::agent::user_message;
+use lumosai_core::cancellation::CancellationToken;
+use lumosai_core::agent::types::AgentGenerateOptions;
+use lumosai_core::{create_basic_agent, Error, LlmOptions, LlmProvider, LumosApp, Message, MockLlmProvider, Result};
+use serde_json::{json, Value};
+
+/// Sets the flag when dropped
+struct DropFlag(Arc<AtomicBool>);
+
+impl Drop for DropFlag {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Provider whose requests never complete, recording when a pending request is dropped
+struct HangingProvider {
+    dropped: Arc<AtomicBool>,
+}
+
+#[async_trait]
+impl LlmProvider for HangingProvider {
+    fn name(&self) -> &str {
+        "hanging"
+    }
+
+    async fn generate(&self, _prompt: &str, options: &LlmOptions) -> Result<String> {
+        self.generate_with_messages(&[], options).await
+    }
+
+    async fn generate_with_messages(&self, _messages: &[Message], _options: &LlmOptions) -> Result<String> {
+        let _request = DropFlag(self.dropped.clone());
+        std::future::pending().await
+    }
+
+    async fn generate_stream<'a>(&'a self, _prompt: &'a str, _options: &'a LlmOptions) -> Result<BoxStream<'a, Result<String>>> {
+        Ok(stream::pending().boxed())
+    }
+
+    async fn get_embedding(&self, _text: &str) -> Result<Vec<f32>> {
+        Ok(Vec::new())
+    }
+}
+
+async fn wait_for(flag: &AtomicBool) -> bool {
+    for _ in 0..100 {
+        if flag.load(Ordering::SeqCst) {
+            return true;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    false
+}
+
+#[tokio::test]
+async fn test_cancelling_token_aborts_pending_provider_request() {
+    let dropped = Arc::new(AtomicBool::new(false));
+    let agent = create_basic_agent("slow", "You are slow", Arc::new(HangingProvider { dropped: dropped.clone() }));
+
+    let token = CancellationToken::new();
+    let mut options = AgentGenerateOptions::default();
+    options.llm_options = options.llm_options.with_cancellation_token(token.clone());
+
+    let canceller = token.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        canceller.cancel();
+    });
+
+    let result = tokio::time::timeout(Duration::from_secs(5), agent.generate(&[user_message("Hi")], &options))
+        .await
+        .expect("generation should stop once cancelled");
+    assert!(matches!(result, Err(Error::Cancelled(_))));
+    assert!(dropped.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn test_client_disconnect_aborts_generation() {
+    let dropped = Arc::new(AtomicBool::new(false));
+    let slow = create_basic_agent("slow", "You are slow", Arc::new(HangingProvider { dropped: dropped.clone() }));
+    let llm = Arc::new(MockLlmProvider::new(vec!["Streaming works".to_string()]));
+    let fast = create_basic_agent("fast", "You are fast", llm);
+
+    let mut app = LumosApp::new("cancellation");
+    app.add_agent("slow".to_string(), slow);
+    app.add_agent("fast".to_string(), fast);
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(axum::serve(listener, app.router()).into_future());
+
+    // The client gives up; the server must drop the pending provider request
+    let impatient = reqwest::Client::builder().timeout(Duration::from_millis(200)).build().unwrap();
+    let abandoned = impatient.post(format!("{}/api/agents/slow/generate", base))
+        .json(&json!({ "message": "Hi" }))
+        .send().await;
+    assert!(abandoned.is_err());
+    assert!(wait_for(&dropped).await, "provider request still running after the client disconnected");
+
+    let response = reqwest::Client::new().post(format!("{}/api/agents/fast/stream", base))
+        .json(&json!({ "message": "Hi" }))
+        .send().await.unwrap();
+    assert_eq!(response.headers()["content-type"], "text/event-stream");
+    let body = response.text().await.unwrap();
+    let text: String = body.lines()
+        .filter_map(|line| line.strip_prefix("data: "))
+        .map(|data| serde_json::from_str::<Value>(data).unwrap()["delta"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(text, "Streaming works");
+}
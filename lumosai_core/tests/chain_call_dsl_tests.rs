@@ -269,6 +269,47 @@ async fn test_app_from_config() {
     // (if config structure was wrong, we'd get a different error type)
 }
 
+#[tokio::test]
+async fn test_app_reload_config() {
+    let yaml = r#"
+providers:
+  primary:
+    type: openai
+    api_key: test-key
+
+agents:
+  assistant:
+    model: gpt-4
+    instructions: You are helpful
+    provider: primary
+  reviewer:
+    model: gpt-4
+    instructions: You review answers
+    provider: primary
+"#;
+    let config = YamlConfig::from_str(yaml).unwrap();
+    let mut app = LumosApp::from_yaml_config(config.clone()).await.unwrap();
+    assert_eq!(app.agents().len(), 2);
+    
+    // Change one prompt, drop one agent and add another
+    let mut updated = config.clone();
+    let agents = updated.agents.as_mut().unwrap();
+    agents.get_mut("assistant").unwrap().instructions = "You are very helpful".to_string();
+    let mut writer = agents.remove("reviewer").unwrap();
+    writer.instructions = "You write drafts".to_string();
+    agents.insert("writer".to_string(), writer);
+    
+    let changes = app.reload_config(updated.clone()).await.unwrap();
+    assert_eq!(changes.updated, vec!["assistant".to_string()]);
+    assert_eq!(changes.added, vec!["writer".to_string()]);
+    assert_eq!(changes.removed, vec!["reviewer".to_string()]);
+    assert!(app.agent("writer").is_ok());
+    assert!(app.agent("reviewer").is_err());
+    
+    // Reloading identical configuration is a no-op
+    assert!(app.reload_config(updated).await.unwrap().is_empty());
+}
+
 #[tokio::test]
 async fn test_config_convenience_methods() {
     let config = YamlConfig::default();
@@ -0,0 +1,78 @@
+//! Integration tests for the debate/consensus orchestration pattern
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde_json::json;
+
+use lumosai_core::agent::{
+    create_basic_agent, AgentExecutionState, AgentOrchestrator, AgentTrait, BasicOrchestrator, CollaborationTask,
+    ConsensusOutcome, ConsensusSelection, ConsensusStage, EventBus, OrchestrationPattern, VotingStrategy,
+};
+use lumosai_core::MockLlmProvider;
+
+fn agent(name: &str, replies: &[&str]) -> Arc<dyn AgentTrait> {
+    let llm = Arc::new(MockLlmProvider::new(replies.iter().map(|reply| reply.to_string()).collect()));
+    Arc::new(create_basic_agent(name, "Answer the question", llm))
+}
+
+fn task(participants: &[&str], selection: ConsensusSelection) -> CollaborationTask {
+    CollaborationTask {
+        id: "capital".to_string(),
+        name: "Capital of Australia".to_string(),
+        description: "Agree on the capital of Australia".to_string(),
+        participants: participants.iter().map(|id| id.to_string()).collect(),
+        pattern: OrchestrationPattern::Consensus { rounds: 1, selection },
+        input: json!("What is the capital of Australia?"),
+        expected_output: None,
+        timeout: None,
+        retry_config: None,
+    }
+}
+
+async fn run(task: CollaborationTask, agents: HashMap<String, Arc<dyn AgentTrait>>) -> (ConsensusOutcome, HashMap<String, AgentExecutionState>) {
+    let orchestrator = BasicOrchestrator::new(Arc::new(EventBus::new(100)));
+    let session_id = orchestrator.create_session(task, agents).await.unwrap();
+    let session = orchestrator.get_session(&session_id).await.unwrap();
+    let mut session = session.lock().await;
+    let result = orchestrator.execute_collaboration(&mut session).await.unwrap();
+    (serde_json::from_value(result).unwrap(), session.get_results().await)
+}
+
+#[tokio::test]
+async fn test_consensus_by_majority_vote() {
+    let agents = HashMap::from([
+        ("a".to_string(), agent("a", &["Sydney", "Canberra, Sydney is only the largest city", "2"])),
+        ("b".to_string(), agent("b", &["Canberra", "Canberra", "I vote for 2"])),
+        ("c".to_string(), agent("c", &["Melbourne", "Melbourne was the capital until 1927", "3"])),
+    ]);
+
+    let (outcome, states) = run(task(&["a", "b", "c"], ConsensusSelection::Vote(VotingStrategy::Majority)), agents).await;
+    assert_eq!(outcome.selected, "b");
+    assert_eq!(outcome.answer, "Canberra");
+    assert_eq!(outcome.votes["b"], 2.0);
+    assert!(outcome.reached);
+
+    let stages: Vec<(usize, ConsensusStage)> = outcome.rounds.iter().map(|round| (round.round, round.stage)).collect();
+    assert_eq!(stages, [(0, ConsensusStage::Answer), (1, ConsensusStage::Critique), (2, ConsensusStage::Selection)]);
+    assert_eq!(outcome.rounds[0].entries[0].content, "Sydney");
+    assert_eq!(outcome.rounds[2].entries[2].choice, Some(3));
+    assert!(matches!(&states["c"], AgentExecutionState::Completed(answer) if answer == "Melbourne was the capital until 1927"));
+}
+
+#[tokio::test]
+async fn test_consensus_by_judge() {
+    let agents = HashMap::from([
+        ("a".to_string(), agent("a", &["Sydney", "Sydney"])),
+        ("b".to_string(), agent("b", &["Canberra", "Canberra"])),
+        ("judge".to_string(), agent("judge", &["[2] Canberra has been the capital since 1913."])),
+    ]);
+
+    let (outcome, states) = run(task(&["a", "b"], ConsensusSelection::Judge("judge".to_string())), agents).await;
+    assert_eq!(outcome.selected, "b");
+    assert!(outcome.reached);
+    assert!(outcome.votes.is_empty());
+    assert_eq!(outcome.rounds.last().unwrap().entries[0].agent_id, "judge");
+    // The judge does not take part in the debate
+    assert!(!states.contains_key("judge"));
+}
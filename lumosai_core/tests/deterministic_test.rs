@@ -0,0 +1,109 @@
+//! Integration tests for deterministic replay mode
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream, StreamExt};
+use lumosai_core::agent::types::AgentGenerateOptions;
+use lumosai_core::agent::{user_message, AgentBuilder, FewShotConfig, FewShotExample};
+use lumosai_core::{Agent, LlmOptions, LlmProvider, Message, Result};
+
+/// Provider recording the options and prompts it receives
+///
+/// Embeddings put the "later" example a hair closer to any query than the "earlier" one.
+#[derive(Default)]
+struct ReplayProvider {
+    seeded: bool,
+    calls: Mutex<Vec<(LlmOptions, Vec<Message>)>>,
+}
+
+#[async_trait]
+impl LlmProvider for ReplayProvider {
+    fn name(&self) -> &str {
+        "replay"
+    }
+
+    async fn generate(&self, _prompt: &str, _options: &LlmOptions) -> Result<String> {
+        Ok("ok".to_string())
+    }
+
+    async fn generate_with_messages(&self, messages: &[Message], options: &LlmOptions) -> Result<String> {
+        self.calls.lock().unwrap().push((options.clone(), messages.to_vec()));
+        Ok("ok".to_string())
+    }
+
+    async fn generate_stream<'a>(&'a self, _prompt: &'a str, _options: &'a LlmOptions) -> Result<BoxStream<'a, Result<String>>> {
+        Ok(stream::empty().boxed())
+    }
+
+    async fn get_embedding(&self, text: &str) -> Result<Vec<f32>> {
+        Ok(match text {
+            "earlier" => vec![1.0, 0.0],
+            "later" => vec![1.0, 0.0105],
+            "unrelated" => vec![0.0, 1.0],
+            _ => vec![1.0, 0.01],
+        })
+    }
+
+    fn supports_seed(&self) -> bool {
+        self.seeded
+    }
+}
+
+fn agent(llm: Arc<ReplayProvider>) -> impl Agent {
+    let examples = vec![
+        FewShotExample::new("earlier", "first answer"),
+        FewShotExample::new("later", "second answer"),
+        FewShotExample::new("unrelated", "third answer"),
+    ];
+    AgentBuilder::new()
+        .name("replayer")
+        .instructions("Answer briefly")
+        .model(llm)
+        .few_shot(FewShotConfig::new(examples).with_top_k(1))
+        .build()
+        .unwrap()
+}
+
+fn deterministic() -> AgentGenerateOptions {
+    AgentGenerateOptions {
+        deterministic: true,
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_deterministic_mode_pins_sampling_and_example_selection() {
+    let llm = Arc::new(ReplayProvider { seeded: true, ..Default::default() });
+    let agent = agent(llm.clone());
+
+    let result = agent.generate(&[user_message("question")], &deterministic()).await.unwrap();
+    assert!(!result.metadata.contains_key("nondeterminism_warnings"));
+    agent.generate(&[user_message("question")], &AgentGenerateOptions::default()).await.unwrap();
+
+    let calls = llm.calls.lock().unwrap();
+    let (options, prompt) = &calls[0];
+    assert_eq!(options.temperature, Some(0.0));
+    assert_eq!(options.seed, Some(AgentGenerateOptions::DETERMINISTIC_SEED));
+    assert_eq!(prompt[1].content, "earlier");
+
+    // Outside replay mode the marginally closer example wins
+    let (options, prompt) = &calls[1];
+    assert_eq!(options.seed, None);
+    assert_eq!(prompt[1].content, "later");
+}
+
+#[tokio::test]
+async fn test_deterministic_mode_keeps_configured_seed_and_reports_unseeded_providers() {
+    let llm = Arc::new(ReplayProvider::default());
+    let agent = agent(llm.clone());
+    let mut options = deterministic();
+    options.llm_options.seed = Some(1234);
+
+    let result = agent.generate(&[user_message("question")], &options).await.unwrap();
+    assert_eq!(llm.calls.lock().unwrap()[0].0.seed, Some(1234));
+
+    let warnings = result.metadata["nondeterminism_warnings"].as_array().unwrap();
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].as_str().unwrap().contains("'replay'"));
+}
@@ -0,0 +1,72 @@
+//! Integration tests for capturing feedback on session messages over HTTP
+
+use std::future::IntoFuture;
+use std::sync::Arc;
+
+use lumosai_core::agent::{MemorySessionStorage, SessionManager, SessionStorage};
+use lumosai_core::llm::Role;
+use lumosai_core::{LumosApp, Message};
+use serde_json::{json, Value};
+
+fn message(role: Role, content: &str) -> Message {
+    Message { role, content: content.to_string(), metadata: None, name: None }
+}
+
+async fn serve(app: LumosApp) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(axum::serve(listener, app.router()).into_future());
+    base
+}
+
+#[tokio::test]
+async fn test_feedback_endpoints() {
+    let storage: Arc<dyn SessionStorage> = Arc::new(MemorySessionStorage::new());
+    let sessions = SessionManager::new(storage.clone());
+    sessions.create_session("s1".to_string(), "support".to_string(), Some("ann".to_string())).await.unwrap();
+    sessions.add_message("s1", message(Role::User, "How do I reset my password?")).await.unwrap();
+    sessions.add_message("s1", message(Role::Assistant, "You can't.")).await.unwrap();
+
+    let base = serve(LumosApp::new("feedback").with_session_storage(storage)).await;
+    let client = reqwest::Client::new();
+
+    let response = client.post(format!("{}/api/sessions/s1/messages/1/feedback", base))
+        .header("x-user-id", "ann")
+        .json(&json!({ "thumbs": "down", "rating": 1, "correction": "Use the 'Forgot password' link." }))
+        .send().await.unwrap();
+    assert_eq!(response.status(), 201);
+    let created: Value = response.json().await.unwrap();
+    assert_eq!(created["user_id"], "ann");
+    assert_eq!(created["thumbs"], "down");
+
+    // Feedback on a user message or with an out-of-range rating is rejected
+    let response = client.post(format!("{}/api/sessions/s1/messages/0/feedback", base))
+        .json(&json!({ "thumbs": "up" }))
+        .send().await.unwrap();
+    assert_eq!(response.status(), 400);
+    let response = client.post(format!("{}/api/sessions/s1/messages/1/feedback", base))
+        .json(&json!({ "rating": 9 }))
+        .send().await.unwrap();
+    assert_eq!(response.status(), 400);
+    let response = client.post(format!("{}/api/sessions/missing/messages/1/feedback", base))
+        .json(&json!({ "thumbs": "up" }))
+        .send().await.unwrap();
+    assert_eq!(response.status(), 404);
+
+    let listed: Value = client.get(format!("{}/api/sessions/s1/feedback", base))
+        .send().await.unwrap()
+        .json().await.unwrap();
+    let feedback = listed["feedback"].as_array().unwrap();
+    assert_eq!(feedback.len(), 1);
+    assert_eq!(feedback[0]["correction"], "Use the 'Forgot password' link.");
+    assert_eq!(feedback[0]["message_index"], 1);
+}
+
+#[tokio::test]
+async fn test_feedback_requires_session_storage() {
+    let base = serve(LumosApp::new("no-sessions")).await;
+    let response = reqwest::Client::new()
+        .get(format!("{}/api/sessions/s1/feedback", base))
+        .send().await.unwrap();
+    assert_eq!(response.status(), 404);
+}
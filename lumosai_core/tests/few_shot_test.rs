@@ -0,0 +1,87 @@
+//! Integration tests for few-shot example selection and injection
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream, StreamExt};
+use lumosai_core::agent::types::AgentGenerateOptions;
+use lumosai_core::agent::{user_message, AgentBuilder, FewShotConfig, FewShotExample};
+use lumosai_core::{Agent, LlmOptions, LlmProvider, Message, Result, Role};
+
+const TOPICS: [&str; 3] = ["password", "invoice", "shipping"];
+
+/// Provider embedding texts by topic keywords and recording the prompts it receives
+#[derive(Default)]
+struct TopicProvider {
+    prompts: Mutex<Vec<Vec<Message>>>,
+}
+
+#[async_trait]
+impl LlmProvider for TopicProvider {
+    fn name(&self) -> &str {
+        "topics"
+    }
+
+    async fn generate(&self, _prompt: &str, _options: &LlmOptions) -> Result<String> {
+        Ok("ok".to_string())
+    }
+
+    async fn generate_with_messages(&self, messages: &[Message], _options: &LlmOptions) -> Result<String> {
+        self.prompts.lock().unwrap().push(messages.to_vec());
+        Ok("ok".to_string())
+    }
+
+    async fn generate_stream<'a>(&'a self, _prompt: &'a str, _options: &'a LlmOptions) -> Result<BoxStream<'a, Result<String>>> {
+        Ok(stream::empty().boxed())
+    }
+
+    async fn get_embedding(&self, text: &str) -> Result<Vec<f32>> {
+        let text = text.to_lowercase();
+        Ok(TOPICS.iter().map(|topic| if text.contains(topic) { 1.0 } else { 0.0 }).collect())
+    }
+}
+
+#[tokio::test]
+async fn test_most_similar_examples_follow_the_system_message() {
+    let llm = Arc::new(TopicProvider::default());
+    let examples = vec![
+        FewShotExample::new("Where is my shipping label?", "Under Orders > Labels."),
+        FewShotExample::new("I forgot my password", "Use the reset link on the sign-in page.").with_label("account"),
+        FewShotExample::new("Can I get a copy of my invoice?", "Invoices are under Billing."),
+    ];
+    let agent = AgentBuilder::new()
+        .name("support")
+        .instructions("Answer support questions")
+        .model(llm.clone())
+        .few_shot(FewShotConfig::new(examples).with_top_k(1))
+        .build()
+        .unwrap();
+
+    agent.generate(&[user_message("How do I change my password?")], &AgentGenerateOptions::default()).await.unwrap();
+
+    let prompt = llm.prompts.lock().unwrap()[0].clone();
+    let roles: Vec<Role> = prompt.iter().map(|message| message.role.clone()).collect();
+    assert_eq!(roles, [Role::System, Role::User, Role::Assistant, Role::User]);
+    assert_eq!(prompt[1].content, "I forgot my password");
+    assert_eq!(prompt[2].content, "Use the reset link on the sign-in page.");
+    assert_eq!(prompt[3].content, "How do I change my password?");
+}
+
+#[tokio::test]
+async fn test_examples_added_at_runtime_are_selected() {
+    let llm = Arc::new(TopicProvider::default());
+    let agent = AgentBuilder::new()
+        .name("support")
+        .instructions("Answer support questions")
+        .model(llm.clone())
+        .few_shot(FewShotConfig::new(vec![FewShotExample::new("I forgot my password", "Use the reset link.")]).with_top_k(1))
+        .build()
+        .unwrap();
+    agent.few_shot().unwrap().add(FewShotExample::new("Where is my invoice?", "Under Billing."));
+
+    agent.generate(&[user_message("Please resend the invoice")], &AgentGenerateOptions::default()).await.unwrap();
+
+    let prompt = llm.prompts.lock().unwrap()[0].clone();
+    assert_eq!(prompt.len(), 4);
+    assert_eq!(prompt[1].content, "Where is my invoice?");
+}
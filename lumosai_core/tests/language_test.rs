@@ -0,0 +1,51 @@
+//! Integration tests for controlling the response language
+
+use std::sync::Arc;
+
+use lumosai_core::agent::types::AgentGenerateOptions;
+use lumosai_core::agent::{user_message, AgentBuilder};
+use lumosai_core::{Agent, MockLlmProvider};
+
+fn responses(responses: &[&str]) -> Arc<MockLlmProvider> {
+    Arc::new(MockLlmProvider::new(responses.iter().map(|r| r.to_string()).collect()))
+}
+
+fn in_language(language: &str) -> AgentGenerateOptions {
+    AgentGenerateOptions {
+        language: Some(language.to_string()),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_answer_in_wrong_language_is_rewritten() {
+    let llm = responses(&[
+        "You can reset the password from the settings page, and it is not hard to do.",
+        "Vous pouvez réinitialiser le mot de passe dans la page des paramètres, et ce n'est pas difficile.",
+    ]);
+    let agent = AgentBuilder::new()
+        .name("support")
+        .instructions("Help users with their accounts")
+        .model(llm)
+        .build()
+        .unwrap();
+
+    let result = agent.generate(&[user_message("How do I reset my password?")], &in_language("fr")).await.unwrap();
+    assert!(result.response.starts_with("Vous pouvez"));
+    assert_eq!(result.metadata["language_rewritten"], true);
+}
+
+#[tokio::test]
+async fn test_answer_in_requested_language_is_kept() {
+    let llm = responses(&["Sie können das Passwort auf der Seite mit den Einstellungen zurücksetzen, und das ist nicht schwer."]);
+    let agent = AgentBuilder::new()
+        .name("support")
+        .instructions("Help users with their accounts")
+        .model(llm)
+        .build()
+        .unwrap();
+
+    let result = agent.generate(&[user_message("How do I reset my password?")], &in_language("German")).await.unwrap();
+    assert!(result.response.starts_with("Sie können"));
+    assert!(!result.metadata.contains_key("language_rewritten"));
+}
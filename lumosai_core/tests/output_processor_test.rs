@@ -0,0 +1,60 @@
+//! Integration tests for the output post-processing pipeline
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use lumosai_core::agent::types::AgentGenerateOptions;
+use lumosai_core::agent::{user_message, AgentBuilder, LinkRewriter, OutputProcessor, Redactor, StripReasoning};
+use lumosai_core::{Agent, Error, MockLlmProvider, Result};
+
+fn responses(responses: &[&str]) -> Arc<MockLlmProvider> {
+    Arc::new(MockLlmProvider::new(responses.iter().map(|r| r.to_string()).collect()))
+}
+
+/// Rejects responses mentioning a banned word
+struct BlockWord(&'static str);
+
+#[async_trait]
+impl OutputProcessor for BlockWord {
+    fn name(&self) -> &str {
+        "block_word"
+    }
+
+    async fn process(&self, output: String) -> Result<String> {
+        if output.contains(self.0) {
+            return Err(Error::guardrail(self.name(), format!("Responses may not mention {}", self.0)));
+        }
+        Ok(output)
+    }
+}
+
+#[tokio::test]
+async fn test_processors_run_in_order_on_the_final_response() {
+    let agent = AgentBuilder::new()
+        .name("support")
+        .instructions("Answer support questions")
+        .model(responses(&["<think>Check the wiki.</think>See http://wiki.internal/reset or mail ops@corp.example.com"]))
+        .output_processor(StripReasoning)
+        .output_processor(LinkRewriter::new().rewrite("http://wiki.internal/", "https://help.example.com/"))
+        .output_processor(Redactor::with_defaults())
+        .build()
+        .unwrap();
+
+    let result = agent.generate(&[user_message("How do I reset?")], &AgentGenerateOptions::default()).await.unwrap();
+    assert_eq!(result.response, "See https://help.example.com/reset or mail [REDACTED_EMAIL]");
+    assert_eq!(result.steps.last().unwrap().output.as_ref().unwrap().content, result.response);
+}
+
+#[tokio::test]
+async fn test_processor_can_reject_the_response() {
+    let agent = AgentBuilder::new()
+        .name("support")
+        .instructions("Answer support questions")
+        .model(responses(&["Our competitor Acme is cheaper"]))
+        .output_processor(BlockWord("Acme"))
+        .build()
+        .unwrap();
+
+    let result = agent.generate(&[user_message("Who is cheaper?")], &AgentGenerateOptions::default()).await;
+    assert!(matches!(result, Err(Error::GuardrailViolation { guardrail, .. }) if guardrail == "block_word"));
+}
@@ -0,0 +1,90 @@
+//! Integration tests for the agent load testing harness
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream, StreamExt};
+use lumosai_core::performance::{run_load_test, LoadTestConfig, SimulatedProvider, TokenPricing};
+use lumosai_core::{create_basic_agent, Agent, Error, LlmOptions, LlmProvider, Message, Result};
+
+/// Provider whose every request fails
+struct FailingProvider;
+
+#[async_trait]
+impl LlmProvider for FailingProvider {
+    fn name(&self) -> &str {
+        "failing"
+    }
+
+    async fn generate(&self, _prompt: &str, _options: &LlmOptions) -> Result<String> {
+        Err(Error::Llm("rate limited".to_string()))
+    }
+
+    async fn generate_with_messages(&self, _messages: &[Message], _options: &LlmOptions) -> Result<String> {
+        Err(Error::Llm("rate limited".to_string()))
+    }
+
+    async fn generate_stream<'a>(&'a self, _prompt: &'a str, _options: &'a LlmOptions) -> Result<BoxStream<'a, Result<String>>> {
+        Ok(stream::empty().boxed())
+    }
+
+    async fn get_embedding(&self, _text: &str) -> Result<Vec<f32>> {
+        Ok(Vec::new())
+    }
+}
+
+fn simulated_agent(latency_ms: u64) -> Arc<dyn Agent> {
+    let llm = Arc::new(SimulatedProvider::new(Duration::from_millis(latency_ms)));
+    Arc::new(create_basic_agent("bench", "Answer briefly", llm))
+}
+
+#[tokio::test]
+async fn test_concurrent_conversations_report_latency_throughput_and_cost() {
+    let config = LoadTestConfig {
+        concurrency: 10,
+        conversations: 20,
+        turns: 2,
+        prompts: vec!["Hello".to_string(), "Tell me more".to_string()],
+        pricing: Some(TokenPricing { input_per_million: 1.0, output_per_million: 2.0 }),
+        ..Default::default()
+    };
+
+    let report = run_load_test(simulated_agent(20), &config).await.unwrap();
+    assert_eq!(report.requests, 40);
+    assert_eq!(report.failures, 0);
+    assert_eq!(report.success_rate(), 1.0);
+    assert!(report.latency.p50_ms >= 20.0);
+    assert!(report.latency.p50_ms <= report.latency.p99_ms);
+    // 40 sequential requests would take at least 800 ms
+    assert!(report.duration_ms < 600, "took {} ms", report.duration_ms);
+    assert!(report.throughput_rps > 0.0);
+    assert!(report.usage.prompt_tokens > 0 && report.usage.completion_tokens > 0);
+    assert!(report.cost_usd.unwrap() > 0.0);
+}
+
+#[tokio::test]
+async fn test_failures_and_timeouts_are_counted() {
+    let agent: Arc<dyn Agent> = Arc::new(create_basic_agent("bench", "Answer briefly", Arc::new(FailingProvider)));
+    let config = LoadTestConfig { concurrency: 2, conversations: 3, turns: 2, ..Default::default() };
+
+    // A failed request ends its conversation
+    let report = run_load_test(agent, &config).await.unwrap();
+    assert_eq!(report.requests, 3);
+    assert_eq!(report.failures, 3);
+    assert_eq!(report.errors.values().sum::<usize>(), 3);
+    assert!(report.errors.keys().all(|error| error.contains("rate limited")));
+    assert!(report.cost_usd.is_none());
+
+    let config = LoadTestConfig {
+        conversations: 2,
+        request_timeout: Some(Duration::from_millis(10)),
+        ..Default::default()
+    };
+    let report = run_load_test(simulated_agent(500), &config).await.unwrap();
+    assert_eq!(report.failures, 2);
+    assert!(report.errors.keys().all(|error| error.contains("timed out")));
+
+    let invalid = LoadTestConfig { concurrency: 0, ..Default::default() };
+    assert!(run_load_test(simulated_agent(0), &invalid).await.is_err());
+}
@@ -0,0 +1,97 @@
+//! Integration tests for the plan-and-execute agent
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream, StreamExt};
+use lumosai_core::agent::types::AgentGenerateOptions;
+use lumosai_core::agent::{PlannerAgent, StepStatus};
+use lumosai_core::{create_basic_agent, Error, LlmOptions, LlmProvider, Message, MockLlmProvider, Result};
+
+/// Provider replaying scripted replies, including failures
+struct ScriptedProvider {
+    replies: Mutex<Vec<Result<String>>>,
+}
+
+impl ScriptedProvider {
+    fn new(replies: Vec<Result<String>>) -> Self {
+        Self { replies: Mutex::new(replies) }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for ScriptedProvider {
+    fn name(&self) -> &str {
+        "scripted"
+    }
+
+    async fn generate(&self, _prompt: &str, _options: &LlmOptions) -> Result<String> {
+        self.replies.lock().unwrap().remove(0)
+    }
+
+    async fn generate_with_messages(&self, _messages: &[Message], options: &LlmOptions) -> Result<String> {
+        self.generate("", options).await
+    }
+
+    async fn generate_stream<'a>(&'a self, _prompt: &'a str, _options: &'a LlmOptions) -> Result<BoxStream<'a, Result<String>>> {
+        Ok(stream::empty().boxed())
+    }
+
+    async fn get_embedding(&self, _text: &str) -> Result<Vec<f32>> {
+        Ok(Vec::new())
+    }
+}
+
+fn mock(replies: &[&str]) -> Arc<MockLlmProvider> {
+    Arc::new(MockLlmProvider::new(replies.iter().map(|r| r.to_string()).collect()))
+}
+
+#[tokio::test]
+async fn test_planner_executes_steps_and_replans_on_failure() {
+    let planner = mock(&[
+        r#"{"steps": [{"description": "Search flights to Paris", "tools": ["flight_search"]}, {"description": "Book the cheapest flight"}]}"#,
+        r#"{"steps": [{"description": "Book the cheapest flight with another airline"}]}"#,
+        "Booked AF123 to Paris for $420.",
+    ]);
+    let worker = create_basic_agent("worker", "You complete travel tasks", Arc::new(ScriptedProvider::new(vec![
+        Ok("Cheapest is $420 on AF123".to_string()),
+        Err(Error::provider("scripted", Some(400), "Booking rejected")),
+        Ok("Booked AF123".to_string()),
+    ])));
+
+    let agent = PlannerAgent::builder()
+        .name("travel")
+        .planner(planner)
+        .worker(Arc::new(worker))
+        .max_replans(1)
+        .build()
+        .unwrap();
+    let state = agent.execute("Fly to Paris", &AgentGenerateOptions::default()).await.unwrap();
+
+    assert_eq!(state.answer.as_deref(), Some("Booked AF123 to Paris for $420."));
+    assert_eq!(state.replans, 1);
+    let statuses: Vec<StepStatus> = state.steps.iter().map(|progress| progress.status).collect();
+    assert_eq!(statuses, [StepStatus::Completed, StepStatus::Failed, StepStatus::Completed]);
+    // The worker has no flight_search tool, so it is dropped from the plan
+    assert!(state.steps[0].step.tools.is_empty());
+    assert_eq!(state.steps[0].output.as_deref(), Some("Cheapest is $420 on AF123"));
+    assert!(state.steps[1].error.as_deref().unwrap().contains("Booking rejected"));
+    assert!(state.is_finished());
+}
+
+#[tokio::test]
+async fn test_planner_gives_up_after_replan_limit() {
+    let planner = mock(&[r#"{"steps": [{"description": "Search"}, {"description": "Book"}]}"#]);
+    let worker = create_basic_agent("worker", "You complete travel tasks", Arc::new(ScriptedProvider::new(vec![
+        Err(Error::provider("scripted", Some(400), "Search is down")),
+    ])));
+    let agent = PlannerAgent::builder()
+        .planner(planner)
+        .worker(Arc::new(worker))
+        .max_replans(0)
+        .build()
+        .unwrap();
+
+    let result = agent.execute("Fly to Paris", &AgentGenerateOptions::default()).await;
+    assert!(matches!(result, Err(Error::Agent(message)) if message.contains("Search is down")));
+}
@@ -0,0 +1,65 @@
+//! Integration tests for the agent self-reflection loop
+
+use std::sync::Arc;
+
+use lumosai_core::agent::types::{AgentGenerateOptions, StepType};
+use lumosai_core::agent::{user_message, AgentBuilder, ReflectionConfig};
+use lumosai_core::{Agent, MockLlmProvider};
+use serde_json::json;
+
+fn responses(responses: &[&str]) -> Arc<MockLlmProvider> {
+    Arc::new(MockLlmProvider::new(responses.iter().map(|r| r.to_string()).collect()))
+}
+
+#[tokio::test]
+async fn test_reflection_revises_until_judge_accepts() {
+    let llm = responses(&[
+        "Paris is the capital of Germany.",
+        r#"{"score": 0.2, "critique": "Paris is in France, not Germany."}"#,
+        "Paris is the capital of France.",
+        r#"{"score": 0.9, "critique": "Correct."}"#,
+    ]);
+    let agent = AgentBuilder::new()
+        .name("geographer")
+        .instructions("Answer geography questions")
+        .model(llm)
+        .reflection(ReflectionConfig::new().with_max_iterations(3).with_score_threshold(0.8))
+        .build()
+        .unwrap();
+
+    let result = agent.generate(&[user_message("What is Paris the capital of?")], &AgentGenerateOptions::default()).await.unwrap();
+    assert_eq!(result.response, "Paris is the capital of France.");
+    assert_eq!(result.metadata["reflection_iterations"], 2);
+    assert_eq!(result.metadata["reflection_scores"], json!([0.2, 0.9]));
+
+    let reflections: Vec<_> = result.steps.iter()
+        .filter(|step| matches!(step.step_type, StepType::Reflection))
+        .collect();
+    assert_eq!(reflections.len(), 2);
+    assert_eq!(reflections[0].metadata["critique"], "Paris is in France, not Germany.");
+    assert_eq!(reflections[0].output.as_ref().unwrap().content, "Paris is the capital of France.");
+    assert!(reflections[1].output.is_none());
+    assert!(reflections[0].input[1].content.contains("Paris is the capital of Germany."));
+}
+
+#[tokio::test]
+async fn test_reflection_stops_at_iteration_limit() {
+    let llm = responses(&[
+        "Draft",
+        r#"{"score": 0.1, "critique": "Too short"}"#,
+        "Longer draft",
+        // Never reached: the limit stops the loop after one revision
+        r#"{"score": 0.1, "critique": "Still too short"}"#,
+    ]);
+    let agent = AgentBuilder::new()
+        .name("writer")
+        .instructions("Write answers")
+        .model(llm)
+        .reflection(ReflectionConfig::new().with_max_iterations(1))
+        .build()
+        .unwrap();
+
+    let result = agent.generate(&[user_message("Write something")], &AgentGenerateOptions::default()).await.unwrap();
+    assert_eq!(result.response, "Longer draft");
+    assert_eq!(result.metadata["reflection_iterations"], 1);
+}
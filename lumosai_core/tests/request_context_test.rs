@@ -0,0 +1,86 @@
+//! Integration tests for propagating the request context through served agents
+
+use std::future::IntoFuture;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream, StreamExt};
+use lumosai_core::{create_basic_agent, LlmOptions, LlmProvider, LumosApp, Message, RequestContext, Result};
+use serde_json::json;
+
+/// Provider that records the request context each call runs in
+#[derive(Default)]
+struct RecordingProvider {
+    seen: Mutex<Vec<Option<RequestContext>>>,
+}
+
+#[async_trait]
+impl LlmProvider for RecordingProvider {
+    fn name(&self) -> &str {
+        "recording"
+    }
+
+    async fn generate(&self, _prompt: &str, _options: &LlmOptions) -> Result<String> {
+        self.seen.lock().unwrap().push(RequestContext::current());
+        Ok("ok".to_string())
+    }
+
+    async fn generate_with_messages(&self, _messages: &[Message], options: &LlmOptions) -> Result<String> {
+        self.generate("", options).await
+    }
+
+    async fn generate_stream<'a>(&'a self, _prompt: &'a str, _options: &'a LlmOptions) -> Result<BoxStream<'a, Result<String>>> {
+        self.seen.lock().unwrap().push(RequestContext::current());
+        Ok(stream::iter(vec![Ok("ok".to_string())]).boxed())
+    }
+
+    async fn get_embedding(&self, _text: &str) -> Result<Vec<f32>> {
+        Ok(Vec::new())
+    }
+}
+
+#[tokio::test]
+async fn test_request_headers_populate_context_for_agent_calls() {
+    let llm = Arc::new(RecordingProvider::default());
+    let agent = create_basic_agent("assistant", "You help", llm.clone());
+    let mut app = LumosApp::new("context");
+    app.add_agent("assistant".to_string(), agent);
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(axum::serve(listener, app.router()).into_future());
+    let client = reqwest::Client::new();
+
+    let response = client.post(format!("{}/api/agents/assistant/generate", base))
+        .header("x-request-id", "req-123")
+        .header("x-tenant-id", "acme")
+        .header("x-user-id", "ann")
+        .header("x-session-id", "s-1")
+        .json(&json!({ "message": "Hi" }))
+        .send().await.unwrap();
+    assert!(response.status().is_success());
+    assert_eq!(response.headers()["x-request-id"], "req-123");
+
+    // Streaming runs in a spawned task and must inherit the context
+    let response = client.post(format!("{}/api/agents/assistant/stream", base))
+        .header("traceparent", "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01")
+        .json(&json!({ "message": "Hi" }))
+        .send().await.unwrap();
+    assert_eq!(response.headers()["x-request-id"], "4bf92f3577b34da6a3ce929d0e0e4736");
+    response.text().await.unwrap();
+
+    // Without headers a fresh trace ID is generated, even for rejected requests
+    let missing = client.post(format!("{}/api/agents/unknown/generate", base))
+        .json(&json!({ "message": "Hi" }))
+        .send().await.unwrap();
+    let generated = missing.headers()["x-request-id"].to_str().unwrap();
+    assert_eq!(generated.len(), 32);
+
+    let seen = llm.seen.lock().unwrap();
+    assert_eq!(seen.len(), 2);
+    let context = seen[0].clone().expect("generate should run inside the request context");
+    assert_eq!(context, RequestContext::with_trace_id("req-123").with_tenant("acme").with_user("ann").with_session("s-1"));
+    let context = seen[1].clone().expect("stream should run inside the request context");
+    assert_eq!(context.trace_id, "4bf92f3577b34da6a3ce929d0e0e4736");
+    assert!(context.tenant_id.is_none());
+}
@@ -0,0 +1,80 @@
+//! Integration tests for flagging low-confidence answers into the review queue
+
+use std::future::IntoFuture;
+use std::sync::Arc;
+
+use lumosai_core::agent::types::AgentGenerateOptions;
+use lumosai_core::agent::{user_message, AgentBuilder, FewShotConfig, ReflectionConfig, ReviewDecision, ReviewQueue, ReviewStatus};
+use lumosai_core::{Agent, LumosApp, MockLlmProvider};
+use serde_json::{json, Value};
+
+fn responses(responses: &[&str]) -> Arc<MockLlmProvider> {
+    Arc::new(MockLlmProvider::new(responses.iter().map(|r| r.to_string()).collect()))
+}
+
+#[tokio::test]
+async fn test_low_judge_score_is_flagged_and_correction_fed_back() {
+    let queue = Arc::new(ReviewQueue::new(0.5));
+    let llm = responses(&[
+        "Refunds are available any time.",
+        r#"{"score": 0.3, "critique": "Contradicts the 30 day policy."}"#,
+        "Refunds are available within 30 days.",
+        r#"{"score": 0.9, "critique": "Correct."}"#,
+    ]);
+    let agent = AgentBuilder::new()
+        .name("support")
+        .instructions("Answer billing questions")
+        .model(llm)
+        .reflection(ReflectionConfig::new().with_max_iterations(1))
+        .few_shot(FewShotConfig::new(Vec::new()))
+        .review_queue(queue.clone())
+        .build()
+        .unwrap();
+
+    let result = agent.generate(&[user_message("Can I get a refund?")], &AgentGenerateOptions::default()).await.unwrap();
+    let pending = queue.list(Some(ReviewStatus::Pending));
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].agent, "support");
+    assert_eq!(pending[0].input, "Can I get a refund?");
+    assert_eq!(pending[0].answer, "Refunds are available within 30 days.");
+    assert_eq!(pending[0].reason.as_deref(), Some("Contradicts the 30 day policy."));
+    assert_eq!(result.metadata["review_id"], pending[0].id.as_str());
+
+    // Reviewed items are applied to the agent's few-shot examples on the next call
+    queue.review(&pending[0].id, ReviewDecision::Approve, None).unwrap();
+    let _ = agent.generate(&[user_message("And after 60 days?")], &AgentGenerateOptions::default()).await;
+    assert!(queue.get(&pending[0].id).unwrap().applied);
+}
+
+#[tokio::test]
+async fn test_review_endpoints() {
+    let queue = Arc::new(ReviewQueue::default());
+    let item = queue.consider("support", "Refund after 60 days?", "Yes.", 0.2, "guardrail", None).unwrap();
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let app = LumosApp::new("reviews").with_review_queue(queue.clone());
+    tokio::spawn(axum::serve(listener, app.router()).into_future());
+    let client = reqwest::Client::new();
+
+    let listed: Value = client.get(format!("{}/api/reviews?status=pending", base))
+        .send().await.unwrap()
+        .json().await.unwrap();
+    assert_eq!(listed["pending"], 1);
+    assert_eq!(listed["reviews"][0]["id"], item.id.as_str());
+
+    let response = client.post(format!("{}/api/reviews/{}", base, item.id))
+        .header("x-user-id", "ann")
+        .json(&json!({ "action": "correct", "correction": "No, only within 30 days." }))
+        .send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    let reviewed: Value = response.json().await.unwrap();
+    assert_eq!(reviewed["status"], "corrected");
+    assert_eq!(reviewed["reviewer"], "ann");
+
+    let response = client.post(format!("{}/api/reviews/missing", base))
+        .json(&json!({ "action": "approve" }))
+        .send().await.unwrap();
+    assert_eq!(response.status(), 404);
+    assert_eq!(queue.pending_count(), 0);
+}
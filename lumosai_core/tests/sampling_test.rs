@@ -0,0 +1,97 @@
+//! Integration tests for multi-response sampling and best-of-n selection
+
+use std::future::IntoFuture;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream, StreamExt};
+use lumosai_core::agent::types::AgentGenerateOptions;
+use lumosai_core::agent::user_message;
+use lumosai_core::{create_basic_agent, Agent, LlmOptions, LlmProvider, LumosApp, Message, Result};
+use serde_json::{json, Value};
+
+/// Provider answering with the seed it was given and judging in favour of seed 2
+#[derive(Default)]
+struct SeedProvider {
+    temperatures: Mutex<Vec<f32>>,
+}
+
+#[async_trait]
+impl LlmProvider for SeedProvider {
+    fn name(&self) -> &str {
+        "seeds"
+    }
+
+    async fn generate(&self, _prompt: &str, _options: &LlmOptions) -> Result<String> {
+        Ok(String::new())
+    }
+
+    async fn generate_with_messages(&self, messages: &[Message], options: &LlmOptions) -> Result<String> {
+        if messages[0].content.contains("strict judge") {
+            assert!(messages[1].content.contains("Candidate 2:\nAnswer 2"));
+            return Ok(r#"{"best": 2, "reason": "Most complete"}"#.to_string());
+        }
+        self.temperatures.lock().unwrap().push(options.temperature.unwrap());
+        Ok(format!("Answer {}", options.seed.unwrap()))
+    }
+
+    async fn generate_stream<'a>(&'a self, _prompt: &'a str, _options: &'a LlmOptions) -> Result<BoxStream<'a, Result<String>>> {
+        Ok(stream::empty().boxed())
+    }
+
+    async fn get_embedding(&self, _text: &str) -> Result<Vec<f32>> {
+        Ok(Vec::new())
+    }
+}
+
+#[tokio::test]
+async fn test_generate_n_samples_distinct_candidates() {
+    let llm = Arc::new(SeedProvider::default());
+    let agent = create_basic_agent("writer", "Write answers", llm.clone());
+
+    let candidates = agent.generate_n(&[user_message("Write a slogan")], 3, &AgentGenerateOptions::default()).await.unwrap();
+    let mut responses: Vec<&str> = candidates.iter().map(|candidate| candidate.response.as_str()).collect();
+    responses.sort();
+    assert_eq!(responses, ["Answer 0", "Answer 1", "Answer 2"]);
+
+    let mut temperatures = llm.temperatures.lock().unwrap().clone();
+    temperatures.sort_by(f32::total_cmp);
+    temperatures.dedup();
+    assert_eq!(temperatures.len(), 3);
+
+    assert!(agent.generate_n(&[user_message("Write a slogan")], 0, &AgentGenerateOptions::default()).await.is_err());
+}
+
+#[tokio::test]
+async fn test_best_of_n_uses_the_judge_choice() {
+    let agent = create_basic_agent("writer", "Write answers", Arc::new(SeedProvider::default()));
+
+    let best = agent.generate_best_of_n(&[user_message("Write a slogan")], 3, &AgentGenerateOptions::default(), None).await.unwrap();
+    assert_eq!(best.candidates.len(), 3);
+    assert_eq!(best.best().response, "Answer 2");
+    assert_eq!(best.reason.as_deref(), Some("Most complete"));
+}
+
+#[tokio::test]
+async fn test_variants_endpoint() {
+    let agent = create_basic_agent("writer", "Write answers", Arc::new(SeedProvider::default()));
+    let mut app = LumosApp::new("variants");
+    app.add_agent("writer".to_string(), agent);
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(axum::serve(listener, app.router()).into_future());
+    let client = reqwest::Client::new();
+
+    let body: Value = client.post(format!("{}/api/agents/writer/variants", base))
+        .json(&json!({ "message": "Write a slogan", "n": 3, "select": true }))
+        .send().await.unwrap()
+        .json().await.unwrap();
+    assert_eq!(body["variants"].as_array().unwrap().len(), 3);
+    assert_eq!(body["variants"][body["selected"].as_u64().unwrap() as usize]["response"], "Answer 2");
+
+    let too_many = client.post(format!("{}/api/agents/writer/variants", base))
+        .json(&json!({ "message": "Write a slogan", "n": 50 }))
+        .send().await.unwrap();
+    assert_eq!(too_many.status(), reqwest::StatusCode::BAD_REQUEST);
+}
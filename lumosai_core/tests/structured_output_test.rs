@@ -0,0 +1,79 @@
+//! Integration tests for structured output
+
+use std::sync::Arc;
+
+use futures::StreamExt;
+use serde::Deserialize;
+use serde_json::json;
+
+use lumosai_core::agent::types::{AgentGenerateOptions, AgentStreamOptions};
+use lumosai_core::agent::{user_message, AgentBuilder, AgentStructuredOutput, BasicAgent};
+use lumosai_core::{Error, MockLlmProvider};
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Weather {
+    city: String,
+    #[serde(default)]
+    forecast: Vec<String>,
+}
+
+fn weather_agent(response: &str) -> BasicAgent {
+    AgentBuilder::new()
+        .name("weather")
+        .instructions("Report the weather")
+        .model(Arc::new(MockLlmProvider::new(vec![response.to_string()])))
+        .output_schema(json!({
+            "type": "object",
+            "properties": {
+                "city": { "type": "string" },
+                "forecast": { "type": "array", "items": { "type": "string" } }
+            },
+            "required": ["city"]
+        }))
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_generate_structured() {
+    let agent = weather_agent("```json\n{\"city\": \"Paris\", \"forecast\": [\"sunny\", \"rain\"]}\n```");
+    let weather: Weather = agent
+        .generate_structured(&[user_message("Weather in Paris?")], &AgentGenerateOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(weather, Weather { city: "Paris".to_string(), forecast: vec!["sunny".to_string(), "rain".to_string()] });
+
+    let agent = weather_agent("It is sunny in Paris.");
+    let invalid = agent
+        .generate_structured::<Weather>(&[user_message("Weather in Paris?")], &AgentGenerateOptions::default())
+        .await;
+    assert!(matches!(invalid, Err(Error::Parsing(_))));
+}
+
+#[tokio::test]
+async fn test_stream_structured_yields_partial_values() {
+    let agent = weather_agent("{\"city\": \"Paris\", \"forecast\": [\"sunny\", \"cloudy\", \"rain\"]}");
+    let options = AgentStreamOptions::default();
+    let messages = [user_message("Weather in Paris?")];
+    let outputs: Vec<_> = agent
+        .stream_structured::<Weather>(&messages, &options)
+        .await
+        .unwrap()
+        .map(|output| output.unwrap())
+        .collect()
+        .await;
+
+    // The forecast fills in one entry at a time
+    let forecast_lengths: Vec<usize> = outputs.iter()
+        .filter_map(|output| output.partial.as_ref())
+        .map(|weather| weather.forecast.len())
+        .collect();
+    assert!(forecast_lengths.windows(2).all(|pair| pair[0] <= pair[1]));
+    assert!(forecast_lengths.contains(&1));
+
+    let last = outputs.last().unwrap();
+    assert!(last.complete);
+    assert_eq!(last.partial.as_ref().unwrap().forecast, ["sunny", "cloudy", "rain"]);
+    let text: String = outputs.iter().map(|output| output.delta.as_str()).collect();
+    assert_eq!(text, "{\"city\": \"Paris\", \"forecast\": [\"sunny\", \"cloudy\", \"rain\"]}");
+}
@@ -0,0 +1,147 @@
+//! Integration tests for forced tool choice and its post-validation
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream, StreamExt};
+use serde_json::json;
+
+use lumosai_core::agent::types::{AgentGenerateOptions, StepType, ToolChoice};
+use lumosai_core::agent::{user_message, AgentConfig, BasicAgent};
+use lumosai_core::llm::provider::FunctionCallingResponse;
+use lumosai_core::llm::{FunctionCall, FunctionDefinition, ToolChoice as LlmToolChoice};
+use lumosai_core::tool::{GenericTool, ParameterSchema, ToolSchema};
+use lumosai_core::{Agent, Error, LlmOptions, LlmProvider, Message, Result};
+
+/// Provider replaying scripted function calling replies and recording the tool choice of each call
+struct ScriptedProvider {
+    replies: Mutex<Vec<FunctionCallingResponse>>,
+    choices: Mutex<Vec<String>>,
+}
+
+impl ScriptedProvider {
+    fn new(replies: Vec<FunctionCallingResponse>) -> Self {
+        Self { replies: Mutex::new(replies), choices: Mutex::new(Vec::new()) }
+    }
+
+    fn choices(&self) -> Vec<String> {
+        self.choices.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl LlmProvider for ScriptedProvider {
+    fn name(&self) -> &str {
+        "scripted"
+    }
+
+    async fn generate(&self, _prompt: &str, _options: &LlmOptions) -> Result<String> {
+        Ok(String::new())
+    }
+
+    async fn generate_with_messages(&self, _messages: &[Message], _options: &LlmOptions) -> Result<String> {
+        Ok(String::new())
+    }
+
+    async fn generate_stream<'a>(&'a self, _prompt: &'a str, _options: &'a LlmOptions) -> Result<BoxStream<'a, Result<String>>> {
+        Ok(stream::empty().boxed())
+    }
+
+    async fn get_embedding(&self, _text: &str) -> Result<Vec<f32>> {
+        Ok(Vec::new())
+    }
+
+    fn supports_function_calling(&self) -> bool {
+        true
+    }
+
+    async fn generate_with_functions(
+        &self,
+        _messages: &[Message],
+        _functions: &[FunctionDefinition],
+        tool_choice: &LlmToolChoice,
+        _options: &LlmOptions,
+    ) -> Result<FunctionCallingResponse> {
+        self.choices.lock().unwrap().push(serde_json::to_string(tool_choice)?);
+        Ok(self.replies.lock().unwrap().remove(0))
+    }
+}
+
+fn text(content: &str) -> FunctionCallingResponse {
+    FunctionCallingResponse { content: Some(content.to_string()), function_calls: Vec::new(), finish_reason: "stop".to_string() }
+}
+
+fn call(name: &str) -> FunctionCallingResponse {
+    FunctionCallingResponse {
+        content: None,
+        function_calls: vec![FunctionCall::with_id("call-1".to_string(), name.to_string(), r#"{"city": "Paris"}"#.to_string())],
+        finish_reason: "tool_calls".to_string(),
+    }
+}
+
+fn weather_agent(llm: Arc<ScriptedProvider>) -> BasicAgent {
+    let config = AgentConfig {
+        name: "forecaster".to_string(),
+        instructions: "Answer weather questions".to_string(),
+        enable_function_calling: Some(true),
+        ..Default::default()
+    };
+    let mut agent = BasicAgent::new(config, llm);
+    let schema = ToolSchema::new(vec![ParameterSchema {
+        name: "city".to_string(),
+        description: "City to look up".to_string(),
+        r#type: "string".to_string(),
+        required: true,
+        properties: None,
+        default: None,
+    }]);
+    agent.add_tool(Box::new(GenericTool::new("weather", "Current weather for a city", schema, |_params, _context| {
+        Ok(json!("sunny"))
+    }))).unwrap();
+    agent
+}
+
+fn forcing(tool_choice: ToolChoice) -> AgentGenerateOptions {
+    AgentGenerateOptions {
+        tool_choice: Some(tool_choice),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_forced_tool_call_is_retried_until_honored() {
+    let llm = Arc::new(ScriptedProvider::new(vec![
+        text("It is probably sunny."),
+        call("weather"),
+        text("It is sunny in Paris."),
+    ]));
+    let agent = weather_agent(llm.clone());
+
+    let options = forcing(ToolChoice::Tool { tool_name: "weather".to_string() });
+    let result = agent.generate(&[user_message("Weather in Paris?")], &options).await.unwrap();
+
+    assert_eq!(result.response, "It is sunny in Paris.");
+    assert_eq!(result.metadata["tool_choice_retries"], 1);
+    assert!(result.steps.iter().any(|step| matches!(step.step_type, StepType::Tool) && step.tool_calls[0].name == "weather"));
+    // Once the tool was called the model is free to answer
+    let forced = r#"{"function":{"name":"weather"}}"#;
+    assert_eq!(llm.choices(), [forced, forced, r#""auto""#]);
+}
+
+#[tokio::test]
+async fn test_forced_tool_call_gives_up_after_retries() {
+    let llm = Arc::new(ScriptedProvider::new(vec![text("No."), text("Still no."), text("Never.")]));
+    let agent = weather_agent(llm);
+
+    let result = agent.generate(&[user_message("Weather in Paris?")], &forcing(ToolChoice::Required)).await;
+    assert!(matches!(result, Err(Error::Agent(message)) if message.contains("Required")));
+}
+
+#[tokio::test]
+async fn test_forcing_an_unknown_tool_is_rejected() {
+    let agent = weather_agent(Arc::new(ScriptedProvider::new(Vec::new())));
+
+    let options = forcing(ToolChoice::Tool { tool_name: "stocks".to_string() });
+    let result = agent.generate(&[user_message("Weather in Paris?")], &options).await;
+    assert!(matches!(result, Err(Error::Configuration(message)) if message.contains("stocks")));
+}
@@ -0,0 +1,73 @@
+//! Integration tests for the `tool` attribute macro
+
+use lumos_macro::tool;
+use lumosai_core::llm::function_calling::FunctionDefinition;
+use lumosai_core::tool::{ToolExecutionContext, ToolExecutionOptions};
+use serde_json::json;
+
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+enum Unit {
+    Celsius,
+    Fahrenheit,
+}
+
+#[tool(name = "convert", description = "Converts a Celsius temperature")]
+async fn convert(
+    #[parameter(description = "Temperature in Celsius")]
+    celsius: f64,
+    #[parameter(description = "Target unit")]
+    unit: Option<Unit>,
+) -> Result<f64, String> {
+    if celsius < -273.15 {
+        return Err(format!("{} is below absolute zero", celsius));
+    }
+    Ok(match unit.unwrap_or(Unit::Celsius) {
+        Unit::Celsius => celsius,
+        Unit::Fahrenheit => celsius * 9.0 / 5.0 + 32.0,
+    })
+}
+
+#[tool(name = "greet", description = "Greets someone")]
+fn greet(#[parameter(name = "who", description = "Name to greet")] name: String) -> String {
+    format!("Hello, {}!", name)
+}
+
+#[tokio::test]
+async fn test_async_tool_with_optional_enum_parameter() {
+    let tool = convert();
+    let options = ToolExecutionOptions::default();
+
+    let result = tool.execute(json!({ "celsius": 100.0, "unit": "fahrenheit" }), ToolExecutionContext::new(), &options).await.unwrap();
+    assert_eq!(result, json!(212.0));
+    let result = tool.execute(json!({ "celsius": 20.0 }), ToolExecutionContext::new(), &options).await.unwrap();
+    assert_eq!(result, json!(20.0));
+
+    // Handler errors and invalid arguments are reported to the LLM rather than failing
+    let result = tool.execute(json!({ "celsius": -300.0 }), ToolExecutionContext::new(), &options).await.unwrap();
+    assert_eq!(result, json!({ "error": "-300 is below absolute zero" }));
+    let result = tool.execute(json!({ "celsius": 1.0, "unit": "kelvin" }), ToolExecutionContext::new(), &options).await.unwrap();
+    assert!(result["error"].as_str().unwrap().contains("unit"));
+    let result = tool.execute(json!({}), ToolExecutionContext::new(), &options).await.unwrap();
+    assert_eq!(result, json!({ "error": "Invalid parameters: Required parameter 'celsius' is missing" }));
+}
+
+#[tokio::test]
+async fn test_tool_schema_marks_optional_and_enum_parameters() {
+    let definition = FunctionDefinition::from_tool(convert().as_ref());
+    assert_eq!(definition.name, "convert");
+    assert_eq!(definition.parameters["required"], json!(["celsius"]));
+    assert_eq!(definition.parameters["properties"]["celsius"]["type"], "number");
+    assert_eq!(definition.parameters["properties"]["unit"]["enum"], json!(["celsius", "fahrenheit"]));
+    assert_eq!(definition.parameters["properties"]["unit"]["description"], "Target unit");
+
+    let schema = convert().schema();
+    assert!(schema.parameters.iter().any(|p| p.name == "unit" && !p.required && p.r#type == "string"));
+}
+
+#[tokio::test]
+async fn test_sync_tool_with_renamed_parameter() {
+    let tool = greet();
+    let result = tool.execute(json!({ "who": "Ada" }), ToolExecutionContext::new(), &ToolExecutionOptions::default()).await.unwrap();
+    assert_eq!(result, json!("Hello, Ada!"));
+}
@@ -0,0 +1,127 @@
+//! Integration tests for remembering tool results across turns of a session
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream, StreamExt};
+use serde_json::json;
+
+use lumosai_core::agent::types::AgentGenerateOptions;
+use lumosai_core::agent::{user_message, AgentConfig, BasicAgent, ToolsMemory, ToolsMemoryConfig};
+use lumosai_core::llm::provider::FunctionCallingResponse;
+use lumosai_core::llm::{FunctionCall, FunctionDefinition, Role, ToolChoice as LlmToolChoice};
+use lumosai_core::tool::{GenericTool, ParameterSchema, ToolSchema};
+use lumosai_core::{Agent, LlmOptions, LlmProvider, Message, Result};
+
+/// Provider replaying scripted function calling replies and recording the system messages it was sent
+struct ScriptedProvider {
+    replies: Mutex<Vec<FunctionCallingResponse>>,
+    system_messages: Mutex<Vec<String>>,
+}
+
+#[async_trait]
+impl LlmProvider for ScriptedProvider {
+    fn name(&self) -> &str {
+        "scripted"
+    }
+
+    async fn generate(&self, _prompt: &str, _options: &LlmOptions) -> Result<String> {
+        Ok(String::new())
+    }
+
+    async fn generate_with_messages(&self, _messages: &[Message], _options: &LlmOptions) -> Result<String> {
+        Ok(String::new())
+    }
+
+    async fn generate_stream<'a>(&'a self, _prompt: &'a str, _options: &'a LlmOptions) -> Result<BoxStream<'a, Result<String>>> {
+        Ok(stream::empty().boxed())
+    }
+
+    async fn get_embedding(&self, _text: &str) -> Result<Vec<f32>> {
+        Ok(Vec::new())
+    }
+
+    fn supports_function_calling(&self) -> bool {
+        true
+    }
+
+    async fn generate_with_functions(
+        &self,
+        messages: &[Message],
+        _functions: &[FunctionDefinition],
+        _tool_choice: &LlmToolChoice,
+        _options: &LlmOptions,
+    ) -> Result<FunctionCallingResponse> {
+        let system = messages.iter().filter(|message| message.role == Role::System).map(|message| message.content.clone());
+        self.system_messages.lock().unwrap().extend(system);
+        Ok(self.replies.lock().unwrap().remove(0))
+    }
+}
+
+fn text(content: &str) -> FunctionCallingResponse {
+    FunctionCallingResponse { content: Some(content.to_string()), function_calls: Vec::new(), finish_reason: "stop".to_string() }
+}
+
+fn weather_call() -> FunctionCallingResponse {
+    FunctionCallingResponse {
+        content: None,
+        function_calls: vec![FunctionCall::with_id("call-1".to_string(), "weather".to_string(), r#"{"city": "Paris"}"#.to_string())],
+        finish_reason: "tool_calls".to_string(),
+    }
+}
+
+fn in_thread(thread_id: &str) -> AgentGenerateOptions {
+    AgentGenerateOptions {
+        thread_id: Some(thread_id.to_string()),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_tool_results_are_shown_on_later_turns_of_the_session() {
+    let llm = Arc::new(ScriptedProvider {
+        replies: Mutex::new(vec![
+            weather_call(),
+            text("It is sunny in Paris."),
+            text("Yes, take sunglasses."),
+            text("Which city?"),
+        ]),
+        system_messages: Mutex::new(Vec::new()),
+    });
+    let config = AgentConfig {
+        name: "forecaster".to_string(),
+        instructions: "Answer weather questions".to_string(),
+        enable_function_calling: Some(true),
+        ..Default::default()
+    };
+    let memory = Arc::new(ToolsMemory::new(ToolsMemoryConfig::default()));
+    let mut agent = BasicAgent::new(config, llm.clone()).with_tools_memory(memory.clone());
+    let schema = ToolSchema::new(vec![ParameterSchema {
+        name: "city".to_string(),
+        description: "City to look up".to_string(),
+        r#type: "string".to_string(),
+        required: true,
+        properties: None,
+        default: None,
+    }]);
+    agent.add_tool(Box::new(GenericTool::new("weather", "Current weather for a city", schema, |_params, _context| {
+        Ok(json!({ "sky": "sunny", "temperature": 24 }))
+    }))).unwrap();
+
+    agent.generate(&[user_message("Weather in Paris?")], &in_thread("t1")).await.unwrap();
+    let records = memory.records("t1");
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].arguments, json!({ "city": "Paris" }));
+    assert!(llm.system_messages.lock().unwrap().iter().all(|message| !message.contains("already fetched")));
+
+    llm.system_messages.lock().unwrap().clear();
+    agent.generate(&[user_message("Do I need sunglasses?")], &in_thread("t1")).await.unwrap();
+    let remembered = llm.system_messages.lock().unwrap().iter()
+        .any(|message| message.contains(r#"- weather({"city":"Paris"}): {"sky":"sunny","temperature":24}"#));
+    assert!(remembered);
+
+    // Other sessions do not see the results
+    llm.system_messages.lock().unwrap().clear();
+    agent.generate(&[user_message("Is it cold?")], &in_thread("t2")).await.unwrap();
+    assert!(llm.system_messages.lock().unwrap().iter().all(|message| !message.contains("already fetched")));
+}
@@ -0,0 +1,71 @@
+//! Integration tests for parallel blocks, error handlers and output mapping in the `workflow!` macro
+
+use std::sync::Arc;
+
+use lumos_macro::workflow;
+use lumosai_core::{create_basic_agent, BasicAgent, MockLlmProvider};
+use serde_json::json;
+
+fn mock_agent(name: &str, response: &str) -> Arc<BasicAgent> {
+    let llm = Arc::new(MockLlmProvider::new(vec![response.to_string()]));
+    Arc::new(create_basic_agent(name, "You are a helpful assistant", llm))
+}
+
+#[tokio::test]
+async fn test_workflow_macro_with_parallel_block_and_error_handlers() {
+    let researcher = mock_agent("researcher", r#"{"text": "Rust has ownership"}"#);
+    let writer = mock_agent("writer", r#"{"text": "draft"}"#);
+    let planner = mock_agent("planner", r#"{"sections": 3}"#);
+    let reviewer = mock_agent("reviewer", "Looks good");
+
+    let workflow = workflow! {
+        name: "content_creation",
+        description: "Writes and reviews an article",
+        steps: {
+            {
+                name: "research",
+                agent: researcher,
+                instructions: "Research the topic",
+                input: { topic: "trigger.topic" },
+                on_error: retry(2),
+            },
+            parallel {
+                {
+                    name: "writing",
+                    agent: writer,
+                    instructions: "Write a draft",
+                    input: { notes: "research.text" },
+                },
+                {
+                    name: "outline",
+                    agent: planner,
+                    instructions: "Outline the article",
+                    on_error: fallback_step("simple_outline"),
+                }
+            },
+            {
+                name: "review",
+                agent: reviewer,
+                instructions: "Review the draft",
+                input: { draft: "writing.text", outline: "outline" },
+                on_error: abort,
+            },
+            {
+                name: "simple_outline",
+                agent: planner,
+                instructions: "Outline the article in three sections",
+            }
+        }
+    };
+    assert_eq!(workflow.name(), "content_creation");
+    assert_eq!(workflow.description(), Some("Writes and reviews an article"));
+
+    let result = workflow.create_run(json!({ "topic": "rust" })).run().await.unwrap();
+    let output = |step: &str| serde_json::to_value(&result.results[step]).unwrap();
+
+    assert_eq!(output("research"), json!({ "Success": { "output": { "text": "Rust has ownership" } } }));
+    assert_eq!(output("outline"), json!({ "Success": { "output": { "sections": 3 } } }));
+    assert_eq!(output("review"), json!({ "Success": { "output": { "text": "Looks good" } } }));
+    // The fallback step only runs when the outline step fails
+    assert!(!result.results.contains_key("simple_outline"));
+}
@@ -1,7 +1,8 @@
 //! Procedural macros for LumosAI
 //! 
 //! This crate provides derive macros to automatically generate function schemas
-//! for use with OpenAI function calling from Rust structs.
+//! for use with OpenAI function calling from Rust structs, and typed agent state
+//! persisted in sessions.
 
 use proc_macro::TokenStream;
 use quote::quote;
@@ -116,6 +117,192 @@ fn generate_function_schema(input: &DeriveInput) -> syn::Result<proc_macro2::Tok
     Ok(expanded)
 }
 
+/// Derive macro for typed agent state persisted in a session across turns
+/// 
+/// Implements `lumosai_core::agent::AgentState`: field-by-field serialization (fields
+/// missing from a stored state fall back to their defaults), a JSON schema built from
+/// the field types and doc comments, and a diff listing the fields that changed. The
+/// struct must implement `Default` and `Clone`, and each persisted field `Serialize`,
+/// `DeserializeOwned` and `Default`.
+/// 
+/// The state is stored under the struct name in snake case unless
+/// `#[agent_state(key = "...")]` is given; `#[agent_state(skip)]` excludes a field.
+/// 
+/// # Example
+/// 
+/// ```rust,ignore
+/// use lumosai_derive::AgentState;
+/// 
+/// #[derive(Debug, Default, Clone, AgentState)]
+/// #[agent_state(key = "checkout")]
+/// pub struct CheckoutState {
+///     /// Items in the cart
+///     pub items: Vec<String>,
+///     /// Whether the user confirmed the order
+///     pub confirmed: bool,
+///     #[agent_state(skip)]
+///     pub cache: Option<String>,
+/// }
+/// ```
+#[proc_macro_derive(AgentState, attributes(agent_state))]
+pub fn derive_agent_state(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    
+    match generate_agent_state(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn generate_agent_state(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(syn::Error::new_spanned(input, "AgentState only supports structs with named fields")),
+        },
+        _ => return Err(syn::Error::new_spanned(input, "AgentState only supports structs")),
+    };
+    
+    let key = match parse_agent_state_attribute(&input.attrs)? {
+        AgentStateAttribute::Key(key) => key,
+        AgentStateAttribute::None => to_snake_case(&name.to_string()),
+        AgentStateAttribute::Skip => {
+            return Err(syn::Error::new_spanned(input, "`skip` can only be used on fields"));
+        },
+    };
+    
+    let mut persisted = Vec::new();
+    for field in fields {
+        match parse_agent_state_attribute(&field.attrs)? {
+            AgentStateAttribute::Skip => continue,
+            AgentStateAttribute::None => persisted.push(field),
+            AgentStateAttribute::Key(_) => {
+                return Err(syn::Error::new_spanned(field, "`key` can only be used on the struct"));
+            },
+        }
+    }
+    
+    let idents: Vec<_> = persisted.iter().map(|field| field.ident.as_ref().unwrap()).collect();
+    let field_names: Vec<String> = idents.iter().map(|ident| ident.to_string()).collect();
+    let schemas = persisted.iter().map(|field| {
+        let (json_type, _) = get_type_info(&field.ty, is_option_type(&field.ty));
+        let description = parse_field_description(&field.attrs);
+        if description == "No description" {
+            quote! { json!({ "type": #json_type }) }
+        } else {
+            quote! { json!({ "type": #json_type, "description": #description }) }
+        }
+    });
+    
+    let expanded = quote! {
+        impl #impl_generics ::lumosai_core::agent::AgentState for #name #ty_generics #where_clause {
+            const STATE_KEY: &'static str = #key;
+            
+            fn to_state_value(&self) -> ::lumosai_core::Result<::serde_json::Value> {
+                let mut state = ::serde_json::Map::new();
+                #(
+                    state.insert(#field_names.to_string(), ::serde_json::to_value(&self.#idents)?);
+                )*
+                Ok(::serde_json::Value::Object(state))
+            }
+            
+            fn from_state_value(value: &::serde_json::Value) -> ::lumosai_core::Result<Self> {
+                let state = value.as_object().ok_or_else(|| {
+                    ::lumosai_core::Error::InvalidInput(format!("State `{}` must be a JSON object", #key))
+                })?;
+                let mut result = <Self as ::std::default::Default>::default();
+                #(
+                    if let Some(field) = state.get(#field_names) {
+                        result.#idents = ::serde_json::from_value(field.clone())?;
+                    }
+                )*
+                Ok(result)
+            }
+            
+            fn state_schema() -> ::serde_json::Value {
+                use ::serde_json::json;
+                
+                let mut properties = ::serde_json::Map::new();
+                #(
+                    properties.insert(#field_names.to_string(), #schemas);
+                )*
+                json!({
+                    "type": "object",
+                    "title": #key,
+                    "properties": properties
+                })
+            }
+            
+            fn diff(&self, previous: &Self) -> ::lumosai_core::Result<Vec<::lumosai_core::agent::StateChange>> {
+                let mut changes = Vec::new();
+                #(
+                    let old = ::serde_json::to_value(&previous.#idents)?;
+                    let new = ::serde_json::to_value(&self.#idents)?;
+                    if old != new {
+                        changes.push(::lumosai_core::agent::StateChange {
+                            field: #field_names.to_string(),
+                            old,
+                            new,
+                        });
+                    }
+                )*
+                Ok(changes)
+            }
+        }
+    };
+    
+    Ok(expanded)
+}
+
+/// Parsed `#[agent_state(...)]` attribute
+enum AgentStateAttribute {
+    None,
+    Key(String),
+    Skip,
+}
+
+fn parse_agent_state_attribute(attrs: &[Attribute]) -> syn::Result<AgentStateAttribute> {
+    let mut result = AgentStateAttribute::None;
+    
+    for attr in attrs {
+        if !attr.path().is_ident("agent_state") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("key") {
+                let key: syn::LitStr = meta.value()?.parse()?;
+                result = AgentStateAttribute::Key(key.value());
+                Ok(())
+            } else if meta.path.is_ident("skip") {
+                result = AgentStateAttribute::Skip;
+                Ok(())
+            } else {
+                Err(meta.error("expected `key = \"...\"` or `skip`"))
+            }
+        })?;
+    }
+    
+    Ok(result)
+}
+
+fn to_snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (index, ch) in name.chars().enumerate() {
+        if ch.is_uppercase() {
+            if index > 0 {
+                snake.push('_');
+            }
+            snake.extend(ch.to_lowercase());
+        } else {
+            snake.push(ch);
+        }
+    }
+    snake
+}
+
 fn parse_function_attributes(attrs: &[Attribute]) -> syn::Result<(String, String)> {
     let mut function_name = None;
     let mut function_description = None;
@@ -1,6 +1,8 @@
 //! 异常检测模块
 //! 
-//! 提供企业级异常检测和机器学习功能
+//! 提供企业级异常检测和机器学习功能。每个指标维护一个在线基线：
+//! 无季节性时为带趋势的EWMA（Holt线性平滑），配置季节长度后为加法Holt-Winters。
+//! 观测值与预测值的残差按残差的指数加权标准差归一化，换算为异常分数和严重程度。
 
 use async_trait::async_trait;
 use std::collections::HashMap;
@@ -44,7 +46,7 @@ pub struct AnomalyAlert {
 }
 
 /// 异常严重程度
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum AnomalySeverity {
     Low,
     Medium,
@@ -52,10 +54,229 @@ pub enum AnomalySeverity {
     Critical,
 }
 
+impl AnomalySeverity {
+    /// 根据异常分数确定严重程度
+    pub fn from_score(score: f64) -> Self {
+        if score > 0.9 {
+            AnomalySeverity::Critical
+        } else if score > 0.8 {
+            AnomalySeverity::High
+        } else if score > ALERT_SCORE_THRESHOLD {
+            AnomalySeverity::Medium
+        } else {
+            AnomalySeverity::Low
+        }
+    }
+}
+
+/// 产生告警的异常分数下限
+pub const ALERT_SCORE_THRESHOLD: f64 = 0.7;
+
+/// 请求延迟指标（毫秒）
+pub const METRIC_LATENCY: &str = "latency_ms";
+
+/// 错误率指标 (0.0-1.0)
+pub const METRIC_ERROR_RATE: &str = "error_rate";
+
+/// Token用量指标
+pub const METRIC_TOKEN_USAGE: &str = "token_usage";
+
+/// 成本指标
+pub const METRIC_COST: &str = "cost";
+
+/// 关注的偏离方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AnomalyDirection {
+    /// 只关注高于基线
+    Above,
+    /// 只关注低于基线
+    Below,
+    /// 两个方向都关注
+    Both,
+}
+
+/// 指标基线配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BaselineConfig {
+    /// 水平平滑系数
+    pub alpha: f64,
+    /// 趋势平滑系数，0表示不跟踪趋势
+    pub beta: f64,
+    /// 季节平滑系数
+    pub gamma: f64,
+    /// 季节长度（观测个数），0表示无季节性
+    pub season_length: usize,
+    /// 开始打分前需要的观测数，季节模型至少需要两个完整季节
+    pub warmup: usize,
+    /// 标准差下限占基线水平的比例，避免平稳指标上的微小波动被放大
+    pub min_relative_std: f64,
+    /// 关注的偏离方向
+    pub direction: AnomalyDirection,
+}
+
+impl Default for BaselineConfig {
+    fn default() -> Self {
+        Self {
+            alpha: 0.3,
+            beta: 0.05,
+            gamma: 0.1,
+            season_length: 0,
+            warmup: 10,
+            min_relative_std: 0.05,
+            direction: AnomalyDirection::Both,
+        }
+    }
+}
+
+impl BaselineConfig {
+    /// 带季节性的Holt-Winters基线，如按小时采样的日周期为 `seasonal(24)`
+    pub fn seasonal(season_length: usize) -> Self {
+        Self {
+            season_length,
+            warmup: season_length * 2,
+            ..Self::default()
+        }
+    }
+
+    /// 设置关注的偏离方向
+    pub fn with_direction(mut self, direction: AnomalyDirection) -> Self {
+        self.direction = direction;
+        self
+    }
+}
+
+/// 单个指标的在线基线
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricBaseline {
+    config: BaselineConfig,
+    level: f64,
+    trend: f64,
+    seasonal: Vec<f64>,
+    /// 残差的指数加权方差
+    variance: f64,
+    observations: usize,
+    /// 季节初始化阶段缓存的观测
+    pending: Vec<f64>,
+}
+
+impl MetricBaseline {
+    /// 创建基线
+    pub fn new(config: BaselineConfig) -> Self {
+        Self {
+            seasonal: vec![0.0; config.season_length],
+            config,
+            level: 0.0,
+            trend: 0.0,
+            variance: 0.0,
+            observations: 0,
+            pending: Vec::new(),
+        }
+    }
+
+    /// 已观测的数量
+    pub fn observations(&self) -> usize {
+        self.observations
+    }
+
+    /// 是否已经完成预热，可以打分
+    pub fn is_ready(&self) -> bool {
+        self.observations >= self.config.warmup.max(2) && self.pending.is_empty()
+    }
+
+    /// 下一个观测的预测值
+    pub fn forecast(&self) -> f64 {
+        self.level + self.trend + self.season_factor()
+    }
+
+    fn season_factor(&self) -> f64 {
+        match self.config.season_length {
+            0 => 0.0,
+            m => self.seasonal[self.observations % m],
+        }
+    }
+
+    fn std_dev(&self) -> f64 {
+        self.variance.sqrt().max(self.level.abs() * self.config.min_relative_std).max(f64::EPSILON)
+    }
+
+    /// 观测值相对预测值的标准分数，只计算关注方向上的偏离
+    pub fn z_score(&self, value: f64) -> f64 {
+        let z = (value - self.forecast()) / self.std_dev();
+        match self.config.direction {
+            AnomalyDirection::Above => z.max(0.0),
+            AnomalyDirection::Below => (-z).max(0.0),
+            AnomalyDirection::Both => z.abs(),
+        }
+    }
+
+    /// 异常分数 (0.0-1.0)，预热完成前为0
+    pub fn score(&self, value: f64) -> f64 {
+        if !self.is_ready() {
+            return 0.0;
+        }
+        z_to_score(self.z_score(value))
+    }
+
+    /// 用新的观测更新基线
+    pub fn update(&mut self, value: f64) {
+        let m = self.config.season_length;
+
+        // 季节模型先收集一个完整季节来初始化水平和季节因子
+        if m > 0 && self.observations < m {
+            self.pending.push(value);
+            self.observations += 1;
+            if self.pending.len() == m {
+                self.level = self.pending.iter().sum::<f64>() / m as f64;
+                self.seasonal = self.pending.iter().map(|v| v - self.level).collect();
+                self.variance = self.seasonal.iter().map(|s| s * s).sum::<f64>() / m as f64;
+                self.pending.clear();
+            }
+            return;
+        }
+
+        if self.observations == 0 {
+            self.level = value;
+            self.observations = 1;
+            return;
+        }
+
+        let BaselineConfig { alpha, beta, gamma, .. } = self.config;
+        let season = self.season_factor();
+
+        // 残差按当前标准差截断，避免异常值污染基线
+        let std_dev = self.std_dev();
+        let residual = value - self.forecast();
+        let clipped = if self.is_ready() {
+            residual.clamp(-4.0 * std_dev, 4.0 * std_dev)
+        } else {
+            residual
+        };
+        let value = self.forecast() + clipped;
+
+        let previous_level = self.level;
+        self.level = alpha * (value - season) + (1.0 - alpha) * (self.level + self.trend);
+        self.trend = beta * (self.level - previous_level) + (1.0 - beta) * self.trend;
+        if m > 0 {
+            let index = self.observations % m;
+            self.seasonal[index] = gamma * (value - self.level) + (1.0 - gamma) * season;
+        }
+        self.variance = alpha * clipped * clipped + (1.0 - alpha) * self.variance;
+        self.observations += 1;
+    }
+}
+
+/// 标准分数换算为异常分数：z≈2.8 时为0.7，z≈3.8 时为0.9
+fn z_to_score(z: f64) -> f64 {
+    1.0 - (-(z / 2.5).powi(2)).exp()
+}
+
 /// ML异常检测引擎
 pub struct MLAnomalyEngine {
-    /// 模型参数
-    model_params: HashMap<String, f64>,
+    /// 按指标名称的基线配置
+    configs: HashMap<String, BaselineConfig>,
+
+    /// 按指标名称的基线
+    baselines: HashMap<String, MetricBaseline>,
 }
 
 impl AnomalyDetector {
@@ -67,32 +288,18 @@ impl AnomalyDetector {
         }
     }
     
-    /// 检测异常
+    /// 检测异常，同时用该观测更新指标基线
     pub async fn detect_anomaly(&mut self, metric_name: &str, value: f64) -> Result<Option<AnomalyAlert>> {
-        let anomaly_score = self.ml_engine.calculate_anomaly_score(metric_name, value).await?;
-        
-        if anomaly_score > 0.7 {
-            let alert = AnomalyAlert {
-                id: uuid::Uuid::new_v4().to_string(),
-                metric_name: metric_name.to_string(),
-                anomaly_value: value,
-                expected_value: self.ml_engine.get_expected_value(metric_name).await?,
-                anomaly_score,
-                detected_at: Utc::now(),
-                severity: if anomaly_score > 0.9 {
-                    AnomalySeverity::Critical
-                } else if anomaly_score > 0.8 {
-                    AnomalySeverity::High
-                } else {
-                    AnomalySeverity::Medium
-                },
-            };
-            
+        let alert = self.ml_engine.observe(metric_name, value);
+        if let Some(alert) = &alert {
             self.anomaly_alerts.push(alert.clone());
-            Ok(Some(alert))
-        } else {
-            Ok(None)
         }
+        Ok(alert)
+    }
+
+    /// ML引擎
+    pub fn ml_engine_mut(&mut self) -> &mut MLAnomalyEngine {
+        &mut self.ml_engine
     }
     
     /// 获取异常告警
@@ -102,23 +309,70 @@ impl AnomalyDetector {
 }
 
 impl MLAnomalyEngine {
-    /// 创建新的ML引擎
+    /// 创建新的ML引擎，为延迟、错误率、Token用量和成本指标预置基线配置
     pub fn new() -> Self {
+        let mut configs = HashMap::new();
+        configs.insert(METRIC_LATENCY.to_string(), BaselineConfig::default().with_direction(AnomalyDirection::Above));
+        configs.insert(METRIC_ERROR_RATE.to_string(), BaselineConfig::default().with_direction(AnomalyDirection::Above));
+        configs.insert(METRIC_TOKEN_USAGE.to_string(), BaselineConfig::default());
+        configs.insert(METRIC_COST.to_string(), BaselineConfig::default().with_direction(AnomalyDirection::Above));
+
         Self {
-            model_params: HashMap::new(),
+            configs,
+            baselines: HashMap::new(),
         }
     }
+
+    /// 设置指标的基线配置，已有基线会被重置
+    pub fn configure(&mut self, metric_name: &str, config: BaselineConfig) {
+        self.configs.insert(metric_name.to_string(), config);
+        self.baselines.remove(metric_name);
+    }
+
+    /// 指标的基线
+    pub fn baseline(&self, metric_name: &str) -> Option<&MetricBaseline> {
+        self.baselines.get(metric_name)
+    }
+
+    /// 对观测值打分并更新基线，分数超过阈值时返回告警
+    pub fn observe(&mut self, metric_name: &str, value: f64) -> Option<AnomalyAlert> {
+        let config = self.configs.get(metric_name).cloned().unwrap_or_default();
+        let baseline = self.baselines
+            .entry(metric_name.to_string())
+            .or_insert_with(|| MetricBaseline::new(config));
+
+        let anomaly_score = baseline.score(value);
+        let expected_value = baseline.forecast();
+        baseline.update(value);
+
+        (anomaly_score > ALERT_SCORE_THRESHOLD).then(|| AnomalyAlert {
+            id: uuid::Uuid::new_v4().to_string(),
+            metric_name: metric_name.to_string(),
+            anomaly_value: value,
+            expected_value,
+            anomaly_score,
+            detected_at: Utc::now(),
+            severity: AnomalySeverity::from_score(anomaly_score),
+        })
+    }
     
-    /// 计算异常分数
-    pub async fn calculate_anomaly_score(&self, _metric_name: &str, _value: f64) -> Result<f64> {
-        // 简化实现，返回随机分数
-        Ok(0.5)
+    /// 计算异常分数，不更新基线；基线未预热时为0
+    pub async fn calculate_anomaly_score(&self, metric_name: &str, value: f64) -> Result<f64> {
+        Ok(self.baselines.get(metric_name).map(|b| b.score(value)).unwrap_or(0.0))
     }
     
-    /// 获取期望值
-    pub async fn get_expected_value(&self, _metric_name: &str) -> Result<f64> {
-        // 简化实现
-        Ok(100.0)
+    /// 获取指标下一个观测的期望值
+    pub async fn get_expected_value(&self, metric_name: &str) -> Result<f64> {
+        self.baselines
+            .get(metric_name)
+            .map(MetricBaseline::forecast)
+            .ok_or_else(|| EnterpriseError::AnomalyDetection(format!("指标 {} 还没有基线", metric_name)))
+    }
+}
+
+impl Default for MLAnomalyEngine {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -127,3 +381,53 @@ impl Default for AnomalyDetector {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_latency_spike_detected() {
+        let mut detector = AnomalyDetector::new();
+        for i in 0..50 {
+            let latency = 100.0 + (i % 5) as f64 * 2.0;
+            assert!(detector.detect_anomaly(METRIC_LATENCY, latency).await.unwrap().is_none());
+        }
+
+        let alert = detector.detect_anomaly(METRIC_LATENCY, 400.0).await.unwrap().unwrap();
+        assert_eq!(alert.severity, AnomalySeverity::Critical);
+        assert!((alert.expected_value - 104.0).abs() < 5.0);
+
+        // 延迟下降不是异常
+        assert!(detector.detect_anomaly(METRIC_LATENCY, 20.0).await.unwrap().is_none());
+        assert_eq!(detector.get_alerts().await.unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_seasonal_baseline() {
+        let mut engine = MLAnomalyEngine::new();
+        engine.configure(METRIC_TOKEN_USAGE, BaselineConfig::seasonal(4));
+
+        // 每4个观测一个周期的规律波动
+        let pattern = [1000.0, 5000.0, 9000.0, 5000.0];
+        for i in 0..40 {
+            assert!(engine.observe(METRIC_TOKEN_USAGE, pattern[i % 4]).is_none(), "observation {}", i);
+        }
+        let baseline = engine.baseline(METRIC_TOKEN_USAGE).unwrap();
+        assert!((baseline.forecast() - 1000.0).abs() < 500.0);
+
+        // 周期高点出现在低点位置
+        let alert = engine.observe(METRIC_TOKEN_USAGE, 9000.0).unwrap();
+        assert!(alert.anomaly_score > ALERT_SCORE_THRESHOLD);
+    }
+
+    #[test]
+    fn test_warmup_and_severity() {
+        let mut engine = MLAnomalyEngine::new();
+        assert!(engine.observe(METRIC_COST, 1.0).is_none());
+        assert!(engine.observe(METRIC_COST, 1000.0).is_none());
+        assert_eq!(AnomalySeverity::from_score(0.95), AnomalySeverity::Critical);
+        assert_eq!(AnomalySeverity::from_score(0.5), AnomalySeverity::Low);
+        assert!(z_to_score(2.8) > 0.7 && z_to_score(2.7) < 0.7);
+    }
+}
@@ -1,21 +1,94 @@
 //! 容量规划模块
-//! 
-//! 提供企业级容量规划和预测功能
+//!
+//! 提供企业级容量规划和预测功能：根据QPS、Token用量和向量数量的历史样本拟合增长趋势
+//! （线性或指数，取拟合优度更高者），预测规划期末的用量，
+//! 并给出副本数、向量索引分片数和成本预测等扩容建议，可按固定周期自动生成。
 
-use async_trait::async_trait;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
 
 use crate::error::{EnterpriseError, Result};
 
+/// 每秒请求数
+pub const RESOURCE_QPS: &str = "qps";
+
+/// 每日Token用量
+pub const RESOURCE_DAILY_TOKENS: &str = "daily_tokens";
+
+/// 向量索引中的向量数量
+pub const RESOURCE_VECTOR_COUNT: &str = "vector_count";
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+const DAYS_PER_MONTH: f64 = 30.0;
+
 /// 容量规划器
 pub struct CapacityPlanner {
     /// 容量指标
     capacity_metrics: Vec<CapacityMetrics>,
-    
+
     /// 扩容建议
     scaling_recommendations: Vec<ScalingRecommendation>,
+
+    /// 规划参数
+    config: PlannerConfig,
+
+    /// 当前副本数
+    current_replicas: Option<u32>,
+
+    /// 当前向量索引分片数
+    current_shards: Option<u32>,
+}
+
+/// 规划参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlannerConfig {
+    /// 预测的天数
+    pub horizon_days: u32,
+
+    /// 拟合趋势所需的最少样本数
+    pub min_samples: usize,
+
+    /// 单个副本可承载的QPS
+    pub qps_per_replica: f64,
+
+    /// 副本的目标利用率，预留余量应对突发流量
+    pub target_utilization: f64,
+
+    /// 最少副本数
+    pub min_replicas: u32,
+
+    /// 单个分片的向量数量上限
+    pub vectors_per_shard: u64,
+
+    /// 每百万Token的综合单价
+    pub cost_per_million_tokens: f64,
+
+    /// 每个副本每月成本
+    pub cost_per_replica_month: f64,
+
+    /// 每个分片每月成本
+    pub cost_per_shard_month: f64,
+}
+
+impl Default for PlannerConfig {
+    fn default() -> Self {
+        Self {
+            horizon_days: 30,
+            min_samples: 3,
+            qps_per_replica: 50.0,
+            target_utilization: 0.7,
+            min_replicas: 2,
+            vectors_per_shard: 5_000_000,
+            cost_per_million_tokens: 5.0,
+            cost_per_replica_month: 150.0,
+            cost_per_shard_month: 100.0,
+        }
+    }
 }
 
 /// 容量指标
@@ -23,44 +96,130 @@ pub struct CapacityPlanner {
 pub struct CapacityMetrics {
     /// 资源类型
     pub resource_type: String,
-    
+
     /// 当前使用量
     pub current_usage: f64,
-    
+
     /// 总容量
     pub total_capacity: f64,
-    
+
     /// 使用率
     pub utilization_rate: f64,
-    
+
     /// 测量时间
     pub measured_at: DateTime<Utc>,
 }
 
+/// 趋势模型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TrendModel {
+    /// 每天增加固定的量
+    Linear,
+    /// 每天按固定比例增长
+    Exponential,
+}
+
+/// 拟合的增长趋势
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrowthTrend {
+    /// 资源类型
+    pub resource_type: String,
+
+    /// 趋势模型
+    pub model: TrendModel,
+
+    /// 线性模型为截距，指数模型为截距的对数
+    pub intercept: f64,
+
+    /// 线性模型为每天增量，指数模型为每天增长率的对数
+    pub slope: f64,
+
+    /// 拟合优度
+    pub r_squared: f64,
+
+    /// 时间原点，即第一个样本的时间
+    pub origin: DateTime<Utc>,
+
+    /// 最近一次样本的值
+    pub latest: f64,
+
+    /// 样本数量
+    pub samples: usize,
+}
+
+impl GrowthTrend {
+    /// 预测某一时刻的值
+    pub fn forecast(&self, at: DateTime<Utc>) -> f64 {
+        let days = days_between(self.origin, at);
+        let value = match self.model {
+            TrendModel::Linear => self.intercept + self.slope * days,
+            TrendModel::Exponential => (self.intercept + self.slope * days).exp(),
+        };
+        value.max(0.0)
+    }
+
+    /// 每天的相对增长率
+    pub fn daily_growth_rate(&self, at: DateTime<Utc>) -> f64 {
+        match self.model {
+            TrendModel::Exponential => self.slope.exp() - 1.0,
+            TrendModel::Linear => {
+                let current = self.forecast(at);
+                if current > 0.0 { self.slope / current } else { 0.0 }
+            }
+        }
+    }
+}
+
 /// 扩容建议
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScalingRecommendation {
     /// 资源类型
     pub resource_type: String,
-    
+
     /// 建议操作
     pub action: ScalingAction,
-    
+
     /// 建议值
     pub recommended_value: f64,
-    
+
     /// 理由
     pub reason: String,
-    
+
     /// 优先级
     pub priority: RecommendationPriority,
-    
+
     /// 生成时间
     pub generated_at: DateTime<Utc>,
+
+    /// 建议类型
+    #[serde(default)]
+    pub kind: RecommendationKind,
+
+    /// 规划期末的预测用量
+    #[serde(default)]
+    pub forecast_value: Option<f64>,
+
+    /// 预测的月度成本
+    #[serde(default)]
+    pub projected_monthly_cost: Option<f64>,
+}
+
+/// 建议类型
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RecommendationKind {
+    /// 按当前使用率调整容量
+    #[default]
+    Capacity,
+    /// 服务副本数
+    Replicas,
+    /// 向量索引分片数
+    IndexShards,
+    /// 成本预测
+    CostProjection,
 }
 
 /// 扩容操作
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ScalingAction {
     ScaleUp,
     ScaleDown,
@@ -68,7 +227,7 @@ pub enum ScalingAction {
 }
 
 /// 建议优先级
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum RecommendationPriority {
     Low,
     Medium,
@@ -79,25 +238,137 @@ pub enum RecommendationPriority {
 impl CapacityPlanner {
     /// 创建新的容量规划器
     pub fn new() -> Self {
+        Self::with_config(PlannerConfig::default())
+    }
+
+    /// 使用指定参数创建
+    pub fn with_config(config: PlannerConfig) -> Self {
         Self {
             capacity_metrics: Vec::new(),
             scaling_recommendations: Vec::new(),
+            config,
+            current_replicas: None,
+            current_shards: None,
         }
     }
-    
+
+    /// 规划参数
+    pub fn config(&self) -> &PlannerConfig {
+        &self.config
+    }
+
+    /// 设置当前部署的副本数
+    pub fn set_current_replicas(&mut self, replicas: u32) {
+        self.current_replicas = Some(replicas);
+    }
+
+    /// 设置当前向量索引的分片数
+    pub fn set_current_shards(&mut self, shards: u32) {
+        self.current_shards = Some(shards);
+    }
+
     /// 添加容量指标
     pub async fn add_metrics(&mut self, metrics: CapacityMetrics) -> Result<()> {
         self.capacity_metrics.push(metrics);
         Ok(())
     }
-    
-    /// 生成扩容建议
+
+    /// 记录一个没有容量上限的用量样本，如QPS或Token用量
+    pub fn record_usage(&mut self, resource_type: &str, value: f64, measured_at: DateTime<Utc>) {
+        self.capacity_metrics.push(CapacityMetrics {
+            resource_type: resource_type.to_string(),
+            current_usage: value,
+            total_capacity: 0.0,
+            utilization_rate: 0.0,
+            measured_at,
+        });
+    }
+
+    /// 资源的历史样本，按时间升序
+    fn history(&self, resource_type: &str) -> Vec<(DateTime<Utc>, f64)> {
+        let mut samples: Vec<(DateTime<Utc>, f64)> = self.capacity_metrics.iter()
+            .filter(|m| m.resource_type == resource_type)
+            .map(|m| (m.measured_at, m.current_usage))
+            .collect();
+        samples.sort_by_key(|(at, _)| *at);
+        samples
+    }
+
+    /// 拟合资源的增长趋势
+    pub fn fit_trend(&self, resource_type: &str) -> Result<GrowthTrend> {
+        let samples = self.history(resource_type);
+        if samples.len() < self.config.min_samples.max(2) {
+            return Err(EnterpriseError::CapacityPlanning(format!(
+                "{} 的样本不足: {} < {}",
+                resource_type,
+                samples.len(),
+                self.config.min_samples.max(2),
+            )));
+        }
+
+        let origin = samples[0].0;
+        let xs: Vec<f64> = samples.iter().map(|(at, _)| days_between(origin, *at)).collect();
+        let ys: Vec<f64> = samples.iter().map(|(_, value)| *value).collect();
+        let latest = *ys.last().unwrap();
+
+        let (intercept, slope, r_squared) = least_squares(&xs, &ys).ok_or_else(|| {
+            EnterpriseError::CapacityPlanning(format!("{} 的样本时间相同，无法拟合趋势", resource_type))
+        })?;
+        let mut trend = GrowthTrend {
+            resource_type: resource_type.to_string(),
+            model: TrendModel::Linear,
+            intercept,
+            slope,
+            r_squared,
+            origin,
+            latest,
+            samples: samples.len(),
+        };
+
+        // 只有全部为正且在增长时才考虑指数模型
+        if slope > 0.0 && ys.iter().all(|y| *y > 0.0) {
+            let logs: Vec<f64> = ys.iter().map(|y| y.ln()).collect();
+            if let Some((log_intercept, log_slope, _)) = least_squares(&xs, &logs) {
+                let predicted: Vec<f64> = xs.iter().map(|x| (log_intercept + log_slope * x).exp()).collect();
+                let exp_r_squared = r_squared_of(&ys, &predicted);
+                if exp_r_squared > r_squared {
+                    trend.model = TrendModel::Exponential;
+                    trend.intercept = log_intercept;
+                    trend.slope = log_slope;
+                    trend.r_squared = exp_r_squared;
+                }
+            }
+        }
+        Ok(trend)
+    }
+
+    /// 生成扩容建议：按使用率调整已知容量的资源，并根据增长趋势给出副本、分片和成本建议
     pub async fn generate_recommendations(&mut self) -> Result<Vec<ScalingRecommendation>> {
-        self.scaling_recommendations.clear();
-        
-        for metrics in &self.capacity_metrics {
+        self.scaling_recommendations = self.utilization_recommendations();
+        let forecasts = self.forecast_recommendations(Utc::now());
+        self.scaling_recommendations.extend(forecasts);
+        Ok(self.scaling_recommendations.clone())
+    }
+
+    /// 最近一次生成的建议
+    pub fn recommendations(&self) -> &[ScalingRecommendation] {
+        &self.scaling_recommendations
+    }
+
+    fn utilization_recommendations(&self) -> Vec<ScalingRecommendation> {
+        // 每种资源只看最近一次有容量上限的指标
+        let mut latest: BTreeMap<&str, &CapacityMetrics> = BTreeMap::new();
+        for metrics in self.capacity_metrics.iter().filter(|m| m.total_capacity > 0.0) {
+            let entry = latest.entry(metrics.resource_type.as_str()).or_insert(metrics);
+            if metrics.measured_at >= entry.measured_at {
+                *entry = metrics;
+            }
+        }
+
+        let mut recommendations = Vec::new();
+        for metrics in latest.into_values() {
             if metrics.utilization_rate > 0.8 {
-                let recommendation = ScalingRecommendation {
+                recommendations.push(ScalingRecommendation {
                     resource_type: metrics.resource_type.clone(),
                     action: ScalingAction::ScaleUp,
                     recommended_value: metrics.total_capacity * 1.5,
@@ -108,22 +379,133 @@ impl CapacityPlanner {
                         RecommendationPriority::High
                     },
                     generated_at: Utc::now(),
-                };
-                self.scaling_recommendations.push(recommendation);
+                    kind: RecommendationKind::Capacity,
+                    forecast_value: None,
+                    projected_monthly_cost: None,
+                });
             } else if metrics.utilization_rate < 0.3 {
-                let recommendation = ScalingRecommendation {
+                recommendations.push(ScalingRecommendation {
                     resource_type: metrics.resource_type.clone(),
                     action: ScalingAction::ScaleDown,
                     recommended_value: metrics.total_capacity * 0.7,
                     reason: "Low utilization detected".to_string(),
                     priority: RecommendationPriority::Medium,
                     generated_at: Utc::now(),
-                };
-                self.scaling_recommendations.push(recommendation);
+                    kind: RecommendationKind::Capacity,
+                    forecast_value: None,
+                    projected_monthly_cost: None,
+                });
             }
         }
-        
-        Ok(self.scaling_recommendations.clone())
+        recommendations
+    }
+
+    /// 根据增长趋势生成规划期末的建议，样本不足的资源跳过
+    pub fn forecast_recommendations(&self, now: DateTime<Utc>) -> Vec<ScalingRecommendation> {
+        let horizon_end = now + chrono::Duration::days(self.config.horizon_days as i64);
+        let mut recommendations = Vec::new();
+
+        if let Ok(trend) = self.fit_trend(RESOURCE_QPS) {
+            let peak_qps = trend.forecast(horizon_end).max(trend.latest);
+            let per_replica = self.config.qps_per_replica * self.config.target_utilization;
+            let replicas = ((peak_qps / per_replica).ceil() as u32).max(self.config.min_replicas);
+            let current = self.current_replicas.unwrap_or(self.config.min_replicas);
+            recommendations.push(ScalingRecommendation {
+                resource_type: RESOURCE_QPS.to_string(),
+                action: scaling_action(current, replicas),
+                recommended_value: replicas as f64,
+                reason: format!(
+                    "QPS forecast {:.1} in {} days ({}, {:+.1}%/day); {} replicas at {:.0}% of {:.0} QPS each",
+                    peak_qps,
+                    self.config.horizon_days,
+                    model_label(trend.model),
+                    trend.daily_growth_rate(now) * 100.0,
+                    replicas,
+                    self.config.target_utilization * 100.0,
+                    self.config.qps_per_replica,
+                ),
+                priority: headroom_priority(current as f64 * per_replica, trend.forecast(now), peak_qps),
+                generated_at: now,
+                kind: RecommendationKind::Replicas,
+                forecast_value: Some(peak_qps),
+                projected_monthly_cost: Some(replicas as f64 * self.config.cost_per_replica_month),
+            });
+        }
+
+        if let Ok(trend) = self.fit_trend(RESOURCE_VECTOR_COUNT) {
+            let vectors = trend.forecast(horizon_end).max(trend.latest);
+            let shards = ((vectors / self.config.vectors_per_shard as f64).ceil() as u32).max(1);
+            let current = self.current_shards.unwrap_or(1);
+            recommendations.push(ScalingRecommendation {
+                resource_type: RESOURCE_VECTOR_COUNT.to_string(),
+                action: if shards > current { ScalingAction::ScaleUp } else { ScalingAction::Maintain },
+                recommended_value: shards as f64,
+                reason: format!(
+                    "Vector count forecast {:.0} in {} days; {} shards of up to {} vectors",
+                    vectors, self.config.horizon_days, shards, self.config.vectors_per_shard,
+                ),
+                priority: headroom_priority(
+                    current as f64 * self.config.vectors_per_shard as f64,
+                    trend.forecast(now),
+                    vectors,
+                ),
+                generated_at: now,
+                kind: RecommendationKind::IndexShards,
+                forecast_value: Some(vectors),
+                projected_monthly_cost: Some(shards as f64 * self.config.cost_per_shard_month),
+            });
+        }
+
+        if let Ok(trend) = self.fit_trend(RESOURCE_DAILY_TOKENS) {
+            // 对规划期内每天的预测用量求和，得到下一个月的Token量
+            let month_start = horizon_end - chrono::Duration::days(DAYS_PER_MONTH as i64);
+            let monthly_tokens: f64 = (0..DAYS_PER_MONTH as i64)
+                .map(|day| trend.forecast(month_start + chrono::Duration::days(day)))
+                .sum();
+            let current_monthly = trend.latest * DAYS_PER_MONTH;
+            let cost = monthly_tokens / 1_000_000.0 * self.config.cost_per_million_tokens;
+            let growth = if current_monthly > 0.0 { monthly_tokens / current_monthly - 1.0 } else { 0.0 };
+            recommendations.push(ScalingRecommendation {
+                resource_type: RESOURCE_DAILY_TOKENS.to_string(),
+                action: ScalingAction::Maintain,
+                recommended_value: monthly_tokens,
+                reason: format!(
+                    "Token usage forecast {:.0}/month ({:+.0}% vs current), about {:.2} at {:.2} per million",
+                    monthly_tokens, growth * 100.0, cost, self.config.cost_per_million_tokens,
+                ),
+                priority: if growth > 1.0 {
+                    RecommendationPriority::High
+                } else if growth > 0.25 {
+                    RecommendationPriority::Medium
+                } else {
+                    RecommendationPriority::Low
+                },
+                generated_at: now,
+                kind: RecommendationKind::CostProjection,
+                forecast_value: Some(monthly_tokens),
+                projected_monthly_cost: Some(cost),
+            });
+        }
+
+        recommendations
+    }
+
+    /// 按固定周期生成建议，每次生成后调用回调
+    pub fn schedule<F>(planner: Arc<RwLock<CapacityPlanner>>, every: Duration, mut on_recommendations: F) -> JoinHandle<()>
+    where
+        F: FnMut(Vec<ScalingRecommendation>) + Send + 'static,
+    {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(every);
+            loop {
+                ticker.tick().await;
+                let result = planner.write().await.generate_recommendations().await;
+                match result {
+                    Ok(recommendations) => on_recommendations(recommendations),
+                    Err(e) => tracing::warn!("生成扩容建议失败: {}", e),
+                }
+            }
+        })
     }
 }
 
@@ -132,3 +514,140 @@ impl Default for CapacityPlanner {
         Self::new()
     }
 }
+
+fn days_between(from: DateTime<Utc>, to: DateTime<Utc>) -> f64 {
+    (to - from).num_milliseconds() as f64 / 1000.0 / SECONDS_PER_DAY
+}
+
+/// 最小二乘拟合 `y = a + b·x`，返回 `(a, b, r²)`；x全部相同时返回None
+fn least_squares(xs: &[f64], ys: &[f64]) -> Option<(f64, f64, f64)> {
+    let n = xs.len() as f64;
+    let mean_x = xs.iter().sum::<f64>() / n;
+    let mean_y = ys.iter().sum::<f64>() / n;
+    let sxx: f64 = xs.iter().map(|x| (x - mean_x).powi(2)).sum();
+    if sxx <= f64::EPSILON {
+        return None;
+    }
+    let sxy: f64 = xs.iter().zip(ys).map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    let slope = sxy / sxx;
+    let intercept = mean_y - slope * mean_x;
+    let predicted: Vec<f64> = xs.iter().map(|x| intercept + slope * x).collect();
+    Some((intercept, slope, r_squared_of(ys, &predicted)))
+}
+
+fn r_squared_of(actual: &[f64], predicted: &[f64]) -> f64 {
+    let mean = actual.iter().sum::<f64>() / actual.len() as f64;
+    let total: f64 = actual.iter().map(|y| (y - mean).powi(2)).sum();
+    if total <= f64::EPSILON {
+        return 1.0;
+    }
+    let residual: f64 = actual.iter().zip(predicted).map(|(y, p)| (y - p).powi(2)).sum();
+    1.0 - residual / total
+}
+
+fn scaling_action(current: u32, recommended: u32) -> ScalingAction {
+    match recommended.cmp(&current) {
+        std::cmp::Ordering::Greater => ScalingAction::ScaleUp,
+        std::cmp::Ordering::Less => ScalingAction::ScaleDown,
+        std::cmp::Ordering::Equal => ScalingAction::Maintain,
+    }
+}
+
+/// 按当前容量能否承载现在和规划期末的用量确定优先级
+fn headroom_priority(capacity: f64, now: f64, forecast: f64) -> RecommendationPriority {
+    if now > capacity {
+        RecommendationPriority::Critical
+    } else if forecast > capacity {
+        RecommendationPriority::High
+    } else if forecast > capacity * 0.8 {
+        RecommendationPriority::Medium
+    } else {
+        RecommendationPriority::Low
+    }
+}
+
+fn model_label(model: TrendModel) -> &'static str {
+    match model {
+        TrendModel::Linear => "linear",
+        TrendModel::Exponential => "exponential",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn days_ago(days: i64) -> DateTime<Utc> {
+        Utc::now() - chrono::Duration::days(days)
+    }
+
+    #[test]
+    fn test_fit_trend_models() {
+        let mut planner = CapacityPlanner::new();
+        // 线性增长：每天+10 QPS
+        for day in 0..10 {
+            planner.record_usage(RESOURCE_QPS, 100.0 + 10.0 * day as f64, days_ago(9 - day));
+        }
+        // 指数增长：每天+5%
+        for day in 0..20 {
+            planner.record_usage(RESOURCE_VECTOR_COUNT, 1_000_000.0 * 1.05f64.powi(day), days_ago(19 - day as i64));
+        }
+
+        let qps = planner.fit_trend(RESOURCE_QPS).unwrap();
+        assert_eq!(qps.model, TrendModel::Linear);
+        assert!((qps.slope - 10.0).abs() < 1e-6);
+        assert!((qps.forecast(Utc::now() + chrono::Duration::days(10)) - 290.0).abs() < 1.0);
+
+        let vectors = planner.fit_trend(RESOURCE_VECTOR_COUNT).unwrap();
+        assert_eq!(vectors.model, TrendModel::Exponential);
+        assert!((vectors.daily_growth_rate(Utc::now()) - 0.05).abs() < 1e-6);
+
+        assert!(planner.fit_trend(RESOURCE_DAILY_TOKENS).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_forecast_recommendations() {
+        let mut planner = CapacityPlanner::with_config(PlannerConfig {
+            horizon_days: 30,
+            qps_per_replica: 100.0,
+            target_utilization: 0.5,
+            vectors_per_shard: 1_000_000,
+            cost_per_million_tokens: 2.0,
+            ..PlannerConfig::default()
+        });
+        planner.set_current_replicas(4);
+        for day in 0..10 {
+            planner.record_usage(RESOURCE_QPS, 100.0 + 10.0 * day as f64, days_ago(9 - day));
+            planner.record_usage(RESOURCE_VECTOR_COUNT, 900_000.0 + 10_000.0 * day as f64, days_ago(9 - day));
+            planner.record_usage(RESOURCE_DAILY_TOKENS, 1_000_000.0, days_ago(9 - day));
+        }
+        planner.add_metrics(CapacityMetrics {
+            resource_type: "memory".to_string(),
+            current_usage: 95.0,
+            total_capacity: 100.0,
+            utilization_rate: 0.95,
+            measured_at: Utc::now(),
+        }).await.unwrap();
+
+        let recommendations = planner.generate_recommendations().await.unwrap();
+        let find = |kind| recommendations.iter().find(|r| r.kind == kind).unwrap();
+
+        // 30天后约 490 QPS，每副本 50 QPS -> 10 副本
+        let replicas = find(RecommendationKind::Replicas);
+        assert_eq!(replicas.recommended_value, 10.0);
+        assert_eq!(replicas.action, ScalingAction::ScaleUp);
+        assert_eq!(replicas.priority, RecommendationPriority::High);
+
+        // 30天后约 1,290,000 个向量 -> 2 个分片
+        let shards = find(RecommendationKind::IndexShards);
+        assert_eq!(shards.recommended_value, 2.0);
+
+        // 平稳用量：每月 3000 万 Token，成本 60
+        let cost = find(RecommendationKind::CostProjection);
+        assert!((cost.recommended_value - 30_000_000.0).abs() < 1.0);
+        assert!((cost.projected_monthly_cost.unwrap() - 60.0).abs() < 1e-6);
+
+        let capacity = find(RecommendationKind::Capacity);
+        assert_eq!(capacity.priority, RecommendationPriority::Critical);
+    }
+}
@@ -190,7 +190,7 @@ pub struct ComplianceConfig {
 }
 
 /// 合规标准
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum ComplianceStandard {
     /// SOC 2
     SOC2,
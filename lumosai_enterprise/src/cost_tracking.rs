@@ -1,15 +1,34 @@
 //! 成本跟踪模块
 //! 
-//! 提供企业级成本跟踪和分析功能
+//! 提供企业级成本跟踪和分析功能：按提供商/模型的价格表为Token用量计价，
+//! 从 `UsageTracker` 导入使用量记录，按租户、Agent和模型汇总，
+//! 检查预算并产生告警，以及导出CSV/JSON供财务使用。
 
 use async_trait::async_trait;
-use std::collections::HashMap;
-use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+use std::time::SystemTime;
+use chrono::{DateTime, Datelike, Utc};
+use lumosai_core::billing::{UsageRecord, UsageTracker};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::error::{EnterpriseError, Result};
 
+/// 输入Token的资源类型
+pub const RESOURCE_INPUT_TOKENS: &str = "input_tokens";
+
+/// 输出Token的资源类型
+pub const RESOURCE_OUTPUT_TOKENS: &str = "output_tokens";
+
+/// 使用量记录中的提供商元数据
+pub const META_PROVIDER: &str = "provider";
+
+/// 使用量记录中的模型元数据
+pub const META_MODEL: &str = "model";
+
+/// 使用量记录中的Agent元数据
+pub const META_AGENT: &str = "agent_id";
+
 /// 成本跟踪器
 pub struct CostTracker {
     /// 成本记录
@@ -20,6 +39,15 @@ pub struct CostTracker {
     
     /// 预算限制
     budget_limits: HashMap<String, BudgetLimit>,
+
+    /// 模型价格表
+    pricing: PricingTable,
+
+    /// 已导入的使用量记录ID
+    ingested: HashSet<String>,
+
+    /// 已产生的预算告警
+    budget_alerts: Vec<BudgetAlert>,
 }
 
 /// 成本记录
@@ -48,6 +76,18 @@ pub struct CostRecord {
     
     /// 标签
     pub tags: HashMap<String, String>,
+
+    /// 产生成本的Agent
+    #[serde(default)]
+    pub agent_id: Option<String>,
+
+    /// 模型提供商
+    #[serde(default)]
+    pub provider: Option<String>,
+
+    /// 模型名称
+    #[serde(default)]
+    pub model: Option<String>,
 }
 
 /// 成本规则
@@ -96,11 +136,152 @@ pub struct CostMetrics {
     
     /// 按资源类型分组的成本
     pub cost_by_resource: HashMap<String, f64>,
+
+    /// 按Agent分组的成本，未标注Agent的记录不计入
+    pub cost_by_agent: HashMap<String, f64>,
+
+    /// 按 `提供商/模型` 分组的成本
+    pub cost_by_model: HashMap<String, f64>,
+
+    /// 按租户和Agent分组的成本
+    pub cost_by_tenant_agent: HashMap<(String, String), f64>,
     
     /// 时间范围
     pub time_range: (DateTime<Utc>, DateTime<Utc>),
 }
 
+/// 模型价格，单位为每百万Token的美元价格
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelPrice {
+    /// 提供商，如 openai、anthropic、deepseek
+    pub provider: String,
+
+    /// 模型名称，也匹配以该名称开头的带版本后缀的模型
+    pub model: String,
+
+    /// 输入Token价格
+    pub input_per_million: f64,
+
+    /// 输出Token价格
+    pub output_per_million: f64,
+}
+
+impl ModelPrice {
+    /// 创建模型价格
+    pub fn new(provider: &str, model: &str, input_per_million: f64, output_per_million: f64) -> Self {
+        Self {
+            provider: provider.to_string(),
+            model: model.to_string(),
+            input_per_million,
+            output_per_million,
+        }
+    }
+
+    /// 计算一次调用的成本
+    pub fn cost(&self, input_tokens: u64, output_tokens: u64) -> f64 {
+        (input_tokens as f64 * self.input_per_million + output_tokens as f64 * self.output_per_million) / 1_000_000.0
+    }
+}
+
+/// 按提供商和模型的价格表
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PricingTable {
+    /// 价格列表
+    pub prices: Vec<ModelPrice>,
+}
+
+impl PricingTable {
+    /// 创建空价格表
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 各提供商公开的标准价格（美元/百万Token）
+    pub fn standard() -> Self {
+        Self {
+            prices: vec![
+                ModelPrice::new("openai", "gpt-4o", 2.50, 10.00),
+                ModelPrice::new("openai", "gpt-4o-mini", 0.15, 0.60),
+                ModelPrice::new("openai", "gpt-4-turbo", 10.00, 30.00),
+                ModelPrice::new("openai", "gpt-4", 30.00, 60.00),
+                ModelPrice::new("openai", "gpt-3.5-turbo", 0.50, 1.50),
+                ModelPrice::new("openai", "o1", 15.00, 60.00),
+                ModelPrice::new("openai", "o1-mini", 3.00, 12.00),
+                ModelPrice::new("openai", "text-embedding-3-small", 0.02, 0.0),
+                ModelPrice::new("openai", "text-embedding-3-large", 0.13, 0.0),
+                ModelPrice::new("anthropic", "claude-3-5-sonnet", 3.00, 15.00),
+                ModelPrice::new("anthropic", "claude-3-5-haiku", 0.80, 4.00),
+                ModelPrice::new("anthropic", "claude-3-opus", 15.00, 75.00),
+                ModelPrice::new("anthropic", "claude-3-haiku", 0.25, 1.25),
+                ModelPrice::new("deepseek", "deepseek-chat", 0.27, 1.10),
+                ModelPrice::new("deepseek", "deepseek-reasoner", 0.55, 2.19),
+            ],
+        }
+    }
+
+    /// 从JSON加载价格表
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// 设置模型价格，替换同一提供商和模型的已有价格
+    pub fn set_price(&mut self, price: ModelPrice) {
+        self.prices.retain(|p| !(p.provider == price.provider && p.model == price.model));
+        self.prices.push(price);
+    }
+
+    /// 查找模型价格，优先完全匹配，其次是最长的前缀匹配（如 `gpt-4o-2024-08-06` 匹配 `gpt-4o`）
+    pub fn lookup(&self, provider: &str, model: &str) -> Option<&ModelPrice> {
+        let candidates = self.prices.iter().filter(|p| p.provider.eq_ignore_ascii_case(provider));
+        candidates
+            .filter(|p| model == p.model || model.starts_with(&format!("{}-", p.model)))
+            .max_by_key(|p| p.model.len())
+    }
+}
+
+/// 预算周期
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BudgetPeriod {
+    /// 自然月
+    Monthly,
+    /// 自然年
+    Yearly,
+}
+
+/// 预算告警级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BudgetAlertLevel {
+    /// 达到告警阈值
+    Warning,
+    /// 超出预算
+    Exceeded,
+}
+
+/// 预算告警
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BudgetAlert {
+    /// 租户ID
+    pub tenant_id: String,
+
+    /// 预算周期
+    pub period: BudgetPeriod,
+
+    /// 周期标识，如 `2024-06` 或 `2024`
+    pub period_label: String,
+
+    /// 告警级别
+    pub level: BudgetAlertLevel,
+
+    /// 本周期已花费
+    pub spent: f64,
+
+    /// 本周期预算
+    pub budget: f64,
+
+    /// 告警时间
+    pub triggered_at: DateTime<Utc>,
+}
+
 /// 计费管理器
 pub struct BillingManager {
     /// 成本跟踪器
@@ -119,47 +300,219 @@ pub struct InvoiceGenerator {
 impl CostTracker {
     /// 创建新的成本跟踪器
     pub fn new() -> Self {
+        Self::with_pricing(PricingTable::standard())
+    }
+
+    /// 使用指定价格表创建成本跟踪器
+    pub fn with_pricing(pricing: PricingTable) -> Self {
         Self {
             cost_records: Vec::new(),
             cost_rules: HashMap::new(),
             budget_limits: HashMap::new(),
+            pricing,
+            ingested: HashSet::new(),
+            budget_alerts: Vec::new(),
         }
     }
+
+    /// 价格表
+    pub fn pricing(&self) -> &PricingTable {
+        &self.pricing
+    }
+
+    /// 可修改的价格表
+    pub fn pricing_mut(&mut self) -> &mut PricingTable {
+        &mut self.pricing
+    }
     
     /// 记录成本
     pub async fn record_cost(&mut self, tenant_id: &str, resource_type: &str, usage_amount: f64) -> Result<()> {
-        if let Some(rule) = self.cost_rules.get(resource_type) {
-            let total_cost = usage_amount * rule.unit_cost;
-            
+        if let Some(rule) = self.cost_rules.get(resource_type).filter(|rule| rule.enabled) {
             let record = CostRecord {
                 id: Uuid::new_v4(),
                 tenant_id: tenant_id.to_string(),
                 resource_type: resource_type.to_string(),
                 usage_amount,
                 unit_cost: rule.unit_cost,
-                total_cost,
+                total_cost: usage_amount * rule.unit_cost,
                 timestamp: Utc::now(),
                 tags: HashMap::new(),
+                agent_id: None,
+                provider: None,
+                model: None,
             };
             
-            self.cost_records.push(record);
+            self.push_record(record);
         }
         
         Ok(())
     }
+
+    /// 导入一条使用量记录，返回生成的成本记录
+    ///
+    /// `input_tokens`/`output_tokens` 记录按元数据中的提供商和模型查价，
+    /// 其他资源类型按成本规则计价；已带成本的记录直接使用其成本。
+    /// 同一记录重复导入时忽略。
+    pub fn ingest_usage(&mut self, usage: &UsageRecord) -> Result<Option<CostRecord>> {
+        if self.ingested.contains(&usage.id) {
+            return Ok(None);
+        }
+
+        let provider = usage.metadata.get(META_PROVIDER).cloned();
+        let model = usage.metadata.get(META_MODEL).cloned();
+        let quantity = usage.quantity as f64;
+
+        let unit_cost = if let Some(cost) = usage.cost {
+            if quantity > 0.0 { cost / quantity } else { 0.0 }
+        } else if usage.resource_type == RESOURCE_INPUT_TOKENS || usage.resource_type == RESOURCE_OUTPUT_TOKENS {
+            let (Some(provider), Some(model)) = (&provider, &model) else {
+                return Err(EnterpriseError::CostTracking(format!(
+                    "使用量记录 {} 缺少 {} 或 {} 元数据", usage.id, META_PROVIDER, META_MODEL
+                )));
+            };
+            let price = self.pricing.lookup(provider, model).ok_or_else(|| {
+                EnterpriseError::CostTracking(format!("价格表中没有模型 {}/{}", provider, model))
+            })?;
+            let per_million = if usage.resource_type == RESOURCE_INPUT_TOKENS {
+                price.input_per_million
+            } else {
+                price.output_per_million
+            };
+            per_million / 1_000_000.0
+        } else if let Some(rule) = self.cost_rules.get(&usage.resource_type).filter(|rule| rule.enabled) {
+            rule.unit_cost
+        } else {
+            // 没有计价方式的资源不产生成本
+            self.ingested.insert(usage.id.clone());
+            return Ok(None);
+        };
+
+        let tags = usage.metadata.iter()
+            .filter(|(key, _)| ![META_PROVIDER, META_MODEL, META_AGENT].contains(&key.as_str()))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+
+        let record = CostRecord {
+            id: Uuid::new_v4(),
+            tenant_id: usage.tenant_id.to_string(),
+            resource_type: usage.resource_type.clone(),
+            usage_amount: quantity,
+            unit_cost,
+            total_cost: quantity * unit_cost,
+            timestamp: DateTime::<Utc>::from(usage.timestamp),
+            tags,
+            agent_id: usage.metadata.get(META_AGENT).cloned(),
+            provider,
+            model,
+        };
+
+        self.ingested.insert(usage.id.clone());
+        self.push_record(record.clone());
+        Ok(Some(record))
+    }
+
+    /// 导入使用量跟踪器中指定时间之后的记录，返回新生成的成本记录数
+    ///
+    /// 无法计价的记录会被跳过并记录警告，不影响其他记录的导入。
+    pub fn ingest_tracker(&mut self, tracker: &UsageTracker, since: SystemTime) -> usize {
+        let mut ingested = 0;
+        for usage in tracker.records_since(since) {
+            match self.ingest_usage(usage) {
+                Ok(Some(_)) => ingested += 1,
+                Ok(None) => {}
+                Err(e) => tracing::warn!("跳过无法计价的使用量记录: {}", e),
+            }
+        }
+        ingested
+    }
+
+    fn push_record(&mut self, record: CostRecord) {
+        let tenant_id = record.tenant_id.clone();
+        let timestamp = record.timestamp;
+        self.cost_records.push(record);
+        self.check_budget(&tenant_id, timestamp);
+    }
+
+    /// 检查租户在记录所在周期的预算，每个周期每个级别只告警一次
+    fn check_budget(&mut self, tenant_id: &str, at: DateTime<Utc>) {
+        let Some(limit) = self.budget_limits.get(tenant_id).cloned() else {
+            return;
+        };
+
+        for (period, budget) in [(BudgetPeriod::Monthly, limit.monthly_budget), (BudgetPeriod::Yearly, limit.yearly_budget)] {
+            if budget <= 0.0 {
+                continue;
+            }
+
+            let (period_label, spent) = self.period_spend(tenant_id, period, at);
+            let level = if spent > budget {
+                BudgetAlertLevel::Exceeded
+            } else if spent >= budget * limit.alert_threshold {
+                BudgetAlertLevel::Warning
+            } else {
+                continue;
+            };
+
+            let already_alerted = self.budget_alerts.iter().any(|alert| {
+                alert.tenant_id == tenant_id && alert.period == period && alert.period_label == period_label && alert.level == level
+            });
+            if !already_alerted {
+                tracing::warn!("租户 {} 的{:?}预算告警: 已花费 {:.2} / 预算 {:.2}", tenant_id, period, spent, budget);
+                self.budget_alerts.push(BudgetAlert {
+                    tenant_id: tenant_id.to_string(),
+                    period,
+                    period_label,
+                    level,
+                    spent,
+                    budget,
+                    triggered_at: Utc::now(),
+                });
+            }
+        }
+    }
+
+    /// 租户在某个周期内的花费
+    fn period_spend(&self, tenant_id: &str, period: BudgetPeriod, at: DateTime<Utc>) -> (String, f64) {
+        let in_period = |timestamp: &DateTime<Utc>| match period {
+            BudgetPeriod::Monthly => timestamp.year() == at.year() && timestamp.month() == at.month(),
+            BudgetPeriod::Yearly => timestamp.year() == at.year(),
+        };
+        let label = match period {
+            BudgetPeriod::Monthly => format!("{:04}-{:02}", at.year(), at.month()),
+            BudgetPeriod::Yearly => format!("{:04}", at.year()),
+        };
+        let spent = self.cost_records.iter()
+            .filter(|record| record.tenant_id == tenant_id && in_period(&record.timestamp))
+            .map(|record| record.total_cost)
+            .sum();
+        (label, spent)
+    }
+
+    /// 已产生的预算告警
+    pub fn budget_alerts(&self) -> &[BudgetAlert] {
+        &self.budget_alerts
+    }
     
     /// 获取成本指标
     pub async fn get_metrics(&self, start_time: DateTime<Utc>, end_time: DateTime<Utc>) -> Result<CostMetrics> {
         let mut total_cost = 0.0;
         let mut cost_by_tenant = HashMap::new();
         let mut cost_by_resource = HashMap::new();
+        let mut cost_by_agent = HashMap::new();
+        let mut cost_by_model = HashMap::new();
+        let mut cost_by_tenant_agent = HashMap::new();
         
-        for record in &self.cost_records {
-            if record.timestamp >= start_time && record.timestamp <= end_time {
-                total_cost += record.total_cost;
-                
-                *cost_by_tenant.entry(record.tenant_id.clone()).or_insert(0.0) += record.total_cost;
-                *cost_by_resource.entry(record.resource_type.clone()).or_insert(0.0) += record.total_cost;
+        for record in self.records_between(start_time, end_time) {
+            total_cost += record.total_cost;
+            
+            *cost_by_tenant.entry(record.tenant_id.clone()).or_insert(0.0) += record.total_cost;
+            *cost_by_resource.entry(record.resource_type.clone()).or_insert(0.0) += record.total_cost;
+            if let Some(agent_id) = &record.agent_id {
+                *cost_by_agent.entry(agent_id.clone()).or_insert(0.0) += record.total_cost;
+                *cost_by_tenant_agent.entry((record.tenant_id.clone(), agent_id.clone())).or_insert(0.0) += record.total_cost;
+            }
+            if let (Some(provider), Some(model)) = (&record.provider, &record.model) {
+                *cost_by_model.entry(format!("{}/{}", provider, model)).or_insert(0.0) += record.total_cost;
             }
         }
         
@@ -167,9 +520,47 @@ impl CostTracker {
             total_cost,
             cost_by_tenant,
             cost_by_resource,
+            cost_by_agent,
+            cost_by_model,
+            cost_by_tenant_agent,
             time_range: (start_time, end_time),
         })
     }
+
+    /// 时间范围内的成本记录
+    pub fn records_between(&self, start_time: DateTime<Utc>, end_time: DateTime<Utc>) -> impl Iterator<Item = &CostRecord> {
+        self.cost_records.iter()
+            .filter(move |record| record.timestamp >= start_time && record.timestamp <= end_time)
+    }
+
+    /// 导出时间范围内的成本记录为CSV
+    pub fn export_csv(&self, start_time: DateTime<Utc>, end_time: DateTime<Utc>) -> String {
+        let mut csv = String::from("id,timestamp,tenant_id,agent_id,provider,model,resource_type,usage_amount,unit_cost,total_cost\n");
+        for record in self.records_between(start_time, end_time) {
+            let fields = [
+                record.id.to_string(),
+                record.timestamp.to_rfc3339(),
+                record.tenant_id.clone(),
+                record.agent_id.clone().unwrap_or_default(),
+                record.provider.clone().unwrap_or_default(),
+                record.model.clone().unwrap_or_default(),
+                record.resource_type.clone(),
+                record.usage_amount.to_string(),
+                format!("{:.10}", record.unit_cost),
+                format!("{:.6}", record.total_cost),
+            ];
+            let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+            csv.push_str(&row.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+
+    /// 导出时间范围内的成本记录为JSON
+    pub fn export_json(&self, start_time: DateTime<Utc>, end_time: DateTime<Utc>) -> Result<String> {
+        let records: Vec<&CostRecord> = self.records_between(start_time, end_time).collect();
+        Ok(serde_json::to_string_pretty(&records)?)
+    }
     
     /// 设置成本规则
     pub async fn set_cost_rule(&mut self, rule: CostRule) -> Result<()> {
@@ -184,6 +575,15 @@ impl CostTracker {
     }
 }
 
+/// 按RFC 4180转义CSV字段
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
 impl BillingManager {
     /// 创建新的计费管理器
     pub async fn new() -> Result<Self> {
@@ -276,4 +676,101 @@ mod tests {
         
         assert_eq!(bill, 0.4); // 8.0 * 0.05
     }
+
+    fn token_usage(tenant_id: Uuid, resource_type: &str, quantity: u64, agent_id: &str) -> UsageRecord {
+        UsageRecord::new(tenant_id, resource_type.to_string(), quantity, "tokens".to_string())
+            .with_metadata(META_PROVIDER.to_string(), "openai".to_string())
+            .with_metadata(META_MODEL.to_string(), "gpt-4o-2024-08-06".to_string())
+            .with_metadata(META_AGENT.to_string(), agent_id.to_string())
+    }
+
+    #[test]
+    fn test_pricing_lookup() {
+        let pricing = PricingTable::standard();
+        assert_eq!(pricing.lookup("openai", "gpt-4o").unwrap().model, "gpt-4o");
+        assert_eq!(pricing.lookup("openai", "gpt-4o-mini").unwrap().model, "gpt-4o-mini");
+        assert_eq!(pricing.lookup("OpenAI", "gpt-4o-2024-08-06").unwrap().model, "gpt-4o");
+        assert!(pricing.lookup("openai", "gpt-4oo").is_none());
+        assert!(pricing.lookup("anthropic", "gpt-4o").is_none());
+
+        let price = pricing.lookup("anthropic", "claude-3-5-sonnet-20241022").unwrap();
+        assert!((price.cost(1_000_000, 100_000) - 4.5).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_ingest_usage_tracker() {
+        let tenant_id = Uuid::new_v4();
+        let mut usage = UsageTracker::new();
+        usage.record_usage(token_usage(tenant_id, RESOURCE_INPUT_TOKENS, 200_000, "support")).unwrap();
+        usage.record_usage(token_usage(tenant_id, RESOURCE_OUTPUT_TOKENS, 50_000, "support")).unwrap();
+        usage.record_usage(token_usage(tenant_id, RESOURCE_OUTPUT_TOKENS, 10_000, "writer")).unwrap();
+        usage.record_usage(UsageRecord::new(tenant_id, "storage_gb".to_string(), 5, "GB".to_string())).unwrap();
+
+        let mut tracker = CostTracker::new();
+        let since = SystemTime::now() - std::time::Duration::from_secs(60);
+        assert_eq!(tracker.ingest_tracker(&usage, since), 3);
+        // 重复导入不会重复计费
+        assert_eq!(tracker.ingest_tracker(&usage, since), 0);
+
+        let metrics = tracker.get_metrics(Utc::now() - chrono::Duration::hours(1), Utc::now()).await.unwrap();
+        // 200k * 2.5/1M + 60k * 10/1M
+        assert!((metrics.total_cost - 1.1).abs() < 1e-9);
+        assert!((metrics.cost_by_agent["support"] - 1.0).abs() < 1e-9);
+        assert!((metrics.cost_by_agent["writer"] - 0.1).abs() < 1e-9);
+        assert!((metrics.cost_by_model["openai/gpt-4o-2024-08-06"] - 1.1).abs() < 1e-9);
+        assert!(metrics.cost_by_tenant_agent.contains_key(&(tenant_id.to_string(), "support".to_string())));
+
+        let unknown = UsageRecord::new(tenant_id, RESOURCE_INPUT_TOKENS.to_string(), 10, "tokens".to_string())
+            .with_metadata(META_PROVIDER.to_string(), "acme".to_string())
+            .with_metadata(META_MODEL.to_string(), "mystery".to_string());
+        assert!(tracker.ingest_usage(&unknown).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_budget_alerts() {
+        let tenant_id = Uuid::new_v4();
+        let mut tracker = CostTracker::new();
+        tracker.set_budget_limit(BudgetLimit {
+            tenant_id: tenant_id.to_string(),
+            monthly_budget: 1.0,
+            yearly_budget: 100.0,
+            alert_threshold: 0.8,
+        }).await.unwrap();
+
+        tracker.ingest_usage(&token_usage(tenant_id, RESOURCE_OUTPUT_TOKENS, 50_000, "support")).unwrap();
+        assert!(tracker.budget_alerts().is_empty());
+
+        tracker.ingest_usage(&token_usage(tenant_id, RESOURCE_OUTPUT_TOKENS, 40_000, "support")).unwrap();
+        assert_eq!(tracker.budget_alerts().len(), 1);
+        assert_eq!(tracker.budget_alerts()[0].level, BudgetAlertLevel::Warning);
+        assert_eq!(tracker.budget_alerts()[0].period, BudgetPeriod::Monthly);
+
+        tracker.ingest_usage(&token_usage(tenant_id, RESOURCE_OUTPUT_TOKENS, 5_000, "support")).unwrap();
+        assert_eq!(tracker.budget_alerts().len(), 1);
+
+        tracker.ingest_usage(&token_usage(tenant_id, RESOURCE_OUTPUT_TOKENS, 20_000, "support")).unwrap();
+        assert_eq!(tracker.budget_alerts().len(), 2);
+        assert_eq!(tracker.budget_alerts()[1].level, BudgetAlertLevel::Exceeded);
+    }
+
+    #[test]
+    fn test_export() {
+        let tenant_id = Uuid::new_v4();
+        let mut tracker = CostTracker::new();
+        let usage = token_usage(tenant_id, RESOURCE_INPUT_TOKENS, 1_000, "support, eu")
+            .with_metadata("conversation".to_string(), "c1".to_string());
+        tracker.ingest_usage(&usage).unwrap();
+
+        let (start, end) = (Utc::now() - chrono::Duration::hours(1), Utc::now());
+        let csv = tracker.export_csv(start, end);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("id,timestamp,tenant_id,agent_id"));
+        assert!(lines[1].contains("\"support, eu\",openai,gpt-4o-2024-08-06,input_tokens,1000,"));
+        assert!(lines[1].ends_with(",0.002500"));
+
+        let json: Vec<CostRecord> = serde_json::from_str(&tracker.export_json(start, end).unwrap()).unwrap();
+        assert_eq!(json.len(), 1);
+        assert_eq!(json[0].tags["conversation"], "c1");
+    }
 }
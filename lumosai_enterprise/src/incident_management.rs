@@ -1,22 +1,35 @@
 //! 事件管理模块
-//! 
-//! 提供企业级事件管理和响应功能
+//!
+//! 提供企业级事件管理和响应功能：从告警创建事件（相同去重键的告警合并到未结束的事件），
+//! 记录事件时间线，按响应计划分配响应人，并通过工作流引擎自动执行修复步骤，
+//! 如切换模型提供商或扩容。
 
-use async_trait::async_trait;
 use std::collections::HashMap;
+use std::sync::Arc;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use uuid::Uuid;
 
+use lumosai_core::agent::types::RuntimeContext;
+use lumosai_core::workflow::Workflow;
+
+use crate::alerting::AlertRule;
+use crate::anomaly_detection::{AnomalyAlert, AnomalySeverity};
+use crate::cost_tracking::{BudgetAlert, BudgetAlertLevel};
 use crate::error::{EnterpriseError, Result};
+use crate::sla_monitoring::{SLAViolation, ViolationSeverity};
 
 /// 事件管理器
 pub struct IncidentManager {
     /// 事件记录
     incidents: HashMap<Uuid, Incident>,
-    
+
     /// 响应计划
     response_plans: HashMap<String, IncidentResponse>,
+
+    /// 可用于修复的工作流
+    workflows: HashMap<String, Arc<dyn Workflow>>,
 }
 
 /// 事件
@@ -24,34 +37,80 @@ pub struct IncidentManager {
 pub struct Incident {
     /// 事件ID
     pub id: Uuid,
-    
+
     /// 标题
     pub title: String,
-    
+
     /// 描述
     pub description: String,
-    
+
     /// 严重程度
     pub severity: IncidentSeverity,
-    
+
     /// 状态
     pub status: IncidentStatus,
-    
+
     /// 创建时间
     pub created_at: DateTime<Utc>,
-    
+
     /// 更新时间
     pub updated_at: DateTime<Utc>,
-    
+
     /// 负责人
     pub assignee: Option<String>,
-    
+
     /// 标签
     pub tags: Vec<String>,
+
+    /// 告警来源，如 `anomaly`、`sla`、`budget`
+    #[serde(default)]
+    pub source: Option<String>,
+
+    /// 告警去重键
+    #[serde(default)]
+    pub dedup_key: Option<String>,
+
+    /// 合并到该事件的告警次数
+    #[serde(default)]
+    pub alert_count: u32,
+
+    /// 响应人
+    #[serde(default)]
+    pub responders: Vec<String>,
+
+    /// 时间线
+    #[serde(default)]
+    pub timeline: Vec<TimelineEntry>,
+
+    /// 确认时间
+    #[serde(default)]
+    pub acknowledged_at: Option<DateTime<Utc>>,
+
+    /// 解决时间
+    #[serde(default)]
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+impl Incident {
+    /// 事件是否仍未结束
+    pub fn is_active(&self) -> bool {
+        !matches!(self.status, IncidentStatus::Resolved | IncidentStatus::Closed)
+    }
+
+    fn record(&mut self, kind: TimelineKind, actor: Option<&str>, message: impl Into<String>) {
+        let now = Utc::now();
+        self.timeline.push(TimelineEntry {
+            at: now,
+            kind,
+            actor: actor.map(String::from),
+            message: message.into(),
+        });
+        self.updated_at = now;
+    }
 }
 
 /// 事件严重程度
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum IncidentSeverity {
     Low,
     Medium,
@@ -59,29 +118,179 @@ pub enum IncidentSeverity {
     Critical,
 }
 
+impl From<AnomalySeverity> for IncidentSeverity {
+    fn from(severity: AnomalySeverity) -> Self {
+        match severity {
+            AnomalySeverity::Low => IncidentSeverity::Low,
+            AnomalySeverity::Medium => IncidentSeverity::Medium,
+            AnomalySeverity::High => IncidentSeverity::High,
+            AnomalySeverity::Critical => IncidentSeverity::Critical,
+        }
+    }
+}
+
+impl From<&ViolationSeverity> for IncidentSeverity {
+    fn from(severity: &ViolationSeverity) -> Self {
+        match severity {
+            ViolationSeverity::Low => IncidentSeverity::Low,
+            ViolationSeverity::Medium => IncidentSeverity::Medium,
+            ViolationSeverity::High => IncidentSeverity::High,
+            ViolationSeverity::Critical => IncidentSeverity::Critical,
+        }
+    }
+}
+
 /// 事件状态
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum IncidentStatus {
     Open,
+    Acknowledged,
     InProgress,
     Resolved,
     Closed,
 }
 
+/// 时间线条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelineEntry {
+    /// 发生时间
+    pub at: DateTime<Utc>,
+
+    /// 条目类型
+    pub kind: TimelineKind,
+
+    /// 操作人，系统操作为空
+    pub actor: Option<String>,
+
+    /// 内容
+    pub message: String,
+}
+
+/// 时间线条目类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimelineKind {
+    Created,
+    AlertFired,
+    SeverityChanged,
+    StatusChanged,
+    ResponderAssigned,
+    Note,
+    StepPending,
+    RemediationStarted,
+    RemediationSucceeded,
+    RemediationFailed,
+}
+
+/// 触发事件的告警
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IncidentAlert {
+    /// 告警来源，用于匹配响应计划
+    pub source: String,
+
+    /// 去重键，相同键的告警合并到同一个未结束的事件
+    pub dedup_key: String,
+
+    /// 标题
+    pub title: String,
+
+    /// 描述
+    pub description: String,
+
+    /// 严重程度
+    pub severity: IncidentSeverity,
+
+    /// 标签，也用于匹配响应计划
+    pub tags: Vec<String>,
+}
+
+impl IncidentAlert {
+    /// 来自异常检测
+    pub fn from_anomaly(alert: &AnomalyAlert) -> Self {
+        Self {
+            source: "anomaly".to_string(),
+            dedup_key: format!("anomaly:{}", alert.metric_name),
+            title: format!("Anomaly detected in {}", alert.metric_name),
+            description: format!(
+                "{} was {:.3}, expected {:.3} (score {:.2})",
+                alert.metric_name, alert.anomaly_value, alert.expected_value, alert.anomaly_score,
+            ),
+            severity: alert.severity.into(),
+            tags: vec![alert.metric_name.clone()],
+        }
+    }
+
+    /// 来自SLA违约
+    pub fn from_sla_violation(violation: &SLAViolation) -> Self {
+        Self {
+            source: "sla".to_string(),
+            dedup_key: format!("sla:{}:{:?}", violation.sla_id, violation.violation_type),
+            title: format!("SLA violation on {}: {:?}", violation.service_name, violation.violation_type),
+            description: format!(
+                "target {:.3}, actual {:.3}",
+                violation.target_value, violation.actual_value,
+            ),
+            severity: (&violation.severity).into(),
+            tags: vec![violation.service_name.clone()],
+        }
+    }
+
+    /// 来自预算告警
+    pub fn from_budget_alert(alert: &BudgetAlert) -> Self {
+        Self {
+            source: "budget".to_string(),
+            dedup_key: format!("budget:{}:{}", alert.tenant_id, alert.period_label),
+            title: format!("Budget {:?} for tenant {}", alert.level, alert.tenant_id),
+            description: format!("spent {:.2} of {:.2} in {}", alert.spent, alert.budget, alert.period_label),
+            severity: match alert.level {
+                BudgetAlertLevel::Warning => IncidentSeverity::Medium,
+                BudgetAlertLevel::Exceeded => IncidentSeverity::High,
+            },
+            tags: vec![alert.tenant_id.clone()],
+        }
+    }
+
+    /// 来自告警规则
+    pub fn from_alert_rule(rule: &AlertRule, value: f64, severity: IncidentSeverity) -> Self {
+        Self {
+            source: "alert_rule".to_string(),
+            dedup_key: format!("rule:{}", rule.id),
+            title: rule.name.clone(),
+            description: format!(
+                "{} = {} ({:?} {})",
+                rule.metric_name, value, rule.comparison, rule.threshold,
+            ),
+            severity,
+            tags: vec![rule.metric_name.clone()],
+        }
+    }
+}
+
 /// 事件响应
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IncidentResponse {
     /// 响应计划ID
     pub id: String,
-    
-    /// 事件类型
+
+    /// 事件类型，匹配告警来源或事件标签
     pub incident_type: String,
-    
+
     /// 响应步骤
     pub response_steps: Vec<ResponseStep>,
-    
-    /// 通知列表
+
+    /// 通知列表，创建事件时分配为响应人
     pub notification_list: Vec<String>,
+
+    /// 适用的最低严重程度
+    #[serde(default)]
+    pub min_severity: Option<IncidentSeverity>,
+}
+
+impl IncidentResponse {
+    fn applies_to(&self, incident: &Incident) -> bool {
+        let type_matches = incident.source.as_deref() == Some(self.incident_type.as_str())
+            || incident.tags.iter().any(|tag| tag == &self.incident_type);
+        type_matches && self.min_severity.is_none_or(|min| incident.severity >= min)
+    }
 }
 
 /// 响应步骤
@@ -89,15 +298,30 @@ pub struct IncidentResponse {
 pub struct ResponseStep {
     /// 步骤名称
     pub name: String,
-    
+
     /// 描述
     pub description: String,
-    
+
     /// 执行顺序
     pub order: u32,
-    
+
     /// 是否自动执行
     pub automated: bool,
+
+    /// 自动执行的修复工作流
+    #[serde(default)]
+    pub remediation: Option<RemediationAction>,
+}
+
+/// 修复动作，执行已注册的工作流
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemediationAction {
+    /// 工作流名称，如 `switch_provider`、`scale_up`
+    pub workflow: String,
+
+    /// 工作流输入，事件信息会以 `incident` 字段合并进去
+    #[serde(default)]
+    pub input: Value,
 }
 
 impl IncidentManager {
@@ -106,13 +330,24 @@ impl IncidentManager {
         Self {
             incidents: HashMap::new(),
             response_plans: HashMap::new(),
+            workflows: HashMap::new(),
         }
     }
-    
+
+    /// 添加响应计划
+    pub fn add_response_plan(&mut self, plan: IncidentResponse) {
+        self.response_plans.insert(plan.id.clone(), plan);
+    }
+
+    /// 注册修复工作流
+    pub fn register_workflow(&mut self, name: impl Into<String>, workflow: Arc<dyn Workflow>) {
+        self.workflows.insert(name.into(), workflow);
+    }
+
     /// 创建事件
     pub async fn create_incident(&mut self, title: String, description: String, severity: IncidentSeverity) -> Result<Uuid> {
         let id = Uuid::new_v4();
-        let incident = Incident {
+        let mut incident = Incident {
             id,
             title,
             description,
@@ -122,30 +357,204 @@ impl IncidentManager {
             updated_at: Utc::now(),
             assignee: None,
             tags: Vec::new(),
+            source: None,
+            dedup_key: None,
+            alert_count: 0,
+            responders: Vec::new(),
+            timeline: Vec::new(),
+            acknowledged_at: None,
+            resolved_at: None,
         };
-        
+        incident.record(TimelineKind::Created, None, format!("Incident opened with severity {:?}", severity));
+
         self.incidents.insert(id, incident);
         Ok(id)
     }
-    
+
+    /// 处理告警：合并到相同去重键的未结束事件，否则创建事件并执行匹配的响应计划
+    pub async fn handle_alert(&mut self, alert: IncidentAlert) -> Result<Uuid> {
+        let existing = self.incidents.values_mut()
+            .find(|incident| incident.is_active() && incident.dedup_key.as_deref() == Some(alert.dedup_key.as_str()));
+        if let Some(incident) = existing {
+            incident.alert_count += 1;
+            incident.record(TimelineKind::AlertFired, None, alert.description);
+            if alert.severity > incident.severity {
+                let message = format!("Severity raised from {:?} to {:?}", incident.severity, alert.severity);
+                incident.severity = alert.severity;
+                incident.record(TimelineKind::SeverityChanged, None, message);
+            }
+            return Ok(incident.id);
+        }
+
+        let id = self.create_incident(alert.title, alert.description.clone(), alert.severity).await?;
+        let incident = self.incident_mut(id)?;
+        incident.source = Some(alert.source);
+        incident.dedup_key = Some(alert.dedup_key);
+        incident.tags = alert.tags;
+        incident.alert_count = 1;
+        incident.record(TimelineKind::AlertFired, None, alert.description);
+
+        self.run_response_plans(id).await?;
+        Ok(id)
+    }
+
+    async fn run_response_plans(&mut self, id: Uuid) -> Result<()> {
+        let incident = self.incident_mut(id)?.clone();
+        let mut plans: Vec<IncidentResponse> = self.response_plans.values()
+            .filter(|plan| plan.applies_to(&incident))
+            .cloned()
+            .collect();
+        plans.sort_by(|a, b| a.id.cmp(&b.id));
+
+        for plan in plans {
+            for responder in &plan.notification_list {
+                self.assign_responder(id, responder).await?;
+            }
+
+            let mut steps = plan.response_steps.clone();
+            steps.sort_by_key(|step| step.order);
+            for step in steps {
+                match (&step.remediation, step.automated) {
+                    (Some(action), true) => {
+                        // 失败已记录在时间线上，不中断后续步骤
+                        if let Err(e) = self.trigger_remediation(id, action).await {
+                            tracing::warn!("事件 {} 的修复步骤 {} 失败: {}", id, step.name, e);
+                        }
+                    }
+                    _ => self.incident_mut(id)?.record(
+                        TimelineKind::StepPending,
+                        None,
+                        format!("{} ({}): {}", step.name, plan.id, step.description),
+                    ),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 执行修复工作流，结果记录在事件时间线上
+    pub async fn trigger_remediation(&mut self, id: Uuid, action: &RemediationAction) -> Result<Value> {
+        let incident = self.incident_mut(id)?;
+        let summary = serde_json::json!({
+            "id": incident.id,
+            "title": incident.title,
+            "severity": incident.severity,
+            "source": incident.source,
+            "tags": incident.tags,
+        });
+        incident.record(TimelineKind::RemediationStarted, None, format!("Running workflow {}", action.workflow));
+
+        let Some(workflow) = self.workflows.get(&action.workflow).cloned() else {
+            let message = format!("Workflow {} is not registered", action.workflow);
+            self.incident_mut(id)?.record(TimelineKind::RemediationFailed, None, message.clone());
+            return Err(EnterpriseError::IncidentManagement(message));
+        };
+
+        let input = match &action.input {
+            Value::Object(fields) => {
+                let mut input = fields.clone();
+                input.insert("incident".to_string(), summary);
+                Value::Object(input)
+            }
+            Value::Null => serde_json::json!({ "incident": summary }),
+            other => serde_json::json!({ "incident": summary, "input": other }),
+        };
+
+        match workflow.execute(input, &RuntimeContext::new()).await {
+            Ok(output) => {
+                self.incident_mut(id)?.record(
+                    TimelineKind::RemediationSucceeded,
+                    None,
+                    format!("Workflow {} completed: {}", action.workflow, output),
+                );
+                Ok(output)
+            }
+            Err(e) => {
+                let message = format!("Workflow {} failed: {}", action.workflow, e);
+                self.incident_mut(id)?.record(TimelineKind::RemediationFailed, None, message.clone());
+                Err(EnterpriseError::IncidentManagement(message))
+            }
+        }
+    }
+
+    /// 分配响应人，第一个响应人同时成为负责人
+    pub async fn assign_responder(&mut self, id: Uuid, responder: &str) -> Result<()> {
+        let incident = self.incident_mut(id)?;
+        if incident.responders.iter().any(|r| r == responder) {
+            return Ok(());
+        }
+        incident.responders.push(responder.to_string());
+        if incident.assignee.is_none() {
+            incident.assignee = Some(responder.to_string());
+        }
+        incident.record(TimelineKind::ResponderAssigned, None, format!("{} assigned", responder));
+        Ok(())
+    }
+
+    /// 响应人确认事件
+    pub async fn acknowledge(&mut self, id: Uuid, responder: &str) -> Result<()> {
+        self.assign_responder(id, responder).await?;
+        let incident = self.incident_mut(id)?;
+        incident.assignee = Some(responder.to_string());
+        self.set_status(id, IncidentStatus::Acknowledged, Some(responder))
+    }
+
+    /// 添加备注
+    pub async fn add_note(&mut self, id: Uuid, actor: &str, note: &str) -> Result<()> {
+        self.incident_mut(id)?.record(TimelineKind::Note, Some(actor), note);
+        Ok(())
+    }
+
     /// 更新事件状态
     pub async fn update_incident_status(&mut self, id: Uuid, status: IncidentStatus) -> Result<()> {
-        if let Some(incident) = self.incidents.get_mut(&id) {
-            incident.status = status;
-            incident.updated_at = Utc::now();
+        self.set_status(id, status, None)
+    }
+
+    fn set_status(&mut self, id: Uuid, status: IncidentStatus, actor: Option<&str>) -> Result<()> {
+        let incident = self.incident_mut(id)?;
+        if incident.status == status {
+            return Ok(());
         }
+
+        let message = format!("Status changed from {:?} to {:?}", incident.status, status);
+        incident.status = status;
+        match status {
+            IncidentStatus::Acknowledged => {
+                incident.acknowledged_at.get_or_insert_with(Utc::now);
+            }
+            IncidentStatus::Resolved | IncidentStatus::Closed => {
+                incident.resolved_at.get_or_insert_with(Utc::now);
+            }
+            IncidentStatus::Open | IncidentStatus::InProgress => incident.resolved_at = None,
+        }
+        incident.record(TimelineKind::StatusChanged, actor, message);
         Ok(())
     }
-    
+
     /// 获取事件
     pub async fn get_incident(&self, id: Uuid) -> Result<Option<Incident>> {
         Ok(self.incidents.get(&id).cloned())
     }
-    
+
     /// 列出事件
     pub async fn list_incidents(&self) -> Result<Vec<Incident>> {
         Ok(self.incidents.values().cloned().collect())
     }
+
+    /// 未结束的事件，按严重程度降序、创建时间升序
+    pub async fn active_incidents(&self) -> Result<Vec<Incident>> {
+        let mut incidents: Vec<Incident> = self.incidents.values()
+            .filter(|incident| incident.is_active())
+            .cloned()
+            .collect();
+        incidents.sort_by(|a, b| b.severity.cmp(&a.severity).then(a.created_at.cmp(&b.created_at)));
+        Ok(incidents)
+    }
+
+    fn incident_mut(&mut self, id: Uuid) -> Result<&mut Incident> {
+        self.incidents.get_mut(&id)
+            .ok_or_else(|| EnterpriseError::IncidentManagement(format!("事件不存在: {}", id)))
+    }
 }
 
 impl Default for IncidentManager {
@@ -153,3 +562,153 @@ impl Default for IncidentManager {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use lumosai_core::workflow::WorkflowStatus;
+    use std::sync::Mutex;
+
+    /// 记录输入的修复工作流
+    struct RecordingWorkflow {
+        id: String,
+        inputs: Mutex<Vec<Value>>,
+        fail: bool,
+    }
+
+    impl RecordingWorkflow {
+        fn new(id: &str, fail: bool) -> Arc<Self> {
+            Arc::new(Self { id: id.to_string(), inputs: Mutex::new(Vec::new()), fail })
+        }
+    }
+
+    #[async_trait]
+    impl Workflow for RecordingWorkflow {
+        fn id(&self) -> &str {
+            &self.id
+        }
+
+        fn description(&self) -> Option<&str> {
+            None
+        }
+
+        async fn execute(&self, input: Value, _context: &RuntimeContext) -> lumosai_core::Result<Value> {
+            self.inputs.lock().unwrap().push(input);
+            if self.fail {
+                return Err(lumosai_core::Error::Internal("provider unavailable".to_string()));
+            }
+            Ok(serde_json::json!({ "switched_to": "anthropic" }))
+        }
+
+        async fn execute_stream(
+            &self,
+            _input: Value,
+            _context: &RuntimeContext,
+        ) -> lumosai_core::Result<Box<dyn futures::Stream<Item = lumosai_core::Result<Value>> + Send + Unpin>> {
+            unimplemented!()
+        }
+
+        async fn suspend(&self, _run_id: &str) -> lumosai_core::Result<()> {
+            Ok(())
+        }
+
+        async fn resume(&self, _run_id: &str, _input: Option<Value>) -> lumosai_core::Result<Value> {
+            Ok(Value::Null)
+        }
+
+        async fn get_status(&self, _run_id: &str) -> lumosai_core::Result<WorkflowStatus> {
+            Ok(WorkflowStatus::NotFound)
+        }
+    }
+
+    fn anomaly(severity: AnomalySeverity) -> AnomalyAlert {
+        AnomalyAlert {
+            id: Uuid::new_v4().to_string(),
+            metric_name: "llm.error_rate".to_string(),
+            anomaly_value: 0.4,
+            expected_value: 0.01,
+            anomaly_score: 0.95,
+            detected_at: Utc::now(),
+            severity,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_alert_to_incident_with_remediation() {
+        let switch_provider = RecordingWorkflow::new("switch_provider", false);
+        let scale_up = RecordingWorkflow::new("scale_up", true);
+
+        let mut manager = IncidentManager::new();
+        manager.register_workflow("switch_provider", switch_provider.clone());
+        manager.register_workflow("scale_up", scale_up.clone());
+        manager.add_response_plan(IncidentResponse {
+            id: "llm-errors".to_string(),
+            incident_type: "llm.error_rate".to_string(),
+            response_steps: vec![
+                ResponseStep {
+                    name: "Check provider status page".to_string(),
+                    description: "Confirm the outage upstream".to_string(),
+                    order: 3,
+                    automated: false,
+                    remediation: None,
+                },
+                ResponseStep {
+                    name: "Switch provider".to_string(),
+                    description: "Fail over to the backup provider".to_string(),
+                    order: 1,
+                    automated: true,
+                    remediation: Some(RemediationAction {
+                        workflow: "switch_provider".to_string(),
+                        input: serde_json::json!({ "to": "anthropic" }),
+                    }),
+                },
+                ResponseStep {
+                    name: "Scale up".to_string(),
+                    description: "Add workers".to_string(),
+                    order: 2,
+                    automated: true,
+                    remediation: Some(RemediationAction { workflow: "scale_up".to_string(), input: Value::Null }),
+                },
+            ],
+            notification_list: vec!["oncall-ml".to_string()],
+            min_severity: Some(IncidentSeverity::Medium),
+        });
+
+        let id = manager.handle_alert(IncidentAlert::from_anomaly(&anomaly(AnomalySeverity::High))).await.unwrap();
+        let incident = manager.get_incident(id).await.unwrap().unwrap();
+        assert_eq!(incident.assignee.as_deref(), Some("oncall-ml"));
+        assert_eq!(incident.alert_count, 1);
+
+        let inputs = switch_provider.inputs.lock().unwrap().clone();
+        assert_eq!(inputs.len(), 1);
+        assert_eq!(inputs[0]["to"], "anthropic");
+        assert_eq!(inputs[0]["incident"]["id"], id.to_string());
+
+        let kinds: Vec<TimelineKind> = incident.timeline.iter().map(|entry| entry.kind).collect();
+        assert!(kinds.contains(&TimelineKind::RemediationSucceeded));
+        assert!(kinds.contains(&TimelineKind::RemediationFailed));
+        assert_eq!(kinds.last(), Some(&TimelineKind::StepPending));
+
+        // 重复告警合并到同一事件并升级严重程度
+        let again = manager.handle_alert(IncidentAlert::from_anomaly(&anomaly(AnomalySeverity::Critical))).await.unwrap();
+        assert_eq!(again, id);
+        let incident = manager.get_incident(id).await.unwrap().unwrap();
+        assert_eq!(incident.alert_count, 2);
+        assert_eq!(incident.severity, IncidentSeverity::Critical);
+        assert_eq!(switch_provider.inputs.lock().unwrap().len(), 1);
+
+        manager.acknowledge(id, "alice").await.unwrap();
+        manager.update_incident_status(id, IncidentStatus::Resolved).await.unwrap();
+        let incident = manager.get_incident(id).await.unwrap().unwrap();
+        assert_eq!(incident.assignee.as_deref(), Some("alice"));
+        assert!(incident.acknowledged_at.is_some() && incident.resolved_at.is_some());
+        assert!(manager.active_incidents().await.unwrap().is_empty());
+
+        // 已解决的事件不再合并新告警
+        let new_id = manager.handle_alert(IncidentAlert::from_anomaly(&anomaly(AnomalySeverity::Low))).await.unwrap();
+        assert_ne!(new_id, id);
+        // 低于计划的最低严重程度，不执行修复
+        assert_eq!(switch_provider.inputs.lock().unwrap().len(), 1);
+    }
+}
@@ -21,13 +21,14 @@ pub use monitoring::{EnterpriseMonitoring, EnterpriseMetric, ComplianceMonitor};
 pub use security::{SecurityFramework, SecurityPolicy, ThreatDetectionEngine};
 pub use compliance::{ComplianceManager, ComplianceStandard, AuditManager};
 pub use multi_tenant::{MultiTenantArchitecture, TenantManager, TenantContext};
-pub use cost_tracking::{CostTracker, CostMetrics, BillingManager};
+pub use cost_tracking::{CostTracker, CostMetrics, BillingManager, PricingTable, ModelPrice, BudgetAlert};
 pub use sla_monitoring::{SLAMonitor, SLAMetrics, ServiceLevelAgreement};
 pub use incident_management::{IncidentManager, Incident, IncidentResponse};
 pub use capacity_planning::{CapacityPlanner, CapacityMetrics, ScalingRecommendation};
-pub use anomaly_detection::{AnomalyDetector, AnomalyAlert, MLAnomalyEngine};
+pub use anomaly_detection::{AnomalyDetector, AnomalyAlert, AnomalySeverity, BaselineConfig, MLAnomalyEngine};
 pub use alerting::{AlertingSystem, AlertRule, NotificationChannel};
 pub use reporting::{ReportGenerator, ComplianceReport, PerformanceReport};
+pub use reporting::conversation_analytics::{ConversationAnalyticsJob, ConversationAnalyticsConfig, ConversationAnalyticsReport};
 pub use config::EnterpriseConfig;
 pub use error::{EnterpriseError, Result};
 
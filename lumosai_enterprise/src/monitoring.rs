@@ -1,7 +1,7 @@
 //! 企业级监控和可观测性扩展
 
 use async_trait::async_trait;
-use prometheus::{Counter, Histogram, Gauge, Registry, Encoder, TextEncoder};
+use prometheus::{Counter, Histogram, HistogramOpts, Gauge, Registry, Encoder, TextEncoder};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -529,8 +529,9 @@ impl EnterpriseMonitoring {
         let encoder = TextEncoder::new();
         let metric_families = self.metrics_registry.gather();
         let mut buffer = Vec::new();
-        encoder.encode(&metric_families, &mut buffer)?;
-        Ok(String::from_utf8(buffer)?)
+        encoder.encode(&metric_families, &mut buffer)
+            .map_err(|e| EnterpriseError::Monitoring(e.to_string()))?;
+        String::from_utf8(buffer).map_err(|e| EnterpriseError::Monitoring(e.to_string()))
     }
     
     /// 启动指标收集
@@ -564,7 +565,10 @@ impl PerformanceMonitor {
         Ok(Self {
             thresholds: PerformanceThresholds::default(),
             metrics: PerformanceMetrics {
-                response_time_histogram: Arc::new(Histogram::new(vec![0.1, 0.5, 1.0, 2.5, 5.0, 10.0]).unwrap()),
+                response_time_histogram: Arc::new(Histogram::with_opts(
+                    HistogramOpts::new("response_time", "Response time histogram")
+                        .buckets(vec![0.1, 0.5, 1.0, 2.5, 5.0, 10.0])
+                ).unwrap()),
                 throughput_counter: Arc::new(Counter::new("throughput", "Throughput counter").unwrap()),
                 error_rate_counter: Arc::new(Counter::new("errors", "Error rate counter").unwrap()),
                 cpu_usage_gauge: Arc::new(Gauge::new("cpu_usage", "CPU usage gauge").unwrap()),
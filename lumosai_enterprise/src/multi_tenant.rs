@@ -56,7 +56,7 @@ pub struct Tenant {
 }
 
 /// 租户类型
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TenantType {
     /// 个人
     Individual,
@@ -71,7 +71,7 @@ pub enum TenantType {
 }
 
 /// 租户状态
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TenantStatus {
     /// 活跃
     Active,
@@ -1275,6 +1275,9 @@ impl AutoScaler {
         Ok(*self.current_instances.get(tenant_id).unwrap_or(&1))
     }
 }
+
+#[cfg(test)]
+mod tests {
     use super::*;
     
     #[tokio::test]
@@ -1,14 +1,23 @@
 //! 报告生成模块
 //! 
-//! 提供企业级报告生成功能
+//! 提供企业级报告生成功能，合规报告可导出为HTML和PDF，对话分析见[`conversation_analytics`]
+
+pub mod conversation_analytics;
 
 use async_trait::async_trait;
 use std::collections::HashMap;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::compliance::{ComplianceCheckResult, ComplianceStatus, TimeRange};
 use crate::error::{EnterpriseError, Result};
 
+/// PDF每页行数
+const PDF_LINES_PER_PAGE: usize = 60;
+
+/// PDF每行最大字符数
+const PDF_LINE_WIDTH: usize = 95;
+
 /// 报告生成器
 pub struct ReportGenerator {
     /// 报告模板
@@ -55,6 +64,53 @@ pub struct ComplianceReport {
     
     /// 合规分数
     pub compliance_score: f64,
+
+    /// 报告期间开始
+    #[serde(default)]
+    pub period_start: Option<DateTime<Utc>>,
+
+    /// 报告期间结束
+    #[serde(default)]
+    pub period_end: Option<DateTime<Utc>>,
+
+    /// 各标准的检查结果
+    #[serde(default)]
+    pub sections: Vec<ComplianceSection>,
+}
+
+/// 合规报告中单个标准的检查结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComplianceSection {
+    /// 合规标准
+    pub standard: String,
+
+    /// 总体状态
+    pub status: String,
+
+    /// 控制项结果
+    pub controls: Vec<ControlSummary>,
+
+    /// 建议
+    pub recommendations: Vec<String>,
+}
+
+/// 合规报告中单个控制项的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ControlSummary {
+    /// 控制项ID
+    pub id: String,
+
+    /// 状态
+    pub status: String,
+
+    /// 检查详情
+    pub details: String,
+
+    /// 证据
+    pub evidence: Vec<String>,
+
+    /// 差距
+    pub gaps: Vec<String>,
 }
 
 /// 性能报告
@@ -88,8 +144,56 @@ impl ReportGenerator {
             generated_at: Utc::now(),
             content: "Compliance report content".to_string(),
             compliance_score: 95.0,
+            period_start: None,
+            period_end: None,
+            sections: Vec::new(),
         })
     }
+
+    /// 根据合规检查结果生成报告，部分合规的控制项按一半计分
+    pub fn build_compliance_report(&self, period: &TimeRange, results: &[ComplianceCheckResult]) -> ComplianceReport {
+        let mut total = 0usize;
+        let mut points = 0.0;
+        let sections: Vec<ComplianceSection> = results.iter()
+            .map(|result| {
+                let controls = result.requirement_results.iter()
+                    .map(|requirement| {
+                        total += 1;
+                        points += match requirement.status {
+                            ComplianceStatus::Compliant => 1.0,
+                            ComplianceStatus::PartiallyCompliant => 0.5,
+                            _ => 0.0,
+                        };
+                        ControlSummary {
+                            id: requirement.requirement_id.clone(),
+                            status: status_label(requirement.status).to_string(),
+                            details: requirement.details.clone(),
+                            evidence: requirement.evidence.iter().map(|e| format!("[{:?}] {}", e.evidence_type, e.content)).collect(),
+                            gaps: requirement.gaps.iter().map(|gap| gap.description.clone()).collect(),
+                        }
+                    })
+                    .collect();
+                ComplianceSection {
+                    standard: format!("{:?}", result.standard),
+                    status: status_label(result.overall_status).to_string(),
+                    controls,
+                    recommendations: result.recommendations.iter().map(|r| r.content.clone()).collect(),
+                }
+            })
+            .collect();
+
+        let mut report = ComplianceReport {
+            id: uuid::Uuid::new_v4().to_string(),
+            generated_at: Utc::now(),
+            content: String::new(),
+            compliance_score: if total == 0 { 0.0 } else { points / total as f64 * 100.0 },
+            period_start: Some(period.start),
+            period_end: Some(period.end),
+            sections,
+        };
+        report.content = report.to_html();
+        report
+    }
     
     /// 生成性能报告
     pub async fn generate_performance_report(&self) -> Result<PerformanceReport> {
@@ -107,6 +211,170 @@ impl ReportGenerator {
     }
 }
 
+fn status_label(status: ComplianceStatus) -> &'static str {
+    match status {
+        ComplianceStatus::Compliant => "Compliant",
+        ComplianceStatus::NonCompliant => "Non-compliant",
+        ComplianceStatus::PartiallyCompliant => "Partially compliant",
+        ComplianceStatus::Unknown => "Unknown",
+    }
+}
+
+impl ComplianceReport {
+    fn period_label(&self) -> String {
+        match (self.period_start, self.period_end) {
+            (Some(start), Some(end)) => format!("{} - {}", start.format("%Y-%m-%d"), end.format("%Y-%m-%d")),
+            _ => "-".to_string(),
+        }
+    }
+
+    /// 导出为HTML
+    pub fn to_html(&self) -> String {
+        let mut html = String::new();
+        html.push_str("<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>Compliance Report</title>");
+        html.push_str("<style>body{font-family:sans-serif}table{border-collapse:collapse}td,th{border:1px solid #ccc;padding:4px;vertical-align:top}</style>");
+        html.push_str("</head><body>\n<h1>Compliance Report</h1>\n");
+        html.push_str(&format!(
+            "<p>Generated: {}<br>Period: {}<br>Score: {:.1}%</p>\n",
+            self.generated_at.to_rfc3339(),
+            self.period_label(),
+            self.compliance_score,
+        ));
+        for section in &self.sections {
+            html.push_str(&format!("<h2>{} &mdash; {}</h2>\n", escape_html(&section.standard), escape_html(&section.status)));
+            html.push_str("<table><tr><th>Control</th><th>Status</th><th>Details</th><th>Evidence</th><th>Gaps</th></tr>\n");
+            for control in &section.controls {
+                html.push_str(&format!(
+                    "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                    escape_html(&control.id),
+                    escape_html(&control.status),
+                    escape_html(&control.details),
+                    html_list(&control.evidence),
+                    html_list(&control.gaps),
+                ));
+            }
+            html.push_str("</table>\n");
+            if !section.recommendations.is_empty() {
+                html.push_str("<h3>Recommendations</h3>\n");
+                html.push_str(&html_list(&section.recommendations));
+                html.push('\n');
+            }
+        }
+        html.push_str("</body></html>\n");
+        html
+    }
+
+    /// 导出为PDF，使用内置Helvetica字体，非ASCII字符以 `?` 代替
+    pub fn to_pdf(&self) -> Vec<u8> {
+        let mut lines = vec![
+            "Compliance Report".to_string(),
+            String::new(),
+            format!("Generated: {}", self.generated_at.to_rfc3339()),
+            format!("Period: {}", self.period_label()),
+            format!("Score: {:.1}%", self.compliance_score),
+        ];
+        for section in &self.sections {
+            lines.push(String::new());
+            lines.push(format!("{} - {}", section.standard, section.status));
+            for control in &section.controls {
+                lines.push(format!("  {} [{}] {}", control.id, control.status, control.details));
+                lines.extend(control.evidence.iter().map(|e| format!("      evidence: {}", e)));
+                lines.extend(control.gaps.iter().map(|g| format!("      gap: {}", g)));
+            }
+            if !section.recommendations.is_empty() {
+                lines.push("  Recommendations:".to_string());
+                lines.extend(section.recommendations.iter().map(|r| format!("    - {}", r)));
+            }
+        }
+        render_pdf(&lines)
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn html_list(items: &[String]) -> String {
+    if items.is_empty() {
+        return String::new();
+    }
+    let items: String = items.iter().map(|item| format!("<li>{}</li>", escape_html(item))).collect();
+    format!("<ul>{}</ul>", items)
+}
+
+fn pdf_text(line: &str) -> String {
+    let mut text = String::with_capacity(line.len());
+    for c in line.chars() {
+        match c {
+            '\\' | '(' | ')' => {
+                text.push('\\');
+                text.push(c);
+            }
+            ' '..='~' => text.push(c),
+            _ => text.push('?'),
+        }
+    }
+    text
+}
+
+/// 生成单字体的纯文本PDF
+fn render_pdf(lines: &[String]) -> Vec<u8> {
+    let wrapped: Vec<String> = lines.iter()
+        .flat_map(|line| {
+            let chars: Vec<char> = line.chars().collect();
+            if chars.is_empty() {
+                return vec![String::new()];
+            }
+            chars.chunks(PDF_LINE_WIDTH).map(|chunk| chunk.iter().collect()).collect()
+        })
+        .collect();
+    let pages: Vec<&[String]> = if wrapped.is_empty() {
+        vec![&[]]
+    } else {
+        wrapped.chunks(PDF_LINES_PER_PAGE).collect()
+    };
+
+    // 对象1为目录，2为页面树，3为字体，之后每页依次为页面对象和内容流
+    let page_ids: Vec<usize> = (0..pages.len()).map(|i| 4 + i * 2).collect();
+    let mut objects = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            page_ids.iter().map(|id| format!("{} 0 R", id)).collect::<Vec<_>>().join(" "),
+            pages.len(),
+        ),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_string(),
+    ];
+    for (page, id) in pages.iter().zip(&page_ids) {
+        let mut stream = String::from("BT /F1 10 Tf 12 TL 50 800 Td\n");
+        for line in page.iter() {
+            stream.push_str(&format!("({}) Tj T*\n", pdf_text(line)));
+        }
+        stream.push_str("ET");
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 595 842] /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+            id + 1,
+        ));
+        objects.push(format!("<< /Length {} >>\nstream\n{}\nendstream", stream.len(), stream));
+    }
+
+    let mut pdf = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend_from_slice(format!("{} 0 obj\n{}\nendobj\n", i + 1, object).as_bytes());
+    }
+    let xref = pdf.len();
+    pdf.extend_from_slice(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes());
+    for offset in offsets {
+        pdf.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+    }
+    pdf.extend_from_slice(
+        format!("trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n", objects.len() + 1, xref).as_bytes(),
+    );
+    pdf
+}
+
 impl Default for ReportGenerator {
     fn default() -> Self {
         Self::new()
@@ -0,0 +1,570 @@
+//! 对话分析
+//!
+//! [`ConversationAnalyticsJob`]对存储的对话做主题聚类和意图分析：用嵌入模型向量化每个对话的
+//! 用户消息，以球面k-means聚类，再让LLM根据每个簇中最具代表性的消息给出主题和意图标签，
+//! 最后按周统计各主题和意图的对话数及趋势。报告可序列化为JSON，供UI的分析页面展示。
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use lumosai_core::llm::{LlmOptions, LlmProvider, Message, Role};
+use lumosai_core::memory::{GetMessagesParams, MemoryThreadStorage, MessageFilter};
+
+use crate::compliance::TimeRange;
+use crate::error::{EnterpriseError, Result};
+
+/// 无法解析标签时使用的意图
+const UNKNOWN_INTENT: &str = "unknown";
+
+/// 对话分析配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversationAnalyticsConfig {
+    /// 最多聚成的主题数
+    pub max_topics: usize,
+    /// k-means最大迭代次数
+    pub max_iterations: usize,
+    /// 每个主题交给LLM标注并写入报告的示例消息数
+    pub samples_per_topic: usize,
+    /// 每个对话参与嵌入的最大字符数
+    pub max_chars: usize,
+}
+
+impl Default for ConversationAnalyticsConfig {
+    fn default() -> Self {
+        Self {
+            max_topics: 8,
+            max_iterations: 20,
+            samples_per_topic: 5,
+            max_chars: 2000,
+        }
+    }
+}
+
+/// 待分析的对话
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Conversation {
+    /// 对话ID
+    pub id: String,
+    /// 开始时间，决定对话计入哪一周
+    pub started_at: DateTime<Utc>,
+    /// 对话消息
+    pub messages: Vec<Message>,
+}
+
+impl Conversation {
+    /// 用户消息拼接成的文本
+    fn user_text(&self, max_chars: usize) -> String {
+        let text = self.messages.iter()
+            .filter(|message| message.role == Role::User)
+            .map(|message| message.content.trim())
+            .filter(|content| !content.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+        text.chars().take(max_chars).collect()
+    }
+}
+
+/// 对话分析报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversationAnalyticsReport {
+    /// 报告ID
+    pub id: String,
+    /// 生成时间
+    pub generated_at: DateTime<Utc>,
+    /// 报告期间开始
+    pub period_start: DateTime<Utc>,
+    /// 报告期间结束
+    pub period_end: DateTime<Utc>,
+    /// 参与分析的对话数
+    pub total_conversations: usize,
+    /// 主题，按对话数降序
+    pub topics: Vec<TopicSummary>,
+    /// 意图，按对话数降序
+    pub intents: Vec<IntentSummary>,
+    /// 每周统计，按时间升序
+    pub weeks: Vec<WeeklyBreakdown>,
+}
+
+/// 单个主题的统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopicSummary {
+    /// 主题标签
+    pub topic: String,
+    /// 该主题下用户的主要意图
+    pub intent: String,
+    /// 对话数
+    pub conversations: usize,
+    /// 占全部对话的百分比
+    pub percentage: f64,
+    /// 最后一周相对前一周的变化："up"、"down"或"stable"
+    pub trend: String,
+    /// 最接近簇中心的用户消息
+    pub examples: Vec<String>,
+    /// 属于该主题的对话ID
+    pub conversation_ids: Vec<String>,
+}
+
+/// 单个意图的统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntentSummary {
+    /// 意图标签
+    pub intent: String,
+    /// 对话数
+    pub conversations: usize,
+    /// 占全部对话的百分比
+    pub percentage: f64,
+    /// 最后一周相对前一周的变化："up"、"down"或"stable"
+    pub trend: String,
+}
+
+/// 一周内各主题和意图的对话数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeeklyBreakdown {
+    /// 该周的周一
+    pub week_start: NaiveDate,
+    /// 该周的对话数
+    pub conversations: usize,
+    /// 主题到对话数
+    pub topics: BTreeMap<String, usize>,
+    /// 意图到对话数
+    pub intents: BTreeMap<String, usize>,
+}
+
+/// LLM返回的簇标签
+#[derive(Debug, Deserialize)]
+struct ClusterLabel {
+    topic: String,
+    intent: String,
+}
+
+/// 对话主题聚类和意图分析任务
+pub struct ConversationAnalyticsJob {
+    /// 标注主题和意图的模型
+    llm: Arc<dyn LlmProvider>,
+    /// 生成嵌入的模型
+    embedder: Arc<dyn LlmProvider>,
+    config: ConversationAnalyticsConfig,
+}
+
+impl ConversationAnalyticsJob {
+    /// 创建分析任务，`llm`同时用于嵌入和标注
+    pub fn new(llm: Arc<dyn LlmProvider>) -> Self {
+        Self {
+            embedder: llm.clone(),
+            llm,
+            config: ConversationAnalyticsConfig::default(),
+        }
+    }
+
+    /// 使用单独的嵌入模型
+    pub fn with_embedder(mut self, embedder: Arc<dyn LlmProvider>) -> Self {
+        self.embedder = embedder;
+        self
+    }
+
+    /// 设置分析配置
+    pub fn with_config(mut self, config: ConversationAnalyticsConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// 截至`now`的最近`weeks`个完整周，从周一零点开始
+    pub fn weekly_period(now: DateTime<Utc>, weeks: u32) -> TimeRange {
+        let end = week_start(now.date_naive()).and_hms_opt(0, 0, 0).unwrap().and_utc();
+        TimeRange {
+            start: end - Duration::weeks(weeks.max(1) as i64),
+            end,
+        }
+    }
+
+    /// 从线程存储中读取指定代理在`period`内开始的对话
+    pub async fn load_conversations(
+        storage: &dyn MemoryThreadStorage,
+        agent_ids: &[String],
+        period: &TimeRange,
+    ) -> Result<Vec<Conversation>> {
+        let params = GetMessagesParams {
+            limit: None,
+            filter: Some(MessageFilter {
+                role: Some(Role::User.to_string()),
+                date_range: None,
+                keywords: None,
+                metadata: None,
+            }),
+            ..GetMessagesParams::default()
+        };
+
+        let mut conversations = Vec::new();
+        for agent_id in agent_ids {
+            let threads = storage.list_threads_by_agent(agent_id).await
+                .map_err(|e| EnterpriseError::Reporting(format!("读取代理 {} 的对话失败: {}", agent_id, e)))?;
+            for thread in threads {
+                if thread.created_at < period.start || thread.created_at >= period.end {
+                    continue;
+                }
+                let messages = storage.get_messages(&thread.id, &params).await
+                    .map_err(|e| EnterpriseError::Reporting(format!("读取对话 {} 的消息失败: {}", thread.id, e)))?;
+                conversations.push(Conversation {
+                    id: thread.id,
+                    started_at: thread.created_at,
+                    messages,
+                });
+            }
+        }
+        Ok(conversations)
+    }
+
+    /// 分析`period`内开始的对话
+    pub async fn run(&self, period: &TimeRange, conversations: &[Conversation]) -> Result<ConversationAnalyticsReport> {
+        let mut texts = Vec::new();
+        let mut embeddings = Vec::new();
+        for conversation in conversations {
+            if conversation.started_at < period.start || conversation.started_at >= period.end {
+                continue;
+            }
+            let text = conversation.user_text(self.config.max_chars);
+            if text.is_empty() {
+                continue;
+            }
+            let embedding = self.embedder.get_embedding(&text).await
+                .map_err(|e| EnterpriseError::Reporting(format!("对话 {} 嵌入失败: {}", conversation.id, e)))?;
+            embeddings.push(normalize(embedding));
+            texts.push((conversation, text));
+        }
+
+        let clusters = kmeans(&embeddings, self.config.max_topics, self.config.max_iterations);
+        let mut assignments: Vec<(String, String)> = vec![Default::default(); texts.len()];
+        let mut topics = Vec::with_capacity(clusters.len());
+        for (number, cluster) in clusters.iter().enumerate() {
+            // 按与簇中心的相似度排序，最具代表性的消息在前
+            let mut members = cluster.members.clone();
+            members.sort_by(|a, b| dot(&embeddings[*b], &cluster.centroid).total_cmp(&dot(&embeddings[*a], &cluster.centroid)));
+            let examples: Vec<String> = members.iter()
+                .take(self.config.samples_per_topic.max(1))
+                .map(|member| texts[*member].1.clone())
+                .collect();
+
+            let label = self.label(&examples).await.unwrap_or_else(|| ClusterLabel {
+                topic: format!("Topic {}", number + 1),
+                intent: UNKNOWN_INTENT.to_string(),
+            });
+            for member in &members {
+                assignments[*member] = (label.topic.clone(), label.intent.clone());
+            }
+            topics.push(TopicSummary {
+                topic: label.topic,
+                intent: label.intent,
+                conversations: members.len(),
+                percentage: percentage(members.len(), texts.len()),
+                trend: String::new(),
+                examples,
+                conversation_ids: members.iter().map(|member| texts[*member].0.id.clone()).collect(),
+            });
+        }
+
+        // 每周统计
+        let mut weeks: BTreeMap<NaiveDate, WeeklyBreakdown> = BTreeMap::new();
+        let mut week = week_start(period.start.date_naive());
+        while week.and_hms_opt(0, 0, 0).unwrap().and_utc() < period.end {
+            weeks.insert(week, WeeklyBreakdown {
+                week_start: week,
+                conversations: 0,
+                topics: BTreeMap::new(),
+                intents: BTreeMap::new(),
+            });
+            week += Duration::weeks(1);
+        }
+        for ((conversation, _), (topic, intent)) in texts.iter().zip(&assignments) {
+            let week = weeks.entry(week_start(conversation.started_at.date_naive())).or_insert_with_key(|week| WeeklyBreakdown {
+                week_start: *week,
+                conversations: 0,
+                topics: BTreeMap::new(),
+                intents: BTreeMap::new(),
+            });
+            week.conversations += 1;
+            *week.topics.entry(topic.clone()).or_default() += 1;
+            *week.intents.entry(intent.clone()).or_default() += 1;
+        }
+        let weeks: Vec<WeeklyBreakdown> = weeks.into_values().collect();
+
+        for topic in topics.iter_mut() {
+            topic.trend = trend(&weeks, |week| week.topics.get(&topic.topic).copied().unwrap_or(0));
+        }
+        topics.sort_by(|a, b| b.conversations.cmp(&a.conversations).then_with(|| a.topic.cmp(&b.topic)));
+
+        let mut intent_counts: HashMap<&str, usize> = HashMap::new();
+        for (_, intent) in &assignments {
+            *intent_counts.entry(intent.as_str()).or_default() += 1;
+        }
+        let mut intents: Vec<IntentSummary> = intent_counts.into_iter()
+            .map(|(intent, count)| IntentSummary {
+                intent: intent.to_string(),
+                conversations: count,
+                percentage: percentage(count, texts.len()),
+                trend: trend(&weeks, |week| week.intents.get(intent).copied().unwrap_or(0)),
+            })
+            .collect();
+        intents.sort_by(|a, b| b.conversations.cmp(&a.conversations).then_with(|| a.intent.cmp(&b.intent)));
+
+        Ok(ConversationAnalyticsReport {
+            id: uuid::Uuid::new_v4().to_string(),
+            generated_at: Utc::now(),
+            period_start: period.start,
+            period_end: period.end,
+            total_conversations: texts.len(),
+            topics,
+            intents,
+            weeks,
+        })
+    }
+
+    /// 让LLM为一个簇命名主题和意图，失败时返回`None`
+    async fn label(&self, examples: &[String]) -> Option<ClusterLabel> {
+        let samples: String = examples.iter().map(|example| format!("- {}\n", example.replace('\n', " "))).collect();
+        let prompt = format!(
+            "The following user messages come from conversations that were grouped together by similarity:\n{}\n\
+             Reply with JSON only, in the form {{\"topic\": \"<short topic name>\", \"intent\": \"<what the users want, in a few words>\"}}.",
+            samples
+        );
+        let response = match self.llm.generate(&prompt, &LlmOptions::default().with_temperature(0.0)).await {
+            Ok(response) => response,
+            Err(e) => {
+                tracing::warn!("主题标注失败: {}", e);
+                return None;
+            }
+        };
+
+        let json = response.find('{').zip(response.rfind('}')).and_then(|(start, end)| response.get(start..=end));
+        match json.map(serde_json::from_str::<ClusterLabel>) {
+            Some(Ok(label)) if !label.topic.trim().is_empty() => Some(ClusterLabel {
+                topic: label.topic.trim().to_string(),
+                intent: match label.intent.trim() {
+                    "" => UNKNOWN_INTENT.to_string(),
+                    intent => intent.to_lowercase(),
+                },
+            }),
+            _ => {
+                tracing::warn!("无法解析主题标签: {}", response);
+                None
+            }
+        }
+    }
+}
+
+/// 聚类得到的簇
+struct Cluster {
+    centroid: Vec<f32>,
+    members: Vec<usize>,
+}
+
+/// 球面k-means，输入向量需已归一化；以最远点法确定初始中心，结果可复现
+fn kmeans(vectors: &[Vec<f32>], k: usize, max_iterations: usize) -> Vec<Cluster> {
+    let k = k.min(vectors.len());
+    if k == 0 {
+        return Vec::new();
+    }
+
+    let mut centroids = vec![vectors[0].clone()];
+    while centroids.len() < k {
+        let farthest = (0..vectors.len())
+            .map(|i| (i, centroids.iter().map(|c| dot(&vectors[i], c)).fold(f32::MIN, f32::max)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _)| i)
+            .unwrap();
+        centroids.push(vectors[farthest].clone());
+    }
+
+    let nearest = |vector: &[f32], centroids: &[Vec<f32>]| {
+        (0..centroids.len())
+            .max_by(|a, b| dot(vector, &centroids[*a]).total_cmp(&dot(vector, &centroids[*b])).then_with(|| b.cmp(a)))
+            .unwrap()
+    };
+    let mut assignment: Vec<usize> = vectors.iter().map(|vector| nearest(vector, &centroids)).collect();
+    for _ in 0..max_iterations {
+        for (cluster, centroid) in centroids.iter_mut().enumerate() {
+            let mut sum = vec![0.0; centroid.len()];
+            for (vector, _) in vectors.iter().zip(&assignment).filter(|(_, assigned)| **assigned == cluster) {
+                for (total, value) in sum.iter_mut().zip(vector) {
+                    *total += value;
+                }
+            }
+            if sum.iter().any(|value| *value != 0.0) {
+                *centroid = normalize(sum);
+            }
+        }
+        let next: Vec<usize> = vectors.iter().map(|vector| nearest(vector, &centroids)).collect();
+        if next == assignment {
+            break;
+        }
+        assignment = next;
+    }
+
+    centroids.into_iter()
+        .enumerate()
+        .map(|(cluster, centroid)| Cluster {
+            centroid,
+            members: (0..vectors.len()).filter(|i| assignment[*i] == cluster).collect(),
+        })
+        .filter(|cluster| !cluster.members.is_empty())
+        .collect()
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn normalize(mut vector: Vec<f32>) -> Vec<f32> {
+    let norm = dot(&vector, &vector).sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|value| *value /= norm);
+    }
+    vector
+}
+
+fn percentage(count: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        count as f64 / total as f64 * 100.0
+    }
+}
+
+/// 所在周的周一
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+/// 比较最后两周的计数
+fn trend(weeks: &[WeeklyBreakdown], count: impl Fn(&WeeklyBreakdown) -> usize) -> String {
+    let trend = match weeks {
+        [.., previous, last] => match count(last).cmp(&count(previous)) {
+            std::cmp::Ordering::Greater => "up",
+            std::cmp::Ordering::Less => "down",
+            std::cmp::Ordering::Equal => "stable",
+        },
+        _ => "stable",
+    };
+    trend.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use futures::stream::BoxStream;
+    use chrono::TimeZone;
+
+    /// 按关键词嵌入和标注的模型
+    struct KeywordProvider;
+
+    #[async_trait]
+    impl LlmProvider for KeywordProvider {
+        fn name(&self) -> &str {
+            "keyword"
+        }
+
+        async fn generate(&self, prompt: &str, _options: &LlmOptions) -> lumosai_core::Result<String> {
+            if prompt.contains("refund") {
+                Ok(r#"Sure: {"topic": "Refunds", "intent": "Get Money Back"}"#.to_string())
+            } else {
+                Ok("I cannot label these".to_string())
+            }
+        }
+
+        async fn generate_with_messages(&self, _messages: &[Message], options: &LlmOptions) -> lumosai_core::Result<String> {
+            self.generate("", options).await
+        }
+
+        async fn generate_stream<'a>(
+            &'a self,
+            _prompt: &'a str,
+            _options: &'a LlmOptions,
+        ) -> lumosai_core::Result<BoxStream<'a, lumosai_core::Result<String>>> {
+            Err(lumosai_core::Error::Unavailable("streaming not supported".to_string()))
+        }
+
+        async fn get_embedding(&self, text: &str) -> lumosai_core::Result<Vec<f32>> {
+            if text.contains("refund") {
+                Ok(vec![1.0, 0.1, 0.0])
+            } else {
+                Ok(vec![0.0, 0.2, 1.0])
+            }
+        }
+    }
+
+    fn conversation(id: &str, day: u32, text: &str) -> Conversation {
+        Conversation {
+            id: id.to_string(),
+            started_at: Utc.with_ymd_and_hms(2024, 3, day, 12, 0, 0).unwrap(),
+            messages: vec![
+                Message::new(Role::User, text.to_string(), None, None),
+                Message::new(Role::Assistant, "ok".to_string(), None, None),
+            ],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_topic_clusters_and_weekly_trends() {
+        // 2024-03-04和2024-03-11都是周一
+        let period = ConversationAnalyticsJob::weekly_period(Utc.with_ymd_and_hms(2024, 3, 20, 9, 0, 0).unwrap(), 2);
+        assert_eq!(period.start, Utc.with_ymd_and_hms(2024, 3, 4, 0, 0, 0).unwrap());
+        assert_eq!(period.end, Utc.with_ymd_and_hms(2024, 3, 18, 0, 0, 0).unwrap());
+
+        let conversations = vec![
+            conversation("a", 5, "I want a refund for my order"),
+            conversation("b", 12, "How do I get a refund?"),
+            conversation("c", 13, "refund please, the item broke"),
+            conversation("d", 6, "I forgot my password"),
+            conversation("e", 7, "Cannot log in to my account"),
+            conversation("f", 14, "password reset link expired"),
+            conversation("outside", 19, "refund"),
+        ];
+
+        let job = ConversationAnalyticsJob::new(Arc::new(KeywordProvider))
+            .with_config(ConversationAnalyticsConfig { max_topics: 2, ..Default::default() });
+        let report = job.run(&period, &conversations).await.unwrap();
+
+        assert_eq!(report.total_conversations, 6);
+        assert_eq!(report.topics.len(), 2);
+        let refunds = report.topics.iter().find(|topic| topic.topic == "Refunds").unwrap();
+        assert_eq!(refunds.intent, "get money back");
+        assert_eq!(refunds.conversations, 3);
+        assert_eq!(refunds.trend, "up");
+        assert!((refunds.percentage - 50.0).abs() < 1e-9);
+
+        // 无法解析的标签退回编号主题
+        let other = report.topics.iter().find(|topic| topic.topic != "Refunds").unwrap();
+        assert!(other.topic.starts_with("Topic "));
+        assert_eq!(other.intent, UNKNOWN_INTENT);
+        assert_eq!(other.trend, "down");
+        let mut ids = other.conversation_ids.clone();
+        ids.sort();
+        assert_eq!(ids, vec!["d", "e", "f"]);
+
+        assert_eq!(report.weeks.len(), 2);
+        assert_eq!(report.weeks[0].week_start, NaiveDate::from_ymd_opt(2024, 3, 4).unwrap());
+        assert_eq!(report.weeks[0].conversations, 3);
+        assert_eq!(report.weeks[1].topics["Refunds"], 2);
+        assert_eq!(report.intents.len(), 2);
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["weeks"][1]["week_start"], "2024-03-11");
+    }
+
+    #[test]
+    fn test_kmeans_separates_directions() {
+        let vectors: Vec<Vec<f32>> = vec![
+            vec![1.0, 0.0], vec![0.9, 0.1], vec![0.0, 1.0], vec![0.1, 0.9], vec![0.95, 0.05],
+        ].into_iter().map(normalize).collect();
+        let clusters = kmeans(&vectors, 2, 10);
+        let mut members: Vec<Vec<usize>> = clusters.into_iter().map(|cluster| cluster.members).collect();
+        members.sort();
+        assert_eq!(members, vec![vec![0, 1, 4], vec![2, 3]]);
+
+        assert!(kmeans(&[], 3, 10).is_empty());
+        assert_eq!(kmeans(&vectors[..1], 3, 10).len(), 1);
+    }
+}
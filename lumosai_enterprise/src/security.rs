@@ -641,7 +641,7 @@ mod tests {
         let framework = SecurityFramework::new(config).await.unwrap();
         
         // 测试基本功能
-        assert!(framework.verify_token("invalid_token").is_err());
+        assert!(framework.verify_token("invalid_token").await.is_err());
     }
     
     #[tokio::test]
@@ -652,7 +652,7 @@ mod tests {
         let token = framework.authenticate("testuser", "password").await.unwrap();
         assert!(!token.is_empty());
         
-        let claims = framework.verify_token(&token).unwrap();
+        let claims = framework.verify_token(&token).await.unwrap();
         assert_eq!(claims.sub, "user123");
     }
 }
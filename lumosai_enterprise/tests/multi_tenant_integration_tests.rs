@@ -125,7 +125,7 @@ async fn test_billing_system() {
     let mut architecture = MultiTenantArchitecture::new().await.unwrap();
     
     // 创建租户
-    let tenant = create_test_tenant("billing_test", TenantType::Professional);
+    let tenant = create_test_tenant("billing_test", TenantType::SmallBusiness);
     assert!(architecture.create_tenant(tenant).await.is_ok());
     
     // 分配一些资源（会自动记录计费）
@@ -7,9 +7,17 @@ pub mod error;
 pub mod types;
 pub mod metrics;
 pub mod evaluator;
+pub mod suite;
+pub mod store;
+pub mod prompts;
+pub mod optimizer;
 
 // 重导出主要的类型和函数，使API更易用
 pub use error::{Error, Result};
-pub use types::{EvalOptions, EvalResult, TestInfo};
+pub use types::{EvalOptions, EvalResult, EvalTestCase, TestInfo};
 pub use metrics::{Metric, MetricResult};
-pub use evaluator::Evaluator; 
\ No newline at end of file
+pub use evaluator::Evaluator;
+pub use suite::{CaseOutput, CaseReport, EvalSuite, SuiteMetric, SuiteReport, SuiteThresholds};
+pub use store::{MetricTrend, Regression, ReportStore, TrendPoint};
+pub use prompts::{PromptDraft, PromptRegistry, PromptVersion};
+pub use optimizer::{CandidateResult, OptimizationMetric, OptimizationReport, OptimizerConfig, PromptCandidate, PromptOptimizer};
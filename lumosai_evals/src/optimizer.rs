@@ -0,0 +1,489 @@
+//! 提示词自动优化模块
+//!
+//! 参考DSPy的优化循环：以代理当前的指令为基线，每轮由优化模型根据当前最佳候选的失败用例
+//! 提出若干指令和少样本示例的变体，同时从得分较高的用例中自举少样本示例，
+//! 在评测集上逐一打分并保留得分最高的候选。优化结束后，若最佳候选优于基线，
+//! 则作为新版本发布到 [`PromptRegistry`]。
+
+use std::sync::Arc;
+
+use lumosai_core::agent::types::AgentGenerateOptions;
+use lumosai_core::agent::{system_message, user_message, AgentBuilder, FewShotConfig, FewShotExample};
+use lumosai_core::llm::{LlmOptions, LlmProvider};
+use lumosai_core::Agent;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::metrics::Metric;
+use crate::prompts::{PromptDraft, PromptRegistry, PromptVersion};
+use crate::suite::{CaseOutput, SuiteMetric};
+use crate::types::EvalTestCase;
+
+/// 输出截断长度，避免优化提示过长
+const MAX_SHOWN_CHARS: usize = 500;
+
+/// 优化目标指标
+#[derive(Clone)]
+pub enum OptimizationMetric {
+    /// 套件中的确定性指标，使用用例的期望输出
+    Suite(SuiteMetric),
+    /// 自定义指标，只根据输入和输出打分
+    Custom(Arc<dyn Metric>),
+}
+
+impl OptimizationMetric {
+    /// 指标名称
+    pub fn name(&self) -> String {
+        match self {
+            OptimizationMetric::Suite(metric) => metric.name().to_string(),
+            OptimizationMetric::Custom(metric) => metric.name().to_string(),
+        }
+    }
+
+    /// 为用例打分，指标不适用于该用例时返回 `None`
+    async fn score(&self, case: &EvalTestCase, output: &CaseOutput) -> Result<Option<f64>> {
+        match self {
+            OptimizationMetric::Suite(metric) => metric.score(case, output).await,
+            OptimizationMetric::Custom(metric) => Ok(Some(metric.measure(&case.input, &output.output).await?.score)),
+        }
+    }
+}
+
+impl From<SuiteMetric> for OptimizationMetric {
+    fn from(metric: SuiteMetric) -> Self {
+        OptimizationMetric::Suite(metric)
+    }
+}
+
+impl From<Arc<dyn Metric>> for OptimizationMetric {
+    fn from(metric: Arc<dyn Metric>) -> Self {
+        OptimizationMetric::Custom(metric)
+    }
+}
+
+/// 优化配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptimizerConfig {
+    /// 优化轮数
+    pub rounds: usize,
+    /// 每轮由优化模型提出的候选数
+    pub candidates_per_round: usize,
+    /// 每个候选最多包含的少样本示例数
+    pub max_examples: usize,
+    /// 每轮展示给优化模型的失败用例数
+    pub max_failures_shown: usize,
+    /// 用例得分不低于该值视为成功，成功用例可作为自举示例
+    pub success_threshold: f64,
+    /// 候选得分至少超出当前最佳这么多才被采用
+    pub min_improvement: f64,
+}
+
+impl Default for OptimizerConfig {
+    fn default() -> Self {
+        Self {
+            rounds: 3,
+            candidates_per_round: 3,
+            max_examples: 3,
+            max_failures_shown: 5,
+            success_threshold: 0.8,
+            min_improvement: 0.0,
+        }
+    }
+}
+
+/// 候选提示词：代理指令和少样本示例
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PromptCandidate {
+    /// 代理指令
+    pub instructions: String,
+    /// 少样本示例
+    #[serde(default)]
+    pub examples: Vec<FewShotExample>,
+}
+
+impl PromptCandidate {
+    /// 只包含指令的候选
+    pub fn new(instructions: impl Into<String>) -> Self {
+        Self {
+            instructions: instructions.into(),
+            examples: Vec::new(),
+        }
+    }
+}
+
+/// 一个候选的评估结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CandidateResult {
+    /// 产生候选的轮次，基线为0
+    pub round: usize,
+    /// 候选提示词
+    pub candidate: PromptCandidate,
+    /// 评测集上的平均得分
+    pub score: f64,
+}
+
+/// 优化报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptimizationReport {
+    /// 被优化的代理
+    pub agent: String,
+    /// 指标名称
+    pub metric: String,
+    /// 基线得分
+    pub baseline_score: f64,
+    /// 最佳候选
+    pub best: PromptCandidate,
+    /// 最佳候选的得分
+    pub best_score: f64,
+    /// 所有候选的评估结果，按评估顺序
+    pub history: Vec<CandidateResult>,
+    /// 发布到版本库的版本
+    pub version: Option<PromptVersion>,
+}
+
+impl OptimizationReport {
+    /// 最佳候选是否优于基线
+    pub fn improved(&self) -> bool {
+        self.best_score > self.baseline_score
+    }
+}
+
+/// 单个用例的运行结果
+struct CaseRun {
+    index: usize,
+    output: String,
+    score: f64,
+}
+
+/// 候选在评测集上的运行结果
+struct Evaluation {
+    score: f64,
+    runs: Vec<CaseRun>,
+}
+
+/// 优化模型返回的候选
+#[derive(Deserialize)]
+struct Proposals {
+    #[serde(default)]
+    candidates: Vec<PromptCandidate>,
+}
+
+/// 提示词优化器
+pub struct PromptOptimizer {
+    llm: Arc<dyn LlmProvider>,
+    metric: OptimizationMetric,
+    config: OptimizerConfig,
+    registry: Option<PromptRegistry>,
+}
+
+impl PromptOptimizer {
+    /// 创建优化器，`llm` 用于提出候选提示词
+    pub fn new(llm: Arc<dyn LlmProvider>, metric: impl Into<OptimizationMetric>) -> Self {
+        Self {
+            llm,
+            metric: metric.into(),
+            config: OptimizerConfig::default(),
+            registry: None,
+        }
+    }
+
+    /// 设置优化配置
+    pub fn with_config(mut self, config: OptimizerConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// 将优于基线的最佳候选发布到版本库，版本名称为代理名称
+    pub fn with_registry(mut self, registry: PromptRegistry) -> Self {
+        self.registry = Some(registry);
+        self
+    }
+
+    /// 在评测集上优化代理的提示词
+    ///
+    /// 候选通过以代理的模型和工具构建的新代理运行，原代理不会被修改。
+    pub async fn optimize(&self, agent: &dyn Agent, dataset: &[EvalTestCase]) -> Result<OptimizationReport> {
+        if dataset.is_empty() {
+            return Err(Error::Configuration("提示词优化需要至少一个评测用例".to_string()));
+        }
+
+        let baseline = PromptCandidate::new(agent.get_instructions());
+        let mut best_evaluation = self.evaluate(agent, &baseline, dataset).await?;
+        let baseline_score = best_evaluation.score;
+        let mut best = baseline.clone();
+        let mut history = vec![CandidateResult { round: 0, candidate: baseline, score: baseline_score }];
+
+        for round in 1..=self.config.rounds {
+            if best_evaluation.score >= 1.0 {
+                break;
+            }
+
+            let mut candidates = self.propose(&best, &best_evaluation, dataset).await;
+            if let Some(bootstrapped) = self.bootstrap(&best, &best_evaluation, dataset, round) {
+                candidates.push(bootstrapped);
+            }
+
+            for candidate in candidates {
+                if history.iter().any(|result| result.candidate == candidate) {
+                    continue;
+                }
+                let evaluation = self.evaluate(agent, &candidate, dataset).await?;
+                tracing::debug!("第{}轮候选得分 {:.3}（当前最佳 {:.3}）", round, evaluation.score, best_evaluation.score);
+                history.push(CandidateResult { round, candidate: candidate.clone(), score: evaluation.score });
+                if evaluation.score > best_evaluation.score + self.config.min_improvement {
+                    best = candidate;
+                    best_evaluation = evaluation;
+                }
+            }
+        }
+
+        let mut report = OptimizationReport {
+            agent: agent.get_name().to_string(),
+            metric: self.metric.name(),
+            baseline_score,
+            best,
+            best_score: best_evaluation.score,
+            history,
+            version: None,
+        };
+
+        if let (Some(registry), true) = (&self.registry, report.improved()) {
+            let mut draft = PromptDraft::new(report.best.instructions.clone());
+            draft.examples = report.best.examples.clone();
+            draft.score = Some(report.best_score);
+            draft.metadata.insert("source".to_string(), "optimizer".into());
+            draft.metadata.insert("metric".to_string(), report.metric.clone().into());
+            draft.metadata.insert("baseline_score".to_string(), baseline_score.into());
+            draft.metadata.insert("candidates_evaluated".to_string(), report.history.len().into());
+            report.version = Some(registry.publish(&report.agent, draft)?);
+        }
+
+        Ok(report)
+    }
+
+    /// 以候选提示词运行评测集并计算平均得分
+    async fn evaluate(&self, agent: &dyn Agent, candidate: &PromptCandidate, dataset: &[EvalTestCase]) -> Result<Evaluation> {
+        let mut builder = AgentBuilder::new()
+            .name(agent.get_name())
+            .instructions(candidate.instructions.clone())
+            .model(agent.get_llm())
+            .tools(agent.get_tools().into_values().collect());
+        if !candidate.examples.is_empty() {
+            builder = builder.few_shot(FewShotConfig::new(candidate.examples.clone()));
+        }
+        let variant = builder.build()?;
+
+        let mut runs = Vec::with_capacity(dataset.len());
+        for (index, case) in dataset.iter().enumerate() {
+            let output = match variant.generate(&[user_message(&case.input)], &AgentGenerateOptions::default()).await {
+                Ok(result) => CaseOutput {
+                    tools: result.steps
+                        .iter()
+                        .flat_map(|step| step.tool_calls.iter().map(|call| call.name.clone()))
+                        .collect(),
+                    output: result.response,
+                    ..CaseOutput::default()
+                },
+                Err(e) => CaseOutput {
+                    error: Some(e.to_string()),
+                    ..CaseOutput::default()
+                },
+            };
+
+            let score = if output.error.is_some() {
+                Some(0.0)
+            } else {
+                self.metric.score(case, &output).await?
+            };
+            if let Some(score) = score {
+                runs.push(CaseRun { index, output: output.output, score });
+            }
+        }
+
+        if runs.is_empty() {
+            return Err(Error::Configuration(format!("指标 {} 无法为任何评测用例打分", self.metric.name())));
+        }
+        let score = runs.iter().map(|run| run.score).sum::<f64>() / runs.len() as f64;
+        Ok(Evaluation { score, runs })
+    }
+
+    /// 请优化模型根据失败用例提出候选，无法解析回复时返回空列表
+    async fn propose(&self, best: &PromptCandidate, evaluation: &Evaluation, dataset: &[EvalTestCase]) -> Vec<PromptCandidate> {
+        let mut failures = String::new();
+        for run in evaluation.runs.iter()
+            .filter(|run| run.score < self.config.success_threshold)
+            .take(self.config.max_failures_shown)
+        {
+            let case = &dataset[run.index];
+            failures.push_str(&format!("Input: {}\n", truncate(&case.input)));
+            if let Some(expected) = &case.expected_output {
+                failures.push_str(&format!("Expected: {}\n", truncate(expected)));
+            }
+            failures.push_str(&format!("Actual: {}\nScore: {:.2}\n\n", truncate(&run.output), run.score));
+        }
+
+        let examples = serde_json::to_string(&best.examples).unwrap_or_default();
+        let prompt = format!(
+            concat!(
+                "The following instructions for an AI agent score {score:.3} (0 to 1) on the metric \"{metric}\".\n\n",
+                "Instructions:\n{instructions}\n\n",
+                "Few-shot examples:\n{examples}\n\n",
+                "Cases the agent got wrong:\n{failures}",
+                "Propose {count} improved variations of the instructions, each with at most {max_examples} few-shot examples.\n",
+                "Respond with JSON only, in the format ",
+                "{{\"candidates\": [{{\"instructions\": \"...\", \"examples\": [{{\"input\": \"...\", \"output\": \"...\"}}]}}]}}"
+            ),
+            score = evaluation.score,
+            metric = self.metric.name(),
+            instructions = best.instructions,
+            examples = examples,
+            failures = if failures.is_empty() { "(none)\n\n".to_string() } else { failures },
+            count = self.config.candidates_per_round,
+            max_examples = self.config.max_examples,
+        );
+        let messages = vec![
+            system_message("You improve the instructions and few-shot examples of AI agents."),
+            user_message(&prompt),
+        ];
+
+        let response = match self.llm.generate_with_messages(&messages, &LlmOptions::default()).await {
+            Ok(response) => response,
+            Err(e) => {
+                tracing::warn!("优化模型提出候选失败: {}", e);
+                return Vec::new();
+            }
+        };
+        let json = match (response.find('{'), response.rfind('}')) {
+            (Some(start), Some(end)) if start < end => &response[start..=end],
+            _ => "",
+        };
+        match serde_json::from_str::<Proposals>(json) {
+            Ok(proposals) => proposals.candidates.into_iter()
+                .filter(|candidate| !candidate.instructions.trim().is_empty())
+                .take(self.config.candidates_per_round)
+                .map(|mut candidate| {
+                    candidate.examples.truncate(self.config.max_examples);
+                    candidate
+                })
+                .collect(),
+            Err(e) => {
+                tracing::warn!("无法解析优化模型的候选: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// 从成功的用例中自举少样本示例，每轮轮换选取的用例
+    fn bootstrap(&self, best: &PromptCandidate, evaluation: &Evaluation, dataset: &[EvalTestCase], round: usize) -> Option<PromptCandidate> {
+        let successes: Vec<&CaseRun> = evaluation.runs.iter()
+            .filter(|run| run.score >= self.config.success_threshold)
+            .collect();
+        if successes.is_empty() || self.config.max_examples == 0 {
+            return None;
+        }
+
+        let offset = (round - 1) * self.config.max_examples;
+        let examples = (0..self.config.max_examples.min(successes.len()))
+            .map(|i| {
+                let run = successes[(offset + i) % successes.len()];
+                let case = &dataset[run.index];
+                let output = case.expected_output.clone().unwrap_or_else(|| run.output.clone());
+                FewShotExample::new(case.input.clone(), output)
+            })
+            .collect();
+        Some(PromptCandidate {
+            instructions: best.instructions.clone(),
+            examples,
+        })
+    }
+}
+
+fn truncate(text: &str) -> String {
+    match text.char_indices().nth(MAX_SHOWN_CHARS) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use futures::stream::{self, BoxStream, StreamExt};
+    use lumosai_core::llm::MockLlmProvider;
+    use lumosai_core::{Message, Role};
+
+    /// 只有指令要求时才简洁作答的模型
+    struct TerseWhenAsked;
+
+    #[async_trait]
+    impl LlmProvider for TerseWhenAsked {
+        fn name(&self) -> &str {
+            "terse"
+        }
+
+        async fn generate(&self, _prompt: &str, _options: &LlmOptions) -> lumosai_core::Result<String> {
+            Ok(String::new())
+        }
+
+        async fn generate_with_messages(&self, messages: &[Message], _options: &LlmOptions) -> lumosai_core::Result<String> {
+            let terse = messages.iter().any(|message| message.role == Role::System && message.content.contains("city name only"));
+            let question = messages.last().map(|message| message.content.as_str()).unwrap_or_default();
+            let city = if question.contains("France") { "Paris" } else { "Madrid" };
+            Ok(if terse { city.to_string() } else { format!("I believe the answer is {}.", city) })
+        }
+
+        async fn generate_stream<'a>(&'a self, _prompt: &'a str, _options: &'a LlmOptions) -> lumosai_core::Result<BoxStream<'a, lumosai_core::Result<String>>> {
+            Ok(stream::empty().boxed())
+        }
+
+        async fn get_embedding(&self, _text: &str) -> lumosai_core::Result<Vec<f32>> {
+            Ok(vec![1.0])
+        }
+    }
+
+    fn case(input: &str, expected: &str) -> EvalTestCase {
+        let mut case = EvalTestCase::new(input);
+        case.expected_output = Some(expected.to_string());
+        case
+    }
+
+    #[tokio::test]
+    async fn test_optimizer_publishes_best_candidate() {
+        let agent = AgentBuilder::new()
+            .name("geography")
+            .instructions("Answer questions.")
+            .model(Arc::new(TerseWhenAsked))
+            .build()
+            .unwrap();
+        let dataset = vec![case("Capital of France?", "Paris"), case("Capital of Spain?", "Madrid")];
+
+        let proposals = r#"Here you go:
+{"candidates": [
+  {"instructions": "Answer questions politely."},
+  {"instructions": "Answer with the city name only.", "examples": [{"input": "Capital of Italy?", "output": "Rome"}]}
+]}"#;
+        let optimizer_llm = Arc::new(MockLlmProvider::new(vec![proposals.to_string()]));
+        let dir = std::env::temp_dir().join(format!("lumos_optimizer_{}", std::process::id()));
+        let optimizer = PromptOptimizer::new(optimizer_llm, SuiteMetric::ExactMatch { threshold: 1.0 })
+            .with_registry(PromptRegistry::new(&dir));
+
+        let report = optimizer.optimize(&agent, &dataset).await.unwrap();
+        assert_eq!(report.baseline_score, 0.0);
+        assert_eq!(report.best_score, 1.0);
+        assert_eq!(report.best.instructions, "Answer with the city name only.");
+        assert_eq!(report.best.examples.len(), 1);
+        // 基线和两个候选，第一轮即达到满分
+        assert_eq!(report.history.len(), 3);
+
+        let version = report.version.unwrap();
+        assert_eq!(version.version, 1);
+        assert_eq!(version.score, Some(1.0));
+        let published = PromptRegistry::new(&dir).latest("geography").unwrap().unwrap();
+        assert_eq!(published.instructions, "Answer with the city name only.");
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        // 原代理不受影响
+        assert_eq!(agent.get_instructions(), "Answer questions.");
+    }
+}
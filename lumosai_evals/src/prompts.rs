@@ -0,0 +1,169 @@
+//! 提示词版本库
+//!
+//! 按名称保存代理提示词（指令和少样本示例）的各个版本，版本号从1开始递增。
+//! 目录结构为 `<dir>/<名称>/v<版本>.json`，已发布的版本不会被修改。
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use lumosai_core::agent::{FewShotConfig, FewShotExample};
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
+use crate::store::report_file_stem;
+
+/// 提示词的一个版本
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptVersion {
+    /// 提示词名称，通常为代理名称
+    pub name: String,
+    /// 版本号
+    pub version: u32,
+    /// 代理指令
+    pub instructions: String,
+    /// 少样本示例
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub examples: Vec<FewShotExample>,
+    /// 在评测集上的得分
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<f64>,
+    /// 额外元数据，例如产生该版本的优化过程
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub metadata: HashMap<String, serde_json::Value>,
+    /// 发布时间
+    pub created_at: DateTime<Utc>,
+}
+
+impl PromptVersion {
+    /// 少样本示例配置，没有示例时返回 `None`
+    pub fn few_shot(&self) -> Option<FewShotConfig> {
+        (!self.examples.is_empty()).then(|| FewShotConfig::new(self.examples.clone()))
+    }
+}
+
+/// 待发布的提示词
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PromptDraft {
+    /// 代理指令
+    pub instructions: String,
+    /// 少样本示例
+    #[serde(default)]
+    pub examples: Vec<FewShotExample>,
+    /// 在评测集上的得分
+    #[serde(default)]
+    pub score: Option<f64>,
+    /// 额外元数据
+    #[serde(default)]
+    pub metadata: HashMap<String, serde_json::Value>,
+}
+
+impl PromptDraft {
+    /// 只包含指令的提示词
+    pub fn new(instructions: impl Into<String>) -> Self {
+        Self {
+            instructions: instructions.into(),
+            ..Self::default()
+        }
+    }
+}
+
+/// 基于目录的提示词版本库
+#[derive(Debug, Clone)]
+pub struct PromptRegistry {
+    dir: PathBuf,
+}
+
+impl PromptRegistry {
+    /// 创建版本库
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// 存储目录
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// 发布提示词的新版本
+    pub fn publish(&self, name: &str, draft: PromptDraft) -> Result<PromptVersion> {
+        let prompt_dir = self.dir.join(report_file_stem(name));
+        fs::create_dir_all(&prompt_dir)?;
+
+        let version = PromptVersion {
+            name: name.to_string(),
+            version: self.latest(name)?.map_or(1, |latest| latest.version + 1),
+            instructions: draft.instructions,
+            examples: draft.examples,
+            score: draft.score,
+            metadata: draft.metadata,
+            created_at: Utc::now(),
+        };
+        fs::write(
+            prompt_dir.join(format!("v{}.json", version.version)),
+            serde_json::to_string_pretty(&version)?,
+        )?;
+        Ok(version)
+    }
+
+    /// 提示词的所有版本，按版本号升序
+    pub fn versions(&self, name: &str) -> Result<Vec<PromptVersion>> {
+        let prompt_dir = self.dir.join(report_file_stem(name));
+        if !prompt_dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut versions = Vec::new();
+        for entry in fs::read_dir(&prompt_dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            match serde_json::from_str::<PromptVersion>(&fs::read_to_string(&path)?) {
+                Ok(version) if version.name == name => versions.push(version),
+                Ok(_) => {}
+                Err(e) => tracing::warn!("跳过无法解析的提示词版本 {}: {}", path.display(), e),
+            }
+        }
+        versions.sort_by_key(|version| version.version);
+        Ok(versions)
+    }
+
+    /// 最新版本
+    pub fn latest(&self, name: &str) -> Result<Option<PromptVersion>> {
+        Ok(self.versions(name)?.pop())
+    }
+
+    /// 指定版本
+    pub fn get(&self, name: &str, version: u32) -> Result<Option<PromptVersion>> {
+        Ok(self.versions(name)?.into_iter().find(|candidate| candidate.version == version))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_publish_increments_versions() {
+        let dir = std::env::temp_dir().join(format!("lumos_prompts_{}", std::process::id()));
+        let registry = PromptRegistry::new(&dir);
+        assert!(registry.latest("support agent").unwrap().is_none());
+
+        registry.publish("support agent", PromptDraft::new("Be helpful.")).unwrap();
+        let mut draft = PromptDraft::new("Be helpful and brief.");
+        draft.examples.push(FewShotExample::new("Hi", "Hello!"));
+        draft.score = Some(0.8);
+        let published = registry.publish("support agent", draft).unwrap();
+        assert_eq!(published.version, 2);
+
+        let latest = registry.latest("support agent").unwrap().unwrap();
+        assert_eq!(latest, published);
+        assert_eq!(latest.few_shot().unwrap().examples.len(), 1);
+        assert_eq!(registry.get("support agent", 1).unwrap().unwrap().instructions, "Be helpful.");
+        assert_eq!(registry.versions("support agent").unwrap().len(), 2);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
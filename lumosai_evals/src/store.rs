@@ -0,0 +1,313 @@
+//! 评估报告存储模块
+//!
+//! 按套件保存每次运行的 [`SuiteReport`]，并计算各指标随时间的变化趋势。
+//! 趋势按模型/提示词变体分组，最近一次运行相比上一次下降超过容差时视为回归。
+//! 报告可以上传到对象存储（S3、GCS、Azure Blob等），在多台机器之间共享历史。
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use lumosai_core::storage::object::download_file;
+use lumosai_core::storage::ObjectStore;
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
+use crate::suite::SuiteReport;
+
+/// 默认的回归容差
+pub const DEFAULT_REGRESSION_TOLERANCE: f64 = 0.02;
+
+/// 指标在一次运行中的取值
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrendPoint {
+    /// 运行开始时间
+    pub started_at: DateTime<Utc>,
+    /// 平均得分
+    pub mean: f64,
+    /// 通过比例
+    pub pass_rate: f64,
+}
+
+/// 最近一次运行相比上一次的下降
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Regression {
+    /// 上一次的平均得分
+    pub previous: f64,
+    /// 最近一次的平均得分
+    pub current: f64,
+}
+
+impl Regression {
+    /// 得分变化量（为负数）
+    pub fn delta(&self) -> f64 {
+        self.current - self.previous
+    }
+}
+
+/// 单个指标在某个变体上的趋势
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricTrend {
+    /// 指标名称
+    pub metric: String,
+    /// 变体标签，见 [`SuiteReport::variant_label`]
+    pub variant: String,
+    /// 按时间排序的取值
+    pub points: Vec<TrendPoint>,
+    /// 回归信息
+    pub regression: Option<Regression>,
+}
+
+/// 基于目录的评估报告存储
+///
+/// 目录结构为 `<dir>/<套件>/<开始时间>.json`，每个文件是一份完整的套件报告。
+#[derive(Debug, Clone)]
+pub struct ReportStore {
+    dir: PathBuf,
+    regression_tolerance: f64,
+}
+
+impl ReportStore {
+    /// 创建存储
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Self {
+        Self {
+            dir: dir.into(),
+            regression_tolerance: DEFAULT_REGRESSION_TOLERANCE,
+        }
+    }
+
+    /// 设置回归容差，平均得分下降超过该值才视为回归
+    pub fn with_regression_tolerance(mut self, tolerance: f64) -> Self {
+        self.regression_tolerance = tolerance;
+        self
+    }
+
+    /// 存储目录
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// 保存一次运行的报告，返回写入的文件
+    pub fn save(&self, report: &SuiteReport) -> Result<PathBuf> {
+        let path = self.dir.join(report_key(report));
+        if let Some(suite_dir) = path.parent() {
+            fs::create_dir_all(suite_dir)?;
+        }
+        fs::write(&path, report.to_json()?)?;
+        Ok(path)
+    }
+
+    /// 将报告上传到对象存储，返回对象键；键与报告在存储目录中的相对路径相同
+    pub async fn push(&self, report: &SuiteReport, objects: &dyn ObjectStore) -> Result<String> {
+        let key = report_key(report);
+        objects.put(&key, "application/json", report.to_json()?.into_bytes()).await?;
+        Ok(key)
+    }
+
+    /// 下载对象存储中本地还没有的报告，返回下载的数量
+    ///
+    /// 多台机器（如CI）各自上传报告后，先拉取再计算趋势即可看到完整的历史。
+    pub async fn pull(&self, objects: &dyn ObjectStore) -> Result<usize> {
+        let mut downloaded = 0;
+        for key in objects.list("").await? {
+            // 只接受 `<套件>/<文件>.json` 形式的键，其他对象不是报告
+            let valid = key.split_once('/').is_some_and(|(suite, file)| {
+                suite == report_file_stem(suite) && !file.contains('/') && !file.starts_with('.') && file.ends_with(".json")
+            });
+            if !valid {
+                continue;
+            }
+            let path = self.dir.join(&key);
+            if path.exists() {
+                continue;
+            }
+            download_file(objects, &key, &path).await?;
+            downloaded += 1;
+        }
+        Ok(downloaded)
+    }
+
+    /// 存储中的套件名称
+    pub fn suites(&self) -> Result<Vec<String>> {
+        if !self.dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut suites = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                if let Some(report) = read_reports(&path)?.into_iter().last() {
+                    suites.push(report.suite);
+                }
+            }
+        }
+        suites.sort();
+        Ok(suites)
+    }
+
+    /// 套件的所有报告，按开始时间升序
+    pub fn list(&self, suite: &str) -> Result<Vec<SuiteReport>> {
+        let suite_dir = self.dir.join(report_file_stem(suite));
+        if !suite_dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut reports: Vec<SuiteReport> = read_reports(&suite_dir)?
+            .into_iter()
+            .filter(|report| report.suite == suite)
+            .collect();
+        reports.sort_by_key(|report| report.started_at);
+        Ok(reports)
+    }
+
+    /// 套件各指标按变体分组的趋势
+    pub fn trends(&self, suite: &str) -> Result<Vec<MetricTrend>> {
+        Ok(self.compute_trends(&self.list(suite)?))
+    }
+
+    /// 根据按时间升序的报告计算趋势
+    pub fn compute_trends(&self, reports: &[SuiteReport]) -> Vec<MetricTrend> {
+        let mut grouped: BTreeMap<(String, String), Vec<TrendPoint>> = BTreeMap::new();
+        for report in reports {
+            for summary in &report.metrics {
+                grouped
+                    .entry((summary.metric.clone(), report.variant_label()))
+                    .or_default()
+                    .push(TrendPoint {
+                        started_at: report.started_at,
+                        mean: summary.mean,
+                        pass_rate: summary.pass_rate,
+                    });
+            }
+        }
+
+        grouped
+            .into_iter()
+            .map(|((metric, variant), points)| {
+                let regression = match points.as_slice() {
+                    [.., previous, current] if previous.mean - current.mean > self.regression_tolerance => {
+                        Some(Regression { previous: previous.mean, current: current.mean })
+                    }
+                    _ => None,
+                };
+                MetricTrend { metric, variant, points, regression }
+            })
+            .collect()
+    }
+}
+
+fn read_reports(dir: &Path) -> Result<Vec<SuiteReport>> {
+    let mut reports = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+            continue;
+        }
+        match serde_json::from_str::<SuiteReport>(&fs::read_to_string(&path)?) {
+            Ok(report) => reports.push(report),
+            Err(e) => tracing::warn!("跳过无法解析的评估报告 {}: {}", path.display(), e),
+        }
+    }
+    Ok(reports)
+}
+
+/// 报告在存储中的相对路径：`<套件>/<开始时间>[-<变体>].json`
+fn report_key(report: &SuiteReport) -> String {
+    let mut file_name = report.started_at.format("%Y%m%dT%H%M%S%.3fZ").to_string();
+    if report.model.is_some() || report.variant.is_some() {
+        file_name.push('-');
+        file_name.push_str(&report_file_stem(&report.variant_label()));
+    }
+    format!("{}/{}.json", report_file_stem(&report.suite), file_name)
+}
+
+/// 将套件名称转换为可用作文件名的形式
+pub fn report_file_stem(suite: &str) -> String {
+    let stem: String = suite
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '-' })
+        .collect();
+    if stem.is_empty() { "report".to_string() } else { stem }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::suite::{MetricSummary, SuiteThresholds};
+    use chrono::Duration;
+
+    fn report(minutes: i64, variant: Option<&str>, mean: f64) -> SuiteReport {
+        SuiteReport {
+            suite: "smoke tests".to_string(),
+            model: Some("gpt-4o".to_string()),
+            variant: variant.map(String::from),
+            started_at: Utc::now() - Duration::minutes(60 - minutes),
+            duration_ms: 10,
+            cases: Vec::new(),
+            metrics: vec![MetricSummary {
+                metric: "similarity".to_string(),
+                mean,
+                min: mean,
+                pass_rate: 1.0,
+                count: 1,
+            }],
+            pass_rate: 1.0,
+            mean_score: mean,
+            thresholds: SuiteThresholds::default(),
+            failures: Vec::new(),
+            passed: true,
+        }
+    }
+
+    #[test]
+    fn test_report_store_trends() {
+        let dir = std::env::temp_dir().join(format!("lumos-eval-store-{}", uuid::Uuid::new_v4()));
+        let store = ReportStore::new(&dir);
+        assert!(store.suites().unwrap().is_empty());
+
+        store.save(&report(0, None, 0.9)).unwrap();
+        store.save(&report(1, None, 0.7)).unwrap();
+        store.save(&report(2, Some("concise"), 0.8)).unwrap();
+        store.save(&report(3, Some("concise"), 0.81)).unwrap();
+
+        assert_eq!(store.suites().unwrap(), vec!["smoke tests".to_string()]);
+        let reports = store.list("smoke tests").unwrap();
+        assert_eq!(reports.len(), 4);
+        assert!(reports.windows(2).all(|pair| pair[0].started_at <= pair[1].started_at));
+
+        let trends = store.trends("smoke tests").unwrap();
+        assert_eq!(trends.len(), 2);
+        let default = trends.iter().find(|t| t.variant == "gpt-4o").unwrap();
+        assert_eq!(default.points.len(), 2);
+        assert!((default.regression.as_ref().unwrap().delta() + 0.2).abs() < 1e-9);
+        let concise = trends.iter().find(|t| t.variant == "gpt-4o · concise").unwrap();
+        assert!(concise.regression.is_none());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_push_and_pull() {
+        let root = std::env::temp_dir().join(format!("lumos-eval-remote-{}", uuid::Uuid::new_v4()));
+        let objects = lumosai_core::storage::LocalObjectStore::new(root.join("bucket"));
+        let ci = ReportStore::new(root.join("ci"));
+        let laptop = ReportStore::new(root.join("laptop"));
+
+        let (first, second) = (report(0, None, 0.9), report(1, None, 0.7));
+        let key = ci.push(&first, &objects).await.unwrap();
+        assert!(key.starts_with("smoke-tests/") && key.ends_with("-gpt-4o.json"));
+        ci.push(&second, &objects).await.unwrap();
+        objects.put("notes.txt", "text/plain", b"not a report".to_vec()).await.unwrap();
+
+        // 本地已有的报告不会重复下载
+        laptop.save(&first).unwrap();
+        assert_eq!(laptop.pull(&objects).await.unwrap(), 1);
+        assert_eq!(laptop.pull(&objects).await.unwrap(), 0);
+        let trends = laptop.trends("smoke tests").unwrap();
+        assert!(trends[0].regression.is_some());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
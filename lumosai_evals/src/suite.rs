@@ -0,0 +1,644 @@
+//! 评估套件模块
+//!
+//! 评估套件描述一组测试用例、用于打分的指标以及整体通过阈值，
+//! 可以从YAML或JSON文件加载，用于在CI中批量评估代理。
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::evaluator::rule_eval::{Rule, RuleEvaluator, RuleType};
+use crate::evaluator::Evaluator;
+use crate::types::{EvalOptions, EvalTestCase};
+
+/// 评估套件定义
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalSuite {
+    /// 套件名称
+    pub name: String,
+
+    /// 套件描述
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// 默认的目标代理，测试用例可以单独覆盖
+    #[serde(default)]
+    pub agent: Option<String>,
+
+    /// 额外的JSONL测试用例文件，相对于套件文件
+    #[serde(default)]
+    pub cases_file: Option<String>,
+
+    /// 内联测试用例
+    #[serde(default)]
+    pub tests: Vec<EvalTestCase>,
+
+    /// 打分指标
+    #[serde(default = "default_metrics")]
+    pub metrics: Vec<SuiteMetric>,
+
+    /// 整体通过阈值
+    #[serde(default)]
+    pub thresholds: SuiteThresholds,
+}
+
+fn default_metrics() -> Vec<SuiteMetric> {
+    vec![SuiteMetric::Similarity { threshold: default_similarity_threshold() }]
+}
+
+fn default_threshold() -> f64 {
+    1.0
+}
+
+fn default_similarity_threshold() -> f64 {
+    0.5
+}
+
+/// 套件整体通过阈值
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuiteThresholds {
+    /// 通过的用例比例下限
+    #[serde(default = "default_threshold")]
+    pub pass_rate: f64,
+
+    /// 所有指标平均得分下限
+    #[serde(default)]
+    pub mean_score: Option<f64>,
+}
+
+impl Default for SuiteThresholds {
+    fn default() -> Self {
+        Self {
+            pass_rate: default_threshold(),
+            mean_score: None,
+        }
+    }
+}
+
+/// 套件中使用的确定性指标
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SuiteMetric {
+    /// 输出与期望输出完全一致（忽略首尾空白和大小写）
+    ExactMatch {
+        #[serde(default = "default_threshold")]
+        threshold: f64,
+    },
+    /// 输出包含期望输出
+    Contains {
+        #[serde(default = "default_threshold")]
+        threshold: f64,
+    },
+    /// 输出与期望输出的词级F1相似度
+    Similarity {
+        #[serde(default = "default_similarity_threshold")]
+        threshold: f64,
+    },
+    /// 输出包含的关键词比例
+    Keywords {
+        keywords: Vec<String>,
+        #[serde(default = "default_threshold")]
+        threshold: f64,
+    },
+    /// 输出匹配正则表达式
+    Regex {
+        pattern: String,
+        #[serde(default = "default_threshold")]
+        threshold: f64,
+    },
+    /// 输出长度在范围内
+    Length {
+        #[serde(default)]
+        min: Option<usize>,
+        #[serde(default)]
+        max: Option<usize>,
+        #[serde(default = "default_threshold")]
+        threshold: f64,
+    },
+    /// 期望的工具都被调用
+    ExpectedTools {
+        #[serde(default = "default_threshold")]
+        threshold: f64,
+    },
+    /// 响应耗时不超过上限
+    Latency { max_ms: u64 },
+}
+
+impl SuiteMetric {
+    /// 指标名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            SuiteMetric::ExactMatch { .. } => "exact_match",
+            SuiteMetric::Contains { .. } => "contains",
+            SuiteMetric::Similarity { .. } => "similarity",
+            SuiteMetric::Keywords { .. } => "keywords",
+            SuiteMetric::Regex { .. } => "regex",
+            SuiteMetric::Length { .. } => "length",
+            SuiteMetric::ExpectedTools { .. } => "expected_tools",
+            SuiteMetric::Latency { .. } => "latency",
+        }
+    }
+
+    /// 单个用例的通过阈值
+    pub fn threshold(&self) -> f64 {
+        match self {
+            SuiteMetric::ExactMatch { threshold }
+            | SuiteMetric::Contains { threshold }
+            | SuiteMetric::Similarity { threshold }
+            | SuiteMetric::Keywords { threshold, .. }
+            | SuiteMetric::Regex { threshold, .. }
+            | SuiteMetric::Length { threshold, .. }
+            | SuiteMetric::ExpectedTools { threshold } => *threshold,
+            SuiteMetric::Latency { .. } => 1.0,
+        }
+    }
+
+    /// 为用例打分，指标不适用于该用例时返回 `None`
+    pub async fn score(&self, case: &EvalTestCase, output: &CaseOutput) -> Result<Option<f64>> {
+        let expected = case.expected_output.as_deref();
+        let score = match self {
+            SuiteMetric::ExactMatch { .. } => expected.map(|expected| {
+                let matched = expected.trim().to_lowercase() == output.output.trim().to_lowercase();
+                if matched { 1.0 } else { 0.0 }
+            }),
+            SuiteMetric::Contains { .. } => expected.map(|expected| {
+                let matched = output.output.to_lowercase().contains(&expected.trim().to_lowercase());
+                if matched { 1.0 } else { 0.0 }
+            }),
+            SuiteMetric::Similarity { .. } => expected.map(|expected| token_f1(&output.output, expected)),
+            SuiteMetric::Keywords { keywords, .. } => {
+                Some(self.rule_score(RuleType::Keywords(keywords.clone()), case, output).await?)
+            }
+            SuiteMetric::Regex { pattern, .. } => {
+                Some(self.rule_score(RuleType::Regex(pattern.clone()), case, output).await?)
+            }
+            SuiteMetric::Length { min, max, .. } => {
+                Some(self.rule_score(RuleType::Length { min: *min, max: *max }, case, output).await?)
+            }
+            SuiteMetric::ExpectedTools { .. } => {
+                if case.expected_tools.is_empty() {
+                    None
+                } else {
+                    let called = case.expected_tools.iter().filter(|tool| output.tools.contains(tool)).count();
+                    Some(called as f64 / case.expected_tools.len() as f64)
+                }
+            }
+            SuiteMetric::Latency { max_ms } => Some(if output.latency_ms <= *max_ms { 1.0 } else { 0.0 }),
+        };
+        Ok(score)
+    }
+
+    /// 使用规则评估器计算单条规则的得分
+    async fn rule_score(&self, rule_type: RuleType, case: &EvalTestCase, output: &CaseOutput) -> Result<f64> {
+        let evaluator = RuleEvaluator::new(self.name()).add_rule(Rule {
+            name: self.name().to_string(),
+            description: String::new(),
+            weight: 1.0,
+            rule_type,
+        });
+        let options = EvalOptions {
+            log_results: false,
+            ..EvalOptions::default()
+        };
+        Ok(evaluator.evaluate(&case.input, &output.output, &options).await?.score)
+    }
+}
+
+/// 代理对单个用例的实际输出
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CaseOutput {
+    /// 代理回复
+    pub output: String,
+    /// 调用过的工具
+    pub tools: Vec<String>,
+    /// 耗时（毫秒）
+    pub latency_ms: u64,
+    /// 运行错误
+    pub error: Option<String>,
+}
+
+/// 单个指标的得分
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricScore {
+    /// 指标名称
+    pub metric: String,
+    /// 得分 (0.0-1.0)
+    pub score: f64,
+    /// 通过阈值
+    pub threshold: f64,
+    /// 是否通过
+    pub passed: bool,
+}
+
+/// 单个用例的评估报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaseReport {
+    /// 用例ID
+    pub id: String,
+    /// 目标代理
+    pub agent: Option<String>,
+    /// 输入
+    pub input: String,
+    /// 期望输出
+    pub expected_output: Option<String>,
+    /// 实际输出
+    pub output: CaseOutput,
+    /// 各指标得分
+    pub scores: Vec<MetricScore>,
+    /// 是否通过
+    pub passed: bool,
+}
+
+/// 指标在整个套件上的汇总
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricSummary {
+    /// 指标名称
+    pub metric: String,
+    /// 平均得分
+    pub mean: f64,
+    /// 最低得分
+    pub min: f64,
+    /// 通过比例
+    pub pass_rate: f64,
+    /// 参与打分的用例数
+    pub count: usize,
+}
+
+/// 评估套件报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuiteReport {
+    /// 套件名称
+    pub suite: String,
+    /// 被评估的模型
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// 提示词或配置变体
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant: Option<String>,
+    /// 开始时间
+    pub started_at: DateTime<Utc>,
+    /// 总耗时（毫秒）
+    pub duration_ms: u64,
+    /// 用例报告
+    pub cases: Vec<CaseReport>,
+    /// 指标汇总
+    pub metrics: Vec<MetricSummary>,
+    /// 用例通过比例
+    pub pass_rate: f64,
+    /// 所有得分的平均值
+    pub mean_score: f64,
+    /// 套件阈值
+    pub thresholds: SuiteThresholds,
+    /// 未满足的阈值说明
+    pub failures: Vec<String>,
+    /// 是否通过所有阈值
+    pub passed: bool,
+}
+
+impl EvalSuite {
+    /// 从YAML或JSON文件加载套件，并合并 `cases_file` 中的用例
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)?;
+
+        let mut suite: EvalSuite = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => serde_json::from_str(&content)?,
+            _ => serde_yaml::from_str(&content)
+                .map_err(|e| Error::Configuration(format!("无法解析评估套件 {}: {}", path.display(), e)))?,
+        };
+
+        if let Some(cases_file) = &suite.cases_file {
+            let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+            suite.tests.extend(EvalTestCase::read_jsonl(base_dir.join(cases_file))?);
+        }
+
+        suite.validate()?;
+        Ok(suite)
+    }
+
+    /// 校验套件定义
+    pub fn validate(&self) -> Result<()> {
+        if self.tests.is_empty() {
+            return Err(Error::Configuration(format!("评估套件 '{}' 没有测试用例", self.name)));
+        }
+        if self.metrics.is_empty() {
+            return Err(Error::Configuration(format!("评估套件 '{}' 没有配置指标", self.name)));
+        }
+        for metric in &self.metrics {
+            if let SuiteMetric::Regex { pattern, .. } = metric {
+                regex::Regex::new(pattern)
+                    .map_err(|e| Error::Configuration(format!("无效的正则表达式 '{}': {}", pattern, e)))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 为单个用例的输出打分
+    pub async fn score_case(&self, case: &EvalTestCase, output: CaseOutput) -> Result<CaseReport> {
+        let mut scores = Vec::new();
+        if output.error.is_none() {
+            for metric in &self.metrics {
+                if let Some(score) = metric.score(case, &output).await? {
+                    scores.push(MetricScore {
+                        metric: metric.name().to_string(),
+                        score,
+                        threshold: metric.threshold(),
+                        passed: score >= metric.threshold(),
+                    });
+                }
+            }
+        }
+
+        Ok(CaseReport {
+            id: case.id.clone(),
+            agent: case.agent.clone().or_else(|| self.agent.clone()),
+            input: case.input.clone(),
+            expected_output: case.expected_output.clone(),
+            passed: output.error.is_none() && scores.iter().all(|score| score.passed),
+            output,
+            scores,
+        })
+    }
+
+    /// 汇总用例报告并检查阈值
+    pub fn report(&self, cases: Vec<CaseReport>, started_at: DateTime<Utc>) -> SuiteReport {
+        let duration_ms = (Utc::now() - started_at).num_milliseconds().max(0) as u64;
+
+        let mut by_metric: Vec<(String, Vec<&MetricScore>)> = Vec::new();
+        for score in cases.iter().flat_map(|case| &case.scores) {
+            match by_metric.iter_mut().find(|(name, _)| name == &score.metric) {
+                Some((_, scores)) => scores.push(score),
+                None => by_metric.push((score.metric.clone(), vec![score])),
+            }
+        }
+
+        let metrics = by_metric
+            .into_iter()
+            .map(|(metric, scores)| MetricSummary {
+                metric,
+                mean: scores.iter().map(|s| s.score).sum::<f64>() / scores.len() as f64,
+                min: scores.iter().map(|s| s.score).fold(f64::INFINITY, f64::min),
+                pass_rate: scores.iter().filter(|s| s.passed).count() as f64 / scores.len() as f64,
+                count: scores.len(),
+            })
+            .collect();
+
+        let pass_rate = if cases.is_empty() {
+            0.0
+        } else {
+            cases.iter().filter(|case| case.passed).count() as f64 / cases.len() as f64
+        };
+
+        let all_scores: Vec<f64> = cases.iter().flat_map(|case| case.scores.iter().map(|s| s.score)).collect();
+        let mean_score = if all_scores.is_empty() {
+            0.0
+        } else {
+            all_scores.iter().sum::<f64>() / all_scores.len() as f64
+        };
+
+        let mut failures = Vec::new();
+        if pass_rate < self.thresholds.pass_rate {
+            failures.push(format!("用例通过率 {:.1}% 低于阈值 {:.1}%", pass_rate * 100.0, self.thresholds.pass_rate * 100.0));
+        }
+        if let Some(threshold) = self.thresholds.mean_score {
+            if mean_score < threshold {
+                failures.push(format!("平均得分 {:.3} 低于阈值 {:.3}", mean_score, threshold));
+            }
+        }
+
+        SuiteReport {
+            suite: self.name.clone(),
+            model: None,
+            variant: None,
+            started_at,
+            duration_ms,
+            cases,
+            metrics,
+            pass_rate,
+            mean_score,
+            thresholds: self.thresholds.clone(),
+            passed: failures.is_empty(),
+            failures,
+        }
+    }
+}
+
+impl SuiteReport {
+    /// 模型与变体组成的标签，用于区分同一套件的不同运行配置
+    pub fn variant_label(&self) -> String {
+        match (&self.model, &self.variant) {
+            (Some(model), Some(variant)) => format!("{} · {}", model, variant),
+            (Some(label), None) | (None, Some(label)) => label.clone(),
+            (None, None) => "default".to_string(),
+        }
+    }
+
+    /// 序列化为JSON报告
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// 生成独立的HTML报告
+    pub fn to_html(&self) -> String {
+        let status = if self.passed { "PASSED" } else { "FAILED" };
+        let mut html = format!(
+            concat!(
+                "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{suite} - Eval Report</title>\n",
+                "<style>body{{font-family:sans-serif;margin:2em}}table{{border-collapse:collapse;width:100%}}",
+                "td,th{{border:1px solid #ddd;padding:6px;vertical-align:top;text-align:left}}",
+                ".pass{{color:#2e7d32}}.fail{{color:#c62828}}pre{{white-space:pre-wrap;margin:0}}</style>\n",
+                "</head>\n<body>\n<h1>{suite} <span class=\"{class}\">{status}</span></h1>\n",
+                "<p>{started} · {duration} ms · pass rate {pass_rate:.1}% · mean score {mean:.3}</p>\n"
+            ),
+            suite = escape_html(&self.suite),
+            class = if self.passed { "pass" } else { "fail" },
+            status = status,
+            started = self.started_at.to_rfc3339(),
+            duration = self.duration_ms,
+            pass_rate = self.pass_rate * 100.0,
+            mean = self.mean_score,
+        );
+
+        for failure in &self.failures {
+            html.push_str(&format!("<p class=\"fail\">{}</p>\n", escape_html(failure)));
+        }
+
+        html.push_str("<h2>Metrics</h2>\n<table>\n<tr><th>Metric</th><th>Mean</th><th>Min</th><th>Pass rate</th><th>Cases</th></tr>\n");
+        for metric in &self.metrics {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{:.3}</td><td>{:.3}</td><td>{:.1}%</td><td>{}</td></tr>\n",
+                escape_html(&metric.metric), metric.mean, metric.min, metric.pass_rate * 100.0, metric.count,
+            ));
+        }
+        html.push_str("</table>\n<h2>Cases</h2>\n<table>\n<tr><th>Status</th><th>Input</th><th>Expected</th><th>Output</th><th>Scores</th></tr>\n");
+
+        for case in &self.cases {
+            let scores = case.scores
+                .iter()
+                .map(|s| format!("{}: {:.3}", escape_html(&s.metric), s.score))
+                .collect::<Vec<_>>()
+                .join("<br>");
+            let output = match &case.output.error {
+                Some(error) => format!("<span class=\"fail\">{}</span>", escape_html(error)),
+                None => escape_html(&case.output.output),
+            };
+            html.push_str(&format!(
+                "<tr><td class=\"{}\">{}</td><td><pre>{}</pre></td><td><pre>{}</pre></td><td><pre>{}</pre></td><td>{}</td></tr>\n",
+                if case.passed { "pass" } else { "fail" },
+                if case.passed { "PASS" } else { "FAIL" },
+                escape_html(&case.input),
+                escape_html(case.expected_output.as_deref().unwrap_or("")),
+                output,
+                scores,
+            ));
+        }
+
+        html.push_str("</table>\n</body>\n</html>\n");
+        html
+    }
+}
+
+/// 词级F1相似度，中日韩字符按单字切分
+pub fn token_f1(output: &str, expected: &str) -> f64 {
+    let output_tokens = tokenize(output);
+    let expected_tokens = tokenize(expected);
+    if output_tokens.is_empty() || expected_tokens.is_empty() {
+        return if output_tokens.is_empty() && expected_tokens.is_empty() { 1.0 } else { 0.0 };
+    }
+
+    let mut expected_counts: HashMap<&str, usize> = HashMap::new();
+    for token in &expected_tokens {
+        *expected_counts.entry(token.as_str()).or_default() += 1;
+    }
+
+    let mut overlap = 0;
+    for token in &output_tokens {
+        if let Some(count) = expected_counts.get_mut(token.as_str()) {
+            if *count > 0 {
+                *count -= 1;
+                overlap += 1;
+            }
+        }
+    }
+
+    if overlap == 0 {
+        return 0.0;
+    }
+    let precision = overlap as f64 / output_tokens.len() as f64;
+    let recall = overlap as f64 / expected_tokens.len() as f64;
+    2.0 * precision * recall / (precision + recall)
+}
+
+fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    for c in text.to_lowercase().chars() {
+        if c.is_alphanumeric() && c.is_ascii() {
+            current.push(c);
+            continue;
+        }
+        if !current.is_empty() {
+            tokens.push(std::mem::take(&mut current));
+        }
+        if c.is_alphanumeric() {
+            tokens.push(c.to_string());
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn suite() -> EvalSuite {
+        serde_yaml::from_str(
+            r#"
+name: smoke
+agent: assistant
+tests:
+  - input: "What is 2 + 2?"
+    expected_output: "4"
+  - input: "Capital of France?"
+    expected_output: "Paris"
+    expected_tools: [search]
+metrics:
+  - type: contains
+  - type: expected_tools
+  - type: length
+    max: 100
+thresholds:
+  pass_rate: 0.5
+"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_token_f1() {
+        assert_eq!(token_f1("the cat sat", "the cat sat"), 1.0);
+        assert_eq!(token_f1("dog", "cat"), 0.0);
+        assert!(token_f1("the cat", "the cat sat") > 0.7);
+        assert!(token_f1("北京是首都", "首都是北京") > 0.99);
+    }
+
+    #[tokio::test]
+    async fn test_suite_scoring_and_thresholds() {
+        let suite = suite();
+        suite.validate().unwrap();
+        assert_eq!(suite.thresholds.pass_rate, 0.5);
+        assert_eq!(suite.thresholds.mean_score, None);
+
+        let started_at = Utc::now();
+        let first = suite
+            .score_case(&suite.tests[0], CaseOutput { output: "The answer is 4".to_string(), ..CaseOutput::default() })
+            .await
+            .unwrap();
+        assert!(first.passed);
+        // expected_tools不适用于没有期望工具的用例
+        assert_eq!(first.scores.len(), 2);
+
+        let second = suite
+            .score_case(&suite.tests[1], CaseOutput { output: "Paris".to_string(), ..CaseOutput::default() })
+            .await
+            .unwrap();
+        assert!(!second.passed);
+
+        let report = suite.report(vec![first, second], started_at);
+        assert_eq!(report.pass_rate, 0.5);
+        assert!(report.passed);
+        assert!(report.to_html().contains("smoke"));
+
+        let mut strict = suite.clone();
+        strict.thresholds.pass_rate = 1.0;
+        let report = strict.report(report.cases, started_at);
+        assert!(!report.passed);
+        assert_eq!(report.failures.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_failed_run_is_not_scored() {
+        let suite = suite();
+        let report = suite
+            .score_case(&suite.tests[0], CaseOutput { error: Some("timeout".to_string()), ..CaseOutput::default() })
+            .await
+            .unwrap();
+        assert!(!report.passed);
+        assert!(report.scores.is_empty());
+    }
+}
@@ -1,7 +1,11 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
+use lumosai_core::agent::ReviewItem;
 
 /// 测试信息，描述评估的背景
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -115,4 +119,150 @@ impl Default for EvalOptions {
             log_results: true,
         }
     }
-} 
\ No newline at end of file
+}
+
+/// 评估测试用例，每行一个JSON对象存储在 `.jsonl` 文件中
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EvalTestCase {
+    /// 唯一ID
+    #[serde(default = "default_test_case_id")]
+    pub id: String,
+
+    /// 发送给代理的输入
+    pub input: String,
+
+    /// 期望的输出，为空时只运行不比对
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_output: Option<String>,
+
+    /// 目标代理名称
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent: Option<String>,
+
+    /// 生成时使用的模型
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+
+    /// 检索到的上下文片段
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub context: Vec<String>,
+
+    /// 期望被调用的工具
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub expected_tools: Vec<String>,
+
+    /// 测试标签
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
+    /// 额外元数据
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub metadata: HashMap<String, serde_json::Value>,
+}
+
+fn default_test_case_id() -> String {
+    Uuid::new_v4().to_string()
+}
+
+impl EvalTestCase {
+    /// 创建新的测试用例
+    pub fn new(input: impl Into<String>) -> Self {
+        Self {
+            id: default_test_case_id(),
+            input: input.into(),
+            expected_output: None,
+            agent: None,
+            model: None,
+            context: Vec::new(),
+            expected_tools: Vec::new(),
+            tags: Vec::new(),
+            metadata: HashMap::new(),
+        }
+    }
+
+    /// 由审核过的低置信度回复生成测试用例，期望输出为修正或认可的回复
+    ///
+    /// 未审核或已忽略的条目返回 `None`
+    pub fn from_review(item: &ReviewItem) -> Option<Self> {
+        let expected_output = item.expected_output()?;
+        let mut case = Self::new(item.input.clone());
+        case.id = format!("review-{}", item.id);
+        case.expected_output = Some(expected_output.to_string());
+        case.agent = Some(item.agent.clone());
+        case.tags = vec!["review".to_string(), item.source.clone()];
+        case.metadata.insert("review_id".to_string(), serde_json::Value::from(item.id.clone()));
+        case.metadata.insert("confidence".to_string(), serde_json::Value::from(item.confidence));
+        Some(case)
+    }
+
+    /// 从JSONL文件读取测试用例，忽略空行
+    pub fn read_jsonl<P: AsRef<Path>>(path: P) -> crate::Result<Vec<Self>> {
+        let content = fs::read_to_string(path)?;
+        content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| serde_json::from_str(line).map_err(Into::into))
+            .collect()
+    }
+
+    /// 将测试用例追加写入JSONL文件
+    pub fn append_jsonl<P: AsRef<Path>>(path: P, cases: &[Self]) -> crate::Result<()> {
+        if let Some(parent) = path.as_ref().parent() {
+            if !parent.as_os_str().is_empty() {
+                fs::create_dir_all(parent)?;
+            }
+        }
+
+        let mut file = fs::OpenOptions::new().create(true).append(true).open(path)?;
+        for case in cases {
+            writeln!(file, "{}", serde_json::to_string(case)?)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eval_test_case_jsonl_roundtrip() {
+        let path = std::env::temp_dir().join(format!("lumosai_eval_cases_{}.jsonl", Uuid::new_v4()));
+
+        let mut case = EvalTestCase::new("What is Rust?");
+        case.expected_output = Some("A systems programming language".to_string());
+        case.agent = Some("assistant".to_string());
+        case.expected_tools = vec!["web_search".to_string()];
+
+        EvalTestCase::append_jsonl(&path, &[case.clone()]).unwrap();
+        EvalTestCase::append_jsonl(&path, &[EvalTestCase::new("Hello")]).unwrap();
+
+        let cases = EvalTestCase::read_jsonl(&path).unwrap();
+        assert_eq!(cases.len(), 2);
+        assert_eq!(cases[0], case);
+        assert_eq!(cases[1].input, "Hello");
+
+        // 手写的用例可以省略ID
+        let parsed: EvalTestCase = serde_json::from_str(r#"{"input": "hi"}"#).unwrap();
+        assert!(!parsed.id.is_empty());
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_eval_test_case_from_review() {
+        use lumosai_core::agent::{ReviewDecision, ReviewQueue};
+
+        let queue = ReviewQueue::default();
+        let item = queue.consider("support", "Refund after 60 days?", "Yes.", 0.2, "judge", None).unwrap();
+        assert!(EvalTestCase::from_review(&item).is_none());
+
+        let decision = ReviewDecision::Correct { correction: "Only within 30 days.".to_string() };
+        let reviewed = queue.review(&item.id, decision, None).unwrap();
+        let case = EvalTestCase::from_review(&reviewed).unwrap();
+        assert_eq!(case.expected_output.as_deref(), Some("Only within 30 days."));
+        assert_eq!(case.agent.as_deref(), Some("support"));
+        assert_eq!(case.tags, vec!["review", "judge"]);
+        assert_eq!(case.metadata["review_id"], item.id.as_str());
+    }
+}
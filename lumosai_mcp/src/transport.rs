@@ -8,6 +8,7 @@ use futures::StreamExt;
 use reqwest::Client;
 use std::sync::Arc;
 use tokio_util::codec::{FramedRead, LinesCodec};
+use lumosai_core::config::http_client_for;
 use futures::stream::BoxStream;
 use futures::TryStreamExt;
 
@@ -235,8 +236,8 @@ pub struct SSETransport {
 impl SSETransport {
     pub fn new(params: SSEServerParameters) -> Self {
         Self {
+            client: http_client_for(params.url.as_str()),
             params,
-            client: Client::new(),
             is_connected: false,
             broadcast_tx: Arc::new(Mutex::new(None)),
             outbound_messages: Arc::new(Mutex::new(Vec::new())),
@@ -0,0 +1,258 @@
+//! Agent能力注册表
+//!
+//! 记录每个Agent声明的能力（技能标签、成本、延迟SLO）以及当前正在处理的任务数，
+//! 按任务所需技能查询候选Agent，并在候选中优先选择负载最低的Agent。
+
+use std::collections::BTreeSet;
+
+use dashmap::DashMap;
+use serde::{Serialize, Deserialize};
+
+use crate::types::{AgentId, AgentCapability};
+
+/// 能力查询条件
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CapabilityQuery {
+    /// 需要的技能，Agent须全部具备
+    pub skills: Vec<String>,
+    /// 可接受的最大成本
+    #[serde(default)]
+    pub max_cost: Option<f64>,
+    /// 可接受的最大延迟SLO（毫秒）
+    #[serde(default)]
+    pub max_latency_ms: Option<u64>,
+}
+
+impl CapabilityQuery {
+    /// 按所需技能创建查询
+    pub fn new<I, S>(skills: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            skills: skills.into_iter().map(Into::into).collect(),
+            max_cost: None,
+            max_latency_ms: None,
+        }
+    }
+
+    /// 设置最大成本
+    pub fn with_max_cost(mut self, max_cost: f64) -> Self {
+        self.max_cost = Some(max_cost);
+        self
+    }
+
+    /// 设置最大延迟SLO
+    pub fn with_max_latency(mut self, latency: std::time::Duration) -> Self {
+        self.max_latency_ms = Some(latency.as_millis() as u64);
+        self
+    }
+}
+
+/// 满足查询的候选Agent
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CapabilityMatch {
+    /// Agent ID
+    pub agent_id: AgentId,
+    /// 完成任务的成本，为所用能力的成本之和，未声明成本的能力记为0
+    pub cost: f64,
+    /// 完成任务的延迟SLO，为所用能力中最大的SLO；有能力未声明SLO时为空
+    pub latency_slo_ms: Option<u64>,
+    /// 当前正在处理的任务数
+    pub load: usize,
+}
+
+/// Agent能力注册表
+#[derive(Debug, Default)]
+pub struct CapabilityRegistry {
+    /// 各Agent的能力
+    capabilities: DashMap<AgentId, Vec<AgentCapability>>,
+    /// 各Agent正在处理的任务数
+    loads: DashMap<AgentId, usize>,
+}
+
+impl CapabilityRegistry {
+    /// 创建空的注册表
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 注册Agent的能力，替换之前注册的能力
+    pub fn register(&self, agent_id: AgentId, capabilities: Vec<AgentCapability>) {
+        self.capabilities.insert(agent_id, capabilities);
+    }
+
+    /// 注销Agent
+    pub fn unregister(&self, agent_id: &AgentId) {
+        self.capabilities.remove(agent_id);
+        self.loads.remove(agent_id);
+    }
+
+    /// 获取Agent的能力
+    pub fn capabilities(&self, agent_id: &AgentId) -> Option<Vec<AgentCapability>> {
+        self.capabilities.get(agent_id).map(|entry| entry.value().clone())
+    }
+
+    /// 所有已注册的技能，包括能力名称
+    pub fn skills(&self) -> Vec<String> {
+        let mut skills = BTreeSet::new();
+        for entry in self.capabilities.iter() {
+            for capability in entry.value() {
+                skills.insert(capability.name.clone());
+                skills.extend(capability.skills.iter().cloned());
+            }
+        }
+        skills.into_iter().collect()
+    }
+
+    /// 查询满足条件的Agent，按负载、成本、延迟SLO从优到劣排序
+    pub fn find(&self, query: &CapabilityQuery) -> Vec<CapabilityMatch> {
+        let mut matches: Vec<CapabilityMatch> = self.capabilities.iter()
+            .filter_map(|entry| self.evaluate(entry.key(), entry.value(), query))
+            .collect();
+
+        matches.sort_by(|a, b| {
+            a.load.cmp(&b.load)
+                .then(a.cost.total_cmp(&b.cost))
+                .then(a.latency_slo_ms.unwrap_or(u64::MAX).cmp(&b.latency_slo_ms.unwrap_or(u64::MAX)))
+                .then(a.agent_id.as_str().cmp(b.agent_id.as_str()))
+        });
+        matches
+    }
+
+    /// 选择最合适的Agent
+    pub fn select(&self, query: &CapabilityQuery) -> Option<AgentId> {
+        self.find(query).into_iter().next().map(|candidate| candidate.agent_id)
+    }
+
+    /// 记录Agent开始处理一个任务
+    pub fn begin_task(&self, agent_id: &AgentId) {
+        *self.loads.entry(agent_id.clone()).or_default() += 1;
+    }
+
+    /// 记录Agent完成一个任务
+    pub fn finish_task(&self, agent_id: &AgentId) {
+        if let Some(mut load) = self.loads.get_mut(agent_id) {
+            *load = load.saturating_sub(1);
+        }
+    }
+
+    /// Agent正在处理的任务数
+    pub fn load(&self, agent_id: &AgentId) -> usize {
+        self.loads.get(agent_id).map_or(0, |load| *load)
+    }
+
+    /// 判断Agent是否满足查询；每项技能选用成本最低的能力
+    fn evaluate(&self, agent_id: &AgentId, capabilities: &[AgentCapability], query: &CapabilityQuery) -> Option<CapabilityMatch> {
+        let mut used = BTreeSet::new();
+        for skill in &query.skills {
+            let (index, _) = capabilities.iter().enumerate()
+                .filter(|(_, capability)| capability.provides(skill))
+                .min_by(|(_, a), (_, b)| a.cost.unwrap_or_default().total_cmp(&b.cost.unwrap_or_default()))?;
+            used.insert(index);
+        }
+
+        let cost: f64 = used.iter().map(|index| capabilities[*index].cost.unwrap_or_default()).sum();
+        let latency_slo_ms = used.iter()
+            .map(|index| capabilities[*index].latency_slo_ms)
+            .try_fold(0, |max, latency| latency.map(|latency| max.max(latency)));
+
+        if query.max_cost.is_some_and(|max_cost| cost > max_cost) {
+            return None;
+        }
+        if let Some(max_latency) = query.max_latency_ms {
+            // 未声明SLO的Agent无法保证延迟
+            if latency_slo_ms.is_none_or(|latency| latency > max_latency) {
+                return None;
+            }
+        }
+
+        Some(CapabilityMatch {
+            agent_id: agent_id.clone(),
+            cost,
+            latency_slo_ms,
+            load: self.load(agent_id),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn registry() -> CapabilityRegistry {
+        let registry = CapabilityRegistry::new();
+        registry.register(AgentId::from_str("translator"), vec![
+            AgentCapability::new("translate", "Translation")
+                .with_skill("french")
+                .with_skill("german")
+                .with_cost(2.0)
+                .with_latency_slo(Duration::from_millis(500)),
+        ]);
+        registry.register(AgentId::from_str("polyglot"), vec![
+            AgentCapability::new("translate", "Translation")
+                .with_skill("french")
+                .with_cost(5.0)
+                .with_latency_slo(Duration::from_millis(200)),
+            AgentCapability::new("summarize", "Summarization").with_cost(1.0),
+        ]);
+        registry
+    }
+
+    #[test]
+    fn test_find_by_skills() {
+        let registry = registry();
+
+        let matches = registry.find(&CapabilityQuery::new(["french"]));
+        let ids: Vec<&str> = matches.iter().map(|m| m.agent_id.as_str()).collect();
+        // 负载相同时成本低的优先
+        assert_eq!(ids, ["translator", "polyglot"]);
+
+        assert_eq!(registry.select(&CapabilityQuery::new(["german"])), Some(AgentId::from_str("translator")));
+        assert!(registry.find(&CapabilityQuery::new(["french", "spanish"])).is_empty());
+
+        let both = registry.find(&CapabilityQuery::new(["french", "summarize"]));
+        assert_eq!(both.len(), 1);
+        assert_eq!(both[0].cost, 6.0);
+        // summarize没有声明SLO
+        assert_eq!(both[0].latency_slo_ms, None);
+
+        assert_eq!(registry.skills(), ["french", "german", "summarize", "translate"]);
+    }
+
+    #[test]
+    fn test_cost_and_latency_limits() {
+        let registry = registry();
+
+        let cheap = CapabilityQuery::new(["french"]).with_max_cost(3.0);
+        assert_eq!(registry.select(&cheap), Some(AgentId::from_str("translator")));
+
+        let fast = CapabilityQuery::new(["french"]).with_max_latency(Duration::from_millis(300));
+        assert_eq!(registry.select(&fast), Some(AgentId::from_str("polyglot")));
+
+        let unbounded = CapabilityQuery::new(["summarize"]).with_max_latency(Duration::from_secs(10));
+        assert_eq!(registry.select(&unbounded), None);
+    }
+
+    #[test]
+    fn test_load_aware_selection() {
+        let registry = registry();
+        let translator = AgentId::from_str("translator");
+        let query = CapabilityQuery::new(["french"]);
+
+        registry.begin_task(&translator);
+        assert_eq!(registry.load(&translator), 1);
+        assert_eq!(registry.select(&query), Some(AgentId::from_str("polyglot")));
+
+        registry.finish_task(&translator);
+        registry.finish_task(&translator);
+        assert_eq!(registry.load(&translator), 0);
+        assert_eq!(registry.select(&query), Some(translator.clone()));
+
+        registry.unregister(&translator);
+        assert!(registry.capabilities(&translator).is_none());
+        assert_eq!(registry.select(&query), Some(AgentId::from_str("polyglot")));
+    }
+}
@@ -261,7 +261,7 @@ impl ServiceDiscovery for InMemoryServiceDiscovery {
             
             // 检查能力
             let has_capabilities = query.required_capabilities.iter().all(|required_cap| {
-                registration.capabilities.iter().any(|cap| &cap.name == required_cap)
+                registration.capabilities.iter().any(|cap| cap.provides(required_cap))
             });
             
             if !query.required_capabilities.is_empty() && !has_capabilities {
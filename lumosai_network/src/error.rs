@@ -16,6 +16,10 @@ pub enum Error {
     Topology(String),
     /// 服务发现错误
     Discovery(String),
+    /// 消息多次失败，已移入死信队列
+    DeadLettered(String),
+    /// 消息持久化错误
+    Persistence(String),
     /// 序列化/反序列化错误
     Serialization(String),
     /// IO错误
@@ -32,6 +36,8 @@ impl fmt::Display for Error {
             Error::Routing(msg) => write!(f, "路由错误: {}", msg),
             Error::Topology(msg) => write!(f, "拓扑错误: {}", msg),
             Error::Discovery(msg) => write!(f, "服务发现错误: {}", msg),
+            Error::DeadLettered(id) => write!(f, "消息已移入死信队列: {}", id),
+            Error::Persistence(msg) => write!(f, "消息持久化错误: {}", msg),
             Error::Serialization(msg) => write!(f, "序列化/反序列化错误: {}", msg),
             Error::Io(err) => write!(f, "IO错误: {}", err),
             Error::Other(msg) => write!(f, "其他错误: {}", msg),
@@ -53,5 +59,12 @@ impl From<serde_json::Error> for Error {
     }
 }
 
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+impl From<sqlx::Error> for Error {
+    fn from(err: sqlx::Error) -> Self {
+        Error::Persistence(err.to_string())
+    }
+}
+
 /// 结果类型
 pub type Result<T> = result::Result<T, Error>; 
\ No newline at end of file
@@ -0,0 +1,427 @@
+//! 网络拓扑观测
+//!
+//! 汇总网络中的节点、边、消息速率和健康状态，生成可序列化为JSON的[`TopologySnapshot`]，
+//! 供CLI的`visualize`命令和UI绘制实时的Agent网络图。
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use dashmap::DashMap;
+use serde::{Serialize, Deserialize};
+
+use crate::topology::TopologyType;
+use crate::types::{AgentId, AgentStatus, AgentType};
+
+/// 默认的消息速率统计窗口
+const DEFAULT_RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// 一对Agent之间的消息统计
+#[derive(Debug)]
+struct EdgeTraffic {
+    /// 累计消息数
+    total: u64,
+    /// 统计窗口内各消息的发送时间
+    recent: VecDeque<Instant>,
+}
+
+/// 一对Agent之间的消息流量
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrafficStats {
+    /// 发送者
+    pub source: AgentId,
+    /// 接收者
+    pub target: AgentId,
+    /// 累计消息数
+    pub message_count: u64,
+    /// 统计窗口内的平均每秒消息数
+    pub messages_per_second: f64,
+}
+
+/// 消息流量监控，按发送者和接收者统计已投递的消息
+#[derive(Debug)]
+pub struct TrafficMonitor {
+    /// 速率统计窗口
+    window: Duration,
+    /// 各(发送者, 接收者)的统计
+    edges: DashMap<(AgentId, AgentId), EdgeTraffic>,
+}
+
+impl Default for TrafficMonitor {
+    fn default() -> Self {
+        Self::new(DEFAULT_RATE_WINDOW)
+    }
+}
+
+impl TrafficMonitor {
+    /// 创建流量监控，消息速率按`window`内的消息数计算
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window: window.max(Duration::from_secs(1)),
+            edges: DashMap::new(),
+        }
+    }
+
+    /// 记录一条已投递的消息
+    pub fn record(&self, source: &AgentId, target: &AgentId) {
+        let now = Instant::now();
+        let mut traffic = self.edges.entry((source.clone(), target.clone()))
+            .or_insert_with(|| EdgeTraffic { total: 0, recent: VecDeque::new() });
+        traffic.total += 1;
+        traffic.recent.push_back(now);
+        Self::expire(&mut traffic.recent, now, self.window);
+    }
+
+    /// 各(发送者, 接收者)的消息流量
+    pub fn stats(&self) -> Vec<TrafficStats> {
+        let now = Instant::now();
+        self.edges.iter_mut()
+            .map(|mut entry| {
+                Self::expire(&mut entry.recent, now, self.window);
+                let (source, target) = entry.key().clone();
+                TrafficStats {
+                    source,
+                    target,
+                    message_count: entry.total,
+                    messages_per_second: entry.recent.len() as f64 / self.window.as_secs_f64(),
+                }
+            })
+            .collect()
+    }
+
+    /// 清除Agent相关的统计
+    pub fn remove_agent(&self, agent_id: &AgentId) {
+        self.edges.retain(|(source, target), _| source != agent_id && target != agent_id);
+    }
+
+    fn expire(recent: &mut VecDeque<Instant>, now: Instant, window: Duration) {
+        while recent.front().is_some_and(|sent| now.duration_since(*sent) > window) {
+            recent.pop_front();
+        }
+    }
+}
+
+/// 节点健康状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeHealth {
+    /// 正在运行且心跳正常
+    Healthy,
+    /// 已初始化或已暂停，未处理消息
+    Idle,
+    /// 正在运行但服务发现中的心跳已过期
+    Stale,
+    /// 已停止或处于错误状态
+    Down,
+}
+
+impl NodeHealth {
+    /// 根据Agent状态和心跳是否正常判断健康状态
+    pub fn evaluate(status: AgentStatus, heartbeat_ok: bool) -> Self {
+        match status {
+            AgentStatus::Running if heartbeat_ok => NodeHealth::Healthy,
+            AgentStatus::Running => NodeHealth::Stale,
+            AgentStatus::Initialized | AgentStatus::Paused => NodeHealth::Idle,
+            AgentStatus::Stopped | AgentStatus::Error => NodeHealth::Down,
+        }
+    }
+}
+
+/// 节点快照
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeSnapshot {
+    /// Agent ID
+    pub id: AgentId,
+    /// Agent名称
+    pub name: String,
+    /// Agent类型
+    pub agent_type: AgentType,
+    /// Agent状态
+    pub status: AgentStatus,
+    /// 健康状态
+    pub health: NodeHealth,
+    /// 技能，包括能力名称
+    pub skills: Vec<String>,
+    /// 正在处理的按技能路由的任务数
+    pub load: usize,
+    /// 累计发送的消息数
+    pub messages_sent: u64,
+    /// 累计接收的消息数
+    pub messages_received: u64,
+    /// 每秒收发的消息数
+    pub messages_per_second: f64,
+}
+
+/// 边快照
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EdgeSnapshot {
+    /// 源节点
+    pub source: AgentId,
+    /// 目标节点
+    pub target: AgentId,
+    /// 是否为拓扑中的边；为false时表示两个Agent之间有消息往来但拓扑中没有直接连接
+    pub in_topology: bool,
+    /// 边权重
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<f64>,
+    /// 延迟
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency: Option<f64>,
+    /// 边标签
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// 累计消息数
+    pub message_count: u64,
+    /// 每秒消息数
+    pub messages_per_second: f64,
+}
+
+/// 网络概况
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NetworkSummary {
+    /// 节点数
+    pub node_count: usize,
+    /// 边数
+    pub edge_count: usize,
+    /// 健康节点数
+    pub healthy_nodes: usize,
+    /// 累计消息数
+    pub total_messages: u64,
+    /// 每秒消息数
+    pub messages_per_second: f64,
+    /// 死信数量
+    pub dead_letters: usize,
+}
+
+/// 网络拓扑快照
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TopologySnapshot {
+    /// 网络ID
+    pub network_id: String,
+    /// 拓扑类型
+    pub topology_type: TopologyType,
+    /// 生成时间（毫秒时间戳）
+    pub generated_at: u64,
+    /// 节点
+    pub nodes: Vec<NodeSnapshot>,
+    /// 边
+    pub edges: Vec<EdgeSnapshot>,
+    /// 概况
+    pub summary: NetworkSummary,
+}
+
+impl TopologySnapshot {
+    /// 由节点、拓扑中的边和消息流量组装快照
+    pub(crate) fn assemble(
+        network_id: String,
+        topology_type: TopologyType,
+        mut nodes: Vec<NodeSnapshot>,
+        topology_edges: Vec<EdgeSnapshot>,
+        traffic: Vec<TrafficStats>,
+        dead_letters: usize,
+    ) -> Self {
+        let mut traffic: HashMap<(AgentId, AgentId), TrafficStats> = traffic.into_iter()
+            .map(|stats| ((stats.source.clone(), stats.target.clone()), stats))
+            .collect();
+
+        // 节点的收发统计
+        let mut sent: HashMap<&AgentId, (u64, f64)> = HashMap::new();
+        let mut received: HashMap<&AgentId, (u64, f64)> = HashMap::new();
+        for stats in traffic.values() {
+            let entry = sent.entry(&stats.source).or_default();
+            entry.0 += stats.message_count;
+            entry.1 += stats.messages_per_second;
+            let entry = received.entry(&stats.target).or_default();
+            entry.0 += stats.message_count;
+            entry.1 += stats.messages_per_second;
+        }
+        for node in &mut nodes {
+            let (sent_count, sent_rate) = sent.get(&node.id).copied().unwrap_or_default();
+            let (received_count, received_rate) = received.get(&node.id).copied().unwrap_or_default();
+            node.messages_sent = sent_count;
+            node.messages_received = received_count;
+            node.messages_per_second = sent_rate + received_rate;
+        }
+        nodes.sort_by(|a, b| a.id.as_str().cmp(b.id.as_str()));
+
+        // 拓扑中的边附上流量，其余有流量的Agent对作为额外的边
+        let mut edges: Vec<EdgeSnapshot> = topology_edges.into_iter()
+            .map(|mut edge| {
+                if let Some(stats) = traffic.remove(&(edge.source.clone(), edge.target.clone())) {
+                    edge.message_count = stats.message_count;
+                    edge.messages_per_second = stats.messages_per_second;
+                }
+                edge
+            })
+            .collect();
+        edges.extend(traffic.into_values().map(|stats| EdgeSnapshot {
+            source: stats.source,
+            target: stats.target,
+            in_topology: false,
+            weight: None,
+            latency: None,
+            label: None,
+            message_count: stats.message_count,
+            messages_per_second: stats.messages_per_second,
+        }));
+        edges.sort_by(|a, b| (a.source.as_str(), a.target.as_str()).cmp(&(b.source.as_str(), b.target.as_str())));
+
+        let summary = NetworkSummary {
+            node_count: nodes.len(),
+            edge_count: edges.len(),
+            healthy_nodes: nodes.iter().filter(|node| node.health == NodeHealth::Healthy).count(),
+            total_messages: edges.iter().map(|edge| edge.message_count).sum(),
+            messages_per_second: edges.iter().map(|edge| edge.messages_per_second).sum(),
+            dead_letters,
+        };
+
+        let generated_at = SystemTime::now().duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_millis() as u64);
+        Self {
+            network_id,
+            topology_type,
+            generated_at,
+            nodes,
+            edges,
+            summary,
+        }
+    }
+
+    /// 转换为Graphviz DOT格式，节点按健康状态着色，没有直接连接的消息往来用虚线表示
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph network {\n");
+        dot.push_str("  node [shape=box, style=filled];\n");
+
+        for node in &self.nodes {
+            let color = match node.health {
+                NodeHealth::Healthy => "lightgreen",
+                NodeHealth::Idle => "lightgrey",
+                NodeHealth::Stale => "lightyellow",
+                NodeHealth::Down => "lightpink",
+            };
+            let name = if node.name.is_empty() { node.id.as_str() } else { node.name.as_str() };
+            let _ = writeln!(
+                dot,
+                "  \"{}\" [label=\"{}\\n{:?} | {:.2} msg/s\", fillcolor={}];",
+                escape(node.id.as_str()), escape(name), node.status, node.messages_per_second, color
+            );
+        }
+
+        for edge in &self.edges {
+            let style = if edge.in_topology { "solid" } else { "dashed" };
+            let label = if edge.message_count > 0 {
+                format!("{} ({:.2}/s)", edge.message_count, edge.messages_per_second)
+            } else {
+                edge.label.clone().unwrap_or_default()
+            };
+            let _ = writeln!(
+                dot,
+                "  \"{}\" -> \"{}\" [label=\"{}\", style={}];",
+                escape(edge.source.as_str()), escape(edge.target.as_str()), escape(&label), style
+            );
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// 转义DOT字符串中的引号和反斜杠
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(id: &str, status: AgentStatus) -> NodeSnapshot {
+        NodeSnapshot {
+            id: AgentId::from_str(id),
+            name: id.to_string(),
+            agent_type: AgentType::Worker,
+            status,
+            health: NodeHealth::evaluate(status, true),
+            skills: Vec::new(),
+            load: 0,
+            messages_sent: 0,
+            messages_received: 0,
+            messages_per_second: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_traffic_monitor() {
+        let monitor = TrafficMonitor::new(Duration::from_secs(10));
+        let a = AgentId::from_str("a");
+        let b = AgentId::from_str("b");
+
+        for _ in 0..5 {
+            monitor.record(&a, &b);
+        }
+        monitor.record(&b, &a);
+
+        let mut stats = monitor.stats();
+        stats.sort_by(|x, y| x.source.as_str().cmp(y.source.as_str()));
+        assert_eq!(stats[0].message_count, 5);
+        assert_eq!(stats[0].messages_per_second, 0.5);
+        assert_eq!(stats[1].message_count, 1);
+
+        monitor.remove_agent(&a);
+        assert!(monitor.stats().is_empty());
+    }
+
+    #[test]
+    fn test_snapshot_assembly() {
+        let a = AgentId::from_str("a");
+        let b = AgentId::from_str("b");
+        let c = AgentId::from_str("c");
+        let topology_edge = EdgeSnapshot {
+            source: a.clone(),
+            target: b.clone(),
+            in_topology: true,
+            weight: Some(1.0),
+            latency: None,
+            label: None,
+            message_count: 0,
+            messages_per_second: 0.0,
+        };
+        let traffic = vec![
+            TrafficStats { source: a.clone(), target: b.clone(), message_count: 4, messages_per_second: 0.5 },
+            TrafficStats { source: a.clone(), target: c.clone(), message_count: 1, messages_per_second: 0.25 },
+        ];
+
+        let snapshot = TopologySnapshot::assemble(
+            "net".to_string(),
+            TopologyType::Custom,
+            vec![node("b", AgentStatus::Running), node("a", AgentStatus::Running), node("c", AgentStatus::Stopped)],
+            vec![topology_edge],
+            traffic,
+            2,
+        );
+
+        assert_eq!(snapshot.nodes[0].id, a);
+        assert_eq!(snapshot.nodes[0].messages_sent, 5);
+        assert_eq!(snapshot.nodes[0].messages_per_second, 0.75);
+        assert_eq!(snapshot.nodes[1].messages_received, 4);
+        assert_eq!(snapshot.nodes[2].health, NodeHealth::Down);
+
+        assert_eq!(snapshot.edges.len(), 2);
+        assert!(snapshot.edges[0].in_topology);
+        assert_eq!(snapshot.edges[0].message_count, 4);
+        assert!(!snapshot.edges[1].in_topology);
+
+        assert_eq!(snapshot.summary.healthy_nodes, 2);
+        assert_eq!(snapshot.summary.total_messages, 5);
+        assert_eq!(snapshot.summary.dead_letters, 2);
+
+        let json = serde_json::to_value(&snapshot).unwrap();
+        assert_eq!(json["nodes"][2]["health"], "down");
+        assert_eq!(json["edges"][1]["in_topology"], false);
+
+        let dot = snapshot.to_dot();
+        assert!(dot.contains("\"a\" -> \"b\" [label=\"4 (0.50/s)\", style=solid];"));
+        assert!(dot.contains("\"a\" -> \"c\" [label=\"1 (0.25/s)\", style=dashed];"));
+        assert!(dot.contains("fillcolor=lightpink"));
+    }
+}
@@ -7,6 +7,9 @@
 //! - 消息路由和传递
 //! - 网络事件处理
 //! - 分布式Agent协调
+//! - 基于技能和负载的任务路由
+//! - 消息持久化和至少一次投递
+//! - 网络拓扑、消息速率和健康状态观测
 
 pub mod error;
 pub mod types;
@@ -15,15 +18,21 @@ pub mod message;
 pub mod router;
 pub mod topology;
 pub mod discovery;
+pub mod capability;
+pub mod persistence;
+pub mod introspection;
 
 // 重新导出主要类型
 pub use error::Error;
 pub use types::{AgentId, AgentType, AgentStatus, AgentCapability};
 pub use network::{AgentNetwork, AgentNode};
-pub use message::{Message, MessageType, MessageStatus};
+pub use message::{Message, MessageType, MessageStatus, DeadLetter, DeadLetterQueue, FailureKind};
 pub use router::MessageRouter;
 pub use topology::{NetworkTopology, TopologyType};
 pub use discovery::ServiceDiscovery;
+pub use capability::{CapabilityRegistry, CapabilityQuery, CapabilityMatch};
+pub use persistence::{MessageLog, InMemoryMessageLog, LoggedMessage};
+pub use introspection::{TopologySnapshot, NodeSnapshot, EdgeSnapshot, NodeHealth, TrafficMonitor};
 
 /// 创建Agent网络
 pub async fn create_agent_network() -> AgentNetwork {
@@ -3,10 +3,15 @@
 use std::collections::HashMap;
 use std::fmt::{self, Display};
 use std::time::{Duration, SystemTime};
+use dashmap::DashMap;
 use serde::{Serialize, Deserialize};
 use uuid::Uuid;
 
 use crate::types::AgentId;
+use crate::capability::CapabilityQuery;
+
+/// 消息元数据中存放能力查询的键
+const CAPABILITY_QUERY_KEY: &str = "capability_query";
 
 /// 消息ID类型
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
@@ -183,6 +188,28 @@ impl Message {
         self
     }
     
+    /// 声明处理该消息所需的技能，未指定接收者时路由器据此选择Agent
+    pub fn with_required_skills<I, S>(self, skills: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.with_capability_query(CapabilityQuery::new(skills))
+    }
+    
+    /// 设置能力查询条件，包括所需技能、最大成本和延迟SLO
+    pub fn with_capability_query(mut self, query: CapabilityQuery) -> Self {
+        let query = serde_json::to_value(query).unwrap_or_default();
+        self.metadata.insert(CAPABILITY_QUERY_KEY.to_string(), query);
+        self
+    }
+    
+    /// 获取能力查询条件
+    pub fn capability_query(&self) -> Option<CapabilityQuery> {
+        self.metadata.get(CAPABILITY_QUERY_KEY)
+            .and_then(|query| serde_json::from_value(query.clone()).ok())
+    }
+    
     /// 将状态设置为已发送
     pub fn mark_as_sent(&mut self) {
         self.status = MessageStatus::Sent;
@@ -198,6 +225,11 @@ impl Message {
         self.status = MessageStatus::Processed;
     }
     
+    /// 将状态设置为已失败
+    pub fn mark_as_failed(&mut self) {
+        self.status = MessageStatus::Failed;
+    }
+    
     /// 创建对此消息的回复
     pub fn create_reply(&self, content: impl Into<serde_json::Value>) -> Self {
         Self {
@@ -225,6 +257,176 @@ impl Message {
     }
 }
 
+/// 默认最大尝试次数
+const DEFAULT_MAX_ATTEMPTS: usize = 3;
+
+/// 失败发生的阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FailureKind {
+    /// 投递失败，例如接收者不存在或通道已关闭
+    Delivery,
+    /// 接收者处理消息失败
+    Processing,
+}
+
+/// 一次失败记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MessageFailure {
+    /// 失败阶段
+    pub kind: FailureKind,
+    /// 错误信息
+    pub error: String,
+    /// 失败时的接收者
+    pub receivers: Vec<AgentId>,
+    /// 失败时间
+    pub failed_at: SystemTime,
+}
+
+/// 死信，即多次失败后不再投递的消息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetter {
+    /// 原始消息，状态为已失败
+    pub message: Message,
+    /// 历次失败记录，按时间先后排列
+    pub failures: Vec<MessageFailure>,
+    /// 移入死信队列的时间
+    pub dead_lettered_at: SystemTime,
+}
+
+impl DeadLetter {
+    /// 尝试次数
+    pub fn attempts(&self) -> usize {
+        self.failures.len()
+    }
+    
+    /// 最后一次失败
+    pub fn last_failure(&self) -> Option<&MessageFailure> {
+        self.failures.last()
+    }
+}
+
+/// 死信队列
+///
+/// 记录每条消息的失败次数，消息投递或处理失败达到最大尝试次数后被移入队列，
+/// 不再自动重试，可以查看、重新投递或清除。
+#[derive(Debug)]
+pub struct DeadLetterQueue {
+    /// 最大尝试次数
+    max_attempts: usize,
+    /// 尚未移入队列的消息的失败记录
+    failures: DashMap<MessageId, Vec<MessageFailure>>,
+    /// 死信
+    dead_letters: DashMap<MessageId, DeadLetter>,
+}
+
+impl Default for DeadLetterQueue {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_ATTEMPTS)
+    }
+}
+
+impl DeadLetterQueue {
+    /// 创建死信队列，消息失败`max_attempts`次后移入队列
+    pub fn new(max_attempts: usize) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            failures: DashMap::new(),
+            dead_letters: DashMap::new(),
+        }
+    }
+    
+    /// 最大尝试次数
+    pub fn max_attempts(&self) -> usize {
+        self.max_attempts
+    }
+    
+    /// 记录一次失败，达到最大尝试次数时将消息移入队列并返回true
+    pub fn record_failure(&self, message: &Message, kind: FailureKind, error: impl Into<String>) -> bool {
+        let failure = MessageFailure {
+            kind,
+            error: error.into(),
+            receivers: message.receivers.clone(),
+            failed_at: SystemTime::now(),
+        };
+        
+        let attempts = {
+            let mut failures = self.failures.entry(message.id.clone()).or_default();
+            failures.push(failure);
+            failures.len()
+        };
+        if attempts < self.max_attempts {
+            return false;
+        }
+        
+        let failures = self.failures.remove(&message.id).map(|(_, failures)| failures).unwrap_or_default();
+        let mut message = message.clone();
+        message.mark_as_failed();
+        log::warn!("消息 {} 失败{}次，已移入死信队列", message.id, failures.len());
+        self.dead_letters.insert(message.id.clone(), DeadLetter {
+            message,
+            failures,
+            dead_lettered_at: SystemTime::now(),
+        });
+        true
+    }
+    
+    /// 消息已成功处理，清除其失败记录
+    pub fn acknowledge(&self, id: &MessageId) {
+        self.failures.remove(id);
+    }
+    
+    /// 消息已失败的次数，包括已移入队列的消息
+    pub fn attempts(&self, id: &MessageId) -> usize {
+        if let Some(dead_letter) = self.dead_letters.get(id) {
+            return dead_letter.attempts();
+        }
+        self.failures.get(id).map_or(0, |failures| failures.len())
+    }
+    
+    /// 查看死信
+    pub fn get(&self, id: &MessageId) -> Option<DeadLetter> {
+        self.dead_letters.get(id).map(|entry| entry.value().clone())
+    }
+    
+    /// 所有死信，按移入时间先后排列
+    pub fn list(&self) -> Vec<DeadLetter> {
+        let mut dead_letters: Vec<DeadLetter> = self.dead_letters.iter().map(|entry| entry.value().clone()).collect();
+        dead_letters.sort_by_key(|dead_letter| dead_letter.dead_lettered_at);
+        dead_letters
+    }
+    
+    /// 取出死信以便重新投递，消息状态重置为已创建，失败次数从零开始
+    pub fn take(&self, id: &MessageId) -> Option<Message> {
+        self.dead_letters.remove(id).map(|(_, dead_letter)| {
+            let mut message = dead_letter.message;
+            message.status = MessageStatus::Created;
+            message
+        })
+    }
+    
+    /// 清除一条死信，返回是否存在
+    pub fn purge(&self, id: &MessageId) -> bool {
+        self.dead_letters.remove(id).is_some()
+    }
+    
+    /// 清除所有死信，返回清除的数量
+    pub fn purge_all(&self) -> usize {
+        let count = self.dead_letters.len();
+        self.dead_letters.clear();
+        count
+    }
+    
+    /// 死信数量
+    pub fn len(&self) -> usize {
+        self.dead_letters.len()
+    }
+    
+    /// 队列是否为空
+    pub fn is_empty(&self) -> bool {
+        self.dead_letters.is_empty()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -270,6 +472,53 @@ mod tests {
         assert_eq!(reply.reference_id.unwrap(), message.id);
     }
     
+    #[test]
+    fn test_dead_letter_queue() {
+        let queue = DeadLetterQueue::new(2);
+        let message = Message::new(
+            AgentId::from_str("agent1"),
+            vec![AgentId::from_str("agent2")],
+            MessageType::Command,
+            "poison"
+        );
+        
+        assert!(!queue.record_failure(&message, FailureKind::Delivery, "channel closed"));
+        assert_eq!(queue.attempts(&message.id), 1);
+        assert!(queue.is_empty());
+        
+        assert!(queue.record_failure(&message, FailureKind::Processing, "invalid payload"));
+        let dead_letter = queue.get(&message.id).unwrap();
+        assert_eq!(dead_letter.attempts(), 2);
+        assert_eq!(dead_letter.message.status, MessageStatus::Failed);
+        assert_eq!(dead_letter.last_failure().unwrap().kind, FailureKind::Processing);
+        assert_eq!(queue.list().len(), 1);
+        
+        // 取出后重新计数
+        let retried = queue.take(&message.id).unwrap();
+        assert_eq!(retried.status, MessageStatus::Created);
+        assert_eq!(queue.attempts(&message.id), 0);
+        assert!(queue.is_empty());
+        
+        queue.record_failure(&message, FailureKind::Delivery, "channel closed");
+        queue.record_failure(&message, FailureKind::Delivery, "channel closed");
+        assert!(queue.purge(&message.id));
+        assert!(!queue.purge(&message.id));
+        assert_eq!(queue.purge_all(), 0);
+    }
+    
+    #[test]
+    fn test_capability_query_metadata() {
+        let message = Message::new(AgentId::from_str("agent1"), Vec::new(), MessageType::Command, "translate")
+            .with_capability_query(CapabilityQuery::new(["french"]).with_max_cost(3.0));
+        
+        let query = message.capability_query().unwrap();
+        assert_eq!(query.skills, ["french"]);
+        assert_eq!(query.max_cost, Some(3.0));
+        
+        let plain = Message::new(AgentId::from_str("agent1"), Vec::new(), MessageType::Text, "hi");
+        assert!(plain.capability_query().is_none());
+    }
+    
     #[test]
     fn test_message_expiry() {
         // 创建一个很快过期的消息
@@ -6,6 +6,9 @@
 //! - 网络拓扑管理
 //! - 消息路由策略
 //! - 服务发现机制
+//! - 按技能路由
+//! - 消息持久化
+//! - 拓扑观测
 //! - Agent网络管理
 
 mod error;
@@ -14,13 +17,19 @@ mod message;
 mod router;
 mod topology;
 mod discovery;
+mod capability;
+mod persistence;
+mod introspection;
 mod network;
 
 // 重新导出
 pub use error::{Error, Result};
 pub use types::{AgentId, AgentType, AgentStatus, AgentCapability};
-pub use message::{Message, MessageType, MessageStatus};
+pub use message::{Message, MessageType, MessageStatus, DeadLetter, DeadLetterQueue, FailureKind};
 pub use router::{MessageRouter, DefaultMessageRouter, RoutingStrategy, RoutingRule};
 pub use topology::{NetworkTopology, GraphTopology, TopologyType, EdgeAttributes, NodeAttributes};
 pub use discovery::{ServiceDiscovery, InMemoryServiceDiscovery, ServiceRegistration, ServiceQuery};
+pub use capability::{CapabilityRegistry, CapabilityQuery, CapabilityMatch};
+pub use persistence::{MessageLog, InMemoryMessageLog, LoggedMessage};
+pub use introspection::{TopologySnapshot, NodeSnapshot, EdgeSnapshot, NodeHealth, TrafficMonitor};
 pub use network::{AgentNetwork, AgentNode, AgentConfig}; 
\ No newline at end of file
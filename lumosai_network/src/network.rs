@@ -15,6 +15,7 @@ use crate::message::{Message, MessageType};
 use crate::router::{MessageRouter, DefaultMessageRouter};
 use crate::topology::{NetworkTopology, GraphTopology};
 use crate::discovery::{ServiceDiscovery, InMemoryServiceDiscovery, ServiceRegistration};
+use crate::introspection::{TopologySnapshot, NodeSnapshot, EdgeSnapshot, NodeHealth};
 
 /// Agent配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -206,6 +207,13 @@ impl AgentNode {
                     
                     // 标记消息已处理
                     message.mark_as_processed();
+                    
+                    // 确认消息，避免被重新投递
+                    if let Some(net) = &network {
+                        if let Err(e) = net.router.acknowledge(&message.id, &agent_id).await {
+                            log::error!("确认消息失败: {}", e);
+                        }
+                    }
                 } else {
                     break;
                 }
@@ -233,6 +241,7 @@ impl AgentNode {
     async fn process_message(&self, message: Message) -> Result<Vec<Message>> {
         // 创建结果向量
         let mut results = Vec::new();
+        let mut failure = None;
         
         // 获取消息类型
         let msg_type = &message.message_type;
@@ -248,11 +257,17 @@ impl AgentNode {
                     }
                     Err(e) => {
                         log::error!("处理消息时出错: {}", e);
+                        failure.get_or_insert(e.to_string());
                     }
                 }
             }
         }
         
+        // 交给路由器重新投递或移入死信队列
+        if let (Some(error), Some(network)) = (failure, &self.network) {
+            network.router.report_failure(message, error).await?;
+        }
+        
         Ok(results)
     }
 }
@@ -354,6 +369,7 @@ impl AgentNetwork {
         // 注册到路由器
         let sender = agent_arc.sender();
         self.router.register(agent_id.clone(), sender).await?;
+        self.router.register_capabilities(agent_id.clone(), agent_arc.config.capabilities.clone()).await?;
         
         // 添加到拓扑
         self.topology.add_node(agent_id.clone(), None).await?;
@@ -415,6 +431,60 @@ impl AgentNetwork {
         self.agents.iter().map(|entry| entry.value().clone()).collect()
     }
     
+    /// 生成网络拓扑快照，包括节点、边、消息速率和健康状态
+    pub async fn topology_snapshot(&self) -> Result<TopologySnapshot> {
+        let capabilities = self.router.capability_registry();
+        let mut nodes = Vec::new();
+        for agent in self.get_all_agents() {
+            let status = agent.status().await;
+            // 未注册到服务发现的Agent没有心跳可检查
+            let heartbeat_ok = !agent.config.register_with_discovery
+                || self.discovery.get_by_id(agent.id()).await.is_ok();
+            
+            let mut skills: Vec<String> = agent.config.capabilities.iter()
+                .flat_map(|capability| std::iter::once(capability.name.clone()).chain(capability.skills.iter().cloned()))
+                .collect();
+            skills.sort();
+            skills.dedup();
+            
+            nodes.push(NodeSnapshot {
+                id: agent.id().clone(),
+                name: agent.config.name.clone(),
+                agent_type: agent.config.agent_type.clone(),
+                status,
+                health: NodeHealth::evaluate(status, heartbeat_ok),
+                skills,
+                load: capabilities.load(agent.id()),
+                messages_sent: 0,
+                messages_received: 0,
+                messages_per_second: 0.0,
+            });
+        }
+        
+        let edges = self.topology.get_all_edges().await?
+            .into_iter()
+            .map(|(source, target, attrs)| EdgeSnapshot {
+                source,
+                target,
+                in_topology: true,
+                weight: Some(attrs.weight),
+                latency: attrs.latency,
+                label: attrs.label,
+                message_count: 0,
+                messages_per_second: 0.0,
+            })
+            .collect();
+        
+        Ok(TopologySnapshot::assemble(
+            self.id.clone(),
+            self.topology.get_topology_type(),
+            nodes,
+            edges,
+            self.router.traffic().stats(),
+            self.router.dead_letter_queue().len(),
+        ))
+    }
+    
     /// 发送消息
     pub async fn send_message(&self, message: Message) -> Result<()> {
         // 检查接收者是否存在
@@ -498,6 +568,27 @@ impl AgentNetwork {
             }
         })
     }
+    
+    /// 启动重新投递任务，定期重新投递超过`ack_timeout`仍未确认的消息
+    ///
+    /// 只有路由器配置了消息日志时才会重新投递。
+    pub fn start_redelivery_task(&self, interval: Duration, ack_timeout: Duration) -> JoinHandle<()> {
+        let router = self.router.clone();
+        
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            
+            loop {
+                ticker.tick().await;
+                
+                match router.redeliver_unacknowledged(ack_timeout).await {
+                    Ok(0) => {}
+                    Ok(count) => log::info!("重新投递了{}条未确认的消息", count),
+                    Err(e) => log::error!("重新投递消息失败: {}", e),
+                }
+            }
+        })
+    }
 }
 
 impl Clone for AgentNetwork {
@@ -542,6 +633,7 @@ impl NetworkManager {
         
         // 处理消息并生成响应消息
         let mut responses = Vec::new();
+        let mut failure = None;
         
         // 检查是否有该类型的消息处理器
         if let Some(handlers) = self.message_handlers.get(msg_type) {
@@ -557,11 +649,19 @@ impl NetworkManager {
                     }
                     Err(e) => {
                         eprintln!("处理消息时出错: {}", e);
+                        failure.get_or_insert(e.to_string());
                     }
                 }
             }
         }
         
+        // 交给路由器重新投递或移入死信队列
+        if let Some(error) = failure {
+            if let Err(e) = self.router.report_failure(message, error).await {
+                eprintln!("报告消息处理失败时出错: {}", e);
+            }
+        }
+        
         // 将所有响应消息通过路由器发送
         for response in responses {
             if let Err(e) = self.router.route(response).await {
@@ -699,6 +799,55 @@ mod tests {
         agent2_arc.stop().await.unwrap();
     }
     
+    #[tokio::test]
+    async fn test_topology_snapshot() {
+        let network = AgentNetwork::new().await;
+        
+        let client = AgentNode::new(Some(AgentId::from_str("client")), AgentConfig {
+            name: "client".to_string(),
+            ..Default::default()
+        });
+        // 运行中的Agent会自动回复文本消息
+        let echo = AgentNode::new(Some(AgentId::from_str("echo")), AgentConfig {
+            name: "echo".to_string(),
+            capabilities: vec![AgentCapability::new("echo", "Echo").with_skill("text")],
+            ..Default::default()
+        });
+        
+        let client = network.add_agent(client).await.unwrap();
+        let echo = network.add_agent(echo).await.unwrap();
+        echo.start().await.unwrap();
+        
+        for _ in 0..3 {
+            let message = Message::new(client.id().clone(), vec![echo.id().clone()], MessageType::Text, "ping");
+            network.send_message(message).await.unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        
+        let snapshot = network.topology_snapshot().await.unwrap();
+        assert_eq!(snapshot.network_id, network.id());
+        assert_eq!(snapshot.topology_type, crate::topology::TopologyType::FullyConnected);
+        assert_eq!(snapshot.summary.node_count, 2);
+        assert_eq!(snapshot.summary.edge_count, 2);
+        assert_eq!(snapshot.summary.healthy_nodes, 1);
+        
+        let client_node = &snapshot.nodes[0];
+        assert_eq!(client_node.id, *client.id());
+        assert_eq!(client_node.health, NodeHealth::Idle);
+        assert_eq!(client_node.messages_sent, 3);
+        assert_eq!(client_node.messages_received, 3);
+        assert_eq!(snapshot.nodes[1].skills, ["echo", "text"]);
+        
+        let to_echo = snapshot.edges.iter()
+            .find(|edge| &edge.source == client.id() && &edge.target == echo.id())
+            .unwrap();
+        assert!(to_echo.in_topology);
+        assert_eq!(to_echo.message_count, 3);
+        assert!(to_echo.messages_per_second > 0.0);
+        
+        echo.stop().await.unwrap();
+    }
+    
     #[tokio::test]
     async fn test_service_discovery_integration() {
         // 创建网络
@@ -0,0 +1,419 @@
+//! 消息持久化
+//!
+//! 路由器在投递消息前先把每个接收者的副本写入[`MessageLog`]，接收者处理完成后确认
+//! （或回复该消息），确认后的记录从日志中删除。Agent崩溃时尚未确认的消息保留在日志中，
+//! 在Agent重新注册或确认超时后重新投递，从而实现至少一次投递。
+//!
+//! 内置内存实现[`InMemoryMessageLog`]，启用`sqlite`或`postgres`特性后可使用
+//! [`SqliteMessageLog`]或[`PostgresMessageLog`]把日志保存到数据库。
+
+use std::time::SystemTime;
+
+use async_trait::async_trait;
+use dashmap::DashMap;
+use serde::{Serialize, Deserialize};
+
+use crate::error::{Error, Result};
+use crate::message::{Message, MessageId};
+use crate::types::AgentId;
+
+/// 日志中一条未确认的消息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoggedMessage {
+    /// 消息，只有一个接收者
+    pub message: Message,
+    /// 已发送次数
+    pub attempts: u32,
+    /// 最后一次发送时间
+    pub last_sent_at: SystemTime,
+}
+
+impl LoggedMessage {
+    /// 消息的接收者
+    pub fn receiver(&self) -> &AgentId {
+        &self.message.receivers[0]
+    }
+}
+
+/// 消息日志接口
+#[async_trait]
+pub trait MessageLog: Send + Sync {
+    /// 记录一次发送，`message`只有一个接收者；同一消息再次发送时增加发送次数
+    async fn append(&self, message: &Message) -> Result<()>;
+
+    /// 确认接收者已处理消息，返回记录是否存在
+    async fn acknowledge(&self, id: &MessageId, receiver: &AgentId) -> Result<bool>;
+
+    /// 接收者所有未确认的消息，按最后发送时间先后排列
+    async fn pending_for(&self, receiver: &AgentId) -> Result<Vec<LoggedMessage>>;
+
+    /// 最后发送时间早于`before`且未确认的消息，按最后发送时间先后排列
+    async fn unacknowledged(&self, before: SystemTime) -> Result<Vec<LoggedMessage>>;
+}
+
+/// 单个接收者的消息副本
+fn single_receiver(message: &Message) -> Result<&AgentId> {
+    match message.receivers.as_slice() {
+        [receiver] => Ok(receiver),
+        _ => Err(Error::Persistence(format!("消息 {} 必须只有一个接收者", message.id))),
+    }
+}
+
+/// 内存中的消息日志，进程退出后丢失，适用于测试和单进程部署
+#[derive(Debug, Default)]
+pub struct InMemoryMessageLog {
+    /// 未确认的消息，按(消息ID, 接收者)索引
+    entries: DashMap<(MessageId, AgentId), LoggedMessage>,
+}
+
+impl InMemoryMessageLog {
+    /// 创建空的消息日志
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 未确认的消息数量
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// 是否没有未确认的消息
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn collect(&self, filter: impl Fn(&LoggedMessage) -> bool) -> Vec<LoggedMessage> {
+        let mut entries: Vec<LoggedMessage> = self.entries.iter()
+            .filter(|entry| filter(entry.value()))
+            .map(|entry| entry.value().clone())
+            .collect();
+        entries.sort_by_key(|entry| entry.last_sent_at);
+        entries
+    }
+}
+
+#[async_trait]
+impl MessageLog for InMemoryMessageLog {
+    async fn append(&self, message: &Message) -> Result<()> {
+        let receiver = single_receiver(message)?.clone();
+        let now = SystemTime::now();
+        self.entries.entry((message.id.clone(), receiver))
+            .and_modify(|entry| {
+                entry.attempts += 1;
+                entry.last_sent_at = now;
+            })
+            .or_insert_with(|| LoggedMessage {
+                message: message.clone(),
+                attempts: 1,
+                last_sent_at: now,
+            });
+        Ok(())
+    }
+
+    async fn acknowledge(&self, id: &MessageId, receiver: &AgentId) -> Result<bool> {
+        Ok(self.entries.remove(&(id.clone(), receiver.clone())).is_some())
+    }
+
+    async fn pending_for(&self, receiver: &AgentId) -> Result<Vec<LoggedMessage>> {
+        Ok(self.collect(|entry| entry.receiver() == receiver))
+    }
+
+    async fn unacknowledged(&self, before: SystemTime) -> Result<Vec<LoggedMessage>> {
+        Ok(self.collect(|entry| entry.last_sent_at < before))
+    }
+}
+
+/// 时间转换为数据库中保存的毫秒时间戳
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+fn to_millis(time: SystemTime) -> i64 {
+    time.duration_since(std::time::UNIX_EPOCH).map_or(0, |duration| duration.as_millis() as i64)
+}
+
+/// 数据库中的一行转换为日志记录
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+fn from_row(message: &str, attempts: i64, last_sent_at: i64) -> Result<LoggedMessage> {
+    Ok(LoggedMessage {
+        message: serde_json::from_str(message)?,
+        attempts: attempts as u32,
+        last_sent_at: std::time::UNIX_EPOCH + std::time::Duration::from_millis(last_sent_at.max(0) as u64),
+    })
+}
+
+/// 保存在SQLite中的消息日志
+#[cfg(feature = "sqlite")]
+pub struct SqliteMessageLog {
+    pool: sqlx::SqlitePool,
+}
+
+#[cfg(feature = "sqlite")]
+impl SqliteMessageLog {
+    /// 连接数据库并创建日志表
+    pub async fn new(database_url: &str) -> Result<Self> {
+        let pool = sqlx::SqlitePool::connect(database_url).await?;
+        Self::with_pool(pool).await
+    }
+
+    /// 使用已有的连接池并创建日志表
+    pub async fn with_pool(pool: sqlx::SqlitePool) -> Result<Self> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS network_message_log (
+                message_id TEXT NOT NULL,
+                receiver TEXT NOT NULL,
+                message TEXT NOT NULL, -- JSON object
+                attempts INTEGER NOT NULL,
+                last_sent_at INTEGER NOT NULL, -- 毫秒时间戳
+                PRIMARY KEY (message_id, receiver)
+            )
+            "#,
+        )
+        .execute(&pool)
+        .await?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_network_message_log_sent ON network_message_log(last_sent_at)")
+            .execute(&pool)
+            .await?;
+
+        Ok(Self { pool })
+    }
+
+    async fn select(&self, sql: &str, bind: impl Into<SqlBind>) -> Result<Vec<LoggedMessage>> {
+        use sqlx::Row;
+
+        let query = sqlx::query(sql);
+        let query = match bind.into() {
+            SqlBind::Text(value) => query.bind(value),
+            SqlBind::Integer(value) => query.bind(value),
+        };
+        query.fetch_all(&self.pool).await?
+            .iter()
+            .map(|row| from_row(row.get("message"), row.get("attempts"), row.get("last_sent_at")))
+            .collect()
+    }
+}
+
+#[cfg(feature = "sqlite")]
+#[async_trait]
+impl MessageLog for SqliteMessageLog {
+    async fn append(&self, message: &Message) -> Result<()> {
+        let receiver = single_receiver(message)?;
+        sqlx::query(
+            r#"
+            INSERT INTO network_message_log (message_id, receiver, message, attempts, last_sent_at)
+            VALUES (?, ?, ?, 1, ?)
+            ON CONFLICT (message_id, receiver)
+            DO UPDATE SET attempts = attempts + 1, last_sent_at = excluded.last_sent_at
+            "#,
+        )
+        .bind(&message.id.0)
+        .bind(receiver.as_str())
+        .bind(serde_json::to_string(message)?)
+        .bind(to_millis(SystemTime::now()))
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn acknowledge(&self, id: &MessageId, receiver: &AgentId) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM network_message_log WHERE message_id = ? AND receiver = ?")
+            .bind(&id.0)
+            .bind(receiver.as_str())
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn pending_for(&self, receiver: &AgentId) -> Result<Vec<LoggedMessage>> {
+        self.select(
+            "SELECT message, attempts, last_sent_at FROM network_message_log WHERE receiver = ? ORDER BY last_sent_at",
+            receiver.value(),
+        ).await
+    }
+
+    async fn unacknowledged(&self, before: SystemTime) -> Result<Vec<LoggedMessage>> {
+        self.select(
+            "SELECT message, attempts, last_sent_at FROM network_message_log WHERE last_sent_at < ? ORDER BY last_sent_at",
+            to_millis(before),
+        ).await
+    }
+}
+
+/// 保存在PostgreSQL中的消息日志
+#[cfg(feature = "postgres")]
+pub struct PostgresMessageLog {
+    pool: sqlx::PgPool,
+}
+
+#[cfg(feature = "postgres")]
+impl PostgresMessageLog {
+    /// 连接数据库并创建日志表
+    pub async fn new(database_url: &str) -> Result<Self> {
+        let pool = sqlx::PgPool::connect(database_url).await?;
+        Self::with_pool(pool).await
+    }
+
+    /// 使用已有的连接池并创建日志表
+    pub async fn with_pool(pool: sqlx::PgPool) -> Result<Self> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS network_message_log (
+                message_id TEXT NOT NULL,
+                receiver TEXT NOT NULL,
+                message TEXT NOT NULL, -- JSON object
+                attempts BIGINT NOT NULL,
+                last_sent_at BIGINT NOT NULL, -- 毫秒时间戳
+                PRIMARY KEY (message_id, receiver)
+            )
+            "#,
+        )
+        .execute(&pool)
+        .await?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_network_message_log_sent ON network_message_log(last_sent_at)")
+            .execute(&pool)
+            .await?;
+
+        Ok(Self { pool })
+    }
+
+    async fn select(&self, sql: &str, bind: impl Into<SqlBind>) -> Result<Vec<LoggedMessage>> {
+        use sqlx::Row;
+
+        let query = sqlx::query(sql);
+        let query = match bind.into() {
+            SqlBind::Text(value) => query.bind(value),
+            SqlBind::Integer(value) => query.bind(value),
+        };
+        query.fetch_all(&self.pool).await?
+            .iter()
+            .map(|row| from_row(row.get("message"), row.get("attempts"), row.get("last_sent_at")))
+            .collect()
+    }
+}
+
+#[cfg(feature = "postgres")]
+#[async_trait]
+impl MessageLog for PostgresMessageLog {
+    async fn append(&self, message: &Message) -> Result<()> {
+        let receiver = single_receiver(message)?;
+        sqlx::query(
+            r#"
+            INSERT INTO network_message_log (message_id, receiver, message, attempts, last_sent_at)
+            VALUES ($1, $2, $3, 1, $4)
+            ON CONFLICT (message_id, receiver)
+            DO UPDATE SET attempts = network_message_log.attempts + 1, last_sent_at = EXCLUDED.last_sent_at
+            "#,
+        )
+        .bind(&message.id.0)
+        .bind(receiver.as_str())
+        .bind(serde_json::to_string(message)?)
+        .bind(to_millis(SystemTime::now()))
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn acknowledge(&self, id: &MessageId, receiver: &AgentId) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM network_message_log WHERE message_id = $1 AND receiver = $2")
+            .bind(&id.0)
+            .bind(receiver.as_str())
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn pending_for(&self, receiver: &AgentId) -> Result<Vec<LoggedMessage>> {
+        self.select(
+            "SELECT message, attempts, last_sent_at FROM network_message_log WHERE receiver = $1 ORDER BY last_sent_at",
+            receiver.value(),
+        ).await
+    }
+
+    async fn unacknowledged(&self, before: SystemTime) -> Result<Vec<LoggedMessage>> {
+        self.select(
+            "SELECT message, attempts, last_sent_at FROM network_message_log WHERE last_sent_at < $1 ORDER BY last_sent_at",
+            to_millis(before),
+        ).await
+    }
+}
+
+/// 查询参数
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+enum SqlBind {
+    Text(String),
+    Integer(i64),
+}
+
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+impl From<String> for SqlBind {
+    fn from(value: String) -> Self {
+        SqlBind::Text(value)
+    }
+}
+
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+impl From<i64> for SqlBind {
+    fn from(value: i64) -> Self {
+        SqlBind::Integer(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, UNIX_EPOCH};
+    use crate::message::MessageType;
+
+    fn task(receiver: &str) -> Message {
+        Message::new(AgentId::from_str("coordinator"), vec![AgentId::from_str(receiver)], MessageType::Command, "work")
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_log() {
+        let log = InMemoryMessageLog::new();
+        let first = task("worker1");
+        let second = task("worker2");
+
+        log.append(&first).await.unwrap();
+        log.append(&first).await.unwrap();
+        log.append(&second).await.unwrap();
+        assert_eq!(log.len(), 2);
+
+        let pending = log.pending_for(&AgentId::from_str("worker1")).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].attempts, 2);
+        assert_eq!(pending[0].message.id, first.id);
+
+        let later = SystemTime::now() + Duration::from_secs(1);
+        assert_eq!(log.unacknowledged(later).await.unwrap().len(), 2);
+        assert!(log.unacknowledged(UNIX_EPOCH).await.unwrap().is_empty());
+
+        assert!(log.acknowledge(&first.id, &AgentId::from_str("worker1")).await.unwrap());
+        assert!(!log.acknowledge(&first.id, &AgentId::from_str("worker1")).await.unwrap());
+        assert_eq!(log.len(), 1);
+
+        // 多个接收者的消息需要先拆分
+        let broadcast = Message::new(AgentId::from_str("coordinator"), Vec::new(), MessageType::Event, "hi");
+        assert!(matches!(log.append(&broadcast).await, Err(Error::Persistence(_))));
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_sqlite_log() {
+        let log = SqliteMessageLog::new("sqlite::memory:").await.unwrap();
+        let message = task("worker1");
+
+        log.append(&message).await.unwrap();
+        log.append(&message).await.unwrap();
+
+        let pending = log.pending_for(&AgentId::from_str("worker1")).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].attempts, 2);
+        assert_eq!(pending[0].message.content, serde_json::json!("work"));
+
+        let later = SystemTime::now() + Duration::from_secs(1);
+        assert_eq!(log.unacknowledged(later).await.unwrap().len(), 1);
+        assert!(log.acknowledge(&message.id, &AgentId::from_str("worker1")).await.unwrap());
+        assert!(log.unacknowledged(later).await.unwrap().is_empty());
+    }
+}
@@ -2,13 +2,17 @@
 
 use std::sync::Arc;
 use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
 use async_trait::async_trait;
 use tokio::sync::{RwLock, mpsc};
 use serde::{Serialize, Deserialize};
 
 use crate::error::{Error, Result};
-use crate::types::AgentId;
-use crate::message::Message;
+use crate::types::{AgentId, AgentCapability};
+use crate::message::{Message, MessageId, DeadLetterQueue, FailureKind};
+use crate::capability::{CapabilityRegistry, CapabilityQuery};
+use crate::introspection::TrafficMonitor;
+use crate::persistence::MessageLog;
 use crate::topology::NetworkTopology;
 
 /// 定义路由策略
@@ -18,7 +22,7 @@ pub enum RoutingStrategy {
     Direct,
     /// 广播，发送给所有Agent
     Broadcast,
-    /// 智能路由，基于能力匹配
+    /// 智能路由，基于能力匹配：按消息声明的技能选择负载最低的Agent
     CapabilityBased,
     /// 基于角色路由
     RoleBased,
@@ -82,6 +86,34 @@ pub trait MessageRouter: Send + Sync {
     
     /// 获取已注册的Agent数量
     async fn agent_count(&self) -> usize;
+    
+    /// 注册Agent的能力，用于按技能路由
+    async fn register_capabilities(&self, agent_id: AgentId, capabilities: Vec<AgentCapability>) -> Result<()>;
+    
+    /// 按能力查询选择最合适的已注册Agent
+    async fn select_agent(&self, query: &CapabilityQuery) -> Result<AgentId>;
+    
+    /// 获取能力注册表
+    fn capability_registry(&self) -> Arc<CapabilityRegistry>;
+    
+    /// 获取消息流量监控
+    fn traffic(&self) -> Arc<TrafficMonitor>;
+    
+    /// 获取死信队列
+    fn dead_letter_queue(&self) -> Arc<DeadLetterQueue>;
+    
+    /// 报告接收者处理消息失败；未达到最大尝试次数时重新投递给原接收者，
+    /// 否则移入死信队列并返回true
+    async fn report_failure(&self, message: Message, error: String) -> Result<bool>;
+    
+    /// 重新投递死信
+    async fn retry_dead_letter(&self, id: &MessageId) -> Result<()>;
+    
+    /// 确认接收者已处理消息，回复消息时自动确认
+    async fn acknowledge(&self, id: &MessageId, receiver: &AgentId) -> Result<()>;
+    
+    /// 重新投递超过`ack_timeout`仍未确认的消息，返回重新投递的数量
+    async fn redeliver_unacknowledged(&self, ack_timeout: Duration) -> Result<usize>;
 }
 
 /// 默认消息路由器实现
@@ -92,6 +124,16 @@ pub struct DefaultMessageRouter {
     routing_rules: RwLock<Vec<RoutingRule>>,
     /// 网络拓扑
     topology: RwLock<Option<Arc<dyn NetworkTopology>>>,
+    /// Agent能力注册表
+    capabilities: Arc<CapabilityRegistry>,
+    /// 按技能路由后尚未收到回复的任务，任务消息ID映射到处理它的Agent
+    pending_tasks: RwLock<HashMap<MessageId, AgentId>>,
+    /// 死信队列
+    dead_letters: Arc<DeadLetterQueue>,
+    /// 消息日志，设置后按至少一次语义投递
+    message_log: Option<Arc<dyn MessageLog>>,
+    /// 消息流量监控
+    traffic: Arc<TrafficMonitor>,
 }
 
 impl DefaultMessageRouter {
@@ -101,11 +143,101 @@ impl DefaultMessageRouter {
             routing_table: RwLock::new(HashMap::new()),
             routing_rules: RwLock::new(Vec::new()),
             topology: RwLock::new(None),
+            capabilities: Arc::new(CapabilityRegistry::new()),
+            pending_tasks: RwLock::new(HashMap::new()),
+            dead_letters: Arc::new(DeadLetterQueue::default()),
+            message_log: None,
+            traffic: Arc::new(TrafficMonitor::default()),
+        }
+    }
+    
+    /// 使用消息日志：消息投递前写入日志，确认后删除，未确认的消息会被重新投递
+    pub fn with_message_log(mut self, log: Arc<dyn MessageLog>) -> Self {
+        self.message_log = Some(log);
+        self
+    }
+    
+    /// 使用指定的死信队列，例如自定义最大尝试次数或在多个路由器间共享
+    pub fn with_dead_letter_queue(mut self, queue: Arc<DeadLetterQueue>) -> Self {
+        self.dead_letters = queue;
+        self
+    }
+    
+    /// 记录投递失败，达到最大尝试次数时消息被移入死信队列
+    async fn delivery_failed(&self, message: &Message, error: Error) -> Error {
+        if !self.dead_letters.record_failure(message, FailureKind::Delivery, error.to_string()) {
+            return error;
+        }
+        
+        if let Err(e) = self.forget(message).await {
+            log::error!("从消息日志删除死信失败: {}", e);
+        }
+        Error::DeadLettered(message.id.to_string())
+    }
+    
+    /// 从消息日志中删除只有一个接收者的消息
+    async fn forget(&self, message: &Message) -> Result<()> {
+        if let (Some(log), [receiver]) = (&self.message_log, message.receivers.as_slice()) {
+            log.acknowledge(&message.id, receiver).await?;
         }
+        Ok(())
+    }
+    
+    /// 写入消息日志后发送给单个接收者
+    async fn deliver(&self, sender: &mpsc::Sender<Message>, message: Message) -> Result<()> {
+        if let Some(log) = &self.message_log {
+            log.append(&message).await?;
+        }
+        
+        let sender_id = message.sender.clone();
+        let receivers = message.receivers.clone();
+        if let Err(e) = sender.send(message.clone()).await {
+            let error = Error::Routing(format!("无法发送消息: {}", e));
+            return Err(self.delivery_failed(&message, error).await);
+        }
+        
+        for receiver in &receivers {
+            self.traffic.record(&sender_id, receiver);
+        }
+        Ok(())
+    }
+    
+    /// 在路由表中的候选Agent里选择负载最低的一个
+    fn choose_candidate(
+        &self,
+        query: &CapabilityQuery,
+        table: &HashMap<AgentId, mpsc::Sender<Message>>,
+        accept: impl Fn(&AgentId) -> bool,
+    ) -> Option<AgentId> {
+        self.capabilities.find(query)
+            .into_iter()
+            .map(|candidate| candidate.agent_id)
+            .find(|agent_id| table.contains_key(agent_id) && accept(agent_id))
+    }
+    
+    /// 收到回复说明原消息已处理成功：确认原消息，并减少处理该任务的Agent的负载
+    async fn handle_reply(&self, message: &Message) -> Result<()> {
+        let Some(reference_id) = &message.reference_id else {
+            return Ok(());
+        };
+        
+        self.acknowledge(reference_id, &message.sender).await?;
+        
+        let mut pending = self.pending_tasks.write().await;
+        if pending.get(reference_id) == Some(&message.sender) {
+            pending.remove(reference_id);
+            self.capabilities.finish_task(&message.sender);
+        }
+        Ok(())
     }
     
     /// 根据规则选择路由策略
     async fn select_strategy(&self, message: &Message) -> RoutingStrategy {
+        // 未指定接收者但声明了所需技能的消息按技能路由
+        if message.receivers.is_empty() && message.capability_query().is_some() {
+            return RoutingStrategy::CapabilityBased;
+        }
+        
         let rules = self.routing_rules.read().await;
         
         // 尝试找到匹配的规则
@@ -125,13 +257,25 @@ impl DefaultMessageRouter {
 impl MessageRouter for DefaultMessageRouter {
     async fn register(&self, agent_id: AgentId, sender: mpsc::Sender<Message>) -> Result<()> {
         let mut table = self.routing_table.write().await;
-        table.insert(agent_id, sender);
+        table.insert(agent_id.clone(), sender.clone());
+        drop(table);
+        
+        // Agent重启后重新投递崩溃前未确认的消息
+        if let Some(log) = &self.message_log {
+            for entry in log.pending_for(&agent_id).await? {
+                self.deliver(&sender, entry.message).await?;
+            }
+        }
         Ok(())
     }
     
     async fn unregister(&self, agent_id: &AgentId) -> Result<()> {
         let mut table = self.routing_table.write().await;
         table.remove(agent_id);
+        
+        self.capabilities.unregister(agent_id);
+        self.traffic.remove_agent(agent_id);
+        self.pending_tasks.write().await.retain(|_, assignee| assignee != agent_id);
         Ok(())
     }
     
@@ -139,6 +283,8 @@ impl MessageRouter for DefaultMessageRouter {
         // 标记消息为已发送
         message.mark_as_sent();
         
+        self.handle_reply(&message).await?;
+        
         // 根据规则选择路由策略
         let strategy = self.select_strategy(&message).await;
         
@@ -154,11 +300,11 @@ impl MessageRouter for DefaultMessageRouter {
                         // 设置单个接收者
                         msg_clone.receivers = vec![receiver.clone()];
                         
-                        if let Err(e) = sender.send(msg_clone).await {
-                            return Err(Error::Routing(format!("无法发送消息: {}", e)));
-                        }
+                        self.deliver(sender, msg_clone).await?;
                     } else {
-                        return Err(Error::AgentNotFound(receiver.value()));
+                        let mut msg_clone = message.clone();
+                        msg_clone.receivers = vec![receiver.clone()];
+                        return Err(self.delivery_failed(&msg_clone, Error::AgentNotFound(receiver.value())).await);
                     }
                 }
             },
@@ -170,12 +316,28 @@ impl MessageRouter for DefaultMessageRouter {
                         // 设置单个接收者
                         msg_clone.receivers = vec![agent_id.clone()];
                         
-                        if let Err(e) = sender.send(msg_clone).await {
-                            return Err(Error::Routing(format!("无法发送消息: {}", e)));
-                        }
+                        self.deliver(sender, msg_clone).await?;
                     }
                 }
             },
+            RoutingStrategy::CapabilityBased => {
+                let query = message.capability_query().unwrap_or_default();
+                // 指定了接收者时只在接收者中选择
+                let receivers = message.receivers.clone();
+                let chosen = self.choose_candidate(&query, &table, |agent_id| {
+                    agent_id != &message.sender && (receivers.is_empty() || receivers.contains(agent_id))
+                });
+                let Some(agent_id) = chosen else {
+                    let error = Error::Routing(format!("没有满足技能要求的Agent: {:?}", query.skills));
+                    return Err(self.delivery_failed(&message, error).await);
+                };
+                
+                message.receivers = vec![agent_id.clone()];
+                self.deliver(&table[&agent_id], message.clone()).await?;
+                
+                self.capabilities.begin_task(&agent_id);
+                self.pending_tasks.write().await.insert(message.id.clone(), agent_id);
+            },
             // 其他策略实现可以在这里添加
             _ => {
                 // 如果没有实现特定策略，使用直接路由
@@ -223,6 +385,97 @@ impl MessageRouter for DefaultMessageRouter {
         let table = self.routing_table.read().await;
         table.len()
     }
+    
+    async fn register_capabilities(&self, agent_id: AgentId, capabilities: Vec<AgentCapability>) -> Result<()> {
+        self.capabilities.register(agent_id, capabilities);
+        Ok(())
+    }
+    
+    async fn select_agent(&self, query: &CapabilityQuery) -> Result<AgentId> {
+        let table = self.routing_table.read().await;
+        self.choose_candidate(query, &table, |_| true)
+            .ok_or_else(|| Error::Routing(format!("没有满足技能要求的Agent: {:?}", query.skills)))
+    }
+    
+    fn capability_registry(&self) -> Arc<CapabilityRegistry> {
+        self.capabilities.clone()
+    }
+    
+    fn traffic(&self) -> Arc<TrafficMonitor> {
+        self.traffic.clone()
+    }
+    
+    fn dead_letter_queue(&self) -> Arc<DeadLetterQueue> {
+        self.dead_letters.clone()
+    }
+    
+    async fn report_failure(&self, message: Message, error: String) -> Result<bool> {
+        if self.dead_letters.record_failure(&message, FailureKind::Processing, error) {
+            // 不再等待该任务的回复
+            if let Some(agent_id) = self.pending_tasks.write().await.remove(&message.id) {
+                self.capabilities.finish_task(&agent_id);
+            }
+            self.forget(&message).await?;
+            return Ok(true);
+        }
+        
+        // 直接重新投递给原接收者，不再经过路由策略
+        let table = self.routing_table.read().await;
+        for receiver in &message.receivers {
+            let mut msg_clone = message.clone();
+            msg_clone.receivers = vec![receiver.clone()];
+            msg_clone.mark_as_sent();
+            
+            let Some(sender) = table.get(receiver) else {
+                return Err(self.delivery_failed(&msg_clone, Error::AgentNotFound(receiver.value())).await);
+            };
+            self.deliver(sender, msg_clone).await?;
+        }
+        
+        Ok(false)
+    }
+    
+    async fn retry_dead_letter(&self, id: &MessageId) -> Result<()> {
+        let message = self.dead_letters.take(id)
+            .ok_or_else(|| Error::Routing(format!("死信 '{}' 不存在", id)))?;
+        self.route(message).await
+    }
+    
+    async fn acknowledge(&self, id: &MessageId, receiver: &AgentId) -> Result<()> {
+        self.dead_letters.acknowledge(id);
+        if let Some(log) = &self.message_log {
+            log.acknowledge(id, receiver).await?;
+        }
+        Ok(())
+    }
+    
+    async fn redeliver_unacknowledged(&self, ack_timeout: Duration) -> Result<usize> {
+        let Some(message_log) = &self.message_log else {
+            return Ok(0);
+        };
+        
+        let deadline = SystemTime::now().checked_sub(ack_timeout).unwrap_or(SystemTime::UNIX_EPOCH);
+        let table = self.routing_table.read().await;
+        let mut redelivered = 0;
+        for entry in message_log.unacknowledged(deadline).await? {
+            // 接收者未注册时等它重新注册后再投递
+            let Some(sender) = table.get(entry.receiver()) else {
+                continue;
+            };
+            
+            let error = format!("{}秒内未确认", ack_timeout.as_secs());
+            if self.dead_letters.record_failure(&entry.message, FailureKind::Delivery, error) {
+                self.forget(&entry.message).await?;
+                continue;
+            }
+            
+            match self.deliver(sender, entry.message).await {
+                Ok(()) => redelivered += 1,
+                Err(e) => log::warn!("重新投递消息失败: {}", e),
+            }
+        }
+        Ok(redelivered)
+    }
 }
 
 #[cfg(test)]
@@ -230,6 +483,7 @@ mod tests {
     use super::*;
     use tokio::sync::mpsc;
     use crate::message::MessageType;
+    use crate::persistence::InMemoryMessageLog;
     use crate::types::AgentId;
     
     #[tokio::test]
@@ -334,4 +588,130 @@ mod tests {
         // agent1不应该收到消息
         assert!(rx1.try_recv().is_err());
     }
+    
+    #[tokio::test]
+    async fn test_skill_based_routing() {
+        let router = DefaultMessageRouter::new();
+        
+        let client = AgentId::from_str("client");
+        let (tx_client, mut rx_client) = mpsc::channel(10);
+        let cheap = AgentId::from_str("cheap");
+        let (tx_cheap, mut rx_cheap) = mpsc::channel(10);
+        let pricey = AgentId::from_str("pricey");
+        let (tx_pricey, mut rx_pricey) = mpsc::channel(10);
+        
+        router.register(client.clone(), tx_client).await.unwrap();
+        router.register(cheap.clone(), tx_cheap).await.unwrap();
+        router.register(pricey.clone(), tx_pricey).await.unwrap();
+        router.register_capabilities(cheap.clone(), vec![
+            AgentCapability::new("translate", "Translation").with_skill("french").with_cost(1.0),
+        ]).await.unwrap();
+        router.register_capabilities(pricey.clone(), vec![
+            AgentCapability::new("translate", "Translation").with_skill("french").with_cost(3.0),
+        ]).await.unwrap();
+        
+        let task = || Message::new(client.clone(), Vec::new(), MessageType::Command, "Bonjour")
+            .with_required_skills(["french"]);
+        
+        // 负载相同时选择成本低的Agent
+        router.route(task()).await.unwrap();
+        let first = rx_cheap.recv().await.unwrap();
+        assert_eq!(first.receivers, vec![cheap.clone()]);
+        assert_eq!(router.capability_registry().load(&cheap), 1);
+        
+        // 低成本Agent忙碌时选择空闲的Agent
+        router.route(task()).await.unwrap();
+        assert!(rx_pricey.recv().await.is_some());
+        
+        // 回复后负载恢复
+        router.route(first.create_reply("Hello")).await.unwrap();
+        assert!(rx_client.recv().await.is_some());
+        assert_eq!(router.capability_registry().load(&cheap), 0);
+        assert_eq!(router.select_agent(&CapabilityQuery::new(["french"])).await.unwrap(), cheap);
+        
+        let unknown = Message::new(client.clone(), Vec::new(), MessageType::Command, "Hola")
+            .with_required_skills(["spanish"]);
+        assert!(matches!(router.route(unknown).await, Err(Error::Routing(_))));
+    }
+    
+    #[tokio::test]
+    async fn test_undeliverable_message_is_dead_lettered() {
+        let router = DefaultMessageRouter::new()
+            .with_dead_letter_queue(Arc::new(DeadLetterQueue::new(2)));
+        
+        let agent1 = AgentId::from_str("agent1");
+        let (tx1, _rx1) = mpsc::channel(10);
+        let agent2 = AgentId::from_str("agent2");
+        let (tx2, mut rx2) = mpsc::channel(10);
+        router.register(agent1.clone(), tx1).await.unwrap();
+        router.register(agent2.clone(), tx2).await.unwrap();
+        
+        let message = Message::new(agent1.clone(), vec![AgentId::from_str("missing")], MessageType::Text, "Hello");
+        assert!(matches!(router.route(message.clone()).await, Err(Error::AgentNotFound(_))));
+        assert!(matches!(router.route(message.clone()).await, Err(Error::DeadLettered(_))));
+        
+        let queue = router.dead_letter_queue();
+        let dead_letter = queue.get(&message.id).unwrap();
+        assert_eq!(dead_letter.attempts(), 2);
+        assert_eq!(dead_letter.failures[0].kind, FailureKind::Delivery);
+        
+        // 接收者上线后重新投递
+        let missing = AgentId::from_str("missing");
+        let (tx3, mut rx3) = mpsc::channel(10);
+        router.register(missing, tx3).await.unwrap();
+        router.retry_dead_letter(&message.id).await.unwrap();
+        assert_eq!(rx3.recv().await.unwrap().id, message.id);
+        assert!(queue.is_empty());
+        assert!(router.retry_dead_letter(&message.id).await.is_err());
+        
+        // 处理失败的消息先被重新投递，多次失败后移入死信队列
+        let poison = Message::new(agent1.clone(), vec![agent2.clone()], MessageType::Command, "poison");
+        router.route(poison.clone()).await.unwrap();
+        let received = rx2.recv().await.unwrap();
+        assert!(!router.report_failure(received, "invalid payload".to_string()).await.unwrap());
+        let redelivered = rx2.recv().await.unwrap();
+        assert_eq!(redelivered.id, poison.id);
+        assert!(router.report_failure(redelivered, "invalid payload".to_string()).await.unwrap());
+        assert!(rx2.try_recv().is_err());
+        assert_eq!(queue.get(&poison.id).unwrap().last_failure().unwrap().kind, FailureKind::Processing);
+        
+        assert_eq!(queue.purge_all(), 1);
+    }
+    
+    #[tokio::test]
+    async fn test_unacknowledged_messages_are_redelivered() {
+        let log = Arc::new(InMemoryMessageLog::new());
+        let router = DefaultMessageRouter::new().with_message_log(log.clone());
+        
+        let coordinator = AgentId::from_str("coordinator");
+        let (tx1, mut rx1) = mpsc::channel(10);
+        let worker = AgentId::from_str("worker");
+        let (tx2, rx2) = mpsc::channel(10);
+        router.register(coordinator.clone(), tx1).await.unwrap();
+        router.register(worker.clone(), tx2).await.unwrap();
+        
+        let task = Message::new(coordinator.clone(), vec![worker.clone()], MessageType::Command, "index");
+        router.route(task.clone()).await.unwrap();
+        assert_eq!(log.len(), 1);
+        
+        // Worker崩溃，消息未确认；重新注册后再次收到
+        drop(rx2);
+        let (tx2, mut rx2) = mpsc::channel(10);
+        router.register(worker.clone(), tx2).await.unwrap();
+        let received = rx2.recv().await.unwrap();
+        assert_eq!(received.id, task.id);
+        assert_eq!(log.pending_for(&worker).await.unwrap()[0].attempts, 2);
+        
+        // 确认超时后重新投递
+        assert_eq!(router.redeliver_unacknowledged(Duration::ZERO).await.unwrap(), 1);
+        assert_eq!(rx2.recv().await.unwrap().id, task.id);
+        
+        // 回复即确认原消息，回复本身由接收者确认
+        router.route(received.create_reply("done")).await.unwrap();
+        assert!(log.pending_for(&worker).await.unwrap().is_empty());
+        let reply = rx1.recv().await.unwrap();
+        router.acknowledge(&reply.id, &coordinator).await.unwrap();
+        assert!(log.is_empty());
+        assert_eq!(router.redeliver_unacknowledged(Duration::ZERO).await.unwrap(), 0);
+    }
 } 
\ No newline at end of file
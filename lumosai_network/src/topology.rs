@@ -94,6 +94,9 @@ pub trait NetworkTopology: Send + Sync {
     
     /// 获取所有节点
     async fn get_all_nodes(&self) -> Result<Vec<AgentId>>;
+
+    /// 获取所有边及其属性
+    async fn get_all_edges(&self) -> Result<Vec<(AgentId, AgentId, EdgeAttributes)>>;
     
     /// 获取节点数量
     async fn node_count(&self) -> usize;
@@ -356,6 +359,18 @@ impl NetworkTopology for GraphTopology {
         Ok(indices.keys().cloned().collect())
     }
     
+    async fn get_all_edges(&self) -> Result<Vec<(AgentId, AgentId, EdgeAttributes)>> {
+        let graph = self.graph.read();
+        let edges = graph.edge_references()
+            .map(|edge| (
+                graph[edge.source()].id.clone(),
+                graph[edge.target()].id.clone(),
+                edge.weight().clone(),
+            ))
+            .collect();
+        Ok(edges)
+    }
+    
     async fn node_count(&self) -> usize {
         let graph = self.graph.read();
         graph.node_count()
@@ -432,6 +447,10 @@ mod tests {
         assert!(topology.is_connected(&node3, &node4).await.unwrap());
         assert!(topology.is_connected(&node4, &node1).await.unwrap());
         
+        let edges = topology.get_all_edges().await.unwrap();
+        assert_eq!(edges.len(), 4);
+        assert!(edges.iter().any(|(from, to, _)| from == &node4 && to == &node1));
+        
         // 节点1到节点3的最短路径应该是 node1 -> node2 -> node3
         let path = topology.get_shortest_path(&node1, &node3).await.unwrap();
         assert_eq!(path, vec![node1.clone(), node2.clone(), node3.clone()]);
@@ -1,6 +1,7 @@
 //! Agent网络基本类型定义
 
 use std::fmt;
+use std::time::Duration;
 use serde::{Serialize, Deserialize};
 use uuid::Uuid;
 
@@ -91,7 +92,10 @@ pub enum AgentStatus {
 }
 
 /// Agent能力
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+///
+/// 除名称外，能力还可以声明技能标签、单次任务成本和延迟SLO，
+/// [`CapabilityRegistry`](crate::capability::CapabilityRegistry)据此为任务挑选Agent。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AgentCapability {
     /// 能力名称
     pub name: String,
@@ -99,6 +103,15 @@ pub struct AgentCapability {
     pub description: String,
     /// 能力元数据
     pub metadata: Option<serde_json::Value>,
+    /// 技能标签，能力名称本身也视为一个技能
+    #[serde(default)]
+    pub skills: Vec<String>,
+    /// 单次任务成本
+    #[serde(default)]
+    pub cost: Option<f64>,
+    /// 延迟SLO（毫秒）
+    #[serde(default)]
+    pub latency_slo_ms: Option<u64>,
 }
 
 impl AgentCapability {
@@ -108,6 +121,9 @@ impl AgentCapability {
             name: name.into(),
             description: description.into(),
             metadata: None,
+            skills: Vec::new(),
+            cost: None,
+            latency_slo_ms: None,
         }
     }
     
@@ -116,6 +132,29 @@ impl AgentCapability {
         self.metadata = Some(metadata);
         self
     }
+    
+    /// 添加技能标签
+    pub fn with_skill(mut self, skill: impl Into<String>) -> Self {
+        self.skills.push(skill.into());
+        self
+    }
+    
+    /// 设置单次任务成本
+    pub fn with_cost(mut self, cost: f64) -> Self {
+        self.cost = Some(cost);
+        self
+    }
+    
+    /// 设置延迟SLO
+    pub fn with_latency_slo(mut self, latency: Duration) -> Self {
+        self.latency_slo_ms = Some(latency.as_millis() as u64);
+        self
+    }
+    
+    /// 是否提供指定技能
+    pub fn provides(&self, skill: &str) -> bool {
+        self.name == skill || self.skills.iter().any(|s| s == skill)
+    }
 }
 
 /// Agent地理位置信息
@@ -0,0 +1,169 @@
+//! Document-level access control for retrieval
+//!
+//! An [`AccessPolicy`] turns the identity of the caller into mandatory metadata filters.
+//! [`RagPipeline::search`](crate::RagPipeline::search) merges them into every vector
+//! search, so users only retrieve chunks they are allowed to read regardless of the
+//! filters the caller asked for.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::error::{RagError, Result};
+use crate::types::RetrievalOptions;
+
+/// Identity of the user a search runs on behalf of
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UserContext {
+    /// User identifier
+    pub user_id: String,
+    /// Groups the user belongs to
+    pub groups: Vec<String>,
+    /// Additional attributes, e.g. tenant or clearance level
+    pub attributes: HashMap<String, Value>,
+}
+
+impl UserContext {
+    /// Create a context for a user
+    pub fn new(user_id: impl Into<String>) -> Self {
+        Self {
+            user_id: user_id.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Add a group membership
+    pub fn with_group(mut self, group: impl Into<String>) -> Self {
+        self.groups.push(group.into());
+        self
+    }
+
+    /// Set an attribute
+    pub fn with_attribute(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.attributes.insert(key.into(), value.into());
+        self
+    }
+}
+
+/// Policy deciding which documents a user may retrieve
+pub trait AccessPolicy: Send + Sync {
+    /// Mandatory metadata filters for the user; an error denies the search
+    fn filters(&self, user: &UserContext) -> Result<HashMap<String, Value>>;
+
+    /// Merge the mandatory filters into the caller's retrieval options
+    ///
+    /// Mandatory filters replace caller filters on the same key, so a caller can never
+    /// widen what the policy allows.
+    fn scope(&self, user: &UserContext, options: &RetrievalOptions) -> Result<RetrievalOptions> {
+        let mut scoped = options.clone();
+        let filter = scoped.filter.get_or_insert_with(HashMap::new);
+        filter.extend(self.filters(user)?);
+        Ok(scoped)
+    }
+}
+
+impl<F> AccessPolicy for F
+where
+    F: Fn(&UserContext) -> Result<HashMap<String, Value>> + Send + Sync,
+{
+    fn filters(&self, user: &UserContext) -> Result<HashMap<String, Value>> {
+        self(user)
+    }
+}
+
+/// Allow documents whose group field lists one of the user's groups
+///
+/// Documents carry the allowed groups as an array, e.g. `"allowed_groups": ["hr", "admin"]`.
+#[derive(Debug, Clone)]
+pub struct GroupAccessPolicy {
+    field: String,
+}
+
+impl GroupAccessPolicy {
+    /// Match the user's groups against the given metadata field
+    pub fn new(field: impl Into<String>) -> Self {
+        Self { field: field.into() }
+    }
+}
+
+impl Default for GroupAccessPolicy {
+    fn default() -> Self {
+        Self::new("allowed_groups")
+    }
+}
+
+impl AccessPolicy for GroupAccessPolicy {
+    fn filters(&self, user: &UserContext) -> Result<HashMap<String, Value>> {
+        if user.groups.is_empty() {
+            return Err(RagError::AccessDenied(format!("User '{}' belongs to no groups", user.user_id)));
+        }
+        let groups = user.groups.iter().cloned().map(Value::String).collect();
+        Ok(HashMap::from([(
+            self.field.clone(),
+            Value::Object(serde_json::Map::from_iter([("$in".to_string(), Value::Array(groups))])),
+        )]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embedding::EmbeddingProvider;
+    use crate::retriever::{InMemoryVectorStore, VectorStore};
+    use crate::types::{Document, Metadata};
+    use crate::RagPipeline;
+    use async_trait::async_trait;
+    use std::sync::Arc;
+
+    struct KeywordEmbedder;
+
+    #[async_trait]
+    impl EmbeddingProvider for KeywordEmbedder {
+        async fn generate_embedding(&self, text: &str) -> Result<Vec<f32>> {
+            Ok(vec![text.contains("salary") as u8 as f32, 1.0])
+        }
+    }
+
+    fn document(id: &str, content: &str, groups: &[&str]) -> Document {
+        let mut metadata = Metadata::new();
+        metadata.add("allowed_groups", groups.to_vec());
+        Document {
+            id: id.to_string(),
+            content: content.to_string(),
+            metadata,
+            embedding: Some(vec![content.contains("salary") as u8 as f32, 1.0]),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_respects_group_policy() {
+        let mut store = InMemoryVectorStore::new();
+        store.add_documents(vec![
+            document("payroll", "salary bands", &["hr"]),
+            document("handbook", "salary review process", &["hr", "staff"]),
+            document("wiki", "office hours", &["staff"]),
+        ]).await.unwrap();
+
+        let pipeline = RagPipeline::new(Box::new(KeywordEmbedder))
+            .with_access_policy(Arc::new(GroupAccessPolicy::default()));
+        let options = RetrievalOptions { limit: Some(10), ..Default::default() };
+
+        let staff = UserContext::new("bob").with_group("staff");
+        let result = pipeline.search(&store, "salary", &options, Some(&staff)).await.unwrap();
+        let mut ids: Vec<_> = result.documents.iter().map(|d| d.document.id.as_str()).collect();
+        ids.sort();
+        assert_eq!(ids, vec!["handbook", "wiki"]);
+
+        // Caller filters cannot widen access
+        let mut widened = options.clone();
+        widened.filter = Some(HashMap::from([("allowed_groups".to_string(), serde_json::json!(["hr"]))]));
+        let result = pipeline.search(&store, "salary", &widened, Some(&staff)).await.unwrap();
+        assert!(result.documents.iter().all(|d| d.document.id != "payroll"));
+
+        // Searches without a user are denied once a policy is configured
+        assert!(matches!(
+            pipeline.search(&store, "salary", &options, None).await,
+            Err(RagError::AccessDenied(_))
+        ));
+    }
+}
@@ -188,7 +188,7 @@ impl ContextManager {
     /// Estimate token count for documents
     fn estimate_tokens(&self, documents: &[ScoredDocument]) -> usize {
         documents.iter()
-            .map(|doc| doc.document.content.split_whitespace().count())
+            .map(|doc| window::estimate_tokens(&doc.document.content))
             .sum()
     }
 
@@ -1,6 +1,7 @@
 //! Context window management implementations
 
 use async_trait::async_trait;
+use lumosai_core::llm::tokenizer::tokenizer_for_model;
 use crate::{
     types::ScoredDocument,
     error::Result,
@@ -217,10 +218,9 @@ impl ContextWindow for HierarchicalWindow {
     }
 }
 
-/// Estimate token count for text (simple word-based estimation)
-fn estimate_tokens(text: &str) -> usize {
-    // Simple estimation: ~1.3 tokens per word on average
-    (text.split_whitespace().count() as f32 * 1.3) as usize
+/// Count tokens for text with the default OpenAI-compatible tokenizer
+pub(crate) fn estimate_tokens(text: &str) -> usize {
+    tokenizer_for_model("cl100k_base").count_tokens(text)
 }
 
 #[cfg(test)]
@@ -1,10 +1,24 @@
 use async_trait::async_trait;
 use uuid::Uuid;
 use regex::Regex;
+use std::sync::Arc;
+use lumosai_core::llm::tokenizer::{tokenizer_for_model, Tokenizer};
 
 use crate::error::{RagError, Result};
 use crate::types::{ChunkingConfig, ChunkingStrategy, Document};
 
+/// Resolve the tokenizer for a token-based strategy, preferring the model name
+/// over the encoding name and defaulting to the OpenAI `cl100k_base` encoding
+fn chunking_tokenizer(strategy: &ChunkingStrategy) -> Arc<dyn Tokenizer> {
+    let name = match strategy {
+        ChunkingStrategy::Token { encoding_name, model_name } => {
+            model_name.as_deref().or(encoding_name.as_deref())
+        }
+        _ => None,
+    };
+    tokenizer_for_model(name.unwrap_or("cl100k_base"))
+}
+
 /// Trait for document chunkers that split documents into smaller pieces
 #[async_trait]
 pub trait DocumentChunker: Send + Sync {
@@ -33,7 +47,7 @@ impl EnhancedChunker {
             ChunkingStrategy::Character { separator, is_separator_regex } => {
                 self.chunk_character(&document, config, separator, *is_separator_regex).await
             }
-            ChunkingStrategy::Token { encoding_name: _, model_name: _ } => {
+            ChunkingStrategy::Token { .. } => {
                 self.chunk_token(&document, config).await
             }
             ChunkingStrategy::Markdown { headers, return_each_line, strip_headers } => {
@@ -92,32 +106,14 @@ impl EnhancedChunker {
         self.create_chunk_documents(document, merged_chunks)
     }
 
-    /// Token-based chunking (simplified implementation)
+    /// Token-based chunking using the tokenizer of the configured model
     async fn chunk_token(
         &self,
         document: &Document,
         config: &ChunkingConfig,
     ) -> Result<Vec<Document>> {
-        // Simple word-based tokenization for now
-        // In a real implementation, you'd use a proper tokenizer like tiktoken
-        let words: Vec<&str> = document.content.split_whitespace().collect();
-        let chunk_size = config.chunk_size;
-        let overlap = config.chunk_overlap;
-
-        let mut chunks = Vec::new();
-        let mut i = 0;
-
-        while i < words.len() {
-            let end = std::cmp::min(i + chunk_size, words.len());
-            let chunk = words[i..end].join(" ");
-            chunks.push(chunk);
-
-            if end >= words.len() {
-                break;
-            }
-
-            i += chunk_size.saturating_sub(overlap);
-        }
+        let tokenizer = chunking_tokenizer(&config.strategy);
+        let chunks = tokenizer.split(&document.content, config.chunk_size, config.chunk_overlap);
 
         self.create_chunk_documents(document, chunks)
     }
@@ -542,8 +538,11 @@ impl TextChunker {
     
     /// 将文本分块
     pub fn chunk_text(&self, text: &str) -> Result<Vec<String>> {
-        // Use character-based chunking by default
-        self.chunk_by_chars(text)
+        match &self.config.strategy {
+            ChunkingStrategy::Token { .. } => self.chunk_by_tokens(text, &self.config),
+            // Use character-based chunking by default
+            _ => self.chunk_by_chars(text),
+        }
     }
     
     /// 将文档分块
@@ -598,98 +597,14 @@ impl TextChunker {
         Ok(chunks)
     }
     
-    /// 按句子分块
-    fn chunk_by_sentences(&self, text: &str) -> Result<Vec<String>> {
-        // 不使用正则表达式的简单句子分割方法
-        let sentence_endings = ['.', '!', '?'];
-        
-        let mut sentences = Vec::new();
-        let mut current_sentence = String::new();
-        let mut last_char = ' ';
-        
-        for c in text.chars() {
-            current_sentence.push(c);
-            
-            // 如果当前字符是句子结束符号，且下一个字符是空格
-            if sentence_endings.contains(&last_char) && c.is_whitespace() {
-                sentences.push(current_sentence.clone());
-                current_sentence.clear();
-            }
-            
-            last_char = c;
-        }
-        
-        // 添加最后一个句子（如果有）
-        if !current_sentence.is_empty() {
-            sentences.push(current_sentence);
-        }
-        
-        // 将句子合并为块
-        let chunk_size = self.config.chunk_size;
-        let overlap = self.config.chunk_overlap;
-        
-        if chunk_size == 0 {
-            return Err(RagError::DocumentChunking("Chunk size cannot be zero".into()));
-        }
-        
-        let mut chunks = Vec::new();
-        let mut current_chunk = String::new();
-        let mut sentences_in_chunk = 0;
-        
-        for sentence in sentences {
-            // 如果当前块已经达到或接近目标大小，创建新块
-            if sentences_in_chunk >= chunk_size {
-                chunks.push(current_chunk.clone());
-                
-                // 考虑重叠
-                if overlap > 0 {
-                    let overlap_sentences = sentences_in_chunk.min(overlap);
-                    sentences_in_chunk -= overlap_sentences;
-                } else {
-                    current_chunk.clear();
-                    sentences_in_chunk = 0;
-                }
-            }
-            
-            current_chunk.push_str(&sentence);
-            sentences_in_chunk += 1;
-        }
-        
-        // 添加最后一个块（如果有）
-        if !current_chunk.is_empty() {
-            chunks.push(current_chunk);
-        }
-        
-        Ok(chunks)
-    }
-    
     /// 按token分块
-    fn chunk_by_tokens(&self, text: &str) -> Result<Vec<String>> {
-        // 简单实现，将单词作为token
-        let words: Vec<&str> = text.split_whitespace().collect();
-        let chunk_size = self.config.chunk_size;
-        let overlap = self.config.chunk_overlap;
-        
-        if chunk_size == 0 {
+    fn chunk_by_tokens(&self, text: &str, config: &ChunkingConfig) -> Result<Vec<String>> {
+        if config.chunk_size == 0 {
             return Err(RagError::DocumentChunking("Chunk size cannot be zero".into()));
         }
-        
-        let mut chunks = Vec::new();
-        let mut i = 0;
-        
-        while i < words.len() {
-            let end = std::cmp::min(i + chunk_size, words.len());
-            let chunk = words[i..end].join(" ");
-            chunks.push(chunk);
-            
-            if end >= words.len() {
-                break;
-            }
-            
-            i += chunk_size - overlap;
-        }
-        
-        Ok(chunks)
+
+        let tokenizer = chunking_tokenizer(&config.strategy);
+        Ok(tokenizer.split(text, config.chunk_size, config.chunk_overlap))
     }
 }
 
@@ -698,9 +613,7 @@ impl DocumentChunker for TextChunker {
     async fn chunk(&self, document: Document, config: &ChunkingConfig) -> Result<Vec<Document>> {
         let chunks = match &config.strategy {
             crate::types::ChunkingStrategy::Token { .. } => {
-                // A proper implementation would use a tokenizer library
-                // For this example, we'll approximate with sentences
-                self.chunk_by_sentences(&document.content)?
+                self.chunk_by_tokens(&document.content, config)?
             }
             _ => {
                 self.chunk_by_chars(&document.content)?
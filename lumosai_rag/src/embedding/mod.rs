@@ -4,6 +4,8 @@
 
 mod provider;
 pub mod openai;
+pub mod truncate;
 
 pub use provider::{EmbeddingProvider, utils};
-pub use openai::OpenAIEmbeddingProvider; 
\ No newline at end of file
+pub use openai::OpenAIEmbeddingProvider;
+pub use truncate::TruncatedEmbeddingProvider;
@@ -1,5 +1,6 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use lumosai_core::config::http_client_for;
 
 use crate::error::{RagError, Result};
 use crate::embedding::provider::{EmbeddingProvider, utils};
@@ -17,12 +18,17 @@ pub struct OpenAIEmbeddingProvider {
     
     /// HTTP client
     client: reqwest::Client,
+    
+    /// Output dimensions for models that support shortening (text-embedding-3-*)
+    dimensions: Option<usize>,
 }
 
 #[derive(Debug, Serialize)]
 struct EmbeddingRequest {
     model: String,
     input: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dimensions: Option<usize>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -51,15 +57,26 @@ impl OpenAIEmbeddingProvider {
             api_key,
             model,
             base_url: "https://api.openai.com/v1".to_string(),
-            client: reqwest::Client::new(),
+            client: http_client_for("https://api.openai.com/v1"),
+            dimensions: None,
         }
     }
     
     /// Set a custom base URL for the API
     pub fn with_base_url(mut self, base_url: String) -> Self {
+        self.client = http_client_for(&base_url);
         self.base_url = base_url;
         self
     }
+    
+    /// Request shortened embeddings with the given number of dimensions
+    ///
+    /// The API truncates and renormalizes server-side, which is cheaper than
+    /// truncating full embeddings locally.
+    pub fn with_dimensions(mut self, dimensions: usize) -> Self {
+        self.dimensions = Some(dimensions);
+        self
+    }
 }
 
 #[async_trait]
@@ -88,6 +105,7 @@ impl EmbeddingProvider for OpenAIEmbeddingProvider {
         let request = EmbeddingRequest {
             model: self.model.clone(),
             input: texts.to_vec(),
+            dimensions: self.dimensions,
         };
         
         let response = self.client
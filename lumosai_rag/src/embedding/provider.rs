@@ -58,6 +58,20 @@ pub trait EmbeddingProvider: Send + Sync {
 
 /// Utility functions for embeddings
 pub mod utils {
+    /// Keep the leading `dimensions` components of a Matryoshka embedding
+    ///
+    /// With `renormalize` the result is scaled back to unit length.
+    pub fn truncate_embedding(embedding: &[f32], dimensions: usize, renormalize: bool) -> Vec<f32> {
+        let mut truncated = embedding[..dimensions.min(embedding.len())].to_vec();
+        if renormalize {
+            let norm = truncated.iter().map(|x| x * x).sum::<f32>().sqrt();
+            if norm > 0.0 {
+                truncated.iter_mut().for_each(|x| *x /= norm);
+            }
+        }
+        truncated
+    }
+    
     /// Compute cosine similarity between two vectors
     pub fn compute_cosine_similarity(vec1: &[f32], vec2: &[f32]) -> f32 {
         if vec1.len() != vec2.len() || vec1.is_empty() {
@@ -0,0 +1,98 @@
+use async_trait::async_trait;
+
+use crate::error::{RagError, Result};
+use crate::embedding::provider::{EmbeddingProvider, utils};
+
+/// Embedding provider that truncates Matryoshka embeddings to fewer dimensions
+///
+/// Models trained with Matryoshka representation learning keep most of their quality
+/// in the leading dimensions, so e.g. 256 of 1536 dimensions can be stored at a sixth
+/// of the cost.
+pub struct TruncatedEmbeddingProvider<P: EmbeddingProvider> {
+    inner: P,
+    dimensions: usize,
+    renormalize: bool,
+}
+
+impl<P: EmbeddingProvider> TruncatedEmbeddingProvider<P> {
+    /// Truncate the embeddings of `inner` to `dimensions`, renormalized to unit length
+    pub fn new(inner: P, dimensions: usize) -> Self {
+        Self {
+            inner,
+            dimensions,
+            renormalize: true,
+        }
+    }
+
+    /// Set whether truncated embeddings are renormalized
+    pub fn with_renormalize(mut self, renormalize: bool) -> Self {
+        self.renormalize = renormalize;
+        self
+    }
+
+    /// Number of dimensions produced
+    pub fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    /// Wrapped provider, e.g. to embed at full dimension for rescoring
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+
+    fn truncate(&self, embedding: Vec<f32>) -> Result<Vec<f32>> {
+        if embedding.len() < self.dimensions {
+            return Err(RagError::Embedding(format!(
+                "Cannot truncate a {}-dimensional embedding to {} dimensions",
+                embedding.len(),
+                self.dimensions
+            )));
+        }
+        Ok(utils::truncate_embedding(&embedding, self.dimensions, self.renormalize))
+    }
+}
+
+#[async_trait]
+impl<P: EmbeddingProvider> EmbeddingProvider for TruncatedEmbeddingProvider<P> {
+    async fn generate_embedding(&self, text: &str) -> Result<Vec<f32>> {
+        self.truncate(self.inner.generate_embedding(text).await?)
+    }
+
+    async fn embed_text(&self, text: &str) -> Result<Vec<f32>> {
+        self.truncate(self.inner.embed_text(text).await?)
+    }
+
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        self.inner
+            .embed_batch(texts)
+            .await?
+            .into_iter()
+            .map(|embedding| self.truncate(embedding))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FixedProvider;
+
+    #[async_trait]
+    impl EmbeddingProvider for FixedProvider {
+        async fn generate_embedding(&self, _text: &str) -> Result<Vec<f32>> {
+            Ok(vec![3.0, 4.0, 12.0, 0.5])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_truncates_and_renormalizes() {
+        let provider = TruncatedEmbeddingProvider::new(FixedProvider, 2);
+        assert_eq!(provider.embed_text("hello").await.unwrap(), vec![0.6, 0.8]);
+
+        let raw = TruncatedEmbeddingProvider::new(FixedProvider, 3).with_renormalize(false);
+        assert_eq!(raw.embed_batch(&["a".to_string()]).await.unwrap(), vec![vec![3.0, 4.0, 12.0]]);
+
+        assert!(TruncatedEmbeddingProvider::new(FixedProvider, 8).embed_text("hello").await.is_err());
+    }
+}
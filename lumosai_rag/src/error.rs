@@ -27,6 +27,10 @@ pub enum RagError {
     #[error("Retrieval error: {0}")]
     Retrieval(String),
 
+    /// Access denied by the access policy
+    #[error("Access denied: {0}")]
+    AccessDenied(String),
+
     /// Invalid configuration
     #[error("Invalid configuration: {0}")]
     Configuration(String),
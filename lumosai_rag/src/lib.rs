@@ -9,6 +9,7 @@
 //! - Embedding generation: converting text to vector representations
 //! - Retrieval: storing and retrieving relevant documents based on queries
 
+pub mod access;
 pub mod document;
 pub mod embedding;
 pub mod retriever;
@@ -26,6 +27,7 @@ pub mod retrieval {
     pub use crate::retriever::*;
 }
 
+pub use access::{AccessPolicy, GroupAccessPolicy, UserContext};
 pub use error::RagError;
 pub use types::*;
 pub use pipeline::{RagPipeline, RagPipelineBuilder};
\ No newline at end of file
@@ -4,17 +4,21 @@
 //! similar to Mastra's document processing pipeline.
 
 use std::collections::HashMap;
+use std::sync::Arc;
 
+use crate::access::{AccessPolicy, UserContext};
 use crate::document::{DocumentChunker, EnhancedChunker};
 use crate::embedding::EmbeddingProvider;
 use crate::error::{RagError, Result};
-use crate::types::{Document, ProcessingConfig};
+use crate::retriever::VectorStore;
+use crate::types::{Document, ProcessingConfig, RetrievalOptions, RetrievalResult};
 
 /// RAG Pipeline for processing documents and performing retrieval
 pub struct RagPipeline {
     chunker: Box<dyn DocumentChunker>,
     embedding_provider: Box<dyn EmbeddingProvider>,
     config: ProcessingConfig,
+    access_policy: Option<Arc<dyn AccessPolicy>>,
 }
 
 impl RagPipeline {
@@ -24,6 +28,7 @@ impl RagPipeline {
             chunker: Box::new(EnhancedChunker::new()),
             embedding_provider,
             config: ProcessingConfig::default(),
+            access_policy: None,
         }
     }
     
@@ -36,6 +41,7 @@ impl RagPipeline {
             chunker: Box::new(EnhancedChunker::new()),
             embedding_provider,
             config,
+            access_policy: None,
         }
     }
     
@@ -43,6 +49,33 @@ impl RagPipeline {
     pub fn builder() -> RagPipelineBuilder {
         RagPipelineBuilder::new()
     }
+
+    /// Enforce an access policy on every search
+    pub fn with_access_policy(mut self, policy: Arc<dyn AccessPolicy>) -> Self {
+        self.access_policy = Some(policy);
+        self
+    }
+
+    /// Search a vector store on behalf of a user
+    ///
+    /// When an access policy is configured its mandatory filters are merged into the
+    /// options, and searches without a user are denied.
+    pub async fn search(
+        &self,
+        store: &dyn VectorStore,
+        query: &str,
+        options: &RetrievalOptions,
+        user: Option<&UserContext>,
+    ) -> Result<RetrievalResult> {
+        let options = match (&self.access_policy, user) {
+            (Some(policy), Some(user)) => policy.scope(user, options)?,
+            (Some(_), None) => {
+                return Err(RagError::AccessDenied("Search requires a user context".to_string()));
+            }
+            (None, _) => options.clone(),
+        };
+        store.query_by_text(query, &options, self.embedding_provider.as_ref()).await
+    }
     
     /// Process a single document through the RAG pipeline
     pub async fn process_document(&self, document: Document) -> Result<Vec<Document>> {
@@ -206,6 +239,7 @@ pub struct RagPipelineBuilder {
     embedding_provider: Option<Box<dyn EmbeddingProvider>>,
     chunker: Option<Box<dyn DocumentChunker>>,
     config: ProcessingConfig,
+    access_policy: Option<Arc<dyn AccessPolicy>>,
 }
 
 impl RagPipelineBuilder {
@@ -214,6 +248,7 @@ impl RagPipelineBuilder {
             embedding_provider: None,
             chunker: None,
             config: ProcessingConfig::default(),
+            access_policy: None,
         }
     }
     
@@ -247,6 +282,11 @@ impl RagPipelineBuilder {
         self
     }
     
+    pub fn access_policy(mut self, policy: Arc<dyn AccessPolicy>) -> Self {
+        self.access_policy = Some(policy);
+        self
+    }
+    
     pub fn extract_metadata(mut self, extract_title: bool, extract_summary: bool, extract_keywords: bool) -> Self {
         self.config.extraction.extract_title = extract_title;
         self.config.extraction.extract_summary = extract_summary;
@@ -264,6 +304,7 @@ impl RagPipelineBuilder {
             chunker,
             embedding_provider,
             config: self.config,
+            access_policy: self.access_policy,
         })
     }
 }
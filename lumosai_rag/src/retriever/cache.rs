@@ -0,0 +1,382 @@
+//! Retrieval result caching keyed by query embedding
+//!
+//! Queries are cached under their quantized, normalized embedding together with the
+//! retrieval options. Repeated queries hit the exact key; near-duplicate phrasings whose
+//! embeddings are within the similarity threshold of a cached query reuse its result.
+//! Either way the vector store round trip is skipped.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::embedding::{utils, EmbeddingProvider};
+use crate::error::Result;
+use crate::retriever::vector_store::VectorStore;
+use crate::types::{Document, RetrievalOptions, RetrievalResult};
+
+/// Retrieval cache configuration
+#[derive(Debug, Clone)]
+pub struct RetrievalCacheConfig {
+    /// Maximum number of cached queries
+    pub capacity: usize,
+    /// Minimum cosine similarity for a near-duplicate query to reuse a cached result
+    pub similarity_threshold: f32,
+    /// Entries older than this are ignored and evicted
+    pub ttl: Option<Duration>,
+}
+
+impl Default for RetrievalCacheConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 1024,
+            similarity_threshold: 0.98,
+            ttl: Some(Duration::from_secs(600)),
+        }
+    }
+}
+
+/// Cache hit and miss counters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetrievalCacheStats {
+    /// Lookups answered by an identical query
+    pub exact_hits: u64,
+    /// Lookups answered by a near-duplicate query
+    pub similar_hits: u64,
+    /// Lookups that went to the vector store
+    pub misses: u64,
+    /// Entries evicted to stay within capacity or TTL
+    pub evictions: u64,
+    /// Entries currently cached
+    pub entries: usize,
+}
+
+impl RetrievalCacheStats {
+    /// Fraction of lookups answered from the cache
+    pub fn hit_rate(&self) -> f64 {
+        let hits = self.exact_hits + self.similar_hits;
+        match hits + self.misses {
+            0 => 0.0,
+            total => hits as f64 / total as f64,
+        }
+    }
+}
+
+/// Quantized embedding plus the options it was retrieved with
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    embedding: Vec<i8>,
+    options: String,
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    embedding: Vec<f32>,
+    result: RetrievalResult,
+    inserted_at: Instant,
+    last_used: u64,
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    entries: HashMap<CacheKey, CacheEntry>,
+    clock: u64,
+    stats: RetrievalCacheStats,
+}
+
+/// LRU cache of retrieval results keyed by query embedding
+#[derive(Debug)]
+pub struct RetrievalCache {
+    config: RetrievalCacheConfig,
+    state: Mutex<CacheState>,
+}
+
+/// Normalize to unit length so quantization and similarity ignore the vector magnitude
+fn normalize(embedding: &[f32]) -> Vec<f32> {
+    let norm = embedding.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm == 0.0 {
+        return embedding.to_vec();
+    }
+    embedding.iter().map(|v| v / norm).collect()
+}
+
+fn quantize(normalized: &[f32]) -> Vec<i8> {
+    normalized.iter().map(|v| (v * 127.0).round().clamp(-127.0, 127.0) as i8).collect()
+}
+
+fn options_key(options: &RetrievalOptions) -> String {
+    // Filter maps serialize in arbitrary order, so sort them for a stable key
+    let filter = options.filter.as_ref().map(|filter| {
+        let mut pairs: Vec<_> = filter.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+        pairs.sort();
+        pairs.join("&")
+    });
+    format!("{:?}|{:?}|{:?}", options.limit, options.threshold, filter)
+}
+
+impl RetrievalCache {
+    /// Create a cache
+    pub fn new(config: RetrievalCacheConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(CacheState::default()),
+        }
+    }
+
+    /// Cache configuration
+    pub fn config(&self) -> &RetrievalCacheConfig {
+        &self.config
+    }
+
+    fn is_expired(&self, entry: &CacheEntry) -> bool {
+        self.config.ttl.is_some_and(|ttl| entry.inserted_at.elapsed() > ttl)
+    }
+
+    /// Look up the result for a query embedding
+    pub fn get(&self, embedding: &[f32], options: &RetrievalOptions) -> Option<RetrievalResult> {
+        let normalized = normalize(embedding);
+        let key = CacheKey { embedding: quantize(&normalized), options: options_key(options) };
+        let mut state = self.state.lock().ok()?;
+        state.clock += 1;
+        let now = state.clock;
+
+        if let Some(entry) = state.entries.get_mut(&key) {
+            if !self.is_expired(entry) {
+                entry.last_used = now;
+                let result = entry.result.clone();
+                state.stats.exact_hits += 1;
+                return Some(result);
+            }
+        }
+
+        // Fall back to the most similar live entry retrieved with the same options
+        let best = state.entries.iter()
+            .filter(|(candidate, entry)| candidate.options == key.options && !self.is_expired(entry))
+            .map(|(candidate, entry)| (candidate, utils::compute_cosine_similarity(&normalized, &entry.embedding)))
+            .filter(|(_, similarity)| *similarity >= self.config.similarity_threshold)
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(candidate, _)| candidate.clone());
+
+        match best.and_then(|candidate| state.entries.get_mut(&candidate)) {
+            Some(entry) => {
+                entry.last_used = now;
+                let result = entry.result.clone();
+                state.stats.similar_hits += 1;
+                Some(result)
+            }
+            None => {
+                state.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Store the result for a query embedding, evicting the least recently used entries
+    pub fn insert(&self, embedding: &[f32], options: &RetrievalOptions, result: RetrievalResult) {
+        if self.config.capacity == 0 {
+            return;
+        }
+        let normalized = normalize(embedding);
+        let key = CacheKey { embedding: quantize(&normalized), options: options_key(options) };
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        state.clock += 1;
+        let now = state.clock;
+
+        let before = state.entries.len();
+        state.entries.retain(|_, entry| !self.is_expired(entry));
+        state.stats.evictions += (before - state.entries.len()) as u64;
+
+        while state.entries.len() >= self.config.capacity && !state.entries.contains_key(&key) {
+            let oldest = state.entries.iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone());
+            match oldest {
+                Some(oldest) => {
+                    state.entries.remove(&oldest);
+                    state.stats.evictions += 1;
+                }
+                None => break,
+            }
+        }
+
+        state.entries.insert(key, CacheEntry {
+            embedding: normalized,
+            result,
+            inserted_at: Instant::now(),
+            last_used: now,
+        });
+        state.stats.entries = state.entries.len();
+    }
+
+    /// Drop all cached results, e.g. after the underlying store changed
+    pub fn invalidate(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.entries.clear();
+            state.stats.entries = 0;
+        }
+    }
+
+    /// Current hit-rate statistics
+    pub fn stats(&self) -> RetrievalCacheStats {
+        self.state.lock().map(|state| state.stats).unwrap_or_default()
+    }
+}
+
+/// Vector store wrapper that answers repeated queries from a [`RetrievalCache`]
+///
+/// Writes go to the inner store and invalidate the cache, so cached results never
+/// outlive the documents they were computed from.
+pub struct CachedVectorStore<S: VectorStore> {
+    inner: S,
+    cache: RetrievalCache,
+}
+
+impl<S: VectorStore> CachedVectorStore<S> {
+    /// Wrap a vector store with the default cache configuration
+    pub fn new(inner: S) -> Self {
+        Self::with_config(inner, RetrievalCacheConfig::default())
+    }
+
+    /// Wrap a vector store with a custom cache configuration
+    pub fn with_config(inner: S, config: RetrievalCacheConfig) -> Self {
+        Self { inner, cache: RetrievalCache::new(config) }
+    }
+
+    /// Underlying store
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Cache hit-rate statistics
+    pub fn cache_stats(&self) -> RetrievalCacheStats {
+        self.cache.stats()
+    }
+}
+
+#[async_trait]
+impl<S: VectorStore> VectorStore for CachedVectorStore<S> {
+    async fn add_document(&mut self, document: Document) -> Result<()> {
+        self.cache.invalidate();
+        self.inner.add_document(document).await
+    }
+
+    async fn add_documents(&mut self, documents: Vec<Document>) -> Result<()> {
+        self.cache.invalidate();
+        self.inner.add_documents(documents).await
+    }
+
+    async fn update_document(&mut self, document: Document) -> Result<()> {
+        self.cache.invalidate();
+        self.inner.update_document(document).await
+    }
+
+    async fn delete_document(&mut self, document_id: &str) -> Result<()> {
+        self.cache.invalidate();
+        self.inner.delete_document(document_id).await
+    }
+
+    async fn query_by_text(
+        &self,
+        query: &str,
+        options: &RetrievalOptions,
+        embedding_provider: &dyn EmbeddingProvider,
+    ) -> Result<RetrievalResult> {
+        let embedding = embedding_provider.embed_text(query).await?;
+        self.query_by_vector(&embedding, options).await
+    }
+
+    async fn query_by_vector(&self, embedding: &[f32], options: &RetrievalOptions) -> Result<RetrievalResult> {
+        if let Some(result) = self.cache.get(embedding, options) {
+            return Ok(result);
+        }
+        let result = self.inner.query_by_vector(embedding, options).await?;
+        self.cache.insert(embedding, options, result.clone());
+        Ok(result)
+    }
+
+    async fn get_document(&self, document_id: &str) -> Result<Option<Document>> {
+        self.inner.get_document(document_id).await
+    }
+
+    async fn get_documents(&self, document_ids: &[String]) -> Result<Vec<Document>> {
+        self.inner.get_documents(document_ids).await
+    }
+
+    async fn get_all_documents(&self) -> Result<Vec<Document>> {
+        self.inner.get_all_documents().await
+    }
+
+    async fn count_documents(&self) -> Result<usize> {
+        self.inner.count_documents().await
+    }
+
+    async fn clear(&mut self) -> Result<()> {
+        self.cache.invalidate();
+        self.inner.clear().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::retriever::InMemoryVectorStore;
+    use crate::types::Metadata;
+
+    fn document(id: &str, embedding: Vec<f32>) -> Document {
+        Document {
+            id: id.to_string(),
+            content: format!("content of {}", id),
+            metadata: Metadata::new(),
+            embedding: Some(embedding),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_exact_and_near_duplicate_hits() {
+        let mut store = CachedVectorStore::new(InMemoryVectorStore::new());
+        store.add_document(document("rust", vec![1.0, 0.0, 0.0])).await.unwrap();
+        store.add_document(document("go", vec![0.0, 1.0, 0.0])).await.unwrap();
+        let options = RetrievalOptions::default();
+
+        let first = store.query_by_vector(&[0.9, 0.1, 0.0], &options).await.unwrap();
+        let repeated = store.query_by_vector(&[0.9, 0.1, 0.0], &options).await.unwrap();
+        let near = store.query_by_vector(&[0.9, 0.14, 0.0], &options).await.unwrap();
+        assert_eq!(first.documents[0].document.id, "rust");
+        assert_eq!(repeated.documents[0].document.id, "rust");
+        assert_eq!(near.documents[0].document.id, "rust");
+
+        // Different options or a dissimilar query go to the store
+        store.query_by_vector(&[0.9, 0.1, 0.0], &RetrievalOptions { limit: Some(1), ..Default::default() }).await.unwrap();
+        store.query_by_vector(&[0.1, 0.9, 0.0], &options).await.unwrap();
+
+        let stats = store.cache_stats();
+        assert_eq!((stats.exact_hits, stats.similar_hits, stats.misses), (1, 1, 3));
+        assert!((stats.hit_rate() - 0.4).abs() < 1e-9);
+
+        // Writes invalidate cached results
+        store.add_document(document("zig", vec![0.9, 0.1, 0.0])).await.unwrap();
+        let after = store.query_by_vector(&[0.9, 0.1, 0.0], &options).await.unwrap();
+        assert_eq!(after.documents[0].document.id, "zig");
+    }
+
+    #[test]
+    fn test_lru_eviction() {
+        let cache = RetrievalCache::new(RetrievalCacheConfig { capacity: 2, ..Default::default() });
+        let options = RetrievalOptions::default();
+        let result = RetrievalResult { documents: Vec::new(), total_count: 0 };
+
+        cache.insert(&[1.0, 0.0], &options, result.clone());
+        cache.insert(&[0.0, 1.0], &options, result.clone());
+        assert!(cache.get(&[1.0, 0.0], &options).is_some());
+        cache.insert(&[-1.0, 0.0], &options, result);
+
+        // [0, 1] was least recently used
+        assert!(cache.get(&[0.0, 1.0], &options).is_none());
+        assert!(cache.get(&[1.0, 0.0], &options).is_some());
+        assert_eq!(cache.stats().evictions, 1);
+        assert_eq!(cache.stats().entries, 2);
+    }
+}
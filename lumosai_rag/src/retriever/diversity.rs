@@ -0,0 +1,248 @@
+//! Result diversification for retrieval
+//!
+//! Chunked corpora often contain the same paragraph several times (copied pages,
+//! overlapping chunks, mirrored documents). Without a diversification stage the top-k
+//! context can be filled with copies of one passage. This module provides:
+//! - Near-duplicate suppression: drop results too similar to a higher-ranked one
+//! - Maximal marginal relevance (MMR): trade relevance against redundancy when selecting
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::{
+    embedding::utils,
+    error::Result,
+    retriever::Retriever,
+    types::{Document, RetrievalOptions, RetrievalRequest, RetrievalResult, ScoredDocument},
+};
+
+/// Configuration for result diversification
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiversityConfig {
+    /// MMR trade-off between relevance (1.0) and diversity (0.0); `None` keeps score order
+    pub mmr_lambda: Option<f32>,
+    /// Similarity at or above which a result counts as a duplicate; `None` keeps duplicates
+    pub duplicate_threshold: Option<f32>,
+    /// How many times the requested limit to fetch as candidates
+    pub candidate_multiplier: usize,
+}
+
+impl Default for DiversityConfig {
+    fn default() -> Self {
+        Self {
+            mmr_lambda: Some(0.7),
+            duplicate_threshold: Some(0.95),
+            candidate_multiplier: 3,
+        }
+    }
+}
+
+impl DiversityConfig {
+    /// Set the MMR trade-off
+    pub fn with_mmr(mut self, lambda: f32) -> Self {
+        self.mmr_lambda = Some(lambda.clamp(0.0, 1.0));
+        self
+    }
+
+    /// Select by score only, with duplicate suppression still applied
+    pub fn without_mmr(mut self) -> Self {
+        self.mmr_lambda = None;
+        self
+    }
+
+    /// Set the duplicate similarity threshold
+    pub fn with_duplicate_threshold(mut self, threshold: f32) -> Self {
+        self.duplicate_threshold = Some(threshold);
+        self
+    }
+
+    /// Keep near-duplicate results
+    pub fn without_duplicate_suppression(mut self) -> Self {
+        self.duplicate_threshold = None;
+        self
+    }
+
+    /// Set the candidate multiplier
+    pub fn with_candidate_multiplier(mut self, multiplier: usize) -> Self {
+        self.candidate_multiplier = multiplier.max(1);
+        self
+    }
+}
+
+/// Word trigram shingles of normalized content, used when embeddings are missing
+fn shingles(content: &str) -> HashSet<String> {
+    let words: Vec<String> = content
+        .split_whitespace()
+        .map(|word| word.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect::<String>())
+        .filter(|word| !word.is_empty())
+        .collect();
+    if words.len() < 3 {
+        return std::iter::once(words.join(" ")).collect();
+    }
+    words.windows(3).map(|window| window.join(" ")).collect()
+}
+
+/// Pairwise similarity of two documents
+///
+/// Uses embedding cosine similarity when both documents have embeddings and falls back
+/// to the Jaccard similarity of word trigrams otherwise.
+fn document_similarity(a: &Document, a_shingles: &HashSet<String>, b: &Document, b_shingles: &HashSet<String>) -> f32 {
+    if let (Some(x), Some(y)) = (&a.embedding, &b.embedding) {
+        if x.len() == y.len() {
+            return utils::compute_cosine_similarity(x, y);
+        }
+    }
+    let union = a_shingles.union(b_shingles).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a_shingles.intersection(b_shingles).count() as f32 / union as f32
+}
+
+/// Select up to `limit` diverse results from score-ordered candidates
+pub fn diversify(mut candidates: Vec<ScoredDocument>, limit: usize, config: &DiversityConfig) -> Vec<ScoredDocument> {
+    if config.mmr_lambda.is_none() && config.duplicate_threshold.is_none() {
+        candidates.truncate(limit);
+        return candidates;
+    }
+    candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+    let shingles: Vec<HashSet<String>> = candidates.iter().map(|c| shingles(&c.document.content)).collect();
+    let mut remaining: Vec<usize> = (0..candidates.len()).collect();
+    let mut selected: Vec<usize> = Vec::with_capacity(limit);
+
+    while selected.len() < limit && !remaining.is_empty() {
+        let mut best: Option<(usize, f32)> = None;
+        remaining.retain(|&i| {
+            let redundancy = selected
+                .iter()
+                .map(|&j| document_similarity(&candidates[i].document, &shingles[i], &candidates[j].document, &shingles[j]))
+                .fold(0.0f32, f32::max);
+            if config.duplicate_threshold.is_some_and(|threshold| redundancy >= threshold) {
+                return false;
+            }
+            let value = match config.mmr_lambda {
+                Some(lambda) => lambda * candidates[i].score - (1.0 - lambda) * redundancy,
+                None => candidates[i].score,
+            };
+            if best.is_none_or(|(_, best_value)| value > best_value) {
+                best = Some((i, value));
+            }
+            true
+        });
+        match best {
+            Some((index, _)) => {
+                selected.push(index);
+                remaining.retain(|&i| i != index);
+            }
+            None => break,
+        }
+    }
+
+    let mut slots: Vec<Option<ScoredDocument>> = candidates.into_iter().map(Some).collect();
+    selected.into_iter().filter_map(|i| slots[i].take()).collect()
+}
+
+/// Retriever wrapper that over-fetches candidates and diversifies them
+pub struct DiversifiedRetriever<R: Retriever> {
+    inner: R,
+    config: DiversityConfig,
+}
+
+impl<R: Retriever> DiversifiedRetriever<R> {
+    /// Wrap a retriever with the default diversity configuration
+    pub fn new(inner: R) -> Self {
+        Self::with_config(inner, DiversityConfig::default())
+    }
+
+    /// Wrap a retriever with a custom diversity configuration
+    pub fn with_config(inner: R, config: DiversityConfig) -> Self {
+        Self { inner, config }
+    }
+}
+
+#[async_trait]
+impl<R: Retriever> Retriever for DiversifiedRetriever<R> {
+    async fn retrieve(&self, request: &RetrievalRequest) -> Result<RetrievalResult> {
+        let limit = request.options.limit.unwrap_or(5);
+        let candidate_request = RetrievalRequest {
+            query: request.query.clone(),
+            options: RetrievalOptions {
+                limit: Some(limit * self.config.candidate_multiplier.max(1)),
+                ..request.options.clone()
+            },
+        };
+        let candidates = self.inner.retrieve(&candidate_request).await?;
+        let documents = diversify(candidates.documents, limit, &self.config);
+        let total_count = documents.len();
+        Ok(RetrievalResult { documents, total_count })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Metadata;
+
+    fn scored(id: &str, content: &str, embedding: Option<Vec<f32>>, score: f32) -> ScoredDocument {
+        ScoredDocument {
+            document: Document {
+                id: id.to_string(),
+                content: content.to_string(),
+                metadata: Metadata::new(),
+                embedding,
+            },
+            score,
+        }
+    }
+
+    struct FixedRetriever(Vec<ScoredDocument>);
+
+    #[async_trait]
+    impl Retriever for FixedRetriever {
+        async fn retrieve(&self, request: &RetrievalRequest) -> Result<RetrievalResult> {
+            let documents: Vec<_> = self.0.iter().take(request.options.limit.unwrap_or(5)).cloned().collect();
+            let total_count = documents.len();
+            Ok(RetrievalResult { documents, total_count })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_near_duplicates_are_suppressed() {
+        let paragraph = "Rust guarantees memory safety without a garbage collector";
+        let retriever = DiversifiedRetriever::with_config(
+            FixedRetriever(vec![
+                scored("a", paragraph, None, 0.95),
+                scored("b", &format!("{}.", paragraph), None, 0.94),
+                scored("c", &paragraph.to_uppercase(), None, 0.93),
+                scored("d", "Cargo manages dependencies and builds crates", None, 0.6),
+            ]),
+            DiversityConfig::default().without_mmr(),
+        );
+        let request = RetrievalRequest {
+            query: "rust safety".to_string(),
+            options: RetrievalOptions { limit: Some(2), ..Default::default() },
+        };
+
+        let result = retriever.retrieve(&request).await.unwrap();
+        let ids: Vec<_> = result.documents.iter().map(|d| d.document.id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "d"]);
+    }
+
+    #[test]
+    fn test_mmr_prefers_novel_results() {
+        let candidates = vec![
+            scored("a", "first", Some(vec![1.0, 0.0]), 0.9),
+            scored("b", "second", Some(vec![0.9, 0.3]), 0.85),
+            scored("c", "third", Some(vec![0.0, 1.0]), 0.7),
+        ];
+
+        let relevance_only = diversify(candidates.clone(), 2, &DiversityConfig::default().with_mmr(1.0).without_duplicate_suppression());
+        assert_eq!(relevance_only[1].document.id, "b");
+
+        let diverse = diversify(candidates, 2, &DiversityConfig::default().with_mmr(0.5));
+        let ids: Vec<_> = diverse.iter().map(|d| d.document.id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "c"]);
+    }
+}
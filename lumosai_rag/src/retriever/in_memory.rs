@@ -50,12 +50,9 @@ impl InMemoryVectorStore {
                     let mut matches_filter = true;
                     
                     for (key, value) in filter {
-                        if !doc.metadata.fields.contains_key(key) {
-                            matches_filter = false;
-                            break;
-                        }
-                        
-                        if &doc.metadata.fields[key] != value {
+                        let matches = doc.metadata.fields.get(key)
+                            .is_some_and(|field| metadata_matches(field, value));
+                        if !matches {
                             matches_filter = false;
                             break;
                         }
@@ -192,6 +189,20 @@ impl VectorStore for InMemoryVectorStore {
     }
 }
 
+/// Match a metadata field against a filter value
+///
+/// Plain values match by equality. `{"$in": [...]}` matches when the field equals one of
+/// the listed values or, for array fields, shares at least one element with the list.
+fn metadata_matches(field: &serde_json::Value, filter: &serde_json::Value) -> bool {
+    match filter.get("$in").and_then(|values| values.as_array()) {
+        Some(values) => match field.as_array() {
+            Some(items) => items.iter().any(|item| values.contains(item)),
+            None => values.contains(field),
+        },
+        None => field == filter,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -19,8 +19,14 @@ mod vector_store;
 mod in_memory;
 pub mod hybrid;
 pub mod bm25;
+pub mod cache;
+pub mod diversity;
+pub mod rerank;
 
 pub use vector_store::VectorStore;
 pub use in_memory::InMemoryVectorStore;
 pub use hybrid::{HybridRetriever, HybridSearchConfig, RerankStrategy, KeywordRetriever};
-pub use bm25::{BM25Retriever, BM25Config, BM25Stats};
\ No newline at end of file
+pub use bm25::{BM25Retriever, BM25Config, BM25Stats};
+pub use cache::{CachedVectorStore, RetrievalCache, RetrievalCacheConfig, RetrievalCacheStats};
+pub use diversity::{diversify, DiversifiedRetriever, DiversityConfig};
+pub use rerank::{rerank_documents, Reranker, RerankingConfig, RerankingRetriever};
\ No newline at end of file
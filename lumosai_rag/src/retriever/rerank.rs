@@ -0,0 +1,197 @@
+//! Cross-encoder reranking for retrieval
+//!
+//! First-stage retrievers score the query and each document independently. A
+//! cross-encoder reads the query and a document together, which ranks far more
+//! accurately but is too slow to run over a whole corpus. The usual pipeline fetches a
+//! few dozen candidates cheaply and lets the reranker order them.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{RagError, Result},
+    retriever::Retriever,
+    types::{RetrievalOptions, RetrievalRequest, RetrievalResult, ScoredDocument},
+};
+
+pub use lumosai_vector_core::traits::Reranker;
+
+/// Configuration for reranking
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RerankingConfig {
+    /// How many times the requested limit to fetch as candidates
+    pub candidate_multiplier: usize,
+    /// Drop results scoring below this value
+    pub min_score: Option<f32>,
+}
+
+impl Default for RerankingConfig {
+    fn default() -> Self {
+        Self {
+            candidate_multiplier: 4,
+            min_score: None,
+        }
+    }
+}
+
+impl RerankingConfig {
+    /// Set the candidate multiplier
+    pub fn with_candidate_multiplier(mut self, multiplier: usize) -> Self {
+        self.candidate_multiplier = multiplier.max(1);
+        self
+    }
+
+    /// Set the minimum reranker score
+    pub fn with_min_score(mut self, min_score: f32) -> Self {
+        self.min_score = Some(min_score);
+        self
+    }
+}
+
+/// Reorder documents by reranker score and keep the best `limit`
+///
+/// Document scores are replaced with the reranker's scores.
+pub async fn rerank_documents<K: Reranker + ?Sized>(
+    reranker: &K,
+    query: &str,
+    documents: Vec<ScoredDocument>,
+    limit: usize,
+) -> Result<Vec<ScoredDocument>> {
+    if documents.is_empty() {
+        return Ok(documents);
+    }
+    let contents: Vec<String> = documents.iter().map(|d| d.document.content.clone()).collect();
+    let scores = reranker
+        .score(query, &contents)
+        .await
+        .map_err(|e| RagError::Retrieval(format!("Reranking with {} failed: {}", reranker.model_name(), e)))?;
+    if scores.len() != documents.len() {
+        return Err(RagError::Retrieval(format!(
+            "Reranker {} returned {} scores for {} documents",
+            reranker.model_name(),
+            scores.len(),
+            documents.len()
+        )));
+    }
+
+    let mut reranked: Vec<ScoredDocument> = documents
+        .into_iter()
+        .zip(scores)
+        .map(|(document, score)| ScoredDocument { score, ..document })
+        .collect();
+    reranked.sort_by(|a, b| b.score.total_cmp(&a.score));
+    reranked.truncate(limit);
+    Ok(reranked)
+}
+
+/// Retriever wrapper that over-fetches candidates and reranks them
+pub struct RerankingRetriever<R: Retriever, K: Reranker> {
+    inner: R,
+    reranker: K,
+    config: RerankingConfig,
+}
+
+impl<R: Retriever, K: Reranker> RerankingRetriever<R, K> {
+    /// Wrap a retriever with the default reranking configuration
+    pub fn new(inner: R, reranker: K) -> Self {
+        Self::with_config(inner, reranker, RerankingConfig::default())
+    }
+
+    /// Wrap a retriever with a custom reranking configuration
+    pub fn with_config(inner: R, reranker: K, config: RerankingConfig) -> Self {
+        Self { inner, reranker, config }
+    }
+}
+
+#[async_trait]
+impl<R: Retriever, K: Reranker> Retriever for RerankingRetriever<R, K> {
+    async fn retrieve(&self, request: &RetrievalRequest) -> Result<RetrievalResult> {
+        let limit = request.options.limit.unwrap_or(5);
+        // The inner threshold applies to first-stage scores, which are not comparable to
+        // reranker scores
+        let candidate_request = RetrievalRequest {
+            query: request.query.clone(),
+            options: RetrievalOptions {
+                limit: Some(limit * self.config.candidate_multiplier.max(1)),
+                threshold: None,
+                ..request.options.clone()
+            },
+        };
+        let candidates = self.inner.retrieve(&candidate_request).await?;
+        let mut documents = rerank_documents(&self.reranker, &request.query, candidates.documents, limit).await?;
+        if let Some(min_score) = self.config.min_score {
+            documents.retain(|d| d.score >= min_score);
+        }
+        let total_count = documents.len();
+        Ok(RetrievalResult { documents, total_count })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Document, Metadata};
+
+    fn scored(id: &str, content: &str, score: f32) -> ScoredDocument {
+        ScoredDocument {
+            document: Document {
+                id: id.to_string(),
+                content: content.to_string(),
+                metadata: Metadata::new(),
+                embedding: None,
+            },
+            score,
+        }
+    }
+
+    struct FixedRetriever(Vec<ScoredDocument>);
+
+    #[async_trait]
+    impl Retriever for FixedRetriever {
+        async fn retrieve(&self, request: &RetrievalRequest) -> Result<RetrievalResult> {
+            let documents: Vec<_> = self.0.iter().take(request.options.limit.unwrap_or(5)).cloned().collect();
+            let total_count = documents.len();
+            Ok(RetrievalResult { documents, total_count })
+        }
+    }
+
+    /// Scores documents by how many query words they contain
+    struct OverlapReranker;
+
+    #[async_trait]
+    impl Reranker for OverlapReranker {
+        async fn score(&self, query: &str, documents: &[String]) -> lumosai_vector_core::Result<Vec<f32>> {
+            Ok(documents
+                .iter()
+                .map(|document| query.split_whitespace().filter(|word| document.contains(word)).count() as f32)
+                .collect())
+        }
+
+        fn model_name(&self) -> &str {
+            "overlap"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_candidates_are_reranked() {
+        let retriever = RerankingRetriever::with_config(
+            FixedRetriever(vec![
+                scored("a", "pandas eat bamboo", 0.9),
+                scored("b", "rust is a language", 0.8),
+                scored("c", "rust borrow checker is strict", 0.7),
+                scored("d", "unrelated", 0.6),
+            ]),
+            OverlapReranker,
+            RerankingConfig::default().with_min_score(1.0),
+        );
+        let request = RetrievalRequest {
+            query: "rust borrow checker".to_string(),
+            options: RetrievalOptions { limit: Some(3), ..Default::default() },
+        };
+
+        let result = retriever.retrieve(&request).await.unwrap();
+        let ids: Vec<_> = result.documents.iter().map(|d| d.document.id.as_str()).collect();
+        assert_eq!(ids, vec!["c", "b"]);
+        assert_eq!(result.documents[0].score, 3.0);
+    }
+}
@@ -12,6 +12,7 @@ import { toggleVisibility } from './typescript/api-keys/toggle-visibility'
 import { autoExpand } from './typescript/console/auto-expand'
 import { initializeSidebar } from './typescript/layout/responsive-nav'
 import { streamingChat } from './typescript/console/streaming-chat'
+import { agentConsole } from './typescript/console/agent-console'
 import { formatter } from './typescript/console/format-json'
 import { copyPaste } from './typescript/console/copy-paste'
 import { snackBar } from './typescript/layout/snackbar'
@@ -38,6 +39,7 @@ function loadEverything() {
     autoExpand()
     formatter()
     streamingChat()
+    agentConsole()
     copyPaste()
     snackBar()
     selectMenu()
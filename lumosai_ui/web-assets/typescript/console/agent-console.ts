@@ -0,0 +1,341 @@
+import { Markdown } from "./markdown"
+
+// Drives the enhanced console: streams agent replies over SSE, renders tool calls
+// as they happen, supports stop/regenerate and keeps the conversation across reloads.
+
+const CONVERSATION_KEY = 'lumos-console-conversation'
+
+type StreamEvent =
+    | { type: 'start', conversation_id: string, message_id: string }
+    | { type: 'delta', content: string }
+    | { type: 'tool_call', id: string, name: string, arguments: string }
+    | { type: 'tool_result', id: string, name: string, success: boolean, result?: unknown, error?: string, execution_time_ms: number }
+    | { type: 'done', message_id: string, total_tokens?: number }
+    | { type: 'error', message: string, code?: string }
+
+interface StoredMessage {
+    role: 'user' | 'assistant' | 'system' | 'tool'
+    content?: string
+    tool_calls?: string
+    tool_call_id?: string
+}
+
+export const agentConsole = () => {
+    const root = document.getElementById('agent-console')
+    const form = document.getElementById('agent-console-form')
+    const streamUrl = root?.dataset.streamUrl
+
+    if (!root || !(form instanceof HTMLFormElement) || !streamUrl || root.dataset.initialized) {
+        return
+    }
+    root.dataset.initialized = 'true'
+
+    new AgentConsole(root, form, streamUrl).init()
+}
+
+class AgentConsole {
+    private markdown = new Markdown()
+    private messages: HTMLElement
+    private textarea: HTMLTextAreaElement | null
+    private sendButton: HTMLElement | null
+    private stopButton: HTMLElement | null
+    private regenerateButton: HTMLElement | null
+    private status: HTMLElement | null
+    private conversationId: string | null
+    private abortController: AbortController | null = null
+    private toolCards = new Map<string, HTMLElement>()
+
+    constructor(private root: HTMLElement, private form: HTMLFormElement, private streamUrl: string) {
+        this.messages = document.getElementById('agent-console-messages') ?? root
+        this.textarea = form.querySelector('textarea[name="message"]')
+        this.sendButton = document.getElementById('agent-console-send')
+        this.stopButton = document.getElementById('agent-console-stop')
+        this.regenerateButton = document.getElementById('agent-console-regenerate')
+        this.status = document.getElementById('agent-console-status')
+        this.conversationId = root.dataset.conversationId || localStorage.getItem(CONVERSATION_KEY)
+    }
+
+    init() {
+        this.form.addEventListener('submit', (event) => {
+            event.preventDefault()
+            const message = this.textarea?.value.trim()
+            if (message && !this.abortController) {
+                this.textarea!.value = ''
+                this.appendUserMessage(message)
+                this.stream({ message })
+            }
+        })
+
+        this.stopButton?.addEventListener('click', () => this.abortController?.abort())
+
+        this.regenerateButton?.addEventListener('click', () => {
+            if (!this.abortController && this.conversationId) {
+                this.removeLastReply()
+                this.stream({ regenerate: true })
+            }
+        })
+
+        document.getElementById('agent-console-new')?.addEventListener('click', (event) => {
+            event.preventDefault()
+            this.abortController?.abort()
+            this.setConversation(null)
+            this.messages.innerHTML = ''
+            this.regenerateButton?.classList.add('hidden')
+        })
+
+        if (this.conversationId) {
+            this.loadHistory(this.conversationId)
+        }
+    }
+
+    private async stream(body: { message?: string, regenerate?: boolean }) {
+        this.abortController = new AbortController()
+        this.setStreaming(true)
+
+        const reply = this.appendAssistantMessage()
+        let content = ''
+
+        try {
+            const response = await fetch(this.streamUrl, {
+                method: 'POST',
+                headers: { 'Content-Type': 'application/json' },
+                body: JSON.stringify({ ...body, conversation_id: this.conversationId }),
+                signal: this.abortController.signal,
+            })
+            if (!response.ok || !response.body) {
+                throw new Error(`请求失败: ${response.status}`)
+            }
+
+            for await (const event of readEvents(response.body)) {
+                switch (event.type) {
+                    case 'start':
+                        this.setConversation(event.conversation_id)
+                        break
+                    case 'delta':
+                        content += event.content
+                        reply.innerHTML = this.markdown.markdown(content)
+                        break
+                    case 'tool_call':
+                        reply.before(this.createToolCard(event.id, event.name, event.arguments))
+                        break
+                    case 'tool_result':
+                        this.completeToolCard(event)
+                        break
+                    case 'error':
+                        reply.insertAdjacentHTML('beforeend', `<p class="text-error">${escapeHtml(event.message)}</p>`)
+                        break
+                    case 'done':
+                        break
+                }
+                this.scrollToBottom()
+            }
+        } catch (error) {
+            if (this.abortController?.signal.aborted) {
+                reply.insertAdjacentHTML('beforeend', '<p class="text-xs text-base-content/50">已停止生成</p>')
+            } else {
+                reply.insertAdjacentHTML('beforeend', `<p class="text-error">${escapeHtml(String(error))}</p>`)
+            }
+        } finally {
+            this.abortController = null
+            this.setStreaming(false)
+        }
+    }
+
+    private async loadHistory(conversationId: string) {
+        const url = new URL(`/api/conversations/${conversationId}`, new URL(this.streamUrl, location.href))
+        try {
+            const response = await fetch(url)
+            const data = await response.json()
+            if (!data.success) {
+                // The conversation no longer exists on the server, start afresh.
+                this.setConversation(null)
+                return
+            }
+
+            this.setConversation(conversationId)
+            const results = new Map<string, StoredMessage>()
+            for (const message of data.messages as StoredMessage[]) {
+                if (message.role === 'tool' && message.tool_call_id) {
+                    results.set(message.tool_call_id, message)
+                }
+            }
+
+            for (const message of data.messages as StoredMessage[]) {
+                if (message.role === 'user') {
+                    this.appendUserMessage(message.content ?? '')
+                } else if (message.role === 'assistant') {
+                    for (const call of parseToolCalls(message.tool_calls)) {
+                        this.messages.append(this.createToolCard(call.id, call.function.name, call.function.arguments))
+                        const result = results.get(call.id)
+                        if (result?.content) {
+                            this.completeToolCard({ id: call.id, ...JSON.parse(result.content) })
+                        }
+                    }
+                    if (message.content) {
+                        this.appendAssistantMessage().innerHTML = this.markdown.markdown(message.content)
+                    }
+                }
+            }
+            this.regenerateButton?.classList.toggle('hidden', data.messages.length === 0)
+            this.scrollToBottom()
+        } catch (error) {
+            console.error('Failed to load conversation history', error)
+        }
+    }
+
+    private setConversation(conversationId: string | null) {
+        this.conversationId = conversationId
+        this.root.dataset.conversationId = conversationId ?? ''
+        if (conversationId) {
+            localStorage.setItem(CONVERSATION_KEY, conversationId)
+        } else {
+            localStorage.removeItem(CONVERSATION_KEY)
+        }
+
+        const label = document.getElementById('agent-console-conversation-label')
+        if (label) {
+            label.textContent = conversationId ? `对话ID: ${conversationId}` : ''
+            label.classList.toggle('hidden', !conversationId)
+        }
+    }
+
+    private setStreaming(streaming: boolean) {
+        this.sendButton?.classList.toggle('hidden', streaming)
+        this.stopButton?.classList.toggle('hidden', !streaming)
+        this.regenerateButton?.classList.toggle('hidden', streaming || !this.conversationId)
+        if (this.status) {
+            this.status.textContent = streaming ? '生成中...' : ''
+        }
+    }
+
+    private appendUserMessage(content: string) {
+        const message = document.createElement('div')
+        message.className = 'chat chat-end'
+        message.dataset.role = 'user'
+        message.innerHTML = `<div class="chat-bubble chat-bubble-primary whitespace-pre-wrap"></div>`
+        message.firstElementChild!.textContent = content
+        this.messages.append(message)
+        this.scrollToBottom()
+    }
+
+    private appendAssistantMessage(): HTMLElement {
+        const message = document.createElement('div')
+        message.className = 'chat chat-start'
+        message.dataset.role = 'assistant'
+        message.innerHTML = `<div class="chat-bubble prose max-w-none"><span class="loading loading-dots loading-sm"></span></div>`
+        this.messages.append(message)
+        return message.firstElementChild as HTMLElement
+    }
+
+    // Drop everything after the last user message, mirroring what the server does on regenerate.
+    private removeLastReply() {
+        const children = Array.from(this.messages.children) as HTMLElement[]
+        for (const child of children.reverse()) {
+            if (child.dataset.role === 'user') {
+                break
+            }
+            child.remove()
+        }
+    }
+
+    private createToolCard(id: string, name: string, args: string): HTMLElement {
+        const template = document.getElementById('tool-call-template')
+        const card = template instanceof HTMLTemplateElement
+            ? template.content.firstElementChild!.cloneNode(true) as HTMLElement
+            : document.createElement('div')
+
+        setField(card, 'name', name)
+        setField(card, 'arguments', formatJson(args))
+        this.toolCards.set(id, card)
+        return card
+    }
+
+    private completeToolCard(result: { id: string, success: boolean, result?: unknown, error?: string, execution_time_ms: number }) {
+        const card = this.toolCards.get(result.id)
+        if (!card) {
+            return
+        }
+
+        const status = card.querySelector<HTMLElement>('[data-field="status"]')
+        if (status) {
+            status.textContent = result.success ? '成功' : '失败'
+            status.className = `badge badge-sm ${result.success ? 'badge-success' : 'badge-error'}`
+        }
+        setField(card, 'time', `${result.execution_time_ms}ms`)
+        setField(card, 'result', result.success ? formatJson(JSON.stringify(result.result)) : result.error ?? '')
+        card.querySelector('[data-field="result-section"]')?.classList.remove('hidden')
+    }
+
+    private scrollToBottom() {
+        const scroll = document.getElementById('agent-console-scroll')
+        if (scroll) {
+            scroll.scrollTop = scroll.scrollHeight
+        }
+    }
+}
+
+// Parse a text/event-stream body into typed events.
+async function* readEvents(body: ReadableStream<Uint8Array>): AsyncGenerator<StreamEvent> {
+    const reader = body.getReader()
+    const decoder = new TextDecoder()
+    let buffer = ''
+
+    while (true) {
+        const { value, done } = await reader.read()
+        if (done) {
+            break
+        }
+
+        buffer += decoder.decode(value, { stream: true })
+        let boundary
+        while ((boundary = buffer.indexOf('\n\n')) >= 0) {
+            const block = buffer.slice(0, boundary)
+            buffer = buffer.slice(boundary + 2)
+
+            const data = block
+                .split('\n')
+                .filter((line) => line.startsWith('data:'))
+                .map((line) => line.slice(5).trim())
+                .join('\n')
+            if (data) {
+                try {
+                    yield JSON.parse(data) as StreamEvent
+                } catch {
+                    // keep-alive comments and other non-JSON payloads are ignored
+                }
+            }
+        }
+    }
+}
+
+function parseToolCalls(toolCalls?: string): { id: string, function: { name: string, arguments: string } }[] {
+    if (!toolCalls) {
+        return []
+    }
+    try {
+        return JSON.parse(toolCalls)
+    } catch {
+        return []
+    }
+}
+
+function setField(card: HTMLElement, field: string, value: string) {
+    const element = card.querySelector(`[data-field="${field}"]`)
+    if (element) {
+        element.textContent = value
+    }
+}
+
+function formatJson(value: string): string {
+    try {
+        return JSON.stringify(JSON.parse(value), null, 2)
+    } catch {
+        return value
+    }
+}
+
+function escapeHtml(value: string): string {
+    const div = document.createElement('div')
+    div.textContent = value
+    return div.innerHTML
+}
@@ -10,6 +10,7 @@
 - **成本分析**: 令牌使用成本和预算管理
 - **用户行为**: 用户活动模式和偏好分析
 - **报告生成**: 自动化报告生成和导出功能
+- **评估看板**: 评估套件各指标随时间的趋势，按模型/提示词变体对比并标出回归
 */
 
 #![allow(non_snake_case)]
@@ -17,6 +18,9 @@ use dioxus::prelude::*;
 use crate::app_layout::{Layout, SideBar};
 use crate::types::Rbac;
 use crate::charts::{TokenUsageChartCard, ApiRequestChartCard};
+use crate::routes;
+use daisy_rsx::*;
+use web_assets::files::*;
 
 // 分析数据类型定义
 #[derive(Clone, Debug, PartialEq)]
@@ -615,3 +619,261 @@ fn CostAnalysisCard(breakdown: Vec<CostBreakdown>) -> Element {
         }
     }
 }
+
+/// 评估套件的一次运行
+#[derive(Clone, Debug, PartialEq)]
+pub struct EvalRunView {
+    pub started_at_iso: String,
+    /// 模型与提示词变体标签
+    pub variant: String,
+    pub case_count: usize,
+    pub pass_rate: f64,
+    pub mean_score: f64,
+    pub passed: bool,
+    pub failures: Vec<String>,
+}
+
+/// 指标在某个变体上的一次取值
+#[derive(Clone, Debug, PartialEq)]
+pub struct EvalTrendPoint {
+    pub started_at_iso: String,
+    pub mean: f64,
+    pub pass_rate: f64,
+}
+
+/// 指标在某个变体上的趋势
+#[derive(Clone, Debug, PartialEq)]
+pub struct EvalMetricTrend {
+    pub metric: String,
+    pub variant: String,
+    pub points: Vec<EvalTrendPoint>,
+    /// 最近一次相比上一次下降时为 (上一次, 最近一次) 的平均得分
+    pub regression: Option<(f64, f64)>,
+}
+
+/// 评估看板数据
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EvalDashboard {
+    /// 报告历史目录
+    pub history_dir: String,
+    pub suites: Vec<String>,
+    pub selected_suite: Option<String>,
+    /// 所选套件的运行，最新的在前
+    pub runs: Vec<EvalRunView>,
+    pub trends: Vec<EvalMetricTrend>,
+}
+
+const TREND_COLORS: [&str; 6] = ["#3b82f6", "#10b981", "#f59e0b", "#8b5cf6", "#ef4444", "#06b6d4"];
+
+/// 评估看板页面
+pub fn page(rbac: Rbac, team_id: i32, dashboard: EvalDashboard) -> String {
+    let mut metrics: Vec<String> = Vec::new();
+    for trend in &dashboard.trends {
+        if !metrics.contains(&trend.metric) {
+            metrics.push(trend.metric.clone());
+        }
+    }
+    let regressions: Vec<EvalMetricTrend> = dashboard.trends.iter()
+        .filter(|trend| trend.regression.is_some())
+        .cloned()
+        .collect();
+
+    let page = rsx! {
+        Layout {
+            section_class: "p-4",
+            selected_item: SideBar::Analytics,
+            team_id: team_id,
+            rbac: rbac,
+            title: "Eval Dashboard",
+            header: rsx!(
+                h3 { "Eval Dashboard" }
+            ),
+
+            if dashboard.suites.is_empty() {
+                BlankSlate {
+                    heading: "No evaluation runs recorded yet",
+                    visual: nav_audit_svg.name,
+                    description: "Run `lumos eval <suite>` in the project; each run is stored in {dashboard.history_dir} and charted here"
+                }
+            } else {
+                div {
+                    class: "tabs tabs-box mb-4",
+                    for suite in &dashboard.suites {
+                        a {
+                            class: if dashboard.selected_suite.as_ref() == Some(suite) { "tab tab-active" } else { "tab" },
+                            href: format!("{}?suite={}", routes::analytics::Index{team_id}, encode_query(suite)),
+                            "{suite}"
+                        }
+                    }
+                }
+
+                for trend in &regressions {
+                    if let Some((previous, current)) = trend.regression {
+                        Alert {
+                            class: "mb-2",
+                            alert_color: AlertColor::Warn,
+                            "Regression: {trend.metric} ({trend.variant}) dropped from {previous:.3} to {current:.3}"
+                        }
+                    }
+                }
+
+                div {
+                    class: "grid gap-4 lg:grid-cols-2",
+                    for metric in metrics {
+                        EvalTrendCard {
+                            metric: metric.clone(),
+                            trends: dashboard.trends.iter().filter(|t| t.metric == metric).cloned().collect::<Vec<_>>()
+                        }
+                    }
+                }
+
+                Card {
+                    class: "has-data-table mt-4",
+                    CardHeader {
+                        title: "Runs"
+                    }
+                    CardBody {
+                        table {
+                            class: "table table-sm",
+                            thead {
+                                th { "Started" }
+                                th { "Variant" }
+                                th { "Status" }
+                                th { class: "text-right", "Cases" }
+                                th { class: "text-right", "Pass Rate" }
+                                th { class: "text-right", "Mean Score" }
+                            }
+                            tbody {
+                                for run in &dashboard.runs {
+                                    tr {
+                                        td {
+                                            RelativeTime {
+                                                format: RelativeTimeFormat::Relative,
+                                                datetime: &run.started_at_iso
+                                            }
+                                        }
+                                        td { class: "font-mono text-xs", "{run.variant}" }
+                                        td {
+                                            if run.passed {
+                                                Label { label_role: LabelRole::Success, "Passed" }
+                                            } else {
+                                                Label { label_role: LabelRole::Danger, "Failed" }
+                                                for failure in &run.failures {
+                                                    span { class: "block text-xs text-error", "{failure}" }
+                                                }
+                                            }
+                                        }
+                                        td { class: "text-right", "{run.case_count}" }
+                                        td { class: "text-right", {format!("{:.1}%", run.pass_rate * 100.0)} }
+                                        td { class: "text-right", {format!("{:.3}", run.mean_score)} }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    crate::render(page)
+}
+
+/// 单个指标的趋势图，每个变体一条折线
+#[component]
+fn EvalTrendCard(metric: String, trends: Vec<EvalMetricTrend>) -> Element {
+    // 所有变体共用按时间排序的横轴
+    let mut times: Vec<String> = trends.iter()
+        .flat_map(|trend| trend.points.iter().map(|p| p.started_at_iso.clone()))
+        .collect();
+    times.sort();
+    times.dedup();
+
+    let step = if times.len() > 1 { 360.0 / (times.len() - 1) as f64 } else { 0.0 };
+    let x = |time: &str| 20.0 + step * times.iter().position(|t| t == time).unwrap_or(0) as f64;
+    let y = |score: f64| 190.0 - score.clamp(0.0, 1.0) * 170.0;
+
+    rsx! {
+        Card {
+            CardHeader {
+                title: "{metric}"
+            }
+            CardBody {
+                div {
+                    class: "w-full h-56 p-2",
+                    svg {
+                        width: "100%",
+                        height: "100%",
+                        view_box: "0 0 400 200",
+                        for score in [0.0, 0.5, 1.0] {
+                            line {
+                                x1: "20", x2: "380",
+                                y1: "{y(score)}", y2: "{y(score)}",
+                                stroke: "#e5e7eb",
+                                stroke_dasharray: "4"
+                            }
+                            text {
+                                x: "0", y: "{y(score) + 3.0}",
+                                font_size: "9",
+                                "{score:.1}"
+                            }
+                        }
+                        for (i, trend) in trends.iter().enumerate() {
+                            g {
+                                polyline {
+                                    fill: "none",
+                                    stroke: TREND_COLORS[i % TREND_COLORS.len()],
+                                    stroke_width: "2",
+                                    points: trend.points.iter()
+                                        .map(|p| format!("{:.1},{:.1}", x(&p.started_at_iso), y(p.mean)))
+                                        .collect::<Vec<_>>()
+                                        .join(" ")
+                                }
+                                for point in &trend.points {
+                                    circle {
+                                        cx: "{x(&point.started_at_iso):.1}",
+                                        cy: "{y(point.mean):.1}",
+                                        r: "3",
+                                        fill: TREND_COLORS[i % TREND_COLORS.len()],
+                                        title {
+                                            {format!("{} · {:.3} · {:.0}% passed", point.started_at_iso, point.mean, point.pass_rate * 100.0)}
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                div {
+                    class: "flex flex-wrap gap-3 px-2 pb-2 text-xs",
+                    for (i, trend) in trends.iter().enumerate() {
+                        span {
+                            class: "flex items-center gap-1",
+                            span {
+                                class: "inline-block w-3 h-3 rounded-full",
+                                style: "background: {TREND_COLORS[i % TREND_COLORS.len()]}"
+                            }
+                            span { class: "font-mono", "{trend.variant}" }
+                            if let Some(last) = trend.points.last() {
+                                span { class: "text-base-content/60", {format!("{:.3}", last.mean)} }
+                            }
+                            if trend.regression.is_some() {
+                                Label { label_role: LabelRole::Danger, "regressed" }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn encode_query(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
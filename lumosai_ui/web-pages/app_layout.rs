@@ -10,6 +10,7 @@ use dioxus::prelude::*;
 #[derive(PartialEq, Clone, Eq, Debug)]
 pub enum SideBar {
     None,
+    Analytics,
     ApiKeys,
     AuditTrail,
     Console,
@@ -23,9 +24,11 @@ pub enum SideBar {
     Prompts,
     Profile,
     RateLimits,
+    Reviews,
     Switch,
     Team,
     Security,
+    Traces,
     Workflows,
 }
 
@@ -134,6 +137,27 @@ pub fn Layout(props: LayoutProps) -> Element {
                                     title: "Workflows"
                                 }
                             }
+                            NavItem {
+                                id: SideBar::Traces.to_string(),
+                                selected_item_id: props.selected_item.to_string(),
+                                href: super::routes::traces::Index { team_id: props.team_id },
+                                icon: nav_history_svg.name,
+                                title: "Run Traces"
+                            }
+                            NavItem {
+                                id: SideBar::Reviews.to_string(),
+                                selected_item_id: props.selected_item.to_string(),
+                                href: super::routes::reviews::Index { team_id: props.team_id },
+                                icon: nav_audit_svg.name,
+                                title: "Review Queue"
+                            }
+                            NavItem {
+                                id: SideBar::Analytics.to_string(),
+                                selected_item_id: props.selected_item.to_string(),
+                                href: super::routes::analytics::Index { team_id: props.team_id },
+                                icon: nav_audit_svg.name,
+                                title: "Eval Dashboard"
+                            }
                             NavItem {
                                 id: SideBar::DocumentPipelines.to_string(),
                                 selected_item_id: props.selected_item.to_string(),
@@ -0,0 +1,373 @@
+/*!
+# Agent Builder
+
+Agent构建页面，将模型、指令、工具、RAG集合和安全护栏组合成一个Agent，
+保存到项目配置文件并热重载正在运行的Agent。
+
+## 功能特性
+
+- **模型选择**: 从配置的提供商和常用模型中选择
+- **工具选择**: 内置工具、配置中声明的工具和服务端工具注册表
+- **知识检索**: 关联配置中的RAG管道
+- **安全护栏**: 禁止话题、附加规则和单次回复的工具调用上限
+- **少样本示例**: 带标签的示例对话，按与请求的相似度选取注入提示词
+*/
+
+#![allow(non_snake_case)]
+use crate::app_layout::{Layout, SideBar};
+use crate::routes::assistant_builder;
+use crate::types::Rbac;
+use crate::ConfirmModal;
+use daisy_rsx::*;
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// 正在编辑的Agent
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AssistantDraft {
+    pub name: String,
+    pub model: String,
+    pub instructions: String,
+    #[serde(default)]
+    pub tools: Vec<String>,
+    pub rag: Option<String>,
+    #[serde(default)]
+    pub blocked_topics: Vec<String>,
+    #[serde(default)]
+    pub rules: Vec<String>,
+    pub max_tool_calls: Option<u32>,
+    pub temperature: Option<f32>,
+    #[serde(default)]
+    pub examples: Vec<ExampleDraft>,
+    /// 每次请求注入的示例数
+    pub few_shot_top_k: Option<usize>,
+}
+
+/// 少样本示例
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExampleDraft {
+    pub input: String,
+    pub output: String,
+    pub label: Option<String>,
+}
+
+/// 可选工具
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolOption {
+    pub name: String,
+    pub description: String,
+    /// 工具来源：builtin、config 或 registry
+    pub source: String,
+}
+
+/// 构建器的可选项
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BuilderOptions {
+    /// 写入的配置文件
+    pub config_path: String,
+    pub models: Vec<String>,
+    pub tools: Vec<ToolOption>,
+    pub rag_collections: Vec<String>,
+}
+
+/// 保存结果提示
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuilderNotice {
+    pub success: bool,
+    pub message: String,
+}
+
+pub fn page(
+    rbac: Rbac,
+    team_id: i32,
+    assistants: Vec<AssistantDraft>,
+    draft: AssistantDraft,
+    options: BuilderOptions,
+    notice: Option<BuilderNotice>,
+) -> String {
+    let editing = assistants.iter().any(|a| a.name == draft.name);
+
+    let page = rsx! {
+        Layout {
+            section_class: "p-4",
+            selected_item: SideBar::Prompts,
+            team_id: team_id,
+            rbac: rbac.clone(),
+            title: "Agent Builder",
+            header: rsx!(
+                h3 { "Agent Builder" }
+                a {
+                    href: assistant_builder::Index{team_id}.to_string(),
+                    class: "btn btn-primary btn-sm",
+                    "New Agent"
+                }
+            ),
+
+            if let Some(notice) = notice {
+                Alert {
+                    class: "mb-4",
+                    alert_color: if notice.success { AlertColor::Success } else { AlertColor::Error },
+                    "{notice.message}"
+                }
+            }
+
+            div {
+                class: "grid gap-4 lg:grid-cols-3",
+
+                Card {
+                    class: "has-data-table lg:col-span-1",
+                    CardHeader {
+                        title: "Configured Agents"
+                    }
+                    CardBody {
+                        if assistants.is_empty() {
+                            p {
+                                class: "p-4 text-sm text-base-content/70",
+                                "No agents in {options.config_path} yet"
+                            }
+                        } else {
+                            table {
+                                class: "table table-sm",
+                                thead {
+                                    th { "Name" }
+                                    th { "Model" }
+                                    th { "Tools" }
+                                    th { class: "text-right", "Action" }
+                                }
+                                tbody {
+                                    for assistant in &assistants {
+                                        tr {
+                                            td {
+                                                a {
+                                                    href: assistant_builder::Edit{team_id, name: assistant.name.clone()}.to_string(),
+                                                    "{assistant.name}"
+                                                }
+                                            }
+                                            td { class: "font-mono text-xs", "{assistant.model}" }
+                                            td { "{assistant.tools.len()}" }
+                                            td {
+                                                class: "text-right",
+                                                DropDown {
+                                                    direction: Direction::Left,
+                                                    button_text: "...",
+                                                    DropDownLink {
+                                                        href: assistant_builder::Edit{team_id, name: assistant.name.clone()}.to_string(),
+                                                        target: "_top",
+                                                        "Edit"
+                                                    }
+                                                    DropDownLink {
+                                                        popover_target: format!("delete-agent-{}", assistant.name),
+                                                        href: "#",
+                                                        target: "_top",
+                                                        "Delete"
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                Card {
+                    class: "lg:col-span-2",
+                    CardHeader {
+                        title: if editing { format!("Edit {}", draft.name) } else { "New Agent".to_string() }
+                    }
+                    CardBody {
+                        form {
+                            class: "flex flex-col p-4",
+                            action: assistant_builder::Upsert{team_id}.to_string(),
+                            method: "post",
+
+                            Input {
+                                input_type: InputType::Text,
+                                name: "name",
+                                label: "Name",
+                                help_text: "Agents are stored under agents.<name> in {options.config_path}",
+                                required: true,
+                                readonly: editing,
+                                value: draft.name.clone()
+                            }
+
+                            Select {
+                                name: "model",
+                                label: "Model",
+                                label_class: "mt-4",
+                                help_text: "Models served by the configured providers",
+                                for model in &options.models {
+                                    SelectOption {
+                                        value: "{model}",
+                                        selected_value: "{draft.model}",
+                                        "{model}"
+                                    }
+                                }
+                            }
+
+                            TextArea {
+                                name: "instructions",
+                                label: "Instructions",
+                                label_class: "mt-4",
+                                rows: "8",
+                                required: true,
+                                value: draft.instructions.clone()
+                            }
+
+                            fieldset {
+                                class: "fieldset mt-4",
+                                legend { class: "fieldset-legend", "Tools" }
+                                if options.tools.is_empty() {
+                                    p { class: "text-sm text-base-content/70", "No tools available" }
+                                }
+                                for tool in &options.tools {
+                                    label {
+                                        class: "label flex items-start gap-2 py-1",
+                                        CheckBox {
+                                            name: "tools",
+                                            value: "{tool.name}",
+                                            checked: draft.tools.contains(&tool.name),
+                                        }
+                                        span {
+                                            span { class: "font-mono", "{tool.name}" }
+                                            Label {
+                                                class: "ml-2",
+                                                label_role: LabelRole::Info,
+                                                "{tool.source}"
+                                            }
+                                            if !tool.description.is_empty() {
+                                                span { class: "block text-xs text-base-content/60", "{tool.description}" }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+
+                            Select {
+                                name: "rag",
+                                label: "Knowledge",
+                                label_class: "mt-4",
+                                help_text: "RAG pipeline used to ground answers",
+                                SelectOption {
+                                    value: "",
+                                    selected_value: draft.rag.clone().unwrap_or_default(),
+                                    "None"
+                                }
+                                for collection in &options.rag_collections {
+                                    SelectOption {
+                                        value: "{collection}",
+                                        selected_value: draft.rag.clone().unwrap_or_default(),
+                                        "{collection}"
+                                    }
+                                }
+                            }
+
+                            Input {
+                                input_type: InputType::Number,
+                                name: "temperature",
+                                label: "Temperature",
+                                label_class: "mt-4",
+                                step: "0.1",
+                                value: draft.temperature.map(|t| t.to_string()).unwrap_or_default()
+                            }
+
+                            h4 { class: "font-bold mt-6", "Guardrails" }
+
+                            TextArea {
+                                name: "blocked_topics",
+                                label: "Blocked Topics",
+                                label_class: "mt-2",
+                                help_text: "One topic per line; the agent politely declines these",
+                                rows: "3",
+                                value: draft.blocked_topics.join("\n")
+                            }
+
+                            TextArea {
+                                name: "rules",
+                                label: "Rules",
+                                label_class: "mt-4",
+                                help_text: "One rule per line, appended to the instructions",
+                                rows: "3",
+                                value: draft.rules.join("\n")
+                            }
+
+                            Input {
+                                input_type: InputType::Number,
+                                name: "max_tool_calls",
+                                label: "Max Tool Calls",
+                                label_class: "mt-4",
+                                help_text: "Upper bound on tool calls per response",
+                                value: draft.max_tool_calls.map(|n| n.to_string()).unwrap_or_default()
+                            }
+
+                            h4 { class: "font-bold mt-6", "Few-shot Examples" }
+                            p {
+                                class: "text-sm text-base-content/70",
+                                "The most similar examples are shown to the model before each request. Leave a row empty to remove it."
+                            }
+
+                            for (index, example) in draft.examples.iter().cloned().chain(std::iter::once(ExampleDraft::default())).enumerate() {
+                                fieldset {
+                                    class: "fieldset mt-2 border border-base-300 rounded-box p-2",
+                                    legend { class: "fieldset-legend", "Example {index + 1}" }
+                                    Input {
+                                        input_type: InputType::Text,
+                                        name: "example_label",
+                                        label: "Label",
+                                        value: example.label.clone().unwrap_or_default()
+                                    }
+                                    TextArea {
+                                        name: "example_input",
+                                        label: "User Message",
+                                        label_class: "mt-2",
+                                        rows: "2",
+                                        value: example.input.clone()
+                                    }
+                                    TextArea {
+                                        name: "example_output",
+                                        label: "Agent Answer",
+                                        label_class: "mt-2",
+                                        rows: "3",
+                                        value: example.output.clone()
+                                    }
+                                }
+                            }
+
+                            Input {
+                                input_type: InputType::Number,
+                                name: "few_shot_top_k",
+                                label: "Examples per Request",
+                                label_class: "mt-4",
+                                help_text: "How many of the most similar examples to include (default 3)",
+                                value: draft.few_shot_top_k.map(|n| n.to_string()).unwrap_or_default()
+                            }
+
+                            div {
+                                class: "flex justify-end mt-6",
+                                Button {
+                                    button_type: ButtonType::Submit,
+                                    button_scheme: ButtonScheme::Primary,
+                                    "Save and Reload"
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            for assistant in assistants {
+                ConfirmModal {
+                    action: assistant_builder::Delete{team_id, name: assistant.name.clone()}.to_string(),
+                    trigger_id: format!("delete-agent-{}", assistant.name),
+                    submit_label: "Delete".to_string(),
+                    heading: "Delete this Agent?".to_string(),
+                    warning: format!("{} will be removed from the project config and unloaded.", assistant.name),
+                    hidden_fields: vec![],
+                }
+            }
+        }
+    };
+
+    crate::render(page)
+}
@@ -34,6 +34,11 @@ pub fn page(team_id: i32, rbac: Rbac, prompts: Vec<Prompt>, categories: Vec<Cate
                         class: "btn btn-ghost btn-sm font-bold! mr-4",
                         "My Assistants"
                     }
+                    a {
+                        href: crate::routes::assistant_builder::Index{team_id}.to_string(),
+                        class: "btn btn-ghost btn-sm font-bold! mr-4",
+                        "Agent Builder"
+                    }
                     Button {
                         button_type: ButtonType::Link,
                         prefix_image_src: "{button_plus_svg.name}",
@@ -1,4 +1,5 @@
 pub mod assistant_console;
+pub mod builder;
 pub mod conversation;
 pub mod index;
 pub mod prompt_card;
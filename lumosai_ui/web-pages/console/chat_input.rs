@@ -9,7 +9,7 @@
 - **文件上传**: 支持文件拖拽和选择上传
 - **语音输入**: 支持语音录制和转文字
 - **快捷操作**: 支持快捷键发送和格式化
-- **实时预览**: 支持Markdown实时预览
+- **生成控制**: 支持停止生成和重新生成
 */
 
 #![allow(non_snake_case)]
@@ -18,6 +18,9 @@ use web_assets::files::*;
 use crate::types::Rbac;
 
 /// 聊天输入组件
+///
+/// 表单由 `agent-console` 脚本接管：提交时以SSE流式请求Agent，生成期间显示停止按钮，
+/// 完成后可重新生成最后一条回复。
 #[component]
 pub fn ChatInput(
     team_id: i32,
@@ -25,43 +28,18 @@ pub fn ChatInput(
     rbac: Rbac,
     disabled: bool,
 ) -> Element {
-    // 状态管理
-    let mut message = use_signal(|| String::new());
-    let mut is_recording = use_signal(|| false);
-    let mut show_file_upload = use_signal(|| false);
-    let mut is_sending = use_signal(|| false);
-
-    // 发送消息处理
-    let mut send_message = move |_| {
-        let msg = message.read().clone();
-        if !msg.trim().is_empty() && !is_sending() {
-            is_sending.set(true);
-            // TODO: 实际发送消息到后端
-            println!("发送消息: {}", msg);
-            message.set(String::new());
-            // 模拟发送完成
-            spawn(async move {
-                tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-                is_sending.set(false);
-            });
-        }
-    };
-
     rsx! {
         div {
             class: "border-t border-base-300 bg-base-100 p-4",
 
-            // 文件上传区域
-            if show_file_upload() {
-                FileUploadArea {
-                    team_id,
-                    conversation_id,
-                    on_close: move |_| show_file_upload.set(false)
-                }
+            // 文件上传区域，由附件按钮切换显示
+            FileUploadArea {
+                team_id,
+                conversation_id
             }
 
-            // 主输入区域
-            div {
+            form {
+                id: "agent-console-form",
                 class: "flex flex-col space-y-3",
 
                 // 输入框和按钮
@@ -72,19 +50,11 @@ pub fn ChatInput(
                     div {
                         class: "flex-1 relative",
                         textarea {
-                            class: "textarea textarea-bordered w-full min-h-[60px] max-h-[200px] resize-none pr-12",
+                            class: "textarea textarea-bordered w-full min-h-[60px] max-h-[200px] resize-none pr-12 auto-expand submit-on-enter",
+                            name: "message",
+                            maxlength: "2000",
                             placeholder: if disabled { "AI正在处理中..." } else { "输入您的消息... (Shift+Enter换行，Enter发送)" },
-                            value: "{message}",
                             disabled,
-                            oninput: move |evt| {
-                                message.set(evt.value());
-                            },
-                            onkeydown: move |evt| {
-                                if evt.key() == Key::Enter && !evt.modifiers().shift() {
-                                    evt.prevent_default();
-                                    send_message(());
-                                }
-                            }
                         }
 
                         // 输入框内的操作按钮
@@ -93,28 +63,24 @@ pub fn ChatInput(
 
                             // 语音输入按钮
                             button {
-                                class: "btn btn-ghost btn-xs",
-                                class: if is_recording() { "text-error" } else { "text-base-content/60" },
-                                title: if is_recording() { "停止录音" } else { "语音输入" },
+                                id: "speech-to-text-button",
+                                r#type: "button",
+                                class: "btn btn-ghost btn-xs text-base-content/60",
+                                title: "语音输入",
                                 disabled,
-                                onclick: move |_| {
-                                    is_recording.set(!is_recording());
-                                    // TODO: 实现语音录制
-                                },
                                 img {
-                                    src: if is_recording() { stop_recording_svg.name } else { microphone_svg.name },
+                                    src: microphone_svg.name,
                                     class: "w-4 h-4"
                                 }
                             }
 
                             // 文件上传按钮
                             button {
+                                id: "attach-button",
+                                r#type: "button",
                                 class: "btn btn-ghost btn-xs text-base-content/60",
                                 title: "上传文件",
                                 disabled,
-                                onclick: move |_| {
-                                    show_file_upload.set(!show_file_upload());
-                                },
                                 img {
                                     src: attach_svg.name,
                                     class: "w-4 h-4"
@@ -125,19 +91,25 @@ pub fn ChatInput(
 
                     // 发送按钮
                     button {
+                        id: "agent-console-send",
+                        r#type: "submit",
                         class: "btn btn-primary",
-                        class: if message.read().trim().is_empty() || disabled { "btn-disabled" } else { "" },
-                        disabled: message.read().trim().is_empty() || disabled || is_sending(),
-                        onclick: move |_| send_message(()),
-                        if is_sending() {
-                            span {
-                                class: "loading loading-spinner loading-sm"
-                            }
-                        } else {
-                            img {
-                                src: submit_button_svg.name,
-                                class: "w-5 h-5"
-                            }
+                        disabled,
+                        img {
+                            src: submit_button_svg.name,
+                            class: "w-5 h-5"
+                        }
+                    }
+
+                    // 停止按钮，生成期间替换发送按钮
+                    button {
+                        id: "agent-console-stop",
+                        r#type: "button",
+                        class: "btn btn-primary hidden",
+                        title: "停止生成",
+                        img {
+                            src: streaming_stop_svg.name,
+                            class: "w-5 h-5"
                         }
                     }
                 }
@@ -152,25 +124,27 @@ pub fn ChatInput(
                         span {
                             "💡 提示: Shift+Enter换行，Enter发送"
                         }
-                        if let Some(conv_id) = conversation_id {
-                            span {
+                        span {
+                            id: "agent-console-conversation-label",
+                            class: if conversation_id.is_none() { "hidden" } else { "" },
+                            if let Some(conv_id) = conversation_id {
                                 "对话ID: {conv_id}"
                             }
                         }
                     }
 
-                    // 右侧：字符计数和状态
+                    // 右侧：重新生成和状态
                     div {
                         class: "flex items-center space-x-2",
-                        span {
-                            class: if message.read().len() > 1000 { "text-warning" } else { "" },
-                            "{message.read().len()}/2000"
+                        button {
+                            id: "agent-console-regenerate",
+                            r#type: "button",
+                            class: "btn btn-ghost btn-xs hidden",
+                            title: "重新生成最后一条回复",
+                            "🔄 重新生成"
                         }
-                        if is_recording() {
-                            span {
-                                class: "text-error animate-pulse",
-                                "🔴 录音中"
-                            }
+                        span {
+                            id: "agent-console-status",
                         }
                     }
                 }
@@ -184,26 +158,11 @@ pub fn ChatInput(
 fn FileUploadArea(
     team_id: i32,
     conversation_id: Option<i64>,
-    on_close: EventHandler<()>,
 ) -> Element {
-    let mut drag_over = use_signal(|| false);
-
     rsx! {
         div {
-            class: "border border-dashed border-base-300 rounded-lg p-4 mb-3 bg-base-50",
-            class: if drag_over() { "border-primary bg-primary/5" } else { "" },
-            ondragover: move |evt| {
-                evt.prevent_default();
-                drag_over.set(true);
-            },
-            ondragleave: move |_| {
-                drag_over.set(false);
-            },
-            ondrop: move |evt| {
-                evt.prevent_default();
-                drag_over.set(false);
-                // TODO: 处理文件拖拽
-            },
+            id: "chat-file-upload",
+            class: "hidden border border-dashed border-base-300 rounded-lg p-4 mb-3 bg-base-50",
 
             div {
                 class: "flex items-center justify-between mb-3",
@@ -212,8 +171,9 @@ fn FileUploadArea(
                     "📎 文件上传"
                 }
                 button {
+                    r#type: "button",
                     class: "btn btn-ghost btn-sm",
-                    onclick: move |_| on_close.call(()),
+                    "data-close": "chat-file-upload",
                     "✕"
                 }
             }
@@ -243,6 +203,7 @@ fn FileUploadArea(
                     accept: ".txt,.md,.pdf,.doc,.docx,.jpg,.jpeg,.png,.gif,.zip"
                 }
                 button {
+                    r#type: "button",
                     class: "btn btn-primary btn-sm",
                     "上传文件"
                 }
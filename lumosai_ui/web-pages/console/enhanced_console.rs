@@ -20,6 +20,7 @@ use crate::console::{ChatWithChunks, PendingChatState};
 use crate::console::console_stream::ConsoleStream;
 use crate::console::chat_input::ChatInput;
 use crate::console::tools_modal::ToolsModal;
+use crate::console::tool_call_card::ToolCallTemplate;
 // use crate::console::history_drawer::HistoryDrawer;
 // use crate::console::model_popup::ModelPopup;
 
@@ -36,9 +37,13 @@ pub struct Capability {
 }
 
 /// 增强的助手控制台组件
+///
+/// `stream_url` 指向Agent运行时的SSE聊天接口（如 `/api/chat/stream`），
+/// 客户端脚本经由该接口流式生成回复、展示工具调用，并按 `conversation_id` 持久化对话。
 #[component]
 pub fn EnhancedAssistantConsole(
     team_id: i32,
+    stream_url: String,
     conversation_id: Option<i64>,
     rbac: Rbac,
     chat_history: Vec<ChatWithChunks>,
@@ -57,6 +62,7 @@ pub fn EnhancedAssistantConsole(
     let show_tools_modal = false;
     let _show_history_drawer = false;
     let _show_model_popup = false;
+    let conversation_attr = conversation_id.map(|id| id.to_string()).unwrap_or_default();
 
     rsx! {
         Layout {
@@ -69,7 +75,10 @@ pub fn EnhancedAssistantConsole(
 
             // 主控制台区域
             div {
+                id: "agent-console",
                 class: "flex-1 flex flex-col bg-base-100 relative",
+                "data-stream-url": "{stream_url}",
+                "data-conversation-id": "{conversation_attr}",
 
                 // 控制台头部工具栏
                 ConsoleHeader {
@@ -83,7 +92,8 @@ pub fn EnhancedAssistantConsole(
 
                 // 聊天流区域
                 div {
-                    class: "flex-1 overflow-hidden",
+                    id: "agent-console-scroll",
+                    class: "flex-1 flex flex-col overflow-y-auto",
                     ConsoleStream {
                         team_id,
                         chat_history: chat_history.clone(),
@@ -91,8 +101,16 @@ pub fn EnhancedAssistantConsole(
                         is_tts_disabled,
                         rbac: rbac.clone()
                     }
+
+                    // 实时消息，由客户端脚本按SSE事件追加
+                    div {
+                        id: "agent-console-messages",
+                        class: "flex flex-col gap-4 p-4 w-full max-w-prose mx-auto"
+                    }
                 }
 
+                ToolCallTemplate {}
+
                 // 输入表单区域
                 ChatInput {
                     team_id,
@@ -237,8 +255,9 @@ fn ConsoleHeader(
                         }
                         li {
                             a {
+                                id: "agent-console-new",
                                 href: "#",
-                                "🗑️ 清空对话"
+                                "🗑️ 新建对话"
                             }
                         }
                     }
@@ -17,6 +17,7 @@ pub mod enhanced_console;
 pub mod file_upload;
 pub mod voice_input;
 pub mod chat_input;
+pub mod tool_call_card;
 
 use crate::types::{Chat, ToolCall};
 use serde::{Deserialize, Serialize};
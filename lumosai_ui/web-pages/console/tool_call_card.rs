@@ -0,0 +1,108 @@
+/*!
+# Tool Call Card Component
+
+工具调用可视化组件，展示Agent在回复过程中调用的工具、参数和结果。
+
+## 功能特性
+
+- **调用状态**: 执行中、成功、失败三种状态
+- **参数展示**: 格式化显示调用参数
+- **结果展示**: 折叠显示工具返回结果和耗时
+- **流式模板**: 提供模板供客户端在流式响应中实时渲染
+*/
+
+#![allow(non_snake_case)]
+use dioxus::prelude::*;
+
+/// 工具调用状态
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ToolCallStatus {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+impl ToolCallStatus {
+    fn badge(&self) -> (&'static str, &'static str) {
+        match self {
+            ToolCallStatus::Running => ("badge badge-warning badge-sm", "执行中"),
+            ToolCallStatus::Succeeded => ("badge badge-success badge-sm", "成功"),
+            ToolCallStatus::Failed => ("badge badge-error badge-sm", "失败"),
+        }
+    }
+}
+
+/// 工具调用卡片
+#[component]
+pub fn ToolCallCard(
+    name: String,
+    arguments: String,
+    result: Option<String>,
+    status: ToolCallStatus,
+    execution_time_ms: Option<u64>,
+) -> Element {
+    let (badge_class, badge_label) = status.badge();
+
+    rsx! {
+        div {
+            class: "tool-call collapse collapse-arrow border border-base-300 bg-base-200 rounded-lg text-sm",
+            input { r#type: "checkbox" }
+            div {
+                class: "collapse-title flex items-center space-x-2 min-h-0 py-2",
+                span { "🛠️" }
+                span { class: "font-mono font-medium", "{name}" }
+                span { class: "{badge_class}", "{badge_label}" }
+                if let Some(ms) = execution_time_ms {
+                    span { class: "text-xs text-base-content/60", "{ms}ms" }
+                }
+            }
+            div {
+                class: "collapse-content space-y-2",
+                div {
+                    p { class: "text-xs font-semibold text-base-content/70", "参数" }
+                    pre { class: "bg-base-100 rounded p-2 overflow-x-auto text-xs", "{arguments}" }
+                }
+                if let Some(result) = result {
+                    div {
+                        p { class: "text-xs font-semibold text-base-content/70", "结果" }
+                        pre { class: "bg-base-100 rounded p-2 overflow-x-auto text-xs", "{result}" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// 工具调用卡片模板，客户端脚本在收到 `tool_call` 事件时克隆并填充
+#[component]
+pub fn ToolCallTemplate() -> Element {
+    rsx! {
+        template {
+            id: "tool-call-template",
+            div {
+                class: "tool-call collapse collapse-arrow border border-base-300 bg-base-200 rounded-lg text-sm",
+                input { r#type: "checkbox" }
+                div {
+                    class: "collapse-title flex items-center space-x-2 min-h-0 py-2",
+                    span { "🛠️" }
+                    span { class: "font-mono font-medium", "data-field": "name" }
+                    span { class: "badge badge-warning badge-sm", "data-field": "status", "执行中" }
+                    span { class: "text-xs text-base-content/60", "data-field": "time" }
+                }
+                div {
+                    class: "collapse-content space-y-2",
+                    div {
+                        p { class: "text-xs font-semibold text-base-content/70", "参数" }
+                        pre { class: "bg-base-100 rounded p-2 overflow-x-auto text-xs", "data-field": "arguments" }
+                    }
+                    div {
+                        class: "hidden",
+                        "data-field": "result-section",
+                        p { class: "text-xs font-semibold text-base-content/70", "结果" }
+                        pre { class: "bg-base-100 rounded p-2 overflow-x-auto text-xs", "data-field": "result" }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -81,7 +81,7 @@ pub fn page(rbac: Rbac, team_id: i32, dataset: Dataset, documents: Vec<Document>
                         trigger_id: format!("delete-doc-trigger-{}-{}", doc.id, team_id),
                         submit_label: "Delete Document".to_string(),
                         heading: "Delete this document?".to_string(),
-                        warning: "Are you sure you want to delete this document?".to_string(),
+                        warning: "Are you sure you want to delete this document? Its embeddings will be removed from the dataset.".to_string(),
                         hidden_fields: vec![
                             ("team_id".into(), team_id.to_string()),
                             ("document_id".into(), doc.id.to_string()),
@@ -109,7 +109,10 @@ pub fn Row(document: Document, team_id: i32, first_time: bool) -> Element {
         "None".to_string()
     };
 
-    let class = if document.waiting > 0 || document.batches == 0 {
+    let processing = document.failure_reason.is_none()
+        && (document.waiting > 0 || document.batches == 0);
+
+    let class = if processing {
         "processing"
     } else {
         "processing-finished"
@@ -129,6 +132,8 @@ pub fn Row(document: Document, team_id: i32, first_time: bool) -> Element {
         None
     };
 
+    let embedded = document.batches - document.waiting;
+
     rsx!(
         tr {
             td { "{document.file_name}" }
@@ -138,20 +143,11 @@ pub fn Row(document: Document, team_id: i32, first_time: bool) -> Element {
              }
             td { "{document.content_size}" }
             td {
-                if document.waiting > 0 || document.batches == 0 {
-                    turbo-frame {
-                        id,
-                        src,
-                        Label {
-                            class: class,
-                            "Processing ({document.waiting} remaining)"
-                        }
-                    }
-                } else if document.failure_reason.is_some() {
-                    turbo-frame {
-                        id,
-                        src,
+                turbo-frame {
+                    id,
+                    src,
 
+                    if document.failure_reason.is_some() {
                         ToolTip {
                             text: "{text}",
                             Label {
@@ -159,41 +155,30 @@ pub fn Row(document: Document, team_id: i32, first_time: bool) -> Element {
                                 "Failed"
                             }
                         }
-                    }
-                } else if document.batches == 0 {
-                    turbo-frame {
-                        id,
-                        src,
-
+                    } else if document.batches == 0 {
                         Label {
-                            "Queued"
+                            class: class,
+                            "Chunking"
                         }
-                    }
-                } else if document.fail_count > 0 {
-                    turbo-frame {
-                        id,
-                        src,
-
-                        Label {
-                            label_role: LabelRole::Danger,
-                            "Processed ({document.fail_count} failed)"
+                    } else if document.waiting > 0 {
+                        div {
+                            class: "flex flex-col gap-1",
+                            Label {
+                                class: class,
+                                "Embedding ({embedded}/{document.batches})"
+                            }
+                            progress {
+                                class: "progress progress-primary w-32",
+                                value: "{embedded}",
+                                max: "{document.batches}"
+                            }
                         }
-                    }
-                } else if document.failure_reason.is_some() {
-                    turbo-frame {
-                        id,
-                        src,
-
+                    } else if document.fail_count > 0 {
                         Label {
                             label_role: LabelRole::Danger,
-                            "Failed"
+                            "Processed ({document.fail_count} failed)"
                         }
-                    }
-                } else {
-                    turbo-frame {
-                        id,
-                        src,
-
+                    } else {
                         Label {
                             label_role: LabelRole::Success,
                             "Processed"
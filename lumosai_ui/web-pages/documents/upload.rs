@@ -20,6 +20,7 @@ pub fn Upload(upload_action: String) -> Element {
                     input {
                         "type": "file",
                         name: "payload",
+                        accept: ".eml,.html,.htm,.json,.md,.markdown,.rst,.txt,.xml,.yaml,.yml,.csv,.tsv",
                         multiple: true
                     }
 
@@ -40,15 +41,11 @@ pub fn Upload(upload_action: String) -> Element {
                             class: "mt-4",
                             li {
                                 strong {"Plaintext "}
-                                ".eml, .html, .json, .md, .msg, .rst, .rtf, .txt, .xml"
+                                ".eml, .html, .json, .md, .rst, .txt, .xml, .yaml"
                             }
                             li {
-                                strong {"Images "}
-                                ".jpeg, .png"
-                            }
-                            li {
-                                strong {"Documents "}
-                                ".csv, .doc, .docx, .epub, .odt, .pdf, .ppt, .pptx, .tsv, .xlsx"
+                                strong {"Tabular "}
+                                ".csv, .tsv"
                             }
                         }
                     }
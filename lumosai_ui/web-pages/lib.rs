@@ -40,9 +40,11 @@ pub mod my_assistants;
 pub mod notification_system;
 pub mod pipelines;
 pub mod rate_limits;
+pub mod reviews;
 pub mod settings;
 pub mod team;
 pub mod teams;
+pub mod traces;
 pub mod workflows;
 
 // Re-export commonly used components
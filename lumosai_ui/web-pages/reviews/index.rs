@@ -0,0 +1,140 @@
+#![allow(non_snake_case)]
+use super::{ReviewNotice, ReviewView};
+use crate::app_layout::{Layout, SideBar};
+use crate::routes;
+use crate::types::Rbac;
+use daisy_rsx::*;
+use dioxus::prelude::*;
+use web_assets::files::*;
+
+pub fn page(
+    rbac: Rbac,
+    team_id: i32,
+    threshold: f64,
+    pending: Vec<ReviewView>,
+    reviewed: Vec<ReviewView>,
+    notice: Option<ReviewNotice>,
+) -> String {
+    let page = rsx! {
+        Layout {
+            section_class: "p-4",
+            selected_item: SideBar::Reviews,
+            team_id: team_id,
+            rbac: rbac,
+            title: "Review Queue",
+            header: rsx!(
+                h3 { "Review Queue" }
+                span {
+                    class: "text-sm text-base-content/70",
+                    {format!("Flagging answers below {:.2} confidence", threshold)}
+                }
+            ),
+
+            if let Some(notice) = notice {
+                Alert {
+                    class: "mb-4",
+                    alert_color: if notice.success { AlertColor::Success } else { AlertColor::Error },
+                    "{notice.message}"
+                }
+            }
+
+            if pending.is_empty() {
+                BlankSlate {
+                    heading: "Nothing to review",
+                    visual: nav_audit_svg.name,
+                    description: "Answers the judge or a guardrail scores below the confidence threshold appear here for review"
+                }
+            } else {
+                for item in pending {
+                    Card {
+                        class: "mb-4",
+                        CardHeader {
+                            title: "{item.agent}"
+                        }
+                        CardBody {
+                            div {
+                                class: "flex flex-wrap gap-2 text-sm mb-2",
+                                ConfidenceLabel { confidence: item.confidence }
+                                span { class: "text-base-content/70", "{item.source}" }
+                                RelativeTime {
+                                    format: RelativeTimeFormat::Relative,
+                                    datetime: &item.created_at_iso
+                                }
+                            }
+                            p { class: "font-bold", "User" }
+                            p { class: "whitespace-pre-wrap mb-2", "{item.input}" }
+                            p { class: "font-bold", "Answer" }
+                            p { class: "whitespace-pre-wrap mb-2", "{item.answer}" }
+                            if let Some(reason) = &item.reason {
+                                p { class: "text-sm text-warning mb-2", "{reason}" }
+                            }
+                            form {
+                                action: routes::reviews::Decide{team_id, id: item.id.clone()}.to_string(),
+                                method: "post",
+                                TextArea {
+                                    name: "correction",
+                                    label: "Correction",
+                                    rows: "3",
+                                    help_text: "The answer the agent should have given; used as a few-shot example and eval case",
+                                    value: item.answer.clone()
+                                }
+                                div {
+                                    class: "flex justify-end gap-2 mt-4",
+                                    button { class: "btn btn-sm", r#type: "submit", name: "action", value: "dismiss", "Dismiss" }
+                                    button { class: "btn btn-sm", r#type: "submit", name: "action", value: "approve", "Approve" }
+                                    button { class: "btn btn-sm btn-primary", r#type: "submit", name: "action", value: "correct", "Save Correction" }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            if !reviewed.is_empty() {
+                Card {
+                    class: "has-data-table",
+                    CardHeader {
+                        title: "Reviewed"
+                    }
+                    CardBody {
+                        table {
+                            class: "table table-sm",
+                            thead {
+                                th { "Agent" }
+                                th { "User" }
+                                th { "Status" }
+                                th { class: "max-sm:hidden", "Reviewer" }
+                                th { class: "text-right", "Confidence" }
+                            }
+                            tbody {
+                                for item in reviewed {
+                                    tr {
+                                        td { class: "font-mono", "{item.agent}" }
+                                        td { class: "truncate max-w-xs", "{item.input}" }
+                                        td { "{item.status}" }
+                                        td { class: "max-sm:hidden", {item.reviewer.clone().unwrap_or_else(|| "-".to_string())} }
+                                        td { class: "text-right", {format!("{:.2}", item.confidence)} }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    crate::render(page)
+}
+
+#[component]
+pub fn ConfidenceLabel(confidence: f64) -> Element {
+    let role = if confidence < 0.3 { LabelRole::Danger } else { LabelRole::Warning };
+
+    rsx!(
+        Label {
+            label_role: role,
+            {format!("Confidence {:.2}", confidence)}
+        }
+    )
+}
@@ -0,0 +1,26 @@
+pub mod index;
+
+/// 审核队列中的一条回复
+#[derive(PartialEq, Clone, Debug)]
+pub struct ReviewView {
+    pub id: String,
+    pub agent: String,
+    pub input: String,
+    pub answer: String,
+    pub confidence: f64,
+    /// judge、guardrail 等
+    pub source: String,
+    pub reason: Option<String>,
+    /// pending、approved、corrected 或 dismissed
+    pub status: String,
+    pub correction: Option<String>,
+    pub reviewer: Option<String>,
+    pub created_at_iso: String,
+}
+
+/// 审核操作的结果提示
+#[derive(PartialEq, Clone, Debug)]
+pub struct ReviewNotice {
+    pub success: bool,
+    pub message: String,
+}
@@ -187,6 +187,73 @@ pub mod console {
     }
 }
 
+pub mod assistant_builder {
+    use axum_extra::routing::TypedPath;
+    use serde::Deserialize;
+
+    #[derive(TypedPath, Deserialize)]
+    #[typed_path("/app/team/{team_id}/assistant_builder")]
+    pub struct Index {
+        pub team_id: i32,
+    }
+
+    #[derive(TypedPath, Deserialize)]
+    #[typed_path("/app/team/{team_id}/assistant_builder/edit/{name}")]
+    pub struct Edit {
+        pub team_id: i32,
+        pub name: String,
+    }
+
+    #[derive(TypedPath, Deserialize)]
+    #[typed_path("/app/team/{team_id}/assistant_builder/upsert")]
+    pub struct Upsert {
+        pub team_id: i32,
+    }
+
+    #[derive(TypedPath, Deserialize)]
+    #[typed_path("/app/team/{team_id}/assistant_builder/delete/{name}")]
+    pub struct Delete {
+        pub team_id: i32,
+        pub name: String,
+    }
+}
+
+pub mod traces {
+    use axum_extra::routing::TypedPath;
+    use serde::Deserialize;
+
+    #[derive(TypedPath, Deserialize)]
+    #[typed_path("/app/team/{team_id}/traces")]
+    pub struct Index {
+        pub team_id: i32,
+    }
+
+    #[derive(TypedPath, Deserialize)]
+    #[typed_path("/app/team/{team_id}/traces/{trace_id}")]
+    pub struct View {
+        pub team_id: i32,
+        pub trace_id: String,
+    }
+}
+
+pub mod reviews {
+    use axum_extra::routing::TypedPath;
+    use serde::Deserialize;
+
+    #[derive(TypedPath, Deserialize)]
+    #[typed_path("/app/team/{team_id}/reviews")]
+    pub struct Index {
+        pub team_id: i32,
+    }
+
+    #[derive(TypedPath, Deserialize)]
+    #[typed_path("/app/team/{team_id}/reviews/{id}")]
+    pub struct Decide {
+        pub team_id: i32,
+        pub id: String,
+    }
+}
+
 pub mod prompts {
     use axum_extra::routing::TypedPath;
     use serde::Deserialize;
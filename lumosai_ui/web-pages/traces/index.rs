@@ -0,0 +1,103 @@
+#![allow(non_snake_case)]
+use super::{format_duration, TraceSummary};
+use crate::app_layout::{Layout, SideBar};
+use crate::routes;
+use crate::types::Rbac;
+use daisy_rsx::*;
+use dioxus::prelude::*;
+use web_assets::files::*;
+
+pub fn page(rbac: Rbac, team_id: i32, traces: Vec<TraceSummary>) -> String {
+    let page = rsx! {
+        Layout {
+            section_class: "p-4",
+            selected_item: SideBar::Traces,
+            team_id: team_id,
+            rbac: rbac,
+            title: "Run Traces",
+            header: rsx!(
+                h3 { "Run Traces" }
+            ),
+
+            if traces.is_empty() {
+                BlankSlate {
+                    heading: "No agent runs recorded yet",
+                    visual: nav_history_svg.name,
+                    description: "Every chat request is traced; send a message in the console to see its LLM and tool calls here"
+                }
+            } else {
+                Card {
+                    class: "has-data-table",
+                    CardHeader {
+                        title: "Recent Runs"
+                    }
+                    CardBody {
+                        table {
+                            class: "table table-sm",
+                            thead {
+                                th { "Started" }
+                                th { "Agent" }
+                                th { "Status" }
+                                th { class: "text-right", "Duration" }
+                                th { class: "text-right max-sm:hidden", "Spans" }
+                                th { class: "text-right max-sm:hidden", "Tool Calls" }
+                                th { class: "text-right", "Tokens" }
+                            }
+                            tbody {
+                                for trace in traces {
+                                    tr {
+                                        td {
+                                            a {
+                                                href: routes::traces::View{team_id, trace_id: trace.trace_id.clone()}.to_string(),
+                                                RelativeTime {
+                                                    format: RelativeTimeFormat::Relative,
+                                                    datetime: &trace.started_at_iso
+                                                }
+                                            }
+                                        }
+                                        td { class: "font-mono", "{trace.agent_id}" }
+                                        td {
+                                            TraceStatus {
+                                                success: trace.success,
+                                                finished: trace.finished
+                                            }
+                                        }
+                                        td { class: "text-right", {format_duration(trace.duration_ms)} }
+                                        td { class: "text-right max-sm:hidden", "{trace.span_count}" }
+                                        td { class: "text-right max-sm:hidden", "{trace.tool_calls}" }
+                                        td {
+                                            class: "text-right",
+                                            if trace.total_tokens > 0 {
+                                                "{trace.total_tokens}"
+                                            } else {
+                                                "-"
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    crate::render(page)
+}
+
+#[component]
+pub fn TraceStatus(success: bool, finished: bool) -> Element {
+    let (role, label) = match (finished, success) {
+        (false, _) => (LabelRole::Info, "Running"),
+        (true, true) => (LabelRole::Success, "Succeeded"),
+        (true, false) => (LabelRole::Danger, "Failed"),
+    };
+
+    rsx!(
+        Label {
+            label_role: role,
+            "{label}"
+        }
+    )
+}
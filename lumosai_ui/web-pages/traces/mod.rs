@@ -0,0 +1,68 @@
+pub mod index;
+pub mod view;
+
+/// 追踪列表中的一行
+#[derive(PartialEq, Clone, Debug)]
+pub struct TraceSummary {
+    pub trace_id: String,
+    pub agent_id: String,
+    pub started_at_iso: String,
+    pub duration_ms: u64,
+    pub span_count: usize,
+    pub tool_calls: usize,
+    pub total_tokens: u32,
+    pub success: bool,
+    pub finished: bool,
+}
+
+/// 运行检查器展示的完整追踪
+#[derive(PartialEq, Clone, Debug)]
+pub struct TraceView {
+    pub summary: TraceSummary,
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+    pub metadata: Vec<(String, String)>,
+    pub spans: Vec<SpanView>,
+}
+
+/// 追踪中的一个跨度
+#[derive(PartialEq, Clone, Debug)]
+pub struct SpanView {
+    pub name: String,
+    /// llm、tool、retrieval 等
+    pub kind: String,
+    /// 相对追踪开始的偏移
+    pub offset_ms: u64,
+    pub duration_ms: u64,
+    pub success: bool,
+    pub error: Option<String>,
+    pub prompt_tokens: Option<u32>,
+    pub completion_tokens: Option<u32>,
+    pub input: Option<String>,
+    pub output: Option<String>,
+    pub metadata: Vec<(String, String)>,
+    pub children: Vec<SpanView>,
+}
+
+/// 按深度优先顺序展开跨度树，返回每个跨度及其深度
+pub fn flatten_spans(spans: &[SpanView]) -> Vec<(usize, &SpanView)> {
+    fn walk<'a>(spans: &'a [SpanView], depth: usize, rows: &mut Vec<(usize, &'a SpanView)>) {
+        for span in spans {
+            rows.push((depth, span));
+            walk(&span.children, depth + 1, rows);
+        }
+    }
+
+    let mut rows = Vec::new();
+    walk(spans, 0, &mut rows);
+    rows
+}
+
+/// 格式化毫秒耗时
+pub fn format_duration(ms: u64) -> String {
+    if ms >= 1000 {
+        format!("{:.2}s", ms as f64 / 1000.0)
+    } else {
+        format!("{}ms", ms)
+    }
+}
@@ -0,0 +1,208 @@
+#![allow(non_snake_case)]
+use super::index::TraceStatus;
+use super::{flatten_spans, format_duration, SpanView, TraceView};
+use crate::app_layout::{Layout, SideBar};
+use crate::routes;
+use crate::types::Rbac;
+use daisy_rsx::*;
+use dioxus::prelude::*;
+
+pub fn page(rbac: Rbac, team_id: i32, trace: TraceView) -> String {
+    let rows = flatten_spans(&trace.spans);
+    // 时间轴以追踪总时长为准，运行中的追踪以最后结束的跨度为准
+    let timeline_ms = rows
+        .iter()
+        .map(|(_, span)| span.offset_ms + span.duration_ms)
+        .chain(std::iter::once(trace.summary.duration_ms))
+        .max()
+        .unwrap_or(0)
+        .max(1);
+
+    let page = rsx! {
+        Layout {
+            section_class: "p-4",
+            selected_item: SideBar::Traces,
+            team_id: team_id,
+            rbac: rbac,
+            title: "Run Inspector",
+            header: rsx!(
+                h3 { "Run Inspector" }
+                a {
+                    href: routes::traces::Index{team_id}.to_string(),
+                    class: "btn btn-ghost btn-sm",
+                    "All Runs"
+                }
+            ),
+
+            div {
+                class: "stats stats-vertical lg:stats-horizontal shadow w-full mb-4",
+                div {
+                    class: "stat",
+                    div { class: "stat-title", "Status" }
+                    div {
+                        class: "stat-value text-lg",
+                        TraceStatus {
+                            success: trace.summary.success,
+                            finished: trace.summary.finished
+                        }
+                    }
+                    div { class: "stat-desc font-mono", "{trace.summary.trace_id}" }
+                }
+                div {
+                    class: "stat",
+                    div { class: "stat-title", "Duration" }
+                    div { class: "stat-value text-lg", {format_duration(trace.summary.duration_ms)} }
+                    div { class: "stat-desc", "{trace.summary.span_count} spans, {trace.summary.tool_calls} tool calls" }
+                }
+                div {
+                    class: "stat",
+                    div { class: "stat-title", "Tokens" }
+                    div { class: "stat-value text-lg", "{trace.summary.total_tokens}" }
+                    div { class: "stat-desc", "{trace.prompt_tokens} prompt / {trace.completion_tokens} completion" }
+                }
+            }
+
+            if !trace.metadata.is_empty() {
+                Card {
+                    class: "mb-4",
+                    CardHeader {
+                        title: "Run Metadata"
+                    }
+                    CardBody {
+                        MetadataTable {
+                            metadata: trace.metadata.clone()
+                        }
+                    }
+                }
+            }
+
+            Card {
+                CardHeader {
+                    title: "Span Tree"
+                }
+                CardBody {
+                    if rows.is_empty() {
+                        p { class: "p-4 text-sm text-base-content/70", "This run has not recorded any spans yet" }
+                    }
+                    for (depth, span) in rows {
+                        SpanRow {
+                            depth: depth,
+                            span: span.clone(),
+                            timeline_ms: timeline_ms
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    crate::render(page)
+}
+
+#[component]
+fn SpanRow(depth: usize, span: SpanView, timeline_ms: u64) -> Element {
+    let left = span.offset_ms as f64 * 100.0 / timeline_ms as f64;
+    let width = (span.duration_ms as f64 * 100.0 / timeline_ms as f64).max(0.5);
+    let indent = depth as f64 * 1.5;
+    let (kind_role, bar_class) = match span.kind.as_str() {
+        "llm" => (LabelRole::Highlight, "bg-primary"),
+        "tool" => (LabelRole::Warning, "bg-warning"),
+        "retrieval" => (LabelRole::Info, "bg-info"),
+        _ => (LabelRole::Neutral, "bg-neutral"),
+    };
+    let bar_class = if span.success { bar_class } else { "bg-error" };
+    let tokens = match (span.prompt_tokens, span.completion_tokens) {
+        (None, None) => None,
+        (prompt, completion) => Some(format!("{} → {}", prompt.unwrap_or(0), completion.unwrap_or(0))),
+    };
+
+    rsx!(
+        div {
+            class: "border-b border-base-300 px-4 py-2",
+            div {
+                class: "grid grid-cols-12 items-center gap-2 text-sm",
+                div {
+                    class: "col-span-5 flex items-center gap-2 min-w-0",
+                    style: "padding-left: {indent}rem",
+                    Label {
+                        label_role: kind_role,
+                        "{span.kind}"
+                    }
+                    span { class: "truncate font-medium", "{span.name}" }
+                    if !span.success {
+                        Label {
+                            label_role: LabelRole::Danger,
+                            "Error"
+                        }
+                    }
+                }
+                div {
+                    class: "col-span-4",
+                    div {
+                        class: "relative h-3 rounded bg-base-200",
+                        div {
+                            class: "absolute h-3 rounded {bar_class}",
+                            style: "left: {left:.2}%; width: {width:.2}%"
+                        }
+                    }
+                }
+                div { class: "col-span-1 text-right", {format_duration(span.duration_ms)} }
+                div {
+                    class: "col-span-2 text-right text-xs text-base-content/70",
+                    if let Some(tokens) = tokens {
+                        "{tokens} tokens"
+                    }
+                }
+            }
+            details {
+                class: "mt-1 text-xs",
+                style: "padding-left: {indent}rem",
+                summary { class: "cursor-pointer text-base-content/60", "Details" }
+                div {
+                    class: "space-y-2 mt-2",
+                    if let Some(error) = &span.error {
+                        div {
+                            p { class: "font-semibold text-error", "Error" }
+                            pre { class: "bg-base-200 rounded p-2 overflow-x-auto whitespace-pre-wrap", "{error}" }
+                        }
+                    }
+                    if let Some(input) = &span.input {
+                        div {
+                            p { class: "font-semibold text-base-content/70", "Input" }
+                            pre { class: "bg-base-200 rounded p-2 overflow-x-auto max-h-96", "{input}" }
+                        }
+                    }
+                    if let Some(output) = &span.output {
+                        div {
+                            p { class: "font-semibold text-base-content/70", "Output" }
+                            pre { class: "bg-base-200 rounded p-2 overflow-x-auto max-h-96", "{output}" }
+                        }
+                    }
+                    if !span.metadata.is_empty() {
+                        MetadataTable {
+                            metadata: span.metadata.clone()
+                        }
+                    }
+                    p { class: "text-base-content/50", "Started at +{format_duration(span.offset_ms)}" }
+                }
+            }
+        }
+    )
+}
+
+#[component]
+fn MetadataTable(metadata: Vec<(String, String)>) -> Element {
+    rsx!(
+        table {
+            class: "table table-xs",
+            tbody {
+                for (key, value) in metadata {
+                    tr {
+                        td { class: "font-mono w-48", "{key}" }
+                        td { class: "font-mono break-all", "{value}" }
+                    }
+                }
+            }
+        }
+    )
+}
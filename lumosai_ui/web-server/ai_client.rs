@@ -18,8 +18,9 @@ AI服务客户端，支持多种AI提供商的统一接口。
 - 错误处理和重试机制
 */
 
+use futures::StreamExt;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use tokio_stream::Stream;
 
 /// AI服务提供商
@@ -77,7 +78,17 @@ pub struct ChatCompletionRequest {
     pub temperature: Option<f32>,
     pub max_tokens: Option<u32>,
     pub stream: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub tools: Option<Vec<Tool>>,
+    /// 流式请求的附加选项，用于在最后一个块中返回Token用量
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream_options: Option<StreamOptions>,
+}
+
+/// 流式请求选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamOptions {
+    pub include_usage: bool,
 }
 
 /// 工具定义
@@ -126,11 +137,81 @@ pub struct Usage {
 /// 流式响应块
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StreamChunk {
+    #[serde(default)]
     pub id: String,
+    #[serde(default)]
     pub object: String,
+    #[serde(default)]
     pub created: u64,
+    #[serde(default)]
     pub model: String,
-    pub choices: Vec<Choice>,
+    pub choices: Vec<StreamChoice>,
+    /// 仅在请求了 `include_usage` 的最后一个块中出现
+    #[serde(default)]
+    pub usage: Option<Usage>,
+}
+
+/// 流式选择项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamChoice {
+    #[serde(default)]
+    pub index: u32,
+    #[serde(default)]
+    pub delta: ChatDelta,
+    pub finish_reason: Option<String>,
+}
+
+/// 流式消息增量
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChatDelta {
+    pub role: Option<MessageRole>,
+    pub content: Option<String>,
+    pub tool_calls: Option<Vec<ToolCallDelta>>,
+}
+
+/// 流式工具调用增量，同一调用的参数分多个块到达，按 `index` 拼接
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolCallDelta {
+    #[serde(default)]
+    pub index: usize,
+    pub id: Option<String>,
+    pub function: Option<FunctionCallDelta>,
+}
+
+/// 函数调用增量
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FunctionCallDelta {
+    pub name: Option<String>,
+    pub arguments: Option<String>,
+}
+
+impl ToolCallDelta {
+    /// 将增量合并到已累积的工具调用列表
+    pub fn merge_into(self, calls: &mut Vec<ToolCall>) {
+        while calls.len() <= self.index {
+            calls.push(ToolCall {
+                id: String::new(),
+                r#type: "function".to_string(),
+                function: FunctionCall {
+                    name: String::new(),
+                    arguments: String::new(),
+                },
+            });
+        }
+
+        let call = &mut calls[self.index];
+        if let Some(id) = self.id {
+            call.id = id;
+        }
+        if let Some(function) = self.function {
+            if let Some(name) = function.name {
+                call.function.name.push_str(&name);
+            }
+            if let Some(arguments) = function.arguments {
+                call.function.arguments.push_str(&arguments);
+            }
+        }
+    }
 }
 
 /// AI客户端配置
@@ -186,6 +267,11 @@ impl AIClient {
         Self { config, client }
     }
 
+    /// 当前使用的模型
+    pub fn model(&self) -> &str {
+        &self.config.model
+    }
+
     /// 创建OpenAI客户端
     pub fn openai(api_key: String) -> Self {
         let config = AIClientConfig {
@@ -234,6 +320,7 @@ impl AIClient {
             max_tokens: Some(self.config.max_tokens),
             stream: Some(false),
             tools: None,
+            stream_options: None,
         };
 
         let mut req_builder = self
@@ -265,6 +352,15 @@ impl AIClient {
     pub async fn chat_completion_stream(
         &self,
         messages: Vec<ChatMessage>,
+    ) -> Result<impl Stream<Item = Result<StreamChunk, AIClientError>>, AIClientError> {
+        self.chat_completion_stream_with_tools(messages, None).await
+    }
+
+    /// 发送携带工具定义的流式聊天完成请求
+    pub async fn chat_completion_stream_with_tools(
+        &self,
+        messages: Vec<ChatMessage>,
+        tools: Option<Vec<Tool>>,
     ) -> Result<impl Stream<Item = Result<StreamChunk, AIClientError>>, AIClientError> {
         let request = ChatCompletionRequest {
             model: self.config.model.clone(),
@@ -272,7 +368,8 @@ impl AIClient {
             temperature: Some(self.config.temperature),
             max_tokens: Some(self.config.max_tokens),
             stream: Some(true),
-            tools: None,
+            tools: tools.filter(|tools| !tools.is_empty()),
+            stream_options: Some(StreamOptions { include_usage: true }),
         };
 
         let mut req_builder = self
@@ -296,9 +393,64 @@ impl AIClient {
             });
         }
 
-        // 这里需要实现SSE流解析
-        // 暂时返回一个空的流，后续会完善
-        use tokio_stream::iter;
-        Ok(iter(vec![]))
+        let state = SseState {
+            bytes: Box::pin(response.bytes_stream()),
+            buffer: Vec::new(),
+            pending: VecDeque::new(),
+            finished: false,
+        };
+
+        Ok(futures::stream::unfold(state, |mut state| async move {
+            loop {
+                if let Some(item) = state.pending.pop_front() {
+                    return Some((item, state));
+                }
+                if state.finished {
+                    return None;
+                }
+
+                match state.bytes.next().await {
+                    Some(Ok(bytes)) => state.push(bytes.as_ref()),
+                    Some(Err(e)) => {
+                        state.finished = true;
+                        return Some((Err(e.into()), state));
+                    }
+                    None => state.finished = true,
+                }
+            }
+        }))
+    }
+}
+
+/// SSE解析状态
+struct SseState<S> {
+    bytes: std::pin::Pin<Box<S>>,
+    buffer: Vec<u8>,
+    pending: VecDeque<Result<StreamChunk, AIClientError>>,
+    finished: bool,
+}
+
+impl<S> SseState<S> {
+    /// 追加字节，解析其中完整的 `data:` 行
+    fn push(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+
+        while let Some(end) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            let Some(data) = line.trim().strip_prefix("data:") else {
+                continue;
+            };
+
+            let data = data.trim();
+            if data == "[DONE]" {
+                self.finished = true;
+                break;
+            }
+
+            self.pending.push_back(
+                serde_json::from_str::<StreamChunk>(data).map_err(AIClientError::from),
+            );
+        }
     }
 }
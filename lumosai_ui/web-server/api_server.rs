@@ -12,7 +12,7 @@
 */
 
 use axum::{
-    extract::{Path, State},
+    extract::{DefaultBodyLimit, Path, State},
     http::{header, Method, StatusCode},
     response::{IntoResponse, Json},
     routing::{delete, get, post},
@@ -28,6 +28,13 @@ use crate::database::Database;
 use crate::streaming::{self, AppState};
 use crate::tools::ToolRegistry;
 use crate::file_handler::{FileHandler, FileConfig};
+use crate::knowledge::{self, KnowledgeBase};
+use crate::assistant_builder::{self, AssistantBuilder};
+use crate::traces::{self, TraceStore};
+use crate::evals::{self, EvalReports};
+use crate::reviews;
+use crate::network;
+use lumosai_core::agent::scheduler::{RequestScheduler, SchedulerConfig};
 
 /// 启动API服务器
 pub async fn start_api_server() -> Result<(), Box<dyn std::error::Error>> {
@@ -38,18 +45,33 @@ pub async fn start_api_server() -> Result<(), Box<dyn std::error::Error>> {
     let database = create_database().await?;
     let tool_registry = ToolRegistry::new();
     let file_handler = create_file_handler(database.clone()).await?;
+    let knowledge = create_knowledge_base().await?;
+    let assistants = create_assistant_builder().await?;
     let app_state = AppState {
         ai_client,
         database,
         tool_registry,
-        file_handler: file_handler.clone()
+        file_handler: file_handler.clone(),
+        knowledge,
+        assistants,
+        traces: TraceStore::new(),
+        evals: EvalReports::from_env(),
+        reviews: reviews::create_review_queue(),
+        scheduler: create_scheduler(),
+        network: network::create_agent_network().await,
     };
 
     // 配置CORS
     let cors = CorsLayer::new()
         .allow_origin(Any)
         .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
-        .allow_headers([header::CONTENT_TYPE, header::AUTHORIZATION]);
+        .allow_headers([
+            header::CONTENT_TYPE,
+            header::AUTHORIZATION,
+            header::HeaderName::from_static(streaming::TENANT_HEADER),
+            header::HeaderName::from_static(streaming::PRIORITY_HEADER),
+            header::HeaderName::from_static(reviews::REVIEWER_HEADER),
+        ]);
 
     // 构建路由
     let app = Router::new()
@@ -62,12 +84,12 @@ pub async fn start_api_server() -> Result<(), Box<dyn std::error::Error>> {
         
         // 对话管理
         .route("/api/conversations", get(streaming::list_conversations))
-        .route("/api/conversations/:id", get(streaming::get_conversation))
-        .route("/api/conversations/:id", delete(streaming::delete_conversation))
+        .route("/api/conversations/{id}", get(streaming::get_conversation))
+        .route("/api/conversations/{id}", delete(streaming::delete_conversation))
         
         // AI模型管理
         .route("/api/models", get(list_models))
-        .route("/api/models/:id", get(get_model))
+        .route("/api/models/{id}", get(get_model))
         
         // 配置管理
         .route("/api/config", get(get_config))
@@ -80,13 +102,63 @@ pub async fn start_api_server() -> Result<(), Box<dyn std::error::Error>> {
         // 文件管理
         .route("/api/files/upload", post(upload_files_handler))
         .route("/api/files", get(list_files_handler))
-        .route("/api/files/:id", delete(delete_file_handler))
+        .route("/api/files/{id}", delete(delete_file_handler))
+
+        // 知识库
+        .route("/api/datasets", get(knowledge::list_datasets).post(knowledge::create_dataset))
+        .route("/api/datasets/{id}", delete(knowledge::delete_dataset))
+        .route("/api/datasets/{id}/documents", get(knowledge::list_documents).post(knowledge::upload_documents))
+        .route("/api/datasets/{id}/search", post(knowledge::search_dataset))
+        .route("/api/documents/{id}", get(knowledge::get_document).delete(knowledge::delete_document))
+
+        // 知识库页面
+        .route("/app/team/{team_id}/datasets", get(knowledge::datasets_page))
+        .route("/app/team/{team_id}/datasets/upsert", post(knowledge::upsert_dataset_form))
+        .route("/app/team/{team_id}/datasets/delete/{id}", post(knowledge::delete_dataset_form))
+        .route("/app/team/{team_id}/dataset/{dataset_id}/documents", get(knowledge::documents_page))
+        .route("/app/team/{team_id}/dataset/{dataset_id}/doc_upload", post(knowledge::upload_documents_form))
+        .route("/app/team/{team_id}/processing/{document_id}", get(knowledge::document_processing))
+        .route("/app/team/{team_id}/delete_doc/{document_id}", post(knowledge::delete_document_form))
+
+        // Agent构建器
+        .route("/api/assistants", get(assistant_builder::list_assistants).post(assistant_builder::save_assistant))
+        .route("/api/assistants/{name}", delete(assistant_builder::delete_assistant))
+        .route("/app/team/{team_id}/assistant_builder", get(assistant_builder::builder_page))
+        .route("/app/team/{team_id}/assistant_builder/upsert", post(assistant_builder::upsert_form))
+        .route("/app/team/{team_id}/assistant_builder/edit/{name}", get(assistant_builder::edit_page))
+        .route("/app/team/{team_id}/assistant_builder/delete/{name}", post(assistant_builder::delete_form))
+
+        // 运行追踪
+        .route("/api/traces", get(traces::list_traces))
+        .route("/api/traces/{id}", get(traces::get_trace))
+        .route("/app/team/{team_id}/traces", get(traces::traces_page))
+        .route("/app/team/{team_id}/traces/{trace_id}", get(traces::trace_page))
+
+        // 评估看板
+        .route("/api/evals", get(evals::list_suites))
+        .route("/api/evals/{suite}", get(evals::suite_trends))
+        .route("/app/team/{team_id}/analytics", get(evals::dashboard_page))
+
+        // 审核队列
+        .route("/api/reviews", get(reviews::list_reviews).post(reviews::submit_review))
+        .route("/api/reviews/eval_cases", get(reviews::eval_cases))
+        .route("/api/reviews/{id}", post(reviews::decide_review))
+        .route("/app/team/{team_id}/reviews", get(reviews::reviews_page))
+        .route("/app/team/{team_id}/reviews/{id}", post(reviews::decide_form))
+
+        // 请求调度
+        .route("/api/scheduler", get(scheduler_stats))
+
+        // Agent网络
+        .route("/api/network/topology", get(network::get_topology))
+        .route("/api/network/agents/{id}", get(network::get_agent))
 
         // 静态文件和文档
         .route("/", get(api_info))
         .route("/docs", get(api_docs))
         
         .layer(ServiceBuilder::new().layer(cors))
+        .layer(DefaultBodyLimit::max(FileConfig::default().max_file_size))
         .with_state(app_state);
 
     // 启动服务器
@@ -147,6 +219,49 @@ async fn create_file_handler(database: Database) -> Result<FileHandler, Box<dyn
     Ok(file_handler)
 }
 
+/// 创建知识库
+async fn create_knowledge_base() -> Result<KnowledgeBase, Box<dyn std::error::Error>> {
+    let knowledge = KnowledgeBase::from_env(FileConfig::default().upload_dir.join("datasets"));
+
+    // 初始化文档存储目录
+    knowledge.init().await?;
+    println!("📚 Knowledge base initialized");
+
+    Ok(knowledge)
+}
+
+/// 创建Agent构建器，加载项目配置中的Agent
+async fn create_assistant_builder() -> Result<AssistantBuilder, Box<dyn std::error::Error>> {
+    let assistants = AssistantBuilder::from_env();
+    assistants.init().await?;
+    println!("🤖 Assistant builder initialized");
+
+    Ok(assistants)
+}
+
+/// 创建请求调度器
+///
+/// `LUMOS_MAX_CONCURRENT_REQUESTS` 设置全局并发上限，`LUMOS_RESERVED_INTERACTIVE` 设置为交互式请求保留的槽位数。
+fn create_scheduler() -> RequestScheduler {
+    let mut config = SchedulerConfig::default();
+    if let Some(max) = std::env::var("LUMOS_MAX_CONCURRENT_REQUESTS").ok().and_then(|v| v.parse().ok()) {
+        config.max_concurrent = max;
+    }
+    if let Some(reserved) = std::env::var("LUMOS_RESERVED_INTERACTIVE").ok().and_then(|v| v.parse().ok()) {
+        config.reserved_interactive = reserved;
+    }
+    RequestScheduler::new(config)
+}
+
+/// 各租户的调度统计
+async fn scheduler_stats(State(state): State<AppState>) -> impl IntoResponse {
+    Json(json!({
+        "max_concurrent": state.scheduler.config().max_concurrent,
+        "reserved_interactive": state.scheduler.config().reserved_interactive,
+        "tenants": state.scheduler.stats(),
+    }))
+}
+
 /// API信息
 async fn api_info() -> impl IntoResponse {
     Json(json!({
@@ -162,6 +277,13 @@ async fn api_info() -> impl IntoResponse {
             "config": "/api/config",
             "tools": "/api/tools",
             "files": "/api/files",
+            "datasets": "/api/datasets",
+            "assistants": "/api/assistants",
+            "traces": "/api/traces",
+            "evals": "/api/evals",
+            "reviews": "/api/reviews",
+            "scheduler": "/api/scheduler",
+            "network_topology": "/api/network/topology",
             "docs": "/docs"
         }
     }))
@@ -182,10 +304,15 @@ Content-Type: application/json
 {
     "message": "你好，请介绍一下自己",
     "conversation_id": "optional_conversation_id",
-    "model": "optional_model_name"
+    "model": "optional_model_name",
+    "regenerate": false
 }
 ```
 
+`regenerate` 为 `true` 时丢弃最后一条用户消息之后的回复并重新生成，此时可省略 `message`。
+响应为SSE事件流，事件类型包括 `start`、`delta`、`tool_call`、`tool_result`、`done` 和 `error`；
+客户端断开连接即停止生成，已生成的部分回复会保存到对话中。
+
 ### 简单聊天
 ```
 POST /api/chat/simple
@@ -198,6 +325,12 @@ Content-Type: application/json
 }
 ```
 
+### 请求调度
+聊天请求按租户排队：`X-Tenant-Id` 指定租户（缺省为 `default`），
+`X-Request-Priority` 取 `interactive`（缺省）或 `batch`。批处理请求不会占用为交互式请求保留的槽位；
+排队已满或超时时流式聊天返回 `code` 为 `overloaded` 的 `error` 事件。
+`GET /api/scheduler` 返回各租户运行中、排队中和被拒绝的请求数。
+
 ## 对话管理
 
 ### 获取对话列表
@@ -215,6 +348,124 @@ GET /api/conversations/{id}
 DELETE /api/conversations/{id}
 ```
 
+## 知识库
+
+### 数据集
+```
+GET /api/datasets
+POST /api/datasets
+DELETE /api/datasets/{id}
+```
+
+创建数据集时可指定 `combine_under_n_chars` 和 `new_after_n_chars` 控制分块大小。
+
+### 上传文档
+```
+POST /api/datasets/{id}/documents
+Content-Type: multipart/form-data
+```
+
+上传后在后台完成分块和嵌入，支持txt、md、json、csv、html、xml、yaml等文本格式。
+
+### 文档进度与删除
+```
+GET /api/documents/{id}
+DELETE /api/documents/{id}
+```
+
+`batches` 为分块总数，`waiting` 为待嵌入分块数；删除文档会同时移除其向量。
+
+### 检索
+```
+POST /api/datasets/{id}/search
+Content-Type: application/json
+
+{
+    "query": "检索内容",
+    "top_k": 5
+}
+```
+
+## Agent构建器
+
+Agent保存在项目配置文件中（`LUMOSAI_PROJECT_DIR` 下的 `lumos.yaml` 等），保存或删除后热重载。
+
+### 列出Agent及可选项
+```
+GET /api/assistants
+```
+
+### 创建或更新Agent
+```
+POST /api/assistants
+Content-Type: application/json
+
+{
+    "name": "support",
+    "model": "gpt-4",
+    "instructions": "你是客服助手",
+    "tools": ["calculator"],
+    "rag": "docs",
+    "blocked_topics": ["定价"],
+    "rules": ["不要承诺退款"],
+    "max_tool_calls": 3
+}
+```
+
+配置已写入但Agent构建失败时，`reload_error` 中给出原因，正在运行的Agent保持不变。
+
+### 删除Agent
+```
+DELETE /api/assistants/{name}
+```
+
+## 运行追踪
+
+每次流式聊天请求都会记录一条追踪：每轮模型调用为一个跨度（含输入消息、输出、Token用量），
+其中的工具调用为子跨度（含参数、结果和耗时）。`done` 事件中的 `trace_id` 指向本次运行。
+
+```
+GET /api/traces
+GET /api/traces/{id}
+```
+
+## 评估看板
+
+读取 `lumos eval` 写入的报告历史（`LUMOSAI_EVAL_HISTORY`，默认 `eval-reports/history`），
+返回套件的运行报告以及各指标按模型/提示词变体分组的趋势。最近一次运行的平均得分
+比上一次下降超过容差时，趋势中的 `regression` 给出前后两次得分。
+
+```
+GET /api/evals
+GET /api/evals/{suite}
+```
+
+## 审核队列
+
+评判模型或护栏置信度低于阈值（`LUMOSAI_REVIEW_THRESHOLD`，默认0.6）的回复进入审核队列。
+提交时请求体为 `{"agent", "input", "answer", "confidence", "source", "reason"}`；
+审核结论为 `{"action": "approve"|"dismiss"}` 或 `{"action": "correct", "correction": "..."}`，
+审核人取自 `x-user-id` 请求头。审核过的回复可导出为评测用例。
+
+```
+GET /api/reviews?status=pending
+POST /api/reviews
+POST /api/reviews/{id}
+GET /api/reviews/eval_cases
+```
+
+## Agent网络
+
+返回服务器内Agent网络的拓扑快照：`nodes` 为各Agent的状态、健康（`healthy`、`idle`、`stale`、`down`）、
+技能、负载和收发消息数，`edges` 为拓扑中的边以及有消息往来但没有直接连接的Agent对（`in_topology` 为 `false`），
+消息速率按最近一分钟统计。`format=dot` 时返回Graphviz DOT，可配合 `lumos visualize --network` 渲染。
+
+```
+GET /api/network/topology
+GET /api/network/topology?format=dot
+GET /api/network/agents/{id}
+```
+
 ## 模型管理
 
 ### 获取可用模型
@@ -0,0 +1,516 @@
+/*!
+# Assistant Builder Module
+
+Agent构建器后端，把页面中编辑的Agent写入项目配置文件（`lumos.yaml` 等），
+并热重载由该配置驱动的 `LumosApp`。
+
+## 功能特性
+
+- **配置持久化**: 只改写 `agents.<name>` 条目，保存前校验整份配置
+- **提示词文件**: 使用 `instructions_file` 的Agent会把指令写回对应文件
+- **热重载**: 保存或删除后只重建发生变化的Agent
+- **可选项**: 模型、内置及配置中声明的工具、RAG管道
+- **少样本示例**: 在表单中编辑 `few_shot` 示例及每次注入的数量
+*/
+
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{Html, IntoResponse, Json, Response},
+};
+use lumosai_core::app::{ConfigReload, LumosApp};
+use lumosai_core::agent::{FewShotConfig, FewShotExample};
+use lumosai_core::config::{AgentConfig, ConfigLoader, GuardrailsConfig, YamlConfig, BUILTIN_TOOL_NAMES};
+use serde_json::json;
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+use thiserror::Error;
+use tokio::sync::RwLock;
+use web_pages::assistants::builder::{AssistantDraft, BuilderNotice, BuilderOptions, ExampleDraft, ToolOption};
+
+use crate::knowledge::default_rbac;
+use crate::streaming::AppState;
+
+/// 默认配置文件名
+const DEFAULT_CONFIG_FILE: &str = "lumos.yaml";
+
+/// 未配置提供商默认模型时提供的常用模型
+const COMMON_MODELS: &[&str] = &["gpt-4", "gpt-4o", "gpt-4o-mini", "gpt-3.5-turbo", "deepseek-chat", "qwen-plus"];
+
+/// Agent构建器错误
+#[derive(Debug, Error)]
+pub enum BuilderError {
+    #[error("Agent不存在: {0}")]
+    AgentNotFound(String),
+    #[error("Agent配置无效: {0}")]
+    InvalidAgent(String),
+    #[error("配置错误: {0}")]
+    Config(#[from] lumosai_core::Error),
+    #[error("IO错误: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl IntoResponse for BuilderError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            BuilderError::AgentNotFound(_) => StatusCode::NOT_FOUND,
+            BuilderError::InvalidAgent(_) | BuilderError::Config(_) => StatusCode::BAD_REQUEST,
+            BuilderError::Io(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        (status, Json(json!({ "success": false, "error": self.to_string() }))).into_response()
+    }
+}
+
+/// 热重载结果
+///
+/// 配置已写入但Agent构建失败（如缺少API密钥）时 `error` 不为空，
+/// 正在运行的Agent保持原状。
+#[derive(Debug, Default)]
+pub struct ReloadOutcome {
+    pub changes: ConfigReload,
+    pub error: Option<String>,
+}
+
+impl ReloadOutcome {
+    fn notice(&self, action: &str, name: &str) -> BuilderNotice {
+        match &self.error {
+            Some(error) => BuilderNotice {
+                success: false,
+                message: format!("{} {} saved to config, but reload failed: {}", action, name, error),
+            },
+            None => BuilderNotice {
+                success: true,
+                message: format!(
+                    "{} {} (added: {}, updated: {}, removed: {})",
+                    action,
+                    name,
+                    self.changes.added.len(),
+                    self.changes.updated.len(),
+                    self.changes.removed.len(),
+                ),
+            },
+        }
+    }
+}
+
+/// Agent构建器
+#[derive(Clone)]
+pub struct AssistantBuilder {
+    config_path: PathBuf,
+    app: Arc<RwLock<Option<LumosApp>>>,
+}
+
+impl AssistantBuilder {
+    /// 创建构建器
+    pub fn new(config_path: PathBuf) -> Self {
+        Self {
+            config_path,
+            app: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// 在 `LUMOSAI_PROJECT_DIR`（默认当前目录）中查找项目配置
+    pub fn from_env() -> Self {
+        let dir = std::env::var("LUMOSAI_PROJECT_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from("."));
+        let config_path = ConfigLoader::find_in_dir(&dir).unwrap_or_else(|| dir.join(DEFAULT_CONFIG_FILE));
+        Self::new(config_path)
+    }
+
+    /// 加载配置中的Agent
+    pub async fn init(&self) -> Result<(), BuilderError> {
+        if self.config_path.is_file() {
+            let outcome = self.reload().await;
+            if let Some(error) = outcome.error {
+                tracing::warn!("Agents from {} not loaded: {}", self.config_path.display(), error);
+            }
+        }
+        Ok(())
+    }
+
+    /// 当前配置中的全部Agent，按名称排序
+    pub fn list(&self) -> Result<Vec<AssistantDraft>, BuilderError> {
+        let config = self.load_config()?;
+        let mut assistants: Vec<_> = config.agents.iter().flatten()
+            .map(|(name, agent)| to_draft(name, agent))
+            .collect();
+        assistants.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(assistants)
+    }
+
+    /// 获取指定Agent
+    pub fn get(&self, name: &str) -> Result<AssistantDraft, BuilderError> {
+        let config = self.load_config()?;
+        config.get_agent(name)
+            .map(|agent| to_draft(name, agent))
+            .ok_or_else(|| BuilderError::AgentNotFound(name.to_string()))
+    }
+
+    /// 页面可选的模型、工具和RAG管道
+    pub fn options(&self) -> Result<BuilderOptions, BuilderError> {
+        let config = self.load_config()?;
+
+        let mut models: BTreeSet<String> = COMMON_MODELS.iter().map(|m| m.to_string()).collect();
+        models.extend(config.providers.iter().flatten().filter_map(|(_, p)| p.model.clone()));
+        models.extend(config.agents.iter().flatten().map(|(_, a)| a.model.clone()).filter(|m| !m.is_empty()));
+
+        let mut tools: Vec<ToolOption> = BUILTIN_TOOL_NAMES.iter()
+            .map(|name| ToolOption {
+                name: name.to_string(),
+                description: builtin_tool_description(name).to_string(),
+                source: "builtin".to_string(),
+            })
+            .collect();
+        let mut declared: Vec<_> = config.tools.iter().flatten()
+            .filter(|(name, tool)| tool.enabled != Some(false) && !BUILTIN_TOOL_NAMES.contains(&name.as_str()))
+            .map(|(name, _)| ToolOption {
+                name: name.clone(),
+                description: String::new(),
+                source: "config".to_string(),
+            })
+            .collect();
+        declared.sort_by(|a, b| a.name.cmp(&b.name));
+        tools.extend(declared);
+
+        let mut rag_collections: Vec<String> = config.rag_pipelines.iter().flatten().map(|(name, _)| name.clone()).collect();
+        rag_collections.sort();
+        if config.rag.is_some() {
+            rag_collections.insert(0, "default".to_string());
+        }
+
+        Ok(BuilderOptions {
+            config_path: self.config_path.display().to_string(),
+            models: models.into_iter().collect(),
+            tools,
+            rag_collections,
+        })
+    }
+
+    /// 保存Agent并热重载
+    pub async fn save(&self, draft: AssistantDraft) -> Result<ReloadOutcome, BuilderError> {
+        validate_name(&draft.name)?;
+        if draft.model.trim().is_empty() {
+            return Err(BuilderError::InvalidAgent("model is required".to_string()));
+        }
+
+        let config = self.load_config()?;
+        let mut agent = config.get_agent(&draft.name).cloned().unwrap_or_else(|| empty_agent(&draft.model));
+        agent.model = draft.model;
+        agent.tools = (!draft.tools.is_empty()).then_some(draft.tools);
+        agent.temperature = draft.temperature;
+        agent.rag = draft.rag.filter(|rag| !rag.is_empty());
+
+        let guardrails = GuardrailsConfig {
+            blocked_topics: (!draft.blocked_topics.is_empty()).then_some(draft.blocked_topics),
+            rules: (!draft.rules.is_empty()).then_some(draft.rules),
+            max_tool_calls: draft.max_tool_calls,
+        };
+        agent.guardrails = (guardrails.blocked_topics.is_some()
+            || guardrails.rules.is_some()
+            || guardrails.max_tool_calls.is_some())
+            .then_some(guardrails);
+
+        let examples: Vec<FewShotExample> = draft.examples.into_iter()
+            .map(|example| FewShotExample {
+                input: example.input,
+                output: example.output,
+                label: example.label.filter(|label| !label.is_empty()),
+            })
+            .collect();
+        agent.few_shot = (!examples.is_empty()).then(|| {
+            let few_shot = FewShotConfig::new(examples);
+            match draft.few_shot_top_k {
+                Some(top_k) => few_shot.with_top_k(top_k),
+                None => few_shot,
+            }
+        });
+
+        // 使用提示词文件的Agent只改写文件，配置中保留文件引用
+        match &agent.instructions_file {
+            Some(file) => {
+                let path = self.base_dir().join(file);
+                tokio::fs::write(&path, &draft.instructions).await?;
+                agent.instructions = String::new();
+            }
+            None => agent.instructions = draft.instructions,
+        }
+
+        ConfigLoader::save_agent(&self.config_path, &draft.name, &agent)?;
+        Ok(self.reload().await)
+    }
+
+    /// 删除Agent并热重载
+    pub async fn delete(&self, name: &str) -> Result<ReloadOutcome, BuilderError> {
+        if !ConfigLoader::remove_agent(&self.config_path, name)? {
+            return Err(BuilderError::AgentNotFound(name.to_string()));
+        }
+        Ok(self.reload().await)
+    }
+
+    /// 从磁盘重新加载配置并应用到正在运行的应用
+    async fn reload(&self) -> ReloadOutcome {
+        let config = match ConfigLoader::load_validated(&self.config_path) {
+            Ok(config) => config,
+            Err(e) => return ReloadOutcome { error: Some(e.to_string()), ..Default::default() },
+        };
+
+        let mut app = self.app.write().await;
+        let result = match app.as_mut() {
+            Some(app) => app.reload_config(config).await,
+            None => {
+                let added = config.list_agents();
+                LumosApp::from_yaml_config(config).await.map(|loaded| {
+                    *app = Some(loaded);
+                    ConfigReload { added, ..Default::default() }
+                })
+            }
+        };
+
+        match result {
+            Ok(changes) => {
+                tracing::info!("Reloaded agents from {}: {:?}", self.config_path.display(), changes);
+                ReloadOutcome { changes, error: None }
+            }
+            Err(e) => ReloadOutcome { error: Some(e.to_string()), ..Default::default() },
+        }
+    }
+
+    fn load_config(&self) -> Result<YamlConfig, BuilderError> {
+        if !self.config_path.exists() {
+            return Ok(YamlConfig {
+                project: None,
+                agents: None,
+                workflows: None,
+                rag: None,
+                deployment: None,
+                tools: None,
+                providers: None,
+                rag_pipelines: None,
+                http: None,
+                channels: None,
+            });
+        }
+        Ok(ConfigLoader::load(&self.config_path)?)
+    }
+
+    fn base_dir(&self) -> PathBuf {
+        self.config_path.parent().map(PathBuf::from).unwrap_or_default()
+    }
+}
+
+fn to_draft(name: &str, agent: &AgentConfig) -> AssistantDraft {
+    let guardrails = agent.guardrails.clone().unwrap_or_default();
+    let few_shot = agent.few_shot.clone().unwrap_or_default();
+    AssistantDraft {
+        name: name.to_string(),
+        model: agent.model.clone(),
+        instructions: agent.instructions.clone(),
+        tools: agent.tools.clone().unwrap_or_default(),
+        rag: agent.rag.clone(),
+        blocked_topics: guardrails.blocked_topics.unwrap_or_default(),
+        rules: guardrails.rules.unwrap_or_default(),
+        max_tool_calls: guardrails.max_tool_calls,
+        temperature: agent.temperature,
+        examples: few_shot.examples.into_iter()
+            .map(|example| ExampleDraft {
+                input: example.input,
+                output: example.output,
+                label: example.label,
+            })
+            .collect(),
+        few_shot_top_k: Some(few_shot.top_k),
+    }
+}
+
+fn empty_agent(model: &str) -> AgentConfig {
+    AgentConfig {
+        model: model.to_string(),
+        instructions: String::new(),
+        instructions_file: None,
+        tools: None,
+        temperature: None,
+        max_tokens: None,
+        timeout: None,
+        memory: None,
+        voice: None,
+        provider: None,
+        rag: None,
+        guardrails: None,
+        few_shot: None,
+        tools_memory: None,
+    }
+}
+
+fn validate_name(name: &str) -> Result<(), BuilderError> {
+    let valid = !name.is_empty()
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if valid {
+        Ok(())
+    } else {
+        Err(BuilderError::InvalidAgent(format!(
+            "name '{}' may only contain letters, digits, '_' and '-'", name
+        )))
+    }
+}
+
+fn builtin_tool_description(name: &str) -> &'static str {
+    match name {
+        "web_search" => "Search the web",
+        "calculator" => "Evaluate math expressions",
+        "file_manager" => "Read and write files",
+        "code_executor" => "Run code snippets",
+        _ => "",
+    }
+}
+
+/// 解析构建器表单，`tools` 可以出现多次
+///
+/// 每个 `example_label` 开始一条新示例，随后的 `example_input`/`example_output` 属于该示例，
+/// 输入和输出都为空的示例会被丢弃。
+fn parse_draft(body: &[u8]) -> AssistantDraft {
+    let mut draft = AssistantDraft::default();
+    let lines = |value: &str| -> Vec<String> {
+        value.lines().map(str::trim).filter(|line| !line.is_empty()).map(String::from).collect()
+    };
+
+    for (key, value) in url::form_urlencoded::parse(body) {
+        let value = value.trim();
+        match key.as_ref() {
+            "name" => draft.name = value.to_string(),
+            "model" => draft.model = value.to_string(),
+            "instructions" => draft.instructions = value.to_string(),
+            "tools" => draft.tools.push(value.to_string()),
+            "rag" => draft.rag = (!value.is_empty()).then(|| value.to_string()),
+            "temperature" => draft.temperature = value.parse().ok(),
+            "blocked_topics" => draft.blocked_topics = lines(value),
+            "rules" => draft.rules = lines(value),
+            "max_tool_calls" => draft.max_tool_calls = value.parse().ok(),
+            "example_label" => draft.examples.push(ExampleDraft {
+                label: (!value.is_empty()).then(|| value.to_string()),
+                ..Default::default()
+            }),
+            "example_input" => {
+                if let Some(example) = draft.examples.last_mut() {
+                    example.input = value.to_string();
+                }
+            }
+            "example_output" => {
+                if let Some(example) = draft.examples.last_mut() {
+                    example.output = value.to_string();
+                }
+            }
+            "few_shot_top_k" => draft.few_shot_top_k = value.parse().ok(),
+            _ => {}
+        }
+    }
+    draft.examples.retain(|example| !example.input.is_empty() || !example.output.is_empty());
+    draft
+}
+
+fn render_page(
+    builder: &AssistantBuilder,
+    team_id: i32,
+    draft: AssistantDraft,
+    notice: Option<BuilderNotice>,
+) -> Result<Html<String>, BuilderError> {
+    Ok(Html(web_pages::assistants::builder::page(
+        default_rbac(team_id),
+        team_id,
+        builder.list()?,
+        draft,
+        builder.options()?,
+        notice,
+    )))
+}
+
+// ---- JSON API ----
+
+/// 列出Agent及可选项
+pub async fn list_assistants(State(state): State<AppState>) -> Result<impl IntoResponse, BuilderError> {
+    Ok(Json(json!({
+        "success": true,
+        "assistants": state.assistants.list()?,
+        "options": state.assistants.options()?,
+    })))
+}
+
+/// 创建或更新Agent
+pub async fn save_assistant(
+    State(state): State<AppState>,
+    Json(draft): Json<AssistantDraft>,
+) -> Result<impl IntoResponse, BuilderError> {
+    let outcome = state.assistants.save(draft).await?;
+    Ok(Json(json!({
+        "success": true,
+        "reload": outcome.changes,
+        "reload_error": outcome.error,
+    })))
+}
+
+/// 删除Agent
+pub async fn delete_assistant(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, BuilderError> {
+    let outcome = state.assistants.delete(&name).await?;
+    Ok(Json(json!({
+        "success": true,
+        "reload": outcome.changes,
+        "reload_error": outcome.error,
+    })))
+}
+
+// ---- 页面 ----
+
+/// 构建器页面
+pub async fn builder_page(
+    Path(team_id): Path<i32>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, BuilderError> {
+    render_page(&state.assistants, team_id, AssistantDraft::default(), None)
+}
+
+/// 编辑已有Agent
+pub async fn edit_page(
+    Path((team_id, name)): Path<(i32, String)>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, BuilderError> {
+    let draft = state.assistants.get(&name)?;
+    render_page(&state.assistants, team_id, draft, None)
+}
+
+/// 保存构建器表单
+///
+/// 校验失败时保留表单内容并显示错误。
+pub async fn upsert_form(
+    Path(team_id): Path<i32>,
+    State(state): State<AppState>,
+    body: Bytes,
+) -> Result<impl IntoResponse, BuilderError> {
+    let draft = parse_draft(&body);
+    let name = draft.name.clone();
+
+    match state.assistants.save(draft.clone()).await {
+        Ok(outcome) => {
+            let draft = state.assistants.get(&name)?;
+            render_page(&state.assistants, team_id, draft, Some(outcome.notice("Saved", &name)))
+        }
+        Err(e) => {
+            let notice = BuilderNotice { success: false, message: e.to_string() };
+            render_page(&state.assistants, team_id, draft, Some(notice))
+        }
+    }
+}
+
+/// 删除构建器中的Agent
+pub async fn delete_form(
+    Path((team_id, name)): Path<(i32, String)>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, BuilderError> {
+    let outcome = state.assistants.delete(&name).await?;
+    render_page(&state.assistants, team_id, AssistantDraft::default(), Some(outcome.notice("Deleted", &name)))
+}
@@ -305,6 +305,27 @@ impl Database {
         }
     }
 
+    /// 删除指定消息之后的所有消息，返回删除数量
+    ///
+    /// 用于重新生成回复：保留最后一条用户消息，丢弃其后的助手和工具消息。
+    pub async fn remove_messages_after(
+        &self,
+        conversation_id: i64,
+        message_id: i64,
+    ) -> Result<usize, DatabaseError> {
+        let mut store = self.store.lock().map_err(|e| DatabaseError::Internal(e.to_string()))?;
+
+        let messages = store
+            .messages
+            .get_mut(&conversation_id)
+            .ok_or(DatabaseError::ConversationNotFound)?;
+
+        let before = messages.len();
+        messages.retain(|message| message.id <= message_id);
+
+        Ok(before - messages.len())
+    }
+
     /// 更新对话的更新时间
     pub async fn touch_conversation(&self, conversation_id: i64) -> Result<(), DatabaseError> {
         let mut store = self.store.lock().map_err(|e| DatabaseError::Internal(e.to_string()))?;
@@ -0,0 +1,150 @@
+/*!
+# Evals Module
+
+评估看板模块，读取 `lumos eval` 写入的报告历史，展示各指标随时间的趋势。
+
+## 功能特性
+
+- **报告历史**: 读取 `lumosai_evals` 报告存储中的套件运行
+- **趋势对比**: 按模型/提示词变体分组展示指标得分
+- **回归提示**: 最近一次运行得分下降时在看板中标出
+*/
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{Html, IntoResponse, Json, Response},
+};
+use lumosai_evals::{MetricTrend, ReportStore, SuiteReport};
+use serde::Deserialize;
+use serde_json::json;
+use std::path::PathBuf;
+use thiserror::Error;
+use web_pages::analytics::{EvalDashboard, EvalMetricTrend, EvalRunView, EvalTrendPoint};
+
+use crate::knowledge::default_rbac;
+use crate::streaming::AppState;
+
+/// 评估报告读取错误
+#[derive(Debug, Error)]
+#[error("读取评估报告失败: {0}")]
+pub struct EvalsError(#[from] lumosai_evals::Error);
+
+impl IntoResponse for EvalsError {
+    fn into_response(self) -> Response {
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "success": false, "error": self.to_string() }))).into_response()
+    }
+}
+
+/// 评估报告历史
+#[derive(Clone)]
+pub struct EvalReports {
+    store: ReportStore,
+}
+
+impl EvalReports {
+    pub fn new(store: ReportStore) -> Self {
+        Self { store }
+    }
+
+    /// 读取 `LUMOSAI_EVAL_HISTORY`，默认为项目目录下 `lumos eval` 写入的历史目录
+    pub fn from_env() -> Self {
+        let dir = std::env::var("LUMOSAI_EVAL_HISTORY")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| {
+                std::env::var("LUMOSAI_PROJECT_DIR")
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|_| PathBuf::from("."))
+                    .join("eval-reports")
+                    .join("history")
+            });
+        Self::new(ReportStore::new(dir))
+    }
+
+    /// 看板数据，未指定套件时展示第一个套件
+    pub fn dashboard(&self, suite: Option<String>) -> Result<EvalDashboard, EvalsError> {
+        let suites = self.store.suites()?;
+        let selected_suite = suite
+            .filter(|suite| suites.contains(suite))
+            .or_else(|| suites.first().cloned());
+
+        let (runs, trends) = match &selected_suite {
+            Some(suite) => {
+                let reports = self.store.list(suite)?;
+                let trends = self.store.compute_trends(&reports);
+                (reports.iter().rev().map(to_run).collect(), trends.iter().map(to_trend).collect())
+            }
+            None => (Vec::new(), Vec::new()),
+        };
+
+        Ok(EvalDashboard {
+            history_dir: self.store.dir().display().to_string(),
+            suites,
+            selected_suite,
+            runs,
+            trends,
+        })
+    }
+}
+
+fn to_run(report: &SuiteReport) -> EvalRunView {
+    EvalRunView {
+        started_at_iso: report.started_at.to_rfc3339(),
+        variant: report.variant_label(),
+        case_count: report.cases.len(),
+        pass_rate: report.pass_rate,
+        mean_score: report.mean_score,
+        passed: report.passed,
+        failures: report.failures.clone(),
+    }
+}
+
+fn to_trend(trend: &MetricTrend) -> EvalMetricTrend {
+    EvalMetricTrend {
+        metric: trend.metric.clone(),
+        variant: trend.variant.clone(),
+        points: trend.points.iter()
+            .map(|point| EvalTrendPoint {
+                started_at_iso: point.started_at.to_rfc3339(),
+                mean: point.mean,
+                pass_rate: point.pass_rate,
+            })
+            .collect(),
+        regression: trend.regression.as_ref().map(|r| (r.previous, r.current)),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SuiteQuery {
+    pub suite: Option<String>,
+}
+
+// ---- JSON API ----
+
+/// 报告历史中的套件
+pub async fn list_suites(State(state): State<AppState>) -> Result<impl IntoResponse, EvalsError> {
+    let suites = state.evals.store.suites()?;
+    Ok(Json(json!({ "success": true, "suites": suites })))
+}
+
+/// 套件的运行报告和指标趋势
+pub async fn suite_trends(
+    Path(suite): Path<String>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, EvalsError> {
+    let reports = state.evals.store.list(&suite)?;
+    let trends = state.evals.store.compute_trends(&reports);
+    Ok(Json(json!({ "success": true, "reports": reports, "trends": trends })))
+}
+
+// ---- 页面 ----
+
+/// 评估看板页面
+pub async fn dashboard_page(
+    Path(team_id): Path<i32>,
+    Query(query): Query<SuiteQuery>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, EvalsError> {
+    let dashboard = state.evals.dashboard(query.suite)?;
+    Ok(Html(web_pages::analytics::page(default_rbac(team_id), team_id, dashboard)))
+}
@@ -0,0 +1,690 @@
+/*!
+# Knowledge Module
+
+知识库模块，管理数据集和文档，并通过 `lumosai_rag` 完成文档解析、分块和嵌入。
+
+## 功能特性
+
+- **文档上传**: 保存原始文件并在后台触发摄取
+- **分块嵌入**: 按数据集的分块配置切分文档，逐块生成嵌入写入向量存储
+- **进度跟踪**: 记录每个文档的分块总数、待处理数和失败数
+- **向量清理**: 删除文档或数据集时同步移除向量存储中的分块
+*/
+
+use axum::{
+    extract::{Multipart, Path, State},
+    http::StatusCode,
+    response::{Html, IntoResponse, Json, Redirect, Response},
+    Form,
+};
+use lumosai_rag::document::{DocumentChunker, DocumentParser, EnhancedChunker, MarkdownParser, TextParser};
+use lumosai_rag::embedding::{EmbeddingProvider, OpenAIEmbeddingProvider};
+use lumosai_rag::retriever::{InMemoryVectorStore, VectorStore};
+use lumosai_rag::{ChunkingConfig, Metadata, RagError, RetrievalOptions};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use time::OffsetDateTime;
+use tokio::fs;
+use tokio::sync::{Mutex, RwLock};
+use web_pages::types::{Dataset, Document, Model, ModelType, Rbac, Visibility};
+
+use crate::streaming::AppState;
+
+/// 默认团队ID
+const DEFAULT_TEAM_ID: i32 = 1;
+
+/// 单个文件的大小上限
+const MAX_FILE_SIZE: usize = 50 * 1024 * 1024;
+
+/// 可直接作为文本解析的文件类型
+const TEXT_FILE_TYPES: &[&str] = &[
+    "txt", "md", "markdown", "rst", "json", "csv", "tsv", "html", "htm", "xml", "yaml", "yml", "eml",
+];
+
+/// 处理状态轮询时等待进度变化的时间
+const PROCESSING_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 知识库错误
+#[derive(Debug, Error)]
+pub enum KnowledgeError {
+    #[error("数据集不存在: {0}")]
+    DatasetNotFound(i32),
+    #[error("文档不存在: {0}")]
+    DocumentNotFound(i32),
+    #[error("文件类型不支持: {0}")]
+    UnsupportedFileType(String),
+    #[error("文件大小超限: {0} bytes")]
+    FileSizeExceeded(usize),
+    #[error("上传数据无效: {0}")]
+    InvalidUpload(String),
+    #[error("摄取失败: {0}")]
+    Ingestion(String),
+    #[error("IO错误: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("RAG错误: {0}")]
+    Rag(#[from] RagError),
+}
+
+impl IntoResponse for KnowledgeError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            KnowledgeError::DatasetNotFound(_) | KnowledgeError::DocumentNotFound(_) => StatusCode::NOT_FOUND,
+            KnowledgeError::UnsupportedFileType(_)
+            | KnowledgeError::FileSizeExceeded(_)
+            | KnowledgeError::InvalidUpload(_) => StatusCode::BAD_REQUEST,
+            KnowledgeError::Ingestion(_) | KnowledgeError::Io(_) | KnowledgeError::Rag(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        };
+
+        (status, Json(json!({ "success": false, "error": self.to_string() }))).into_response()
+    }
+}
+
+/// 数据集配置
+#[derive(Debug, Deserialize)]
+pub struct DatasetForm {
+    pub id: Option<i32>,
+    pub name: String,
+    pub description: Option<String>,
+    pub visibility: Option<String>,
+    pub combine_under_n_chars: Option<i32>,
+    pub new_after_n_chars: Option<i32>,
+}
+
+/// 检索请求
+#[derive(Debug, Deserialize)]
+pub struct SearchRequest {
+    pub query: String,
+    pub top_k: Option<usize>,
+}
+
+#[derive(Default)]
+struct Catalog {
+    next_id: i32,
+    datasets: BTreeMap<i32, Dataset>,
+    documents: BTreeMap<i32, Document>,
+    /// 文档ID到其已写入向量存储的分块ID
+    chunks: HashMap<i32, Vec<String>>,
+    /// 文档ID到原始文件路径
+    files: HashMap<i32, PathBuf>,
+}
+
+impl Catalog {
+    fn next_id(&mut self) -> i32 {
+        self.next_id += 1;
+        self.next_id
+    }
+}
+
+/// 知识库
+#[derive(Clone)]
+pub struct KnowledgeBase {
+    catalog: Arc<RwLock<Catalog>>,
+    /// 每个数据集一个向量存储
+    stores: Arc<Mutex<HashMap<i32, InMemoryVectorStore>>>,
+    embedder: Arc<dyn EmbeddingProvider>,
+    embedding_model: String,
+    upload_dir: PathBuf,
+}
+
+impl KnowledgeBase {
+    /// 创建知识库
+    pub fn new(embedder: Arc<dyn EmbeddingProvider>, embedding_model: String, upload_dir: PathBuf) -> Self {
+        Self {
+            catalog: Arc::new(RwLock::new(Catalog::default())),
+            stores: Arc::new(Mutex::new(HashMap::new())),
+            embedder,
+            embedding_model,
+            upload_dir,
+        }
+    }
+
+    /// 从环境变量创建知识库
+    ///
+    /// 配置了 `OPENAI_API_KEY` 时使用OpenAI嵌入接口，否则使用本地Ollama的兼容接口。
+    /// 嵌入模型可通过 `LUMOSAI_EMBEDDING_MODEL` 覆盖。
+    pub fn from_env(upload_dir: PathBuf) -> Self {
+        let (provider, model) = match std::env::var("OPENAI_API_KEY") {
+            Ok(key) => {
+                let model = std::env::var("LUMOSAI_EMBEDDING_MODEL")
+                    .unwrap_or_else(|_| "text-embedding-3-small".to_string());
+                (OpenAIEmbeddingProvider::new(key, model.clone()), model)
+            }
+            Err(_) => {
+                let model = std::env::var("LUMOSAI_EMBEDDING_MODEL")
+                    .unwrap_or_else(|_| "nomic-embed-text".to_string());
+                let provider = OpenAIEmbeddingProvider::new(String::new(), model.clone())
+                    .with_base_url("http://localhost:11434/v1".to_string());
+                (provider, model)
+            }
+        };
+
+        Self::new(Arc::new(provider), model, upload_dir)
+    }
+
+    /// 初始化上传目录
+    pub async fn init(&self) -> Result<(), KnowledgeError> {
+        fs::create_dir_all(&self.upload_dir).await?;
+        Ok(())
+    }
+
+    /// 数据集列表，`count` 为文档数量
+    pub async fn list_datasets(&self) -> Vec<Dataset> {
+        let catalog = self.catalog.read().await;
+        catalog.datasets.values()
+            .map(|dataset| {
+                let mut dataset = dataset.clone();
+                dataset.count = catalog.documents.values()
+                    .filter(|doc| doc.dataset_id == dataset.id)
+                    .count() as i64;
+                dataset
+            })
+            .collect()
+    }
+
+    /// 获取数据集
+    pub async fn get_dataset(&self, dataset_id: i32) -> Result<Dataset, KnowledgeError> {
+        self.list_datasets().await
+            .into_iter()
+            .find(|dataset| dataset.id == dataset_id)
+            .ok_or(KnowledgeError::DatasetNotFound(dataset_id))
+    }
+
+    /// 创建或更新数据集
+    pub async fn upsert_dataset(&self, team_id: i32, form: DatasetForm) -> Result<Dataset, KnowledgeError> {
+        let mut catalog = self.catalog.write().await;
+        let id = match form.id {
+            Some(id) if catalog.datasets.contains_key(&id) => id,
+            Some(id) => return Err(KnowledgeError::DatasetNotFound(id)),
+            None => catalog.next_id(),
+        };
+
+        let created_at = catalog.datasets.get(&id)
+            .map(|dataset| dataset.created_at)
+            .unwrap_or_else(OffsetDateTime::now_utc);
+
+        let dataset = Dataset {
+            id,
+            name: form.name,
+            description: form.description,
+            team_id,
+            visibility: form.visibility.as_deref()
+                .map(web_pages::types::string_to_visibility)
+                .unwrap_or(Visibility::Private),
+            count: 0,
+            combine_under_n_chars: form.combine_under_n_chars.unwrap_or(500).max(0),
+            new_after_n_chars: form.new_after_n_chars.unwrap_or(1000).max(1),
+            embeddings_model_name: Some(self.embedding_model.clone()),
+            created_at,
+        };
+        catalog.datasets.insert(id, dataset.clone());
+        Ok(dataset)
+    }
+
+    /// 删除数据集及其全部文档和向量
+    pub async fn delete_dataset(&self, dataset_id: i32) -> Result<(), KnowledgeError> {
+        let files = {
+            let mut catalog = self.catalog.write().await;
+            catalog.datasets.remove(&dataset_id)
+                .ok_or(KnowledgeError::DatasetNotFound(dataset_id))?;
+
+            let document_ids: Vec<i32> = catalog.documents.values()
+                .filter(|doc| doc.dataset_id == dataset_id)
+                .map(|doc| doc.id)
+                .collect();
+
+            let mut files = Vec::new();
+            for id in document_ids {
+                catalog.documents.remove(&id);
+                catalog.chunks.remove(&id);
+                files.extend(catalog.files.remove(&id));
+            }
+            files
+        };
+
+        self.stores.lock().await.remove(&dataset_id);
+        for file in files {
+            let _ = fs::remove_file(file).await;
+        }
+        Ok(())
+    }
+
+    /// 数据集中的文档
+    pub async fn list_documents(&self, dataset_id: i32) -> Vec<Document> {
+        self.catalog.read().await
+            .documents.values()
+            .filter(|doc| doc.dataset_id == dataset_id)
+            .cloned()
+            .collect()
+    }
+
+    /// 获取文档
+    pub async fn get_document(&self, document_id: i32) -> Result<Document, KnowledgeError> {
+        self.catalog.read().await
+            .documents.get(&document_id)
+            .cloned()
+            .ok_or(KnowledgeError::DocumentNotFound(document_id))
+    }
+
+    /// 保存上传的文件并在后台开始摄取
+    pub async fn add_document(&self, dataset_id: i32, file_name: &str, data: &[u8]) -> Result<Document, KnowledgeError> {
+        let extension = std::path::Path::new(file_name)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_lowercase())
+            .ok_or_else(|| KnowledgeError::InvalidUpload(format!("{} 没有扩展名", file_name)))?;
+        if !TEXT_FILE_TYPES.contains(&extension.as_str()) {
+            return Err(KnowledgeError::UnsupportedFileType(extension));
+        }
+        if data.len() > MAX_FILE_SIZE {
+            return Err(KnowledgeError::FileSizeExceeded(data.len()));
+        }
+        let content = String::from_utf8(data.to_vec())
+            .map_err(|_| KnowledgeError::InvalidUpload(format!("{} 不是UTF-8文本", file_name)))?;
+
+        let dataset = self.get_dataset(dataset_id).await?;
+        let document = {
+            let mut catalog = self.catalog.write().await;
+            let document = Document {
+                id: catalog.next_id(),
+                dataset_id,
+                name: file_name.to_string(),
+                content: String::new(),
+                file_name: file_name.to_string(),
+                content_size: data.len() as i64,
+                batches: 0,
+                waiting: 0,
+                fail_count: 0,
+                failure_reason: None,
+                created_at: OffsetDateTime::now_utc(),
+            };
+            catalog.documents.insert(document.id, document.clone());
+            document
+        };
+
+        let dir = self.upload_dir.join(dataset_id.to_string());
+        fs::create_dir_all(&dir).await?;
+        let path = dir.join(format!("{}.{}", document.id, extension));
+        fs::write(&path, data).await?;
+        self.catalog.write().await.files.insert(document.id, path);
+
+        let knowledge = self.clone();
+        let document_id = document.id;
+        tokio::spawn(async move {
+            if let Err(e) = knowledge.ingest(&dataset, document_id, &extension, content).await {
+                tracing::warn!("Ingestion of document {} failed: {}", document_id, e);
+                knowledge.update(document_id, |doc| doc.failure_reason = Some(e.to_string())).await;
+            }
+        });
+
+        Ok(document)
+    }
+
+    /// 删除文档，同时移除其在向量存储中的全部分块
+    pub async fn delete_document(&self, document_id: i32) -> Result<Document, KnowledgeError> {
+        let (document, chunk_ids, file) = {
+            let mut catalog = self.catalog.write().await;
+            let document = catalog.documents.remove(&document_id)
+                .ok_or(KnowledgeError::DocumentNotFound(document_id))?;
+            let chunk_ids = catalog.chunks.remove(&document_id).unwrap_or_default();
+            (document, chunk_ids, catalog.files.remove(&document_id))
+        };
+
+        if let Some(store) = self.stores.lock().await.get_mut(&document.dataset_id) {
+            for chunk_id in &chunk_ids {
+                store.delete_document(chunk_id).await?;
+            }
+        }
+        if let Some(file) = file {
+            let _ = fs::remove_file(file).await;
+        }
+        Ok(document)
+    }
+
+    /// 在数据集中检索
+    pub async fn search(&self, dataset_id: i32, query: &str, top_k: usize) -> Result<Vec<lumosai_rag::ScoredDocument>, KnowledgeError> {
+        self.get_dataset(dataset_id).await?;
+        let stores = self.stores.lock().await;
+        let Some(store) = stores.get(&dataset_id) else {
+            return Ok(Vec::new());
+        };
+
+        let options = RetrievalOptions { limit: Some(top_k), ..Default::default() };
+        let result = store.query_by_text(query, &options, self.embedder.as_ref()).await?;
+        Ok(result.documents)
+    }
+
+    /// 解析、分块并逐块嵌入文档
+    async fn ingest(&self, dataset: &Dataset, document_id: i32, extension: &str, content: String) -> Result<(), KnowledgeError> {
+        let mut metadata = Metadata::new().with_source(self.file_name(document_id).await);
+        metadata
+            .add("dataset_id", dataset.id)
+            .add("document_id", document_id);
+
+        let parsed = match extension {
+            "md" | "markdown" => MarkdownParser::new(false).parse(&content, metadata.clone()).await?,
+            _ => TextParser.parse(&content, metadata.clone()).await?,
+        };
+
+        let chunk_size = dataset.new_after_n_chars as usize;
+        let config = ChunkingConfig {
+            chunk_size,
+            chunk_overlap: ChunkingConfig::default().chunk_overlap.min(chunk_size / 4),
+            min_chunk_size: Some(dataset.combine_under_n_chars as usize),
+            max_chunk_size: Some(chunk_size * 2),
+            ..Default::default()
+        };
+        let chunks: Vec<_> = EnhancedChunker::new().chunk(parsed, &config).await?
+            .into_iter()
+            .filter(|chunk| !chunk.content.trim().is_empty())
+            .collect();
+
+        if chunks.is_empty() {
+            return Err(KnowledgeError::Ingestion("文档没有可摄取的文本内容".to_string()));
+        }
+
+        let total = chunks.len() as i32;
+        if !self.update(document_id, |doc| {
+            doc.batches = total;
+            doc.waiting = total;
+        }).await {
+            return Ok(());
+        }
+
+        for mut chunk in chunks {
+            chunk.metadata.fields.extend(metadata.fields.clone());
+            let embedded = match self.embedder.generate_embedding(&chunk.content).await {
+                Ok(embedding) => {
+                    chunk.embedding = Some(embedding);
+                    let chunk_id = chunk.id.clone();
+                    self.stores.lock().await
+                        .entry(dataset.id)
+                        .or_default()
+                        .add_document(chunk)
+                        .await?;
+
+                    // 文档可能在嵌入期间被删除，此时撤销刚写入的分块并停止摄取
+                    let mut catalog = self.catalog.write().await;
+                    if !catalog.documents.contains_key(&document_id) {
+                        drop(catalog);
+                        if let Some(store) = self.stores.lock().await.get_mut(&dataset.id) {
+                            store.delete_document(&chunk_id).await?;
+                        }
+                        return Ok(());
+                    }
+                    catalog.chunks.entry(document_id).or_default().push(chunk_id);
+                    true
+                }
+                Err(e) => {
+                    tracing::warn!("Embedding chunk of document {} failed: {}", document_id, e);
+                    false
+                }
+            };
+
+            if !self.update(document_id, |doc| {
+                doc.waiting -= 1;
+                if !embedded {
+                    doc.fail_count += 1;
+                }
+            }).await {
+                return Ok(());
+            }
+        }
+
+        if self.get_document(document_id).await.is_ok_and(|doc| doc.fail_count == total) {
+            return Err(KnowledgeError::Ingestion("所有分块嵌入均失败，请检查嵌入模型配置".to_string()));
+        }
+        Ok(())
+    }
+
+    /// 更新文档状态，文档已被删除时返回false
+    async fn update(&self, document_id: i32, f: impl FnOnce(&mut Document)) -> bool {
+        match self.catalog.write().await.documents.get_mut(&document_id) {
+            Some(document) => {
+                f(document);
+                true
+            }
+            None => false,
+        }
+    }
+
+    async fn file_name(&self, document_id: i32) -> String {
+        self.catalog.read().await
+            .documents.get(&document_id)
+            .map(|doc| doc.file_name.clone())
+            .unwrap_or_default()
+    }
+
+    /// 页面中展示的嵌入模型
+    fn models(&self) -> Vec<Model> {
+        vec![Model {
+            id: 1,
+            name: self.embedding_model.clone(),
+            model_type: ModelType::Custom,
+            base_url: None,
+            api_key: None,
+            tpm_limit: None,
+            rpm_limit: None,
+            context_size: None,
+            created_at: OffsetDateTime::now_utc(),
+        }]
+    }
+
+    /// 读取multipart中的全部文件并逐个添加
+    async fn add_documents(&self, dataset_id: i32, mut multipart: Multipart) -> Result<(Vec<Document>, Vec<String>), KnowledgeError> {
+        let mut documents = Vec::new();
+        let mut errors = Vec::new();
+
+        while let Some(field) = multipart.next_field().await
+            .map_err(|e| KnowledgeError::InvalidUpload(e.to_string()))?
+        {
+            let Some(file_name) = field.file_name().map(str::to_string) else {
+                continue;
+            };
+            if file_name.is_empty() {
+                continue;
+            }
+
+            let result = match field.bytes().await {
+                Ok(data) => self.add_document(dataset_id, &file_name, &data).await,
+                Err(e) => Err(KnowledgeError::InvalidUpload(e.to_string())),
+            };
+            match result {
+                Ok(document) => documents.push(document),
+                Err(e @ KnowledgeError::DatasetNotFound(_)) => return Err(e),
+                Err(e) => errors.push(format!("{}: {}", file_name, e)),
+            }
+        }
+
+        Ok((documents, errors))
+    }
+}
+
+pub(crate) fn default_rbac(team_id: i32) -> Rbac {
+    Rbac {
+        email: "user@example.com".to_string(),
+        first_name: None,
+        last_name: None,
+        team_id,
+        role: "Admin".to_string(),
+    }
+}
+
+fn is_processing(document: &Document) -> bool {
+    document.failure_reason.is_none() && (document.batches == 0 || document.waiting > 0)
+}
+
+// ---- JSON API ----
+
+/// 获取数据集列表
+pub async fn list_datasets(State(state): State<AppState>) -> impl IntoResponse {
+    Json(json!({ "success": true, "datasets": state.knowledge.list_datasets().await }))
+}
+
+/// 创建数据集
+pub async fn create_dataset(
+    State(state): State<AppState>,
+    Json(form): Json<DatasetForm>,
+) -> Result<impl IntoResponse, KnowledgeError> {
+    let dataset = state.knowledge.upsert_dataset(DEFAULT_TEAM_ID, form).await?;
+    Ok((StatusCode::CREATED, Json(json!({ "success": true, "dataset": dataset }))))
+}
+
+/// 删除数据集
+pub async fn delete_dataset(
+    Path(dataset_id): Path<i32>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, KnowledgeError> {
+    state.knowledge.delete_dataset(dataset_id).await?;
+    Ok(Json(json!({ "success": true })))
+}
+
+/// 获取数据集中的文档
+pub async fn list_documents(
+    Path(dataset_id): Path<i32>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, KnowledgeError> {
+    state.knowledge.get_dataset(dataset_id).await?;
+    let documents = state.knowledge.list_documents(dataset_id).await;
+    Ok(Json(json!({ "success": true, "documents": documents })))
+}
+
+/// 上传文档并开始摄取
+pub async fn upload_documents(
+    Path(dataset_id): Path<i32>,
+    State(state): State<AppState>,
+    multipart: Multipart,
+) -> Result<impl IntoResponse, KnowledgeError> {
+    let (documents, errors) = state.knowledge.add_documents(dataset_id, multipart).await?;
+    Ok(Json(json!({
+        "success": errors.is_empty(),
+        "documents": documents,
+        "errors": errors
+    })))
+}
+
+/// 获取文档及其摄取进度
+pub async fn get_document(
+    Path(document_id): Path<i32>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, KnowledgeError> {
+    let document = state.knowledge.get_document(document_id).await?;
+    Ok(Json(json!({
+        "success": true,
+        "processing": is_processing(&document),
+        "embedded": document.batches - document.waiting - document.fail_count,
+        "document": document
+    })))
+}
+
+/// 删除文档及其向量
+pub async fn delete_document(
+    Path(document_id): Path<i32>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, KnowledgeError> {
+    state.knowledge.delete_document(document_id).await?;
+    Ok(Json(json!({ "success": true })))
+}
+
+/// 在数据集中检索
+pub async fn search_dataset(
+    Path(dataset_id): Path<i32>,
+    State(state): State<AppState>,
+    Json(request): Json<SearchRequest>,
+) -> Result<impl IntoResponse, KnowledgeError> {
+    let results = state.knowledge
+        .search(dataset_id, &request.query, request.top_k.unwrap_or(5))
+        .await?;
+    Ok(Json(json!({ "success": true, "results": results })))
+}
+
+// ---- 页面 ----
+
+/// 数据集页面
+pub async fn datasets_page(
+    Path(team_id): Path<i32>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let datasets = state.knowledge.list_datasets().await;
+    Html(web_pages::datasets::index::page(
+        default_rbac(team_id),
+        team_id,
+        datasets,
+        state.knowledge.models(),
+        true,
+    ))
+}
+
+/// 保存数据集表单
+pub async fn upsert_dataset_form(
+    Path(team_id): Path<i32>,
+    State(state): State<AppState>,
+    Form(form): Form<DatasetForm>,
+) -> Result<Redirect, KnowledgeError> {
+    state.knowledge.upsert_dataset(team_id, form).await?;
+    Ok(Redirect::to(&web_pages::routes::datasets::Index { team_id }.to_string()))
+}
+
+/// 删除数据集表单
+pub async fn delete_dataset_form(
+    Path((team_id, dataset_id)): Path<(i32, i32)>,
+    State(state): State<AppState>,
+) -> Result<Redirect, KnowledgeError> {
+    state.knowledge.delete_dataset(dataset_id).await?;
+    Ok(Redirect::to(&web_pages::routes::datasets::Index { team_id }.to_string()))
+}
+
+/// 文档页面
+pub async fn documents_page(
+    Path((team_id, dataset_id)): Path<(i32, i32)>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, KnowledgeError> {
+    let dataset = state.knowledge.get_dataset(dataset_id).await?;
+    let documents = state.knowledge.list_documents(dataset_id).await;
+    Ok(Html(web_pages::documents::index::page(default_rbac(team_id), team_id, dataset, documents)))
+}
+
+/// 上传文档表单
+pub async fn upload_documents_form(
+    Path((team_id, dataset_id)): Path<(i32, i32)>,
+    State(state): State<AppState>,
+    multipart: Multipart,
+) -> Result<Redirect, KnowledgeError> {
+    let (_, errors) = state.knowledge.add_documents(dataset_id, multipart).await?;
+    for error in errors {
+        tracing::warn!("Document upload rejected: {}", error);
+    }
+    Ok(Redirect::to(&web_pages::routes::documents::Index { team_id, dataset_id }.to_string()))
+}
+
+/// 文档处理状态，供页面中的turbo-frame轮询
+///
+/// 文档仍在处理时等待一段时间再返回，返回的行会继续轮询直到处理结束。
+pub async fn document_processing(
+    Path((team_id, document_id)): Path<(i32, i32)>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, KnowledgeError> {
+    let mut document = state.knowledge.get_document(document_id).await?;
+    if is_processing(&document) {
+        tokio::time::sleep(PROCESSING_POLL_INTERVAL).await;
+        document = state.knowledge.get_document(document_id).await?;
+    }
+
+    let processing = is_processing(&document);
+    Ok(Html(web_pages::documents::status::status(document, team_id, processing)))
+}
+
+/// 删除文档表单
+pub async fn delete_document_form(
+    Path((team_id, document_id)): Path<(i32, i32)>,
+    State(state): State<AppState>,
+) -> Result<Redirect, KnowledgeError> {
+    let document = state.knowledge.delete_document(document_id).await?;
+    Ok(Redirect::to(&web_pages::routes::documents::Index { team_id, dataset_id: document.dataset_id }.to_string()))
+}
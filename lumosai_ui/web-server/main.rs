@@ -47,6 +47,18 @@ mod database;
 mod tools;
 #[cfg(any(feature = "server", feature = "fullstack"))]
 mod file_handler;
+#[cfg(any(feature = "server", feature = "fullstack"))]
+mod knowledge;
+#[cfg(any(feature = "server", feature = "fullstack"))]
+mod assistant_builder;
+#[cfg(any(feature = "server", feature = "fullstack"))]
+mod traces;
+#[cfg(any(feature = "server", feature = "fullstack"))]
+mod evals;
+#[cfg(any(feature = "server", feature = "fullstack"))]
+mod reviews;
+#[cfg(any(feature = "server", feature = "fullstack"))]
+mod network;
 
 #[cfg(any(feature = "server", feature = "fullstack"))]
 use ai_client::AIClient;
@@ -412,7 +424,12 @@ fn Assistants() -> Element {
 
 #[component]
 fn Console() -> Element {
-    // 创建模拟数据
+    // 聊天由浏览器端脚本直接连接API服务器的SSE接口，可通过LUMOSAI_API_URL覆盖地址
+    let stream_url = format!(
+        "{}/api/chat/stream",
+        option_env!("LUMOSAI_API_URL").unwrap_or("http://127.0.0.1:3001")
+    );
+
     let rbac = Rbac {
         email: "user@example.com".to_string(),
         first_name: Some("Demo".to_string()),
@@ -439,6 +456,7 @@ fn Console() -> Element {
             // 使用增强的聊天控制台组件
             EnhancedAssistantConsole {
                 team_id: 1,
+                stream_url: stream_url,
                 conversation_id: None,
                 rbac: rbac,
                 chat_history: vec![],
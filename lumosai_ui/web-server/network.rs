@@ -0,0 +1,86 @@
+/*!
+# Network Module
+
+Agent网络观测模块，以JSON返回 `lumosai_network` 网络的拓扑快照，
+供CLI的 `lumos visualize --network` 和UI绘制实时的Agent网络图。
+
+## 功能特性
+
+- **拓扑**: 节点、拓扑中的边以及有消息往来但没有直接连接的Agent对
+- **消息速率**: 每个节点和每条边的累计消息数及最近一分钟的每秒消息数
+- **健康状态**: 按Agent状态和服务发现心跳判断节点是否健康
+*/
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Json, Response},
+};
+use lumosai_network::{AgentId, AgentNetwork, NodeSnapshot};
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+use thiserror::Error;
+
+use crate::streaming::AppState;
+
+/// 网络观测错误
+#[derive(Debug, Error)]
+pub enum NetworkApiError {
+    /// Agent不在网络中
+    #[error("Agent不存在: {0}")]
+    AgentNotFound(String),
+    /// 生成快照失败
+    #[error("读取网络拓扑失败: {0}")]
+    Network(#[from] lumosai_network::Error),
+}
+
+impl IntoResponse for NetworkApiError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            NetworkApiError::AgentNotFound(_) => StatusCode::NOT_FOUND,
+            NetworkApiError::Network(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, Json(json!({ "success": false, "error": self.to_string() }))).into_response()
+    }
+}
+
+/// 创建服务器内Agent共享的网络
+pub async fn create_agent_network() -> Arc<AgentNetwork> {
+    Arc::new(AgentNetwork::new().await)
+}
+
+/// 拓扑查询参数
+#[derive(Debug, Deserialize)]
+pub struct TopologyParams {
+    /// `json`（默认）或 `dot`
+    #[serde(default)]
+    pub format: Option<String>,
+}
+
+/// 网络拓扑快照，`format=dot` 时返回Graphviz DOT
+pub async fn get_topology(
+    State(state): State<AppState>,
+    Query(params): Query<TopologyParams>,
+) -> Result<Response, NetworkApiError> {
+    let snapshot = state.network.topology_snapshot().await?;
+    if params.format.as_deref() == Some("dot") {
+        return Ok(([(header::CONTENT_TYPE, "text/vnd.graphviz")], snapshot.to_dot()).into_response());
+    }
+    Ok(Json(snapshot).into_response())
+}
+
+/// 单个Agent的节点快照
+pub async fn get_agent(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<NodeSnapshot>, NetworkApiError> {
+    let agent_id = AgentId::from(id.as_str());
+    let snapshot = state.network.topology_snapshot().await?;
+    snapshot
+        .nodes
+        .into_iter()
+        .find(|node| node.id == agent_id)
+        .map(Json)
+        .ok_or(NetworkApiError::AgentNotFound(id))
+}
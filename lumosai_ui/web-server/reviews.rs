@@ -0,0 +1,218 @@
+/*!
+# Reviews Module
+
+审核队列模块，展示评判模型或护栏置信度较低的回复，供人工认可、修正或忽略。
+
+## 功能特性
+
+- **低置信度标记**: 置信度低于阈值（`LUMOSAI_REVIEW_THRESHOLD`，默认0.6）的回复进入队列
+- **人工修正**: 审核结论记录审核人，修正后的回复作为共享该队列的Agent的少样本示例
+- **评测用例**: 审核过的回复可导出为 `lumosai_evals` 测试用例
+*/
+
+use axum::{
+    body::Bytes,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{Html, IntoResponse, Json, Response},
+};
+use lumosai_core::agent::{ReviewDecision, ReviewItem, ReviewQueue, ReviewStatus, DEFAULT_REVIEW_THRESHOLD};
+use lumosai_evals::EvalTestCase;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+use thiserror::Error;
+use web_pages::reviews::{ReviewNotice, ReviewView};
+
+use crate::knowledge::default_rbac;
+use crate::streaming::AppState;
+
+/// 审核人请求头
+pub const REVIEWER_HEADER: &str = "x-user-id";
+
+/// 页面展示的最近审核记录数量
+const RECENT_REVIEWS: usize = 50;
+
+/// 审核操作错误
+#[derive(Debug, Error)]
+#[error("审核失败: {0}")]
+pub struct ReviewError(#[from] lumosai_core::Error);
+
+impl IntoResponse for ReviewError {
+    fn into_response(self) -> Response {
+        let status = StatusCode::from_u16(self.0.http_status()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        (status, Json(json!({ "success": false, "error": self.to_string() }))).into_response()
+    }
+}
+
+/// 读取 `LUMOSAI_REVIEW_THRESHOLD` 创建审核队列
+pub fn create_review_queue() -> Arc<ReviewQueue> {
+    let threshold = std::env::var("LUMOSAI_REVIEW_THRESHOLD")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_REVIEW_THRESHOLD);
+    Arc::new(ReviewQueue::new(threshold))
+}
+
+fn to_view(item: ReviewItem) -> ReviewView {
+    let status = serde_json::to_value(item.status)
+        .ok()
+        .and_then(|value| value.as_str().map(String::from))
+        .unwrap_or_default();
+    ReviewView {
+        id: item.id,
+        agent: item.agent,
+        input: item.input,
+        answer: item.answer,
+        confidence: item.confidence,
+        source: item.source,
+        reason: item.reason,
+        status,
+        correction: item.correction,
+        reviewer: item.reviewer,
+        created_at_iso: item.created_at.to_rfc3339(),
+    }
+}
+
+fn reviewer(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(REVIEWER_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| !value.is_empty())
+        .map(String::from)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StatusQuery {
+    pub status: Option<ReviewStatus>,
+}
+
+/// 外部评判或护栏提交的回复
+#[derive(Debug, Deserialize)]
+pub struct ReviewCandidate {
+    pub agent: String,
+    pub input: String,
+    pub answer: String,
+    pub confidence: f64,
+    #[serde(default = "default_source")]
+    pub source: String,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+fn default_source() -> String {
+    "guardrail".to_string()
+}
+
+// ---- JSON API ----
+
+/// 审核队列中的回复
+pub async fn list_reviews(
+    Query(query): Query<StatusQuery>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    Json(json!({
+        "success": true,
+        "threshold": state.reviews.threshold(),
+        "pending": state.reviews.pending_count(),
+        "reviews": state.reviews.list(query.status),
+    }))
+}
+
+/// 提交一条回复，置信度低于阈值时进入队列
+pub async fn submit_review(
+    State(state): State<AppState>,
+    Json(candidate): Json<ReviewCandidate>,
+) -> impl IntoResponse {
+    let item = state.reviews.consider(
+        candidate.agent,
+        candidate.input,
+        candidate.answer,
+        candidate.confidence,
+        candidate.source,
+        candidate.reason,
+    );
+    Json(json!({ "success": true, "flagged": item.is_some(), "review": item }))
+}
+
+/// 记录审核结论
+pub async fn decide_review(
+    Path(id): Path<String>,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(decision): Json<ReviewDecision>,
+) -> Result<impl IntoResponse, ReviewError> {
+    let item = state.reviews.review(&id, decision, reviewer(&headers))?;
+    Ok(Json(json!({ "success": true, "review": item })))
+}
+
+/// 审核过的回复导出为评测用例
+pub async fn eval_cases(State(state): State<AppState>) -> impl IntoResponse {
+    let cases: Vec<EvalTestCase> = state.reviews.list(None).iter()
+        .filter_map(EvalTestCase::from_review)
+        .collect();
+    Json(json!({ "success": true, "cases": cases }))
+}
+
+// ---- 页面 ----
+
+/// 审核队列页面
+pub async fn reviews_page(
+    Path(team_id): Path<i32>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    render_page(&state.reviews, team_id, None)
+}
+
+/// 提交审核表单
+///
+/// `action` 为 approve、correct 或 dismiss，修正内容取自 `correction`。
+pub async fn decide_form(
+    Path((team_id, id)): Path<(i32, String)>,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> impl IntoResponse {
+    let mut action = String::new();
+    let mut correction = String::new();
+    for (key, value) in url::form_urlencoded::parse(&body) {
+        match key.as_ref() {
+            "action" => action = value.into_owned(),
+            "correction" => correction = value.trim().to_string(),
+            _ => {}
+        }
+    }
+
+    let decision = match action.as_str() {
+        "approve" => Some(ReviewDecision::Approve),
+        "correct" => Some(ReviewDecision::Correct { correction }),
+        "dismiss" => Some(ReviewDecision::Dismiss),
+        _ => None,
+    };
+    let notice = match decision {
+        Some(decision) => match state.reviews.review(&id, decision, reviewer(&headers)) {
+            Ok(item) => ReviewNotice { success: true, message: format!("Review saved for {}", item.agent) },
+            Err(e) => ReviewNotice { success: false, message: e.to_string() },
+        },
+        None => ReviewNotice { success: false, message: format!("Unknown review action: {}", action) },
+    };
+    render_page(&state.reviews, team_id, Some(notice))
+}
+
+fn render_page(queue: &ReviewQueue, team_id: i32, notice: Option<ReviewNotice>) -> Html<String> {
+    let pending = queue.list(Some(ReviewStatus::Pending)).into_iter().map(to_view).collect();
+    let mut reviewed: Vec<ReviewItem> = queue.list(None).into_iter()
+        .filter(|item| item.status != ReviewStatus::Pending)
+        .collect();
+    reviewed.sort_by(|a, b| b.reviewed_at.cmp(&a.reviewed_at));
+    let reviewed = reviewed.into_iter().take(RECENT_REVIEWS).map(to_view).collect();
+
+    Html(web_pages::reviews::index::page(
+        default_rbac(team_id),
+        team_id,
+        queue.threshold(),
+        pending,
+        reviewed,
+        notice,
+    ))
+}
@@ -13,6 +13,7 @@
 
 use axum::{
     extract::{Path, State},
+    http::HeaderMap,
     response::{
         sse::{Event, KeepAlive, Sse},
         IntoResponse,
@@ -21,20 +22,51 @@ use axum::{
 };
 use futures::stream::{self, Stream};
 use serde::{Deserialize, Serialize};
-use std::{convert::Infallible, time::Duration};
-use tokio_stream::StreamExt;
+use std::{collections::HashMap, convert::Infallible, pin::Pin, sync::Arc, time::{Duration, Instant}};
+use tokio::sync::mpsc;
+use tokio_stream::{wrappers::ReceiverStream, StreamExt};
 
-use crate::ai_client::{AIClient, ChatMessage, MessageRole};
-use crate::database::Database;
-use crate::tools::{ToolRegistry, ToolContext};
+use crate::ai_client::{AIClient, ChatMessage, FunctionCall, MessageRole, Tool, ToolCall, ToolFunction};
+use crate::database::{self, Database, Message};
+use crate::tools::{ToolRegistry, ToolContext, ToolResult};
 use crate::file_handler::FileHandler;
+use crate::knowledge::KnowledgeBase;
+use crate::assistant_builder::AssistantBuilder;
+use crate::evals::EvalReports;
+use crate::traces::{RunTrace, TraceStore};
+use lumosai_core::agent::ReviewQueue;
+use lumosai_core::agent::scheduler::{PriorityClass, RequestScheduler, SchedulerPermit};
+use lumosai_core::telemetry::{StepType, TokenUsage, TraceStep};
+use lumosai_network::AgentNetwork;
+
+/// 默认用户ID（系统用户）
+const DEFAULT_USER_ID: i64 = 1;
+
+/// 单次请求中模型调用工具的最大轮数
+const MAX_TOOL_ROUNDS: usize = 5;
+
+/// 对话标题的最大字符数
+const TITLE_MAX_CHARS: usize = 30;
+
+/// 标识请求所属租户的请求头
+pub const TENANT_HEADER: &str = "x-tenant-id";
+
+/// 请求优先级请求头，取值为 `interactive` 或 `batch`
+pub const PRIORITY_HEADER: &str = "x-request-priority";
+
+/// 未携带租户头的请求归入的租户
+const DEFAULT_TENANT: &str = "default";
 
 /// 流式聊天请求
 #[derive(Debug, Deserialize)]
 pub struct StreamChatRequest {
+    #[serde(default)]
     pub message: String,
     pub conversation_id: Option<String>,
     pub model: Option<String>,
+    /// 丢弃最后一条用户消息之后的回复并重新生成
+    #[serde(default)]
+    pub regenerate: bool,
 }
 
 /// 流式聊天响应事件
@@ -59,11 +91,23 @@ pub enum StreamEvent {
         name: String,
         arguments: String,
     },
+    /// 工具调用结果
+    #[serde(rename = "tool_result")]
+    ToolResult {
+        id: String,
+        name: String,
+        success: bool,
+        result: Option<serde_json::Value>,
+        error: Option<String>,
+        execution_time_ms: u64,
+    },
     /// 完成
     #[serde(rename = "done")]
     Done {
         message_id: String,
         total_tokens: Option<u32>,
+        /// 本次运行的追踪ID，可在运行检查器中查看
+        trace_id: Option<String>,
     },
     /// 错误
     #[serde(rename = "error")]
@@ -73,6 +117,26 @@ pub enum StreamEvent {
     },
 }
 
+impl StreamEvent {
+    fn into_sse(self) -> Event {
+        let name = match &self {
+            StreamEvent::Done { .. } => "done",
+            StreamEvent::Error { .. } => "error",
+            _ => "message",
+        };
+        Event::default()
+            .event(name)
+            .data(serde_json::to_string(&self).unwrap_or_default())
+    }
+
+    fn error(message: impl Into<String>, code: &str) -> Self {
+        StreamEvent::Error {
+            message: message.into(),
+            code: Some(code.to_string()),
+        }
+    }
+}
+
 /// 应用状态
 #[derive(Clone)]
 pub struct AppState {
@@ -80,14 +144,55 @@ pub struct AppState {
     pub database: Database,
     pub tool_registry: ToolRegistry,
     pub file_handler: FileHandler,
+    pub knowledge: KnowledgeBase,
+    pub assistants: AssistantBuilder,
+    pub traces: TraceStore,
+    pub evals: EvalReports,
+    pub reviews: Arc<ReviewQueue>,
+    pub scheduler: RequestScheduler,
+    pub network: Arc<AgentNetwork>,
+}
+
+type EventStream = Pin<Box<dyn Stream<Item = Result<Event, Infallible>> + Send>>;
+
+/// 从请求头解析租户和优先级，聊天请求默认为交互式
+fn request_class(headers: &HeaderMap) -> (String, PriorityClass) {
+    let tenant = headers
+        .get(TENANT_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| !value.is_empty())
+        .unwrap_or(DEFAULT_TENANT)
+        .to_string();
+    let priority = headers
+        .get(PRIORITY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(PriorityClass::parse)
+        .unwrap_or(PriorityClass::Interactive);
+    (tenant, priority)
+}
+
+/// 按租户配额和优先级排队，取得执行许可
+async fn acquire_permit(state: &AppState, headers: &HeaderMap) -> Result<SchedulerPermit, StreamEvent> {
+    let (tenant, priority) = request_class(headers);
+    state
+        .scheduler
+        .acquire(&tenant, priority)
+        .await
+        .map_err(|e| StreamEvent::error(e.to_string(), "overloaded"))
 }
 
 /// 流式聊天处理器
+///
+/// 客户端断开连接（如点击停止按钮）时生成随即终止，已生成的部分仍会保存到对话中。
 pub async fn stream_chat(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(request): Json<StreamChatRequest>,
 ) -> impl IntoResponse {
-    let stream = create_chat_stream(state.ai_client, state.database, request).await;
+    let stream: EventStream = match acquire_permit(&state, &headers).await {
+        Ok(permit) => create_chat_stream(state, request, permit).await,
+        Err(event) => Box::pin(stream::once(async move { Ok(event.into_sse()) })),
+    };
     
     Sse::new(stream)
         .keep_alive(
@@ -98,110 +203,387 @@ pub async fn stream_chat(
 }
 
 /// 创建聊天流
-async fn create_chat_stream(
-    ai_client: AIClient,
-    _database: Database,
-    request: StreamChatRequest,
-) -> impl Stream<Item = Result<Event, Infallible>> {
-    let conversation_id = request.conversation_id.unwrap_or_else(|| generate_id());
+///
+/// 执行许可随Agent循环一起释放。
+async fn create_chat_stream(state: AppState, request: StreamChatRequest, permit: SchedulerPermit) -> EventStream {
+    let conversation_id = match prepare_conversation(&state.database, &request).await {
+        Ok(conversation_id) => conversation_id,
+        Err(event) => return Box::pin(stream::once(async move { Ok(event.into_sse()) })),
+    };
+
+    let history = match state.database.get_messages(conversation_id).await {
+        Ok(messages) => messages.iter().map(to_chat_message).collect(),
+        Err(e) => {
+            let event = StreamEvent::error(e.to_string(), "database_error");
+            return Box::pin(stream::once(async move { Ok(event.into_sse()) }));
+        }
+    };
+
     let message_id = generate_id();
+    let (tx, rx) = mpsc::channel(64);
+    let _ = tx
+        .send(StreamEvent::Start {
+            conversation_id: conversation_id.to_string(),
+            message_id: message_id.clone(),
+        })
+        .await;
+
+    tokio::spawn(async move {
+        let _permit = permit;
+        run_agent(state, conversation_id, history, message_id, tx).await;
+    });
 
-    // 创建消息历史
-    let messages = vec![ChatMessage {
-        role: MessageRole::User,
-        content: Some(request.message),
-        tool_calls: None,
-        tool_call_id: None,
-    }];
+    Box::pin(ReceiverStream::new(rx).map(|event| Ok(event.into_sse())))
+}
 
-    // 发送开始事件
-    let start_event = StreamEvent::Start {
-        conversation_id: conversation_id.clone(),
-        message_id: message_id.clone(),
+/// 解析或创建对话，并记录本轮用户消息
+async fn prepare_conversation(
+    database: &Database,
+    request: &StreamChatRequest,
+) -> Result<i64, StreamEvent> {
+    let existing = request
+        .conversation_id
+        .as_deref()
+        .and_then(|id| id.parse::<i64>().ok());
+
+    let conversation_id = match existing {
+        Some(id) => database
+            .get_conversation(id, DEFAULT_USER_ID)
+            .await
+            .map_err(|e| StreamEvent::error(e.to_string(), "conversation_error"))?
+            .id,
+        None => {
+            if request.regenerate {
+                return Err(StreamEvent::error("没有可重新生成的对话", "invalid_request"));
+            }
+            let title: String = request.message.trim().chars().take(TITLE_MAX_CHARS).collect();
+            database
+                .create_conversation(DEFAULT_USER_ID, &title)
+                .await
+                .map_err(|e| StreamEvent::error(e.to_string(), "database_error"))?
+                .id
+        }
     };
 
-    let start_stream = stream::once(async move {
-        Ok(Event::default()
-            .event("message")
-            .data(serde_json::to_string(&start_event).unwrap_or_default()))
-    });
+    if request.regenerate {
+        let messages = database
+            .get_messages(conversation_id)
+            .await
+            .map_err(|e| StreamEvent::error(e.to_string(), "database_error"))?;
+        let last_user = messages
+            .iter()
+            .rev()
+            .find(|message| message.role == database::MessageRole::User)
+            .ok_or_else(|| StreamEvent::error("对话中没有用户消息", "invalid_request"))?;
+
+        database
+            .remove_messages_after(conversation_id, last_user.id)
+            .await
+            .map_err(|e| StreamEvent::error(e.to_string(), "database_error"))?;
+    } else {
+        if request.message.trim().is_empty() {
+            return Err(StreamEvent::error("消息不能为空", "invalid_request"));
+        }
+        database
+            .add_message(conversation_id, database::MessageRole::User, Some(request.message.clone()), None, None)
+            .await
+            .map_err(|e| StreamEvent::error(e.to_string(), "database_error"))?;
+    }
 
-    // 创建AI响应流
-    let ai_stream = match ai_client.chat_completion_stream(messages).await {
-        Ok(stream) => {
-            let mapped_stream = stream.map(move |chunk_result| {
-                match chunk_result {
-                    Ok(chunk) => {
-                        // 处理流式响应块
-                        if let Some(choice) = chunk.choices.first() {
-                            if let Some(ref delta) = choice.delta {
-                                if let Some(ref content) = delta.content {
-                                    let event = StreamEvent::Delta {
-                                        content: content.clone(),
-                                    };
-                                    return Ok(Event::default()
-                                        .event("message")
-                                        .data(serde_json::to_string(&event).unwrap_or_default()));
-                                }
-                            }
-                        }
-                        
-                        // 如果没有内容，发送空的delta
-                        let event = StreamEvent::Delta {
-                            content: String::new(),
-                        };
-                        Ok(Event::default()
-                            .event("message")
-                            .data(serde_json::to_string(&event).unwrap_or_default()))
-                    }
-                    Err(e) => {
-                        let event = StreamEvent::Error {
-                            message: e.to_string(),
-                            code: Some("ai_error".to_string()),
-                        };
-                        Ok(Event::default()
-                            .event("error")
-                            .data(serde_json::to_string(&event).unwrap_or_default()))
+    Ok(conversation_id)
+}
+
+/// 执行Agent循环并记录追踪
+async fn run_agent(
+    state: AppState,
+    conversation_id: i64,
+    messages: Vec<ChatMessage>,
+    message_id: String,
+    tx: mpsc::Sender<StreamEvent>,
+) {
+    let mut metadata = HashMap::new();
+    metadata.insert("conversation_id".to_string(), serde_json::Value::from(conversation_id));
+    metadata.insert("message_id".to_string(), serde_json::Value::from(message_id.clone()));
+    metadata.insert("model".to_string(), serde_json::Value::from(state.ai_client.model()));
+    let trace = state.traces.start("chat", metadata).await;
+
+    let success = run_rounds(&state, conversation_id, messages, message_id, &tx, trace.as_ref()).await;
+    if let Some(trace) = trace {
+        trace.finish(success).await;
+    }
+}
+
+/// 流式生成回复，模型请求工具时执行工具并继续生成
+///
+/// 每轮模型调用记录为一个跨度，其工具调用为子跨度；返回是否正常完成。
+async fn run_rounds(
+    state: &AppState,
+    conversation_id: i64,
+    mut messages: Vec<ChatMessage>,
+    message_id: String,
+    tx: &mpsc::Sender<StreamEvent>,
+    trace: Option<&RunTrace>,
+) -> bool {
+    let tools = tool_specs(&state.tool_registry);
+    let context = ToolContext {
+        user_id: DEFAULT_USER_ID,
+        conversation_id,
+        permissions: vec!["basic".to_string()],
+    };
+    let mut total_usage = TokenUsage::default();
+
+    for round in 1..=MAX_TOOL_ROUNDS {
+        let mut span = TraceStep::new(format!("LLM call (round {})", round), StepType::LlmCall);
+        span.metadata.insert("model".to_string(), serde_json::Value::from(state.ai_client.model()));
+        span.input = serde_json::to_value(&messages).ok();
+        let started = Instant::now();
+
+        let mut stream = match state
+            .ai_client
+            .chat_completion_stream_with_tools(messages.clone(), Some(tools.clone()))
+            .await
+        {
+            Ok(stream) => Box::pin(stream),
+            Err(e) => {
+                record_failure(trace, span, started, e.to_string()).await;
+                let _ = tx.send(StreamEvent::error(e.to_string(), "connection_error")).await;
+                return false;
+            }
+        };
+
+        let mut content = String::new();
+        let mut tool_calls: Vec<ToolCall> = Vec::new();
+
+        while let Some(chunk) = stream.next().await {
+            let chunk = match chunk {
+                Ok(chunk) => chunk,
+                Err(e) => {
+                    record_failure(trace, span, started, e.to_string()).await;
+                    save_reply(&state.database, conversation_id, content, None).await;
+                    let _ = tx.send(StreamEvent::error(e.to_string(), "ai_error")).await;
+                    return false;
+                }
+            };
+
+            if let Some(usage) = chunk.usage {
+                let usage = TokenUsage {
+                    prompt_tokens: usage.prompt_tokens,
+                    completion_tokens: usage.completion_tokens,
+                    total_tokens: usage.total_tokens,
+                };
+                span.set_token_usage(&usage);
+                total_usage.prompt_tokens += usage.prompt_tokens;
+                total_usage.completion_tokens += usage.completion_tokens;
+                total_usage.total_tokens += usage.total_tokens;
+            }
+
+            for choice in chunk.choices {
+                if let Some(delta) = choice.delta.content.filter(|delta| !delta.is_empty()) {
+                    content.push_str(&delta);
+                    if tx.send(StreamEvent::Delta { content: delta }).await.is_err() {
+                        // 客户端已停止生成，保留已生成的部分
+                        record_failure(trace, span, started, "client disconnected".to_string()).await;
+                        save_reply(&state.database, conversation_id, content, None).await;
+                        return false;
                     }
                 }
-            });
-            
-            Box::pin(mapped_stream) as std::pin::Pin<Box<dyn Stream<Item = Result<Event, Infallible>> + Send>>
+                for call in choice.delta.tool_calls.unwrap_or_default() {
+                    call.merge_into(&mut tool_calls);
+                }
+            }
         }
-        Err(e) => {
-            let error_event = StreamEvent::Error {
-                message: e.to_string(),
-                code: Some("connection_error".to_string()),
+
+        span.set_duration(started.elapsed());
+        span.success = true;
+        span.output = Some(serde_json::json!({ "content": content, "tool_calls": tool_calls }));
+
+        if tool_calls.is_empty() {
+            if let Some(trace) = trace {
+                trace.record(span).await;
+            }
+            save_reply(&state.database, conversation_id, content, None).await;
+            let _ = tx
+                .send(StreamEvent::Done {
+                    message_id,
+                    total_tokens: (total_usage.total_tokens > 0).then_some(total_usage.total_tokens),
+                    trace_id: trace.map(|trace| trace.trace_id().to_string()),
+                })
+                .await;
+            return true;
+        }
+
+        save_reply(&state.database, conversation_id, content.clone(), Some(&tool_calls)).await;
+        messages.push(ChatMessage {
+            role: MessageRole::Assistant,
+            content: (!content.is_empty()).then_some(content),
+            tool_calls: Some(tool_calls.clone()),
+            tool_call_id: None,
+        });
+
+        let mut completed = true;
+        for call in tool_calls {
+            let event = StreamEvent::ToolCall {
+                id: call.id.clone(),
+                name: call.function.name.clone(),
+                arguments: call.function.arguments.clone(),
             };
-            let error_stream = stream::once(async move {
-                Ok(Event::default()
-                    .event("error")
-                    .data(serde_json::to_string(&error_event).unwrap_or_default()))
+            if tx.send(event).await.is_err() {
+                completed = false;
+                break;
+            }
+
+            let mut tool_span = TraceStep::new(call.function.name.clone(), StepType::ToolCall);
+            tool_span.metadata.insert("tool_call_id".to_string(), serde_json::Value::from(call.id.clone()));
+            tool_span.input = Some(
+                serde_json::from_str(&call.function.arguments)
+                    .unwrap_or_else(|_| serde_json::Value::String(call.function.arguments.clone())),
+            );
+            let tool_started = Instant::now();
+
+            let result = execute_tool_call(&state.tool_registry, &call.function, &context);
+            let output = serde_json::to_string(&result).unwrap_or_default();
+
+            tool_span.set_duration(tool_started.elapsed());
+            tool_span.success = result.success;
+            tool_span.error = result.error.clone();
+            tool_span.output = result.result.clone();
+            span.add_child(tool_span);
+
+            let _ = state
+                .database
+                .add_message(conversation_id, database::MessageRole::Tool, Some(output.clone()), None, Some(call.id.clone()))
+                .await;
+            messages.push(ChatMessage {
+                role: MessageRole::Tool,
+                content: Some(output),
+                tool_calls: None,
+                tool_call_id: Some(call.id.clone()),
             });
-            Box::pin(error_stream)
+
+            let event = StreamEvent::ToolResult {
+                id: call.id,
+                name: call.function.name,
+                success: result.success,
+                result: result.result,
+                error: result.error,
+                execution_time_ms: result.execution_time_ms,
+            };
+            if tx.send(event).await.is_err() {
+                completed = false;
+                break;
+            }
         }
-    };
 
-    // 创建完成事件流
-    let done_event = StreamEvent::Done {
-        message_id: message_id.clone(),
-        total_tokens: None,
+        if let Some(trace) = trace {
+            trace.record(span).await;
+        }
+        if !completed {
+            return false;
+        }
+    }
+
+    let _ = tx
+        .send(StreamEvent::error(
+            format!("工具调用超过{}轮，已停止生成", MAX_TOOL_ROUNDS),
+            "tool_limit",
+        ))
+        .await;
+    false
+}
+
+/// 记录失败的模型调用跨度
+async fn record_failure(trace: Option<&RunTrace>, mut span: TraceStep, started: Instant, error: String) {
+    if let Some(trace) = trace {
+        span.set_duration(started.elapsed());
+        span.success = false;
+        span.error = Some(error);
+        trace.record(span).await;
+    }
+}
+
+/// 执行模型请求的工具，失败时返回带错误信息的结果以便模型继续处理
+fn execute_tool_call(registry: &ToolRegistry, function: &FunctionCall, context: &ToolContext) -> ToolResult {
+    let params = serde_json::from_str(&function.arguments).unwrap_or_else(|_| serde_json::json!({}));
+
+    registry
+        .execute_tool(&function.name, params, context)
+        .unwrap_or_else(|e| ToolResult {
+            success: false,
+            result: None,
+            error: Some(e.to_string()),
+            execution_time_ms: 0,
+        })
+}
+
+/// 保存助手回复，内容和工具调用均为空时跳过
+async fn save_reply(database: &Database, conversation_id: i64, content: String, tool_calls: Option<&[ToolCall]>) {
+    if content.is_empty() && tool_calls.is_none() {
+        return;
+    }
+
+    let tool_calls = tool_calls.and_then(|calls| serde_json::to_string(calls).ok());
+    let content = (!content.is_empty()).then_some(content);
+    if let Err(e) = database
+        .add_message(conversation_id, database::MessageRole::Assistant, content, tool_calls, None)
+        .await
+    {
+        tracing::warn!("保存助手回复失败: {}", e);
+    }
+}
+
+/// 将启用的工具转换为模型的函数定义
+fn tool_specs(registry: &ToolRegistry) -> Vec<Tool> {
+    registry
+        .get_enabled_definitions()
+        .into_iter()
+        .map(|definition| Tool {
+            r#type: "function".to_string(),
+            function: ToolFunction {
+                parameters: definition.json_schema(),
+                name: definition.name,
+                description: definition.description,
+            },
+        })
+        .collect()
+}
+
+/// 将存储的消息转换为模型消息
+fn to_chat_message(message: &Message) -> ChatMessage {
+    let role = match message.role {
+        database::MessageRole::User => MessageRole::User,
+        database::MessageRole::Assistant => MessageRole::Assistant,
+        database::MessageRole::System => MessageRole::System,
+        database::MessageRole::Tool => MessageRole::Tool,
     };
-    let done_stream = stream::once(async move {
-        Ok(Event::default()
-            .event("done")
-            .data(serde_json::to_string(&done_event).unwrap_or_default()))
-    });
 
-    // 合并所有流
-    start_stream.chain(ai_stream).chain(done_stream)
+    ChatMessage {
+        role,
+        content: message.content.clone(),
+        tool_calls: message
+            .tool_calls
+            .as_deref()
+            .and_then(|calls| serde_json::from_str(calls).ok()),
+        tool_call_id: message.tool_call_id.clone(),
+    }
 }
 
 /// 简单的聊天处理器（非流式）
 pub async fn simple_chat(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(request): Json<StreamChatRequest>,
 ) -> impl IntoResponse {
+    let (tenant, priority) = request_class(&headers);
+    let _permit = match state.scheduler.acquire(&tenant, priority).await {
+        Ok(permit) => permit,
+        Err(e) => {
+            return Json(serde_json::json!({
+                "success": false,
+                "error": e.to_string()
+            }))
+        }
+    };
+
     let messages = vec![ChatMessage {
         role: MessageRole::User,
         content: Some(request.message),
@@ -249,8 +631,7 @@ pub async fn get_conversation(
         }
     };
 
-    // 默认用户ID为1（系统用户）
-    let user_id = 1;
+    let user_id = DEFAULT_USER_ID;
 
     match state.database.get_conversation(conversation_id, user_id).await {
         Ok(conversation) => {
@@ -293,8 +674,7 @@ pub async fn delete_conversation(
         }
     };
 
-    // 默认用户ID为1（系统用户）
-    let user_id = 1;
+    let user_id = DEFAULT_USER_ID;
 
     match state.database.delete_conversation(conversation_id, user_id).await {
         Ok(_) => Json(serde_json::json!({
@@ -312,8 +692,7 @@ pub async fn delete_conversation(
 pub async fn list_conversations(
     State(state): State<AppState>,
 ) -> impl IntoResponse {
-    // 默认用户ID为1（系统用户）
-    let user_id = 1;
+    let user_id = DEFAULT_USER_ID;
 
     match state.database.get_conversations(user_id).await {
         Ok(conversations) => Json(serde_json::json!({
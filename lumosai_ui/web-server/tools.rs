@@ -58,6 +58,39 @@ pub struct ToolDefinition {
     pub enabled: bool,
 }
 
+impl ToolDefinition {
+    /// 将参数列表转换为JSON Schema，供模型的函数调用使用
+    pub fn json_schema(&self) -> Value {
+        let properties: serde_json::Map<String, Value> = self
+            .parameters
+            .iter()
+            .map(|param| {
+                let mut property = json!({
+                    "type": param.r#type,
+                    "description": param.description,
+                });
+                if let Some(default) = &param.default {
+                    property["default"] = default.clone();
+                }
+                (param.name.clone(), property)
+            })
+            .collect();
+
+        let required: Vec<&str> = self
+            .parameters
+            .iter()
+            .filter(|param| param.required)
+            .map(|param| param.name.as_str())
+            .collect();
+
+        json!({
+            "type": "object",
+            "properties": properties,
+            "required": required,
+        })
+    }
+}
+
 /// 工具执行结果
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolResult {
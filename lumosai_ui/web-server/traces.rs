@@ -0,0 +1,242 @@
+/*!
+# Traces Module
+
+运行追踪模块，把每次Agent请求记录为 `lumosai_core` 遥测子系统中的执行追踪，
+供运行检查器页面展示。
+
+## 功能特性
+
+- **跨度树**: 每轮模型调用为一个跨度，其调用的工具为子跨度
+- **耗时与Token**: 记录每个跨度的耗时和模型返回的Token用量
+- **原始载荷**: 保存模型输入输出、工具参数和结果
+*/
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{Html, IntoResponse, Json, Response},
+};
+use lumosai_core::telemetry::trace::TraceQuery;
+use lumosai_core::telemetry::{ExecutionTrace, InMemoryMetricsCollector, StepType, TraceCollector, TraceStep};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use time::OffsetDateTime;
+use web_pages::traces::{SpanView, TraceSummary, TraceView};
+
+use crate::knowledge::default_rbac;
+use crate::streaming::AppState;
+
+/// 列表页展示的最近追踪数量
+const RECENT_TRACES: usize = 50;
+
+/// 追踪不存在
+#[derive(Debug)]
+pub struct TraceNotFound(String);
+
+impl IntoResponse for TraceNotFound {
+    fn into_response(self) -> Response {
+        let message = format!("追踪不存在: {}", self.0);
+        (StatusCode::NOT_FOUND, Json(json!({ "success": false, "error": message }))).into_response()
+    }
+}
+
+/// 追踪存储
+#[derive(Clone)]
+pub struct TraceStore {
+    collector: Arc<InMemoryMetricsCollector>,
+}
+
+impl TraceStore {
+    /// 创建内存追踪存储
+    pub fn new() -> Self {
+        Self {
+            collector: Arc::new(InMemoryMetricsCollector::new()),
+        }
+    }
+
+    /// 开始记录一次运行，遥测失败时不影响请求本身
+    pub async fn start(&self, agent_id: &str, metadata: HashMap<String, serde_json::Value>) -> Option<RunTrace> {
+        match self.collector.start_trace(agent_id.to_string(), metadata).await {
+            Ok(trace_id) => Some(RunTrace {
+                collector: self.collector.clone(),
+                trace_id,
+            }),
+            Err(e) => {
+                tracing::warn!("开始追踪失败: {}", e);
+                None
+            }
+        }
+    }
+
+    /// 最近的追踪，按开始时间倒序
+    pub async fn recent(&self, limit: usize) -> Vec<ExecutionTrace> {
+        let query = TraceQuery { limit: None, ..Default::default() };
+        let mut traces = self.collector.search_traces(query).await.unwrap_or_default();
+        traces.sort_by(|a, b| b.start_time.cmp(&a.start_time));
+        traces.truncate(limit);
+        traces
+    }
+
+    /// 获取追踪
+    pub async fn get(&self, trace_id: &str) -> Result<ExecutionTrace, TraceNotFound> {
+        self.collector.get_trace(trace_id).await
+            .ok()
+            .flatten()
+            .ok_or_else(|| TraceNotFound(trace_id.to_string()))
+    }
+}
+
+/// 正在记录的运行
+pub struct RunTrace {
+    collector: Arc<InMemoryMetricsCollector>,
+    trace_id: String,
+}
+
+impl RunTrace {
+    /// 追踪ID
+    pub fn trace_id(&self) -> &str {
+        &self.trace_id
+    }
+
+    /// 记录一个顶层跨度
+    pub async fn record(&self, step: TraceStep) {
+        if let Err(e) = self.collector.add_trace_step(&self.trace_id, step).await {
+            tracing::warn!("记录追踪步骤失败: {}", e);
+        }
+    }
+
+    /// 结束运行
+    pub async fn finish(self, success: bool) {
+        if let Err(e) = self.collector.end_trace(&self.trace_id, success).await {
+            tracing::warn!("结束追踪失败: {}", e);
+        }
+    }
+}
+
+fn span_kind(step_type: &StepType) -> String {
+    match step_type {
+        StepType::LlmCall => "llm",
+        StepType::ToolCall => "tool",
+        StepType::Retrieval => "retrieval",
+        StepType::MemoryOperation => "memory",
+        StepType::DataProcessing => "processing",
+        StepType::Validation => "validation",
+        StepType::Transformation => "transformation",
+        StepType::Custom(name) => name,
+    }
+    .to_string()
+}
+
+fn payload(value: &Option<serde_json::Value>) -> Option<String> {
+    value.as_ref().map(|value| serde_json::to_string_pretty(value).unwrap_or_default())
+}
+
+fn to_span(step: &TraceStep, trace_start: u64) -> SpanView {
+    let usage = step.token_usage();
+    let mut metadata: Vec<(String, String)> = step.metadata.iter()
+        .filter(|(key, _)| key.as_str() != "prompt_tokens" && key.as_str() != "completion_tokens")
+        .map(|(key, value)| (key.clone(), value.as_str().map(String::from).unwrap_or_else(|| value.to_string())))
+        .collect();
+    metadata.sort();
+
+    SpanView {
+        name: step.name.clone(),
+        kind: span_kind(&step.step_type),
+        offset_ms: step.start_time.saturating_sub(trace_start),
+        duration_ms: step.duration_ms,
+        success: step.success,
+        error: step.error.clone(),
+        prompt_tokens: usage.as_ref().map(|u| u.prompt_tokens),
+        completion_tokens: usage.as_ref().map(|u| u.completion_tokens),
+        input: payload(&step.input),
+        output: payload(&step.output),
+        metadata,
+        children: step.children.iter().map(|child| to_span(child, trace_start)).collect(),
+    }
+}
+
+fn count_tool_calls(steps: &[TraceStep]) -> usize {
+    steps.iter()
+        .map(|step| matches!(step.step_type, StepType::ToolCall) as usize + count_tool_calls(&step.children))
+        .sum()
+}
+
+fn started_at_iso(trace: &ExecutionTrace) -> String {
+    OffsetDateTime::from_unix_timestamp_nanos(trace.start_time as i128 * 1_000_000)
+        .ok()
+        .and_then(|time| time.format(&time::format_description::well_known::Rfc3339).ok())
+        .unwrap_or_default()
+}
+
+fn to_summary(trace: &ExecutionTrace) -> TraceSummary {
+    let usage = trace.token_usage();
+    TraceSummary {
+        trace_id: trace.trace_id.clone(),
+        agent_id: trace.agent_id.clone(),
+        started_at_iso: started_at_iso(trace),
+        duration_ms: trace.total_duration_ms,
+        span_count: trace.steps.len(),
+        tool_calls: count_tool_calls(&trace.steps),
+        total_tokens: usage.total_tokens,
+        success: trace.success,
+        finished: trace.end_time > trace.start_time || trace.total_duration_ms > 0,
+    }
+}
+
+fn to_view(trace: &ExecutionTrace) -> TraceView {
+    let usage = trace.token_usage();
+    let mut metadata: Vec<(String, String)> = trace.metadata.iter()
+        .map(|(key, value)| (key.clone(), value.as_str().map(String::from).unwrap_or_else(|| value.to_string())))
+        .collect();
+    metadata.sort();
+
+    TraceView {
+        summary: to_summary(trace),
+        prompt_tokens: usage.prompt_tokens,
+        completion_tokens: usage.completion_tokens,
+        metadata,
+        spans: trace.steps.iter().map(|step| to_span(step, trace.start_time)).collect(),
+    }
+}
+
+// ---- JSON API ----
+
+/// 最近的追踪
+pub async fn list_traces(State(state): State<AppState>) -> impl IntoResponse {
+    let traces = state.traces.recent(RECENT_TRACES).await;
+    Json(json!({ "success": true, "traces": traces }))
+}
+
+/// 单个追踪的完整数据
+pub async fn get_trace(
+    Path(trace_id): Path<String>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, TraceNotFound> {
+    let trace = state.traces.get(&trace_id).await?;
+    Ok(Json(json!({ "success": true, "trace": trace })))
+}
+
+// ---- 页面 ----
+
+/// 追踪列表页面
+pub async fn traces_page(
+    Path(team_id): Path<i32>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let traces = state.traces.recent(RECENT_TRACES).await;
+    Html(web_pages::traces::index::page(
+        default_rbac(team_id),
+        team_id,
+        traces.iter().map(to_summary).collect(),
+    ))
+}
+
+/// 运行检查器页面
+pub async fn trace_page(
+    Path((team_id, trace_id)): Path<(i32, String)>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, TraceNotFound> {
+    let trace = state.traces.get(&trace_id).await?;
+    Ok(Html(web_pages::traces::view::page(default_rbac(team_id), team_id, to_view(&trace))))
+}
@@ -0,0 +1,118 @@
+//! Vector storage backend benchmarks on SIFT/GloVe-style datasets
+//!
+//! Run with: cd lumosai_vector && cargo bench --bench vector_backends --features qdrant,postgres
+//!
+//! - `LUMOS_BENCH_SCALES`: comma separated dataset sizes (default `1000,10000`)
+//! - `LUMOS_BENCH_SIFT_DIR`: directory holding `sift_base.fvecs`/`sift_query.fvecs` to use real SIFT vectors
+//! - `LUMOS_BENCH_GLOVE`: path of a GloVe text file (e.g. `glove.6B.100d.txt`) to use real GloVe vectors
+//! - `QDRANT_URL` / `DATABASE_URL`: enable the Qdrant and PostgreSQL backends
+//! - `LUMOS_BENCH_REPORT_DIR`: where the comparison report is written (default: `target/vector-benchmarks` of the workspace)
+//!
+//! The SQLite backend lives in `lumosai_core` and is benchmarked by its `sqlite_vector` bench,
+//! which adds its results to the same report.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use tokio::runtime::Runtime;
+use lumosai_vector::benchmark::{measure, BenchmarkReport, Dataset, RECALL_K};
+use lumosai_vector::memory::MemoryVectorStorage;
+use lumosai_vector::prelude::*;
+
+const QUERIES: usize = 100;
+const SEED: u64 = 42;
+
+fn scales() -> Vec<usize> {
+    std::env::var("LUMOS_BENCH_SCALES")
+        .ok()
+        .map(|scales| scales.split(',').filter_map(|scale| scale.trim().parse().ok()).collect())
+        .filter(|scales: &Vec<usize>| !scales.is_empty())
+        .unwrap_or_else(|| vec![1_000, 10_000])
+}
+
+fn datasets(size: usize) -> Vec<Dataset> {
+    let sift = match std::env::var("LUMOS_BENCH_SIFT_DIR") {
+        Ok(dir) => Dataset::load_texmex(dir, "sift", size, QUERIES),
+        Err(_) => Dataset::sift_like(size, QUERIES, SEED),
+    };
+    let glove = match std::env::var("LUMOS_BENCH_GLOVE") {
+        Ok(path) => Dataset::load_glove(path, size, QUERIES),
+        Err(_) => Dataset::glove_like(size, QUERIES, SEED),
+    };
+    vec![sift.expect("Failed to load SIFT dataset"), glove.expect("Failed to load GloVe dataset")]
+}
+
+// Criterion measurements for one backend, followed by a recall run recorded in the report
+fn bench_backend<S: VectorStorage>(c: &mut Criterion, rt: &Runtime, backend: &str, storage: &S, report: &mut BenchmarkReport) {
+    for size in scales() {
+        for dataset in datasets(size) {
+            let index = format!("criterion_{}_{}", dataset.name, size);
+            let _ = rt.block_on(storage.delete_index(&index));
+            rt.block_on(storage.create_index(dataset.index_config(&index))).unwrap();
+            let batches = dataset.document_batches();
+
+            let mut group = c.benchmark_group(format!("{}/{}", dataset.name, backend));
+            group.sample_size(10);
+
+            group.throughput(Throughput::Elements(size as u64));
+            group.bench_with_input(BenchmarkId::new("insert", size), &batches, |b, batches| {
+                b.to_async(rt).iter(|| async {
+                    for batch in batches {
+                        storage.upsert_documents(&index, batch.clone()).await.unwrap();
+                    }
+                });
+            });
+
+            group.throughput(Throughput::Elements(1));
+            group.bench_with_input(BenchmarkId::new("search", size), &dataset.queries, |b, queries| {
+                let mut next = 0;
+                b.to_async(rt).iter(|| {
+                    let request = SearchRequest::new(index.as_str(), queries[next % queries.len()].clone()).with_top_k(RECALL_K);
+                    next += 1;
+                    async move { storage.search(request).await.unwrap() }
+                });
+            });
+            group.finish();
+
+            rt.block_on(storage.delete_index(&index)).unwrap();
+
+            let record = rt.block_on(measure(storage, backend, &dataset)).unwrap();
+            println!(
+                "{} {} x{}: {:.0} inserts/s, {:.1} QPS, recall@{} {:.3}",
+                backend, dataset.name, size, record.insert_per_sec, record.search_qps, RECALL_K, record.recall
+            );
+            report.upsert(record);
+        }
+    }
+}
+
+fn vector_backends(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let dir = BenchmarkReport::default_dir();
+    let mut report = BenchmarkReport::load(&dir).unwrap();
+
+    let memory = rt.block_on(MemoryVectorStorage::new()).unwrap();
+    bench_backend(c, &rt, "memory", &memory, &mut report);
+
+    #[cfg(feature = "qdrant")]
+    match std::env::var("QDRANT_URL") {
+        Ok(url) => {
+            let qdrant = rt.block_on(lumosai_vector::qdrant::QdrantVectorStorage::new(&url)).unwrap();
+            bench_backend(c, &rt, "qdrant", &qdrant, &mut report);
+        }
+        Err(_) => println!("Skipping Qdrant benchmarks: QDRANT_URL not set"),
+    }
+
+    #[cfg(feature = "postgres")]
+    match std::env::var("DATABASE_URL") {
+        Ok(url) => {
+            let postgres = rt.block_on(lumosai_vector::postgres::PostgresVectorStorage::new(&url)).unwrap();
+            bench_backend(c, &rt, "postgres", &postgres, &mut report);
+        }
+        Err(_) => println!("Skipping PostgreSQL benchmarks: DATABASE_URL not set"),
+    }
+
+    report.save(&dir).unwrap();
+    println!("Benchmark report written to {}", dir.join("report.md").display());
+}
+
+criterion_group!(benches, vector_backends);
+criterion_main!(benches);
@@ -0,0 +1,266 @@
+//! Metadata aggregations
+//!
+//! Counts, facets and min/max over document metadata, optionally restricted by a
+//! filter. Backends that can aggregate natively (e.g. in SQL) do so; the others feed
+//! their documents through an [`Aggregator`].
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::types::{FilterCondition, Metadata, MetadataValue};
+
+/// A single aggregation over the documents of an index
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Aggregation {
+    /// Number of matching documents
+    Count,
+    /// Number of matching documents per value of a metadata field, most frequent first
+    Facet {
+        /// Metadata field
+        field: String,
+        /// Keep only the most frequent values
+        limit: Option<usize>,
+    },
+    /// Smallest value of a metadata field
+    Min(String),
+    /// Largest value of a metadata field
+    Max(String),
+}
+
+impl Aggregation {
+    /// Count documents per value of `field`
+    pub fn facet(field: impl Into<String>) -> Self {
+        Self::Facet { field: field.into(), limit: None }
+    }
+
+    /// Count documents per value of `field`, keeping the `limit` most frequent values
+    pub fn top_values(field: impl Into<String>, limit: usize) -> Self {
+        Self::Facet { field: field.into(), limit: Some(limit) }
+    }
+
+    /// Smallest value of `field`
+    pub fn min(field: impl Into<String>) -> Self {
+        Self::Min(field.into())
+    }
+
+    /// Largest value of `field`
+    pub fn max(field: impl Into<String>) -> Self {
+        Self::Max(field.into())
+    }
+}
+
+/// Number of documents with a given field value
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FacetCount {
+    /// Field value
+    pub value: MetadataValue,
+    /// Number of documents with this value
+    pub count: usize,
+}
+
+/// Result of one aggregation
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AggregationResult {
+    /// Result of [`Aggregation::Count`]
+    Count(usize),
+    /// Result of [`Aggregation::Facet`]
+    Facet(Vec<FacetCount>),
+    /// Result of [`Aggregation::Min`] or [`Aggregation::Max`]; `None` if no document has the field
+    Value(Option<MetadataValue>),
+}
+
+/// Aggregation request
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AggregateRequest {
+    /// Index to aggregate
+    pub index_name: String,
+    /// Aggregations to compute
+    pub aggregations: Vec<Aggregation>,
+    /// Only aggregate documents matching this filter
+    pub filter: Option<FilterCondition>,
+}
+
+impl AggregateRequest {
+    /// Create an empty aggregation request
+    pub fn new(index_name: impl Into<String>) -> Self {
+        Self {
+            index_name: index_name.into(),
+            aggregations: Vec::new(),
+            filter: None,
+        }
+    }
+
+    /// Add an aggregation
+    pub fn with_aggregation(mut self, aggregation: Aggregation) -> Self {
+        self.aggregations.push(aggregation);
+        self
+    }
+
+    /// Only aggregate documents matching `filter`
+    pub fn with_filter(mut self, filter: FilterCondition) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+}
+
+/// Aggregation response
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AggregateResponse {
+    /// One result per requested aggregation, in request order
+    pub results: Vec<AggregationResult>,
+}
+
+/// Order metadata values by type, then by value
+///
+/// Types rank like PostgreSQL `jsonb`: null < string < number < boolean < array < object,
+/// so client-side and SQL aggregations agree. Integers and floats compare numerically.
+pub fn compare_metadata_values(a: &MetadataValue, b: &MetadataValue) -> Ordering {
+    fn rank(value: &MetadataValue) -> u8 {
+        match value {
+            MetadataValue::Null => 0,
+            MetadataValue::String(_) => 1,
+            MetadataValue::Integer(_) | MetadataValue::Float(_) => 2,
+            MetadataValue::Boolean(_) => 3,
+            MetadataValue::Array(_) => 4,
+            MetadataValue::Object(_) => 5,
+        }
+    }
+    fn number(value: &MetadataValue) -> f64 {
+        match value {
+            MetadataValue::Integer(i) => *i as f64,
+            MetadataValue::Float(f) => *f,
+            _ => 0.0,
+        }
+    }
+
+    rank(a).cmp(&rank(b)).then_with(|| match (a, b) {
+        (MetadataValue::String(x), MetadataValue::String(y)) => x.cmp(y),
+        (MetadataValue::Boolean(x), MetadataValue::Boolean(y)) => x.cmp(y),
+        (MetadataValue::Array(x), MetadataValue::Array(y)) => x
+            .iter()
+            .zip(y)
+            .map(|(x, y)| compare_metadata_values(x, y))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or_else(|| x.len().cmp(&y.len())),
+        _ if rank(a) == 2 => number(a).total_cmp(&number(b)),
+        _ => Ordering::Equal,
+    })
+}
+
+enum State {
+    Count(usize),
+    Facet(HashMap<String, FacetCount>),
+    Value(Option<MetadataValue>),
+}
+
+fn count_value(counts: &mut HashMap<String, FacetCount>, value: MetadataValue, count: usize) {
+    counts
+        .entry(format!("{:?}", value))
+        .or_insert_with(|| FacetCount { value, count: 0 })
+        .count += count;
+}
+
+/// Keep `value` if it beats the current min or max
+fn offer_value(aggregation: &Aggregation, current: &mut Option<MetadataValue>, value: &MetadataValue) {
+    let wanted = match aggregation {
+        Aggregation::Max(_) => Ordering::Greater,
+        _ => Ordering::Less,
+    };
+    if current.as_ref().is_none_or(|c| compare_metadata_values(value, c) == wanted) {
+        *current = Some(value.clone());
+    }
+}
+
+/// Computes aggregations client-side, one document at a time
+pub struct Aggregator {
+    aggregations: Vec<Aggregation>,
+    states: Vec<State>,
+}
+
+impl Aggregator {
+    /// Start computing `aggregations`
+    pub fn new(aggregations: &[Aggregation]) -> Self {
+        let states = aggregations
+            .iter()
+            .map(|aggregation| match aggregation {
+                Aggregation::Count => State::Count(0),
+                Aggregation::Facet { .. } => State::Facet(HashMap::new()),
+                Aggregation::Min(_) | Aggregation::Max(_) => State::Value(None),
+            })
+            .collect();
+        Self {
+            aggregations: aggregations.to_vec(),
+            states,
+        }
+    }
+
+    /// Add the metadata of a matching document
+    pub fn add(&mut self, metadata: &Metadata) {
+        for (aggregation, state) in self.aggregations.iter().zip(&mut self.states) {
+            match (aggregation, state) {
+                (Aggregation::Count, State::Count(count)) => *count += 1,
+                (Aggregation::Facet { field, .. }, State::Facet(counts)) => {
+                    if let Some(value) = metadata.get(field) {
+                        count_value(counts, value.clone(), 1);
+                    }
+                }
+                (Aggregation::Min(field) | Aggregation::Max(field), State::Value(current)) => {
+                    if let Some(value) = metadata.get(field) {
+                        offer_value(aggregation, current, value);
+                    }
+                }
+                _ => unreachable!("aggregation state is created from the aggregation"),
+            }
+        }
+    }
+
+    /// Fold in the results of the same aggregations computed over another partition
+    ///
+    /// Facets are only exact if the partial results were computed without a limit.
+    pub fn merge(&mut self, partial: AggregateResponse) {
+        for ((aggregation, state), result) in self.aggregations.iter().zip(&mut self.states).zip(partial.results) {
+            match (state, result) {
+                (State::Count(count), AggregationResult::Count(partial)) => *count += partial,
+                (State::Facet(counts), AggregationResult::Facet(partial)) => {
+                    for facet in partial {
+                        count_value(counts, facet.value, facet.count);
+                    }
+                }
+                (State::Value(current), AggregationResult::Value(Some(value))) => {
+                    offer_value(aggregation, current, &value);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Final results, in the order of the aggregations
+    pub fn finish(self) -> AggregateResponse {
+        let results = self
+            .aggregations
+            .iter()
+            .zip(self.states)
+            .map(|(aggregation, state)| match state {
+                State::Count(count) => AggregationResult::Count(count),
+                State::Value(value) => AggregationResult::Value(value),
+                State::Facet(counts) => {
+                    let mut facets: Vec<FacetCount> = counts.into_values().collect();
+                    facets.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| compare_metadata_values(&a.value, &b.value)));
+                    if let Aggregation::Facet { limit: Some(limit), .. } = aggregation {
+                        facets.truncate(*limit);
+                    }
+                    AggregationResult::Facet(facets)
+                }
+            })
+            .collect();
+        AggregateResponse { results }
+    }
+}
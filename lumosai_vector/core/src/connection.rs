@@ -0,0 +1,321 @@
+//! Connection management for remote vector storage backends
+//!
+//! Remote backends (PostgreSQL, Milvus, Weaviate) share the same concerns: how many
+//! requests may be in flight, whether the server is reachable, and how to recover
+//! when it is not. A [`ConnectionManager`] owns the backend connection handle, limits
+//! concurrent use to the configured pool size, probes health, and reconnects with
+//! exponential backoff. Its [`PoolMetrics`] are surfaced through [`BackendInfo`].
+
+use async_trait::async_trait;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{RwLock, Semaphore};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Result, VectorError};
+use crate::traits::BackendInfo;
+
+/// Exponential backoff settings for reconnects and retries
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BackoffConfig {
+    /// Delay before the first retry
+    pub initial_delay: Duration,
+    /// Upper bound for the delay
+    pub max_delay: Duration,
+    /// Factor applied to the delay after each attempt
+    pub multiplier: f64,
+    /// Number of retries after the first attempt
+    pub max_retries: u32,
+}
+
+impl Default for BackoffConfig {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(10),
+            multiplier: 2.0,
+            max_retries: 3,
+        }
+    }
+}
+
+impl BackoffConfig {
+    /// Delay before retry number `attempt` (starting at 0)
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = self.multiplier.max(1.0).powi(attempt as i32);
+        self.initial_delay.mul_f64(factor).min(self.max_delay)
+    }
+}
+
+/// Pool sizing, health probing and reconnect settings
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ConnectionManagerConfig {
+    /// Maximum number of concurrent requests against the backend
+    pub max_connections: u32,
+    /// Minimum number of idle connections the backend keeps open
+    pub min_connections: u32,
+    /// How long to wait for a free slot before failing
+    pub acquire_timeout: Duration,
+    /// Interval between background health probes
+    pub health_check_interval: Duration,
+    /// Consecutive failed probes before the connection is re-established
+    pub unhealthy_threshold: u32,
+    /// Backoff for reconnects and retried operations
+    pub backoff: BackoffConfig,
+}
+
+impl Default for ConnectionManagerConfig {
+    fn default() -> Self {
+        Self {
+            max_connections: 10,
+            min_connections: 1,
+            acquire_timeout: Duration::from_secs(30),
+            health_check_interval: Duration::from_secs(30),
+            unhealthy_threshold: 3,
+            backoff: BackoffConfig::default(),
+        }
+    }
+}
+
+impl ConnectionManagerConfig {
+    /// Set the pool size limits
+    pub fn with_pool_size(mut self, min_connections: u32, max_connections: u32) -> Self {
+        self.max_connections = max_connections.max(1);
+        self.min_connections = min_connections.min(self.max_connections);
+        self
+    }
+
+    /// Set the acquire timeout
+    pub fn with_acquire_timeout(mut self, timeout: Duration) -> Self {
+        self.acquire_timeout = timeout;
+        self
+    }
+
+    /// Set the health check interval
+    pub fn with_health_check_interval(mut self, interval: Duration) -> Self {
+        self.health_check_interval = interval;
+        self
+    }
+
+    /// Set the backoff configuration
+    pub fn with_backoff(mut self, backoff: BackoffConfig) -> Self {
+        self.backoff = backoff;
+        self
+    }
+}
+
+/// Backend-specific logic for opening and probing a connection
+///
+/// The connection is a cheaply clonable handle such as a database pool or an HTTP
+/// client; the manager hands out clones of it.
+#[async_trait]
+pub trait Connector: Send + Sync + 'static {
+    /// Connection handle
+    type Connection: Clone + Send + Sync + 'static;
+
+    /// Open a new connection sized according to the configuration
+    async fn connect(&self, config: &ConnectionManagerConfig) -> Result<Self::Connection>;
+
+    /// Check that the connection can reach the backend
+    async fn probe(&self, connection: &Self::Connection) -> Result<()>;
+}
+
+/// Connection pool metrics
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PoolMetrics {
+    /// Configured maximum number of concurrent requests
+    pub max_connections: u32,
+    /// Requests currently holding a slot
+    pub in_use: u32,
+    /// Whether the last health probe succeeded
+    pub healthy: bool,
+    /// Requests served
+    pub total_requests: u64,
+    /// Operations retried after a transient failure
+    pub retries: u64,
+    /// Times the connection was re-established
+    pub reconnects: u64,
+    /// Failed health probes
+    pub failed_probes: u64,
+}
+
+impl BackendInfo {
+    /// Add connection pool metrics to the backend metadata
+    pub fn with_pool_metrics(self, metrics: &PoolMetrics) -> Self {
+        self.with_metadata("pool.max_connections", metrics.max_connections as i64)
+            .with_metadata("pool.in_use", metrics.in_use as i64)
+            .with_metadata("pool.healthy", metrics.healthy)
+            .with_metadata("pool.total_requests", metrics.total_requests as i64)
+            .with_metadata("pool.retries", metrics.retries as i64)
+            .with_metadata("pool.reconnects", metrics.reconnects as i64)
+            .with_metadata("pool.failed_probes", metrics.failed_probes as i64)
+    }
+}
+
+/// Shared connection manager for remote backends
+pub struct ConnectionManager<C: Connector> {
+    connector: C,
+    config: ConnectionManagerConfig,
+    connection: RwLock<C::Connection>,
+    slots: Semaphore,
+    healthy: AtomicBool,
+    consecutive_failures: AtomicU64,
+    total_requests: AtomicU64,
+    retries: AtomicU64,
+    reconnects: AtomicU64,
+    failed_probes: AtomicU64,
+}
+
+impl<C: Connector> ConnectionManager<C> {
+    /// Connect to the backend, retrying with backoff
+    pub async fn new(connector: C, config: ConnectionManagerConfig) -> Result<Self> {
+        let connection = Self::connect_with_backoff(&connector, &config).await?;
+        Ok(Self {
+            slots: Semaphore::new(config.max_connections.max(1) as usize),
+            connector,
+            config,
+            connection: RwLock::new(connection),
+            healthy: AtomicBool::new(true),
+            consecutive_failures: AtomicU64::new(0),
+            total_requests: AtomicU64::new(0),
+            retries: AtomicU64::new(0),
+            reconnects: AtomicU64::new(0),
+            failed_probes: AtomicU64::new(0),
+        })
+    }
+
+    async fn connect_with_backoff(connector: &C, config: &ConnectionManagerConfig) -> Result<C::Connection> {
+        let mut attempt = 0;
+        loop {
+            match connector.connect(config).await {
+                Ok(connection) => return Ok(connection),
+                Err(e) if e.is_retryable() && attempt < config.backoff.max_retries => {
+                    tokio::time::sleep(config.backoff.delay(attempt)).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Backend connector
+    pub fn connector(&self) -> &C {
+        &self.connector
+    }
+
+    /// Manager configuration
+    pub fn config(&self) -> &ConnectionManagerConfig {
+        &self.config
+    }
+
+    /// Current connection handle
+    pub async fn connection(&self) -> C::Connection {
+        self.connection.read().await.clone()
+    }
+
+    /// Replace the connection with a freshly opened one
+    pub async fn reconnect(&self) -> Result<()> {
+        let connection = Self::connect_with_backoff(&self.connector, &self.config).await?;
+        *self.connection.write().await = connection;
+        self.reconnects.fetch_add(1, Ordering::Relaxed);
+        self.consecutive_failures.store(0, Ordering::Relaxed);
+        self.healthy.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Probe the backend, reconnecting after repeated failures
+    pub async fn health_check(&self) -> Result<()> {
+        let connection = self.connection().await;
+        match self.connector.probe(&connection).await {
+            Ok(()) => {
+                self.consecutive_failures.store(0, Ordering::Relaxed);
+                self.healthy.store(true, Ordering::Relaxed);
+                Ok(())
+            }
+            Err(e) => {
+                self.failed_probes.fetch_add(1, Ordering::Relaxed);
+                self.healthy.store(false, Ordering::Relaxed);
+                let failures = self.consecutive_failures.fetch_add(1, Ordering::Relaxed) + 1;
+                if failures >= self.config.unhealthy_threshold.max(1) as u64 {
+                    // A failed reconnect keeps the old handle; the next probe tries again
+                    let _ = self.reconnect().await;
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Probe the backend at the configured interval until the manager is dropped
+    ///
+    /// Spawn the returned future on the runtime of your choice.
+    pub fn health_monitor(self: &Arc<Self>) -> impl Future<Output = ()> + Send + 'static {
+        let manager = Arc::downgrade(self);
+        let interval = self.config.health_check_interval;
+        async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                match manager.upgrade() {
+                    Some(manager) => {
+                        let _ = manager.health_check().await;
+                    }
+                    None => break,
+                }
+            }
+        }
+    }
+
+    /// Run an operation with a pool slot, retrying transient failures with backoff
+    ///
+    /// Connection failures mark the backend unhealthy and re-establish the connection
+    /// before the next attempt. Only use this for idempotent operations.
+    pub async fn execute<T, F, Fut>(&self, operation: F) -> Result<T>
+    where
+        F: Fn(C::Connection) -> Fut + Send + Sync,
+        Fut: Future<Output = Result<T>> + Send,
+        T: Send,
+    {
+        let _slot = tokio::time::timeout(self.config.acquire_timeout, self.slots.acquire())
+            .await
+            .map_err(|_| VectorError::ConnectionFailed("Timed out waiting for a pooled connection".to_string()))?
+            .map_err(|e| VectorError::ConnectionFailed(format!("Connection pool closed: {}", e)))?;
+        self.total_requests.fetch_add(1, Ordering::Relaxed);
+
+        let mut attempt = 0;
+        loop {
+            match operation(self.connection().await).await {
+                Ok(value) => return Ok(value),
+                Err(e) if e.is_retryable() && attempt < self.config.backoff.max_retries => {
+                    self.retries.fetch_add(1, Ordering::Relaxed);
+                    if matches!(e, VectorError::ConnectionFailed(_)) {
+                        self.healthy.store(false, Ordering::Relaxed);
+                        let _ = self.reconnect().await;
+                    }
+                    tokio::time::sleep(self.config.backoff.delay(attempt)).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Current pool metrics
+    pub fn metrics(&self) -> PoolMetrics {
+        let max_connections = self.config.max_connections.max(1);
+        PoolMetrics {
+            max_connections,
+            in_use: max_connections.saturating_sub(self.slots.available_permits() as u32),
+            healthy: self.healthy.load(Ordering::Relaxed),
+            total_requests: self.total_requests.load(Ordering::Relaxed),
+            retries: self.retries.load(Ordering::Relaxed),
+            reconnects: self.reconnects.load(Ordering::Relaxed),
+            failed_probes: self.failed_probes.load(Ordering::Relaxed),
+        }
+    }
+}
@@ -18,6 +18,13 @@ pub enum VectorError {
     #[error("Invalid index configuration: {0}")]
     InvalidIndexConfig(String),
     
+    /// Alias-related errors
+    #[error("Alias '{0}' not found")]
+    AliasNotFound(String),
+    
+    #[error("Alias '{0}' already exists")]
+    AliasAlreadyExists(String),
+    
     /// Vector-related errors
     #[error("Vector '{0}' not found")]
     VectorNotFound(String),
@@ -107,6 +114,16 @@ impl VectorError {
         Self::IndexAlreadyExists(name.into())
     }
     
+    /// Create an alias not found error
+    pub fn alias_not_found(alias: impl Into<String>) -> Self {
+        Self::AliasNotFound(alias.into())
+    }
+    
+    /// Create an alias already exists error
+    pub fn alias_already_exists(alias: impl Into<String>) -> Self {
+        Self::AliasAlreadyExists(alias.into())
+    }
+    
     /// Create a dimension mismatch error
     pub fn dimension_mismatch(expected: usize, actual: usize) -> Self {
         Self::DimensionMismatch { expected, actual }
@@ -163,6 +180,7 @@ impl VectorError {
         matches!(
             self,
             VectorError::IndexNotFound(_)
+                | VectorError::AliasNotFound(_)
                 | VectorError::VectorNotFound(_)
                 | VectorError::DimensionMismatch { .. }
                 | VectorError::InvalidQuery(_)
@@ -15,7 +15,8 @@
 //!
 //! - **VectorStorage**: The main trait for vector storage backends
 //! - **EmbeddingModel**: Trait for embedding generation
-//! - **Document**: Unified document representation with embedding support
+//! - **Document**: Unified document representation with embedding support,
+//!   including named vector fields for multi-vector models
 //! - **SearchRequest/Response**: Structured query interface
 //!
 //! ## Example
@@ -37,11 +38,21 @@
 #![allow(non_camel_case_types, ambiguous_glob_reexports, hidden_glob_reexports)]
 #![allow(unexpected_cfgs, unused_assignments)]
 
+pub mod aggregation;
 pub mod error;
 pub mod types;
 pub mod traits;
 pub mod config;
 pub mod performance;
+pub mod connection;
+pub mod multi_vector;
+pub mod quantization;
+pub mod replication;
+pub mod scoring;
+pub mod sparse;
+pub mod truncation;
+#[cfg(feature = "serde")]
+pub mod wal;
 
 #[cfg(test)]
 mod tests;
@@ -52,6 +63,14 @@ pub use types::*;
 pub use traits::*;
 pub use config::*;
 pub use performance::*;
+pub use connection::*;
+pub use multi_vector::*;
+pub use quantization::*;
+pub use replication::*;
+pub use scoring::*;
+pub use sparse::*;
+pub use truncation::*;
+pub use aggregation::*;
 
 /// Prelude module for convenient imports
 pub mod prelude {
@@ -60,4 +79,12 @@ pub mod prelude {
     pub use crate::traits::*;
     pub use crate::config::*;
     pub use crate::performance::*;
+    pub use crate::connection::*;
+    pub use crate::multi_vector::*;
+    pub use crate::quantization::*;
+    pub use crate::replication::*;
+    pub use crate::scoring::*;
+    pub use crate::sparse::*;
+    pub use crate::truncation::*;
+    pub use crate::aggregation::*;
 }
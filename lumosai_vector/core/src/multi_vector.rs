@@ -0,0 +1,126 @@
+//! Named vectors and multi-vector search
+//!
+//! A document can carry several embeddings besides its main one, each stored under a
+//! field name: separate title and body embeddings, or ColBERT-style late-interaction
+//! models that keep one vector per token. Multi-vector queries target one or more of
+//! these fields and combine the per-field scores with weights.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Result, VectorError};
+use crate::traits::SimilarityCalculator;
+use crate::types::Vector;
+
+/// Vector stored under a named field
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum NamedVector {
+    /// A single dense vector, e.g. a title embedding
+    Dense(Vector),
+    /// Several vectors of equal dimension, e.g. ColBERT token embeddings
+    Multi(Vec<Vector>),
+}
+
+impl NamedVector {
+    /// The vectors of this field, one for a dense field
+    pub fn vectors(&self) -> &[Vector] {
+        match self {
+            Self::Dense(vector) => std::slice::from_ref(vector),
+            Self::Multi(vectors) => vectors,
+        }
+    }
+
+    /// Dimension of the vectors, or `None` for an empty multi-vector
+    pub fn dimension(&self) -> Option<usize> {
+        self.vectors().first().map(Vec::len)
+    }
+
+    /// Check that the field holds at least one vector and all vectors share a dimension
+    pub fn validate(&self) -> Result<()> {
+        let Some(dimension) = self.dimension() else {
+            return Err(VectorError::InvalidVector("Multi-vector has no vectors".to_string()));
+        };
+        match self.vectors().iter().find(|vector| vector.len() != dimension) {
+            Some(vector) => Err(VectorError::dimension_mismatch(dimension, vector.len())),
+            None => Ok(()),
+        }
+    }
+
+    /// Late-interaction (MaxSim) score against a stored field
+    ///
+    /// Each query vector is matched with its most similar stored vector and the best
+    /// similarities are summed, so two dense vectors score their plain similarity.
+    pub fn max_sim(&self, stored: &NamedVector, calculator: &dyn SimilarityCalculator) -> Result<f32> {
+        let mut score = 0.0;
+        for query in self.vectors() {
+            let mut best = f32::NEG_INFINITY;
+            for vector in stored.vectors() {
+                best = best.max(calculator.calculate_similarity(query, vector)?);
+            }
+            if best.is_finite() {
+                score += best;
+            }
+        }
+        Ok(score)
+    }
+}
+
+impl From<Vector> for NamedVector {
+    fn from(vector: Vector) -> Self {
+        Self::Dense(vector)
+    }
+}
+
+impl From<Vec<Vector>> for NamedVector {
+    fn from(vectors: Vec<Vector>) -> Self {
+        Self::Multi(vectors)
+    }
+}
+
+/// Query against one named vector field of a multi-vector search
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct VectorFieldQuery {
+    /// Name of the vector field to search
+    pub field: String,
+    /// Query vector or vectors
+    pub vector: NamedVector,
+    /// Weight of this field's score in the combined score
+    pub weight: f32,
+}
+
+impl VectorFieldQuery {
+    /// Query a field with weight 1
+    pub fn new(field: impl Into<String>, vector: impl Into<NamedVector>) -> Self {
+        Self {
+            field: field.into(),
+            vector: vector.into(),
+            weight: 1.0,
+        }
+    }
+
+    /// Set the weight of this field's score
+    pub fn with_weight(mut self, weight: f32) -> Self {
+        self.weight = weight;
+        self
+    }
+}
+
+/// Weighted sum of the field scores of a document
+///
+/// Returns `None` if the document has none of the queried fields; missing fields
+/// contribute nothing.
+pub fn combine_field_scores<'a>(
+    queries: &[VectorFieldQuery],
+    mut stored: impl FnMut(&str) -> Option<&'a NamedVector>,
+    calculator: &dyn SimilarityCalculator,
+) -> Result<Option<f32>> {
+    let mut score = None;
+    for query in queries {
+        if let Some(vector) = stored(&query.field) {
+            *score.get_or_insert(0.0) += query.weight * query.vector.max_sim(vector, calculator)?;
+        }
+    }
+    Ok(score)
+}
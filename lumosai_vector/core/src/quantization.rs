@@ -0,0 +1,223 @@
+//! Embedding quantization
+//!
+//! Large corpora spend most of their memory on `f32` embeddings. Quantized vectors trade
+//! a little accuracy for a much smaller footprint:
+//! - Int8 scalar quantization stores one byte per dimension (4x smaller)
+//! - Binary quantization stores one bit per dimension (32x smaller)
+//!
+//! Binary vectors are compared by Hamming distance in a cheap first pass; the best
+//! candidates are then rescored against the full-precision query.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Result, VectorError};
+use crate::traits::SimilarityCalculator;
+use crate::types::Vector;
+
+/// Default factor by which binary search over-fetches candidates before rescoring
+pub const DEFAULT_RESCORE_MULTIPLIER: usize = 4;
+
+/// Quantization applied to stored embeddings
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum QuantizationType {
+    /// Keep full-precision `f32` embeddings
+    #[default]
+    None,
+    /// Scalar quantization to one signed byte per dimension
+    Int8,
+    /// One bit per dimension, set when the component is positive
+    Binary,
+}
+
+/// Quantization settings for a vector store
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct QuantizationConfig {
+    /// Quantization type
+    pub quantization: QuantizationType,
+    /// How many times `top_k` binary candidates are rescored with the full query
+    pub rescore_multiplier: usize,
+}
+
+impl Default for QuantizationConfig {
+    fn default() -> Self {
+        Self {
+            quantization: QuantizationType::None,
+            rescore_multiplier: DEFAULT_RESCORE_MULTIPLIER,
+        }
+    }
+}
+
+impl QuantizationConfig {
+    /// Quantize embeddings with the given type
+    pub fn new(quantization: QuantizationType) -> Self {
+        Self {
+            quantization,
+            ..Default::default()
+        }
+    }
+
+    /// Set the rescore multiplier
+    pub fn with_rescore_multiplier(mut self, multiplier: usize) -> Self {
+        self.rescore_multiplier = multiplier.max(1);
+        self
+    }
+
+    /// Whether embeddings are quantized
+    pub fn is_enabled(&self) -> bool {
+        self.quantization != QuantizationType::None
+    }
+}
+
+/// A quantized embedding
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum QuantizedVector {
+    /// Scalar-quantized components; `value = (q + 128) * scale + min`
+    Int8 {
+        values: Vec<i8>,
+        min: f32,
+        scale: f32,
+    },
+    /// Sign bits packed into 64-bit words
+    Binary {
+        bits: Vec<u64>,
+        dimension: usize,
+    },
+}
+
+impl QuantizedVector {
+    /// Quantize a vector; returns `None` for [`QuantizationType::None`]
+    pub fn quantize(vector: &[f32], quantization: QuantizationType) -> Option<Self> {
+        match quantization {
+            QuantizationType::None => None,
+            QuantizationType::Int8 => Some(Self::int8(vector)),
+            QuantizationType::Binary => Some(Self::binary(vector)),
+        }
+    }
+
+    /// Scalar-quantize a vector to int8 using its own value range
+    pub fn int8(vector: &[f32]) -> Self {
+        let min = vector.iter().copied().fold(f32::INFINITY, f32::min);
+        let max = vector.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        let (min, scale) = if vector.is_empty() {
+            (0.0, 1.0)
+        } else if max <= min {
+            (min, 1.0)
+        } else {
+            (min, (max - min) / 255.0)
+        };
+        let values = vector
+            .iter()
+            .map(|x| (((x - min) / scale).round().clamp(0.0, 255.0) as i32 - 128) as i8)
+            .collect();
+        Self::Int8 { values, min, scale }
+    }
+
+    /// Binary-quantize a vector by the sign of each component
+    pub fn binary(vector: &[f32]) -> Self {
+        let mut bits = vec![0u64; vector.len().div_ceil(64)];
+        for (i, x) in vector.iter().enumerate() {
+            if *x > 0.0 {
+                bits[i / 64] |= 1 << (i % 64);
+            }
+        }
+        Self::Binary { bits, dimension: vector.len() }
+    }
+
+    /// Quantization type of this vector
+    pub fn quantization(&self) -> QuantizationType {
+        match self {
+            Self::Int8 { .. } => QuantizationType::Int8,
+            Self::Binary { .. } => QuantizationType::Binary,
+        }
+    }
+
+    /// Number of dimensions
+    pub fn dimension(&self) -> usize {
+        match self {
+            Self::Int8 { values, .. } => values.len(),
+            Self::Binary { dimension, .. } => *dimension,
+        }
+    }
+
+    /// Approximate heap and inline size in bytes
+    pub fn memory_bytes(&self) -> usize {
+        match self {
+            Self::Int8 { values, .. } => values.len() + 2 * std::mem::size_of::<f32>(),
+            Self::Binary { bits, .. } => bits.len() * std::mem::size_of::<u64>() + std::mem::size_of::<usize>(),
+        }
+    }
+
+    /// Reconstruct an approximate `f32` vector
+    ///
+    /// Binary vectors reconstruct to `+1.0` / `-1.0` per component.
+    pub fn dequantize(&self) -> Vector {
+        match self {
+            Self::Int8 { values, min, scale } => values.iter().map(|q| (*q as i32 + 128) as f32 * scale + min).collect(),
+            Self::Binary { bits, dimension } => (0..*dimension)
+                .map(|i| if bits[i / 64] & (1 << (i % 64)) != 0 { 1.0 } else { -1.0 })
+                .collect(),
+        }
+    }
+
+    /// Hamming distance between two binary vectors of the same dimension
+    pub fn hamming_distance(&self, other: &Self) -> Result<u32> {
+        match (self, other) {
+            (Self::Binary { bits: a, dimension: da }, Self::Binary { bits: b, dimension: db }) => {
+                if da != db {
+                    return Err(VectorError::dimension_mismatch(*da, *db));
+                }
+                Ok(a.iter().zip(b).map(|(x, y)| (x ^ y).count_ones()).sum())
+            }
+            _ => Err(VectorError::InvalidVector("Hamming distance needs binary vectors".to_string())),
+        }
+    }
+
+    /// Similarity to a full-precision query, used for scoring and rescoring
+    pub fn similarity(&self, query: &[f32], calculator: &dyn SimilarityCalculator) -> Result<f32> {
+        calculator.calculate_similarity(query, &self.dequantize())
+    }
+}
+
+/// Similarity in `[0, 1]` of two binary vectors from their Hamming distance
+pub fn hamming_similarity(a: &QuantizedVector, b: &QuantizedVector) -> Result<f32> {
+    let dimension = a.dimension();
+    if dimension == 0 {
+        return Ok(0.0);
+    }
+    Ok(1.0 - a.hamming_distance(b)? as f32 / dimension as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::similarity::CosineSimilarity;
+
+    #[test]
+    fn test_int8_round_trip_is_close() {
+        let vector = vec![-0.8, -0.1, 0.0, 0.25, 0.9];
+        let quantized = QuantizedVector::int8(&vector);
+        assert_eq!(quantized.dimension(), 5);
+        for (original, restored) in vector.iter().zip(quantized.dequantize()) {
+            assert!((original - restored).abs() < 0.01);
+        }
+        assert!(quantized.similarity(&vector, &CosineSimilarity).unwrap() > 0.999);
+    }
+
+    #[test]
+    fn test_binary_packs_signs_and_shrinks_memory() {
+        let vector: Vec<f32> = (0..384).map(|i| if i % 3 == 0 { 0.5 } else { -0.5 }).collect();
+        let quantized = QuantizedVector::binary(&vector);
+        assert_eq!(quantized.dimension(), 384);
+        assert!(vector.len() * 4 / quantized.memory_bytes() >= 24);
+
+        let flipped: Vec<f32> = vector.iter().map(|x| -x).collect();
+        let opposite = QuantizedVector::binary(&flipped);
+        assert_eq!(quantized.hamming_distance(&opposite).unwrap(), 384);
+        assert_eq!(hamming_similarity(&quantized, &quantized).unwrap(), 1.0);
+        assert!(quantized.hamming_distance(&QuantizedVector::int8(&vector)).is_err());
+    }
+}
@@ -0,0 +1,313 @@
+//! Read replicas and write/read splitting
+//!
+//! [`ReplicatedStorage`] wraps a primary backend and any number of read replicas of the
+//! same type. Writes and schema operations go to the primary; searches and document
+//! reads are spread over the replicas. Staleness is bounded two ways: replicas lagging
+//! more than [`ReplicaConfig::max_lag`] are taken out of rotation, and reads of an index
+//! written within [`ReplicaConfig::read_your_writes`] are served by the primary.
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::aggregation::{AggregateRequest, AggregateResponse};
+use crate::error::{Result, VectorError};
+use crate::traits::{BackendInfo, VectorStorage};
+use crate::types::*;
+
+/// Staleness tolerance and routing settings for read replicas
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ReplicaConfig {
+    /// Maximum replication lag before a replica stops serving reads; `None` accepts any lag
+    pub max_lag: Option<Duration>,
+    /// Window after a write during which reads of the same index go to the primary
+    pub read_your_writes: Duration,
+    /// How often replica health and lag are re-checked
+    pub check_interval: Duration,
+    /// Serve reads from the primary when no replica is available
+    pub fallback_to_primary: bool,
+}
+
+impl Default for ReplicaConfig {
+    fn default() -> Self {
+        Self {
+            max_lag: Some(Duration::from_secs(5)),
+            read_your_writes: Duration::from_secs(2),
+            check_interval: Duration::from_secs(10),
+            fallback_to_primary: true,
+        }
+    }
+}
+
+impl ReplicaConfig {
+    /// Set the maximum tolerated replication lag
+    pub fn with_max_lag(mut self, max_lag: Duration) -> Self {
+        self.max_lag = Some(max_lag);
+        self
+    }
+
+    /// Accept replicas regardless of their lag
+    pub fn without_max_lag(mut self) -> Self {
+        self.max_lag = None;
+        self
+    }
+
+    /// Set the read-your-writes window
+    pub fn with_read_your_writes(mut self, window: Duration) -> Self {
+        self.read_your_writes = window;
+        self
+    }
+
+    /// Set the replica check interval
+    pub fn with_check_interval(mut self, interval: Duration) -> Self {
+        self.check_interval = interval;
+        self
+    }
+
+    /// Set whether reads fall back to the primary
+    pub fn with_fallback_to_primary(mut self, fallback: bool) -> Self {
+        self.fallback_to_primary = fallback;
+        self
+    }
+}
+
+/// Primary backend with read replicas
+pub struct ReplicatedStorage<S: VectorStorage> {
+    primary: S,
+    replicas: Vec<S>,
+    available: Vec<AtomicBool>,
+    config: ReplicaConfig,
+    next_replica: AtomicUsize,
+    last_check: Mutex<Option<Instant>>,
+    last_writes: Mutex<HashMap<String, Instant>>,
+}
+
+impl<S: VectorStorage> ReplicatedStorage<S> {
+    /// Route reads over `replicas` and writes to `primary`
+    pub fn new(primary: S, replicas: Vec<S>, config: ReplicaConfig) -> Self {
+        Self {
+            available: replicas.iter().map(|_| AtomicBool::new(true)).collect(),
+            primary,
+            replicas,
+            config,
+            next_replica: AtomicUsize::new(0),
+            last_check: Mutex::new(None),
+            last_writes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Primary backend
+    pub fn primary(&self) -> &S {
+        &self.primary
+    }
+
+    /// Read replicas
+    pub fn replicas(&self) -> &[S] {
+        &self.replicas
+    }
+
+    /// Replica configuration
+    pub fn config(&self) -> &ReplicaConfig {
+        &self.config
+    }
+
+    /// Number of replicas currently serving reads
+    pub fn available_replicas(&self) -> usize {
+        self.available.iter().filter(|a| a.load(Ordering::Relaxed)).count()
+    }
+
+    /// Re-check health and lag of every replica
+    pub async fn refresh_replicas(&self) {
+        for (replica, available) in self.replicas.iter().zip(&self.available) {
+            let usable = match replica.health_check().await {
+                Ok(()) => match (self.config.max_lag, replica.replication_lag().await) {
+                    (None, _) | (Some(_), Ok(None)) => true,
+                    (Some(max_lag), Ok(Some(lag))) => lag <= max_lag,
+                    (Some(_), Err(_)) => false,
+                },
+                Err(_) => false,
+            };
+            available.store(usable, Ordering::Relaxed);
+        }
+        *self.last_check.lock().unwrap() = Some(Instant::now());
+    }
+
+    async fn refresh_if_due(&self) {
+        let due = self
+            .last_check
+            .lock()
+            .unwrap()
+            .is_none_or(|checked| checked.elapsed() >= self.config.check_interval);
+        if due {
+            self.refresh_replicas().await;
+        }
+    }
+
+    fn record_write(&self, index_name: &str) {
+        self.last_writes.lock().unwrap().insert(index_name.to_string(), Instant::now());
+    }
+
+    fn recently_written(&self, index_name: &str) -> bool {
+        let mut last_writes = self.last_writes.lock().unwrap();
+        let window = self.config.read_your_writes;
+        last_writes.retain(|_, written| written.elapsed() < window);
+        last_writes.contains_key(index_name)
+    }
+
+    /// Pick the replica for the next read of `index_name`, or `None` for the primary
+    async fn read_target(&self, index_name: &str) -> Result<Option<&S>> {
+        if self.replicas.is_empty() || self.recently_written(index_name) {
+            return Ok(None);
+        }
+        self.refresh_if_due().await;
+
+        let start = self.next_replica.fetch_add(1, Ordering::Relaxed);
+        let replica = (0..self.replicas.len())
+            .map(|offset| (start + offset) % self.replicas.len())
+            .find(|&i| self.available[i].load(Ordering::Relaxed));
+        match replica {
+            Some(i) => Ok(Some(&self.replicas[i])),
+            None if self.config.fallback_to_primary => Ok(None),
+            None => Err(VectorError::ConnectionFailed("No read replica within the staleness limit".to_string())),
+        }
+    }
+
+    fn mark_unavailable(&self, replica: &S) {
+        if let Some(i) = self.replicas.iter().position(|r| std::ptr::eq(r, replica)) {
+            self.available[i].store(false, Ordering::Relaxed);
+        }
+    }
+}
+
+#[async_trait]
+impl<S: VectorStorage> VectorStorage for ReplicatedStorage<S> {
+    type Config = S::Config;
+
+    async fn create_index(&self, config: IndexConfig) -> Result<()> {
+        let name = config.name.clone();
+        self.primary.create_index(config).await?;
+        self.record_write(&name);
+        Ok(())
+    }
+
+    async fn list_indexes(&self) -> Result<Vec<String>> {
+        self.primary.list_indexes().await
+    }
+
+    async fn describe_index(&self, index_name: &str) -> Result<IndexInfo> {
+        self.primary.describe_index(index_name).await
+    }
+
+    async fn delete_index(&self, index_name: &str) -> Result<()> {
+        self.primary.delete_index(index_name).await?;
+        self.record_write(index_name);
+        Ok(())
+    }
+
+    async fn upsert_documents(&self, index_name: &str, documents: Vec<Document>) -> Result<Vec<DocumentId>> {
+        let ids = self.primary.upsert_documents(index_name, documents).await?;
+        self.record_write(index_name);
+        Ok(ids)
+    }
+
+    async fn search(&self, request: SearchRequest) -> Result<SearchResponse> {
+        match self.read_target(&request.index_name).await? {
+            Some(replica) => match replica.search(request.clone()).await {
+                Err(e) if e.is_retryable() && self.config.fallback_to_primary => {
+                    self.mark_unavailable(replica);
+                    self.primary.search(request).await
+                }
+                result => result,
+            },
+            None => self.primary.search(request).await,
+        }
+    }
+
+    async fn update_document(&self, index_name: &str, document: Document) -> Result<()> {
+        self.primary.update_document(index_name, document).await?;
+        self.record_write(index_name);
+        Ok(())
+    }
+
+    async fn delete_documents(&self, index_name: &str, ids: Vec<DocumentId>) -> Result<()> {
+        self.primary.delete_documents(index_name, ids).await?;
+        self.record_write(index_name);
+        Ok(())
+    }
+
+    async fn get_documents(&self, index_name: &str, ids: Vec<DocumentId>, include_vectors: bool) -> Result<Vec<Document>> {
+        match self.read_target(index_name).await? {
+            Some(replica) => match replica.get_documents(index_name, ids.clone(), include_vectors).await {
+                Err(e) if e.is_retryable() && self.config.fallback_to_primary => {
+                    self.mark_unavailable(replica);
+                    self.primary.get_documents(index_name, ids, include_vectors).await
+                }
+                result => result,
+            },
+            None => self.primary.get_documents(index_name, ids, include_vectors).await,
+        }
+    }
+
+    async fn list_documents(
+        &self,
+        index_name: &str,
+        cursor: Option<String>,
+        limit: usize,
+        filter: Option<FilterCondition>,
+    ) -> Result<DocumentPage> {
+        self.primary.list_documents(index_name, cursor, limit, filter).await
+    }
+
+    async fn aggregate(&self, request: AggregateRequest) -> Result<AggregateResponse> {
+        match self.read_target(&request.index_name).await? {
+            Some(replica) => match replica.aggregate(request.clone()).await {
+                Err(e) if e.is_retryable() && self.config.fallback_to_primary => {
+                    self.mark_unavailable(replica);
+                    self.primary.aggregate(request).await
+                }
+                result => result,
+            },
+            None => self.primary.aggregate(request).await,
+        }
+    }
+
+    async fn create_alias(&self, alias: &str, index_name: &str) -> Result<()> {
+        self.primary.create_alias(alias, index_name).await?;
+        self.record_write(alias);
+        Ok(())
+    }
+
+    async fn swap_alias(&self, alias: &str, index_name: &str) -> Result<()> {
+        self.primary.swap_alias(alias, index_name).await?;
+        self.record_write(alias);
+        Ok(())
+    }
+
+    async fn delete_alias(&self, alias: &str) -> Result<()> {
+        self.primary.delete_alias(alias).await?;
+        self.record_write(alias);
+        Ok(())
+    }
+
+    async fn list_aliases(&self) -> Result<HashMap<String, String>> {
+        self.primary.list_aliases().await
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        self.primary.health_check().await
+    }
+
+    fn backend_info(&self) -> BackendInfo {
+        self.primary
+            .backend_info()
+            .with_feature("read_replicas")
+            .with_metadata("replicas.total", self.replicas.len() as i64)
+            .with_metadata("replicas.available", self.available_replicas() as i64)
+    }
+}
@@ -0,0 +1,205 @@
+//! Query-time scoring modifiers
+//!
+//! Similarity alone ranks a stale document the same as a fresh one. Scoring modifiers
+//! adjust the similarity score after retrieval using document metadata: field boosts
+//! favor documents with a matching value or a higher numeric priority, and recency
+//! decay discounts documents by the age of a timestamp field.
+
+use chrono::{DateTime, Utc};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::types::{Metadata, MetadataValue, SearchResult};
+
+/// Default factor by which backends over-fetch candidates before rescoring
+pub const DEFAULT_CANDIDATE_MULTIPLIER: usize = 4;
+
+/// Boost applied to documents based on a metadata field
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum FieldBoost {
+    /// Multiply the score by `factor` when the field equals `value`
+    Match {
+        field: String,
+        value: MetadataValue,
+        factor: f32,
+    },
+    /// Add `weight * value` to the score for a numeric field, e.g. a priority
+    Numeric {
+        field: String,
+        weight: f32,
+    },
+}
+
+impl FieldBoost {
+    /// Boost documents whose field equals the given value
+    pub fn matching(field: impl Into<String>, value: impl Into<MetadataValue>, factor: f32) -> Self {
+        Self::Match {
+            field: field.into(),
+            value: value.into(),
+            factor,
+        }
+    }
+
+    /// Boost documents in proportion to a numeric field
+    pub fn numeric(field: impl Into<String>, weight: f32) -> Self {
+        Self::Numeric {
+            field: field.into(),
+            weight,
+        }
+    }
+
+    fn apply(&self, score: f32, metadata: &Metadata) -> f32 {
+        match self {
+            Self::Match { field, value, factor } => {
+                if metadata.get(field) == Some(value) {
+                    score * factor
+                } else {
+                    score
+                }
+            }
+            Self::Numeric { field, weight } => {
+                match metadata.get(field) {
+                    Some(MetadataValue::Integer(v)) => score + weight * *v as f32,
+                    Some(MetadataValue::Float(v)) => score + weight * *v as f32,
+                    _ => score,
+                }
+            }
+        }
+    }
+}
+
+/// Exponential decay of the score by document age
+///
+/// The score is multiplied by `(1 - weight) + weight * 0.5^(age / half_life)`, so a
+/// document one half-life old with `weight = 1.0` keeps half its score. Documents
+/// without a readable timestamp are treated as fully decayed.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RecencyDecay {
+    /// Metadata field holding the timestamp (unix seconds or RFC 3339)
+    pub field: String,
+    /// Age at which the decayed part of the score halves, in seconds
+    pub half_life_secs: f64,
+    /// Share of the score subject to decay, between 0 and 1
+    pub weight: f32,
+}
+
+impl RecencyDecay {
+    /// Decay the whole score with the given half-life
+    pub fn new(field: impl Into<String>, half_life: std::time::Duration) -> Self {
+        Self {
+            field: field.into(),
+            half_life_secs: half_life.as_secs_f64(),
+            weight: 1.0,
+        }
+    }
+
+    /// Set the share of the score subject to decay
+    pub fn with_weight(mut self, weight: f32) -> Self {
+        self.weight = weight.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Multiplier for a document with the given metadata
+    pub fn factor(&self, metadata: &Metadata, now: DateTime<Utc>) -> f32 {
+        let decay = match metadata.get(&self.field).and_then(parse_timestamp) {
+            Some(timestamp) if self.half_life_secs > 0.0 => {
+                let age = (now - timestamp).num_milliseconds().max(0) as f64 / 1000.0;
+                0.5f64.powf(age / self.half_life_secs) as f32
+            }
+            Some(_) => 1.0,
+            None => 0.0,
+        };
+        (1.0 - self.weight) + self.weight * decay
+    }
+}
+
+fn parse_timestamp(value: &MetadataValue) -> Option<DateTime<Utc>> {
+    match value {
+        MetadataValue::Integer(secs) => DateTime::from_timestamp(*secs, 0),
+        MetadataValue::Float(secs) => DateTime::from_timestamp_millis((secs * 1000.0) as i64),
+        MetadataValue::String(s) => DateTime::parse_from_rfc3339(s).ok().map(|dt| dt.with_timezone(&Utc)),
+        _ => None,
+    }
+}
+
+/// Scoring modifiers applied to search results after retrieval
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ScoringModifiers {
+    /// Field boosts, applied in order
+    pub boosts: Vec<FieldBoost>,
+    /// Optional recency decay, applied after the boosts
+    pub recency: Option<RecencyDecay>,
+    /// How many times `top_k` candidates backends fetch before rescoring
+    pub candidate_multiplier: usize,
+}
+
+impl Default for ScoringModifiers {
+    fn default() -> Self {
+        Self {
+            boosts: Vec::new(),
+            recency: None,
+            candidate_multiplier: DEFAULT_CANDIDATE_MULTIPLIER,
+        }
+    }
+}
+
+impl ScoringModifiers {
+    /// Create empty scoring modifiers
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a field boost
+    pub fn with_boost(mut self, boost: FieldBoost) -> Self {
+        self.boosts.push(boost);
+        self
+    }
+
+    /// Set the recency decay
+    pub fn with_recency(mut self, recency: RecencyDecay) -> Self {
+        self.recency = Some(recency);
+        self
+    }
+
+    /// Set the candidate over-fetch multiplier
+    pub fn with_candidate_multiplier(mut self, multiplier: usize) -> Self {
+        self.candidate_multiplier = multiplier.max(1);
+        self
+    }
+
+    /// Whether the modifiers change any score
+    pub fn is_empty(&self) -> bool {
+        self.boosts.is_empty() && self.recency.is_none()
+    }
+
+    /// Number of candidates to retrieve so rescoring can promote documents below `top_k`
+    pub fn candidate_limit(&self, top_k: usize) -> usize {
+        top_k.saturating_mul(self.candidate_multiplier.max(1))
+    }
+
+    /// Modified score for a result with the given metadata
+    pub fn score(&self, score: f32, metadata: &Metadata, now: DateTime<Utc>) -> f32 {
+        let boosted = self.boosts.iter().fold(score, |score, boost| boost.apply(score, metadata));
+        match &self.recency {
+            Some(recency) => boosted * recency.factor(metadata, now),
+            None => boosted,
+        }
+    }
+
+    /// Rescore results, sort them by the new score and keep the best `top_k`
+    ///
+    /// Results must carry metadata; those without it keep their similarity score.
+    pub fn apply(&self, results: &mut Vec<SearchResult>, top_k: usize, now: DateTime<Utc>) {
+        let empty = Metadata::new();
+        for result in results.iter_mut() {
+            let metadata = result.metadata.as_ref().unwrap_or(&empty);
+            result.score = self.score(result.score, metadata, now);
+        }
+        results.sort_by(|a, b| b.score.total_cmp(&a.score));
+        results.truncate(top_k);
+    }
+}
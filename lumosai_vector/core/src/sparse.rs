@@ -0,0 +1,134 @@
+//! Sparse vectors and hybrid search fusion
+//!
+//! Learned sparse models such as SPLADE map text to a few hundred weighted vocabulary
+//! terms. Sparse vectors match exact terms and rare identifiers that dense embeddings
+//! blur, so hybrid search runs a dense and a sparse query and fuses the two rankings.
+
+use std::collections::HashMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Result, VectorError};
+use crate::types::{DocumentId, SearchResult};
+
+/// Default `k` constant of reciprocal rank fusion
+pub const DEFAULT_RRF_K: u32 = 60;
+
+/// Sparse vector holding only its non-zero dimensions, sorted by index
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SparseVector {
+    /// Dimension indices, strictly increasing
+    pub indices: Vec<u32>,
+    /// Values of the dimensions in `indices`
+    pub values: Vec<f32>,
+}
+
+impl SparseVector {
+    /// Create a sparse vector from parallel index and value lists
+    ///
+    /// Entries are sorted by index; duplicate indices are rejected.
+    pub fn new(indices: Vec<u32>, values: Vec<f32>) -> Result<Self> {
+        if indices.len() != values.len() {
+            return Err(VectorError::InvalidVector(format!(
+                "Sparse vector has {} indices but {} values",
+                indices.len(),
+                values.len()
+            )));
+        }
+        let mut entries: Vec<(u32, f32)> = indices.into_iter().zip(values).collect();
+        entries.sort_by_key(|(index, _)| *index);
+        if entries.windows(2).any(|pair| pair[0].0 == pair[1].0) {
+            return Err(VectorError::InvalidVector("Sparse vector has duplicate indices".to_string()));
+        }
+        let (indices, values) = entries.into_iter().unzip();
+        Ok(Self { indices, values })
+    }
+
+    /// Number of non-zero dimensions
+    pub fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    /// Whether the vector has no non-zero dimensions
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// Dot product with another sparse vector
+    pub fn dot(&self, other: &SparseVector) -> f32 {
+        let (mut i, mut j, mut sum) = (0, 0, 0.0);
+        while i < self.indices.len() && j < other.indices.len() {
+            match self.indices[i].cmp(&other.indices[j]) {
+                std::cmp::Ordering::Less => i += 1,
+                std::cmp::Ordering::Greater => j += 1,
+                std::cmp::Ordering::Equal => {
+                    sum += self.values[i] * other.values[j];
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+        sum
+    }
+}
+
+/// How the dense and sparse rankings of a hybrid query are combined
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum FusionMethod {
+    /// Reciprocal rank fusion: each list contributes `1 / (k + rank)`
+    ReciprocalRank { k: u32 },
+    /// Weighted sum of min-max normalized scores; the sparse side gets `1 - dense_weight`
+    Weighted { dense_weight: f32 },
+}
+
+impl Default for FusionMethod {
+    fn default() -> Self {
+        Self::ReciprocalRank { k: DEFAULT_RRF_K }
+    }
+}
+
+/// Fuse dense and sparse result lists into one ranking, best first
+///
+/// Documents found by only one side keep that side's contribution. Metadata, content
+/// and vectors are taken from the dense result when both sides return a document.
+pub fn fuse_results(dense: Vec<SearchResult>, sparse: Vec<SearchResult>, fusion: FusionMethod) -> Vec<SearchResult> {
+    let contributions = |results: &[SearchResult], weight: f32| -> Vec<f32> {
+        match fusion {
+            FusionMethod::ReciprocalRank { k } => (0..results.len())
+                .map(|rank| weight / (k as f32 + rank as f32 + 1.0))
+                .collect(),
+            FusionMethod::Weighted { .. } => {
+                let min = results.iter().map(|r| r.score).fold(f32::INFINITY, f32::min);
+                let max = results.iter().map(|r| r.score).fold(f32::NEG_INFINITY, f32::max);
+                results
+                    .iter()
+                    .map(|r| weight * if max > min { (r.score - min) / (max - min) } else { 1.0 })
+                    .collect()
+            }
+        }
+    };
+    let (dense_weight, sparse_weight) = match fusion {
+        FusionMethod::ReciprocalRank { .. } => (1.0, 1.0),
+        FusionMethod::Weighted { dense_weight } => {
+            let dense_weight = dense_weight.clamp(0.0, 1.0);
+            (dense_weight, 1.0 - dense_weight)
+        }
+    };
+
+    let dense_scores = contributions(&dense, dense_weight);
+    let sparse_scores = contributions(&sparse, sparse_weight);
+    let mut fused: HashMap<DocumentId, SearchResult> = HashMap::new();
+    for (result, score) in dense.into_iter().zip(dense_scores).chain(sparse.into_iter().zip(sparse_scores)) {
+        fused
+            .entry(result.id.clone())
+            .and_modify(|existing| existing.score += score)
+            .or_insert(SearchResult { score, ..result });
+    }
+
+    let mut results: Vec<SearchResult> = fused.into_values().collect();
+    results.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.id.cmp(&b.id)));
+    results
+}
@@ -259,4 +259,219 @@ mod tests {
         assert_eq!(info.features.len(), 2);
         assert_eq!(info.metadata.len(), 1);
     }
+
+    #[test]
+    fn test_scoring_modifiers() {
+        let now = chrono::Utc::now();
+        let day = 24 * 60 * 60;
+        let result = |id: &str, score: f32, age_days: i64, priority: i64| {
+            SearchResult::new(id, score).with_metadata(HashMap::from([
+                ("updated_at".to_string(), MetadataValue::Integer(now.timestamp() - age_days * day)),
+                ("priority".to_string(), MetadataValue::Integer(priority)),
+                ("source".to_string(), MetadataValue::String(if priority > 0 { "docs" } else { "forum" }.to_string())),
+            ]))
+        };
+        let mut results = vec![result("stale", 0.9, 60, 0), result("fresh", 0.8, 1, 0), result("pinned", 0.5, 60, 1)];
+
+        let scoring = ScoringModifiers::new()
+            .with_recency(RecencyDecay::new("updated_at", std::time::Duration::from_secs(30 * day as u64)).with_weight(0.5))
+            .with_boost(FieldBoost::matching("source", "docs", 1.5))
+            .with_boost(FieldBoost::numeric("priority", 0.2));
+        scoring.apply(&mut results, 2, now);
+
+        let ids: Vec<_> = results.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["fresh", "pinned"]);
+        // 0.8 * (0.5 + 0.5 * 0.5^(1/30))
+        assert!((results[0].score - 0.7908).abs() < 1e-3);
+        assert_eq!(scoring.candidate_limit(10), 40);
+
+        // Missing timestamps are fully decayed
+        let decay = RecencyDecay::new("updated_at", std::time::Duration::from_secs(day as u64));
+        assert_eq!(decay.factor(&HashMap::new(), now), 0.0);
+        let rfc3339 = HashMap::from([("updated_at".to_string(), MetadataValue::String(now.to_rfc3339()))]);
+        assert!((decay.factor(&rfc3339, now) - 1.0).abs() < 1e-6);
+    }
+
+    struct FlakyConnector {
+        connects: std::sync::atomic::AtomicU32,
+        fail_first: u32,
+        probe_ok: std::sync::atomic::AtomicBool,
+    }
+
+    #[async_trait::async_trait]
+    impl Connector for FlakyConnector {
+        type Connection = u32;
+
+        async fn connect(&self, _config: &ConnectionManagerConfig) -> Result<u32> {
+            let attempt = self.connects.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            if attempt < self.fail_first {
+                return Err(VectorError::connection_failed("refused"));
+            }
+            Ok(attempt)
+        }
+
+        async fn probe(&self, _connection: &u32) -> Result<()> {
+            if self.probe_ok.load(std::sync::atomic::Ordering::SeqCst) {
+                Ok(())
+            } else {
+                Err(VectorError::connection_failed("probe failed"))
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_connection_manager_reconnects_with_backoff() {
+        let connector = FlakyConnector {
+            connects: Default::default(),
+            fail_first: 2,
+            probe_ok: std::sync::atomic::AtomicBool::new(true),
+        };
+        let config = ConnectionManagerConfig::default()
+            .with_pool_size(1, 4)
+            .with_backoff(BackoffConfig { initial_delay: std::time::Duration::from_millis(1), ..Default::default() });
+        let manager = ConnectionManager::new(connector, config).await.unwrap();
+        assert_eq!(manager.connection().await, 2);
+
+        // Transient failures are retried with a fresh connection
+        let calls = std::sync::atomic::AtomicU32::new(0);
+        let value = manager.execute(|connection| {
+            let call = calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            async move {
+                if call == 0 { Err(VectorError::connection_failed("reset")) } else { Ok(connection) }
+            }
+        }).await.unwrap();
+        assert_eq!(value, 3);
+
+        // Repeated probe failures trigger a reconnect
+        manager.connector().probe_ok.store(false, std::sync::atomic::Ordering::SeqCst);
+        for _ in 0..3 {
+            assert!(manager.health_check().await.is_err());
+        }
+        let metrics = manager.metrics();
+        assert_eq!((metrics.retries, metrics.reconnects, metrics.failed_probes), (1, 2, 3));
+        assert_eq!(metrics.in_use, 0);
+
+        let info = BackendInfo::new("remote", "1.0").with_pool_metrics(&metrics);
+        assert_eq!(info.metadata.get("pool.max_connections"), Some(&MetadataValue::Integer(4)));
+        assert_eq!(BackoffConfig::default().delay(10), std::time::Duration::from_secs(10));
+    }
+
+    struct TaggedStorage {
+        name: &'static str,
+        lag: Option<std::time::Duration>,
+        searches: std::sync::atomic::AtomicUsize,
+    }
+
+    impl TaggedStorage {
+        fn new(name: &'static str, lag: Option<std::time::Duration>) -> Self {
+            Self { name, lag, searches: Default::default() }
+        }
+
+        fn searches(&self) -> usize {
+            self.searches.load(std::sync::atomic::Ordering::Relaxed)
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl VectorStorage for TaggedStorage {
+        type Config = ();
+
+        async fn create_index(&self, _config: IndexConfig) -> Result<()> { Ok(()) }
+        async fn list_indexes(&self) -> Result<Vec<String>> { Ok(vec![self.name.to_string()]) }
+        async fn describe_index(&self, index_name: &str) -> Result<IndexInfo> {
+            Err(VectorError::index_not_found(index_name))
+        }
+        async fn delete_index(&self, _index_name: &str) -> Result<()> { Ok(()) }
+        async fn upsert_documents(&self, _index_name: &str, documents: Vec<Document>) -> Result<Vec<DocumentId>> {
+            Ok(documents.into_iter().map(|d| d.id).collect())
+        }
+        async fn search(&self, _request: SearchRequest) -> Result<SearchResponse> {
+            self.searches.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            Ok(SearchResponse::new(vec![SearchResult::new(self.name, 1.0)]))
+        }
+        async fn update_document(&self, _index_name: &str, _document: Document) -> Result<()> { Ok(()) }
+        async fn delete_documents(&self, _index_name: &str, _ids: Vec<DocumentId>) -> Result<()> { Ok(()) }
+        async fn get_documents(&self, _index_name: &str, _ids: Vec<DocumentId>, _include_vectors: bool) -> Result<Vec<Document>> {
+            Ok(Vec::new())
+        }
+        async fn health_check(&self) -> Result<()> { Ok(()) }
+        async fn replication_lag(&self) -> Result<Option<std::time::Duration>> { Ok(self.lag) }
+        fn backend_info(&self) -> BackendInfo { BackendInfo::new(self.name, "1.0") }
+    }
+
+    #[tokio::test]
+    async fn test_replicated_storage_routes_reads_to_fresh_replicas() {
+        use std::time::Duration;
+
+        let storage = ReplicatedStorage::new(
+            TaggedStorage::new("primary", None),
+            vec![
+                TaggedStorage::new("fresh", Some(Duration::from_millis(100))),
+                TaggedStorage::new("stale", Some(Duration::from_secs(60))),
+            ],
+            ReplicaConfig::default().with_read_your_writes(Duration::from_millis(50)),
+        );
+
+        for _ in 0..4 {
+            storage.search(SearchRequest::new("docs", vec![1.0])).await.unwrap();
+        }
+        assert_eq!(storage.replicas()[0].searches(), 4);
+        assert_eq!(storage.replicas()[1].searches(), 0);
+        assert_eq!(storage.available_replicas(), 1);
+
+        // Reads right after a write see the primary
+        storage.upsert_documents("docs", vec![Document::new("d1", "text")]).await.unwrap();
+        let response = storage.search(SearchRequest::new("docs", vec![1.0])).await.unwrap();
+        assert_eq!(response.results[0].id, "primary");
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        let response = storage.search(SearchRequest::new("docs", vec![1.0])).await.unwrap();
+        assert_eq!(response.results[0].id, "fresh");
+
+        let info = storage.backend_info();
+        assert_eq!(info.name, "primary");
+        assert_eq!(info.metadata.get("replicas.available"), Some(&MetadataValue::Integer(1)));
+    }
+
+    #[test]
+    fn test_sparse_vectors_and_hybrid_fusion() {
+        let query = SparseVector::new(vec![7, 2, 40], vec![0.5, 1.0, 2.0]).unwrap();
+        assert_eq!(query.indices, vec![2, 7, 40]);
+        let doc = SparseVector::new(vec![2, 40, 99], vec![3.0, 0.5, 1.0]).unwrap();
+        assert_eq!(query.dot(&doc), 4.0);
+        assert!(SparseVector::new(vec![1, 1], vec![1.0, 2.0]).is_err());
+        assert!(SparseVector::new(vec![1], vec![]).is_err());
+
+        let dense = vec![SearchResult::new("a", 0.9), SearchResult::new("b", 0.8), SearchResult::new("c", 0.1)];
+        let sparse = vec![SearchResult::new("b", 12.0), SearchResult::new("c", 4.0)];
+
+        // "b" ranks well in both lists and wins under rank fusion
+        let fused = fuse_results(dense.clone(), sparse.clone(), FusionMethod::default());
+        let ids: Vec<_> = fused.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["b", "c", "a"]);
+
+        // A dense-only weighting keeps the dense order
+        let fused = fuse_results(dense, sparse, FusionMethod::Weighted { dense_weight: 1.0 });
+        assert_eq!(fused[0].id, "a");
+        assert_eq!(fused[2].id, "c");
+    }
+
+    #[test]
+    fn test_named_vector_max_sim_and_field_weights() {
+        let calculator = crate::traits::similarity::create_calculator(SimilarityMetric::DotProduct);
+        let stored = NamedVector::Multi(vec![vec![1.0, 0.0], vec![0.0, 2.0]]);
+        let query = NamedVector::Multi(vec![vec![1.0, 0.0], vec![0.0, 1.0]]);
+        assert_eq!(query.max_sim(&stored, calculator.as_ref()).unwrap(), 3.0);
+        assert!(NamedVector::Multi(vec![vec![1.0], vec![1.0, 2.0]]).validate().is_err());
+        assert!(NamedVector::Multi(Vec::new()).validate().is_err());
+
+        let document = Document::new("a", "text").with_named_vector("title", vec![2.0, 0.0]);
+        let queries = vec![
+            VectorFieldQuery::new("title", vec![1.0, 0.0]).with_weight(0.5),
+            VectorFieldQuery::new("body", vec![1.0, 0.0]),
+        ];
+        let score = combine_field_scores(&queries, |field| document.vectors.get(field), calculator.as_ref()).unwrap();
+        assert_eq!(score, Some(1.0));
+        assert_eq!(combine_field_scores(&queries[1..], |field| document.vectors.get(field), calculator.as_ref()).unwrap(), None);
+    }
 }
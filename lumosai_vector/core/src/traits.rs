@@ -4,7 +4,9 @@ use async_trait::async_trait;
 use std::collections::HashMap;
 
 use crate::{
+    aggregation::{AggregateRequest, AggregateResponse, Aggregator},
     error::{Result, VectorError},
+    sparse::SparseVector,
     types::*,
 };
 
@@ -47,11 +49,95 @@ pub trait VectorStorage: Send + Sync {
     
     /// Check if the storage backend is healthy
     async fn health_check(&self) -> Result<()>;
-    
+
+    /// List documents page by page in a stable order
+    ///
+    /// Pass the `next_cursor` of a page to fetch the next one. Documents are returned
+    /// with their embeddings.
+    async fn list_documents(
+        &self,
+        index_name: &str,
+        cursor: Option<String>,
+        limit: usize,
+        filter: Option<FilterCondition>,
+    ) -> Result<DocumentPage> {
+        let _ = (index_name, cursor, limit, filter);
+        Err(VectorError::NotSupported(format!(
+            "{} does not support listing documents",
+            self.backend_info().name
+        )))
+    }
+
+    /// Compute counts, facets and min/max over metadata, optionally filtered
+    ///
+    /// Backends that cannot aggregate natively page through [`Self::list_documents`]
+    /// and aggregate client-side.
+    async fn aggregate(&self, request: AggregateRequest) -> Result<AggregateResponse> {
+        let mut aggregator = Aggregator::new(&request.aggregations);
+        let mut cursor = None;
+        loop {
+            let page = self
+                .list_documents(&request.index_name, cursor, AGGREGATE_PAGE_SIZE, request.filter.clone())
+                .await?;
+            for document in &page.documents {
+                aggregator.add(&document.metadata);
+            }
+            cursor = page.next_cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+        Ok(aggregator.finish())
+    }
+
+    /// Create an alias pointing at `index_name`
+    ///
+    /// Requests that name the alias are served by the index it points at. Fails with
+    /// [`VectorError::AliasAlreadyExists`] if the alias exists; use
+    /// [`swap_alias`](Self::swap_alias) to repoint it.
+    async fn create_alias(&self, alias: &str, index_name: &str) -> Result<()> {
+        let _ = (alias, index_name);
+        Err(aliases_not_supported(&self.backend_info()))
+    }
+
+    /// Point `alias` at `index_name`, creating the alias if it does not exist
+    ///
+    /// Repointing an alias is atomic, so traffic moves to a rebuilt index without
+    /// downtime.
+    async fn swap_alias(&self, alias: &str, index_name: &str) -> Result<()> {
+        let _ = (alias, index_name);
+        Err(aliases_not_supported(&self.backend_info()))
+    }
+
+    /// Delete an alias; the index it points at is kept
+    async fn delete_alias(&self, alias: &str) -> Result<()> {
+        let _ = alias;
+        Err(aliases_not_supported(&self.backend_info()))
+    }
+
+    /// All aliases, mapped to the index each one points at
+    async fn list_aliases(&self) -> Result<HashMap<String, String>> {
+        Err(aliases_not_supported(&self.backend_info()))
+    }
+
+    /// How far this storage lags behind its primary, if it is a read replica
+    ///
+    /// Backends that cannot measure replication lag return `None`.
+    async fn replication_lag(&self) -> Result<Option<std::time::Duration>> {
+        Ok(None)
+    }
+
     /// Get storage backend information
     fn backend_info(&self) -> BackendInfo;
 }
 
+/// Documents fetched per page by the default [`VectorStorage::aggregate`]
+const AGGREGATE_PAGE_SIZE: usize = 1000;
+
+fn aliases_not_supported(backend: &BackendInfo) -> VectorError {
+    VectorError::NotSupported(format!("{} does not support index aliases", backend.name))
+}
+
 /// Trait for embedding models
 /// 
 /// This trait abstracts over different embedding providers like OpenAI,
@@ -80,6 +166,36 @@ pub trait EmbeddingModel: Send + Sync {
     async fn health_check(&self) -> Result<()>;
 }
 
+/// Trait for sparse embedding models
+///
+/// Learned sparse models (e.g. SPLADE) produce weighted vocabulary terms rather than
+/// dense vectors and are used alongside an [`EmbeddingModel`] for hybrid search.
+#[async_trait]
+pub trait SparseEmbeddingModel: Send + Sync {
+    /// Generate a sparse embedding for a single text
+    async fn embed_sparse(&self, text: &str) -> Result<SparseVector>;
+    
+    /// Generate sparse embeddings for multiple texts
+    async fn embed_sparse_batch(&self, texts: &[String]) -> Result<Vec<SparseVector>>;
+    
+    /// Get the model name/identifier
+    fn model_name(&self) -> &str;
+}
+
+/// Trait for rerankers
+///
+/// Rerankers such as cross-encoders score each (query, document) pair jointly. That is
+/// slower than embedding similarity but more accurate, so they reorder a short list of
+/// retrieved candidates rather than search the whole corpus.
+#[async_trait]
+pub trait Reranker: Send + Sync {
+    /// Relevance score of each document for the query, in input order (higher is better)
+    async fn score(&self, query: &str, documents: &[String]) -> Result<Vec<f32>>;
+    
+    /// Get the model name/identifier
+    fn model_name(&self) -> &str;
+}
+
 /// Trait for documents that can be embedded
 /// 
 /// This trait is inspired by Rig's Embed trait and allows automatic
@@ -3,6 +3,10 @@
 use std::collections::HashMap;
 use uuid::Uuid;
 
+use crate::multi_vector::{NamedVector, VectorFieldQuery};
+use crate::scoring::ScoringModifiers;
+use crate::sparse::{FusionMethod, SparseVector};
+
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
@@ -250,6 +254,12 @@ pub struct Document {
     pub content: String,
     /// Vector embedding (optional)
     pub embedding: Option<Vector>,
+    /// Sparse embedding for lexical or hybrid search (optional)
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub sparse_embedding: Option<SparseVector>,
+    /// Additional named vectors, e.g. a title embedding or per-token vectors
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "HashMap::is_empty"))]
+    pub vectors: HashMap<String, NamedVector>,
     /// Document metadata
     pub metadata: Metadata,
 }
@@ -261,6 +271,8 @@ impl Document {
             id: id.into(),
             content: content.into(),
             embedding: None,
+            sparse_embedding: None,
+            vectors: HashMap::new(),
             metadata: HashMap::new(),
         }
     }
@@ -276,6 +288,18 @@ impl Document {
         self
     }
     
+    /// Set the sparse embedding
+    pub fn with_sparse_embedding(mut self, sparse_embedding: SparseVector) -> Self {
+        self.sparse_embedding = Some(sparse_embedding);
+        self
+    }
+    
+    /// Add a named vector field
+    pub fn with_named_vector(mut self, field: impl Into<String>, vector: impl Into<NamedVector>) -> Self {
+        self.vectors.insert(field.into(), vector.into());
+        self
+    }
+    
     /// Add metadata
     pub fn with_metadata(mut self, key: impl Into<String>, value: impl Into<MetadataValue>) -> Self {
         self.metadata.insert(key.into(), value.into());
@@ -307,6 +331,8 @@ pub struct SearchRequest {
     pub include_metadata: bool,
     /// Search options
     pub options: HashMap<String, MetadataValue>,
+    /// Scoring modifiers applied after retrieval
+    pub scoring: Option<ScoringModifiers>,
 }
 
 impl SearchRequest {
@@ -320,6 +346,7 @@ impl SearchRequest {
             include_vectors: false,
             include_metadata: true,
             options: HashMap::new(),
+            scoring: None,
         }
     }
 
@@ -333,6 +360,36 @@ impl SearchRequest {
             include_vectors: false,
             include_metadata: true,
             options: HashMap::new(),
+            scoring: None,
+        }
+    }
+
+    /// Create a new search request with a sparse vector query
+    pub fn new_sparse(index_name: impl Into<String>, sparse: SparseVector) -> Self {
+        Self {
+            query: SearchQuery::Sparse(sparse),
+            ..Self::new(index_name, Vec::new())
+        }
+    }
+
+    /// Create a new hybrid search request fusing a dense and a sparse query
+    pub fn new_hybrid(
+        index_name: impl Into<String>,
+        dense: Vector,
+        sparse: SparseVector,
+        fusion: FusionMethod,
+    ) -> Self {
+        Self {
+            query: SearchQuery::Hybrid { dense, sparse, fusion },
+            ..Self::new(index_name, Vec::new())
+        }
+    }
+
+    /// Create a new search request over named vector fields, combining their weighted scores
+    pub fn new_multi_vector(index_name: impl Into<String>, queries: Vec<VectorFieldQuery>) -> Self {
+        Self {
+            query: SearchQuery::MultiVector(queries),
+            ..Self::new(index_name, Vec::new())
         }
     }
 
@@ -365,9 +422,16 @@ impl SearchRequest {
         self.options.insert(key.into(), value);
         self
     }
+
+    /// Set the scoring modifiers
+    pub fn with_scoring(mut self, scoring: ScoringModifiers) -> Self {
+        self.scoring = Some(scoring);
+        self
+    }
 }
 
-/// Search query can be either a vector or text
+/// Search query can be a dense vector, text, a sparse vector, a hybrid of both or
+/// a query over named vector fields
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum SearchQuery {
@@ -375,6 +439,16 @@ pub enum SearchQuery {
     Vector(Vector),
     /// Text query (requires embedding model)
     Text(String),
+    /// Sparse vector query, scored by dot product
+    Sparse(SparseVector),
+    /// Dense and sparse queries whose rankings are fused
+    Hybrid {
+        dense: Vector,
+        sparse: SparseVector,
+        fusion: FusionMethod,
+    },
+    /// Queries against named vector fields whose scores are summed by weight
+    MultiVector(Vec<VectorFieldQuery>),
 }
 
 /// Search result item
@@ -461,3 +535,20 @@ impl SearchResponse {
         self
     }
 }
+
+/// One page of an index listing
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DocumentPage {
+    /// Documents in this page
+    pub documents: Vec<Document>,
+    /// Cursor of the next page; `None` on the last page
+    pub next_cursor: Option<String>,
+}
+
+impl DocumentPage {
+    /// Create a page of documents
+    pub fn new(documents: Vec<Document>, next_cursor: Option<String>) -> Self {
+        Self { documents, next_cursor }
+    }
+}
@@ -0,0 +1,223 @@
+//! Write-ahead log for embedded vector stores
+//!
+//! Embedded stores whose batch writes are not atomic, such as LanceDB's delete-then-add
+//! upsert, record every batch in the log and sync it to disk before touching the index,
+//! then mark it committed once applied. After a crash, [`WriteAheadLog::open`] returns
+//! the batches that were logged but never committed so the store can re-apply them
+//! before serving requests. Re-applying a batch must therefore be idempotent.
+//!
+//! The log is a JSON lines file. It is truncated whenever no batch is in flight, so it
+//! stays small during normal operation.
+
+use std::collections::BTreeSet;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Result, VectorError};
+use crate::types::{Document, DocumentId};
+
+/// Write recorded in the log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum WalOperation {
+    /// Insert or replace documents
+    Upsert {
+        /// Target index
+        index: String,
+        /// Documents to write
+        documents: Vec<Document>,
+    },
+    /// Delete documents
+    Delete {
+        /// Target index
+        index: String,
+        /// IDs of the documents to delete
+        ids: Vec<DocumentId>,
+    },
+}
+
+impl WalOperation {
+    /// Index the operation writes to
+    pub fn index(&self) -> &str {
+        match self {
+            WalOperation::Upsert { index, .. } | WalOperation::Delete { index, .. } => index,
+        }
+    }
+}
+
+/// Logged operation with its sequence number
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalRecord {
+    /// Sequence number, passed to [`WriteAheadLog::commit`] once applied
+    pub sequence: u64,
+    /// The logged write
+    pub operation: WalOperation,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum WalLine {
+    Record(WalRecord),
+    Commit { committed: u64 },
+}
+
+struct WalState {
+    file: File,
+    next_sequence: u64,
+    in_flight: BTreeSet<u64>,
+}
+
+/// Append-only log of pending writes
+pub struct WriteAheadLog {
+    path: PathBuf,
+    state: Mutex<WalState>,
+}
+
+impl WriteAheadLog {
+    /// Open the log at `path`, returning the records that were never committed
+    ///
+    /// The returned records stay in flight until they are committed, so a crash during
+    /// recovery replays them again on the next open. A record torn by a crash while it
+    /// was appended is discarded, since its write never started.
+    pub fn open(path: impl Into<PathBuf>) -> Result<(Self, Vec<WalRecord>)> {
+        let path = path.into();
+        let mut pending = Vec::new();
+        let mut next_sequence = 1;
+
+        if path.exists() {
+            for line in BufReader::new(File::open(&path)?).lines() {
+                let Ok(line) = serde_json::from_str::<WalLine>(&line?) else {
+                    break;
+                };
+                match line {
+                    WalLine::Record(record) => {
+                        next_sequence = next_sequence.max(record.sequence + 1);
+                        pending.push(record);
+                    }
+                    WalLine::Commit { committed } => pending.retain(|record| record.sequence != committed),
+                }
+            }
+        }
+
+        // Rewrite the log with only the pending records, dropping any torn tail
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let temp_path = path.with_extension("tmp");
+        let mut temp = File::create(&temp_path)?;
+        for record in &pending {
+            temp.write_all(&Self::encode(&WalLine::Record(record.clone()))?)?;
+        }
+        temp.sync_all()?;
+        fs::rename(&temp_path, &path)?;
+
+        let file = OpenOptions::new().append(true).open(&path)?;
+        let state = WalState {
+            file,
+            next_sequence,
+            in_flight: pending.iter().map(|record| record.sequence).collect(),
+        };
+        Ok((Self { path, state: Mutex::new(state) }, pending))
+    }
+
+    /// Path of the log file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Durably record `operation` before it is applied, returning its sequence number
+    pub fn append(&self, operation: WalOperation) -> Result<u64> {
+        let mut state = self.lock()?;
+        let record = WalRecord { sequence: state.next_sequence, operation };
+        state.file.write_all(&Self::encode(&WalLine::Record(record))?)?;
+        state.file.sync_data()?;
+
+        let sequence = state.next_sequence;
+        state.next_sequence += 1;
+        state.in_flight.insert(sequence);
+        Ok(sequence)
+    }
+
+    /// Mark the operation with `sequence` as applied
+    ///
+    /// Commit markers are not synced: losing one only replays an idempotent write.
+    pub fn commit(&self, sequence: u64) -> Result<()> {
+        let mut state = self.lock()?;
+        state.in_flight.remove(&sequence);
+        if state.in_flight.is_empty() {
+            state.file.set_len(0)?;
+        } else {
+            state.file.write_all(&Self::encode(&WalLine::Commit { committed: sequence })?)?;
+        }
+        Ok(())
+    }
+
+    /// Number of operations logged but not yet committed
+    pub fn in_flight(&self) -> usize {
+        self.lock().map(|state| state.in_flight.len()).unwrap_or_default()
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, WalState>> {
+        self.state.lock().map_err(|_| VectorError::internal("Write-ahead log lock poisoned"))
+    }
+
+    fn encode(line: &WalLine) -> Result<Vec<u8>> {
+        let mut bytes = serde_json::to_vec(line)?;
+        bytes.push(b'\n');
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log_path(test: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("lumos_wal_{}_{}.jsonl", test, std::process::id()))
+    }
+
+    fn upsert(id: &str) -> WalOperation {
+        WalOperation::Upsert {
+            index: "docs".to_string(),
+            documents: vec![Document::new(id, "text").with_embedding(vec![1.0, 0.0])],
+        }
+    }
+
+    #[test]
+    fn test_uncommitted_records_are_recovered() {
+        let path = log_path("recover");
+        let (wal, pending) = WriteAheadLog::open(&path).unwrap();
+        assert!(pending.is_empty());
+
+        let first = wal.append(upsert("a")).unwrap();
+        let second = wal.append(WalOperation::Delete { index: "docs".to_string(), ids: vec!["b".to_string()] }).unwrap();
+        let third = wal.append(upsert("c")).unwrap();
+        wal.commit(second).unwrap();
+        assert_eq!(wal.in_flight(), 2);
+        // Simulate a crash in the middle of appending
+        drop(wal);
+        OpenOptions::new().append(true).open(&path).unwrap().write_all(br#"{"sequence":4,"operat"#).unwrap();
+
+        let (wal, pending) = WriteAheadLog::open(&path).unwrap();
+        assert_eq!(pending.iter().map(|record| record.sequence).collect::<Vec<_>>(), vec![first, third]);
+        match &pending[1].operation {
+            WalOperation::Upsert { documents, .. } => assert_eq!(documents[0].embedding, Some(vec![1.0, 0.0])),
+            other => panic!("unexpected operation {:?}", other),
+        }
+
+        // Sequence numbers continue after the recovered records
+        assert_eq!(wal.append(upsert("d")).unwrap(), 4);
+        for sequence in [first, third, 4] {
+            wal.commit(sequence).unwrap();
+        }
+        assert_eq!(fs::metadata(&path).unwrap().len(), 0);
+
+        let (_, pending) = WriteAheadLog::open(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!(pending.is_empty());
+    }
+}
@@ -63,6 +63,8 @@ async fn main() -> Result<()> {
             id: "doc_1".to_string(),
             content: "Artificial intelligence is transforming the world of technology.".to_string(),
             embedding: Some(generate_sample_embedding(384, 1)),
+            sparse_embedding: None,
+            vectors: HashMap::new(),
             metadata: create_metadata(vec![
                 ("category", MetadataValue::String("technology".to_string())),
                 ("author", MetadataValue::String("Alice".to_string())),
@@ -74,6 +76,8 @@ async fn main() -> Result<()> {
             id: "doc_2".to_string(),
             content: "Machine learning algorithms are becoming more sophisticated.".to_string(),
             embedding: Some(generate_sample_embedding(384, 2)),
+            sparse_embedding: None,
+            vectors: HashMap::new(),
             metadata: create_metadata(vec![
                 ("category", MetadataValue::String("technology".to_string())),
                 ("author", MetadataValue::String("Bob".to_string())),
@@ -85,6 +89,8 @@ async fn main() -> Result<()> {
             id: "doc_3".to_string(),
             content: "Climate change is a pressing global issue requiring immediate action.".to_string(),
             embedding: Some(generate_sample_embedding(384, 3)),
+            sparse_embedding: None,
+            vectors: HashMap::new(),
             metadata: create_metadata(vec![
                 ("category", MetadataValue::String("environment".to_string())),
                 ("author", MetadataValue::String("Carol".to_string())),
@@ -96,6 +102,8 @@ async fn main() -> Result<()> {
             id: "doc_4".to_string(),
             content: "Renewable energy sources are becoming more cost-effective.".to_string(),
             embedding: Some(generate_sample_embedding(384, 4)),
+            sparse_embedding: None,
+            vectors: HashMap::new(),
             metadata: create_metadata(vec![
                 ("category", MetadataValue::String("environment".to_string())),
                 ("author", MetadataValue::String("David".to_string())),
@@ -165,6 +173,8 @@ async fn main() -> Result<()> {
         id: "doc_1".to_string(),
         content: "Artificial intelligence and machine learning are revolutionizing technology.".to_string(),
         embedding: Some(generate_sample_embedding(384, 5)),
+        sparse_embedding: None,
+        vectors: HashMap::new(),
         metadata: create_metadata(vec![
             ("category", MetadataValue::String("technology".to_string())),
             ("author", MetadataValue::String("Alice".to_string())),
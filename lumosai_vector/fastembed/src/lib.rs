@@ -9,6 +9,8 @@
 //! - **Multiple Models**: Support for various pre-trained models
 //! - **High Performance**: Optimized for batch processing
 //! - **Easy Integration**: Seamless integration with LumosAI vector storage
+//! - **Sparse Embeddings**: SPLADE sparse vectors for hybrid search
+//! - **Reranking**: Cross-encoder rerankers for retrieved candidates
 //!
 //! ## Quick Start
 //!
@@ -35,15 +37,19 @@ use tokio::sync::Mutex;
 
 pub mod models;
 pub mod provider;
+pub mod sparse;
+pub mod rerank;
 pub mod error;
 
-pub use models::{FastEmbedModel, ModelInfo};
+pub use models::{FastEmbedModel, FastEmbedRerankerModel, FastEmbedSparseModel, ModelInfo};
 pub use provider::FastEmbedProvider;
+pub use sparse::FastEmbedSparseProvider;
+pub use rerank::{FastEmbedReranker, RerankScore};
 pub use error::{FastEmbedError, Result};
 
 // Re-export core types for convenience
 pub use lumosai_vector_core::types::{Vector, Metadata};
-pub use lumosai_vector_core::traits::EmbeddingModel;
+pub use lumosai_vector_core::traits::{EmbeddingModel, Reranker, SparseEmbeddingModel};
 
 /// FastEmbed client for managing embedding models
 #[derive(Clone)]
@@ -109,6 +115,16 @@ impl FastEmbedClient {
         FastEmbedProvider::new(model, self.config.clone()).await
     }
     
+    /// Create a sparse embedding provider for the specified model
+    pub async fn sparse_embedding_provider(&self, model: FastEmbedSparseModel) -> Result<FastEmbedSparseProvider> {
+        FastEmbedSparseProvider::new(model, self.config.clone()).await
+    }
+    
+    /// Create a cross-encoder reranker for the specified model
+    pub async fn reranker(&self, model: FastEmbedRerankerModel) -> Result<FastEmbedReranker> {
+        FastEmbedReranker::new(model, self.config.clone()).await
+    }
+    
     /// Get or create a model instance
     async fn get_or_create_model(
         &self,
@@ -228,6 +228,102 @@ impl FastEmbedModel {
     }
 }
 
+/// Available FastEmbed sparse (SPLADE) models
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FastEmbedSparseModel {
+    /// SPLADE++ English v1
+    /// Best for: Keyword-sensitive retrieval and hybrid search
+    SpladePPEnV1,
+}
+
+impl FastEmbedSparseModel {
+    /// Get the model name string used by FastEmbed
+    pub fn model_name(&self) -> &str {
+        match self {
+            FastEmbedSparseModel::SpladePPEnV1 => "prithivida/Splade_PP_en_v1",
+        }
+    }
+    
+    /// Get the maximum sequence length in tokens
+    pub fn max_sequence_length(&self) -> usize {
+        match self {
+            FastEmbedSparseModel::SpladePPEnV1 => 512,
+        }
+    }
+    
+    /// Get model description
+    pub fn description(&self) -> &str {
+        match self {
+            FastEmbedSparseModel::SpladePPEnV1 => "SPLADE++ learned sparse model producing weighted vocabulary terms",
+        }
+    }
+    
+    /// Convert to fastembed SparseModel enum
+    pub fn to_fastembed_model(&self) -> fastembed::SparseModel {
+        match self {
+            FastEmbedSparseModel::SpladePPEnV1 => fastembed::SparseModel::SPLADEPPV1,
+        }
+    }
+}
+
+/// Available FastEmbed cross-encoder reranker models
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FastEmbedRerankerModel {
+    /// BGE Reranker Base
+    /// Best for: English and Chinese reranking
+    #[default]
+    BGERerankerBase,
+    
+    /// BGE Reranker v2 M3
+    /// Best for: Multilingual reranking
+    BGERerankerV2M3,
+    
+    /// Jina Reranker v1 Turbo English
+    /// Best for: Fast English reranking
+    JinaRerankerV1TurboEn,
+    
+    /// Jina Reranker v2 Base Multilingual
+    /// Best for: Multilingual reranking with long documents
+    JinaRerankerV2BaseMultilingual,
+}
+
+impl FastEmbedRerankerModel {
+    /// Get the model name string used by FastEmbed
+    pub fn model_name(&self) -> &str {
+        match self {
+            FastEmbedRerankerModel::BGERerankerBase => "BAAI/bge-reranker-base",
+            FastEmbedRerankerModel::BGERerankerV2M3 => "rozgo/bge-reranker-v2-m3",
+            FastEmbedRerankerModel::JinaRerankerV1TurboEn => "jinaai/jina-reranker-v1-turbo-en",
+            FastEmbedRerankerModel::JinaRerankerV2BaseMultilingual => "jinaai/jina-reranker-v2-base-multilingual",
+        }
+    }
+    
+    /// Get model description
+    pub fn description(&self) -> &str {
+        match self {
+            FastEmbedRerankerModel::BGERerankerBase => "Cross-encoder reranker for English and Chinese",
+            FastEmbedRerankerModel::BGERerankerV2M3 => "Multilingual cross-encoder reranker",
+            FastEmbedRerankerModel::JinaRerankerV1TurboEn => "Fast English cross-encoder reranker",
+            FastEmbedRerankerModel::JinaRerankerV2BaseMultilingual => "Multilingual cross-encoder reranker",
+        }
+    }
+    
+    /// Whether the model handles languages other than English
+    pub fn is_multilingual(&self) -> bool {
+        !matches!(self, FastEmbedRerankerModel::JinaRerankerV1TurboEn)
+    }
+    
+    /// Convert to fastembed RerankerModel enum
+    pub fn to_fastembed_model(&self) -> fastembed::RerankerModel {
+        match self {
+            FastEmbedRerankerModel::BGERerankerBase => fastembed::RerankerModel::BGERerankerBase,
+            FastEmbedRerankerModel::BGERerankerV2M3 => fastembed::RerankerModel::BGERerankerV2M3,
+            FastEmbedRerankerModel::JinaRerankerV1TurboEn => fastembed::RerankerModel::JINARerankerV1TurboEn,
+            FastEmbedRerankerModel::JinaRerankerV2BaseMultilingual => fastembed::RerankerModel::JINARerankerV2BaseMultiligual,
+        }
+    }
+}
+
 /// Model family classification
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ModelFamily {
@@ -315,4 +411,17 @@ mod tests {
         assert_eq!(info.dimensions, 768);
         assert!(info.language_support.contains(&"en".to_string()));
     }
+    #[test]
+    fn test_sparse_model() {
+        let model = FastEmbedSparseModel::SpladePPEnV1;
+        assert_eq!(model.model_name(), "prithivida/Splade_PP_en_v1");
+        assert_eq!(model.max_sequence_length(), 512);
+    }
+    #[test]
+    fn test_reranker_model() {
+        let model = FastEmbedRerankerModel::default();
+        assert_eq!(model.model_name(), "BAAI/bge-reranker-base");
+        assert!(model.is_multilingual());
+        assert!(!FastEmbedRerankerModel::JinaRerankerV1TurboEn.is_multilingual());
+    }
 }
@@ -0,0 +1,187 @@
+//! FastEmbed sparse (SPLADE) embedding provider
+
+use async_trait::async_trait;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::{debug, info};
+
+use lumosai_vector_core::error::VectorError;
+use lumosai_vector_core::sparse::SparseVector;
+use lumosai_vector_core::traits::SparseEmbeddingModel;
+
+use crate::error::{FastEmbedError, Result};
+use crate::models::FastEmbedSparseModel;
+use crate::FastEmbedConfig;
+
+/// FastEmbed sparse embedding provider
+///
+/// Generates SPLADE sparse vectors locally. Pair it with a [`crate::FastEmbedProvider`]
+/// to index documents for hybrid dense + sparse search.
+pub struct FastEmbedSparseProvider {
+    /// The sparse model instance
+    model: Arc<Mutex<Option<fastembed::SparseTextEmbedding>>>,
+
+    /// Model configuration
+    model_config: FastEmbedSparseModel,
+
+    /// Provider configuration
+    config: FastEmbedConfig,
+
+    /// Model name for identification
+    model_name: String,
+}
+
+impl FastEmbedSparseProvider {
+    /// Create a new sparse provider with the specified model
+    pub async fn new(model: FastEmbedSparseModel, config: FastEmbedConfig) -> Result<Self> {
+        let model_name = model.model_name().to_string();
+        info!("Creating FastEmbed sparse provider with model: {}", model_name);
+
+        let provider = Self {
+            model: Arc::new(Mutex::new(None)),
+            model_config: model,
+            config,
+            model_name,
+        };
+
+        provider.ensure_model_loaded().await?;
+
+        Ok(provider)
+    }
+
+    /// Create a new sparse provider with default configuration
+    pub async fn with_model(model: FastEmbedSparseModel) -> Result<Self> {
+        Self::new(model, FastEmbedConfig::default()).await
+    }
+
+    /// Ensure the sparse model is loaded (lazy loading)
+    async fn ensure_model_loaded(&self) -> Result<()> {
+        let mut model_guard = self.model.lock().await;
+
+        if model_guard.is_none() {
+            debug!("Initializing FastEmbed sparse model: {}", self.model_name);
+
+            let mut init_options = fastembed::SparseInitOptions::new(self.model_config.to_fastembed_model())
+                .with_max_length(self.model_config.max_sequence_length())
+                .with_show_download_progress(self.config.show_download_progress);
+
+            if let Some(cache_dir) = &self.config.cache_dir {
+                init_options = init_options.with_cache_dir(cache_dir.into());
+            }
+
+            let sparse_model = fastembed::SparseTextEmbedding::try_new(init_options)
+                .map_err(|e| FastEmbedError::ModelInitialization(format!(
+                    "Failed to initialize FastEmbed sparse model '{}': {}",
+                    self.model_name, e
+                )))?;
+
+            *model_guard = Some(sparse_model);
+            info!("FastEmbed sparse model '{}' initialized successfully", self.model_name);
+        }
+
+        Ok(())
+    }
+
+    /// Get the model configuration
+    pub fn model_config(&self) -> &FastEmbedSparseModel {
+        &self.model_config
+    }
+
+    /// Process texts in batches to respect model limits
+    async fn process_in_batches(&self, texts: &[String]) -> Result<Vec<SparseVector>> {
+        self.ensure_model_loaded().await?;
+
+        let model_guard = self.model.lock().await;
+        let model = model_guard.as_ref().ok_or_else(|| {
+            FastEmbedError::ModelNotInitialized("FastEmbed sparse model not initialized".to_string())
+        })?;
+
+        let mut all_embeddings = Vec::with_capacity(texts.len());
+
+        for chunk in texts.chunks(self.config.max_batch_size) {
+            debug!("Processing sparse batch of {} texts", chunk.len());
+
+            let embeddings = model.embed(chunk.to_vec(), None)
+                .map_err(|e| FastEmbedError::EmbeddingGeneration(format!(
+                    "FastEmbed sparse embedding failed: {}", e
+                )))?;
+
+            for embedding in embeddings {
+                all_embeddings.push(Self::to_sparse_vector(embedding)?);
+            }
+        }
+
+        Ok(all_embeddings)
+    }
+
+    /// Convert a FastEmbed sparse embedding to a core sparse vector
+    fn to_sparse_vector(embedding: fastembed::SparseEmbedding) -> Result<SparseVector> {
+        let indices = embedding.indices.into_iter()
+            .map(|index| u32::try_from(index).map_err(|_| {
+                FastEmbedError::EmbeddingGeneration(format!("Sparse index {} out of range", index))
+            }))
+            .collect::<Result<Vec<u32>>>()?;
+
+        SparseVector::new(indices, embedding.values)
+            .map_err(|e| FastEmbedError::EmbeddingGeneration(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl SparseEmbeddingModel for FastEmbedSparseProvider {
+    async fn embed_sparse(&self, text: &str) -> std::result::Result<SparseVector, VectorError> {
+        let texts = vec![text.to_string()];
+        let embeddings = self.process_in_batches(&texts).await
+            .map_err(|e| VectorError::EmbeddingError(e.to_string()))?;
+
+        embeddings.into_iter().next()
+            .ok_or_else(|| VectorError::EmbeddingError(
+                "No sparse embedding returned from FastEmbed".to_string()
+            ))
+    }
+
+    async fn embed_sparse_batch(&self, texts: &[String]) -> std::result::Result<Vec<SparseVector>, VectorError> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        self.process_in_batches(texts).await
+            .map_err(|e| VectorError::EmbeddingError(e.to_string()))
+    }
+
+    fn model_name(&self) -> &str {
+        &self.model_name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sparse_embedding_conversion() {
+        let embedding = fastembed::SparseEmbedding {
+            indices: vec![2045, 12, 998],
+            values: vec![0.4, 1.3, 0.7],
+        };
+
+        let sparse = FastEmbedSparseProvider::to_sparse_vector(embedding).unwrap();
+        assert_eq!(sparse.indices, vec![12, 998, 2045]);
+        assert_eq!(sparse.values, vec![1.3, 0.7, 0.4]);
+    }
+
+    #[tokio::test]
+    async fn test_sparse_provider_creation() {
+        // Note: This test might fail if FastEmbed models are not available
+        match FastEmbedSparseProvider::with_model(FastEmbedSparseModel::SpladePPEnV1).await {
+            Ok(provider) => {
+                assert_eq!(provider.model_name(), "prithivida/Splade_PP_en_v1");
+                let sparse = provider.embed_sparse("hybrid search with SPLADE").await.unwrap();
+                assert!(!sparse.is_empty());
+            }
+            Err(e) => {
+                eprintln!("FastEmbed sparse model not available (this is OK in CI): {}", e);
+            }
+        }
+    }
+}
@@ -1,5 +1,6 @@
 //! LanceDB configuration module
 
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 use serde::{Deserialize, Serialize};
 
@@ -283,6 +284,21 @@ impl LanceDbConfig {
         config
     }
     
+    /// Location of the write-ahead log, if enabled
+    ///
+    /// The log is a local file next to the tables, so it is only kept for local databases.
+    pub fn wal_path(&self) -> Option<PathBuf> {
+        if !self.enable_wal {
+            return None;
+        }
+        let path = match self.uri.strip_prefix("file://") {
+            Some(path) => path,
+            None if self.uri.contains("://") => return None,
+            None => &self.uri,
+        };
+        Some(Path::new(path).join("_lumos_wal.jsonl"))
+    }
+    
     /// Validate the configuration
     pub fn validate(&self) -> LanceDbResult<()> {
         if self.uri.is_empty() {
@@ -429,6 +445,16 @@ mod tests {
         assert!(!config.performance.enable_compression);
     }
     
+    #[test]
+    fn test_wal_path() {
+        assert_eq!(LanceDbConfig::local("/tmp/lance").wal_path(), Some(PathBuf::from("/tmp/lance/_lumos_wal.jsonl")));
+        assert_eq!(LanceDbConfig::default().wal_path(), Some(PathBuf::from("./lance_data/_lumos_wal.jsonl")));
+        assert_eq!(LanceDbConfig::s3("my-bucket", "us-west-2").wal_path(), None);
+        
+        let config = LanceDbConfigBuilder::new("./test").enable_wal(false).build().unwrap();
+        assert_eq!(config.wal_path(), None);
+    }
+    
     #[test]
     fn test_config_validation() {
         let mut config = LanceDbConfig::default();
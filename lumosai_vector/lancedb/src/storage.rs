@@ -14,6 +14,7 @@ use lumosai_vector_core::{
     traits::{VectorStorage, BackendInfo},
     types::*,
     error::{Result, VectorError},
+    wal::{WalOperation, WalRecord, WriteAheadLog},
 };
 
 use crate::{
@@ -33,6 +34,9 @@ pub struct LanceDbStorage {
     
     /// Table schemas cache
     schemas: Arc<tokio::sync::RwLock<HashMap<String, Schema>>>,
+    
+    /// Write-ahead log of upserts and deletes, for local databases
+    wal: Option<Arc<WriteAheadLog>>,
 }
 
 impl LanceDbStorage {
@@ -41,12 +45,22 @@ impl LanceDbStorage {
         config.validate()?;
         
         let client = LanceDbClient::new(config.clone()).await?;
+        let (wal, pending) = match config.wal_path() {
+            Some(path) => {
+                let (wal, pending) = WriteAheadLog::open(path).map_err(|e| LanceDbError::storage(e.to_string()))?;
+                (Some(Arc::new(wal)), pending)
+            }
+            None => (None, Vec::new()),
+        };
         
-        Ok(Self {
+        let storage = Self {
             client,
             config,
             schemas: Arc::new(tokio::sync::RwLock::new(HashMap::new())),
-        })
+            wal,
+        };
+        storage.recover(pending).await?;
+        Ok(storage)
     }
     
     /// Create a new LanceDB storage instance with default configuration
@@ -65,6 +79,96 @@ impl LanceDbStorage {
         &self.config
     }
     
+    /// Re-apply writes that were logged but not committed before a crash
+    ///
+    /// Writes that still fail, e.g. because their index was deleted since, are discarded
+    /// so that the database can be opened.
+    async fn recover(&self, pending: Vec<WalRecord>) -> LanceDbResult<()> {
+        let Some(wal) = &self.wal else {
+            return Ok(());
+        };
+        if !pending.is_empty() {
+            tracing::info!("Recovering {} uncommitted writes from {}", pending.len(), wal.path().display());
+        }
+        
+        for record in pending {
+            let result = match record.operation {
+                WalOperation::Upsert { index, documents } => self.apply_upsert(&index, &documents).await,
+                WalOperation::Delete { index, ids } => self.apply_delete(&index, &ids).await,
+            };
+            if let Err(e) = result {
+                tracing::warn!("Discarding write {} from the write-ahead log: {}", record.sequence, e);
+            }
+            wal.commit(record.sequence).map_err(|e| LanceDbError::storage(e.to_string()))?;
+        }
+        Ok(())
+    }
+    
+    /// Apply `operation`, logging it first if the write-ahead log is enabled
+    async fn logged<F>(&self, operation: WalOperation, apply: F) -> Result<()>
+    where
+        F: std::future::Future<Output = LanceDbResult<()>>,
+    {
+        let sequence = match &self.wal {
+            Some(wal) => Some(wal.append(operation)?),
+            None => None,
+        };
+        // A failed write stays uncommitted and is retried on the next open
+        apply.await?;
+        if let (Some(wal), Some(sequence)) = (&self.wal, sequence) {
+            wal.commit(sequence)?;
+        }
+        Ok(())
+    }
+    
+    /// Replace documents: delete any existing rows with their IDs, then add them
+    ///
+    /// The delete and the add are separate table versions, which the write-ahead log makes
+    /// atomic across crashes. Applying the same batch twice leaves one copy of each document.
+    async fn apply_upsert(&self, index_name: &str, documents: &[Document]) -> LanceDbResult<()> {
+        if !self.client.table_exists(index_name).await? {
+            return Err(LanceDbError::not_found(format!("Index '{}' not found", index_name)));
+        }
+        
+        // Get vector dimension from first document
+        let vector_dim = documents.first().and_then(|doc| doc.embedding.as_ref()).map(Vec::len).unwrap_or_default();
+        let schema = self.get_or_create_schema(index_name, vector_dim).await?;
+        
+        // Convert documents to record batch
+        let batch = utils::documents_to_record_batch(documents, &schema)?;
+        
+        let table = self.client.connection().open_table(index_name).execute().await?;
+        let ids: Vec<DocumentId> = documents.iter().map(|doc| doc.id.clone()).collect();
+        table.delete(&Self::id_condition(&ids)).await?;
+        
+        // Use add operation for inserting data
+        table
+            .add(RecordBatchIterator::new(
+                vec![batch].into_iter().map(Ok::<_, ArrowError>),
+                Arc::new(schema)
+            ))
+            .execute()
+            .await?;
+        Ok(())
+    }
+    
+    /// Delete documents by ID
+    async fn apply_delete(&self, index_name: &str, ids: &[DocumentId]) -> LanceDbResult<()> {
+        if !self.client.table_exists(index_name).await? {
+            return Err(LanceDbError::not_found(format!("Index '{}' not found", index_name)));
+        }
+        
+        let table = self.client.connection().open_table(index_name).execute().await?;
+        table.delete(&Self::id_condition(ids)).await?;
+        Ok(())
+    }
+    
+    /// Filter expression matching the given document IDs
+    fn id_condition(ids: &[DocumentId]) -> String {
+        let ids_str: Vec<String> = ids.iter().map(|id| format!("'{}'", id.replace('\'', "''"))).collect();
+        format!("id IN ({})", ids_str.join(", "))
+    }
+    
     /// Get or create a table schema
     async fn get_or_create_schema(&self, index_name: &str, vector_dim: usize) -> LanceDbResult<Schema> {
         let schemas = self.schemas.read().await;
@@ -336,8 +440,6 @@ impl VectorStorage for LanceDbStorage {
             return Ok(Vec::new());
         }
 
-        let db = self.client.connection();
-
         if !self.client.table_exists(index_name).await.map_err(VectorError::from)? {
             return Err(LanceDbError::not_found(format!("Index '{}' not found", index_name)).into());
         }
@@ -349,25 +451,8 @@ impl VectorStorage for LanceDbStorage {
             }
         }
 
-        // Get vector dimension from first document
-        let vector_dim = documents[0].embedding.as_ref().unwrap().len();
-        let schema = self.get_or_create_schema(index_name, vector_dim).await.map_err(VectorError::from)?;
-
-        // Convert documents to record batch
-        let batch = utils::documents_to_record_batch(&documents, &schema).map_err(VectorError::from)?;
-
-        // Open table and add data
-        let table = db.open_table(index_name).execute().await.map_err(|e| LanceDbError::from(e))?;
-
-        // Use add operation for inserting data
-        table
-            .add(RecordBatchIterator::new(
-                vec![batch].into_iter().map(Ok::<_, ArrowError>),
-                Arc::new(schema)
-            ))
-            .execute()
-            .await
-            .map_err(|e| LanceDbError::from(e))?;
+        let operation = WalOperation::Upsert { index: index_name.to_string(), documents: documents.clone() };
+        self.logged(operation, self.apply_upsert(index_name, &documents)).await?;
 
         // Return document IDs
         Ok(documents.into_iter().map(|doc| doc.id).collect())
@@ -386,6 +471,12 @@ impl VectorStorage for LanceDbStorage {
         let vector = match &request.query {
             SearchQuery::Vector(v) => v.clone(),
             SearchQuery::Text(_) => return Err(LanceDbError::InvalidData("Text search not supported yet".to_string()).into()),
+            SearchQuery::Sparse(_) | SearchQuery::Hybrid { .. } => {
+                return Err(LanceDbError::InvalidData("Sparse and hybrid search not supported yet".to_string()).into())
+            }
+            SearchQuery::MultiVector(_) => {
+                return Err(LanceDbError::InvalidData("Multi-vector search not supported yet".to_string()).into())
+            }
         };
 
         let mut query = table
@@ -451,25 +542,12 @@ impl VectorStorage for LanceDbStorage {
             return Ok(());
         }
 
-        let db = self.client.connection();
-
         if !self.client.table_exists(index_name).await.map_err(VectorError::from)? {
             return Err(LanceDbError::not_found(format!("Index '{}' not found", index_name)).into());
         }
 
-        let table = db.open_table(index_name).execute().await.map_err(|e| LanceDbError::from(e))?;
-
-        // Build delete condition
-        let ids_str: Vec<String> = ids.iter().map(|id| format!("'{}'", id)).collect();
-        let delete_condition = format!("id IN ({})", ids_str.join(", "));
-
-        // Execute delete
-        table
-            .delete(&delete_condition)
-            .await
-            .map_err(|e| LanceDbError::from(e))?;
-
-        Ok(())
+        let operation = WalOperation::Delete { index: index_name.to_string(), ids: ids.clone() };
+        self.logged(operation, self.apply_delete(index_name, &ids)).await
     }
 
     async fn get_documents(&self, index_name: &str, ids: Vec<DocumentId>, include_vectors: bool) -> Result<Vec<Document>> {
@@ -486,8 +564,7 @@ impl VectorStorage for LanceDbStorage {
         let table = db.open_table(index_name).execute().await.map_err(|e| LanceDbError::from(e))?;
 
         // Build query condition
-        let ids_str: Vec<String> = ids.iter().map(|id| format!("'{}'", id)).collect();
-        let condition = format!("id IN ({})", ids_str.join(", "));
+        let condition = Self::id_condition(&ids);
 
         // Select columns based on include_vectors flag
         let columns = if include_vectors {
@@ -546,5 +623,6 @@ impl VectorStorage for LanceDbStorage {
             .with_metadata("uri", self.config.uri.clone())
             .with_metadata("batch_size", self.config.performance.batch_size as i64)
             .with_metadata("compression_enabled", self.config.performance.enable_compression)
+            .with_metadata("write_ahead_log", self.wal.is_some())
     }
 }
@@ -397,3 +397,39 @@ async fn test_batch_operations() {
         .expect("Failed to check deleted documents");
     assert!(remaining.is_empty());
 }
+
+#[tokio::test]
+async fn test_write_ahead_log_recovery() {
+    use lumosai_vector_core::wal::{WalOperation, WriteAheadLog};
+    
+    let (storage, temp_dir) = create_test_storage().await;
+    let config = storage.config().clone();
+    storage.create_index(IndexConfig::new("docs", 8)).await.expect("Failed to create index");
+    storage.upsert_documents("docs", vec![
+        Document::new("doc1", "Original").with_embedding(generate_test_embedding(8, 1)),
+    ]).await.expect("Failed to insert document");
+    drop(storage);
+    
+    // Simulate a crash after a batch was logged but before it was applied
+    let (wal, pending) = WriteAheadLog::open(config.wal_path().unwrap()).expect("Failed to open log");
+    assert!(pending.is_empty());
+    wal.append(WalOperation::Upsert {
+        index: "docs".to_string(),
+        documents: vec![
+            Document::new("doc1", "Replaced").with_embedding(generate_test_embedding(8, 1)),
+            Document::new("doc2", "Recovered").with_embedding(generate_test_embedding(8, 2)),
+        ],
+    }).expect("Failed to append to log");
+    drop(wal);
+    
+    let storage = LanceDbStorage::new(config.clone()).await.expect("Failed to reopen storage");
+    let recovered = storage.get_documents("docs", vec!["doc1".to_string(), "doc2".to_string()], false).await
+        .expect("Failed to get documents");
+    assert_eq!(recovered.len(), 2);
+    assert!(recovered.iter().any(|doc| doc.id == "doc1" && doc.content == "Replaced"));
+    drop(storage);
+    
+    let (_, pending) = WriteAheadLog::open(config.wal_path().unwrap()).expect("Failed to open log");
+    assert!(pending.is_empty());
+    drop(temp_dir);
+}
@@ -23,13 +23,30 @@ pub struct MemoryIndex {
     filter_evaluator: StandardFilterEvaluator,
     /// Memory usage tracking
     memory_usage_bytes: u64,
+    /// Embedding quantization settings
+    quantization: QuantizationConfig,
+    /// Quantized embeddings, stored instead of the documents' `f32` embeddings
+    quantized: HashMap<DocumentId, QuantizedVector>,
+    /// Matryoshka dimension truncation settings
+    truncation: Option<TruncationConfig>,
+    /// Full-dimension embeddings kept for rescoring truncated results
+    full_embeddings: HashMap<DocumentId, Vector>,
 }
 
 impl MemoryIndex {
     /// Create a new memory index
-    pub fn new(config: IndexConfig, _memory_config: &MemoryConfig) -> Result<Self> {
+    pub fn new(config: IndexConfig, memory_config: &MemoryConfig) -> Result<Self> {
         let similarity_calculator = similarity::create_calculator(config.metric);
         
+        if let Some(truncation) = &memory_config.truncation {
+            if truncation.dimensions == 0 || truncation.dimensions > config.dimension {
+                return Err(VectorError::InvalidConfig(format!(
+                    "Cannot truncate index {} of dimension {} to {} dimensions",
+                    config.name, config.dimension, truncation.dimensions
+                )));
+            }
+        }
+        
         Ok(Self {
             config,
             created_at: Utc::now(),
@@ -38,6 +55,10 @@ impl MemoryIndex {
             similarity_calculator,
             filter_evaluator: StandardFilterEvaluator,
             memory_usage_bytes: 0,
+            quantization: memory_config.quantization.clone(),
+            quantized: HashMap::new(),
+            truncation: memory_config.truncation.clone(),
+            full_embeddings: HashMap::new(),
         })
     }
     
@@ -51,10 +72,25 @@ impl MemoryIndex {
             size_bytes: self.memory_usage_bytes,
             created_at: Some(self.created_at),
             updated_at: Some(self.updated_at),
-            metadata: HashMap::new(),
+            metadata: HashMap::from([(
+                "quantization".to_string(),
+                MetadataValue::String(format!("{:?}", self.quantization.quantization).to_lowercase()),
+            )]),
         }
     }
     
+    /// Get the index configuration
+    pub fn config(&self) -> &IndexConfig {
+        &self.config
+    }
+    
+    /// All stored documents ordered by ID, with their embeddings restored
+    pub fn documents(&self) -> Vec<Document> {
+        let mut ids: Vec<&DocumentId> = self.documents.keys().collect();
+        ids.sort();
+        ids.into_iter().map(|id| self.restore(&self.documents[id])).collect()
+    }
+    
     /// Get the dimension of vectors in this index
     pub fn dimension(&self) -> usize {
         self.config.dimension
@@ -80,9 +116,31 @@ impl MemoryIndex {
         // Content
         size += document.content.len() as u64;
         
-        // Embedding (if present)
+        // Embedding (if present), at its stored dimension and precision
         if let Some(embedding) = &document.embedding {
-            size += embedding.len() as u64 * 4; // f32 = 4 bytes
+            let stored = match &self.truncation {
+                Some(truncation) => embedding.len().min(truncation.dimensions),
+                None => embedding.len(),
+            };
+            size += match self.quantization.quantization {
+                QuantizationType::None => stored as u64 * 4, // f32 = 4 bytes
+                QuantizationType::Int8 => stored as u64 + 8,
+                QuantizationType::Binary => stored.div_ceil(64) as u64 * 8 + 8,
+            };
+            if self.truncation.as_ref().is_some_and(|t| t.rescore_multiplier.is_some()) {
+                size += embedding.len() as u64 * 4;
+            }
+        }
+        
+        // Sparse embedding: u32 index and f32 value per entry
+        if let Some(sparse) = &document.sparse_embedding {
+            size += sparse.len() as u64 * 8;
+        }
+        
+        // Named vectors, kept at full precision
+        for (field, vector) in &document.vectors {
+            size += field.len() as u64;
+            size += vector.vectors().iter().map(|v| v.len() as u64 * 4).sum::<u64>();
         }
         
         // Metadata (rough estimate)
@@ -110,8 +168,47 @@ impl MemoryIndex {
         }
     }
     
+    /// Store a document with its embedding truncated and quantized as configured
+    fn store(&mut self, mut document: Document) -> Result<()> {
+        self.full_embeddings.remove(&document.id);
+        if let (Some(truncation), Some(embedding)) = (&self.truncation, document.embedding.as_mut()) {
+            let truncated = truncation.apply(embedding)?;
+            let full = std::mem::replace(embedding, truncated);
+            if truncation.rescore_multiplier.is_some() {
+                self.full_embeddings.insert(document.id.clone(), full);
+            }
+        }
+        
+        if let Some(embedding) = document.embedding.take_if(|_| self.quantization.is_enabled()) {
+            if let Some(quantized) = QuantizedVector::quantize(&embedding, self.quantization.quantization) {
+                self.quantized.insert(document.id.clone(), quantized);
+            }
+        } else {
+            self.quantized.remove(&document.id);
+        }
+        self.documents.insert(document.id.clone(), document);
+        Ok(())
+    }
+    
+    /// Stored document with its embedding, at full dimension if kept, dequantized if necessary
+    fn restore(&self, document: &Document) -> Document {
+        let mut document = document.clone();
+        if let Some(full) = self.full_embeddings.get(&document.id) {
+            document.embedding = Some(full.clone());
+        } else if let Some(quantized) = self.quantized.get(&document.id) {
+            document.embedding = Some(quantized.dequantize());
+        }
+        document
+    }
+    
+    /// Check that every named vector field is well-formed
+    fn validate_named_vectors(document: &Document) -> Result<()> {
+        document.vectors.values().try_for_each(NamedVector::validate)
+    }
+    
     /// Insert or update a document
     pub fn upsert_document(&mut self, document: Document) -> Result<bool> {
+        Self::validate_named_vectors(&document)?;
         let was_new = !self.documents.contains_key(&document.id);
         
         if was_new {
@@ -119,12 +216,12 @@ impl MemoryIndex {
         } else {
             // For updates, we'll just recalculate (could be optimized)
             if let Some(old_doc) = self.documents.get(&document.id) {
-                self.memory_usage_bytes -= self.estimate_document_memory(old_doc);
+                self.memory_usage_bytes -= self.estimate_document_memory(&self.restore(old_doc));
             }
             self.memory_usage_bytes += self.estimate_document_memory(&document);
         }
         
-        self.documents.insert(document.id.clone(), document);
+        self.store(document)?;
         self.updated_at = Utc::now();
         
         Ok(was_new)
@@ -135,14 +232,15 @@ impl MemoryIndex {
         if !self.documents.contains_key(&document.id) {
             return Err(VectorError::vector_not_found(&document.id));
         }
+        Self::validate_named_vectors(&document)?;
         
         // Update memory usage
         if let Some(old_doc) = self.documents.get(&document.id) {
-            self.memory_usage_bytes -= self.estimate_document_memory(old_doc);
+            self.memory_usage_bytes -= self.estimate_document_memory(&self.restore(old_doc));
         }
         self.memory_usage_bytes += self.estimate_document_memory(&document);
         
-        self.documents.insert(document.id.clone(), document);
+        self.store(document)?;
         self.updated_at = Utc::now();
         
         Ok(())
@@ -150,7 +248,10 @@ impl MemoryIndex {
     
     /// Delete a document
     pub fn delete_document(&mut self, id: &DocumentId) -> Result<Option<Document>> {
-        if let Some(document) = self.documents.remove(id) {
+        if let Some(document) = self.documents.get(id).map(|document| self.restore(document)) {
+            self.documents.remove(id);
+            self.quantized.remove(id);
+            self.full_embeddings.remove(id);
             self.memory_usage_bytes -= self.estimate_document_memory(&document);
             self.updated_at = Utc::now();
             Ok(Some(document))
@@ -161,7 +262,54 @@ impl MemoryIndex {
     
     /// Get a document by ID
     pub fn get_document(&self, id: &DocumentId) -> Result<Option<Document>> {
-        Ok(self.documents.get(id).cloned())
+        Ok(self.documents.get(id).map(|document| self.restore(document)))
+    }
+    
+    /// List documents ordered by ID, starting after the `cursor` ID
+    ///
+    /// The cursor of the next page is the ID of the last document returned.
+    pub fn list_documents(&self, cursor: Option<&str>, limit: usize, filter: Option<&FilterCondition>) -> Result<DocumentPage> {
+        let mut ids: Vec<&DocumentId> = self.documents.keys()
+            .filter(|id| cursor.is_none_or(|cursor| id.as_str() > cursor))
+            .collect();
+        ids.sort();
+
+        let mut documents = Vec::with_capacity(limit.min(ids.len()));
+        let mut remaining = false;
+        for id in ids {
+            let document = &self.documents[id];
+            if let Some(filter) = filter {
+                if !self.filter_evaluator.evaluate(filter, &document.metadata)? {
+                    continue;
+                }
+            }
+            if documents.len() == limit {
+                remaining = true;
+                break;
+            }
+            documents.push(self.restore(document));
+        }
+
+        let next_cursor = if remaining {
+            documents.last().map(|document: &Document| document.id.clone())
+        } else {
+            None
+        };
+        Ok(DocumentPage::new(documents, next_cursor))
+    }
+    
+    /// Aggregate the metadata of documents matching the filter
+    pub fn aggregate(&self, aggregations: &[Aggregation], filter: Option<&FilterCondition>) -> Result<AggregateResponse> {
+        let mut aggregator = Aggregator::new(aggregations);
+        for document in self.documents.values() {
+            if let Some(filter) = filter {
+                if !self.filter_evaluator.evaluate(filter, &document.metadata)? {
+                    continue;
+                }
+            }
+            aggregator.add(&document.metadata);
+        }
+        Ok(aggregator.finish())
     }
     
     /// Search for similar documents
@@ -171,15 +319,24 @@ impl MemoryIndex {
                 if vector.len() != self.config.dimension {
                     return Err(VectorError::dimension_mismatch(self.config.dimension, vector.len()));
                 }
-                vector.clone()
+                match &self.truncation {
+                    Some(truncation) => truncation.apply(vector)?,
+                    None => vector.clone(),
+                }
             },
             SearchQuery::Text(_) => {
                 return Err(VectorError::NotSupported(
                     "Text queries require an embedding model".to_string()
                 ));
             },
+            SearchQuery::Sparse(sparse) => return self.search_sparse(request, sparse),
+            SearchQuery::Hybrid { dense, sparse, fusion } => return self.search_hybrid(request, dense, sparse, *fusion),
+            SearchQuery::MultiVector(queries) => return self.search_multi_vector(request, queries),
         };
         
+        let scoring = request.scoring.as_ref().filter(|scoring| !scoring.is_empty());
+        let binary_query = (self.quantization.quantization == QuantizationType::Binary)
+            .then(|| QuantizedVector::binary(&query_vector));
         let mut results = Vec::new();
         
         for (id, document) in &self.documents {
@@ -190,25 +347,177 @@ impl MemoryIndex {
                 }
             }
             
-            // Calculate similarity
-            if let Some(embedding) = &document.embedding {
-                let score = self.similarity_calculator.calculate_similarity(&query_vector, embedding)?;
-                
-                let mut result = SearchResult::new(id.clone(), score);
-                
-                if request.include_vectors {
-                    result = result.with_vector(embedding.clone());
+            // Calculate similarity; binary vectors get a Hamming first pass and are rescored below
+            let quantized = self.quantized.get(id);
+            let score = match (quantized, &binary_query, &document.embedding) {
+                (Some(quantized), Some(query), _) => hamming_similarity(query, quantized)?,
+                (Some(quantized), None, _) => quantized.similarity(&query_vector, self.similarity_calculator.as_ref())?,
+                (None, _, Some(embedding)) => self.similarity_calculator.calculate_similarity(&query_vector, embedding)?,
+                (None, _, None) => continue,
+            };
+            
+            let mut result = SearchResult::new(id.clone(), score);
+            
+            if request.include_vectors {
+                let vector = match (self.full_embeddings.get(id), quantized) {
+                    (Some(full), _) => full.clone(),
+                    (None, Some(quantized)) => quantized.dequantize(),
+                    (None, None) => document.embedding.clone().unwrap_or_default(),
+                };
+                result = result.with_vector(vector);
+            }
+            
+            // Scoring modifiers read metadata, so keep it until rescoring is done
+            if request.include_metadata || scoring.is_some() {
+                result = result.with_metadata(document.metadata.clone());
+            }
+            
+            // Include content if available
+            result = result.with_content(document.content.clone());
+            
+            results.push(result);
+        }
+        
+        if binary_query.is_some() {
+            // Rescore the best Hamming candidates against the full-precision query
+            let candidates = request.top_k.saturating_mul(self.quantization.rescore_multiplier.max(1));
+            results.sort_by(|a, b| b.score.total_cmp(&a.score));
+            results.truncate(candidates);
+            for result in &mut results {
+                if let Some(quantized) = self.quantized.get(&result.id) {
+                    result.score = quantized.similarity(&query_vector, self.similarity_calculator.as_ref())?;
+                }
+            }
+        }
+        
+        if let (Some(multiplier), SearchQuery::Vector(full_query)) =
+            (self.truncation.as_ref().and_then(|t| t.rescore_multiplier), &request.query)
+        {
+            // Rescore the best truncated candidates with the full-dimension embeddings
+            results.sort_by(|a, b| b.score.total_cmp(&a.score));
+            results.truncate(request.top_k.saturating_mul(multiplier));
+            for result in &mut results {
+                if let Some(full) = self.full_embeddings.get(&result.id) {
+                    result.score = self.similarity_calculator.calculate_similarity(full_query, full)?;
+                }
+            }
+        }
+        
+        Ok(Self::rank(results, request, scoring))
+    }
+    
+    /// Score documents with sparse embeddings by dot product with the query
+    fn search_sparse(&self, request: &SearchRequest, query: &SparseVector) -> Result<Vec<SearchResult>> {
+        let scoring = request.scoring.as_ref().filter(|scoring| !scoring.is_empty());
+        let mut results = Vec::new();
+        
+        for (id, document) in &self.documents {
+            let Some(sparse) = &document.sparse_embedding else {
+                continue;
+            };
+            if let Some(filter) = &request.filter {
+                if !self.filter_evaluator.evaluate(filter, &document.metadata)? {
+                    continue;
+                }
+            }
+            
+            // Documents sharing no term with the query are not matches
+            let score = query.dot(sparse);
+            if score == 0.0 {
+                continue;
+            }
+            
+            let mut result = SearchResult::new(id.clone(), score).with_content(document.content.clone());
+            if request.include_vectors {
+                result = result.with_vector(self.restore(document).embedding.unwrap_or_default());
+            }
+            if request.include_metadata || scoring.is_some() {
+                result = result.with_metadata(document.metadata.clone());
+            }
+            results.push(result);
+        }
+        
+        Ok(Self::rank(results, request, scoring))
+    }
+    
+    /// Score documents by the weighted MaxSim scores of the queried vector fields
+    fn search_multi_vector(&self, request: &SearchRequest, queries: &[VectorFieldQuery]) -> Result<Vec<SearchResult>> {
+        if queries.is_empty() {
+            return Err(VectorError::InvalidVector("Multi-vector query targets no fields".to_string()));
+        }
+        for query in queries {
+            query.vector.validate()?;
+        }
+        
+        let scoring = request.scoring.as_ref().filter(|scoring| !scoring.is_empty());
+        let mut results = Vec::new();
+        
+        for (id, document) in &self.documents {
+            if let Some(filter) = &request.filter {
+                if !self.filter_evaluator.evaluate(filter, &document.metadata)? {
+                    continue;
                 }
-                
-                if request.include_metadata {
-                    result = result.with_metadata(document.metadata.clone());
+            }
+            
+            // Documents without any of the queried fields are not matches
+            let score = combine_field_scores(queries, |field| document.vectors.get(field), self.similarity_calculator.as_ref())?;
+            let Some(score) = score else {
+                continue;
+            };
+            
+            let mut result = SearchResult::new(id.clone(), score).with_content(document.content.clone());
+            if request.include_vectors {
+                result = result.with_vector(self.restore(document).embedding.unwrap_or_default());
+            }
+            if request.include_metadata || scoring.is_some() {
+                result = result.with_metadata(document.metadata.clone());
+            }
+            results.push(result);
+        }
+        
+        Ok(Self::rank(results, request, scoring))
+    }
+    
+    /// Run the dense and sparse halves of a hybrid query and fuse their rankings
+    fn search_hybrid(
+        &self,
+        request: &SearchRequest,
+        dense: &Vector,
+        sparse: &SparseVector,
+        fusion: FusionMethod,
+    ) -> Result<Vec<SearchResult>> {
+        // Each side ranks the whole index so fusion sees every document either side found
+        let side = |query: SearchQuery| SearchRequest {
+            query,
+            top_k: self.documents.len(),
+            include_metadata: true,
+            scoring: None,
+            ..request.clone()
+        };
+        let dense_results = self.search(&side(SearchQuery::Vector(dense.clone())))?;
+        let sparse_results = self.search(&side(SearchQuery::Sparse(sparse.clone())))?;
+        
+        let scoring = request.scoring.as_ref().filter(|scoring| !scoring.is_empty());
+        let mut results = fuse_results(dense_results, sparse_results, fusion);
+        if !request.include_metadata && scoring.is_none() {
+            for result in &mut results {
+                result.metadata = None;
+            }
+        }
+        Ok(Self::rank(results, request, scoring))
+    }
+    
+    /// Apply scoring modifiers, sort by score and cut to `top_k`
+    fn rank(mut results: Vec<SearchResult>, request: &SearchRequest, scoring: Option<&ScoringModifiers>) -> Vec<SearchResult> {
+        if let Some(scoring) = scoring {
+            // Every document is a candidate here, so rescore them all before cutting to top_k
+            scoring.apply(&mut results, request.top_k, chrono::Utc::now());
+            if !request.include_metadata {
+                for result in &mut results {
+                    result.metadata = None;
                 }
-                
-                // Include content if available
-                result = result.with_content(document.content.clone());
-                
-                results.push(result);
             }
+            return results;
         }
         
         // Sort by score (descending)
@@ -217,6 +526,179 @@ impl MemoryIndex {
         // Limit to top_k
         results.truncate(request.top_k);
         
-        Ok(results)
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index(quantization: QuantizationType) -> MemoryIndex {
+        let config = MemoryConfig::default().with_quantization(quantization);
+        MemoryIndex::new(IndexConfig::new("docs", 64), &config).unwrap()
+    }
+
+    fn embedding(seed: usize) -> Vec<f32> {
+        (0..64).map(|i| (((i * 7 + seed * 13) % 17) as f32 - 8.0) / 8.0).collect()
+    }
+
+    #[test]
+    fn test_quantized_search_matches_full_precision_ranking() {
+        let mut full = index(QuantizationType::None);
+        let mut int8 = index(QuantizationType::Int8);
+        let mut binary = index(QuantizationType::Binary);
+        for seed in 0..20 {
+            let document = Document::new(format!("doc{}", seed), "text").with_embedding(embedding(seed));
+            full.upsert_document(document.clone()).unwrap();
+            int8.upsert_document(document.clone()).unwrap();
+            binary.upsert_document(document).unwrap();
+        }
+
+        assert!(full.memory_usage() > int8.memory_usage());
+        assert!(int8.memory_usage() > binary.memory_usage());
+
+        let request = SearchRequest::new("docs", embedding(5)).with_top_k(1);
+        for quantized in [&int8, &binary] {
+            assert_eq!(quantized.search(&request).unwrap()[0].id, "doc5");
+        }
+        assert!(int8.search(&request).unwrap()[0].score > 0.99);
+
+        // Stored embeddings come back dequantized
+        let restored = int8.get_document(&"doc3".to_string()).unwrap().unwrap();
+        let original = embedding(3);
+        assert!(restored.embedding.unwrap().iter().zip(&original).all(|(a, b)| (a - b).abs() < 0.01));
+    }
+
+    #[test]
+    fn test_truncated_search_with_full_rescoring() {
+        let truncation = TruncationConfig::new(16).with_full_rescoring(4);
+        let config = MemoryConfig::default().with_truncation(truncation);
+        let mut truncated = MemoryIndex::new(IndexConfig::new("docs", 64), &config).unwrap();
+        let mut full = index(QuantizationType::None);
+        for seed in 0..20 {
+            let document = Document::new(format!("doc{}", seed), "text").with_embedding(embedding(seed));
+            truncated.upsert_document(document.clone()).unwrap();
+            full.upsert_document(document).unwrap();
+        }
+
+        // Full rescoring restores the exact full-dimension ranking and scores
+        let request = SearchRequest::new("docs", embedding(7)).with_top_k(3);
+        let expected = full.search(&request).unwrap();
+        let results = truncated.search(&request).unwrap();
+        assert_eq!(results[0].id, "doc7");
+        assert!((results[0].score - expected[0].score).abs() < 1e-6);
+
+        let stored = truncated.get_document(&"doc7".to_string()).unwrap().unwrap();
+        assert_eq!(stored.embedding.unwrap().len(), 64);
+
+        let too_wide = MemoryConfig::default().with_truncation(TruncationConfig::new(128));
+        assert!(MemoryIndex::new(IndexConfig::new("docs", 64), &too_wide).is_err());
+    }
+    #[test]
+    fn test_sparse_and_hybrid_search() {
+        let mut index = index(QuantizationType::None);
+        for seed in 0..10 {
+            let sparse = SparseVector::new(vec![seed as u32, 100], vec![1.0, 0.1]).unwrap();
+            let document = Document::new(format!("doc{}", seed), "text")
+                .with_embedding(embedding(seed))
+                .with_sparse_embedding(sparse);
+            index.upsert_document(document).unwrap();
+        }
+
+        // The sparse query only matches the exact term of doc3
+        let term = SparseVector::new(vec![3], vec![2.0]).unwrap();
+        let results = index.search(&SearchRequest::new_sparse("docs", term.clone()).with_top_k(1)).unwrap();
+        assert_eq!(results[0].id, "doc3");
+        assert_eq!(results[0].score, 2.0);
+
+        // Dense query favours doc5, the sparse term pulls doc3 up next to it
+        let request = SearchRequest::new_hybrid("docs", embedding(5), term, FusionMethod::default()).with_top_k(2);
+        let ids: Vec<_> = index.search(&request).unwrap().into_iter().map(|r| r.id).collect();
+        assert!(ids.contains(&"doc5".to_string()));
+        assert!(ids.contains(&"doc3".to_string()));
+    }
+
+    #[test]
+    fn test_multi_vector_search_combines_fields() {
+        let mut index = index(QuantizationType::None);
+        let title = |x: f32| vec![x, 1.0 - x];
+        index.upsert_document(Document::new("a", "text")
+            .with_embedding(embedding(0))
+            .with_named_vector("title", title(1.0))
+            .with_named_vector("tokens", vec![vec![1.0, 0.0, 0.0], vec![0.0, 1.0, 0.0]])).unwrap();
+        index.upsert_document(Document::new("b", "text")
+            .with_embedding(embedding(1))
+            .with_named_vector("title", title(0.0))
+            .with_named_vector("tokens", vec![vec![0.0, 0.0, 1.0]])).unwrap();
+        index.upsert_document(Document::new("c", "text").with_embedding(embedding(2))).unwrap();
+
+        // Token vectors score by MaxSim: each query token takes its best match
+        let tokens = VectorFieldQuery::new("tokens", vec![vec![1.0, 0.0, 0.0], vec![0.0, 1.0, 0.0]]);
+        let results = index.search(&SearchRequest::new_multi_vector("docs", vec![tokens.clone()])).unwrap();
+        assert_eq!(results.iter().map(|r| r.id.as_str()).collect::<Vec<_>>(), vec!["a", "b"]);
+        assert!((results[0].score - 2.0).abs() < 1e-6);
+
+        // A heavily weighted title field overrides the token ranking
+        let queries = vec![tokens, VectorFieldQuery::new("title", title(0.0)).with_weight(10.0)];
+        let results = index.search(&SearchRequest::new_multi_vector("docs", queries)).unwrap();
+        assert_eq!(results[0].id, "b");
+
+        let ragged = Document::new("d", "text").with_named_vector("tokens", vec![vec![1.0], vec![1.0, 0.0]]);
+        assert!(index.upsert_document(ragged).is_err());
+    }
+
+    #[test]
+    fn test_list_documents_with_cursor_and_filter() {
+        let mut index = index(QuantizationType::None);
+        for seed in 0..10 {
+            let document = Document::new(format!("doc{}", seed), "text")
+                .with_embedding(embedding(seed))
+                .with_metadata("category", if seed % 3 == 0 { "news" } else { "blog" });
+            index.upsert_document(document).unwrap();
+        }
+
+        let filter = FilterCondition::eq("category", "blog");
+        let first = index.list_documents(None, 4, Some(&filter)).unwrap();
+        let ids: Vec<_> = first.documents.iter().map(|d| d.id.as_str()).collect();
+        assert_eq!(ids, vec!["doc1", "doc2", "doc4", "doc5"]);
+        assert_eq!(first.next_cursor.as_deref(), Some("doc5"));
+
+        // A document inserted before the cursor does not shift the next page
+        index.upsert_document(Document::new("doc0a", "text").with_embedding(embedding(0)).with_metadata("category", "blog")).unwrap();
+        let second = index.list_documents(first.next_cursor.as_deref(), 4, Some(&filter)).unwrap();
+        let ids: Vec<_> = second.documents.iter().map(|d| d.id.as_str()).collect();
+        assert_eq!(ids, vec!["doc7", "doc8"]);
+        assert!(second.next_cursor.is_none());
+        assert!(second.documents[0].embedding.is_some());
+    }
+
+    #[test]
+    fn test_aggregate_with_filter() {
+        let mut index = index(QuantizationType::None);
+        for seed in 0..10 {
+            let document = Document::new(format!("doc{}", seed), "text")
+                .with_embedding(embedding(seed))
+                .with_metadata("category", if seed % 3 == 0 { "news" } else { "blog" })
+                .with_metadata("views", seed as i64 * 10);
+            index.upsert_document(document).unwrap();
+        }
+
+        let aggregations = [
+            Aggregation::Count,
+            Aggregation::top_values("category", 1),
+            Aggregation::min("views"),
+            Aggregation::max("views"),
+            Aggregation::max("missing"),
+        ];
+        let filter = FilterCondition::gt("views", 20i64);
+        let response = index.aggregate(&aggregations, Some(&filter)).unwrap();
+        assert_eq!(response.results, vec![
+            AggregationResult::Count(7),
+            AggregationResult::Facet(vec![FacetCount { value: "blog".into(), count: 4 }]),
+            AggregationResult::Value(Some(MetadataValue::Integer(30))),
+            AggregationResult::Value(Some(MetadataValue::Integer(90))),
+            AggregationResult::Value(None),
+        ]);
     }
 }
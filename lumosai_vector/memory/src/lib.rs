@@ -11,6 +11,7 @@
 //! - **Advanced Filtering**: Complex filter conditions with AND/OR/NOT logic
 //! - **Thread Safe**: Full async support with efficient locking
 //! - **Memory Efficient**: Configurable capacity and memory management
+//! - **Persistence**: Optional memory-mapped snapshots that survive restarts
 //!
 //! ## Example
 //!
@@ -61,9 +62,11 @@ use lumosai_vector_core::prelude::*;
 
 mod storage;
 mod index;
+mod persistence;
 mod utils;
 
 pub use storage::MemoryVectorStorage;
+pub use persistence::PersistenceConfig;
 
 // Type alias for compatibility
 pub type MemoryVectorStore = MemoryVectorStorage;
@@ -80,6 +83,12 @@ pub struct MemoryConfig {
     pub enable_approximate: bool,
     /// Memory usage threshold for triggering cleanup
     pub memory_threshold_mb: Option<usize>,
+    /// Quantization of stored embeddings
+    pub quantization: QuantizationConfig,
+    /// Matryoshka truncation of stored embeddings
+    pub truncation: Option<TruncationConfig>,
+    /// Snapshot file the storage is loaded from and saved to
+    pub persistence: Option<PersistenceConfig>,
 }
 
 impl Default for MemoryConfig {
@@ -89,6 +98,9 @@ impl Default for MemoryConfig {
             max_vectors_per_index: None,
             enable_approximate: false,
             memory_threshold_mb: None,
+            quantization: QuantizationConfig::default(),
+            truncation: None,
+            persistence: None,
         }
     }
 }
@@ -122,4 +134,28 @@ impl MemoryConfig {
         self.memory_threshold_mb = Some(threshold_mb);
         self
     }
+    
+    /// Store embeddings quantized, cutting their memory 4x (int8) or 32x (binary)
+    pub fn with_quantization(mut self, quantization: QuantizationType) -> Self {
+        self.quantization = QuantizationConfig::new(quantization);
+        self
+    }
+    
+    /// Set the full quantization configuration
+    pub fn with_quantization_config(mut self, quantization: QuantizationConfig) -> Self {
+        self.quantization = quantization;
+        self
+    }
+    
+    /// Store only the leading dimensions of Matryoshka embeddings
+    pub fn with_truncation(mut self, truncation: TruncationConfig) -> Self {
+        self.truncation = Some(truncation);
+        self
+    }
+    
+    /// Load from and snapshot to a file, so the storage survives restarts
+    pub fn with_persistence(mut self, persistence: PersistenceConfig) -> Self {
+        self.persistence = Some(persistence);
+        self
+    }
 }
@@ -0,0 +1,239 @@
+//! Snapshot persistence for the memory storage
+//!
+//! A snapshot is a single file: a magic tag, the length of a JSON header holding the
+//! index configurations, aliases and documents without their embeddings, followed by
+//! every embedding as little-endian `f32`s. Snapshots are read through a memory map, so
+//! embeddings are copied from the page cache straight into the indexes instead of being
+//! parsed from JSON. Writes go to a temporary file that is renamed over the snapshot,
+//! so a crash while snapshotting leaves the previous snapshot intact.
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use memmap2::Mmap;
+use serde::{Deserialize, Serialize};
+
+use lumosai_vector_core::prelude::*;
+
+/// Tag at the start of every snapshot file, including the format version
+const MAGIC: &[u8; 8] = b"LUMOSMV1";
+
+/// Length of the fixed part preceding the header: magic tag and header length
+const PREAMBLE_LEN: usize = MAGIC.len() + 8;
+
+/// Snapshot persistence settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PersistenceConfig {
+    /// Snapshot file, loaded when the storage is created
+    pub path: PathBuf,
+    /// Write a snapshot automatically after this many write operations
+    pub snapshot_every: Option<usize>,
+}
+
+impl PersistenceConfig {
+    /// Persist to the snapshot file at `path`
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            snapshot_every: None,
+        }
+    }
+
+    /// Snapshot automatically after every `writes` write operations
+    pub fn with_snapshot_every(mut self, writes: usize) -> Self {
+        self.snapshot_every = Some(writes.max(1));
+        self
+    }
+}
+
+/// Contents of a snapshot
+#[derive(Debug, Default)]
+pub(crate) struct Snapshot {
+    /// Index configurations with their documents, embeddings included
+    pub indexes: Vec<(IndexConfig, Vec<Document>)>,
+    /// Index aliases
+    pub aliases: HashMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SnapshotHeader {
+    indexes: Vec<IndexEntry>,
+    aliases: HashMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct IndexEntry {
+    config: IndexConfig,
+    documents: Vec<DocumentEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct DocumentEntry {
+    /// Document without its embedding
+    document: Document,
+    /// Position of the embedding in the data section, in `f32`s
+    embedding: Option<(usize, usize)>,
+}
+
+/// Write `snapshot` to `path`, replacing any previous snapshot
+pub(crate) fn write_snapshot(path: &Path, snapshot: Snapshot) -> Result<()> {
+    let mut embeddings = Vec::new();
+    let mut offset = 0;
+    let indexes = snapshot
+        .indexes
+        .into_iter()
+        .map(|(config, documents)| {
+            let documents = documents
+                .into_iter()
+                .map(|mut document| {
+                    let embedding = document.embedding.take().map(|embedding| {
+                        let slot = (offset, embedding.len());
+                        offset += embedding.len();
+                        embeddings.push(embedding);
+                        slot
+                    });
+                    DocumentEntry { document, embedding }
+                })
+                .collect();
+            IndexEntry { config, documents }
+        })
+        .collect();
+    let header = serde_json::to_vec(&SnapshotHeader { indexes, aliases: snapshot.aliases })?;
+
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    let temp_path = path.with_extension("tmp");
+    let file = File::create(&temp_path)?;
+    let mut writer = BufWriter::new(&file);
+    writer.write_all(MAGIC)?;
+    writer.write_all(&(header.len() as u64).to_le_bytes())?;
+    writer.write_all(&header)?;
+    for embedding in embeddings {
+        for value in embedding {
+            writer.write_all(&value.to_le_bytes())?;
+        }
+    }
+    writer.flush()?;
+    drop(writer);
+    file.sync_all()?;
+    fs::rename(&temp_path, path)?;
+    Ok(())
+}
+
+/// Read the snapshot at `path`, or `None` if there is none yet
+pub(crate) fn read_snapshot(path: &Path) -> Result<Option<Snapshot>> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    // SAFETY: snapshots are only ever replaced by renaming a new file over them, never
+    // modified in place, so the mapped contents cannot change while they are read.
+    let mmap = unsafe { Mmap::map(&file)? };
+    let corrupt = |reason: &str| VectorError::Deserialization(format!("Corrupt snapshot {}: {}", path.display(), reason));
+
+    if mmap.len() < PREAMBLE_LEN || &mmap[..MAGIC.len()] != MAGIC {
+        return Err(corrupt("not a memory storage snapshot"));
+    }
+    let header_len = u64::from_le_bytes(mmap[MAGIC.len()..PREAMBLE_LEN].try_into().unwrap()) as usize;
+    let data_start = PREAMBLE_LEN
+        .checked_add(header_len)
+        .filter(|end| *end <= mmap.len())
+        .ok_or_else(|| corrupt("truncated header"))?;
+    let header: SnapshotHeader = serde_json::from_slice(&mmap[PREAMBLE_LEN..data_start])
+        .map_err(|e| VectorError::Deserialization(format!("Corrupt snapshot {}: {}", path.display(), e)))?;
+    let data = &mmap[data_start..];
+
+    let mut indexes = Vec::with_capacity(header.indexes.len());
+    for entry in header.indexes {
+        let mut documents = Vec::with_capacity(entry.documents.len());
+        for DocumentEntry { mut document, embedding } in entry.documents {
+            if let Some((offset, len)) = embedding {
+                let bytes = offset
+                    .checked_mul(4)
+                    .zip(offset.checked_add(len).and_then(|end| end.checked_mul(4)))
+                    .and_then(|(start, end)| data.get(start..end))
+                    .ok_or_else(|| corrupt("truncated embeddings"))?;
+                document.embedding = Some(
+                    bytes
+                        .chunks_exact(4)
+                        .map(|value| f32::from_le_bytes([value[0], value[1], value[2], value[3]]))
+                        .collect(),
+                );
+            }
+            documents.push(document);
+        }
+        indexes.push((entry.config, documents));
+    }
+
+    Ok(Some(Snapshot { indexes, aliases: header.aliases }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MemoryConfig, MemoryVectorStorage};
+
+    fn snapshot_path(test: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("lumos_memory_{}_{}.snapshot", test, std::process::id()))
+    }
+
+    async fn persistent(config: PersistenceConfig) -> MemoryVectorStorage {
+        MemoryVectorStorage::with_config(MemoryConfig::new().with_persistence(config)).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_survives_restart() {
+        let path = snapshot_path("restart");
+        let storage = persistent(PersistenceConfig::new(&path)).await;
+        storage.create_index(IndexConfig::new("docs", 3).with_metric(SimilarityMetric::Euclidean)).await.unwrap();
+        storage.create_index(IndexConfig::new("empty", 2)).await.unwrap();
+        storage.upsert_documents("docs", vec![
+            Document::new("a", "first").with_embedding(vec![1.0, 0.0, 0.5]).with_metadata("tag", "x"),
+            Document::new("b", "second").with_embedding(vec![0.0, 1.0, -0.25]),
+        ]).await.unwrap();
+        storage.create_alias("current", "docs").await.unwrap();
+        storage.snapshot().await.unwrap();
+
+        let restored = persistent(PersistenceConfig::new(&path)).await;
+        fs::remove_file(&path).unwrap();
+
+        let mut indexes = restored.list_indexes().await.unwrap();
+        indexes.sort();
+        assert_eq!(indexes, vec!["docs", "empty"]);
+        let info = restored.describe_index("current").await.unwrap();
+        assert_eq!((info.dimension, info.metric, info.vector_count), (3, SimilarityMetric::Euclidean, 2));
+
+        let documents = restored.get_documents("docs", vec!["a".to_string()], true).await.unwrap();
+        assert_eq!(documents[0].content, "first");
+        assert_eq!(documents[0].embedding, Some(vec![1.0, 0.0, 0.5]));
+        assert_eq!(documents[0].metadata.get("tag"), Some(&MetadataValue::String("x".to_string())));
+
+        let response = restored.search(SearchRequest::new("docs", vec![0.0, 1.0, -0.25]).with_top_k(1)).await.unwrap();
+        assert_eq!(response.results[0].id, "b");
+    }
+
+    #[tokio::test]
+    async fn test_automatic_snapshots_and_corrupt_files() {
+        let path = snapshot_path("auto");
+        let storage = persistent(PersistenceConfig::new(&path).with_snapshot_every(2)).await;
+        storage.create_index(IndexConfig::new("docs", 2)).await.unwrap();
+        assert!(!path.exists());
+        storage.upsert_documents("docs", vec![Document::new("a", "").with_embedding(vec![1.0, 0.0])]).await.unwrap();
+        assert!(path.exists());
+
+        let restored = persistent(PersistenceConfig::new(&path)).await;
+        assert_eq!(restored.describe_index("docs").await.unwrap().vector_count, 1);
+
+        fs::write(&path, b"LUMOSMV1 not a header").unwrap();
+        let result = MemoryVectorStorage::with_config(MemoryConfig::new().with_persistence(PersistenceConfig::new(&path))).await;
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(result, Err(VectorError::Deserialization(_))));
+
+        let in_memory = MemoryVectorStorage::new().await.unwrap();
+        assert!(matches!(in_memory.snapshot().await, Err(VectorError::InvalidConfig(_))));
+    }
+}
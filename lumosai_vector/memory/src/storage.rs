@@ -1,13 +1,16 @@
 //! Memory vector storage implementation
 
 use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
 use async_trait::async_trait;
 
 use lumosai_vector_core::prelude::*;
 use crate::{MemoryConfig, MemoryIndex};
+use crate::persistence::{self, Snapshot};
 
 /// High-performance in-memory vector storage
 pub struct MemoryVectorStorage {
@@ -15,12 +18,18 @@ pub struct MemoryVectorStorage {
     config: MemoryConfig,
     /// Indexes stored in memory
     indexes: Arc<RwLock<HashMap<String, MemoryIndex>>>,
+    /// Index aliases, mapped to the index they point at
+    aliases: Arc<RwLock<HashMap<String, String>>>,
     /// Storage statistics
     stats: Arc<RwLock<StorageStats>>,
     /// Performance monitor
     performance_monitor: Arc<PerformanceMonitor>,
     /// Search result cache
     search_cache: Arc<LRUCache<String, SearchResponse>>,
+    /// Write operations since the last snapshot
+    writes_since_snapshot: Arc<AtomicUsize>,
+    /// Serializes snapshot writes
+    snapshot_lock: Arc<Mutex<()>>,
 }
 
 /// Storage statistics
@@ -59,13 +68,20 @@ impl MemoryVectorStorage {
             stats_interval: std::time::Duration::from_secs(60),
         };
 
-        Ok(Self {
+        let storage = Self {
             config,
             indexes: Arc::new(RwLock::new(HashMap::new())),
+            aliases: Arc::new(RwLock::new(HashMap::new())),
             stats: Arc::new(RwLock::new(StorageStats::default())),
             performance_monitor: Arc::new(PerformanceMonitor::new()),
             search_cache: Arc::new(LRUCache::new(cache_config)),
-        })
+            writes_since_snapshot: Arc::new(AtomicUsize::new(0)),
+            snapshot_lock: Arc::new(Mutex::new(())),
+        };
+        if let Some(persistence) = &storage.config.persistence {
+            storage.load_snapshot(&persistence.path).await?;
+        }
+        Ok(storage)
     }
     
     /// Get storage statistics
@@ -93,6 +109,80 @@ impl MemoryVectorStorage {
         Ok(())
     }
 
+    /// Write a snapshot to the configured persistence file
+    pub async fn snapshot(&self) -> Result<()> {
+        let persistence = self.config.persistence.as_ref()
+            .ok_or_else(|| VectorError::InvalidConfig("Memory storage has no persistence configured".to_string()))?;
+        self.writes_since_snapshot.store(0, Ordering::Relaxed);
+        self.snapshot_to(&persistence.path).await
+    }
+    
+    /// Write a snapshot of all indexes and aliases to `path`
+    pub async fn snapshot_to(&self, path: impl AsRef<Path>) -> Result<()> {
+        let _guard = self.snapshot_lock.lock().await;
+        let snapshot = {
+            let indexes = self.indexes.read().await;
+            let mut names: Vec<&String> = indexes.keys().collect();
+            names.sort();
+            Snapshot {
+                indexes: names.into_iter().map(|name| (indexes[name].config().clone(), indexes[name].documents())).collect(),
+                aliases: self.aliases.read().await.clone(),
+            }
+        };
+        persistence::write_snapshot(path.as_ref(), snapshot)
+    }
+    
+    /// Load the snapshot at `path`, if one has been written
+    async fn load_snapshot(&self, path: &Path) -> Result<()> {
+        let Some(snapshot) = persistence::read_snapshot(path)? else {
+            return Ok(());
+        };
+        
+        let mut indexes = self.indexes.write().await;
+        let mut stats = self.stats.write().await;
+        for (config, documents) in snapshot.indexes {
+            let mut index = MemoryIndex::new(config.clone(), &self.config)?;
+            for document in documents {
+                index.upsert_document(document)?;
+            }
+            stats.index_count += 1;
+            stats.total_vectors += index.vector_count();
+            stats.memory_usage_bytes += index.memory_usage();
+            indexes.insert(config.name, index);
+        }
+        *self.aliases.write().await = snapshot.aliases;
+        Ok(())
+    }
+    
+    /// Count a write operation, snapshotting once the configured number is reached
+    ///
+    /// Must be called after the write's locks are released.
+    async fn record_write(&self) -> Result<()> {
+        let Some(every) = self.config.persistence.as_ref().and_then(|persistence| persistence.snapshot_every) else {
+            return Ok(());
+        };
+        if self.writes_since_snapshot.fetch_add(1, Ordering::Relaxed) + 1 >= every {
+            self.snapshot().await?;
+        }
+        Ok(())
+    }
+    
+    /// Check that `alias` can point at `index_name`
+    fn check_alias_target(indexes: &HashMap<String, MemoryIndex>, alias: &str, index_name: &str) -> Result<()> {
+        if indexes.contains_key(alias) {
+            return Err(VectorError::InvalidConfig(format!("Alias {} conflicts with an existing index", alias)));
+        }
+        if !indexes.contains_key(index_name) {
+            return Err(VectorError::index_not_found(index_name));
+        }
+        Ok(())
+    }
+    
+    /// Resolve an alias to the index it points at; other names are returned unchanged
+    async fn resolve(&self, name: &str) -> String {
+        self.aliases.read().await.get(name).cloned().unwrap_or_else(|| name.to_string())
+    }
+
     /// Get performance metrics
     pub async fn get_performance_metrics(&self) -> lumosai_vector_core::PerformanceMetrics {
         self.performance_monitor.get_metrics().await
@@ -111,7 +201,7 @@ impl VectorStorage for MemoryVectorStorage {
     async fn create_index(&self, config: IndexConfig) -> Result<()> {
         let mut indexes = self.indexes.write().await;
         
-        if indexes.contains_key(&config.name) {
+        if indexes.contains_key(&config.name) || self.aliases.read().await.contains_key(&config.name) {
             return Err(VectorError::index_already_exists(&config.name));
         }
         
@@ -119,10 +209,10 @@ impl VectorStorage for MemoryVectorStorage {
         indexes.insert(config.name.clone(), index);
         
         // Update stats
-        let mut stats = self.stats.write().await;
-        stats.index_count += 1;
+        self.stats.write().await.index_count += 1;
+        drop(indexes);
         
-        Ok(())
+        self.record_write().await
     }
     
     async fn list_indexes(&self) -> Result<Vec<String>> {
@@ -131,6 +221,7 @@ impl VectorStorage for MemoryVectorStorage {
     }
     
     async fn describe_index(&self, index_name: &str) -> Result<IndexInfo> {
+        let index_name = &self.resolve(index_name).await;
         let indexes = self.indexes.read().await;
         let index = indexes.get(index_name)
             .ok_or_else(|| VectorError::index_not_found(index_name))?;
@@ -146,17 +237,21 @@ impl VectorStorage for MemoryVectorStorage {
         }
         
         let removed_index = indexes.remove(index_name).unwrap();
+        // Aliases of a deleted index would dangle, so they go with it
+        self.aliases.write().await.retain(|_, target| target != index_name);
         
         // Update stats
         let mut stats = self.stats.write().await;
         stats.index_count -= 1;
         stats.total_vectors -= removed_index.vector_count();
         stats.memory_usage_bytes -= removed_index.memory_usage();
+        drop((stats, indexes));
         
-        Ok(())
+        self.record_write().await
     }
     
     async fn upsert_documents(&self, index_name: &str, documents: Vec<Document>) -> Result<Vec<DocumentId>> {
+        let index_name = &self.resolve(index_name).await;
         let mut indexes = self.indexes.write().await;
         let index = indexes.get_mut(index_name)
             .ok_or_else(|| VectorError::index_not_found(index_name))?;
@@ -195,18 +290,22 @@ impl VectorStorage for MemoryVectorStorage {
         let mut stats = self.stats.write().await;
         stats.total_vectors += vectors_added;
         stats.memory_usage_bytes += memory_added;
+        drop((stats, indexes));
         
+        self.record_write().await?;
         Ok(document_ids)
     }
     
     async fn search(&self, request: SearchRequest) -> Result<SearchResponse> {
         let start_time = Instant::now();
+        let index_name = self.resolve(&request.index_name).await;
 
         // Generate cache key for the search request
-        let cache_key = format!("{}_{}_{}",
-            request.index_name,
+        let cache_key = format!("{}_{}_{}_{}",
+            index_name,
             request.top_k,
-            serde_json::to_string(&request.query).unwrap_or_default()
+            serde_json::to_string(&request.query).unwrap_or_default(),
+            serde_json::to_string(&request.scoring).unwrap_or_default()
         );
 
         // Check cache first
@@ -217,8 +316,8 @@ impl VectorStorage for MemoryVectorStorage {
         }
 
         let indexes = self.indexes.read().await;
-        let index = indexes.get(&request.index_name)
-            .ok_or_else(|| VectorError::index_not_found(&request.index_name))?;
+        let index = indexes.get(&index_name)
+            .ok_or_else(|| VectorError::index_not_found(&index_name))?;
 
         let results = index.search(&request)?;
 
@@ -245,6 +344,7 @@ impl VectorStorage for MemoryVectorStorage {
     }
     
     async fn update_document(&self, index_name: &str, document: Document) -> Result<()> {
+        let index_name = &self.resolve(index_name).await;
         let mut indexes = self.indexes.write().await;
         let index = indexes.get_mut(index_name)
             .ok_or_else(|| VectorError::index_not_found(index_name))?;
@@ -256,10 +356,13 @@ impl VectorStorage for MemoryVectorStorage {
         }
         
         index.update_document(document)?;
-        Ok(())
+        drop(indexes);
+        
+        self.record_write().await
     }
     
     async fn delete_documents(&self, index_name: &str, ids: Vec<DocumentId>) -> Result<()> {
+        let index_name = &self.resolve(index_name).await;
         let mut indexes = self.indexes.write().await;
         let index = indexes.get_mut(index_name)
             .ok_or_else(|| VectorError::index_not_found(index_name))?;
@@ -278,11 +381,13 @@ impl VectorStorage for MemoryVectorStorage {
         let mut stats = self.stats.write().await;
         stats.total_vectors -= vectors_removed;
         stats.memory_usage_bytes = stats.memory_usage_bytes.saturating_sub(memory_freed);
+        drop((stats, indexes));
         
-        Ok(())
+        self.record_write().await
     }
     
     async fn get_documents(&self, index_name: &str, ids: Vec<DocumentId>, include_vectors: bool) -> Result<Vec<Document>> {
+        let index_name = &self.resolve(index_name).await;
         let indexes = self.indexes.read().await;
         let index = indexes.get(index_name)
             .ok_or_else(|| VectorError::index_not_found(index_name))?;
@@ -300,6 +405,65 @@ impl VectorStorage for MemoryVectorStorage {
         Ok(documents)
     }
     
+    async fn list_documents(
+        &self,
+        index_name: &str,
+        cursor: Option<String>,
+        limit: usize,
+        filter: Option<FilterCondition>,
+    ) -> Result<DocumentPage> {
+        let index_name = &self.resolve(index_name).await;
+        let indexes = self.indexes.read().await;
+        let index = indexes.get(index_name)
+            .ok_or_else(|| VectorError::index_not_found(index_name))?;
+        
+        index.list_documents(cursor.as_deref(), limit, filter.as_ref())
+    }
+    
+    async fn aggregate(&self, request: AggregateRequest) -> Result<AggregateResponse> {
+        let index_name = &self.resolve(&request.index_name).await;
+        let indexes = self.indexes.read().await;
+        let index = indexes.get(index_name)
+            .ok_or_else(|| VectorError::index_not_found(index_name))?;
+        
+        index.aggregate(&request.aggregations, request.filter.as_ref())
+    }
+    
+    async fn create_alias(&self, alias: &str, index_name: &str) -> Result<()> {
+        let indexes = self.indexes.read().await;
+        Self::check_alias_target(&indexes, alias, index_name)?;
+        
+        let mut aliases = self.aliases.write().await;
+        if aliases.contains_key(alias) {
+            return Err(VectorError::alias_already_exists(alias));
+        }
+        aliases.insert(alias.to_string(), index_name.to_string());
+        drop((aliases, indexes));
+        
+        self.record_write().await
+    }
+    
+    async fn swap_alias(&self, alias: &str, index_name: &str) -> Result<()> {
+        let indexes = self.indexes.read().await;
+        Self::check_alias_target(&indexes, alias, index_name)?;
+        
+        self.aliases.write().await.insert(alias.to_string(), index_name.to_string());
+        drop(indexes);
+        
+        self.record_write().await
+    }
+    
+    async fn delete_alias(&self, alias: &str) -> Result<()> {
+        self.aliases.write().await.remove(alias)
+            .ok_or_else(|| VectorError::alias_not_found(alias))?;
+        
+        self.record_write().await
+    }
+    
+    async fn list_aliases(&self) -> Result<HashMap<String, String>> {
+        Ok(self.aliases.read().await.clone())
+    }
+    
     async fn health_check(&self) -> Result<()> {
         // Check if we can acquire locks
         let _indexes = self.indexes.read().await;
@@ -324,8 +488,14 @@ impl VectorStorage for MemoryVectorStorage {
             .with_feature("thread_safe")
             .with_feature("complex_filtering")
             .with_feature("multiple_metrics")
+            .with_feature("aliases")
+            .with_feature("aggregations")
+            .with_feature("document_listing")
+            .with_feature("multi_vector")
             .with_metadata("initial_capacity", MetadataValue::Integer(self.config.initial_capacity as i64))
             .with_metadata("approximate_search", MetadataValue::Boolean(self.config.enable_approximate))
+            .with_metadata("quantization", format!("{:?}", self.config.quantization.quantization).to_lowercase())
+            .with_metadata("persistent", MetadataValue::Boolean(self.config.persistence.is_some()))
     }
 }
 
@@ -334,9 +504,12 @@ impl Clone for MemoryVectorStorage {
         Self {
             config: self.config.clone(),
             indexes: Arc::clone(&self.indexes),
+            aliases: Arc::clone(&self.aliases),
             stats: Arc::clone(&self.stats),
             performance_monitor: Arc::clone(&self.performance_monitor),
             search_cache: Arc::clone(&self.search_cache),
+            writes_since_snapshot: Arc::clone(&self.writes_since_snapshot),
+            snapshot_lock: Arc::clone(&self.snapshot_lock),
         }
     }
 }
@@ -119,6 +119,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         include_metadata: true,
         include_vectors: false,
         options: HashMap::new(),
+        scoring: None,
     };
     
     match storage.search(search_request).await {
@@ -153,6 +154,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         include_metadata: true,
         include_vectors: false,
         options: HashMap::new(),
+        scoring: None,
     };
     
     match storage.search(filtered_search_request).await {
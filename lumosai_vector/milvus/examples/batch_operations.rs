@@ -140,6 +140,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             include_metadata: false, // Faster without metadata
             include_vectors: false,
             options: std::collections::HashMap::new(),
+            scoring: None,
         };
         
         match storage.search(search_request).await {
@@ -214,6 +215,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             include_metadata: true,
             include_vectors: false,
             options: std::collections::HashMap::new(),
+            scoring: None,
         };
         
         match storage.search(search_request).await {
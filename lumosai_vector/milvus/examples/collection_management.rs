@@ -146,6 +146,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             include_metadata: true,
             include_vectors: false,
             options: std::collections::HashMap::new(),
+            scoring: None,
         };
         
         let start_time = std::time::Instant::now();
@@ -218,6 +219,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             include_metadata: false,
             include_vectors: false,
             options: std::collections::HashMap::new(),
+            scoring: None,
         };
         
         match storage.search(search_request).await {
@@ -24,6 +24,13 @@ impl MilvusClient {
         
         Ok(Self { connection })
     }
+
+    /// Create a new Milvus client using `builder` for its HTTP client
+    pub async fn with_client_builder(config: MilvusConfig, builder: reqwest::ClientBuilder) -> MilvusResult<Self> {
+        let connection = MilvusConnection::with_client_builder(config, builder).await?;
+
+        Ok(Self { connection })
+    }
     
     /// Get the connection
     pub fn connection(&self) -> &MilvusConnection {
@@ -213,6 +220,7 @@ impl MilvusClient {
         // Prepare field data
         let mut ids = Vec::new();
         let mut vectors = Vec::new();
+        let mut sparse_vectors = Vec::new();
         let mut contents = Vec::new();
         let mut metadata_json = Vec::new();
         
@@ -220,12 +228,16 @@ impl MilvusClient {
             ids.push(entity.id.clone());
             vectors.push(entity.vector.clone());
             contents.push(entity.content.clone());
+            // Rows without a sparse embedding get an empty sparse vector
+            sparse_vectors.push(entity.sparse_vector.as_ref()
+                .map(crate::utils::sparse_vector_to_milvus)
+                .unwrap_or_else(|| serde_json::json!({})));
             
             let metadata_str = serde_json::to_string(&entity.metadata)?;
             metadata_json.push(metadata_str);
         }
         
-        let fields_data = vec![
+        let mut fields_data = vec![
             FieldData {
                 field_name: "id".to_string(),
                 field_type: DataType::VarChar,
@@ -248,6 +260,18 @@ impl MilvusClient {
             },
         ];
         
+        if self.connection.config().collection_config.sparse_vectors {
+            fields_data.push(FieldData {
+                field_name: SPARSE_VECTOR_FIELD.to_string(),
+                field_type: DataType::SparseFloatVector,
+                field: serde_json::Value::Array(sparse_vectors),
+            });
+        } else if entities.iter().any(|entity| entity.sparse_vector.is_some()) {
+            return Err(MilvusError::InvalidConfiguration(
+                "Sparse vectors are disabled for this client; enable collection_config.sparse_vectors".to_string()
+            ));
+        }
+        
         let request = InsertRequest {
             collection_name: collection_name.to_string(),
             fields_data,
@@ -280,12 +304,10 @@ impl MilvusClient {
         output_fields: &[String],
         expr: Option<&str>,
     ) -> MilvusResult<SearchResponse> {
-        let url = format!("{}/v1/vector/search", self.connection.config().endpoint);
-        
         let request = SearchRequest {
             collection_name: collection_name.to_string(),
             vector_field_name: "vector".to_string(),
-            vectors: vectors.to_vec(),
+            vectors: vectors.iter().map(serde_json::to_value).collect::<Result<_, _>>()?,
             search_params: SearchParams {
                 metric_type: metric_type.to_string(),
                 params,
@@ -295,6 +317,62 @@ impl MilvusClient {
             expr: expr.map(|s| s.to_string()),
         };
         
+        self.execute_search(request).await
+    }
+    
+    /// Search the sparse vector field by inner product
+    pub async fn search_sparse(
+        &self,
+        collection_name: &str,
+        vectors: &[lumosai_vector_core::sparse::SparseVector],
+        limit: usize,
+        params: serde_json::Value,
+        output_fields: &[String],
+        expr: Option<&str>,
+    ) -> MilvusResult<SearchResponse> {
+        let request = SearchRequest {
+            collection_name: collection_name.to_string(),
+            vector_field_name: SPARSE_VECTOR_FIELD.to_string(),
+            vectors: vectors.iter().map(crate::utils::sparse_vector_to_milvus).collect(),
+            search_params: SearchParams {
+                metric_type: "IP".to_string(),
+                params,
+            },
+            limit,
+            output_fields: output_fields.to_vec(),
+            expr: expr.map(|s| s.to_string()),
+        };
+        
+        self.execute_search(request).await
+    }
+    
+    /// Hybrid search over several vector fields with server-side reranking
+    pub async fn hybrid_search(&self, request: HybridSearchRequest) -> MilvusResult<HybridSearchResponse> {
+        let url = format!("{}/v2/vectordb/entities/hybrid_search", self.connection.config().endpoint);
+        
+        let response = self.connection
+            .post(&url)
+            .json(&request)
+            .send()
+            .await?;
+        
+        if response.status().is_success() {
+            let search_response: HybridSearchResponse = response.json().await?;
+            
+            if search_response.code == 0 {
+                Ok(search_response)
+            } else {
+                Err(MilvusError::Query(format!("Hybrid search failed: {}", search_response.message)))
+            }
+        } else {
+            let error_text = response.text().await.unwrap_or_default();
+            Err(MilvusError::Query(format!("Failed to run hybrid search: {}", error_text)))
+        }
+    }
+    
+    async fn execute_search(&self, request: SearchRequest) -> MilvusResult<SearchResponse> {
+        let url = format!("{}/v1/vector/search", self.connection.config().endpoint);
+        
         let response = self.connection
             .post(&url)
             .json(&request)
@@ -315,6 +393,81 @@ impl MilvusClient {
         }
     }
     
+    /// Create an alias for a collection
+    pub async fn create_alias(&self, alias: &str, collection_name: &str) -> MilvusResult<()> {
+        self.alias_operation("create", alias, Some(collection_name)).await?;
+        tracing::info!("Alias '{}' created for collection '{}'", alias, collection_name);
+        Ok(())
+    }
+    
+    /// Repoint an existing alias to another collection
+    pub async fn alter_alias(&self, alias: &str, collection_name: &str) -> MilvusResult<()> {
+        self.alias_operation("alter", alias, Some(collection_name)).await?;
+        tracing::info!("Alias '{}' now points at collection '{}'", alias, collection_name);
+        Ok(())
+    }
+    
+    /// Drop an alias
+    pub async fn drop_alias(&self, alias: &str) -> MilvusResult<()> {
+        self.alias_operation("drop", alias, None).await?;
+        Ok(())
+    }
+    
+    /// Collection an alias points at
+    pub async fn describe_alias(&self, alias: &str) -> MilvusResult<String> {
+        let data = self.alias_operation("describe", alias, None).await?;
+        data.get("collectionName")
+            .and_then(|name| name.as_str())
+            .map(str::to_string)
+            .ok_or_else(|| MilvusError::NotFound(format!("Alias '{}' not found", alias)))
+    }
+    
+    /// List alias names
+    pub async fn list_aliases(&self) -> MilvusResult<Vec<String>> {
+        let url = format!("{}/v2/vectordb/aliases/list", self.connection.config().endpoint);
+        let response = self.connection
+            .post(&url)
+            .json(&serde_json::json!({}))
+            .send()
+            .await?;
+        
+        let data = Self::alias_response(response, "list aliases").await?;
+        Ok(data.as_array()
+            .map(|aliases| aliases.iter().filter_map(|a| a.as_str().map(str::to_string)).collect())
+            .unwrap_or_default())
+    }
+    
+    async fn alias_operation(&self, operation: &str, alias: &str, collection_name: Option<&str>) -> MilvusResult<serde_json::Value> {
+        let url = format!("{}/v2/vectordb/aliases/{}", self.connection.config().endpoint, operation);
+        let request = AliasRequest {
+            alias_name: alias.to_string(),
+            collection_name: collection_name.map(str::to_string),
+        };
+        
+        let response = self.connection
+            .post(&url)
+            .json(&request)
+            .send()
+            .await?;
+        
+        Self::alias_response(response, &format!("{} alias '{}'", operation, alias)).await
+    }
+    
+    async fn alias_response(response: reqwest::Response, action: &str) -> MilvusResult<serde_json::Value> {
+        if response.status().is_success() {
+            let alias_response: AliasResponse = response.json().await?;
+            
+            if alias_response.code == 0 {
+                Ok(alias_response.data)
+            } else {
+                Err(MilvusError::Collection(format!("Failed to {}: {}", action, alias_response.message)))
+            }
+        } else {
+            let error_text = response.text().await.unwrap_or_default();
+            Err(MilvusError::Collection(format!("Failed to {}: {}", action, error_text)))
+        }
+    }
+    
     /// Query entities
     pub async fn query(
         &self,
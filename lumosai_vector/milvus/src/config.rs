@@ -161,6 +161,10 @@ pub struct CollectionConfig {
     
     /// Resource groups
     pub resource_groups: Vec<String>,
+    
+    /// Add a sparse vector field to new collections for sparse and hybrid search
+    #[serde(default)]
+    pub sparse_vectors: bool,
 }
 
 /// Consistency level for Milvus operations
@@ -249,6 +253,7 @@ impl Default for CollectionConfig {
             shards_num: 1,
             replica_number: 1,
             resource_groups: vec!["default".to_string()],
+            sparse_vectors: false,
         }
     }
 }
@@ -316,6 +321,12 @@ impl MilvusConfig {
         self
     }
     
+    /// Enable the sparse vector field for sparse and hybrid search
+    pub fn with_sparse_vectors(mut self, enabled: bool) -> Self {
+        self.collection_config.sparse_vectors = enabled;
+        self
+    }
+    
     /// Validate the configuration
     pub fn validate(&self) -> MilvusResult<()> {
         if self.endpoint.is_empty() {
@@ -415,6 +426,12 @@ impl MilvusConfigBuilder {
         self
     }
     
+    /// Enable the sparse vector field
+    pub fn sparse_vectors(mut self, enabled: bool) -> Self {
+        self.config.collection_config.sparse_vectors = enabled;
+        self
+    }
+    
     /// Set replica number
     pub fn replica_number(mut self, replica_number: usize) -> Self {
         self.config.collection_config.replica_number = replica_number;
@@ -53,7 +53,7 @@ pub mod error;
 pub mod client;
 pub mod types;
 
-pub use storage::MilvusStorage;
+pub use storage::{MilvusConnector, MilvusStorage};
 pub use config::{MilvusConfig, MilvusConfigBuilder};
 pub use error::{MilvusError, MilvusResult};
 pub use client::MilvusClient;
@@ -63,6 +63,9 @@ pub use types::*;
 pub use lumosai_vector_core::types::*;
 pub use lumosai_vector_core::traits::VectorStorage;
 
+/// Creates the HTTP client builder for new connections, e.g. with a proxy or private CA
+pub type ClientBuilderFn = Arc<dyn Fn() -> reqwest::ClientBuilder + Send + Sync>;
+
 /// Milvus client for managing connections and databases
 #[derive(Clone)]
 pub struct MilvusConnection {
@@ -79,7 +82,14 @@ pub struct MilvusConnection {
 impl MilvusConnection {
     /// Create a new Milvus connection
     pub async fn new(config: MilvusConfig) -> MilvusResult<Self> {
-        let client = reqwest::Client::builder()
+        Self::with_client_builder(config, reqwest::Client::builder()).await
+    }
+
+    /// Create a new Milvus connection using `builder` for its HTTP client
+    ///
+    /// The configured timeout overrides any timeout set on the builder.
+    pub async fn with_client_builder(config: MilvusConfig, builder: reqwest::ClientBuilder) -> MilvusResult<Self> {
+        let client = builder
             .timeout(config.timeout)
             .build()
             .map_err(|e| MilvusError::Connection(e.to_string()))?;
@@ -210,6 +220,7 @@ pub async fn create_milvus_storage_with_config(config: MilvusConfig) -> MilvusRe
 /// Utility functions for Milvus operations
 pub mod utils {
     use super::*;
+    use lumosai_vector_core::sparse::SparseVector;
     use lumosai_vector_core::types::{Document, MetadataValue};
     
     /// Convert LumosAI documents to Milvus entities
@@ -224,6 +235,7 @@ pub mod utils {
                 id: doc.id.clone(),
                 vector: embedding.clone(),
                 content: doc.content.clone(),
+                sparse_vector: doc.sparse_embedding.clone(),
                 metadata: doc.metadata.clone(),
             };
             
@@ -238,11 +250,23 @@ pub mod utils {
         entities.iter().map(|entity| {
             let mut document = Document::new(&entity.id, &entity.content);
             document.embedding = Some(entity.vector.clone());
+            document.sparse_embedding = entity.sparse_vector.clone();
             document.metadata = entity.metadata.clone();
             document
         }).collect()
     }
     
+    /// Convert a sparse vector to Milvus' `{index: value}` object form
+    pub fn sparse_vector_to_milvus(sparse: &SparseVector) -> serde_json::Value {
+        let entries: serde_json::Map<String, serde_json::Value> = sparse
+            .indices
+            .iter()
+            .zip(&sparse.values)
+            .map(|(index, value)| (index.to_string(), serde_json::json!(value)))
+            .collect();
+        serde_json::Value::Object(entries)
+    }
+    
     /// Convert metadata value to Milvus-compatible value
     pub fn metadata_value_to_milvus(value: &MetadataValue) -> serde_json::Value {
         match value {
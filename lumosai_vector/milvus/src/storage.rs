@@ -1,26 +1,76 @@
 //! Milvus storage implementation
 
 use std::collections::HashMap;
+use std::sync::Arc;
 use async_trait::async_trait;
 
 use lumosai_vector_core::{
+    aggregation::{AggregateRequest, AggregateResponse, AggregationResult, Aggregation, Aggregator},
     traits::{VectorStorage, BackendInfo},
     types::*,
-    error::Result,
+    sparse::FusionMethod,
+    error::{Result, VectorError},
+    connection::{BackoffConfig, ConnectionManager, ConnectionManagerConfig, Connector},
 };
 
 use crate::{
     config::MilvusConfig,
     error::{MilvusError, MilvusResult},
     client::MilvusClient,
-    types::{CollectionSchema, MilvusEntity},
+    types::{
+        AnnSearchRequest, CollectionSchema, HybridSearchRequest, HybridSearchResponse, MilvusEntity,
+        QueryResponse, RerankStrategy, SearchResponse as MilvusSearchResponse, SPARSE_VECTOR_FIELD,
+    },
     utils,
+    ClientBuilderFn,
 };
 
+/// Rows fetched per query when aggregating client-side
+const AGGREGATE_PAGE_SIZE: usize = 1000;
+
+/// Opens Milvus clients for the [`ConnectionManager`]
+pub struct MilvusConnector {
+    config: MilvusConfig,
+    client_builder: Option<ClientBuilderFn>,
+}
+
+impl MilvusConnector {
+    /// Create a connector for the given configuration
+    pub fn new(config: MilvusConfig) -> Self {
+        Self { config, client_builder: None }
+    }
+
+    /// Build HTTP clients from `client_builder` instead of the reqwest defaults
+    pub fn with_client_builder(mut self, client_builder: ClientBuilderFn) -> Self {
+        self.client_builder = Some(client_builder);
+        self
+    }
+}
+
+#[async_trait]
+impl Connector for MilvusConnector {
+    type Connection = MilvusClient;
+
+    async fn connect(&self, _manager: &ConnectionManagerConfig) -> Result<MilvusClient> {
+        match &self.client_builder {
+            Some(client_builder) => Ok(MilvusClient::with_client_builder(self.config.clone(), client_builder()).await?),
+            None => Ok(MilvusClient::new(self.config.clone()).await?),
+        }
+    }
+
+    async fn probe(&self, client: &MilvusClient) -> Result<()> {
+        if client.health_check().await? {
+            Ok(())
+        } else {
+            Err(VectorError::ConnectionFailed("Milvus service is not healthy".to_string()))
+        }
+    }
+}
+
 /// Milvus vector storage implementation
 pub struct MilvusStorage {
-    /// Milvus client
-    client: MilvusClient,
+    /// Connection manager owning the Milvus client
+    connections: Arc<ConnectionManager<MilvusConnector>>,
     
     /// Configuration
     config: MilvusConfig,
@@ -29,16 +79,44 @@ pub struct MilvusStorage {
 impl MilvusStorage {
     /// Create a new Milvus storage instance
     pub async fn new(config: MilvusConfig) -> MilvusResult<Self> {
+        let connector = MilvusConnector::new(config.clone());
+        Self::with_connector(config, connector).await
+    }
+
+    /// Create a new Milvus storage instance whose HTTP clients come from `client_builder`
+    pub async fn with_client_builder(config: MilvusConfig, client_builder: ClientBuilderFn) -> MilvusResult<Self> {
+        let connector = MilvusConnector::new(config.clone()).with_client_builder(client_builder);
+        Self::with_connector(config, connector).await
+    }
+
+    async fn with_connector(config: MilvusConfig, connector: MilvusConnector) -> MilvusResult<Self> {
         config.validate()?;
         
-        let client = MilvusClient::new(config.clone()).await?;
+        let performance = &config.performance;
+        let manager_config = ConnectionManagerConfig::default()
+            .with_pool_size(0, performance.max_parallel_requests as u32)
+            .with_acquire_timeout(performance.request_timeout)
+            .with_backoff(BackoffConfig {
+                initial_delay: performance.retry_config.initial_delay,
+                max_delay: performance.retry_config.max_delay,
+                multiplier: performance.retry_config.backoff_multiplier,
+                max_retries: performance.retry_config.max_retries as u32,
+            });
+        let connections = ConnectionManager::new(connector, manager_config)
+            .await
+            .map_err(|e| MilvusError::Connection(e.to_string()))?;
         
-        Ok(Self { client, config })
+        Ok(Self { connections: Arc::new(connections), config })
+    }
+    
+    /// Get the current client
+    pub async fn client(&self) -> MilvusClient {
+        self.connections.connection().await
     }
     
-    /// Get the client
-    pub fn client(&self) -> &MilvusClient {
-        &self.client
+    /// Connection manager shared by all operations
+    pub fn connection_manager(&self) -> &Arc<ConnectionManager<MilvusConnector>> {
+        &self.connections
     }
     
     /// Get the configuration
@@ -123,6 +201,134 @@ impl MilvusStorage {
         }
     }
     
+    /// Error for sparse queries or documents when the sparse field is disabled
+    fn ensure_sparse_enabled(&self) -> Result<()> {
+        if self.config.collection_config.sparse_vectors {
+            Ok(())
+        } else {
+            Err(VectorError::NotSupported(
+                "Sparse vectors are disabled; enable them with MilvusConfig::with_sparse_vectors".to_string()
+            ))
+        }
+    }
+    
+    /// Reranking strategy for a hybrid query
+    fn rerank_strategy(fusion: FusionMethod) -> RerankStrategy {
+        match fusion {
+            FusionMethod::ReciprocalRank { k } => RerankStrategy {
+                strategy: "rrf".to_string(),
+                params: serde_json::json!({ "k": k }),
+            },
+            FusionMethod::Weighted { dense_weight } => {
+                let dense_weight = dense_weight.clamp(0.0, 1.0);
+                RerankStrategy {
+                    strategy: "weighted".to_string(),
+                    params: serde_json::json!({ "weights": [dense_weight, 1.0 - dense_weight] }),
+                }
+            }
+        }
+    }
+    
+    /// Convert a v1 search response to search results
+    fn convert_search_response(response: &MilvusSearchResponse, request: &SearchRequest) -> Vec<SearchResult> {
+        let mut results = Vec::new();
+
+        if let Some(ids) = &response.results.ids.str_id {
+            for (i, id) in ids.data.iter().enumerate() {
+                let score = response.results.scores.get(i).copied().unwrap_or(0.0);
+
+                let mut result = SearchResult::new(id.clone(), score);
+
+                if request.include_metadata {
+                    result.metadata = Some(HashMap::new());
+                }
+
+                if request.include_vectors {
+                    // Would need to extract vector from response
+                }
+
+                results.push(result);
+            }
+        }
+
+        results
+    }
+    
+    /// Convert hybrid search rows to search results
+    fn convert_hybrid_response(response: HybridSearchResponse, request: &SearchRequest) -> Vec<SearchResult> {
+        response.data.into_iter()
+            .filter_map(|row| {
+                let id = match row.get("id")? {
+                    serde_json::Value::String(id) => id.clone(),
+                    other => other.to_string(),
+                };
+                let score = row.get("distance").and_then(|d| d.as_f64()).unwrap_or(0.0) as f32;
+                let mut result = SearchResult::new(id, score);
+                if let Some(content) = row.get("content").and_then(|c| c.as_str()) {
+                    result = result.with_content(content);
+                }
+                if request.include_metadata {
+                    let metadata = row.get("metadata")
+                        .and_then(|m| match m {
+                            serde_json::Value::String(json) => serde_json::from_str(json).ok(),
+                            other => serde_json::from_value(other.clone()).ok(),
+                        })
+                        .unwrap_or_default();
+                    result = result.with_metadata(metadata);
+                }
+                Some(result)
+            })
+            .collect()
+    }
+    
+    /// Read the result of a `count(*)` query
+    fn count_from_response(response: &QueryResponse) -> Result<usize> {
+        response.fields_data.iter()
+            .find(|field| field.field_name == "count(*)")
+            .and_then(|field| match &field.field {
+                serde_json::Value::Array(values) => values.first().and_then(|v| v.as_u64()),
+                value => value.as_u64(),
+            })
+            .map(|count| count as usize)
+            .ok_or_else(|| VectorError::OperationFailed("Milvus count query returned no count".to_string()))
+    }
+    
+    /// Values of one column of a query response
+    fn column<'a>(response: &'a QueryResponse, name: &str) -> &'a [serde_json::Value] {
+        response.fields_data.iter()
+            .find(|field| field.field_name == name)
+            .and_then(|field| field.field.as_array())
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+    
+    /// Parse the metadata column of a query response
+    fn metadata_from_response(response: &QueryResponse) -> Vec<Metadata> {
+        Self::column(response, "metadata").iter()
+            .map(|row| match row {
+                serde_json::Value::String(json) => serde_json::from_str(json).unwrap_or_default(),
+                other => serde_json::from_value(other.clone()).unwrap_or_default(),
+            })
+            .collect()
+    }
+    
+    /// Rebuild documents from the id, content, vector and metadata columns of a query response
+    fn documents_from_response(response: &QueryResponse) -> Vec<Document> {
+        let contents = Self::column(response, "content");
+        let vectors = Self::column(response, "vector");
+        let metadata = Self::metadata_from_response(response);
+        Self::column(response, "id").iter()
+            .enumerate()
+            .filter_map(|(row, id)| {
+                let mut document = Document::new(id.as_str()?, contents.get(row).and_then(|c| c.as_str()).unwrap_or_default());
+                document.embedding = vectors.get(row)
+                    .and_then(|vector| serde_json::from_value(vector.clone()).ok());
+                document.metadata = metadata.get(row).cloned().unwrap_or_default();
+                Some(document)
+            })
+            .collect()
+    }
+    
     /// Convert filter condition to Milvus expression
     fn build_filter_expression(&self, filter: &FilterCondition) -> MilvusResult<String> {
         match filter {
@@ -229,15 +435,18 @@ impl VectorStorage for MilvusStorage {
     
     async fn create_index(&self, config: IndexConfig) -> Result<()> {
         // Check if collection already exists
-        if self.client.has_collection(&config.name).await.map_err(|e| lumosai_vector_core::error::VectorError::from(e))? {
+        if self.client().await.has_collection(&config.name).await.map_err(|e| lumosai_vector_core::error::VectorError::from(e))? {
             return Err(lumosai_vector_core::error::VectorError::IndexAlreadyExists(format!("Collection '{}' already exists", config.name)));
         }
 
         // Create collection schema
-        let schema = CollectionSchema::document_schema(&config.name, config.dimension);
+        let mut schema = CollectionSchema::document_schema(&config.name, config.dimension);
+        if self.config.collection_config.sparse_vectors {
+            schema = schema.with_sparse_field();
+        }
 
         // Create collection
-        self.client.create_collection(schema).await.map_err(|e| lumosai_vector_core::error::VectorError::from(e))?;
+        self.client().await.create_collection(schema).await.map_err(|e| lumosai_vector_core::error::VectorError::from(e))?;
         
         // Create index if auto-create is enabled
         if self.config.index_config.auto_create_index {
@@ -245,27 +454,33 @@ impl VectorStorage for MilvusStorage {
             let metric_type = self.similarity_to_metric_type(&config.metric);
             let params = self.build_index_params(&self.config.index_config.default_index_type);
             
-            self.client
+            self.client().await
                 .create_index(&config.name, "vector", index_type, metric_type, params)
                 .await
                 .map_err(|e| lumosai_vector_core::error::VectorError::from(e))?;
+            
+            if self.config.collection_config.sparse_vectors {
+                self.client().await
+                    .create_index(&config.name, SPARSE_VECTOR_FIELD, "SPARSE_INVERTED_INDEX", "IP", serde_json::json!({}))
+                    .await?;
+            }
         }
         
         Ok(())
     }
     
     async fn list_indexes(&self) -> Result<Vec<String>> {
-        let collections = self.client.list_collections().await.map_err(|e| lumosai_vector_core::error::VectorError::from(e))?;
+        let collections = self.client().await.list_collections().await.map_err(|e| lumosai_vector_core::error::VectorError::from(e))?;
         Ok(collections)
     }
     
     async fn describe_index(&self, index_name: &str) -> Result<IndexInfo> {
-        if !self.client.has_collection(index_name).await.map_err(|e| lumosai_vector_core::error::VectorError::from(e))? {
+        if !self.client().await.has_collection(index_name).await.map_err(|e| lumosai_vector_core::error::VectorError::from(e))? {
             return Err(MilvusError::not_found(format!("Collection '{}' not found", index_name)).into());
         }
         
-        let collection_info = self.client.describe_collection(index_name).await.map_err(|e| lumosai_vector_core::error::VectorError::from(e))?;
-        let stats = self.client.get_collection_stats(index_name).await.map_err(|e| lumosai_vector_core::error::VectorError::from(e))?;
+        let collection_info = self.client().await.describe_collection(index_name).await.map_err(|e| lumosai_vector_core::error::VectorError::from(e))?;
+        let stats = self.client().await.get_collection_stats(index_name).await.map_err(|e| lumosai_vector_core::error::VectorError::from(e))?;
         
         // Extract vector dimension from schema
         let dimension = collection_info.schema.fields
@@ -288,11 +503,11 @@ impl VectorStorage for MilvusStorage {
     }
     
     async fn delete_index(&self, index_name: &str) -> Result<()> {
-        if !self.client.has_collection(index_name).await.map_err(|e| lumosai_vector_core::error::VectorError::from(e))? {
+        if !self.client().await.has_collection(index_name).await.map_err(|e| lumosai_vector_core::error::VectorError::from(e))? {
             return Err(lumosai_vector_core::error::VectorError::IndexNotFound(format!("Collection '{}' not found", index_name)));
         }
 
-        self.client.drop_collection(index_name).await.map_err(|e| lumosai_vector_core::error::VectorError::from(e))?;
+        self.client().await.drop_collection(index_name).await.map_err(|e| lumosai_vector_core::error::VectorError::from(e))?;
         Ok(())
     }
 
@@ -301,7 +516,7 @@ impl VectorStorage for MilvusStorage {
             return Ok(Vec::new());
         }
 
-        if !self.client.has_collection(index_name).await.map_err(|e| lumosai_vector_core::error::VectorError::from(e))? {
+        if !self.client().await.has_collection(index_name).await.map_err(|e| lumosai_vector_core::error::VectorError::from(e))? {
             return Err(lumosai_vector_core::error::VectorError::IndexNotFound(format!("Collection '{}' not found", index_name)));
         }
 
@@ -310,6 +525,9 @@ impl VectorStorage for MilvusStorage {
             if doc.embedding.is_none() {
                 return Err(lumosai_vector_core::error::VectorError::InvalidVector(format!("Document '{}' missing embedding", doc.id)));
             }
+            if doc.sparse_embedding.is_some() {
+                self.ensure_sparse_enabled()?;
+            }
         }
 
         // Convert documents to Milvus entities
@@ -318,7 +536,7 @@ impl VectorStorage for MilvusStorage {
         // Insert entities in batches
         let batch_size = self.config.performance.batch_size;
         for chunk in entities.chunks(batch_size) {
-            self.client.insert(index_name, chunk).await.map_err(|e| lumosai_vector_core::error::VectorError::from(e))?;
+            self.client().await.insert(index_name, chunk).await.map_err(|e| lumosai_vector_core::error::VectorError::from(e))?;
         }
 
         // Return document IDs
@@ -326,18 +544,10 @@ impl VectorStorage for MilvusStorage {
     }
 
     async fn search(&self, request: SearchRequest) -> Result<SearchResponse> {
-        if !self.client.has_collection(&request.index_name).await.map_err(|e| lumosai_vector_core::error::VectorError::from(e))? {
+        if !self.client().await.has_collection(&request.index_name).await.map_err(|e| lumosai_vector_core::error::VectorError::from(e))? {
             return Err(lumosai_vector_core::error::VectorError::IndexNotFound(format!("Collection '{}' not found", request.index_name)));
         }
 
-        // Extract vector from query
-        let query_vector = match &request.query {
-            SearchQuery::Vector(vector) => vector.clone(),
-            SearchQuery::Text(_) => {
-                return Err(lumosai_vector_core::error::VectorError::OperationFailed("Text queries not supported yet".to_string()));
-            }
-        };
-
         let metric_type = "COSINE"; // Default metric
         let search_params = self.build_search_params(metric_type);
 
@@ -352,39 +562,72 @@ impl VectorStorage for MilvusStorage {
             .transpose()
             .map_err(|e| lumosai_vector_core::error::VectorError::from(e))?;
 
-        let search_response = self.client
-            .search(
-                &request.index_name,
-                &[query_vector],
-                request.top_k,
-                metric_type,
-                search_params,
-                &output_fields,
-                filter_expr.as_deref(),
-            )
-            .await
-            .map_err(|e| lumosai_vector_core::error::VectorError::from(e))?;
-
-        // Convert Milvus response to SearchResponse
-        let mut results = Vec::new();
-
-        if let Some(ids) = &search_response.results.ids.str_id {
-            for (i, id) in ids.data.iter().enumerate() {
-                let score = search_response.results.scores.get(i).copied().unwrap_or(0.0);
-
-                let mut result = SearchResult::new(id.clone(), score);
-
-                if request.include_metadata {
-                    result.metadata = Some(HashMap::new());
-                }
-
-                if request.include_vectors {
-                    // Would need to extract vector from response
-                }
-
-                results.push(result);
+        let results = match &request.query {
+            SearchQuery::Vector(query_vector) => {
+                let search_response = self.client().await
+                    .search(
+                        &request.index_name,
+                        std::slice::from_ref(query_vector),
+                        request.top_k,
+                        metric_type,
+                        search_params,
+                        &output_fields,
+                        filter_expr.as_deref(),
+                    )
+                    .await
+                    .map_err(|e| lumosai_vector_core::error::VectorError::from(e))?;
+                Self::convert_search_response(&search_response, &request)
             }
-        }
+            SearchQuery::Text(_) => {
+                return Err(lumosai_vector_core::error::VectorError::OperationFailed("Text queries not supported yet".to_string()));
+            }
+            SearchQuery::MultiVector(_) => {
+                return Err(VectorError::NotSupported("Multi-vector queries not supported by Milvus storage".to_string()));
+            }
+            SearchQuery::Sparse(sparse) => {
+                self.ensure_sparse_enabled()?;
+                let search_response = self.client().await
+                    .search_sparse(
+                        &request.index_name,
+                        std::slice::from_ref(sparse),
+                        request.top_k,
+                        serde_json::json!({}),
+                        &output_fields,
+                        filter_expr.as_deref(),
+                    )
+                    .await?;
+                Self::convert_search_response(&search_response, &request)
+            }
+            SearchQuery::Hybrid { dense, sparse, fusion } => {
+                self.ensure_sparse_enabled()?;
+                // Each field contributes more candidates than requested so reranking can reorder them
+                let candidates = request.top_k.saturating_mul(4);
+                let hybrid_request = HybridSearchRequest {
+                    collection_name: request.index_name.clone(),
+                    search: vec![
+                        AnnSearchRequest {
+                            data: vec![serde_json::to_value(dense).map_err(MilvusError::from)?],
+                            anns_field: "vector".to_string(),
+                            limit: candidates,
+                            filter: filter_expr.clone(),
+                            params: serde_json::json!({ "metric_type": metric_type, "params": search_params }),
+                        },
+                        AnnSearchRequest {
+                            data: vec![crate::utils::sparse_vector_to_milvus(sparse)],
+                            anns_field: SPARSE_VECTOR_FIELD.to_string(),
+                            limit: candidates,
+                            filter: filter_expr,
+                            params: serde_json::json!({ "metric_type": "IP" }),
+                        },
+                    ],
+                    rerank: Self::rerank_strategy(*fusion),
+                    limit: request.top_k,
+                    output_fields,
+                };
+                let response = self.client().await.hybrid_search(hybrid_request).await?;
+                Self::convert_hybrid_response(response, &request)
+            }
+        };
 
         let total_count = results.len();
         Ok(SearchResponse::new(results)
@@ -403,7 +646,7 @@ impl VectorStorage for MilvusStorage {
             return Ok(());
         }
 
-        if !self.client.has_collection(index_name).await.map_err(|e| lumosai_vector_core::error::VectorError::from(e))? {
+        if !self.client().await.has_collection(index_name).await.map_err(|e| lumosai_vector_core::error::VectorError::from(e))? {
             return Err(lumosai_vector_core::error::VectorError::index_not_found(format!("Collection '{}' not found", index_name)));
         }
 
@@ -412,7 +655,7 @@ impl VectorStorage for MilvusStorage {
         let delete_expr = format!("id in [{}]", ids_str.join(", "));
 
         // Execute delete
-        self.client
+        self.client().await
             .delete(index_name, &delete_expr)
             .await
             .map_err(|e| lumosai_vector_core::error::VectorError::from(e))?;
@@ -425,7 +668,7 @@ impl VectorStorage for MilvusStorage {
             return Ok(Vec::new());
         }
 
-        if !self.client.has_collection(index_name).await.map_err(|e| lumosai_vector_core::error::VectorError::from(e))? {
+        if !self.client().await.has_collection(index_name).await.map_err(|e| lumosai_vector_core::error::VectorError::from(e))? {
             return Err(lumosai_vector_core::error::VectorError::IndexNotFound(format!("Collection '{}' not found", index_name)));
         }
 
@@ -441,7 +684,7 @@ impl VectorStorage for MilvusStorage {
         };
 
         // Execute query
-        let query_response = self.client
+        let query_response = self.client().await
             .query(index_name, &query_expr, &output_fields, None, None)
             .await
             .map_err(|e| lumosai_vector_core::error::VectorError::from(e))?;
@@ -464,14 +707,123 @@ impl VectorStorage for MilvusStorage {
         Ok(documents)
     }
 
-    async fn health_check(&self) -> Result<()> {
-        let is_healthy = self.client.health_check().await.map_err(|e| lumosai_vector_core::error::VectorError::from(e))?;
+    /// Keyset pagination on the primary key; Milvus returns limited query results in key order
+    async fn list_documents(
+        &self,
+        index_name: &str,
+        cursor: Option<String>,
+        limit: usize,
+        filter: Option<FilterCondition>,
+    ) -> Result<DocumentPage> {
+        let client = self.client().await;
+        if !client.has_collection(index_name).await? {
+            return Err(VectorError::IndexNotFound(format!("Collection '{}' not found", index_name)));
+        }
 
-        if is_healthy {
-            Ok(())
+        let mut clauses = Vec::new();
+        if let Some(cursor) = &cursor {
+            clauses.push(format!("id > {}", self.format_value(&MetadataValue::String(cursor.clone()))?));
+        }
+        if let Some(filter) = &filter {
+            clauses.push(format!("({})", self.build_filter_expression(filter)?));
+        }
+        let output_fields = ["id", "content", "vector", "metadata"].map(String::from);
+
+        // Fetch one extra row to learn whether another page follows
+        let response = client
+            .query(index_name, &clauses.join(" and "), &output_fields, Some(limit + 1), None)
+            .await?;
+        let mut documents = Self::documents_from_response(&response);
+        documents.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let remaining = documents.len() > limit;
+        documents.truncate(limit);
+        let next_cursor = if remaining {
+            documents.last().map(|document| document.id.clone())
+        } else {
+            None
+        };
+        Ok(DocumentPage::new(documents, next_cursor))
+    }
+
+    /// Counts run natively; facets and min/max page through the metadata column, which
+    /// Milvus caps at 16384 rows (offset + limit) per query
+    async fn aggregate(&self, request: AggregateRequest) -> Result<AggregateResponse> {
+        let client = self.client().await;
+        if !client.has_collection(&request.index_name).await? {
+            return Err(VectorError::IndexNotFound(format!("Collection '{}' not found", request.index_name)));
+        }
+        let expr = match &request.filter {
+            Some(filter) => self.build_filter_expression(filter)?,
+            None => String::new(),
+        };
+
+        if request.aggregations.iter().all(|aggregation| *aggregation == Aggregation::Count) {
+            let response = client.query(&request.index_name, &expr, &["count(*)".to_string()], None, None).await?;
+            let count = Self::count_from_response(&response)?;
+            return Ok(AggregateResponse {
+                results: vec![AggregationResult::Count(count); request.aggregations.len()],
+            });
+        }
+
+        let mut aggregator = Aggregator::new(&request.aggregations);
+        let mut offset = 0;
+        loop {
+            let response = client
+                .query(&request.index_name, &expr, &["metadata".to_string()], Some(AGGREGATE_PAGE_SIZE), Some(offset))
+                .await?;
+            let rows = Self::metadata_from_response(&response);
+            for metadata in &rows {
+                aggregator.add(metadata);
+            }
+            if rows.len() < AGGREGATE_PAGE_SIZE {
+                break;
+            }
+            offset += rows.len();
+        }
+        Ok(aggregator.finish())
+    }
+
+    async fn create_alias(&self, alias: &str, index_name: &str) -> Result<()> {
+        let client = self.client().await;
+        if client.list_aliases().await?.iter().any(|name| name == alias) {
+            return Err(VectorError::alias_already_exists(alias));
+        }
+        client.create_alias(alias, index_name).await?;
+        Ok(())
+    }
+
+    async fn swap_alias(&self, alias: &str, index_name: &str) -> Result<()> {
+        let client = self.client().await;
+        if client.list_aliases().await?.iter().any(|name| name == alias) {
+            client.alter_alias(alias, index_name).await?;
         } else {
-            Err(lumosai_vector_core::error::VectorError::ConnectionFailed("Milvus service is not healthy".to_string()))
+            client.create_alias(alias, index_name).await?;
         }
+        Ok(())
+    }
+
+    async fn delete_alias(&self, alias: &str) -> Result<()> {
+        let client = self.client().await;
+        if !client.list_aliases().await?.iter().any(|name| name == alias) {
+            return Err(VectorError::alias_not_found(alias));
+        }
+        client.drop_alias(alias).await?;
+        Ok(())
+    }
+
+    async fn list_aliases(&self) -> Result<HashMap<String, String>> {
+        let client = self.client().await;
+        let mut aliases = HashMap::new();
+        for alias in client.list_aliases().await? {
+            let collection = client.describe_alias(&alias).await?;
+            aliases.insert(alias, collection);
+        }
+        Ok(aliases)
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        self.connections.health_check().await
     }
 
     fn backend_info(&self) -> BackendInfo {
@@ -483,9 +835,15 @@ impl VectorStorage for MilvusStorage {
             .with_feature("cloud_native")
             .with_feature("multi_tenancy")
             .with_feature("acid_transactions")
+            .with_feature("sparse_vectors")
+            .with_feature("hybrid_search")
+            .with_feature("aliases")
+            .with_feature("aggregations")
+            .with_feature("document_listing")
             .with_metadata("endpoint", self.config.endpoint.clone())
             .with_metadata("database", self.config.database.clone())
             .with_metadata("batch_size", self.config.performance.batch_size as i64)
             .with_metadata("consistency_level", format!("{:?}", self.config.collection_config.consistency_level))
+            .with_pool_metrics(&self.connections.metrics())
     }
 }
@@ -2,7 +2,10 @@
 
 use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
-use lumosai_vector_core::types::MetadataValue;
+use lumosai_vector_core::{sparse::SparseVector, types::MetadataValue};
+
+/// Name of the sparse vector field in collections with sparse vectors enabled
+pub const SPARSE_VECTOR_FIELD: &str = "sparse_vector";
 
 /// Milvus entity representing a document with vector and metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,6 +19,10 @@ pub struct MilvusEntity {
     /// Text content
     pub content: String,
     
+    /// Sparse vector embedding
+    #[serde(default)]
+    pub sparse_vector: Option<SparseVector>,
+    
     /// Metadata fields
     pub metadata: HashMap<String, MetadataValue>,
 }
@@ -108,6 +115,9 @@ pub enum DataType {
     
     /// Binary vector
     BinaryVector,
+    
+    /// Sparse float vector
+    SparseFloatVector,
 }
 
 /// Type parameters for fields
@@ -200,8 +210,8 @@ pub struct SearchRequest {
     /// Vector field name
     pub vector_field_name: String,
     
-    /// Search vectors
-    pub vectors: Vec<Vec<f32>>,
+    /// Search vectors: float arrays for dense fields, `{index: value}` objects for sparse fields
+    pub vectors: Vec<serde_json::Value>,
     
     /// Search parameters
     pub search_params: SearchParams,
@@ -216,6 +226,99 @@ pub struct SearchRequest {
     pub expr: Option<String>,
 }
 
+/// Hybrid search request, served by the v2 REST API
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HybridSearchRequest {
+    /// Collection name
+    pub collection_name: String,
+    
+    /// One ANN search per vector field
+    pub search: Vec<AnnSearchRequest>,
+    
+    /// How the per-field results are combined
+    pub rerank: RerankStrategy,
+    
+    /// Limit
+    pub limit: usize,
+    
+    /// Output fields
+    pub output_fields: Vec<String>,
+}
+
+/// ANN search over one vector field of a hybrid search
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnSearchRequest {
+    /// Query vectors
+    pub data: Vec<serde_json::Value>,
+    
+    /// Vector field name
+    pub anns_field: String,
+    
+    /// Number of candidates from this field
+    pub limit: usize,
+    
+    /// Filter expression
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+    
+    /// Search parameters
+    pub params: serde_json::Value,
+}
+
+/// Reranking strategy of a hybrid search
+#[derive(Debug, Serialize)]
+pub struct RerankStrategy {
+    /// Strategy name, `rrf` or `weighted`
+    pub strategy: String,
+    
+    /// Strategy parameters
+    pub params: serde_json::Value,
+}
+
+/// Alias operation request
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AliasRequest {
+    /// Alias name
+    pub alias_name: String,
+    
+    /// Collection the alias points at, for create and alter
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collection_name: Option<String>,
+}
+
+/// Response of an alias operation
+#[derive(Debug, Deserialize)]
+pub struct AliasResponse {
+    /// Status code, 0 on success
+    pub code: i32,
+    
+    /// Error message
+    #[serde(default)]
+    pub message: String,
+    
+    /// Operation result: alias names for list, an alias description for describe
+    #[serde(default)]
+    pub data: serde_json::Value,
+}
+
+/// Hybrid search response
+#[derive(Debug, Deserialize)]
+pub struct HybridSearchResponse {
+    /// Status code, 0 on success
+    pub code: i32,
+    
+    /// Error message
+    #[serde(default)]
+    pub message: String,
+    
+    /// Result rows with `id`, `distance` and the output fields
+    #[serde(default)]
+    pub data: Vec<serde_json::Map<String, serde_json::Value>>,
+}
+
 /// Search parameters
 #[derive(Debug, Serialize)]
 pub struct SearchParams {
@@ -407,10 +510,17 @@ impl MilvusEntity {
             id,
             vector,
             content,
+            sparse_vector: None,
             metadata: HashMap::new(),
         }
     }
     
+    /// Set the sparse vector embedding
+    pub fn with_sparse_vector(mut self, sparse_vector: SparseVector) -> Self {
+        self.sparse_vector = Some(sparse_vector);
+        self
+    }
+    
     /// Add metadata field
     pub fn with_metadata<K: Into<String>, V: Into<MetadataValue>>(mut self, key: K, value: V) -> Self {
         self.metadata.insert(key.into(), value.into());
@@ -435,6 +545,18 @@ impl CollectionSchema {
         self
     }
     
+    /// Add a sparse vector field for sparse and hybrid search
+    pub fn with_sparse_field(self) -> Self {
+        self.add_field(FieldSchema {
+            name: SPARSE_VECTOR_FIELD.to_string(),
+            data_type: DataType::SparseFloatVector,
+            is_primary_key: false,
+            auto_id: false,
+            description: "Sparse vector embedding".to_string(),
+            type_params: None,
+        })
+    }
+    
     /// Create a standard document schema with ID, vector, content, and metadata fields
     pub fn document_schema(name: &str, vector_dim: usize) -> Self {
         Self::new(name, "Document collection with vector embeddings")
@@ -519,4 +641,18 @@ mod tests {
         assert!(matches!(vector_field.data_type, DataType::FloatVector));
         assert_eq!(vector_field.type_params.as_ref().unwrap().dim, Some(384));
     }
+    #[test]
+    fn test_sparse_field_and_entity() {
+        let schema = CollectionSchema::document_schema("test_collection", 384).with_sparse_field();
+        let sparse_field = schema.fields.last().unwrap();
+        assert_eq!(sparse_field.name, SPARSE_VECTOR_FIELD);
+        assert!(matches!(sparse_field.data_type, DataType::SparseFloatVector));
+
+        let sparse = SparseVector::new(vec![3, 17], vec![0.5, 1.25]).unwrap();
+        let entity = MilvusEntity::new("id".to_string(), vec![1.0], String::new()).with_sparse_vector(sparse.clone());
+        assert_eq!(
+            crate::utils::sparse_vector_to_milvus(entity.sparse_vector.as_ref().unwrap()),
+            serde_json::json!({"3": 0.5, "17": 1.25})
+        );
+    }
 }
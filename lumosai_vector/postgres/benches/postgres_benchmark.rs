@@ -25,6 +25,8 @@ fn generate_documents(count: usize, dimension: usize) -> Vec<Document> {
                 id: format!("doc_{}", i),
                 content: format!("This is document number {} with some content for testing", i),
                 embedding: Some(embedding),
+                sparse_embedding: None,
+                vectors: HashMap::new(),
                 metadata,
             }
         })
@@ -0,0 +1,10 @@
+-- Registry of vector index tables and the schema version each one is at
+CREATE TABLE IF NOT EXISTS lumos_vector_indexes (
+    table_name TEXT PRIMARY KEY,
+    index_name TEXT NOT NULL,
+    dimension INTEGER NOT NULL,
+    metric TEXT NOT NULL DEFAULT 'cosine',
+    schema_version INTEGER NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    updated_at TIMESTAMPTZ DEFAULT NOW()
+);
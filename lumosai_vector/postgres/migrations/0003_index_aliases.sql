@@ -0,0 +1,9 @@
+-- Index aliases; each alias is keyed by the table name it would occupy so aliases
+-- share the namespace of index tables
+CREATE TABLE IF NOT EXISTS lumos_vector_aliases (
+    table_name TEXT PRIMARY KEY,
+    alias TEXT NOT NULL,
+    index_name TEXT NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    updated_at TIMESTAMPTZ DEFAULT NOW()
+);
@@ -2,6 +2,7 @@
 
 use std::time::Duration;
 use serde::{Deserialize, Serialize};
+use lumosai_vector_core::{ConnectionManagerConfig, ReplicaConfig};
 
 /// PostgreSQL vector storage configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,6 +18,14 @@ pub struct PostgresConfig {
     
     /// Performance settings
     pub performance: PerformanceConfig,
+    
+    /// Connection URLs of read replicas
+    #[serde(default)]
+    pub replicas: Vec<String>,
+    
+    /// Staleness tolerance for read replicas
+    #[serde(default)]
+    pub replica: ReplicaConfig,
 }
 
 /// Connection pool configuration
@@ -52,6 +61,9 @@ pub struct TableConfig {
     
     /// Whether to create indexes automatically
     pub auto_create_indexes: bool,
+    
+    /// Whether to apply pending schema migrations on connect
+    pub run_migrations: bool,
 }
 
 /// Performance configuration
@@ -121,6 +133,8 @@ impl Default for PostgresConfig {
             pool: PoolConfig::default(),
             table: TableConfig::default(),
             performance: PerformanceConfig::default(),
+            replicas: Vec::new(),
+            replica: ReplicaConfig::default(),
         }
     }
 }
@@ -137,6 +151,15 @@ impl Default for PoolConfig {
     }
 }
 
+impl PoolConfig {
+    /// Connection manager settings derived from the pool configuration
+    pub fn manager_config(&self) -> ConnectionManagerConfig {
+        ConnectionManagerConfig::default()
+            .with_pool_size(self.min_connections, self.max_connections)
+            .with_acquire_timeout(self.connect_timeout)
+    }
+}
+
 impl Default for TableConfig {
     fn default() -> Self {
         Self {
@@ -144,6 +167,7 @@ impl Default for TableConfig {
             table_prefix: Some("lumos_".to_string()),
             auto_create_tables: true,
             auto_create_indexes: true,
+            run_migrations: true,
         }
     }
 }
@@ -214,6 +238,32 @@ impl PostgresConfig {
         self
     }
     
+    /// Add a read replica
+    pub fn with_read_replica(mut self, database_url: impl Into<String>) -> Self {
+        self.replicas.push(database_url.into());
+        self
+    }
+    
+    /// Set the staleness tolerance for read replicas
+    pub fn with_replica_config(mut self, replica: ReplicaConfig) -> Self {
+        self.replica = replica;
+        self
+    }
+    
+    /// Configuration for connecting to one of the read replicas
+    ///
+    /// Replicas are read-only, so migrations and automatic table or index creation are
+    /// disabled.
+    pub fn replica_config(&self, database_url: impl Into<String>) -> Self {
+        let mut config = self.clone();
+        config.database_url = database_url.into();
+        config.replicas.clear();
+        config.table.run_migrations = false;
+        config.table.auto_create_tables = false;
+        config.table.auto_create_indexes = false;
+        config
+    }
+    
     /// Get the full table name with schema and prefix
     pub fn table_name(&self, name: &str) -> String {
         let prefix = self.table.table_prefix.as_deref().unwrap_or("");
@@ -0,0 +1,45 @@
+//! Connection management for PostgreSQL vector storage
+
+use async_trait::async_trait;
+use sqlx::{postgres::PgPoolOptions, PgPool};
+
+use lumosai_vector_core::prelude::*;
+use crate::{PostgresConfig, PostgresError};
+
+/// Opens sqlx pools for the [`ConnectionManager`]
+pub struct PgConnector {
+    config: PostgresConfig,
+}
+
+impl PgConnector {
+    /// Create a connector for the given configuration
+    pub fn new(config: PostgresConfig) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait]
+impl Connector for PgConnector {
+    type Connection = PgPool;
+
+    async fn connect(&self, manager: &ConnectionManagerConfig) -> Result<PgPool> {
+        let pool = PgPoolOptions::new()
+            .max_connections(manager.max_connections)
+            .min_connections(manager.min_connections)
+            .acquire_timeout(manager.acquire_timeout)
+            .idle_timeout(self.config.pool.idle_timeout)
+            .max_lifetime(self.config.pool.max_lifetime)
+            .connect(&self.config.database_url)
+            .await
+            .map_err(PostgresError::from)?;
+        Ok(pool)
+    }
+
+    async fn probe(&self, pool: &PgPool) -> Result<()> {
+        sqlx::query("SELECT 1")
+            .fetch_one(pool)
+            .await
+            .map_err(PostgresError::from)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,164 @@
+//! Translation of metadata filters into SQL conditions on the `metadata` JSONB column
+//!
+//! Field names and values are always bound as parameters. Conditions follow the
+//! semantics of the core filter evaluator: comparisons against a missing field are
+//! false, so `Ne` and `NotIn` match documents without the field.
+
+use sqlx::{Postgres, QueryBuilder};
+
+use lumosai_vector_core::prelude::*;
+use crate::storage::PostgresVectorStorage;
+
+/// Append ` WHERE <filter>` if there is a filter
+pub(crate) fn push_where(builder: &mut QueryBuilder<'_, Postgres>, filter: Option<&FilterCondition>) -> Result<()> {
+    if let Some(filter) = filter {
+        builder.push(" WHERE ");
+        push_condition(builder, filter)?;
+    }
+    Ok(())
+}
+
+/// Append a filter as a boolean SQL expression
+pub(crate) fn push_condition(builder: &mut QueryBuilder<'_, Postgres>, filter: &FilterCondition) -> Result<()> {
+    match filter {
+        FilterCondition::And(conditions) => push_junction(builder, conditions, " AND ", "true")?,
+        FilterCondition::Or(conditions) => push_junction(builder, conditions, " OR ", "false")?,
+        FilterCondition::Not(condition) => {
+            builder.push("NOT (");
+            push_condition(builder, condition)?;
+            builder.push(")");
+        }
+        // Leaves never evaluate to NULL, so NOT behaves like the in-memory evaluator
+        leaf => {
+            builder.push("COALESCE(");
+            push_leaf(builder, leaf)?;
+            builder.push(", false)");
+        }
+    }
+    Ok(())
+}
+
+fn push_junction(
+    builder: &mut QueryBuilder<'_, Postgres>,
+    conditions: &[FilterCondition],
+    separator: &str,
+    empty: &str,
+) -> Result<()> {
+    if conditions.is_empty() {
+        builder.push(empty);
+        return Ok(());
+    }
+    builder.push("(");
+    for (i, condition) in conditions.iter().enumerate() {
+        if i > 0 {
+            builder.push(separator);
+        }
+        push_condition(builder, condition)?;
+    }
+    builder.push(")");
+    Ok(())
+}
+
+fn push_leaf(builder: &mut QueryBuilder<'_, Postgres>, filter: &FilterCondition) -> Result<()> {
+    match filter {
+        FilterCondition::Eq(field, value) => {
+            push_field(builder, field, "->");
+            builder.push(" = ").push_bind(to_json(value)?);
+        }
+        FilterCondition::Ne(field, value) => {
+            push_field(builder, field, "->");
+            builder.push(" <> ").push_bind(to_json(value)?);
+            builder.push(" OR NOT (metadata ? ").push_bind(field.clone()).push(")");
+        }
+        FilterCondition::Gt(field, value) => push_numeric(builder, field, ">", value)?,
+        FilterCondition::Gte(field, value) => push_numeric(builder, field, ">=", value)?,
+        FilterCondition::Lt(field, value) => push_numeric(builder, field, "<", value)?,
+        FilterCondition::Lte(field, value) => push_numeric(builder, field, "<=", value)?,
+        FilterCondition::In(field, values) => push_membership(builder, field, values, "EXISTS")?,
+        FilterCondition::NotIn(field, values) => push_membership(builder, field, values, "NOT EXISTS")?,
+        FilterCondition::Exists(field) => {
+            builder.push("metadata ? ").push_bind(field.clone());
+        }
+        FilterCondition::NotExists(field) => {
+            builder.push("NOT (metadata ? ").push_bind(field.clone()).push(")");
+        }
+        FilterCondition::Contains(field, substring) => {
+            builder.push("jsonb_typeof(metadata -> ").push_bind(field.clone()).push(") = 'string' AND strpos(");
+            push_field(builder, field, "->>");
+            builder.push(", ").push_bind(substring.clone()).push(") > 0");
+        }
+        FilterCondition::StartsWith(field, prefix) => {
+            builder.push("jsonb_typeof(metadata -> ").push_bind(field.clone()).push(") = 'string' AND starts_with(");
+            push_field(builder, field, "->>");
+            builder.push(", ").push_bind(prefix.clone()).push(")");
+        }
+        FilterCondition::EndsWith(field, suffix) => {
+            builder.push("jsonb_typeof(metadata -> ").push_bind(field.clone()).push(") = 'string' AND right(");
+            push_field(builder, field, "->>");
+            builder.push(", length(").push_bind(suffix.clone()).push(")) = ").push_bind(suffix.clone());
+        }
+        FilterCondition::Regex(field, pattern) => {
+            builder.push("jsonb_typeof(metadata -> ").push_bind(field.clone()).push(") = 'string' AND ");
+            push_field(builder, field, "->>");
+            builder.push(" ~ ").push_bind(pattern.clone());
+        }
+        FilterCondition::And(_) | FilterCondition::Or(_) | FilterCondition::Not(_) => unreachable!("handled by push_condition"),
+    }
+    Ok(())
+}
+
+fn push_field(builder: &mut QueryBuilder<'_, Postgres>, field: &str, operator: &str) {
+    builder.push("(metadata ").push(operator).push(" ").push_bind(field.to_string()).push(")");
+}
+
+/// Numeric comparison; non-numeric field values never match
+fn push_numeric(builder: &mut QueryBuilder<'_, Postgres>, field: &str, operator: &str, value: &MetadataValue) -> Result<()> {
+    let value = match value {
+        MetadataValue::Integer(i) => *i as f64,
+        MetadataValue::Float(f) => *f,
+        _ => return Err(VectorError::InvalidFilter("Expected numeric value".to_string())),
+    };
+    builder.push("CASE WHEN jsonb_typeof(metadata -> ").push_bind(field.to_string()).push(") = 'number' THEN (");
+    push_field(builder, field, "->>");
+    builder.push(")::double precision ").push(operator).push(" ").push_bind(value).push(" ELSE false END");
+    Ok(())
+}
+
+fn push_membership(builder: &mut QueryBuilder<'_, Postgres>, field: &str, values: &[MetadataValue], exists: &str) -> Result<()> {
+    let values = values.iter().map(to_json).collect::<Result<Vec<_>>>()?;
+    builder
+        .push(exists)
+        .push(" (SELECT 1 FROM jsonb_array_elements(")
+        .push_bind(serde_json::Value::Array(values))
+        .push(") AS candidate(value) WHERE candidate.value = ");
+    push_field(builder, field, "->");
+    builder.push(")");
+    Ok(())
+}
+
+fn to_json(value: &MetadataValue) -> Result<serde_json::Value> {
+    Ok(PostgresVectorStorage::metadata_value_to_json(value)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filters_bind_fields_and_values() {
+        let filter = FilterCondition::And(vec![
+            FilterCondition::eq("lang", "en"),
+            FilterCondition::Not(Box::new(FilterCondition::gt("year", 2020i64))),
+        ]);
+        let mut builder = QueryBuilder::new("SELECT COUNT(*) FROM docs");
+        push_where(&mut builder, Some(&filter)).unwrap();
+        assert_eq!(
+            builder.sql(),
+            "SELECT COUNT(*) FROM docs WHERE (COALESCE((metadata -> $1) = $2, false) AND NOT (COALESCE(CASE WHEN \
+             jsonb_typeof(metadata -> $3) = 'number' THEN ((metadata ->> $4))::double precision > $5 ELSE false END, false)))"
+        );
+
+        let mut builder = QueryBuilder::new("");
+        assert!(push_where(&mut builder, Some(&FilterCondition::gt("year", "recent"))).is_err());
+    }
+}
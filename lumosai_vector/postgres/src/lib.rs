@@ -44,10 +44,15 @@
 pub mod storage;
 pub mod config;
 pub mod error;
+pub mod connection;
+pub mod migrations;
+mod filter;
 
 pub use storage::PostgresVectorStorage;
 pub use config::PostgresConfig;
+pub use connection::PgConnector;
 pub use error::{PostgresError, PostgresResult};
+pub use migrations::{DimensionChangePolicy, IndexSchema};
 
 // Re-export core types for convenience
 pub use lumosai_vector_core::prelude::*;
@@ -0,0 +1,394 @@
+//! Schema migrations for PostgreSQL vector storage
+//!
+//! Database-wide setup (the pgvector extension, the shared trigger function and the
+//! index registry) lives in versioned SQL files under `migrations/` and is applied with
+//! sqlx's migrator. Index tables are created per index with a runtime dimension, so
+//! their schema is versioned separately: each table has a row in `lumos_vector_indexes`
+//! recording its dimension and schema version, and [`ensure_index_table`] brings it up
+//! to [`INDEX_SCHEMA_VERSION`] under an advisory lock.
+
+use std::collections::HashMap;
+
+use sqlx::migrate::Migrator;
+use sqlx::{PgPool, Postgres, Row, Transaction};
+use tracing::{debug, info};
+
+use lumosai_vector_core::prelude::SimilarityMetric;
+use crate::{PostgresConfig, PostgresError, PostgresResult};
+
+/// Database-wide migrations embedded from `migrations/`
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// Current schema version of index tables
+pub const INDEX_SCHEMA_VERSION: i32 = 3;
+
+/// How to handle existing embeddings when an index changes dimension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DimensionChangePolicy {
+    /// Refuse the change while any document has an embedding
+    RequireEmpty,
+    /// Clear existing embeddings; documents must be re-embedded afterwards
+    ClearEmbeddings,
+}
+
+/// Registry entry for an index table
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexSchema {
+    /// Index name
+    pub index_name: String,
+    /// Embedding dimension
+    pub dimension: usize,
+    /// Similarity metric
+    pub metric: SimilarityMetric,
+    /// Schema version the table is at
+    pub schema_version: i32,
+}
+
+/// Apply pending database-wide migrations
+pub async fn run_migrations(pool: &PgPool) -> PostgresResult<()> {
+    MIGRATOR.run(pool).await.map_err(|e| {
+        let message = e.to_string();
+        if message.contains("extension \"vector\"") {
+            crate::error::pgvector_extension_error()
+        } else {
+            PostgresError::Migration(message)
+        }
+    })?;
+    debug!("Database migrations are up to date");
+    Ok(())
+}
+
+/// Name of a metric as stored in the registry
+pub(crate) fn metric_name(metric: SimilarityMetric) -> &'static str {
+    match metric {
+        SimilarityMetric::Cosine => "cosine",
+        SimilarityMetric::Euclidean => "euclidean",
+        SimilarityMetric::DotProduct => "dot_product",
+        SimilarityMetric::Manhattan => "manhattan",
+        SimilarityMetric::Hamming => "hamming",
+    }
+}
+
+fn parse_metric(name: &str) -> SimilarityMetric {
+    match name {
+        "euclidean" => SimilarityMetric::Euclidean,
+        "dot_product" => SimilarityMetric::DotProduct,
+        "manhattan" => SimilarityMetric::Manhattan,
+        "hamming" => SimilarityMetric::Hamming,
+        _ => SimilarityMetric::Cosine,
+    }
+}
+
+/// SQL statements that upgrade an index table from `version - 1` to `version`
+///
+/// Every step is idempotent so tables created before the registry existed can be
+/// adopted by replaying all steps.
+pub(crate) fn index_schema_step(config: &PostgresConfig, index_name: &str, dimension: usize, version: i32) -> Vec<String> {
+    let table_name = config.table_name(index_name);
+    match version {
+        1 => vec![format!(
+            "CREATE TABLE IF NOT EXISTS {} (
+                id TEXT PRIMARY KEY,
+                content TEXT,
+                embedding vector({}),
+                metadata JSONB DEFAULT '{{}}',
+                created_at TIMESTAMPTZ DEFAULT NOW(),
+                updated_at TIMESTAMPTZ DEFAULT NOW()
+            )",
+            table_name, dimension
+        )],
+        2 => vec![
+            format!("DROP TRIGGER IF EXISTS update_{}_updated_at ON {}", index_name, table_name),
+            format!(
+                "CREATE TRIGGER update_{}_updated_at BEFORE UPDATE ON {} FOR EACH ROW EXECUTE FUNCTION update_updated_at_column()",
+                index_name, table_name
+            ),
+        ],
+        3 => vec![format!(
+            "CREATE INDEX IF NOT EXISTS {} ON {} USING GIN (metadata)",
+            config.index_name(index_name, "metadata"), table_name
+        )],
+        _ => Vec::new(),
+    }
+}
+
+async fn lock_table(tx: &mut Transaction<'_, Postgres>, table_name: &str) -> PostgresResult<()> {
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtext($1))")
+        .bind(table_name)
+        .execute(&mut **tx)
+        .await?;
+    Ok(())
+}
+
+async fn registry_entry(tx: &mut Transaction<'_, Postgres>, table_name: &str) -> PostgresResult<Option<IndexSchema>> {
+    let row = sqlx::query(
+        "SELECT index_name, dimension, metric, schema_version FROM lumos_vector_indexes WHERE table_name = $1"
+    )
+    .bind(table_name)
+    .fetch_optional(&mut **tx)
+    .await?;
+
+    row.map(|row| -> PostgresResult<IndexSchema> {
+        let dimension: i32 = row.try_get("dimension")?;
+        let metric: String = row.try_get("metric")?;
+        Ok(IndexSchema {
+            index_name: row.try_get("index_name")?,
+            dimension: dimension as usize,
+            metric: parse_metric(&metric),
+            schema_version: row.try_get("schema_version")?,
+        })
+    })
+    .transpose()
+}
+
+/// Dimension of the embedding column of an existing table
+async fn column_dimension(tx: &mut Transaction<'_, Postgres>, table_name: &str) -> PostgresResult<Option<usize>> {
+    let row = sqlx::query(
+        "SELECT atttypmod FROM pg_attribute WHERE attrelid = to_regclass($1) AND attname = 'embedding' AND NOT attisdropped"
+    )
+    .bind(table_name)
+    .fetch_optional(&mut **tx)
+    .await?;
+
+    match row {
+        Some(row) => {
+            let typmod: i32 = row.try_get("atttypmod")?;
+            Ok((typmod > 0).then_some(typmod as usize))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Look up the registry entry for an index
+pub async fn index_schema(pool: &PgPool, config: &PostgresConfig, index_name: &str) -> PostgresResult<Option<IndexSchema>> {
+    let mut tx = pool.begin().await?;
+    let entry = registry_entry(&mut tx, &config.table_name(index_name)).await?;
+    tx.commit().await?;
+    Ok(entry)
+}
+
+/// Create or upgrade the table backing an index
+///
+/// Fails when the table already exists with a different dimension; use
+/// [`change_dimension`] to change it deliberately.
+pub async fn ensure_index_table(
+    pool: &PgPool,
+    config: &PostgresConfig,
+    index_name: &str,
+    dimension: usize,
+    metric: SimilarityMetric,
+) -> PostgresResult<()> {
+    let table_name = config.table_name(index_name);
+    let mut tx = pool.begin().await?;
+    lock_table(&mut tx, &table_name).await?;
+
+    let current = match registry_entry(&mut tx, &table_name).await? {
+        Some(entry) => Some((entry.dimension, entry.schema_version)),
+        // Tables created before the registry existed are adopted from version 0
+        None => column_dimension(&mut tx, &table_name).await?.map(|existing| (existing, 0)),
+    };
+
+    let from_version = match current {
+        Some((existing, _)) if existing != dimension => {
+            return Err(PostgresError::Migration(format!(
+                "Index '{}' has dimension {}, requested {}; change it explicitly with change_dimension",
+                index_name, existing, dimension
+            )));
+        }
+        Some((_, version)) => version,
+        None => 0,
+    };
+
+    for version in (from_version + 1)..=INDEX_SCHEMA_VERSION {
+        for sql in index_schema_step(config, index_name, dimension, version) {
+            sqlx::query(&sql).execute(&mut *tx).await?;
+        }
+        info!("Migrated index table {} to schema version {}", table_name, version);
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO lumos_vector_indexes (table_name, index_name, dimension, metric, schema_version)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (table_name) DO UPDATE SET schema_version = EXCLUDED.schema_version, updated_at = NOW()
+        "#
+    )
+    .bind(&table_name)
+    .bind(index_name)
+    .bind(dimension as i32)
+    .bind(metric_name(metric))
+    .bind(INDEX_SCHEMA_VERSION)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Change the embedding dimension of an index
+///
+/// The approximate-nearest-neighbour index on the embedding column is dropped because
+/// it is bound to the old dimension; callers recreate it afterwards.
+pub async fn change_dimension(
+    pool: &PgPool,
+    config: &PostgresConfig,
+    index_name: &str,
+    dimension: usize,
+    policy: DimensionChangePolicy,
+) -> PostgresResult<()> {
+    let table_name = config.table_name(index_name);
+    let mut tx = pool.begin().await?;
+    lock_table(&mut tx, &table_name).await?;
+
+    let current = registry_entry(&mut tx, &table_name).await?
+        .ok_or_else(|| crate::error::table_not_found_error(&table_name))?;
+    if current.dimension == dimension {
+        return Ok(());
+    }
+
+    if policy == DimensionChangePolicy::RequireEmpty {
+        let row = sqlx::query(&format!("SELECT COUNT(*) AS count FROM {} WHERE embedding IS NOT NULL", table_name))
+            .fetch_one(&mut *tx)
+            .await?;
+        let count: i64 = row.try_get("count")?;
+        if count > 0 {
+            return Err(PostgresError::Migration(format!(
+                "Index '{}' has {} embeddings of dimension {}; re-embed them or use DimensionChangePolicy::ClearEmbeddings",
+                index_name, count, current.dimension
+            )));
+        }
+    }
+
+    sqlx::query(&format!("DROP INDEX IF EXISTS {}.{}", config.table.schema, config.index_name(index_name, "embedding")))
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query(&format!("ALTER TABLE {} ALTER COLUMN embedding TYPE vector({}) USING NULL", table_name, dimension))
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("UPDATE lumos_vector_indexes SET dimension = $2, updated_at = NOW() WHERE table_name = $1")
+        .bind(&table_name)
+        .bind(dimension as i32)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    info!("Changed dimension of {} from {} to {}", table_name, current.dimension, dimension);
+    Ok(())
+}
+
+/// Remove an index and the aliases pointing at it from the registry after its table was dropped
+pub async fn forget_index(pool: &PgPool, config: &PostgresConfig, index_name: &str) -> PostgresResult<()> {
+    sqlx::query("DELETE FROM lumos_vector_indexes WHERE table_name = $1")
+        .bind(config.table_name(index_name))
+        .execute(pool)
+        .await?;
+    for (alias, target) in list_aliases(pool, config).await? {
+        if target == index_name {
+            drop_alias(pool, config, &alias).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Index an alias points at, or `name` itself if it is not an alias
+pub async fn resolve_alias(pool: &PgPool, config: &PostgresConfig, name: &str) -> PostgresResult<String> {
+    let row = sqlx::query("SELECT index_name FROM lumos_vector_aliases WHERE table_name = $1")
+        .bind(config.table_name(name))
+        .fetch_optional(pool)
+        .await
+        .map_err(PostgresError::from);
+
+    match row {
+        Ok(Some(row)) => Ok(row.try_get("index_name")?),
+        Ok(None) => Ok(name.to_string()),
+        // Databases that never ran the alias migration have no aliases
+        Err(e) if crate::error::is_table_not_found_error(&e) => Ok(name.to_string()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Point an alias at an index
+///
+/// Returns `false` without changing anything if the alias exists and `replace` is
+/// not set. Repointing is a single statement, so readers see either index, never none.
+pub async fn set_alias(
+    pool: &PgPool,
+    config: &PostgresConfig,
+    alias: &str,
+    index_name: &str,
+    replace: bool,
+) -> PostgresResult<bool> {
+    let mut tx = pool.begin().await?;
+    if registry_entry(&mut tx, &config.table_name(index_name)).await?.is_none() {
+        return Err(crate::error::table_not_found_error(&config.table_name(index_name)));
+    }
+    if registry_entry(&mut tx, &config.table_name(alias)).await?.is_some() {
+        return Err(PostgresError::Config(format!("Alias '{}' conflicts with an existing index", alias)));
+    }
+
+    let conflict = if replace {
+        "DO UPDATE SET index_name = EXCLUDED.index_name, updated_at = NOW()"
+    } else {
+        "DO NOTHING"
+    };
+    let result = sqlx::query(&format!(
+        "INSERT INTO lumos_vector_aliases (table_name, alias, index_name) VALUES ($1, $2, $3) ON CONFLICT (table_name) {}",
+        conflict
+    ))
+    .bind(config.table_name(alias))
+    .bind(alias)
+    .bind(index_name)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Delete an alias, returning whether it existed
+pub async fn drop_alias(pool: &PgPool, config: &PostgresConfig, alias: &str) -> PostgresResult<bool> {
+    let result = sqlx::query("DELETE FROM lumos_vector_aliases WHERE table_name = $1")
+        .bind(config.table_name(alias))
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Aliases of the configured schema and table prefix, mapped to their index
+pub async fn list_aliases(pool: &PgPool, config: &PostgresConfig) -> PostgresResult<HashMap<String, String>> {
+    let rows = sqlx::query("SELECT table_name, alias, index_name FROM lumos_vector_aliases")
+        .fetch_all(pool)
+        .await?;
+
+    let mut aliases = HashMap::new();
+    for row in rows {
+        let table_name: String = row.try_get("table_name")?;
+        let alias: String = row.try_get("alias")?;
+        if table_name == config.table_name(&alias) {
+            aliases.insert(alias, row.try_get("index_name")?);
+        }
+    }
+    Ok(aliases)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_index_schema_steps() {
+        let config = PostgresConfig::default();
+
+        let create = index_schema_step(&config, "docs", 768, 1);
+        assert!(create[0].contains("CREATE TABLE IF NOT EXISTS public.lumos_docs"));
+        assert!(create[0].contains("vector(768)"));
+        assert!(index_schema_step(&config, "docs", 768, 3)[0].contains("lumos_docs_metadata_idx"));
+
+        // Every version up to the current one has statements
+        assert!((1..=INDEX_SCHEMA_VERSION).all(|v| !index_schema_step(&config, "docs", 768, v).is_empty()));
+        assert!(index_schema_step(&config, "docs", 768, INDEX_SCHEMA_VERSION + 1).is_empty());
+
+        assert_eq!(parse_metric(metric_name(SimilarityMetric::DotProduct)), SimilarityMetric::DotProduct);
+        assert_eq!(MIGRATOR.iter().count(), 3);
+    }
+}
@@ -1,17 +1,21 @@
 //! PostgreSQL vector storage implementation
 
 use std::collections::HashMap;
+use std::sync::Arc;
 use async_trait::async_trait;
-use sqlx::{PgPool, Row, postgres::PgPoolOptions};
+use sqlx::{PgPool, Row};
 use serde_json::Value as JsonValue;
 use tracing::{debug, instrument, warn};
 
 use lumosai_vector_core::prelude::*;
+use crate::connection::PgConnector;
+use crate::filter;
+use crate::migrations::{self, DimensionChangePolicy, IndexSchema};
 use crate::{PostgresConfig, PostgresError, PostgresResult};
 
 /// PostgreSQL vector storage implementation using pgvector
 pub struct PostgresVectorStorage {
-    pool: PgPool,
+    connections: Arc<ConnectionManager<PgConnector>>,
     config: PostgresConfig,
 }
 
@@ -24,17 +28,16 @@ impl PostgresVectorStorage {
     
     /// Create a new PostgreSQL vector storage instance with configuration
     pub async fn with_config(config: PostgresConfig) -> Result<Self> {
-        let pool = PgPoolOptions::new()
-            .max_connections(config.pool.max_connections)
-            .min_connections(config.pool.min_connections)
-            .acquire_timeout(config.pool.connect_timeout)
-            .idle_timeout(config.pool.idle_timeout)
-            .max_lifetime(config.pool.max_lifetime)
-            .connect(&config.database_url)
-            .await
-            .map_err(PostgresError::from)?;
+        let connections = ConnectionManager::new(
+            PgConnector::new(config.clone()),
+            config.pool.manager_config(),
+        ).await?;
         
-        let storage = Self { pool, config };
+        let storage = Self { connections: Arc::new(connections), config };
+        
+        if storage.config.table.run_migrations {
+            storage.migrate().await?;
+        }
         
         // Check pgvector extension
         storage.ensure_pgvector_extension().await?;
@@ -42,10 +45,62 @@ impl PostgresVectorStorage {
         Ok(storage)
     }
     
+    /// Connect to the primary and the configured read replicas
+    ///
+    /// Searches and document reads are served by replicas within the configured
+    /// staleness tolerance; writes go to the primary.
+    pub async fn replicated(config: PostgresConfig) -> Result<ReplicatedStorage<Self>> {
+        let mut replicas = Vec::with_capacity(config.replicas.len());
+        for url in &config.replicas {
+            replicas.push(Self::with_config(config.replica_config(url)).await?);
+        }
+        let replica = config.replica.clone();
+        let primary = Self::with_config(PostgresConfig { replicas: Vec::new(), ..config }).await?;
+        Ok(ReplicatedStorage::new(primary, replicas, replica))
+    }
+    
+    /// Current connection pool
+    async fn pool(&self) -> PgPool {
+        self.connections.connection().await
+    }
+    
+    /// Connection manager shared by all operations
+    pub fn connection_manager(&self) -> &Arc<ConnectionManager<PgConnector>> {
+        &self.connections
+    }
+    
+    /// Apply pending database-wide schema migrations
+    pub async fn migrate(&self) -> Result<()> {
+        migrations::run_migrations(&self.pool().await).await?;
+        Ok(())
+    }
+    
+    /// Registry entry for an index, including its dimension and schema version
+    pub async fn index_schema(&self, index_name: &str) -> Result<Option<IndexSchema>> {
+        Ok(migrations::index_schema(&self.pool().await, &self.config, index_name).await?)
+    }
+    
+    /// Change the embedding dimension of an index and rebuild its vector index
+    pub async fn change_dimension(
+        &self,
+        index_name: &str,
+        dimension: usize,
+        policy: DimensionChangePolicy,
+    ) -> Result<()> {
+        migrations::change_dimension(&self.pool().await, &self.config, index_name, dimension, policy).await?;
+        self.ensure_vector_index(index_name).await?;
+        Ok(())
+    }
+    
+    /// Resolve an alias to the index it points at; other names are returned unchanged
+    async fn resolve(&self, name: &str) -> Result<String> {
+        Ok(migrations::resolve_alias(&self.pool().await, &self.config, name).await?)
+    }
+    
     /// Ensure pgvector extension is installed
     async fn ensure_pgvector_extension(&self) -> PostgresResult<()> {
         let result = sqlx::query("SELECT 1 FROM pg_extension WHERE extname = 'vector'")
-            .fetch_optional(&self.pool)
+            .fetch_optional(&self.pool().await)
             .await?;
         
         if result.is_none() {
@@ -55,56 +110,6 @@ impl PostgresVectorStorage {
         Ok(())
     }
     
-    /// Create table for an index if it doesn't exist
-    async fn ensure_table(&self, index_name: &str, dimension: usize) -> PostgresResult<()> {
-        let table_name = self.config.table_name(index_name);
-        
-        let create_table_sql = format!(
-            r#"
-            CREATE TABLE IF NOT EXISTS {} (
-                id TEXT PRIMARY KEY,
-                content TEXT,
-                embedding vector({}),
-                metadata JSONB DEFAULT '{{}}',
-                created_at TIMESTAMPTZ DEFAULT NOW(),
-                updated_at TIMESTAMPTZ DEFAULT NOW()
-            )
-            "#,
-            table_name, dimension
-        );
-        
-        sqlx::query(&create_table_sql)
-            .execute(&self.pool)
-            .await?;
-        
-        // Create updated_at trigger
-        let trigger_sql = format!(
-            r#"
-            CREATE OR REPLACE FUNCTION update_updated_at_column()
-            RETURNS TRIGGER AS $$
-            BEGIN
-                NEW.updated_at = NOW();
-                RETURN NEW;
-            END;
-            $$ language 'plpgsql';
-            
-            DROP TRIGGER IF EXISTS update_{}_updated_at ON {};
-            CREATE TRIGGER update_{}_updated_at
-                BEFORE UPDATE ON {}
-                FOR EACH ROW
-                EXECUTE FUNCTION update_updated_at_column();
-            "#,
-            index_name, table_name, index_name, table_name
-        );
-        
-        sqlx::query(&trigger_sql)
-            .execute(&self.pool)
-            .await?;
-        
-        debug!("Ensured table exists: {}", table_name);
-        Ok(())
-    }
-    
     /// Create vector index if configured
     async fn ensure_vector_index(&self, index_name: &str) -> PostgresResult<()> {
         if !self.config.table.auto_create_indexes {
@@ -120,7 +125,7 @@ impl PostgresVectorStorage {
         )
         .bind(format!("{}{}", self.config.table.table_prefix.as_deref().unwrap_or(""), index_name))
         .bind(&idx_name)
-        .fetch_optional(&self.pool)
+        .fetch_optional(&self.pool().await)
         .await?;
         
         if exists.is_some() {
@@ -132,7 +137,7 @@ impl PostgresVectorStorage {
         
         if !index_sql.is_empty() {
             sqlx::query(&index_sql)
-                .execute(&self.pool)
+                .execute(&self.pool().await)
                 .await
                 .map_err(|e| crate::error::index_creation_error(&idx_name, &e.to_string()))?;
             
@@ -186,7 +191,7 @@ impl PostgresVectorStorage {
     }
     
     /// Convert single metadata value to JSON
-    fn metadata_value_to_json(value: &MetadataValue) -> PostgresResult<JsonValue> {
+    pub(crate) fn metadata_value_to_json(value: &MetadataValue) -> PostgresResult<JsonValue> {
         match value {
             MetadataValue::String(s) => Ok(JsonValue::String(s.clone())),
             MetadataValue::Integer(i) => Ok(JsonValue::Number((*i).into())),
@@ -255,6 +260,69 @@ impl PostgresVectorStorage {
         }
     }
     
+    /// Compute one aggregation in SQL
+    async fn run_aggregation(
+        pool: &PgPool,
+        table_name: &str,
+        aggregation: &Aggregation,
+        filter: Option<&FilterCondition>,
+    ) -> Result<AggregationResult> {
+        let field = match aggregation {
+            Aggregation::Count => {
+                let mut query = sqlx::QueryBuilder::new(format!("SELECT COUNT(*) FROM {}", table_name));
+                filter::push_where(&mut query, filter)?;
+                let count: i64 = query.build_query_scalar().fetch_one(pool).await.map_err(PostgresError::from)?;
+                return Ok(AggregationResult::Count(count as usize));
+            }
+            Aggregation::Facet { field, .. } | Aggregation::Min(field) | Aggregation::Max(field) => field,
+        };
+
+        let mut query = sqlx::QueryBuilder::new("SELECT metadata -> ");
+        query.push_bind(field.clone());
+        query.push(match aggregation {
+            Aggregation::Facet { .. } => " AS value, COUNT(*) AS count FROM ",
+            _ => " AS value FROM ",
+        });
+        query.push(table_name).push(" WHERE metadata ? ").push_bind(field.clone());
+        if let Some(filter) = filter {
+            query.push(" AND ");
+            filter::push_condition(&mut query, filter)?;
+        }
+        match aggregation {
+            Aggregation::Facet { limit, .. } => {
+                query.push(" GROUP BY 1 ORDER BY 2 DESC, 1");
+                if let Some(limit) = limit {
+                    query.push(" LIMIT ").push_bind(*limit as i64);
+                }
+            }
+            Aggregation::Min(_) => {
+                query.push(" ORDER BY 1 ASC LIMIT 1");
+            }
+            _ => {
+                query.push(" ORDER BY 1 DESC LIMIT 1");
+            }
+        }
+
+        let rows = query.build().fetch_all(pool).await.map_err(PostgresError::from)?;
+        if !matches!(aggregation, Aggregation::Facet { .. }) {
+            let value = match rows.first() {
+                Some(row) => Self::json_value_to_metadata_value(row.try_get("value").map_err(PostgresError::from)?),
+                None => None,
+            };
+            return Ok(AggregationResult::Value(value));
+        }
+
+        let mut facets = Vec::with_capacity(rows.len());
+        for row in rows {
+            let value: JsonValue = row.try_get("value").map_err(PostgresError::from)?;
+            let count: i64 = row.try_get("count").map_err(PostgresError::from)?;
+            if let Some(value) = Self::json_value_to_metadata_value(value) {
+                facets.push(FacetCount { value, count: count as usize });
+            }
+        }
+        Ok(AggregationResult::Facet(facets))
+    }
+    
     /// Set search parameters for the current session
     async fn set_search_params(&self) -> PostgresResult<()> {
         let params = self.config.performance.index_type
@@ -262,7 +330,7 @@ impl PostgresVectorStorage {
 
         for param_sql in params {
             sqlx::query(&param_sql)
-                .execute(&self.pool)
+                .execute(&self.pool().await)
                 .await?;
         }
 
@@ -276,7 +344,7 @@ impl VectorStorage for PostgresVectorStorage {
 
     #[instrument(skip(self))]
     async fn create_index(&self, config: IndexConfig) -> Result<()> {
-        self.ensure_table(&config.name, config.dimension).await?;
+        migrations::ensure_index_table(&self.pool().await, &self.config, &config.name, config.dimension, config.metric).await?;
         self.ensure_vector_index(&config.name).await?;
 
         debug!("Created PostgreSQL index: {}", config.name);
@@ -289,13 +357,14 @@ impl VectorStorage for PostgresVectorStorage {
         let schema = &self.config.table.schema;
 
         let query = format!(
-            "SELECT table_name FROM information_schema.tables WHERE table_schema = $1 AND table_name LIKE $2"
+            "SELECT table_name FROM information_schema.tables WHERE table_schema = $1 AND table_name LIKE $2 \
+             AND table_name NOT IN ('lumos_vector_indexes', 'lumos_vector_aliases', '_sqlx_migrations')"
         );
 
         let rows = sqlx::query(&query)
             .bind(schema)
             .bind(format!("{}%", prefix))
-            .fetch_all(&self.pool)
+            .fetch_all(&self.pool().await)
             .await
             .map_err(PostgresError::from)?;
 
@@ -314,6 +383,7 @@ impl VectorStorage for PostgresVectorStorage {
 
     #[instrument(skip(self))]
     async fn describe_index(&self, index_name: &str) -> Result<IndexInfo> {
+        let index_name = &self.resolve(index_name).await?;
         let table_name = self.config.table_name(index_name);
 
         // Get table info
@@ -329,27 +399,21 @@ impl VectorStorage for PostgresVectorStorage {
         )
         .bind(&self.config.table.schema)
         .bind(format!("{}{}", self.config.table.table_prefix.as_deref().unwrap_or(""), index_name))
-        .fetch_optional(&self.pool)
+        .fetch_optional(&self.pool().await)
         .await
         .map_err(PostgresError::from)?;
 
-        let dimension = if let Some(row) = table_info {
-            // Extract dimension from vector type
-            let data_type: String = row.try_get("data_type").map_err(PostgresError::from)?;
-            if data_type.contains("vector") {
-                // Parse dimension from vector(n) format
-                384 // Default for now, would need to parse from type
-            } else {
-                return Err(VectorError::index_not_found(index_name));
-            }
-        } else {
+        if table_info.is_none() {
             return Err(VectorError::index_not_found(index_name));
-        };
+        }
+        let schema = self.index_schema(index_name).await?;
+        let dimension = schema.as_ref().map(|schema| schema.dimension).unwrap_or(0);
+        let metric = schema.as_ref().map(|schema| schema.metric).unwrap_or(SimilarityMetric::Cosine);
 
         // Get row count
         let count_query = format!("SELECT COUNT(*) as count FROM {}", table_name);
         let count_row = sqlx::query(&count_query)
-            .fetch_one(&self.pool)
+            .fetch_one(&self.pool().await)
             .await
             .map_err(PostgresError::from)?;
         let vector_count: i64 = count_row.try_get("count").map_err(PostgresError::from)?;
@@ -357,7 +421,7 @@ impl VectorStorage for PostgresVectorStorage {
         let info = IndexInfo {
             name: index_name.to_string(),
             dimension,
-            metric: SimilarityMetric::Cosine, // Default, could be stored in metadata
+            metric,
             vector_count: vector_count as usize,
             size_bytes: 0, // Would need to calculate
             created_at: None,
@@ -374,16 +438,17 @@ impl VectorStorage for PostgresVectorStorage {
 
         let drop_sql = format!("DROP TABLE IF EXISTS {} CASCADE", table_name);
         sqlx::query(&drop_sql)
-            .execute(&self.pool)
+            .execute(&self.pool().await)
             .await
             .map_err(PostgresError::from)?;
+        migrations::forget_index(&self.pool().await, &self.config, index_name).await?;
 
         debug!("Deleted PostgreSQL table: {}", table_name);
         Ok(())
     }
 
     async fn upsert_documents(&self, index_name: &str, documents: Vec<Document>) -> Result<Vec<DocumentId>> {
-        let table_name = self.config.table_name(index_name);
+        let table_name = self.config.table_name(&self.resolve(index_name).await?);
         let mut ids = Vec::new();
 
         // Process in batches
@@ -410,7 +475,7 @@ impl VectorStorage for PostgresVectorStorage {
             query_builder.push(" ON CONFLICT (id) DO UPDATE SET content = EXCLUDED.content, embedding = EXCLUDED.embedding, metadata = EXCLUDED.metadata, updated_at = NOW()");
 
             let query = query_builder.build();
-            query.execute(&self.pool).await.map_err(PostgresError::from)?;
+            query.execute(&self.pool().await).await.map_err(PostgresError::from)?;
         }
 
         debug!("Upserted {} documents to table: {}", ids.len(), table_name);
@@ -419,7 +484,7 @@ impl VectorStorage for PostgresVectorStorage {
 
     #[instrument(skip(self, request))]
     async fn search(&self, request: SearchRequest) -> Result<SearchResponse> {
-        let table_name = self.config.table_name(&request.index_name);
+        let table_name = self.config.table_name(&self.resolve(&request.index_name).await?);
 
         // Set search parameters
         self.set_search_params().await?;
@@ -429,6 +494,12 @@ impl VectorStorage for PostgresVectorStorage {
             SearchQuery::Text(_) => {
                 return Err(VectorError::NotSupported("Text search not implemented for PostgreSQL backend".to_string()));
             },
+            SearchQuery::Sparse(_) | SearchQuery::Hybrid { .. } => {
+                return Err(VectorError::NotSupported("Sparse and hybrid search not implemented for PostgreSQL backend".to_string()));
+            },
+            SearchQuery::MultiVector(_) => {
+                return Err(VectorError::NotSupported("Multi-vector search not implemented for PostgreSQL backend".to_string()));
+            },
         };
 
         // Build the search query
@@ -448,11 +519,19 @@ impl VectorStorage for PostgresVectorStorage {
 
         query.push_str(&format!(" ORDER BY distance LIMIT {}", request.top_k));
 
-        let rows = sqlx::query(&query)
-            .bind(&query_vector)
-            .fetch_all(&self.pool)
-            .await
-            .map_err(PostgresError::from)?;
+        // Searches are read-only, so transient connection failures are retried
+        let rows = self.connections.execute(|pool| {
+            let query = &query;
+            let query_vector = &query_vector;
+            async move {
+                let rows = sqlx::query(query)
+                    .bind(query_vector)
+                    .fetch_all(&pool)
+                    .await
+                    .map_err(PostgresError::from)?;
+                Ok(rows)
+            }
+        }).await?;
 
         let mut results = Vec::new();
         for row in rows {
@@ -502,7 +581,7 @@ impl VectorStorage for PostgresVectorStorage {
 
     #[instrument(skip(self))]
     async fn delete_documents(&self, index_name: &str, ids: Vec<DocumentId>) -> Result<()> {
-        let table_name = self.config.table_name(index_name);
+        let table_name = self.config.table_name(&self.resolve(index_name).await?);
 
         if ids.is_empty() {
             return Ok(());
@@ -520,7 +599,7 @@ impl VectorStorage for PostgresVectorStorage {
             sqlx_query = sqlx_query.bind(id);
         }
 
-        let result = sqlx_query.execute(&self.pool).await.map_err(PostgresError::from)?;
+        let result = sqlx_query.execute(&self.pool().await).await.map_err(PostgresError::from)?;
         let deleted_count = result.rows_affected() as usize;
 
         debug!("Deleted {} documents from table: {}", deleted_count, table_name);
@@ -529,7 +608,7 @@ impl VectorStorage for PostgresVectorStorage {
 
     #[instrument(skip(self))]
     async fn get_documents(&self, index_name: &str, ids: Vec<DocumentId>, include_vectors: bool) -> Result<Vec<Document>> {
-        let table_name = self.config.table_name(index_name);
+        let table_name = self.config.table_name(&self.resolve(index_name).await?);
 
         if ids.is_empty() {
             return Ok(vec![]);
@@ -549,7 +628,7 @@ impl VectorStorage for PostgresVectorStorage {
             sqlx_query = sqlx_query.bind(id);
         }
 
-        let rows = sqlx_query.fetch_all(&self.pool).await.map_err(PostgresError::from)?;
+        let rows = sqlx_query.fetch_all(&self.pool().await).await.map_err(PostgresError::from)?;
 
         let mut documents = Vec::new();
         for row in rows {
@@ -570,6 +649,8 @@ impl VectorStorage for PostgresVectorStorage {
                 id,
                 content,
                 embedding,
+                sparse_embedding: None,
+                vectors: HashMap::new(),
                 metadata,
             };
 
@@ -579,12 +660,78 @@ impl VectorStorage for PostgresVectorStorage {
         Ok(documents)
     }
 
+    /// Keyset pagination on the primary key, so pages stay stable under concurrent writes
+    #[instrument(skip(self))]
+    async fn list_documents(
+        &self,
+        index_name: &str,
+        cursor: Option<String>,
+        limit: usize,
+        filter: Option<FilterCondition>,
+    ) -> Result<DocumentPage> {
+        let table_name = self.config.table_name(&self.resolve(index_name).await?);
+
+        let mut query = sqlx::QueryBuilder::new(format!(
+            "SELECT id, content, metadata, embedding FROM {} WHERE true",
+            table_name
+        ));
+        if let Some(cursor) = cursor {
+            query.push(" AND id > ").push_bind(cursor);
+        }
+        if let Some(filter) = &filter {
+            query.push(" AND ");
+            filter::push_condition(&mut query, filter)?;
+        }
+        // Fetch one extra row to learn whether another page follows
+        query.push(" ORDER BY id LIMIT ").push_bind(limit as i64 + 1);
+
+        let rows = query.build().fetch_all(&self.pool().await).await.map_err(PostgresError::from)?;
+        let remaining = rows.len() > limit;
+
+        let mut documents = Vec::with_capacity(limit.min(rows.len()));
+        for row in rows.into_iter().take(limit) {
+            let metadata_json: JsonValue = row.try_get("metadata").map_err(PostgresError::from)?;
+            let embedding: Vec<f32> = row.try_get("embedding").map_err(PostgresError::from)?;
+            documents.push(Document {
+                id: row.try_get("id").map_err(PostgresError::from)?,
+                content: row.try_get("content").map_err(PostgresError::from)?,
+                embedding: Some(embedding),
+                sparse_embedding: None,
+                vectors: HashMap::new(),
+                metadata: Self::jsonb_to_metadata(metadata_json),
+            });
+        }
+
+        let next_cursor = if remaining {
+            documents.last().map(|document| document.id.clone())
+        } else {
+            None
+        };
+        Ok(DocumentPage::new(documents, next_cursor))
+    }
+
+    #[instrument(skip(self))]
+    async fn aggregate(&self, request: AggregateRequest) -> Result<AggregateResponse> {
+        let table_name = self.config.table_name(&self.resolve(&request.index_name).await?);
+
+        let mut results = Vec::with_capacity(request.aggregations.len());
+        for aggregation in &request.aggregations {
+            let result = self.connections.execute(|pool| {
+                let table_name = &table_name;
+                let filter = request.filter.as_ref();
+                async move {
+                    Self::run_aggregation(&pool, table_name, aggregation, filter).await
+                }
+            }).await?;
+            results.push(result);
+        }
+
+        Ok(AggregateResponse { results })
+    }
+
     #[instrument(skip(self))]
     async fn health_check(&self) -> Result<()> {
-        sqlx::query("SELECT 1")
-            .fetch_one(&self.pool)
-            .await
-            .map_err(PostgresError::from)?;
+        self.connections.health_check().await?;
 
         // Check pgvector extension
         self.ensure_pgvector_extension().await?;
@@ -592,6 +739,51 @@ impl VectorStorage for PostgresVectorStorage {
         Ok(())
     }
 
+    async fn replication_lag(&self) -> Result<Option<std::time::Duration>> {
+        // An idle replica that has replayed everything it received is not lagging
+        let row = sqlx::query(
+            r#"
+            SELECT CASE
+                WHEN NOT pg_is_in_recovery() THEN NULL
+                WHEN pg_last_wal_receive_lsn() = pg_last_wal_replay_lsn() THEN 0
+                ELSE EXTRACT(EPOCH FROM (now() - pg_last_xact_replay_timestamp()))
+            END::float8 AS lag
+            "#
+        )
+        .fetch_one(&self.pool().await)
+        .await
+        .map_err(PostgresError::from)?;
+        let lag: Option<f64> = row.try_get("lag").map_err(PostgresError::from)?;
+        Ok(lag.map(|secs| std::time::Duration::from_secs_f64(secs.max(0.0))))
+    }
+
+    #[instrument(skip(self))]
+    async fn create_alias(&self, alias: &str, index_name: &str) -> Result<()> {
+        if !migrations::set_alias(&self.pool().await, &self.config, alias, index_name, false).await? {
+            return Err(VectorError::alias_already_exists(alias));
+        }
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn swap_alias(&self, alias: &str, index_name: &str) -> Result<()> {
+        migrations::set_alias(&self.pool().await, &self.config, alias, index_name, true).await?;
+        debug!("Pointed PostgreSQL alias {} at {}", alias, index_name);
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn delete_alias(&self, alias: &str) -> Result<()> {
+        if !migrations::drop_alias(&self.pool().await, &self.config, alias).await? {
+            return Err(VectorError::alias_not_found(alias));
+        }
+        Ok(())
+    }
+
+    async fn list_aliases(&self) -> Result<HashMap<String, String>> {
+        Ok(migrations::list_aliases(&self.pool().await, &self.config).await?)
+    }
+
     fn backend_info(&self) -> BackendInfo {
         BackendInfo {
             name: "PostgreSQL".to_string(),
@@ -602,8 +794,12 @@ impl VectorStorage for PostgresVectorStorage {
                 "sql_queries".to_string(),
                 "metadata_filtering".to_string(),
                 "vector_indexes".to_string(),
+                "aliases".to_string(),
+                "aggregations".to_string(),
+                "document_listing".to_string(),
             ],
             metadata: HashMap::new(),
         }
+        .with_pool_metrics(&self.connections.metrics())
     }
 }
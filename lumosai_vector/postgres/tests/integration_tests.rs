@@ -31,6 +31,8 @@ async fn test_postgres_basic_operations() -> Result<()> {
             id: "doc1".to_string(),
             content: "This is the first document".to_string(),
             embedding: Some(vec![0.1; 384]),
+            sparse_embedding: None,
+            vectors: HashMap::new(),
             metadata: {
                 let mut meta = HashMap::new();
                 meta.insert("category".to_string(), MetadataValue::String("tech".to_string()));
@@ -42,6 +44,8 @@ async fn test_postgres_basic_operations() -> Result<()> {
             id: "doc2".to_string(),
             content: "This is the second document".to_string(),
             embedding: Some(vec![0.2; 384]),
+            sparse_embedding: None,
+            vectors: HashMap::new(),
             metadata: {
                 let mut meta = HashMap::new();
                 meta.insert("category".to_string(), MetadataValue::String("science".to_string()));
@@ -26,6 +26,11 @@ pub struct QdrantConfig {
     
     /// Collection prefix for multi-tenancy
     pub collection_prefix: Option<String>,
+    
+    /// Name of the sparse vector stored next to the dense vector; `None` disables
+    /// sparse and hybrid search
+    #[serde(default)]
+    pub sparse_vector_name: Option<String>,
 }
 
 impl Default for QdrantConfig {
@@ -38,6 +43,7 @@ impl Default for QdrantConfig {
             batch_size: 100,
             tls: false,
             collection_prefix: None,
+            sparse_vector_name: None,
         }
     }
 }
@@ -87,6 +93,17 @@ impl QdrantConfig {
         self
     }
     
+    /// Store sparse embeddings under the vector name `sparse`, enabling sparse and hybrid search
+    pub fn with_sparse_vectors(self) -> Self {
+        self.with_sparse_vector_name("sparse")
+    }
+    
+    /// Store sparse embeddings under the given vector name
+    pub fn with_sparse_vector_name(mut self, name: impl Into<String>) -> Self {
+        self.sparse_vector_name = Some(name.into());
+        self
+    }
+    
     /// Get the full collection name with prefix
     pub fn collection_name(&self, name: &str) -> String {
         match &self.collection_prefix {
@@ -110,6 +127,7 @@ mod tests {
         assert_eq!(config.batch_size, 100);
         assert!(!config.tls);
         assert_eq!(config.collection_prefix, None);
+        assert_eq!(config.sparse_vector_name, None);
     }
     
     #[test]
@@ -120,7 +138,8 @@ mod tests {
             .with_max_connections(20)
             .with_batch_size(200)
             .with_tls(true)
-            .with_collection_prefix("test");
+            .with_collection_prefix("test")
+            .with_sparse_vectors();
             
         assert_eq!(config.url, "http://example.com:6334");
         assert_eq!(config.api_key, Some("test-key".to_string()));
@@ -129,6 +148,7 @@ mod tests {
         assert_eq!(config.batch_size, 200);
         assert!(config.tls);
         assert_eq!(config.collection_prefix, Some("test".to_string()));
+        assert_eq!(config.sparse_vector_name, Some("sparse".to_string()));
     }
     
     #[test]
@@ -7,7 +7,7 @@ use qdrant_client::{
     qdrant::{
         CreateCollection, VectorParams, VectorsConfig, Distance,
         PointStruct, Value as QdrantValue, SearchPoints,
-        UpsertPoints, DeletePoints, PointsSelector,
+        UpsertPoints, DeletePoints, PointsSelector, CreateAliasBuilder,
     },
 };
 use uuid::Uuid;
@@ -18,6 +18,9 @@ use crate::{QdrantConfig, QdrantError};
 use crate::error::QdrantResult;
 use crate::filter::QdrantFilterConverter;
 
+/// Factor by which each side of a hybrid query over-fetches candidates for fusion
+const HYBRID_CANDIDATE_MULTIPLIER: usize = 4;
+
 /// Qdrant vector storage implementation
 pub struct QdrantVectorStorage {
     client: Qdrant,
@@ -93,6 +96,148 @@ impl QdrantVectorStorage {
     fn collection_name(&self, name: &str) -> String {
         self.config.collection_name(name)
     }
+    
+    /// Name of the sparse vector, or an error if sparse vectors are disabled
+    fn sparse_vector_name(&self) -> Result<&str> {
+        self.config.sparse_vector_name.as_deref().ok_or_else(|| {
+            VectorError::NotSupported(
+                "Sparse vectors are disabled; enable them with QdrantConfig::with_sparse_vectors".to_string()
+            )
+        })
+    }
+    
+    /// Point vectors: the unnamed dense vector, plus the named sparse vector if present
+    fn point_vectors(&self, embedding: Vector, sparse: Option<SparseVector>) -> Result<qdrant_client::qdrant::Vectors> {
+        use qdrant_client::qdrant::{vectors::VectorsOptions, NamedVectors, Vector as QdrantVector};
+        
+        let dense = QdrantVector::new_dense(embedding);
+        let vectors_options = match sparse {
+            Some(sparse) => VectorsOptions::Vectors(NamedVectors {
+                vectors: HashMap::from([
+                    // The empty name addresses the collection's default (unnamed) vector
+                    (String::new(), dense),
+                    (self.sparse_vector_name()?.to_string(), QdrantVector::new_sparse(sparse.indices, sparse.values)),
+                ]),
+            }),
+            None => VectorsOptions::Vector(dense),
+        };
+        Ok(qdrant_client::qdrant::Vectors { vectors_options: Some(vectors_options) })
+    }
+    
+    /// Query API request for the sparse vector
+    fn sparse_query(
+        &self,
+        collection_name: &str,
+        sparse: SparseVector,
+        filter: Option<qdrant_client::qdrant::Filter>,
+        request: &SearchRequest,
+    ) -> Result<qdrant_client::qdrant::QueryPoints> {
+        Ok(qdrant_client::qdrant::QueryPoints {
+            collection_name: collection_name.to_string(),
+            query: Some(qdrant_client::qdrant::Query::new_nearest(
+                qdrant_client::qdrant::VectorInput::new_sparse(sparse.indices, sparse.values),
+            )),
+            using: Some(self.sparse_vector_name()?.to_string()),
+            filter,
+            limit: Some(request.top_k as u64),
+            ..Default::default()
+        })
+    }
+    
+    /// Hybrid search over the dense and sparse vectors
+    ///
+    /// Reciprocal rank fusion runs server-side through the Query API, with Qdrant's own
+    /// `k` constant; weighted fusion runs both queries and fuses their results locally.
+    async fn hybrid_search(
+        &self,
+        collection_name: &str,
+        dense: Vector,
+        sparse: SparseVector,
+        fusion: FusionMethod,
+        filter: Option<qdrant_client::qdrant::Filter>,
+        request: &SearchRequest,
+    ) -> Result<SearchResponse> {
+        use qdrant_client::qdrant::{Fusion, PrefetchQuery, Query, QueryPoints, VectorInput};
+        
+        // Each side contributes more candidates than requested so fusion can reorder them
+        let candidates = request.top_k.saturating_mul(HYBRID_CANDIDATE_MULTIPLIER) as u64;
+        let dense_query = QueryPoints {
+            collection_name: collection_name.to_string(),
+            query: Some(Query::new_nearest(VectorInput::new_dense(dense))),
+            filter: filter.clone(),
+            limit: Some(candidates),
+            ..Default::default()
+        };
+        let mut sparse_query = self.sparse_query(collection_name, sparse, filter, request)?;
+        sparse_query.limit = Some(candidates);
+        
+        match fusion {
+            FusionMethod::ReciprocalRank { .. } => {
+                let prefetch = |query: QueryPoints| PrefetchQuery {
+                    query: query.query,
+                    using: query.using,
+                    filter: query.filter,
+                    limit: query.limit,
+                    ..Default::default()
+                };
+                let query = QueryPoints {
+                    collection_name: collection_name.to_string(),
+                    prefetch: vec![prefetch(dense_query), prefetch(sparse_query)],
+                    query: Some(Query::new_fusion(Fusion::Rrf)),
+                    limit: Some(request.top_k as u64),
+                    ..Default::default()
+                };
+                self.query_points(query, request).await
+            }
+            FusionMethod::Weighted { .. } => {
+                let dense_results = self.query_points(dense_query, request).await?.results;
+                let sparse_results = self.query_points(sparse_query, request).await?.results;
+                let mut results = fuse_results(dense_results, sparse_results, fusion);
+                results.truncate(request.top_k);
+                Ok(SearchResponse::new(results))
+            }
+        }
+    }
+    
+    /// Run a Query API request and convert its points
+    async fn query_points(
+        &self,
+        mut query: qdrant_client::qdrant::QueryPoints,
+        request: &SearchRequest,
+    ) -> Result<SearchResponse> {
+        query.with_payload = Some(true.into());
+        let response = self.client.query(query).await
+            .map_err(|e| VectorError::OperationFailed(format!("Query failed: {}", e)))?;
+        Ok(SearchResponse::new(Self::convert_scored_points(response.result, request)))
+    }
+    
+    /// Convert scored points to search results
+    fn convert_scored_points(points: Vec<qdrant_client::qdrant::ScoredPoint>, request: &SearchRequest) -> Vec<SearchResult> {
+        let mut results = Vec::new();
+        for scored_point in points {
+            let id = match scored_point.id.and_then(|id| id.point_id_options) {
+                Some(qdrant_client::qdrant::point_id::PointIdOptions::Uuid(uuid)) => uuid,
+                Some(qdrant_client::qdrant::point_id::PointIdOptions::Num(num)) => num.to_string(),
+                None => continue,
+            };
+            
+            let vector = if request.include_vectors {
+                // For now, return None as vector extraction is complex with new API
+                None
+            } else {
+                None
+            };
+            
+            let metadata = Self::convert_payload(scored_point.payload);
+            
+            let result = SearchResult::new(id, scored_point.score)
+                .with_vector(vector.unwrap_or_default())
+                .with_metadata(metadata);
+            
+            results.push(result);
+        }
+        results
+    }
 }
 
 #[async_trait]
@@ -113,9 +258,16 @@ impl VectorStorage for QdrantVectorStorage {
             )),
         };
         
+        let sparse_vectors_config = self.config.sparse_vector_name.as_ref().map(|name| {
+            qdrant_client::qdrant::SparseVectorConfig {
+                map: HashMap::from([(name.clone(), qdrant_client::qdrant::SparseVectorParams::default())]),
+            }
+        });
+        
         let create_collection = CreateCollection {
             collection_name: collection_name.clone(),
             vectors_config: Some(vectors_config),
+            sparse_vectors_config,
             ..Default::default()
         };
         
@@ -216,16 +368,7 @@ impl VectorStorage for QdrantVectorStorage {
                 id: Some(qdrant_client::qdrant::PointId {
                     point_id_options: Some(qdrant_client::qdrant::point_id::PointIdOptions::Uuid(id)),
                 }),
-                vectors: Some(qdrant_client::qdrant::Vectors {
-                    vectors_options: Some(qdrant_client::qdrant::vectors::VectorsOptions::Vector(
-                        qdrant_client::qdrant::Vector {
-                            data: embedding,
-                            indices: None,
-                            vectors_count: None,
-                            vector: None,
-                        }
-                    )),
-                }),
+                vectors: Some(self.point_vectors(embedding, doc.sparse_embedding)?),
                 payload,
             };
             
@@ -252,20 +395,32 @@ impl VectorStorage for QdrantVectorStorage {
     async fn search(&self, request: SearchRequest) -> Result<SearchResponse> {
         let collection_name = self.collection_name(&request.index_name);
         
-        let query_vector = match request.query {
-            SearchQuery::Vector(vector) => vector,
+        let filter = if let Some(condition) = request.filter.clone() {
+            Some(QdrantFilterConverter::convert_filter(condition)
+                .map_err(|e| VectorError::InvalidFilter(e.to_string()))?)
+        } else {
+            None
+        };
+        
+        let query_vector = match &request.query {
+            SearchQuery::Vector(vector) => vector.clone(),
             SearchQuery::Text(_) => {
                 return Err(VectorError::NotSupported(
                     "Text queries not supported by Qdrant storage".to_string()
                 ));
             }
-        };
-        
-        let filter = if let Some(condition) = request.filter {
-            Some(QdrantFilterConverter::convert_filter(condition)
-                .map_err(|e| VectorError::InvalidFilter(e.to_string()))?)
-        } else {
-            None
+            SearchQuery::MultiVector(_) => {
+                return Err(VectorError::NotSupported(
+                    "Multi-vector queries not supported by Qdrant storage".to_string()
+                ));
+            }
+            SearchQuery::Sparse(sparse) => {
+                let query = self.sparse_query(&collection_name, sparse.clone(), filter, &request)?;
+                return self.query_points(query, &request).await;
+            }
+            SearchQuery::Hybrid { dense, sparse, fusion } => {
+                return self.hybrid_search(&collection_name, dense.clone(), sparse.clone(), *fusion, filter, &request).await;
+            }
         };
         
         let search_points = qdrant_client::qdrant::SearchPoints {
@@ -289,31 +444,7 @@ impl VectorStorage for QdrantVectorStorage {
         let response = self.client.search_points(search_points).await
             .map_err(|e| VectorError::OperationFailed(format!("Search failed: {}", e)))?;
         
-        let mut results = Vec::new();
-        for scored_point in response.result {
-            let id = match scored_point.id.and_then(|id| id.point_id_options) {
-                Some(qdrant_client::qdrant::point_id::PointIdOptions::Uuid(uuid)) => uuid,
-                Some(qdrant_client::qdrant::point_id::PointIdOptions::Num(num)) => num.to_string(),
-                None => continue,
-            };
-            
-            let vector = if request.include_vectors {
-                // For now, return None as vector extraction is complex with new API
-                None
-            } else {
-                None
-            };
-            
-            let metadata = Self::convert_payload(scored_point.payload);
-            
-            let result = SearchResult::new(id, scored_point.score)
-                .with_vector(vector.unwrap_or_default())
-                .with_metadata(metadata);
-            
-            results.push(result);
-        }
-        
-        Ok(SearchResponse::new(results))
+        Ok(SearchResponse::new(Self::convert_scored_points(response.result, &request)))
     }
     
     async fn update_document(&self, index_name: &str, document: Document) -> Result<()> {
@@ -356,6 +487,96 @@ impl VectorStorage for QdrantVectorStorage {
         Err(VectorError::NotSupported("get_documents not yet implemented for Qdrant".to_string()))
     }
     
+    /// Pages through the Scroll API; the cursor is the ID of the first point of the next page
+    async fn list_documents(
+        &self,
+        index_name: &str,
+        cursor: Option<String>,
+        limit: usize,
+        filter: Option<FilterCondition>,
+    ) -> Result<DocumentPage> {
+        use qdrant_client::qdrant::{point_id::PointIdOptions, vectors_output::VectorsOptions, PointId, ScrollPoints};
+        
+        let filter = filter
+            .map(QdrantFilterConverter::convert_filter)
+            .transpose()
+            .map_err(|e| VectorError::InvalidFilter(e.to_string()))?;
+        
+        let scroll = ScrollPoints {
+            collection_name: self.collection_name(index_name),
+            filter,
+            offset: cursor.map(|id| PointId { point_id_options: Some(PointIdOptions::Uuid(id)) }),
+            limit: Some(limit as u32),
+            with_payload: Some(true.into()),
+            with_vectors: Some(true.into()),
+            ..Default::default()
+        };
+        let response = self.client.scroll(scroll).await
+            .map_err(|e| VectorError::OperationFailed(format!("Scroll failed: {}", e)))?;
+        
+        let point_id = |id: Option<PointId>| match id.and_then(|id| id.point_id_options) {
+            Some(PointIdOptions::Uuid(uuid)) => Some(uuid),
+            Some(PointIdOptions::Num(num)) => Some(num.to_string()),
+            None => None,
+        };
+        let documents = response.result.into_iter()
+            .filter_map(|point| {
+                let mut document = Document::new(point_id(point.id)?, "");
+                // Only the dense vector is returned; a named sparse vector is not restored
+                #[allow(deprecated)]
+                let embedding = match point.vectors.and_then(|vectors| vectors.vectors_options) {
+                    Some(VectorsOptions::Vector(vector)) => Some(vector.data),
+                    Some(VectorsOptions::Vectors(mut named)) => named.vectors.remove("").map(|vector| vector.data),
+                    None => None,
+                };
+                document.embedding = embedding;
+                document.metadata = Self::convert_payload(point.payload);
+                Some(document)
+            })
+            .collect();
+        
+        Ok(DocumentPage::new(documents, point_id(response.next_page_offset)))
+    }
+    
+    async fn create_alias(&self, alias: &str, index_name: &str) -> Result<()> {
+        if self.list_aliases().await?.contains_key(alias) {
+            return Err(VectorError::alias_already_exists(alias));
+        }
+        self.swap_alias(alias, index_name).await
+    }
+    
+    async fn swap_alias(&self, alias: &str, index_name: &str) -> Result<()> {
+        // Creating an existing alias repoints it in a single atomic operation
+        self.client.create_alias(CreateAliasBuilder::new(self.collection_name(index_name), self.collection_name(alias))).await
+            .map_err(|e| VectorError::OperationFailed(format!("Failed to point alias {} at {}: {}", alias, index_name, e)))?;
+        
+        debug!("Pointed Qdrant alias {} at {}", alias, index_name);
+        Ok(())
+    }
+    
+    async fn delete_alias(&self, alias: &str) -> Result<()> {
+        if !self.list_aliases().await?.contains_key(alias) {
+            return Err(VectorError::alias_not_found(alias));
+        }
+        self.client.delete_alias(self.collection_name(alias)).await
+            .map_err(|e| VectorError::OperationFailed(format!("Failed to delete alias {}: {}", alias, e)))?;
+        Ok(())
+    }
+    
+    async fn list_aliases(&self) -> Result<HashMap<String, String>> {
+        let response = self.client.list_aliases().await
+            .map_err(|e| VectorError::OperationFailed(format!("Failed to list aliases: {}", e)))?;
+        
+        let prefix = self.config.collection_prefix.as_ref().map(|prefix| format!("{}_", prefix));
+        let unprefixed = |name: String| match &prefix {
+            Some(prefix) => name.strip_prefix(prefix.as_str()).map(str::to_string),
+            None => Some(name),
+        };
+        Ok(response.aliases.into_iter()
+            .filter_map(|alias| Some((unprefixed(alias.alias_name)?, unprefixed(alias.collection_name)?)))
+            .collect())
+    }
+    
     async fn health_check(&self) -> Result<()> {
         self.client.list_collections().await
             .map_err(|e| VectorError::ConnectionFailed(format!("Health check failed: {}", e)))?;
@@ -368,5 +589,9 @@ impl VectorStorage for QdrantVectorStorage {
             .with_feature("distributed")
             .with_feature("filtering")
             .with_feature("batch_operations")
+            .with_feature("sparse_vectors")
+            .with_feature("hybrid_search")
+            .with_feature("aliases")
+            .with_feature("document_listing")
     }
 }
@@ -0,0 +1,491 @@
+//! Vector storage benchmarks
+//!
+//! Standardized datasets and measurements shared by the backend benchmark suites.
+//! [`Dataset`] provides SIFT- and GloVe-style vectors, either generated
+//! deterministically or loaded from the original files, together with exact nearest
+//! neighbours. [`measure`] inserts a dataset into a backend and records insert
+//! throughput, search throughput and recall, and [`BenchmarkReport`] collects the
+//! records of all backends into a JSON and Markdown comparison.
+
+use std::collections::HashSet;
+use std::fs;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use lumosai_vector_core::prelude::*;
+
+/// Number of neighbours recall is measured at
+pub const RECALL_K: usize = 10;
+
+/// Documents inserted per upsert call
+pub const INSERT_BATCH_SIZE: usize = 500;
+
+/// Environment variable overriding the directory reports are written to
+pub const REPORT_DIR_ENV: &str = "LUMOS_BENCH_REPORT_DIR";
+
+/// Vectors to index, queries to run against them and their exact nearest neighbours
+#[derive(Debug, Clone)]
+pub struct Dataset {
+    /// Dataset name, used in index names and reports
+    pub name: String,
+    /// Vector dimension
+    pub dimension: usize,
+    /// Metric the ground truth was computed with
+    pub metric: SimilarityMetric,
+    /// Vectors to index
+    pub base: Vec<Vector>,
+    /// Query vectors
+    pub queries: Vec<Vector>,
+    /// Indices into `base` of the [`RECALL_K`] nearest neighbours of each query
+    pub ground_truth: Vec<Vec<usize>>,
+}
+
+impl Dataset {
+    /// Build a dataset and compute its ground truth by brute force
+    pub fn new(name: impl Into<String>, metric: SimilarityMetric, base: Vec<Vector>, queries: Vec<Vector>) -> Result<Self> {
+        let dimension = base.first().map(Vec::len).unwrap_or_default();
+        if dimension == 0 {
+            return Err(VectorError::InvalidConfig("Benchmark dataset needs at least one non-empty vector".to_string()));
+        }
+        if let Some(vector) = base.iter().chain(&queries).find(|vector| vector.len() != dimension) {
+            return Err(VectorError::dimension_mismatch(dimension, vector.len()));
+        }
+
+        let ground_truth = queries.iter().map(|query| nearest_neighbours(metric, &base, query, RECALL_K)).collect();
+        Ok(Self {
+            name: name.into(),
+            dimension,
+            metric,
+            base,
+            queries,
+            ground_truth,
+        })
+    }
+
+    /// SIFT-style dataset: 128-dimensional non-negative integer gradients compared by Euclidean distance
+    pub fn sift_like(size: usize, queries: usize, seed: u64) -> Result<Self> {
+        let mut rng = SplitMix64(seed);
+        let centers = random_vectors(&mut rng, cluster_count(size), 128, |rng| rng.next_f32() * 128.0);
+        let sample = |rng: &mut SplitMix64| {
+            let center = &centers[rng.next_index(centers.len())];
+            center.iter().map(|value| (value + rng.next_gaussian() * 20.0).clamp(0.0, 255.0).round()).collect::<Vector>()
+        };
+        let base = (0..size).map(|_| sample(&mut rng)).collect();
+        let queries = (0..queries).map(|_| sample(&mut rng)).collect();
+        Self::new("sift_like", SimilarityMetric::Euclidean, base, queries)
+    }
+
+    /// GloVe-style dataset: 100-dimensional dense word embeddings compared by cosine similarity
+    pub fn glove_like(size: usize, queries: usize, seed: u64) -> Result<Self> {
+        let mut rng = SplitMix64(seed);
+        let centers = random_vectors(&mut rng, cluster_count(size), 100, |rng| rng.next_gaussian() * 0.5);
+        let sample = |rng: &mut SplitMix64| {
+            let center = &centers[rng.next_index(centers.len())];
+            center.iter().map(|value| value + rng.next_gaussian() * 0.3).collect::<Vector>()
+        };
+        let base = (0..size).map(|_| sample(&mut rng)).collect();
+        let queries = (0..queries).map(|_| sample(&mut rng)).collect();
+        Self::new("glove_like", SimilarityMetric::Cosine, base, queries)
+    }
+
+    /// Load the first `size` base vectors and `queries` query vectors of a TEXMEX corpus
+    ///
+    /// Reads `{name}_base.fvecs` and `{name}_query.fvecs` from `dir`, as distributed for
+    /// SIFT1M and GIST1M. The shipped ground truth only holds for the full corpus, so it
+    /// is recomputed for the loaded subset.
+    pub fn load_texmex(dir: impl AsRef<Path>, name: &str, size: usize, queries: usize) -> Result<Self> {
+        let dir = dir.as_ref();
+        let base = read_fvecs(dir.join(format!("{}_base.fvecs", name)), size)?;
+        let queries = read_fvecs(dir.join(format!("{}_query.fvecs", name)), queries)?;
+        Self::new(name, SimilarityMetric::Euclidean, base, queries)
+    }
+
+    /// Load GloVe vectors from the text format, one `word v1 v2 ...` entry per line
+    ///
+    /// The first `size` words are indexed and the following `queries` words are used as queries.
+    pub fn load_glove(path: impl AsRef<Path>, size: usize, queries: usize) -> Result<Self> {
+        let file = fs::File::open(path.as_ref())?;
+        let mut vectors = Vec::with_capacity(size + queries);
+        for line in BufReader::new(file).lines().take(size + queries) {
+            let line = line?;
+            let vector = line
+                .split_whitespace()
+                .skip(1)
+                .map(|value| value.parse::<f32>())
+                .collect::<std::result::Result<Vector, _>>()
+                .map_err(|e| VectorError::Deserialization(format!("Invalid GloVe vector: {}", e)))?;
+            vectors.push(vector);
+        }
+        let query_vectors = vectors.split_off(size.min(vectors.len()));
+        Self::new("glove", SimilarityMetric::Cosine, vectors, query_vectors)
+    }
+
+    /// Number of indexed vectors
+    pub fn len(&self) -> usize {
+        self.base.len()
+    }
+
+    /// Whether the dataset has no indexed vectors
+    pub fn is_empty(&self) -> bool {
+        self.base.is_empty()
+    }
+
+    /// Document id of the `index`-th base vector
+    ///
+    /// Ids are UUID-shaped so that backends requiring UUID point ids accept them.
+    pub fn document_id(index: usize) -> DocumentId {
+        format!("00000000-0000-0000-0000-{:012}", index)
+    }
+
+    /// Base vectors as documents, in batches of [`INSERT_BATCH_SIZE`]
+    pub fn document_batches(&self) -> Vec<Vec<Document>> {
+        self.base
+            .chunks(INSERT_BATCH_SIZE)
+            .enumerate()
+            .map(|(batch, vectors)| {
+                vectors
+                    .iter()
+                    .enumerate()
+                    .map(|(offset, vector)| {
+                        Document::new(Self::document_id(batch * INSERT_BATCH_SIZE + offset), "").with_embedding(vector.clone())
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Index configuration matching the dataset
+    pub fn index_config(&self, name: impl Into<String>) -> IndexConfig {
+        IndexConfig::new(name, self.dimension).with_metric(self.metric)
+    }
+
+    /// Fraction of the exact nearest neighbours of `query` found in `results`
+    pub fn recall(&self, query: usize, results: &[DocumentId]) -> f64 {
+        let expected = &self.ground_truth[query];
+        if expected.is_empty() {
+            return 1.0;
+        }
+        let found: HashSet<&str> = results.iter().take(RECALL_K).map(String::as_str).collect();
+        let hits = expected.iter().filter(|index| found.contains(Self::document_id(**index).as_str())).count();
+        hits as f64 / expected.len() as f64
+    }
+}
+
+/// Measurements of one backend on one dataset
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkRecord {
+    /// Backend name
+    pub backend: String,
+    /// Dataset name
+    pub dataset: String,
+    /// Number of indexed vectors
+    pub size: usize,
+    /// Vector dimension
+    pub dimension: usize,
+    /// Vectors inserted per second
+    pub insert_per_sec: f64,
+    /// Searches per second, run one at a time
+    pub search_qps: f64,
+    /// 95th percentile search latency in milliseconds
+    pub search_p95_ms: f64,
+    /// Mean recall at [`RECALL_K`]
+    pub recall: f64,
+}
+
+impl BenchmarkRecord {
+    /// Summarize the timings of one run
+    pub fn from_timings(backend: impl Into<String>, dataset: &Dataset, insert: Duration, searches: &[Duration], recall: f64) -> Self {
+        let mut millis: Vec<f64> = searches.iter().map(|latency| latency.as_secs_f64() * 1000.0).collect();
+        millis.sort_by(f64::total_cmp);
+        let search_secs: f64 = searches.iter().map(Duration::as_secs_f64).sum();
+        let p95 = ((0.95 * millis.len() as f64).ceil() as usize).clamp(1, millis.len().max(1)) - 1;
+
+        Self {
+            backend: backend.into(),
+            dataset: dataset.name.clone(),
+            size: dataset.len(),
+            dimension: dataset.dimension,
+            insert_per_sec: dataset.len() as f64 / insert.as_secs_f64().max(f64::EPSILON),
+            search_qps: searches.len() as f64 / search_secs.max(f64::EPSILON),
+            search_p95_ms: millis.get(p95).copied().unwrap_or_default(),
+            recall,
+        }
+    }
+}
+
+/// Insert `dataset` into a fresh index of `storage`, run every query and record the results
+///
+/// The index is deleted afterwards.
+pub async fn measure<S: VectorStorage>(storage: &S, backend: &str, dataset: &Dataset) -> Result<BenchmarkRecord> {
+    let index = format!(
+        "bench_{}_{}",
+        dataset.name.replace(|c: char| !c.is_ascii_alphanumeric(), "_"),
+        dataset.len()
+    );
+    // Leftover from an interrupted run
+    let _ = storage.delete_index(&index).await;
+    storage.create_index(dataset.index_config(&index)).await?;
+
+    let result = run_measurement(storage, backend, dataset, &index).await;
+    storage.delete_index(&index).await?;
+    result
+}
+
+async fn run_measurement<S: VectorStorage>(storage: &S, backend: &str, dataset: &Dataset, index: &str) -> Result<BenchmarkRecord> {
+    let batches = dataset.document_batches();
+    let started = Instant::now();
+    for batch in batches {
+        storage.upsert_documents(index, batch).await?;
+    }
+    let insert = started.elapsed();
+
+    let mut searches = Vec::with_capacity(dataset.queries.len());
+    let mut recall = 0.0;
+    for (query, vector) in dataset.queries.iter().enumerate() {
+        let started = Instant::now();
+        let response = storage.search(SearchRequest::new(index, vector.clone()).with_top_k(RECALL_K)).await?;
+        searches.push(started.elapsed());
+
+        let ids: Vec<DocumentId> = response.results.into_iter().map(|result| result.id).collect();
+        recall += dataset.recall(query, &ids);
+    }
+    let recall = recall / dataset.queries.len().max(1) as f64;
+
+    Ok(BenchmarkRecord::from_timings(backend, dataset, insert, &searches, recall))
+}
+
+/// Benchmark results of all backends, persisted between runs
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkReport {
+    /// Recorded measurements
+    pub records: Vec<BenchmarkRecord>,
+}
+
+impl BenchmarkReport {
+    /// Directory reports are written to, `target/vector-benchmarks` of the workspace unless overridden by [`REPORT_DIR_ENV`]
+    ///
+    /// Benches run from their own package directory, so the default is anchored to the
+    /// workspace for all suites to share one report.
+    pub fn default_dir() -> PathBuf {
+        std::env::var_os(REPORT_DIR_ENV).map(PathBuf::from).unwrap_or_else(|| {
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("..").join("target").join("vector-benchmarks")
+        })
+    }
+
+    /// Load the report saved in `dir`, or an empty report if there is none
+    pub fn load(dir: impl AsRef<Path>) -> Result<Self> {
+        let path = dir.as_ref().join("report.json");
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// Add a record, replacing an earlier one of the same backend, dataset and size
+    pub fn upsert(&mut self, record: BenchmarkRecord) {
+        self.records.retain(|existing| {
+            (existing.backend.as_str(), existing.dataset.as_str(), existing.size)
+                != (record.backend.as_str(), record.dataset.as_str(), record.size)
+        });
+        self.records.push(record);
+    }
+
+    /// Write `report.json` and `report.md` to `dir`
+    pub fn save(&self, dir: impl AsRef<Path>) -> Result<()> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+        fs::write(dir.join("report.json"), serde_json::to_string_pretty(self)?)?;
+        fs::write(dir.join("report.md"), self.to_markdown())?;
+        Ok(())
+    }
+
+    /// Markdown tables comparing the backends at every measured scale, one per dataset
+    pub fn to_markdown(&self) -> String {
+        let mut records: Vec<&BenchmarkRecord> = self.records.iter().collect();
+        records.sort_by(|a, b| (&a.dataset, a.size, &a.backend).cmp(&(&b.dataset, b.size, &b.backend)));
+
+        let mut markdown = String::from("# Vector storage benchmarks\n");
+        let mut dataset: Option<&str> = None;
+        for record in records {
+            if dataset != Some(record.dataset.as_str()) {
+                dataset = Some(record.dataset.as_str());
+                markdown.push_str(&format!(
+                    "\n## {} ({} dimensions)\n\n| Vectors | Backend | Insert (vectors/s) | Search (QPS) | Search p95 (ms) | Recall@{} |\n|---:|---|---:|---:|---:|---:|\n",
+                    record.dataset, record.dimension, RECALL_K
+                ));
+            }
+            markdown.push_str(&format!(
+                "| {} | {} | {:.0} | {:.1} | {:.2} | {:.3} |\n",
+                record.size, record.backend, record.insert_per_sec, record.search_qps, record.search_p95_ms, record.recall
+            ));
+        }
+        markdown
+    }
+}
+
+/// Read up to `limit` vectors from an `.fvecs` file
+///
+/// Every vector is stored as its dimension as a little-endian `i32` followed by that many `f32`s.
+pub fn read_fvecs(path: impl AsRef<Path>, limit: usize) -> Result<Vec<Vector>> {
+    let path = path.as_ref();
+    let mut reader = BufReader::new(fs::File::open(path)?);
+    let mut vectors = Vec::new();
+    let mut word = [0u8; 4];
+    while vectors.len() < limit {
+        match reader.read_exact(&mut word) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e.into()),
+        }
+        let dimension = i32::from_le_bytes(word);
+        if dimension <= 0 {
+            return Err(VectorError::Deserialization(format!("Invalid vector dimension {} in {}", dimension, path.display())));
+        }
+        let mut bytes = vec![0u8; dimension as usize * 4];
+        reader.read_exact(&mut bytes)?;
+        vectors.push(bytes.chunks_exact(4).map(|value| f32::from_le_bytes([value[0], value[1], value[2], value[3]])).collect());
+    }
+    Ok(vectors)
+}
+
+/// Write vectors to an `.fvecs` file
+pub fn write_fvecs(path: impl AsRef<Path>, vectors: &[Vector]) -> Result<()> {
+    let mut writer = BufWriter::new(fs::File::create(path.as_ref())?);
+    for vector in vectors {
+        writer.write_all(&(vector.len() as i32).to_le_bytes())?;
+        for value in vector {
+            writer.write_all(&value.to_le_bytes())?;
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Indices of the `k` vectors of `base` closest to `query`, closest first
+fn nearest_neighbours(metric: SimilarityMetric, base: &[Vector], query: &[f32], k: usize) -> Vec<usize> {
+    let mut distances: Vec<(f32, usize)> = base.iter().enumerate().map(|(index, vector)| (distance(metric, query, vector), index)).collect();
+    distances.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+    distances.into_iter().take(k).map(|(_, index)| index).collect()
+}
+
+/// Distance under `metric`, lower meaning closer
+fn distance(metric: SimilarityMetric, a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    match metric {
+        SimilarityMetric::Cosine => {
+            let norms = a.iter().map(|x| x * x).sum::<f32>().sqrt() * b.iter().map(|y| y * y).sum::<f32>().sqrt();
+            if norms == 0.0 { 1.0 } else { 1.0 - dot / norms }
+        }
+        SimilarityMetric::DotProduct => -dot,
+        SimilarityMetric::Manhattan => a.iter().zip(b).map(|(x, y)| (x - y).abs()).sum(),
+        SimilarityMetric::Euclidean | SimilarityMetric::Hamming => a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum(),
+    }
+}
+
+/// Clusters in a generated dataset, so that neighbourhoods are non-trivial at every scale
+fn cluster_count(size: usize) -> usize {
+    (size / 100).clamp(1, 64)
+}
+
+fn random_vectors(rng: &mut SplitMix64, count: usize, dimension: usize, value: impl Fn(&mut SplitMix64) -> f32) -> Vec<Vector> {
+    (0..count).map(|_| (0..dimension).map(|_| value(rng)).collect()).collect()
+}
+
+/// Small seeded generator, so generated datasets are identical across runs and platforms
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `[0, 1)`
+    fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    fn next_index(&mut self, len: usize) -> usize {
+        (self.next_u64() % len as u64) as usize
+    }
+
+    /// Standard normal sample (Box–Muller)
+    fn next_gaussian(&mut self) -> f32 {
+        let u1 = 1.0 - self.next_f32();
+        let u2 = self.next_f32();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * std::f32::consts::PI * u2).cos()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_datasets_are_reproducible() {
+        let sift = Dataset::sift_like(300, 5, 7).unwrap();
+        assert_eq!((sift.dimension, sift.len(), sift.queries.len()), (128, 300, 5));
+        assert!(sift.base.iter().flatten().all(|value| (0.0..=255.0).contains(value) && value.fract() == 0.0));
+        assert_eq!(sift.base, Dataset::sift_like(300, 5, 7).unwrap().base);
+        assert_ne!(sift.base, Dataset::sift_like(300, 5, 8).unwrap().base);
+
+        let glove = Dataset::glove_like(300, 5, 7).unwrap();
+        assert_eq!((glove.dimension, glove.metric), (100, SimilarityMetric::Cosine));
+        assert!(glove.ground_truth.iter().all(|neighbours| neighbours.len() == RECALL_K));
+    }
+
+    #[test]
+    fn test_fvecs_roundtrip_and_recall() {
+        let dir = std::env::temp_dir().join(format!("lumos_bench_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let base: Vec<Vector> = (0..20).map(|i| vec![i as f32, 0.0]).collect();
+        write_fvecs(dir.join("toy_base.fvecs"), &base).unwrap();
+        write_fvecs(dir.join("toy_query.fvecs"), &[vec![3.2, 0.0]]).unwrap();
+        assert_eq!(read_fvecs(dir.join("toy_base.fvecs"), 5).unwrap(), base[..5].to_vec());
+
+        let dataset = Dataset::load_texmex(&dir, "toy", 12, 10).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(dataset.len(), 12);
+        assert_eq!(dataset.ground_truth[0], vec![3, 4, 2, 5, 1, 6, 0, 7, 8, 9]);
+
+        let half: Vec<DocumentId> = [3, 4, 2, 5, 1].into_iter().map(Dataset::document_id).collect();
+        assert_eq!(dataset.recall(0, &half), 0.5);
+    }
+
+    #[test]
+    fn test_report_replaces_records_and_renders_tables() {
+        let dataset = Dataset::sift_like(100, 2, 1).unwrap();
+        let searches = [Duration::from_millis(2), Duration::from_millis(4)];
+        let mut report = BenchmarkReport::default();
+        report.upsert(BenchmarkRecord::from_timings("memory", &dataset, Duration::from_millis(10), &searches, 0.5));
+        report.upsert(BenchmarkRecord::from_timings("memory", &dataset, Duration::from_millis(20), &searches, 1.0));
+        report.upsert(BenchmarkRecord::from_timings("sqlite", &dataset, Duration::from_millis(40), &searches, 1.0));
+
+        assert_eq!(report.records.len(), 2);
+        assert_eq!(report.records[1].search_p95_ms, 4.0);
+
+        let markdown = report.to_markdown();
+        assert!(markdown.contains("## sift_like (128 dimensions)"));
+        assert!(markdown.contains("| 100 | memory | 5000 | 333.3 | 4.00 | 1.000 |"));
+        assert!(markdown.find("| memory |").unwrap() < markdown.find("| sqlite |").unwrap());
+    }
+
+    #[cfg(feature = "memory")]
+    #[tokio::test]
+    async fn test_measure_memory_backend() {
+        let storage = crate::memory::MemoryVectorStorage::new().await.unwrap();
+        let dataset = Dataset::glove_like(600, 10, 3).unwrap();
+
+        let record = measure(&storage, "memory", &dataset).await.unwrap();
+        assert_eq!((record.size, record.dimension), (600, 100));
+        assert!(record.recall > 0.99, "recall {}", record.recall);
+        assert!(record.insert_per_sec > 0.0 && record.search_qps > 0.0);
+        assert!(storage.list_indexes().await.unwrap().is_empty());
+    }
+}
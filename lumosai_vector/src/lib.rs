@@ -91,6 +91,13 @@ pub use lumosai_vector_core::prelude::*;
 // Re-export core module for compatibility
 pub use lumosai_vector_core as core;
 
+pub mod benchmark;
+pub mod migration;
+pub mod sharding;
+
+pub use migration::{migrate_embeddings, migrate_embeddings_with_progress, MigrationCheckpoint, MigrationConfig, MigrationReport};
+pub use sharding::{ShardStrategy, ShardedVectorStorage};
+
 // Re-export storage implementations
 #[cfg(feature = "memory")]
 pub use lumosai_vector_memory as memory;
@@ -110,9 +117,14 @@ pub use lumosai_vector_fastembed as fastembed;
 #[cfg(feature = "lancedb")]
 pub use lumosai_vector_lancedb as lancedb;
 
+#[cfg(feature = "milvus")]
+pub use lumosai_vector_milvus as milvus;
+
 /// Prelude module for convenient imports
 pub mod prelude {
     pub use lumosai_vector_core::prelude::*;
+    pub use crate::migration::{migrate_embeddings, MigrationConfig};
+    pub use crate::sharding::{ShardStrategy, ShardedVectorStorage};
 
     #[cfg(feature = "memory")]
     pub use crate::memory::MemoryVectorStorage;
@@ -242,6 +254,30 @@ mod tests {
         assert_eq!(results.results[0].id, "test1");
     }
     
+    #[tokio::test]
+    #[cfg(feature = "memory")]
+    async fn test_alias_swap() {
+        let storage = utils::create_memory_storage().await.unwrap();
+        for (name, x) in [("docs_v1", 1.0), ("docs_v2", 0.0)] {
+            storage.create_index(IndexConfig::new(name, 2)).await.unwrap();
+            let doc = Document::new(name, "content").with_embedding(vec![x, 1.0 - x]);
+            storage.upsert_documents(name, vec![doc]).await.unwrap();
+        }
+
+        storage.create_alias("docs", "docs_v1").await.unwrap();
+        assert!(matches!(storage.create_alias("docs", "docs_v2").await, Err(VectorError::AliasAlreadyExists(_))));
+        let search = || storage.search(SearchRequest::new("docs", vec![1.0, 0.0]).with_top_k(1));
+        assert_eq!(search().await.unwrap().results[0].id, "docs_v1");
+
+        storage.swap_alias("docs", "docs_v2").await.unwrap();
+        assert_eq!(search().await.unwrap().results[0].id, "docs_v2");
+        assert_eq!(storage.list_aliases().await.unwrap().get("docs").map(String::as_str), Some("docs_v2"));
+
+        storage.delete_alias("docs").await.unwrap();
+        assert!(storage.search(SearchRequest::new("docs", vec![1.0, 0.0])).await.is_err());
+        assert!(matches!(storage.delete_alias("docs").await, Err(VectorError::AliasNotFound(_))));
+    }
+    
     #[tokio::test]
     #[cfg(feature = "memory")]
     async fn test_auto_storage_creation() {
@@ -0,0 +1,287 @@
+//! Embedding model migration
+//!
+//! Switching embedding models invalidates every stored vector. [`migrate_embeddings`]
+//! pages through an index, re-embeds each document's content with the new model into a
+//! fresh index and finally repoints an alias at it, so searches keep hitting the old
+//! index until the new one is complete. Progress is reported as checkpoints from which
+//! an interrupted migration can resume.
+
+use serde::{Deserialize, Serialize};
+
+use lumosai_vector_core::prelude::*;
+
+/// Default number of documents re-embedded per batch
+pub const DEFAULT_MIGRATION_BATCH_SIZE: usize = 100;
+
+/// Progress of a migration, reported after every batch
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigrationCheckpoint {
+    /// Cursor of the next page of the source index; `None` before the first batch
+    pub cursor: Option<String>,
+    /// Number of documents written to the target index so far
+    pub migrated: usize,
+    /// Number of documents skipped because they had no content to embed
+    pub skipped: usize,
+}
+
+/// Settings of an embedding migration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MigrationConfig {
+    /// Index or alias to read documents from
+    pub source_index: String,
+    /// Index to create and fill with the new embeddings
+    pub target_index: String,
+    /// Alias to point at the target index once all documents are migrated
+    pub alias: Option<String>,
+    /// Documents re-embedded per batch
+    pub batch_size: usize,
+    /// Checkpoint to resume from; the target index must already exist
+    pub resume_from: Option<MigrationCheckpoint>,
+}
+
+impl MigrationConfig {
+    /// Migrate `source_index` into a new `target_index`
+    pub fn new(source_index: impl Into<String>, target_index: impl Into<String>) -> Self {
+        Self {
+            source_index: source_index.into(),
+            target_index: target_index.into(),
+            alias: None,
+            batch_size: DEFAULT_MIGRATION_BATCH_SIZE,
+            resume_from: None,
+        }
+    }
+
+    /// Point `alias` at the target index when the migration completes
+    pub fn with_alias(mut self, alias: impl Into<String>) -> Self {
+        self.alias = Some(alias.into());
+        self
+    }
+
+    /// Set the batch size
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Resume an interrupted migration
+    pub fn resume_from(mut self, checkpoint: MigrationCheckpoint) -> Self {
+        self.resume_from = Some(checkpoint);
+        self
+    }
+}
+
+/// Outcome of a completed migration
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigrationReport {
+    /// Index holding the new embeddings
+    pub target_index: String,
+    /// Alias now pointing at the target index
+    pub alias: Option<String>,
+    /// Name of the model the source index was embedded with
+    pub old_model: String,
+    /// Name of the model the target index is embedded with
+    pub new_model: String,
+    /// Number of documents migrated
+    pub migrated: usize,
+    /// Number of documents skipped because they had no content to embed
+    pub skipped: usize,
+}
+
+/// Re-embed an index with a new embedding model
+///
+/// See [`migrate_embeddings_with_progress`].
+pub async fn migrate_embeddings<S, O, N>(
+    storage: &S,
+    old_model: &O,
+    new_model: &N,
+    config: MigrationConfig,
+) -> Result<MigrationReport>
+where
+    S: VectorStorage + ?Sized,
+    O: EmbeddingModel + ?Sized,
+    N: EmbeddingModel + ?Sized,
+{
+    migrate_embeddings_with_progress(storage, old_model, new_model, config, |_| {}).await
+}
+
+/// Re-embed an index with a new embedding model, reporting a checkpoint after every batch
+///
+/// The source index must have been embedded with `old_model`; its dimension is checked
+/// against the model before anything is written. The target index is created with the
+/// new model's dimension and the source index's metric. Documents keep their IDs,
+/// metadata and sparse embeddings. The source index is left untouched, so it can be
+/// deleted once the new index has been verified.
+pub async fn migrate_embeddings_with_progress<S, O, N, F>(
+    storage: &S,
+    old_model: &O,
+    new_model: &N,
+    config: MigrationConfig,
+    mut on_checkpoint: F,
+) -> Result<MigrationReport>
+where
+    S: VectorStorage + ?Sized,
+    O: EmbeddingModel + ?Sized,
+    N: EmbeddingModel + ?Sized,
+    F: FnMut(&MigrationCheckpoint) + Send,
+{
+    let source = storage.describe_index(&config.source_index).await?;
+    if source.dimension != old_model.dimensions() {
+        return Err(VectorError::InvalidConfig(format!(
+            "Index {} has {} dimensions but model {} produces {}",
+            config.source_index,
+            source.dimension,
+            old_model.model_name(),
+            old_model.dimensions()
+        )));
+    }
+
+    let mut checkpoint = match config.resume_from {
+        Some(checkpoint) => checkpoint,
+        None => {
+            storage
+                .create_index(IndexConfig::new(&config.target_index, new_model.dimensions()).with_metric(source.metric))
+                .await?;
+            MigrationCheckpoint::default()
+        }
+    };
+
+    loop {
+        let page = storage
+            .list_documents(&config.source_index, checkpoint.cursor.clone(), config.batch_size.max(1), None)
+            .await?;
+
+        let (mut documents, skipped): (Vec<Document>, Vec<Document>) =
+            page.documents.into_iter().partition(|document| !document.content.trim().is_empty());
+        if !documents.is_empty() {
+            let texts: Vec<String> = documents.iter().map(|document| document.content.clone()).collect();
+            let embeddings = new_model.embed_batch(&texts).await?;
+            if embeddings.len() != documents.len() {
+                return Err(VectorError::EmbeddingError(format!(
+                    "Model {} returned {} embeddings for {} documents",
+                    new_model.model_name(),
+                    embeddings.len(),
+                    documents.len()
+                )));
+            }
+            for (document, embedding) in documents.iter_mut().zip(embeddings) {
+                document.embedding = Some(embedding);
+            }
+            checkpoint.migrated += documents.len();
+            storage.upsert_documents(&config.target_index, documents).await?;
+        }
+        checkpoint.skipped += skipped.len();
+
+        let done = page.next_cursor.is_none();
+        checkpoint.cursor = page.next_cursor;
+        on_checkpoint(&checkpoint);
+        if done {
+            break;
+        }
+    }
+
+    if let Some(alias) = &config.alias {
+        storage.swap_alias(alias, &config.target_index).await?;
+    }
+
+    Ok(MigrationReport {
+        target_index: config.target_index,
+        alias: config.alias,
+        old_model: old_model.model_name().to_string(),
+        new_model: new_model.model_name().to_string(),
+        migrated: checkpoint.migrated,
+        skipped: checkpoint.skipped,
+    })
+}
+
+#[cfg(all(test, feature = "memory"))]
+mod tests {
+    use super::*;
+    use crate::memory::MemoryVectorStorage;
+    use async_trait::async_trait;
+
+    /// Embeds text as its length followed by a constant
+    struct FixedModel {
+        name: &'static str,
+        value: f32,
+        dimensions: usize,
+    }
+
+    #[async_trait]
+    impl EmbeddingModel for FixedModel {
+        type Config = ();
+
+        async fn embed_text(&self, text: &str) -> Result<Vector> {
+            let mut vector = vec![self.value; self.dimensions];
+            vector[0] = text.len() as f32;
+            Ok(vector)
+        }
+
+        async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vector>> {
+            let mut vectors = Vec::with_capacity(texts.len());
+            for text in texts {
+                vectors.push(self.embed_text(text).await?);
+            }
+            Ok(vectors)
+        }
+
+        fn dimensions(&self) -> usize {
+            self.dimensions
+        }
+
+        fn model_name(&self) -> &str {
+            self.name
+        }
+
+        fn max_input_length(&self) -> Option<usize> {
+            None
+        }
+
+        async fn health_check(&self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_migration_swaps_alias_after_reembedding() {
+        let storage = MemoryVectorStorage::new().await.unwrap();
+        let old_model = FixedModel { name: "old", value: 0.5, dimensions: 2 };
+        let new_model = FixedModel { name: "new", value: 1.0, dimensions: 3 };
+
+        storage.create_index(IndexConfig::new("docs_v1", 2)).await.unwrap();
+        storage.swap_alias("docs", "docs_v1").await.unwrap();
+        let documents: Vec<Document> = (0..5)
+            .map(|i| Document::new(format!("doc{}", i), if i == 3 { "" } else { "text" }).with_embedding(vec![0.0, 0.5]))
+            .collect();
+        storage.upsert_documents("docs", documents).await.unwrap();
+
+        let mut checkpoints = Vec::new();
+        let config = MigrationConfig::new("docs", "docs_v2").with_alias("docs").with_batch_size(2);
+        let report = migrate_embeddings_with_progress(&storage, &old_model, &new_model, config, |checkpoint| {
+            checkpoints.push(checkpoint.clone())
+        })
+        .await
+        .unwrap();
+
+        assert_eq!((report.migrated, report.skipped), (4, 1));
+        assert_eq!(checkpoints.len(), 3);
+        assert_eq!(checkpoints[0].cursor.as_deref(), Some("doc1"));
+        assert_eq!(checkpoints.last().unwrap().cursor, None);
+
+        let info = storage.describe_index("docs").await.unwrap();
+        assert_eq!((info.name.as_str(), info.dimension, info.vector_count), ("docs_v2", 3, 4));
+        let migrated = storage.get_documents("docs", vec!["doc0".to_string()], true).await.unwrap();
+        assert_eq!(migrated[0].embedding, Some(vec![4.0, 1.0, 1.0]));
+    }
+
+    #[tokio::test]
+    async fn test_migration_rejects_mismatched_old_model() {
+        let storage = MemoryVectorStorage::new().await.unwrap();
+        storage.create_index(IndexConfig::new("docs", 4)).await.unwrap();
+        let old_model = FixedModel { name: "old", value: 0.5, dimensions: 2 };
+        let new_model = FixedModel { name: "new", value: 1.0, dimensions: 3 };
+
+        let result = migrate_embeddings(&storage, &old_model, &new_model, MigrationConfig::new("docs", "docs_v2")).await;
+        assert!(matches!(result, Err(VectorError::InvalidConfig(_))));
+        assert!(storage.describe_index("docs_v2").await.is_err());
+    }
+}
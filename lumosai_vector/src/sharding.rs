@@ -0,0 +1,436 @@
+//! Sharded vector storage
+//!
+//! [`ShardedVectorStorage`] partitions the documents of every index across several
+//! underlying stores. Writes are routed to a single shard chosen from the document id
+//! or a metadata field; searches are scattered to all shards and the per-shard results
+//! merged by score. Every shard holds a partition of each index, so index operations are
+//! applied to all shards.
+
+use async_trait::async_trait;
+use futures::future::{join_all, try_join_all};
+use std::collections::HashMap;
+
+use lumosai_vector_core::prelude::*;
+
+/// How documents are assigned to shards
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShardStrategy {
+    /// Hash of the document id
+    Hash,
+    /// Hash of a metadata field, e.g. a tenant id, so related documents share a shard
+    ///
+    /// Documents without the field fall back to the id hash. Searches filtering on
+    /// the field with an equality condition only visit the owning shard.
+    MetadataKey(String),
+}
+
+/// FNV-1a, stable across processes and Rust versions
+fn stable_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| (hash ^ *byte as u64).wrapping_mul(0x100000001b3))
+}
+
+fn value_key(value: &MetadataValue) -> String {
+    match value {
+        MetadataValue::String(s) => s.clone(),
+        MetadataValue::Integer(i) => i.to_string(),
+        MetadataValue::Boolean(b) => b.to_string(),
+        other => format!("{:?}", other),
+    }
+}
+
+/// Composite storage that partitions documents across shards
+pub struct ShardedVectorStorage<S: VectorStorage> {
+    shards: Vec<S>,
+    strategy: ShardStrategy,
+}
+
+impl<S: VectorStorage> ShardedVectorStorage<S> {
+    /// Partition documents across `shards` with the given strategy
+    pub fn new(shards: Vec<S>, strategy: ShardStrategy) -> Result<Self> {
+        if shards.is_empty() {
+            return Err(VectorError::InvalidConfig("Sharded storage needs at least one shard".to_string()));
+        }
+        Ok(Self { shards, strategy })
+    }
+
+    /// Underlying shards
+    pub fn shards(&self) -> &[S] {
+        &self.shards
+    }
+
+    /// Sharding strategy
+    pub fn strategy(&self) -> &ShardStrategy {
+        &self.strategy
+    }
+
+    fn shard_for_key(&self, key: &str) -> usize {
+        (stable_hash(key.as_bytes()) % self.shards.len() as u64) as usize
+    }
+
+    /// Shard a document is stored on
+    pub fn shard_for(&self, document: &Document) -> usize {
+        match &self.strategy {
+            ShardStrategy::MetadataKey(field) => match document.metadata.get(field) {
+                Some(value) => self.shard_for_key(&value_key(value)),
+                None => self.shard_for_key(&document.id),
+            },
+            ShardStrategy::Hash => self.shard_for_key(&document.id),
+        }
+    }
+
+    /// Shard owning a document id, if it can be derived from the id alone
+    fn shard_for_id(&self, id: &str) -> Option<usize> {
+        match self.strategy {
+            ShardStrategy::Hash => Some(self.shard_for_key(id)),
+            ShardStrategy::MetadataKey(_) => None,
+        }
+    }
+
+    /// Shard a search must visit, if its filter pins the shard key
+    fn shard_for_filter(&self, filter: Option<&FilterCondition>) -> Option<usize> {
+        let ShardStrategy::MetadataKey(field) = &self.strategy else {
+            return None;
+        };
+        match filter? {
+            FilterCondition::Eq(f, value) if f == field => Some(self.shard_for_key(&value_key(value))),
+            FilterCondition::And(conditions) => conditions.iter().find_map(|c| self.shard_for_filter(Some(c))),
+            _ => None,
+        }
+    }
+
+    /// Group ids by owning shard; ids of unknown placement go to every shard
+    fn partition_ids(&self, ids: Vec<DocumentId>) -> Vec<Vec<DocumentId>> {
+        let mut partitions = vec![Vec::new(); self.shards.len()];
+        for id in ids {
+            match self.shard_for_id(&id) {
+                Some(shard) => partitions[shard].push(id),
+                None => partitions.iter_mut().for_each(|p| p.push(id.clone())),
+            }
+        }
+        partitions
+    }
+
+    /// Remove stale copies of documents whose shard key moved them to another shard
+    async fn remove_from_other_shards(&self, placement: &HashMap<usize, Vec<DocumentId>>, index_name: &str) -> Result<()> {
+        if self.strategy == ShardStrategy::Hash {
+            return Ok(());
+        }
+        try_join_all(self.shards.iter().enumerate().filter_map(|(shard, storage)| {
+            let ids: Vec<DocumentId> = placement
+                .iter()
+                .filter(|(owner, _)| **owner != shard)
+                .flat_map(|(_, ids)| ids.iter().cloned())
+                .collect();
+            (!ids.is_empty()).then(|| storage.delete_documents(index_name, ids))
+        }))
+        .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<S: VectorStorage> VectorStorage for ShardedVectorStorage<S> {
+    type Config = S::Config;
+
+    async fn create_index(&self, config: IndexConfig) -> Result<()> {
+        try_join_all(self.shards.iter().map(|shard| shard.create_index(config.clone()))).await?;
+        Ok(())
+    }
+
+    async fn list_indexes(&self) -> Result<Vec<String>> {
+        self.shards[0].list_indexes().await
+    }
+
+    async fn describe_index(&self, index_name: &str) -> Result<IndexInfo> {
+        let infos = try_join_all(self.shards.iter().map(|shard| shard.describe_index(index_name))).await?;
+        let mut infos = infos.into_iter();
+        let mut merged = infos.next().expect("at least one shard");
+        for info in infos {
+            merged.vector_count += info.vector_count;
+            merged.size_bytes += info.size_bytes;
+            merged.created_at = [merged.created_at, info.created_at].into_iter().flatten().min();
+            merged.updated_at = merged.updated_at.max(info.updated_at);
+        }
+        merged.metadata.insert("shards".to_string(), (self.shards.len() as i64).into());
+        Ok(merged)
+    }
+
+    async fn delete_index(&self, index_name: &str) -> Result<()> {
+        try_join_all(self.shards.iter().map(|shard| shard.delete_index(index_name))).await?;
+        Ok(())
+    }
+
+    async fn upsert_documents(&self, index_name: &str, documents: Vec<Document>) -> Result<Vec<DocumentId>> {
+        let order: Vec<DocumentId> = documents.iter().map(|d| d.id.clone()).collect();
+        let mut partitions: HashMap<usize, Vec<Document>> = HashMap::new();
+        for document in documents {
+            partitions.entry(self.shard_for(&document)).or_default().push(document);
+        }
+        let placement: HashMap<usize, Vec<DocumentId>> = partitions
+            .iter()
+            .map(|(shard, docs)| (*shard, docs.iter().map(|d| d.id.clone()).collect()))
+            .collect();
+
+        try_join_all(partitions.into_iter().map(|(shard, docs)| self.shards[shard].upsert_documents(index_name, docs))).await?;
+        self.remove_from_other_shards(&placement, index_name).await?;
+        Ok(order)
+    }
+
+    async fn search(&self, request: SearchRequest) -> Result<SearchResponse> {
+        let started = std::time::Instant::now();
+        let targets: Vec<&S> = match self.shard_for_filter(request.filter.as_ref()) {
+            Some(shard) => vec![&self.shards[shard]],
+            None => self.shards.iter().collect(),
+        };
+        let responses = try_join_all(targets.into_iter().map(|shard| shard.search(request.clone()))).await?;
+
+        let total_count = responses.iter().map(|r| r.total_count).sum::<Option<usize>>();
+        let mut results: Vec<SearchResult> = responses.into_iter().flat_map(|r| r.results).collect();
+        results.sort_by(|a, b| b.score.total_cmp(&a.score));
+        results.truncate(request.top_k);
+
+        let mut response = SearchResponse::new(results).with_execution_time(started.elapsed().as_millis() as u64);
+        response.total_count = total_count;
+        Ok(response)
+    }
+
+    async fn update_document(&self, index_name: &str, document: Document) -> Result<()> {
+        let shard = self.shard_for(&document);
+        if self.strategy == ShardStrategy::Hash {
+            return self.shards[shard].update_document(index_name, document).await;
+        }
+        // A changed shard key moves the document, so it may not exist on its new shard yet
+        let placement = HashMap::from([(shard, vec![document.id.clone()])]);
+        self.shards[shard].upsert_documents(index_name, vec![document]).await?;
+        self.remove_from_other_shards(&placement, index_name).await
+    }
+
+    async fn delete_documents(&self, index_name: &str, ids: Vec<DocumentId>) -> Result<()> {
+        let partitions = self.partition_ids(ids);
+        try_join_all(
+            self.shards
+                .iter()
+                .zip(partitions)
+                .filter(|(_, ids)| !ids.is_empty())
+                .map(|(shard, ids)| shard.delete_documents(index_name, ids)),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn get_documents(&self, index_name: &str, ids: Vec<DocumentId>, include_vectors: bool) -> Result<Vec<Document>> {
+        let order = ids.clone();
+        let partitions = self.partition_ids(ids);
+        let found = try_join_all(
+            self.shards
+                .iter()
+                .zip(partitions)
+                .filter(|(_, ids)| !ids.is_empty())
+                .map(|(shard, ids)| shard.get_documents(index_name, ids, include_vectors)),
+        )
+        .await?;
+
+        let mut by_id: HashMap<DocumentId, Document> = found.into_iter().flatten().map(|d| (d.id.clone(), d)).collect();
+        Ok(order.iter().filter_map(|id| by_id.remove(id)).collect())
+    }
+
+    /// Merges the per-shard pages by id, so shards must page in id order with the last
+    /// returned id as cursor
+    async fn list_documents(
+        &self,
+        index_name: &str,
+        cursor: Option<String>,
+        limit: usize,
+        filter: Option<FilterCondition>,
+    ) -> Result<DocumentPage> {
+        if let Some(shard) = self.shard_for_filter(filter.as_ref()) {
+            return self.shards[shard].list_documents(index_name, cursor, limit, filter).await;
+        }
+        let pages = try_join_all(
+            self.shards.iter().map(|shard| shard.list_documents(index_name, cursor.clone(), limit, filter.clone())),
+        )
+        .await?;
+
+        let mut remaining = pages.iter().any(|page| page.next_cursor.is_some());
+        let mut documents: Vec<Document> = pages.into_iter().flat_map(|page| page.documents).collect();
+        documents.sort_by(|a, b| a.id.cmp(&b.id));
+        remaining |= documents.len() > limit;
+        documents.truncate(limit);
+
+        let next_cursor = if remaining {
+            documents.last().map(|document| document.id.clone())
+        } else {
+            None
+        };
+        Ok(DocumentPage::new(documents, next_cursor))
+    }
+
+    async fn aggregate(&self, request: AggregateRequest) -> Result<AggregateResponse> {
+        let targets: Vec<&S> = match self.shard_for_filter(request.filter.as_ref()) {
+            Some(shard) => return self.shards[shard].aggregate(request).await,
+            None => self.shards.iter().collect(),
+        };
+        // Facet limits are applied after merging, otherwise values that are frequent
+        // overall but not on every shard would be dropped
+        let mut unlimited = request.clone();
+        for aggregation in &mut unlimited.aggregations {
+            if let Aggregation::Facet { limit, .. } = aggregation {
+                *limit = None;
+            }
+        }
+        let partials = try_join_all(targets.into_iter().map(|shard| shard.aggregate(unlimited.clone()))).await?;
+
+        let mut aggregator = Aggregator::new(&request.aggregations);
+        for partial in partials {
+            aggregator.merge(partial);
+        }
+        Ok(aggregator.finish())
+    }
+
+    async fn create_alias(&self, alias: &str, index_name: &str) -> Result<()> {
+        try_join_all(self.shards.iter().map(|shard| shard.create_alias(alias, index_name))).await?;
+        Ok(())
+    }
+
+    async fn swap_alias(&self, alias: &str, index_name: &str) -> Result<()> {
+        try_join_all(self.shards.iter().map(|shard| shard.swap_alias(alias, index_name))).await?;
+        Ok(())
+    }
+
+    async fn delete_alias(&self, alias: &str) -> Result<()> {
+        try_join_all(self.shards.iter().map(|shard| shard.delete_alias(alias))).await?;
+        Ok(())
+    }
+
+    async fn list_aliases(&self) -> Result<HashMap<String, String>> {
+        self.shards[0].list_aliases().await
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        for result in join_all(self.shards.iter().map(|shard| shard.health_check())).await {
+            result?;
+        }
+        Ok(())
+    }
+
+    fn backend_info(&self) -> BackendInfo {
+        let strategy = match &self.strategy {
+            ShardStrategy::Hash => "hash".to_string(),
+            ShardStrategy::MetadataKey(field) => format!("metadata:{}", field),
+        };
+        self.shards[0]
+            .backend_info()
+            .with_feature("sharding")
+            .with_metadata("shards.count", self.shards.len() as i64)
+            .with_metadata("shards.strategy", strategy)
+    }
+}
+
+#[cfg(all(test, feature = "memory"))]
+mod tests {
+    use super::*;
+    use crate::memory::MemoryVectorStorage;
+
+    async fn sharded(strategy: ShardStrategy) -> ShardedVectorStorage<MemoryVectorStorage> {
+        let mut shards = Vec::new();
+        for _ in 0..3 {
+            shards.push(MemoryVectorStorage::new().await.unwrap());
+        }
+        let storage = ShardedVectorStorage::new(shards, strategy).unwrap();
+        storage.create_index(IndexConfig::new("docs", 2)).await.unwrap();
+        storage
+    }
+
+    #[tokio::test]
+    async fn test_scatter_gather_search_merges_scores() {
+        let storage = sharded(ShardStrategy::Hash).await;
+        let documents: Vec<Document> = (0..12)
+            .map(|i| Document::new(format!("doc{}", i), "text").with_embedding(vec![1.0, i as f32 / 10.0]))
+            .collect();
+        storage.upsert_documents("docs", documents).await.unwrap();
+
+        assert_eq!(storage.describe_index("docs").await.unwrap().vector_count, 12);
+        let mut populated = 0;
+        for shard in storage.shards() {
+            populated += (shard.describe_index("docs").await.unwrap().vector_count > 0) as usize;
+        }
+        assert!(populated > 1);
+
+        let response = storage.search(SearchRequest::new("docs", vec![1.0, 0.0]).with_top_k(3)).await.unwrap();
+        let ids: Vec<_> = response.results.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["doc0", "doc1", "doc2"]);
+
+        let fetched = storage.get_documents("docs", vec!["doc7".to_string(), "doc3".to_string()], false).await.unwrap();
+        assert_eq!(fetched.iter().map(|d| d.id.as_str()).collect::<Vec<_>>(), vec!["doc7", "doc3"]);
+    }
+
+    #[tokio::test]
+    async fn test_metadata_key_keeps_tenant_on_one_shard() {
+        let storage = sharded(ShardStrategy::MetadataKey("tenant".to_string())).await;
+        let document = Document::new("a", "text").with_embedding(vec![1.0, 0.0]).with_metadata("tenant", "acme");
+        storage.upsert_documents("docs", vec![document.clone()]).await.unwrap();
+        let owner = storage.shard_for(&document);
+
+        // Moving the document to another tenant removes the stale copy
+        let moved = (0..100)
+            .map(|i| document.clone().with_metadata("tenant", format!("tenant{}", i)))
+            .find(|d| storage.shard_for(d) != owner)
+            .unwrap();
+        storage.update_document("docs", moved.clone()).await.unwrap();
+        assert_eq!(storage.describe_index("docs").await.unwrap().vector_count, 1);
+        assert!(storage.shards()[owner].get_documents("docs", vec!["a".to_string()], false).await.unwrap().is_empty());
+
+        let request = SearchRequest::new("docs", vec![1.0, 0.0])
+            .with_filter(FilterCondition::eq("tenant", moved.metadata.get("tenant").unwrap().clone()));
+        assert_eq!(storage.search(request).await.unwrap().results.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_list_documents_pages_across_shards_in_id_order() {
+        let storage = sharded(ShardStrategy::Hash).await;
+        let documents: Vec<Document> = (0..10)
+            .map(|i| Document::new(format!("doc{}", i), "text").with_embedding(vec![1.0, 0.0]))
+            .collect();
+        storage.upsert_documents("docs", documents).await.unwrap();
+
+        let mut ids = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = storage.list_documents("docs", cursor, 3, None).await.unwrap();
+            assert!(page.documents.len() <= 3);
+            ids.extend(page.documents.into_iter().map(|d| d.id));
+            cursor = page.next_cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+        let mut expected: Vec<String> = (0..10).map(|i| format!("doc{}", i)).collect();
+        expected.sort();
+        assert_eq!(ids, expected);
+    }
+
+    #[tokio::test]
+    async fn test_aggregations_merge_across_shards() {
+        let storage = sharded(ShardStrategy::Hash).await;
+        let documents: Vec<Document> = (0..12)
+            .map(|i| {
+                Document::new(format!("doc{}", i), "text")
+                    .with_embedding(vec![1.0, 0.0])
+                    .with_metadata("lang", if i < 7 { "en" } else { "de" })
+                    .with_metadata("year", 2000 + i as i64)
+            })
+            .collect();
+        storage.upsert_documents("docs", documents).await.unwrap();
+
+        let request = AggregateRequest::new("docs")
+            .with_aggregation(Aggregation::Count)
+            .with_aggregation(Aggregation::top_values("lang", 1))
+            .with_aggregation(Aggregation::max("year"));
+        let response = storage.aggregate(request).await.unwrap();
+        assert_eq!(response.results, vec![
+            AggregationResult::Count(12),
+            AggregationResult::Facet(vec![FacetCount { value: "en".into(), count: 7 }]),
+            AggregationResult::Value(Some(MetadataValue::Integer(2011))),
+        ]);
+    }
+}
@@ -28,6 +28,10 @@ pub struct WeaviateConfig {
     /// Batch size for bulk operations
     pub batch_size: usize,
     
+    /// Maximum number of concurrent requests
+    #[serde(default = "default_max_connections")]
+    pub max_connections: u32,
+    
     /// Whether to create schema automatically
     pub auto_schema: bool,
     
@@ -35,6 +39,10 @@ pub struct WeaviateConfig {
     pub vectorizer: Option<String>,
 }
 
+fn default_max_connections() -> u32 {
+    10
+}
+
 impl Default for WeaviateConfig {
     fn default() -> Self {
         Self {
@@ -45,6 +53,7 @@ impl Default for WeaviateConfig {
             tenant: None,
             timeout_seconds: 30,
             batch_size: 100,
+            max_connections: default_max_connections(),
             auto_schema: true,
             vectorizer: None,
         }
@@ -96,6 +105,12 @@ impl WeaviateConfig {
         self
     }
     
+    /// Set the maximum number of concurrent requests
+    pub fn with_max_connections(mut self, max_connections: u32) -> Self {
+        self.max_connections = max_connections;
+        self
+    }
+    
     /// Enable or disable automatic schema creation
     pub fn with_auto_schema(mut self, auto: bool) -> Self {
         self.auto_schema = auto;
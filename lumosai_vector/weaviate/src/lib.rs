@@ -52,7 +52,7 @@ pub mod filter;
 pub mod error;
 pub mod schema;
 
-pub use storage::WeaviateVectorStorage;
+pub use storage::{ClientBuilderFn, WeaviateConnector, WeaviateVectorStorage};
 pub use config::WeaviateConfig;
 pub use error::WeaviateError;
 
@@ -1,6 +1,7 @@
 //! Weaviate vector storage implementation
 
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Duration;
 use async_trait::async_trait;
 use reqwest::Client;
@@ -14,26 +15,39 @@ use crate::error::WeaviateResult;
 use crate::schema::{WeaviateClass, WeaviateProperty};
 use crate::filter::convert_filter_to_where;
 
-/// Weaviate vector storage implementation
-pub struct WeaviateVectorStorage {
-    client: Client,
+/// Creates the HTTP client builder for new connections, e.g. with a proxy or private CA
+pub type ClientBuilderFn = Arc<dyn Fn() -> reqwest::ClientBuilder + Send + Sync>;
+
+/// Opens HTTP clients for the [`ConnectionManager`]
+pub struct WeaviateConnector {
     config: WeaviateConfig,
-    base_url: String,
+    client_builder: Option<ClientBuilderFn>,
 }
 
-impl WeaviateVectorStorage {
-    /// Create a new Weaviate vector storage instance
-    pub async fn new(url: &str) -> Result<Self> {
-        let config = WeaviateConfig::new(url);
-        Self::with_config(config).await
+impl WeaviateConnector {
+    /// Create a connector for the given configuration
+    pub fn new(config: WeaviateConfig) -> Self {
+        Self { config, client_builder: None }
     }
 
-    /// Create a new Weaviate vector storage instance with configuration
-    pub async fn with_config(config: WeaviateConfig) -> Result<Self> {
-        config.validate().map_err(VectorError::from)?;
-        
-        let mut client_builder = Client::builder()
-            .timeout(Duration::from_secs(config.timeout_seconds));
+    /// Build HTTP clients from `client_builder` instead of the reqwest defaults
+    ///
+    /// The configured timeout and authentication headers are applied on top.
+    pub fn with_client_builder(mut self, client_builder: ClientBuilderFn) -> Self {
+        self.client_builder = Some(client_builder);
+        self
+    }
+}
+
+#[async_trait]
+impl Connector for WeaviateConnector {
+    type Connection = Client;
+
+    async fn connect(&self, manager: &ConnectionManagerConfig) -> Result<Client> {
+        let config = &self.config;
+        let client_builder = self.client_builder.as_ref().map_or_else(Client::builder, |builder| builder())
+            .timeout(Duration::from_secs(config.timeout_seconds))
+            .pool_max_idle_per_host(manager.max_connections as usize);
         
         // Add authentication headers if provided
         let mut default_headers = reqwest::header::HeaderMap::new();
@@ -61,20 +75,74 @@ impl WeaviateVectorStorage {
             .build()
             .map_err(|e| VectorError::ConnectionFailed(format!("Failed to create HTTP client: {}", e)))?;
         
-        let base_url = config.api_url();
-        
         // Test connection
-        let health_url = format!("{}/meta", base_url);
-        client.get(&health_url).send().await
+        self.probe(&client).await?;
+        Ok(client)
+    }
+
+    async fn probe(&self, client: &Client) -> Result<()> {
+        let health_url = format!("{}/meta", self.config.api_url());
+        let response = client.get(&health_url).send().await
             .map_err(|e| VectorError::ConnectionFailed(format!("Failed to connect to Weaviate: {}", e)))?;
+        if !response.status().is_success() {
+            return Err(VectorError::ConnectionFailed(format!("Weaviate health check returned {}", response.status())));
+        }
+        Ok(())
+    }
+}
+
+/// Weaviate vector storage implementation
+pub struct WeaviateVectorStorage {
+    connections: Arc<ConnectionManager<WeaviateConnector>>,
+    config: WeaviateConfig,
+    base_url: String,
+}
+
+impl WeaviateVectorStorage {
+    /// Create a new Weaviate vector storage instance
+    pub async fn new(url: &str) -> Result<Self> {
+        let config = WeaviateConfig::new(url);
+        Self::with_config(config).await
+    }
+
+    /// Create a new Weaviate vector storage instance with configuration
+    pub async fn with_config(config: WeaviateConfig) -> Result<Self> {
+        let connector = WeaviateConnector::new(config.clone());
+        Self::with_connector(config, connector).await
+    }
+
+    /// Create a new Weaviate vector storage instance whose HTTP clients come from `client_builder`
+    pub async fn with_client_builder(config: WeaviateConfig, client_builder: ClientBuilderFn) -> Result<Self> {
+        let connector = WeaviateConnector::new(config.clone()).with_client_builder(client_builder);
+        Self::with_connector(config, connector).await
+    }
+
+    async fn with_connector(config: WeaviateConfig, connector: WeaviateConnector) -> Result<Self> {
+        config.validate().map_err(VectorError::from)?;
+        
+        let manager_config = ConnectionManagerConfig::default()
+            .with_pool_size(0, config.max_connections)
+            .with_acquire_timeout(Duration::from_secs(config.timeout_seconds));
+        let connections = ConnectionManager::new(connector, manager_config).await?;
+        let base_url = config.api_url();
         
         Ok(Self {
-            client,
+            connections: Arc::new(connections),
             config,
             base_url,
         })
     }
     
+    /// Current HTTP client
+    async fn client(&self) -> Client {
+        self.connections.connection().await
+    }
+    
+    /// Connection manager shared by all operations
+    pub fn connection_manager(&self) -> &Arc<ConnectionManager<WeaviateConnector>> {
+        &self.connections
+    }
+    
     /// Convert similarity metric to Weaviate distance
     fn convert_metric(metric: SimilarityMetric) -> &'static str {
         match metric {
@@ -103,7 +171,7 @@ impl WeaviateVectorStorage {
     /// Check if a class exists
     async fn class_exists(&self, class_name: &str) -> WeaviateResult<bool> {
         let url = format!("{}/schema/{}", self.base_url, class_name);
-        let response = self.client.get(&url).send().await?;
+        let response = self.client().await.get(&url).send().await?;
         
         Ok(response.status().is_success())
     }
@@ -139,7 +207,7 @@ impl WeaviateVectorStorage {
         };
         
         let url = format!("{}/schema", self.base_url);
-        let response = self.client
+        let response = self.client().await
             .post(&url)
             .json(&class_def)
             .send()
@@ -157,7 +225,7 @@ impl WeaviateVectorStorage {
     /// Delete a Weaviate class
     async fn delete_class(&self, class_name: &str) -> WeaviateResult<()> {
         let url = format!("{}/schema/{}", self.base_url, class_name);
-        let response = self.client.delete(&url).send().await?;
+        let response = self.client().await.delete(&url).send().await?;
         
         if !response.status().is_success() {
             let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
@@ -171,7 +239,7 @@ impl WeaviateVectorStorage {
     /// Get class information
     async fn get_class_info(&self, class_name: &str) -> WeaviateResult<Value> {
         let url = format!("{}/schema/{}", self.base_url, class_name);
-        let response = self.client.get(&url).send().await?;
+        let response = self.client().await.get(&url).send().await?;
         
         if !response.status().is_success() {
             return Err(WeaviateError::ClassNotFound(class_name.to_string()));
@@ -184,7 +252,7 @@ impl WeaviateVectorStorage {
     /// List all classes
     async fn list_classes(&self) -> WeaviateResult<Vec<String>> {
         let url = format!("{}/schema", self.base_url);
-        let response = self.client.get(&url).send().await?;
+        let response = self.client().await.get(&url).send().await?;
         
         if !response.status().is_success() {
             let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
@@ -258,7 +326,7 @@ impl VectorStorage for WeaviateVectorStorage {
             )
         });
 
-        let count_response = self.client
+        let count_response = self.client().await
             .post(&count_url)
             .json(&count_query)
             .send()
@@ -325,7 +393,7 @@ impl VectorStorage for WeaviateVectorStorage {
                 "objects": objects
             });
 
-            let response = self.client
+            let response = self.client().await
                 .post(&batch_url)
                 .json(&batch_request)
                 .send()
@@ -352,6 +420,16 @@ impl VectorStorage for WeaviateVectorStorage {
                     "Text queries not supported by Weaviate storage".to_string()
                 ));
             }
+            SearchQuery::Sparse(_) | SearchQuery::Hybrid { .. } => {
+                return Err(VectorError::NotSupported(
+                    "Sparse and hybrid queries not supported by Weaviate storage".to_string()
+                ));
+            }
+            SearchQuery::MultiVector(_) => {
+                return Err(VectorError::NotSupported(
+                    "Multi-vector queries not supported by Weaviate storage".to_string()
+                ));
+            }
         };
 
         // Build GraphQL query
@@ -383,7 +461,7 @@ impl VectorStorage for WeaviateVectorStorage {
         });
 
         let url = format!("{}/graphql", self.base_url);
-        let response = self.client
+        let response = self.client().await
             .post(&url)
             .json(&query_request)
             .send()
@@ -452,7 +530,7 @@ impl VectorStorage for WeaviateVectorStorage {
 
         for id in ids {
             let url = format!("{}/objects/{}/{}", self.base_url, class_name, id);
-            let response = self.client.delete(&url).send().await
+            let response = self.client().await.delete(&url).send().await
                 .map_err(|e| VectorError::OperationFailed(format!("Failed to delete document: {}", e)))?;
 
             if !response.status().is_success() && response.status() != reqwest::StatusCode::NOT_FOUND {
@@ -476,7 +554,7 @@ impl VectorStorage for WeaviateVectorStorage {
             };
 
             let url = format!("{}/objects/{}/{}?include={}", self.base_url, class_name, id, fields);
-            let response = self.client.get(&url).send().await
+            let response = self.client().await.get(&url).send().await
                 .map_err(|e| VectorError::OperationFailed(format!("Failed to get document: {}", e)))?;
 
             if response.status() == reqwest::StatusCode::NOT_FOUND {
@@ -533,10 +611,7 @@ impl VectorStorage for WeaviateVectorStorage {
     }
 
     async fn health_check(&self) -> Result<()> {
-        let url = format!("{}/meta", self.base_url);
-        self.client.get(&url).send().await
-            .map_err(|e| VectorError::ConnectionFailed(format!("Health check failed: {}", e)))?;
-        Ok(())
+        self.connections.health_check().await
     }
 
     fn backend_info(&self) -> BackendInfo {
@@ -545,5 +620,6 @@ impl VectorStorage for WeaviateVectorStorage {
             .with_feature("schema_management")
             .with_feature("graphql")
             .with_feature("batch_operations")
+            .with_pool_metrics(&self.connections.metrics())
     }
 }
@@ -105,6 +105,35 @@ pub fn builder() -> AgentBuilder {
     AgentBuilder::new()
 }
 
+/// 先规划后执行的Agent构建器
+///
+/// 规划模型把目标分解为步骤，执行代理逐步完成，步骤失败时重新规划剩余步骤。
+///
+/// # 示例
+/// ```rust,no_run
+/// use std::sync::Arc;
+/// use lumosai_core::agent::create_basic_agent;
+/// use lumosai_core::agent::types::AgentGenerateOptions;
+/// use lumosai_core::llm::MockLlmProvider;
+///
+/// #[tokio::main]
+/// async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
+///     let llm = Arc::new(MockLlmProvider::new(vec![]));
+///     let worker = create_basic_agent("worker", "You complete tasks", llm);
+///     let agent = lumosai::agent::planner()
+///         .worker(Arc::new(worker))
+///         .max_replans(2)
+///         .build()?;
+///
+///     let state = agent.execute("Plan a trip to Paris", &AgentGenerateOptions::default()).await?;
+///     println!("{}", state.answer.unwrap_or_default());
+///     Ok(())
+/// }
+/// ```
+pub fn planner() -> lumosai_core::agent::PlannerAgentBuilder {
+    lumosai_core::agent::PlannerAgent::builder()
+}
+
 /// Agent构建器
 pub struct AgentBuilder {
     name: Option<String>,
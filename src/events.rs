@@ -6,6 +6,26 @@ use crate::{Result, Error};
 use std::sync::Arc;
 use serde::{Serialize, Deserialize};
 
+pub mod backend;
+#[cfg(feature = "events-nats")]
+pub mod nats;
+#[cfg(feature = "events-kafka")]
+pub mod kafka;
+
+pub use backend::{
+    EventBackend,
+    EventConsumer,
+    EventRecord,
+    Delivery,
+    InMemoryBackend,
+    EventBridge,
+    ORIGIN_HEADER,
+};
+#[cfg(feature = "events-nats")]
+pub use nats::{NatsBackend, NatsConfig};
+#[cfg(feature = "events-kafka")]
+pub use kafka::{KafkaRestBackend, KafkaRestConfig};
+
 // 重导出核心类型
 pub use lumosai_core::agent::events::{
     EventBus as CoreEventBus,
@@ -15,6 +35,20 @@ pub use lumosai_core::agent::events::{
     LogEventHandler,
     MetricsEventHandler,
 };
+pub use lumosai_core::agent::webhooks::{
+    WebhookDispatcher,
+    WebhookEndpoint,
+    WebhookRetryConfig,
+    WebhookTransport,
+    HttpWebhookTransport,
+    WebhookDelivery,
+    DeliveryStatus,
+    sign_payload,
+    verify_signature,
+    WEBHOOK_CONVERSATION_COMPLETED,
+    WEBHOOK_TOOL_FAILED,
+    WEBHOOK_GUARDRAIL_TRIGGERED,
+};
 
 /// 事件总线
 pub type EventBus = CoreEventBus;
@@ -255,6 +289,43 @@ pub async fn register_metrics_handler(event_bus: &EventBus) -> Result<Arc<Metric
     Ok(handler)
 }
 
+/// 注册Webhook推送
+/// 
+/// 匹配端点订阅的事件会以HMAC签名的HTTP请求推送，失败时按指数退避重试。
+/// 
+/// # 示例
+/// ```rust,no_run
+/// use lumosai::prelude::*;
+/// 
+/// #[tokio::main]
+/// async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
+///     let event_bus = lumosai::events::create_bus(1000);
+///     let webhooks = lumosai::events::register_webhooks(&event_bus, vec![
+///         lumosai::events::WebhookEndpoint::new("https://example.com/hooks", "whsec_123")
+///             .with_events(["conversation.completed", "tool.failed", "guardrail.triggered"]),
+///     ]).await?;
+///     
+///     // 自定义事件名即为Webhook事件类型
+///     lumosai::events::publish(&event_bus, "conversation.completed", serde_json::json!({
+///         "session_id": "session_001"
+///     })).await?;
+///     
+///     println!("Deliveries: {:?}", webhooks.deliveries().await);
+///     Ok(())
+/// }
+/// ```
+pub async fn register_webhooks(
+    event_bus: &EventBus,
+    endpoints: Vec<WebhookEndpoint>,
+) -> Result<Arc<WebhookDispatcher>> {
+    let dispatcher = Arc::new(WebhookDispatcher::new());
+    for endpoint in endpoints {
+        dispatcher.add_endpoint(endpoint).await?;
+    }
+    event_bus.register_handler(dispatcher.clone()).await?;
+    Ok(dispatcher)
+}
+
 /// 获取事件历史
 /// 
 /// # 示例
@@ -417,6 +488,22 @@ mod tests {
         assert!(metrics.get("total_events").is_some());
     }
     
+    #[tokio::test]
+    async fn test_register_webhooks() {
+        let event_bus = create_bus(100);
+
+        let dispatcher = register_webhooks(&event_bus, vec![
+            WebhookEndpoint::new("https://example.com/hooks", "secret")
+                .with_events([WEBHOOK_CONVERSATION_COMPLETED]),
+        ]).await.expect("Failed to register webhooks");
+        assert!(dispatcher.deliveries().await.is_empty());
+
+        let invalid = register_webhooks(&event_bus, vec![
+            WebhookEndpoint::new("not a url", "secret"),
+        ]).await;
+        assert!(invalid.is_err());
+    }
+    
     #[test]
     fn test_filter_builder() {
         let _filter = filter()
@@ -0,0 +1,492 @@
+//! 可插拔的事件总线后端
+//!
+//! 多实例部署时，各实例通过共享的事件流（内存、NATS JetStream或Kafka）交换事件。
+//! 后端提供至少一次投递语义：同一消费组内每条记录只分配给一个消费者，
+//! 未确认的记录在确认超时后或显式 `nack` 后重新投递；不同消费组各自独立消费全部记录。
+
+use crate::{Error, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, Notify};
+use tokio::task::JoinHandle;
+
+use super::{AgentEvent, CoreEventHandler, EventBus};
+
+/// 记录来源实例的头
+pub const ORIGIN_HEADER: &str = "lumos-origin";
+
+/// 事件记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventRecord {
+    /// 记录ID
+    pub id: String,
+    /// 主题
+    pub topic: String,
+    /// 分区键，相同键的记录保持顺序
+    pub key: Option<String>,
+    /// 事件内容
+    pub payload: serde_json::Value,
+    /// 附加头
+    pub headers: HashMap<String, String>,
+    /// 发布时间
+    pub timestamp: DateTime<Utc>,
+}
+
+impl EventRecord {
+    /// 创建新的事件记录
+    pub fn new(topic: impl Into<String>, payload: serde_json::Value) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            topic: topic.into(),
+            key: None,
+            payload,
+            headers: HashMap::new(),
+            timestamp: Utc::now(),
+        }
+    }
+
+    /// 从Agent事件创建记录，以Agent ID作为分区键
+    pub fn from_agent_event(topic: impl Into<String>, event: &AgentEvent) -> Result<Self> {
+        let mut record = Self::new(topic, serde_json::to_value(event)?);
+        record.key = event.agent_id().map(str::to_string);
+        record.timestamp = event.timestamp();
+        Ok(record)
+    }
+
+    /// 设置分区键
+    pub fn with_key(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    /// 添加头
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.insert(name.into(), value.into());
+        self
+    }
+
+    /// 解析为Agent事件
+    pub fn to_agent_event(&self) -> Option<AgentEvent> {
+        serde_json::from_value(self.payload.clone()).ok()
+    }
+}
+
+/// 一次投递
+#[derive(Debug, Clone)]
+pub struct Delivery {
+    /// 事件记录
+    pub record: EventRecord,
+    /// 第几次投递（从1开始）
+    pub attempt: u32,
+    /// 后端用于确认的句柄
+    pub ack_token: String,
+}
+
+/// 事件总线后端
+#[async_trait]
+pub trait EventBackend: Send + Sync {
+    /// 后端名称
+    fn name(&self) -> &str;
+
+    /// 发布记录
+    async fn publish(&self, record: EventRecord) -> Result<()>;
+
+    /// 以消费组身份订阅主题
+    async fn subscribe(&self, topic: &str, group: &str) -> Result<Box<dyn EventConsumer>>;
+}
+
+/// 事件消费者
+#[async_trait]
+pub trait EventConsumer: Send {
+    /// 拉取最多 `max` 条记录，没有记录时最多等待 `timeout`
+    async fn poll(&mut self, max: usize, timeout: Duration) -> Result<Vec<Delivery>>;
+
+    /// 确认处理完成
+    async fn ack(&mut self, delivery: &Delivery) -> Result<()>;
+
+    /// 处理失败，请求重新投递
+    async fn nack(&mut self, delivery: &Delivery) -> Result<()>;
+}
+
+struct InFlight {
+    deadline: Instant,
+    attempt: u32,
+}
+
+#[derive(Default)]
+struct GroupState {
+    /// 下一条从未投递过的记录
+    next_offset: usize,
+    /// 已投递未确认的记录
+    in_flight: BTreeMap<usize, InFlight>,
+    /// 等待重新投递的记录及其已投递次数
+    redeliver: VecDeque<(usize, u32)>,
+}
+
+#[derive(Default)]
+struct TopicState {
+    log: Vec<EventRecord>,
+    groups: HashMap<String, GroupState>,
+}
+
+struct MemoryShared {
+    topics: Mutex<HashMap<String, TopicState>>,
+    notify: Notify,
+    ack_timeout: Duration,
+}
+
+/// 进程内事件后端，用于测试和单实例部署
+#[derive(Clone)]
+pub struct InMemoryBackend {
+    shared: Arc<MemoryShared>,
+}
+
+impl InMemoryBackend {
+    /// 创建新的内存后端，确认超时为30秒
+    pub fn new() -> Self {
+        Self::with_ack_timeout(Duration::from_secs(30))
+    }
+
+    /// 指定确认超时创建
+    pub fn with_ack_timeout(ack_timeout: Duration) -> Self {
+        Self {
+            shared: Arc::new(MemoryShared {
+                topics: Mutex::new(HashMap::new()),
+                notify: Notify::new(),
+                ack_timeout,
+            }),
+        }
+    }
+}
+
+impl Default for InMemoryBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl EventBackend for InMemoryBackend {
+    fn name(&self) -> &str {
+        "memory"
+    }
+
+    async fn publish(&self, record: EventRecord) -> Result<()> {
+        let mut topics = self.shared.topics.lock().await;
+        topics.entry(record.topic.clone()).or_default().log.push(record);
+        drop(topics);
+        self.shared.notify.notify_waiters();
+        Ok(())
+    }
+
+    async fn subscribe(&self, topic: &str, group: &str) -> Result<Box<dyn EventConsumer>> {
+        let mut topics = self.shared.topics.lock().await;
+        // 新消费组从当前末尾开始，只接收订阅之后发布的记录
+        let state = topics.entry(topic.to_string()).or_default();
+        let end = state.log.len();
+        state.groups.entry(group.to_string()).or_insert_with(|| GroupState {
+            next_offset: end,
+            ..GroupState::default()
+        });
+        Ok(Box::new(InMemoryConsumer {
+            shared: self.shared.clone(),
+            topic: topic.to_string(),
+            group: group.to_string(),
+        }))
+    }
+}
+
+struct InMemoryConsumer {
+    shared: Arc<MemoryShared>,
+    topic: String,
+    group: String,
+}
+
+impl InMemoryConsumer {
+    async fn take(&self, max: usize) -> Vec<Delivery> {
+        let mut topics = self.shared.topics.lock().await;
+        let Some(topic) = topics.get_mut(&self.topic) else {
+            return Vec::new();
+        };
+        let Some(group) = topic.groups.get_mut(&self.group) else {
+            return Vec::new();
+        };
+
+        // 超时未确认的记录重新排队
+        let now = Instant::now();
+        let expired: Vec<usize> = group.in_flight.iter()
+            .filter(|(_, flight)| flight.deadline <= now)
+            .map(|(offset, _)| *offset)
+            .collect();
+        for offset in expired {
+            if let Some(flight) = group.in_flight.remove(&offset) {
+                group.redeliver.push_back((offset, flight.attempt));
+            }
+        }
+
+        let mut deliveries = Vec::new();
+        while deliveries.len() < max {
+            let (offset, previous_attempts) = match group.redeliver.pop_front() {
+                Some(entry) => entry,
+                None if group.next_offset < topic.log.len() => {
+                    group.next_offset += 1;
+                    (group.next_offset - 1, 0)
+                }
+                None => break,
+            };
+            let attempt = previous_attempts + 1;
+            group.in_flight.insert(offset, InFlight { deadline: now + self.shared.ack_timeout, attempt });
+            deliveries.push(Delivery {
+                record: topic.log[offset].clone(),
+                attempt,
+                ack_token: offset.to_string(),
+            });
+        }
+        deliveries
+    }
+
+    async fn settle(&self, delivery: &Delivery, requeue: bool) -> Result<()> {
+        let offset: usize = delivery.ack_token.parse()
+            .map_err(|_| Error::Event(format!("无效的确认句柄: {}", delivery.ack_token)))?;
+        let mut topics = self.shared.topics.lock().await;
+        let group = topics.get_mut(&self.topic)
+            .and_then(|topic| topic.groups.get_mut(&self.group))
+            .ok_or_else(|| Error::Event(format!("消费组不存在: {}", self.group)))?;
+        if let Some(flight) = group.in_flight.remove(&offset) {
+            if requeue {
+                group.redeliver.push_back((offset, flight.attempt));
+            }
+        }
+        drop(topics);
+        if requeue {
+            self.shared.notify.notify_waiters();
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl EventConsumer for InMemoryConsumer {
+    async fn poll(&mut self, max: usize, timeout: Duration) -> Result<Vec<Delivery>> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let notified = self.shared.notify.notified();
+            let deliveries = self.take(max).await;
+            let now = Instant::now();
+            if !deliveries.is_empty() || now >= deadline {
+                return Ok(deliveries);
+            }
+            // 同时按确认超时轮询，使过期记录能被重新投递
+            let wait = (deadline - now).min(self.shared.ack_timeout);
+            let _ = tokio::time::timeout(wait, notified).await;
+        }
+    }
+
+    async fn ack(&mut self, delivery: &Delivery) -> Result<()> {
+        self.settle(delivery, false).await
+    }
+
+    async fn nack(&mut self, delivery: &Delivery) -> Result<()> {
+        self.settle(delivery, true).await
+    }
+}
+
+/// 本地事件总线与共享事件流之间的桥接
+///
+/// 本地发布的事件转发到后端主题，从后端消费到的其他实例的事件重新发布到本地总线。
+/// 重新发布的事件不会再次转发，避免实例之间来回复制。
+pub struct EventBridge {
+    backend: Arc<dyn EventBackend>,
+    topic: String,
+    instance_id: String,
+    name: String,
+    injected: Arc<Mutex<HashSet<u64>>>,
+}
+
+impl EventBridge {
+    /// 创建桥接，`instance_id` 用于识别本实例发布的记录
+    pub fn new(backend: Arc<dyn EventBackend>, topic: impl Into<String>, instance_id: impl Into<String>) -> Self {
+        let topic = topic.into();
+        Self {
+            name: format!("event_bridge:{}:{}", backend.name(), topic),
+            backend,
+            topic,
+            instance_id: instance_id.into(),
+            injected: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    /// 将本地事件转发到后端
+    pub async fn attach(self: &Arc<Self>, event_bus: &EventBus) -> Result<()> {
+        event_bus.register_handler(self.clone()).await
+    }
+
+    /// 以消费组身份消费后端事件并发布到本地总线
+    ///
+    /// 记录在本地发布成功后才确认，发布失败的记录会重新投递。
+    pub async fn start_consuming(self: &Arc<Self>, event_bus: Arc<EventBus>, group: &str) -> Result<JoinHandle<()>> {
+        let mut consumer = self.backend.subscribe(&self.topic, group).await?;
+        let bridge = self.clone();
+        Ok(tokio::spawn(async move {
+            loop {
+                let deliveries = match consumer.poll(100, Duration::from_secs(1)).await {
+                    Ok(deliveries) => deliveries,
+                    Err(e) => {
+                        tracing::warn!("事件消费失败: {}", e);
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                        continue;
+                    }
+                };
+                for delivery in deliveries {
+                    let result = bridge.inject(&delivery.record, &event_bus).await;
+                    let settled = match result {
+                        Ok(()) => consumer.ack(&delivery).await,
+                        Err(e) => {
+                            tracing::warn!("事件 {} 发布到本地总线失败: {}", delivery.record.id, e);
+                            consumer.nack(&delivery).await
+                        }
+                    };
+                    if let Err(e) = settled {
+                        tracing::warn!("事件 {} 确认失败: {}", delivery.record.id, e);
+                    }
+                }
+            }
+        }))
+    }
+
+    async fn inject(&self, record: &EventRecord, event_bus: &EventBus) -> Result<()> {
+        if record.headers.get(ORIGIN_HEADER) == Some(&self.instance_id) {
+            return Ok(());
+        }
+        let Some(event) = record.to_agent_event() else {
+            tracing::debug!("忽略无法解析的事件记录 {}", record.id);
+            return Ok(());
+        };
+        let fingerprint = fingerprint(&event)?;
+        self.injected.lock().await.insert(fingerprint);
+        let result = event_bus.publish(event).await;
+        if result.is_err() {
+            self.injected.lock().await.remove(&fingerprint);
+        }
+        result
+    }
+}
+
+#[async_trait]
+impl CoreEventHandler for EventBridge {
+    async fn handle_event(&self, event: &AgentEvent) -> Result<()> {
+        if self.injected.lock().await.remove(&fingerprint(event)?) {
+            return Ok(());
+        }
+        let record = EventRecord::from_agent_event(self.topic.clone(), event)?
+            .with_header(ORIGIN_HEADER, self.instance_id.clone());
+        self.backend.publish(record).await
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn interested_events(&self) -> Vec<String> {
+        vec!["*".to_string()]
+    }
+}
+
+fn fingerprint(event: &AgentEvent) -> Result<u64> {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    serde_json::to_string(event)?.hash(&mut hasher);
+    Ok(hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(n: i64) -> EventRecord {
+        EventRecord::new("agents", serde_json::json!({ "n": n }))
+    }
+
+    #[tokio::test]
+    async fn test_consumer_groups() {
+        let backend = InMemoryBackend::new();
+        let mut worker_a = backend.subscribe("agents", "workflows").await.unwrap();
+        let mut worker_b = backend.subscribe("agents", "workflows").await.unwrap();
+        let mut analytics = backend.subscribe("agents", "analytics").await.unwrap();
+
+        for n in 0..4 {
+            backend.publish(record(n)).await.unwrap();
+        }
+
+        // 同组的两个消费者分摊记录，另一个组收到全部记录
+        let a = worker_a.poll(2, Duration::from_millis(10)).await.unwrap();
+        let b = worker_b.poll(10, Duration::from_millis(10)).await.unwrap();
+        assert_eq!(a.len() + b.len(), 4);
+        assert_eq!(a[0].record.payload["n"], 0);
+        assert_eq!(b[0].record.payload["n"], 2);
+        assert_eq!(analytics.poll(10, Duration::from_millis(10)).await.unwrap().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_at_least_once_redelivery() {
+        let backend = InMemoryBackend::with_ack_timeout(Duration::from_millis(20));
+        let mut consumer = backend.subscribe("agents", "workers").await.unwrap();
+        backend.publish(record(1)).await.unwrap();
+        backend.publish(record(2)).await.unwrap();
+
+        let first = consumer.poll(10, Duration::from_millis(10)).await.unwrap();
+        assert_eq!(first.len(), 2);
+        consumer.ack(&first[0]).await.unwrap();
+        consumer.nack(&first[1]).await.unwrap();
+
+        let retry = consumer.poll(10, Duration::from_millis(10)).await.unwrap();
+        assert_eq!(retry.len(), 1);
+        assert_eq!(retry[0].record.payload["n"], 2);
+        assert_eq!(retry[0].attempt, 2);
+
+        // 未确认的记录在超时后重新投递
+        let again = consumer.poll(10, Duration::from_millis(200)).await.unwrap();
+        assert_eq!(again.len(), 1);
+        assert_eq!(again[0].attempt, 3);
+        consumer.ack(&again[0]).await.unwrap();
+        assert!(consumer.poll(10, Duration::from_millis(30)).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_bridge_shares_events_between_instances() {
+        let backend: Arc<dyn EventBackend> = Arc::new(InMemoryBackend::new());
+        let mut probe = backend.subscribe("agents", "probe").await.unwrap();
+        let bus_a = Arc::new(EventBus::new(100));
+        let bus_b = Arc::new(EventBus::new(100));
+        let _rx_a = bus_a.subscribe();
+        let mut rx_b = bus_b.subscribe();
+
+        let bridge_a = Arc::new(EventBridge::new(backend.clone(), "agents", "instance-a"));
+        let bridge_b = Arc::new(EventBridge::new(backend.clone(), "agents", "instance-b"));
+        bridge_a.attach(&bus_a).await.unwrap();
+        bridge_b.attach(&bus_b).await.unwrap();
+        let consumer_a = bridge_a.start_consuming(bus_a.clone(), "instance-a").await.unwrap();
+        let consumer_b = bridge_b.start_consuming(bus_b.clone(), "instance-b").await.unwrap();
+
+        bus_a.publish(AgentEvent::AgentStarted {
+            agent_id: "agent-1".to_string(),
+            timestamp: Utc::now(),
+            metadata: HashMap::new(),
+        }).await.unwrap();
+
+        let received = tokio::time::timeout(Duration::from_secs(2), rx_b.recv()).await.unwrap().unwrap();
+        assert_eq!(received.agent_id(), Some("agent-1"));
+
+        // 实例B重新发布的事件不会再转发回共享流
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(probe.poll(10, Duration::from_millis(20)).await.unwrap().len(), 1);
+        assert_eq!(bus_a.get_history(None).await.len(), 1);
+
+        consumer_a.abort();
+        consumer_b.abort();
+    }
+}
@@ -0,0 +1,320 @@
+//! Kafka事件后端
+//!
+//! 通过Confluent REST Proxy（v2 API）访问Kafka：消费组对应Kafka消费组，
+//! 关闭自动提交，只提交已确认记录的连续前缀，因此进程崩溃后未确认的记录会被重新消费。
+//! `nack` 将分区位置回退到失败的记录，其后的记录也会随之重新投递。
+
+use crate::{Error, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::time::Duration;
+
+use super::backend::{Delivery, EventBackend, EventConsumer, EventRecord};
+
+const KAFKA_JSON: &str = "application/vnd.kafka.json.v2+json";
+const KAFKA_V2: &str = "application/vnd.kafka.v2+json";
+
+/// Kafka后端配置
+#[derive(Debug, Clone)]
+pub struct KafkaRestConfig {
+    /// REST Proxy地址，如 `http://localhost:8082`
+    pub base_url: String,
+    /// 主题名前缀
+    pub topic_prefix: String,
+    /// 新消费组的起始位置：`latest` 或 `earliest`
+    pub auto_offset_reset: String,
+    /// 单次拉取的最大字节数
+    pub max_bytes: usize,
+}
+
+impl KafkaRestConfig {
+    /// 创建配置，新消费组从最新位置开始消费
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            topic_prefix: "lumos.events.".to_string(),
+            auto_offset_reset: "latest".to_string(),
+            max_bytes: 1024 * 1024,
+        }
+    }
+
+    /// 设置主题名前缀
+    pub fn with_topic_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.topic_prefix = prefix.into();
+        self
+    }
+
+    /// 新消费组从最早的记录开始消费
+    pub fn from_earliest(mut self) -> Self {
+        self.auto_offset_reset = "earliest".to_string();
+        self
+    }
+
+    fn topic(&self, topic: &str) -> String {
+        format!("{}{}", self.topic_prefix, topic)
+    }
+}
+
+/// 基于Confluent REST Proxy的Kafka事件后端
+pub struct KafkaRestBackend {
+    config: KafkaRestConfig,
+    client: reqwest::Client,
+}
+
+impl KafkaRestBackend {
+    /// 创建后端
+    pub fn new(config: KafkaRestConfig) -> Self {
+        Self { config, client: reqwest::Client::new() }
+    }
+}
+
+async fn check(response: reqwest::Response, action: &str) -> Result<reqwest::Response> {
+    if response.status().is_success() {
+        return Ok(response);
+    }
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    Err(Error::Event(format!("Kafka REST Proxy {} 失败 ({}): {}", action, status, body)))
+}
+
+#[derive(Deserialize)]
+struct ProduceResponse {
+    offsets: Vec<ProduceOffset>,
+}
+
+#[derive(Deserialize)]
+struct ProduceOffset {
+    error: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ConsumerInstance {
+    base_uri: String,
+}
+
+#[derive(Deserialize)]
+struct KafkaRecord {
+    topic: String,
+    partition: i32,
+    offset: i64,
+    value: EventRecord,
+}
+
+#[async_trait]
+impl EventBackend for KafkaRestBackend {
+    fn name(&self) -> &str {
+        "kafka"
+    }
+
+    async fn publish(&self, record: EventRecord) -> Result<()> {
+        // 以记录的分区键作为Kafka消息键，同一Agent的事件落在同一分区并保持顺序
+        let body = serde_json::json!({
+            "records": [{ "key": record.key, "value": record }],
+        });
+        let response = self.client
+            .post(format!("{}/topics/{}", self.config.base_url, self.config.topic(&record.topic)))
+            .header(reqwest::header::CONTENT_TYPE, KAFKA_JSON)
+            .header(reqwest::header::ACCEPT, KAFKA_V2)
+            .body(body.to_string())
+            .send()
+            .await?;
+        let response: ProduceResponse = check(response, "发布").await?.json().await?;
+        if let Some(error) = response.offsets.into_iter().find_map(|offset| offset.error) {
+            return Err(Error::Event(format!("Kafka发布失败: {}", error)));
+        }
+        Ok(())
+    }
+
+    async fn subscribe(&self, topic: &str, group: &str) -> Result<Box<dyn EventConsumer>> {
+        let response = self.client
+            .post(format!("{}/consumers/{}", self.config.base_url, group))
+            .header(reqwest::header::CONTENT_TYPE, KAFKA_V2)
+            .body(serde_json::json!({
+                "name": format!("lumos-{}", uuid::Uuid::new_v4().simple()),
+                "format": "json",
+                "auto.offset.reset": self.config.auto_offset_reset,
+                "auto.commit.enable": "false",
+            }).to_string())
+            .send()
+            .await?;
+        let instance: ConsumerInstance = check(response, "创建消费者").await?.json().await?;
+
+        let response = self.client
+            .post(format!("{}/subscription", instance.base_uri))
+            .header(reqwest::header::CONTENT_TYPE, KAFKA_V2)
+            .body(serde_json::json!({ "topics": [self.config.topic(topic)] }).to_string())
+            .send()
+            .await?;
+        check(response, "订阅").await?;
+
+        Ok(Box::new(KafkaRestConsumer {
+            client: self.client.clone(),
+            base_uri: instance.base_uri,
+            max_bytes: self.config.max_bytes,
+            buffered: VecDeque::new(),
+            tracker: OffsetTracker::default(),
+        }))
+    }
+}
+
+/// 按分区跟踪已投递记录的确认状态
+#[derive(Debug, Default)]
+struct OffsetTracker {
+    /// 分区 -> (offset -> 是否已确认)
+    pending: HashMap<(String, i32), BTreeMap<i64, bool>>,
+    /// 每条记录的投递次数
+    attempts: HashMap<(String, i32, i64), u32>,
+}
+
+impl OffsetTracker {
+    fn delivered(&mut self, topic: &str, partition: i32, offset: i64) -> u32 {
+        self.pending.entry((topic.to_string(), partition)).or_default().insert(offset, false);
+        let attempt = self.attempts.entry((topic.to_string(), partition, offset)).or_insert(0);
+        *attempt += 1;
+        *attempt
+    }
+
+    /// 标记确认，返回可以提交的最大offset
+    fn acked(&mut self, topic: &str, partition: i32, offset: i64) -> Option<i64> {
+        let key = (topic.to_string(), partition);
+        let pending = self.pending.get_mut(&key)?;
+        *pending.get_mut(&offset)? = true;
+
+        let mut committable = None;
+        while let Some(entry) = pending.first_entry() {
+            if !*entry.get() {
+                break;
+            }
+            let offset = *entry.key();
+            entry.remove();
+            self.attempts.remove(&(topic.to_string(), partition, offset));
+            committable = Some(offset);
+        }
+        committable
+    }
+
+    /// 回退到失败的记录，之后的记录将随之重新投递
+    fn rewound(&mut self, topic: &str, partition: i32, offset: i64) {
+        if let Some(pending) = self.pending.get_mut(&(topic.to_string(), partition)) {
+            pending.split_off(&offset);
+        }
+    }
+}
+
+struct KafkaRestConsumer {
+    client: reqwest::Client,
+    base_uri: String,
+    max_bytes: usize,
+    buffered: VecDeque<KafkaRecord>,
+    tracker: OffsetTracker,
+}
+
+fn parse_token(token: &str) -> Result<(String, i32, i64)> {
+    let invalid = || Error::Event(format!("无效的确认句柄: {}", token));
+    let mut parts = token.rsplitn(3, ':');
+    let offset = parts.next().and_then(|s| s.parse().ok()).ok_or_else(invalid)?;
+    let partition = parts.next().and_then(|s| s.parse().ok()).ok_or_else(invalid)?;
+    let topic = parts.next().ok_or_else(invalid)?;
+    Ok((topic.to_string(), partition, offset))
+}
+
+#[async_trait]
+impl EventConsumer for KafkaRestConsumer {
+    async fn poll(&mut self, max: usize, timeout: Duration) -> Result<Vec<Delivery>> {
+        if self.buffered.is_empty() {
+            let response = self.client
+                .get(format!("{}/records", self.base_uri))
+                .query(&[("timeout", timeout.as_millis().to_string()), ("max_bytes", self.max_bytes.to_string())])
+                .header(reqwest::header::ACCEPT, KAFKA_JSON)
+                .timeout(timeout + Duration::from_secs(5))
+                .send()
+                .await?;
+            let records: Vec<KafkaRecord> = check(response, "拉取").await?.json().await?;
+            self.buffered.extend(records);
+        }
+
+        let count = max.min(self.buffered.len());
+        Ok(self.buffered.drain(..count)
+            .map(|record| Delivery {
+                attempt: self.tracker.delivered(&record.topic, record.partition, record.offset),
+                ack_token: format!("{}:{}:{}", record.topic, record.partition, record.offset),
+                record: record.value,
+            })
+            .collect())
+    }
+
+    async fn ack(&mut self, delivery: &Delivery) -> Result<()> {
+        let (topic, partition, offset) = parse_token(&delivery.ack_token)?;
+        let Some(committable) = self.tracker.acked(&topic, partition, offset) else {
+            return Ok(());
+        };
+        // REST Proxy提交的是最后处理的offset，服务端会换算为下一条待读位置
+        let response = self.client
+            .post(format!("{}/offsets", self.base_uri))
+            .header(reqwest::header::CONTENT_TYPE, KAFKA_V2)
+            .body(serde_json::json!({
+                "offsets": [{ "topic": topic, "partition": partition, "offset": committable }],
+            }).to_string())
+            .send()
+            .await?;
+        check(response, "提交offset").await?;
+        Ok(())
+    }
+
+    async fn nack(&mut self, delivery: &Delivery) -> Result<()> {
+        let (topic, partition, offset) = parse_token(&delivery.ack_token)?;
+        self.tracker.rewound(&topic, partition, offset);
+        // 丢弃该分区已缓冲的后续记录，回退位置后会重新拉取
+        self.buffered.retain(|record| record.topic != topic || record.partition != partition);
+        let response = self.client
+            .post(format!("{}/positions", self.base_uri))
+            .header(reqwest::header::CONTENT_TYPE, KAFKA_V2)
+            .body(serde_json::json!({
+                "offsets": [{ "topic": topic, "partition": partition, "offset": offset }],
+            }).to_string())
+            .send()
+            .await?;
+        check(response, "回退位置").await?;
+        Ok(())
+    }
+}
+
+impl Drop for KafkaRestConsumer {
+    fn drop(&mut self) {
+        // 删除消费者实例，让消费组立即重新分配分区
+        let client = self.client.clone();
+        let base_uri = self.base_uri.clone();
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            handle.spawn(async move {
+                let _ = client.delete(base_uri).header(reqwest::header::CONTENT_TYPE, KAFKA_V2).send().await;
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commits_only_contiguous_acks() {
+        let mut tracker = OffsetTracker::default();
+        for offset in 10..13 {
+            assert_eq!(tracker.delivered("lumos.events.agents", 0, offset), 1);
+        }
+        assert_eq!(tracker.acked("lumos.events.agents", 0, 11), None);
+        assert_eq!(tracker.acked("lumos.events.agents", 0, 10), Some(11));
+
+        // 回退后重新投递的记录计入投递次数
+        tracker.rewound("lumos.events.agents", 0, 12);
+        assert_eq!(tracker.delivered("lumos.events.agents", 0, 12), 2);
+        assert_eq!(tracker.acked("lumos.events.agents", 0, 12), Some(12));
+    }
+
+    #[test]
+    fn test_parse_token() {
+        assert_eq!(parse_token("lumos.events.agents:3:42").unwrap(), ("lumos.events.agents".to_string(), 3, 42));
+        assert!(parse_token("42").is_err());
+    }
+}
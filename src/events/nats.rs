@@ -0,0 +1,475 @@
+//! NATS JetStream事件后端
+//!
+//! 直接使用NATS文本协议连接服务器：主题映射为 `<前缀>.<主题>` 形式的subject，
+//! 全部subject存放在同一个JetStream流中；消费组对应按主题过滤的持久化拉取消费者，
+//! 记录在显式确认之前会在 `ack_wait` 超时后重新投递。
+
+use crate::{Error, Result};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::tcp::OwnedWriteHalf;
+use tokio::net::TcpStream;
+use tokio::sync::{mpsc, Mutex};
+
+use super::backend::{Delivery, EventBackend, EventConsumer, EventRecord};
+
+/// NATS后端配置
+#[derive(Debug, Clone)]
+pub struct NatsConfig {
+    /// 服务器地址，如 `127.0.0.1:4222`
+    pub address: String,
+    /// JetStream流名称
+    pub stream: String,
+    /// subject前缀
+    pub subject_prefix: String,
+    /// 未确认记录的重新投递等待时间
+    pub ack_wait: Duration,
+    /// 请求超时
+    pub request_timeout: Duration,
+    /// 认证令牌
+    pub token: Option<String>,
+    /// 用户名和密码
+    pub credentials: Option<(String, String)>,
+}
+
+impl NatsConfig {
+    /// 使用默认流名称和前缀创建配置
+    pub fn new(address: impl Into<String>) -> Self {
+        Self {
+            address: address.into(),
+            stream: "LUMOS_EVENTS".to_string(),
+            subject_prefix: "lumos.events".to_string(),
+            ack_wait: Duration::from_secs(30),
+            request_timeout: Duration::from_secs(5),
+            token: None,
+            credentials: None,
+        }
+    }
+
+    /// 设置流名称和subject前缀
+    pub fn with_stream(mut self, stream: impl Into<String>, subject_prefix: impl Into<String>) -> Self {
+        self.stream = stream.into();
+        self.subject_prefix = subject_prefix.into();
+        self
+    }
+
+    /// 设置重新投递等待时间
+    pub fn with_ack_wait(mut self, ack_wait: Duration) -> Self {
+        self.ack_wait = ack_wait;
+        self
+    }
+
+    /// 使用令牌认证
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// 使用用户名密码认证
+    pub fn with_credentials(mut self, user: impl Into<String>, password: impl Into<String>) -> Self {
+        self.credentials = Some((user.into(), password.into()));
+        self
+    }
+
+    fn subject(&self, topic: &str) -> String {
+        format!("{}.{}", self.subject_prefix, topic)
+    }
+}
+
+/// 服务器推送的消息
+#[derive(Debug, Clone)]
+struct NatsMessage {
+    reply: Option<String>,
+    /// 状态码，来自 `NATS/1.0 404 No Messages` 形式的头
+    status: Option<u16>,
+    payload: Vec<u8>,
+}
+
+/// NATS连接，后台任务负责读取并按订阅ID分发消息
+struct NatsConnection {
+    writer: Mutex<OwnedWriteHalf>,
+    subscriptions: Arc<Mutex<HashMap<u64, mpsc::UnboundedSender<NatsMessage>>>>,
+    next_sid: AtomicU64,
+    request_timeout: Duration,
+}
+
+impl NatsConnection {
+    async fn connect(config: &NatsConfig) -> Result<Arc<Self>> {
+        let stream = TcpStream::connect(&config.address).await?;
+        let (read_half, write_half) = stream.into_split();
+        let mut reader = BufReader::new(read_half);
+
+        let mut line = String::new();
+        reader.read_line(&mut line).await?;
+        if !line.starts_with("INFO") {
+            return Err(Error::Event(format!("NATS握手失败: {}", line.trim())));
+        }
+
+        let mut connect = serde_json::json!({
+            "verbose": false,
+            "pedantic": false,
+            "headers": true,
+            "no_responders": true,
+            "lang": "rust",
+            "name": "lumosai",
+        });
+        if let Some(token) = &config.token {
+            connect["auth_token"] = serde_json::json!(token);
+        }
+        if let Some((user, password)) = &config.credentials {
+            connect["user"] = serde_json::json!(user);
+            connect["pass"] = serde_json::json!(password);
+        }
+
+        let connection = Arc::new(Self {
+            writer: Mutex::new(write_half),
+            subscriptions: Arc::new(Mutex::new(HashMap::new())),
+            next_sid: AtomicU64::new(1),
+            request_timeout: config.request_timeout,
+        });
+        connection.write(format!("CONNECT {}\r\nPING\r\n", connect).as_bytes()).await?;
+
+        line.clear();
+        reader.read_line(&mut line).await?;
+        if !line.starts_with("PONG") {
+            return Err(Error::Event(format!("NATS连接被拒绝: {}", line.trim())));
+        }
+
+        let weak = Arc::downgrade(&connection);
+        let subscriptions = connection.subscriptions.clone();
+        tokio::spawn(async move {
+            if let Err(e) = read_loop(reader, subscriptions, weak).await {
+                tracing::warn!("NATS连接已断开: {}", e);
+            }
+        });
+        Ok(connection)
+    }
+
+    async fn write(&self, bytes: &[u8]) -> Result<()> {
+        let mut writer = self.writer.lock().await;
+        writer.write_all(bytes).await?;
+        writer.flush().await?;
+        Ok(())
+    }
+
+    async fn publish(&self, subject: &str, reply: Option<&str>, payload: &[u8]) -> Result<()> {
+        let mut frame = match reply {
+            Some(reply) => format!("PUB {} {} {}\r\n", subject, reply, payload.len()),
+            None => format!("PUB {} {}\r\n", subject, payload.len()),
+        }.into_bytes();
+        frame.extend_from_slice(payload);
+        frame.extend_from_slice(b"\r\n");
+        self.write(&frame).await
+    }
+
+    async fn subscribe(&self, subject: &str) -> Result<(u64, mpsc::UnboundedReceiver<NatsMessage>)> {
+        let sid = self.next_sid.fetch_add(1, Ordering::Relaxed);
+        let (sender, receiver) = mpsc::unbounded_channel();
+        self.subscriptions.lock().await.insert(sid, sender);
+        self.write(format!("SUB {} {}\r\n", subject, sid).as_bytes()).await?;
+        Ok((sid, receiver))
+    }
+
+    async fn unsubscribe(&self, sid: u64) -> Result<()> {
+        self.subscriptions.lock().await.remove(&sid);
+        self.write(format!("UNSUB {}\r\n", sid).as_bytes()).await
+    }
+
+    /// 发送请求并等待第一条回复
+    async fn request(&self, subject: &str, payload: &[u8]) -> Result<NatsMessage> {
+        let inbox = new_inbox();
+        let (sid, mut receiver) = self.subscribe(&inbox).await?;
+        self.publish(subject, Some(&inbox), payload).await?;
+        let reply = tokio::time::timeout(self.request_timeout, receiver.recv()).await;
+        self.unsubscribe(sid).await?;
+        match reply {
+            Ok(Some(message)) if message.status == Some(503) => {
+                Err(Error::Event(format!("NATS请求 {} 没有响应者，JetStream是否已启用？", subject)))
+            }
+            Ok(Some(message)) => Ok(message),
+            Ok(None) => Err(Error::Event("NATS连接已关闭".to_string())),
+            Err(_) => Err(Error::Timeout(format!("NATS请求 {} 超时", subject))),
+        }
+    }
+
+    /// 发送JetStream API请求，将API错误转换为错误
+    async fn api(&self, subject: &str, body: &serde_json::Value) -> Result<serde_json::Value> {
+        let reply = self.request(subject, body.to_string().as_bytes()).await?;
+        let response: serde_json::Value = serde_json::from_slice(&reply.payload)?;
+        if let Some(error) = response.get("error") {
+            return Err(Error::Event(format!(
+                "JetStream API {} 失败: {}",
+                subject,
+                error.get("description").and_then(|d| d.as_str()).unwrap_or("unknown error"),
+            )));
+        }
+        Ok(response)
+    }
+}
+
+async fn read_loop(
+    mut reader: BufReader<tokio::net::tcp::OwnedReadHalf>,
+    subscriptions: Arc<Mutex<HashMap<u64, mpsc::UnboundedSender<NatsMessage>>>>,
+    connection: std::sync::Weak<NatsConnection>,
+) -> Result<()> {
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).await? == 0 {
+            return Err(Error::Event("服务器关闭了连接".to_string()));
+        }
+        let control = line.trim_end();
+        let mut parts = control.split_whitespace();
+        match parts.next() {
+            Some("PING") => match connection.upgrade() {
+                Some(connection) => connection.write(b"PONG\r\n").await?,
+                None => return Ok(()),
+            },
+            Some("MSG") | Some("HMSG") => {
+                let frame = parse_frame_header(control)?;
+                let mut body = vec![0u8; frame.total_len + 2];
+                reader.read_exact(&mut body).await?;
+                body.truncate(frame.total_len);
+                let status = if frame.header_len > 0 {
+                    parse_status(&body[..frame.header_len])
+                } else {
+                    None
+                };
+                let message = NatsMessage {
+                    reply: frame.reply,
+                    status,
+                    payload: body.split_off(frame.header_len),
+                };
+                if let Some(sender) = subscriptions.lock().await.get(&frame.sid) {
+                    let _ = sender.send(message);
+                }
+            }
+            Some("-ERR") => tracing::warn!("NATS错误: {}", control),
+            _ => {}
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct FrameHeader {
+    sid: u64,
+    reply: Option<String>,
+    header_len: usize,
+    total_len: usize,
+}
+
+/// 解析 `MSG <subject> <sid> [reply] <len>` 或 `HMSG <subject> <sid> [reply] <hdr_len> <total_len>`
+fn parse_frame_header(line: &str) -> Result<FrameHeader> {
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    let invalid = || Error::Event(format!("无法解析的NATS消息头: {}", line));
+    let number = |s: &str| s.parse::<usize>().map_err(|_| invalid());
+    let sid = parts.get(2).and_then(|s| s.parse::<u64>().ok()).ok_or_else(invalid)?;
+    match (parts.first().copied(), parts.len()) {
+        (Some("MSG"), 4) => Ok(FrameHeader { sid, reply: None, header_len: 0, total_len: number(parts[3])? }),
+        (Some("MSG"), 5) => Ok(FrameHeader { sid, reply: Some(parts[3].to_string()), header_len: 0, total_len: number(parts[4])? }),
+        (Some("HMSG"), 5) => Ok(FrameHeader { sid, reply: None, header_len: number(parts[3])?, total_len: number(parts[4])? }),
+        (Some("HMSG"), 6) => Ok(FrameHeader {
+            sid,
+            reply: Some(parts[3].to_string()),
+            header_len: number(parts[4])?,
+            total_len: number(parts[5])?,
+        }),
+        _ => Err(invalid()),
+    }
+}
+
+/// 从 `NATS/1.0 408 Request Timeout` 形式的头中取出状态码
+fn parse_status(headers: &[u8]) -> Option<u16> {
+    let text = std::str::from_utf8(headers).ok()?;
+    let first = text.lines().next()?;
+    first.strip_prefix("NATS/1.0")?.split_whitespace().next()?.parse().ok()
+}
+
+/// 从 `$JS.ACK.<stream>.<consumer>.<delivered>.<sseq>.<cseq>.<ts>.<pending>` 中取出投递次数
+fn delivery_count(reply: &str) -> u32 {
+    let tokens: Vec<&str> = reply.split('.').collect();
+    // 新版本服务器在 `$JS.ACK` 之后附加域和账户哈希，并在末尾附加随机令牌
+    let index = if tokens.len() >= 12 { 6 } else { 4 };
+    tokens.get(index).and_then(|n| n.parse().ok()).unwrap_or(1)
+}
+
+/// JetStream的持久化消费者名称不能包含 `.`、`*`、`>` 和空白
+fn durable_name(group: &str, topic: &str) -> String {
+    format!("{}_{}", group, topic)
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
+
+fn new_inbox() -> String {
+    format!("_INBOX.{}", uuid::Uuid::new_v4().simple())
+}
+
+/// NATS JetStream事件后端
+pub struct NatsBackend {
+    config: NatsConfig,
+    connection: Arc<NatsConnection>,
+}
+
+impl NatsBackend {
+    /// 连接服务器并确保流存在
+    pub async fn connect(config: NatsConfig) -> Result<Self> {
+        let connection = NatsConnection::connect(&config).await?;
+        let info = format!("$JS.API.STREAM.INFO.{}", config.stream);
+        if connection.api(&info, &serde_json::json!({})).await.is_err() {
+            connection.api(
+                &format!("$JS.API.STREAM.CREATE.{}", config.stream),
+                &serde_json::json!({
+                    "name": config.stream,
+                    "subjects": [format!("{}.>", config.subject_prefix)],
+                    "retention": "limits",
+                    "storage": "file",
+                }),
+            ).await?;
+        }
+        Ok(Self { config, connection })
+    }
+}
+
+#[async_trait]
+impl EventBackend for NatsBackend {
+    fn name(&self) -> &str {
+        "nats"
+    }
+
+    async fn publish(&self, record: EventRecord) -> Result<()> {
+        // 等待JetStream的发布确认，确认前的失败由调用方重试
+        let payload = serde_json::to_vec(&record)?;
+        let ack = self.connection.request(&self.config.subject(&record.topic), &payload).await?;
+        let response: serde_json::Value = serde_json::from_slice(&ack.payload)?;
+        if let Some(error) = response.get("error") {
+            return Err(Error::Event(format!("JetStream发布失败: {}", error)));
+        }
+        Ok(())
+    }
+
+    async fn subscribe(&self, topic: &str, group: &str) -> Result<Box<dyn EventConsumer>> {
+        let durable = durable_name(group, topic);
+        self.connection.api(
+            &format!("$JS.API.CONSUMER.CREATE.{}.{}", self.config.stream, durable),
+            &serde_json::json!({
+                "stream_name": self.config.stream,
+                "config": {
+                    "durable_name": durable,
+                    "ack_policy": "explicit",
+                    "ack_wait": self.config.ack_wait.as_nanos() as u64,
+                    "deliver_policy": "new",
+                    "filter_subject": self.config.subject(topic),
+                },
+            }),
+        ).await?;
+
+        let inbox = new_inbox();
+        let (sid, receiver) = self.connection.subscribe(&inbox).await?;
+        Ok(Box::new(NatsConsumer {
+            connection: self.connection.clone(),
+            next_subject: format!("$JS.API.CONSUMER.MSG.NEXT.{}.{}", self.config.stream, durable),
+            inbox,
+            sid,
+            receiver,
+        }))
+    }
+}
+
+struct NatsConsumer {
+    connection: Arc<NatsConnection>,
+    next_subject: String,
+    inbox: String,
+    sid: u64,
+    receiver: mpsc::UnboundedReceiver<NatsMessage>,
+}
+
+#[async_trait]
+impl EventConsumer for NatsConsumer {
+    async fn poll(&mut self, max: usize, timeout: Duration) -> Result<Vec<Delivery>> {
+        let request = serde_json::json!({
+            "batch": max.max(1),
+            "expires": timeout.as_nanos() as u64,
+        });
+        self.connection.publish(&self.next_subject, Some(&self.inbox), request.to_string().as_bytes()).await?;
+
+        let deadline = tokio::time::Instant::now() + timeout + Duration::from_millis(100);
+        let mut deliveries = Vec::new();
+        while deliveries.len() < max {
+            let message = match tokio::time::timeout_at(deadline, self.receiver.recv()).await {
+                Ok(Some(message)) => message,
+                Ok(None) => return Err(Error::Event("NATS连接已关闭".to_string())),
+                Err(_) => break,
+            };
+            // 404/408等状态消息表示本次拉取结束
+            if message.status.is_some() {
+                break;
+            }
+            let Some(reply) = message.reply else {
+                continue;
+            };
+            match serde_json::from_slice::<EventRecord>(&message.payload) {
+                Ok(record) => deliveries.push(Delivery {
+                    record,
+                    attempt: delivery_count(&reply),
+                    ack_token: reply,
+                }),
+                Err(e) => {
+                    // 无法解析的记录直接终止，避免无限重新投递
+                    tracing::warn!("丢弃无法解析的NATS记录: {}", e);
+                    self.connection.publish(&reply, None, b"+TERM").await?;
+                }
+            }
+        }
+        Ok(deliveries)
+    }
+
+    async fn ack(&mut self, delivery: &Delivery) -> Result<()> {
+        self.connection.publish(&delivery.ack_token, None, b"+ACK").await
+    }
+
+    async fn nack(&mut self, delivery: &Delivery) -> Result<()> {
+        self.connection.publish(&delivery.ack_token, None, b"-NAK").await
+    }
+}
+
+impl Drop for NatsConsumer {
+    fn drop(&mut self) {
+        let connection = self.connection.clone();
+        let sid = self.sid;
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            handle.spawn(async move {
+                let _ = connection.unsubscribe(sid).await;
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_frame_header() {
+        assert_eq!(parse_frame_header("MSG lumos.events.agents 7 12").unwrap(), FrameHeader {
+            sid: 7, reply: None, header_len: 0, total_len: 12,
+        });
+        let frame = parse_frame_header("HMSG _INBOX.x 3 $JS.ACK.S.C.2.10.4.1700000000.0 0 40").unwrap();
+        assert_eq!(frame.reply.as_deref(), Some("$JS.ACK.S.C.2.10.4.1700000000.0"));
+        assert_eq!(frame.total_len, 40);
+        assert!(parse_frame_header("MSG subject").is_err());
+    }
+
+    #[test]
+    fn test_jetstream_helpers() {
+        assert_eq!(parse_status(b"NATS/1.0 408 Request Timeout\r\n\r\n"), Some(408));
+        assert_eq!(parse_status(b"NATS/1.0\r\nX: y\r\n\r\n"), None);
+        assert_eq!(delivery_count("$JS.ACK.LUMOS_EVENTS.workers.3.42.40.1700000000000000000.0"), 3);
+        assert_eq!(delivery_count("$JS.ACK.domain.accounthash.LUMOS_EVENTS.workers.2.42.40.1700000000000000000.0.token"), 2);
+        assert_eq!(durable_name("analytics", "agents.v1"), "analytics_agents_v1");
+    }
+}
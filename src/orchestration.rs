@@ -1,6 +1,7 @@
 //! 简化的Agent编排API
 //!
-//! 提供简单易用的多Agent协作功能。
+//! 提供简单易用的多Agent协作功能。任务中的Agent通过共享的[`Blackboard`]读写具名产物
+//! （文档、表格、决策），每个Agent的结果以其ID为名写入黑板。
 
 use crate::{Result, Error, agent::SimpleAgent};
 use std::sync::Arc;
@@ -13,10 +14,25 @@ pub use lumosai_core::agent::orchestration::{
     AgentOrchestrator as CoreAgentOrchestrator,
     BasicOrchestrator as CoreBasicOrchestrator,
     AgentExecutionState,
+    CollaborationSession,
     VotingStrategy,
 };
 
 pub use lumosai_core::agent::events::EventBus;
+pub use lumosai_core::agent::consensus::{
+    ConsensusEntry,
+    ConsensusOutcome,
+    ConsensusRound,
+    ConsensusSelection,
+    ConsensusStage,
+};
+pub use lumosai_core::agent::blackboard::{
+    Artifact,
+    ArtifactVersion,
+    Blackboard,
+    BlackboardChange,
+    BlackboardChangeKind,
+};
 
 /// 简化的协作任务
 #[derive(Clone)]
@@ -27,6 +43,8 @@ pub struct CollaborationTask {
     pub pattern: OrchestrationPattern,
     pub input: serde_json::Value,
     pub timeout: Option<u64>,
+    /// Agent共享的产物黑板
+    pub blackboard: Arc<Blackboard>,
 }
 
 /// Agent编排器
@@ -42,6 +60,9 @@ pub struct OrchestrationResult {
     pub results: std::collections::HashMap<String, serde_json::Value>,
     pub execution_time_ms: u64,
     pub status: String,
+    /// 辩论共识模式的最终答案、票数和各轮发言记录
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub consensus: Option<ConsensusOutcome>,
 }
 
 /// 创建协作任务构建器
@@ -96,8 +117,9 @@ pub fn task() -> TaskBuilder {
 pub async fn execute(task: CollaborationTask) -> Result<OrchestrationResult> {
     let start_time = std::time::Instant::now();
 
-    // 简化实现：模拟协作执行
+    // 简化实现：模拟协作执行，辩论共识由核心编排器执行
     let mut results = std::collections::HashMap::new();
+    let mut consensus = None;
 
     // 根据模式执行
     match task.pattern {
@@ -125,12 +147,27 @@ pub async fn execute(task: CollaborationTask) -> Result<OrchestrationResult> {
                 results.insert(agent_id, serde_json::Value::String(result));
             }
         }
+        OrchestrationPattern::Consensus { .. } => {
+            let outcome = execute_consensus(&task).await?;
+            // 各Agent的最终答案为最后一轮互评后的答案
+            let final_round = outcome.rounds.iter().rev().find(|round| round.stage != ConsensusStage::Selection);
+            for entry in final_round.map(|round| round.entries.as_slice()).unwrap_or_default() {
+                results.insert(entry.agent_id.clone(), serde_json::Value::String(entry.content.clone()));
+            }
+            consensus = Some(outcome);
+        }
         _ => {
             // 简化实现：其他模式暂不支持
             return Err(Error::Agent("Unsupported orchestration pattern".to_string()));
         }
     }
 
+    // 结果写入黑板，后续步骤按名称读取
+    for (agent_id, result) in &results {
+        let content = result.as_str().map_or_else(|| result.to_string(), str::to_string);
+        task.blackboard.write(agent_id, Artifact::document(content), Some(agent_id)).await?;
+    }
+
     let execution_time = start_time.elapsed().as_millis() as u64;
 
     Ok(OrchestrationResult {
@@ -138,9 +175,42 @@ pub async fn execute(task: CollaborationTask) -> Result<OrchestrationResult> {
         results,
         execution_time_ms: execution_time,
         status: "completed".to_string(),
+        consensus,
     })
 }
 
+/// 以核心编排器执行辩论共识，Agent的ID为`agent_{序号}`，评判Agent也按此引用
+async fn execute_consensus(task: &CollaborationTask) -> Result<ConsensusOutcome> {
+    let agents: std::collections::HashMap<String, Arc<dyn lumosai_core::agent::trait_def::Agent>> = task.agents.iter()
+        .enumerate()
+        .map(|(i, agent)| (format!("agent_{}", i), agent.clone()))
+        .collect();
+    let mut participants: Vec<String> = agents.keys().cloned().collect();
+    participants.sort_by_key(|agent_id| agent_id.trim_start_matches("agent_").parse::<usize>().unwrap_or_default());
+    // 评判Agent不参与辩论
+    if let OrchestrationPattern::Consensus { selection: ConsensusSelection::Judge(judge), .. } = &task.pattern {
+        participants.retain(|agent_id| agent_id != judge);
+    }
+
+    let core_task = CoreCollaborationTask {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: task.name.clone(),
+        description: task.description.clone(),
+        participants,
+        pattern: task.pattern.clone(),
+        input: task.input.clone(),
+        expected_output: None,
+        timeout: task.timeout,
+        retry_config: None,
+    };
+    let mut session = CollaborationSession::new(core_task, agents, Arc::new(EventBus::new(100)))
+        .with_blackboard(task.blackboard.clone());
+    let outcome = CoreBasicOrchestrator::new(session.event_bus.clone())
+        .execute_collaboration(&mut session)
+        .await?;
+    Ok(serde_json::from_value(outcome)?)
+}
+
 /// 创建简单的顺序执行任务
 /// 
 /// # 示例
@@ -223,6 +293,7 @@ pub struct TaskBuilder {
     pattern: Option<OrchestrationPattern>,
     input: Option<serde_json::Value>,
     timeout: Option<u64>,
+    blackboard: Option<Arc<Blackboard>>,
 }
 
 impl TaskBuilder {
@@ -234,6 +305,7 @@ impl TaskBuilder {
             pattern: None,
             input: None,
             timeout: None,
+            blackboard: None,
         }
     }
     
@@ -279,6 +351,12 @@ impl TaskBuilder {
         self
     }
     
+    /// 使用已有的黑板，例如与其他任务共享产物
+    pub fn blackboard(mut self, blackboard: Arc<Blackboard>) -> Self {
+        self.blackboard = Some(blackboard);
+        self
+    }
+    
     /// 构建协作任务
     pub fn build(self) -> CollaborationTask {
         CollaborationTask {
@@ -288,6 +366,7 @@ impl TaskBuilder {
             pattern: self.pattern.unwrap_or(OrchestrationPattern::Sequential),
             input: self.input.unwrap_or(serde_json::json!({})),
             timeout: self.timeout,
+            blackboard: self.blackboard.unwrap_or_default(),
         }
     }
 }
@@ -323,6 +402,7 @@ mod tests {
             results: std::collections::HashMap::new(),
             execution_time_ms: 1000,
             status: "completed".to_string(),
+            consensus: None,
         };
         
         let json = serde_json::to_string(&result).expect("Failed to serialize");
@@ -1,8 +1,14 @@
 //! 简化的会话管理API
 //!
 //! 提供简单易用的Agent会话持久化功能。
+//! 用户对助手消息的反馈（[`Feedback`]）可以转换为评测用例或微调样本。
 
-use crate::{Result, Error, Message};
+use crate::{Result, Error, Message, Role};
+use lumosai_evals::EvalTestCase;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
 use std::sync::Arc;
 
 // 重导出核心类型
@@ -17,6 +23,7 @@ pub use lumosai_core::agent::session::{
     ToolCallHistory,
     ToolCallStatus,
 };
+pub use lumosai_core::agent::feedback::{Feedback, FeedbackQuery, Thumbs};
 
 /// 简化的会话类型
 pub type Session = Arc<dyn SessionTrait>;
@@ -176,6 +183,147 @@ pub async fn list_user_sessions(
     storage.list_user_sessions(user_id, limit).await
 }
 
+/// 微调样本中的一条消息
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FineTuningMessage {
+    /// 角色：`system`、`user`或`assistant`
+    pub role: String,
+    /// 消息内容
+    pub content: String,
+}
+
+/// 聊天格式的微调样本，最后一条消息为期望的助手回复
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FineTuningExample {
+    /// 对话消息
+    pub messages: Vec<FineTuningMessage>,
+}
+
+impl FineTuningExample {
+    /// 将样本写入JSONL文件，每行一个样本
+    pub fn write_jsonl<P: AsRef<Path>>(path: P, examples: &[Self]) -> Result<()> {
+        let mut file = std::fs::File::create(path)?;
+        for example in examples {
+            writeln!(file, "{}", serde_json::to_string(example)?)?;
+        }
+        Ok(())
+    }
+}
+
+/// 反馈对应的助手回复及其之前的对话
+struct FeedbackTurn<'a> {
+    feedback: &'a Feedback,
+    session: &'a SessionData,
+    reply: &'a Message,
+}
+
+impl FeedbackTurn<'_> {
+    /// 回复之前的对话
+    fn history(&self) -> &[Message] {
+        &self.session.messages[..self.feedback.message_index]
+    }
+
+    /// 期望的回复：用户修正的回答，或用户认可的原回答
+    fn expected_output(&self) -> Option<&str> {
+        match &self.feedback.correction {
+            Some(correction) => Some(correction),
+            None if self.feedback.is_positive() => Some(&self.reply.content),
+            None => None,
+        }
+    }
+}
+
+/// 加载满足查询条件的反馈及其对应的会话，跳过会话或消息已不存在的反馈
+async fn load_feedback_sessions(
+    storage: &Arc<dyn SessionStorage>,
+    query: &FeedbackQuery,
+) -> Result<(Vec<Feedback>, HashMap<String, SessionData>)> {
+    let feedback = storage.list_feedback(query).await?;
+    let mut sessions = HashMap::new();
+    for entry in &feedback {
+        if !sessions.contains_key(&entry.session_id) {
+            if let Some(session) = storage.load_session(&entry.session_id).await? {
+                sessions.insert(entry.session_id.clone(), session);
+            }
+        }
+    }
+    Ok((feedback, sessions))
+}
+
+fn feedback_turns<'a>(feedback: &'a [Feedback], sessions: &'a HashMap<String, SessionData>) -> impl Iterator<Item = FeedbackTurn<'a>> {
+    feedback.iter().filter_map(|feedback| {
+        let session = sessions.get(&feedback.session_id)?;
+        let reply = session.messages.get(feedback.message_index)
+            .filter(|message| message.role == Role::Assistant)?;
+        Some(FeedbackTurn { feedback, session, reply })
+    })
+}
+
+/// 将用户反馈转换为评测用例
+///
+/// 输入为被评价回复之前的最后一条用户消息；期望输出为修正后的回答，
+/// 或用户认可的原回答，其余反馈只运行不比对。用例带有`feedback`标签以及
+/// `thumbs_up`、`thumbs_down`或`corrected`标签，元数据记录会话ID、消息位置和评分。
+pub async fn feedback_test_cases(
+    storage: Arc<dyn SessionStorage>,
+    query: &FeedbackQuery,
+) -> Result<Vec<EvalTestCase>> {
+    let (feedback, sessions) = load_feedback_sessions(&storage, query).await?;
+
+    Ok(feedback_turns(&feedback, &sessions)
+        .filter_map(|turn| {
+            let input = turn.history().iter().rev().find(|message| message.role == Role::User)?;
+            let mut case = EvalTestCase::new(input.content.clone());
+            case.expected_output = turn.expected_output().map(str::to_string);
+            case.agent = Some(turn.session.metadata.agent_name.clone());
+            case.tags.push("feedback".to_string());
+            match turn.feedback.thumbs {
+                Some(Thumbs::Up) => case.tags.push("thumbs_up".to_string()),
+                Some(Thumbs::Down) => case.tags.push("thumbs_down".to_string()),
+                None => {}
+            }
+            if turn.feedback.correction.is_some() {
+                case.tags.push("corrected".to_string());
+            }
+            case.metadata.insert("session_id".to_string(), turn.feedback.session_id.clone().into());
+            case.metadata.insert("message_index".to_string(), turn.feedback.message_index.into());
+            if let Some(rating) = turn.feedback.rating {
+                case.metadata.insert("rating".to_string(), rating.into());
+            }
+            Some(case)
+        })
+        .collect())
+}
+
+/// 将用户反馈转换为聊天格式的微调样本
+///
+/// 每个样本包含被评价回复之前的对话，最后一条助手消息为修正后的回答或用户认可的原回答；
+/// 既没有修正也没有被认可的反馈不产生样本。
+pub async fn fine_tuning_examples(
+    storage: Arc<dyn SessionStorage>,
+    query: &FeedbackQuery,
+) -> Result<Vec<FineTuningExample>> {
+    let (feedback, sessions) = load_feedback_sessions(&storage, query).await?;
+
+    Ok(feedback_turns(&feedback, &sessions)
+        .filter_map(|turn| {
+            let expected = turn.expected_output()?;
+            let mut messages: Vec<FineTuningMessage> = turn.history().iter()
+                .filter(|message| matches!(message.role, Role::System | Role::User | Role::Assistant))
+                .map(|message| FineTuningMessage {
+                    role: message.role.to_string(),
+                    content: message.content.clone(),
+                })
+                .collect();
+            messages.push(FineTuningMessage {
+                role: Role::Assistant.to_string(),
+                content: expected.to_string(),
+            });
+            Some(FineTuningExample { messages })
+        })
+        .collect())
+}
+
 /// 简单会话实现
 struct SimpleSession {
     data: SessionData,
@@ -367,4 +515,49 @@ mod tests {
         // 测试构建器模式
         assert!(true); // 简单的编译测试
     }
+    
+    #[tokio::test]
+    async fn test_feedback_exports() {
+        let storage: Arc<dyn SessionStorage> = Arc::new(MemorySessionStorage::new());
+        let manager = CoreSessionManager::new(storage.clone());
+        manager.create_session("s1".to_string(), "support".to_string(), None).await.unwrap();
+        for (role, content) in [
+            (Role::System, "Be brief."),
+            (Role::User, "Capital of France?"),
+            (Role::Assistant, "Paris."),
+            (Role::User, "And of Spain?"),
+            (Role::Assistant, "Lisbon."),
+        ] {
+            let message = Message { role, content: content.to_string(), metadata: None, name: None };
+            manager.add_message("s1", message).await.unwrap();
+        }
+        manager.add_feedback(Feedback::new("s1", 2).with_user("ann").with_thumbs(Thumbs::Up)).await.unwrap();
+        manager.add_feedback(Feedback::new("s1", 4).with_user("ann").with_thumbs(Thumbs::Down).with_correction("Madrid.")).await.unwrap();
+        manager.add_feedback(Feedback::new("s1", 4).with_user("bob").with_rating(1)).await.unwrap();
+        
+        let cases = feedback_test_cases(storage.clone(), &FeedbackQuery::default()).await.unwrap();
+        assert_eq!(cases.len(), 3);
+        assert_eq!(cases[0].input, "Capital of France?");
+        assert_eq!(cases[0].expected_output.as_deref(), Some("Paris."));
+        assert_eq!(cases[0].tags, vec!["feedback", "thumbs_up"]);
+        assert_eq!(cases[1].input, "And of Spain?");
+        assert_eq!(cases[1].expected_output.as_deref(), Some("Madrid."));
+        assert_eq!(cases[1].tags, vec!["feedback", "thumbs_down", "corrected"]);
+        assert_eq!(cases[1].agent.as_deref(), Some("support"));
+        assert_eq!(cases[2].expected_output, None);
+        assert_eq!(cases[2].metadata["rating"], 1);
+        
+        // 未修正的负面反馈不产生微调样本
+        let examples = fine_tuning_examples(storage, &FeedbackQuery::default()).await.unwrap();
+        assert_eq!(examples.len(), 2);
+        let roles: Vec<&str> = examples[1].messages.iter().map(|message| message.role.as_str()).collect();
+        assert_eq!(roles, vec!["system", "user", "assistant", "user", "assistant"]);
+        assert_eq!(examples[1].messages[4].content, "Madrid.");
+        
+        let path = std::env::temp_dir().join(format!("lumos_fine_tuning_{}.jsonl", std::process::id()));
+        FineTuningExample::write_jsonl(&path, &examples).unwrap();
+        let written = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(written.lines().count(), 2);
+    }
 }
@@ -254,6 +254,7 @@ mod tests {
             results: HashMap::new(),
             execution_time_ms: 1000,
             status: "completed".to_string(),
+            consensus: None,
         };
         
         let json = serde_json::to_string(&result).expect("Failed to serialize");
@@ -132,6 +132,8 @@ async fn test_api_consistency_check() {
         ])),
         max_tool_calls: Some(5),
         tool_timeout: Some(30),
+        reflection: None,
+        few_shot: None,
     };
     
     let llm = Arc::new(MockLlmProvider::new(vec![
@@ -179,6 +181,8 @@ async fn test_feature_completeness_check() {
         ])),
         max_tool_calls: Some(10),
         tool_timeout: Some(60),
+        reflection: None,
+        few_shot: None,
     };
     
     let llm = Arc::new(MockLlmProvider::new(vec![
@@ -226,6 +230,8 @@ async fn test_agent_status_management() {
         metadata: None,
         max_tool_calls: None,
         tool_timeout: None,
+        reflection: None,
+        few_shot: None,
     };
     
     let llm = Arc::new(MockLlmProvider::new(vec![
@@ -275,6 +281,8 @@ async fn test_agent_health_check() {
         ])),
         max_tool_calls: None,
         tool_timeout: None,
+        reflection: None,
+        few_shot: None,
     };
     
     let llm = Arc::new(MockLlmProvider::new(vec![
@@ -319,6 +327,8 @@ async fn test_comprehensive_integration() {
         ])),
         max_tool_calls: Some(15),
         tool_timeout: Some(120),
+        reflection: None,
+        few_shot: None,
     };
     
     let llm = Arc::new(MockLlmProvider::new(vec![
@@ -32,6 +32,8 @@ async fn test_documentation_generation() -> Result<()> {
         metadata: None,
         max_tool_calls: Some(10),
         tool_timeout: Some(30),
+        reflection: None,
+        few_shot: None,
     };
     
     let agent = BasicAgent::new(config, llm);
@@ -281,6 +283,8 @@ async fn test_documentation_formats() -> Result<()> {
         metadata: None,
         max_tool_calls: Some(5),
         tool_timeout: Some(15),
+        reflection: None,
+        few_shot: None,
     };
     
     let agent = BasicAgent::new(config, llm);
@@ -396,6 +400,8 @@ async fn test_comprehensive_integration() -> Result<()> {
         metadata: None,
         max_tool_calls: Some(20),
         tool_timeout: Some(45),
+        reflection: None,
+        few_shot: None,
     };
     
     let agent = BasicAgent::new(config, llm);